@@ -0,0 +1,7 @@
+"""Python bindings for the Cashu Development Kit.
+
+All async wallet methods are native ``asyncio`` coroutines and can be awaited
+from any running event loop.
+"""
+
+from .cdk_ffi import *  # noqa: F401,F403
@@ -0,0 +1 @@
+from .cdk_ffi import *
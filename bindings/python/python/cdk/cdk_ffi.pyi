@@ -0,0 +1,393 @@
+"""Typing stubs for the generated ``cdk_ffi`` module.
+
+Covers the wallet, wallet repository and storage APIs. Names not listed here
+are still available at runtime and are typed as ``Any``.
+"""
+
+from enum import Enum
+from typing import Any, Dict, List, Optional
+
+def __getattr__(name: str) -> Any: ...
+
+class FfiError(Exception):
+    class Cdk(FfiError):
+        code: int
+        error_message: str
+    class Internal(FfiError):
+        error_message: str
+    class Cancelled(FfiError): ...
+
+class Amount:
+    value: int
+    def __init__(self, value: int) -> None: ...
+
+class MintUrl:
+    url: str
+    def __init__(self, url: str) -> None: ...
+
+class CurrencyUnit:
+    class SAT(CurrencyUnit):
+        def __init__(self) -> None: ...
+    class MSAT(CurrencyUnit):
+        def __init__(self) -> None: ...
+    class USD(CurrencyUnit):
+        def __init__(self) -> None: ...
+    class EUR(CurrencyUnit):
+        def __init__(self) -> None: ...
+    class AUTH(CurrencyUnit):
+        def __init__(self) -> None: ...
+    class CUSTOM(CurrencyUnit):
+        unit: str
+        def __init__(self, unit: str) -> None: ...
+
+class PaymentMethod:
+    class BOLT11(PaymentMethod):
+        def __init__(self) -> None: ...
+    class BOLT12(PaymentMethod):
+        def __init__(self) -> None: ...
+    class ONCHAIN(PaymentMethod):
+        def __init__(self) -> None: ...
+    class CUSTOM(PaymentMethod):
+        method: str
+        def __init__(self, method: str) -> None: ...
+
+class SplitTarget:
+    class NONE(SplitTarget):
+        def __init__(self) -> None: ...
+    class VALUE(SplitTarget):
+        amount: Amount
+        def __init__(self, amount: Amount) -> None: ...
+    class VALUES(SplitTarget):
+        amounts: List[Amount]
+        def __init__(self, amounts: List[Amount]) -> None: ...
+
+class WalletDatabase: ...
+
+class WalletStore:
+    class SQLITE(WalletStore):
+        path: str
+        def __init__(self, path: str) -> None: ...
+    class POSTGRES(WalletStore):
+        url: str
+        def __init__(self, url: str) -> None: ...
+    class CUSTOM(WalletStore):
+        db: WalletDatabase
+        def __init__(self, db: WalletDatabase) -> None: ...
+
+class WalletDbBackend:
+    class SQLITE(WalletDbBackend):
+        path: str
+        def __init__(self, path: str) -> None: ...
+    class POSTGRES(WalletDbBackend):
+        url: str
+        def __init__(self, url: str) -> None: ...
+
+class WalletSqliteDatabase(WalletDatabase):
+    def __init__(self, file_path: str) -> None: ...
+    @classmethod
+    def new_in_memory(cls) -> WalletSqliteDatabase: ...
+
+class WalletConfig:
+    target_proof_count: Optional[int]
+    def __init__(self, target_proof_count: Optional[int]) -> None: ...
+
+class WalletKey:
+    mint_url: MintUrl
+    unit: CurrencyUnit
+    def __init__(self, mint_url: MintUrl, unit: CurrencyUnit) -> None: ...
+
+class Proof:
+    amount: Amount
+    secret: str
+    c: str
+    keyset_id: str
+
+class MintTrustPolicy:
+    allowlist: List[MintUrl]
+    denylist: List[MintUrl]
+    max_amount_from_unknown_mint: Optional[Amount]
+    auto_swap_to_trusted: bool
+    def __init__(
+        self,
+        allowlist: List[MintUrl],
+        denylist: List[MintUrl],
+        max_amount_from_unknown_mint: Optional[Amount],
+        auto_swap_to_trusted: bool,
+    ) -> None: ...
+
+class ReceiveOptions:
+    amount_split_target: SplitTarget
+    p2pk_signing_keys: List[Any]
+    preimages: List[str]
+    metadata: Dict[str, str]
+    transfer_from_other_mint: bool
+    mint_trust_policy: Optional[MintTrustPolicy]
+    def __init__(
+        self,
+        amount_split_target: SplitTarget,
+        p2pk_signing_keys: List[Any],
+        preimages: List[str],
+        metadata: Dict[str, str],
+        transfer_from_other_mint: bool,
+        mint_trust_policy: Optional[MintTrustPolicy],
+    ) -> None: ...
+
+class SendFeePolicy(Enum):
+    RECEIVER_PAYS = 0
+    SENDER_PAYS = 1
+    SPLIT = 2
+
+class SendOptions:
+    memo: Optional[Any]
+    conditions: Optional[Any]
+    amount_split_target: SplitTarget
+    send_kind: Any
+    include_fee: bool
+    fee_policy: SendFeePolicy
+    use_p2bk: bool
+    max_proofs: Optional[int]
+    metadata: Dict[str, str]
+    p2pk_signing_keys: List[Any]
+    p2pk_locked_proof_send_mode: Any
+    include_dleq: bool
+
+class NUT13Options:
+    batch_size: int
+    max_gap: int
+    def __init__(self, batch_size: int, max_gap: int) -> None: ...
+
+class Restored:
+    spent: Amount
+    unspent: Amount
+    pending: Amount
+
+class MintQuote:
+    id: str
+    amount: Optional[Amount]
+    unit: CurrencyUnit
+    request: str
+    expiry: int
+
+class MeltQuote:
+    id: str
+    amount: Amount
+    unit: CurrencyUnit
+    request: str
+    fee_reserve: Amount
+    expiry: int
+    options: Optional[Any]
+
+class MeltQuoteOptions:
+    allow_duplicate: bool
+    def __init__(self, allow_duplicate: bool) -> None: ...
+
+class FinalizedMelt:
+    quote_id: str
+    state: Any
+    preimage: Optional[str]
+    change: Optional[List[Proof]]
+    amount: Amount
+    fee_paid: Amount
+
+class Token:
+    @classmethod
+    def from_string(cls, encoded_token: str) -> Token: ...
+    @classmethod
+    def decode(cls, encoded_token: str) -> Token: ...
+    def value(self) -> Amount: ...
+    def memo(self) -> Optional[str]: ...
+    def unit(self) -> Optional[CurrencyUnit]: ...
+    def mint_url(self) -> MintUrl: ...
+    def info(self) -> TokenInfo: ...
+    def encode(self) -> str: ...
+
+class TokenInfo:
+    mint_url: MintUrl
+    unit: Optional[CurrencyUnit]
+    amount: Amount
+    proof_count: int
+    memo: Optional[str]
+    p2pk_locked: bool
+    p2pk_pubkeys: List[str]
+    p2pk_refund_pubkeys: List[str]
+    htlc_hashes: List[str]
+    locktimes: List[int]
+    dleq_proof_count: int
+
+class KeysCacheStats:
+    hits: int
+    misses: int
+    evictions: int
+    entries: int
+    capacity: int
+
+class TokenData:
+    mint_url: MintUrl
+    proofs: List[Proof]
+    memo: Optional[str]
+    value: Amount
+    unit: CurrencyUnit
+    redeem_fee: Optional[Amount]
+
+class PreparedSend:
+    def operation_id(self) -> str: ...
+    def amount(self) -> Amount: ...
+    def proofs(self) -> List[Proof]: ...
+    def fee(self) -> Amount: ...
+    async def confirm(self, memo: Optional[str]) -> Token: ...
+    async def cancel(self) -> None: ...
+
+class MeltConfirmOptions:
+    skip_swap: bool
+    extra_change_outputs: int
+    p2pk_signing_keys: List[Any]
+    def __init__(
+        self,
+        skip_swap: bool,
+        extra_change_outputs: int,
+        p2pk_signing_keys: List[Any],
+    ) -> None: ...
+
+class PreparedMelt:
+    def operation_id(self) -> str: ...
+    def quote_id(self) -> str: ...
+    def amount(self) -> Amount: ...
+    def fee_reserve(self) -> Amount: ...
+    def total_fee(self) -> Amount: ...
+    def proofs(self) -> List[Proof]: ...
+    async def confirm(self) -> FinalizedMelt: ...
+    async def confirm_with_options(self, options: MeltConfirmOptions) -> FinalizedMelt: ...
+    async def confirm_cancellable(
+        self, options: MeltConfirmOptions, cancel: CancellationToken
+    ) -> FinalizedMelt: ...
+    async def cancel(self) -> None: ...
+
+class PurgedQuotes:
+    mint_quotes: List[str]
+    melt_quotes: List[str]
+
+class CancellationToken:
+    def __init__(self) -> None: ...
+    def cancel(self) -> None: ...
+    def is_cancelled(self) -> bool: ...
+
+class WalletEvent:
+    class MINT_QUOTE_PAID(WalletEvent):
+        quote_id: str
+        amount: Optional[Amount]
+    class PROOFS_RECEIVED(WalletEvent):
+        amount: Amount
+    class QUOTES_PURGED(WalletEvent):
+        mint_quotes: List[str]
+        melt_quotes: List[str]
+    class BALANCE_CHANGED(WalletEvent):
+        balance: Amount
+
+class WalletEventListener:
+    async def on_event(self, event: WalletEvent) -> None: ...
+
+class InvoicePayer:
+    async def pay_invoice(self, invoice: str) -> None: ...
+
+class Wallet:
+    def __init__(self, mint_url: str, unit: CurrencyUnit, mnemonic: str, store: WalletStore, config: WalletConfig) -> None: ...
+    def mint_url(self) -> MintUrl: ...
+    def unit(self) -> CurrencyUnit: ...
+    def set_metadata_cache_ttl(self, ttl_secs: Optional[int]) -> None: ...
+    def set_event_listener(self, listener: WalletEventListener) -> None: ...
+    def clear_event_listener(self) -> None: ...
+    def set_invoice_payer(self, payer: InvoicePayer) -> None: ...
+    def clear_invoice_payer(self) -> None: ...
+    async def top_up(self, amount: Amount, timeout_secs: int) -> List[Proof]: ...
+    async def total_balance(self) -> Amount: ...
+    async def total_pending_balance(self) -> Amount: ...
+    async def total_reserved_balance(self) -> Amount: ...
+    async def fetch_mint_info(self) -> Optional[MintInfo]: ...
+    async def load_mint_info(self) -> MintInfo: ...
+    async def receive(self, token: Token, options: ReceiveOptions) -> Amount: ...
+    async def receive_cancellable(self, token: Token, options: ReceiveOptions, cancel: CancellationToken) -> Amount: ...
+    async def restore(self) -> Restored: ...
+    async def restore_with_opts(self, opts: NUT13Options) -> Restored: ...
+    async def restore_cancellable(self, opts: NUT13Options, cancel: CancellationToken) -> Restored: ...
+    async def verify_token_dleq(self, token: Token) -> None: ...
+    async def receive_proofs(self, proofs: List[Proof], options: ReceiveOptions, memo: Optional[str], token: Optional[str]) -> Amount: ...
+    async def get_pending_sends(self) -> List[str]: ...
+    async def revoke_send(self, operation_id: str) -> Amount: ...
+    async def check_send_status(self, operation_id: str) -> bool: ...
+    async def prepare_send(self, amount: Amount, options: SendOptions) -> PreparedSend: ...
+    async def mint_quote(self, payment_method: PaymentMethod, amount: Optional[Amount], description: Optional[str], extra: Optional[str]) -> MintQuote: ...
+    async def check_mint_quote(self, quote_id: str) -> MintQuote: ...
+    async def check_mint_quote_status(self, quote_id: str) -> MintQuote: ...
+    async def fetch_mint_quote(self, quote_id: str, payment_method: Optional[PaymentMethod]) -> MintQuote: ...
+    async def mint(self, quote_id: str, amount_split_target: SplitTarget, spending_conditions: Optional[SpendingConditions]) -> List[Proof]: ...
+    async def mint_unissued_quotes(self) -> Amount: ...
+    async def prepare_melt(self, quote_id: str) -> PreparedMelt: ...
+    async def prepare_melt_proofs(self, quote_id: str, proofs: List[Proof]) -> PreparedMelt: ...
+    async def prepare_melt_token(self, quote_id: str, encoded_token: str) -> PreparedMelt: ...
+    async def mint_unified(self, quote_id: str, amount_split_target: SplitTarget, spending_conditions: Optional[SpendingConditions]) -> List[Proof]: ...
+    async def melt_quote(self, method: PaymentMethod, request: str, options: Optional[MeltOptions], extra: Optional[str]) -> MeltQuote: ...
+    async def melt_quote_with_options(self, method: PaymentMethod, request: str, options: Optional[MeltOptions], extra: Optional[str], quote_options: MeltQuoteOptions) -> MeltQuote: ...
+    async def quote_onchain_melt_options(self, address: str, amount: Amount, max_fee_amount: Optional[Amount]) -> List[MeltQuote]: ...
+    async def select_onchain_melt_quote(self, quote: MeltQuote) -> MeltQuote: ...
+    async def check_melt_quote_status(self, quote_id: str) -> MeltQuote: ...
+    async def finalize_pending_melts(self) -> List[FinalizedMelt]: ...
+    async def swap(self, amount: Optional[Amount], amount_split_target: SplitTarget, input_proofs: List[Proof], spending_conditions: Optional[SpendingConditions], include_fees: bool) -> Optional[List[Proof]]: ...
+    async def get_proofs_by_states(self, states: List[ProofState]) -> List[Proof]: ...
+    async def check_proofs_spent(self, proofs: List[Proof]) -> List[bool]: ...
+    async def list_transactions(self, direction: Optional[TransactionDirection]) -> List[Transaction]: ...
+    async def get_transaction(self, id: TransactionId) -> Optional[Transaction]: ...
+    async def get_proofs_for_transaction(self, id: TransactionId) -> List[Proof]: ...
+    async def revert_transaction(self, id: TransactionId) -> None: ...
+    async def subscribe(self, params: SubscribeParams) -> ActiveSubscription: ...
+    async def subscribe_mint_quote_state(self, quote_ids: List[str], payment_method: PaymentMethod) -> ActiveSubscription: ...
+    async def keysets(self, policy: Optional[KeysetLoadPolicy]) -> List[KeySet]: ...
+    async def active_keyset(self) -> KeySet: ...
+    async def keyset(self, keyset_id: str) -> KeySet: ...
+    async def get_keyset_fees_by_id(self, keyset_id: str) -> int: ...
+    async def get_keyset_fees_and_amounts(self) -> Dict[str, FeeAndAmounts]: ...
+    async def get_keyset_fees_and_amounts_by_id(self, keyset_id: str) -> FeeAndAmounts: ...
+    async def get_keyset_count_fee(self, keyset_id: str, count: int) -> Amount: ...
+    async def check_all_pending_proofs(self) -> Amount: ...
+    async def recover_incomplete_sagas(self) -> RecoveryReport: ...
+    async def resume_pending_mints(self) -> RecoveryReport: ...
+    async def purge_expired_quotes(self) -> PurgedQuotes: ...
+    async def purge_quotes_expired_for(self, grace_secs: int) -> PurgedQuotes: ...
+    async def calculate_fee(self, proof_count: int, keyset_id: str) -> Amount: ...
+    async def pay_request(self, payment_request: PaymentRequest, custom_amount: Optional[Amount]) -> None: ...
+    async def melt_bip353_quote(self, bip353_address: str, amount_msat: Amount, network: BitcoinNetwork) -> MeltQuote: ...
+    async def melt_lightning_address_quote(self, lightning_address: str, amount_msat: Amount) -> MeltQuote: ...
+    async def melt_human_readable(self, address: str, amount_msat: Amount, network: BitcoinNetwork) -> MeltQuote: ...
+    async def melt_human_readable_quote(self, address: str, amount_msat: Amount, network: BitcoinNetwork) -> MeltQuote: ...
+    async def set_cat(self, cat: str) -> None: ...
+    async def set_refresh_token(self, refresh_token: str) -> None: ...
+    async def refresh_access_token(self) -> None: ...
+    async def mint_blind_auth(self, amount: Amount) -> List[Proof]: ...
+    async def get_unspent_auth_proofs(self) -> List[AuthProof]: ...
+
+class WalletRepository:
+    def __init__(self, mnemonic: str, store: WalletStore) -> None: ...
+    @classmethod
+    def new_with_proxy(cls, mnemonic: str, store: WalletStore, proxy_url: str) -> WalletRepository: ...
+    async def set_metadata_cache_ttl_for_mint(self, mint_url: MintUrl, ttl_secs: Optional[int]) -> None: ...
+    async def set_metadata_cache_ttl_for_all_mints(self, ttl_secs: Optional[int]) -> None: ...
+    async def create_wallet(self, mint_url: MintUrl, unit: Optional[CurrencyUnit], target_proof_count: Optional[int]) -> None: ...
+    async def remove_wallet(self, mint_url: MintUrl, currency_unit: CurrencyUnit) -> None: ...
+    async def has_mint(self, mint_url: MintUrl) -> bool: ...
+    def mint_backup_public_key(self) -> str: ...
+    async def backup_mints(self, relays: List[str], options: BackupOptions) -> BackupResult: ...
+    async def restore_mints(self, relays: List[str], add_mints: bool, options: RestoreOptions) -> RestoreResult: ...
+    async def fetch_mint_backup(self, relays: List[str], options: RestoreOptions) -> MintBackup: ...
+    async def get_balances(self) -> Dict[WalletKey, Amount]: ...
+    async def get_wallets(self) -> List[Wallet]: ...
+    async def get_wallet(self, mint_url: MintUrl, unit: CurrencyUnit) -> Wallet: ...
+    async def get_token_data(self, token: Token) -> TokenData: ...
+
+def generate_mnemonic() -> str: ...
+def mnemonic_to_entropy(mnemonic: str) -> bytes: ...
+def inspect_token(encoded_token: str) -> TokenInfo: ...
+def set_keys_cache_capacity(capacity: int) -> None: ...
+def keys_cache_stats() -> KeysCacheStats: ...
+def sqlite_wallet_store(path: str) -> WalletStore: ...
+def postgres_wallet_store(url: str) -> WalletStore: ...
+def custom_wallet_store(db: WalletDatabase) -> WalletStore: ...
+def create_wallet_db(backend: WalletDbBackend) -> WalletDatabase: ...
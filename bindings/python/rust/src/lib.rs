@@ -0,0 +1,4 @@
+//! Re-export everything from cdk-ffi
+
+/// Re-export cdk_ffi
+pub use cdk_ffi::*;
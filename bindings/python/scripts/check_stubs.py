@@ -0,0 +1,136 @@
+#!/usr/bin/env python3
+"""Check the hand-written typing stubs against the generated UniFFI bindings.
+
+Every class, function, method, field and enum member declared in the stubs must
+exist in the generated module with the same parameter names, so the stubs fail
+CI as soon as the FFI changes underneath them.
+
+Usage: check_stubs.py <cdk_ffi.pyi> <generated cdk_ffi.py>
+"""
+
+import ast
+import sys
+from typing import Dict, List, Optional, Union
+
+FunctionDef = Union[ast.FunctionDef, ast.AsyncFunctionDef]
+
+# Nested variant classes of UniFFI errors are generated in a temporary class
+# and attached to the error class at runtime.
+TEMP_CLASS_PREFIX = "_UniffiTemp"
+
+
+class Scope:
+    """Classes, functions and fields declared in a module or class body."""
+
+    def __init__(self) -> None:
+        self.classes: Dict[str, "Scope"] = {}
+        self.functions: Dict[str, FunctionDef] = {}
+        self.fields: set = set()
+
+    @classmethod
+    def from_body(cls, body: List[ast.stmt]) -> "Scope":
+        scope = cls()
+        for node in body:
+            if isinstance(node, ast.ClassDef):
+                scope.classes[node.name] = cls.from_body(node.body)
+            elif isinstance(node, (ast.FunctionDef, ast.AsyncFunctionDef)):
+                scope.functions[node.name] = node
+                if node.name == "__init__":
+                    scope.fields.update(assigned_attributes(node))
+            elif isinstance(node, ast.AnnAssign) and isinstance(node.target, ast.Name):
+                scope.fields.add(node.target.id)
+            elif isinstance(node, ast.Assign):
+                scope.fields.update(
+                    target.id for target in node.targets if isinstance(target, ast.Name)
+                )
+        return scope
+
+
+def assigned_attributes(function: FunctionDef) -> set:
+    """Attributes assigned to ``self`` in ``function``."""
+    return {
+        target.attr
+        for node in ast.walk(function)
+        if isinstance(node, ast.Assign)
+        for target in node.targets
+        if isinstance(target, ast.Attribute)
+        and isinstance(target.value, ast.Name)
+        and target.value.id == "self"
+    }
+
+
+def parameters(function: FunctionDef) -> List[str]:
+    """Parameter names of ``function`` without ``self``."""
+    args = function.args
+    names = [arg.arg for arg in args.posonlyargs + args.args + args.kwonlyargs]
+    return [name for name in names if name != "self"]
+
+
+def load_generated(path: str) -> Scope:
+    with open(path) as file:
+        scope = Scope.from_body(ast.parse(file.read(), path).body)
+
+    for name, temp in list(scope.classes.items()):
+        if name.startswith(TEMP_CLASS_PREFIX):
+            target = scope.classes.get(name[len(TEMP_CLASS_PREFIX) :])
+            if target is not None:
+                target.classes.update(temp.classes)
+
+    return scope
+
+
+def check(stub: Scope, generated: Optional[Scope], path: str, errors: List[str]) -> None:
+    if generated is None:
+        errors.append(f"{path.rstrip('.')}: not in the generated bindings")
+        return
+
+    for name, function in stub.functions.items():
+        if name == "__getattr__":
+            continue
+        generated_function = generated.functions.get(name)
+        if generated_function is None:
+            errors.append(f"{path}{name}: not in the generated bindings")
+            continue
+        stub_parameters = parameters(function)
+        generated_parameters = parameters(generated_function)
+        if stub_parameters != generated_parameters:
+            errors.append(
+                f"{path}{name}: parameters {stub_parameters} do not match "
+                f"the generated {generated_parameters}"
+            )
+
+    for field in sorted(stub.fields - generated.fields):
+        errors.append(f"{path}{field}: not in the generated bindings")
+
+    for name, stub_class in stub.classes.items():
+        check(stub_class, generated.classes.get(name), f"{path}{name}.", errors)
+
+
+def main() -> int:
+    if len(sys.argv) != 3:
+        print(__doc__.strip().splitlines()[-1], file=sys.stderr)
+        return 2
+
+    stub_path, generated_path = sys.argv[1:]
+    with open(stub_path) as file:
+        stub = Scope.from_body(ast.parse(file.read(), stub_path).body)
+
+    errors: List[str] = []
+    check(stub, load_generated(generated_path), "", errors)
+
+    for error in errors:
+        print(f"{stub_path}: {error}", file=sys.stderr)
+
+    if errors:
+        print(
+            f"{len(errors)} stub declarations are out of date with the generated bindings",
+            file=sys.stderr,
+        )
+        return 1
+
+    print("Typing stubs match the generated bindings")
+    return 0
+
+
+if __name__ == "__main__":
+    sys.exit(main())
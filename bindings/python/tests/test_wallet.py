@@ -0,0 +1,68 @@
+"""Offline tests for the cdk Python package.
+
+Run with ``just test-python`` after building the package with
+``just binding-python``.
+"""
+
+import asyncio
+import tempfile
+from pathlib import Path
+
+import cdk
+
+
+TOKEN = (
+    "cashuBo2F0gqJhaUgA_9SLj17PgGFwgaNhYQFhc3hAYWNjMTI0MzVlN2I4NDg0YzNjZjE4NTAxNDky"
+    "MThhZjkwZjcxNmE1MmJmNGE1ZWQzNDdlNDhlY2MxM2Y3NzM4OGFjWCECRFODGd5IXVW-07KaZCvuWHk3"
+    "WrnnpiDhHki6SCQh88-iYWlIAK0mjE0fWCZhcIKjYWECYXN4QDEzMjNkM2Q0NzA3YTU4YWQyZTIzYWRh"
+    "NGU5ZjFmNDlmNWE1YjRhYzdiNzA4ZWIwZDYxZjczOGY0ODMwN2U4ZWVhY1ghAjRWqhENhLSsdHrr2Cw7"
+    "AFrKUL9Ffr1XN6RBT6w659lNo2FhAWFzeEA1NmJjYmNiYjdjYzY0MDZiM2ZhNWQ1N2QyMTc0ZjRlZmY4"
+    "YjQ0MDJiMTc2OTI2ZDNhNTdkM2MzZGNiYjU5ZDU3YWNYIQJzEpxXGeWZN5qXSmJjY8MzxWyvwObQGr5G"
+    "1YCCgHicY2FtdWh0dHA6Ly9sb2NhbGhvc3Q6MzMzOGF1Y3NhdA=="
+)
+
+
+def test_inspect_token():
+    info = cdk.inspect_token(TOKEN)
+
+    assert info.mint_url.url == "http://localhost:3338"
+    assert info.amount.value == 4
+    assert info.proof_count == 3
+
+
+def test_invalid_token_raises_ffi_error():
+    try:
+        cdk.inspect_token("not a token")
+    except cdk.FfiError:
+        pass
+    else:
+        raise AssertionError("expected FfiError")
+
+
+def test_wallet_balance_is_awaitable():
+    async def run():
+        with tempfile.TemporaryDirectory() as tmp:
+            wallet = cdk.Wallet(
+                "https://mint.example.com",
+                cdk.CurrencyUnit.SAT(),
+                cdk.generate_mnemonic(),
+                cdk.sqlite_wallet_store(str(Path(tmp) / "wallet.db")),
+                cdk.WalletConfig(target_proof_count=None),
+            )
+            balance = await wallet.total_balance()
+            assert balance.value == 0
+
+    asyncio.run(run())
+
+
+def test_wallet_repository_starts_empty():
+    async def run():
+        with tempfile.TemporaryDirectory() as tmp:
+            repository = cdk.WalletRepository(
+                cdk.generate_mnemonic(),
+                cdk.sqlite_wallet_store(str(Path(tmp) / "wallets.db")),
+            )
+            assert await repository.get_balances() == {}
+            assert await repository.get_wallets() == []
+
+    asyncio.run(run())
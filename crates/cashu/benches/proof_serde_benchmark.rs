@@ -0,0 +1,81 @@
+#![allow(missing_docs)]
+#![allow(clippy::unwrap_used)]
+use std::hint::black_box;
+use std::str::FromStr;
+
+use cashu::nuts::nut01::SecretKey;
+use cashu::secret::Secret;
+use cashu::{Amount, BlindedMessage, Id, Proof};
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+
+const KEYSET_ID: &str = "01adc013fa9d85171586660abab27579888611659d357bc86bc09cb26eee8bc035";
+
+fn generate_proofs(count: usize) -> Vec<Proof> {
+    let keyset_id = Id::from_str(KEYSET_ID).unwrap();
+    (0..count)
+        .map(|i| {
+            Proof::new(
+                Amount::from(1u64 << (i % 16)),
+                keyset_id,
+                Secret::generate(),
+                SecretKey::generate().public_key(),
+            )
+        })
+        .collect()
+}
+
+fn generate_blinded_messages(count: usize) -> Vec<BlindedMessage> {
+    let keyset_id = Id::from_str(KEYSET_ID).unwrap();
+    (0..count)
+        .map(|i| {
+            BlindedMessage::new(
+                Amount::from(1u64 << (i % 16)),
+                keyset_id,
+                SecretKey::generate().public_key(),
+            )
+        })
+        .collect()
+}
+
+fn bench_proof_serde(c: &mut Criterion) {
+    let mut group = c.benchmark_group("Proofs");
+
+    for size in [1, 100, 1000] {
+        let proofs = generate_proofs(size);
+        let json = serde_json::to_string(&proofs).unwrap();
+
+        group.bench_with_input(BenchmarkId::new("serialize", size), &proofs, |b, proofs| {
+            b.iter(|| serde_json::to_string(black_box(proofs)).unwrap());
+        });
+        group.bench_with_input(BenchmarkId::new("deserialize", size), &json, |b, json| {
+            b.iter(|| serde_json::from_str::<Vec<Proof>>(black_box(json)).unwrap());
+        });
+    }
+
+    group.finish();
+}
+
+fn bench_blinded_message_serde(c: &mut Criterion) {
+    let mut group = c.benchmark_group("BlindedMessages");
+
+    for size in [1, 100, 1000] {
+        let outputs = generate_blinded_messages(size);
+        let json = serde_json::to_string(&outputs).unwrap();
+
+        group.bench_with_input(
+            BenchmarkId::new("serialize", size),
+            &outputs,
+            |b, outputs| {
+                b.iter(|| serde_json::to_string(black_box(outputs)).unwrap());
+            },
+        );
+        group.bench_with_input(BenchmarkId::new("deserialize", size), &json, |b, json| {
+            b.iter(|| serde_json::from_str::<Vec<BlindedMessage>>(black_box(json)).unwrap());
+        });
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, bench_proof_serde, bench_blinded_message_serde);
+criterion_main!(benches);
@@ -23,6 +23,12 @@ pub enum Error {
     /// Amount overflow
     #[error("Amount Overflow")]
     AmountOverflow,
+    /// Amount underflow
+    #[error("Amount Underflow: cannot subtract {1} from {0}")]
+    AmountUnderflow(u64, u64),
+    /// Msat amount is not a whole number of sats
+    #[error("Amount of {0} msat is not a whole number of sats")]
+    FractionalSat(u64),
     /// Cannot convert units
     #[error("Cannot convert units")]
     CannotConvertUnits,
@@ -62,11 +68,13 @@ pub struct FeeAndAmounts {
 }
 
 impl From<(u64, Vec<u64>)> for FeeAndAmounts {
-    fn from(value: (u64, Vec<u64>)) -> Self {
-        Self {
-            fee: value.0,
-            amounts: value.1,
-        }
+    fn from((fee, mut amounts): (u64, Vec<u64>)) -> Self {
+        // Splitting relies on ascending amounts
+        amounts.sort_unstable();
+        amounts.dedup();
+        amounts.retain(|amount| *amount > 0);
+
+        Self { fee, amounts }
     }
 }
 
@@ -198,7 +206,9 @@ impl Amount<()> {
     ///
     /// Uses a greedy algorithm starting from the largest denomination,
     /// taking as many of each denomination as possible before moving
-    /// to the next smaller one.
+    /// to the next smaller one. This gives the fewest parts for powers of two
+    /// and 1/2/5 style denominations. For other denominations, where the greedy
+    /// split can fail (e.g. 9 from 3 and 5), the fewest parts are searched for.
     ///
     /// Returns an error if the amount cannot be fully represented
     /// with the available denominations.
@@ -218,7 +228,8 @@ impl Amount<()> {
 
         let sum: u64 = parts.iter().map(|a| a.value).sum();
         if sum != self.value {
-            return Err(Error::CannotSplitAmount(self.value, sum));
+            return search_split(self.value, &fee_and_amounts.amounts)
+                .ok_or(Error::CannotSplitAmount(self.value, sum));
         }
 
         Ok(parts)
@@ -349,15 +360,142 @@ impl Amount<()> {
             .map(|v| Amount { value: v, unit: () })
     }
 
+    /// Addition returning [`Error::AmountOverflow`] on overflow
+    pub fn try_add(self, other: Amount<()>) -> Result<Amount<()>, Error> {
+        self.checked_add(other).ok_or(Error::AmountOverflow)
+    }
+
+    /// Subtraction returning [`Error::AmountUnderflow`] if `other` is greater than `self`
+    pub fn try_sub(self, other: Amount<()>) -> Result<Amount<()>, Error> {
+        self.checked_sub(other)
+            .ok_or(Error::AmountUnderflow(self.value, other.value))
+    }
+
+    /// Multiplication returning [`Error::AmountOverflow`] on overflow
+    pub fn try_mul(self, other: Amount<()>) -> Result<Amount<()>, Error> {
+        self.checked_mul(other).ok_or(Error::AmountOverflow)
+    }
+
+    /// Adds `other` to `self`, returning the maximum amount if the result would overflow.
+    pub fn saturating_add(self, other: Self) -> Self {
+        Amount {
+            value: self.value.saturating_add(other.value),
+            unit: (),
+        }
+    }
+
     /// Subtracts `other` from `self`, returning zero if the result would be negative.
     pub fn saturating_sub(self, other: Self) -> Self {
-        if other > self {
-            Self::ZERO
-        } else {
-            self - other
+        Amount {
+            value: self.value.saturating_sub(other.value),
+            unit: (),
         }
     }
 
+    /// Sat amount of `msat` millisatoshis, rounded with `rounding`
+    ///
+    /// ```
+    /// # use cashu::{Amount, amount::MsatRounding};
+    /// assert_eq!(
+    ///     Amount::from_msat(1_500, MsatRounding::Down).unwrap(),
+    ///     Amount::from(1)
+    /// );
+    /// assert_eq!(
+    ///     Amount::from_msat(1_500, MsatRounding::Up).unwrap(),
+    ///     Amount::from(2)
+    /// );
+    /// assert!(Amount::from_msat(1_500, MsatRounding::Exact).is_err());
+    /// ```
+    pub fn from_msat(msat: u64, rounding: MsatRounding) -> Result<Self, Error> {
+        let sat = match rounding {
+            MsatRounding::Down => msat / MSAT_IN_SAT,
+            MsatRounding::Up => msat.div_ceil(MSAT_IN_SAT),
+            MsatRounding::Exact if msat % MSAT_IN_SAT == 0 => msat / MSAT_IN_SAT,
+            MsatRounding::Exact => return Err(Error::FractionalSat(msat)),
+        };
+
+        Ok(Amount::from(sat))
+    }
+
+    /// Format the amount as an amount of `unit` with the unit's precision
+    ///
+    /// ```
+    /// # use cashu::{Amount, nuts::CurrencyUnit};
+    /// assert_eq!(
+    ///     Amount::from(21).display_with_unit(&CurrencyUnit::Sat),
+    ///     "21 sat"
+    /// );
+    /// assert_eq!(
+    ///     Amount::from(150).display_with_unit(&CurrencyUnit::Usd),
+    ///     "1.50 usd"
+    /// );
+    /// ```
+    pub fn display_with_unit(&self, unit: &CurrencyUnit) -> String {
+        let decimals = unit.decimals();
+        if decimals == 0 {
+            return format!("{} {}", self.value, unit);
+        }
+
+        let scale = 10u64.pow(decimals);
+        format!(
+            "{}.{:0width$} {}",
+            self.value / scale,
+            self.value % scale,
+            unit,
+            width = decimals as usize
+        )
+    }
+
+    /// Parse a human readable amount into an amount of `unit`
+    ///
+    /// The string is a decimal number optionally followed by a unit, e.g. `1.50 usd`. Without a
+    /// unit it is read as an amount of `unit`. The number can have at most as many decimals as
+    /// `unit` has, except that sats can be given with up to 3 decimals for an msat amount.
+    ///
+    /// ```
+    /// # use cashu::{Amount, nuts::CurrencyUnit};
+    /// let usd = Amount::from_str_with_unit("1.5 usd", &CurrencyUnit::Usd).unwrap();
+    /// assert_eq!(usd, Amount::from(150));
+    ///
+    /// let msat = Amount::from_str_with_unit("0.5 sat", &CurrencyUnit::Msat).unwrap();
+    /// assert_eq!(msat, Amount::from(500));
+    ///
+    /// assert!(Amount::from_str_with_unit("0.5 sat", &CurrencyUnit::Sat).is_err());
+    /// ```
+    pub fn from_str_with_unit(s: &str, unit: &CurrencyUnit) -> Result<Self, Error> {
+        let s = s.trim();
+        let (number, from_unit) = match s.split_once(char::is_whitespace) {
+            Some((number, from_unit)) => (
+                number,
+                CurrencyUnit::from_str(from_unit.trim())
+                    .map_err(|_| Error::InvalidAmount(s.to_owned()))?,
+            ),
+            None => (s, unit.clone()),
+        };
+
+        // Compare display names so custom units match regardless of case
+        let value = if from_unit.to_string() == unit.to_string() {
+            parse_decimal(number, unit.decimals())?
+        } else {
+            match (&from_unit, unit) {
+                (CurrencyUnit::Sat, CurrencyUnit::Msat) => parse_decimal(number, 3)?,
+                (CurrencyUnit::Msat, CurrencyUnit::Sat) => {
+                    Amount::from_msat(parse_decimal(number, 0)?, MsatRounding::Exact)?.value
+                }
+                _ => return Err(Error::UnitMismatch(from_unit, unit.clone())),
+            }
+        };
+
+        Ok(Amount::from(value))
+    }
+
+    /// Millisatoshis of a sat amount, returning [`Error::AmountOverflow`] on overflow
+    pub fn to_msat(self) -> Result<u64, Error> {
+        self.value
+            .checked_mul(MSAT_IN_SAT)
+            .ok_or(Error::AmountOverflow)
+    }
+
     /// Try sum to check for overflow
     pub fn try_sum<I>(iter: I) -> Result<Self, Error>
     where
@@ -545,25 +683,42 @@ impl Amount<CurrencyUnit> {
     /// assert_eq!(msat.unit(), &CurrencyUnit::Msat);
     /// ```
     pub fn convert_to(&self, target_unit: &CurrencyUnit) -> Result<Self, Error> {
+        self.convert_to_with_rounding(target_unit, MsatRounding::Down)
+    }
+
+    /// Convert to another unit, rounding msat to sat conversions with `rounding`
+    ///
+    /// ```
+    /// # use cashu::{Amount, amount::MsatRounding, nuts::CurrencyUnit};
+    /// let msat = Amount::new(1_001, CurrencyUnit::Msat);
+    /// let sat = msat
+    ///     .convert_to_with_rounding(&CurrencyUnit::Sat, MsatRounding::Up)
+    ///     .unwrap();
+    /// assert_eq!(sat.value(), 2);
+    /// ```
+    pub fn convert_to_with_rounding(
+        &self,
+        target_unit: &CurrencyUnit,
+        rounding: MsatRounding,
+    ) -> Result<Self, Error> {
         if &self.unit == target_unit {
             return Ok(self.clone());
         }
 
         let converted_value = match (&self.unit, target_unit) {
-            (CurrencyUnit::Sat, CurrencyUnit::Msat) => self
-                .value
-                .checked_mul(MSAT_IN_SAT)
-                .ok_or(Error::AmountOverflow)?,
-            (CurrencyUnit::Msat, CurrencyUnit::Sat) => self.value / MSAT_IN_SAT,
+            (CurrencyUnit::Sat, CurrencyUnit::Msat) => Amount::from(self.value).to_msat()?,
+            (CurrencyUnit::Msat, CurrencyUnit::Sat) => {
+                Amount::from_msat(self.value, rounding)?.to_u64()
+            }
             _ => return Err(Error::CannotConvertUnits),
         };
 
         Ok(Amount::new(converted_value, target_unit.clone()))
     }
 
-    /// Returns a string representation that includes the unit
+    /// Returns a string representation that includes the unit, with the unit's precision
     pub fn display_with_unit(&self) -> String {
-        format!("{} {}", self.value, self.unit)
+        Amount::from(self.value).display_with_unit(&self.unit)
     }
 
     /// Convert to millisatoshis and return the raw u64 value
@@ -720,6 +875,139 @@ pub enum SplitTarget {
 /// Msats in sat
 pub const MSAT_IN_SAT: u64 = 1000;
 
+/// Maximum number of denomination counts [`search_split`] tries
+const SPLIT_SEARCH_LIMIT: usize = 100_000;
+
+/// Split `value` into the fewest parts of `amounts` (ascending), for denominations where the
+/// greedy split fails
+///
+/// Tries the counts of each denomination from the largest down, pruning partial splits that
+/// cannot beat the best split found. The count of the last denomination is solved directly.
+/// Gives up after trying [`SPLIT_SEARCH_LIMIT`] counts, returning the best split found so far.
+fn search_split(value: u64, amounts: &[u64]) -> Option<Vec<Amount>> {
+    struct Search {
+        amounts: Vec<u64>,
+        counts: Vec<u64>,
+        best: Option<(u64, Vec<u64>)>,
+        visited: usize,
+    }
+
+    impl Search {
+        fn record(&mut self, parts: u64) {
+            if self.best.as_ref().is_none_or(|(best, _)| parts < *best) {
+                self.best = Some((parts, self.counts.clone()));
+            }
+        }
+
+        fn visit(&mut self, index: usize, remaining: u64, parts: u64) {
+            if remaining == 0 {
+                self.record(parts);
+                return;
+            }
+
+            let Some(&amount) = self.amounts.get(index) else {
+                return;
+            };
+
+            // Every remaining part is at most `amount`
+            if let Some((best, _)) = &self.best {
+                if parts.saturating_add(remaining.div_ceil(amount)) >= *best {
+                    return;
+                }
+            }
+
+            // The last denomination either divides what remains or cannot complete the split
+            if index + 1 == self.amounts.len() {
+                if remaining % amount == 0 {
+                    self.counts[index] = remaining / amount;
+                    self.record(parts + remaining / amount);
+                    self.counts[index] = 0;
+                }
+                return;
+            }
+
+            for count in (0..=remaining / amount).rev() {
+                if self.visited >= SPLIT_SEARCH_LIMIT {
+                    break;
+                }
+                self.visited += 1;
+
+                self.counts[index] = count;
+                self.visit(index + 1, remaining - count * amount, parts + count);
+            }
+            self.counts[index] = 0;
+        }
+    }
+
+    let amounts: Vec<u64> = amounts.iter().rev().copied().filter(|a| *a > 0).collect();
+    let mut search = Search {
+        counts: vec![0; amounts.len()],
+        amounts,
+        best: None,
+        visited: 0,
+    };
+    search.visit(0, value, 0);
+
+    let (_, counts) = search.best?;
+    Some(
+        search
+            .amounts
+            .iter()
+            .zip(counts)
+            .flat_map(|(amount, count)| (0..count).map(|_| Amount::from(*amount)))
+            .collect(),
+    )
+}
+
+/// Parse a decimal number with at most `decimals` decimal places into an integer scaled by
+/// `10^decimals`
+fn parse_decimal(number: &str, decimals: u32) -> Result<u64, Error> {
+    let (integer, fraction) = number.split_once('.').unwrap_or((number, ""));
+    if (integer.is_empty() && fraction.is_empty())
+        || !integer
+            .chars()
+            .chain(fraction.chars())
+            .all(|c| c.is_ascii_digit())
+    {
+        return Err(Error::InvalidAmount(number.to_owned()));
+    }
+    if fraction.len() > decimals as usize {
+        return Err(Error::InvalidAmount(format!(
+            "{number} has more than {decimals} decimal places"
+        )));
+    }
+
+    // Only digits are left, so parsing can only fail on overflow
+    let integer: u64 = match integer {
+        "" => 0,
+        integer => integer.parse().map_err(|_| Error::AmountOverflow)?,
+    };
+    let fraction: u64 = match fraction {
+        "" => 0,
+        fraction => {
+            fraction.parse::<u64>().map_err(|_| Error::AmountOverflow)?
+                * 10u64.pow(decimals - fraction.len() as u32)
+        }
+    };
+
+    integer
+        .checked_mul(10u64.pow(decimals))
+        .and_then(|value| value.checked_add(fraction))
+        .ok_or(Error::AmountOverflow)
+}
+
+/// Rounding of msat amounts that are not a whole number of sats
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum MsatRounding {
+    /// Round down, dropping the remaining msats
+    #[default]
+    Down,
+    /// Round up to the next sat, e.g. for fees and amounts that must be covered
+    Up,
+    /// Return [`Error::FractionalSat`] if the msats are not a whole number of sats
+    Exact,
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -2085,8 +2373,8 @@ mod tests {
         let amount = Amount::new(50, CurrencyUnit::Msat);
         assert_eq!(amount.display_with_unit(), "50 msat");
 
-        let amount = Amount::new(100, CurrencyUnit::Usd);
-        assert_eq!(amount.display_with_unit(), "100 usd");
+        let amount = Amount::new(150, CurrencyUnit::Usd);
+        assert_eq!(amount.display_with_unit(), "1.50 usd");
 
         let amount = Amount::new(123, CurrencyUnit::Custom("BTC".to_string()));
         assert_eq!(amount.display_with_unit(), "123 btc");
@@ -2209,6 +2497,161 @@ mod tests {
         assert_eq!(result, Amount::ZERO);
     }
 
+    #[test]
+    fn test_try_ops_return_typed_errors() {
+        assert_eq!(
+            Amount::from(1).try_add(Amount::from(2)).unwrap(),
+            Amount::from(3)
+        );
+        assert!(matches!(
+            Amount::from(u64::MAX).try_add(Amount::from(1)),
+            Err(Error::AmountOverflow)
+        ));
+        assert_eq!(
+            Amount::from(5).try_sub(Amount::from(5)).unwrap(),
+            Amount::ZERO
+        );
+        assert!(matches!(
+            Amount::from(5).try_sub(Amount::from(6)),
+            Err(Error::AmountUnderflow(5, 6))
+        ));
+        assert!(matches!(
+            Amount::from(u64::MAX).try_mul(Amount::from(2)),
+            Err(Error::AmountOverflow)
+        ));
+        assert_eq!(
+            Amount::from(u64::MAX).saturating_add(Amount::from(1)),
+            Amount::from(u64::MAX)
+        );
+    }
+
+    #[test]
+    fn test_from_msat_rounding() {
+        assert_eq!(
+            Amount::from_msat(2_999, MsatRounding::Down).unwrap(),
+            Amount::from(2)
+        );
+        assert_eq!(
+            Amount::from_msat(2_001, MsatRounding::Up).unwrap(),
+            Amount::from(3)
+        );
+        assert_eq!(
+            Amount::from_msat(2_000, MsatRounding::Up).unwrap(),
+            Amount::from(2)
+        );
+        assert_eq!(
+            Amount::from_msat(2_000, MsatRounding::Exact).unwrap(),
+            Amount::from(2)
+        );
+        assert!(matches!(
+            Amount::from_msat(2_001, MsatRounding::Exact),
+            Err(Error::FractionalSat(2_001))
+        ));
+        assert_eq!(
+            Amount::from_msat(u64::MAX, MsatRounding::Up).unwrap(),
+            Amount::from(u64::MAX / MSAT_IN_SAT + 1)
+        );
+    }
+
+    #[test]
+    fn test_sat_to_msat() {
+        assert_eq!(Amount::from(21).to_msat().unwrap(), 21_000);
+        assert!(matches!(
+            Amount::from(u64::MAX).to_msat(),
+            Err(Error::AmountOverflow)
+        ));
+    }
+
+    #[test]
+    fn test_convert_to_with_rounding() {
+        let msat = Amount::new(1_500, CurrencyUnit::Msat);
+
+        assert_eq!(msat.convert_to(&CurrencyUnit::Sat).unwrap().value(), 1);
+        assert_eq!(
+            msat.convert_to_with_rounding(&CurrencyUnit::Sat, MsatRounding::Up)
+                .unwrap()
+                .value(),
+            2
+        );
+        assert!(msat
+            .convert_to_with_rounding(&CurrencyUnit::Sat, MsatRounding::Exact)
+            .is_err());
+    }
+
+    #[test]
+    fn test_display_with_unit_precision() {
+        assert_eq!(
+            Amount::from(21).display_with_unit(&CurrencyUnit::Sat),
+            "21 sat"
+        );
+        assert_eq!(
+            Amount::from(1_500).display_with_unit(&CurrencyUnit::Msat),
+            "1500 msat"
+        );
+        assert_eq!(
+            Amount::from(150).display_with_unit(&CurrencyUnit::Usd),
+            "1.50 usd"
+        );
+        assert_eq!(
+            Amount::from(5).display_with_unit(&CurrencyUnit::Eur),
+            "0.05 eur"
+        );
+    }
+
+    #[test]
+    fn test_from_str_with_unit() {
+        let parse = |s: &str, unit: CurrencyUnit| Amount::from_str_with_unit(s, &unit);
+
+        assert_eq!(parse("21", CurrencyUnit::Sat).unwrap(), Amount::from(21));
+        assert_eq!(
+            parse(" 21 sat ", CurrencyUnit::Sat).unwrap(),
+            Amount::from(21)
+        );
+        assert_eq!(
+            parse("1.00 usd", CurrencyUnit::Usd).unwrap(),
+            Amount::from(100)
+        );
+        assert_eq!(
+            parse("1.5 USD", CurrencyUnit::Usd).unwrap(),
+            Amount::from(150)
+        );
+        assert_eq!(parse(".05", CurrencyUnit::Eur).unwrap(), Amount::from(5));
+        assert_eq!(
+            parse("0.5 sat", CurrencyUnit::Msat).unwrap(),
+            Amount::from(500)
+        );
+        assert_eq!(
+            parse("2000 msat", CurrencyUnit::Sat).unwrap(),
+            Amount::from(2)
+        );
+
+        assert!(parse("0.5 sat", CurrencyUnit::Sat).is_err());
+        assert!(parse("1.001 usd", CurrencyUnit::Usd).is_err());
+        assert!(parse("1500 msat", CurrencyUnit::Sat).is_err());
+        assert!(parse("1 usd", CurrencyUnit::Sat).is_err());
+        assert!(parse("-1", CurrencyUnit::Sat).is_err());
+        assert!(parse(".", CurrencyUnit::Sat).is_err());
+        assert!(parse("", CurrencyUnit::Sat).is_err());
+        assert!(matches!(
+            parse("18446744073709551615 usd", CurrencyUnit::Usd),
+            Err(Error::AmountOverflow)
+        ));
+    }
+
+    #[test]
+    fn test_display_with_unit_roundtrip() {
+        for unit in [CurrencyUnit::Sat, CurrencyUnit::Msat, CurrencyUnit::Usd] {
+            for value in [0, 1, 99, 100, 12_345] {
+                let amount = Amount::from(value);
+                let displayed = amount.display_with_unit(&unit);
+                assert_eq!(
+                    Amount::from_str_with_unit(&displayed, &unit).unwrap(),
+                    amount
+                );
+            }
+        }
+    }
+
     // =========================================================================
     // Tests for restricted/non-standard keysets (denomination reuse required)
     // =========================================================================
@@ -2359,6 +2802,90 @@ mod tests {
         assert_eq!(result, vec![Amount::from(4), Amount::from(4)]);
     }
 
+    /// Tests split() with a fiat style 1/2/5 keyset.
+    #[test]
+    fn test_split_one_two_five_keyset() {
+        let fee_and_amounts: FeeAndAmounts = (0, vec![1, 2, 5, 10, 20, 50, 100, 200, 500]).into();
+
+        let result = Amount::from(388).split(&fee_and_amounts).unwrap();
+        assert_eq!(
+            result,
+            [200, 100, 50, 20, 10, 5, 2, 1]
+                .into_iter()
+                .map(Amount::from)
+                .collect::<Vec<_>>()
+        );
+
+        let result = Amount::from(1_250).split(&fee_and_amounts).unwrap();
+        assert_eq!(
+            result,
+            [500, 500, 200, 50]
+                .into_iter()
+                .map(Amount::from)
+                .collect::<Vec<_>>()
+        );
+    }
+
+    /// Tests split() with denominations {3, 5} where the greedy split fails.
+    /// Amount 9 cannot be split as 5 + ... but is 3 + 3 + 3.
+    #[test]
+    fn test_split_searches_when_greedy_fails() {
+        let fee_and_amounts: FeeAndAmounts = (0, vec![3, 5]).into();
+
+        let result = Amount::from(9).split(&fee_and_amounts).unwrap();
+        assert_eq!(result, vec![Amount::from(3); 3]);
+
+        // 13 = 5 + 5 + 3, greedy leaves 3 after 5 + 5 and succeeds
+        let result = Amount::from(13).split(&fee_and_amounts).unwrap();
+        assert_eq!(
+            result,
+            vec![Amount::from(5), Amount::from(5), Amount::from(3)]
+        );
+
+        // 11 = 5 + 3 + 3 needs the search, greedy leaves 1 after 5 + 5
+        let result = Amount::from(11).split(&fee_and_amounts).unwrap();
+        assert_eq!(
+            result,
+            vec![Amount::from(5), Amount::from(3), Amount::from(3)]
+        );
+
+        // 7 cannot be represented at all
+        assert!(matches!(
+            Amount::from(7).split(&fee_and_amounts),
+            Err(Error::CannotSplitAmount(7, 5))
+        ));
+    }
+
+    /// Tests that the split search gives up on a large amount no split exists for.
+    /// An odd amount cannot be made from even denominations.
+    #[test]
+    fn test_split_search_is_bounded_for_unsplittable_amount() {
+        let fee_and_amounts: FeeAndAmounts = (0, vec![4, 6]).into();
+        assert!(matches!(
+            Amount::from(1_000_001).split(&fee_and_amounts),
+            Err(Error::CannotSplitAmount(1_000_001, 1_000_000))
+        ));
+
+        let fee_and_amounts: FeeAndAmounts = (0, vec![4, 6, 10]).into();
+        assert!(matches!(
+            Amount::from(1_000_001).split(&fee_and_amounts),
+            Err(Error::CannotSplitAmount(1_000_001, 1_000_000))
+        ));
+    }
+
+    /// Tests that the denominations of a keyset are sorted when building [`FeeAndAmounts`].
+    #[test]
+    fn test_fee_and_amounts_sorts_denominations() {
+        let fee_and_amounts: FeeAndAmounts = (0, vec![5, 1, 2, 2, 0]).into();
+        assert_eq!(fee_and_amounts.amounts(), &[1, 2, 5]);
+
+        let result = Amount::from(8).split(&fee_and_amounts).unwrap();
+        assert_eq!(
+            result,
+            vec![Amount::from(5), Amount::from(2), Amount::from(1)]
+        );
+    }
+
     /// Tests split() with a large amount on a single-denomination keyset {1}.
     /// Should produce many proofs of denomination 1.
     #[test]
@@ -48,7 +48,7 @@ pub use nut00::{PreMint, PreMintSecrets};
 pub use nut01::{Keys, KeysResponse, PublicKey, SecretKey};
 #[cfg(feature = "mint")]
 pub use nut02::MintKeySet;
-pub use nut02::{Id, KeySet, KeySetInfo, KeysetResponse};
+pub use nut02::{invalid_keyset_ids, Id, KeySet, KeySetInfo, KeysetResponse};
 #[cfg(feature = "wallet")]
 pub use nut03::PreSwap;
 pub use nut03::{SwapRequest, SwapResponse};
@@ -57,15 +57,15 @@ pub use nut04::{
     Settings as NUT04Settings,
 };
 pub use nut05::{
-    MeltMethodSettings, MeltQuoteCustomRequest, MeltQuoteCustomResponse, MeltRequest,
-    QuoteState as MeltQuoteState, Settings as NUT05Settings,
+    AppliedMeltChangePolicy, MeltChangePolicy, MeltMethodSettings, MeltQuoteCustomRequest,
+    MeltQuoteCustomResponse, MeltRequest, QuoteState as MeltQuoteState, Settings as NUT05Settings,
 };
 pub use nut06::{ContactInfo, MintInfo, MintVersion, Nuts};
 pub use nut07::{CheckStateRequest, CheckStateResponse, ProofState, State};
 pub use nut09::{RestoreRequest, RestoreResponse};
 pub use nut10::{
-    Conditions, Kind, Secret as Nut10Secret, SecretData, SpendingConditionVerification,
-    SpendingConditions,
+    Conditions, Kind, Nut10SecretBuilder, Secret as Nut10Secret, SecretData,
+    SpendingConditionVerification, SpendingConditions,
 };
 pub use nut11::{P2PKWitness, SigFlag};
 pub use nut12::{BlindSignatureDleq, ProofDleq};
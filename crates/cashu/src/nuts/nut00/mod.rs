@@ -210,9 +210,20 @@ pub enum Error {
     /// NUT11 error
     #[error(transparent)]
     NUT11(#[from] crate::nuts::nut11::Error),
+    /// Token proof of an amount the provided keys have no key for
+    #[error("No key for amount {amount} of keyset {keyset_id}")]
+    MissingKey {
+        /// Keyset of the proof
+        keyset_id: ShortKeysetId,
+        /// Amount of the proof
+        amount: Amount,
+    },
     /// Short keyset id -> id error
     #[error(transparent)]
     NUT02(#[from] crate::nuts::nut02::Error),
+    /// NUT12 error
+    #[error(transparent)]
+    NUT12(#[from] crate::nuts::nut12::Error),
     #[cfg(feature = "wallet")]
     #[error(transparent)]
     /// NUT28 P2BK error
@@ -611,6 +622,19 @@ pub enum CurrencyUnit {
     Custom(String),
 }
 
+impl CurrencyUnit {
+    /// Number of decimal places amounts of the unit are displayed with
+    ///
+    /// Amounts are in the smallest denomination of the unit, e.g. cents for
+    /// [`CurrencyUnit::Usd`], so `150` is displayed as `1.50 usd`.
+    pub fn decimals(&self) -> u32 {
+        match self {
+            Self::Usd | Self::Eur => 2,
+            _ => 0,
+        }
+    }
+}
+
 #[cfg(feature = "mint")]
 impl CurrencyUnit {
     /// Derivation index mint will use for unit
@@ -2,7 +2,7 @@
 //!
 //! <https://github.com/cashubtc/nuts/blob/main/00.md>
 
-use std::collections::{BTreeSet, HashMap, HashSet};
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
 use std::fmt;
 use std::str::FromStr;
 
@@ -15,7 +15,7 @@ use super::{Error, Proof, ProofV3, ProofV4, Proofs};
 use crate::mint_url::MintUrl;
 use crate::nut02::ShortKeysetId;
 use crate::nuts::nut10::SpendingConditions;
-use crate::nuts::{CurrencyUnit, Id, Kind, PublicKey};
+use crate::nuts::{CurrencyUnit, Id, Keys, Kind, PublicKey};
 use crate::{ensure_cdk, Amount, KeySetInfo};
 
 /// Token Enum
@@ -131,6 +131,93 @@ impl Token {
         }
     }
 
+    /// Number of proofs in [`Token`]
+    pub fn proof_count(&self) -> usize {
+        match self {
+            Self::TokenV3(token) => token.token.iter().map(|t| t.proofs.len()).sum(),
+            Self::TokenV4(token) => token.token.iter().map(|t| t.proofs.len()).sum(),
+        }
+    }
+
+    /// Number of proofs in [`Token`] that include a DLEQ proof
+    pub fn dleq_proof_count(&self) -> usize {
+        match self {
+            Self::TokenV3(token) => token
+                .token
+                .iter()
+                .flat_map(|t| t.proofs.iter())
+                .filter(|p| p.dleq.is_some())
+                .count(),
+            Self::TokenV4(token) => token
+                .token
+                .iter()
+                .flat_map(|t| t.proofs.iter())
+                .filter(|p| p.dleq.is_some())
+                .count(),
+        }
+    }
+
+    /// Attach the keys of the keysets of the proofs, so receivers can verify the DLEQ proofs offline
+    ///
+    /// Keys are only included in V4 tokens, in the `k` field. This field is a CDK extension and
+    /// not part of NUT-00. Keysets missing from `keys` are left without keys.
+    pub fn with_keys(self, keys: &HashMap<Id, Keys>) -> Self {
+        match self {
+            Self::TokenV3(token) => Self::TokenV3(token),
+            Self::TokenV4(mut token) => {
+                for t in token.token.iter_mut() {
+                    t.keys = keys
+                        .iter()
+                        .find(|(id, _)| ShortKeysetId::from(**id) == t.keyset_id)
+                        .map(|(_, keys)| keys.clone());
+                }
+                Self::TokenV4(token)
+            }
+        }
+    }
+
+    /// Keys included in the token by the sender, by short keyset id
+    ///
+    /// These keys come from the sender, a receiver has to check they belong to the mint before
+    /// trusting [`Token::verify_offline`] with them.
+    pub fn included_keys(&self) -> HashMap<ShortKeysetId, Keys> {
+        match self {
+            Self::TokenV3(_) => HashMap::new(),
+            Self::TokenV4(token) => token
+                .token
+                .iter()
+                .filter_map(|t| t.keys.clone().map(|keys| (t.keyset_id.clone(), keys)))
+                .collect(),
+        }
+    }
+
+    /// Verify the DLEQ proofs of all proofs in the token against `keys` without contacting the mint
+    ///
+    /// Every proof must carry a valid DLEQ proof for the key of its amount.
+    pub fn verify_offline(&self, keys: &HashMap<ShortKeysetId, Keys>) -> Result<(), Error> {
+        let mint_key = |keyset_id: &ShortKeysetId, amount: Amount| {
+            keys.get(keyset_id)
+                .and_then(|keys| keys.amount_key(amount))
+                .ok_or_else(|| Error::MissingKey {
+                    keyset_id: keyset_id.clone(),
+                    amount,
+                })
+        };
+
+        match self {
+            Self::TokenV3(token) => token
+                .token
+                .iter()
+                .flat_map(|t| t.proofs.iter())
+                .try_for_each(|p| Ok(p.verify_dleq(mint_key(&p.keyset_id, p.amount)?)?)),
+            Self::TokenV4(token) => token.token.iter().try_for_each(|t| {
+                t.proofs
+                    .iter()
+                    .try_for_each(|p| Ok(p.verify_dleq(mint_key(&t.keyset_id, p.amount)?)?))
+            }),
+        }
+    }
+
     /// Return all proof secrets in this token without keyset-id mapping, across V3/V4
     /// This is intended for spending-condition inspection where only the secret matters.
     pub fn token_secrets(&self) -> Vec<&crate::secret::Secret> {
@@ -566,6 +653,7 @@ impl TryFrom<TokenV3> for TokenV4 {
             .map(|(id, proofs)| TokenV4Token {
                 keyset_id: id,
                 proofs,
+                keys: None,
             })
             .collect();
 
@@ -591,6 +679,17 @@ pub struct TokenV4Token {
     /// Proofs
     #[serde(rename = "p")]
     pub proofs: Vec<ProofV4>,
+    /// Keys of the keyset, for offline verification of the DLEQ proofs
+    ///
+    /// CDK extension, not part of NUT-00. Only set when the sender opts in.
+    #[serde(
+        rename = "k",
+        default,
+        skip_serializing_if = "Option::is_none",
+        serialize_with = "serialize_v4_keys",
+        deserialize_with = "deserialize_v4_keys"
+    )]
+    pub keys: Option<Keys>,
 }
 
 fn serialize_v4_keyset_id<S>(keyset_id: &ShortKeysetId, serializer: S) -> Result<S::Ok, S::Error>
@@ -608,6 +707,47 @@ where
     ShortKeysetId::from_bytes(&bytes).map_err(serde::de::Error::custom)
 }
 
+fn serialize_v4_keys<S>(keys: &Option<Keys>, serializer: S) -> Result<S::Ok, S::Error>
+where
+    S: serde::Serializer,
+{
+    use serde::ser::SerializeMap;
+
+    /// Public key serialized as raw bytes, like the proof signatures
+    struct KeyBytes<'a>(&'a PublicKey);
+
+    impl Serialize for KeyBytes<'_> {
+        fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            serializer.serialize_bytes(&self.0.to_bytes())
+        }
+    }
+
+    let Some(keys) = keys else {
+        return serializer.serialize_none();
+    };
+
+    let mut map = serializer.serialize_map(Some(keys.len()))?;
+    for (amount, key) in keys.iter() {
+        map.serialize_entry(&u64::from(*amount), &KeyBytes(key))?;
+    }
+    map.end()
+}
+
+fn deserialize_v4_keys<'de, D>(deserializer: D) -> Result<Option<Keys>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    BTreeMap::<u64, Vec<u8>>::deserialize(deserializer)?
+        .into_iter()
+        .map(|(amount, key)| {
+            PublicKey::from_slice(&key)
+                .map(|key| (Amount::from(amount), key))
+                .map_err(serde::de::Error::custom)
+        })
+        .collect::<Result<BTreeMap<_, _>, _>>()
+        .map(|keys| Some(Keys::new(keys)))
+}
+
 impl TokenV4Token {
     /// Create new [`TokenV4Token`]
     pub fn new(keyset_id: Id, proofs: Proofs) -> Self {
@@ -616,6 +756,7 @@ impl TokenV4Token {
         Self {
             keyset_id: short_id,
             proofs: proofs.into_iter().map(Into::into).collect(),
+            keys: None,
         }
     }
 }
@@ -763,6 +904,8 @@ mod tests {
 
         let unit = token.unit().unwrap();
         assert_eq!(CurrencyUnit::Sat, unit);
+        assert_eq!(token.proof_count(), 3);
+        assert_eq!(token.dleq_proof_count(), 0);
 
         match token {
             Token::TokenV4(token) => {
@@ -975,6 +1118,67 @@ mod tests {
         //println!("{:?}", proofs1);
     }
 
+    #[test]
+    fn test_token_with_keys_verifies_offline() {
+        use crate::dhke::{blind_message, construct_proofs, sign_message};
+        use crate::nuts::{BlindSignature, ProofsMethods, SecretKey};
+
+        let mint_url = MintUrl::from_str("https://example.com").unwrap();
+        let keyset_id = Id::from_str("00ad268c4d1f5826").unwrap();
+        let mint_key = SecretKey::generate();
+        let keys = Keys::new(BTreeMap::from([(Amount::from(8), mint_key.public_key())]));
+
+        let secret = Secret::generate();
+        let (blinded_message, r) = blind_message(secret.as_bytes(), None).unwrap();
+        let signature = BlindSignature::new(
+            Amount::from(8),
+            sign_message(&mint_key, &blinded_message).unwrap(),
+            keyset_id,
+            &blinded_message,
+            &mint_key,
+        )
+        .unwrap();
+        let proofs = construct_proofs(vec![signature], vec![r], vec![secret], &keys).unwrap();
+
+        let token = Token::new(mint_url, proofs.clone(), None, CurrencyUnit::Sat)
+            .with_keys(&HashMap::from([(keyset_id, keys.clone())]));
+        let token = Token::from_str(&token.to_string()).unwrap();
+
+        let included_keys = token.included_keys();
+        assert_eq!(included_keys[&ShortKeysetId::from(keyset_id)], keys);
+        token.verify_offline(&included_keys).unwrap();
+
+        // Keys of another mint
+        let other_keys = HashMap::from([(
+            ShortKeysetId::from(keyset_id),
+            Keys::new(BTreeMap::from([(
+                Amount::from(8),
+                SecretKey::generate().public_key(),
+            )])),
+        )]);
+        assert!(matches!(
+            token.verify_offline(&other_keys),
+            Err(Error::NUT12(_))
+        ));
+        assert!(matches!(
+            token.verify_offline(&HashMap::new()),
+            Err(Error::MissingKey { .. })
+        ));
+
+        // Tokens without DLEQ proofs cannot be verified offline
+        let token = Token::new(
+            MintUrl::from_str("https://example.com").unwrap(),
+            proofs.without_dleqs(),
+            None,
+            CurrencyUnit::Sat,
+        );
+        assert!(token.included_keys().is_empty());
+        assert!(matches!(
+            token.verify_offline(&included_keys),
+            Err(Error::NUT12(crate::nuts::nut12::Error::MissingDleqProof))
+        ));
+    }
+
     #[test]
     fn test_token_proofs_with_unknown_short_keyset_id() {
         let mint_url = MintUrl::from_str("https://example.com").unwrap();
@@ -148,6 +148,13 @@ pub struct KeysResponse {
     pub keysets: Vec<KeySet>,
 }
 
+impl KeysResponse {
+    /// Verify every keyset id in the response matches its keys
+    pub fn verify_ids(&self) -> Result<(), super::nut02::Error> {
+        self.keysets.iter().try_for_each(KeySet::verify_id)
+    }
+}
+
 /// Mint key pairs per amount
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct MintKeys(BTreeMap<Amount, MintKeyPair>);
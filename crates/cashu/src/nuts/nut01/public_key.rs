@@ -6,9 +6,11 @@ use bitcoin::hashes::sha256::Hash as Sha256Hash;
 use bitcoin::hashes::Hash;
 use bitcoin::secp256k1::schnorr::Signature;
 use bitcoin::secp256k1::{self, Message, XOnlyPublicKey};
+use serde::de::{self, Visitor};
 use serde::{Deserialize, Deserializer, Serialize};
 
 use super::Error;
+use crate::util::hex;
 use crate::SECP256K1;
 
 /// PublicKey
@@ -119,7 +121,11 @@ impl Serialize for PublicKey {
     where
         S: serde::Serializer,
     {
-        serializer.serialize_str(&self.to_hex())
+        // Encode on the stack, proofs and blinded messages serialize many keys
+        let mut buf = [0u8; 66];
+        let hex =
+            hex::encode_to_slice(&self.to_bytes(), &mut buf).map_err(serde::ser::Error::custom)?;
+        serializer.serialize_str(hex)
     }
 }
 
@@ -128,8 +134,24 @@ impl<'de> Deserialize<'de> for PublicKey {
     where
         D: Deserializer<'de>,
     {
-        let public_key: String = String::deserialize(deserializer)?;
-        Self::from_hex(public_key).map_err(serde::de::Error::custom)
+        struct PublicKeyVisitor;
+
+        impl Visitor<'_> for PublicKeyVisitor {
+            type Value = PublicKey;
+
+            fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                f.write_str("a hex encoded compressed public key")
+            }
+
+            fn visit_str<E>(self, v: &str) -> Result<Self::Value, E>
+            where
+                E: de::Error,
+            {
+                PublicKey::from_hex(v).map_err(E::custom)
+            }
+        }
+
+        deserializer.deserialize_str(PublicKeyVisitor)
     }
 }
 
@@ -151,6 +173,19 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_public_key_serde_round_trip() {
+        let hex = "02194603ffa36356f4a56b7df9371fc3192472351453ec7398b8da8117e7c3e104";
+        let key = PublicKey::from_hex(hex).unwrap();
+
+        let json = serde_json::to_string(&key).unwrap();
+        assert_eq!(json, format!("\"{hex}\""));
+        assert_eq!(serde_json::from_str::<PublicKey>(&json).unwrap(), key);
+
+        let value = serde_json::to_value(key).unwrap();
+        assert_eq!(serde_json::from_value::<PublicKey>(value).unwrap(), key);
+    }
+
     #[test]
     fn test_invalid_public_key_from_hex() {
         // Uncompressed (is valid but is cashu must be compressed?)
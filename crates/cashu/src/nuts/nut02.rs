@@ -124,14 +124,21 @@ impl IdBytes {
             IdBytes::V2(bytes) => bytes.to_vec(),
         }
     }
+
+    /// Borrow [`IdBytes`] as a byte slice
+    pub fn as_slice(&self) -> &[u8] {
+        match self {
+            IdBytes::V1(bytes) => bytes,
+            IdBytes::V2(bytes) => bytes,
+        }
+    }
 }
 
 /// A keyset ID is an identifier for a specific keyset. It can be derived by
 /// anyone who knows the set of public keys of a mint. The keyset ID **CAN**
 /// be stored in a Cashu token such that the token can be used to identify
 /// which mint or keyset it was generated from.
-#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
-#[serde(into = "String", try_from = "String")]
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct Id {
     version: KeySetVersion,
     id: IdBytes,
@@ -142,6 +149,17 @@ impl Id {
     const BYTELEN_V1: usize = 7;
     const STRLEN_V2: usize = 64;
     const BYTELEN_V2: usize = 32;
+    const MAX_HEX_LEN: usize = Self::STRLEN_V2 + 2;
+
+    /// Hex encode the id into `buf` without allocating
+    fn encode_hex<'a>(&self, buf: &'a mut [u8; Self::MAX_HEX_LEN]) -> Result<&'a str, hex::Error> {
+        let id = self.id.as_slice();
+        let mut bytes = [0u8; Self::BYTELEN_V2 + 1];
+        bytes[0] = self.version.to_byte();
+        bytes[1..=id.len()].copy_from_slice(id);
+
+        hex::encode_to_slice(&bytes[..=id.len()], &mut buf[..2 * (id.len() + 1)])
+    }
 
     /// [`Id`] to bytes
     pub fn to_bytes(&self) -> Vec<u8> {
@@ -258,6 +276,27 @@ impl Id {
         }
     }
 
+    /// Verify that this [`Id`] is derived from the given keys
+    ///
+    /// V1 ids only commit to the public keys, V2 ids additionally commit to the unit,
+    /// input fee and final expiry of the keyset.
+    pub fn verify_against_keys(
+        &self,
+        keys: &Keys,
+        unit: &CurrencyUnit,
+        input_fee_ppk: u64,
+        final_expiry: Option<u64>,
+    ) -> Result<(), Error> {
+        let keys_id = match self.version {
+            KeySetVersion::Version00 => Id::v1_from_keys(keys),
+            KeySetVersion::Version01 => Id::v2_from_data(keys, unit, input_fee_ppk, final_expiry),
+        };
+
+        ensure_cdk!(keys_id == *self, Error::IncorrectKeysetId);
+
+        Ok(())
+    }
+
     /// Selects the correct IDv2 from a list of keysets and the given short-id
     /// or returns the short-id in the case of v1.
     pub fn from_short_keyset_id(
@@ -309,21 +348,14 @@ impl From<Id> for u32 {
 
 impl fmt::Display for Id {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        let hex_id = match self.id {
-            IdBytes::V1(id) => hex::encode(id),
-            IdBytes::V2(id) => hex::encode(id),
-        };
-        f.write_str(&format!("{}{}", self.version, hex_id))
+        let mut buf = [0u8; Self::MAX_HEX_LEN];
+        f.write_str(self.encode_hex(&mut buf).map_err(|_| fmt::Error)?)
     }
 }
 
 impl fmt::Debug for Id {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        let hex_id = match self.id {
-            IdBytes::V1(id) => hex::encode(id),
-            IdBytes::V2(id) => hex::encode(id),
-        };
-        f.write_str(&format!("{}{}", self.version, hex_id))
+        fmt::Display::fmt(self, f)
     }
 }
 
@@ -331,6 +363,14 @@ impl TryFrom<String> for Id {
     type Error = Error;
 
     fn try_from(s: String) -> Result<Self, Self::Error> {
+        Self::from_str(&s)
+    }
+}
+
+impl FromStr for Id {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
         // Check that string is ASCII (required for hex) to avoid panics on byte slicing
         // with multi-byte UTF-8 characters
         ensure_cdk!(
@@ -346,29 +386,64 @@ impl TryFrom<String> for Id {
             Error::Length
         );
 
-        let version: KeySetVersion = KeySetVersion::from_byte(&hex::decode(&s[..2])?[0])?;
+        let mut version = [0u8; 1];
+        hex::decode_to_slice(&s[..2], &mut version)?;
+        let version = KeySetVersion::from_byte(&version[0])?;
         let id = match version {
-            KeySetVersion::Version00 => IdBytes::V1(
-                hex::decode(&s[2..])?
-                    .try_into()
-                    .map_err(|_| Error::Length)?,
-            ),
-            KeySetVersion::Version01 => IdBytes::V2(
-                hex::decode(&s[2..])?
-                    .try_into()
-                    .map_err(|_| Error::Length)?,
-            ),
+            KeySetVersion::Version00 => {
+                ensure_cdk!(s.len() == Self::STRLEN_V1 + 2, Error::Length);
+                let mut id = [0u8; Self::BYTELEN_V1];
+                hex::decode_to_slice(&s[2..], &mut id)?;
+                IdBytes::V1(id)
+            }
+            KeySetVersion::Version01 => {
+                ensure_cdk!(s.len() == Self::STRLEN_V2 + 2, Error::Length);
+                let mut id = [0u8; Self::BYTELEN_V2];
+                hex::decode_to_slice(&s[2..], &mut id)?;
+                IdBytes::V2(id)
+            }
         };
 
         Ok(Self { version, id })
     }
 }
 
-impl FromStr for Id {
-    type Err = Error;
+impl Serialize for Id {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        let mut buf = [0u8; Self::MAX_HEX_LEN];
+        serializer.serialize_str(
+            self.encode_hex(&mut buf)
+                .map_err(serde::ser::Error::custom)?,
+        )
+    }
+}
 
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        Self::try_from(s.to_string())
+impl<'de> Deserialize<'de> for Id {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        struct IdVisitor;
+
+        impl serde::de::Visitor<'_> for IdVisitor {
+            type Value = Id;
+
+            fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                f.write_str("a hex encoded keyset id")
+            }
+
+            fn visit_str<E>(self, v: &str) -> Result<Self::Value, E>
+            where
+                E: serde::de::Error,
+            {
+                Id::from_str(v).map_err(E::custom)
+            }
+        }
+
+        deserializer.deserialize_str(IdVisitor)
     }
 }
 
@@ -506,27 +581,39 @@ pub struct KeySet {
 impl KeySet {
     /// Verify the keyset id matches keys
     pub fn verify_id(&self) -> Result<(), Error> {
-        let keys_id = match self.id.version {
-            KeySetVersion::Version00 => Id::v1_from_keys(&self.keys),
-            KeySetVersion::Version01 => Id::v2_from_data(
-                &self.keys,
-                &self.unit,
-                self.input_fee_ppk,
-                self.final_expiry,
-            ),
-        };
-
-        ensure_cdk!(
-            u32::from(keys_id) == u32::from(self.id),
-            Error::IncorrectKeysetId
-        );
+        self.id.verify_against_keys(
+            &self.keys,
+            &self.unit,
+            self.input_fee_ppk,
+            self.final_expiry,
+        )
+    }
 
-        ensure_cdk!(keys_id == self.id, Error::IncorrectKeysetId);
+    /// Verify the keys match the id and parameters advertised in a [`KeySetInfo`]
+    pub fn verify_against_info(&self, info: &KeySetInfo) -> Result<(), Error> {
+        ensure_cdk!(self.id == info.id, Error::IncorrectKeysetId);
 
-        Ok(())
+        info.id.verify_against_keys(
+            &self.keys,
+            &info.unit,
+            info.input_fee_ppk,
+            info.final_expiry,
+        )
     }
 }
 
+/// Verify the ids of a batch of keysets, returning the ids that do not match their keys
+pub fn invalid_keyset_ids<'a, I>(keysets: I) -> Vec<Id>
+where
+    I: IntoIterator<Item = &'a KeySet>,
+{
+    keysets
+        .into_iter()
+        .filter(|keyset| keyset.verify_id().is_err())
+        .map(|keyset| keyset.id)
+        .collect()
+}
+
 /// KeySetInfo
 #[derive(Debug, Clone, Hash, PartialEq, Eq, Deserialize, Serialize)]
 pub struct KeySetInfo {
@@ -876,6 +963,43 @@ mod test {
         assert_eq!(id, id_from_str);
     }
 
+    #[test]
+    fn test_verify_against_keys() {
+        let unit = CurrencyUnit::Sat;
+        let keys: Keys = serde_json::from_str(SHORT_KEYSET).unwrap();
+
+        let v1 = Id::from_str(SHORT_KEYSET_ID).unwrap();
+        assert!(v1.verify_against_keys(&keys, &unit, 0, None).is_ok());
+
+        let v2 = Id::v2_from_data(&keys, &unit, 100, Some(2059210353));
+        assert!(v2
+            .verify_against_keys(&keys, &unit, 100, Some(2059210353))
+            .is_ok());
+        assert!(v2
+            .verify_against_keys(&keys, &unit, 0, Some(2059210353))
+            .is_err());
+        assert!(v2
+            .verify_against_keys(&keys, &CurrencyUnit::Usd, 100, Some(2059210353))
+            .is_err());
+
+        let other_keys: Keys = serde_json::from_str(KEYSET).unwrap();
+        assert!(v1.verify_against_keys(&other_keys, &unit, 0, None).is_err());
+
+        let valid = KeySet {
+            id: v2,
+            unit: unit.clone(),
+            active: Some(true),
+            keys: keys.clone(),
+            input_fee_ppk: 100,
+            final_expiry: Some(2059210353),
+        };
+        let tampered = KeySet {
+            input_fee_ppk: 0,
+            ..valid.clone()
+        };
+        assert_eq!(invalid_keyset_ids([&valid, &tampered]), vec![v2]);
+    }
+
     #[test]
     fn test_deserialization_keyset_info() {
         let h = r#"{"id":"009a1f293253e41e","unit":"sat","active":true}"#;
@@ -1023,6 +1147,22 @@ mod test {
         assert!(id_from_uppercase.is_ok());
     }
 
+    #[test]
+    fn test_id_serde_round_trip() {
+        for version in [KeySetVersion::Version00, KeySetVersion::Version01] {
+            let id = generate_random_id(version);
+
+            let json = serde_json::to_string(&id).unwrap();
+            assert_eq!(json, format!("\"{id}\""));
+            assert_eq!(serde_json::from_str::<Id>(&json).unwrap(), id);
+
+            let value = serde_json::to_value(id).unwrap();
+            assert_eq!(serde_json::from_value::<Id>(value).unwrap(), id);
+        }
+
+        assert!(serde_json::from_str::<Id>("\"00123\"").is_err());
+    }
+
     #[test]
     fn test_short_keyset_id_from_id() {
         let idv1 = Id::from_str("009a1f293253e41e").unwrap();
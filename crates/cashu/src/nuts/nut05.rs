@@ -32,6 +32,9 @@ pub enum Error {
     /// Invalid quote id
     #[error("Invalid quote id")]
     InvalidQuote,
+    /// Invalid change policy
+    #[error("Invalid change policy: {0}")]
+    InvalidChangePolicy(String),
 }
 
 /// Possible states of a quote
@@ -605,6 +608,97 @@ impl From<MeltQuoteCustomResponse<QuoteId>> for MeltQuoteCustomResponse<String>
     }
 }
 
+/// Policy for the part of a melt's fee reserve that was not spent on the payment
+///
+/// By default the whole surplus is returned to the wallet as change (NUT-08). A mint
+/// can instead keep small surpluses, or round the change down to a denomination, and
+/// book the difference as fee income.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "policy", rename_all = "snake_case")]
+pub enum MeltChangePolicy {
+    /// Return the whole surplus as change
+    #[default]
+    AlwaysReturn,
+    /// Keep surpluses below `threshold` as fee income, return larger ones in full
+    KeepBelowThreshold {
+        /// Smallest surplus that is returned as change
+        threshold: Amount,
+    },
+    /// Return the surplus rounded down to a multiple of `denomination`
+    RoundDown {
+        /// Change is returned in multiples of this amount
+        denomination: Amount,
+    },
+}
+
+impl MeltChangePolicy {
+    /// Split a fee surplus into the change returned to the wallet and the amount kept
+    pub fn split_surplus(&self, surplus: Amount) -> (Amount, Amount) {
+        let returned = match self {
+            Self::AlwaysReturn => surplus,
+            Self::KeepBelowThreshold { threshold } => {
+                if surplus < *threshold {
+                    Amount::ZERO
+                } else {
+                    surplus
+                }
+            }
+            Self::RoundDown { denomination } => match surplus.checked_div(*denomination) {
+                Some(multiples) => multiples.checked_mul(*denomination).unwrap_or(surplus),
+                None => surplus,
+            },
+        };
+
+        (returned, surplus.saturating_sub(returned))
+    }
+}
+
+impl fmt::Display for MeltChangePolicy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::AlwaysReturn => write!(f, "always_return"),
+            Self::KeepBelowThreshold { threshold } => {
+                write!(f, "keep_below_threshold:{threshold}")
+            }
+            Self::RoundDown { denomination } => write!(f, "round_down:{denomination}"),
+        }
+    }
+}
+
+impl FromStr for MeltChangePolicy {
+    type Err = Error;
+
+    /// Parse `always_return`, `keep_below_threshold:<amount>` or `round_down:<amount>`
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || Error::InvalidChangePolicy(s.to_string());
+
+        let (policy, amount) = match s.split_once(':') {
+            Some((policy, amount)) => {
+                let amount: u64 = amount.trim().parse().map_err(|_| invalid())?;
+                (policy.trim(), Some(Amount::from(amount)))
+            }
+            None => (s.trim(), None),
+        };
+
+        match (policy, amount) {
+            ("always_return", None) => Ok(Self::AlwaysReturn),
+            ("keep_below_threshold", Some(threshold)) => Ok(Self::KeepBelowThreshold { threshold }),
+            ("round_down", Some(denomination)) => Ok(Self::RoundDown { denomination }),
+            _ => Err(invalid()),
+        }
+    }
+}
+
+/// Change policy a mint applied when it finalized a melt
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AppliedMeltChangePolicy {
+    /// Configured policy
+    #[serde(flatten)]
+    pub policy: MeltChangePolicy,
+    /// Fee surplus the mint kept as fee income instead of returning it as change
+    pub kept: Amount,
+}
+
 #[cfg(test)]
 mod tests {
     use serde_json::{from_str, json, to_string};
@@ -1030,4 +1124,83 @@ mod tests {
         assert_eq!(parsed["fee_reserve"], json!(10));
         assert_eq!(parsed["method"], json!("custom"));
     }
+
+    #[test]
+    fn test_melt_change_policy_split_surplus() {
+        let surplus = Amount::from(13);
+
+        assert_eq!(
+            MeltChangePolicy::AlwaysReturn.split_surplus(surplus),
+            (Amount::from(13), Amount::ZERO)
+        );
+
+        let keep = MeltChangePolicy::KeepBelowThreshold {
+            threshold: Amount::from(16),
+        };
+        assert_eq!(
+            keep.split_surplus(surplus),
+            (Amount::ZERO, Amount::from(13))
+        );
+        assert_eq!(
+            keep.split_surplus(Amount::from(16)),
+            (Amount::from(16), Amount::ZERO)
+        );
+
+        let round = MeltChangePolicy::RoundDown {
+            denomination: Amount::from(4),
+        };
+        assert_eq!(
+            round.split_surplus(surplus),
+            (Amount::from(12), Amount::from(1))
+        );
+
+        let zero = MeltChangePolicy::RoundDown {
+            denomination: Amount::ZERO,
+        };
+        assert_eq!(
+            zero.split_surplus(surplus),
+            (Amount::from(13), Amount::ZERO)
+        );
+    }
+
+    #[test]
+    fn test_melt_change_policy_from_str_roundtrip() {
+        for policy in [
+            MeltChangePolicy::AlwaysReturn,
+            MeltChangePolicy::KeepBelowThreshold {
+                threshold: Amount::from(10),
+            },
+            MeltChangePolicy::RoundDown {
+                denomination: Amount::from(8),
+            },
+        ] {
+            assert_eq!(
+                policy.to_string().parse::<MeltChangePolicy>().unwrap(),
+                policy
+            );
+        }
+
+        assert!("round_down".parse::<MeltChangePolicy>().is_err());
+        assert!("always_return:5".parse::<MeltChangePolicy>().is_err());
+        assert!("keep_all".parse::<MeltChangePolicy>().is_err());
+    }
+
+    #[test]
+    fn test_applied_melt_change_policy_serialization() {
+        let applied = AppliedMeltChangePolicy {
+            policy: MeltChangePolicy::KeepBelowThreshold {
+                threshold: Amount::from(10),
+            },
+            kept: Amount::from(3),
+        };
+
+        let value = serde_json::to_value(&applied).unwrap();
+        assert_eq!(
+            value,
+            json!({"policy": "keep_below_threshold", "threshold": 10, "kept": 3})
+        );
+
+        let parsed: AppliedMeltChangePolicy = serde_json::from_value(value).unwrap();
+        assert_eq!(parsed, applied);
+    }
 }
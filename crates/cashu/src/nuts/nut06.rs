@@ -10,7 +10,7 @@ use super::nut01::PublicKey;
 use super::nut17::SupportedMethods;
 use super::nut19::CachedEndpoint;
 use super::{
-    nut04, nut05, nut15, nut19, nut29, AuthRequired, BlindAuthSettings, ClearAuthSettings,
+    nut04, nut05, nut09, nut15, nut19, nut29, AuthRequired, BlindAuthSettings, ClearAuthSettings,
     MppMethodSettings, ProtectedEndpoint,
 };
 use crate::util::serde_helpers::deserialize_empty_string_as_none;
@@ -290,7 +290,7 @@ pub struct Nuts {
     /// NUT09 Settings
     #[serde(default)]
     #[serde(rename = "9")]
-    pub nut09: SupportedSettings,
+    pub nut09: nut09::Settings,
     /// NUT10 Settings
     #[serde(rename = "10")]
     #[serde(default)]
@@ -380,7 +380,10 @@ impl Nuts {
     /// Nut09 settings
     pub fn nut09(self, supported: bool) -> Self {
         Self {
-            nut09: SupportedSettings { supported },
+            nut09: nut09::Settings {
+                supported,
+                ..self.nut09
+            },
             ..self
         }
     }
@@ -462,6 +465,36 @@ impl Nuts {
         }
     }
 
+    /// Numbers of the optional NUTs advertised as supported
+    ///
+    /// NUT-04 and NUT-05 count as supported while they are not disabled and
+    /// offer at least one method.
+    pub fn supported_nuts(&self) -> Vec<u8> {
+        let supported = [
+            (4, !self.nut04.disabled && !self.nut04.methods.is_empty()),
+            (5, !self.nut05.disabled && !self.nut05.methods.is_empty()),
+            (7, self.nut07.supported),
+            (8, self.nut08.supported),
+            (9, self.nut09.supported),
+            (10, self.nut10.supported),
+            (11, self.nut11.supported),
+            (12, self.nut12.supported),
+            (14, self.nut14.supported),
+            (15, !self.nut15.is_empty()),
+            (17, !self.nut17.supported.is_empty()),
+            (19, !self.nut19.cached_endpoints.is_empty()),
+            (20, self.nut20.supported),
+            (21, self.nut21.is_some()),
+            (22, self.nut22.is_some()),
+            (29, !self.nut29.is_empty()),
+        ];
+
+        supported
+            .into_iter()
+            .filter_map(|(nut, supported)| supported.then_some(nut))
+            .collect()
+    }
+
     /// Units where minting is supported
     pub fn supported_mint_units(&self) -> Vec<&CurrencyUnit> {
         self.nut04
@@ -882,4 +915,19 @@ mod tests {
         assert!(supported_units.contains(&&CurrencyUnit::Eur));
         assert!(supported_units.contains(&&CurrencyUnit::Usd));
     }
+
+    #[test]
+    fn test_supported_nuts() {
+        assert!(Nuts::default().supported_nuts().is_empty());
+
+        let nuts = Nuts::default()
+            .nut07(true)
+            .nut12(true)
+            .nut15(vec![MppMethodSettings {
+                method: crate::PaymentMethod::Known(KnownMethod::Bolt11),
+                unit: crate::CurrencyUnit::Sat,
+            }]);
+
+        assert_eq!(nuts.supported_nuts(), vec![7, 12, 15]);
+    }
 }
@@ -69,11 +69,31 @@ impl FromStr for State {
 }
 
 /// Check spendable request [NUT-07]
+///
+/// Large requests can be answered in pages: the mint returns at most `limit` states
+/// starting at `cursor` and sets [`CheckStateResponse::next_cursor`] while ys remain.
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct CheckStateRequest {
     /// Y's of the proofs to check
     #[serde(rename = "Ys")]
     pub ys: Vec<PublicKey>,
+    /// Maximum number of states to return in the response
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub limit: Option<usize>,
+    /// Index into `ys` of the first state to return
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cursor: Option<usize>,
+}
+
+impl CheckStateRequest {
+    /// Create a request for the states of all `ys` in a single response
+    pub fn new(ys: Vec<PublicKey>) -> Self {
+        Self {
+            ys,
+            limit: None,
+            cursor: None,
+        }
+    }
 }
 
 /// Proof state [NUT-07]
@@ -103,6 +123,9 @@ impl From<(PublicKey, State)> for ProofState {
 pub struct CheckStateResponse {
     /// Proof states
     pub states: Vec<ProofState>,
+    /// Cursor of the next page, set while states remain
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub next_cursor: Option<usize>,
 }
 
 #[cfg(test)]
@@ -126,4 +149,23 @@ mod tests {
 
         assert_eq!(State::from_str("UNKNOWN"), Err(Error::UnknownState));
     }
+
+    #[test]
+    fn pagination_fields_are_optional_on_the_wire() {
+        let request: CheckStateRequest = serde_json::from_str(r#"{"Ys":[]}"#).unwrap();
+        assert_eq!(request, CheckStateRequest::new(vec![]));
+        assert_eq!(serde_json::to_string(&request).unwrap(), r#"{"Ys":[]}"#);
+
+        let request: CheckStateRequest =
+            serde_json::from_str(r#"{"Ys":[],"limit":10,"cursor":20}"#).unwrap();
+        assert_eq!(request.limit, Some(10));
+        assert_eq!(request.cursor, Some(20));
+
+        let response: CheckStateResponse = serde_json::from_str(r#"{"states":[]}"#).unwrap();
+        assert_eq!(response.next_cursor, None);
+        assert_eq!(
+            serde_json::to_string(&response).unwrap(),
+            r#"{"states":[]}"#
+        );
+    }
 }
@@ -76,6 +76,7 @@ mod tests {
             request: Some("invoice".to_string()),
             unit: Some(CurrencyUnit::Sat),
             method: PaymentMethod::BOLT11,
+            change_policy: None,
         };
 
         assert_eq!(response.change_amount(), Some(Amount::from(5)));
@@ -94,6 +95,7 @@ mod tests {
             request: Some("offer".to_string()),
             unit: Some(CurrencyUnit::Sat),
             method: PaymentMethod::BOLT12,
+            change_policy: None,
         };
 
         assert_eq!(response.change_amount(), Some(Amount::from(7)));
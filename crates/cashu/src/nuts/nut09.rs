@@ -11,6 +11,33 @@ use super::nut00::{BlindSignature, BlindedMessage};
 pub struct RestoreRequest {
     /// Outputs
     pub outputs: Vec<BlindedMessage>,
+    /// Index into `outputs` of the first output to answer
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cursor: Option<usize>,
+}
+
+impl RestoreRequest {
+    /// Create a request answered from the first output
+    pub fn new(outputs: Vec<BlindedMessage>) -> Self {
+        Self {
+            outputs,
+            cursor: None,
+        }
+    }
+}
+
+/// Restore Settings [NUT-09]
+///
+/// The mint answers at most `max_outputs` blinded messages per restore request and returns
+/// [`RestoreResponse::next_cursor`] for the rest. Wallets send pages of at most `max_outputs`
+/// blinded messages each and merge the responses in order.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Hash, Serialize, Deserialize)]
+pub struct Settings {
+    /// Setting supported
+    pub supported: bool,
+    /// Maximum number of outputs per restore request
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_outputs: Option<usize>,
 }
 
 /// Restore Response [NUT-09]
@@ -20,6 +47,9 @@ pub struct RestoreResponse {
     pub outputs: Vec<BlindedMessage>,
     /// Signatures
     pub signatures: Vec<BlindSignature>,
+    /// Cursor of the next page, set while outputs remain unanswered
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub next_cursor: Option<usize>,
 }
 
 mod test {
@@ -29,6 +59,26 @@ mod test {
         use super::*;
         let rs = r#"{"outputs":[{"B_":"0204bbffa045f28ec836117a29ea0a00d77f1d692e38cf94f72a5145bfda6d8f41","amount":0,"id":"00ffd48b8f5ecf80", "witness":null},{"B_":"025f0615ccba96f810582a6885ffdb04bd57c96dbc590f5aa560447b31258988d7","amount":0,"id":"00ffd48b8f5ecf80"}],"signatures":[{"C_":"02e9701b804dc05a5294b5a580b428237a27c7ee1690a0177868016799b1761c81","amount":8,"dleq":null,"id":"00ffd48b8f5ecf80"},{"C_":"031246ee046519b15648f1b8d8ffcb8e537409c84724e148c8d6800b2e62deb795","amount":2,"dleq":null,"id":"00ffd48b8f5ecf80"}]}"#;
 
-        let _res: RestoreResponse = serde_json::from_str(rs).unwrap();
+        let res: RestoreResponse = serde_json::from_str(rs).unwrap();
+        assert_eq!(res.next_cursor, None);
+
+        let req: RestoreRequest = serde_json::from_str(r#"{"outputs":[]}"#).unwrap();
+        assert_eq!(req, RestoreRequest::new(vec![]));
+        assert_eq!(serde_json::to_string(&req).unwrap(), r#"{"outputs":[]}"#);
+    }
+
+    #[test]
+    fn settings_page_limit() {
+        use super::*;
+        let settings: Settings = serde_json::from_str(r#"{"supported":true}"#).unwrap();
+        assert_eq!(settings.max_outputs, None);
+
+        let settings: Settings =
+            serde_json::from_str(r#"{"supported":true,"max_outputs":100}"#).unwrap();
+        assert_eq!(settings.max_outputs, Some(100));
+        assert_eq!(
+            serde_json::to_string(&settings).unwrap(),
+            r#"{"supported":true,"max_outputs":100}"#
+        );
     }
 }
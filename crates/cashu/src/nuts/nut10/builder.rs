@@ -0,0 +1,337 @@
+//! Typed builder and strict parser for well-known NUT-10 secrets
+
+use std::collections::HashSet;
+use std::str::FromStr;
+
+use bitcoin::hashes::sha256::Hash as Sha256Hash;
+use bitcoin::hashes::Hash;
+use serde::Deserialize;
+
+use super::{
+    check_duplicate_pubkeys, Conditions, Error, Kind, Secret, SecretData, SpendingConditions, Tag,
+    TagKind,
+};
+use crate::nuts::nut01::PublicKey;
+use crate::util::unix_time;
+use crate::{ensure_cdk, SigFlag};
+
+/// Lock of a well-known secret kind
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Lock {
+    /// NUT-11 pubkey lock
+    P2PK(PublicKey),
+    /// NUT-14 hash lock
+    HTLC(Sha256Hash),
+}
+
+/// Builder for P2PK and HTLC [`Secret`]s
+///
+/// ```
+/// # use cashu::nuts::nut10::Nut10SecretBuilder;
+/// # use cashu::SecretKey;
+/// let pubkey = SecretKey::generate().public_key();
+/// let refund = SecretKey::generate().public_key();
+///
+/// let secret = Nut10SecretBuilder::p2pk(pubkey)
+///     .locktime(cashu::util::unix_time() + 3600)
+///     .refund_keys([refund])
+///     .build()
+///     .unwrap();
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Nut10SecretBuilder {
+    lock: Lock,
+    locktime: Option<u64>,
+    pubkeys: Vec<PublicKey>,
+    refund_keys: Vec<PublicKey>,
+    num_sigs: Option<u64>,
+    num_sigs_refund: Option<u64>,
+    sig_flag: Option<SigFlag>,
+}
+
+impl Nut10SecretBuilder {
+    fn new(lock: Lock) -> Self {
+        Self {
+            lock,
+            locktime: None,
+            pubkeys: Vec::new(),
+            refund_keys: Vec::new(),
+            num_sigs: None,
+            num_sigs_refund: None,
+            sig_flag: None,
+        }
+    }
+
+    /// Lock to a public key (NUT-11)
+    pub fn p2pk(pubkey: PublicKey) -> Self {
+        Self::new(Lock::P2PK(pubkey))
+    }
+
+    /// Lock to the hash of a preimage (NUT-14)
+    pub fn htlc(hash: Sha256Hash) -> Self {
+        Self::new(Lock::HTLC(hash))
+    }
+
+    /// Lock to the hash of a 32 byte preimage (NUT-14)
+    pub fn htlc_from_preimage(preimage: &[u8; 32]) -> Self {
+        Self::htlc(Sha256Hash::hash(preimage))
+    }
+
+    /// Unix time after which the refund path becomes available
+    pub fn locktime(mut self, locktime: u64) -> Self {
+        self.locktime = Some(locktime);
+        self
+    }
+
+    /// Additional public keys that may sign
+    pub fn pubkeys<I>(mut self, pubkeys: I) -> Self
+    where
+        I: IntoIterator<Item = PublicKey>,
+    {
+        self.pubkeys.extend(pubkeys);
+        self
+    }
+
+    /// Public keys that may spend after the locktime
+    pub fn refund_keys<I>(mut self, refund_keys: I) -> Self
+    where
+        I: IntoIterator<Item = PublicKey>,
+    {
+        self.refund_keys.extend(refund_keys);
+        self
+    }
+
+    /// Number of signatures required on the primary path
+    pub fn num_sigs(mut self, num_sigs: u64) -> Self {
+        self.num_sigs = Some(num_sigs);
+        self
+    }
+
+    /// Number of signatures required on the refund path
+    pub fn num_sigs_refund(mut self, num_sigs_refund: u64) -> Self {
+        self.num_sigs_refund = Some(num_sigs_refund);
+        self
+    }
+
+    /// Signature flag
+    pub fn sig_flag(mut self, sig_flag: SigFlag) -> Self {
+        self.sig_flag = Some(sig_flag);
+        self
+    }
+
+    /// Validate and build the [`SpendingConditions`]
+    pub fn build_conditions(self) -> Result<SpendingConditions, Error> {
+        if let Some(locktime) = self.locktime {
+            ensure_cdk!(
+                locktime >= unix_time(),
+                Error::NUT11(crate::nut11::Error::LocktimeInPast)
+            );
+        }
+
+        let has_conditions = self.locktime.is_some()
+            || !self.pubkeys.is_empty()
+            || !self.refund_keys.is_empty()
+            || self.num_sigs.is_some()
+            || self.num_sigs_refund.is_some()
+            || self.sig_flag.is_some();
+
+        let conditions = has_conditions.then(|| Conditions {
+            locktime: self.locktime,
+            pubkeys: (!self.pubkeys.is_empty()).then_some(self.pubkeys.clone()),
+            refund_keys: (!self.refund_keys.is_empty()).then_some(self.refund_keys.clone()),
+            num_sigs: self.num_sigs,
+            sig_flag: self.sig_flag.unwrap_or_default(),
+            num_sigs_refund: self.num_sigs_refund,
+        });
+
+        let spending_conditions = match self.lock {
+            Lock::P2PK(data) => {
+                let mut primary_keys = vec![data];
+                primary_keys.extend(self.pubkeys.iter().copied());
+                check_duplicate_pubkeys(&primary_keys)?;
+
+                SpendingConditions::P2PKConditions { data, conditions }
+            }
+            Lock::HTLC(data) => {
+                check_duplicate_pubkeys(&self.pubkeys)?;
+
+                SpendingConditions::HTLCConditions { data, conditions }
+            }
+        };
+        check_duplicate_pubkeys(&self.refund_keys)?;
+        spending_conditions.validate()?;
+
+        Ok(spending_conditions)
+    }
+
+    /// Validate and build the NUT-10 [`Secret`]
+    ///
+    /// Tags are emitted in a fixed order, so equal inputs always serialize to the same
+    /// JSON apart from the random nonce.
+    pub fn build(self) -> Result<Secret, Error> {
+        Ok(self.build_conditions()?.into())
+    }
+
+    /// Validate and build the serialized proof [`crate::secret::Secret`]
+    pub fn build_secret(self) -> Result<crate::secret::Secret, Error> {
+        self.build()?.try_into()
+    }
+}
+
+/// Wire form of [`SecretData`] that rejects unknown fields
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct StrictSecretData {
+    nonce: String,
+    data: String,
+    #[serde(default)]
+    tags: Option<Vec<Vec<String>>>,
+}
+
+impl Secret {
+    /// Parse a serialized secret, rejecting anything a well-formed P2PK or HTLC secret
+    /// would not contain
+    ///
+    /// Unlike plain deserialization this fails on unknown fields, an empty nonce, data
+    /// that is not a valid pubkey or hash for the kind, repeated well-known tags and
+    /// unsatisfiable conditions.
+    pub fn from_str_strict(s: &str) -> Result<Self, Error> {
+        let (kind, data): (Kind, StrictSecretData) = serde_json::from_str(s)?;
+
+        ensure_cdk!(!data.nonce.is_empty(), Error::EmptyNonce);
+
+        let mut seen = HashSet::new();
+        for tag in data.tags.iter().flatten() {
+            let tag_kind = Tag::try_from(tag.clone())?.kind();
+            if !matches!(tag_kind, TagKind::Custom(_)) && !seen.insert(tag_kind.clone()) {
+                return Err(Error::DuplicateTag(tag_kind));
+            }
+        }
+
+        let secret = Self::new(
+            kind,
+            SecretData {
+                nonce: data.nonce,
+                data: data.data,
+                tags: data.tags,
+            },
+        );
+
+        let conditions = SpendingConditions::try_from(secret.clone())?;
+        match &conditions {
+            SpendingConditions::P2PKConditions { data, conditions } => {
+                let mut primary_keys = vec![*data];
+                if let Some(pubkeys) = conditions.as_ref().and_then(|c| c.pubkeys.as_ref()) {
+                    primary_keys.extend(pubkeys.iter().copied());
+                }
+                check_duplicate_pubkeys(&primary_keys)?;
+            }
+            SpendingConditions::HTLCConditions { conditions, .. } => {
+                if let Some(pubkeys) = conditions.as_ref().and_then(|c| c.pubkeys.as_ref()) {
+                    check_duplicate_pubkeys(pubkeys)?;
+                }
+            }
+        }
+        if let Some(refund_keys) = conditions.refund_keys() {
+            check_duplicate_pubkeys(&refund_keys)?;
+        }
+        conditions.validate()?;
+
+        Ok(secret)
+    }
+}
+
+impl FromStr for Nut10SecretBuilder {
+    type Err = Error;
+
+    /// Strictly parse a secret back into a builder
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let secret = Secret::from_str_strict(s)?;
+        let conditions = SpendingConditions::try_from(secret)?;
+
+        let (lock, conditions) = match conditions {
+            SpendingConditions::P2PKConditions { data, conditions } => {
+                (Lock::P2PK(data), conditions)
+            }
+            SpendingConditions::HTLCConditions { data, conditions } => {
+                (Lock::HTLC(data), conditions)
+            }
+        };
+
+        let mut builder = Self::new(lock);
+        if let Some(conditions) = conditions {
+            builder.locktime = conditions.locktime;
+            builder.pubkeys = conditions.pubkeys.unwrap_or_default();
+            builder.refund_keys = conditions.refund_keys.unwrap_or_default();
+            builder.num_sigs = conditions.num_sigs;
+            builder.num_sigs_refund = conditions.num_sigs_refund;
+            builder.sig_flag = Some(conditions.sig_flag);
+        }
+
+        Ok(builder)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{nut14, SecretKey};
+
+    #[test]
+    fn test_builder_round_trips_through_strict_parser() {
+        let pubkey = SecretKey::generate().public_key();
+        let extra = SecretKey::generate().public_key();
+        let refund = SecretKey::generate().public_key();
+        let locktime = unix_time() + 3600;
+
+        let builder = Nut10SecretBuilder::p2pk(pubkey)
+            .locktime(locktime)
+            .pubkeys([extra])
+            .num_sigs(2)
+            .refund_keys([refund])
+            .sig_flag(SigFlag::SigAll);
+
+        let secret = builder.clone().build_secret().unwrap();
+        let parsed = Nut10SecretBuilder::from_str(&secret.to_string()).unwrap();
+        assert_eq!(parsed, builder);
+
+        assert!(matches!(
+            Nut10SecretBuilder::p2pk(pubkey).num_sigs(3).build(),
+            Err(Error::NUT11(
+                crate::nut11::Error::ImpossibleMultisigConfiguration { .. }
+            ))
+        ));
+        assert!(matches!(
+            Nut10SecretBuilder::p2pk(pubkey).pubkeys([pubkey]).build(),
+            Err(Error::NUT11(crate::nut11::Error::DuplicatePubkey))
+        ));
+    }
+
+    #[test]
+    fn test_strict_parser_rejects_malformed_secrets() {
+        let pubkey = SecretKey::generate().public_key();
+
+        let duplicate_tag = format!(
+            r#"["P2PK",{{"nonce":"00","data":"{pubkey}","tags":[["n_sigs","1"],["n_sigs","2"]]}}]"#
+        );
+        assert!(matches!(
+            Secret::from_str_strict(&duplicate_tag),
+            Err(Error::DuplicateTag(TagKind::NSigs))
+        ));
+
+        let unknown_field = format!(r#"["P2PK",{{"nonce":"00","data":"{pubkey}","extra":1}}]"#);
+        assert!(Secret::from_str_strict(&unknown_field).is_err());
+
+        let empty_nonce = format!(r#"["P2PK",{{"nonce":"","data":"{pubkey}"}}]"#);
+        assert!(matches!(
+            Secret::from_str_strict(&empty_nonce),
+            Err(Error::EmptyNonce)
+        ));
+
+        let bad_hash = r#"["HTLC",{"nonce":"00","data":"not-a-hash"}]"#;
+        assert!(matches!(
+            Secret::from_str_strict(bad_hash),
+            Err(Error::NUT14(nut14::Error::InvalidHash))
+        ));
+    }
+}
@@ -23,6 +23,21 @@ pub enum Error {
     /// Proof does not contain enough signature for lock
     #[error("proof does not contain enough proofs to be spendable")]
     NotEnoughSignatures,
+    /// Secret nonce is empty
+    #[error("Secret nonce is empty")]
+    EmptyNonce,
+    /// Well-known tag appears more than once
+    #[error("Duplicate `{0}` tag")]
+    DuplicateTag(super::TagKind),
+    /// Witness does not match the secret kind or cannot be decoded
+    #[error("Witness is malformed")]
+    MalformedWitness,
+    /// Witness repeats a signature or holds several from the same key
+    #[error("Witness contains a duplicate signature")]
+    DuplicateWitnessSignature,
+    /// Refund key signed before the locktime passed
+    #[error("Refund path used before the locktime expired")]
+    LocktimeNotExpired,
 
     /// From hex error
     #[error(transparent)]
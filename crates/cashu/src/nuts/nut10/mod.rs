@@ -21,6 +21,11 @@ pub use error::Error;
 pub mod tag;
 pub use tag::{Tag, TagKind};
 
+pub mod builder;
+pub use builder::Nut10SecretBuilder;
+
+mod strict;
+
 /// Refund path requirements (available after locktime for HTLC)
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub(crate) struct RefundPath {
@@ -354,6 +359,22 @@ pub trait SpendingConditionVerification {
         }
     }
 
+    /// Verify spending conditions, rejecting malformed witnesses with specific errors
+    ///
+    /// Every input is checked with the strict secret parser and its witness must decode
+    /// for the secret kind, hold no duplicate signatures and not use refund keys before
+    /// the locktime. The regular [`Self::verify_spending_conditions`] runs afterwards.
+    fn verify_spending_conditions_strict(&self) -> Result<(), Error> {
+        let sig_all_msg = self.sig_all_msg_to_sign();
+        let now = crate::util::unix_time();
+
+        for proof in self.inputs() {
+            strict::verify_input_strict(proof, &sig_all_msg, now)?;
+        }
+
+        self.verify_spending_conditions()
+    }
+
     /// Verify spending conditions when SIG_ALL is present
     ///
     /// When SIG_ALL is set, all proofs in the transaction must be signed together.
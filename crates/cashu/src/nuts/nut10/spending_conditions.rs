@@ -267,7 +267,7 @@ impl Conditions {
 }
 
 impl SpendingConditions {
-    fn validate(&self) -> Result<(), Error> {
+    pub(crate) fn validate(&self) -> Result<(), Error> {
         match self {
             Self::P2PKConditions { conditions, .. } => {
                 if let Some(conditions) = conditions {
@@ -0,0 +1,161 @@
+//! Strict witness checks for NUT-10 inputs
+//!
+//! The regular verification reports most malformed witnesses as unmet spending
+//! conditions. These checks run first and return a dedicated error for each case.
+
+use std::str::FromStr;
+
+use bitcoin::secp256k1::schnorr::Signature;
+
+use super::{Conditions, Error, Kind, Secret, SpendingConditions};
+use crate::ensure_cdk;
+use crate::nuts::nut00::{Proof, Witness};
+use crate::nuts::SigFlag;
+
+/// Strictly check the secret and witness of a single input
+///
+/// `sig_all_msg` is the message `SIG_ALL` signatures commit to. Fails with
+/// [`Error::MalformedWitness`] if the witness does not belong to the secret kind or
+/// holds signatures or a preimage that cannot be decoded, with
+/// [`Error::DuplicateWitnessSignature`] if a signature or signer appears twice and with
+/// [`Error::LocktimeNotExpired`] if a refund key signs before the locktime.
+pub(crate) fn verify_input_strict(
+    proof: &Proof,
+    sig_all_msg: &str,
+    current_time: u64,
+) -> Result<(), Error> {
+    // Plain secrets have no conditions to check
+    if Secret::try_from(&proof.secret).is_err() {
+        ensure_cdk!(proof.witness.is_none(), Error::MalformedWitness);
+        return Ok(());
+    }
+
+    let secret = Secret::from_str_strict(&proof.secret.to_string())?;
+
+    let witness_signatures = match (&proof.witness, secret.kind()) {
+        (None, _) => return Ok(()),
+        (Some(Witness::P2PKWitness(witness)), Kind::P2PK) => witness.signatures.clone(),
+        (Some(Witness::HTLCWitness(witness)), Kind::HTLC) => {
+            // An empty preimage is allowed on the refund path
+            if !witness.preimage.is_empty() {
+                witness
+                    .preimage_data()
+                    .map_err(|_| Error::MalformedWitness)?;
+            }
+            witness.signatures.clone().unwrap_or_default()
+        }
+        _ => return Err(Error::MalformedWitness),
+    };
+
+    let mut signatures: Vec<Signature> = Vec::with_capacity(witness_signatures.len());
+    for signature in &witness_signatures {
+        let signature = Signature::from_str(signature).map_err(|_| Error::MalformedWitness)?;
+        ensure_cdk!(
+            !signatures.contains(&signature),
+            Error::DuplicateWitnessSignature
+        );
+        signatures.push(signature);
+    }
+
+    let conditions =
+        Conditions::try_from(secret.secret_data().tags().cloned().unwrap_or_default())?;
+    let msg = match conditions.sig_flag {
+        SigFlag::SigAll => sig_all_msg.as_bytes().to_vec(),
+        SigFlag::SigInputs => proof.secret.to_bytes(),
+    };
+
+    let spending_conditions = SpendingConditions::try_from(secret)?;
+    let primary_keys = spending_conditions.pubkeys().unwrap_or_default();
+    let refund_keys = spending_conditions.refund_keys().unwrap_or_default();
+
+    for pubkey in primary_keys.iter().chain(refund_keys.iter()) {
+        let signed = signatures
+            .iter()
+            .filter(|signature| pubkey.verify(&msg, signature).is_ok())
+            .count();
+        ensure_cdk!(signed <= 1, Error::DuplicateWitnessSignature);
+    }
+
+    if let Some(locktime) = conditions.locktime {
+        if locktime >= current_time {
+            let signed_by_refund_key = refund_keys
+                .iter()
+                .filter(|key| !primary_keys.contains(*key))
+                .any(|key| {
+                    signatures
+                        .iter()
+                        .any(|signature| key.verify(&msg, signature).is_ok())
+                });
+            ensure_cdk!(!signed_by_refund_key, Error::LocktimeNotExpired);
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::nuts::nut10::Nut10SecretBuilder;
+    use crate::nuts::{HTLCWitness, Id, P2PKWitness, SecretKey};
+    use crate::util::unix_time;
+    use crate::Amount;
+
+    #[test]
+    fn test_strict_witness_errors() {
+        let owner = SecretKey::generate();
+        let refund = SecretKey::generate();
+        let now = unix_time();
+        let locktime = now + 3600;
+
+        let secret = Nut10SecretBuilder::p2pk(owner.public_key())
+            .locktime(locktime)
+            .refund_keys([refund.public_key()])
+            .build_secret()
+            .unwrap();
+        let proof = Proof {
+            amount: Amount::ONE,
+            keyset_id: Id::from_str("009a1f293253e41e").unwrap(),
+            secret,
+            c: owner.public_key(),
+            witness: None,
+            dleq: None,
+            p2pk_e: None,
+        };
+
+        let mut signed = proof.clone();
+        signed.sign_p2pk(owner.clone()).unwrap();
+        assert!(verify_input_strict(&signed, "", now).is_ok());
+
+        let mut duplicate = signed.clone();
+        duplicate.sign_p2pk(owner.clone()).unwrap();
+        assert!(matches!(
+            verify_input_strict(&duplicate, "", now),
+            Err(Error::DuplicateWitnessSignature)
+        ));
+
+        let mut malformed = proof.clone();
+        malformed.witness = Some(Witness::P2PKWitness(P2PKWitness {
+            signatures: vec!["not-a-signature".to_string()],
+        }));
+        assert!(matches!(
+            verify_input_strict(&malformed, "", now),
+            Err(Error::MalformedWitness)
+        ));
+
+        let mut wrong_kind = proof.clone();
+        wrong_kind.witness = Some(Witness::HTLCWitness(HTLCWitness::default()));
+        assert!(matches!(
+            verify_input_strict(&wrong_kind, "", now),
+            Err(Error::MalformedWitness)
+        ));
+
+        let mut refunded = proof;
+        refunded.sign_p2pk(refund).unwrap();
+        assert!(matches!(
+            verify_input_strict(&refunded, "", now),
+            Err(Error::LocktimeNotExpired)
+        ));
+        assert!(verify_input_strict(&refunded, "", locktime + 1).is_ok());
+    }
+}
@@ -20,8 +20,11 @@ use crate::nuts::nut00::BlindedMessage;
 use crate::util::unix_time;
 use crate::{SpendingConditions, SwapRequest};
 
+pub mod multisig;
 pub mod serde_p2pk_witness;
 
+pub use multisig::{MultisigProofs, PartialSignatures, ProofSignature, SignatureThreshold};
+
 /// Nut11 Error
 #[derive(Debug, Error)]
 pub enum Error {
@@ -85,6 +88,12 @@ pub enum Error {
     /// Number of required signatures cannot be zero
     #[error("Number of required signatures must be 1 or greater")]
     ZeroSignaturesRequired,
+    /// Signature references a proof that is not being signed
+    #[error("Signature for unknown proof")]
+    UnknownProof,
+    /// Signer is not one of the keys the proof is locked to
+    #[error("Signer is not authorized to spend this proof")]
+    SignerNotAuthorized,
     /// Serde Json error
     #[error(transparent)]
     SerdeJsonError(#[from] serde_json::Error),
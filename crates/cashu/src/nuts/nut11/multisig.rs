@@ -0,0 +1,304 @@
+//! Multi-signature (n_sigs) witness aggregation
+//!
+//! Lets several signers of a P2PK lock each produce [`PartialSignatures`] for a set of
+//! proofs, merges them into the proof witnesses and checks the threshold is reached
+//! before the proofs are submitted to the mint.
+
+use std::str::FromStr;
+
+use bitcoin::secp256k1::schnorr::Signature;
+use serde::{Deserialize, Serialize};
+
+use super::{valid_signatures, Error, P2PKWitness, SigFlag};
+use crate::nut10::{get_pubkeys_and_required_sigs, Conditions, SpendingRequirements};
+use crate::nuts::nut00::Witness;
+use crate::nuts::{Kind, Nut10Secret, Proof, Proofs, PublicKey, SecretKey};
+use crate::util::unix_time;
+
+/// Signature of one signer over a single proof
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ProofSignature {
+    /// `Y` of the signed proof
+    pub y: PublicKey,
+    /// Schnorr signature over the proof secret
+    pub signature: String,
+}
+
+/// Signatures of one signer over a set of proofs
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PartialSignatures {
+    /// Public key of the signer
+    pub signer: PublicKey,
+    /// Per proof signatures
+    pub signatures: Vec<ProofSignature>,
+}
+
+/// Signature progress of a single proof
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SignatureThreshold {
+    /// Valid signatures on the primary path
+    pub valid: u64,
+    /// Signatures required on the primary path
+    pub required: u64,
+    /// Whether the proof can already be spent through the refund path
+    pub refund_satisfied: bool,
+}
+
+impl SignatureThreshold {
+    /// Whether the proof can be spent with its current witness
+    pub fn is_satisfied(&self) -> bool {
+        self.valid >= self.required || self.refund_satisfied
+    }
+}
+
+/// Collects signatures from several signers for proofs locked to multiple pubkeys
+///
+/// Only `SIG_INPUTS` proofs are supported, as `SIG_ALL` signatures commit to the
+/// outputs of the swap and must be collected on the request itself.
+#[derive(Debug, Clone)]
+pub struct MultisigProofs {
+    proofs: Proofs,
+}
+
+impl MultisigProofs {
+    /// Create a collector for P2PK locked proofs
+    pub fn new(proofs: Proofs) -> Result<Self, Error> {
+        for proof in &proofs {
+            let conditions = p2pk_conditions(proof)?;
+            if conditions.sig_flag == SigFlag::SigAll {
+                return Err(Error::SigAllNotSupportedHere);
+            }
+        }
+
+        Ok(Self { proofs })
+    }
+
+    /// Sign every proof with `secret_key`
+    pub fn sign(&self, secret_key: &SecretKey) -> Result<PartialSignatures, Error> {
+        let signatures = self
+            .proofs
+            .iter()
+            .map(|proof| {
+                Ok(ProofSignature {
+                    y: proof.y().map_err(|_| Error::IncorrectSecretKind)?,
+                    signature: secret_key.sign(&proof.secret.to_bytes())?.to_string(),
+                })
+            })
+            .collect::<Result<Vec<_>, Error>>()?;
+
+        Ok(PartialSignatures {
+            signer: secret_key.public_key(),
+            signatures,
+        })
+    }
+
+    /// Merge the signatures of one signer into the proof witnesses
+    ///
+    /// Every signature is checked against the proof it claims to sign, and the signer must
+    /// be one of the keys allowed to spend it. Merging the same signer twice is a no-op.
+    pub fn merge(&mut self, partial: &PartialSignatures) -> Result<(), Error> {
+        let now = unix_time();
+        let signer = partial.signer.x_only_public_key();
+
+        let mut updates = Vec::with_capacity(partial.signatures.len());
+        for proof_signature in &partial.signatures {
+            let index = self
+                .proofs
+                .iter()
+                .position(|proof| proof.y().ok() == Some(proof_signature.y))
+                .ok_or(Error::UnknownProof)?;
+            let proof = &self.proofs[index];
+
+            let requirements = spending_requirements(proof, now)?;
+            let allowed = requirements.pubkeys.iter().chain(
+                requirements
+                    .refund_path
+                    .iter()
+                    .flat_map(|refund_path| refund_path.pubkeys.iter()),
+            );
+            if !allowed
+                .map(PublicKey::x_only_public_key)
+                .any(|key| key == signer)
+            {
+                return Err(Error::SignerNotAuthorized);
+            }
+
+            let signature = Signature::from_str(&proof_signature.signature)
+                .map_err(|_| Error::InvalidSignature)?;
+            partial
+                .signer
+                .verify(&proof.secret.to_bytes(), &signature)
+                .map_err(|_| Error::InvalidSignature)?;
+
+            updates.push((index, proof_signature.signature.clone()));
+        }
+
+        for (index, signature) in updates {
+            let proof = &mut self.proofs[index];
+            if signer_has_signed(proof, &partial.signer) {
+                continue;
+            }
+
+            match proof.witness.as_mut() {
+                Some(witness) => witness.add_signatures(vec![signature]),
+                None => {
+                    proof.witness = Some(Witness::P2PKWitness(P2PKWitness {
+                        signatures: vec![signature],
+                    }))
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Signature progress of each proof, in order
+    pub fn thresholds(&self) -> Result<Vec<SignatureThreshold>, Error> {
+        let now = unix_time();
+        self.proofs
+            .iter()
+            .map(|proof| signature_threshold(proof, now))
+            .collect()
+    }
+
+    /// Whether every proof has enough signatures to be spent
+    pub fn is_satisfied(&self) -> Result<bool, Error> {
+        Ok(self
+            .thresholds()?
+            .iter()
+            .all(SignatureThreshold::is_satisfied))
+    }
+
+    /// Get the proofs with the merged witnesses
+    ///
+    /// Fails with [`Error::SpendConditionsNotMet`] if any proof is still short of its
+    /// signature threshold.
+    pub fn into_proofs(self) -> Result<Proofs, Error> {
+        if !self.is_satisfied()? {
+            return Err(Error::SpendConditionsNotMet);
+        }
+
+        Ok(self.proofs)
+    }
+}
+
+fn p2pk_conditions(proof: &Proof) -> Result<Conditions, Error> {
+    let secret: Nut10Secret = proof.secret.clone().try_into()?;
+    if secret.kind() != Kind::P2PK {
+        return Err(Error::IncorrectSecretKind);
+    }
+
+    secret
+        .secret_data()
+        .tags()
+        .cloned()
+        .unwrap_or_default()
+        .try_into()
+        .map_err(|_| Error::SpendConditionsNotMet)
+}
+
+fn spending_requirements(proof: &Proof, now: u64) -> Result<SpendingRequirements, Error> {
+    let secret: Nut10Secret = proof.secret.clone().try_into()?;
+    if secret.kind() != Kind::P2PK {
+        return Err(Error::IncorrectSecretKind);
+    }
+
+    get_pubkeys_and_required_sigs(&secret, now).map_err(|err| match err {
+        crate::nut10::Error::NUT11(nut11_err) => nut11_err,
+        _ => Error::SpendConditionsNotMet,
+    })
+}
+
+fn witness_signatures(proof: &Proof) -> Vec<Signature> {
+    proof
+        .witness
+        .as_ref()
+        .and_then(Witness::signatures)
+        .unwrap_or_default()
+        .iter()
+        .filter_map(|s| Signature::from_str(s).ok())
+        .collect()
+}
+
+fn signer_has_signed(proof: &Proof, signer: &PublicKey) -> bool {
+    let msg = proof.secret.to_bytes();
+
+    witness_signatures(proof)
+        .iter()
+        .any(|signature| signer.verify(&msg, signature).is_ok())
+}
+
+fn signature_threshold(proof: &Proof, now: u64) -> Result<SignatureThreshold, Error> {
+    let requirements = spending_requirements(proof, now)?;
+    let signatures = witness_signatures(proof);
+    let msg = proof.secret.to_bytes();
+
+    let valid = valid_signatures(&msg, &requirements.pubkeys, &signatures)?;
+    let refund_satisfied = match &requirements.refund_path {
+        Some(refund_path) => {
+            refund_path.required_sigs == 0
+                || valid_signatures(&msg, &refund_path.pubkeys, &signatures)?
+                    >= refund_path.required_sigs
+        }
+        None => false,
+    };
+
+    Ok(SignatureThreshold {
+        valid,
+        required: requirements.required_sigs,
+        refund_satisfied,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::nuts::Id;
+    use crate::secret::Secret;
+    use crate::{Amount, SpendingConditions};
+
+    #[test]
+    fn test_two_of_three_escrow() {
+        let buyer = SecretKey::generate();
+        let seller = SecretKey::generate();
+        let arbiter = SecretKey::generate();
+        let outsider = SecretKey::generate();
+
+        let conditions = Conditions {
+            pubkeys: Some(vec![seller.public_key(), arbiter.public_key()]),
+            num_sigs: Some(2),
+            ..Default::default()
+        };
+        let secret: Secret = SpendingConditions::new_p2pk(buyer.public_key(), Some(conditions))
+            .try_into()
+            .unwrap();
+        let proof = Proof {
+            amount: Amount::ONE,
+            keyset_id: Id::from_str("009a1f293253e41e").unwrap(),
+            secret,
+            c: buyer.public_key(),
+            witness: None,
+            dleq: None,
+            p2pk_e: None,
+        };
+
+        let mut multisig = MultisigProofs::new(vec![proof]).unwrap();
+
+        assert!(matches!(
+            multisig.merge(&multisig.sign(&outsider).unwrap()),
+            Err(Error::SignerNotAuthorized)
+        ));
+
+        let seller_signatures = multisig.sign(&seller).unwrap();
+        multisig.merge(&seller_signatures).unwrap();
+        multisig.merge(&seller_signatures).unwrap();
+        assert_eq!(multisig.thresholds().unwrap()[0].valid, 1);
+        assert!(!multisig.is_satisfied().unwrap());
+        assert!(multisig.clone().into_proofs().is_err());
+
+        multisig.merge(&multisig.sign(&arbiter).unwrap()).unwrap();
+        let proofs = multisig.into_proofs().unwrap();
+
+        assert!(proofs[0].verify_p2pk().is_ok());
+    }
+}
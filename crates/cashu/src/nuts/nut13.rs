@@ -1,5 +1,8 @@
 //! NUT-13: Deterministic Secrets
 //!
+//! Secrets are derived per keyset only. Version 01 keyset ids commit to the unit and
+//! version 00 keysets have per-unit keys, so a keyset scope already separates units.
+//!
 //! <https://github.com/cashubtc/nuts/blob/main/13.md>
 
 use bitcoin::bip32::{ChildNumber, DerivationPath, Xpriv};
@@ -10,7 +13,7 @@ use tracing::instrument;
 
 use super::nut00::{BlindedMessage, PreMint, PreMintSecrets};
 use super::nut01::SecretKey;
-use super::nut02::Id;
+use super::nut02::{Id, KeySetVersion};
 use crate::amount::{FeeAndAmounts, SplitTarget};
 use crate::dhke::blind_message;
 use crate::secret::Secret;
@@ -46,75 +49,59 @@ pub enum Error {
 impl Secret {
     /// Create new [`Secret`] from seed
     pub fn from_seed(seed: &[u8; 64], keyset_id: Id, counter: u32) -> Result<Self, Error> {
-        match keyset_id.get_version() {
-            super::nut02::KeySetVersion::Version00 => Self::legacy_derive(seed, keyset_id, counter),
-            super::nut02::KeySetVersion::Version01 => Self::derive(seed, keyset_id, counter),
-        }
-    }
-
-    fn legacy_derive(seed: &[u8; 64], keyset_id: Id, counter: u32) -> Result<Self, Error> {
-        let xpriv = Xpriv::new_master(Network::Bitcoin, seed)?;
-        let path = derive_path_from_keyset_id(keyset_id)?
-            .child(ChildNumber::from_hardened_idx(counter)?)
-            .child(ChildNumber::from_normal_idx(0)?);
-        let derived_xpriv = xpriv.derive_priv(&SECP256K1, &path)?;
+        let secret_bytes = match keyset_id.get_version() {
+            KeySetVersion::Version00 => legacy_derive(seed, keyset_id, counter, 0)?.secret_bytes(),
+            KeySetVersion::Version01 => hmac_derive(seed, keyset_id, counter, 0),
+        };
 
-        Ok(Self::new(hex::encode(
-            derived_xpriv.private_key.secret_bytes(),
-        )))
-    }
-
-    fn derive(seed: &[u8; 64], keyset_id: Id, counter: u32) -> Result<Self, Error> {
-        let mut message = Vec::new();
-        message.extend_from_slice(b"Cashu_KDF_HMAC_SHA256");
-        message.extend_from_slice(&keyset_id.to_bytes());
-        message.extend_from_slice(&(counter as u64).to_be_bytes());
-        message.extend_from_slice(b"\x00");
-
-        let mut engine = HmacEngine::<sha256::Hash>::new(seed);
-        engine.input(&message);
-        let hmac_result = hmac::Hmac::<sha256::Hash>::from_engine(engine);
-        let result_bytes = hmac_result.to_byte_array();
-
-        Ok(Self::new(hex::encode(&result_bytes[..32])))
+        Ok(Self::new(hex::encode(secret_bytes)))
     }
 }
 
 impl SecretKey {
     /// Create new [`SecretKey`] from seed
     pub fn from_seed(seed: &[u8; 64], keyset_id: Id, counter: u32) -> Result<Self, Error> {
-        match keyset_id.get_version() {
-            super::nut02::KeySetVersion::Version00 => Self::legacy_derive(seed, keyset_id, counter),
-            super::nut02::KeySetVersion::Version01 => Self::derive(seed, keyset_id, counter),
-        }
+        let secret_key = match keyset_id.get_version() {
+            KeySetVersion::Version00 => legacy_derive(seed, keyset_id, counter, 1)?,
+            KeySetVersion::Version01 => {
+                secp256k1::SecretKey::from_slice(&hmac_derive(seed, keyset_id, counter, 1))?
+            }
+        };
+
+        Ok(Self::from(secret_key))
     }
+}
 
-    fn legacy_derive(seed: &[u8; 64], keyset_id: Id, counter: u32) -> Result<Self, Error> {
-        let xpriv = Xpriv::new_master(Network::Bitcoin, seed)?;
-        let path = derive_path_from_keyset_id(keyset_id)?
-            .child(ChildNumber::from_hardened_idx(counter)?)
-            .child(ChildNumber::from_normal_idx(1)?);
-        let derived_xpriv = xpriv.derive_priv(&SECP256K1, &path)?;
-
-        Ok(Self::from(derived_xpriv.private_key))
-    }
+/// BIP32 derivation for version 00 keysets
+fn legacy_derive(
+    seed: &[u8; 64],
+    keyset_id: Id,
+    counter: u32,
+    leaf: u32,
+) -> Result<secp256k1::SecretKey, Error> {
+    let xpriv = Xpriv::new_master(Network::Bitcoin, seed)?;
+    let path = derive_path_from_keyset_id(keyset_id)?
+        .child(ChildNumber::from_hardened_idx(counter)?)
+        .child(ChildNumber::from_normal_idx(leaf)?);
+    let derived_xpriv = xpriv.derive_priv(&SECP256K1, &path)?;
+
+    Ok(derived_xpriv.private_key)
+}
 
-    fn derive(seed: &[u8; 64], keyset_id: Id, counter: u32) -> Result<Self, Error> {
-        let mut message = Vec::new();
-        message.extend_from_slice(b"Cashu_KDF_HMAC_SHA256");
-        message.extend_from_slice(&keyset_id.to_bytes());
-        message.extend_from_slice(&(counter as u64).to_be_bytes());
-        message.extend_from_slice(b"\x01");
-
-        let mut engine = HmacEngine::<sha256::Hash>::new(seed);
-        engine.input(&message);
-        let hmac_result = hmac::Hmac::<sha256::Hash>::from_engine(engine);
-        let result_bytes = hmac_result.to_byte_array();
-
-        Ok(Self::from(secp256k1::SecretKey::from_slice(
-            &result_bytes[..32],
-        )?))
-    }
+/// HMAC-SHA256 derivation for version 01 keysets
+///
+/// The message commits to every byte of the keyset id, so ids of any length derive
+/// distinct secrets.
+fn hmac_derive(seed: &[u8; 64], keyset_id: Id, counter: u32, kind: u8) -> [u8; 32] {
+    let mut message = Vec::new();
+    message.extend_from_slice(b"Cashu_KDF_HMAC_SHA256");
+    message.extend_from_slice(&keyset_id.to_bytes());
+    message.extend_from_slice(&(counter as u64).to_be_bytes());
+    message.push(kind);
+
+    let mut engine = HmacEngine::<sha256::Hash>::new(seed);
+    engine.input(&message);
+    hmac::Hmac::<sha256::Hash>::from_engine(engine).to_byte_array()
 }
 
 impl PreMintSecrets {
@@ -165,9 +152,23 @@ impl PreMintSecrets {
             return Ok(PreMintSecrets::new(keyset_id));
         }
         let count = ((u64::from(amount) as f64).log2().ceil() as u64).max(1);
+
+        Self::from_seed_blank_outputs(keyset_id, counter, seed, count as u32)
+    }
+
+    /// New [`PreMintSecrets`] from seed with `count` zero amount outputs used for change
+    ///
+    /// Wallets may request more change outputs than the change needs. The mint leaves the
+    /// outputs it does not need unsigned.
+    pub fn from_seed_blank_outputs(
+        keyset_id: Id,
+        counter: u32,
+        seed: &[u8; 64],
+        count: u32,
+    ) -> Result<Self, Error> {
         let mut pre_mint_secrets = PreMintSecrets::new(keyset_id);
 
-        for counter in counter..(counter + count as u32) {
+        for counter in counter..(counter + count) {
             let secret = Secret::from_seed(seed, keyset_id, counter)?;
             let blinding_factor = SecretKey::from_seed(seed, keyset_id, counter)?;
 
@@ -337,6 +338,21 @@ mod tests {
             .all(|secret| secret.amount == Amount::ZERO));
     }
 
+    #[test]
+    fn test_from_seed_blank_outputs_derives_requested_count() {
+        let seed =
+            "half depart obvious quality work element tank gorilla view sugar picture humble";
+        let mnemonic = Mnemonic::from_str(seed).unwrap();
+        let seed: [u8; 64] = mnemonic.to_seed("");
+        let keyset_id = Id::from_str("009a1f293253e41e").unwrap();
+
+        let pre_mint = PreMintSecrets::from_seed_blank_outputs(keyset_id, 5, &seed, 4).unwrap();
+        let blank = PreMintSecrets::from_seed_blank(keyset_id, 5, &seed, Amount::from(3)).unwrap();
+
+        assert_eq!(pre_mint.len(), 4);
+        assert_eq!(pre_mint.secrets[..2], blank.secrets[..]);
+    }
+
     #[test]
     fn test_secret_derivation_keyset_v2() {
         let seed =
@@ -567,4 +583,35 @@ mod tests {
             assert_eq!(pre_mint.secret, expected_secret);
         }
     }
+
+    #[test]
+    fn test_restore_batch_across_versions() {
+        let seed =
+            "half depart obvious quality work element tank gorilla view sugar picture humble";
+        let mnemonic = Mnemonic::from_str(seed).unwrap();
+        let seed: [u8; 64] = mnemonic.to_seed("");
+
+        let keyset_ids = [
+            Id::from_str("009a1f293253e41e").unwrap(),
+            Id::from_str("012e23479a0029432eaad0d2040c09be53bab592d5cbf1d55e0dd26c9495951b30")
+                .unwrap(),
+        ];
+
+        for keyset_id in keyset_ids {
+            let restored = PreMintSecrets::restore_batch(keyset_id, &seed, 3, 4).unwrap();
+            assert_eq!(
+                restored.secrets[0].secret,
+                Secret::from_seed(&seed, keyset_id, 3).unwrap()
+            );
+            assert_eq!(
+                restored.secrets[0].r,
+                SecretKey::from_seed(&seed, keyset_id, 3).unwrap()
+            );
+        }
+
+        assert_ne!(
+            Secret::from_seed(&seed, keyset_ids[0], 0).unwrap(),
+            Secret::from_seed(&seed, keyset_ids[1], 0).unwrap()
+        );
+    }
 }
@@ -646,6 +646,7 @@ mod tests {
             request: Some("lno1...".to_string()),
             unit: Some(CurrencyUnit::Sat),
             method: PaymentMethod::Known(KnownMethod::Bolt12),
+            change_policy: None,
         };
         let payload: NotificationPayload<String> =
             NotificationPayload::MeltQuoteBolt12Response(resp.clone());
@@ -8,7 +8,10 @@ use serde::de::DeserializeOwned;
 use serde::{Deserialize, Serialize};
 use thiserror::Error;
 
-use super::{BlindSignature, CurrencyUnit, MeltQuoteState, Mpp, PaymentMethod, PublicKey};
+use super::{
+    AppliedMeltChangePolicy, BlindSignature, CurrencyUnit, MeltQuoteState, Mpp, PaymentMethod,
+    PublicKey,
+};
 #[cfg(feature = "mint")]
 use crate::quote_id::QuoteId;
 use crate::util::serde_helpers::deserialize_empty_string_as_none;
@@ -288,6 +291,9 @@ pub struct MeltQuoteBolt11Response<Q> {
     /// Payment method
     #[serde(default = "default_bolt11_method")]
     pub method: PaymentMethod,
+    /// Change policy the mint applied to the unspent fee reserve once the quote is paid
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub change_policy: Option<AppliedMeltChangePolicy>,
 }
 
 impl<Q: ToString> MeltQuoteBolt11Response<Q> {
@@ -305,6 +311,7 @@ impl<Q: ToString> MeltQuoteBolt11Response<Q> {
             request: self.request,
             unit: self.unit,
             method: self.method,
+            change_policy: self.change_policy,
         }
     }
 }
@@ -323,6 +330,7 @@ impl From<MeltQuoteBolt11Response<QuoteId>> for MeltQuoteBolt11Response<String>
             request: value.request,
             unit: value.unit,
             method: value.method,
+            change_policy: value.change_policy,
         }
     }
 }
@@ -455,6 +463,7 @@ mod tests {
             request: Some("lnbc...".to_string()),
             unit: Some(CurrencyUnit::Sat),
             method: PaymentMethod::Known(KnownMethod::Bolt11),
+            change_policy: None,
         };
 
         let value = to_value(&response).expect("serialize response");
@@ -3,7 +3,10 @@ use serde::de::DeserializeOwned;
 use serde::{Deserialize, Serialize};
 use thiserror::Error;
 
-use super::{BlindSignature, CurrencyUnit, MeltOptions, MeltQuoteState, PaymentMethod, PublicKey};
+use super::{
+    AppliedMeltChangePolicy, BlindSignature, CurrencyUnit, MeltOptions, MeltQuoteState,
+    PaymentMethod, PublicKey,
+};
 #[cfg(feature = "mint")]
 use crate::quote_id::QuoteId;
 use crate::Amount;
@@ -146,6 +149,9 @@ pub struct MeltQuoteBolt12Response<Q> {
     /// Payment method
     #[serde(default = "default_bolt12_method")]
     pub method: PaymentMethod,
+    /// Change policy the mint applied to the unspent fee reserve once the quote is paid
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub change_policy: Option<AppliedMeltChangePolicy>,
 }
 
 impl<Q: ToString> MeltQuoteBolt12Response<Q> {
@@ -163,6 +169,7 @@ impl<Q: ToString> MeltQuoteBolt12Response<Q> {
             request: self.request,
             unit: self.unit,
             method: self.method,
+            change_policy: self.change_policy,
         }
     }
 }
@@ -181,6 +188,7 @@ impl From<MeltQuoteBolt12Response<QuoteId>> for MeltQuoteBolt12Response<String>
             request: value.request,
             unit: value.unit,
             method: value.method,
+            change_policy: value.change_policy,
         }
     }
 }
@@ -271,6 +279,7 @@ mod tests {
             request: Some("lno1...".to_string()),
             unit: Some(CurrencyUnit::Sat),
             method: PaymentMethod::Known(KnownMethod::Bolt12),
+            change_policy: None,
         };
 
         let value = to_value(&response).expect("serialize response");
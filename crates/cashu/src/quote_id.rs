@@ -4,7 +4,7 @@ use std::str::FromStr;
 
 use bitcoin::base64::engine::general_purpose;
 use bitcoin::base64::Engine as _;
-use serde::{de, Deserialize, Deserializer, Serialize};
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
 use thiserror::Error;
 use uuid::Uuid;
 
@@ -17,11 +17,147 @@ pub enum QuoteIdError {
     /// Invalid base64
     #[error("invalid base64")]
     Base64,
+    /// Invalid ULID
+    #[error("invalid ULID")]
+    Ulid,
     /// Invalid quote ID
-    #[error("neither a valid UUID nor a valid base64 string")]
+    #[error("neither a valid UUID, ULID nor a valid base64 string")]
     InvalidQuoteId,
 }
 
+/// Crockford base32 alphabet used by ULIDs
+const CROCKFORD_ALPHABET: &[u8; 32] = b"0123456789ABCDEFGHJKMNPQRSTVWXYZ";
+
+/// Length of an encoded ULID
+const ULID_LEN: usize = 26;
+
+/// Universally Unique Lexicographically Sortable Identifier
+///
+/// 128 bits, the first 48 of which are the creation time in milliseconds, encoded as 26
+/// Crockford base32 characters. Encoded ULIDs sort in creation order.
+#[derive(Debug, Clone, Copy, PartialEq, PartialOrd, Ord, Eq, Hash)]
+pub struct Ulid(u128);
+
+impl Ulid {
+    /// Create a new ULID for the current time
+    pub fn new() -> Self {
+        // A UUIDv7 starts with the same 48 bit millisecond timestamp as a ULID, followed by
+        // random bits, so its bits are a valid ULID
+        Self(Uuid::now_v7().as_u128())
+    }
+
+    /// Raw 128 bit value
+    pub fn as_u128(&self) -> u128 {
+        self.0
+    }
+}
+
+impl Default for Ulid {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl From<u128> for Ulid {
+    fn from(value: u128) -> Self {
+        Self(value)
+    }
+}
+
+impl fmt::Display for Ulid {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut encoded = [0u8; ULID_LEN];
+        for (i, c) in encoded.iter_mut().enumerate() {
+            let shift = 5 * (ULID_LEN - 1 - i);
+            *c = CROCKFORD_ALPHABET[((self.0 >> shift) & 0x1f) as usize];
+        }
+        // The alphabet is ASCII
+        f.write_str(std::str::from_utf8(&encoded).map_err(|_| fmt::Error)?)
+    }
+}
+
+impl FromStr for Ulid {
+    type Err = QuoteIdError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.len() != ULID_LEN {
+            return Err(QuoteIdError::Ulid);
+        }
+
+        let mut value: u128 = 0;
+        for (i, c) in s.bytes().enumerate() {
+            let digit = CROCKFORD_ALPHABET
+                .iter()
+                .position(|a| *a == c.to_ascii_uppercase())
+                .ok_or(QuoteIdError::Ulid)?;
+
+            // 26 characters carry 130 bits, the first one can only hold the top 3 bits
+            if i == 0 && digit > 7 {
+                return Err(QuoteIdError::Ulid);
+            }
+
+            value = (value << 5) | digit as u128;
+        }
+
+        Ok(Self(value))
+    }
+}
+
+impl Serialize for Ulid {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for Ulid {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let s = String::deserialize(deserializer)?;
+        Ulid::from_str(&s).map_err(de::Error::custom)
+    }
+}
+
+/// Format of newly generated quote IDs
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum QuoteIdFormat {
+    /// Random UUIDv4
+    UuidV4,
+    /// Time-ordered UUIDv7
+    #[default]
+    UuidV7,
+    /// Time-ordered ULID
+    Ulid,
+}
+
+impl fmt::Display for QuoteIdFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            QuoteIdFormat::UuidV4 => write!(f, "uuid_v4"),
+            QuoteIdFormat::UuidV7 => write!(f, "uuid_v7"),
+            QuoteIdFormat::Ulid => write!(f, "ulid"),
+        }
+    }
+}
+
+impl FromStr for QuoteIdFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "uuid_v4" | "uuidv4" | "uuid4" => Ok(QuoteIdFormat::UuidV4),
+            "uuid_v7" | "uuidv7" | "uuid7" => Ok(QuoteIdFormat::UuidV7),
+            "ulid" => Ok(QuoteIdFormat::Ulid),
+            _ => Err(format!("Unknown quote id format: {s}")),
+        }
+    }
+}
+
 /// Mint Quote ID
 #[derive(Serialize, Debug, Clone, PartialEq, PartialOrd, Ord, Eq, Hash)]
 #[serde(untagged)]
@@ -30,6 +166,8 @@ pub enum QuoteId {
     BASE64(String),
     /// UUID quote ID
     UUID(Uuid),
+    /// ULID quote ID
+    ULID(Ulid),
 }
 
 impl QuoteId {
@@ -37,6 +175,15 @@ impl QuoteId {
     pub fn new() -> Self {
         Self::UUID(Uuid::now_v7())
     }
+
+    /// Create a new quote ID in the given format
+    pub fn new_with_format(format: QuoteIdFormat) -> Self {
+        match format {
+            QuoteIdFormat::UuidV4 => Self::UUID(Uuid::new_v4()),
+            QuoteIdFormat::UuidV7 => Self::UUID(Uuid::now_v7()),
+            QuoteIdFormat::Ulid => Self::ULID(Ulid::new()),
+        }
+    }
 }
 
 impl Default for QuoteId {
@@ -51,11 +198,18 @@ impl From<Uuid> for QuoteId {
     }
 }
 
+impl From<Ulid> for QuoteId {
+    fn from(ulid: Ulid) -> Self {
+        Self::ULID(ulid)
+    }
+}
+
 impl fmt::Display for QuoteId {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
             QuoteId::BASE64(s) => write!(f, "{s}"),
             QuoteId::UUID(u) => write!(f, "{}", u.hyphenated()),
+            QuoteId::ULID(u) => write!(f, "{u}"),
         }
     }
 }
@@ -69,6 +223,10 @@ impl FromStr for QuoteId {
             return Ok(QuoteId::UUID(u));
         }
 
+        if let Ok(u) = Ulid::from_str(s) {
+            return Ok(QuoteId::ULID(u));
+        }
+
         // Try base64: decode, then re-encode and compare to ensure canonical form
         // Use the standard (URL/filename safe or standard) depending on your needed alphabet.
         // Here we use standard base64.
@@ -92,13 +250,17 @@ impl<'de> Deserialize<'de> for QuoteId {
             return Ok(QuoteId::UUID(u));
         }
 
+        if let Ok(u) = Ulid::from_str(&s) {
+            return Ok(QuoteId::ULID(u));
+        }
+
         if general_purpose::URL_SAFE.decode(&s).is_ok() {
             return Ok(QuoteId::BASE64(s));
         }
 
         // Neither matched — return a helpful error
         Err(de::Error::custom(format!(
-            "QuoteId must be either a UUID (e.g. {}) or a valid base64 string; got: {}",
+            "QuoteId must be either a UUID (e.g. {}), a ULID or a valid base64 string; got: {}",
             Uuid::nil(),
             s
         )))
@@ -171,6 +333,54 @@ mod tests {
         assert_eq!(quote_id, QuoteId::BASE64("SGVsbG8gV29ybGQh".to_string()));
     }
 
+    #[test]
+    fn test_quote_id_ulid_roundtrip() {
+        let quote_id = QuoteId::new_with_format(QuoteIdFormat::Ulid);
+        let QuoteId::ULID(ulid) = quote_id else {
+            panic!("expected a ULID quote ID");
+        };
+
+        let displayed = quote_id.to_string();
+        assert_eq!(displayed.len(), 26);
+        assert_eq!(displayed.parse::<QuoteId>().unwrap(), quote_id);
+        assert_eq!(
+            displayed.to_lowercase().parse::<QuoteId>().unwrap(),
+            quote_id
+        );
+
+        let json = serde_json::to_string(&quote_id).unwrap();
+        assert_eq!(json, format!("\"{displayed}\""));
+        assert_eq!(serde_json::from_str::<QuoteId>(&json).unwrap(), quote_id);
+
+        // Known encoding from the ULID specification
+        let ulid: Ulid = "01ARZ3NDEKTSV4RRFFQ69G5FAV".parse().unwrap();
+        assert_eq!(ulid.to_string(), "01ARZ3NDEKTSV4RRFFQ69G5FAV");
+        assert!("81ARZ3NDEKTSV4RRFFQ69G5FAV".parse::<Ulid>().is_err());
+
+        // Later ULIDs sort after earlier ones
+        let later = Ulid::from(ulid.as_u128() + (1 << 80));
+        assert!(ulid.to_string() < later.to_string());
+    }
+
+    #[test]
+    fn test_quote_id_new_with_format() {
+        let QuoteId::UUID(v4) = QuoteId::new_with_format(QuoteIdFormat::UuidV4) else {
+            panic!("expected a UUID quote ID");
+        };
+        assert_eq!(v4.get_version(), Some(uuid::Version::Random));
+
+        let QuoteId::UUID(v7) = QuoteId::new_with_format(QuoteIdFormat::default()) else {
+            panic!("expected a UUID quote ID");
+        };
+        assert_eq!(v7.get_version(), Some(uuid::Version::SortRand));
+
+        assert_eq!(
+            "ulid".parse::<QuoteIdFormat>().unwrap(),
+            QuoteIdFormat::Ulid
+        );
+        assert!("snowflake".parse::<QuoteIdFormat>().is_err());
+    }
+
     #[test]
     fn test_quote_id_deserialize_rejects_invalid_id() {
         let err = serde_json::from_str::<QuoteId>(r#""not a quote id""#).unwrap_err();
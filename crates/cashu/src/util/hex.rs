@@ -20,6 +20,13 @@ pub enum Error {
     /// A hex string's length needs to be even, as two digits correspond to
     /// one byte.
     OddLength,
+    /// The output buffer does not match the length of the input
+    InvalidBufferLength {
+        /// Expected buffer length
+        expected: usize,
+        /// Found buffer length
+        found: usize,
+    },
 }
 
 impl std::error::Error for Error {}
@@ -31,6 +38,12 @@ impl fmt::Display for Error {
                 write!(f, "Invalid character {c} at position {index}")
             }
             Self::OddLength => write!(f, "Odd number of digits"),
+            Self::InvalidBufferLength { expected, found } => {
+                write!(
+                    f,
+                    "Invalid buffer length: expected {expected}, found {found}"
+                )
+            }
         }
     }
 }
@@ -58,6 +71,31 @@ where
     hex
 }
 
+/// Hex encode into `out` without allocating
+///
+/// `out` must be exactly twice as long as `data`. Returns the encoded string
+/// borrowed from `out`.
+pub fn encode_to_slice<'a>(data: &[u8], out: &'a mut [u8]) -> Result<&'a str, Error> {
+    ensure_cdk!(
+        out.len() == 2 * data.len(),
+        Error::InvalidBufferLength {
+            expected: 2 * data.len(),
+            found: out.len(),
+        }
+    );
+
+    for (byte, chunk) in data.iter().zip(out.chunks_exact_mut(2)) {
+        chunk[0] = from_digit(byte >> 4) as u8;
+        chunk[1] = from_digit(byte & 0xF) as u8;
+    }
+
+    // All hex digits are ASCII, so this never fails
+    core::str::from_utf8(out).map_err(|e| Error::InvalidHexCharacter {
+        c: '\0',
+        index: e.valid_up_to(),
+    })
+}
+
 const fn val(c: u8, idx: usize) -> Result<u8, Error> {
     match c {
         b'A'..=b'F' => Ok(c - b'A' + 10),
@@ -91,6 +129,34 @@ where
     Ok(bytes)
 }
 
+/// Hex decode into `out` without allocating
+///
+/// `out` must be exactly half as long as `hex`.
+pub fn decode_to_slice<T>(hex: T, out: &mut [u8]) -> Result<(), Error>
+where
+    T: AsRef<[u8]>,
+{
+    let hex = hex.as_ref();
+    let len = hex.len();
+
+    ensure_cdk!(len % 2 == 0, Error::OddLength);
+    ensure_cdk!(
+        out.len() == len / 2,
+        Error::InvalidBufferLength {
+            expected: len / 2,
+            found: out.len(),
+        }
+    );
+
+    for (i, byte) in out.iter_mut().enumerate() {
+        let high = val(hex[2 * i], 2 * i)?;
+        let low = val(hex[2 * i + 1], 2 * i + 1)?;
+        *byte = (high << 4) | low;
+    }
+
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -110,6 +176,24 @@ mod tests {
         assert_eq!(decode("ff"), Ok(vec![0xff]));
     }
 
+    #[test]
+    fn test_slice_round_trip() {
+        let mut buf = [0u8; 12];
+        assert_eq!(encode_to_slice(b"foobar", &mut buf), Ok("666f6f626172"));
+
+        let mut bytes = [0u8; 6];
+        decode_to_slice("666f6f626172", &mut bytes).unwrap();
+        assert_eq!(&bytes, b"foobar");
+
+        assert_eq!(
+            decode_to_slice("666f", &mut bytes).unwrap_err(),
+            Error::InvalidBufferLength {
+                expected: 2,
+                found: 6
+            }
+        );
+    }
+
     #[test]
     fn test_invalid_length() {
         assert_eq!(decode("1").unwrap_err(), Error::OddLength);
@@ -2,6 +2,7 @@
 
 pub mod hex;
 pub mod serde_helpers;
+pub mod ur;
 
 use bitcoin::secp256k1::{rand, All, Secp256k1};
 use once_cell::sync::Lazy;
@@ -0,0 +1,808 @@
+//! Uniform Resources (BC-UR) for animated QR codes
+//!
+//! Large tokens do not fit into a single QR code. This module splits a payload into
+//! fountain coded `ur:bytes` parts that can be shown as an animated QR code, and
+//! reassembles them from any sufficient subset of scanned parts.
+//!
+//! <https://github.com/BlockchainCommons/Research/blob/master/papers/bcr-2020-005-ur.md>
+
+use std::collections::{BTreeSet, HashMap};
+use std::str::FromStr;
+
+use bitcoin::hashes::{sha256, Hash};
+use ciborium::Value;
+use thiserror::Error;
+
+use crate::nuts::Token;
+
+/// UR type used for tokens
+pub const UR_TYPE_BYTES: &str = "bytes";
+
+/// Default maximum fragment length, fits a medium density QR code
+pub const DEFAULT_MAX_FRAGMENT_LEN: usize = 200;
+
+/// Minimum fragment length, as in the BC-UR reference implementation
+pub const MIN_FRAGMENT_LEN: usize = 10;
+
+/// Maximum number of fragments of a message
+///
+/// Bounds the work a decoder does for a single part, since the fountain code samples over
+/// all fragments of the message.
+pub const MAX_FRAGMENT_COUNT: usize = 10_000;
+
+/// Bytewords, four letters per byte value
+const BYTEWORDS: &str = "ableacidalsoapexaquaarchatomauntawayaxisbackbaldbarnbeltbetabiasbluebodybragbrewbulbbuzzcalmcashcatschefcityclawcodecolacookcostcruxcurlcuspcyandarkdatadaysdelidicedietdoordowndrawdropdrumdulldutyeacheasyechoedgeepicevenexamexiteyesfactfairfernfigsfilmfishfizzflapflewfluxfoxyfreefrogfuelfundgalagamegeargemsgiftgirlglowgoodgraygrimgurugushgyrohalfhanghardhawkheathelphighhillholyhopehornhutsicedideaidleinchinkyintoirisironitemjadejazzjoinjoltjowljudojugsjumpjunkjurykeepkenokeptkeyskickkilnkingkitekiwiknoblamblavalazyleaflegsliarlimplionlistlogoloudloveluaulucklungmainmanymathmazememomenumeowmildmintmissmonknailnavyneednewsnextnoonnotenumbobeyoboeomitonyxopenovalowlspaidpartpeckplaypluspoempoolposepuffpumapurrquadquizraceramprealredorichroadrockroofrubyruinrunsrustsafesagascarsetssilkskewslotsoapsolosongstubsurfswantacotasktaxitenttiedtimetinytoiltombtoystriptunatwinuglyundouniturgeuservastveryvetovialvibeviewvisavoidvowswallwandwarmwaspwavewaxywebswhatwhenwhizwolfworkyankyawnyellyogayurtzapszerozestzinczonezoom";
+
+/// UR Error
+#[derive(Debug, Error)]
+pub enum Error {
+    /// String does not start with `ur:`
+    #[error("Invalid UR scheme")]
+    InvalidScheme,
+    /// UR type is empty or contains invalid characters
+    #[error("Invalid UR type")]
+    InvalidType,
+    /// Part has a different type than the previous parts
+    #[error("UR type mismatch: expected `{expected}`, got `{got}`")]
+    TypeMismatch {
+        /// Type of the previous parts
+        expected: String,
+        /// Type of the received part
+        got: String,
+    },
+    /// Sequence component is malformed
+    #[error("Invalid UR sequence")]
+    InvalidSequence,
+    /// Body is not valid minimal bytewords
+    #[error("Invalid bytewords")]
+    InvalidBytewords,
+    /// Checksum does not match the data
+    #[error("Invalid checksum")]
+    InvalidChecksum,
+    /// Part does not belong to the message being decoded
+    #[error("Part does not belong to this message")]
+    InconsistentPart,
+    /// Payload is empty
+    #[error("Payload is empty")]
+    EmptyPayload,
+    /// Maximum fragment length is below [`MIN_FRAGMENT_LEN`]
+    #[error("Fragment length must be at least {MIN_FRAGMENT_LEN}")]
+    InvalidFragmentLength,
+    /// Payload needs more than [`MAX_FRAGMENT_COUNT`] fragments
+    #[error("Payload needs more than {MAX_FRAGMENT_COUNT} fragments")]
+    TooManyFragments,
+    /// Message is not complete yet
+    #[error("UR message is not complete")]
+    Incomplete,
+    /// CBOR is malformed
+    #[error("Invalid CBOR")]
+    InvalidCbor,
+    /// Ciborium serialization error
+    #[error(transparent)]
+    CiboriumSer(#[from] ciborium::ser::Error<std::io::Error>),
+    /// Decoded payload is not a valid token
+    #[error(transparent)]
+    Token(#[from] crate::nuts::nut00::Error),
+}
+
+/// Fountain encoder producing an endless sequence of UR parts
+///
+/// The first [`fragment_count`](Self::fragment_count) parts carry the message as is,
+/// later parts mix several fragments so a scanner can recover missed frames.
+#[derive(Debug, Clone)]
+pub struct UrEncoder {
+    ur_type: String,
+    message: Vec<u8>,
+    fragments: Vec<Vec<u8>>,
+    checksum: u32,
+    seq_num: u32,
+}
+
+impl UrEncoder {
+    /// Create an encoder for `payload` with fragments of at most `max_fragment_len` bytes
+    pub fn new(ur_type: &str, payload: &[u8], max_fragment_len: usize) -> Result<Self, Error> {
+        check_type(ur_type)?;
+        if payload.is_empty() {
+            return Err(Error::EmptyPayload);
+        }
+        if max_fragment_len < MIN_FRAGMENT_LEN {
+            return Err(Error::InvalidFragmentLength);
+        }
+
+        let message = cbor_bytes(payload)?;
+        let fragment_len = fragment_len(message.len(), max_fragment_len);
+        if message.len().div_ceil(fragment_len) > MAX_FRAGMENT_COUNT {
+            return Err(Error::TooManyFragments);
+        }
+        let fragments = message
+            .chunks(fragment_len)
+            .map(|chunk| {
+                let mut fragment = chunk.to_vec();
+                fragment.resize(fragment_len, 0);
+                fragment
+            })
+            .collect();
+
+        Ok(Self {
+            ur_type: ur_type.to_string(),
+            checksum: crc32(&message),
+            message,
+            fragments,
+            seq_num: 0,
+        })
+    }
+
+    /// Number of parts needed at minimum to decode the message
+    pub fn fragment_count(&self) -> usize {
+        self.fragments.len()
+    }
+
+    /// Whether the message fits into a single part
+    pub fn is_single_part(&self) -> bool {
+        self.fragments.len() == 1
+    }
+
+    /// Produce the next part
+    ///
+    /// Single part messages always return the same UR. Parts are lowercase; uppercase
+    /// them to use the more compact alphanumeric QR mode.
+    pub fn next_part(&mut self) -> Result<String, Error> {
+        if self.is_single_part() {
+            return Ok(format!(
+                "ur:{}/{}",
+                self.ur_type,
+                bytewords_encode(&self.message)
+            ));
+        }
+
+        self.seq_num = self.seq_num.wrapping_add(1).max(1);
+        let seq_len = self.fragments.len() as u32;
+        let indexes = choose_fragments(self.seq_num, seq_len, self.checksum);
+
+        let mut data = vec![0u8; self.fragments[0].len()];
+        for index in indexes {
+            xor_into(&mut data, &self.fragments[index]);
+        }
+
+        let part = Value::Array(vec![
+            Value::Integer(self.seq_num.into()),
+            Value::Integer(seq_len.into()),
+            Value::Integer((self.message.len() as u64).into()),
+            Value::Integer(self.checksum.into()),
+            Value::Bytes(data),
+        ]);
+        let mut cbor = Vec::new();
+        ciborium::into_writer(&part, &mut cbor)?;
+
+        Ok(format!(
+            "ur:{}/{}-{}/{}",
+            self.ur_type,
+            self.seq_num,
+            seq_len,
+            bytewords_encode(&cbor)
+        ))
+    }
+}
+
+/// Header shared by all parts of a multi part message
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct PartHeader {
+    seq_len: u32,
+    message_len: usize,
+    checksum: u32,
+    fragment_len: usize,
+}
+
+/// Fountain decoder reassembling a message from UR parts in any order
+#[derive(Debug, Default, Clone)]
+pub struct UrDecoder {
+    ur_type: Option<String>,
+    header: Option<PartHeader>,
+    simple_parts: HashMap<usize, Vec<u8>>,
+    mixed_parts: Vec<(BTreeSet<usize>, Vec<u8>)>,
+    payload: Option<Vec<u8>>,
+}
+
+impl UrDecoder {
+    /// Create an empty decoder
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Receive a scanned part
+    ///
+    /// Duplicate and redundant parts are ignored. Parts are accepted in upper or lower case.
+    pub fn receive(&mut self, part: &str) -> Result<(), Error> {
+        if self.payload.is_some() {
+            return Ok(());
+        }
+
+        let part = part.trim().to_lowercase();
+        let rest = part.strip_prefix("ur:").ok_or(Error::InvalidScheme)?;
+        let components: Vec<&str> = rest.split('/').collect();
+
+        let ur_type = components[0];
+        check_type(ur_type)?;
+        match &self.ur_type {
+            Some(expected) if expected != ur_type => {
+                return Err(Error::TypeMismatch {
+                    expected: expected.clone(),
+                    got: ur_type.to_string(),
+                })
+            }
+            _ => self.ur_type = Some(ur_type.to_string()),
+        }
+
+        match components[1..] {
+            [body] => {
+                let message = bytewords_decode(body)?;
+                self.payload = Some(cbor_bytes_decode(&message)?);
+                Ok(())
+            }
+            [sequence, body] => self.receive_multi_part(sequence, body),
+            _ => Err(Error::InvalidSequence),
+        }
+    }
+
+    fn receive_multi_part(&mut self, sequence: &str, body: &str) -> Result<(), Error> {
+        let (seq_num, seq_len) = sequence.split_once('-').ok_or(Error::InvalidSequence)?;
+        let seq_num = u32::from_str(seq_num).map_err(|_| Error::InvalidSequence)?;
+        let seq_len = u32::from_str(seq_len).map_err(|_| Error::InvalidSequence)?;
+        if seq_num == 0 || seq_len == 0 {
+            return Err(Error::InvalidSequence);
+        }
+
+        let cbor = bytewords_decode(body)?;
+        let value: Value =
+            ciborium::from_reader(cbor.as_slice()).map_err(|_| Error::InvalidCbor)?;
+        let (header, data) = match value {
+            Value::Array(items) => match items.as_slice() {
+                [Value::Integer(num), Value::Integer(len), Value::Integer(message_len), Value::Integer(checksum), Value::Bytes(data)] =>
+                {
+                    let num = u32::try_from(*num).map_err(|_| Error::InvalidCbor)?;
+                    let len = u32::try_from(*len).map_err(|_| Error::InvalidCbor)?;
+                    if num != seq_num || len != seq_len {
+                        return Err(Error::InvalidSequence);
+                    }
+                    let header = PartHeader {
+                        seq_len,
+                        message_len: u64::try_from(*message_len).map_err(|_| Error::InvalidCbor)?
+                            as usize,
+                        checksum: u32::try_from(*checksum).map_err(|_| Error::InvalidCbor)?,
+                        fragment_len: data.len(),
+                    };
+                    (header, data.clone())
+                }
+                _ => return Err(Error::InvalidCbor),
+            },
+            _ => return Err(Error::InvalidCbor),
+        };
+
+        // The sequence length follows from the message and fragment lengths. Bounding it keeps
+        // a forged header from making the decoder sample over billions of fragments.
+        let seq_len = header.seq_len as usize;
+        if header.message_len == 0
+            || fragment_len(header.message_len, data.len()) != data.len()
+            || seq_len != header.message_len.div_ceil(data.len())
+            || seq_len > header.message_len.div_ceil(MIN_FRAGMENT_LEN)
+            || seq_len > MAX_FRAGMENT_COUNT
+        {
+            return Err(Error::InconsistentPart);
+        }
+        match &self.header {
+            Some(expected) if expected != &header => return Err(Error::InconsistentPart),
+            Some(_) => {}
+            None => self.header = Some(header.clone()),
+        }
+
+        let indexes: BTreeSet<usize> = choose_fragments(seq_num, seq_len, header.checksum)
+            .into_iter()
+            .collect();
+        self.process(indexes, data);
+
+        if self.simple_parts.len() == header.seq_len as usize {
+            let mut message: Vec<u8> = (0..header.seq_len as usize)
+                .flat_map(|index| self.simple_parts[&index].clone())
+                .collect();
+            message.truncate(header.message_len);
+
+            if crc32(&message) != header.checksum {
+                return Err(Error::InvalidChecksum);
+            }
+            self.payload = Some(cbor_bytes_decode(&message)?);
+        }
+
+        Ok(())
+    }
+
+    /// Reduce a part by the known fragments and propagate newly recovered ones
+    fn process(&mut self, indexes: BTreeSet<usize>, data: Vec<u8>) {
+        let mut queue = vec![(indexes, data)];
+
+        while let Some((mut indexes, mut data)) = queue.pop() {
+            for (index, fragment) in &self.simple_parts {
+                if indexes.remove(index) {
+                    xor_into(&mut data, fragment);
+                }
+            }
+
+            match indexes.len() {
+                0 => {}
+                1 => {
+                    let index = *indexes.iter().next().expect("one index");
+                    self.simple_parts.insert(index, data.clone());
+
+                    // Mixed parts containing the new fragment can be reduced further
+                    let (reducible, rest): (Vec<_>, Vec<_>) = std::mem::take(&mut self.mixed_parts)
+                        .into_iter()
+                        .partition(|(mixed, _)| mixed.contains(&index));
+                    self.mixed_parts = rest;
+                    queue.extend(reducible);
+                }
+                _ => {
+                    if !self.mixed_parts.iter().any(|(mixed, _)| mixed == &indexes) {
+                        self.mixed_parts.push((indexes, data));
+                    }
+                }
+            }
+        }
+    }
+
+    /// Whether the message has been fully decoded
+    pub fn is_complete(&self) -> bool {
+        self.payload.is_some()
+    }
+
+    /// Fraction of fragments recovered so far, between 0 and 1
+    pub fn progress(&self) -> f64 {
+        match (&self.payload, &self.header) {
+            (Some(_), _) => 1.0,
+            (None, Some(header)) => self.simple_parts.len() as f64 / header.seq_len as f64,
+            (None, None) => 0.0,
+        }
+    }
+
+    /// Type of the received parts
+    pub fn ur_type(&self) -> Option<&str> {
+        self.ur_type.as_deref()
+    }
+
+    /// Decoded payload, once complete
+    pub fn payload(&self) -> Option<&[u8]> {
+        self.payload.as_deref()
+    }
+}
+
+impl Token {
+    /// Create a [`UrEncoder`] showing this token as an animated QR code
+    pub fn to_ur_encoder(&self, max_fragment_len: usize) -> Result<UrEncoder, Error> {
+        UrEncoder::new(UR_TYPE_BYTES, self.to_string().as_bytes(), max_fragment_len)
+    }
+
+    /// Token from a completed [`UrDecoder`]
+    pub fn from_ur_decoder(decoder: &UrDecoder) -> Result<Self, Error> {
+        let payload = decoder.payload().ok_or(Error::Incomplete)?;
+        let token = std::str::from_utf8(payload)
+            .map_err(|_| Error::Token(crate::nuts::nut00::Error::UnsupportedToken))?;
+
+        Ok(Self::from_str(token)?)
+    }
+}
+
+/// Number of fragments a payload of `payload_len` bytes is split into
+pub fn estimate_fragment_count(payload_len: usize, max_fragment_len: usize) -> usize {
+    if max_fragment_len == 0 {
+        return 0;
+    }
+    let message_len = cbor_bytes_len(payload_len);
+
+    message_len.div_ceil(fragment_len(message_len, max_fragment_len))
+}
+
+/// Upper bound of the length of a single UR part for a payload of `payload_len` bytes
+///
+/// Useful to pick a `max_fragment_len` that fits the QR code version a wallet displays.
+pub fn estimate_part_len(ur_type: &str, payload_len: usize, max_fragment_len: usize) -> usize {
+    let message_len = cbor_bytes_len(payload_len);
+    if max_fragment_len == 0 || message_len <= max_fragment_len {
+        return "ur:/".len() + ur_type.len() + 2 * (message_len + 4);
+    }
+
+    let fragment_len = fragment_len(message_len, max_fragment_len);
+    let seq_len = message_len.div_ceil(fragment_len);
+    // Array header, four integers of at most five bytes and the byte string header
+    let cbor_len = 1 + 4 * 5 + cbor_bytes_len(fragment_len);
+    // Sequence numbers keep growing, allow for ten digits
+    let sequence_len = 10 + 1 + seq_len.to_string().len() + 1;
+
+    "ur:/".len() + ur_type.len() + sequence_len + 2 * (cbor_len + 4)
+}
+
+fn check_type(ur_type: &str) -> Result<(), Error> {
+    if ur_type.is_empty()
+        || !ur_type
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-')
+    {
+        return Err(Error::InvalidType);
+    }
+
+    Ok(())
+}
+
+/// Fragment length that splits `message_len` bytes into equally sized fragments
+fn fragment_len(message_len: usize, max_fragment_len: usize) -> usize {
+    let fragment_count = message_len.div_ceil(max_fragment_len);
+    message_len.div_ceil(fragment_count)
+}
+
+/// Length of a CBOR byte string holding `len` bytes
+fn cbor_bytes_len(len: usize) -> usize {
+    let header = match len {
+        0..=23 => 1,
+        24..=0xff => 2,
+        0x100..=0xffff => 3,
+        _ => 5,
+    };
+
+    header + len
+}
+
+fn cbor_bytes(payload: &[u8]) -> Result<Vec<u8>, Error> {
+    let mut cbor = Vec::with_capacity(cbor_bytes_len(payload.len()));
+    ciborium::into_writer(&Value::Bytes(payload.to_vec()), &mut cbor)?;
+
+    Ok(cbor)
+}
+
+fn cbor_bytes_decode(cbor: &[u8]) -> Result<Vec<u8>, Error> {
+    match ciborium::from_reader(cbor).map_err(|_| Error::InvalidCbor)? {
+        Value::Bytes(bytes) => Ok(bytes),
+        _ => Err(Error::InvalidCbor),
+    }
+}
+
+fn xor_into(target: &mut [u8], other: &[u8]) {
+    for (a, b) in target.iter_mut().zip(other) {
+        *a ^= b;
+    }
+}
+
+/// CRC-32 (IEEE) checksum
+fn crc32(data: &[u8]) -> u32 {
+    let mut crc = 0xffff_ffffu32;
+    for byte in data {
+        crc ^= *byte as u32;
+        for _ in 0..8 {
+            let mask = (crc & 1).wrapping_neg();
+            crc = (crc >> 1) ^ (0xedb8_8320 & mask);
+        }
+    }
+
+    !crc
+}
+
+/// Minimal bytewords with an appended CRC-32 checksum
+fn bytewords_encode(data: &[u8]) -> String {
+    let words = BYTEWORDS.as_bytes();
+
+    data.iter()
+        .chain(crc32(data).to_be_bytes().iter())
+        .flat_map(|byte| {
+            let word = &words[*byte as usize * 4..*byte as usize * 4 + 4];
+            [word[0] as char, word[3] as char]
+        })
+        .collect()
+}
+
+fn bytewords_decode(s: &str) -> Result<Vec<u8>, Error> {
+    let words = BYTEWORDS.as_bytes();
+    let s = s.as_bytes();
+    if s.len() % 2 != 0 || s.len() < 10 {
+        return Err(Error::InvalidBytewords);
+    }
+
+    let mut bytes = s
+        .chunks(2)
+        .map(|pair| {
+            (0..256)
+                .find(|i| words[i * 4] == pair[0] && words[i * 4 + 3] == pair[1])
+                .map(|i| i as u8)
+                .ok_or(Error::InvalidBytewords)
+        })
+        .collect::<Result<Vec<u8>, Error>>()?;
+
+    let checksum = bytes.split_off(bytes.len() - 4);
+    if crc32(&bytes).to_be_bytes() != checksum.as_slice() {
+        return Err(Error::InvalidChecksum);
+    }
+
+    Ok(bytes)
+}
+
+/// Xoshiro256** seeded from a SHA-256 digest, as used by the fountain code
+struct Xoshiro256 {
+    s: [u64; 4],
+}
+
+impl Xoshiro256 {
+    fn new(seed: &[u8]) -> Self {
+        let digest = sha256::Hash::hash(seed).to_byte_array();
+        let mut s = [0u64; 4];
+        for (i, chunk) in digest.chunks(8).enumerate() {
+            s[i] = u64::from_be_bytes(chunk.try_into().expect("8 byte chunk"));
+        }
+
+        Self { s }
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        let result = self.s[1].wrapping_mul(5).rotate_left(7).wrapping_mul(9);
+        let t = self.s[1] << 17;
+
+        self.s[2] ^= self.s[0];
+        self.s[3] ^= self.s[1];
+        self.s[1] ^= self.s[2];
+        self.s[0] ^= self.s[3];
+        self.s[2] ^= t;
+        self.s[3] = self.s[3].rotate_left(45);
+
+        result
+    }
+
+    fn next_double(&mut self) -> f64 {
+        self.next_u64() as f64 / (u64::MAX as f64 + 1.0)
+    }
+
+    fn next_int(&mut self, low: u64, high: u64) -> u64 {
+        (self.next_double() * (high - low + 1) as f64) as u64 + low
+    }
+}
+
+/// Walker's alias method sampler over the given weights
+struct WeightedSampler {
+    probs: Vec<f64>,
+    aliases: Vec<usize>,
+}
+
+impl WeightedSampler {
+    fn new(weights: &[f64]) -> Self {
+        let n = weights.len();
+        let sum: f64 = weights.iter().sum();
+        let mut p: Vec<f64> = weights.iter().map(|w| w * n as f64 / sum).collect();
+
+        let mut small = Vec::new();
+        let mut large = Vec::new();
+        for (i, prob) in p.iter().enumerate().rev() {
+            if *prob < 1.0 {
+                small.push(i);
+            } else {
+                large.push(i);
+            }
+        }
+
+        let mut probs = vec![0.0; n];
+        let mut aliases = vec![0; n];
+        while let (Some(&a), Some(&g)) = (small.last(), large.last()) {
+            small.pop();
+            large.pop();
+            probs[a] = p[a];
+            aliases[a] = g;
+            p[g] += p[a] - 1.0;
+            if p[g] < 1.0 {
+                small.push(g);
+            } else {
+                large.push(g);
+            }
+        }
+        for i in large.into_iter().chain(small) {
+            probs[i] = 1.0;
+        }
+
+        Self { probs, aliases }
+    }
+
+    fn next(&self, rng: &mut Xoshiro256) -> usize {
+        let r1 = rng.next_double();
+        let r2 = rng.next_double();
+        let i = (self.probs.len() as f64 * r1) as usize;
+
+        if r2 < self.probs[i] {
+            i
+        } else {
+            self.aliases[i]
+        }
+    }
+}
+
+/// Fragment indexes mixed into part `seq_num`
+fn choose_fragments(seq_num: u32, seq_len: u32, checksum: u32) -> Vec<usize> {
+    if seq_num <= seq_len {
+        return vec![seq_num as usize - 1];
+    }
+
+    let mut seed = seq_num.to_be_bytes().to_vec();
+    seed.extend_from_slice(&checksum.to_be_bytes());
+    let mut rng = Xoshiro256::new(&seed);
+
+    let weights: Vec<f64> = (1..=seq_len).map(|i| 1.0 / i as f64).collect();
+    let degree = WeightedSampler::new(&weights).next(&mut rng) + 1;
+
+    let mut remaining: Vec<usize> = (0..seq_len as usize).collect();
+    let mut shuffled = Vec::with_capacity(remaining.len());
+    while !remaining.is_empty() {
+        let index = rng.next_int(0, remaining.len() as u64 - 1) as usize;
+        shuffled.push(remaining.remove(index));
+    }
+    shuffled.truncate(degree);
+
+    shuffled
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bytewords_round_trip() {
+        assert_eq!(crc32(b"Hello, world!"), 0xebe6_c6e6);
+
+        let data: Vec<u8> = (0..=255).collect();
+        let encoded = bytewords_encode(&data);
+        assert_eq!(&encoded[..4], "aead");
+        assert_eq!(bytewords_decode(&encoded).unwrap(), data);
+
+        let mut corrupted = encoded.into_bytes();
+        corrupted[0] = b'b';
+        corrupted[1] = b'k';
+        assert!(bytewords_decode(std::str::from_utf8(&corrupted).unwrap()).is_err());
+    }
+
+    #[test]
+    fn test_fountain_round_trip_with_lost_parts() {
+        let payload: Vec<u8> = (0..1000u32).map(|i| (i * 7 % 251) as u8).collect();
+        let mut encoder = UrEncoder::new(UR_TYPE_BYTES, &payload, 100).unwrap();
+        assert_eq!(
+            encoder.fragment_count(),
+            estimate_fragment_count(payload.len(), 100)
+        );
+
+        let mut decoder = UrDecoder::new();
+        let mut sent = 0;
+        while !decoder.is_complete() {
+            let part = encoder.next_part().unwrap();
+            assert!(part.len() <= estimate_part_len(UR_TYPE_BYTES, payload.len(), 100));
+            sent += 1;
+            assert!(sent < 100, "decoder did not converge");
+
+            // Drop every third frame, as a scanner would
+            if sent % 3 != 0 {
+                decoder.receive(&part.to_uppercase()).unwrap();
+            }
+        }
+
+        assert_eq!(decoder.payload().unwrap(), payload.as_slice());
+        assert_eq!(decoder.progress(), 1.0);
+    }
+
+    /// Message generated like `makeMessage` of the BC-UR reference tests
+    fn make_message(seed: &str, len: usize) -> Vec<u8> {
+        let mut rng = Xoshiro256::new(seed.as_bytes());
+        (0..len).map(|_| rng.next_int(0, 255) as u8).collect()
+    }
+
+    #[test]
+    fn test_reference_rng_and_fragment_choice() {
+        let mut rng = Xoshiro256::new(b"Wolf");
+        let numbers: Vec<u64> = (0..10).map(|_| rng.next_int(1, 10)).collect();
+        assert_eq!(numbers, [6, 5, 8, 4, 10, 5, 7, 10, 4, 9]);
+
+        let message = make_message("Wolf", 1024);
+        let fragment_len = fragment_len(message.len(), 100);
+        let seq_len = message.len().div_ceil(fragment_len) as u32;
+        let checksum = crc32(&message);
+
+        let expected: [&[usize]; 25] = [
+            &[0],
+            &[1],
+            &[2],
+            &[3],
+            &[4],
+            &[5],
+            &[6],
+            &[7],
+            &[8],
+            &[9],
+            &[10],
+            &[9],
+            &[2, 5, 6, 8, 9, 10],
+            &[8],
+            &[1, 5],
+            &[1],
+            &[0, 2, 4, 5, 8, 10],
+            &[5],
+            &[2],
+            &[2],
+            &[0, 1, 3, 4, 5, 7, 9, 10],
+            &[0, 1, 2, 3, 5, 6, 8, 9, 10],
+            &[0, 2, 4, 5, 7, 8, 9, 10],
+            &[3, 5],
+            &[4],
+        ];
+        for (seq_num, expected) in (1..).zip(expected) {
+            let mut indexes = choose_fragments(seq_num, seq_len, checksum);
+            indexes.sort_unstable();
+            assert_eq!(indexes, expected, "part {seq_num}");
+        }
+    }
+
+    #[test]
+    fn test_reference_multipart_vectors() {
+        // Parts of `makeMessageUR(256)` with a maximum fragment length of 30
+        let expected = [
+            "ur:bytes/1-9/lpadascfadaxcywenbpljkhdcahkadaemejtswhhylkepmykhhtsytsnoyoyaxaedsuttydmmhhpktpmsrjtdkgslpgh",
+            "ur:bytes/2-9/lpaoascfadaxcywenbpljkhdcagwdpfnsboxgwlbaawzuefywkdplrsrjynbvygabwjldapfcsgmghhkhstlrdcxaefz",
+            "ur:bytes/3-9/lpaxascfadaxcywenbpljkhdcahelbknlkuejnbadmssfhfrdpsbiegecpasvssovlgeykssjykklronvsjksopdzmol",
+            "ur:bytes/4-9/lpaaascfadaxcywenbpljkhdcasotkhemthydawydtaxneurlkosgwcekonertkbrlwmplssjtammdplolsbrdzcrtas",
+            "ur:bytes/5-9/lpahascfadaxcywenbpljkhdcatbbdfmssrkzmcwnezelennjpfzbgmuktrhtejscktelgfpdlrkfyfwdajldejokbwf",
+            "ur:bytes/6-9/lpamascfadaxcywenbpljkhdcackjlhkhybssklbwefectpfnbbectrljectpavyrolkzczcpkmwidmwoxkilghdsowp",
+            "ur:bytes/7-9/lpatascfadaxcywenbpljkhdcavszmwnjkwtclrtvaynhpahrtoxmwvwatmedibkaegdosftvandiodagdhthtrlnnhy",
+            "ur:bytes/8-9/lpayascfadaxcywenbpljkhdcadmsponkkbbhgsoltjntegepmttmoonftnbuoiyrehfrtsabzsttorodklubbuyaetk",
+            "ur:bytes/9-9/lpasascfadaxcywenbpljkhdcajskecpmdckihdyhphfotjojtfmlnwmadspaxrkytbztpbauotbgtgtaeaevtgavtny",
+        ];
+        let payload = make_message("Wolf", 256);
+
+        let mut encoder = UrEncoder::new(UR_TYPE_BYTES, &payload, 30).unwrap();
+        assert_eq!(encoder.fragment_count(), expected.len());
+        for part in expected {
+            assert_eq!(encoder.next_part().unwrap(), part);
+        }
+
+        let mut decoder = UrDecoder::new();
+        for part in expected.iter().rev() {
+            decoder.receive(part).unwrap();
+        }
+        assert_eq!(decoder.payload().unwrap(), payload.as_slice());
+    }
+
+    #[test]
+    fn test_forged_sequence_length_is_rejected() {
+        let seq_len = u32::MAX;
+        let part = Value::Array(vec![
+            Value::Integer(1.into()),
+            Value::Integer(seq_len.into()),
+            Value::Integer(u64::from(seq_len).into()),
+            Value::Integer(0.into()),
+            Value::Bytes(vec![0]),
+        ]);
+        let mut cbor = Vec::new();
+        ciborium::into_writer(&part, &mut cbor).unwrap();
+        let part = format!("ur:bytes/1-{seq_len}/{}", bytewords_encode(&cbor));
+
+        let mut decoder = UrDecoder::new();
+        assert!(matches!(
+            decoder.receive(&part),
+            Err(Error::InconsistentPart)
+        ));
+        assert!(matches!(
+            UrEncoder::new(UR_TYPE_BYTES, b"token", MIN_FRAGMENT_LEN - 1),
+            Err(Error::InvalidFragmentLength)
+        ));
+    }
+
+    #[test]
+    fn test_token_ur_round_trip() {
+        let token = Token::from_str("cashuBo2F0gqJhaUgA_9SLj17PgGFwgaNhYQFhc3hAYWNjMTI0MzVlN2I4NDg0YzNjZjE4NTAxNDkyMThhZjkwZjcxNmE1MmJmNGE1ZWQzNDdlNDhlY2MxM2Y3NzM4OGFjWCECRFODGd5IXVW-07KaZCvuWHk3WrnnpiDhHki6SCQh88-iYWlIAK0mjE0fWCZhcIKjYWECYXN4QDEzMjNkM2Q0NzA3YTU4YWQyZTIzYWRhNGU5ZjFmNDlmNWE1YjRhYzdiNzA4ZWIwZDYxZjczOGY0ODMwN2U4ZWVhY1ghAjRWqhENhLSsdHrr2Cw7AFrKUL9Ffr1XN6RBT6w659lNo2FhAWFzeEA1NmJjYmNiYjdjYzY0MDZiM2ZhNWQ1N2QyMTc0ZjRlZmY4YjQ0MDJiMTc2OTI2ZDNhNTdkM2MzZGNiYjU5ZDU3YWNYIQJzEpxXGeWZN5qXSmJjY8MzxWyvwObQGr5G1YCCgHicY2FtdWh0dHA6Ly9sb2NhbGhvc3Q6MzMzOGF1Y3NhdA==").unwrap();
+
+        let mut encoder = token.to_ur_encoder(60).unwrap();
+        assert!(!encoder.is_single_part());
+
+        let mut decoder = UrDecoder::new();
+        while !decoder.is_complete() {
+            decoder.receive(&encoder.next_part().unwrap()).unwrap();
+        }
+
+        assert_eq!(decoder.ur_type(), Some(UR_TYPE_BYTES));
+        assert_eq!(Token::from_ur_decoder(&decoder).unwrap(), token);
+    }
+}
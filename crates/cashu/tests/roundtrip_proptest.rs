@@ -0,0 +1,231 @@
+//! Property-based round-trip tests for tokens, payment requests and NUT-10 secrets
+//!
+//! Encoding a value and parsing it back must give the same value, and parsing arbitrary input
+//! must return an error instead of panicking.
+
+use std::collections::BTreeMap;
+use std::str::FromStr;
+
+use cashu::nut00::token::{TokenV3Token, TokenV4Token};
+use cashu::nuts::nut00::{ProofV3, ProofV4};
+use cashu::nuts::nut02::ShortKeysetId;
+use cashu::secret::Secret;
+use cashu::{
+    Amount, CurrencyUnit, Id, Keys, Kind, MintUrl, Nut10Secret, PaymentRequest, PublicKey,
+    SecretData, SecretKey, Token, TokenV3, TokenV4,
+};
+use proptest::prelude::*;
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{b:02x}")).collect()
+}
+
+fn amount() -> impl Strategy<Value = Amount> {
+    (1u64..=1 << 40).prop_map(Amount::from)
+}
+
+fn unit() -> impl Strategy<Value = CurrencyUnit> {
+    prop_oneof![
+        Just(CurrencyUnit::Sat),
+        Just(CurrencyUnit::Msat),
+        Just(CurrencyUnit::Usd),
+        Just(CurrencyUnit::Eur),
+    ]
+}
+
+fn mint_url() -> impl Strategy<Value = MintUrl> {
+    "[a-z][a-z0-9]{0,11}".prop_map(|host| {
+        MintUrl::from_str(&format!("https://{host}.example.com")).expect("valid mint url")
+    })
+}
+
+fn keyset_id() -> impl Strategy<Value = Id> {
+    any::<[u8; 7]>()
+        .prop_map(|bytes| Id::from_str(&format!("00{}", hex(&bytes))).expect("valid keyset id"))
+}
+
+fn public_key() -> impl Strategy<Value = PublicKey> {
+    any::<[u8; 32]>()
+        .prop_filter_map("invalid scalar", |bytes| SecretKey::from_slice(&bytes).ok())
+        .prop_map(|secret_key| secret_key.public_key())
+}
+
+fn secret() -> impl Strategy<Value = Secret> {
+    any::<[u8; 32]>().prop_map(|bytes| Secret::new(hex(&bytes)))
+}
+
+fn memo() -> impl Strategy<Value = Option<String>> {
+    proptest::option::of(".{0,32}")
+}
+
+fn proof_v4() -> impl Strategy<Value = ProofV4> {
+    (amount(), secret(), public_key()).prop_map(|(amount, secret, c)| ProofV4 {
+        amount,
+        secret,
+        c,
+        witness: None,
+        dleq: None,
+        p2pk_e: None,
+    })
+}
+
+fn keys() -> impl Strategy<Value = Keys> {
+    prop::collection::btree_map(0u32..64, public_key(), 1..4).prop_map(|keys| {
+        Keys::new(
+            keys.into_iter()
+                .map(|(exponent, key)| (Amount::from(1u64 << exponent), key))
+                .collect::<BTreeMap<_, _>>(),
+        )
+    })
+}
+
+fn token_v4() -> impl Strategy<Value = TokenV4> {
+    let group = (
+        keyset_id(),
+        prop::collection::vec(proof_v4(), 1..4),
+        proptest::option::of(keys()),
+    )
+        .prop_map(|(keyset_id, proofs, keys)| TokenV4Token {
+            keyset_id: ShortKeysetId::from(keyset_id),
+            proofs,
+            keys,
+        });
+
+    (
+        mint_url(),
+        unit(),
+        memo(),
+        prop::collection::vec(group, 1..3),
+    )
+        .prop_map(|(mint_url, unit, memo, token)| TokenV4 {
+            mint_url,
+            unit,
+            memo,
+            token,
+        })
+}
+
+fn proof_v3() -> impl Strategy<Value = ProofV3> {
+    (amount(), keyset_id(), secret(), public_key()).prop_map(|(amount, keyset_id, secret, c)| {
+        ProofV3 {
+            amount,
+            keyset_id: ShortKeysetId::from(keyset_id),
+            secret,
+            c,
+            witness: None,
+            dleq: None,
+        }
+    })
+}
+
+fn token_v3() -> impl Strategy<Value = TokenV3> {
+    (
+        mint_url(),
+        proptest::option::of(unit()),
+        memo(),
+        prop::collection::vec(proof_v3(), 1..5),
+    )
+        .prop_map(|(mint, unit, memo, proofs)| TokenV3 {
+            token: vec![TokenV3Token { mint, proofs }],
+            memo,
+            unit,
+        })
+}
+
+fn payment_request() -> impl Strategy<Value = PaymentRequest> {
+    (
+        proptest::option::of("[a-zA-Z0-9]{1,16}"),
+        proptest::option::of((amount(), unit())),
+        proptest::option::of(any::<bool>()),
+        prop::collection::vec(mint_url(), 0..3),
+        proptest::option::of(".{0,32}"),
+    )
+        .prop_map(
+            |(payment_id, amount_unit, single_use, mints, description)| PaymentRequest {
+                payment_id,
+                amount: amount_unit.as_ref().map(|(amount, _)| *amount),
+                unit: amount_unit.map(|(_, unit)| unit),
+                single_use,
+                mint_preferred: None,
+                mints,
+                supported_methods: Vec::new(),
+                description,
+                transports: Vec::new(),
+                nut10: None,
+            },
+        )
+}
+
+fn nut10_secret() -> impl Strategy<Value = Nut10Secret> {
+    (
+        prop_oneof![Just(Kind::P2PK), Just(Kind::HTLC)],
+        ".{0,66}",
+        proptest::option::of(prop::collection::vec(
+            prop::collection::vec(".{0,16}", 1..4),
+            0..4,
+        )),
+    )
+        .prop_map(|(kind, data, tags)| Nut10Secret::new(kind, SecretData::new(data, tags)))
+}
+
+proptest! {
+    #[test]
+    fn token_v4_roundtrip(token in token_v4()) {
+        let token = Token::TokenV4(token);
+        let parsed = Token::from_str(&token.to_string()).expect("token v4 parses");
+        prop_assert_eq!(parsed, token);
+    }
+
+    #[test]
+    fn token_v4_raw_bytes_roundtrip(token in token_v4()) {
+        let bytes = token.to_raw_bytes().expect("token v4 encodes");
+        let parsed = TokenV4::try_from(&bytes).expect("token v4 bytes parse");
+        prop_assert_eq!(parsed, token);
+    }
+
+    #[test]
+    fn token_v3_roundtrip(token in token_v3()) {
+        let token = Token::TokenV3(token);
+        let parsed = Token::from_str(&token.to_string()).expect("token v3 parses");
+        prop_assert_eq!(parsed, token);
+    }
+
+    #[test]
+    fn payment_request_roundtrip(request in payment_request()) {
+        let parsed = PaymentRequest::from_str(&request.to_string()).expect("creqA parses");
+        prop_assert_eq!(&parsed, &request);
+
+        let bech32 = request.to_bech32_string().expect("creqB encodes");
+        let parsed = PaymentRequest::from_str(&bech32).expect("creqB parses");
+        prop_assert_eq!(parsed, request);
+    }
+
+    #[test]
+    fn nut10_secret_roundtrip(nut10 in nut10_secret()) {
+        let json = serde_json::to_string(&nut10).expect("nut10 secret serializes");
+        let parsed: Nut10Secret = serde_json::from_str(&json).expect("nut10 secret parses");
+        prop_assert_eq!(&parsed, &nut10);
+
+        let secret = Secret::try_from(nut10.clone()).expect("nut10 secret converts");
+        let parsed = Nut10Secret::try_from(&secret).expect("secret converts back");
+        prop_assert_eq!(parsed, nut10);
+    }
+
+    #[test]
+    fn parsers_do_not_panic(input in ".{0,256}") {
+        let _ = Token::from_str(&input);
+        let _ = Token::from_str(&format!("cashuA{input}"));
+        let _ = Token::from_str(&format!("cashuB{input}"));
+        let _ = PaymentRequest::from_str(&input);
+        let _ = PaymentRequest::from_str(&format!("creqA{input}"));
+        let _ = serde_json::from_str::<Nut10Secret>(&input);
+    }
+
+    #[test]
+    fn raw_token_bytes_do_not_panic(bytes in prop::collection::vec(any::<u8>(), 0..256)) {
+        let mut prefixed = b"crawB".to_vec();
+        prefixed.extend_from_slice(&bytes);
+        let _ = Token::try_from(&bytes);
+        let _ = Token::try_from(&prefixed);
+    }
+}
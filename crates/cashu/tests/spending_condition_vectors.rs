@@ -0,0 +1,131 @@
+//! NUT-10, NUT-11 and NUT-14 conformance test vectors
+//!
+//! NUT-11 vectors are from <https://github.com/cashubtc/nuts/blob/main/tests/11-test.md>. The
+//! NUT-10 vectors cover the well-known secret format and the NUT-14 vectors cover the HTLC
+//! receiver and refund paths with `SIG_INPUTS`; the `SIG_ALL` HTLC cases are part of the
+//! NUT-11 set. Proofs and requests are checked with both the regular and the strict spending
+//! condition verification.
+
+use cashu::{MeltRequest, Nut10Secret, Proof, SpendingConditionVerification, SwapRequest};
+use serde::de::DeserializeOwned;
+use serde::Deserialize;
+
+const NUT10_VECTORS: &str = include_str!("vectors/nut10.json");
+const NUT11_VECTORS: &str = include_str!("vectors/nut11.json");
+const NUT14_VECTORS: &str = include_str!("vectors/nut14.json");
+
+#[derive(Deserialize)]
+struct SecretVectors {
+    secrets: Vec<SecretVector>,
+}
+
+#[derive(Deserialize)]
+struct SecretVector {
+    description: String,
+    valid: bool,
+    secret: String,
+}
+
+#[derive(Deserialize)]
+struct ProofVectors {
+    proofs: Vec<ProofVector>,
+}
+
+#[derive(Deserialize)]
+struct Vectors {
+    proofs: Vec<ProofVector>,
+    swaps: Vec<RequestVector<SwapRequest>>,
+    melts: Vec<RequestVector<MeltRequest<String>>>,
+}
+
+#[derive(Deserialize)]
+struct ProofVector {
+    description: String,
+    valid: bool,
+    proof: Proof,
+}
+
+#[derive(Deserialize)]
+#[serde(bound = "T: DeserializeOwned")]
+struct RequestVector<T> {
+    description: String,
+    valid: bool,
+    request: T,
+}
+
+fn vectors() -> Vectors {
+    serde_json::from_str(NUT11_VECTORS).expect("valid test vectors")
+}
+
+fn check<R: SpendingConditionVerification>(description: &str, valid: bool, request: &R) {
+    assert_eq!(
+        request.verify_spending_conditions().is_ok(),
+        valid,
+        "{description}"
+    );
+    assert_eq!(
+        request.verify_spending_conditions_strict().is_ok(),
+        valid,
+        "{description} (strict)"
+    );
+}
+
+#[test]
+fn test_nut10_secret_vectors() {
+    let vectors: SecretVectors = serde_json::from_str(NUT10_VECTORS).expect("valid test vectors");
+
+    for vector in vectors.secrets {
+        assert_eq!(
+            Nut10Secret::from_str_strict(&vector.secret).is_ok(),
+            vector.valid,
+            "{}",
+            vector.description
+        );
+    }
+}
+
+#[test]
+fn test_nut11_proof_vectors() {
+    for vector in vectors().proofs {
+        assert_eq!(
+            vector.proof.verify_p2pk().is_ok(),
+            vector.valid,
+            "{}",
+            vector.description
+        );
+
+        let request = SwapRequest::new(vec![vector.proof], vec![]);
+        check(&vector.description, vector.valid, &request);
+    }
+}
+
+#[test]
+fn test_nut11_sig_all_swap_vectors() {
+    for vector in vectors().swaps {
+        check(&vector.description, vector.valid, &vector.request);
+    }
+}
+
+#[test]
+fn test_nut11_sig_all_melt_vectors() {
+    for vector in vectors().melts {
+        check(&vector.description, vector.valid, &vector.request);
+    }
+}
+
+#[test]
+fn test_nut14_proof_vectors() {
+    let vectors: ProofVectors = serde_json::from_str(NUT14_VECTORS).expect("valid test vectors");
+
+    for vector in vectors.proofs {
+        assert_eq!(
+            vector.proof.verify_htlc().is_ok(),
+            vector.valid,
+            "{}",
+            vector.description
+        );
+
+        let request = SwapRequest::new(vec![vector.proof], vec![]);
+        check(&vector.description, vector.valid, &request);
+    }
+}
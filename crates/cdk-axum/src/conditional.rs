@@ -0,0 +1,110 @@
+//! HTTP caching headers for mint resources that rarely change
+//!
+//! Keys, keysets and mint info are served with an `ETag` and a `Cache-Control` header. Clients
+//! that send the `ETag` back in `If-None-Match` get an empty `304 Not Modified` response while the
+//! resource is unchanged.
+
+use axum::http::{header, HeaderMap, HeaderValue, StatusCode};
+use axum::response::{IntoResponse, Response};
+use cdk::util::hex;
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+
+/// `Cache-Control` for resources that change when keysets are rotated or the mint info is updated
+pub(crate) const CACHE_CONTROL_REVALIDATE: &str = "public, max-age=60";
+
+/// `Cache-Control` for the keys of a keyset, which never change for a given keyset id
+pub(crate) const CACHE_CONTROL_IMMUTABLE: &str = "public, max-age=31536000, immutable";
+
+/// Strong `ETag` of the JSON serialization of `value`
+pub(crate) fn etag<T: Serialize>(value: &T) -> Option<String> {
+    serde_json::to_vec(value)
+        .inspect_err(|err| tracing::error!("Could not serialize response for ETag: {}", err))
+        .ok()
+        .map(|bytes| format!("\"{}\"", hex::encode(Sha256::digest(bytes))))
+}
+
+/// Whether an `If-None-Match` request header matches `etag`
+fn matches_if_none_match(request_headers: &HeaderMap, etag: &str) -> bool {
+    request_headers
+        .get_all(header::IF_NONE_MATCH)
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|value| value.split(','))
+        .map(|candidate| candidate.trim())
+        .any(|candidate| candidate == "*" || candidate.trim_start_matches("W/") == etag)
+}
+
+/// Respond with `body` as JSON and caching headers, or `304 Not Modified` if the client's copy
+/// matches `etag`
+///
+/// The `etag` is passed separately so fields that change on every response, such as the time in
+/// the mint info, can be left out of it.
+pub(crate) fn conditional_json<T: Serialize>(
+    request_headers: &HeaderMap,
+    etag: Option<String>,
+    cache_control: &'static str,
+    body: T,
+) -> Response {
+    let Some(etag) = etag.and_then(|etag| HeaderValue::from_str(&etag).ok()) else {
+        return axum::Json(body).into_response();
+    };
+
+    let mut response = match etag.to_str() {
+        Ok(etag) if matches_if_none_match(request_headers, etag) => {
+            StatusCode::NOT_MODIFIED.into_response()
+        }
+        _ => axum::Json(body).into_response(),
+    };
+
+    let headers = response.headers_mut();
+    headers.insert(header::ETAG, etag);
+    headers.insert(
+        header::CACHE_CONTROL,
+        HeaderValue::from_static(cache_control),
+    );
+
+    response
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_conditional_json_not_modified() {
+        let body = serde_json::json!({ "keysets": [] });
+        let etag = etag(&body).expect("etag");
+
+        let response = conditional_json(
+            &HeaderMap::new(),
+            Some(etag.clone()),
+            CACHE_CONTROL_REVALIDATE,
+            body.clone(),
+        );
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(response.headers().get(header::ETAG).unwrap(), etag.as_str());
+        assert_eq!(
+            response.headers().get(header::CACHE_CONTROL).unwrap(),
+            CACHE_CONTROL_REVALIDATE
+        );
+
+        let mut request_headers = HeaderMap::new();
+        request_headers.insert(
+            header::IF_NONE_MATCH,
+            HeaderValue::from_str(&format!("\"other\", W/{etag}")).unwrap(),
+        );
+        let response = conditional_json(
+            &request_headers,
+            Some(etag.clone()),
+            CACHE_CONTROL_REVALIDATE,
+            body.clone(),
+        );
+        assert_eq!(response.status(), StatusCode::NOT_MODIFIED);
+
+        request_headers.insert(header::IF_NONE_MATCH, HeaderValue::from_static("\"stale\""));
+        let response =
+            conditional_json(&request_headers, Some(etag), CACHE_CONTROL_REVALIDATE, body);
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+}
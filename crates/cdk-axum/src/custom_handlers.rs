@@ -11,16 +11,17 @@ use axum::extract::{FromRequestParts, Json, Path, State};
 use axum::http::request::Parts;
 use axum::http::StatusCode;
 use axum::response::{IntoResponse, Response};
-use cdk::mint::QuoteId;
+use cdk::mint::{AuthIdentity, QuoteId};
+use cdk::nuts::nut00::KnownMethod;
 use cdk::nuts::nut21::{Method, ProtectedEndpoint, RoutePath};
 use cdk::nuts::{
     BatchCheckMintQuoteRequest, BatchMintRequest, MeltOnchainRequest, MeltQuoteBolt11Request,
-    MeltQuoteBolt12Request, MeltQuoteCustomRequest, MeltQuoteOnchainRequest,
+    MeltQuoteBolt12Request, MeltQuoteCustomRequest, MeltQuoteOnchainRequest, MeltQuoteState,
     MintQuoteBolt11Request, MintQuoteBolt11Response, MintQuoteBolt12Request,
     MintQuoteBolt12Response, MintQuoteCustomRequest, MintQuoteOnchainRequest,
     MintQuoteOnchainResponse, MintRequest, MintResponse, PaymentMethod,
 };
-use cdk::{MeltQuoteCreateResponse, MeltQuoteResponse};
+use cdk::{MeltQuoteCreateResponse, MeltQuoteRequest, MeltQuoteResponse};
 use serde_json::Value;
 use tracing::instrument;
 
@@ -131,8 +132,8 @@ async fn validate_mint_request_route(
     state: &MintState,
     method: &str,
     quote_ids: &[QuoteId],
-) -> Result<(), cdk::Error> {
-    state
+) -> Result<Option<AuthIdentity>, cdk::Error> {
+    let identity = state
         .mint
         .verify_auth(
             auth.into(),
@@ -140,14 +141,27 @@ async fn validate_mint_request_route(
         )
         .await?;
 
-    validate_mint_quote_methods(state, method, quote_ids).await
+    validate_mint_quote_methods(state, method, quote_ids).await?;
+
+    Ok(identity)
 }
 
 async fn process_mint_input(
     state: &MintState,
+    identity: Option<&AuthIdentity>,
     input: cdk::mint::MintInput,
 ) -> Result<MintResponse, cdk::Error> {
-    state.mint.process_mint_request(input).await
+    let charge = state.mint.charge_mint_quota(identity, &input).await?;
+
+    match state.mint.process_mint_request(input).await {
+        Ok(response) => Ok(response),
+        Err(err) => {
+            if let Some(charge) = charge {
+                state.mint.refund_auth_quota(&charge).await?;
+            }
+            Err(err)
+        }
+    }
 }
 
 async fn validate_melt_request_route(
@@ -155,8 +169,8 @@ async fn validate_melt_request_route(
     state: &MintState,
     method: &str,
     quote_id: &QuoteId,
-) -> Result<(), cdk::Error> {
-    state
+) -> Result<Option<AuthIdentity>, cdk::Error> {
+    let identity = state
         .mint
         .verify_auth(
             auth.into(),
@@ -164,14 +178,130 @@ async fn validate_melt_request_route(
         )
         .await?;
 
-    validate_melt_quote_method(state, method, quote_id).await
+    validate_melt_quote_method(state, method, quote_id).await?;
+
+    Ok(identity)
+}
+
+/// Mint quote request of any payment method, parsed according to the method of the route
+#[derive(Debug)]
+enum GenericMintQuoteRequest {
+    Bolt11(MintQuoteBolt11Request),
+    Bolt12(MintQuoteBolt12Request),
+    Onchain(MintQuoteOnchainRequest),
+    Custom(PaymentMethod, MintQuoteCustomRequest),
+}
+
+impl GenericMintQuoteRequest {
+    /// Parse the JSON body of a `/mint/quote/{method}` request
+    fn from_json(method: &str, payload: Value) -> Result<Self, cdk::Error> {
+        match PaymentMethod::from(method) {
+            PaymentMethod::Known(KnownMethod::Bolt11) => serde_json::from_value(payload)
+                .map(Self::Bolt11)
+                .map_err(|e| {
+                    tracing::error!("Failed to parse bolt11 request: {}", e);
+                    cdk::Error::InvalidPaymentMethod
+                }),
+            PaymentMethod::Known(KnownMethod::Bolt12) => {
+                if payload.get("pubkey").is_none_or(|v| v.is_null()) {
+                    return Err(cdk::Error::PubkeyRequired);
+                }
+                serde_json::from_value(payload)
+                    .map(Self::Bolt12)
+                    .map_err(|e| {
+                        tracing::error!("Failed to parse bolt12 request: {}", e);
+                        cdk::Error::InvalidPaymentMethod
+                    })
+            }
+            PaymentMethod::Known(KnownMethod::Onchain) => serde_json::from_value(payload)
+                .map(Self::Onchain)
+                .map_err(|e| {
+                    tracing::error!("Failed to parse onchain request: {}", e);
+                    if e.to_string().contains("missing field `pubkey`") {
+                        cdk::Error::PubkeyRequired
+                    } else {
+                        cdk::Error::InvalidPaymentMethod
+                    }
+                }),
+            method => serde_json::from_value(payload)
+                .map(|request| Self::Custom(method, request))
+                .map_err(|e| {
+                    tracing::error!("Failed to parse custom request: {}", e);
+                    cdk::Error::InvalidPaymentMethod
+                }),
+        }
+    }
+}
+
+/// Melt quote request of any payment method, parsed according to the method of the route
+#[derive(Debug)]
+enum GenericMeltQuoteRequest {
+    Bolt11(MeltQuoteBolt11Request),
+    Bolt12(MeltQuoteBolt12Request),
+    Onchain(MeltQuoteOnchainRequest),
+    Custom(MeltQuoteCustomRequest),
+}
+
+impl GenericMeltQuoteRequest {
+    /// Parse the JSON body of a `/melt/quote/{method}` request
+    ///
+    /// The method in the body of a custom request must match the method of the route.
+    fn from_json(method: &str, payload: Value) -> Result<Self, cdk::Error> {
+        match PaymentMethod::from(method) {
+            PaymentMethod::Known(KnownMethod::Bolt11) => serde_json::from_value(payload)
+                .map(Self::Bolt11)
+                .map_err(|e| {
+                    tracing::error!("Failed to parse bolt11 melt request: {}", e);
+                    cdk::Error::InvalidPaymentMethod
+                }),
+            PaymentMethod::Known(KnownMethod::Bolt12) => serde_json::from_value(payload)
+                .map(Self::Bolt12)
+                .map_err(|e| {
+                    tracing::error!("Failed to parse bolt12 melt request: {}", e);
+                    cdk::Error::InvalidPaymentMethod
+                }),
+            PaymentMethod::Known(KnownMethod::Onchain) => serde_json::from_value(payload)
+                .map(Self::Onchain)
+                .map_err(|e| {
+                    tracing::error!("Failed to parse onchain melt request: {}", e);
+                    cdk::Error::InvalidPaymentMethod
+                }),
+            route_method => {
+                let custom_request: MeltQuoteCustomRequest = serde_json::from_value(payload)
+                    .map_err(|e| {
+                        tracing::error!("Failed to parse custom melt request: {}", e);
+                        cdk::Error::InvalidPaymentMethod
+                    })?;
+
+                if PaymentMethod::from(custom_request.method.as_str()) != route_method {
+                    return Err(cdk::Error::InvalidPaymentMethod);
+                }
+
+                Ok(Self::Custom(custom_request))
+            }
+        }
+    }
+}
+
+/// Reject quote requests for payment methods without a registered payment processor
+fn ensure_payment_method_supported(state: &MintState, method: &str) -> Result<(), cdk::Error> {
+    if !state
+        .mint
+        .supports_payment_method(&PaymentMethod::from(method))
+    {
+        tracing::debug!("No payment processor registered for method {}", method);
+        return Err(cdk::Error::UnsupportedPaymentMethod);
+    }
+
+    Ok(())
 }
 
-/// Generic handler for custom payment method mint quotes
+/// Generic handler for mint quotes of any payment method
 ///
-/// This handler works for ANY custom payment method (e.g., paypal, venmo, cashapp, bolt11, bolt12).
-/// For bolt11/bolt12, it handles the specific request/response types.
-/// For other methods, it passes the request data directly to the payment processor.
+/// This handler works for ANY payment method with a registered payment processor (e.g.,
+/// paypal, venmo, cashapp, bolt11, bolt12). For bolt11/bolt12/onchain, it handles the specific
+/// request/response types. For other methods, it passes the request data directly to the
+/// payment processor. Methods without a payment processor are rejected with a 400.
 #[instrument(skip_all, fields(method = ?method))]
 pub async fn post_mint_custom_quote(
     auth: AuthHeader,
@@ -188,14 +318,10 @@ pub async fn post_mint_custom_quote(
         .await
         .map_err(into_response)?;
 
-    match method.as_str() {
-        "bolt11" => {
-            let bolt11_request: MintQuoteBolt11Request =
-                serde_json::from_value(payload).map_err(|e| {
-                    tracing::error!("Failed to parse bolt11 request: {}", e);
-                    into_response(cdk::Error::InvalidPaymentMethod)
-                })?;
+    ensure_payment_method_supported(&state, &method).map_err(into_response)?;
 
+    match GenericMintQuoteRequest::from_json(&method, payload).map_err(into_response)? {
+        GenericMintQuoteRequest::Bolt11(bolt11_request) => {
             let quote = state
                 .mint
                 .get_mint_quote(bolt11_request.into())
@@ -206,16 +332,7 @@ pub async fn post_mint_custom_quote(
                 quote.try_into().map_err(into_response)?;
             Ok(Json(response).into_response())
         }
-        "bolt12" => {
-            if payload.get("pubkey").is_none_or(|v| v.is_null()) {
-                return Err(into_response(cdk::Error::PubkeyRequired));
-            }
-            let bolt12_request: MintQuoteBolt12Request =
-                serde_json::from_value(payload).map_err(|e| {
-                    tracing::error!("Failed to parse bolt12 request: {}", e);
-                    into_response(cdk::Error::InvalidPaymentMethod)
-                })?;
-
+        GenericMintQuoteRequest::Bolt12(bolt12_request) => {
             let quote = state
                 .mint
                 .get_mint_quote(bolt12_request.into())
@@ -226,17 +343,7 @@ pub async fn post_mint_custom_quote(
                 quote.try_into().map_err(into_response)?;
             Ok(Json(response).into_response())
         }
-        "onchain" => {
-            let onchain_request: MintQuoteOnchainRequest = serde_json::from_value(payload)
-                .map_err(|e| {
-                    tracing::error!("Failed to parse onchain request: {}", e);
-                    if e.to_string().contains("missing field `pubkey`") {
-                        into_response(cdk::Error::PubkeyRequired)
-                    } else {
-                        into_response(cdk::Error::InvalidPaymentMethod)
-                    }
-                })?;
-
+        GenericMintQuoteRequest::Onchain(onchain_request) => {
             let quote = state
                 .mint
                 .get_mint_quote(onchain_request.into())
@@ -247,17 +354,8 @@ pub async fn post_mint_custom_quote(
                 MintQuoteOnchainResponse::try_from(quote).map_err(into_response)?;
             Ok(Json(response).into_response())
         }
-        _ => {
-            let custom_request: MintQuoteCustomRequest =
-                serde_json::from_value(payload).map_err(|e| {
-                    tracing::error!("Failed to parse custom request: {}", e);
-                    into_response(cdk::Error::InvalidPaymentMethod)
-                })?;
-
-            let quote_request = cdk::mint::MintQuoteRequest::Custom {
-                method: cdk::nuts::PaymentMethod::from(method.clone()),
-                request: custom_request,
-            };
+        GenericMintQuoteRequest::Custom(method, request) => {
+            let quote_request = cdk::mint::MintQuoteRequest::Custom { method, request };
 
             let response = state
                 .mint
@@ -402,7 +500,9 @@ pub async fn post_batch_check_mint_quote(
     }
 }
 
-/// Request a melt quote for custom payment method
+/// Request a melt quote for any payment method
+///
+/// Methods without a registered payment processor are rejected with a 400.
 #[instrument(skip_all, fields(method = ?method))]
 pub async fn post_melt_custom_quote(
     auth: AuthHeader,
@@ -419,72 +519,32 @@ pub async fn post_melt_custom_quote(
         .await
         .map_err(into_response)?;
 
-    let response = match method.as_str() {
-        "bolt11" => {
-            let bolt11_request: MeltQuoteBolt11Request =
-                serde_json::from_value(payload).map_err(|e| {
-                    tracing::error!("Failed to parse bolt11 melt request: {}", e);
-                    into_response(cdk::Error::InvalidPaymentMethod)
-                })?;
-
-            state
-                .mint
-                .get_melt_quote(bolt11_request.into())
-                .await
-                .map_err(into_response)?
-        }
-        "bolt12" => {
-            let bolt12_request: MeltQuoteBolt12Request =
-                serde_json::from_value(payload).map_err(|e| {
-                    tracing::error!("Failed to parse bolt12 melt request: {}", e);
-                    into_response(cdk::Error::InvalidPaymentMethod)
-                })?;
-
-            state
-                .mint
-                .get_melt_quote(bolt12_request.into())
-                .await
-                .map_err(into_response)?
-        }
-        "onchain" => {
-            let onchain_request: MeltQuoteOnchainRequest = serde_json::from_value(payload)
-                .map_err(|e| {
-                    tracing::error!("Failed to parse onchain melt request: {}", e);
-                    into_response(cdk::Error::InvalidPaymentMethod)
-                })?;
-
-            let response = state
-                .mint
-                .get_melt_quote(onchain_request.into())
-                .await
-                .map_err(into_response)?;
-
-            return match response {
-                MeltQuoteCreateResponse::Onchain(r) => Ok(Json(r).into_response()),
-                _ => Err(into_response(cdk::Error::InvalidPaymentMethod)),
-            };
-        }
-        _ => {
-            let custom_request: MeltQuoteCustomRequest =
-                serde_json::from_value(payload).map_err(|e| {
-                    tracing::error!("Failed to parse custom melt request: {}", e);
-                    into_response(cdk::Error::InvalidPaymentMethod)
-                })?;
+    ensure_payment_method_supported(&state, &method).map_err(into_response)?;
 
-            let request_method = PaymentMethod::from(custom_request.method.as_str());
-            let route_method = PaymentMethod::from(method.as_str());
+    let quote_request: MeltQuoteRequest =
+        match GenericMeltQuoteRequest::from_json(&method, payload).map_err(into_response)? {
+            GenericMeltQuoteRequest::Bolt11(request) => request.into(),
+            GenericMeltQuoteRequest::Bolt12(request) => request.into(),
+            GenericMeltQuoteRequest::Onchain(request) => {
+                let response = state
+                    .mint
+                    .get_melt_quote(request.into())
+                    .await
+                    .map_err(into_response)?;
 
-            if request_method != route_method {
-                return Err(into_response(cdk::Error::InvalidPaymentMethod));
+                return match response {
+                    MeltQuoteCreateResponse::Onchain(r) => Ok(Json(r).into_response()),
+                    _ => Err(into_response(cdk::Error::InvalidPaymentMethod)),
+                };
             }
+            GenericMeltQuoteRequest::Custom(request) => request.into(),
+        };
 
-            state
-                .mint
-                .get_melt_quote(custom_request.into())
-                .await
-                .map_err(into_response)?
-        }
-    };
+    let response = state
+        .mint
+        .get_melt_quote(quote_request)
+        .await
+        .map_err(into_response)?;
 
     Ok(melt_quote_create_response_to_json(response))
 }
@@ -521,6 +581,7 @@ pub async fn get_check_melt_custom_quote(
 async fn process_melt_request(
     prefer: PreferHeader,
     state: &MintState,
+    identity: Option<&AuthIdentity>,
     method: &str,
     payload: &cdk::nuts::MeltRequest<QuoteId>,
 ) -> Result<MeltQuoteResponse<QuoteId>, cdk::Error> {
@@ -528,17 +589,29 @@ async fn process_melt_request(
     // For onchain we always want to do the async flow
     let respond_async = prefer.respond_async || payload.is_prefer_async() || method == "onchain";
 
-    let pending = state.mint.melt(payload).await?;
+    // The melt is charged up front so concurrent melts cannot exceed the quota together, and
+    // refunded if the payment does not go through. Asynchronous melts keep the charge, their
+    // outcome is not known yet.
+    let charge = state
+        .mint
+        .charge_melt_quota(identity, payload.quote())
+        .await?;
 
-    let res = if respond_async {
-        // Asynchronous processing - return immediately after setup
-        pending.into_pending_response()
-    } else {
-        // Synchronous processing - wait for completion
-        pending.await?
+    let result = match state.mint.melt(payload).await {
+        Ok(pending) if respond_async => Ok(pending.into_pending_response()),
+        Ok(pending) => pending.await,
+        Err(err) => Err(err),
     };
 
-    Ok(res)
+    let failed = match &result {
+        Ok(res) => res.state() == MeltQuoteState::Unpaid,
+        Err(_) => true,
+    };
+    if let (true, Some(charge)) = (failed, charge) {
+        state.mint.refund_auth_quota(&charge).await?;
+    }
+
+    result
 }
 
 // ============================================================================
@@ -559,7 +632,7 @@ pub async fn cache_post_mint_custom(
     let method = method.0;
     let payload = payload.0;
 
-    validate_mint_request_route(
+    let identity = validate_mint_request_route(
         auth,
         &mint_state,
         &method,
@@ -574,9 +647,13 @@ pub async fn cache_post_mint_custom(
     {
         Some(key) => key,
         None => {
-            let result = process_mint_input(&mint_state, cdk::mint::MintInput::Single(payload))
-                .await
-                .map_err(into_response)?;
+            let result = process_mint_input(
+                &mint_state,
+                identity.as_ref(),
+                cdk::mint::MintInput::Single(payload),
+            )
+            .await
+            .map_err(into_response)?;
 
             return Ok(Json(result));
         }
@@ -587,9 +664,13 @@ pub async fn cache_post_mint_custom(
     }
 
     let result = Json(
-        process_mint_input(&mint_state, cdk::mint::MintInput::Single(payload))
-            .await
-            .map_err(into_response)?,
+        process_mint_input(
+            &mint_state,
+            identity.as_ref(),
+            cdk::mint::MintInput::Single(payload),
+        )
+        .await
+        .map_err(into_response)?,
     );
 
     // Cache the response
@@ -614,7 +695,7 @@ pub async fn cache_post_melt_custom(
     tracing::debug!(method = %method, "melt request received");
     let parsed_payload = parse_melt_payload(&method, payload.deref().clone())?;
 
-    validate_melt_request_route(auth, &mint_state, &method, parsed_payload.quote())
+    let identity = validate_melt_request_route(auth, &mint_state, &method, parsed_payload.quote())
         .await
         .map_err(into_response)?;
 
@@ -625,9 +706,15 @@ pub async fn cache_post_melt_custom(
         {
             Some(key) => key,
             None => {
-                let result = process_melt_request(prefer, &mint_state, &method, &parsed_payload)
-                    .await
-                    .map_err(into_response)?;
+                let result = process_melt_request(
+                    prefer,
+                    &mint_state,
+                    identity.as_ref(),
+                    &method,
+                    &parsed_payload,
+                )
+                .await
+                .map_err(into_response)?;
 
                 return Ok(melt_quote_response_to_json(result));
             }
@@ -641,9 +728,15 @@ pub async fn cache_post_melt_custom(
         return Ok(melt_quote_response_to_json(cached_response));
     }
 
-    let result = process_melt_request(prefer, &mint_state, &method, &parsed_payload)
-        .await
-        .map_err(into_response)?;
+    let result = process_melt_request(
+        prefer,
+        &mint_state,
+        identity.as_ref(),
+        &method,
+        &parsed_payload,
+    )
+    .await
+    .map_err(into_response)?;
 
     mint_state.cache.set(cache_key, &result).await;
 
@@ -694,7 +787,7 @@ pub async fn cache_post_batch_mint(
     let method = method.0;
     let payload = payload.0;
 
-    validate_mint_request_route(auth, &mint_state, &method, &payload.quotes)
+    let identity = validate_mint_request_route(auth, &mint_state, &method, &payload.quotes)
         .await
         .map_err(into_response)?;
 
@@ -704,9 +797,13 @@ pub async fn cache_post_batch_mint(
     {
         Some(key) => key,
         None => {
-            let result = process_mint_input(&mint_state, cdk::mint::MintInput::Batch(payload))
-                .await
-                .map_err(into_response)?;
+            let result = process_mint_input(
+                &mint_state,
+                identity.as_ref(),
+                cdk::mint::MintInput::Batch(payload),
+            )
+            .await
+            .map_err(into_response)?;
 
             return Ok(Json(result));
         }
@@ -717,9 +814,13 @@ pub async fn cache_post_batch_mint(
     }
 
     let result = Json(
-        process_mint_input(&mint_state, cdk::mint::MintInput::Batch(payload))
-            .await
-            .map_err(into_response)?,
+        process_mint_input(
+            &mint_state,
+            identity.as_ref(),
+            cdk::mint::MintInput::Batch(payload),
+        )
+        .await
+        .map_err(into_response)?,
     );
 
     mint_state.cache.set(cache_key, result.deref()).await;
@@ -1176,4 +1277,115 @@ mod tests {
             "mixed-case body method should create a quote under the normalized payment method"
         );
     }
+
+    #[tokio::test]
+    async fn post_quote_rejects_unregistered_method() {
+        let state = create_test_state_with_custom_methods(&["paypal"]).await;
+
+        let payload = serde_json::to_value(MintQuoteCustomRequest {
+            amount: Some(Amount::from(10u64)),
+            unit: CurrencyUnit::Sat,
+            description: None,
+            pubkey: None,
+            extra: Value::Null,
+        })
+        .unwrap();
+
+        let response = post_mint_custom_quote(
+            AuthHeader::None,
+            State(state.clone()),
+            Path("venmo".to_string()),
+            Json(payload),
+        )
+        .await
+        .unwrap_err();
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+        let payload = serde_json::to_value(MeltQuoteCustomRequest {
+            method: "venmo".to_string(),
+            request: "test-payment-request".to_string(),
+            unit: CurrencyUnit::Sat,
+            amount: None,
+            extra: Value::Null,
+        })
+        .unwrap();
+
+        let response = post_melt_custom_quote(
+            AuthHeader::None,
+            State(state.clone()),
+            Path("venmo".to_string()),
+            Json(payload),
+        )
+        .await
+        .unwrap_err();
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+        assert!(state.mint.mint_quotes().await.unwrap().is_empty());
+        assert!(state.mint.melt_quotes().await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn quote_routes_reject_unknown_method_with_bad_request() {
+        use axum::body::Body;
+        use tower::ServiceExt;
+
+        let state = create_test_state_with_custom_methods(&["paypal"]).await;
+        let router = crate::create_mint_router(state.mint.clone(), vec!["paypal".to_string()])
+            .await
+            .unwrap();
+
+        for (uri, body) in [
+            (
+                "/v1/mint/quote/unknown",
+                serde_json::json!({ "amount": 10, "unit": "sat" }),
+            ),
+            (
+                "/v1/melt/quote/unknown",
+                serde_json::json!({
+                    "method": "unknown",
+                    "request": "test-payment-request",
+                    "unit": "sat"
+                }),
+            ),
+        ] {
+            let request = Request::post(uri)
+                .header("content-type", "application/json")
+                .body(Body::from(body.to_string()))
+                .unwrap();
+
+            let response = router.clone().oneshot(request).await.unwrap();
+            assert_eq!(response.status(), StatusCode::BAD_REQUEST, "{uri}");
+        }
+    }
+
+    #[test]
+    fn generic_quote_requests_parse_by_route_method() {
+        let bolt11 = serde_json::json!({ "amount": 10, "unit": "sat" });
+        assert!(matches!(
+            GenericMintQuoteRequest::from_json("bolt11", bolt11.clone()),
+            Ok(GenericMintQuoteRequest::Bolt11(_))
+        ));
+        assert!(matches!(
+            GenericMintQuoteRequest::from_json("bolt12", bolt11.clone()),
+            Err(cdk::Error::PubkeyRequired)
+        ));
+        assert!(matches!(
+            GenericMintQuoteRequest::from_json("paypal", bolt11),
+            Ok(GenericMintQuoteRequest::Custom(PaymentMethod::Custom(_), _))
+        ));
+
+        let melt = serde_json::json!({
+            "method": "paypal",
+            "request": "test-payment-request",
+            "unit": "sat"
+        });
+        assert!(matches!(
+            GenericMeltQuoteRequest::from_json("paypal", melt.clone()),
+            Ok(GenericMeltQuoteRequest::Custom(_))
+        ));
+        assert!(matches!(
+            GenericMeltQuoteRequest::from_json("venmo", melt),
+            Err(cdk::Error::InvalidPaymentMethod)
+        ));
+    }
 }
@@ -27,6 +27,7 @@ use crate::MintState;
 /// - `/melt/{method}` - POST: Melt tokens
 ///
 /// The {method} parameter captures the payment method name dynamically.
+/// Quote requests for methods without a registered payment processor are rejected with a 400.
 pub fn create_custom_routers(state: MintState, custom_methods: Vec<String>) -> Router<MintState> {
     tracing::info!(
         "Creating routes for {} custom payment methods: {:?}",
@@ -0,0 +1,275 @@
+//! CORS and security headers
+//!
+//! Every mint response carries CORS headers so browser wallets hosted on other domains can use
+//! the mint. By default any origin is allowed; the allowed origins and headers, HSTS and
+//! `X-Frame-Options` can be configured with [`Config`].
+
+use std::str::FromStr;
+use std::sync::Arc;
+
+use anyhow::{anyhow, Result};
+use axum::body::Body;
+use axum::extract::State;
+use axum::http::header::{
+    ACCESS_CONTROL_ALLOW_HEADERS, ACCESS_CONTROL_ALLOW_METHODS, ACCESS_CONTROL_ALLOW_ORIGIN,
+    ACCESS_CONTROL_EXPOSE_HEADERS, ACCESS_CONTROL_MAX_AGE, ORIGIN, STRICT_TRANSPORT_SECURITY, VARY,
+    X_FRAME_OPTIONS,
+};
+use axum::http::{HeaderMap, HeaderValue, Method, Request};
+use axum::middleware::Next;
+use axum::response::Response;
+use serde::{Deserialize, Serialize};
+
+/// Value allowing any origin or header
+const ANY: &str = "*";
+
+/// `X-Frame-Options` header value
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FrameOptions {
+    /// The mint can not be displayed in a frame
+    Deny,
+    /// The mint can only be displayed in a frame on the same origin
+    SameOrigin,
+}
+
+impl FrameOptions {
+    fn header_value(&self) -> HeaderValue {
+        match self {
+            Self::Deny => HeaderValue::from_static("DENY"),
+            Self::SameOrigin => HeaderValue::from_static("SAMEORIGIN"),
+        }
+    }
+}
+
+impl FromStr for FrameOptions {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "deny" => Ok(Self::Deny),
+            "same_origin" | "sameorigin" => Ok(Self::SameOrigin),
+            _ => Err(format!("Unknown frame options: {s}")),
+        }
+    }
+}
+
+/// CORS and security headers configuration
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Config {
+    /// Origins allowed to call the mint from a browser, `*` allows any origin
+    pub cors_allowed_origins: Vec<String>,
+    /// Request headers allowed in cross-origin requests, `*` allows any header
+    pub cors_allowed_headers: Vec<String>,
+    /// Seconds browsers may cache the response to a preflight request
+    pub cors_max_age: Option<u64>,
+    /// `max-age` of the `Strict-Transport-Security` header, the header is only sent if set
+    pub hsts_max_age: Option<u64>,
+    /// Apply HSTS to subdomains of the mint
+    pub hsts_include_subdomains: bool,
+    /// `X-Frame-Options` header, not sent if unset
+    pub frame_options: Option<FrameOptions>,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            cors_allowed_origins: vec![ANY.to_string()],
+            cors_allowed_headers: vec![ANY.to_string()],
+            cors_max_age: None,
+            hsts_max_age: None,
+            hsts_include_subdomains: false,
+            frame_options: None,
+        }
+    }
+}
+
+/// Allowed origins of cross-origin requests
+#[derive(Debug, Clone)]
+enum AllowedOrigins {
+    Any,
+    List(Vec<HeaderValue>),
+}
+
+/// Header values built from a [`Config`] once, when the router is created
+#[derive(Debug, Clone)]
+pub(crate) struct Headers {
+    allowed_origins: AllowedOrigins,
+    allowed_headers: HeaderValue,
+    max_age: Option<HeaderValue>,
+    hsts: Option<HeaderValue>,
+    frame_options: Option<HeaderValue>,
+}
+
+impl TryFrom<&Config> for Headers {
+    type Error = anyhow::Error;
+
+    fn try_from(config: &Config) -> Result<Self> {
+        let allowed_origins = if config.cors_allowed_origins.iter().any(|o| o == ANY) {
+            AllowedOrigins::Any
+        } else {
+            AllowedOrigins::List(
+                config
+                    .cors_allowed_origins
+                    .iter()
+                    .map(|origin| {
+                        HeaderValue::from_str(origin.trim_end_matches('/'))
+                            .map_err(|_| anyhow!("Invalid CORS origin: {origin}"))
+                    })
+                    .collect::<Result<_>>()?,
+            )
+        };
+
+        let allowed_headers = if config.cors_allowed_headers.is_empty() {
+            ANY.to_string()
+        } else {
+            config.cors_allowed_headers.join(", ")
+        };
+        let allowed_headers = HeaderValue::from_str(&allowed_headers)
+            .map_err(|_| anyhow!("Invalid CORS allowed headers: {allowed_headers}"))?;
+
+        let hsts = config.hsts_max_age.map(|max_age| {
+            let value = if config.hsts_include_subdomains {
+                format!("max-age={max_age}; includeSubDomains")
+            } else {
+                format!("max-age={max_age}")
+            };
+            HeaderValue::from_str(&value).expect("Valid header value")
+        });
+
+        Ok(Self {
+            allowed_origins,
+            allowed_headers,
+            max_age: config.cors_max_age.map(HeaderValue::from),
+            hsts,
+            frame_options: config.frame_options.map(|f| f.header_value()),
+        })
+    }
+}
+
+impl Headers {
+    /// `Access-Control-Allow-Origin` value for a request from `origin`
+    fn allow_origin(&self, origin: Option<&HeaderValue>) -> Option<HeaderValue> {
+        match &self.allowed_origins {
+            AllowedOrigins::Any => Some(HeaderValue::from_static(ANY)),
+            AllowedOrigins::List(allowed) => {
+                let origin = origin?;
+                allowed.contains(origin).then(|| origin.clone())
+            }
+        }
+    }
+
+    fn insert_cors(&self, headers: &mut HeaderMap, origin: Option<&HeaderValue>) {
+        if let Some(allow_origin) = self.allow_origin(origin) {
+            headers.insert(ACCESS_CONTROL_ALLOW_ORIGIN, allow_origin);
+        }
+        if matches!(self.allowed_origins, AllowedOrigins::List(_)) {
+            headers.append(VARY, HeaderValue::from_static("Origin"));
+        }
+        headers.insert(
+            ACCESS_CONTROL_ALLOW_METHODS,
+            HeaderValue::from_static("GET, POST, OPTIONS"),
+        );
+        headers.insert(ACCESS_CONTROL_ALLOW_HEADERS, self.allowed_headers.clone());
+    }
+
+    fn insert_security(&self, headers: &mut HeaderMap) {
+        if let Some(hsts) = &self.hsts {
+            headers.insert(STRICT_TRANSPORT_SECURITY, hsts.clone());
+        }
+        if let Some(frame_options) = &self.frame_options {
+            headers.insert(X_FRAME_OPTIONS, frame_options.clone());
+        }
+    }
+}
+
+/// Add the CORS and security headers to the response, answering preflight requests directly
+pub(crate) async fn headers_middleware(
+    State(headers): State<Arc<Headers>>,
+    req: Request<Body>,
+    next: Next,
+) -> Response {
+    let origin = req.headers().get(ORIGIN).cloned();
+
+    // Handle preflight requests
+    if req.method() == Method::OPTIONS {
+        let mut response = Response::new(Body::empty());
+        headers.insert_cors(response.headers_mut(), origin.as_ref());
+        if let Some(max_age) = &headers.max_age {
+            response
+                .headers_mut()
+                .insert(ACCESS_CONTROL_MAX_AGE, max_age.clone());
+        }
+        headers.insert_security(response.headers_mut());
+        return response;
+    }
+
+    let mut response = next.run(req).await;
+
+    headers.insert_cors(response.headers_mut(), origin.as_ref());
+    response.headers_mut().insert(
+        ACCESS_CONTROL_EXPOSE_HEADERS,
+        HeaderValue::from_static("ETag"),
+    );
+    headers.insert_security(response.headers_mut());
+
+    response
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_default_allows_any_origin() {
+        let headers = Headers::try_from(&Config::default()).unwrap();
+
+        assert_eq!(
+            headers.allow_origin(None),
+            Some(HeaderValue::from_static("*"))
+        );
+        assert!(headers.hsts.is_none());
+        assert!(headers.frame_options.is_none());
+    }
+
+    #[test]
+    fn test_allowed_origins() {
+        let config = Config {
+            cors_allowed_origins: vec!["https://wallet.example.com/".to_string()],
+            hsts_max_age: Some(31_536_000),
+            hsts_include_subdomains: true,
+            frame_options: Some(FrameOptions::Deny),
+            ..Default::default()
+        };
+        let headers = Headers::try_from(&config).unwrap();
+
+        let allowed = HeaderValue::from_static("https://wallet.example.com");
+        assert_eq!(headers.allow_origin(Some(&allowed)), Some(allowed.clone()));
+        assert_eq!(
+            headers.allow_origin(Some(&HeaderValue::from_static("https://evil.example.com"))),
+            None
+        );
+        assert_eq!(headers.allow_origin(None), None);
+        assert_eq!(
+            headers.hsts,
+            Some(HeaderValue::from_static(
+                "max-age=31536000; includeSubDomains"
+            ))
+        );
+        assert_eq!(
+            headers.frame_options,
+            Some(HeaderValue::from_static("DENY"))
+        );
+    }
+
+    #[test]
+    fn test_frame_options_from_str() {
+        assert_eq!(FrameOptions::from_str("DENY"), Ok(FrameOptions::Deny));
+        assert_eq!(
+            FrameOptions::from_str("same_origin"),
+            Ok(FrameOptions::SameOrigin)
+        );
+        assert!(FrameOptions::from_str("allow").is_err());
+    }
+}
@@ -6,20 +6,27 @@ use std::sync::Arc;
 
 use anyhow::Result;
 use auth::create_auth_router;
-use axum::middleware::from_fn;
-use axum::response::Response;
+use axum::middleware::from_fn_with_state;
 use axum::routing::{get, post};
 use axum::Router;
 use cache::HttpCache;
 use cdk::mint::Mint;
+pub use lightning_address::{
+    create_lightning_address_router, quotes_message, LightningAddressConfig,
+};
+pub use middleware::{with_middleware, MintMiddleware, MintOperation, RequestContext};
 use router_handlers::*;
 
 mod metrics;
 
 mod auth;
 pub mod cache;
+mod conditional;
 mod custom_handlers;
 mod custom_router;
+pub mod headers;
+mod lightning_address;
+mod middleware;
 mod router_handlers;
 mod ws;
 
@@ -39,61 +46,42 @@ pub async fn create_mint_router(mint: Arc<Mint>, custom_methods: Vec<String>) ->
     create_mint_router_with_custom_cache(mint, Default::default(), custom_methods, false).await
 }
 
-async fn cors_middleware(
-    req: axum::http::Request<axum::body::Body>,
-    next: axum::middleware::Next,
-) -> Response {
-    let allowed_headers = "*";
-
-    // Handle preflight requests
-    if req.method() == axum::http::Method::OPTIONS {
-        let mut response = Response::new("".into());
-        response.headers_mut().insert(
-            "Access-Control-Allow-Origin",
-            "*".parse().expect("Valid header value"),
-        );
-        response.headers_mut().insert(
-            "Access-Control-Allow-Methods",
-            "GET, POST, OPTIONS".parse().expect("Valid header value"),
-        );
-        response.headers_mut().insert(
-            "Access-Control-Allow-Headers",
-            allowed_headers.parse().expect("Valid header value"),
-        );
-        return response;
-    }
-
-    // Call the next handler
-    let mut response = next.run(req).await;
-
-    response.headers_mut().insert(
-        "Access-Control-Allow-Origin",
-        "*".parse().expect("Valid header value"),
-    );
-    response.headers_mut().insert(
-        "Access-Control-Allow-Methods",
-        "GET, POST, OPTIONS".parse().expect("Valid header value"),
-    );
-    response.headers_mut().insert(
-        "Access-Control-Allow-Headers",
-        allowed_headers.parse().expect("Valid header value"),
-    );
-
-    response
+/// Create mint [`Router`] with required endpoints for cashu mint with a custom
+/// backend for cache
+///
+/// The `custom_methods` parameter should include all custom payment methods supported
+/// by the payment processor, including "bolt11" and "bolt12" if they are supported.
+pub async fn create_mint_router_with_custom_cache(
+    mint: Arc<Mint>,
+    cache: HttpCache,
+    custom_methods: Vec<String>,
+    enable_info_page: bool,
+) -> Result<Router> {
+    create_mint_router_with_headers(
+        mint,
+        cache,
+        custom_methods,
+        enable_info_page,
+        headers::Config::default(),
+    )
+    .await
 }
 
 /// Create mint [`Router`] with required endpoints for cashu mint with a custom
-/// backend for cache
+/// backend for cache and custom CORS and security headers
 ///
 /// The `custom_methods` parameter should include all custom payment methods supported
 /// by the payment processor, including "bolt11" and "bolt12" if they are supported.
 #[allow(unused_mut, unused_variables)]
-pub async fn create_mint_router_with_custom_cache(
+pub async fn create_mint_router_with_headers(
     mint: Arc<Mint>,
     cache: HttpCache,
     custom_methods: Vec<String>,
     enable_info_page: bool,
+    headers: headers::Config,
 ) -> Result<Router> {
+    let response_headers = Arc::new(headers::Headers::try_from(&headers)?);
+
     let state = MintState {
         mint,
         cache: Arc::new(cache),
@@ -121,24 +109,14 @@ pub async fn create_mint_router_with_custom_cache(
         mint_router.nest("/v1", auth_router)
     };
 
-    // Create and merge custom payment method routers
-    // This now includes bolt11 and bolt12 if they are in custom_methods
-    let mint_router = if !custom_methods.is_empty() {
-        // Validate custom method names
-        custom_router::validate_custom_method_names(&custom_methods)
-            .map_err(|e| anyhow::anyhow!("Invalid custom method names: {}", e))?;
-
-        tracing::info!(
-            "Creating routes for {} payment methods: {:?}",
-            custom_methods.len(),
-            custom_methods
-        );
-
-        let custom_router = custom_router::create_custom_routers(state.clone(), custom_methods);
-        mint_router.nest("/v1", custom_router)
-    } else {
-        mint_router
-    };
+    // Create and merge the payment method routers
+    // The routes are parameterized by method, so they are mounted even without configured
+    // methods and requests for methods without a payment processor are rejected with a 400
+    custom_router::validate_custom_method_names(&custom_methods)
+        .map_err(|e| anyhow::anyhow!("Invalid custom method names: {}", e))?;
+
+    let custom_router = custom_router::create_custom_routers(state.clone(), custom_methods);
+    let mint_router = mint_router.nest("/v1", custom_router);
 
     #[cfg(feature = "prometheus")]
     let mint_router = mint_router.layer(axum::middleware::from_fn_with_state(
@@ -146,7 +124,10 @@ pub async fn create_mint_router_with_custom_cache(
         metrics::global_metrics_middleware,
     ));
     let mint_router = mint_router
-        .layer(from_fn(cors_middleware))
+        .layer(from_fn_with_state(
+            response_headers,
+            headers::headers_middleware,
+        ))
         .with_state(state);
 
     Ok(mint_router)
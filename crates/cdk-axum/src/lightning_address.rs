@@ -0,0 +1,286 @@
+//! Lightning address (LNURL-pay) endpoints
+//!
+//! Serves LUD-16 lightning addresses `user@mint` for users registered with a pubkey. Paying the
+//! address creates a bolt11 mint quote locked to the pubkey of the user, see
+//! [`Mint::create_lightning_address_quote`].
+//!
+//! Every callback creates an invoice at the payment backend, so callbacks are rate limited per
+//! username. The quotes of a username are only listed to requests signed by its pubkey.
+
+use std::collections::{HashMap, VecDeque};
+use std::str::FromStr;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use axum::extract::{Path, Query, State};
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Response};
+use axum::routing::get;
+use axum::{Json, Router};
+use cdk::mint::{Mint, QuoteId};
+use cdk::nuts::PublicKey;
+use cdk::secp256k1::schnorr::Signature;
+use cdk::util::unix_time;
+use serde::{Deserialize, Serialize};
+use tokio::sync::Mutex;
+
+/// Window of the callback rate limit
+const RATE_LIMIT_WINDOW: Duration = Duration::from_secs(60);
+
+/// Maximum age of the timestamp signed to list quotes
+const MAX_QUOTES_SIGNATURE_AGE_SECS: u64 = 300;
+
+/// Lightning address configuration
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LightningAddressConfig {
+    /// Public URL of the mint, the domain of the lightning addresses
+    pub url: String,
+    /// Pubkeys the quotes of each username are locked to
+    pub users: HashMap<String, PublicKey>,
+    /// Minimum payable amount in millisatoshis
+    pub min_sendable_msat: u64,
+    /// Maximum payable amount in millisatoshis
+    pub max_sendable_msat: u64,
+    /// Maximum invoices created per username and minute
+    pub max_invoices_per_minute: usize,
+}
+
+#[derive(Clone)]
+struct LightningAddressState {
+    mint: Arc<Mint>,
+    config: Arc<LightningAddressConfig>,
+    /// Creation times of the recent invoices of each username
+    recent_invoices: Arc<Mutex<HashMap<String, VecDeque<Instant>>>>,
+}
+
+/// LUD-06 pay request
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct PayRequestResponse {
+    callback: String,
+    min_sendable: u64,
+    max_sendable: u64,
+    metadata: String,
+    tag: &'static str,
+}
+
+/// LUD-06 callback response
+#[derive(Debug, Serialize)]
+struct InvoiceResponse {
+    pr: String,
+    routes: Vec<()>,
+}
+
+/// Query of the LUD-06 callback
+#[derive(Debug, Deserialize)]
+struct CallbackQuery {
+    amount: u64,
+}
+
+/// Query listing the quotes of a lightning address
+///
+/// `signature` is a hex encoded Schnorr signature of [`quotes_message`] by the pubkey of the user.
+#[derive(Debug, Deserialize)]
+struct QuotesQuery {
+    timestamp: u64,
+    signature: String,
+}
+
+/// Mint quotes created for a lightning address
+#[derive(Debug, Serialize)]
+struct QuotesResponse {
+    quotes: Vec<QuoteId>,
+}
+
+/// Create the [`Router`] of the lightning address endpoints
+///
+/// - `GET /.well-known/lnurlp/{username}`: LUD-16 pay request
+/// - `GET /lnurlp/{username}/callback?amount=<msat>`: invoice of a new mint quote
+/// - `GET /lnurlp/{username}/quotes?timestamp=<unix>&signature=<hex>`: ids of the mint quotes
+///   created for the username, see [`quotes_message`]
+pub fn create_lightning_address_router(mint: Arc<Mint>, config: LightningAddressConfig) -> Router {
+    let state = LightningAddressState {
+        mint,
+        config: Arc::new(config),
+        recent_invoices: Arc::new(Mutex::new(HashMap::new())),
+    };
+
+    Router::new()
+        .route("/.well-known/lnurlp/{username}", get(get_pay_request))
+        .route("/lnurlp/{username}/callback", get(get_invoice))
+        .route("/lnurlp/{username}/quotes", get(get_quotes))
+        .with_state(state)
+}
+
+impl LightningAddressConfig {
+    fn base_url(&self) -> &str {
+        self.url.trim_end_matches('/')
+    }
+
+    fn address(&self, username: &str) -> String {
+        let domain = self
+            .base_url()
+            .trim_start_matches("https://")
+            .trim_start_matches("http://");
+        format!("{username}@{domain}")
+    }
+
+    /// LUD-06 metadata of the pay request of `username`
+    fn metadata(&self, username: &str) -> String {
+        let address = self.address(username);
+        serde_json::json!([
+            ["text/plain", format!("Payment to {address}")],
+            ["text/identifier", address],
+        ])
+        .to_string()
+    }
+}
+
+impl LightningAddressState {
+    /// Record an invoice for `username` unless its rate limit is reached
+    async fn try_record_invoice(&self, username: &str) -> bool {
+        let now = Instant::now();
+        let mut recent_invoices = self.recent_invoices.lock().await;
+        let recent = recent_invoices.entry(username.to_string()).or_default();
+
+        while recent
+            .front()
+            .is_some_and(|created| now.duration_since(*created) >= RATE_LIMIT_WINDOW)
+        {
+            recent.pop_front();
+        }
+
+        if recent.len() >= self.config.max_invoices_per_minute {
+            return false;
+        }
+
+        recent.push_back(now);
+        true
+    }
+}
+
+/// Message a user signs with the pubkey of `username` to list its quotes at `timestamp`
+///
+/// The signature is a BIP-340 Schnorr signature over the SHA-256 hash of the message.
+pub fn quotes_message(username: &str, timestamp: u64) -> String {
+    format!("lnurlp-quotes:{username}:{timestamp}")
+}
+
+/// LUD-06 error response
+fn lnurl_error(status: StatusCode, reason: impl Into<String>) -> Response {
+    (
+        status,
+        Json(serde_json::json!({
+            "status": "ERROR",
+            "reason": reason.into(),
+        })),
+    )
+        .into_response()
+}
+
+fn unknown_user(username: &str) -> Response {
+    lnurl_error(
+        StatusCode::NOT_FOUND,
+        format!("Unknown lightning address user {username}"),
+    )
+}
+
+async fn get_pay_request(
+    State(state): State<LightningAddressState>,
+    Path(username): Path<String>,
+) -> Response {
+    if !state.config.users.contains_key(&username) {
+        return unknown_user(&username);
+    }
+
+    Json(PayRequestResponse {
+        callback: format!("{}/lnurlp/{}/callback", state.config.base_url(), username),
+        min_sendable: state.config.min_sendable_msat,
+        max_sendable: state.config.max_sendable_msat,
+        metadata: state.config.metadata(&username),
+        tag: "payRequest",
+    })
+    .into_response()
+}
+
+async fn get_invoice(
+    State(state): State<LightningAddressState>,
+    Path(username): Path<String>,
+    Query(query): Query<CallbackQuery>,
+) -> Response {
+    let Some(pubkey) = state.config.users.get(&username) else {
+        return unknown_user(&username);
+    };
+
+    if query.amount < state.config.min_sendable_msat
+        || query.amount > state.config.max_sendable_msat
+    {
+        return lnurl_error(
+            StatusCode::BAD_REQUEST,
+            format!(
+                "Amount must be between {} and {} msat",
+                state.config.min_sendable_msat, state.config.max_sendable_msat
+            ),
+        );
+    }
+
+    if !state.try_record_invoice(&username).await {
+        return lnurl_error(
+            StatusCode::TOO_MANY_REQUESTS,
+            format!("Too many invoices requested for {username}, try again later"),
+        );
+    }
+
+    match state
+        .mint
+        .create_lightning_address_quote(*pubkey, query.amount, state.config.metadata(&username))
+        .await
+    {
+        Ok(quote) => Json(InvoiceResponse {
+            pr: quote.request().to_string(),
+            routes: Vec::new(),
+        })
+        .into_response(),
+        Err(err) => {
+            tracing::warn!(
+                "Could not create lightning address quote for {}: {}",
+                username,
+                err
+            );
+            lnurl_error(StatusCode::BAD_REQUEST, err.to_string())
+        }
+    }
+}
+
+async fn get_quotes(
+    State(state): State<LightningAddressState>,
+    Path(username): Path<String>,
+    Query(query): Query<QuotesQuery>,
+) -> Response {
+    let Some(pubkey) = state.config.users.get(&username) else {
+        return unknown_user(&username);
+    };
+
+    if unix_time().abs_diff(query.timestamp) > MAX_QUOTES_SIGNATURE_AGE_SECS {
+        return lnurl_error(StatusCode::UNAUTHORIZED, "Signature timestamp is too old");
+    }
+
+    let verified = Signature::from_str(&query.signature)
+        .ok()
+        .is_some_and(|signature| {
+            pubkey
+                .verify(
+                    quotes_message(&username, query.timestamp).as_bytes(),
+                    &signature,
+                )
+                .is_ok()
+        });
+    if !verified {
+        return lnurl_error(StatusCode::UNAUTHORIZED, "Invalid signature");
+    }
+
+    match state.mint.lightning_address_quotes(pubkey).await {
+        Ok(quotes) => Json(QuotesResponse { quotes }).into_response(),
+        Err(err) => lnurl_error(StatusCode::INTERNAL_SERVER_ERROR, err.to_string()),
+    }
+}
@@ -0,0 +1,374 @@
+//! Request middleware hooks
+//!
+//! A [`MintMiddleware`] runs before and after every request to the mint, so integrators can add
+//! custom auth, accounting or geo-blocking on top of the mint router instead of forking it. The
+//! hooks get a [`RequestContext`] with the [`MintOperation`] the request is for.
+
+use std::net::SocketAddr;
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use axum::body::Body;
+use axum::extract::{ConnectInfo, MatchedPath, State};
+use axum::http::{Extensions, HeaderMap, Method, Request, Uri};
+use axum::middleware::{from_fn_with_state, Next};
+use axum::response::Response;
+use axum::Router;
+
+/// Mint operation a request is for
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum MintOperation {
+    /// Mint info [NUT-06]
+    Info,
+    /// Keys of the active or a specific keyset [NUT-01]
+    Keys,
+    /// Keysets [NUT-02]
+    Keysets,
+    /// Creating or checking a mint quote
+    MintQuote {
+        /// Payment method of the quote
+        method: String,
+    },
+    /// Minting, including batch minting
+    Mint {
+        /// Payment method of the quote
+        method: String,
+    },
+    /// Creating or checking a melt quote
+    MeltQuote {
+        /// Payment method of the quote
+        method: String,
+    },
+    /// Melting
+    Melt {
+        /// Payment method of the quote
+        method: String,
+    },
+    /// Swap [NUT-03]
+    Swap,
+    /// Proof state check [NUT-07]
+    CheckState,
+    /// Restore [NUT-09]
+    Restore,
+    /// Websocket subscription [NUT-17]
+    WebSocket,
+    /// Blind auth endpoints [NUT-22]
+    Auth,
+    /// Any other route, e.g. routes merged by the integrator
+    Other,
+}
+
+impl MintOperation {
+    /// Operation of a request path, e.g. `/v1/mint/quote/bolt11/<quote_id>`
+    pub fn from_path(path: &str) -> Self {
+        let segments: Vec<&str> = path.split('/').filter(|s| !s.is_empty()).collect();
+
+        let ["v1", segments @ ..] = segments.as_slice() else {
+            return Self::Other;
+        };
+
+        match segments {
+            ["info"] => Self::Info,
+            ["keys", ..] => Self::Keys,
+            ["keysets"] => Self::Keysets,
+            ["swap"] => Self::Swap,
+            ["checkstate"] => Self::CheckState,
+            ["restore"] => Self::Restore,
+            ["ws"] => Self::WebSocket,
+            ["auth", ..] => Self::Auth,
+            ["mint", "quote", method, ..] => Self::MintQuote {
+                method: method.to_string(),
+            },
+            ["mint", method, ..] => Self::Mint {
+                method: method.to_string(),
+            },
+            ["melt", "quote", method, ..] => Self::MeltQuote {
+                method: method.to_string(),
+            },
+            ["melt", method, ..] => Self::Melt {
+                method: method.to_string(),
+            },
+            _ => Self::Other,
+        }
+    }
+}
+
+/// Request passed to the [`MintMiddleware`] hooks
+#[derive(Debug, Clone)]
+pub struct RequestContext {
+    /// HTTP method
+    pub method: Method,
+    /// Request URI
+    pub uri: Uri,
+    /// Route the request matched, e.g. `/v1/mint/quote/{method}`
+    pub route: Option<String>,
+    /// Mint operation of the request
+    pub operation: MintOperation,
+    /// Request headers
+    pub headers: HeaderMap,
+    /// Address of the client, if the server was started with connect info
+    pub remote_addr: Option<SocketAddr>,
+    /// Values a pre-handler hook passes on to the post-handler hooks
+    pub data: Extensions,
+}
+
+/// Hooks run around every request to the mint
+///
+/// Pre-handler hooks run in the order the middleware were added, post-handler hooks in reverse
+/// order. Only the post-handler hooks of middleware whose pre-handler hook ran are called.
+#[async_trait]
+pub trait MintMiddleware: Send + Sync {
+    /// Called before the request is handled
+    ///
+    /// Returning a response rejects the request with it, the remaining pre-handler hooks and
+    /// the handler are skipped.
+    async fn pre_handle(&self, _ctx: &mut RequestContext) -> Option<Response> {
+        None
+    }
+
+    /// Called with the response to the request, including responses of rejected requests
+    async fn post_handle(&self, _ctx: &RequestContext, _response: &mut Response) {}
+}
+
+type Middlewares = Arc<Vec<Arc<dyn MintMiddleware>>>;
+
+/// Run `middleware` around every route of `router`
+///
+/// Routes merged into the router afterwards are not covered.
+pub fn with_middleware(router: Router, middleware: Vec<Arc<dyn MintMiddleware>>) -> Router {
+    if middleware.is_empty() {
+        return router;
+    }
+
+    router.layer(from_fn_with_state(Arc::new(middleware), run_middleware))
+}
+
+async fn run_middleware(
+    State(middleware): State<Middlewares>,
+    mut req: Request<Body>,
+    next: Next,
+) -> Response {
+    let mut ctx = RequestContext {
+        method: req.method().clone(),
+        uri: req.uri().clone(),
+        route: req
+            .extensions()
+            .get::<MatchedPath>()
+            .map(|path| path.as_str().to_string()),
+        operation: MintOperation::from_path(req.uri().path()),
+        headers: req.headers().clone(),
+        remote_addr: req
+            .extensions()
+            .get::<ConnectInfo<SocketAddr>>()
+            .map(|ConnectInfo(addr)| *addr),
+        data: Extensions::new(),
+    };
+
+    let mut ran = 0;
+    let mut rejection = None;
+    for hooks in middleware.iter() {
+        ran += 1;
+        if let Some(response) = hooks.pre_handle(&mut ctx).await {
+            tracing::debug!(
+                "Middleware rejected {} {} with {}",
+                ctx.method,
+                ctx.uri,
+                response.status()
+            );
+            rejection = Some(response);
+            break;
+        }
+    }
+
+    let mut response = match rejection {
+        Some(response) => response,
+        None => {
+            // Pre-handler hooks may have changed the headers
+            *req.headers_mut() = ctx.headers.clone();
+            next.run(req).await
+        }
+    };
+
+    for hooks in middleware.iter().take(ran).rev() {
+        hooks.post_handle(&ctx, &mut response).await;
+    }
+
+    response
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Mutex;
+
+    use axum::http::StatusCode;
+    use axum::response::IntoResponse;
+    use axum::routing::get;
+    use tower::ServiceExt;
+
+    use super::*;
+
+    type Calls = Arc<Mutex<Vec<String>>>;
+
+    struct Recorder {
+        name: &'static str,
+        calls: Calls,
+        reject: bool,
+    }
+
+    #[async_trait]
+    impl MintMiddleware for Recorder {
+        async fn pre_handle(&self, ctx: &mut RequestContext) -> Option<Response> {
+            self.calls
+                .lock()
+                .expect("calls lock")
+                .push(format!("pre {} {:?}", self.name, ctx.remote_addr));
+
+            self.reject.then(|| StatusCode::FORBIDDEN.into_response())
+        }
+
+        async fn post_handle(&self, _ctx: &RequestContext, response: &mut Response) {
+            self.calls.lock().expect("calls lock").push(format!(
+                "post {} {}",
+                self.name,
+                response.status()
+            ));
+        }
+    }
+
+    fn recorder(name: &'static str, calls: &Calls, reject: bool) -> Arc<dyn MintMiddleware> {
+        Arc::new(Recorder {
+            name,
+            calls: calls.clone(),
+            reject,
+        })
+    }
+
+    fn test_router(calls: &Calls, middleware: Vec<Arc<dyn MintMiddleware>>) -> Router {
+        let handler_calls = calls.clone();
+        let router = Router::new().route(
+            "/v1/info",
+            get(move || {
+                let calls = handler_calls.clone();
+                async move {
+                    calls
+                        .lock()
+                        .expect("calls lock")
+                        .push("handler".to_string());
+                    "info"
+                }
+            }),
+        );
+
+        with_middleware(router, middleware)
+    }
+
+    fn info_request() -> Request<Body> {
+        let mut req = Request::get("/v1/info")
+            .body(Body::empty())
+            .expect("test request should build");
+        req.extensions_mut()
+            .insert(ConnectInfo(SocketAddr::from(([127, 0, 0, 1], 3338))));
+        req
+    }
+
+    #[tokio::test]
+    async fn test_hooks_run_in_order_around_handler() {
+        let calls = Calls::default();
+        let router = test_router(
+            &calls,
+            vec![
+                recorder("first", &calls, false),
+                recorder("second", &calls, false),
+            ],
+        );
+
+        let response = router
+            .oneshot(info_request())
+            .await
+            .expect("test service should respond");
+
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(
+            *calls.lock().expect("calls lock"),
+            vec![
+                "pre first Some(127.0.0.1:3338)",
+                "pre second Some(127.0.0.1:3338)",
+                "handler",
+                "post second 200 OK",
+                "post first 200 OK",
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_rejecting_hook_skips_handler_and_later_hooks() {
+        let calls = Calls::default();
+        let router = test_router(
+            &calls,
+            vec![
+                recorder("first", &calls, false),
+                recorder("second", &calls, true),
+                recorder("third", &calls, false),
+            ],
+        );
+
+        let response = router
+            .oneshot(info_request())
+            .await
+            .expect("test service should respond");
+
+        assert_eq!(response.status(), StatusCode::FORBIDDEN);
+        assert_eq!(
+            *calls.lock().expect("calls lock"),
+            vec![
+                "pre first Some(127.0.0.1:3338)",
+                "pre second Some(127.0.0.1:3338)",
+                "post second 403 Forbidden",
+                "post first 403 Forbidden",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_operation_from_path() {
+        assert_eq!(MintOperation::from_path("/v1/info"), MintOperation::Info);
+        assert_eq!(
+            MintOperation::from_path("/v1/keys/00ab"),
+            MintOperation::Keys
+        );
+        assert_eq!(MintOperation::from_path("/v1/swap"), MintOperation::Swap);
+        assert_eq!(
+            MintOperation::from_path("/v1/mint/quote/bolt11/quote-id"),
+            MintOperation::MintQuote {
+                method: "bolt11".to_string()
+            }
+        );
+        assert_eq!(
+            MintOperation::from_path("/v1/mint/bolt12/batch"),
+            MintOperation::Mint {
+                method: "bolt12".to_string()
+            }
+        );
+        assert_eq!(
+            MintOperation::from_path("/v1/melt/quote/paypal"),
+            MintOperation::MeltQuote {
+                method: "paypal".to_string()
+            }
+        );
+        assert_eq!(
+            MintOperation::from_path("/v1/melt/bolt11"),
+            MintOperation::Melt {
+                method: "bolt11".to_string()
+            }
+        );
+        assert_eq!(
+            MintOperation::from_path("/v1/auth/blind/mint"),
+            MintOperation::Auth
+        );
+        assert_eq!(MintOperation::from_path("/"), MintOperation::Other);
+        assert_eq!(
+            MintOperation::from_path("/.well-known/lnurlp/alice"),
+            MintOperation::Other
+        );
+    }
+}
@@ -1,19 +1,22 @@
 use anyhow::Result;
 use axum::extract::ws::WebSocketUpgrade;
 use axum::extract::{Json, Path, State};
-use axum::http::StatusCode;
+use axum::http::{header, HeaderMap, StatusCode};
 use axum::response::{IntoResponse, Response};
 use cdk::error::ErrorResponse;
 use cdk::nuts::nut21::{Method, ProtectedEndpoint, RoutePath};
 use cdk::nuts::{
-    CheckStateRequest, CheckStateResponse, Id, KeysResponse, KeysetResponse, MintInfo,
-    RestoreRequest, RestoreResponse, SwapRequest, SwapResponse,
+    CheckStateRequest, CheckStateResponse, Id, RestoreRequest, RestoreResponse, SwapRequest,
+    SwapResponse,
 };
 use cdk::util::unix_time;
 use paste::paste;
 use tracing::instrument;
 
 use crate::auth::AuthHeader;
+use crate::conditional::{
+    conditional_json, etag, CACHE_CONTROL_IMMUTABLE, CACHE_CONTROL_REVALIDATE,
+};
 use crate::ws::main_websocket;
 use crate::MintState;
 
@@ -91,10 +94,9 @@ post_cache_wrapper!(post_swap, SwapRequest, SwapResponse);
 ///
 /// This endpoint returns a dictionary of all supported token values of the mint and their associated public key.
 #[instrument(skip_all)]
-pub(crate) async fn get_keys(
-    State(state): State<MintState>,
-) -> Result<Json<KeysResponse>, Response> {
-    Ok(Json(state.mint.pubkeys()))
+pub(crate) async fn get_keys(headers: HeaderMap, State(state): State<MintState>) -> Response {
+    let keys = state.mint.pubkeys();
+    conditional_json(&headers, etag(&keys), CACHE_CONTROL_REVALIDATE, keys)
 }
 
 /// Get the public keys of a specific keyset
@@ -102,25 +104,30 @@ pub(crate) async fn get_keys(
 /// Get the public keys of the mint from a specific keyset ID.
 #[instrument(skip_all, fields(keyset_id = ?keyset_id))]
 pub(crate) async fn get_keyset_pubkeys(
+    headers: HeaderMap,
     State(state): State<MintState>,
     Path(keyset_id): Path<Id>,
-) -> Result<Json<KeysResponse>, Response> {
+) -> Result<Response, Response> {
     let pubkeys = state.mint.keyset_pubkeys(&keyset_id).map_err(|err| {
         tracing::error!("Could not get keyset pubkeys: {}", err);
         into_response(err)
     })?;
 
-    Ok(Json(pubkeys))
+    Ok(conditional_json(
+        &headers,
+        etag(&pubkeys),
+        CACHE_CONTROL_IMMUTABLE,
+        pubkeys,
+    ))
 }
 
 /// Get all active keyset IDs of the mint
 ///
 /// This endpoint returns a list of keysets that the mint currently supports and will accept tokens from.
 #[instrument(skip_all)]
-pub(crate) async fn get_keysets(
-    State(state): State<MintState>,
-) -> Result<Json<KeysetResponse>, Response> {
-    Ok(Json(state.mint.keysets()))
+pub(crate) async fn get_keysets(headers: HeaderMap, State(state): State<MintState>) -> Response {
+    let keysets = state.mint.keysets();
+    conditional_json(&headers, etag(&keysets), CACHE_CONTROL_REVALIDATE, keysets)
 }
 
 #[instrument(skip_all)]
@@ -168,21 +175,23 @@ pub(crate) async fn post_check(
 }
 
 /// Mint information, operator contact information, and other info
+///
+/// The `ETag` leaves out the current time, so it only changes when the mint info does.
 #[instrument(skip_all)]
 pub(crate) async fn get_mint_info(
+    headers: HeaderMap,
     State(state): State<MintState>,
-) -> Result<Json<MintInfo>, Response> {
-    Ok(Json(
-        state
-            .mint
-            .mint_info()
-            .await
-            .map_err(|err| {
-                tracing::error!("Could not get mint info: {}", err);
-                into_response(err)
-            })?
-            .clone()
-            .time(unix_time()),
+) -> Result<Response, Response> {
+    let mint_info = state.mint.public_mint_info().await.map_err(|err| {
+        tracing::error!("Could not get mint info: {}", err);
+        into_response(err)
+    })?;
+
+    Ok(conditional_json(
+        &headers,
+        etag(&mint_info),
+        CACHE_CONTROL_REVALIDATE,
+        mint_info.time(unix_time()),
     ))
 }
 
@@ -727,7 +736,7 @@ pub(crate) async fn get_index(
 ) -> Result<impl IntoResponse, Response> {
     use maud::html;
 
-    let mint_info = state.mint.mint_info().await.map_err(into_response)?;
+    let mint_info = state.mint.public_mint_info().await.map_err(into_response)?;
 
     let name = mint_info.name.clone().unwrap_or("CDK Mint".to_string());
     let description = mint_info.description.clone();
@@ -1103,5 +1112,18 @@ where
         "mint returning error response",
     );
     // Per NUT-00 spec: "In case of an error, mints respond with the HTTP status code 400"
-    (StatusCode::BAD_REQUEST, Json(err_response)).into_response()
+    let retry_after = match cdk::Error::from(err_response.clone()) {
+        cdk::Error::TemporarilyUnavailable { retry_after } => Some(retry_after),
+        _ => None,
+    };
+
+    match retry_after {
+        Some(retry_after) => (
+            StatusCode::BAD_REQUEST,
+            [(header::RETRY_AFTER, retry_after.to_string())],
+            Json(err_response),
+        )
+            .into_response(),
+        None => (StatusCode::BAD_REQUEST, Json(err_response)).into_response(),
+    }
 }
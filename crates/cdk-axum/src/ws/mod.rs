@@ -205,6 +205,7 @@ mod tests {
                     input_fee_ppk: *fee,
                     keyset_id_type: KeySetVersion::Version00,
                     final_expiry: None,
+                    activate_at: None,
                 })
                 .await
                 .expect("rotate keyset");
@@ -11,6 +11,8 @@ use bip39::Mnemonic;
 use cdk::cdk_database;
 use cdk::cdk_database::WalletDatabase;
 use cdk::nuts::CurrencyUnit;
+#[cfg(feature = "postgres")]
+use cdk_postgres::new_wallet_pg_database;
 #[cfg(feature = "redb")]
 use cdk_redb::WalletRedbDatabase;
 use cdk_sqlite::WalletSqliteDatabase;
@@ -40,9 +42,12 @@ enum TorToggle {
 #[derive(Parser)]
 #[command(name = "cdk-cli", author = "thesimplekid <tsk@thesimplekid.com>", version = CARGO_PKG_VERSION.unwrap_or("Unknown"), about, long_about = None)]
 struct Cli {
-    /// Database engine to use (sqlite/redb)
+    /// Database engine to use (sqlite/redb/postgres)
     #[arg(short, long, default_value = "sqlite")]
     engine: String,
+    /// Connection string for the postgres engine
+    #[arg(long)]
+    db_url: Option<String>,
     /// Database password for sqlcipher
     #[cfg(feature = "sqlcipher")]
     #[arg(long)]
@@ -198,6 +203,19 @@ async fn main() -> Result<()> {
                     bail!("redb feature not enabled");
                 }
             }
+            "postgres" => {
+                #[cfg(feature = "postgres")]
+                {
+                    let Some(db_url) = args.db_url.as_deref() else {
+                        bail!("Missing --db-url for postgres engine");
+                    };
+                    Arc::new(new_wallet_pg_database(db_url).await?)
+                }
+                #[cfg(not(feature = "postgres"))]
+                {
+                    bail!("postgres feature not enabled");
+                }
+            }
             _ => bail!("Unknown DB engine"),
         };
 
@@ -415,6 +433,24 @@ mod tests {
         assert!(!cli.danger_accept_invalid_certs);
     }
 
+    #[test]
+    fn parses_postgres_engine_and_db_url() {
+        let cli = Cli::parse_from([
+            "cdk-cli",
+            "--engine",
+            "postgres",
+            "--db-url",
+            "host=localhost user=cdk dbname=wallet",
+            "balance",
+        ]);
+
+        assert_eq!(cli.engine, "postgres");
+        assert_eq!(
+            cli.db_url.as_deref(),
+            Some("host=localhost user=cdk dbname=wallet")
+        );
+    }
+
     #[test]
     fn parses_danger_accept_invalid_certs_flag() {
         let cli = Cli::parse_from([
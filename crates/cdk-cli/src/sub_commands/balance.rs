@@ -24,12 +24,15 @@ pub async fn balance(
         println!();
         if unit_totals.len() == 1 {
             if let Some((unit, total)) = unit_totals.into_iter().next() {
-                println!("Total balance across all wallets: {} {}", total, unit);
+                println!(
+                    "Total balance across all wallets: {}",
+                    total.display_with_unit(&unit)
+                );
             }
         } else {
             println!("Total balance across all wallets:");
             for (unit, total) in &unit_totals {
-                println!("  {} {}", total, unit);
+                println!("  {}", total.display_with_unit(unit));
             }
         }
     }
@@ -51,7 +54,7 @@ pub async fn mint_balances(
         .enumerate()
     {
         let WalletKey { mint_url, unit } = wallet_key.clone();
-        println!("{i}: {mint_url} {amount} {unit}");
+        println!("{i}: {mint_url} {}", amount.display_with_unit(&unit));
         wallets_vec.push((mint_url, unit, *amount))
     }
     Ok(wallets_vec)
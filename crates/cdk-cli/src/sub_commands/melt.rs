@@ -2,7 +2,7 @@ use std::collections::HashMap;
 use std::str::FromStr;
 
 use anyhow::{bail, Result};
-use cdk::amount::{amount_for_offer, Amount, MSAT_IN_SAT};
+use cdk::amount::{amount_for_offer, Amount, MsatRounding};
 use cdk::mint_url::MintUrl;
 use cdk::nuts::nut00::KnownMethod;
 use cdk::nuts::{CurrencyUnit, MeltOptions, PaymentMethod};
@@ -98,8 +98,8 @@ fn create_melt_options(
         None => {
             // Payment doesn't have an amount; use CLI amount if supplied, otherwise prompt.
             let user_amount = match cli_amount_sat {
-                Some(amount_sat) => amount_sat * MSAT_IN_SAT,
-                None => get_number_input::<u64>(prompt)? * MSAT_IN_SAT,
+                Some(amount_sat) => Amount::from(amount_sat).to_msat()?,
+                None => Amount::from(get_number_input::<u64>(prompt)?).to_msat()?,
             };
 
             if user_amount > available_funds {
@@ -232,7 +232,7 @@ pub async fn pay(
         }
     };
 
-    let available_funds = <cdk::Amount as Into<u64>>::into(total_balance) * MSAT_IN_SAT;
+    let available_funds = total_balance.to_msat()?;
 
     // Process payment based on payment method using individual wallets
     match sub_command_args.method {
@@ -262,7 +262,8 @@ pub async fn pay(
                 let balances = wallet_repository.get_balances().await?;
                 let required_amount = bolt11
                     .amount_milli_satoshis()
-                    .map(|a| Amount::from(a / MSAT_IN_SAT))
+                    .map(|a| Amount::from_msat(a, MsatRounding::Up))
+                    .transpose()?
                     .unwrap_or(Amount::ZERO);
 
                 balances
@@ -594,7 +595,7 @@ async fn pay_mpp(
         let wallet = get_or_create_wallet(wallet_repository, mint_url, unit).await?;
 
         // Convert amount to millisats for MPP
-        let amount_msat = u64::from(*amount) * MSAT_IN_SAT;
+        let amount_msat = amount.to_msat()?;
         let options = Some(MeltOptions::new_mpp(amount_msat));
 
         let quote = wallet
@@ -1,14 +1,36 @@
 use std::str::FromStr;
 
 use anyhow::{anyhow, Result};
+use bitcoin::hashes::sha256::Hash as Sha256Hash;
 use cdk::mint_url::MintUrl;
-use cdk::nuts::{Conditions, CurrencyUnit, PublicKey, SpendingConditions};
+use cdk::nuts::{CurrencyUnit, Nut10SecretBuilder, PublicKey};
+use cdk::util::hex;
 use cdk::wallet::types::SendKind;
-use cdk::wallet::{SendMemo, SendOptions, WalletRepository};
+use cdk::wallet::{SendFeePolicy, SendMemo, SendOptions, WalletRepository};
 use cdk::Amount;
-use clap::Args;
+use clap::{Args, ValueEnum};
+
+use crate::utils::{get_amount_input, get_number_input, get_or_create_wallet};
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, ValueEnum)]
+pub enum FeePolicy {
+    /// The receiver pays the fee of swapping the token
+    Receiver,
+    /// The token includes the fee so the receiver nets the full amount
+    Sender,
+    /// The token includes half the fee
+    Split,
+}
 
-use crate::utils::{get_number_input, get_or_create_wallet};
+impl From<FeePolicy> for SendFeePolicy {
+    fn from(policy: FeePolicy) -> Self {
+        match policy {
+            FeePolicy::Receiver => SendFeePolicy::ReceiverPays,
+            FeePolicy::Sender => SendFeePolicy::SenderPays,
+            FeePolicy::Split => SendFeePolicy::Split,
+        }
+    }
+}
 
 #[derive(Args)]
 pub struct SendSubCommand {
@@ -42,6 +64,9 @@ pub struct SendSubCommand {
     /// Include fee to redeem in token
     #[arg(short, long)]
     include_fee: bool,
+    /// Who pays the fee of the receiver's swap, `--include-fee` is the same as `sender`
+    #[arg(long, value_enum, default_value_t = FeePolicy::Receiver)]
+    fee_policy: FeePolicy,
     /// Amount willing to overpay to avoid a swap
     #[arg(short, long)]
     tolerance: Option<u64>,
@@ -51,9 +76,15 @@ pub struct SendSubCommand {
     /// Use P2BK (NUT-28) to blind the receiver's pubkey
     #[arg(long)]
     use_p2bk: bool,
-    /// Amount to send
+    /// Include keyset keys next to the DLEQ proofs so any receiver can verify the token offline
+    #[arg(long)]
+    include_dleq: bool,
+    /// Maximum number of proofs in the token, e.g. to keep QR codes small
+    #[arg(long)]
+    max_proofs: Option<usize>,
+    /// Amount to send in the wallet unit, e.g. `21` or `1.50`
     #[arg(short, long)]
-    amount: Option<u64>,
+    amount: Option<String>,
 }
 
 pub async fn send(
@@ -81,8 +112,10 @@ pub async fn send(
             println!("\nAvailable mints and balances:");
             for (index, (key, balance)) in balances_vec.iter().enumerate() {
                 println!(
-                    "  {}: {} ({}) - {} {}",
-                    index, key.mint_url, key.unit, balance, unit
+                    "  {}: {} - {}",
+                    index,
+                    key.mint_url,
+                    balance.display_with_unit(&key.unit)
                 );
             }
 
@@ -98,12 +131,9 @@ pub async fn send(
         }
     };
 
-    let token_amount = match sub_command_args.amount {
-        Some(amount) => Amount::from(amount),
-        None => Amount::from(get_number_input::<u64>(&format!(
-            "Enter value of token in {}",
-            unit
-        ))?),
+    let token_amount = match &sub_command_args.amount {
+        Some(amount) => Amount::from_str_with_unit(amount, unit)?,
+        None => get_amount_input(&format!("Enter value of token in {}", unit), unit)?,
     };
 
     // Get or create wallet for the selected mint
@@ -119,119 +149,52 @@ pub async fn send(
         ));
     }
 
-    let conditions = match (&sub_command_args.preimage, &sub_command_args.hash) {
+    let pubkeys = sub_command_args
+        .pubkey
+        .iter()
+        .map(|p| PublicKey::from_str(p))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let refund_keys = sub_command_args
+        .refund_keys
+        .iter()
+        .map(|p| PublicKey::from_str(p))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let builder = match (&sub_command_args.preimage, &sub_command_args.hash) {
         (Some(_), Some(_)) => {
             // This case shouldn't be reached due to Clap's conflicts_with attribute
             unreachable!("Both preimage and hash were provided despite conflicts_with attribute")
         }
         (Some(preimage), None) => {
-            let pubkeys = match sub_command_args.pubkey.is_empty() {
-                true => None,
-                false => Some(
-                    sub_command_args
-                        .pubkey
-                        .iter()
-                        .map(|p| PublicKey::from_str(p))
-                        .collect::<Result<Vec<_>, _>>()?,
-                ),
-            };
-
-            let refund_keys = match sub_command_args.refund_keys.is_empty() {
-                true => None,
-                false => Some(
-                    sub_command_args
-                        .refund_keys
-                        .iter()
-                        .map(|p| PublicKey::from_str(p))
-                        .collect::<Result<Vec<_>, _>>()?,
-                ),
-            };
-
-            let conditions = Conditions::new(
-                sub_command_args.locktime,
-                pubkeys,
-                refund_keys,
-                sub_command_args.required_sigs,
-                None,
-                None,
-            )?;
+            let preimage: [u8; 32] = hex::decode(preimage)?
+                .try_into()
+                .map_err(|_| anyhow!("Preimage must be 32 bytes"))?;
 
-            Some(SpendingConditions::new_htlc(
-                preimage.clone(),
-                Some(conditions),
-            )?)
+            Some(Nut10SecretBuilder::htlc_from_preimage(&preimage).pubkeys(pubkeys))
         }
         (None, Some(hash)) => {
-            let pubkeys = match sub_command_args.pubkey.is_empty() {
-                true => None,
-                false => Some(
-                    sub_command_args
-                        .pubkey
-                        .iter()
-                        .map(|p| PublicKey::from_str(p))
-                        .collect::<Result<Vec<_>, _>>()?,
-                ),
-            };
+            let hash = Sha256Hash::from_str(hash).map_err(|_| anyhow!("Invalid hash"))?;
 
-            let refund_keys = match sub_command_args.refund_keys.is_empty() {
-                true => None,
-                false => Some(
-                    sub_command_args
-                        .refund_keys
-                        .iter()
-                        .map(|p| PublicKey::from_str(p))
-                        .collect::<Result<Vec<_>, _>>()?,
-                ),
-            };
-
-            let conditions = Conditions::new(
-                sub_command_args.locktime,
-                pubkeys,
-                refund_keys,
-                sub_command_args.required_sigs,
-                None,
-                None,
-            )?;
-
-            Some(SpendingConditions::new_htlc_hash(hash, Some(conditions))?)
+            Some(Nut10SecretBuilder::htlc(hash).pubkeys(pubkeys))
         }
-        (None, None) => match sub_command_args.pubkey.is_empty() {
-            true => None,
-            false => {
-                let pubkeys: Vec<PublicKey> = sub_command_args
-                    .pubkey
-                    .iter()
-                    .map(|p| PublicKey::from_str(p))
-                    .collect::<Result<Vec<_>, _>>()?;
-
-                let refund_keys: Vec<PublicKey> = sub_command_args
-                    .refund_keys
-                    .iter()
-                    .map(|p| PublicKey::from_str(p))
-                    .collect::<Result<Vec<_>, _>>()?;
-
-                let refund_keys = (!refund_keys.is_empty()).then_some(refund_keys);
-
-                let data_pubkey = pubkeys[0];
-                let pubkeys = pubkeys[1..].to_vec();
-                let pubkeys = (!pubkeys.is_empty()).then_some(pubkeys);
-
-                let conditions = Conditions::new(
-                    sub_command_args.locktime,
-                    pubkeys,
-                    refund_keys,
-                    sub_command_args.required_sigs,
-                    None,
-                    None,
-                )?;
+        (None, None) => pubkeys
+            .split_first()
+            .map(|(data, rest)| Nut10SecretBuilder::p2pk(*data).pubkeys(rest.to_vec())),
+    };
 
-                Some(SpendingConditions::P2PKConditions {
-                    data: data_pubkey,
-                    conditions: Some(conditions),
-                })
+    let conditions = builder
+        .map(|mut builder| {
+            builder = builder.refund_keys(refund_keys);
+            if let Some(locktime) = sub_command_args.locktime {
+                builder = builder.locktime(locktime);
             }
-        },
-    };
+            if let Some(required_sigs) = sub_command_args.required_sigs {
+                builder = builder.num_sigs(required_sigs);
+            }
+            builder.build_conditions()
+        })
+        .transpose()?;
 
     let send_kind = match (sub_command_args.offline, sub_command_args.tolerance) {
         (true, Some(amount)) => SendKind::OfflineTolerance(Amount::from(amount)),
@@ -247,8 +210,11 @@ pub async fn send(
         }),
         send_kind,
         include_fee: sub_command_args.include_fee,
+        fee_policy: sub_command_args.fee_policy.into(),
         conditions,
         use_p2bk: sub_command_args.use_p2bk,
+        include_dleq: sub_command_args.include_dleq,
+        max_proofs: sub_command_args.max_proofs,
         ..Default::default()
     };
 
@@ -7,7 +7,7 @@ use cdk::Amount;
 use cdk_common::wallet::WalletKey;
 use clap::Args;
 
-use crate::utils::get_number_input;
+use crate::utils::{get_amount_input, get_number_input};
 
 #[derive(Args)]
 pub struct TransferSubCommand {
@@ -17,9 +17,9 @@ pub struct TransferSubCommand {
     /// Target mint URL to transfer to (optional - will prompt if not provided)
     #[arg(long)]
     target_mint: Option<String>,
-    /// Amount to transfer (optional - will prompt if not provided)
+    /// Amount to transfer in the wallet unit, e.g. `21` or `1.50` (optional - will prompt if not provided)
     #[arg(short, long, conflicts_with = "full_balance")]
-    amount: Option<u64>,
+    amount: Option<String>,
     /// Transfer all available balance from source mint
     #[arg(long, conflicts_with = "amount")]
     full_balance: bool,
@@ -171,12 +171,9 @@ pub async fn transfer(
             target_mint_url, target_balance_after, unit
         );
     } else {
-        let amount = match sub_command_args.amount {
-            Some(amt) => Amount::from(amt),
-            None => Amount::from(get_number_input::<u64>(&format!(
-                "Enter amount to transfer in {}",
-                unit
-            ))?),
+        let amount = match &sub_command_args.amount {
+            Some(amt) => Amount::from_str_with_unit(amt, unit)?,
+            None => get_amount_input(&format!("Enter amount to transfer in {}", unit), unit)?,
         };
 
         if source_balance < amount {
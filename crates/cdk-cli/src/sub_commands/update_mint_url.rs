@@ -27,7 +27,9 @@ pub async fn update_mint_url(
         .await?
         .clone();
 
-    wallet.update_mint_url(new_mint_url.clone()).await?;
+    wallet
+        .migrate_mint_url(old_mint_url.clone(), new_mint_url.clone())
+        .await?;
 
     println!("Mint Url changed from {old_mint_url} to {new_mint_url}");
 
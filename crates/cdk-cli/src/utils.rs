@@ -6,6 +6,7 @@ use anyhow::{bail, Result};
 use cdk::mint_url::MintUrl;
 use cdk::nuts::CurrencyUnit;
 use cdk::wallet::WalletRepository;
+use cdk::Amount;
 
 static NON_INTERACTIVE: AtomicBool = AtomicBool::new(false);
 
@@ -39,6 +40,12 @@ where
     Ok(number)
 }
 
+/// Helper function to get an amount of `unit` from user input, e.g. `1.50` or `1.50 usd`
+pub fn get_amount_input(prompt: &str, unit: &CurrencyUnit) -> Result<Amount> {
+    let input = get_user_input(prompt)?;
+    Ok(Amount::from_str_with_unit(&input, unit)?)
+}
+
 /// Helper function to get an existing wallet or create one if it doesn't exist
 pub async fn get_or_create_wallet(
     wallet_repository: &WalletRepository,
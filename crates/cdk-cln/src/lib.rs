@@ -3,6 +3,7 @@
 #![doc = include_str!("../README.md")]
 
 use std::cmp::max;
+use std::collections::VecDeque;
 use std::path::PathBuf;
 use std::pin::Pin;
 use std::str::FromStr;
@@ -11,7 +12,7 @@ use std::sync::Arc;
 use std::time::Duration;
 
 use async_trait::async_trait;
-use bitcoin::hashes::sha256;
+use bitcoin::hashes::{sha256, Hash};
 use cdk_common::amount::Amount;
 use cdk_common::common::FeeReserve;
 use cdk_common::database::DynKVStore;
@@ -25,8 +26,8 @@ use cdk_common::payment::{
 use cdk_common::util::{hex, unix_time};
 use cdk_common::{Bolt11Invoice, QuoteId};
 use cln_rpc::model::requests::{
-    DecodeRequest, FetchinvoiceRequest, InvoiceRequest, ListinvoicesRequest, ListpaysRequest,
-    OfferRequest, PayRequest, WaitanyinvoiceRequest,
+    DecodeRequest, FetchinvoiceRequest, InvoiceRequest, ListfundsRequest, ListinvoicesRequest,
+    ListpaysRequest, OfferRequest, PayRequest, WaitanyinvoiceRequest,
 };
 use cln_rpc::model::responses::{
     DecodeResponse, InvoiceResponse, ListinvoicesInvoices, ListinvoicesInvoicesStatus,
@@ -36,6 +37,7 @@ use cln_rpc::primitives::{Amount as CLN_Amount, AmountOrAny, Sha256};
 use cln_rpc::ClnRpc;
 use error::Error;
 use futures::{Stream, StreamExt};
+use serde::Deserialize;
 use tokio_util::sync::CancellationToken;
 use tracing::instrument;
 use uuid::Uuid;
@@ -46,14 +48,19 @@ pub mod error;
 const CLN_KV_PRIMARY_NAMESPACE: &str = "cdk_cln_lightning_backend";
 const CLN_KV_SECONDARY_NAMESPACE: &str = "payment_indices";
 const CLN_KV_BOLT12_OUTGOING_SECONDARY_NAMESPACE: &str = "bolt12_outgoing_payments";
+const CLN_KV_HOLD_INVOICES_SECONDARY_NAMESPACE: &str = "hold_invoices";
 const LAST_PAY_INDEX_KV_KEY: &str = "last_pay_index";
 
+/// How often held invoices are checked for accepted HTLCs
+const HOLD_INVOICE_POLL_INTERVAL: Duration = Duration::from_secs(2);
+
 /// CLN mint backend
 #[derive(Clone)]
 pub struct Cln {
     rpc_socket: PathBuf,
     fee_reserve: FeeReserve,
     expose_private_channels: bool,
+    hold_invoices: bool,
     wait_invoice_cancel_token: CancellationToken,
     wait_invoice_is_active: Arc<AtomicBool>,
     kv_store: DynKVStore,
@@ -64,6 +71,7 @@ impl std::fmt::Debug for Cln {
         f.debug_struct("Cln")
             .field("rpc_socket", &self.rpc_socket)
             .field("fee_reserve", &self.fee_reserve)
+            .field("hold_invoices", &self.hold_invoices)
             .finish_non_exhaustive()
     }
 }
@@ -80,11 +88,23 @@ impl Cln {
             rpc_socket,
             fee_reserve,
             expose_private_channels,
+            hold_invoices: false,
             wait_invoice_cancel_token: CancellationToken::new(),
             wait_invoice_is_active: Arc::new(AtomicBool::new(false)),
             kv_store,
         })
     }
+
+    /// Configures the [`Cln`] to use hold invoices for bolt11 mint quotes
+    ///
+    /// Requires the `holdinvoice` plugin on the CLN node. Incoming HTLCs are
+    /// held until the mint has recorded the quote as paid and calls
+    /// [`MintPayment::settle_incoming_payment`], so a crash between receiving
+    /// the payment and updating the quote cannot lose the payment.
+    pub fn with_hold_invoices(mut self, hold_invoices: bool) -> Self {
+        self.hold_invoices = hold_invoices;
+        self
+    }
 }
 
 #[async_trait]
@@ -308,6 +328,12 @@ impl MintPayment for Cln {
         .boxed();
 
         tracing::info!("CLN: Successfully initialized invoice stream");
+
+        if self.hold_invoices {
+            tracing::info!("CLN: Hold invoices enabled, watching for accepted HTLCs");
+            return Ok(futures::stream::select(stream, self.hold_invoice_stream()).boxed());
+        }
+
         Ok(stream)
     }
 
@@ -337,7 +363,18 @@ impl MintPayment for Cln {
                             }
                             amount_msat
                         }
-                        MeltOptions::Mpp { mpp } => mpp.amount,
+                        MeltOptions::Mpp { mpp } => {
+                            let invoice_amount = bolt11_options
+                                .bolt11
+                                .amount_milli_satoshis()
+                                .ok_or(Error::UnknownInvoiceAmount)?;
+
+                            // A partial payment never pays more than the invoice amount
+                            if u64::from(mpp.amount) > invoice_amount {
+                                return Err(payment::Error::AmountMismatch);
+                            }
+                            mpp.amount
+                        }
                     }
                 } else {
                     // Fall back to invoice amount
@@ -432,7 +469,17 @@ impl MintPayment for Cln {
 
                 if let Some(melt_options) = bolt11_options.melt_options {
                     match melt_options {
-                        MeltOptions::Mpp { mpp } => partial_amount = Some(mpp.amount.into()),
+                        MeltOptions::Mpp { mpp } => {
+                            let invoice_amount = bolt11_options
+                                .bolt11
+                                .amount_milli_satoshis()
+                                .ok_or(Error::UnknownInvoiceAmount)?;
+
+                            if u64::from(mpp.amount) > invoice_amount {
+                                return Err(payment::Error::AmountMismatch);
+                            }
+                            partial_amount = Some(mpp.amount.into());
+                        }
                         MeltOptions::Amountless { amountless } => {
                             amount_msat = Some(amountless.amount_msat.into());
                         }
@@ -580,6 +627,7 @@ impl MintPayment for Cln {
                 description,
                 amount,
                 unix_expiry,
+                hash_description,
             }) => {
                 let time_now = unix_time();
 
@@ -595,6 +643,22 @@ impl MintPayment for Cln {
                     .map(|t| t.checked_sub(time_now).ok_or(payment::Error::InvalidExpiry))
                     .transpose()?;
 
+                if self.hold_invoices {
+                    if hash_description {
+                        tracing::error!("CLN: Hold invoices cannot commit to a description hash");
+                        return Err(payment::Error::UnsupportedPaymentOption);
+                    }
+
+                    return self
+                        .create_hold_invoice(
+                            &mut cln_client,
+                            amount_converted.value(),
+                            description.unwrap_or_default(),
+                            expiry,
+                        )
+                        .await;
+                }
+
                 let request = InvoiceRequest {
                     amount_msat,
                     description: description.unwrap_or_default(),
@@ -603,7 +667,8 @@ impl MintPayment for Cln {
                     fallbacks: None,
                     preimage: None,
                     cltv: None,
-                    deschashonly: None,
+                    // CLN commits to the hash of the description and keeps the description
+                    deschashonly: hash_description.then_some(true),
                     exposeprivatechannels: None,
                 };
 
@@ -694,6 +759,25 @@ impl MintPayment for Cln {
     ) -> Result<Vec<WaitPaymentResponse>, Self::Err> {
         let mut cln_client = self.cln_client().await?;
 
+        // Held payments are not reported as paid by CLN until they are settled
+        if let PaymentIdentifier::PaymentHash(payment_hash) = payment_identifier {
+            if self.hold_invoices
+                && self
+                    .read_hold_invoice_preimage(payment_hash)
+                    .await?
+                    .is_some()
+            {
+                let lookup = hold_invoice_lookup(&mut cln_client, payment_hash).await?;
+
+                return Ok(match lookup.state {
+                    HoldInvoiceState::Accepted | HoldInvoiceState::Settled => {
+                        vec![held_payment_response(payment_hash, &lookup)?]
+                    }
+                    HoldInvoiceState::Open | HoldInvoiceState::Canceled => vec![],
+                });
+            }
+        }
+
         let listinvoices_response = match payment_identifier {
             PaymentIdentifier::Label(label) => {
                 // Query by label
@@ -765,6 +849,25 @@ impl MintPayment for Cln {
             .collect())
     }
 
+    #[instrument(skip(self))]
+    async fn outbound_liquidity(&self) -> Result<Option<Amount<CurrencyUnit>>, Self::Err> {
+        let mut cln_client = self.cln_client().await?;
+
+        let funds = cln_client
+            .call_typed(&ListfundsRequest { spent: None })
+            .await
+            .map_err(Error::from)?;
+
+        let our_msat = funds
+            .channels
+            .iter()
+            .filter(|channel| channel.connected)
+            .map(|channel| channel.our_amount_msat.msat())
+            .sum();
+
+        Ok(Some(Amount::new(our_msat, CurrencyUnit::Msat)))
+    }
+
     #[instrument(skip(self))]
     async fn check_outgoing_payment(
         &self,
@@ -838,6 +941,58 @@ impl MintPayment for Cln {
             }),
         }
     }
+
+    #[instrument(skip(self))]
+    async fn settle_incoming_payment(
+        &self,
+        payment_identifier: &PaymentIdentifier,
+    ) -> Result<(), Self::Err> {
+        if !self.hold_invoices {
+            return Ok(());
+        }
+
+        let PaymentIdentifier::PaymentHash(payment_hash) = payment_identifier else {
+            return Ok(());
+        };
+
+        // Only invoices created as hold invoices have a stored preimage; once
+        // settled the record is removed so repeated calls are no-ops.
+        let Some(preimage) = self.read_hold_invoice_preimage(payment_hash).await? else {
+            return Ok(());
+        };
+
+        let mut cln_client = self.cln_client().await?;
+
+        match hold_invoice_lookup(&mut cln_client, payment_hash)
+            .await?
+            .state
+        {
+            HoldInvoiceState::Accepted => {
+                let _: serde_json::Value = cln_client
+                    .call_raw(
+                        "holdinvoicesettle",
+                        &serde_json::json!({ "preimage": hex::encode(preimage) }),
+                    )
+                    .await
+                    .map_err(Error::from)?;
+
+                tracing::info!("CLN: Settled hold invoice {}", hex::encode(payment_hash));
+
+                self.remove_hold_invoice(payment_hash).await?;
+            }
+            HoldInvoiceState::Settled | HoldInvoiceState::Canceled => {
+                self.remove_hold_invoice(payment_hash).await?;
+            }
+            HoldInvoiceState::Open => {
+                tracing::debug!(
+                    "CLN: Hold invoice {} has no accepted HTLCs to settle",
+                    hex::encode(payment_hash)
+                );
+            }
+        }
+
+        Ok(())
+    }
 }
 
 impl Cln {
@@ -848,6 +1003,7 @@ impl Cln {
     fn bolt12_quote_payment_hash_key(quote_id: &QuoteId) -> Result<String, Error> {
         match quote_id {
             QuoteId::UUID(uuid) => Ok(uuid.to_string()),
+            QuoteId::ULID(ulid) => Ok(ulid.to_string()),
             QuoteId::BASE64(_) => Err(Error::InvalidQuoteId),
         }
     }
@@ -953,6 +1109,210 @@ impl Cln {
         }
     }
 
+    /// Create a bolt11 hold invoice through the `holdinvoice` plugin
+    ///
+    /// The preimage is generated here and persisted in the KV store before the
+    /// invoice is returned, so the held payment can always be settled later.
+    async fn create_hold_invoice(
+        &self,
+        cln_client: &mut ClnRpc,
+        amount_msat: u64,
+        description: String,
+        expiry: Option<u64>,
+    ) -> Result<CreateIncomingPaymentResponse, payment::Error> {
+        let preimage: [u8; 32] = bitcoin::secp256k1::rand::random();
+        let payment_hash = sha256::Hash::hash(&preimage).to_byte_array();
+
+        self.write_hold_invoice_preimage(&payment_hash, &preimage)
+            .await?;
+
+        let mut params = serde_json::json!({
+            "payment_hash": hex::encode(payment_hash),
+            "amount_msat": amount_msat,
+            "description": description,
+        });
+        if let Some(expiry) = expiry {
+            params["expiry"] = serde_json::Value::from(expiry);
+        }
+        if self.expose_private_channels {
+            params["exposeprivatechannels"] = serde_json::Value::Bool(true);
+        }
+
+        let hold_invoice: HoldInvoiceResponse = cln_client
+            .call_raw("holdinvoice", &params)
+            .await
+            .map_err(Error::from)?;
+
+        let request = Bolt11Invoice::from_str(&hold_invoice.bolt11)?;
+
+        if *request.payment_hash().as_ref() != payment_hash {
+            tracing::error!("CLN: Hold invoice returned with unexpected payment hash");
+            return Err(Error::InvalidHash.into());
+        }
+
+        Ok(CreateIncomingPaymentResponse {
+            request_lookup_id: PaymentIdentifier::PaymentHash(payment_hash),
+            request: request.to_string(),
+            expiry: request.expires_at().map(|t| t.as_secs()),
+            extra_json: None,
+        })
+    }
+
+    /// Stream of payments to hold invoices that have been accepted but not settled
+    ///
+    /// Accepted payments are re-emitted on every poll until the mint settles
+    /// them, which is safe as the mint deduplicates payments by payment id.
+    fn hold_invoice_stream(&self) -> Pin<Box<dyn Stream<Item = Event> + Send>> {
+        futures::stream::unfold(
+            (self.clone(), VecDeque::new()),
+            |(cln, mut pending)| async move {
+                loop {
+                    if let Some(response) = pending.pop_front() {
+                        return Some((Event::PaymentReceived(response), (cln, pending)));
+                    }
+
+                    tokio::select! {
+                        _ = cln.wait_invoice_cancel_token.cancelled() => {
+                            tracing::info!("CLN: Hold invoice stream cancelled");
+                            return None;
+                        }
+                        _ = tokio::time::sleep(HOLD_INVOICE_POLL_INTERVAL) => {}
+                    }
+
+                    match cln.accepted_hold_invoices().await {
+                        Ok(accepted) => pending.extend(accepted),
+                        Err(err) => {
+                            tracing::warn!("CLN: Could not check hold invoices: {}", err);
+                        }
+                    }
+                }
+            },
+        )
+        .boxed()
+    }
+
+    /// Look up all tracked hold invoices and return those with accepted HTLCs
+    async fn accepted_hold_invoices(&self) -> Result<Vec<WaitPaymentResponse>, Error> {
+        let payment_hashes = self
+            .kv_store
+            .kv_list(
+                CLN_KV_PRIMARY_NAMESPACE,
+                CLN_KV_HOLD_INVOICES_SECONDARY_NAMESPACE,
+            )
+            .await
+            .map_err(|e| Error::Database(e.to_string()))?;
+
+        if payment_hashes.is_empty() {
+            return Ok(vec![]);
+        }
+
+        let mut cln_client = self.cln_client().await?;
+        let mut accepted = Vec::new();
+
+        for key in payment_hashes {
+            let payment_hash = match Self::parse_payment_hash(key) {
+                Ok(payment_hash) => payment_hash,
+                Err(err) => {
+                    tracing::warn!("CLN: Skipping malformed hold invoice record: {}", err);
+                    continue;
+                }
+            };
+
+            let lookup = hold_invoice_lookup(&mut cln_client, &payment_hash).await?;
+
+            match lookup.state {
+                HoldInvoiceState::Accepted => match held_payment_response(&payment_hash, &lookup) {
+                    Ok(response) => accepted.push(response),
+                    Err(err) => tracing::warn!(
+                        "CLN: Skipping accepted hold invoice {}: {}",
+                        hex::encode(payment_hash),
+                        err
+                    ),
+                },
+                HoldInvoiceState::Open => (),
+                HoldInvoiceState::Settled | HoldInvoiceState::Canceled => {
+                    tracing::debug!(
+                        "CLN: Hold invoice {} is {:?}, no longer tracking",
+                        hex::encode(payment_hash),
+                        lookup.state
+                    );
+                    self.remove_hold_invoice(&payment_hash).await?;
+                }
+            }
+        }
+
+        Ok(accepted)
+    }
+
+    async fn write_hold_invoice_preimage(
+        &self,
+        payment_hash: &[u8; 32],
+        preimage: &[u8; 32],
+    ) -> Result<(), Error> {
+        let mut tx = self
+            .kv_store
+            .begin_transaction()
+            .await
+            .map_err(|e| Error::Database(e.to_string()))?;
+
+        tx.kv_write(
+            CLN_KV_PRIMARY_NAMESPACE,
+            CLN_KV_HOLD_INVOICES_SECONDARY_NAMESPACE,
+            &hex::encode(payment_hash),
+            hex::encode(preimage).as_bytes(),
+        )
+        .await
+        .map_err(|e| Error::Database(e.to_string()))?;
+        tx.commit()
+            .await
+            .map_err(|e| Error::Database(e.to_string()))?;
+
+        Ok(())
+    }
+
+    async fn read_hold_invoice_preimage(
+        &self,
+        payment_hash: &[u8; 32],
+    ) -> Result<Option<[u8; 32]>, Error> {
+        let Some(stored_preimage) = self
+            .kv_store
+            .kv_read(
+                CLN_KV_PRIMARY_NAMESPACE,
+                CLN_KV_HOLD_INVOICES_SECONDARY_NAMESPACE,
+                &hex::encode(payment_hash),
+            )
+            .await
+            .map_err(|e| Error::Database(e.to_string()))?
+        else {
+            return Ok(None);
+        };
+
+        let preimage = Self::parse_payment_hash(String::from_utf8(stored_preimage)?)?;
+
+        Ok(Some(preimage))
+    }
+
+    async fn remove_hold_invoice(&self, payment_hash: &[u8; 32]) -> Result<(), Error> {
+        let mut tx = self
+            .kv_store
+            .begin_transaction()
+            .await
+            .map_err(|e| Error::Database(e.to_string()))?;
+
+        tx.kv_remove(
+            CLN_KV_PRIMARY_NAMESPACE,
+            CLN_KV_HOLD_INVOICES_SECONDARY_NAMESPACE,
+            &hex::encode(payment_hash),
+        )
+        .await
+        .map_err(|e| Error::Database(e.to_string()))?;
+        tx.commit()
+            .await
+            .map_err(|e| Error::Database(e.to_string()))?;
+
+        Ok(())
+    }
+
     /// Get last pay index for cln
     async fn get_last_pay_index(&self) -> Result<Option<u64>, Error> {
         // First try to read from KV store
@@ -1039,6 +1399,58 @@ enum Bolt12QuotePaymentHashLookup {
     Malformed,
 }
 
+/// Response of the `holdinvoice` plugin RPC
+#[derive(Debug, Deserialize)]
+struct HoldInvoiceResponse {
+    bolt11: String,
+}
+
+/// State of a hold invoice as reported by the `holdinvoice` plugin
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "UPPERCASE")]
+enum HoldInvoiceState {
+    Open,
+    Accepted,
+    Settled,
+    Canceled,
+}
+
+/// Response of the `holdinvoicelookup` plugin RPC
+#[derive(Debug, Deserialize)]
+struct HoldInvoiceLookupResponse {
+    state: HoldInvoiceState,
+    #[serde(default)]
+    amount_msat: Option<u64>,
+}
+
+async fn hold_invoice_lookup(
+    cln_client: &mut ClnRpc,
+    payment_hash: &[u8; 32],
+) -> Result<HoldInvoiceLookupResponse, Error> {
+    Ok(cln_client
+        .call_raw(
+            "holdinvoicelookup",
+            &serde_json::json!({ "payment_hash": hex::encode(payment_hash) }),
+        )
+        .await?)
+}
+
+/// Payment of an accepted hold invoice
+///
+/// Fails with [`Error::UnknownInvoiceAmount`] if the plugin did not report the held amount.
+fn held_payment_response(
+    payment_hash: &[u8; 32],
+    lookup: &HoldInvoiceLookupResponse,
+) -> Result<WaitPaymentResponse, Error> {
+    let amount_msat = lookup.amount_msat.ok_or(Error::UnknownInvoiceAmount)?;
+
+    Ok(WaitPaymentResponse {
+        payment_identifier: PaymentIdentifier::PaymentHash(*payment_hash),
+        payment_amount: Amount::new(amount_msat, CurrencyUnit::Msat),
+        payment_id: hex::encode(payment_hash),
+    })
+}
+
 fn cln_pays_status_to_mint_state(status: ListpaysPaysStatus) -> MeltQuoteState {
     match status {
         ListpaysPaysStatus::PENDING => MeltQuoteState::Pending,
@@ -1383,6 +1795,7 @@ mod tests {
                 percent_fee_reserve: 0.0,
             },
             expose_private_channels: false,
+            hold_invoices: false,
             wait_invoice_cancel_token: CancellationToken::new(),
             wait_invoice_is_active: Arc::new(AtomicBool::new(false)),
             kv_store,
@@ -1624,4 +2037,86 @@ mod tests {
             Some(payment_hash)
         );
     }
+
+    #[tokio::test]
+    async fn settle_incoming_payment_is_noop_without_hold_invoices() {
+        // The unused kv store errors on any access, so this also checks the
+        // kv store is never touched when hold invoices are disabled.
+        test_cln()
+            .settle_incoming_payment(&PaymentIdentifier::PaymentHash([3; 32]))
+            .await
+            .expect("settle must be a no-op when hold invoices are disabled");
+    }
+
+    #[tokio::test]
+    async fn settle_incoming_payment_ignores_untracked_payment_hash() {
+        let cln = test_cln_with_memory_kv().with_hold_invoices(true);
+
+        cln.settle_incoming_payment(&PaymentIdentifier::PaymentHash([4; 32]))
+            .await
+            .expect("untracked payment hash must not be settled");
+        cln.settle_incoming_payment(&PaymentIdentifier::OfferId("offer".to_string()))
+            .await
+            .expect("non payment hash identifiers must not be settled");
+    }
+
+    #[tokio::test]
+    async fn hold_invoice_preimage_round_trips_through_kv() {
+        let cln = test_cln_with_memory_kv();
+        let preimage = [9; 32];
+        let payment_hash = sha256::Hash::hash(&preimage).to_byte_array();
+
+        assert_eq!(
+            cln.read_hold_invoice_preimage(&payment_hash)
+                .await
+                .expect("missing preimage should read"),
+            None
+        );
+
+        cln.write_hold_invoice_preimage(&payment_hash, &preimage)
+            .await
+            .expect("preimage should be written");
+
+        assert_eq!(
+            cln.read_hold_invoice_preimage(&payment_hash)
+                .await
+                .expect("preimage should be read"),
+            Some(preimage)
+        );
+
+        cln.remove_hold_invoice(&payment_hash)
+            .await
+            .expect("hold invoice should be removed");
+
+        assert_eq!(
+            cln.read_hold_invoice_preimage(&payment_hash)
+                .await
+                .expect("removed preimage should read"),
+            None
+        );
+    }
+
+    #[test]
+    fn hold_invoice_lookup_response_parses_plugin_states() {
+        let lookup: HoldInvoiceLookupResponse =
+            serde_json::from_str(r#"{"state":"ACCEPTED","amount_msat":1000}"#)
+                .expect("accepted lookup should parse");
+
+        assert_eq!(lookup.state, HoldInvoiceState::Accepted);
+        assert_eq!(
+            held_payment_response(&[5; 32], &lookup)
+                .expect("accepted lookup has an amount")
+                .payment_amount,
+            Amount::new(1000, CurrencyUnit::Msat)
+        );
+
+        let lookup: HoldInvoiceLookupResponse =
+            serde_json::from_str(r#"{"state":"CANCELED"}"#).expect("canceled lookup should parse");
+
+        assert_eq!(lookup.state, HoldInvoiceState::Canceled);
+        assert!(matches!(
+            held_payment_response(&[5; 32], &lookup),
+            Err(Error::UnknownInvoiceAmount)
+        ));
+    }
 }
@@ -6,6 +6,7 @@ use std::ops::Deref;
 use std::sync::Arc;
 
 use async_trait::async_trait;
+use jsonwebtoken::errors::ErrorKind;
 use jsonwebtoken::jwk::{AlgorithmParameters, JwkSet};
 use jsonwebtoken::{decode, decode_header, DecodingKey, Validation};
 use serde::Deserialize;
@@ -15,6 +16,7 @@ use thiserror::Error;
 use tokio::sync::RwLock;
 use tracing::instrument;
 
+use crate::clock::{Clock, SystemClock};
 use crate::{HttpClient, HttpError};
 
 fn validate_client_id_claim(
@@ -40,6 +42,26 @@ fn validate_client_id_claim(
     Ok(())
 }
 
+/// Check the `exp` claim against `now`, allowing `leeway` seconds of clock skew
+fn validate_exp_claim(
+    claims: &HashMap<String, serde_json::Value>,
+    now: u64,
+    leeway: u64,
+) -> Result<(), Error> {
+    let exp = claims
+        .get("exp")
+        .and_then(serde_json::Value::as_u64)
+        .ok_or_else(|| {
+            jsonwebtoken::errors::Error::from(ErrorKind::MissingRequiredClaim("exp".to_string()))
+        })?;
+
+    if exp.saturating_add(leeway) < now {
+        return Err(jsonwebtoken::errors::Error::from(ErrorKind::ExpiredSignature).into());
+    }
+
+    Ok(())
+}
+
 fn validate_client_id_claims(
     claims: &HashMap<String, serde_json::Value>,
     client_id: &str,
@@ -189,6 +211,7 @@ pub struct OidcClient {
     client_id: Option<String>,
     oidc_config: Arc<RwLock<Option<OidcConfig>>>,
     jwks_set: Arc<RwLock<Option<JwkSet>>>,
+    clock: Arc<dyn Clock>,
 }
 
 /// OAuth2 grant type
@@ -250,9 +273,16 @@ impl OidcClient {
             client_id,
             oidc_config: Arc::new(RwLock::new(None)),
             jwks_set: Arc::new(RwLock::new(None)),
+            clock: Arc::new(SystemClock),
         }
     }
 
+    /// Check token expiry against `clock` instead of the system time
+    pub fn with_clock(mut self, clock: Arc<dyn Clock>) -> Self {
+        self.clock = clock;
+        self
+    }
+
     /// Get client id
     pub fn client_id(&self) -> Option<String> {
         self.client_id.clone()
@@ -291,6 +321,12 @@ impl OidcClient {
     /// Verify cat token
     #[instrument(skip_all)]
     pub async fn verify_cat(&self, cat_jwt: &str) -> Result<(), Error> {
+        self.verify_cat_subject(cat_jwt).await.map(|_| ())
+    }
+
+    /// Verify cat token and return its `sub` claim
+    #[instrument(skip_all)]
+    pub async fn verify_cat_subject(&self, cat_jwt: &str) -> Result<Option<String>, Error> {
         tracing::debug!("Verifying cat");
         let header = decode_header(cat_jwt)?;
 
@@ -339,7 +375,8 @@ impl OidcClient {
 
         let validation = {
             let mut validation = Validation::new(header.alg);
-            validation.validate_exp = true;
+            // Expiry is checked against `self.clock` once the token is decoded
+            validation.validate_exp = false;
             validation.validate_aud = false;
             validation.set_issuer(&[oidc_config.issuer]);
             validation
@@ -347,18 +384,24 @@ impl OidcClient {
 
         match decode::<HashMap<String, serde_json::Value>>(cat_jwt, &decoding_key, &validation) {
             Ok(claims) => {
+                validate_exp_claim(&claims.claims, self.clock.unix_time(), validation.leeway)?;
+
                 tracing::debug!("Successfully verified cat");
                 if let Some(client_id) = &self.client_id {
                     validate_client_id_claims(&claims.claims, client_id)?;
                 }
+
+                Ok(claims
+                    .claims
+                    .get("sub")
+                    .and_then(|sub| sub.as_str())
+                    .map(str::to_string))
             }
             Err(err) => {
                 tracing::debug!("Could not verify cat: {}", err);
-                return Err(err.into());
+                Err(err.into())
             }
         }
-
-        Ok(())
     }
 
     /// POST form-encoded parameters and parse a JSON response.
@@ -419,6 +462,24 @@ mod tests {
         serde_json::from_value(value).expect("claims should be an object")
     }
 
+    #[test]
+    fn validate_exp_claim_uses_leeway() {
+        let claims = claims(json!({
+            "exp": 1_000,
+        }));
+
+        assert!(validate_exp_claim(&claims, 1_000, 0).is_ok());
+        assert!(validate_exp_claim(&claims, 1_060, 60).is_ok());
+        assert!(matches!(
+            validate_exp_claim(&claims, 1_061, 60),
+            Err(Error::Jwt(err)) if matches!(err.kind(), ErrorKind::ExpiredSignature)
+        ));
+        assert!(matches!(
+            validate_exp_claim(&HashMap::new(), 0, 60),
+            Err(Error::Jwt(_))
+        ));
+    }
+
     #[test]
     fn validate_client_id_claims_accepts_client_id() {
         let claims = claims(json!({
@@ -0,0 +1,172 @@
+//! Clocks
+//!
+//! Code that depends on the current time, like quote expiry, clear auth token expiry and
+//! background sweepers, reads it from a [`Clock`] so tests can swap the [`SystemClock`] for a
+//! [`SimulatedClock`] and fast-forward time instead of sleeping.
+
+use std::fmt::Debug;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
+
+use async_trait::async_trait;
+use tokio::sync::Notify;
+
+use crate::util::unix_time;
+
+/// Source of the current time
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+pub trait Clock: Debug + Send + Sync {
+    /// Current unix time in seconds
+    fn unix_time(&self) -> u64;
+
+    /// Wait until `duration` has passed on this clock
+    async fn sleep(&self, duration: Duration);
+}
+
+/// Clock reading the system time
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SystemClock;
+
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+impl Clock for SystemClock {
+    fn unix_time(&self) -> u64 {
+        unix_time()
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    async fn sleep(&self, duration: Duration) {
+        tokio::time::sleep(duration).await
+    }
+
+    #[cfg(target_arch = "wasm32")]
+    async fn sleep(&self, duration: Duration) {
+        gloo_timers::future::sleep(duration).await
+    }
+}
+
+/// Clock that only moves when told to
+///
+/// [`Clock::sleep`] returns once the clock has been advanced past the end of the sleep, so a test
+/// can trigger every delay and expiry up to a point in time with a single
+/// [`SimulatedClock::advance`].
+#[derive(Debug, Default)]
+pub struct SimulatedClock {
+    /// Current time in unix milliseconds
+    now_ms: AtomicU64,
+    advanced: Notify,
+}
+
+impl SimulatedClock {
+    /// Create a new [`SimulatedClock`] starting at `unix_time` seconds
+    pub fn new(unix_time: u64) -> Self {
+        Self {
+            now_ms: AtomicU64::new(unix_time.saturating_mul(1_000)),
+            advanced: Notify::new(),
+        }
+    }
+
+    /// Create a new [`SimulatedClock`] starting at the current system time
+    pub fn starting_now() -> Self {
+        Self::new(unix_time())
+    }
+
+    /// Move the clock forward by `duration`, waking sleepers that are done
+    pub fn advance(&self, duration: Duration) {
+        let millis = u64::try_from(duration.as_millis()).unwrap_or(u64::MAX);
+        self.now_ms.fetch_add(millis, Ordering::SeqCst);
+        self.advanced.notify_waiters();
+    }
+
+    /// Set the clock to `unix_time` seconds
+    ///
+    /// The clock may be set back, sleepers then keep waiting until it reaches their deadline again.
+    pub fn set(&self, unix_time: u64) {
+        self.now_ms
+            .store(unix_time.saturating_mul(1_000), Ordering::SeqCst);
+        self.advanced.notify_waiters();
+    }
+
+    fn now_ms(&self) -> u64 {
+        self.now_ms.load(Ordering::SeqCst)
+    }
+}
+
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+impl Clock for SimulatedClock {
+    fn unix_time(&self) -> u64 {
+        self.now_ms() / 1_000
+    }
+
+    async fn sleep(&self, duration: Duration) {
+        let millis = u64::try_from(duration.as_millis()).unwrap_or(u64::MAX);
+        let deadline = self.now_ms().saturating_add(millis);
+
+        loop {
+            let advanced = self.advanced.notified();
+            tokio::pin!(advanced);
+            // Register before checking the time so an advance in between is not missed
+            advanced.as_mut().enable();
+
+            if self.now_ms() >= deadline {
+                return;
+            }
+
+            advanced.await;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use super::*;
+
+    #[test]
+    fn test_simulated_clock_advance_and_set() {
+        let clock = SimulatedClock::new(1_000);
+        assert_eq!(clock.unix_time(), 1_000);
+
+        clock.advance(Duration::from_millis(1_500));
+        assert_eq!(clock.unix_time(), 1_001);
+
+        clock.advance(Duration::from_millis(500));
+        assert_eq!(clock.unix_time(), 1_002);
+
+        clock.set(10);
+        assert_eq!(clock.unix_time(), 10);
+    }
+
+    #[tokio::test]
+    async fn test_simulated_clock_sleep() {
+        let clock = Arc::new(SimulatedClock::new(1_000));
+
+        let sleeper = {
+            let clock = clock.clone();
+            tokio::spawn(async move { clock.sleep(Duration::from_secs(60)).await })
+        };
+        // Let the sleeper take its deadline before moving the clock
+        tokio::task::yield_now().await;
+
+        clock.advance(Duration::from_secs(30));
+        tokio::task::yield_now().await;
+        assert!(!sleeper.is_finished());
+
+        clock.advance(Duration::from_secs(30));
+        tokio::time::timeout(Duration::from_secs(5), sleeper)
+            .await
+            .expect("sleeper woken by advance")
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_simulated_clock_zero_sleep() {
+        let clock = SimulatedClock::new(0);
+        tokio::time::timeout(Duration::from_secs(5), clock.sleep(Duration::ZERO))
+            .await
+            .expect("zero sleep returns immediately");
+    }
+}
@@ -196,6 +196,51 @@ impl Default for QuoteTTL {
     }
 }
 
+/// Message of the day shown during a time window
+#[derive(Debug, Clone, Hash, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ScheduledMotd {
+    /// Message of the day
+    pub motd: String,
+    /// Unix timestamp the message becomes active, immediately if unset
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub start: Option<u64>,
+    /// Unix timestamp the message stops being shown, never if unset
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub end: Option<u64>,
+}
+
+impl ScheduledMotd {
+    /// Create new [`ScheduledMotd`]
+    pub fn new(motd: String, start: Option<u64>, end: Option<u64>) -> Result<Self, Error> {
+        let scheduled = Self { motd, start, end };
+        scheduled.validate()?;
+        Ok(scheduled)
+    }
+
+    /// Check that the window is not empty
+    pub fn validate(&self) -> Result<(), Error> {
+        if let (Some(start), Some(end)) = (self.start, self.end) {
+            if start >= end {
+                return Err(Error::Custom(format!(
+                    "MOTD schedule start {start} must be before end {end}"
+                )));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Whether the message should be shown at `now`
+    pub fn is_active(&self, now: u64) -> bool {
+        self.start.is_none_or(|start| start <= now) && self.end.is_none_or(|end| now < end)
+    }
+
+    /// Whether the window has passed at `now`
+    pub fn is_expired(&self, now: u64) -> bool {
+        self.end.is_some_and(|end| end <= now)
+    }
+}
+
 /// Mint Fee Reserve
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct FeeReserve {
@@ -318,7 +363,7 @@ impl<'de> Deserialize<'de> for IssuerVersion {
 mod tests {
     use std::str::FromStr;
 
-    use super::FinalizedMelt;
+    use super::{FinalizedMelt, ScheduledMotd};
     use crate::nuts::{Id, Proof, PublicKey};
     use crate::secret::Secret;
     use crate::Amount;
@@ -495,4 +540,22 @@ mod tests {
 
         assert_eq!(v1.partial_cmp(&v2), None);
     }
+
+    #[test]
+    fn test_scheduled_motd_window() {
+        let motd = ScheduledMotd::new("maintenance".to_string(), Some(100), Some(200)).unwrap();
+
+        assert!(!motd.is_active(99));
+        assert!(motd.is_active(100));
+        assert!(motd.is_active(199));
+        assert!(!motd.is_active(200));
+        assert!(!motd.is_expired(199));
+        assert!(motd.is_expired(200));
+
+        let open_ended = ScheduledMotd::new("hello".to_string(), None, None).unwrap();
+        assert!(open_ended.is_active(0));
+        assert!(!open_ended.is_expired(u64::MAX));
+
+        assert!(ScheduledMotd::new("bad".to_string(), Some(200), Some(100)).is_err());
+    }
 }
@@ -95,6 +95,32 @@ pub trait KVStoreTransaction<Error>: DbTransactionFinalizer<Err = Error> {
         key: &str,
     ) -> Result<(), Error>;
 
+    /// Write value to key-value store if the key still holds `expected`
+    ///
+    /// `None` expects the key to be absent. Returns whether the value was written. The default
+    /// reads and writes separately; backends override it with a single conditional statement so
+    /// that of two concurrent writers expecting the same value only one succeeds.
+    async fn kv_compare_and_write(
+        &mut self,
+        primary_namespace: &str,
+        secondary_namespace: &str,
+        key: &str,
+        expected: Option<&[u8]>,
+        value: &[u8],
+    ) -> Result<bool, Error> {
+        let current = self
+            .kv_read(primary_namespace, secondary_namespace, key)
+            .await?;
+        if current.as_deref() != expected {
+            return Ok(false);
+        }
+
+        self.kv_write(primary_namespace, secondary_namespace, key, value)
+            .await?;
+
+        Ok(true)
+    }
+
     /// List keys in a namespace
     async fn kv_list(
         &mut self,
@@ -0,0 +1,43 @@
+//! Schema migration bookkeeping
+
+/// State of a schema migration known to this build
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MigrationState {
+    /// Applied and, if a checksum was recorded, unchanged since
+    Applied,
+    /// Not applied yet
+    Pending,
+    /// Not applied yet, although a later migration already is
+    OutOfOrder,
+    /// Applied, but the migration changed since it was applied
+    ChecksumMismatch,
+}
+
+impl MigrationState {
+    /// Whether the database diverged from the migrations of this build
+    ///
+    /// Migrating a diverged database could corrupt data, so migrations are aborted instead.
+    pub fn is_divergent(&self) -> bool {
+        matches!(self, Self::OutOfOrder | Self::ChecksumMismatch)
+    }
+}
+
+impl std::fmt::Display for MigrationState {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Applied => write!(f, "applied"),
+            Self::Pending => write!(f, "pending"),
+            Self::OutOfOrder => write!(f, "pending out of order"),
+            Self::ChecksumMismatch => write!(f, "checksum mismatch"),
+        }
+    }
+}
+
+/// Status of a single schema migration
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MigrationStatus {
+    /// Migration name
+    pub name: String,
+    /// Migration state
+    pub state: MigrationState,
+}
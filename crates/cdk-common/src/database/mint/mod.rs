@@ -7,7 +7,7 @@ use async_trait::async_trait;
 use cashu::quote_id::QuoteId;
 use cashu::Amount;
 
-use super::{DbTransactionFinalizer, Error};
+use super::{DbTransactionFinalizer, Error, MigrationStatus};
 use crate::mint::{
     self, MeltQuote, MintKeySetInfo, MintQuote as MintMintQuote, Operation, ProofsWithState,
 };
@@ -367,6 +367,28 @@ pub trait QuotesDatabase {
     ) -> Result<Option<mint::MeltQuote>, Self::Err>;
     /// Get all [`mint::MeltQuote`]s
     async fn get_melt_quotes(&self) -> Result<Vec<mint::MeltQuote>, Self::Err>;
+
+    /// Get up to `limit` mint quote payments received in `from..to`, skipping the first `offset`
+    ///
+    /// Payments are ordered by time, so consecutive pages do not overlap.
+    async fn get_mint_quote_payments_in_range(
+        &self,
+        from: u64,
+        to: u64,
+        offset: u64,
+        limit: u64,
+    ) -> Result<Vec<mint::MintQuotePayment>, Self::Err>;
+
+    /// Get up to `limit` [`mint::MeltQuote`]s paid in `from..to`, skipping the first `offset`
+    ///
+    /// Quotes are ordered by paid time, so consecutive pages do not overlap.
+    async fn get_melt_quotes_paid_in_range(
+        &self,
+        from: u64,
+        to: u64,
+        offset: u64,
+        limit: u64,
+    ) -> Result<Vec<mint::MeltQuote>, Self::Err>;
 }
 
 /// Mint Proof Transaction trait
@@ -390,13 +412,21 @@ pub trait ProofsTransaction {
     ///
     /// Also updates the `state` field on the [`ProofsWithState`] wrapper to reflect
     /// the new state after the database update succeeds.
+    ///
+    /// Proofs are only updated while they are still in the state they were acquired in.
+    /// A proof that was spent in the meantime fails with `AttemptUpdateSpentProof`, any
+    /// other concurrent change with `ConcurrentUpdate`. The update is not retried: the
+    /// transaction is rolled back and the error reaches the wallet, which may resubmit.
     async fn update_proofs_state(
         &mut self,
         proofs: &mut Acquired<ProofsWithState>,
         new_state: State,
     ) -> Result<(), Self::Err>;
 
-    /// get proofs states
+    /// Get proofs together with their current state
+    ///
+    /// The proofs are locked for the rest of the transaction where the database supports it,
+    /// [`Self::update_proofs_state`] still verifies the state is current.
     async fn get_proofs(
         &mut self,
         ys: &[PublicKey],
@@ -420,6 +450,17 @@ pub trait ProofsTransaction {
         &mut self,
         operation_id: &uuid::Uuid,
     ) -> Result<Vec<PublicKey>, Self::Err>;
+
+    /// Move up to `limit` proofs spent before `spent_before` into the spent proof archive and
+    /// return how many were moved.
+    ///
+    /// The archive only keeps the `Y`, keyset and amount of each proof. Archived proofs must
+    /// still be reported as spent and rejected by `add_proofs`.
+    async fn archive_spent_proofs(
+        &mut self,
+        spent_before: u64,
+        limit: u64,
+    ) -> Result<u64, Self::Err>;
 }
 
 /// Mint Proof Database trait
@@ -574,6 +615,22 @@ pub trait CompletedOperationsTransaction {
         operation: &mint::Operation,
         fee_by_keyset: &std::collections::HashMap<crate::nuts::Id, crate::Amount>,
     ) -> Result<(), Self::Err>;
+
+    /// Add an entry to the fee ledger
+    ///
+    /// Input fees passed to [`Self::add_completed_operation`] are recorded in the fee
+    /// ledger already, this is for Lightning fee surplus and sweeps.
+    async fn add_fee_ledger_entry(&mut self, entry: &mint::FeeLedgerEntry)
+        -> Result<(), Self::Err>;
+
+    /// Get the accumulated fee income of `unit`
+    ///
+    /// The fee ledger of the unit stays locked until the transaction ends, so a sweep recorded
+    /// in the same transaction cannot race another one.
+    async fn get_fee_totals_for_update(
+        &mut self,
+        unit: &CurrencyUnit,
+    ) -> Result<mint::FeeTotals, Self::Err>;
 }
 
 #[async_trait]
@@ -596,6 +653,20 @@ pub trait CompletedOperationsDatabase {
 
     /// Get all completed operations
     async fn get_completed_operations(&self) -> Result<Vec<mint::Operation>, Self::Err>;
+
+    /// Get up to `limit` operations completed in `from..to`, skipping the first `offset`
+    ///
+    /// Operations are ordered by completion time, so consecutive pages do not overlap.
+    async fn get_completed_operations_in_range(
+        &self,
+        from: u64,
+        to: u64,
+        offset: u64,
+        limit: u64,
+    ) -> Result<Vec<mint::Operation>, Self::Err>;
+
+    /// Get the accumulated fee income per unit
+    async fn get_fee_totals(&self) -> Result<HashMap<CurrencyUnit, mint::FeeTotals>, Self::Err>;
 }
 
 /// Base database writer
@@ -622,6 +693,18 @@ pub trait Database<Error>:
 {
     /// Begins a transaction
     async fn begin_transaction(&self) -> Result<Box<dyn Transaction<Error> + Send + Sync>, Error>;
+
+    /// Schema migrations applied to the database that this build does not know about
+    ///
+    /// A non-empty list means the database was last used by a newer version of the mint.
+    async fn unknown_migrations(&self) -> Result<Vec<String>, Error> {
+        Ok(Vec::new())
+    }
+
+    /// Status of every schema migration known to this build, in the order they are applied
+    async fn migration_status(&self) -> Result<Vec<MigrationStatus>, Error> {
+        Ok(Vec::new())
+    }
 }
 
 /// Type alias for Mint Database
@@ -1926,3 +1926,47 @@ where
     assert_eq!(quotes[1].as_ref().unwrap().id, quote1.id);
     tx.commit().await.unwrap();
 }
+
+/// Completed operations are read back with their unit and paged by completion time
+pub async fn get_completed_operations_in_range<DB>(db: DB)
+where
+    DB: Database<Error> + KeysDatabase<Err = Error>,
+{
+    let mut tx = Database::begin_transaction(&db).await.unwrap();
+    for completed_at in [100, 200, 300] {
+        let operation = Operation::new(
+            uuid::Uuid::now_v7(),
+            crate::mint::OperationKind::Swap,
+            Amount::from(10u64),
+            Amount::from(11u64),
+            Amount::from(1u64),
+            Some(completed_at),
+            None,
+        )
+        .with_unit(CurrencyUnit::Usd);
+        tx.add_completed_operation(&operation, &Default::default())
+            .await
+            .unwrap();
+    }
+    tx.commit().await.unwrap();
+
+    let first = db
+        .get_completed_operations_in_range(150, u64::MAX, 0, 1)
+        .await
+        .unwrap();
+    let second = db
+        .get_completed_operations_in_range(150, u64::MAX, 1, 1)
+        .await
+        .unwrap();
+    let rest = db
+        .get_completed_operations_in_range(150, u64::MAX, 2, 1)
+        .await
+        .unwrap();
+
+    assert_eq!(first.len(), 1);
+    assert_eq!(*first[0].completed_at(), Some(200));
+    assert_eq!(first[0].unit(), Some(&CurrencyUnit::Usd));
+    assert_eq!(second.len(), 1);
+    assert_eq!(*second[0].completed_at(), Some(300));
+    assert!(rest.is_empty());
+}
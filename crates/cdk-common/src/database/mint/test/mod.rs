@@ -144,6 +144,42 @@ where
     }
 }
 
+/// Test that KV compare and write only writes over the expected value
+pub async fn kvstore_compare_and_write<DB>(db: DB)
+where
+    DB: Database<crate::database::Error> + KVStoreDatabase<Err = crate::database::Error>,
+{
+    let (primary, secondary, key) = ("test_namespace", "sub_namespace", "counter");
+
+    let mut tx = Database::begin_transaction(&db).await.unwrap();
+    assert!(tx
+        .kv_compare_and_write(primary, secondary, key, None, b"1")
+        .await
+        .unwrap());
+    tx.commit().await.unwrap();
+
+    // A writer that still expects the key to be absent or holds a stale value loses
+    let mut tx = Database::begin_transaction(&db).await.unwrap();
+    assert!(!tx
+        .kv_compare_and_write(primary, secondary, key, None, b"2")
+        .await
+        .unwrap());
+    assert!(!tx
+        .kv_compare_and_write(primary, secondary, key, Some(b"0"), b"2")
+        .await
+        .unwrap());
+    assert!(tx
+        .kv_compare_and_write(primary, secondary, key, Some(b"1"), b"2")
+        .await
+        .unwrap());
+    tx.commit().await.unwrap();
+
+    assert_eq!(
+        db.kv_read(primary, secondary, key).await.unwrap(),
+        Some(b"2".to_vec())
+    );
+}
+
 static COUNTER: AtomicU64 = AtomicU64::new(0);
 
 /// Returns a unique, random-looking Base62 string (no external crates).
@@ -192,6 +228,7 @@ macro_rules! mint_db_test {
             add_and_find_proofs,
             add_duplicate_proofs,
             kvstore_functionality,
+            kvstore_compare_and_write,
             add_mint_quote,
             add_mint_quote_only_once,
             register_payments,
@@ -261,12 +298,15 @@ macro_rules! mint_db_test {
             get_blind_signatures_in_transaction,
             reject_duplicate_payment_ids,
             remove_spent_proofs_should_fail,
+            archive_spent_proofs,
             get_proofs_with_inconsistent_states_fails,
             get_proofs_fails_when_some_not_found,
             update_proofs_state_updates_proofs_with_state,
+            update_proofs_state_rejects_stale_state,
             get_mint_quotes_by_ids,
             get_melt_quotes_by_request_lookup_id,
             lock_melt_quote_and_related,
+            get_completed_operations_in_range,
         );
     };
     ($make_db_fn:ident, $($name:ident),+ $(,)?) => {
@@ -314,6 +314,73 @@ where
     tx.commit().await.unwrap();
 }
 
+/// Test that update_proofs_state rejects proofs that changed since they were acquired
+pub async fn update_proofs_state_rejects_stale_state<DB>(db: DB)
+where
+    DB: Database<Error> + KeysDatabase<Err = Error>,
+{
+    use cashu::State;
+
+    let keyset_id = setup_keyset(&db).await;
+
+    let proofs = vec![
+        Proof {
+            amount: Amount::from(100),
+            keyset_id,
+            secret: Secret::generate(),
+            c: SecretKey::generate().public_key(),
+            witness: None,
+            dleq: None,
+            p2pk_e: None,
+        },
+        Proof {
+            amount: Amount::from(200),
+            keyset_id,
+            secret: Secret::generate(),
+            c: SecretKey::generate().public_key(),
+            witness: None,
+            dleq: None,
+            p2pk_e: None,
+        },
+    ];
+
+    let ys: Vec<_> = proofs.iter().map(|p| p.y().unwrap()).collect();
+
+    let mut tx = Database::begin_transaction(&db).await.unwrap();
+    tx.add_proofs(
+        proofs,
+        None,
+        &Operation::new_swap(Amount::ZERO, Amount::ZERO, Amount::ZERO),
+    )
+    .await
+    .unwrap();
+    tx.commit().await.unwrap();
+
+    // Two snapshots of the same unspent proof, the first one reserves it
+    let mut tx = Database::begin_transaction(&db).await.unwrap();
+    let mut first = tx.get_proofs(&ys[0..1]).await.unwrap();
+    let mut stale = tx.get_proofs(&ys[0..1]).await.unwrap();
+    tx.update_proofs_state(&mut first, State::Pending)
+        .await
+        .unwrap();
+
+    let result = tx.update_proofs_state(&mut stale, State::Pending).await;
+    assert!(matches!(result, Err(Error::ConcurrentUpdate)));
+    assert_eq!(stale.state, State::Unspent);
+
+    // Once spent, the stale snapshot reports the proof as spent
+    tx.update_proofs_state(&mut first, State::Spent)
+        .await
+        .unwrap();
+    let result = tx.update_proofs_state(&mut stale, State::Pending).await;
+    assert!(matches!(result, Err(Error::AttemptUpdateSpentProof)));
+    tx.commit().await.unwrap();
+
+    // Proofs that were not touched concurrently are unaffected
+    let states = db.get_proofs_states(&ys).await.unwrap();
+    assert_eq!(states, vec![Some(State::Spent), Some(State::Unspent)]);
+}
+
 /// Test removing proofs
 pub async fn remove_proofs<DB>(db: DB)
 where
@@ -1041,3 +1108,82 @@ where
 
     tx.rollback().await.unwrap();
 }
+
+/// Test that spent proofs are archived by their spend time and stay spent
+pub async fn archive_spent_proofs<DB>(db: DB)
+where
+    DB: Database<Error> + KeysDatabase<Err = Error>,
+{
+    use cashu::State;
+
+    let keyset_id = setup_keyset(&db).await;
+
+    let proofs: Vec<Proof> = (0..3)
+        .map(|_| Proof {
+            amount: Amount::from(100),
+            keyset_id,
+            secret: Secret::generate(),
+            c: SecretKey::generate().public_key(),
+            witness: None,
+            dleq: None,
+            p2pk_e: None,
+        })
+        .collect();
+
+    let ys: Vec<_> = proofs.iter().map(|p| p.y().unwrap()).collect();
+
+    let mut tx = Database::begin_transaction(&db).await.unwrap();
+    tx.add_proofs(
+        proofs.clone(),
+        None,
+        &Operation::new_swap(Amount::ZERO, Amount::ZERO, Amount::ZERO),
+    )
+    .await
+    .unwrap();
+    tx.commit().await.unwrap();
+
+    // Proofs created before the cutoff but spent after it are kept
+    std::thread::sleep(std::time::Duration::from_millis(1_100));
+    let spent_before = crate::util::unix_time();
+
+    // Spend the first two proofs, leave the third unspent
+    let mut tx = Database::begin_transaction(&db).await.unwrap();
+    let mut records = tx.get_proofs(&ys[..2]).await.unwrap();
+    tx.update_proofs_state(&mut records, State::Pending)
+        .await
+        .unwrap();
+    tx.update_proofs_state(&mut records, State::Spent)
+        .await
+        .unwrap();
+    tx.commit().await.unwrap();
+
+    // Nothing was spent before the cutoff
+    let mut tx = Database::begin_transaction(&db).await.unwrap();
+    assert_eq!(tx.archive_spent_proofs(spent_before, 10).await.unwrap(), 0);
+    tx.commit().await.unwrap();
+
+    let spent_before = crate::util::unix_time() + 1;
+    let mut tx = Database::begin_transaction(&db).await.unwrap();
+    assert_eq!(tx.archive_spent_proofs(spent_before, 1).await.unwrap(), 1);
+    assert_eq!(tx.archive_spent_proofs(spent_before, 10).await.unwrap(), 1);
+    tx.commit().await.unwrap();
+
+    // Archived proofs are no longer stored but are still spent
+    assert!(db.get_proofs_by_ys(&ys[..2]).await.unwrap()[0].is_none());
+    assert_eq!(
+        db.get_proofs_states(&ys).await.unwrap(),
+        vec![Some(State::Spent), Some(State::Spent), Some(State::Unspent)]
+    );
+
+    // Adding an archived proof again is a double spend
+    let mut tx = Database::begin_transaction(&db).await.unwrap();
+    let result = tx
+        .add_proofs(
+            vec![proofs[0].clone()],
+            None,
+            &Operation::new_swap(Amount::ZERO, Amount::ZERO, Amount::ZERO),
+        )
+        .await;
+    assert!(matches!(result, Err(Error::AttemptUpdateSpentProof)));
+    tx.rollback().await.unwrap();
+}
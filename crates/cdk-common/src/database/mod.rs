@@ -1,6 +1,7 @@
 //! CDK Database
 
 mod kvstore;
+mod migration;
 
 #[cfg(feature = "mint")]
 pub mod mint;
@@ -12,6 +13,7 @@ pub use kvstore::{
     validate_kvstore_params, validate_kvstore_string, KVStore, KVStoreDatabase, KVStoreTransaction,
     KVSTORE_NAMESPACE_KEY_ALPHABET, KVSTORE_NAMESPACE_KEY_MAX_LEN,
 };
+pub use migration::{MigrationState, MigrationStatus};
 
 /// Arc-wrapped KV store for shared ownership
 pub type DynKVStore = std::sync::Arc<dyn KVStore<Err = Error> + Send + Sync>;
@@ -28,7 +30,10 @@ pub use mint::{
 #[cfg(feature = "mint")]
 pub use mint::{DynMintAuthDatabase, MintAuthDatabase, MintAuthTransaction};
 #[cfg(feature = "wallet")]
-pub use wallet::Database as WalletDatabase;
+pub use wallet::{
+    Database as WalletDatabase, DatabaseExt as WalletDatabaseExt,
+    DatabaseTransaction as WalletDatabaseTransaction,
+};
 
 /// Data conversion error
 #[derive(thiserror::Error, Debug)]
@@ -220,6 +225,10 @@ pub enum Error {
     /// Concurrent update detected
     #[error("Concurrent update detected")]
     ConcurrentUpdate,
+
+    /// The database diverged from the migrations of this build
+    #[error("Database diverged from migrations: {0}")]
+    MigrationDiverged(String),
 }
 
 #[cfg(feature = "mint")]
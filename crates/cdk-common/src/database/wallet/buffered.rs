@@ -0,0 +1,191 @@
+//! Transactions for backends without native transactions
+
+use std::sync::Arc;
+
+use async_trait::async_trait;
+
+use super::{Database, DatabaseTransaction};
+use crate::database::{DbTransactionFinalizer, Error};
+use crate::nuts::{Id, PublicKey, State};
+use crate::wallet::{self, MintQuote as WalletMintQuote, ProofInfo, Transaction};
+
+#[derive(Debug)]
+enum BufferedWrite {
+    UpdateProofs {
+        added: Vec<ProofInfo>,
+        removed_ys: Vec<PublicKey>,
+    },
+    UpdateProofsState {
+        ys: Vec<PublicKey>,
+        state: State,
+    },
+    AddTransaction(Transaction),
+    AddMintQuote(WalletMintQuote),
+    AddMeltQuote(wallet::MeltQuote),
+    KvWrite {
+        primary_namespace: String,
+        secondary_namespace: String,
+        key: String,
+        value: Vec<u8>,
+    },
+    KvRemove {
+        primary_namespace: String,
+        secondary_namespace: String,
+        key: String,
+    },
+}
+
+/// [`DatabaseTransaction`] for backends that cannot group writes natively
+///
+/// Writes are buffered and applied in order on commit, so nothing is written if the transaction
+/// is rolled back or dropped. A failure part way through the commit is not undone. Keyset counters
+/// are incremented immediately, since skipping counter values is harmless while reusing them is
+/// not.
+pub struct BufferedTransaction<Err> {
+    db: Arc<dyn Database<Err> + Send + Sync>,
+    writes: Vec<BufferedWrite>,
+}
+
+impl<Err> std::fmt::Debug for BufferedTransaction<Err> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("BufferedTransaction")
+            .field("writes", &self.writes)
+            .finish()
+    }
+}
+
+impl<Err> BufferedTransaction<Err>
+where
+    Err: Into<Error> + From<Error>,
+{
+    /// Buffer writes to `db`
+    pub fn new(db: Arc<dyn Database<Err> + Send + Sync>) -> Self {
+        Self {
+            db,
+            writes: Vec::new(),
+        }
+    }
+}
+
+#[async_trait]
+impl<Err> DbTransactionFinalizer for BufferedTransaction<Err>
+where
+    Err: Into<Error> + From<Error> + Send + Sync,
+{
+    type Err = Err;
+
+    async fn commit(self: Box<Self>) -> Result<(), Err> {
+        for write in self.writes {
+            match write {
+                BufferedWrite::UpdateProofs { added, removed_ys } => {
+                    self.db.update_proofs(added, removed_ys).await?
+                }
+                BufferedWrite::UpdateProofsState { ys, state } => {
+                    self.db.update_proofs_state(ys, state).await?
+                }
+                BufferedWrite::AddTransaction(transaction) => {
+                    self.db.add_transaction(transaction).await?
+                }
+                BufferedWrite::AddMintQuote(quote) => self.db.add_mint_quote(quote).await?,
+                BufferedWrite::AddMeltQuote(quote) => self.db.add_melt_quote(quote).await?,
+                BufferedWrite::KvWrite {
+                    primary_namespace,
+                    secondary_namespace,
+                    key,
+                    value,
+                } => {
+                    self.db
+                        .kv_write(&primary_namespace, &secondary_namespace, &key, &value)
+                        .await?
+                }
+                BufferedWrite::KvRemove {
+                    primary_namespace,
+                    secondary_namespace,
+                    key,
+                } => {
+                    self.db
+                        .kv_remove(&primary_namespace, &secondary_namespace, &key)
+                        .await?
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    async fn rollback(self: Box<Self>) -> Result<(), Err> {
+        Ok(())
+    }
+}
+
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+impl<Err> DatabaseTransaction<Err> for BufferedTransaction<Err>
+where
+    Err: Into<Error> + From<Error> + Send + Sync,
+{
+    async fn update_proofs(
+        &mut self,
+        added: Vec<ProofInfo>,
+        removed_ys: Vec<PublicKey>,
+    ) -> Result<(), Err> {
+        self.writes
+            .push(BufferedWrite::UpdateProofs { added, removed_ys });
+        Ok(())
+    }
+
+    async fn update_proofs_state(&mut self, ys: Vec<PublicKey>, state: State) -> Result<(), Err> {
+        self.writes
+            .push(BufferedWrite::UpdateProofsState { ys, state });
+        Ok(())
+    }
+
+    async fn add_transaction(&mut self, transaction: Transaction) -> Result<(), Err> {
+        self.writes.push(BufferedWrite::AddTransaction(transaction));
+        Ok(())
+    }
+
+    async fn increment_keyset_counter(&mut self, keyset_id: &Id, count: u32) -> Result<u32, Err> {
+        self.db.increment_keyset_counter(keyset_id, count).await
+    }
+
+    async fn add_mint_quote(&mut self, quote: WalletMintQuote) -> Result<(), Err> {
+        self.writes.push(BufferedWrite::AddMintQuote(quote));
+        Ok(())
+    }
+
+    async fn add_melt_quote(&mut self, quote: wallet::MeltQuote) -> Result<(), Err> {
+        self.writes.push(BufferedWrite::AddMeltQuote(quote));
+        Ok(())
+    }
+
+    async fn kv_write(
+        &mut self,
+        primary_namespace: &str,
+        secondary_namespace: &str,
+        key: &str,
+        value: &[u8],
+    ) -> Result<(), Err> {
+        self.writes.push(BufferedWrite::KvWrite {
+            primary_namespace: primary_namespace.to_string(),
+            secondary_namespace: secondary_namespace.to_string(),
+            key: key.to_string(),
+            value: value.to_vec(),
+        });
+        Ok(())
+    }
+
+    async fn kv_remove(
+        &mut self,
+        primary_namespace: &str,
+        secondary_namespace: &str,
+        key: &str,
+    ) -> Result<(), Err> {
+        self.writes.push(BufferedWrite::KvRemove {
+            primary_namespace: primary_namespace.to_string(),
+            secondary_namespace: secondary_namespace.to_string(),
+            key: key.to_string(),
+        });
+        Ok(())
+    }
+}
@@ -6,8 +6,9 @@ use std::fmt::Debug;
 use async_trait::async_trait;
 use bitcoin::bip32::DerivationPath;
 use cashu::KeySet;
+use futures::future::BoxFuture;
 
-use super::Error;
+use super::{DbTransactionFinalizer, Error};
 use crate::mint_url::MintUrl;
 use crate::nuts::{
     CurrencyUnit, Id, KeySetInfo, Keys, MintInfo, PublicKey, SpendingConditions, State,
@@ -16,9 +17,59 @@ use crate::wallet::{
     self, MintQuote as WalletMintQuote, ProofInfo, Transaction, TransactionDirection, TransactionId,
 };
 
+mod buffered;
 #[cfg(feature = "test")]
 pub mod test;
 
+pub use buffered::BufferedTransaction;
+
+/// Wallet writes that are committed or rolled back together
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+pub trait DatabaseTransaction<Err>: DbTransactionFinalizer<Err = Err>
+where
+    Err: Into<Error> + From<Error>,
+{
+    /// Add proofs and remove proofs by their Y value
+    async fn update_proofs(
+        &mut self,
+        added: Vec<ProofInfo>,
+        removed_ys: Vec<PublicKey>,
+    ) -> Result<(), Err>;
+
+    /// Update proofs state
+    async fn update_proofs_state(&mut self, ys: Vec<PublicKey>, state: State) -> Result<(), Err>;
+
+    /// Add transaction
+    async fn add_transaction(&mut self, transaction: Transaction) -> Result<(), Err>;
+
+    /// Increment Keyset counter and return new value
+    async fn increment_keyset_counter(&mut self, keyset_id: &Id, count: u32) -> Result<u32, Err>;
+
+    /// Add mint quote
+    async fn add_mint_quote(&mut self, quote: WalletMintQuote) -> Result<(), Err>;
+
+    /// Add melt quote
+    async fn add_melt_quote(&mut self, quote: wallet::MeltQuote) -> Result<(), Err>;
+
+    /// Write a value to the key-value store
+    async fn kv_write(
+        &mut self,
+        primary_namespace: &str,
+        secondary_namespace: &str,
+        key: &str,
+        value: &[u8],
+    ) -> Result<(), Err>;
+
+    /// Remove a value from the key-value store
+    async fn kv_remove(
+        &mut self,
+        primary_namespace: &str,
+        secondary_namespace: &str,
+        key: &str,
+    ) -> Result<(), Err>;
+}
+
 /// Wallet Database trait
 #[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
 #[cfg_attr(not(target_arch = "wasm32"), async_trait)]
@@ -26,6 +77,11 @@ pub trait Database<Err>: Debug
 where
     Err: Into<Error> + From<Error>,
 {
+    /// Begin a transaction whose writes are committed together
+    async fn begin_db_transaction(
+        &self,
+    ) -> Result<Box<dyn DatabaseTransaction<Err> + Send + Sync>, Err>;
+
     /// Get mint from storage
     async fn get_mint(&self, mint_url: MintUrl) -> Result<Option<MintInfo>, Err>;
 
@@ -91,6 +147,35 @@ where
         unit: Option<CurrencyUnit>,
     ) -> Result<Vec<Transaction>, Err>;
 
+    /// List one page of the transactions of a mint and unit with a timestamp in `from..to`
+    ///
+    /// Transactions are ordered by timestamp, oldest first. The default implementation
+    /// loads and sorts every transaction of the mint and unit for each page, which is O(n)
+    /// in the size of the history. Backends should override it with a paged query.
+    #[allow(clippy::too_many_arguments)]
+    async fn list_transactions_page(
+        &self,
+        mint_url: MintUrl,
+        unit: CurrencyUnit,
+        from: u64,
+        to: u64,
+        offset: u64,
+        limit: u64,
+    ) -> Result<Vec<Transaction>, Err> {
+        let mut transactions = self
+            .list_transactions(Some(mint_url), None, Some(unit))
+            .await?;
+        transactions
+            .retain(|transaction| from <= transaction.timestamp && transaction.timestamp < to);
+        transactions.sort_by_key(|transaction| (transaction.timestamp, transaction.id()));
+
+        Ok(transactions
+            .into_iter()
+            .skip(offset as usize)
+            .take(limit as usize)
+            .collect())
+    }
+
     /// Update the proofs in storage by adding new proofs or removing proofs by
     /// their Y value
     async fn update_proofs(
@@ -103,6 +188,10 @@ where
     async fn update_proofs_state(&self, ys: Vec<PublicKey>, state: State) -> Result<(), Err>;
 
     /// Add transaction to storage
+    ///
+    /// The `saga_id` identifies the operation that wrote the transaction. If a transaction with
+    /// the same `saga_id` but a different id is already stored, the write is a replay of that
+    /// operation and is ignored.
     async fn add_transaction(&self, transaction: Transaction) -> Result<(), Err>;
 
     /// Update mint url
@@ -255,3 +344,56 @@ where
     /// Tries to get the latest p2pk key generated
     async fn latest_p2pk(&self) -> Result<Option<wallet::P2PKSigningKey>, Err>;
 }
+
+/// Helpers built on top of [`Database`]
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+pub trait DatabaseExt<Err>: Database<Err>
+where
+    Err: Into<Error> + From<Error> + Send + 'static,
+{
+    /// Run `f` in a transaction, committing if it returns `Ok` and rolling back otherwise
+    ///
+    /// ```ignore
+    /// db.transaction(|tx| {
+    ///     Box::pin(async move {
+    ///         tx.update_proofs(added, removed_ys).await?;
+    ///         tx.add_transaction(transaction).await
+    ///     })
+    /// })
+    /// .await?;
+    /// ```
+    async fn transaction<T, F>(&self, f: F) -> Result<T, Err>
+    where
+        T: Send,
+        F: for<'t> FnOnce(
+                &'t mut (dyn DatabaseTransaction<Err> + Send + Sync),
+            ) -> BoxFuture<'t, Result<T, Err>>
+            + Send,
+    {
+        let mut tx = self.begin_db_transaction().await?;
+
+        match f(tx.as_mut()).await {
+            Ok(value) => {
+                tx.commit().await?;
+                Ok(value)
+            }
+            Err(err) => {
+                if let Err(rollback_err) = tx.rollback().await {
+                    tracing::warn!(
+                        "Failed to roll back wallet transaction: {}",
+                        Into::<Error>::into(rollback_err)
+                    );
+                }
+                Err(err)
+            }
+        }
+    }
+}
+
+impl<Err, D> DatabaseExt<Err> for D
+where
+    D: Database<Err> + ?Sized,
+    Err: Into<Error> + From<Error> + Send + 'static,
+{
+}
@@ -12,7 +12,7 @@ use std::sync::atomic::{AtomicU64, Ordering};
 use bitcoin::bip32::DerivationPath;
 use cashu::nut00::KnownMethod;
 use cashu::secret::Secret;
-use cashu::{Amount, CurrencyUnit, MeltQuoteState, MintQuoteState, SecretKey};
+use cashu::{Amount, CurrencyUnit, MeltOptions, MeltQuoteState, MintQuoteState, SecretKey};
 use web_time::{SystemTime, UNIX_EPOCH};
 
 use super::*;
@@ -134,6 +134,7 @@ fn test_melt_quote() -> MeltQuote {
         payment_proof: None,
         estimated_blocks: None,
         fee_index: None,
+        options: Some(MeltOptions::new_mpp(500)),
         payment_method: cashu::PaymentMethod::Known(KnownMethod::Bolt11),
         used_by_operation: None,
         version: 0,
@@ -244,13 +245,53 @@ where
     let old_url = test_mint_url();
     let new_url = test_mint_url_2();
 
-    // Add mint with old URL
+    // Add mint with old URL and records of every kind on it
     db.add_mint(old_url.clone(), None).await.unwrap();
 
+    let proof_info = test_proof_info(test_keyset_id(), 100, old_url.clone());
+    db.update_proofs(vec![proof_info.clone()], vec![])
+        .await
+        .unwrap();
+
+    let mint_quote = test_mint_quote(old_url.clone());
+    db.add_mint_quote(mint_quote.clone()).await.unwrap();
+
+    let melt_quote = test_melt_quote();
+    db.add_melt_quote(melt_quote.clone()).await.unwrap();
+
+    let transaction = test_transaction(old_url.clone(), TransactionDirection::Incoming);
+    db.add_transaction(transaction.clone()).await.unwrap();
+
     // Update URL
     db.update_mint_url(old_url.clone(), new_url.clone())
         .await
         .unwrap();
+
+    let proofs = db
+        .get_proofs(Some(new_url.clone()), None, None, None)
+        .await
+        .unwrap();
+    assert_eq!(proofs.len(), 1);
+    assert_eq!(proofs[0].y, proof_info.y);
+    assert!(db
+        .get_proofs(Some(old_url.clone()), None, None, None)
+        .await
+        .unwrap()
+        .is_empty());
+
+    let mint_quote = db.get_mint_quote(&mint_quote.id).await.unwrap().unwrap();
+    assert_eq!(mint_quote.mint_url, new_url);
+
+    let melt_quote = db.get_melt_quote(&melt_quote.id).await.unwrap().unwrap();
+    assert_eq!(melt_quote.mint_url, Some(new_url.clone()));
+
+    let transaction = db.get_transaction(transaction.id()).await.unwrap().unwrap();
+    assert_eq!(transaction.mint_url, new_url);
+    assert!(db
+        .list_transactions(Some(old_url), None, None)
+        .await
+        .unwrap()
+        .is_empty());
 }
 
 // =============================================================================
@@ -461,7 +502,9 @@ where
     // Get quote
     let retrieved = db.get_melt_quote(&quote.id).await.unwrap();
     assert!(retrieved.is_some());
-    assert_eq!(retrieved.unwrap().id, quote.id);
+    let retrieved = retrieved.unwrap();
+    assert_eq!(retrieved.id, quote.id);
+    assert_eq!(retrieved.options, quote.options);
 
     // Get all quotes
     let quotes = db.get_melt_quotes().await.unwrap();
@@ -869,6 +912,101 @@ where
     assert_eq!(retrieved.unwrap().id(), tx_id);
 }
 
+/// Test that replays of the same operation are recorded once
+pub async fn add_transaction_coalesces_replays<DB>(db: DB)
+where
+    DB: Database<crate::database::Error>,
+{
+    let mint_url = test_mint_url();
+    let saga_id = uuid::Uuid::new_v4();
+
+    let mut transaction = test_transaction(mint_url.clone(), TransactionDirection::Incoming);
+    transaction.saga_id = Some(saga_id);
+    let mut replay = test_transaction(mint_url, TransactionDirection::Incoming);
+    replay.saga_id = Some(saga_id);
+    assert_ne!(transaction.id(), replay.id());
+
+    db.add_transaction(transaction.clone()).await.unwrap();
+    db.add_transaction(transaction.clone()).await.unwrap();
+    db.add_transaction(replay.clone()).await.unwrap();
+
+    let transactions = db.list_transactions(None, None, None).await.unwrap();
+    assert_eq!(transactions.len(), 1);
+    assert_eq!(transactions[0].id(), transaction.id());
+    assert!(db.get_transaction(replay.id()).await.unwrap().is_none());
+}
+
+/// Test that transaction writes are only visible once committed
+pub async fn transaction_commit_and_rollback<DB>(db: DB)
+where
+    DB: Database<crate::database::Error> + Send + Sync,
+{
+    let mint_url = test_mint_url();
+    let keyset_id = test_keyset_id();
+
+    let rolled_back = test_proof_info(keyset_id, 100, mint_url.clone());
+    let mut tx = db.begin_db_transaction().await.unwrap();
+    tx.update_proofs(vec![rolled_back.clone()], vec![])
+        .await
+        .unwrap();
+    tx.add_transaction(test_transaction(
+        mint_url.clone(),
+        TransactionDirection::Incoming,
+    ))
+    .await
+    .unwrap();
+    tx.kv_write("test", "tx", "rolled_back", b"value")
+        .await
+        .unwrap();
+    tx.rollback().await.unwrap();
+
+    assert!(db
+        .kv_read("test", "tx", "rolled_back")
+        .await
+        .unwrap()
+        .is_none());
+    assert!(db
+        .get_proofs_by_ys(vec![rolled_back.y])
+        .await
+        .unwrap()
+        .is_empty());
+    assert!(db
+        .list_transactions(None, None, None)
+        .await
+        .unwrap()
+        .is_empty());
+
+    let committed = test_proof_info(keyset_id, 64, mint_url.clone());
+    let transaction = test_transaction(mint_url, TransactionDirection::Incoming);
+    db.transaction(|tx| {
+        let committed = committed.clone();
+        let transaction = transaction.clone();
+        Box::pin(async move {
+            tx.update_proofs(vec![committed], vec![]).await?;
+            tx.add_transaction(transaction).await?;
+            tx.kv_write("test", "tx", "committed", b"value").await?;
+            tx.increment_keyset_counter(&keyset_id, 2).await
+        })
+    })
+    .await
+    .unwrap();
+
+    assert_eq!(
+        db.get_proofs_by_ys(vec![committed.y]).await.unwrap().len(),
+        1
+    );
+    assert!(db
+        .get_transaction(transaction.id())
+        .await
+        .unwrap()
+        .is_some());
+    assert_eq!(
+        db.kv_read("test", "tx", "committed").await.unwrap(),
+        Some(b"value".to_vec())
+    );
+    assert_eq!(db.increment_keyset_counter(&keyset_id, 0).await.unwrap(), 2);
+}
+
 /// Test listing transactions
 pub async fn list_transactions<DB>(db: DB)
 where
@@ -922,6 +1060,50 @@ where
     assert_eq!(transactions.len(), 1);
 }
 
+/// Test listing a page of transactions by timestamp
+pub async fn list_transactions_page<DB>(db: DB)
+where
+    DB: Database<crate::database::Error>,
+{
+    let mint_url = test_mint_url();
+    for timestamp in [500, 100, 400, 200, 300] {
+        let mut transaction = test_transaction(mint_url.clone(), TransactionDirection::Incoming);
+        transaction.timestamp = timestamp;
+        db.add_transaction(transaction).await.unwrap();
+    }
+    let mut other_mint = test_transaction(test_mint_url_2(), TransactionDirection::Incoming);
+    other_mint.timestamp = 300;
+    db.add_transaction(other_mint).await.unwrap();
+
+    let page = |from, to, offset, limit| {
+        let db = &db;
+        let mint_url = mint_url.clone();
+        async move {
+            db.list_transactions_page(mint_url, CurrencyUnit::Sat, from, to, offset, limit)
+                .await
+                .unwrap()
+                .into_iter()
+                .map(|transaction| transaction.timestamp)
+                .collect::<Vec<_>>()
+        }
+    };
+
+    assert_eq!(
+        page(0, u64::MAX, 0, 10).await,
+        vec![100, 200, 300, 400, 500]
+    );
+    assert_eq!(page(0, u64::MAX, 1, 2).await, vec![200, 300]);
+    assert_eq!(page(200, 400, 0, 10).await, vec![200, 300]);
+    assert_eq!(page(0, u64::MAX, 5, 10).await, Vec::<u64>::new());
+
+    let first = db
+        .list_transactions_page(mint_url.clone(), CurrencyUnit::Sat, 0, u64::MAX, 0, 1)
+        .await
+        .unwrap();
+    db.remove_transaction(first[0].id()).await.unwrap();
+    assert_eq!(page(0, u64::MAX, 0, 10).await, vec![200, 300, 400, 500]);
+}
+
 /// Test removing transaction
 pub async fn remove_transaction<DB>(db: DB)
 where
@@ -1569,8 +1751,11 @@ macro_rules! wallet_db_test {
             increment_keyset_counter,
             keyset_counter_isolation,
             add_and_get_transaction,
+            add_transaction_coalesces_replays,
+            transaction_commit_and_rollback,
             list_transactions,
             filter_transactions_by_mint,
+            list_transactions_page,
             remove_transaction,
             kvstore_write_and_read,
             kvstore_list,
@@ -45,6 +45,12 @@ pub enum Error {
     /// Bolt11 invoice does not have amount
     #[error("Invoice Amount undefined")]
     InvoiceAmountUndefined,
+    /// Bolt11 invoice has expired
+    #[error("Invoice expired at `{0}`")]
+    InvoiceExpired(u64),
+    /// Bolt11 invoice pays the lightning node of the mint itself
+    #[error("Invoice is payable to the mint's own node")]
+    SelfPaymentNotAllowed,
     /// Split Values must be less then or equal to amount
     #[error("Split Values must be less then or equal to amount")]
     SplitValuesGreater,
@@ -87,6 +93,22 @@ pub enum Error {
     /// Insufficient blind auth tokens
     #[error("Insufficient blind auth tokens, must reauth")]
     InsufficientBlindAuthTokens,
+    /// Daily quota of the authenticated identity exceeded
+    #[error(
+        "Daily {operation} quota exceeded: requested {requested}, used {used} of {limit}, retry after {retry_after} seconds"
+    )]
+    QuotaExceeded {
+        /// Operation the quota limits, e.g. `mint`
+        operation: String,
+        /// Amount of the rejected request
+        requested: u64,
+        /// Amount already used today
+        used: u64,
+        /// Daily limit
+        limit: u64,
+        /// Seconds until the quota resets
+        retry_after: u64,
+    },
     /// Auth localstore undefined
     #[error("Auth localstore undefined")]
     AuthLocalstoreUndefined,
@@ -206,6 +228,12 @@ pub enum Error {
     /// Lightning Address request error
     #[error("Failed to request invoice from Lightning address service: {0}")]
     LightningAddressRequest(String),
+    /// LNURL parsing error
+    #[error("Failed to parse LNURL: {0}")]
+    LnurlParse(String),
+    /// LNURL service request error
+    #[error("LNURL request failed: {0}")]
+    LnurlRequest(String),
 
     /// Internal Error - Send error
     #[error("Internal send error: {0}")]
@@ -228,6 +256,33 @@ pub enum Error {
     /// Amount is outside of allowed range
     #[error("Amount must be between `{0}` and `{1}` is `{2}`")]
     AmountOutofLimitRange(Amount, Amount, Amount),
+    /// Mint rejected the amount as outside of its limits without reporting the bounds
+    #[error("Amount is outside of the mint limits")]
+    AmountOutOfLimits,
+    /// Amount is below the minimum of the payment method and unit
+    #[error("Amount `{amount}` is below the minimum `{min}` for {method} {unit}")]
+    AmountBelowMinimum {
+        /// Payment method of the request
+        method: PaymentMethod,
+        /// Unit of the request
+        unit: CurrencyUnit,
+        /// Configured minimum
+        min: Amount,
+        /// Requested amount
+        amount: Amount,
+    },
+    /// Amount is above the maximum of the payment method and unit
+    #[error("Amount `{amount}` is above the maximum `{max}` for {method} {unit}")]
+    AmountAboveMaximum {
+        /// Payment method of the request
+        method: PaymentMethod,
+        /// Unit of the request
+        unit: CurrencyUnit,
+        /// Configured maximum
+        max: Amount,
+        /// Requested amount
+        amount: Amount,
+    },
     /// Quote is not paid
     #[error("Quote not paid")]
     UnpaidQuote,
@@ -304,6 +359,12 @@ pub enum Error {
         /// Maximum allowed batch size
         max: usize,
     },
+    /// Mint is in maintenance mode and does not accept mint, swap or melt requests
+    #[error("Mint temporarily unavailable, retry after {retry_after} seconds")]
+    TemporarilyUnavailable {
+        /// Seconds after which the request may be retried
+        retry_after: u64,
+    },
     /// Proof content too large (secret or witness exceeds max length)
     #[error("Proof content too large: {actual} bytes, max {max}")]
     ProofContentTooLarge {
@@ -380,9 +441,11 @@ pub enum Error {
     /// Url path segments could not be joined
     #[error("Url path segments could not be joined")]
     UrlPathSegments,
-    ///  Unknown error response
-    #[error("Unknown error response: `{0}`")]
-    UnknownErrorResponse(String),
+    /// Mint error response without a dedicated variant
+    ///
+    /// Keeps the NUT error code so it can be inspected with [`Error::error_code`].
+    #[error("Mint error response: {0}")]
+    UnmappedErrorResponse(ErrorResponse),
     /// Invalid DLEQ proof
     #[error("Could not verify DLEQ proof")]
     CouldNotVerifyDleq,
@@ -406,6 +469,14 @@ pub enum Error {
         /// URL of the unknown mint
         mint_url: String,
     },
+    /// Token rejected by the mint trust policy of the wallet
+    #[error("Token from mint {mint_url} rejected by trust policy: {reason}")]
+    UntrustedMint {
+        /// URL of the untrusted mint
+        mint_url: String,
+        /// Why the policy rejected the token
+        reason: String,
+    },
     /// Transfer between mints timed out
     #[error("Transfer timeout: failed to transfer {amount} from {source_mint} to {target_mint}")]
     TransferTimeout {
@@ -419,6 +490,14 @@ pub enum Error {
     /// Insufficient Funds
     #[error("Insufficient funds")]
     InsufficientFunds,
+    /// Send needs more proofs than allowed by `SendOptions::max_proofs`
+    #[error("Maximum proofs exceeded: send needs {required} proofs, max {max}")]
+    MaxProofsExceeded {
+        /// Number of proofs the send needs
+        required: usize,
+        /// Maximum number of proofs allowed
+        max: usize,
+    },
     /// Unexpected proof state
     #[error("Unexpected proof state")]
     UnexpectedProofState,
@@ -458,6 +537,9 @@ pub enum Error {
     /// Invalid response from mint
     #[error("Invalid mint response: {0}")]
     InvalidMintResponse(String),
+    /// Melt change returned by the mint does not match the requested outputs
+    #[error("Invalid melt change: {0}")]
+    InvalidMeltChange(String),
     /// Subscription error
     #[error("Subscription error: {0}")]
     SubscriptionError(String),
@@ -507,6 +589,9 @@ pub enum Error {
     /// Amount Error
     #[error(transparent)]
     AmountError(#[from] crate::amount::Error),
+    /// Rate Error
+    #[error(transparent)]
+    Rate(#[from] crate::rate::Error),
     /// DHKE Error
     #[error(transparent)]
     DHKE(#[from] crate::dhke::Error),
@@ -628,6 +713,7 @@ mod tests {
         let max_inputs = Error::from(ErrorResponse {
             code: ErrorCode::MaxInputsExceeded,
             detail: "Maximum inputs exceeded: 2 provided, max 1".to_string(),
+            data: None,
         });
         assert!(matches!(
             max_inputs,
@@ -638,6 +724,7 @@ mod tests {
         let max_outputs = Error::from(ErrorResponse {
             code: ErrorCode::MaxOutputsExceeded,
             detail: "Maximum outputs exceeded: 2 provided, max 1".to_string(),
+            data: None,
         });
         assert!(matches!(
             max_outputs,
@@ -645,6 +732,159 @@ mod tests {
         ));
         assert!(max_outputs.is_definitive_failure());
     }
+
+    #[test]
+    fn test_temporarily_unavailable_round_trips_retry_after() {
+        let response = ErrorResponse::from(Error::TemporarilyUnavailable { retry_after: 120 });
+        assert_eq!(response.code.to_code(), 50001);
+
+        let err = Error::from(response);
+        assert!(matches!(
+            err,
+            Error::TemporarilyUnavailable { retry_after: 120 }
+        ));
+        assert!(err.is_definitive_failure());
+    }
+
+    #[test]
+    fn test_error_responses_round_trip_structured() {
+        let unbalanced = Error::from(ErrorResponse::from(Error::TransactionUnbalanced(10, 8, 1)));
+        assert!(matches!(unbalanced, Error::TransactionUnbalanced(10, 8, 1)));
+        assert_eq!(unbalanced.error_code(), ErrorCode::TransactionUnbalanced);
+
+        let expired = Error::from(ErrorResponse::from(Error::ExpiredQuote(100, 200)));
+        assert!(matches!(expired, Error::ExpiredQuote(100, 200)));
+        assert_eq!(expired.error_code(), ErrorCode::QuoteExpired);
+
+        let expired_invoice = ErrorResponse::from(Error::InvoiceExpired(1_700_000_000));
+        assert_eq!(expired_invoice.code.to_code(), 20010);
+        let expired_invoice = Error::from(expired_invoice);
+        assert!(matches!(
+            expired_invoice,
+            Error::InvoiceExpired(1_700_000_000)
+        ));
+        assert!(expired_invoice.is_definitive_failure());
+
+        let self_payment = ErrorResponse::from(Error::SelfPaymentNotAllowed);
+        assert_eq!(self_payment.code.to_code(), 20011);
+        assert!(matches!(
+            Error::from(self_payment),
+            Error::SelfPaymentNotAllowed
+        ));
+
+        let spent = Error::from(ErrorResponse::from(Error::TokenAlreadySpent));
+        assert!(matches!(spent, Error::TokenAlreadySpent));
+
+        let quota = Error::from(ErrorResponse::from(Error::QuotaExceeded {
+            operation: "melt".to_string(),
+            requested: 500,
+            used: 9_800,
+            limit: 10_000,
+            retry_after: 3_600,
+        }));
+        assert_eq!(quota.error_code(), ErrorCode::QuotaExceeded);
+        assert!(matches!(
+            quota,
+            Error::QuotaExceeded {
+                ref operation,
+                requested: 500,
+                used: 9_800,
+                limit: 10_000,
+                retry_after: 3_600,
+            } if operation == "melt"
+        ));
+    }
+
+    #[test]
+    fn test_error_response_data_is_optional_on_the_wire() {
+        let response = ErrorResponse::from(Error::TransactionUnbalanced(10, 8, 1));
+        let json = serde_json::to_value(&response).unwrap();
+        assert_eq!(json["data"]["inputs"], 10);
+        assert_eq!(json["data"]["fee"], 1);
+
+        let spent = serde_json::to_value(ErrorResponse::from(Error::TokenAlreadySpent)).unwrap();
+        assert!(spent.get("data").is_none());
+
+        // Without data the detail is not parsed for fields
+        let quota = ErrorResponse::from_json(
+            r#"{"code":30003,"detail":"Daily mint quota exceeded: requested 5, used 10 of 10, retry after 60 seconds"}"#,
+        )
+        .unwrap();
+        assert!(quota.data.is_none());
+        assert!(matches!(
+            Error::from(quota),
+            Error::UnmappedErrorResponse(ErrorResponse {
+                code: ErrorCode::QuotaExceeded,
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn test_amount_limit_errors_report_violated_bound() {
+        let err = Error::AmountAboveMaximum {
+            method: PaymentMethod::from("bolt11"),
+            unit: CurrencyUnit::Sat,
+            max: Amount::from(1000),
+            amount: Amount::from(2000),
+        };
+        assert!(err.is_definitive_failure());
+
+        let response = ErrorResponse::from(err);
+        assert_eq!(response.code, ErrorCode::AmountOutofLimitRange);
+        assert_eq!(
+            response.detail,
+            "Amount `2000` is above the maximum `1000` for bolt11 sat"
+        );
+
+        // Wallets receive the code without bounds and do not invent any
+        let err = Error::from(response);
+        assert!(matches!(err, Error::AmountOutOfLimits));
+        assert_eq!(err.error_code(), ErrorCode::AmountOutofLimitRange);
+        assert!(err.is_definitive_failure());
+    }
+
+    #[test]
+    fn test_strict_witness_errors_round_trip() {
+        let malformed = Error::from(crate::nuts::nut10::Error::MalformedWitness);
+        assert_eq!(malformed.error_code().to_code(), 20008);
+
+        let duplicate = Error::from(ErrorResponse::from(Error::from(
+            crate::nuts::nut10::Error::DuplicateWitnessSignature,
+        )));
+        assert!(matches!(
+            duplicate,
+            Error::NUT10(crate::nuts::nut10::Error::DuplicateWitnessSignature)
+        ));
+
+        let locktime = Error::from(ErrorResponse::from(Error::from(
+            crate::nuts::nut10::Error::LocktimeNotExpired,
+        )));
+        assert!(matches!(
+            locktime,
+            Error::NUT10(crate::nuts::nut10::Error::LocktimeNotExpired)
+        ));
+
+        let generic = Error::from(ErrorResponse::new(
+            ErrorCode::WitnessMissingOrInvalid,
+            String::new(),
+        ));
+        assert!(matches!(generic, Error::SignatureMissingOrInvalid));
+    }
+
+    #[test]
+    fn test_unmapped_error_response_keeps_code() {
+        let err = Error::from(ErrorResponse::new(
+            ErrorCode::Unknown(42000),
+            "custom mint error".to_string(),
+        ));
+
+        assert_eq!(err.error_code(), ErrorCode::Unknown(42000));
+
+        let response = ErrorResponse::from(err);
+        assert_eq!(response.code, ErrorCode::Unknown(42000));
+        assert_eq!(response.detail, "custom mint error");
+    }
 }
 
 impl Error {
@@ -663,6 +903,8 @@ impl Error {
             | Self::KeysetUnknown(_)
             | Self::UnsupportedUnit
             | Self::InvoiceAmountUndefined
+            | Self::InvoiceExpired(_)
+            | Self::SelfPaymentNotAllowed
             | Self::SplitValuesGreater
             | Self::AmountOverflow
             | Self::OverIssue
@@ -680,6 +922,9 @@ impl Error {
             | Self::UnknownQuote
             | Self::ExpiredQuote(_, _)
             | Self::AmountOutofLimitRange(_, _, _)
+            | Self::AmountOutOfLimits
+            | Self::AmountBelowMinimum { .. }
+            | Self::AmountAboveMaximum { .. }
             | Self::UnpaidQuote
             | Self::IssuedQuote
             | Self::PaidQuote
@@ -695,6 +940,7 @@ impl Error {
             | Self::MaxOutputsExceeded { .. }
             | Self::DuplicateQuoteIds
             | Self::BatchSizeExceeded { .. }
+            | Self::TemporarilyUnavailable { .. }
             | Self::MultipleUnits
             | Self::UnitMismatch
             | Self::SigAllUsedInMelt
@@ -705,12 +951,14 @@ impl Error {
             | Self::InvalidSpendConditions(_)
             | Self::IncorrectWallet(_)
             | Self::MaxFeeExceeded
+            | Self::MaxProofsExceeded { .. }
             | Self::InvalidNut13Options { .. }
             | Self::DleqProofNotProvided
             | Self::IncorrectMint
             | Self::MultiMintTokenNotSupported
             | Self::PreimageNotProvided
             | Self::UnknownMint { .. }
+            | Self::UntrustedMint { .. }
             | Self::UnexpectedProofState
             | Self::NoActiveKeyset
             | Self::IncorrectQuoteAmount
@@ -725,7 +973,8 @@ impl Error {
             | Self::Bip353NoBolt12Offer
             | Self::Bip321Parse(_)
             | Self::Bip321Encode(_)
-            | Self::LightningAddressParse(_) => true,
+            | Self::LightningAddressParse(_)
+            | Self::LnurlParse(_) => true,
 
             #[cfg(feature = "mint")]
             Self::OnchainQuoteLookupIdMismatch { .. }
@@ -747,14 +996,16 @@ impl Error {
             | Self::PendingQuote
             | Self::TokenPending
             | Self::CouldNotGetMintInfo
-            | Self::UnknownErrorResponse(_)
+            | Self::UnmappedErrorResponse(_)
             | Self::InvalidMintResponse(_)
+            | Self::InvalidMeltChange(_)
             | Self::ConcurrentUpdate
             | Self::SendError(_)
             | Self::RecvError(_)
             | Self::TransferTimeout { .. }
             | Self::Bip353Resolve(_)
-            | Self::LightningAddressRequest(_) => false,
+            | Self::LightningAddressRequest(_)
+            | Self::LnurlRequest(_) => false,
 
             // Network/IO/Parsing Errors (Usually ambiguous as they could happen reading response)
             Self::HttpError(None, _) // No status code means network error
@@ -768,6 +1019,7 @@ impl Error {
             | Self::ClearAuthFailed
             | Self::BlindAuthFailed
             | Self::InsufficientBlindAuthTokens
+            | Self::QuotaExceeded { .. }
             | Self::AuthSettingsUndefined
             | Self::AuthLocalstoreUndefined
             | Self::OidcNotSet => true,
@@ -792,6 +1044,7 @@ impl Error {
             Self::NUT03(_) => true,
             Self::NUT04(_) => true,
             Self::NUT05(_) => true,
+            Self::NUT10(_) => true,
             Self::NUT11(_) => true,
             Self::NUT12(_) => true,
             #[cfg(feature = "wallet")]
@@ -834,6 +1087,11 @@ pub struct ErrorResponse {
     /// Human readable description
     #[serde(default)]
     pub detail: String,
+    /// Structured fields of the error, e.g. the amounts of an unbalanced transaction
+    ///
+    /// Mints that do not send them are mapped by `code` alone.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub data: Option<Value>,
 }
 
 impl fmt::Display for ErrorResponse {
@@ -845,7 +1103,11 @@ impl fmt::Display for ErrorResponse {
 impl ErrorResponse {
     /// Create new [`ErrorResponse`]
     pub fn new(code: ErrorCode, detail: String) -> Self {
-        Self { code, detail }
+        Self {
+            code,
+            detail,
+            data: None,
+        }
     }
 
     /// Error response from json
@@ -862,9 +1124,88 @@ impl ErrorResponse {
             Err(_) => Ok(Self {
                 code: ErrorCode::Unknown(999),
                 detail: value.to_string(),
+                data: None,
             }),
         }
     }
+
+    /// Unsigned integer field `name` of the structured error data
+    fn data_u64(&self, name: &str) -> Option<u64> {
+        self.data.as_ref()?.get(name)?.as_u64()
+    }
+
+    /// Count field `name` of the structured error data
+    fn data_usize(&self, name: &str) -> Option<usize> {
+        usize::try_from(self.data_u64(name)?).ok()
+    }
+
+    /// String field `name` of the structured error data
+    fn data_str(&self, name: &str) -> Option<&str> {
+        self.data.as_ref()?.get(name)?.as_str()
+    }
+}
+
+/// Name of a strict witness error in the structured error data
+fn witness_error_name(err: &crate::nuts::nut10::Error) -> Option<&'static str> {
+    match err {
+        crate::nuts::nut10::Error::MalformedWitness => Some("malformed_witness"),
+        crate::nuts::nut10::Error::DuplicateWitnessSignature => Some("duplicate_witness_signature"),
+        crate::nuts::nut10::Error::LocktimeNotExpired => Some("locktime_not_expired"),
+        _ => None,
+    }
+}
+
+/// Strict witness error named `name` in the structured error data
+fn witness_error_from_name(name: &str) -> Option<crate::nuts::nut10::Error> {
+    [
+        crate::nuts::nut10::Error::MalformedWitness,
+        crate::nuts::nut10::Error::DuplicateWitnessSignature,
+        crate::nuts::nut10::Error::LocktimeNotExpired,
+    ]
+    .into_iter()
+    .find(|err| witness_error_name(err) == Some(name))
+}
+
+/// Structured fields of `err` sent along with its detail
+fn error_data(err: &Error) -> Option<Value> {
+    let data = match err {
+        Error::TransactionUnbalanced(inputs, outputs, fee) => serde_json::json!({
+            "inputs": inputs,
+            "outputs": outputs,
+            "fee": fee,
+        }),
+        Error::ExpiredQuote(expiry, time) => serde_json::json!({
+            "expiry": expiry,
+            "time": time,
+        }),
+        Error::InvoiceExpired(expiry) => serde_json::json!({ "expiry": expiry }),
+        Error::MaxInputsExceeded { actual, max }
+        | Error::MaxOutputsExceeded { actual, max }
+        | Error::BatchSizeExceeded { actual, max } => serde_json::json!({
+            "actual": actual,
+            "max": max,
+        }),
+        Error::QuotaExceeded {
+            operation,
+            requested,
+            used,
+            limit,
+            retry_after,
+        } => serde_json::json!({
+            "operation": operation,
+            "requested": requested,
+            "used": used,
+            "limit": limit,
+            "retry_after": retry_after,
+        }),
+        Error::TemporarilyUnavailable { retry_after } => {
+            serde_json::json!({ "retry_after": retry_after })
+        }
+        Error::NUT10(err) => serde_json::json!({ "witness_error": witness_error_name(err)? }),
+        _ => return None,
+    };
+
+    Some(data)
 }
 
 /// Maps NUT11 errors to appropriate error codes
@@ -874,281 +1215,107 @@ fn map_nut11_error(_nut11_error: &crate::nuts::nut11::Error) -> ErrorCode {
     ErrorCode::WitnessMissingOrInvalid
 }
 
-impl From<Error> for ErrorResponse {
-    fn from(err: Error) -> ErrorResponse {
-        match err {
-            Error::TokenAlreadySpent => ErrorResponse {
-                code: ErrorCode::TokenAlreadySpent,
-                detail: err.to_string(),
-            },
-            Error::UnsupportedUnit => ErrorResponse {
-                code: ErrorCode::UnsupportedUnit,
-                detail: err.to_string(),
-            },
-            Error::PaymentFailed => ErrorResponse {
-                code: ErrorCode::LightningError,
-                detail: err.to_string(),
-            },
-            Error::RequestAlreadyPaid => ErrorResponse {
-                code: ErrorCode::InvoiceAlreadyPaid,
-                detail: "Invoice already paid.".to_string(),
-            },
-            Error::TransactionUnbalanced(inputs_total, outputs_total, fee_expected) => {
-                ErrorResponse {
-                    code: ErrorCode::TransactionUnbalanced,
-                    detail: format!(
-                        "Inputs: {inputs_total}, Outputs: {outputs_total}, expected_fee: {fee_expected}. Transaction inputs should equal outputs less fee"
-                    ),
-                }
-            }
-            Error::MintingDisabled => ErrorResponse {
-                code: ErrorCode::MintingDisabled,
-                detail: err.to_string(),
-            },
-            Error::BlindedMessageAlreadySigned => ErrorResponse {
-                code: ErrorCode::BlindedMessageAlreadySigned,
-                detail: err.to_string(),
-            },
-            Error::InsufficientFunds => ErrorResponse {
-                code: ErrorCode::TransactionUnbalanced,
-                detail: err.to_string(),
-            },
-            Error::AmountOutofLimitRange(_min, _max, _amount) => ErrorResponse {
-                code: ErrorCode::AmountOutofLimitRange,
-                detail: err.to_string(),
-            },
-            Error::ExpiredQuote(_, _) => ErrorResponse {
-                code: ErrorCode::QuoteExpired,
-                detail: err.to_string(),
-            },
-            Error::PendingQuote => ErrorResponse {
-                code: ErrorCode::QuotePending,
-                detail: err.to_string(),
-            },
-            Error::PendingMeltTimeout { .. } => ErrorResponse {
-                code: ErrorCode::QuotePending,
-                detail: err.to_string(),
-            },
-            Error::TokenPending => ErrorResponse {
-                code: ErrorCode::TokenPending,
-                detail: err.to_string(),
-            },
-            Error::ClearAuthRequired => ErrorResponse {
-                code: ErrorCode::ClearAuthRequired,
-                detail: Error::ClearAuthRequired.to_string(),
-            },
-            Error::ClearAuthFailed => ErrorResponse {
-                code: ErrorCode::ClearAuthFailed,
-                detail: Error::ClearAuthFailed.to_string(),
-            },
-            Error::BlindAuthRequired => ErrorResponse {
-                code: ErrorCode::BlindAuthRequired,
-                detail: Error::BlindAuthRequired.to_string(),
-            },
-            Error::BlindAuthFailed => ErrorResponse {
-                code: ErrorCode::BlindAuthFailed,
-                detail: Error::BlindAuthFailed.to_string(),
-            },
-            Error::NUT20(err) => ErrorResponse {
-                code: ErrorCode::WitnessMissingOrInvalid,
-                detail: err.to_string(),
-            },
-            Error::DuplicateInputs => ErrorResponse {
-                code: ErrorCode::DuplicateInputs,
-                detail: err.to_string(),
-            },
-            Error::DuplicateOutputs => ErrorResponse {
-                code: ErrorCode::DuplicateOutputs,
-                detail: err.to_string(),
-            },
-            Error::MultipleUnits => ErrorResponse {
-                code: ErrorCode::MultipleUnits,
-                detail: err.to_string(),
-            },
-            Error::UnitMismatch => ErrorResponse {
-                code: ErrorCode::UnitMismatch,
-                detail: err.to_string(),
-            },
-            Error::UnpaidQuote => ErrorResponse {
-                code: ErrorCode::QuoteNotPaid,
-                detail: Error::UnpaidQuote.to_string(),
-            },
-            Error::NUT11(err) => {
-                let code = map_nut11_error(&err);
-                let extra = if matches!(err, crate::nuts::nut11::Error::SignaturesNotProvided) {
-                    Some("P2PK signatures are required but not provided".to_string())
-                } else {
-                    None
-                };
-                ErrorResponse {
-                    code,
-                    detail: match extra {
-                        Some(extra) => format!("{err}. {extra}"),
-                        None => err.to_string(),
-                    },
-                }
-            },
-            Error::DuplicateSignatureError => ErrorResponse {
-                code: ErrorCode::WitnessMissingOrInvalid,
-                detail: err.to_string(),
-            },
-            Error::IssuedQuote => ErrorResponse {
-                code: ErrorCode::TokensAlreadyIssued,
-                detail: err.to_string(),
-            },
-            Error::UnknownKeySet => ErrorResponse {
-                code: ErrorCode::KeysetNotFound,
-                detail: err.to_string(),
-            },
-            Error::InactiveKeyset => ErrorResponse {
-                code: ErrorCode::KeysetInactive,
-                detail: err.to_string(),
-            },
-            Error::ExpiredKeyset => ErrorResponse {
-                code: ErrorCode::KeysetExpired,
-                detail: err.to_string(),
-            },
-            Error::AmountLessNotAllowed => ErrorResponse {
-                code: ErrorCode::AmountlessInvoiceNotSupported,
-                detail: err.to_string(),
-            },
-            Error::IncorrectQuoteAmount => ErrorResponse {
-                code: ErrorCode::IncorrectQuoteAmount,
-                detail: err.to_string(),
-            },
-            Error::PubkeyRequired => ErrorResponse {
-                code: ErrorCode::PubkeyRequired,
-                detail: err.to_string(),
-            },
-            Error::PaidQuote => ErrorResponse {
-                code: ErrorCode::InvoiceAlreadyPaid,
-                detail: err.to_string(),
-            },
-            Error::DuplicatePaymentId => ErrorResponse {
-                code: ErrorCode::InvoiceAlreadyPaid,
-                detail: err.to_string(),
-            },
+impl Error {
+    /// NUT error code reported to wallets for this error
+    ///
+    /// Errors without a standardized code map to [`ErrorCode::Unknown`].
+    pub fn error_code(&self) -> ErrorCode {
+        match self {
+            Self::TokenAlreadySpent => ErrorCode::TokenAlreadySpent,
+            Self::UnsupportedUnit => ErrorCode::UnsupportedUnit,
+            Self::PaymentFailed => ErrorCode::LightningError,
+            Self::RequestAlreadyPaid
+            | Self::PaidQuote
+            | Self::DuplicatePaymentId
             // Database duplicate error indicates another quote with same invoice is already pending/paid
-            Error::Database(crate::database::Error::Duplicate) => ErrorResponse {
-                code: ErrorCode::InvoiceAlreadyPaid,
-                detail: "Invoice already paid or pending".to_string(),
-            },
-
+            | Self::Database(crate::database::Error::Duplicate) => ErrorCode::InvoiceAlreadyPaid,
+            Self::TransactionUnbalanced(_, _, _)
+            | Self::InsufficientFunds
+            | Self::SplitValuesGreater
+            | Self::AmountOverflow
+            | Self::OverIssue => ErrorCode::TransactionUnbalanced,
+            Self::MintingDisabled | Self::MeltingDisabled => ErrorCode::MintingDisabled,
+            Self::BlindedMessageAlreadySigned => ErrorCode::BlindedMessageAlreadySigned,
+            Self::AmountOutofLimitRange(_, _, _)
+            | Self::AmountOutOfLimits
+            | Self::AmountBelowMinimum { .. }
+            | Self::AmountAboveMaximum { .. } => ErrorCode::AmountOutofLimitRange,
+            Self::ExpiredQuote(_, _) => ErrorCode::QuoteExpired,
+            Self::InvoiceExpired(_) => ErrorCode::InvoiceExpired,
+            Self::SelfPaymentNotAllowed => ErrorCode::SelfPaymentNotAllowed,
+            Self::PendingQuote | Self::PendingMeltTimeout { .. } | Self::PaymentPending => {
+                ErrorCode::QuotePending
+            }
+            Self::TokenPending => ErrorCode::TokenPending,
+            Self::ClearAuthRequired => ErrorCode::ClearAuthRequired,
+            Self::ClearAuthFailed => ErrorCode::ClearAuthFailed,
+            Self::BlindAuthRequired => ErrorCode::BlindAuthRequired,
+            Self::BlindAuthFailed => ErrorCode::BlindAuthFailed,
+            Self::QuotaExceeded { .. } => ErrorCode::QuotaExceeded,
+            Self::NUT11(err) => map_nut11_error(err),
+            // Strict witness errors have no code of their own, the detail names the cause
+            Self::NUT10(crate::nuts::nut10::Error::MalformedWitness)
+            | Self::NUT10(crate::nuts::nut10::Error::DuplicateWitnessSignature)
+            | Self::NUT10(crate::nuts::nut10::Error::LocktimeNotExpired)
+            | Self::NUT20(_)
+            | Self::DuplicateSignatureError
+            | Self::SignatureMissingOrInvalid
+            | Self::SigAllUsedInMelt => ErrorCode::WitnessMissingOrInvalid,
+            Self::DuplicateInputs => ErrorCode::DuplicateInputs,
+            Self::DuplicateOutputs => ErrorCode::DuplicateOutputs,
+            Self::MultipleUnits => ErrorCode::MultipleUnits,
+            Self::UnitMismatch => ErrorCode::UnitMismatch,
+            Self::UnpaidQuote => ErrorCode::QuoteNotPaid,
+            Self::IssuedQuote => ErrorCode::TokensAlreadyIssued,
+            Self::UnknownKeySet | Self::AmountKey | Self::KeysetUnknown(_) => {
+                ErrorCode::KeysetNotFound
+            }
+            Self::InactiveKeyset | Self::NoActiveKeyset => ErrorCode::KeysetInactive,
+            Self::ExpiredKeyset => ErrorCode::KeysetExpired,
+            Self::AmountLessNotAllowed | Self::InvoiceAmountUndefined => {
+                ErrorCode::AmountlessInvoiceNotSupported
+            }
+            Self::IncorrectQuoteAmount => ErrorCode::IncorrectQuoteAmount,
+            Self::PubkeyRequired => ErrorCode::PubkeyRequired,
             // DHKE errors - TokenNotVerified for actual verification failures
-            Error::DHKE(crate::dhke::Error::TokenNotVerified) => ErrorResponse {
-                code: ErrorCode::TokenNotVerified,
-                detail: err.to_string(),
-            },
-            Error::DHKE(_) => ErrorResponse {
-                code: ErrorCode::Unknown(50000),
-                detail: err.to_string(),
-            },
-
-            // Verification errors
-            Error::CouldNotVerifyDleq => ErrorResponse {
-                code: ErrorCode::TokenNotVerified,
-                detail: err.to_string(),
-            },
-            Error::SignatureMissingOrInvalid => ErrorResponse {
-                code: ErrorCode::WitnessMissingOrInvalid,
-                detail: err.to_string(),
-            },
-            Error::SigAllUsedInMelt => ErrorResponse {
-                code: ErrorCode::WitnessMissingOrInvalid,
-                detail: err.to_string(),
-            },
-
-            // Keyset/key errors
-            Error::AmountKey => ErrorResponse {
-                code: ErrorCode::KeysetNotFound,
-                detail: err.to_string(),
-            },
-            Error::KeysetUnknown(_) => ErrorResponse {
-                code: ErrorCode::KeysetNotFound,
-                detail: err.to_string(),
-            },
-            Error::NoActiveKeyset => ErrorResponse {
-                code: ErrorCode::KeysetInactive,
-                detail: err.to_string(),
-            },
-
-            // Quote/payment errors
-            Error::UnknownQuote => ErrorResponse {
-                code: ErrorCode::Unknown(50000),
-                detail: err.to_string(),
-            },
-            Error::MeltingDisabled => ErrorResponse {
-                code: ErrorCode::MintingDisabled,
-                detail: err.to_string(),
-            },
-            Error::PaymentPending => ErrorResponse {
-                code: ErrorCode::QuotePending,
-                detail: err.to_string(),
-            },
-            Error::UnknownPaymentState => ErrorResponse {
-                code: ErrorCode::Unknown(50000),
-                detail: err.to_string(),
-            },
-
-            // Transaction/amount errors
-            Error::SplitValuesGreater => ErrorResponse {
-                code: ErrorCode::TransactionUnbalanced,
-                detail: err.to_string(),
-            },
-            Error::AmountOverflow => ErrorResponse {
-                code: ErrorCode::TransactionUnbalanced,
-                detail: err.to_string(),
-            },
-            Error::OverIssue => ErrorResponse {
-                code: ErrorCode::TransactionUnbalanced,
-                detail: err.to_string(),
-            },
+            Self::DHKE(crate::dhke::Error::TokenNotVerified) | Self::CouldNotVerifyDleq => {
+                ErrorCode::TokenNotVerified
+            }
+            Self::ConcurrentUpdate => ErrorCode::ConcurrentUpdate,
+            Self::MaxInputsExceeded { .. } => ErrorCode::MaxInputsExceeded,
+            Self::MaxOutputsExceeded { .. } => ErrorCode::MaxOutputsExceeded,
+            Self::DuplicateQuoteIds => ErrorCode::DuplicateQuoteIds,
+            Self::BatchSizeExceeded { .. } => ErrorCode::BatchSizeExceeded,
+            Self::TemporarilyUnavailable { .. } => ErrorCode::TemporarilyUnavailable,
+            Self::UnmappedErrorResponse(response) => response.code,
+            // Internal/system errors and errors without a spec code
+            _ => ErrorCode::Unknown(50000),
+        }
+    }
+}
 
-            // Invoice parsing errors - no spec code for invalid format
-            Error::InvalidPaymentRequest => ErrorResponse {
-                code: ErrorCode::Unknown(50000),
-                detail: err.to_string(),
-            },
-            Error::InvoiceAmountUndefined => ErrorResponse {
-                code: ErrorCode::AmountlessInvoiceNotSupported,
-                detail: err.to_string(),
-            },
+impl From<Error> for ErrorResponse {
+    fn from(err: Error) -> ErrorResponse {
+        let code = err.error_code();
+        let data = error_data(&err);
+
+        let detail = match err {
+            Error::RequestAlreadyPaid => "Invoice already paid.".to_string(),
+            Error::TransactionUnbalanced(inputs_total, outputs_total, fee_expected) => format!(
+                "Inputs: {inputs_total}, Outputs: {outputs_total}, expected_fee: {fee_expected}. Transaction inputs should equal outputs less fee"
+            ),
+            Error::NUT11(crate::nuts::nut11::Error::SignaturesNotProvided) => format!(
+                "{}. P2PK signatures are required but not provided",
+                crate::nuts::nut11::Error::SignaturesNotProvided
+            ),
+            Error::Database(crate::database::Error::Duplicate) => {
+                "Invoice already paid or pending".to_string()
+            }
+            Error::UnmappedErrorResponse(response) => return response,
+            err => err.to_string(),
+        };
 
-            // Internal/system errors - use Unknown(99999)
-            Error::Internal => ErrorResponse {
-                code: ErrorCode::Unknown(50000),
-                detail: err.to_string(),
-            },
-            Error::Database(_) => ErrorResponse {
-                code: ErrorCode::Unknown(50000),
-                detail: err.to_string(),
-            },
-            Error::ConcurrentUpdate => ErrorResponse {
-                code: ErrorCode::ConcurrentUpdate,
-                detail: err.to_string(),
-            },
-            Error::MaxInputsExceeded { .. } => ErrorResponse {
-                code: ErrorCode::MaxInputsExceeded,
-                detail: err.to_string()
-            },
-            Error::MaxOutputsExceeded { .. } => ErrorResponse {
-                code: ErrorCode::MaxOutputsExceeded,
-                detail: err.to_string()
-            },
-            Error::DuplicateQuoteIds => ErrorResponse {
-                code: ErrorCode::DuplicateQuoteIds,
-                detail: err.to_string(),
-            },
-            Error::BatchSizeExceeded { .. } => ErrorResponse {
-                code: ErrorCode::BatchSizeExceeded,
-                detail: err.to_string(),
-            },
-            // Fallback for any remaining errors - use Unknown(99999) instead of TokenNotVerified
-            _ => ErrorResponse {
-                code: ErrorCode::Unknown(50000),
-                detail: err.to_string(),
-            },
-        }
+        ErrorResponse { code, detail, data }
     }
 }
 
@@ -1185,6 +1352,14 @@ fn parse_limit_counts(detail: &str) -> Option<(usize, usize)> {
     Some((actual.trim().parse().ok()?, max.trim().parse().ok()?))
 }
 
+/// Actual and maximum count of a limit error, read from the detail if the mint sent no data
+fn limit_counts(err: &ErrorResponse) -> (usize, usize) {
+    match (err.data_usize("actual"), err.data_usize("max")) {
+        (Some(actual), Some(max)) => (actual, max),
+        _ => parse_limit_counts(&err.detail).unwrap_or((0, 0)),
+    }
+}
+
 impl From<ErrorResponse> for Error {
     fn from(err: ErrorResponse) -> Error {
         match err.code {
@@ -1195,22 +1370,27 @@ impl From<ErrorResponse> for Error {
             ErrorCode::TokenPending => Self::TokenPending,
             ErrorCode::BlindedMessageAlreadySigned => Self::BlindedMessageAlreadySigned,
             ErrorCode::OutputsPending => Self::TokenPending, // Map to closest equivalent
-            ErrorCode::TransactionUnbalanced => Self::TransactionUnbalanced(0, 0, 0),
-            ErrorCode::AmountOutofLimitRange => {
-                Self::AmountOutofLimitRange(Amount::default(), Amount::default(), Amount::default())
-            }
+            ErrorCode::TransactionUnbalanced => Self::TransactionUnbalanced(
+                err.data_u64("inputs").unwrap_or_default(),
+                err.data_u64("outputs").unwrap_or_default(),
+                err.data_u64("fee").unwrap_or_default(),
+            ),
+            ErrorCode::AmountOutofLimitRange => Self::AmountOutOfLimits,
             ErrorCode::DuplicateInputs => Self::DuplicateInputs,
             ErrorCode::DuplicateOutputs => Self::DuplicateOutputs,
             ErrorCode::MaxInputsExceeded => {
-                let (actual, max) = parse_limit_counts(&err.detail).unwrap_or((0, 0));
+                let (actual, max) = limit_counts(&err);
                 Self::MaxInputsExceeded { actual, max }
             }
             ErrorCode::MaxOutputsExceeded => {
-                let (actual, max) = parse_limit_counts(&err.detail).unwrap_or((0, 0));
+                let (actual, max) = limit_counts(&err);
                 Self::MaxOutputsExceeded { actual, max }
             }
             ErrorCode::DuplicateQuoteIds => Self::DuplicateQuoteIds,
-            ErrorCode::BatchSizeExceeded => Self::BatchSizeExceeded { actual: 0, max: 0 },
+            ErrorCode::BatchSizeExceeded => {
+                let (actual, max) = limit_counts(&err);
+                Self::BatchSizeExceeded { actual, max }
+            }
             ErrorCode::MultipleUnits => Self::MultipleUnits,
             ErrorCode::UnitMismatch => Self::UnitMismatch,
             ErrorCode::AmountlessInvoiceNotSupported => Self::AmountLessNotAllowed,
@@ -1227,18 +1407,52 @@ impl From<ErrorResponse> for Error {
             ErrorCode::LightningError => Self::PaymentFailed,
             ErrorCode::QuotePending => Self::PendingQuote,
             ErrorCode::InvoiceAlreadyPaid => Self::RequestAlreadyPaid,
-            ErrorCode::QuoteExpired => Self::ExpiredQuote(0, 0),
-            ErrorCode::WitnessMissingOrInvalid => Self::SignatureMissingOrInvalid,
+            ErrorCode::QuoteExpired => Self::ExpiredQuote(
+                err.data_u64("expiry").unwrap_or_default(),
+                err.data_u64("time").unwrap_or_default(),
+            ),
+            ErrorCode::WitnessMissingOrInvalid => err
+                .data_str("witness_error")
+                .and_then(witness_error_from_name)
+                .map(Self::NUT10)
+                .unwrap_or(Self::SignatureMissingOrInvalid),
             ErrorCode::PubkeyRequired => Self::PubkeyRequired,
+            ErrorCode::InvoiceExpired => match err.data_u64("expiry") {
+                Some(expiry) => Self::InvoiceExpired(expiry),
+                None => Self::UnmappedErrorResponse(err),
+            },
+            ErrorCode::SelfPaymentNotAllowed => Self::SelfPaymentNotAllowed,
             // 30xxx - Clear auth errors
             ErrorCode::ClearAuthRequired => Self::ClearAuthRequired,
             ErrorCode::ClearAuthFailed => Self::ClearAuthFailed,
+            ErrorCode::QuotaExceeded => match (
+                err.data_str("operation").map(str::to_string),
+                err.data_u64("requested"),
+                err.data_u64("used"),
+                err.data_u64("limit"),
+                err.data_u64("retry_after"),
+            ) {
+                (Some(operation), Some(requested), Some(used), Some(limit), Some(retry_after)) => {
+                    Self::QuotaExceeded {
+                        operation,
+                        requested,
+                        used,
+                        limit,
+                        retry_after,
+                    }
+                }
+                _ => Self::UnmappedErrorResponse(err),
+            },
             // 31xxx - Blind auth errors
             ErrorCode::BlindAuthRequired => Self::BlindAuthRequired,
             ErrorCode::BlindAuthFailed => Self::BlindAuthFailed,
             ErrorCode::BatMintMaxExceeded => Self::InsufficientBlindAuthTokens,
             ErrorCode::BatRateLimitExceeded => Self::InsufficientBlindAuthTokens,
-            _ => Self::UnknownErrorResponse(err.to_string()),
+            ErrorCode::TemporarilyUnavailable => match err.data_u64("retry_after") {
+                Some(retry_after) => Self::TemporarilyUnavailable { retry_after },
+                None => Self::UnmappedErrorResponse(err),
+            },
+            _ => Self::UnmappedErrorResponse(err),
         }
     }
 }
@@ -1324,12 +1538,19 @@ pub enum ErrorCode {
     WitnessMissingOrInvalid,
     /// Pubkey required for mint quote (20009)
     PubkeyRequired,
+    // Mint-specific codes, not part of the NUT error code registry
+    /// Invoice of the melt quote request has expired (20010, mint-specific)
+    InvoiceExpired,
+    /// Invoice of the melt quote request pays the mint's own node (20011, mint-specific)
+    SelfPaymentNotAllowed,
 
     // 30xxx - Clear auth errors
     /// Endpoint requires clear auth (30001)
     ClearAuthRequired,
     /// Clear authentication failed (30002)
     ClearAuthFailed,
+    /// Daily quota of the authenticated identity exceeded (30003)
+    QuotaExceeded,
 
     // 31xxx - Blind auth errors
     /// Endpoint requires blind auth (31001)
@@ -1343,6 +1564,8 @@ pub enum ErrorCode {
 
     /// Concurrent update detected
     ConcurrentUpdate,
+    /// Mint is in maintenance mode (50001)
+    TemporarilyUnavailable,
 
     /// Unknown error code
     Unknown(u16),
@@ -1386,14 +1609,18 @@ impl ErrorCode {
             20007 => Self::QuoteExpired,
             20008 => Self::WitnessMissingOrInvalid,
             20009 => Self::PubkeyRequired,
+            20010 => Self::InvoiceExpired,
+            20011 => Self::SelfPaymentNotAllowed,
             // 30xxx - Clear auth errors
             30001 => Self::ClearAuthRequired,
             30002 => Self::ClearAuthFailed,
+            30003 => Self::QuotaExceeded,
             // 31xxx - Blind auth errors
             31001 => Self::BlindAuthRequired,
             31002 => Self::BlindAuthFailed,
             31003 => Self::BatMintMaxExceeded,
             31004 => Self::BatRateLimitExceeded,
+            50001 => Self::TemporarilyUnavailable,
             _ => Self::Unknown(code),
         }
     }
@@ -1435,15 +1662,19 @@ impl ErrorCode {
             Self::QuoteExpired => 20007,
             Self::WitnessMissingOrInvalid => 20008,
             Self::PubkeyRequired => 20009,
+            Self::InvoiceExpired => 20010,
+            Self::SelfPaymentNotAllowed => 20011,
             // 30xxx - Clear auth errors
             Self::ClearAuthRequired => 30001,
             Self::ClearAuthFailed => 30002,
+            Self::QuotaExceeded => 30003,
             // 31xxx - Blind auth errors
             Self::BlindAuthRequired => 31001,
             Self::BlindAuthFailed => 31002,
             Self::BatMintMaxExceeded => 31003,
             Self::BatRateLimitExceeded => 31004,
             Self::ConcurrentUpdate => 50000,
+            Self::TemporarilyUnavailable => 50001,
             Self::Unknown(code) => *code,
         }
     }
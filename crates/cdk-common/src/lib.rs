@@ -24,6 +24,7 @@ pub const PAYMENT_PROCESSOR_PROTOCOL_VERSION: &str = "3.0.0";
 #[cfg(feature = "grpc")]
 pub mod grpc;
 
+pub mod clock;
 pub mod common;
 pub mod database;
 pub mod error;
@@ -34,6 +35,7 @@ pub mod mint_quote;
 #[cfg(feature = "mint")]
 pub mod payment;
 pub mod pub_sub;
+pub mod rate;
 #[cfg(feature = "mint")]
 pub mod state;
 pub mod subscription;
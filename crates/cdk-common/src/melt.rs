@@ -288,6 +288,7 @@ where
                     request: Some(value.request.to_string()),
                     unit: Some(value.unit.clone()),
                     method: PaymentMethod::Known(KnownMethod::Bolt11),
+                    change_policy: None,
                 })
             }
             PaymentMethod::Known(KnownMethod::Bolt12) => {
@@ -302,6 +303,7 @@ where
                     request: Some(value.request.to_string()),
                     unit: Some(value.unit.clone()),
                     method: PaymentMethod::Known(KnownMethod::Bolt12),
+                    change_policy: None,
                 })
             }
             PaymentMethod::Known(KnownMethod::Onchain) => {
@@ -359,6 +361,7 @@ mod tests {
             request: Some("lnbc100".to_string()),
             unit: Some(CurrencyUnit::Sat),
             method: PaymentMethod::Known(KnownMethod::Bolt11),
+            change_policy: None,
         }
     }
 
@@ -374,6 +377,7 @@ mod tests {
             request: Some("lno200".to_string()),
             unit: Some(CurrencyUnit::Sat),
             method: PaymentMethod::Known(KnownMethod::Bolt12),
+            change_policy: None,
         }
     }
 
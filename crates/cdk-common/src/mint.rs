@@ -371,6 +371,8 @@ pub struct Operation {
     payment_fee: Option<Amount>,
     /// Payment method (only for mint/melt operations)
     payment_method: Option<PaymentMethod>,
+    /// Unit of all amounts of the operation
+    unit: Option<CurrencyUnit>,
 }
 
 impl Operation {
@@ -394,6 +396,7 @@ impl Operation {
             payment_amount: None,
             payment_fee: None,
             payment_method,
+            unit: None,
         }
     }
 
@@ -409,6 +412,7 @@ impl Operation {
             payment_amount: None,
             payment_fee: None,
             payment_method: Some(payment_method),
+            unit: None,
         }
     }
 
@@ -424,6 +428,7 @@ impl Operation {
             payment_amount: None,
             payment_fee: None,
             payment_method: Some(payment_method),
+            unit: None,
         }
     }
 
@@ -445,6 +450,7 @@ impl Operation {
             payment_amount: None,
             payment_fee: None,
             payment_method: Some(payment_method),
+            unit: None,
         }
     }
 
@@ -460,6 +466,7 @@ impl Operation {
             payment_amount: None,
             payment_fee: None,
             payment_method: None,
+            unit: None,
         }
     }
 
@@ -518,6 +525,119 @@ impl Operation {
     pub fn payment_method(&self) -> Option<PaymentMethod> {
         self.payment_method.clone()
     }
+
+    /// Set the unit the amounts of the operation are in
+    pub fn with_unit(mut self, unit: CurrencyUnit) -> Self {
+        self.unit = Some(unit);
+        self
+    }
+
+    /// Unit of the amounts, `None` for operations recorded before the unit was stored
+    pub fn unit(&self) -> Option<&CurrencyUnit> {
+        self.unit.as_ref()
+    }
+}
+
+/// Source of a [`FeeLedgerEntry`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FeeLedgerKind {
+    /// Input fee charged on spent proofs according to the keyset `input_fee_ppk`
+    InputFee,
+    /// Part of a melt fee reserve that was neither paid to the network nor returned as change
+    LightningSurplus,
+    /// Fee income moved into a designated balance
+    Sweep,
+}
+
+impl fmt::Display for FeeLedgerKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FeeLedgerKind::InputFee => write!(f, "input_fee"),
+            FeeLedgerKind::LightningSurplus => write!(f, "lightning_surplus"),
+            FeeLedgerKind::Sweep => write!(f, "sweep"),
+        }
+    }
+}
+
+impl FromStr for FeeLedgerKind {
+    type Err = Error;
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value {
+            "input_fee" => Ok(FeeLedgerKind::InputFee),
+            "lightning_surplus" => Ok(FeeLedgerKind::LightningSurplus),
+            "sweep" => Ok(FeeLedgerKind::Sweep),
+            _ => Err(Error::Custom(format!("Invalid fee ledger kind: {value}"))),
+        }
+    }
+}
+
+/// Entry of the mint fee ledger
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FeeLedgerEntry {
+    /// Source of the entry
+    pub kind: FeeLedgerKind,
+    /// Unit of the amount
+    pub unit: CurrencyUnit,
+    /// Amount
+    pub amount: Amount,
+    /// Keyset the fee was charged on (only for input fees)
+    pub keyset_id: Option<Id>,
+    /// Operation the fee was collected in
+    pub operation_id: Option<Uuid>,
+    /// Balance the fees were moved into (only for sweeps)
+    pub destination: Option<String>,
+    /// Unix time the entry was recorded
+    pub created_time: u64,
+}
+
+impl FeeLedgerEntry {
+    /// Lightning fee surplus kept by the mint on a melt
+    pub fn lightning_surplus(unit: CurrencyUnit, amount: Amount, operation_id: Uuid) -> Self {
+        Self {
+            kind: FeeLedgerKind::LightningSurplus,
+            unit,
+            amount,
+            keyset_id: None,
+            operation_id: Some(operation_id),
+            destination: None,
+            created_time: unix_time(),
+        }
+    }
+
+    /// Sweep of fee income into `destination`
+    pub fn sweep(unit: CurrencyUnit, amount: Amount, destination: String) -> Self {
+        Self {
+            kind: FeeLedgerKind::Sweep,
+            unit,
+            amount,
+            keyset_id: None,
+            operation_id: None,
+            destination: Some(destination),
+            created_time: unix_time(),
+        }
+    }
+}
+
+/// Accumulated fee income of a unit
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct FeeTotals {
+    /// Total input fees collected
+    pub input_fees: Amount,
+    /// Total Lightning fee surplus kept
+    pub lightning_surplus: Amount,
+    /// Total fee income swept
+    pub swept: Amount,
+}
+
+impl FeeTotals {
+    /// Fee income that has not been swept yet
+    pub fn available(&self) -> Amount {
+        self.input_fees
+            .checked_add(self.lightning_surplus)
+            .unwrap_or(Amount::from(u64::MAX))
+            .saturating_sub(self.swept)
+    }
 }
 
 /// Tracks pending changes made to a [`MintQuote`] that need to be persisted.
@@ -833,6 +953,17 @@ impl IncomingPayment {
     }
 }
 
+/// Payment received for a mint quote, together with the quote it paid
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MintQuotePayment {
+    /// Id of the mint quote
+    pub quote_id: QuoteId,
+    /// Payment method of the mint quote
+    pub payment_method: PaymentMethod,
+    /// The payment
+    pub payment: IncomingPayment,
+}
+
 /// Information about issued quote
 #[derive(Debug, Clone, Hash, PartialEq, Eq)]
 pub struct Issuance {
@@ -1384,6 +1515,7 @@ impl From<&MeltQuote> for MeltQuoteBolt12Response<QuoteId> {
             request: None,
             unit: Some(melt_quote.unit.clone()),
             method: PaymentMethod::Known(cashu::nuts::nut00::KnownMethod::Bolt12),
+            change_policy: None,
         }
     }
 }
@@ -1401,6 +1533,7 @@ impl From<MeltQuote> for MeltQuoteBolt12Response<QuoteId> {
             request: Some(melt_quote.request.to_string()),
             unit: Some(melt_quote.unit.clone()),
             method: PaymentMethod::Known(cashu::nuts::nut00::KnownMethod::Bolt12),
+            change_policy: None,
         }
     }
 }
@@ -1542,6 +1675,7 @@ impl From<&MeltQuote> for MeltQuoteBolt11Response<QuoteId> {
             request: None,
             unit: Some(melt_quote.unit.clone()),
             method: melt_quote.payment_method.clone(),
+            change_policy: None,
         }
     }
 }
@@ -1559,6 +1693,7 @@ impl From<MeltQuote> for MeltQuoteBolt11Response<QuoteId> {
             request: Some(melt_quote.request.to_string()),
             unit: Some(melt_quote.unit.clone()),
             method: melt_quote.payment_method.clone(),
+            change_policy: None,
         }
     }
 }
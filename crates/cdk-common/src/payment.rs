@@ -192,6 +192,10 @@ pub struct Bolt11IncomingPaymentOptions {
     pub amount: Amount<CurrencyUnit>,
     /// Optional expiry time as Unix timestamp in seconds
     pub unix_expiry: Option<u64>,
+    /// Commit to the SHA-256 hash of the description instead of the description itself
+    ///
+    /// LNURL-pay (LUD-06) invoices carry the hash of the pay request metadata.
+    pub hash_description: bool,
 }
 
 impl Default for Bolt11IncomingPaymentOptions {
@@ -200,6 +204,7 @@ impl Default for Bolt11IncomingPaymentOptions {
             description: None,
             amount: Amount::new(0, CurrencyUnit::Sat),
             unix_expiry: None,
+            hash_description: false,
         }
     }
 }
@@ -231,6 +236,10 @@ pub struct CustomIncomingPaymentOptions {
     /// These fields are passed through to the payment processor for
     /// method-specific validation (e.g., ehash share).
     pub extra_json: Option<String>,
+    /// The mint's quote id for this mint quote. Backends that cannot embed
+    /// their own identifier in the payment request (e.g. keysend) can use it
+    /// to correlate incoming payments with the quote.
+    pub quote_id: QuoteId,
 }
 
 /// Options for creating an onchain incoming payment request
@@ -486,6 +495,27 @@ pub trait MintPayment {
         &self,
         payment_identifier: &PaymentIdentifier,
     ) -> Result<MakePaymentResponse, Self::Err>;
+
+    /// Funds the backend can currently use to make outgoing payments
+    ///
+    /// Returns `None` if the backend cannot report its outbound liquidity.
+    async fn outbound_liquidity(&self) -> Result<Option<Amount<CurrencyUnit>>, Self::Err> {
+        Ok(None)
+    }
+
+    /// Settle an incoming payment that is being held by the backend
+    ///
+    /// Called by the mint once a received payment has been durably recorded
+    /// against its quote. Backends that hold incoming payments (e.g. hold
+    /// invoices) release the funds here; all others can rely on the default
+    /// no-op. Implementations must be idempotent, as the mint may call this
+    /// more than once for the same payment.
+    async fn settle_incoming_payment(
+        &self,
+        _payment_identifier: &PaymentIdentifier,
+    ) -> Result<(), Self::Err> {
+        Ok(())
+    }
 }
 
 /// An event emitted which should be handled by the mint
@@ -841,6 +871,29 @@ where
 
         result
     }
+
+    async fn outbound_liquidity(&self) -> Result<Option<Amount<CurrencyUnit>>, Self::Err> {
+        let metrics = MintMetricGuard::new("outbound_liquidity");
+
+        let result = self.inner.outbound_liquidity().await;
+
+        metrics.record(result.is_ok());
+
+        result
+    }
+
+    async fn settle_incoming_payment(
+        &self,
+        payment_identifier: &PaymentIdentifier,
+    ) -> Result<(), Self::Err> {
+        let metrics = MintMetricGuard::new("settle_incoming_payment");
+
+        let result = self.inner.settle_incoming_payment(payment_identifier).await;
+
+        metrics.record(result.is_ok());
+
+        result
+    }
 }
 
 /// Type alias for Mint Payment trait
@@ -0,0 +1,420 @@
+//! Exchange rates between units
+//!
+//! A [`RateOracle`] quotes the [`SpotRate`] between two units, e.g. `sat` and `usd`. Every rate
+//! carries the time it was quoted at and how long it may be used for, so callers can refuse
+//! stale rates. [`StaticRates`] serves rates set in configuration and, with the `http` feature,
+//! [`HttpRateOracle`] fetches the price of bitcoin from a price API.
+//!
+//! The oracle is used by the mint to convert between the units of its keysets and by wallets to
+//! display balances in another unit.
+
+use std::collections::HashMap;
+use std::fmt::Debug;
+
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::amount::Amount;
+use crate::nuts::CurrencyUnit;
+use crate::util::unix_time;
+
+/// Default seconds a rate may be used for
+pub const DEFAULT_RATE_TTL: u64 = 60;
+
+/// Sats in one bitcoin
+#[cfg(feature = "http")]
+const SATS_PER_BTC: u64 = 100_000_000;
+
+/// Rate Error
+#[derive(Debug, Error)]
+pub enum Error {
+    /// No rate between the units
+    #[error("No rate from {0} to {1}")]
+    UnsupportedPair(CurrencyUnit, CurrencyUnit),
+    /// Rate amounts must not be zero
+    #[error("Invalid rate")]
+    InvalidRate,
+    /// Rate is past its TTL
+    #[error("Rate from {0} to {1} is expired")]
+    Expired(CurrencyUnit, CurrencyUnit),
+    /// Price API response could not be read
+    #[error("Invalid price response: {0}")]
+    InvalidResponse(String),
+    /// Http error
+    #[cfg(feature = "http")]
+    #[error(transparent)]
+    Http(#[from] crate::HttpError),
+    /// Amount error
+    #[error(transparent)]
+    Amount(#[from] crate::amount::Error),
+}
+
+/// Rate between two units at a point in time
+///
+/// `from_amount` of `from` are worth `to_amount` of `to`, both in the smallest denomination of
+/// their unit, e.g. `100_000_000` sat to `6_500_000` usd cents.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SpotRate {
+    /// Unit converted from
+    pub from: CurrencyUnit,
+    /// Unit converted to
+    pub to: CurrencyUnit,
+    /// Amount of `from`
+    pub from_amount: u64,
+    /// Value of `from_amount` in `to`
+    pub to_amount: u64,
+    /// Unix time the rate was quoted at
+    pub timestamp: u64,
+    /// Seconds after `timestamp` the rate may be used for
+    pub ttl: u64,
+}
+
+impl SpotRate {
+    /// Rate of `from_amount` of `from` to `to_amount` of `to`
+    pub fn new(
+        from: CurrencyUnit,
+        to: CurrencyUnit,
+        from_amount: u64,
+        to_amount: u64,
+        timestamp: u64,
+        ttl: u64,
+    ) -> Result<Self, Error> {
+        if from_amount == 0 || to_amount == 0 {
+            return Err(Error::InvalidRate);
+        }
+
+        Ok(Self {
+            from,
+            to,
+            from_amount,
+            to_amount,
+            timestamp,
+            ttl,
+        })
+    }
+
+    /// Unix time after which the rate must not be used
+    pub fn expires_at(&self) -> u64 {
+        self.timestamp.saturating_add(self.ttl)
+    }
+
+    /// Whether the rate is past its TTL at unix time `now`
+    pub fn is_expired(&self, now: u64) -> bool {
+        now > self.expires_at()
+    }
+
+    /// Rate in the opposite direction
+    pub fn inverse(&self) -> Self {
+        Self {
+            from: self.to.clone(),
+            to: self.from.clone(),
+            from_amount: self.to_amount,
+            to_amount: self.from_amount,
+            timestamp: self.timestamp,
+            ttl: self.ttl,
+        }
+    }
+
+    /// Convert `amount` of `from` to `to`, rounding down
+    pub fn convert(&self, amount: Amount) -> Result<Amount, Error> {
+        let converted =
+            u128::from(amount.to_u64()) * u128::from(self.to_amount) / u128::from(self.from_amount);
+
+        u64::try_from(converted)
+            .map(Amount::from)
+            .map_err(|_| Error::Amount(crate::amount::Error::AmountOverflow))
+    }
+}
+
+/// Source of exchange rates between units
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+pub trait RateOracle: Debug + Send + Sync {
+    /// Current rate from `from` to `to`
+    async fn spot_rate(&self, from: &CurrencyUnit, to: &CurrencyUnit) -> Result<SpotRate, Error>;
+}
+
+/// Convert `amount` to `to` at the current rate of `oracle`, rounding down
+///
+/// Refuses expired rates.
+pub async fn convert_amount(
+    oracle: &dyn RateOracle,
+    amount: Amount<CurrencyUnit>,
+    to: &CurrencyUnit,
+) -> Result<Amount<CurrencyUnit>, Error> {
+    if amount.unit() == to {
+        return Ok(amount);
+    }
+
+    let rate = oracle.spot_rate(amount.unit(), to).await?;
+    if rate.is_expired(unix_time()) {
+        return Err(Error::Expired(rate.from, rate.to));
+    }
+
+    Ok(rate.convert(amount.into())?.with_unit(to.clone()))
+}
+
+/// Rates set by the operator
+///
+/// A rate set in one direction is also served inverted for the opposite direction.
+#[derive(Debug, Clone)]
+pub struct StaticRates {
+    rates: HashMap<(CurrencyUnit, CurrencyUnit), (u64, u64)>,
+    ttl: u64,
+}
+
+impl Default for StaticRates {
+    fn default() -> Self {
+        Self {
+            rates: HashMap::new(),
+            ttl: DEFAULT_RATE_TTL,
+        }
+    }
+}
+
+impl StaticRates {
+    /// No rates
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// `from_amount` of `from` are worth `to_amount` of `to`
+    pub fn with_rate(
+        mut self,
+        from: CurrencyUnit,
+        to: CurrencyUnit,
+        from_amount: u64,
+        to_amount: u64,
+    ) -> Result<Self, Error> {
+        if from_amount == 0 || to_amount == 0 {
+            return Err(Error::InvalidRate);
+        }
+
+        self.rates.insert((from, to), (from_amount, to_amount));
+        Ok(self)
+    }
+
+    /// Seconds the served rates may be used for
+    pub fn with_ttl(mut self, ttl: u64) -> Self {
+        self.ttl = ttl;
+        self
+    }
+}
+
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+impl RateOracle for StaticRates {
+    async fn spot_rate(&self, from: &CurrencyUnit, to: &CurrencyUnit) -> Result<SpotRate, Error> {
+        let (from_amount, to_amount) = match self.rates.get(&(from.clone(), to.clone())) {
+            Some(&rate) => rate,
+            None => self
+                .rates
+                .get(&(to.clone(), from.clone()))
+                .map(|&(to_amount, from_amount)| (from_amount, to_amount))
+                .ok_or_else(|| Error::UnsupportedPair(from.clone(), to.clone()))?,
+        };
+
+        SpotRate::new(
+            from.clone(),
+            to.clone(),
+            from_amount,
+            to_amount,
+            unix_time(),
+            self.ttl,
+        )
+    }
+}
+
+/// Amount of `unit` in one bitcoin, if it is a bitcoin unit
+#[cfg(feature = "http")]
+fn bitcoin_unit_amount(unit: &CurrencyUnit) -> Option<u64> {
+    match unit {
+        CurrencyUnit::Sat => Some(SATS_PER_BTC),
+        CurrencyUnit::Msat => Some(SATS_PER_BTC * 1_000),
+        _ => None,
+    }
+}
+
+/// Rates from the bitcoin price of a price API
+///
+/// The URL and the JSON pointer to the price in the response may contain a `{currency}`
+/// placeholder, replaced with the lowercase name of the fiat unit. The price is the value of
+/// one bitcoin in the whole fiat currency, e.g. dollars for [`CurrencyUnit::Usd`], and is cached
+/// for the TTL of the oracle.
+#[cfg(feature = "http")]
+#[derive(Debug)]
+pub struct HttpRateOracle {
+    client: crate::HttpClient,
+    url: String,
+    price_pointer: String,
+    ttl: u64,
+    prices: parking_lot::Mutex<HashMap<CurrencyUnit, (f64, u64)>>,
+}
+
+#[cfg(feature = "http")]
+impl HttpRateOracle {
+    /// Fetch prices from `url`, reading the price at `price_pointer` in the JSON response
+    ///
+    /// E.g. `https://api.coingecko.com/api/v3/simple/price?ids=bitcoin&vs_currencies={currency}`
+    /// with the pointer `/bitcoin/{currency}`.
+    pub fn new(url: impl Into<String>, price_pointer: impl Into<String>) -> Self {
+        Self {
+            client: crate::HttpClient::new(),
+            url: url.into(),
+            price_pointer: price_pointer.into(),
+            ttl: DEFAULT_RATE_TTL,
+            prices: parking_lot::Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Cache prices and let the served rates be used for `ttl` seconds
+    pub fn with_ttl(mut self, ttl: u64) -> Self {
+        self.ttl = ttl;
+        self
+    }
+
+    /// Price of one bitcoin in `fiat` and the unix time it was fetched at
+    async fn bitcoin_price(&self, fiat: &CurrencyUnit) -> Result<(f64, u64), Error> {
+        let now = unix_time();
+        if let Some(&(price, fetched_at)) = self.prices.lock().get(fiat) {
+            if now <= fetched_at.saturating_add(self.ttl) {
+                return Ok((price, fetched_at));
+            }
+        }
+
+        let currency = fiat.to_string().to_lowercase();
+        let url = self.url.replace("{currency}", &currency);
+        let pointer = self.price_pointer.replace("{currency}", &currency);
+
+        tracing::debug!("Fetching bitcoin price in {} from {}", currency, url);
+        let response: serde_json::Value = self.client.fetch(&url).await?;
+
+        let price = response
+            .pointer(&pointer)
+            .and_then(|price| match price {
+                serde_json::Value::String(price) => price.parse::<f64>().ok(),
+                price => price.as_f64(),
+            })
+            .filter(|price| price.is_finite() && *price > 0.0)
+            .ok_or_else(|| Error::InvalidResponse(format!("No price at {pointer}")))?;
+
+        self.prices.lock().insert(fiat.clone(), (price, now));
+
+        Ok((price, now))
+    }
+}
+
+#[cfg(feature = "http")]
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+impl RateOracle for HttpRateOracle {
+    async fn spot_rate(&self, from: &CurrencyUnit, to: &CurrencyUnit) -> Result<SpotRate, Error> {
+        match (bitcoin_unit_amount(from), bitcoin_unit_amount(to)) {
+            (Some(from_amount), Some(to_amount)) => SpotRate::new(
+                from.clone(),
+                to.clone(),
+                from_amount,
+                to_amount,
+                unix_time(),
+                self.ttl,
+            ),
+            (Some(from_amount), None) if *to != CurrencyUnit::Auth => {
+                let (price, fetched_at) = self.bitcoin_price(to).await?;
+                let to_amount = (price * 10f64.powi(to.decimals() as i32)).round() as u64;
+
+                SpotRate::new(
+                    from.clone(),
+                    to.clone(),
+                    from_amount,
+                    to_amount,
+                    fetched_at,
+                    self.ttl,
+                )
+            }
+            (None, Some(_)) => Ok(self.spot_rate(to, from).await?.inverse()),
+            _ => Err(Error::UnsupportedPair(from.clone(), to.clone())),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_spot_rate_convert() {
+        let rate = SpotRate::new(
+            CurrencyUnit::Sat,
+            CurrencyUnit::Usd,
+            100_000_000,
+            6_500_000,
+            1_000,
+            60,
+        )
+        .unwrap();
+
+        assert_eq!(
+            rate.convert(Amount::from(100_000)).unwrap(),
+            Amount::from(6_500)
+        );
+        // Rounds down
+        assert_eq!(rate.convert(Amount::from(10)).unwrap(), Amount::ZERO);
+        assert_eq!(
+            rate.inverse().convert(Amount::from(6_500)).unwrap(),
+            Amount::from(100_000)
+        );
+
+        assert!(!rate.is_expired(1_060));
+        assert!(rate.is_expired(1_061));
+
+        assert!(matches!(
+            SpotRate::new(CurrencyUnit::Sat, CurrencyUnit::Usd, 0, 1, 0, 60),
+            Err(Error::InvalidRate)
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_static_rates() {
+        let rates = StaticRates::new()
+            .with_rate(CurrencyUnit::Sat, CurrencyUnit::Eur, 1_000, 60)
+            .unwrap();
+
+        let amount = convert_amount(
+            &rates,
+            Amount::from(2_000).with_unit(CurrencyUnit::Sat),
+            &CurrencyUnit::Eur,
+        )
+        .await
+        .unwrap();
+        assert_eq!(amount, Amount::from(120).with_unit(CurrencyUnit::Eur));
+
+        // The inverse direction is served too
+        let rate = rates
+            .spot_rate(&CurrencyUnit::Eur, &CurrencyUnit::Sat)
+            .await
+            .unwrap();
+        assert_eq!((rate.from_amount, rate.to_amount), (60, 1_000));
+
+        assert!(matches!(
+            rates
+                .spot_rate(&CurrencyUnit::Sat, &CurrencyUnit::Usd)
+                .await,
+            Err(Error::UnsupportedPair(_, _))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_static_rates_ttl() {
+        let rates = StaticRates::new()
+            .with_rate(CurrencyUnit::Sat, CurrencyUnit::Usd, 1_000, 60)
+            .unwrap()
+            .with_ttl(0);
+
+        let rate = rates
+            .spot_rate(&CurrencyUnit::Sat, &CurrencyUnit::Usd)
+            .await
+            .unwrap();
+        assert!(rate.is_expired(rate.timestamp + 1));
+    }
+}
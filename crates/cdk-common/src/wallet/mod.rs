@@ -1,6 +1,6 @@
 //! Wallet Types
 
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::fmt;
 use std::str::FromStr;
 
@@ -19,7 +19,8 @@ use uuid::Uuid;
 use crate::mint_quote::quote_state_from_amounts;
 use crate::mint_url::MintUrl;
 use crate::nuts::{
-    CurrencyUnit, Id, MeltQuoteState, MintQuoteState, SecretKey, SpendingConditions, State,
+    CurrencyUnit, Id, MeltOptions, MeltQuoteState, MintQuoteState, SecretKey, SpendingConditions,
+    State,
 };
 use crate::{Amount, Error};
 
@@ -241,6 +242,9 @@ pub struct MeltQuote {
     /// Selected fee option index for onchain quotes
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub fee_index: Option<u32>,
+    /// Melt options (MPP or amountless) the quote was requested with
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub options: Option<MeltOptions>,
     /// Payment method
     pub payment_method: PaymentMethod,
     /// Operation ID that has reserved this quote (for saga pattern)
@@ -404,8 +408,16 @@ pub struct SendOptions {
     /// Send kind
     pub send_kind: SendKind,
     /// Include fee
+    ///
+    /// Shorthand for [`SendFeePolicy::SenderPays`], takes precedence over `fee_policy`.
     pub include_fee: bool,
+    /// Who pays the input fee of the receiver's swap
+    pub fee_policy: SendFeePolicy,
     /// Maximum number of proofs to include in the token
+    ///
+    /// Keeps tokens small, e.g. to fit a QR code. Online sends swap first when the selected
+    /// proofs exceed the limit; the send fails with `Error::MaxProofsExceeded` if the amount
+    /// cannot be expressed in that many proofs or an offline send would exceed it.
     pub max_proofs: Option<usize>,
     /// Metadata
     pub metadata: HashMap<String, String>,
@@ -415,6 +427,12 @@ pub struct SendOptions {
     pub p2pk_signing_keys: Vec<SecretKey>,
     /// How P2PK-locked input proofs should be handled during send
     pub p2pk_locked_proof_send_mode: P2PKLockedProofSendMode,
+    /// Also include the keyset keys next to the DLEQ proofs (NUT-12) in the token
+    ///
+    /// Lets a receiver that does not know the mint verify the signatures offline. DLEQ proofs are
+    /// kept either way. The keys go in the V4 `k` field, which is not part of NUT-00, so receivers
+    /// that do not know the extension ignore them.
+    pub include_dleq: bool,
 }
 
 impl fmt::Debug for SendOptions {
@@ -425,6 +443,7 @@ impl fmt::Debug for SendOptions {
             .field("amount_split_target", &self.amount_split_target)
             .field("send_kind", &self.send_kind)
             .field("include_fee", &self.include_fee)
+            .field("fee_policy", &self.fee_policy)
             .field("max_proofs", &self.max_proofs)
             .field("metadata", &self.metadata)
             .field("use_p2bk", &self.use_p2bk)
@@ -433,10 +452,45 @@ impl fmt::Debug for SendOptions {
                 "p2pk_locked_proof_send_mode",
                 &self.p2pk_locked_proof_send_mode,
             )
+            .field("include_dleq", &self.include_dleq)
             .finish()
     }
 }
 
+impl SendOptions {
+    /// Fee policy of the send, taking `include_fee` into account
+    pub fn effective_fee_policy(&self) -> SendFeePolicy {
+        if self.include_fee {
+            SendFeePolicy::SenderPays
+        } else {
+            self.fee_policy
+        }
+    }
+}
+
+/// Who pays the input fee the receiver is charged when swapping a token
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum SendFeePolicy {
+    /// The token is worth the send amount and the receiver nets it minus the fee
+    #[default]
+    ReceiverPays,
+    /// The token includes the fee so the receiver nets exactly the send amount
+    SenderPays,
+    /// The token includes half the fee, rounded down
+    Split,
+}
+
+impl SendFeePolicy {
+    /// Part of the receiver's input fee `fee` the sender adds to the token
+    pub fn sender_share(&self, fee: Amount) -> Amount {
+        match self {
+            Self::ReceiverPays => Amount::ZERO,
+            Self::SenderPays => fee,
+            Self::Split => Amount::from(fee.to_u64() / 2),
+        }
+    }
+}
+
 /// Send behavior for selected P2PK-locked input proofs
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
 pub enum P2PKLockedProofSendMode {
@@ -477,6 +531,10 @@ pub struct ReceiveOptions {
     pub preimages: Vec<String>,
     /// Metadata
     pub metadata: HashMap<String, String>,
+    /// Melt a token issued by another mint at its mint and mint the amount at the wallet's mint
+    pub transfer_from_other_mint: bool,
+    /// Trust policy the mint of received tokens is checked against
+    pub mint_trust_policy: Option<MintTrustPolicy>,
 }
 
 impl fmt::Debug for ReceiveOptions {
@@ -486,10 +544,68 @@ impl fmt::Debug for ReceiveOptions {
             .field("p2pk_signing_keys", &"[redacted]")
             .field("preimages", &self.preimages)
             .field("metadata", &self.metadata)
+            .field("transfer_from_other_mint", &self.transfer_from_other_mint)
+            .field("mint_trust_policy", &self.mint_trust_policy)
             .finish()
     }
 }
 
+/// Policy deciding whether a token from a mint is received
+///
+/// Known mints, the wallet's own mint or mints already in a wallet repository, are trusted like
+/// allowlisted ones. Tokens from other mints are unknown.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MintTrustPolicy {
+    /// Mints tokens are always accepted from
+    pub allowlist: HashSet<MintUrl>,
+    /// Mints tokens are never accepted from, takes precedence over the allowlist
+    pub denylist: HashSet<MintUrl>,
+    /// Largest token kept as ecash of an unknown mint, `None` for no limit
+    pub max_amount_from_unknown_mint: Option<Amount>,
+    /// Move tokens of unknown mints to a trusted mint over Lightning instead of keeping them
+    pub auto_swap_to_trusted: bool,
+}
+
+/// Outcome of [`MintTrustPolicy::evaluate`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MintTrustDecision {
+    /// Receive the token at its mint
+    Accept,
+    /// Melt the token at its mint and mint the amount at a trusted mint
+    SwapToTrusted,
+    /// Refuse the token
+    Reject(String),
+}
+
+impl MintTrustPolicy {
+    /// Whether tokens of `mint_url` are trusted, given whether the mint is already `known`
+    pub fn is_trusted(&self, mint_url: &MintUrl, known: bool) -> bool {
+        !self.denylist.contains(mint_url) && (known || self.allowlist.contains(mint_url))
+    }
+
+    /// Decide how a token of `amount` from `mint_url` is received
+    pub fn evaluate(&self, mint_url: &MintUrl, amount: Amount, known: bool) -> MintTrustDecision {
+        if self.denylist.contains(mint_url) {
+            return MintTrustDecision::Reject("mint is denylisted".to_string());
+        }
+
+        if self.is_trusted(mint_url, known) {
+            return MintTrustDecision::Accept;
+        }
+
+        if self.auto_swap_to_trusted {
+            return MintTrustDecision::SwapToTrusted;
+        }
+
+        match self.max_amount_from_unknown_mint {
+            Some(max) if amount > max => MintTrustDecision::Reject(format!(
+                "{amount} exceeds the maximum of {max} from an unknown mint"
+            )),
+            _ => MintTrustDecision::Accept,
+        }
+    }
+}
+
 /// Send Kind
 #[derive(Debug, Clone, Copy, Hash, PartialEq, Eq, Default, Serialize, Deserialize)]
 pub enum SendKind {
@@ -799,6 +915,47 @@ pub enum KeysetLoadPolicy {
     Refresh,
 }
 
+/// Policy controlling when the wallet refreshes keysets on its own.
+///
+/// Keysets and mint info share one cache, so the policy applies to both.
+/// Independently of the policy, operations the mint rejects because of an
+/// unknown or inactive keyset refresh the keysets and are retried once.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum KeysetRefreshPolicy {
+    /// Fetch keysets from the mint before every operation
+    OnEveryOp,
+    /// Fetch keysets once the cached ones are older than the given duration
+    Ttl(std::time::Duration),
+    /// Only fetch keysets when none are cached or when explicitly refreshed
+    Manual,
+}
+
+impl Default for KeysetRefreshPolicy {
+    fn default() -> Self {
+        Self::Ttl(std::time::Duration::from_secs(3600))
+    }
+}
+
+impl KeysetRefreshPolicy {
+    /// Metadata cache TTL implementing this policy
+    pub fn cache_ttl(&self) -> Option<std::time::Duration> {
+        match self {
+            Self::OnEveryOp => Some(std::time::Duration::ZERO),
+            Self::Ttl(ttl) => Some(*ttl),
+            Self::Manual => None,
+        }
+    }
+
+    /// Policy implemented by a metadata cache TTL
+    pub fn from_cache_ttl(ttl: Option<std::time::Duration>) -> Self {
+        match ttl {
+            Some(ttl) if ttl.is_zero() => Self::OnEveryOp,
+            Some(ttl) => Self::Ttl(ttl),
+            None => Self::Manual,
+        }
+    }
+}
+
 /// Unified wallet trait providing a common interface for wallet operations.
 ///
 /// This trait abstracts over different wallet implementations (CDK wallet, FFI
@@ -1208,6 +1365,21 @@ mod tests {
     use crate::nuts::Id;
     use crate::secret::Secret;
 
+    #[test]
+    fn test_send_fee_policy_sender_share() {
+        let fee = Amount::from(3);
+        assert_eq!(SendFeePolicy::ReceiverPays.sender_share(fee), Amount::ZERO);
+        assert_eq!(SendFeePolicy::SenderPays.sender_share(fee), fee);
+        assert_eq!(SendFeePolicy::Split.sender_share(fee), Amount::from(1));
+
+        let opts = SendOptions {
+            include_fee: true,
+            fee_policy: SendFeePolicy::Split,
+            ..Default::default()
+        };
+        assert_eq!(opts.effective_fee_policy(), SendFeePolicy::SenderPays);
+    }
+
     #[test]
     fn test_transaction_id_from_hex() {
         let hex_str = "a1b2c3d4e5f60718293a0b1c2d3e4f506172839a0b1c2d3e4f506172839a0b1c";
@@ -1376,4 +1548,50 @@ mod tests {
             }
         ));
     }
+
+    #[test]
+    fn mint_trust_policy_evaluate() {
+        let trusted = MintUrl::from_str("https://trusted.example.com").unwrap();
+        let denied = MintUrl::from_str("https://denied.example.com").unwrap();
+        let unknown = MintUrl::from_str("https://unknown.example.com").unwrap();
+
+        let mut policy = MintTrustPolicy {
+            allowlist: HashSet::from([trusted.clone(), denied.clone()]),
+            denylist: HashSet::from([denied.clone()]),
+            max_amount_from_unknown_mint: Some(Amount::from(100)),
+            auto_swap_to_trusted: false,
+        };
+
+        // The denylist takes precedence over the allowlist and known mints
+        assert!(matches!(
+            policy.evaluate(&denied, Amount::from(1), true),
+            MintTrustDecision::Reject(_)
+        ));
+        assert_eq!(
+            policy.evaluate(&trusted, Amount::from(1_000), false),
+            MintTrustDecision::Accept
+        );
+        assert_eq!(
+            policy.evaluate(&unknown, Amount::from(1_000), true),
+            MintTrustDecision::Accept
+        );
+        assert_eq!(
+            policy.evaluate(&unknown, Amount::from(100), false),
+            MintTrustDecision::Accept
+        );
+        assert!(matches!(
+            policy.evaluate(&unknown, Amount::from(101), false),
+            MintTrustDecision::Reject(_)
+        ));
+
+        policy.auto_swap_to_trusted = true;
+        assert_eq!(
+            policy.evaluate(&unknown, Amount::from(1), false),
+            MintTrustDecision::SwapToTrusted
+        );
+        assert_eq!(
+            policy.evaluate(&trusted, Amount::from(1), false),
+            MintTrustDecision::Accept
+        );
+    }
 }
@@ -14,6 +14,12 @@ pub enum Error {
     /// Unknown invoice
     #[error("No channel receiver")]
     NoReceiver,
+    /// Failure percentage out of range
+    #[error("Failure percent must be between 0 and 100, got {0}")]
+    InvalidFailurePercent(u8),
+    /// Failure injected by the failure injection settings
+    #[error("Injected melt failure")]
+    InjectedMeltFailure,
 }
 
 impl From<Error> for cdk_common::payment::Error {
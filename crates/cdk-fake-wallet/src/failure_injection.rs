@@ -0,0 +1,147 @@
+//! Runtime failure injection for the fake wallet
+//!
+//! Lets integration tests exercise error paths without relying on randomness.
+//! Melt failures are spread evenly over attempts, so a failure percentage of
+//! 50 fails exactly every second melt.
+
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+
+use serde::{Deserialize, Serialize};
+use tokio::sync::RwLock;
+
+use crate::error::Error;
+
+/// Failure injection settings of the fake wallet
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FailureInjectionSettings {
+    /// Percentage (0-100) of melt attempts that fail
+    pub melt_failure_percent: u8,
+    /// Additional delay in seconds before an incoming payment is reported as paid
+    pub paid_notification_delay: u64,
+    /// Number of partial payments an incoming bolt12 payment is split into
+    ///
+    /// `0` and `1` pay the full amount at once.
+    pub bolt12_partial_payments: u32,
+}
+
+impl FailureInjectionSettings {
+    fn validate(&self) -> Result<(), Error> {
+        if self.melt_failure_percent > 100 {
+            return Err(Error::InvalidFailurePercent(self.melt_failure_percent));
+        }
+
+        Ok(())
+    }
+}
+
+/// Shared handle to the failure injection settings of one or more fake wallets
+#[derive(Debug, Clone, Default)]
+pub struct FailureInjection {
+    settings: Arc<RwLock<FailureInjectionSettings>>,
+    melt_attempts: Arc<AtomicU64>,
+}
+
+impl FailureInjection {
+    /// Create new [`FailureInjection`]
+    pub fn new(settings: FailureInjectionSettings) -> Result<Self, Error> {
+        settings.validate()?;
+
+        Ok(Self {
+            settings: Arc::new(RwLock::new(settings)),
+            melt_attempts: Arc::new(AtomicU64::new(0)),
+        })
+    }
+
+    /// Current settings
+    pub async fn settings(&self) -> FailureInjectionSettings {
+        self.settings.read().await.clone()
+    }
+
+    /// Replace the settings
+    ///
+    /// Resets the melt attempt counter so the failure pattern starts over.
+    pub async fn update(&self, settings: FailureInjectionSettings) -> Result<(), Error> {
+        settings.validate()?;
+
+        let mut current = self.settings.write().await;
+        *current = settings;
+        self.melt_attempts.store(0, Ordering::SeqCst);
+
+        Ok(())
+    }
+
+    /// Record a melt attempt and decide if it should fail
+    pub(crate) async fn should_fail_melt(&self) -> bool {
+        let percent = u64::from(self.settings.read().await.melt_failure_percent);
+        let attempt = self.melt_attempts.fetch_add(1, Ordering::SeqCst) + 1;
+
+        // Fail whenever the running share of failures crosses the next whole attempt
+        attempt * percent / 100 > (attempt - 1) * percent / 100
+    }
+
+    /// Delay before reporting an incoming payment as paid
+    pub(crate) async fn paid_notification_delay(&self) -> u64 {
+        self.settings.read().await.paid_notification_delay
+    }
+
+    /// Number of parts an incoming bolt12 payment is split into
+    pub(crate) async fn bolt12_partial_payments(&self) -> u32 {
+        self.settings.read().await.bolt12_partial_payments.max(1)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    async fn failures(percent: u8, attempts: usize) -> Vec<bool> {
+        let injection = FailureInjection::new(FailureInjectionSettings {
+            melt_failure_percent: percent,
+            ..Default::default()
+        })
+        .expect("valid settings");
+
+        let mut results = Vec::with_capacity(attempts);
+        for _ in 0..attempts {
+            results.push(injection.should_fail_melt().await);
+        }
+        results
+    }
+
+    #[tokio::test]
+    async fn melt_failures_are_deterministic() {
+        assert_eq!(failures(0, 4).await, vec![false; 4]);
+        assert_eq!(failures(100, 4).await, vec![true; 4]);
+        assert_eq!(failures(50, 4).await, vec![false, true, false, true]);
+        assert_eq!(failures(25, 8).await.iter().filter(|f| **f).count(), 2);
+    }
+
+    #[tokio::test]
+    async fn update_resets_attempts() {
+        let injection = FailureInjection::default();
+        assert!(!injection.should_fail_melt().await);
+
+        injection
+            .update(FailureInjectionSettings {
+                melt_failure_percent: 50,
+                ..Default::default()
+            })
+            .await
+            .expect("valid settings");
+
+        assert!(!injection.should_fail_melt().await);
+        assert!(injection.should_fail_melt().await);
+    }
+
+    #[tokio::test]
+    async fn invalid_percent_is_rejected() {
+        let settings = FailureInjectionSettings {
+            melt_failure_percent: 101,
+            ..Default::default()
+        };
+
+        assert!(FailureInjection::new(settings.clone()).is_err());
+        assert!(FailureInjection::default().update(settings).await.is_err());
+    }
+}
@@ -23,6 +23,7 @@ use bitcoin::hashes::{sha256, Hash};
 use bitcoin::secp256k1::{Secp256k1, SecretKey};
 use bitcoin::{Address, CompressedPublicKey, Network};
 use cdk_common::amount::Amount;
+use cdk_common::clock::{Clock, SystemClock};
 use cdk_common::common::FeeReserve;
 use cdk_common::ensure_cdk;
 use cdk_common::nuts::nut30::MeltQuoteOnchainFeeOption;
@@ -33,10 +34,14 @@ use cdk_common::payment::{
     WaitPaymentResponse,
 };
 use error::Error;
+pub use failure_injection::{FailureInjection, FailureInjectionSettings};
 use futures::stream::StreamExt;
 use futures::Stream;
 use lightning::offers::offer::OfferBuilder;
-use lightning_invoice::{Bolt11Invoice, Currency, InvoiceBuilder, PaymentSecret};
+use lightning_invoice::{
+    Bolt11Invoice, Bolt11InvoiceDescription, Currency, Description, InvoiceBuilder, PaymentSecret,
+    Sha256,
+};
 use serde::{Deserialize, Serialize};
 use tokio::sync::{Mutex, RwLock};
 use tokio::time;
@@ -46,6 +51,7 @@ use tracing::instrument;
 use uuid::Uuid;
 
 pub mod error;
+mod failure_injection;
 
 /// Default maximum size for the secondary repayment queue
 const DEFAULT_REPAY_QUEUE_MAX_SIZE: usize = 100;
@@ -351,6 +357,8 @@ pub struct FakeWallet {
     secondary_repayment_queue: SecondaryRepaymentQueue,
     exchange_rate_cache: ExchangeRateCache,
     custom_payment_methods: HashMap<String, String>,
+    failure_injection: FailureInjection,
+    clock: Arc<dyn Clock>,
 }
 
 impl FakeWallet {
@@ -401,9 +409,33 @@ impl FakeWallet {
             secondary_repayment_queue,
             exchange_rate_cache: ExchangeRateCache::new(),
             custom_payment_methods: HashMap::new(),
+            failure_injection: FailureInjection::default(),
+            clock: Arc::new(SystemClock),
         }
     }
 
+    /// Wait out payment delays on `clock` instead of the system time
+    ///
+    /// With a [`SimulatedClock`](cdk_common::clock::SimulatedClock) delayed payments settle as
+    /// soon as the test advances the clock past the delay.
+    pub fn with_clock(mut self, clock: Arc<dyn Clock>) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    /// Use shared failure injection controls.
+    ///
+    /// The same handle can be given to several fake wallets and updated at runtime.
+    pub fn with_failure_injection(mut self, failure_injection: FailureInjection) -> Self {
+        self.failure_injection = failure_injection;
+        self
+    }
+
+    /// Failure injection controls of this fake wallet
+    pub fn failure_injection(&self) -> FailureInjection {
+        self.failure_injection.clone()
+    }
+
     /// Configure custom payment methods advertised by this fake wallet.
     pub fn with_custom_payment_methods(
         mut self,
@@ -668,7 +700,12 @@ impl MintPayment for FakeWallet {
     ) -> Result<MakePaymentResponse, Self::Err> {
         if self.payment_delay > 0 {
             let duration = time::Duration::from_secs(self.payment_delay);
-            time::sleep(duration).await;
+            self.clock.sleep(duration).await;
+        }
+
+        if self.failure_injection.should_fail_melt().await {
+            tracing::info!("Fake wallet failing melt due to failure injection");
+            return Err(Error::InjectedMeltFailure.into());
         }
 
         match options {
@@ -873,7 +910,16 @@ impl MintPayment for FakeWallet {
                 )
                 .await?;
 
-                let invoice = create_fake_invoice(amount_msat.value(), description.clone());
+                let invoice = if bolt11_options.hash_description {
+                    create_fake_invoice_with_description(
+                        amount_msat.value(),
+                        Bolt11InvoiceDescription::Hash(Sha256(sha256::Hash::hash(
+                            description.as_bytes(),
+                        ))),
+                    )
+                } else {
+                    create_fake_invoice(amount_msat.value(), description.clone())
+                };
                 let payment_hash = invoice.payment_hash();
 
                 (
@@ -912,7 +958,10 @@ impl MintPayment for FakeWallet {
 
         // ALL invoices get immediate payment processing (original behavior)
         let sender = self.sender.clone();
-        let duration = time::Duration::from_secs(self.payment_delay);
+        let duration = time::Duration::from_secs(
+            self.payment_delay + self.failure_injection.paid_notification_delay().await,
+        );
+        let clock = self.clock.clone();
         let payment_hash_clone = payment_hash.clone();
         let incoming_payment = self.incoming_payments.clone();
 
@@ -931,25 +980,39 @@ impl MintPayment for FakeWallet {
         // Check if this is an any-amount invoice before moving final_amount
         let is_any_amount = final_amount.value() == 0;
 
+        let parts = match payment_hash {
+            PaymentIdentifier::OfferId(_) => self.failure_injection.bolt12_partial_payments().await,
+            _ => 1,
+        };
+
         // Schedule the immediate payment (original behavior maintained)
         tokio::spawn(async move {
             // Wait for the random delay to elapse
-            time::sleep(duration).await;
+            clock.sleep(duration).await;
 
-            let response = WaitPaymentResponse {
-                payment_identifier: payment_hash_clone.clone(),
-                payment_amount: final_amount,
-                payment_id: payment_hash_clone.to_string(),
-            };
-            let mut incoming = incoming_payment.write().await;
-            incoming
-                .entry(payment_hash_clone.clone())
-                .or_insert_with(Vec::new)
-                .push(response.clone());
-
-            // Send the message after waiting for the specified duration
-            if sender.send(response.clone()).await.is_err() {
-                tracing::error!("Failed to send label: {:?}", payment_hash_clone);
+            for (index, payment_amount) in split_amount(final_amount, parts).into_iter().enumerate()
+            {
+                let payment_id = match index {
+                    0 => payment_hash_clone.to_string(),
+                    index => format!("{payment_hash_clone}-{index}"),
+                };
+
+                let response = WaitPaymentResponse {
+                    payment_identifier: payment_hash_clone.clone(),
+                    payment_amount,
+                    payment_id,
+                };
+                incoming_payment
+                    .write()
+                    .await
+                    .entry(payment_hash_clone.clone())
+                    .or_insert_with(Vec::new)
+                    .push(response.clone());
+
+                // Send the message after waiting for the specified duration
+                if sender.send(response).await.is_err() {
+                    tracing::error!("Failed to send label: {:?}", payment_hash_clone);
+                }
             }
         });
 
@@ -1021,13 +1084,48 @@ impl MintPayment for FakeWallet {
     }
 }
 
+/// Split an amount into `parts` payments, the first one carrying the remainder
+fn split_amount(amount: Amount<CurrencyUnit>, parts: u32) -> Vec<Amount<CurrencyUnit>> {
+    let parts = u64::from(parts).clamp(1, max(amount.value(), 1));
+    let share = amount.value() / parts;
+    let remainder = amount.value() % parts;
+
+    (0..parts)
+        .map(|index| {
+            let value = match index {
+                0 => share + remainder,
+                _ => share,
+            };
+            Amount::new(value, amount.unit().clone())
+        })
+        .collect()
+}
+
 /// Create fake invoice
 ///
 /// # Panics
 ///
-/// Panics if the hardcoded secret key or payment hash bytes are invalid.
+/// Panics if the description is too long for an invoice, see
+/// [`create_fake_invoice_with_description`].
 #[instrument]
 pub fn create_fake_invoice(amount_msat: u64, description: String) -> Bolt11Invoice {
+    create_fake_invoice_with_description(
+        amount_msat,
+        Bolt11InvoiceDescription::Direct(
+            Description::new(description).expect("Valid invoice description"),
+        ),
+    )
+}
+
+/// Create a fake invoice committing to `description`, which may be a description hash
+///
+/// # Panics
+///
+/// Panics if the hardcoded secret key or payment hash bytes are invalid.
+pub fn create_fake_invoice_with_description(
+    amount_msat: u64,
+    description: Bolt11InvoiceDescription,
+) -> Bolt11Invoice {
     let private_key = SecretKey::from_slice(
         &[
             0xe1, 0x26, 0xf6, 0x8f, 0x7e, 0xaf, 0xcc, 0x8b, 0x74, 0xf5, 0x4d, 0x26, 0x9f, 0xe2,
@@ -1047,7 +1145,7 @@ pub fn create_fake_invoice(amount_msat: u64, description: String) -> Bolt11Invoi
     let payment_secret = PaymentSecret([42u8; 32]);
 
     InvoiceBuilder::new(Currency::Bitcoin)
-        .description(description)
+        .invoice_description(description)
         .payment_hash(payment_hash)
         .payment_secret(payment_secret)
         .amount_milli_satoshis(amount_msat)
@@ -1160,6 +1258,7 @@ mod tests {
                     amount: Some(Amount::new(10, CurrencyUnit::Sat)),
                     unix_expiry: None,
                     extra_json: None,
+                    quote_id: cdk_common::QuoteId::new(),
                 },
             )))
             .await
@@ -1213,6 +1312,39 @@ mod tests {
         assert_eq!(response.status, MeltQuoteState::Paid);
     }
 
+    #[tokio::test(start_paused = true)]
+    async fn configured_delay_waits_on_simulated_clock() {
+        let clock = Arc::new(cdk_common::clock::SimulatedClock::new(1_000));
+        let wallet = test_wallet_with_delay(60).with_clock(clock.clone());
+        let quote_id = cdk_common::QuoteId::new();
+
+        let options = OutgoingPaymentOptions::Onchain(Box::new(OnchainOutgoingPaymentOptions {
+            address: "bcrt1qfakeaddress".to_string(),
+            amount: Amount::new(100, CurrencyUnit::Sat),
+            max_fee_amount: None,
+            quote_id: quote_id.clone(),
+            fee_index: None,
+            metadata: None,
+        }));
+
+        let payment = wallet.make_payment(&CurrencyUnit::Sat, options);
+        tokio::pin!(payment);
+
+        // Tokio time passing does not move the simulated clock
+        let early_result = tokio::time::timeout(time::Duration::from_secs(120), &mut payment).await;
+        assert!(
+            early_result.is_err(),
+            "outgoing fake payment completed before the simulated clock advanced"
+        );
+
+        clock.advance(time::Duration::from_secs(60));
+
+        let response = payment
+            .await
+            .expect("fake onchain payment should complete once the clock advanced");
+        assert_eq!(response.status, MeltQuoteState::Paid);
+    }
+
     #[tokio::test]
     async fn custom_outgoing_quote_prefers_typed_amount() {
         let wallet = test_wallet()
@@ -1266,4 +1398,81 @@ mod tests {
 
         assert_eq!(response.amount, Amount::new(30, CurrencyUnit::Sat));
     }
+
+    #[tokio::test]
+    async fn injected_melt_failure_fails_payment() {
+        let failure_injection = FailureInjection::new(FailureInjectionSettings {
+            melt_failure_percent: 100,
+            ..Default::default()
+        })
+        .expect("valid failure injection settings");
+        let wallet = test_wallet()
+            .with_custom_payment_methods(HashMap::from([("venmo".to_string(), "{}".to_string())]))
+            .with_failure_injection(failure_injection.clone());
+
+        let result = wallet
+            .make_payment(&CurrencyUnit::Sat, custom_outgoing_options(None, None))
+            .await;
+        assert!(result.is_err());
+
+        failure_injection
+            .update(FailureInjectionSettings::default())
+            .await
+            .expect("valid failure injection settings");
+
+        wallet
+            .make_payment(&CurrencyUnit::Sat, custom_outgoing_options(None, None))
+            .await
+            .expect("payment should succeed once failures are disabled");
+    }
+
+    #[tokio::test]
+    async fn bolt12_payment_is_split_into_partial_payments() {
+        let wallet = test_wallet().with_failure_injection(
+            FailureInjection::new(FailureInjectionSettings {
+                bolt12_partial_payments: 3,
+                ..Default::default()
+            })
+            .expect("valid failure injection settings"),
+        );
+
+        let response = wallet
+            .create_incoming_payment_request(IncomingPaymentOptions::Bolt12(Box::new(
+                payment::Bolt12IncomingPaymentOptions {
+                    description: None,
+                    amount: Some(Amount::new(10, CurrencyUnit::Sat)),
+                    unix_expiry: None,
+                },
+            )))
+            .await
+            .expect("bolt12 offer should be created");
+
+        let mut stream = wallet
+            .wait_payment_event()
+            .await
+            .expect("payment stream should start");
+
+        let mut amounts = Vec::new();
+        for _ in 0..3 {
+            match stream.next().await {
+                Some(Event::PaymentReceived(payment)) => {
+                    assert_eq!(payment.payment_identifier, response.request_lookup_id);
+                    amounts.push(payment.payment_amount.value());
+                }
+                _ => panic!("expected partial payment"),
+            }
+        }
+
+        assert_eq!(amounts, vec![4, 3, 3]);
+    }
+
+    #[test]
+    fn split_amount_keeps_total() {
+        let parts = split_amount(Amount::new(2, CurrencyUnit::Sat), 5);
+        assert_eq!(parts.len(), 2);
+        assert_eq!(parts.iter().map(|a| a.value()).sum::<u64>(), 2);
+
+        let parts = split_amount(Amount::new(10, CurrencyUnit::Sat), 0);
+        assert_eq!(parts, vec![Amount::new(10, CurrencyUnit::Sat)]);
+    }
 }
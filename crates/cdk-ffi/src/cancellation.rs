@@ -0,0 +1,95 @@
+//! Cancellation of long-running wallet operations
+//!
+//! Foreign apps pass a [`CancellationToken`] to the cancellable variants of
+//! melt, receive and restore and cancel it when the user navigates away.
+
+use std::future::Future;
+
+use cdk::wallet::{StartedSagas, Wallet as CdkWallet};
+
+use crate::error::FfiError;
+
+/// Token used to cancel an in-flight wallet operation
+#[derive(Debug, Default, uniffi::Object)]
+pub struct CancellationToken {
+    inner: tokio_util::sync::CancellationToken,
+}
+
+#[uniffi::export]
+impl CancellationToken {
+    /// Create a new token that is not cancelled
+    #[uniffi::constructor]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Cancel every operation using this token
+    pub fn cancel(&self) {
+        self.inner.cancel();
+    }
+
+    /// Whether the token has been cancelled
+    pub fn is_cancelled(&self) -> bool {
+        self.inner.is_cancelled()
+    }
+}
+
+impl CancellationToken {
+    /// Run `operation` until it completes or the token is cancelled
+    ///
+    /// On cancellation the operation is dropped and the sagas it started, along
+    /// with `saga_ids` started before, are recovered, so proofs reserved by the
+    /// operation are released. Sagas of concurrent operations are left alone.
+    pub(crate) async fn run<T, F>(
+        &self,
+        wallet: &CdkWallet,
+        saga_ids: &[uuid::Uuid],
+        operation: F,
+    ) -> Result<T, FfiError>
+    where
+        F: Future<Output = Result<T, FfiError>>,
+    {
+        if self.is_cancelled() {
+            return Err(FfiError::Cancelled);
+        }
+
+        let started = StartedSagas::default();
+
+        tokio::select! {
+            biased;
+            _ = self.inner.cancelled() => {
+                let mut saga_ids = saga_ids.to_vec();
+                saga_ids.extend(started.ids());
+                release_reserved(wallet, &saga_ids).await;
+                Err(FfiError::Cancelled)
+            }
+            result = started.scope(operation) => result,
+        }
+    }
+}
+
+/// Roll back the sagas left behind by a dropped operation
+async fn release_reserved(wallet: &CdkWallet, saga_ids: &[uuid::Uuid]) {
+    match wallet.recover_sagas_by_id(saga_ids).await {
+        Ok(report) => tracing::debug!(
+            "Recovered sagas after cancellation: {} compensated, {} recovered",
+            report.compensated,
+            report.recovered
+        ),
+        Err(err) => tracing::warn!("Could not recover sagas after cancellation: {}", err),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cancellation_token_state() {
+        let token = CancellationToken::new();
+        assert!(!token.is_cancelled());
+
+        token.cancel();
+        assert!(token.is_cancelled());
+    }
+}
@@ -4,7 +4,10 @@ use std::collections::HashMap;
 use std::sync::Arc;
 
 use cdk_common::bitcoin::bip32::DerivationPath;
-use cdk_common::database::WalletDatabase as CdkWalletDatabase;
+use cdk_common::database::wallet::BufferedTransaction;
+use cdk_common::database::{
+    WalletDatabase as CdkWalletDatabase, WalletDatabaseTransaction as CdkWalletDatabaseTransaction,
+};
 use cdk_common::wallet::WalletSaga;
 
 use crate::error::FfiError;
@@ -88,6 +91,20 @@ pub trait WalletDatabase: Send + Sync {
         unit: Option<CurrencyUnit>,
     ) -> Result<Vec<Transaction>, FfiError>;
 
+    /// List one page of the transactions of a mint and unit with a timestamp in `from..to`
+    ///
+    /// Transactions are ordered by timestamp, oldest first.
+    #[allow(clippy::too_many_arguments)]
+    async fn list_transactions_page(
+        &self,
+        mint_url: MintUrl,
+        unit: CurrencyUnit,
+        from: u64,
+        to: u64,
+        offset: u64,
+        limit: u64,
+    ) -> Result<Vec<Transaction>, FfiError>;
+
     /// Read a value from the KV store
     async fn kv_read(
         &self,
@@ -265,6 +282,7 @@ pub trait WalletDatabase: Send + Sync {
 
 /// Internal bridge trait to convert from the FFI trait to the CDK database trait
 /// This allows us to bridge between the UniFFI trait and the CDK's internal database trait
+#[derive(Clone)]
 struct WalletDatabaseBridge {
     ffi_db: Arc<dyn WalletDatabase>,
 }
@@ -283,6 +301,16 @@ impl std::fmt::Debug for WalletDatabaseBridge {
 
 #[async_trait::async_trait]
 impl CdkWalletDatabase<cdk::cdk_database::Error> for WalletDatabaseBridge {
+    /// Foreign databases only expose single writes, so writes are buffered until commit
+    async fn begin_db_transaction(
+        &self,
+    ) -> Result<
+        Box<dyn CdkWalletDatabaseTransaction<cdk::cdk_database::Error> + Send + Sync>,
+        cdk::cdk_database::Error,
+    > {
+        Ok(Box::new(BufferedTransaction::new(Arc::new(self.clone()))))
+    }
+
     async fn kv_read(
         &self,
         primary_namespace: &str,
@@ -676,6 +704,28 @@ impl CdkWalletDatabase<cdk::cdk_database::Error> for WalletDatabaseBridge {
             .map_err(|e| cdk::cdk_database::Error::Database(e.to_string().into()))
     }
 
+    async fn list_transactions_page(
+        &self,
+        mint_url: cdk::mint_url::MintUrl,
+        unit: cdk::nuts::CurrencyUnit,
+        from: u64,
+        to: u64,
+        offset: u64,
+        limit: u64,
+    ) -> Result<Vec<cdk::wallet::types::Transaction>, cdk::cdk_database::Error> {
+        let result = self
+            .ffi_db
+            .list_transactions_page(mint_url.into(), unit.into(), from, to, offset, limit)
+            .await
+            .map_err(|e| cdk::cdk_database::Error::Database(e.to_string().into()))?;
+
+        result
+            .into_iter()
+            .map(|tx| tx.try_into())
+            .collect::<Result<Vec<_>, FfiError>>()
+            .map_err(|e| cdk::cdk_database::Error::Database(e.to_string().into()))
+    }
+
     // P2PK methods
 
     async fn add_p2pk_key(
@@ -1381,6 +1431,24 @@ where
         Ok(result.into_iter().map(Into::into).collect())
     }
 
+    async fn list_transactions_page(
+        &self,
+        mint_url: MintUrl,
+        unit: CurrencyUnit,
+        from: u64,
+        to: u64,
+        offset: u64,
+        limit: u64,
+    ) -> Result<Vec<Transaction>, FfiError> {
+        let result = self
+            .inner
+            .list_transactions_page(mint_url.try_into()?, unit.into(), from, to, offset, limit)
+            .await
+            .map_err(FfiError::internal)?;
+
+        Ok(result.into_iter().map(Into::into).collect())
+    }
+
     async fn kv_read(
         &self,
         primary_namespace: String,
@@ -1898,6 +1966,20 @@ macro_rules! impl_ffi_wallet_database {
                     .await
             }
 
+            async fn list_transactions_page(
+                &self,
+                mint_url: MintUrl,
+                unit: CurrencyUnit,
+                from: u64,
+                to: u64,
+                offset: u64,
+                limit: u64,
+            ) -> Result<Vec<Transaction>, FfiError> {
+                self.inner
+                    .list_transactions_page(mint_url, unit, from, to, offset, limit)
+                    .await
+            }
+
             async fn kv_read(
                 &self,
                 primary_namespace: String,
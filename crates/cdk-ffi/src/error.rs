@@ -27,6 +27,10 @@ pub enum FfiError {
         /// Human-readable error message
         error_message: String,
     },
+
+    /// The operation was cancelled through a `CancellationToken`
+    #[error("Operation cancelled")]
+    Cancelled,
 }
 
 impl FfiError {
@@ -0,0 +1,218 @@
+//! FFI wallet event callbacks
+//!
+//! Lets foreign apps register a listener that is notified when a mint quote is
+//! paid, proofs are received, expired quotes are purged or the balance changes,
+//! instead of polling.
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use cdk::wallet::Wallet as CdkWallet;
+use cdk_common::parking_lot::RwLock;
+use tokio::sync::Mutex;
+
+use crate::types::Amount;
+
+/// How long a mint quote without expiry is watched for payment
+const MINT_QUOTE_WATCH_TIMEOUT: Duration = Duration::from_secs(24 * 60 * 60);
+
+/// Wallet event pushed to a [`WalletEventListener`]
+#[derive(Debug, Clone, PartialEq, Eq, uniffi::Enum)]
+pub enum WalletEvent {
+    /// A mint quote has been paid and can be minted
+    MintQuotePaid {
+        /// Quote id
+        quote_id: String,
+        /// Amount paid, if reported by the mint
+        amount: Option<Amount>,
+    },
+    /// Proofs were added to the wallet
+    ProofsReceived {
+        /// Total amount of the received proofs
+        amount: Amount,
+    },
+    /// Expired unpaid quotes were removed from the wallet
+    QuotesPurged {
+        /// Ids of the removed mint quotes
+        mint_quotes: Vec<String>,
+        /// Ids of the removed melt quotes
+        melt_quotes: Vec<String>,
+    },
+    /// The spendable balance of the wallet changed
+    BalanceChanged {
+        /// New spendable balance
+        balance: Amount,
+    },
+}
+
+/// Listener for wallet events, implemented by the foreign app
+#[uniffi::export(with_foreign)]
+#[async_trait::async_trait]
+pub trait WalletEventListener: Send + Sync {
+    /// Called for every wallet event
+    async fn on_event(&self, event: WalletEvent);
+}
+
+/// Dispatches wallet events to the registered listener
+#[derive(Clone, Default)]
+pub(crate) struct WalletEventEmitter {
+    listener: Arc<RwLock<Option<Arc<dyn WalletEventListener>>>>,
+    last_balance: Arc<Mutex<Option<cdk::Amount>>>,
+}
+
+impl WalletEventEmitter {
+    /// Replace the registered listener
+    pub(crate) fn set_listener(&self, listener: Option<Arc<dyn WalletEventListener>>) {
+        *self.listener.write() = listener;
+    }
+
+    fn listener(&self) -> Option<Arc<dyn WalletEventListener>> {
+        self.listener.read().clone()
+    }
+
+    async fn emit(&self, event: WalletEvent) {
+        if let Some(listener) = self.listener() {
+            listener.on_event(event).await;
+        }
+    }
+
+    /// Report received proofs followed by the resulting balance change
+    pub(crate) async fn proofs_received(&self, wallet: &CdkWallet, amount: cdk::Amount) {
+        if self.listener().is_none() {
+            return;
+        }
+
+        if amount > cdk::Amount::ZERO {
+            self.emit(WalletEvent::ProofsReceived {
+                amount: amount.into(),
+            })
+            .await;
+        }
+
+        self.balance_changed(wallet).await;
+    }
+
+    /// Report quotes removed by a purge
+    pub(crate) async fn quotes_purged(&self, purged: &cdk::wallet::PurgedQuotes) {
+        if purged.is_empty() {
+            return;
+        }
+
+        self.emit(WalletEvent::QuotesPurged {
+            mint_quotes: purged.mint_quotes.clone(),
+            melt_quotes: purged.melt_quotes.clone(),
+        })
+        .await;
+    }
+
+    /// Report the balance if it changed since the last report
+    pub(crate) async fn balance_changed(&self, wallet: &CdkWallet) {
+        if self.listener().is_none() {
+            return;
+        }
+
+        let balance = match wallet.total_balance().await {
+            Ok(balance) => balance,
+            Err(err) => {
+                tracing::warn!("Could not get balance for wallet event: {}", err);
+                return;
+            }
+        };
+
+        {
+            let mut last_balance = self.last_balance.lock().await;
+            if *last_balance == Some(balance) {
+                return;
+            }
+            *last_balance = Some(balance);
+        }
+
+        self.emit(WalletEvent::BalanceChanged {
+            balance: balance.into(),
+        })
+        .await;
+    }
+
+    /// Watch a mint quote in the background and report when it is paid
+    ///
+    /// Does nothing when no listener is registered.
+    pub(crate) fn watch_mint_quote(&self, wallet: Arc<CdkWallet>, quote: cdk::wallet::MintQuote) {
+        if self.listener().is_none() {
+            return;
+        }
+
+        let emitter = self.clone();
+        let timeout = quote
+            .expiry
+            .checked_sub(cdk::util::unix_time())
+            .filter(|remaining| *remaining > 0)
+            .map(Duration::from_secs)
+            .unwrap_or(MINT_QUOTE_WATCH_TIMEOUT);
+
+        tokio::spawn(async move {
+            match wallet.wait_for_payment(&quote, timeout).await {
+                Ok(amount) => {
+                    emitter
+                        .emit(WalletEvent::MintQuotePaid {
+                            quote_id: quote.id.clone(),
+                            amount: amount.map(Into::into),
+                        })
+                        .await;
+                }
+                Err(err) => {
+                    tracing::debug!("Stopped watching mint quote {}: {}", quote.id, err);
+                }
+            }
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Default)]
+    struct RecordingListener {
+        events: Mutex<Vec<WalletEvent>>,
+    }
+
+    #[async_trait::async_trait]
+    impl WalletEventListener for RecordingListener {
+        async fn on_event(&self, event: WalletEvent) {
+            self.events.lock().await.push(event);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_events_reach_registered_listener() {
+        let emitter = WalletEventEmitter::default();
+        let listener = Arc::new(RecordingListener::default());
+
+        emitter
+            .emit(WalletEvent::BalanceChanged {
+                balance: Amount::new(1),
+            })
+            .await;
+
+        emitter.set_listener(Some(listener.clone()));
+        emitter
+            .emit(WalletEvent::ProofsReceived {
+                amount: Amount::new(2),
+            })
+            .await;
+
+        emitter.set_listener(None);
+        emitter
+            .emit(WalletEvent::BalanceChanged {
+                balance: Amount::new(3),
+            })
+            .await;
+
+        assert_eq!(
+            *listener.events.lock().await,
+            vec![WalletEvent::ProofsReceived {
+                amount: Amount::new(2)
+            }]
+        );
+    }
+}
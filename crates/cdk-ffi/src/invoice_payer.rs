@@ -0,0 +1,17 @@
+//! FFI foreign Lightning invoice payer
+//!
+//! Lets foreign apps plug in their own Lightning wallet (e.g. an embedded
+//! node on mobile) so the wallet can pay its own mint quotes for one-tap
+//! top-ups.
+
+use crate::error::FfiError;
+
+/// Lightning wallet implemented by the foreign app
+#[uniffi::export(with_foreign)]
+#[async_trait::async_trait]
+pub trait InvoicePayer: Send + Sync {
+    /// Pay a bolt11 invoice
+    ///
+    /// Should return once the payment has been sent. An error aborts the top up.
+    async fn pay_invoice(&self, invoice: String) -> Result<(), FfiError>;
+}
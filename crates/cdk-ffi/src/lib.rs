@@ -7,8 +7,11 @@
 #![allow(missing_debug_implementations)]
 
 pub mod bip321;
+pub mod cancellation;
 pub mod database;
 pub mod error;
+pub mod events;
+pub mod invoice_payer;
 pub mod logging;
 #[cfg(feature = "npubcash")]
 pub mod npubcash;
@@ -26,8 +29,11 @@ pub mod wallet;
 pub mod wallet_repository;
 mod wallet_trait;
 
+pub use cancellation::*;
 pub use database::*;
 pub use error::*;
+pub use events::*;
+pub use invoice_payer::*;
 pub use logging::*;
 #[cfg(feature = "npubcash")]
 pub use npubcash::*;
@@ -207,11 +213,13 @@ mod tests {
                 tolerance: Amount::new(50),
             },
             include_fee: true,
+            fee_policy: SendFeePolicy::Split,
             max_proofs: Some(10),
             metadata,
             use_p2bk: false,
             p2pk_signing_keys: Vec::new(),
             p2pk_locked_proof_send_mode: P2PKLockedProofSendMode::Swap,
+            include_dleq: false,
         };
 
         assert!(options.memo.is_some());
@@ -225,6 +233,7 @@ mod tests {
             SendKind::OnlineTolerance { .. }
         ));
         assert!(options.include_fee);
+        assert_eq!(options.fee_policy, SendFeePolicy::Split);
         assert_eq!(options.max_proofs, Some(10));
         assert!(!options.metadata.is_empty());
     }
@@ -244,6 +253,8 @@ mod tests {
             p2pk_signing_keys: vec![secret_key],
             preimages: vec!["preimage1".to_string(), "preimage2".to_string()],
             metadata,
+            transfer_from_other_mint: false,
+            mint_trust_policy: None,
         };
 
         assert!(matches!(
@@ -264,6 +275,8 @@ mod tests {
             }],
             preimages: Vec::new(),
             metadata: Default::default(),
+            transfer_from_other_mint: false,
+            mint_trust_policy: None,
         };
 
         let result: Result<cdk::wallet::ReceiveOptions, _> = options.try_into();
@@ -605,4 +618,19 @@ mod tests {
 
         assert_eq!(id.to_string(), "009a1f293253e41e");
     }
+
+    #[test]
+    fn test_inspect_token() {
+        let info = token::inspect_token("cashuBo2F0gqJhaUgA_9SLj17PgGFwgaNhYQFhc3hAYWNjMTI0MzVlN2I4NDg0YzNjZjE4NTAxNDkyMThhZjkwZjcxNmE1MmJmNGE1ZWQzNDdlNDhlY2MxM2Y3NzM4OGFjWCECRFODGd5IXVW-07KaZCvuWHk3WrnnpiDhHki6SCQh88-iYWlIAK0mjE0fWCZhcIKjYWECYXN4QDEzMjNkM2Q0NzA3YTU4YWQyZTIzYWRhNGU5ZjFmNDlmNWE1YjRhYzdiNzA4ZWIwZDYxZjczOGY0ODMwN2U4ZWVhY1ghAjRWqhENhLSsdHrr2Cw7AFrKUL9Ffr1XN6RBT6w659lNo2FhAWFzeEA1NmJjYmNiYjdjYzY0MDZiM2ZhNWQ1N2QyMTc0ZjRlZmY4YjQ0MDJiMTc2OTI2ZDNhNTdkM2MzZGNiYjU5ZDU3YWNYIQJzEpxXGeWZN5qXSmJjY8MzxWyvwObQGr5G1YCCgHicY2FtdWh0dHA6Ly9sb2NhbGhvc3Q6MzMzOGF1Y3NhdA==".to_string())
+            .expect("valid token");
+
+        assert_eq!(info.mint_url.url, "http://localhost:3338");
+        assert_eq!(info.unit, Some(CurrencyUnit::Sat));
+        assert_eq!(info.amount, Amount::new(4));
+        assert_eq!(info.proof_count, 3);
+        assert!(!info.p2pk_locked);
+        assert_eq!(info.dleq_proof_count, 0);
+
+        assert!(token::inspect_token("not a token".to_string()).is_err());
+    }
 }
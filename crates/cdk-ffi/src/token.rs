@@ -6,6 +6,39 @@ use std::str::FromStr;
 use crate::error::FfiError;
 use crate::{Amount, CurrencyUnit, KeySetInfo, MintUrl, Proofs};
 
+/// Summary of a token for previews, decoded without a wallet
+#[derive(Debug, Clone, PartialEq, Eq, uniffi::Record)]
+pub struct TokenInfo {
+    /// Mint URL
+    pub mint_url: MintUrl,
+    /// Currency unit, if set
+    pub unit: Option<CurrencyUnit>,
+    /// Total value of the proofs
+    pub amount: Amount,
+    /// Number of proofs
+    pub proof_count: u64,
+    /// Memo, if set
+    pub memo: Option<String>,
+    /// Whether any proof is locked with P2PK spending conditions
+    pub p2pk_locked: bool,
+    /// P2PK pubkeys the proofs are locked to
+    pub p2pk_pubkeys: Vec<String>,
+    /// P2PK refund pubkeys
+    pub p2pk_refund_pubkeys: Vec<String>,
+    /// HTLC hashes the proofs are locked to
+    pub htlc_hashes: Vec<String>,
+    /// Locktimes of the spending conditions (sorted ascending)
+    pub locktimes: Vec<u64>,
+    /// Number of proofs that include a DLEQ proof
+    pub dleq_proof_count: u64,
+}
+
+/// Decode a token string into a [`TokenInfo`] without a wallet
+#[uniffi::export]
+pub fn inspect_token(encoded_token: String) -> Result<TokenInfo, FfiError> {
+    Token::from_str(&encoded_token)?.info()
+}
+
 /// FFI-compatible Token
 #[derive(Debug, uniffi::Object)]
 pub struct Token {
@@ -70,6 +103,28 @@ impl Token {
         Ok(self.inner.mint_url()?.into())
     }
 
+    /// Summary of the token for previews
+    pub fn info(&self) -> Result<TokenInfo, FfiError> {
+        let p2pk_locked = self
+            .spending_conditions()
+            .iter()
+            .any(|conditions| matches!(conditions, crate::types::SpendingConditions::P2PK { .. }));
+
+        Ok(TokenInfo {
+            mint_url: self.mint_url()?,
+            unit: self.unit(),
+            amount: self.value()?,
+            proof_count: self.inner.proof_count() as u64,
+            memo: self.memo(),
+            p2pk_locked,
+            p2pk_pubkeys: self.p2pk_pubkeys(),
+            p2pk_refund_pubkeys: self.p2pk_refund_pubkeys(),
+            htlc_hashes: self.htlc_hashes(),
+            locktimes: self.locktimes(),
+            dleq_proof_count: self.inner.dleq_proof_count() as u64,
+        })
+    }
+
     /// Get proofs from the token (simplified - no keyset filtering for now)
     pub fn proofs_simple(&self) -> Result<Proofs, FfiError> {
         // For now, return empty keysets to get all proofs
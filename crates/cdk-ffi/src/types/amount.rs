@@ -90,6 +90,18 @@ impl From<Amount> for CdkAmount {
     }
 }
 
+/// Format an amount of `unit` for display with the unit's precision, e.g. `1.50 usd`
+#[uniffi::export]
+pub fn amount_display_with_unit(amount: Amount, unit: CurrencyUnit) -> String {
+    CdkAmount::from(amount).display_with_unit(&unit.into())
+}
+
+/// Parse a human readable amount into an amount of `unit`, e.g. `1.50 usd` or `0.5 sat` for msat
+#[uniffi::export]
+pub fn amount_from_str_with_unit(amount: String, unit: CurrencyUnit) -> Result<Amount, FfiError> {
+    Ok(CdkAmount::from_str_with_unit(&amount, &unit.into())?.into())
+}
+
 /// FFI-compatible FeeAndAmounts
 #[derive(Debug, Clone, Serialize, Deserialize, uniffi::Record)]
 pub struct FeeAndAmounts {
@@ -261,3 +261,42 @@ impl TryFrom<Id> for cdk::nuts::Id {
         Self::from_str(&id.hex).map_err(|e| FfiError::internal(format!("Invalid ID hex: {}", e)))
     }
 }
+
+/// FFI-compatible KeysCacheStats
+#[derive(Debug, Clone, Serialize, Deserialize, uniffi::Record)]
+pub struct KeysCacheStats {
+    /// Lookups answered from the cache
+    pub hits: u64,
+    /// Lookups that were not in the cache
+    pub misses: u64,
+    /// Keysets dropped to stay within the capacity
+    pub evictions: u64,
+    /// Keysets currently cached
+    pub entries: u64,
+    /// Maximum number of cached keysets
+    pub capacity: u64,
+}
+
+impl From<cdk::wallet::KeysCacheStats> for KeysCacheStats {
+    fn from(stats: cdk::wallet::KeysCacheStats) -> Self {
+        Self {
+            hits: stats.hits,
+            misses: stats.misses,
+            evictions: stats.evictions,
+            entries: stats.entries as u64,
+            capacity: stats.capacity as u64,
+        }
+    }
+}
+
+/// Set how many keysets the keys cache shared by all wallets keeps in memory
+#[uniffi::export]
+pub fn set_keys_cache_capacity(capacity: u64) {
+    cdk::wallet::KeysCache::global().set_capacity(capacity as usize);
+}
+
+/// Counters of the keys cache shared by all wallets
+#[uniffi::export]
+pub fn keys_cache_stats() -> KeysCacheStats {
+    cdk::wallet::KeysCache::global().stats().into()
+}
@@ -495,6 +495,8 @@ pub struct Nuts {
     pub nut08_supported: bool,
     /// NUT09 Settings - Restore signature
     pub nut09_supported: bool,
+    /// NUT09 Settings - Maximum number of outputs per restore request
+    pub nut09_max_outputs: Option<u64>,
     /// NUT10 Settings - Spending conditions
     pub nut10_supported: bool,
     /// NUT11 Settings - Pay to Public Key Hash
@@ -536,6 +538,7 @@ impl From<cdk::nuts::Nuts> for Nuts {
             nut07_supported: nuts.nut07.supported,
             nut08_supported: nuts.nut08.supported,
             nut09_supported: nuts.nut09.supported,
+            nut09_max_outputs: nuts.nut09.max_outputs.map(|max| max as u64),
             nut10_supported: nuts.nut10.supported,
             nut11_supported: nuts.nut11.supported,
             nut12_supported: nuts.nut12.supported,
@@ -563,8 +566,9 @@ impl TryFrom<Nuts> for cdk::nuts::Nuts {
             nut08: cdk::nuts::nut06::SupportedSettings {
                 supported: n.nut08_supported,
             },
-            nut09: cdk::nuts::nut06::SupportedSettings {
+            nut09: cdk::nuts::nut09::Settings {
                 supported: n.nut09_supported,
+                max_outputs: n.nut09_max_outputs.map(|max| max as usize),
             },
             nut10: cdk::nuts::nut06::SupportedSettings {
                 supported: n.nut10_supported,
@@ -736,7 +740,10 @@ mod tests {
             },
             nut07: cdk::nuts::nut06::SupportedSettings { supported: true },
             nut08: cdk::nuts::nut06::SupportedSettings { supported: true },
-            nut09: cdk::nuts::nut06::SupportedSettings { supported: false },
+            nut09: cdk::nuts::nut09::Settings {
+                supported: false,
+                max_outputs: None,
+            },
             nut10: cdk::nuts::nut06::SupportedSettings { supported: true },
             nut11: cdk::nuts::nut06::SupportedSettings { supported: true },
             nut12: cdk::nuts::nut06::SupportedSettings { supported: true },
@@ -892,7 +899,10 @@ mod tests {
             nut05: Default::default(),
             nut07: cdk::nuts::nut06::SupportedSettings { supported: true },
             nut08: cdk::nuts::nut06::SupportedSettings { supported: false },
-            nut09: cdk::nuts::nut06::SupportedSettings { supported: false },
+            nut09: cdk::nuts::nut09::Settings {
+                supported: false,
+                max_outputs: None,
+            },
             nut10: cdk::nuts::nut06::SupportedSettings { supported: false },
             nut11: cdk::nuts::nut06::SupportedSettings { supported: false },
             nut12: cdk::nuts::nut06::SupportedSettings { supported: false },
@@ -928,6 +938,7 @@ mod tests {
             nut07_supported: false,
             nut08_supported: false,
             nut09_supported: false,
+            nut09_max_outputs: None,
             nut10_supported: false,
             nut11_supported: false,
             nut12_supported: false,
@@ -1085,6 +1096,7 @@ mod tests {
                 nut07_supported: true,
                 nut08_supported: false,
                 nut09_supported: false,
+                nut09_max_outputs: None,
                 nut10_supported: false,
                 nut11_supported: false,
                 nut12_supported: true,
@@ -4,6 +4,7 @@ use serde::{Deserialize, Serialize};
 
 use super::amount::{Amount, CurrencyUnit};
 use super::mint::MintUrl;
+use super::wallet::MeltOptions;
 use crate::error::FfiError;
 
 /// FFI-compatible MintQuote
@@ -517,6 +518,9 @@ pub struct MeltQuote {
     pub estimated_blocks: Option<u32>,
     /// Selected fee option index for onchain quotes
     pub fee_index: Option<u32>,
+    /// Melt options (MPP or amountless) the quote was requested with
+    #[serde(default)]
+    pub options: Option<MeltOptions>,
     /// Payment method
     pub payment_method: PaymentMethod,
     /// Operation ID that reserved this quote
@@ -540,6 +544,7 @@ impl From<cdk::wallet::MeltQuote> for MeltQuote {
             payment_proof: quote.payment_proof.clone(),
             estimated_blocks: quote.estimated_blocks,
             fee_index: quote.fee_index,
+            options: quote.options.map(Into::into),
             payment_method: quote.payment_method.into(),
             used_by_operation: quote.used_by_operation.map(|id| id.to_string()),
             version: quote.version,
@@ -563,6 +568,7 @@ impl TryFrom<MeltQuote> for cdk::wallet::MeltQuote {
             payment_proof: quote.payment_proof,
             estimated_blocks: quote.estimated_blocks,
             fee_index: quote.fee_index,
+            options: quote.options.map(Into::into),
             payment_method: quote.payment_method.into(),
             used_by_operation: quote.used_by_operation,
             version: quote.version,
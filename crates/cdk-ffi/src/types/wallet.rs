@@ -9,7 +9,9 @@ use serde::{Deserialize, Serialize};
 
 use super::amount::{Amount, SplitTarget};
 use super::proof::{Proofs, SpendingConditions};
+use crate::cancellation::CancellationToken;
 use crate::error::FfiError;
+use crate::events::WalletEventEmitter;
 use crate::token::Token;
 use crate::{CurrencyUnit, MintUrl, PublicKey};
 
@@ -216,6 +218,40 @@ impl From<cdk::wallet::P2PKLockedProofSendMode> for P2PKLockedProofSendMode {
     }
 }
 
+/// FFI-compatible send fee policy
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, uniffi::Enum, Default,
+)]
+pub enum SendFeePolicy {
+    /// The receiver pays the fee of swapping the token
+    #[default]
+    ReceiverPays,
+    /// The token includes the fee so the receiver nets exactly the send amount
+    SenderPays,
+    /// The token includes half the fee, rounded down
+    Split,
+}
+
+impl From<SendFeePolicy> for cdk::wallet::SendFeePolicy {
+    fn from(policy: SendFeePolicy) -> Self {
+        match policy {
+            SendFeePolicy::ReceiverPays => cdk::wallet::SendFeePolicy::ReceiverPays,
+            SendFeePolicy::SenderPays => cdk::wallet::SendFeePolicy::SenderPays,
+            SendFeePolicy::Split => cdk::wallet::SendFeePolicy::Split,
+        }
+    }
+}
+
+impl From<cdk::wallet::SendFeePolicy> for SendFeePolicy {
+    fn from(policy: cdk::wallet::SendFeePolicy) -> Self {
+        match policy {
+            cdk::wallet::SendFeePolicy::ReceiverPays => SendFeePolicy::ReceiverPays,
+            cdk::wallet::SendFeePolicy::SenderPays => SendFeePolicy::SenderPays,
+            cdk::wallet::SendFeePolicy::Split => SendFeePolicy::Split,
+        }
+    }
+}
+
 /// FFI-compatible Send options
 #[derive(Debug, Clone, Serialize, Deserialize, uniffi::Record)]
 pub struct SendOptions {
@@ -229,6 +265,9 @@ pub struct SendOptions {
     pub send_kind: SendKind,
     /// Include fee
     pub include_fee: bool,
+    /// Who pays the input fee of the receiver's swap
+    #[serde(default)]
+    pub fee_policy: SendFeePolicy,
     pub use_p2bk: bool,
     /// Maximum number of proofs to include in the token
     pub max_proofs: Option<u32>,
@@ -240,6 +279,9 @@ pub struct SendOptions {
     /// How P2PK-locked input proofs should be handled during send
     #[serde(default)]
     pub p2pk_locked_proof_send_mode: P2PKLockedProofSendMode,
+    /// Also include keyset keys in the token (non-standard `k` field) for offline verification
+    #[serde(default)]
+    pub include_dleq: bool,
 }
 
 impl Default for SendOptions {
@@ -250,11 +292,13 @@ impl Default for SendOptions {
             amount_split_target: SplitTarget::None,
             send_kind: SendKind::OnlineExact,
             include_fee: false,
+            fee_policy: SendFeePolicy::ReceiverPays,
             max_proofs: None,
             metadata: HashMap::new(),
             use_p2bk: false,
             p2pk_signing_keys: Vec::new(),
             p2pk_locked_proof_send_mode: P2PKLockedProofSendMode::Swap,
+            include_dleq: false,
         }
     }
 }
@@ -275,11 +319,13 @@ impl TryFrom<SendOptions> for cdk::wallet::SendOptions {
             amount_split_target: opts.amount_split_target.into(),
             send_kind: opts.send_kind.into(),
             include_fee: opts.include_fee,
+            fee_policy: opts.fee_policy.into(),
             max_proofs: opts.max_proofs.map(|p| p as usize),
             metadata: opts.metadata,
             use_p2bk: opts.use_p2bk,
             p2pk_signing_keys,
             p2pk_locked_proof_send_mode: opts.p2pk_locked_proof_send_mode.into(),
+            include_dleq: opts.include_dleq,
         })
     }
 }
@@ -292,11 +338,13 @@ impl From<cdk::wallet::SendOptions> for SendOptions {
             amount_split_target: opts.amount_split_target.into(),
             send_kind: opts.send_kind.into(),
             include_fee: opts.include_fee,
+            fee_policy: opts.fee_policy.into(),
             max_proofs: opts.max_proofs.map(|p| p as u32),
             metadata: opts.metadata,
             use_p2bk: opts.use_p2bk,
             p2pk_signing_keys: opts.p2pk_signing_keys.into_iter().map(Into::into).collect(),
             p2pk_locked_proof_send_mode: opts.p2pk_locked_proof_send_mode.into(),
+            include_dleq: opts.include_dleq,
         }
     }
 }
@@ -395,6 +443,12 @@ pub struct ReceiveOptions {
     pub preimages: Vec<String>,
     /// Metadata
     pub metadata: HashMap<String, String>,
+    /// Melt a token issued by another mint at its mint and mint the amount at the wallet's mint
+    #[serde(default)]
+    pub transfer_from_other_mint: bool,
+    /// Trust policy the mint of received tokens is checked against
+    #[serde(default)]
+    pub mint_trust_policy: Option<MintTrustPolicy>,
 }
 
 impl Default for ReceiveOptions {
@@ -404,6 +458,8 @@ impl Default for ReceiveOptions {
             p2pk_signing_keys: Vec::new(),
             preimages: Vec::new(),
             metadata: HashMap::new(),
+            transfer_from_other_mint: false,
+            mint_trust_policy: None,
         }
     }
 }
@@ -423,6 +479,8 @@ impl TryFrom<ReceiveOptions> for cdk::wallet::ReceiveOptions {
             p2pk_signing_keys,
             preimages: opts.preimages,
             metadata: opts.metadata,
+            transfer_from_other_mint: opts.transfer_from_other_mint,
+            mint_trust_policy: opts.mint_trust_policy.map(TryInto::try_into).transpose()?,
         })
     }
 }
@@ -434,6 +492,53 @@ impl From<cdk::wallet::ReceiveOptions> for ReceiveOptions {
             p2pk_signing_keys: opts.p2pk_signing_keys.into_iter().map(Into::into).collect(),
             preimages: opts.preimages,
             metadata: opts.metadata,
+            transfer_from_other_mint: opts.transfer_from_other_mint,
+            mint_trust_policy: opts.mint_trust_policy.map(Into::into),
+        }
+    }
+}
+
+/// FFI-compatible mint trust policy
+#[derive(Debug, Clone, Default, Serialize, Deserialize, uniffi::Record)]
+pub struct MintTrustPolicy {
+    /// Mints tokens are always accepted from
+    pub allowlist: Vec<MintUrl>,
+    /// Mints tokens are never accepted from, takes precedence over the allowlist
+    pub denylist: Vec<MintUrl>,
+    /// Largest token kept as ecash of an unknown mint, `None` for no limit
+    pub max_amount_from_unknown_mint: Option<Amount>,
+    /// Move tokens of unknown mints to a trusted mint over Lightning instead of keeping them
+    pub auto_swap_to_trusted: bool,
+}
+
+impl TryFrom<MintTrustPolicy> for cdk::wallet::MintTrustPolicy {
+    type Error = FfiError;
+
+    fn try_from(policy: MintTrustPolicy) -> Result<Self, Self::Error> {
+        Ok(cdk::wallet::MintTrustPolicy {
+            allowlist: policy
+                .allowlist
+                .into_iter()
+                .map(TryInto::try_into)
+                .collect::<Result<_, _>>()?,
+            denylist: policy
+                .denylist
+                .into_iter()
+                .map(TryInto::try_into)
+                .collect::<Result<_, _>>()?,
+            max_amount_from_unknown_mint: policy.max_amount_from_unknown_mint.map(Into::into),
+            auto_swap_to_trusted: policy.auto_swap_to_trusted,
+        })
+    }
+}
+
+impl From<cdk::wallet::MintTrustPolicy> for MintTrustPolicy {
+    fn from(policy: cdk::wallet::MintTrustPolicy) -> Self {
+        Self {
+            allowlist: policy.allowlist.into_iter().map(Into::into).collect(),
+            denylist: policy.denylist.into_iter().map(Into::into).collect(),
+            max_amount_from_unknown_mint: policy.max_amount_from_unknown_mint.map(Into::into),
+            auto_swap_to_trusted: policy.auto_swap_to_trusted,
         }
     }
 }
@@ -507,6 +612,7 @@ pub struct PreparedSend {
     proofs_to_send: cdk::nuts::Proofs,
     swap_fee: Amount,
     send_fee: Amount,
+    events: WalletEventEmitter,
 }
 
 impl std::fmt::Debug for PreparedSend {
@@ -533,8 +639,15 @@ impl PreparedSend {
             proofs_to_send: prepared.proofs_to_send().clone(),
             swap_fee: prepared.swap_fee().into(),
             send_fee: prepared.send_fee().into(),
+            events: WalletEventEmitter::default(),
         }
     }
+
+    /// Report the balance change of the confirmed send to the wallet's event listener
+    pub(crate) fn with_events(mut self, events: WalletEventEmitter) -> Self {
+        self.events = events;
+        self
+    }
 }
 
 #[uniffi::export(async_runtime = "tokio")]
@@ -586,6 +699,8 @@ impl PreparedSend {
             )
             .await?;
 
+        self.events.balance_changed(&self.wallet).await;
+
         Ok(token.into())
     }
 
@@ -719,6 +834,7 @@ pub struct PreparedMelt {
     input_fee: Amount,
     input_fee_without_swap: Amount,
     metadata: HashMap<String, String>,
+    events: WalletEventEmitter,
 }
 
 impl std::fmt::Debug for PreparedMelt {
@@ -744,9 +860,16 @@ impl PreparedMelt {
             input_fee: prepared.input_fee().into(),
             input_fee_without_swap: prepared.input_fee_without_swap().into(),
             metadata: prepared.metadata().clone(),
+            events: WalletEventEmitter::default(),
         }
     }
 
+    /// Report the balance change of the confirmed melt to the wallet's event listener
+    pub(crate) fn with_events(mut self, events: WalletEventEmitter) -> Self {
+        self.events = events;
+        self
+    }
+
     async fn confirm_prefer_async_with_options(
         &self,
         options: MeltConfirmOptions,
@@ -761,10 +884,12 @@ impl PreparedMelt {
                 self.input_fee.into(),
                 self.input_fee_without_swap.into(),
                 self.metadata.clone(),
-                options.into(),
+                options.try_into()?,
             )
             .await?;
 
+        self.events.balance_changed(&self.wallet).await;
+
         match outcome {
             cdk::wallet::MeltOutcome::Paid(finalized) => Ok(MeltConfirmOutcome::Paid {
                 finalized: finalized.into(),
@@ -886,13 +1011,40 @@ impl PreparedMelt {
                 self.input_fee.into(),
                 self.input_fee_without_swap.into(),
                 self.metadata.clone(),
-                options.into(),
+                options.try_into()?,
             )
             .await?;
 
+        self.events.balance_changed(&self.wallet).await;
+
         Ok(finalized.into())
     }
 
+    /// Confirm the prepared melt, aborting when `cancel` is cancelled
+    ///
+    /// If the token is already cancelled the prepared melt is cancelled and
+    /// its reserved proofs are released. A melt aborted while the payment is in
+    /// flight is recovered from the mint, which releases the proofs unless the
+    /// payment went through.
+    pub async fn confirm_cancellable(
+        &self,
+        options: MeltConfirmOptions,
+        cancel: Arc<CancellationToken>,
+    ) -> Result<FinalizedMelt, FfiError> {
+        if cancel.is_cancelled() {
+            self.cancel().await?;
+            return Err(FfiError::Cancelled);
+        }
+
+        cancel
+            .run(
+                &self.wallet,
+                &[self.operation_id],
+                self.confirm_with_options(options),
+            )
+            .await
+    }
+
     /// Confirm the prepared melt using NUT-05 async support when the mint accepts it.
     ///
     /// If the melt completes immediately, this returns
@@ -1002,19 +1154,53 @@ impl From<cdk::wallet::RecoveryReport> for RecoveryReport {
     }
 }
 
+/// Quotes removed by a purge of expired quotes.
+#[derive(Debug, Clone, PartialEq, Eq, Default, uniffi::Record)]
+pub struct PurgedQuotes {
+    /// Ids of the removed mint quotes.
+    pub mint_quotes: Vec<String>,
+    /// Ids of the removed melt quotes.
+    pub melt_quotes: Vec<String>,
+}
+
+impl From<cdk::wallet::PurgedQuotes> for PurgedQuotes {
+    fn from(purged: cdk::wallet::PurgedQuotes) -> Self {
+        Self {
+            mint_quotes: purged.mint_quotes,
+            melt_quotes: purged.melt_quotes,
+        }
+    }
+}
+
 /// FFI-compatible options for confirming a melt operation
 #[derive(Debug, Clone, Default, Serialize, Deserialize, uniffi::Record)]
 pub struct MeltConfirmOptions {
     /// Skip the pre-melt swap and send proofs directly to melt.
     /// When true, saves swap input fees but gets change from melt instead.
     pub skip_swap: bool,
+    /// Change outputs to request in addition to the ones the change needs
+    #[serde(default)]
+    pub extra_change_outputs: u32,
+    /// Signing keys for P2PK-locked input proofs
+    #[serde(default)]
+    pub p2pk_signing_keys: Vec<SecretKey>,
 }
 
-impl From<MeltConfirmOptions> for cdk::wallet::MeltConfirmOptions {
-    fn from(opts: MeltConfirmOptions) -> Self {
-        cdk::wallet::MeltConfirmOptions {
+impl TryFrom<MeltConfirmOptions> for cdk::wallet::MeltConfirmOptions {
+    type Error = FfiError;
+
+    fn try_from(opts: MeltConfirmOptions) -> Result<Self, Self::Error> {
+        let p2pk_signing_keys = opts
+            .p2pk_signing_keys
+            .into_iter()
+            .map(TryInto::try_into)
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(cdk::wallet::MeltConfirmOptions {
             skip_swap: opts.skip_swap,
-        }
+            extra_change_outputs: opts.extra_change_outputs,
+            p2pk_signing_keys,
+        })
     }
 }
 
@@ -1022,6 +1208,31 @@ impl From<cdk::wallet::MeltConfirmOptions> for MeltConfirmOptions {
     fn from(opts: cdk::wallet::MeltConfirmOptions) -> Self {
         Self {
             skip_swap: opts.skip_swap,
+            extra_change_outputs: opts.extra_change_outputs,
+            p2pk_signing_keys: opts.p2pk_signing_keys.into_iter().map(Into::into).collect(),
+        }
+    }
+}
+
+/// FFI-compatible options for requesting a melt quote
+#[derive(Debug, Clone, Default, Serialize, Deserialize, uniffi::Record)]
+pub struct MeltQuoteOptions {
+    /// Request a new quote even if the wallet holds an active one for the same invoice
+    pub allow_duplicate: bool,
+}
+
+impl From<MeltQuoteOptions> for cdk::wallet::MeltQuoteOptions {
+    fn from(opts: MeltQuoteOptions) -> Self {
+        cdk::wallet::MeltQuoteOptions {
+            allow_duplicate: opts.allow_duplicate,
+        }
+    }
+}
+
+impl From<cdk::wallet::MeltQuoteOptions> for MeltQuoteOptions {
+    fn from(opts: cdk::wallet::MeltQuoteOptions) -> Self {
+        Self {
+            allow_duplicate: opts.allow_duplicate,
         }
     }
 }
@@ -4,9 +4,14 @@ use std::str::FromStr;
 use std::sync::Arc;
 
 use bip39::Mnemonic;
+use cdk::nuts::nut00::ProofsMethods;
 use cdk::wallet::{Wallet as CdkWallet, WalletBuilder as CdkWalletBuilder};
+use cdk_common::parking_lot::RwLock;
 
+use crate::cancellation::CancellationToken;
 use crate::error::FfiError;
+use crate::events::{WalletEventEmitter, WalletEventListener};
+use crate::invoice_payer::InvoicePayer;
 use crate::token::Token;
 #[cfg(all(feature = "bip353", not(target_arch = "wasm32")))]
 use crate::types::bip321::BitcoinNetwork;
@@ -24,18 +29,29 @@ use crate::types::*;
 #[derive(uniffi::Object)]
 pub struct Wallet {
     inner: Arc<CdkWallet>,
+    events: WalletEventEmitter,
+    invoice_payer: RwLock<Option<Arc<dyn InvoicePayer>>>,
 }
 
 impl Wallet {
     /// Create a Wallet from an existing CDK wallet (internal use only)
     pub(crate) fn from_inner(inner: Arc<CdkWallet>) -> Self {
-        Self { inner }
+        Self {
+            inner,
+            events: WalletEventEmitter::default(),
+            invoice_payer: RwLock::new(None),
+        }
     }
 
     /// Access the inner CDK wallet
     pub(crate) fn inner(&self) -> &Arc<CdkWallet> {
         &self.inner
     }
+
+    /// Access the emitter of the registered wallet event listener
+    pub(crate) fn events(&self) -> &WalletEventEmitter {
+        &self.events
+    }
 }
 
 #[uniffi::export(async_runtime = "tokio")]
@@ -78,9 +94,7 @@ impl Wallet {
             .build()
             .map_err(FfiError::from)?;
 
-        Ok(Self {
-            inner: Arc::new(wallet),
-        })
+        Ok(Self::from_inner(Arc::new(wallet)))
     }
 
     /// Get the mint URL
@@ -116,6 +130,70 @@ impl Wallet {
         self.inner.set_metadata_cache_ttl(ttl);
     }
 
+    /// Register a listener for wallet events
+    ///
+    /// Replaces any previously registered listener. While a listener is set,
+    /// new mint quotes are watched in the background and reported once paid.
+    pub fn set_event_listener(&self, listener: Arc<dyn WalletEventListener>) {
+        self.events.set_listener(Some(listener));
+    }
+
+    /// Remove the registered event listener
+    pub fn clear_event_listener(&self) {
+        self.events.set_listener(None);
+    }
+
+    /// Register a Lightning wallet used to pay mint quotes in [`Wallet::top_up`]
+    pub fn set_invoice_payer(&self, payer: Arc<dyn InvoicePayer>) {
+        *self.invoice_payer.write() = Some(payer);
+    }
+
+    /// Remove the registered invoice payer
+    pub fn clear_invoice_payer(&self) {
+        *self.invoice_payer.write() = None;
+    }
+
+    /// Top up the wallet from the registered invoice payer.
+    ///
+    /// Creates a bolt11 mint quote, has the invoice payer pay it and mints
+    /// the proofs once the mint sees the payment. Fails if no invoice payer is
+    /// registered or the payment is not seen within `timeout_secs`.
+    pub async fn top_up(&self, amount: Amount, timeout_secs: u64) -> Result<Proofs, FfiError> {
+        let payer = self
+            .invoice_payer
+            .read()
+            .clone()
+            .ok_or_else(|| FfiError::internal("No invoice payer registered"))?;
+
+        let quote = self
+            .inner
+            .mint_quote(
+                cdk::nuts::PaymentMethod::BOLT11,
+                Some(amount.into()),
+                None,
+                None,
+            )
+            .await?;
+
+        payer.pay_invoice(quote.request.clone()).await?;
+
+        let proofs = self
+            .inner
+            .wait_and_mint_quote(
+                quote,
+                cdk::amount::SplitTarget::default(),
+                None,
+                std::time::Duration::from_secs(timeout_secs),
+            )
+            .await?;
+
+        if let Ok(amount) = proofs.total_amount() {
+            self.events.proofs_received(&self.inner, amount).await;
+        }
+
+        Ok(proofs.into_iter().map(|p| p.into()).collect())
+    }
+
     /// Get total balance
     pub async fn total_balance(&self) -> Result<Amount, FfiError> {
         let balance = self.inner.total_balance().await?;
@@ -162,21 +240,50 @@ impl Wallet {
             .inner
             .receive(&token.to_string(), options.try_into()?)
             .await?;
+        self.events.proofs_received(&self.inner, amount).await;
         Ok(amount.into())
     }
 
+    /// Receive tokens, aborting when `cancel` is cancelled
+    ///
+    /// Proofs reserved by an aborted receive are released before returning
+    /// [`FfiError::Cancelled`].
+    pub async fn receive_cancellable(
+        &self,
+        token: std::sync::Arc<Token>,
+        options: ReceiveOptions,
+        cancel: Arc<CancellationToken>,
+    ) -> Result<Amount, FfiError> {
+        cancel
+            .run(&self.inner, &[], self.receive(token, options))
+            .await
+    }
+
     /// Restore wallet from seed
     pub async fn restore(&self) -> Result<Restored, FfiError> {
         let restored = self.inner.restore().await?;
+        self.events.balance_changed(&self.inner).await;
         Ok(restored.into())
     }
 
     /// Restore wallet from seed with custom NUT-13 options
     pub async fn restore_with_opts(&self, opts: NUT13Options) -> Result<Restored, FfiError> {
         let restored = self.inner.restore_with_opts(opts.try_into()?).await?;
+        self.events.balance_changed(&self.inner).await;
         Ok(restored.into())
     }
 
+    /// Restore wallet from seed, aborting when `cancel` is cancelled
+    pub async fn restore_cancellable(
+        &self,
+        opts: NUT13Options,
+        cancel: Arc<CancellationToken>,
+    ) -> Result<Restored, FfiError> {
+        cancel
+            .run(&self.inner, &[], self.restore_with_opts(opts))
+            .await
+    }
+
     /// Verify token DLEQ proofs
     pub async fn verify_token_dleq(&self, token: std::sync::Arc<Token>) -> Result<(), FfiError> {
         let cdk_token = token.inner.clone();
@@ -204,6 +311,7 @@ impl Wallet {
             .inner
             .receive_proofs(cdk_proofs, options.try_into()?, memo, token)
             .await?;
+        self.events.proofs_received(&self.inner, amount).await;
         Ok(amount.into())
     }
 
@@ -218,6 +326,7 @@ impl Wallet {
         let uuid = uuid::Uuid::parse_str(&operation_id)
             .map_err(|e| FfiError::internal(format!("Invalid operation ID: {}", e)))?;
         let amount = self.inner.revoke_send(uuid).await?;
+        self.events.balance_changed(&self.inner).await;
         Ok(amount.into())
     }
 
@@ -239,10 +348,9 @@ impl Wallet {
             .inner
             .prepare_send(amount.into(), options.try_into()?)
             .await?;
-        Ok(std::sync::Arc::new(PreparedSend::new(
-            self.inner.clone(),
-            &prepared,
-        )))
+        Ok(std::sync::Arc::new(
+            PreparedSend::new(self.inner.clone(), &prepared).with_events(self.events.clone()),
+        ))
     }
 
     /// Get a mint quote
@@ -262,6 +370,8 @@ impl Wallet {
                 extra,
             )
             .await?;
+        self.events
+            .watch_mint_quote(self.inner.clone(), quote.clone());
         Ok(quote.into())
     }
 
@@ -342,6 +452,9 @@ impl Wallet {
             .inner
             .mint(&quote_id, amount_split_target.into(), conditions)
             .await?;
+        if let Ok(amount) = proofs.total_amount() {
+            self.events.proofs_received(&self.inner, amount).await;
+        }
         Ok(proofs.into_iter().map(|p| p.into()).collect())
     }
 
@@ -351,6 +464,7 @@ impl Wallet {
     /// It may perform network requests and write newly issued proofs to the wallet store.
     pub async fn mint_unissued_quotes(&self) -> Result<Amount, FfiError> {
         let minted = self.inner.mint_unissued_quotes().await?;
+        self.events.proofs_received(&self.inner, minted).await;
         Ok(minted.into())
     }
 
@@ -362,7 +476,7 @@ impl Wallet {
             .inner
             .prepare_melt(&quote_id, std::collections::HashMap::new())
             .await?;
-        Ok(PreparedMelt::new(Arc::clone(&self.inner), &prepared))
+        Ok(PreparedMelt::new(Arc::clone(&self.inner), &prepared).with_events(self.events.clone()))
     }
 
     /// Prepare a melt operation with specific proofs
@@ -392,7 +506,7 @@ impl Wallet {
             .inner
             .prepare_melt_proofs(&quote_id, cdk_proofs, std::collections::HashMap::new())
             .await?;
-        Ok(PreparedMelt::new(Arc::clone(&self.inner), &prepared))
+        Ok(PreparedMelt::new(Arc::clone(&self.inner), &prepared).with_events(self.events.clone()))
     }
 
     /// Prepare a melt operation from an encoded token
@@ -417,7 +531,7 @@ impl Wallet {
             .inner
             .prepare_melt_token(&quote_id, &encoded_token, std::collections::HashMap::new())
             .await?;
-        Ok(PreparedMelt::new(Arc::clone(&self.inner), &prepared))
+        Ok(PreparedMelt::new(Arc::clone(&self.inner), &prepared).with_events(self.events.clone()))
     }
 
     /// Mint tokens using the unified payment-method interface.
@@ -467,6 +581,31 @@ impl Wallet {
         Ok(quote.into())
     }
 
+    /// Get a melt quote with [`MeltQuoteOptions`]
+    ///
+    /// See `melt_quote`. An active bolt11 quote for the same invoice and melt options is
+    /// returned instead of requesting a new one, unless `quote_options.allow_duplicate` is set.
+    pub async fn melt_quote_with_options(
+        &self,
+        method: PaymentMethod,
+        request: String,
+        options: Option<MeltOptions>,
+        extra: Option<String>,
+        quote_options: MeltQuoteOptions,
+    ) -> Result<MeltQuote, FfiError> {
+        let quote = self
+            .inner
+            .melt_quote_with_options::<cdk::nuts::PaymentMethod, _>(
+                method.into(),
+                request,
+                options.map(Into::into),
+                extra,
+                quote_options.into(),
+            )
+            .await?;
+        Ok(quote.into())
+    }
+
     /// Fetch available onchain melt quote options.
     ///
     /// Each returned quote represents one selectable confirmation target/fee reserve.
@@ -534,6 +673,7 @@ impl Wallet {
                 false,
             )
             .await?;
+        self.events.balance_changed(&self.inner).await;
 
         Ok(result.map(|proofs| proofs.into_iter().map(|p| p.into()).collect()))
     }
@@ -744,6 +884,7 @@ impl Wallet {
     /// transitions.
     pub async fn check_all_pending_proofs(&self) -> Result<Amount, FfiError> {
         let amount = self.inner.check_all_pending_proofs().await?;
+        self.events.balance_changed(&self.inner).await;
         Ok(amount.into())
     }
 
@@ -760,6 +901,36 @@ impl Wallet {
         Ok(report.into())
     }
 
+    /// Remove unpaid quotes that have expired
+    ///
+    /// Each candidate is confirmed with the mint before it is removed. Reports
+    /// the removed quotes as a `QuotesPurged` event.
+    pub async fn purge_expired_quotes(&self) -> Result<PurgedQuotes, FfiError> {
+        self.purge_quotes_expired_for(0).await
+    }
+
+    /// Remove unpaid quotes that expired more than `grace_secs` seconds ago
+    pub async fn purge_quotes_expired_for(
+        &self,
+        grace_secs: u64,
+    ) -> Result<PurgedQuotes, FfiError> {
+        let purged = self
+            .inner
+            .purge_quotes_expired_for(std::time::Duration::from_secs(grace_secs))
+            .await?;
+        self.events.quotes_purged(&purged).await;
+        Ok(purged.into())
+    }
+
+    /// Resume mint requests interrupted by a crash
+    ///
+    /// Re-submits mint requests whose blinded messages were stored before a crash and
+    /// unblinds the signatures into proofs. Only issue operations are recovered.
+    pub async fn resume_pending_mints(&self) -> Result<RecoveryReport, FfiError> {
+        let report = self.inner.resume_pending_mints().await?;
+        Ok(report.into())
+    }
+
     /// Calculate fee for a given number of proofs with the specified keyset
     pub async fn calculate_fee(
         &self,
@@ -973,4 +1144,15 @@ mod tests {
             .expect("trait call should mint zero from empty quote store");
         assert!(trait_minted.is_zero());
     }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn top_up_requires_invoice_payer() {
+        let wallet = test_wallet();
+
+        let err = wallet
+            .top_up(Amount::new(100), 1)
+            .await
+            .expect_err("top up without invoice payer should fail");
+        assert!(matches!(err, FfiError::Internal { .. }));
+    }
 }
@@ -248,7 +248,9 @@ impl WalletTraitDef for Wallet {
         options: cdk_common::wallet::SendOptions,
     ) -> Result<Arc<PreparedSend>, Self::Error> {
         let prepared = self.inner().prepare_send(amount.into(), options).await?;
-        Ok(Arc::new(PreparedSend::new(self.inner().clone(), &prepared)))
+        Ok(Arc::new(
+            PreparedSend::new(self.inner().clone(), &prepared).with_events(self.events().clone()),
+        ))
     }
 
     async fn get_pending_sends(&self) -> Result<Vec<String>, Self::Error> {
@@ -317,7 +319,7 @@ impl WalletTraitDef for Wallet {
         metadata: HashMap<String, String>,
     ) -> Result<PreparedMelt, Self::Error> {
         let prepared = self.inner().prepare_melt(quote_id, metadata).await?;
-        Ok(PreparedMelt::new(self.inner().clone(), &prepared))
+        Ok(PreparedMelt::new(self.inner().clone(), &prepared).with_events(self.events().clone()))
     }
 
     async fn prepare_melt_proofs(
@@ -330,7 +332,7 @@ impl WalletTraitDef for Wallet {
             .inner()
             .prepare_melt_proofs(quote_id, proofs, metadata)
             .await?;
-        Ok(PreparedMelt::new(self.inner().clone(), &prepared))
+        Ok(PreparedMelt::new(self.inner().clone(), &prepared).with_events(self.events().clone()))
     }
 
     async fn prepare_melt_token(
@@ -343,7 +345,7 @@ impl WalletTraitDef for Wallet {
             .inner()
             .prepare_melt_token(quote_id, encoded_token, metadata)
             .await?;
-        Ok(PreparedMelt::new(self.inner().clone(), &prepared))
+        Ok(PreparedMelt::new(self.inner().clone(), &prepared).with_events(self.events().clone()))
     }
 
     async fn swap(
@@ -170,14 +170,15 @@ impl HttpClient {
     pub async fn get_raw(&self, url: &str) -> Response<RawResponse> {
         let request = bitreq::get(url);
         let request = self.configure_request(request, url)?;
-        let response = request
+        let mut response = request
             .send_async_with_client(&self.inner)
             .await
             .map_err(HttpError::from)?;
-        Ok(RawResponse::new(
-            response.status_code as u16,
-            response.into_bytes(),
-        ))
+        let headers = std::mem::take(&mut response.headers);
+        Ok(
+            RawResponse::new(response.status_code as u16, response.into_bytes())
+                .with_headers(headers),
+        )
     }
 
     /// POST request builder for complex cases
@@ -280,6 +281,12 @@ impl BitreqRequestBuilder {
         self
     }
 
+    /// Set the raw request body. The caller sets the `Content-Type` header.
+    pub fn body(mut self, body: impl Into<Vec<u8>>) -> Self {
+        self.inner = self.inner.with_body(body.into());
+        self
+    }
+
     /// Set the request body as form data.
     pub fn form<T>(mut self, body: &T) -> Self
     where
@@ -308,14 +315,15 @@ impl BitreqRequestBuilder {
         } else {
             request
         };
-        let response = request
+        let mut response = request
             .send_async_with_client(&self.client)
             .await
             .map_err(HttpError::from)?;
-        Ok(RawResponse::new(
-            response.status_code as u16,
-            response.into_bytes(),
-        ))
+        let headers = std::mem::take(&mut response.headers);
+        Ok(
+            RawResponse::new(response.status_code as u16, response.into_bytes())
+                .with_headers(headers),
+        )
     }
 
     /// Send the request and deserialize the response as JSON.
@@ -108,8 +108,9 @@ impl HttpClient {
             .await
             .map_err(map_reqwest_error)?;
         let status = response.status().as_u16();
+        let headers = response_headers(response.headers());
         let body = response.bytes().await.map_err(map_reqwest_error)?.to_vec();
-        Ok(RawResponse::new(status, body))
+        Ok(RawResponse::new(status, body).with_headers(headers))
     }
 
     /// POST request builder for complex cases.
@@ -138,6 +139,18 @@ fn map_reqwest_error(err: reqwest::Error) -> HttpError {
     }
 }
 
+fn response_headers(headers: &reqwest::header::HeaderMap) -> Vec<(String, String)> {
+    headers
+        .iter()
+        .filter_map(|(key, value)| {
+            value
+                .to_str()
+                .ok()
+                .map(|value| (key.as_str().to_string(), value.to_string()))
+        })
+        .collect()
+}
+
 /// reqwest-based RequestBuilder wrapper.
 pub struct ReqwestRequestBuilder {
     inner: Option<reqwest::RequestBuilder>,
@@ -201,6 +214,12 @@ impl ReqwestRequestBuilder {
         }
     }
 
+    /// Set the raw request body. The caller sets the `Content-Type` header.
+    pub fn body(self, body: impl Into<Vec<u8>>) -> Self {
+        let body = body.into();
+        self.map_inner(|inner| inner.body(body))
+    }
+
     /// Set the request body as form data.
     pub fn form<T>(self, body: &T) -> Self
     where
@@ -232,8 +251,9 @@ impl ReqwestRequestBuilder {
 
         let response = inner.send().await.map_err(map_reqwest_error)?;
         let status = response.status().as_u16();
+        let headers = response_headers(response.headers());
         let body = response.bytes().await.map_err(map_reqwest_error)?.to_vec();
-        Ok(RawResponse::new(status, body))
+        Ok(RawResponse::new(status, body).with_headers(headers))
     }
 
     /// Send the request and deserialize the response as JSON.
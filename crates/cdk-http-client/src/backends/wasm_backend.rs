@@ -102,6 +102,7 @@ pub struct WasmRequestBuilder {
 enum WasmBody {
     Json(Vec<u8>),
     Form(String),
+    Raw(Vec<u8>),
 }
 
 impl WasmRequestBuilder {
@@ -129,7 +130,7 @@ impl WasmRequestBuilder {
 
         if let Some(body) = &self.body {
             match body {
-                WasmBody::Json(bytes) => {
+                WasmBody::Json(bytes) | WasmBody::Raw(bytes) => {
                     let uint8_array = js_sys::Uint8Array::from(bytes.as_slice());
                     opts.set_body(&uint8_array.into());
                 }
@@ -158,6 +159,16 @@ impl WasmRequestBuilder {
             .map_err(|_| HttpError::Other("Response is not a web_sys::Response".to_string()))?;
 
         let status = resp.status();
+        let headers = ["etag", "cache-control"]
+            .into_iter()
+            .filter_map(|name| {
+                resp.headers()
+                    .get(name)
+                    .ok()
+                    .flatten()
+                    .map(|value| (name.to_string(), value))
+            })
+            .collect::<Vec<_>>();
 
         let body_promise = resp
             .array_buffer()
@@ -170,7 +181,7 @@ impl WasmRequestBuilder {
         let body_array = js_sys::Uint8Array::new(&body_value);
         let body = body_array.to_vec();
 
-        Ok(RawResponse::new(status, body))
+        Ok(RawResponse::new(status, body).with_headers(headers))
     }
     /// Add a header to the request.
     pub fn header(mut self, key: impl AsRef<str>, value: impl AsRef<str>) -> Self {
@@ -195,6 +206,12 @@ impl WasmRequestBuilder {
         self
     }
 
+    /// Set the raw request body. The caller sets the `Content-Type` header.
+    pub fn body(mut self, body: impl Into<Vec<u8>>) -> Self {
+        self.body = Some(WasmBody::Raw(body.into()));
+        self
+    }
+
     /// Set the request body as form data.
     pub fn form<T>(mut self, body: &T) -> Self
     where
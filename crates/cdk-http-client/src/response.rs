@@ -13,12 +13,23 @@ pub type Response<R, E = HttpError> = Result<R, E>;
 pub struct RawResponse {
     status: u16,
     pub(crate) body: Vec<u8>,
+    headers: Vec<(String, String)>,
 }
 
 impl RawResponse {
     /// Create a new RawResponse from status and body bytes
     pub fn new(status: u16, body: Vec<u8>) -> Self {
-        Self { status, body }
+        Self {
+            status,
+            body,
+            headers: Vec::new(),
+        }
+    }
+
+    /// Attach the response headers
+    pub fn with_headers(mut self, headers: impl IntoIterator<Item = (String, String)>) -> Self {
+        self.headers = headers.into_iter().collect();
+        self
     }
 
     /// Get the HTTP status code
@@ -26,6 +37,14 @@ impl RawResponse {
         self.status
     }
 
+    /// Get a response header by its case-insensitive name
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(key, _)| key.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value.as_str())
+    }
+
     /// Check if the response status is a success (2xx)
     pub fn is_success(&self) -> bool {
         (200..300).contains(&self.status)
@@ -80,6 +99,15 @@ mod tests {
     // Note: RawResponse tests require a real response,
     // so they are in tests/integration.rs using mockito.
 
+    #[test]
+    fn test_raw_response_header_lookup() {
+        let response = RawResponse::new(304, Vec::new())
+            .with_headers([("etag".to_string(), "\"abc\"".to_string())]);
+
+        assert_eq!(response.header("ETag"), Some("\"abc\""));
+        assert_eq!(response.header("Cache-Control"), None);
+    }
+
     #[test]
     fn test_response_type_is_result() {
         // Response<R, E> is just a type alias for Result<R, E>
@@ -53,6 +53,21 @@ pub trait Transport: Default + Send + Sync + Debug + Clone {
         auth: Option<AuthToken>,
     ) -> Result<RawResponse, HttpError>;
 
+    /// HTTP GET request revalidating a cached copy of the resource.
+    ///
+    /// Sends `etag` in `If-None-Match`, so the server answers with `304 Not Modified` and an
+    /// empty body while the resource is unchanged. Transports that do not support conditional
+    /// requests ignore `etag` and always fetch the resource.
+    async fn http_get_conditional(
+        &self,
+        url: Url,
+        auth: Option<AuthToken>,
+        etag: Option<&str>,
+    ) -> Result<RawResponse, HttpError> {
+        let _ = etag;
+        self.http_get_raw(url, auth).await
+    }
+
     /// HTTP POST request.
     async fn http_post<P, R>(
         &self,
@@ -191,6 +206,26 @@ impl Transport for Async {
         request.send().await
     }
 
+    async fn http_get_conditional(
+        &self,
+        url: Url,
+        auth: Option<AuthToken>,
+        etag: Option<&str>,
+    ) -> Result<RawResponse, HttpError> {
+        let url_str = url.to_string();
+        let mut request = self.inner.get(&url_str);
+
+        if let Some(auth) = auth {
+            request = request.header(auth.header_key(), auth.to_string());
+        }
+
+        if let Some(etag) = etag {
+            request = request.header("If-None-Match", etag);
+        }
+
+        request.send().await
+    }
+
     async fn http_post<P, R>(
         &self,
         url: Url,
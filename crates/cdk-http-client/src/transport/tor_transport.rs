@@ -199,11 +199,21 @@ impl TorAsync {
             .map_err(|e| HttpError::Connection(e.to_string()))?;
 
         let status = resp.status().as_u16();
+        let headers = resp
+            .headers()
+            .iter()
+            .filter_map(|(key, value)| {
+                value
+                    .to_str()
+                    .ok()
+                    .map(|value| (key.as_str().to_string(), value.to_string()))
+            })
+            .collect::<Vec<_>>();
         let bytes = hyper::body::to_bytes(resp.into_body())
             .await
             .map_err(|e| HttpError::Other(e.to_string()))?;
 
-        Ok(RawResponse::new(status, bytes.to_vec()))
+        Ok(RawResponse::new(status, bytes.to_vec()).with_headers(headers))
     }
 
     async fn request<R>(
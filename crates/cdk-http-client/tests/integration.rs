@@ -744,6 +744,36 @@ async fn test_request_builder_post_with_form() {
     mock.assert_async().await;
 }
 
+#[tokio::test]
+async fn test_request_builder_post_with_raw_body() {
+    let mut server = mockito::Server::new_async().await;
+
+    let body = br#"{"b":1,"a":2}"#;
+    let mock = server
+        .mock("POST", "/api/raw-body")
+        .match_header("content-type", "application/json")
+        .match_body(mockito::Matcher::Exact(
+            String::from_utf8(body.to_vec()).expect("utf-8 body"),
+        ))
+        .with_status(204)
+        .create_async()
+        .await;
+
+    let client = HttpClient::new();
+    let url = format!("{}/api/raw-body", server.url());
+    let response = client
+        .post(&url)
+        .header("Content-Type", "application/json")
+        .body(body.to_vec())
+        .send()
+        .await
+        .expect("Request should succeed");
+
+    assert_eq!(response.status(), 204);
+
+    mock.assert_async().await;
+}
+
 #[tokio::test]
 async fn test_request_builder_patch() {
     let mut server = mockito::Server::new_async().await;
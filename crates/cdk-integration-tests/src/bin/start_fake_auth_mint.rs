@@ -64,6 +64,7 @@ async fn start_fake_auth_mint(
         min_delay_time: 1,
         max_delay_time: 3,
         keyset_rotations: Vec::new(),
+        failure_injection: Default::default(),
     };
 
     let mut settings = shared::create_fake_wallet_settings(
@@ -91,6 +92,9 @@ async fn start_fake_auth_mint(
         restore: AuthType::Blind,
         check_proof_state: AuthType::Blind,
         websocket_auth: AuthType::Blind,
+        max_mint_per_day: None,
+        max_melt_per_day: None,
+        max_bat_per_day: None,
     });
 
     // Set description for the mint
@@ -102,6 +102,7 @@ async fn start_fake_mint(
                 expired: false,
             },
         ],
+        failure_injection: Default::default(),
     });
 
     // Create settings struct for fake mint using shared function
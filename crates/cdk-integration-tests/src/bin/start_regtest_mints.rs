@@ -97,6 +97,7 @@ async fn start_cln_mint(
         rpc_path: cln_rpc_path,
         bolt12: false,
         expose_private_channels: false,
+        hold_invoices: false,
         fee_percent: 0.0,
         reserve_fee_min: 0.into(),
     };
@@ -174,6 +175,7 @@ async fn start_lnd_mint(
         macaroon_file: lnd_macaroon_file,
         fee_percent: 0.0,
         reserve_fee_min: 0.into(),
+        keysend: false,
     };
 
     // Create settings struct for LND mint using shared function
@@ -447,10 +449,12 @@ fn create_ldk_settings(
                     .to_string(),
             ),
             input_fee_ppk: None,
+            max_order: None,
             use_keyset_v2: None,
             http_cache: cdk_axum::cache::Config::default(),
             enable_info_page: None,
             logging: LoggingConfig::default(),
+            ..Default::default()
         },
         mint_info: cdk_mintd::config::MintInfo::default(),
         limits: cdk_mintd::config::Limits::default(),
@@ -555,10 +559,12 @@ fn create_onchain_settings(port: u16) -> cdk_mintd::config::Settings {
                     .to_string(),
             ),
             input_fee_ppk: None,
+            max_order: None,
             use_keyset_v2: None,
             http_cache: cdk_axum::cache::Config::default(),
             enable_info_page: None,
             logging: LoggingConfig::default(),
+            ..Default::default()
         },
         mint_info: cdk_mintd::config::MintInfo::default(),
         limits: cdk_mintd::config::Limits::default(),
@@ -1,384 +1,24 @@
 use std::collections::{HashMap, HashSet};
-use std::fmt::{Debug, Formatter};
 use std::path::PathBuf;
-use std::str::FromStr;
 use std::sync::Arc;
 use std::{env, fs};
 
 use anyhow::{anyhow, bail, Result};
-use async_trait::async_trait;
 use bip39::Mnemonic;
 use cashu::nut00::KnownMethod;
-use cashu::quote_id::QuoteId;
 use cdk::amount::SplitTarget;
 use cdk::cdk_database::{self, WalletDatabase};
 use cdk::mint::{MintBuilder, MintMeltLimits};
 use cdk::nuts::nut00::ProofsMethods;
-use cdk::nuts::{
-    BatchCheckMintQuoteRequest, BatchMintRequest, CheckStateRequest, CheckStateResponse,
-    CurrencyUnit, Id, KeySet, KeysetResponse, MeltRequest, MintInfo, MintRequest, MintResponse,
-    PaymentMethod, RestoreRequest, RestoreResponse, SwapRequest, SwapResponse,
-};
+use cdk::nuts::{CurrencyUnit, PaymentMethod};
 use cdk::types::{FeeReserve, QuoteTTL};
-use cdk::util::unix_time;
-use cdk::wallet::{AuthWallet, MintConnector, Wallet, WalletBuilder};
-use cdk::{Amount, Error, MeltQuoteCreateResponse, Mint, StreamExt};
-use cdk_common::{MeltQuoteRequest, MeltQuoteResponse, MintQuoteRequest, MintQuoteResponse};
+pub use cdk::wallet::DirectMintConnector;
+use cdk::wallet::{Wallet, WalletBuilder};
+use cdk::{Amount, Mint, StreamExt};
 use cdk_fake_wallet::FakeWallet;
-use tokio::sync::RwLock;
 use tracing_subscriber::EnvFilter;
 use uuid::Uuid;
 
-pub struct DirectMintConnection {
-    pub mint: Mint,
-    auth_wallet: Arc<RwLock<Option<AuthWallet>>>,
-}
-
-impl DirectMintConnection {
-    pub fn new(mint: Mint) -> Self {
-        Self {
-            mint,
-            auth_wallet: Arc::new(RwLock::new(None)),
-        }
-    }
-}
-
-impl Debug for DirectMintConnection {
-    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        write!(f, "DirectMintConnection",)
-    }
-}
-
-/// Implements the generic [MintConnector] (i.e. use the interface that expects to communicate
-/// to a generic mint, where we don't know that quote ID's are [Uuid]s) for [DirectMintConnection],
-/// where we know we're dealing with a mint that uses [Uuid]s for quotes.
-/// Convert the requests and responses between the [String] and [Uuid] variants as necessary.
-#[async_trait]
-impl MintConnector for DirectMintConnection {
-    async fn resolve_dns_txt(&self, _domain: &str) -> Result<Vec<String>, Error> {
-        panic!("Not implemented");
-    }
-
-    async fn fetch_lnurl_pay_request(
-        &self,
-        _url: &str,
-    ) -> Result<cdk::wallet::LnurlPayResponse, Error> {
-        unimplemented!("Lightning address not supported in DirectMintConnection")
-    }
-
-    async fn fetch_lnurl_invoice(
-        &self,
-        _url: &str,
-    ) -> Result<cdk::wallet::LnurlPayInvoiceResponse, Error> {
-        unimplemented!("Lightning address not supported in DirectMintConnection")
-    }
-
-    async fn get_mint_keys(&self) -> Result<Vec<KeySet>, Error> {
-        Ok(self.mint.pubkeys().keysets)
-    }
-
-    async fn get_mint_keyset(&self, keyset_id: Id) -> Result<KeySet, Error> {
-        self.mint.keyset(&keyset_id).ok_or(Error::UnknownKeySet)
-    }
-
-    async fn get_mint_keysets(&self) -> Result<KeysetResponse, Error> {
-        Ok(self.mint.keysets())
-    }
-
-    async fn post_mint_quote(
-        &self,
-        request: MintQuoteRequest,
-    ) -> Result<MintQuoteResponse<String>, Error> {
-        match request {
-            MintQuoteRequest::Bolt11(req) => {
-                let response = self.mint.get_mint_quote(req.into()).await?;
-                match response {
-                    cdk_common::MintQuoteResponse::Bolt11(r) => {
-                        Ok(MintQuoteResponse::Bolt11(r.to_string_id()))
-                    }
-                    _ => Err(Error::InvalidPaymentMethod),
-                }
-            }
-            MintQuoteRequest::Bolt12(req) => {
-                let response = self.mint.get_mint_quote(req.into()).await?;
-                match response {
-                    cdk_common::MintQuoteResponse::Bolt12(r) => {
-                        Ok(MintQuoteResponse::Bolt12(r.to_string_id()))
-                    }
-                    _ => Err(Error::InvalidPaymentMethod),
-                }
-            }
-            MintQuoteRequest::Custom { method, request } => {
-                let response = self
-                    .mint
-                    .get_mint_quote(cdk::mint::MintQuoteRequest::Custom { method, request })
-                    .await?;
-                match response {
-                    cdk_common::MintQuoteResponse::Custom { method, response } => {
-                        Ok(MintQuoteResponse::Custom {
-                            method,
-                            response: response.to_string_id(),
-                        })
-                    }
-                    _ => Err(Error::InvalidPaymentMethod),
-                }
-            }
-            MintQuoteRequest::Onchain(req) => {
-                let response = self.mint.get_mint_quote(req.into()).await?;
-                match response {
-                    cdk_common::MintQuoteResponse::Onchain(r) => {
-                        Ok(MintQuoteResponse::Onchain(r.to_string_id()))
-                    }
-                    _ => Err(Error::InvalidPaymentMethod),
-                }
-            }
-        }
-    }
-
-    async fn get_mint_quote_status(
-        &self,
-        method: PaymentMethod,
-        quote_id: &str,
-    ) -> Result<MintQuoteResponse<String>, Error> {
-        let response = self
-            .mint
-            .check_mint_quotes(&[QuoteId::from_str(quote_id)?])
-            .await?
-            .first()
-            .ok_or(Error::UnknownQuote)?
-            .clone();
-
-        match method {
-            PaymentMethod::Known(KnownMethod::Bolt11) => match response {
-                cdk_common::MintQuoteResponse::Bolt11(r) => {
-                    Ok(MintQuoteResponse::Bolt11(r.to_string_id()))
-                }
-                _ => Err(Error::InvalidPaymentMethod),
-            },
-            PaymentMethod::Known(KnownMethod::Bolt12) => match response {
-                cdk_common::MintQuoteResponse::Bolt12(r) => {
-                    Ok(MintQuoteResponse::Bolt12(r.to_string_id()))
-                }
-                _ => Err(Error::InvalidPaymentMethod),
-            },
-            PaymentMethod::Known(KnownMethod::Onchain) => match response {
-                cdk_common::MintQuoteResponse::Onchain(r) => {
-                    Ok(MintQuoteResponse::Onchain(r.to_string_id()))
-                }
-                _ => Err(Error::InvalidPaymentMethod),
-            },
-            PaymentMethod::Custom(_) => match response {
-                cdk_common::MintQuoteResponse::Custom { method, response } => {
-                    Ok(MintQuoteResponse::Custom {
-                        method,
-                        response: response.to_string_id(),
-                    })
-                }
-                _ => Err(Error::InvalidPaymentMethod),
-            },
-        }
-    }
-
-    async fn post_mint(
-        &self,
-        _method: &PaymentMethod,
-        request: MintRequest<String>,
-    ) -> Result<MintResponse, Error> {
-        let request_id: MintRequest<QuoteId> = request.try_into().unwrap();
-        self.mint
-            .process_mint_request(cdk::mint::MintInput::Single(request_id))
-            .await
-    }
-
-    async fn post_batch_check_mint_quote_status(
-        &self,
-        _method: &PaymentMethod,
-        request: BatchCheckMintQuoteRequest<String>,
-    ) -> Result<Vec<MintQuoteResponse<String>>, Error> {
-        let quote_ids: Vec<QuoteId> = request
-            .quotes
-            .iter()
-            .filter_map(|s| QuoteId::from_str(s).ok())
-            .collect();
-        self.mint
-            .check_mint_quotes(&quote_ids)
-            .await
-            .map(|responses| responses.into_iter().map(Into::into).collect())
-    }
-
-    async fn post_batch_mint(
-        &self,
-        _method: &PaymentMethod,
-        request: BatchMintRequest<String>,
-    ) -> Result<MintResponse, Error> {
-        let quotes: Vec<QuoteId> = request
-            .quotes
-            .iter()
-            .filter_map(|s| QuoteId::from_str(s).ok())
-            .collect();
-
-        let request_id = BatchMintRequest {
-            quotes,
-            quote_amounts: request.quote_amounts,
-            outputs: request.outputs,
-            signatures: request.signatures,
-        };
-
-        self.mint
-            .process_mint_request(cdk::mint::MintInput::Batch(request_id))
-            .await
-    }
-
-    async fn post_melt_quote(
-        &self,
-        request: MeltQuoteRequest,
-    ) -> Result<MeltQuoteCreateResponse<String>, Error> {
-        match request {
-            MeltQuoteRequest::Bolt11(req) => {
-                let response = self.mint.get_melt_quote(req.into()).await?;
-                match response {
-                    cdk_common::MeltQuoteCreateResponse::Bolt11(r) => {
-                        Ok(MeltQuoteCreateResponse::Bolt11(r.to_string_id()))
-                    }
-                    _ => Err(Error::InvalidPaymentMethod),
-                }
-            }
-            MeltQuoteRequest::Bolt12(req) => {
-                let response = self.mint.get_melt_quote(req.into()).await?;
-                match response {
-                    cdk_common::MeltQuoteCreateResponse::Bolt12(r) => {
-                        Ok(MeltQuoteCreateResponse::Bolt12(r.to_string_id()))
-                    }
-                    _ => Err(Error::InvalidPaymentMethod),
-                }
-            }
-            MeltQuoteRequest::Custom(req) => {
-                let response = self.mint.get_melt_quote(req.into()).await?;
-                match response {
-                    cdk_common::MeltQuoteCreateResponse::Custom((method, r)) => {
-                        Ok(MeltQuoteCreateResponse::Custom((method, r.to_string_id())))
-                    }
-                    _ => Err(Error::InvalidPaymentMethod),
-                }
-            }
-            MeltQuoteRequest::Onchain(req) => {
-                let response = self.mint.get_melt_quote(req.into()).await?;
-                match response {
-                    cdk_common::MeltQuoteCreateResponse::Onchain(r) => {
-                        Ok(MeltQuoteCreateResponse::Onchain(r.into()))
-                    }
-                    _ => Err(Error::InvalidPaymentMethod),
-                }
-            }
-        }
-    }
-
-    async fn get_melt_quote_status(
-        &self,
-        method: PaymentMethod,
-        quote_id: &str,
-    ) -> Result<MeltQuoteResponse<String>, Error> {
-        let response = self
-            .mint
-            .check_melt_quote(&QuoteId::from_str(quote_id)?)
-            .await?;
-
-        match method {
-            PaymentMethod::Known(KnownMethod::Bolt11) => match response {
-                cdk_common::MeltQuoteResponse::Bolt11(r) => {
-                    Ok(MeltQuoteResponse::Bolt11(r.to_string_id()))
-                }
-                _ => Err(Error::InvalidPaymentMethod),
-            },
-            PaymentMethod::Known(KnownMethod::Bolt12) => match response {
-                cdk_common::MeltQuoteResponse::Bolt12(r) => {
-                    Ok(MeltQuoteResponse::Bolt12(r.to_string_id()))
-                }
-                _ => Err(Error::InvalidPaymentMethod),
-            },
-            PaymentMethod::Custom(_) => match response {
-                cdk_common::MeltQuoteResponse::Custom((quote_method, r)) => {
-                    Ok(MeltQuoteResponse::Custom((quote_method, r.to_string_id())))
-                }
-                _ => Err(Error::InvalidPaymentMethod),
-            },
-            PaymentMethod::Known(KnownMethod::Onchain) => match response {
-                cdk_common::MeltQuoteResponse::Onchain(r) => {
-                    Ok(MeltQuoteResponse::Onchain(r.into()))
-                }
-                _ => Err(Error::InvalidPaymentMethod),
-            },
-        }
-    }
-
-    async fn post_melt(
-        &self,
-        method: &PaymentMethod,
-        request: MeltRequest<String>,
-    ) -> Result<MeltQuoteResponse<String>, Error> {
-        let request_uuid = request.try_into().unwrap();
-        let response = self.mint.melt(&request_uuid).await?.await?;
-
-        match method {
-            PaymentMethod::Known(KnownMethod::Bolt11) => match response {
-                cdk_common::MeltQuoteResponse::Bolt11(r) => {
-                    Ok(MeltQuoteResponse::Bolt11(r.to_string_id()))
-                }
-                _ => Err(Error::InvalidPaymentMethod),
-            },
-            PaymentMethod::Known(KnownMethod::Bolt12) => match response {
-                cdk_common::MeltQuoteResponse::Bolt12(r) => {
-                    Ok(MeltQuoteResponse::Bolt12(r.to_string_id()))
-                }
-                _ => Err(Error::InvalidPaymentMethod),
-            },
-            PaymentMethod::Custom(_) => match response {
-                cdk_common::MeltQuoteResponse::Custom((quote_method, r)) => {
-                    Ok(MeltQuoteResponse::Custom((quote_method, r.to_string_id())))
-                }
-                _ => Err(Error::InvalidPaymentMethod),
-            },
-            PaymentMethod::Known(KnownMethod::Onchain) => match response {
-                cdk_common::MeltQuoteResponse::Onchain(r) => {
-                    Ok(MeltQuoteResponse::Onchain(r.into()))
-                }
-                _ => Err(Error::InvalidPaymentMethod),
-            },
-        }
-    }
-
-    async fn post_swap(&self, swap_request: SwapRequest) -> Result<SwapResponse, Error> {
-        self.mint.process_swap_request(swap_request).await
-    }
-
-    async fn get_mint_info(&self) -> Result<MintInfo, Error> {
-        Ok(self.mint.mint_info().await?.clone().time(unix_time()))
-    }
-
-    async fn post_check_state(
-        &self,
-        request: CheckStateRequest,
-    ) -> Result<CheckStateResponse, Error> {
-        self.mint.check_state(&request).await
-    }
-
-    async fn post_restore(&self, request: RestoreRequest) -> Result<RestoreResponse, Error> {
-        self.mint.restore(request).await
-    }
-
-    /// Get the auth wallet for the client
-    async fn get_auth_wallet(&self) -> Option<AuthWallet> {
-        self.auth_wallet.read().await.clone()
-    }
-
-    /// Set auth wallet on client
-    async fn set_auth_wallet(&self, wallet: Option<AuthWallet>) {
-        let mut auth_wallet = self.auth_wallet.write().await;
-
-        *auth_wallet = wallet;
-    }
-}
-
 pub fn setup_tracing() {
     let default_filter = "debug";
 
@@ -582,7 +222,7 @@ pub async fn create_test_wallet_for_mint(mint: Mint) -> Result<Wallet> {
 ///
 /// Useful for restore tests where two wallets must share the same seed.
 pub async fn create_test_wallet_for_mint_with_seed(mint: Mint, seed: [u8; 64]) -> Result<Wallet> {
-    let connector = DirectMintConnection::new(mint.clone());
+    let connector = DirectMintConnector::new(mint.clone());
 
     let mint_info = mint.mint_info().await?;
     let mint_url = mint_info
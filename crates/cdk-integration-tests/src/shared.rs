@@ -188,14 +188,17 @@ pub fn create_fake_wallet_settings(
             seed: None,
             mnemonic: mnemonic.clone(),
             input_fee_ppk: None,
+            max_order: None,
             use_keyset_v2: None,
             http_cache: cache::Config::default(),
             logging: cdk_mintd::config::LoggingConfig {
                 output: cdk_mintd::config::LoggingOutput::Both,
                 console_level: Some("debug".to_string()),
                 file_level: Some("debug".to_string()),
+                ..Default::default()
             },
             enable_info_page: None,
+            ..Default::default()
         },
         signatory: signatory_config,
         mint_info: cdk_mintd::config::MintInfo::default(),
@@ -260,14 +263,17 @@ pub fn create_cln_settings(
             seed: None,
             mnemonic: Some(mnemonic),
             input_fee_ppk: None,
+            max_order: None,
             use_keyset_v2: None,
             http_cache: cache::Config::default(),
             logging: cdk_mintd::config::LoggingConfig {
                 output: cdk_mintd::config::LoggingOutput::Both,
                 console_level: Some("debug".to_string()),
                 file_level: Some("debug".to_string()),
+                ..Default::default()
             },
             enable_info_page: None,
+            ..Default::default()
         },
         mint_info: cdk_mintd::config::MintInfo::default(),
         limits: cdk_mintd::config::Limits::default(),
@@ -314,14 +320,17 @@ pub fn create_lnd_settings(
             seed: None,
             mnemonic: Some(mnemonic),
             input_fee_ppk: None,
+            max_order: None,
             use_keyset_v2: None,
             http_cache: cache::Config::default(),
             logging: cdk_mintd::config::LoggingConfig {
                 output: cdk_mintd::config::LoggingOutput::Both,
                 console_level: Some("debug".to_string()),
                 file_level: Some("debug".to_string()),
+                ..Default::default()
             },
             enable_info_page: None,
+            ..Default::default()
         },
         mint_info: cdk_mintd::config::MintInfo::default(),
         limits: cdk_mintd::config::Limits::default(),
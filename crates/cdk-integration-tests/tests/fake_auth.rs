@@ -257,7 +257,7 @@ async fn test_melt_without_auth() {
 async fn test_check_without_auth() {
     let client = HttpClient::new(MintUrl::from_str(MINT_URL).expect("Valid mint url"), None);
 
-    let request = CheckStateRequest { ys: vec![] };
+    let request = CheckStateRequest::new(vec![]);
 
     let quote_res = client.post_check_state(request).await;
 
@@ -272,7 +272,7 @@ async fn test_check_without_auth() {
 async fn test_restore_without_auth() {
     let client = HttpClient::new(MintUrl::from_str(MINT_URL).expect("Valid mint url"), None);
 
-    let request = RestoreRequest { outputs: vec![] };
+    let request = RestoreRequest::new(vec![]);
 
     let restore_res = client.post_restore(request).await;
 
@@ -501,14 +501,7 @@ async fn test_mint_auth_over_max() {
         .await;
 
     assert!(
-        matches!(
-            auth_proofs,
-            Err(Error::AmountOutofLimitRange(
-                Amount::ZERO,
-                Amount::ZERO,
-                Amount::ZERO,
-            ))
-        ),
+        matches!(auth_proofs, Err(Error::AmountOutOfLimits)),
         "Expected amount out of limit error, got {:?}",
         auth_proofs
     );
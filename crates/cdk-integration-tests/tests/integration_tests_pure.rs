@@ -24,15 +24,18 @@ use cashu::mint_url::MintUrl;
 use cashu::nuts::nut10::Conditions;
 use cashu::nuts::SigFlag;
 use cashu::{
-    CurrencyUnit, Id, KeySet, KeySetInfo, MeltRequest, NotificationPayload, PaymentMethod,
-    PreMintSecrets, ProofState, SecretKey, SpendingConditions, State, SwapRequest,
+    CurrencyUnit, Id, KeySet, KeySetInfo, MeltQuoteState, MeltRequest, NotificationPayload,
+    PaymentMethod, PreMintSecrets, ProofState, SecretKey, SpendingConditions, State, SwapRequest,
 };
 use cdk::mint::Mint;
 use cdk::nuts::nut00::ProofsMethods;
 use cdk::subscription::Params;
 use cdk::types::QuoteTTL;
 use cdk::wallet::types::{TransactionDirection, TransactionId};
-use cdk::wallet::{MintConnector, P2PKLockedProofSendMode, ReceiveOptions, SendMemo, SendOptions};
+use cdk::wallet::{
+    MeltConfirmOptions, MintConnector, P2PKLockedProofSendMode, ReceiveOptions, SendMemo,
+    SendOptions, Wallet,
+};
 use cdk::{Amount, StreamExt};
 use cdk_common::mint::OperationKind;
 use cdk_common::payment::{
@@ -1752,7 +1755,7 @@ async fn test_direct_connector_custom_melt_enum_roundtrip() {
     let mint = create_and_start_test_mint()
         .await
         .expect("Failed to create test mint");
-    let connector = DirectMintConnection::new(mint.clone());
+    let connector = DirectMintConnector::new(mint.clone());
 
     let request = MeltQuoteRequest::Custom(cdk::nuts::MeltQuoteCustomRequest {
         method: "paypal".to_string(),
@@ -2997,6 +3000,202 @@ async fn test_p2pk_locked_proof_sign_and_send_rejects_sig_all() {
     );
 }
 
+/// Replace the unspent proofs of `wallet` with proofs locked to `pubkey` with SIG_ALL
+async fn lock_wallet_proofs_sig_all(mint: &Mint, wallet: &Wallet, pubkey: cashu::PublicKey) {
+    let sig_all_conditions = Conditions {
+        sig_flag: SigFlag::SigAll,
+        ..Default::default()
+    };
+    let spending_conditions = SpendingConditions::new_p2pk(pubkey, Some(sig_all_conditions));
+
+    let plain_proofs = wallet
+        .get_unspent_proofs()
+        .await
+        .expect("Failed to get proofs");
+    let plain_ys: Vec<_> = plain_proofs.iter().map(|p| p.y().unwrap()).collect();
+    let total_amount = plain_proofs.total_amount().unwrap();
+
+    let keyset_id = get_keyset_id(mint).await;
+    let keys = mint.pubkeys().keysets.first().cloned().unwrap().keys;
+    let fee_and_amounts = (0u64, (0..32).map(|x| 2u64.pow(x)).collect::<Vec<_>>()).into();
+
+    let pre_mint = PreMintSecrets::with_conditions(
+        keyset_id,
+        total_amount,
+        &SplitTarget::default(),
+        &spending_conditions,
+        &fee_and_amounts,
+    )
+    .unwrap();
+
+    let swap_request = SwapRequest::new(plain_proofs, pre_mint.blinded_messages());
+    let swap_response = mint.process_swap_request(swap_request).await.unwrap();
+    let locked_proofs = construct_proofs(
+        swap_response.signatures,
+        pre_mint.rs(),
+        pre_mint.secrets(),
+        &keys,
+    )
+    .unwrap();
+
+    let locked_proof_infos: Vec<_> = locked_proofs
+        .into_iter()
+        .map(|p| {
+            ProofInfo::new(
+                p,
+                wallet.mint_url.clone(),
+                State::Unspent,
+                CurrencyUnit::Sat,
+            )
+            .unwrap()
+        })
+        .collect();
+    wallet
+        .localstore
+        .update_proofs(locked_proof_infos, plain_ys)
+        .await
+        .unwrap();
+}
+
+/// Receiving SIG_ALL locked proofs signs the swap request with the receive keys
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_p2pk_sig_all_receive() {
+    setup_tracing();
+
+    let mint = create_and_start_test_mint()
+        .await
+        .expect("Failed to create test mint");
+    let wallet_alice = create_test_wallet_for_mint(mint.clone())
+        .await
+        .expect("Failed to create alice wallet");
+    let wallet_bob = create_test_wallet_for_mint(mint.clone())
+        .await
+        .expect("Failed to create bob wallet");
+
+    fund_wallet(wallet_alice.clone(), 64, None)
+        .await
+        .expect("Failed to fund alice");
+
+    let bob_secret = SecretKey::generate();
+    let sig_all_conditions = Conditions {
+        sig_flag: SigFlag::SigAll,
+        ..Default::default()
+    };
+    let spending_conditions =
+        SpendingConditions::new_p2pk(bob_secret.public_key(), Some(sig_all_conditions));
+
+    let prepared = wallet_alice
+        .prepare_send(
+            10.into(),
+            SendOptions {
+                conditions: Some(spending_conditions),
+                ..Default::default()
+            },
+        )
+        .await
+        .expect("prepare_send should succeed");
+    let token = prepared
+        .confirm(None)
+        .await
+        .expect("confirm should succeed");
+
+    let received = wallet_bob
+        .receive(
+            &token.to_string(),
+            ReceiveOptions {
+                p2pk_signing_keys: vec![bob_secret],
+                ..Default::default()
+            },
+        )
+        .await
+        .expect("Bob should receive the SIG_ALL token");
+
+    assert_eq!(received, Amount::from(10));
+    assert_eq!(wallet_bob.total_balance().await.unwrap(), Amount::from(10));
+}
+
+/// Melting SIG_ALL locked proofs directly signs the melt request
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_p2pk_sig_all_melt_without_swap() {
+    setup_tracing();
+
+    let mint = create_and_start_test_mint()
+        .await
+        .expect("Failed to create test mint");
+    let wallet_alice = create_test_wallet_for_mint(mint.clone())
+        .await
+        .expect("Failed to create alice wallet");
+
+    fund_wallet(wallet_alice.clone(), 100, None)
+        .await
+        .expect("Failed to fund alice");
+
+    let alice_secret = SecretKey::generate();
+    lock_wallet_proofs_sig_all(&mint, &wallet_alice, alice_secret.public_key()).await;
+    let locked_proofs = wallet_alice.get_unspent_proofs().await.unwrap();
+
+    let fake_invoice = create_fake_invoice(1000, "".to_string());
+    let melt_quote = wallet_alice
+        .melt_quote(PaymentMethod::BOLT11, fake_invoice.to_string(), None, None)
+        .await
+        .unwrap();
+
+    let prepared = wallet_alice
+        .prepare_melt_proofs(&melt_quote.id, locked_proofs, HashMap::new())
+        .await
+        .unwrap();
+    let finalized = prepared
+        .confirm_with_options(
+            MeltConfirmOptions::skip_swap().with_p2pk_signing_keys(vec![alice_secret]),
+        )
+        .await
+        .expect("SIG_ALL melt should succeed");
+
+    assert_eq!(MeltQuoteState::Paid, finalized.state());
+}
+
+/// The pre-melt swap of SIG_ALL locked proofs signs the swap request
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_p2pk_sig_all_melt_with_swap() {
+    setup_tracing();
+
+    let mint = create_and_start_test_mint()
+        .await
+        .expect("Failed to create test mint");
+    let wallet_alice = create_test_wallet_for_mint(mint.clone())
+        .await
+        .expect("Failed to create alice wallet");
+
+    fund_wallet(wallet_alice.clone(), 100, None)
+        .await
+        .expect("Failed to fund alice");
+
+    let alice_secret = SecretKey::generate();
+    lock_wallet_proofs_sig_all(&mint, &wallet_alice, alice_secret.public_key()).await;
+
+    let fake_invoice = create_fake_invoice(1000, "".to_string());
+    let melt_quote = wallet_alice
+        .melt_quote(PaymentMethod::BOLT11, fake_invoice.to_string(), None, None)
+        .await
+        .unwrap();
+
+    let prepared = wallet_alice
+        .prepare_melt(&melt_quote.id, HashMap::new())
+        .await
+        .unwrap();
+    assert!(
+        !prepared.proofs_to_swap().is_empty(),
+        "The melt should swap the locked proofs first"
+    );
+
+    let finalized = prepared
+        .confirm_with_options(MeltConfirmOptions::new().with_p2pk_signing_keys(vec![alice_secret]))
+        .await
+        .expect("SIG_ALL melt with pre-swap should succeed");
+
+    assert_eq!(MeltQuoteState::Paid, finalized.state());
+}
+
 /// The wallet's keyring automatically supplies signing keys at send time.
 ///
 /// Alice generates a P2PK key via `generate_public_key()`, which stores the key in the wallet
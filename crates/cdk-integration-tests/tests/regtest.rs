@@ -94,6 +94,7 @@ async fn test_internal_payment() {
         .unwrap();
 
     assert_eq!(melt.amount, 10.into());
+    assert_eq!(melt.fee_reserve, 0.into());
 
     let prepared = wallet
         .prepare_melt(&melt.id, std::collections::HashMap::new())
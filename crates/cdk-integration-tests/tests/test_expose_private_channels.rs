@@ -85,6 +85,7 @@ async fn test_expose_private_channels() -> Result<()> {
                     amount,
                     description: Some(format!("test exposed {i}")),
                     unix_expiry: None,
+                    hash_description: false,
                 },
             ))
             .await?;
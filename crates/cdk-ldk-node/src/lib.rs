@@ -19,7 +19,7 @@ use ldk_node::bitcoin::Network;
 use ldk_node::lightning::ln::channelmanager::PaymentId;
 use ldk_node::lightning::ln::msgs::SocketAddress;
 use ldk_node::lightning::routing::router::RouteParametersConfig;
-use ldk_node::lightning_invoice::{Bolt11InvoiceDescription, Description};
+use ldk_node::lightning_invoice::{Bolt11InvoiceDescription, Description, Sha256};
 use ldk_node::lightning_types::payment::PaymentHash;
 use ldk_node::logger::{LogLevel, LogWriter};
 use ldk_node::payment::{PaymentDetails, PaymentDirection, PaymentKind, PaymentStatus};
@@ -618,9 +618,15 @@ impl MintPayment for CdkLdkNode {
                     None => 36000,
                 };
 
-                let description = Bolt11InvoiceDescription::Direct(
-                    Description::new(description).map_err(|_| Error::InvalidDescription)?,
-                );
+                let description = if bolt11_options.hash_description {
+                    Bolt11InvoiceDescription::Hash(Sha256(
+                        ldk_node::bitcoin::hashes::sha256::Hash::hash(description.as_bytes()),
+                    ))
+                } else {
+                    Bolt11InvoiceDescription::Direct(
+                        Description::new(description).map_err(|_| Error::InvalidDescription)?,
+                    )
+                };
 
                 let payment = self
                     .inner
@@ -1115,6 +1121,23 @@ impl MintPayment for CdkLdkNode {
         Ok(vec![response])
     }
 
+    /// Outbound capacity of the usable channels, unknown until the node is started
+    async fn outbound_liquidity(&self) -> Result<Option<Amount<CurrencyUnit>>, Self::Err> {
+        if !self.inner.status().is_running {
+            return Ok(None);
+        }
+
+        let outbound_msat = self
+            .inner
+            .list_channels()
+            .iter()
+            .filter(|channel| channel.is_usable)
+            .map(|channel| channel.outbound_capacity_msat)
+            .sum();
+
+        Ok(Some(Amount::new(outbound_msat, CurrencyUnit::Msat)))
+    }
+
     /// Check the status of an outgoing payment
     async fn check_outgoing_payment(
         &self,
@@ -17,6 +17,12 @@ pub enum Error {
     /// Invalid payment hash
     #[error("Invalid payment hash")]
     InvalidPaymentHash,
+    /// Invalid webhook url
+    #[error("Invalid webhook url")]
+    InvalidWebhookUrl,
+    /// Webhook secret not set
+    #[error("Webhook secret must be set")]
+    MissingWebhookSecret,
     /// Anyhow error
     #[error(transparent)]
     Anyhow(#[from] anyhow::Error),
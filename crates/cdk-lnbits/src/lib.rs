@@ -9,7 +9,7 @@ use std::sync::Arc;
 
 use anyhow::anyhow;
 use async_trait::async_trait;
-use cdk_common::amount::{Amount, MSAT_IN_SAT};
+use cdk_common::amount::Amount;
 use cdk_common::common::FeeReserve;
 use cdk_common::nuts::{CurrencyUnit, MeltOptions, MeltQuoteState};
 use cdk_common::payment::{
@@ -24,8 +24,10 @@ use futures::Stream;
 use lnbits_rs::api::invoice::CreateInvoiceRequest;
 use lnbits_rs::LNBitsClient;
 use tokio_util::sync::CancellationToken;
+use webhook::Webhook;
 
 pub mod error;
+mod webhook;
 
 /// LNbits
 #[derive(Clone)]
@@ -35,12 +37,16 @@ pub struct LNbits {
     wait_invoice_cancel_token: CancellationToken,
     wait_invoice_is_active: Arc<AtomicBool>,
     settings: SettingsResponse,
+    invoice_api_key: String,
+    api_url: String,
+    webhook: Option<Webhook>,
 }
 
 impl std::fmt::Debug for LNbits {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.debug_struct("LNbits")
             .field("fee_reserve", &self.fee_reserve)
+            .field("webhook", &self.webhook.is_some())
             .finish_non_exhaustive()
     }
 }
@@ -72,9 +78,33 @@ impl LNbits {
                 onchain: None,
                 custom: std::collections::HashMap::new(),
             },
+            invoice_api_key,
+            api_url,
+            webhook: None,
         })
     }
 
+    /// Receive payment notifications through an LNbits webhook
+    ///
+    /// Invoices are created with a callback to `webhook_url`, which must be
+    /// served by [`LNbits::webhook_router`] and reachable by LNbits. Callbacks
+    /// are authenticated with an HMAC keyed by `webhook_secret`. Pending
+    /// invoices are still polled periodically in case a webhook is lost, and
+    /// the websocket does not need to be subscribed.
+    pub fn with_webhook(
+        mut self,
+        webhook_url: &str,
+        webhook_secret: String,
+    ) -> Result<Self, Error> {
+        self.webhook = Some(Webhook::new(
+            webhook_url,
+            webhook_secret,
+            &self.api_url,
+            self.invoice_api_key.clone(),
+        )?);
+        Ok(self)
+    }
+
     /// Subscribe to lnbits ws
     pub async fn subscribe_ws(&self) -> Result<(), Error> {
         if rustls::crypto::CryptoProvider::get_default().is_none() {
@@ -170,6 +200,10 @@ impl MintPayment for LNbits {
         let cancel_token = self.wait_invoice_cancel_token.clone();
         let is_active = Arc::clone(&self.wait_invoice_is_active);
 
+        if let Some(webhook) = &self.webhook {
+            return Ok(webhook.payment_stream(api, cancel_token, is_active));
+        }
+
         Ok(Box::pin(futures::stream::unfold(
             (api, cancel_token, is_active, 0u32),
             |(api, cancel_token, is_active, mut retry_count)| async move {
@@ -261,8 +295,7 @@ impl MintPayment for LNbits {
                 let relative_fee_reserve =
                     (self.fee_reserve.percent_fee_reserve * u64::from(amount_msat) as f32) as u64;
 
-                let absolute_fee_reserve: u64 =
-                    u64::from(self.fee_reserve.min_fee_reserve) * MSAT_IN_SAT;
+                let absolute_fee_reserve = self.fee_reserve.min_fee_reserve.to_msat()?;
 
                 let fee = max(relative_fee_reserve, absolute_fee_reserve);
 
@@ -359,6 +392,11 @@ impl MintPayment for LNbits {
     ) -> Result<CreateIncomingPaymentResponse, Self::Err> {
         match options {
             IncomingPaymentOptions::Bolt11(bolt11_options) => {
+                if bolt11_options.hash_description {
+                    tracing::error!("LNbits: Invoices cannot commit to a description hash");
+                    return Err(payment::Error::UnsupportedPaymentOption);
+                }
+
                 let description = bolt11_options.description.unwrap_or_default();
                 let amount = bolt11_options.amount;
                 let unix_expiry = bolt11_options.unix_expiry;
@@ -377,17 +415,41 @@ impl MintPayment for LNbits {
                     out: false,
                 };
 
-                let create_invoice_response = self
-                    .lnbits_api
-                    .create_invoice(&invoice_request)
-                    .await
-                    .map_err(|err| {
-                        tracing::error!("Could not create invoice");
-                        tracing::error!("{}", err.to_string());
-                        Self::Err::Anyhow(anyhow!("Could not create invoice"))
-                    })?;
-
-                let request: Bolt11Invoice = create_invoice_response.bolt11().parse()?;
+                let request: Bolt11Invoice = match &self.webhook {
+                    Some(webhook) => {
+                        let (id, response) = webhook
+                            .create_invoice(&invoice_request)
+                            .await
+                            .map_err(|err| {
+                                tracing::error!("Could not create invoice");
+                                tracing::error!("{}", err.to_string());
+                                Self::Err::Anyhow(anyhow!("Could not create invoice"))
+                            })?;
+
+                        let request: Bolt11Invoice = response.bolt11.parse()?;
+                        webhook
+                            .track_invoice(
+                                id,
+                                hex::encode(request.payment_hash().as_ref()),
+                                request.expires_at().map(|t| t.as_secs()),
+                            )
+                            .await;
+                        request
+                    }
+                    None => {
+                        let create_invoice_response = self
+                            .lnbits_api
+                            .create_invoice(&invoice_request)
+                            .await
+                            .map_err(|err| {
+                                tracing::error!("Could not create invoice");
+                                tracing::error!("{}", err.to_string());
+                                Self::Err::Anyhow(anyhow!("Could not create invoice"))
+                            })?;
+
+                        create_invoice_response.bolt11().parse()?
+                    }
+                };
 
                 let expiry = request.expires_at().map(|t| t.as_secs());
 
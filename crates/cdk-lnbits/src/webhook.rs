@@ -0,0 +1,425 @@
+//! Webhook based payment notifications for LNbits
+//!
+//! Every invoice created while webhooks are enabled registers a callback URL
+//! with LNbits. The URL carries a random invoice id together with an HMAC of
+//! that id keyed by the configured secret, so only LNbits (which learned the
+//! URL from us) can trigger a status check. A periodic fallback poll covers
+//! webhooks that are lost or never delivered.
+
+use std::collections::{HashMap, VecDeque};
+use std::pin::Pin;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use axum::body::Bytes;
+use axum::extract::{Query, State};
+use axum::http::StatusCode;
+use axum::routing::post;
+use axum::Router;
+use bitcoin::hashes::{hmac, sha256, Hash, HashEngine, HmacEngine};
+use cdk_common::payment::{Event, WaitPaymentResponse};
+use cdk_common::util::{hex, unix_time};
+use cdk_common::HttpClient;
+use futures::Stream;
+use lnbits_rs::api::invoice::CreateInvoiceRequest;
+use lnbits_rs::LNBitsClient;
+use serde::Deserialize;
+use tokio::sync::{mpsc, Mutex};
+use tokio_util::sync::CancellationToken;
+use url::Url;
+
+use crate::error::Error;
+use crate::LNbits;
+
+/// Interval between fallback status checks of invoices awaiting a webhook
+const WEBHOOK_FALLBACK_POLL_INTERVAL: Duration = Duration::from_secs(30);
+/// Capacity of the channel between the webhook handler and the payment stream
+const WEBHOOK_CHANNEL_SIZE: usize = 256;
+
+/// Invoice waiting for its settlement webhook
+#[derive(Debug, Clone)]
+struct PendingInvoice {
+    payment_hash: String,
+    expiry: Option<u64>,
+}
+
+/// Query parameters LNbits echoes back on the webhook call
+#[derive(Debug, Deserialize)]
+struct WebhookQuery {
+    id: String,
+    hmac: String,
+}
+
+/// Subset of the payment LNbits posts to the webhook
+#[derive(Debug, Deserialize)]
+struct WebhookPayload {
+    payment_hash: String,
+}
+
+/// Response of the LNbits create invoice endpoint
+#[derive(Debug, Deserialize)]
+pub(crate) struct WebhookInvoiceResponse {
+    #[serde(alias = "payment_request")]
+    pub bolt11: String,
+}
+
+/// What woke the webhook payment stream
+enum WebhookWake {
+    Cancelled,
+    Notified(Option<String>),
+    Poll,
+}
+
+/// Shared webhook state
+#[derive(Clone)]
+pub(crate) struct Webhook {
+    url: Url,
+    secret: String,
+    payments_url: String,
+    invoice_api_key: String,
+    http_client: HttpClient,
+    sender: mpsc::Sender<String>,
+    receiver: Arc<Mutex<mpsc::Receiver<String>>>,
+    pending: Arc<Mutex<HashMap<String, PendingInvoice>>>,
+}
+
+impl Webhook {
+    /// Create webhook state for the given public callback url
+    pub(crate) fn new(
+        webhook_url: &str,
+        secret: String,
+        api_url: &str,
+        invoice_api_key: String,
+    ) -> Result<Self, Error> {
+        let url = Url::parse(webhook_url).map_err(|_| Error::InvalidWebhookUrl)?;
+
+        if secret.is_empty() {
+            return Err(Error::MissingWebhookSecret);
+        }
+
+        let (sender, receiver) = mpsc::channel(WEBHOOK_CHANNEL_SIZE);
+
+        Ok(Self {
+            url,
+            secret,
+            payments_url: payments_url(api_url),
+            invoice_api_key,
+            http_client: HttpClient::new(),
+            sender,
+            receiver: Arc::new(Mutex::new(receiver)),
+            pending: Arc::new(Mutex::new(HashMap::new())),
+        })
+    }
+
+    /// Create an invoice on LNbits with a webhook pointing back at the mint
+    ///
+    /// Returns the invoice id embedded in the callback url alongside the response.
+    pub(crate) async fn create_invoice(
+        &self,
+        request: &CreateInvoiceRequest,
+    ) -> Result<(String, WebhookInvoiceResponse), Error> {
+        let id = hex::encode(bitcoin::secp256k1::rand::random::<[u8; 16]>());
+
+        let mut body = serde_json::to_value(request)
+            .map_err(|err| Error::Anyhow(anyhow::anyhow!("Invalid invoice request: {err}")))?;
+        body["webhook"] = serde_json::Value::String(self.callback_url(&id).to_string());
+
+        let response: WebhookInvoiceResponse = self
+            .http_client
+            .post(&self.payments_url)
+            .header("X-Api-Key", &self.invoice_api_key)
+            .json(&body)
+            .send_json()
+            .await
+            .map_err(|err| Error::Anyhow(anyhow::anyhow!("Could not create invoice: {err}")))?;
+
+        Ok((id, response))
+    }
+
+    /// Start tracking an invoice created through [`Self::create_invoice`]
+    pub(crate) async fn track_invoice(
+        &self,
+        id: String,
+        payment_hash: String,
+        expiry: Option<u64>,
+    ) {
+        self.pending.lock().await.insert(
+            id,
+            PendingInvoice {
+                payment_hash,
+                expiry,
+            },
+        );
+    }
+
+    /// Callback url registered with LNbits for an invoice id
+    fn callback_url(&self, id: &str) -> Url {
+        let mut url = self.url.clone();
+        url.query_pairs_mut()
+            .append_pair("id", id)
+            .append_pair("hmac", &self.sign(id));
+        url
+    }
+
+    /// Hex encoded HMAC-SHA256 of an invoice id keyed by the webhook secret
+    fn sign(&self, id: &str) -> String {
+        let mut engine = HmacEngine::<sha256::Hash>::new(self.secret.as_bytes());
+        engine.input(id.as_bytes());
+        hex::encode(hmac::Hmac::<sha256::Hash>::from_engine(engine).to_byte_array())
+    }
+
+    /// Check that the hmac was produced for this id with our secret
+    fn verify(&self, id: &str, hmac: &str) -> bool {
+        constant_time_eq(self.sign(id).as_bytes(), hmac.to_lowercase().as_bytes())
+    }
+
+    /// Stream of payments reported by webhooks and the fallback poll
+    pub(crate) fn payment_stream(
+        &self,
+        api: LNBitsClient,
+        cancel_token: CancellationToken,
+        is_active: Arc<AtomicBool>,
+    ) -> Pin<Box<dyn Stream<Item = Event> + Send>> {
+        let mut interval = tokio::time::interval(WEBHOOK_FALLBACK_POLL_INTERVAL);
+        interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Skip);
+
+        Box::pin(futures::stream::unfold(
+            (
+                self.clone(),
+                api,
+                cancel_token,
+                is_active,
+                interval,
+                VecDeque::new(),
+            ),
+            |(webhook, api, cancel_token, is_active, mut interval, mut queue)| async move {
+                is_active.store(true, Ordering::SeqCst);
+
+                loop {
+                    if let Some(response) = queue.pop_front() {
+                        return Some((
+                            Event::PaymentReceived(response),
+                            (webhook, api, cancel_token, is_active, interval, queue),
+                        ));
+                    }
+
+                    let wake = {
+                        let mut receiver = webhook.receiver.lock().await;
+
+                        tokio::select! {
+                            _ = cancel_token.cancelled() => WebhookWake::Cancelled,
+                            payment_hash = receiver.recv() => WebhookWake::Notified(payment_hash),
+                            _ = interval.tick() => WebhookWake::Poll,
+                        }
+                    };
+
+                    match wake {
+                        WebhookWake::Cancelled | WebhookWake::Notified(None) => {
+                            is_active.store(false, Ordering::SeqCst);
+                            tracing::info!("Waiting for lnbits webhooks ending");
+                            return None;
+                        }
+                        WebhookWake::Notified(Some(payment_hash)) => {
+                            if let Some(response) =
+                                LNbits::process_message(Some(payment_hash), &api, &is_active).await
+                            {
+                                queue.push_back(response);
+                            }
+                        }
+                        WebhookWake::Poll => {
+                            queue.extend(webhook.poll_pending(&api).await);
+                        }
+                    }
+                }
+            },
+        ))
+    }
+
+    /// Check every invoice still waiting for a webhook and return the paid ones
+    async fn poll_pending(&self, api: &LNBitsClient) -> Vec<WaitPaymentResponse> {
+        let now = unix_time();
+        let grace = WEBHOOK_FALLBACK_POLL_INTERVAL.as_secs();
+
+        let pending: Vec<(String, PendingInvoice)> = {
+            let mut pending = self.pending.lock().await;
+            pending.retain(|_, invoice| {
+                invoice
+                    .expiry
+                    .is_none_or(|expiry| expiry.saturating_add(grace) > now)
+            });
+            pending
+                .iter()
+                .map(|(id, invoice)| (id.clone(), invoice.clone()))
+                .collect()
+        };
+
+        let mut responses = Vec::new();
+
+        for (id, invoice) in pending {
+            let payment = match api.get_payment_info(&invoice.payment_hash).await {
+                Ok(payment) => payment,
+                Err(err) => {
+                    tracing::warn!(
+                        "LNbits fallback poll could not check {}: {}",
+                        invoice.payment_hash,
+                        err
+                    );
+                    continue;
+                }
+            };
+
+            if !payment.paid {
+                continue;
+            }
+
+            tracing::debug!(
+                "LNbits fallback poll found paid invoice {} without webhook",
+                invoice.payment_hash
+            );
+            self.pending.lock().await.remove(&id);
+
+            match LNbits::create_payment_response(&invoice.payment_hash, &payment) {
+                Ok(Some(response)) => responses.push(response),
+                Ok(None) => {}
+                Err(err) => tracing::error!("Failed to create payment response: {}", err),
+            }
+        }
+
+        responses
+    }
+}
+
+impl LNbits {
+    /// Router serving the LNbits settlement webhook
+    ///
+    /// Returns `None` when webhooks are not enabled. The route is mounted at
+    /// the path of the configured webhook url and must be reachable by LNbits.
+    pub fn webhook_router(&self) -> Option<Router> {
+        let webhook = self.webhook.clone()?;
+        let path = webhook.url.path().to_string();
+
+        Some(
+            Router::new()
+                .route(&path, post(handle_webhook))
+                .with_state(webhook),
+        )
+    }
+}
+
+/// Handle a settlement webhook from LNbits
+async fn handle_webhook(
+    State(webhook): State<Webhook>,
+    Query(query): Query<WebhookQuery>,
+    body: Bytes,
+) -> StatusCode {
+    if !webhook.verify(&query.id, &query.hmac) {
+        tracing::warn!("Rejected LNbits webhook with invalid hmac");
+        return StatusCode::UNAUTHORIZED;
+    }
+
+    let tracked = webhook.pending.lock().await.remove(&query.id);
+
+    let payment_hash = match tracked {
+        Some(invoice) => invoice.payment_hash,
+        // Invoices created before a restart are no longer tracked, fall back
+        // to the hash reported by LNbits. The payment is verified before use.
+        None => match serde_json::from_slice::<WebhookPayload>(&body) {
+            Ok(payload) => payload.payment_hash,
+            Err(err) => {
+                tracing::warn!("Invalid LNbits webhook payload: {}", err);
+                return StatusCode::BAD_REQUEST;
+            }
+        },
+    };
+
+    tracing::debug!("Received LNbits webhook for {}", payment_hash);
+
+    match webhook.sender.send(payment_hash).await {
+        Ok(()) => StatusCode::OK,
+        Err(_) => StatusCode::SERVICE_UNAVAILABLE,
+    }
+}
+
+/// LNbits payments endpoint for the configured api url
+fn payments_url(api_url: &str) -> String {
+    let base = api_url.trim_end_matches('/');
+    let base = base.strip_suffix("/api/v1").unwrap_or(base);
+    format!("{base}/api/v1/payments")
+}
+
+/// Compare two byte slices without short-circuiting on the first mismatch
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    if a.len() != b.len() {
+        return false;
+    }
+
+    a.iter().zip(b).fold(0u8, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn webhook() -> Webhook {
+        Webhook::new(
+            "https://mint.example.com/webhook/lnbits",
+            "secret".to_string(),
+            "https://lnbits.example.com",
+            "invoice_key".to_string(),
+        )
+        .expect("valid webhook")
+    }
+
+    #[test]
+    fn test_payments_url() {
+        assert_eq!(
+            payments_url("https://lnbits.example.com"),
+            "https://lnbits.example.com/api/v1/payments"
+        );
+        assert_eq!(
+            payments_url("https://lnbits.example.com/api/v1/"),
+            "https://lnbits.example.com/api/v1/payments"
+        );
+    }
+
+    #[test]
+    fn test_webhook_requires_secret() {
+        let result = Webhook::new(
+            "https://mint.example.com/webhook/lnbits",
+            String::new(),
+            "https://lnbits.example.com",
+            "invoice_key".to_string(),
+        );
+        assert!(matches!(result, Err(Error::MissingWebhookSecret)));
+    }
+
+    #[test]
+    fn test_callback_url_verifies() {
+        let webhook = webhook();
+        let url = webhook.callback_url("abcd");
+
+        assert_eq!(url.path(), "/webhook/lnbits");
+
+        let pairs: HashMap<_, _> = url.query_pairs().into_owned().collect();
+        assert_eq!(pairs.get("id").map(String::as_str), Some("abcd"));
+
+        let hmac = pairs.get("hmac").expect("hmac in callback url");
+        assert!(webhook.verify("abcd", hmac));
+        assert!(!webhook.verify("abce", hmac));
+    }
+
+    #[test]
+    fn test_hmac_depends_on_secret() {
+        let webhook = webhook();
+        let other = Webhook::new(
+            "https://mint.example.com/webhook/lnbits",
+            "other".to_string(),
+            "https://lnbits.example.com",
+            "invoice_key".to_string(),
+        )
+        .expect("valid webhook");
+
+        assert!(!other.verify("abcd", &webhook.sign("abcd")));
+    }
+}
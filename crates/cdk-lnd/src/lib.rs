@@ -13,15 +13,15 @@ use std::sync::Arc;
 
 use anyhow::anyhow;
 use async_trait::async_trait;
-use cdk_common::amount::{Amount, MSAT_IN_SAT};
+use cdk_common::amount::{Amount, MsatRounding};
 use cdk_common::bitcoin::hashes::Hash;
 use cdk_common::common::FeeReserve;
 use cdk_common::database::DynKVStore;
 use cdk_common::nuts::{CurrencyUnit, MeltOptions, MeltQuoteState};
 use cdk_common::payment::{
-    self, CreateIncomingPaymentResponse, Event, IncomingPaymentOptions, MakePaymentResponse,
-    MintPayment, OutgoingPaymentOptions, PaymentIdentifier, PaymentQuoteResponse, SettingsResponse,
-    WaitPaymentResponse,
+    self, CreateIncomingPaymentResponse, CustomIncomingPaymentOptions, Event,
+    IncomingPaymentOptions, MakePaymentResponse, MintPayment, OutgoingPaymentOptions,
+    PaymentIdentifier, PaymentQuoteResponse, SettingsResponse, WaitPaymentResponse,
 };
 use cdk_common::util::{hex, unix_time};
 use cdk_common::Bolt11Invoice;
@@ -40,6 +40,7 @@ mod proto;
 pub(crate) use proto::{lnrpc, routerrpc};
 
 use crate::lnrpc::invoice::InvoiceState;
+use crate::lnrpc::InvoiceHtlcState;
 
 /// LND KV Store constants
 const LND_KV_PRIMARY_NAMESPACE: &str = "cdk_lnd_lightning_backend";
@@ -47,6 +48,12 @@ const LND_KV_SECONDARY_NAMESPACE: &str = "payment_indices";
 const LAST_ADD_INDEX_KV_KEY: &str = "last_add_index";
 const LAST_SETTLE_INDEX_KV_KEY: &str = "last_settle_index";
 
+/// Custom payment method name for keysend and AMP mint quotes
+pub const KEYSEND_PAYMENT_METHOD: &str = "keysend";
+
+/// Custom TLV record type carrying the mint quote id in keysend and AMP payments
+pub const KEYSEND_QUOTE_ID_TLV_TYPE: u64 = 5_482_373_485;
+
 /// Lnd mint backend
 #[derive(Clone)]
 pub struct Lnd {
@@ -60,6 +67,7 @@ pub struct Lnd {
     wait_invoice_is_active: Arc<AtomicBool>,
     settings: SettingsResponse,
     unit: CurrencyUnit,
+    keysend: bool,
 }
 
 impl std::fmt::Debug for Lnd {
@@ -135,6 +143,61 @@ impl Lnd {
                 custom: std::collections::HashMap::new(),
             },
             unit,
+            keysend: false,
+        })
+    }
+
+    /// Configures the [`Lnd`] to accept keysend and AMP payments for mint quotes
+    ///
+    /// Advertises the [`KEYSEND_PAYMENT_METHOD`] custom payment method. Payers
+    /// send to the node pubkey returned as the quote request and include the
+    /// quote id in the [`KEYSEND_QUOTE_ID_TLV_TYPE`] custom record. LND must be
+    /// started with `--accept-keysend` and/or `--accept-amp`.
+    pub fn with_keysend(mut self, keysend: bool) -> Self {
+        self.keysend = keysend;
+
+        if keysend {
+            self.settings.custom.insert(
+                KEYSEND_PAYMENT_METHOD.to_string(),
+                serde_json::json!({
+                    "tlv_type": KEYSEND_QUOTE_ID_TLV_TYPE,
+                    "amp": true,
+                })
+                .to_string(),
+            );
+        } else {
+            self.settings.custom.remove(KEYSEND_PAYMENT_METHOD);
+        }
+
+        self
+    }
+
+    /// Create a keysend quote request pointing the payer at this node
+    async fn create_keysend_request(
+        &self,
+        options: &CustomIncomingPaymentOptions,
+    ) -> Result<CreateIncomingPaymentResponse, Error> {
+        let mut lnd_client = self.lnd_client.clone();
+
+        let info = lnd_client
+            .lightning()
+            .get_info(lnrpc::GetInfoRequest {})
+            .await
+            .map_err(Error::LndError)?
+            .into_inner();
+
+        let quote_id = options.quote_id.to_string();
+
+        Ok(CreateIncomingPaymentResponse {
+            request_lookup_id: PaymentIdentifier::CustomId(quote_id.clone()),
+            request: info.identity_pubkey.clone(),
+            expiry: options.unix_expiry,
+            extra_json: Some(serde_json::json!({
+                "pubkey": info.identity_pubkey,
+                "tlv_type": KEYSEND_QUOTE_ID_TLV_TYPE,
+                "tlv_value": quote_id,
+                "amp": true,
+            })),
         })
     }
 
@@ -202,16 +265,9 @@ fn msat_total_spent_for_unit(
     total_msat: u64,
     unit: &CurrencyUnit,
 ) -> Result<Amount<CurrencyUnit>, Error> {
-    match unit {
-        CurrencyUnit::Msat => Ok(Amount::new(total_msat, CurrencyUnit::Msat)),
-        CurrencyUnit::Sat => Ok(Amount::new(
-            total_msat.div_ceil(MSAT_IN_SAT),
-            CurrencyUnit::Sat,
-        )),
-        _ => Amount::new(total_msat, CurrencyUnit::Msat)
-            .convert_to(unit)
-            .map_err(Error::from),
-    }
+    Amount::new(total_msat, CurrencyUnit::Msat)
+        .convert_to_with_rounding(unit, MsatRounding::Up)
+        .map_err(Error::from)
 }
 
 #[async_trait]
@@ -329,6 +385,25 @@ impl MintPayment for Lnd {
                                         tracing::warn!("LND: Failed to begin KV transaction for storing indices");
                                     }
 
+                                    // Keysend and AMP payments carry the quote id in a custom record
+                                    if msg.is_keysend || msg.is_amp {
+                                        match keysend_payment_response(&msg) {
+                                            Some(wait_response) => {
+                                                tracing::info!(
+                                                    "LND: Spontaneous payment for quote {} with amount {} msat",
+                                                    wait_response.payment_identifier,
+                                                    wait_response.payment_amount.value()
+                                                );
+                                                let event = Event::PaymentReceived(wait_response);
+                                                return Some((event, (stream, cancel_token, is_active, kv_store, current_add_index, current_settle_index)));
+                                            }
+                                            None => {
+                                                tracing::debug!("LND: Spontaneous payment without settled quote id record, skipping");
+                                                continue;
+                                            }
+                                        }
+                                    }
+
                                     // Only emit event for settled invoices
                                     if msg.state() == InvoiceState::Settled {
                                         let hash_slice: Result<[u8;32], _> = msg.r_hash.try_into();
@@ -400,7 +475,18 @@ impl MintPayment for Lnd {
 
                         amount_msat
                     }
-                    Some(MeltOptions::Mpp { mpp }) => mpp.amount,
+                    Some(MeltOptions::Mpp { mpp }) => {
+                        let invoice_amount = bolt11_options
+                            .bolt11
+                            .amount_milli_satoshis()
+                            .ok_or(Error::UnknownInvoiceAmount)?;
+
+                        // A partial payment never pays more than the invoice amount
+                        if u64::from(mpp.amount) > invoice_amount {
+                            return Err(payment::Error::AmountMismatch);
+                        }
+                        mpp.amount
+                    }
                     None => bolt11_options
                         .bolt11
                         .amount_milli_satoshis()
@@ -475,6 +561,9 @@ impl MintPayment for Lnd {
                             .ok_or(Error::UnknownInvoiceAmount)?;
                         {
                             let partial_amount_msat = mpp.amount;
+                            if u64::from(partial_amount_msat) > amount_msat {
+                                return Err(payment::Error::AmountMismatch);
+                            }
                             let invoice = bolt11;
                             let max_fee: Option<Amount<CurrencyUnit>> =
                                 bolt11_options.max_fee_amount.clone();
@@ -684,9 +773,18 @@ impl MintPayment for Lnd {
 
                 let amount_msat: Amount = amount.convert_to(&CurrencyUnit::Msat)?.into();
 
+                let (memo, description_hash) = if bolt11_options.hash_description {
+                    let hash =
+                        cdk_common::bitcoin::hashes::sha256::Hash::hash(description.as_bytes());
+                    (String::new(), hash.to_byte_array().to_vec())
+                } else {
+                    (description, Vec::new())
+                };
+
                 let invoice_request = lnrpc::Invoice {
                     value_msat: u64::from(amount_msat) as i64,
-                    memo: description,
+                    memo,
+                    description_hash,
                     expiry: unix_expiry
                         .map(|t| {
                             t.checked_sub(unix_time())
@@ -723,6 +821,11 @@ impl MintPayment for Lnd {
             IncomingPaymentOptions::Bolt12(_) => {
                 Err(Self::Err::Anyhow(anyhow!("BOLT12 not supported by LND")))
             }
+            IncomingPaymentOptions::Custom(custom_options)
+                if self.keysend && custom_options.method == KEYSEND_PAYMENT_METHOD =>
+            {
+                Ok(self.create_keysend_request(&custom_options).await?)
+            }
             IncomingPaymentOptions::Custom(_) | IncomingPaymentOptions::Onchain(_) => {
                 Err(payment::Error::UnsupportedPaymentOption)
             }
@@ -734,6 +837,12 @@ impl MintPayment for Lnd {
         &self,
         payment_identifier: &PaymentIdentifier,
     ) -> Result<Vec<WaitPaymentResponse>, Self::Err> {
+        // LND cannot look up invoices by custom record, keysend and AMP
+        // payments are only reported through the invoice subscription.
+        if let PaymentIdentifier::CustomId(_) = payment_identifier {
+            return Ok(vec![]);
+        }
+
         let mut lnd_client = self.lnd_client.clone();
 
         let invoice_request = lnrpc::PaymentHash {
@@ -759,6 +868,25 @@ impl MintPayment for Lnd {
         }
     }
 
+    #[instrument(skip(self))]
+    async fn outbound_liquidity(&self) -> Result<Option<Amount<CurrencyUnit>>, Self::Err> {
+        let mut lnd_client = self.lnd_client.clone();
+
+        let balance = lnd_client
+            .lightning()
+            .channel_balance(lnrpc::ChannelBalanceRequest {})
+            .await
+            .map_err(Error::LndError)?
+            .into_inner();
+
+        let local_msat = balance
+            .local_balance
+            .map(|local_balance| local_balance.msat)
+            .unwrap_or_default();
+
+        Ok(Some(Amount::new(local_msat, CurrencyUnit::Msat)))
+    }
+
     #[instrument(skip(self))]
     async fn check_outgoing_payment(
         &self,
@@ -841,8 +969,46 @@ impl MintPayment for Lnd {
     }
 }
 
+/// Build a payment response for a settled keysend or AMP invoice
+///
+/// Returns `None` if no settled HTLC carries a quote id record.
+fn keysend_payment_response(invoice: &lnrpc::Invoice) -> Option<WaitPaymentResponse> {
+    let settled_htlcs: Vec<&lnrpc::InvoiceHtlc> = invoice
+        .htlcs
+        .iter()
+        .filter(|htlc| htlc.state() == InvoiceHtlcState::Settled)
+        .collect();
+
+    let quote_id = settled_htlcs.iter().find_map(|htlc| {
+        htlc.custom_records
+            .get(&KEYSEND_QUOTE_ID_TLV_TYPE)
+            .and_then(|value| String::from_utf8(value.clone()).ok())
+    })?;
+
+    let amount_msat: u64 = settled_htlcs.iter().map(|htlc| htlc.amt_msat).sum();
+
+    if amount_msat == 0 {
+        return None;
+    }
+
+    // AMP payments to the same invoice share the payment hash, each payment
+    // is identified by its set id instead.
+    let payment_id = match settled_htlcs.iter().find_map(|htlc| htlc.amp.as_ref()) {
+        Some(amp) if !amp.set_id.is_empty() => hex::encode(&amp.set_id),
+        _ => hex::encode(&invoice.r_hash),
+    };
+
+    Some(WaitPaymentResponse {
+        payment_identifier: PaymentIdentifier::CustomId(quote_id),
+        payment_amount: Amount::new(amount_msat, CurrencyUnit::Msat),
+        payment_id,
+    })
+}
+
 #[cfg(test)]
 mod tests {
+    use std::collections::HashMap;
+
     use super::*;
 
     #[test]
@@ -888,4 +1054,82 @@ mod tests {
 
         assert_eq!(total_spent, Amount::new(2, CurrencyUnit::Sat));
     }
+
+    fn keysend_htlc(amt_msat: u64, quote_id: Option<&str>) -> lnrpc::InvoiceHtlc {
+        let mut custom_records = HashMap::new();
+        if let Some(quote_id) = quote_id {
+            custom_records.insert(KEYSEND_QUOTE_ID_TLV_TYPE, quote_id.as_bytes().to_vec());
+        }
+
+        lnrpc::InvoiceHtlc {
+            amt_msat,
+            state: InvoiceHtlcState::Settled as i32,
+            custom_records,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn keysend_payment_response_maps_quote_id_record() {
+        let invoice = lnrpc::Invoice {
+            r_hash: vec![1; 32],
+            is_keysend: true,
+            htlcs: vec![keysend_htlc(1000, Some("quote")), keysend_htlc(500, None)],
+            ..Default::default()
+        };
+
+        let response =
+            keysend_payment_response(&invoice).expect("keysend payment should be mapped");
+
+        assert_eq!(
+            response.payment_identifier,
+            PaymentIdentifier::CustomId("quote".to_string())
+        );
+        assert_eq!(
+            response.payment_amount,
+            Amount::new(1500, CurrencyUnit::Msat)
+        );
+        assert_eq!(response.payment_id, hex::encode([1; 32]));
+    }
+
+    #[test]
+    fn keysend_payment_response_uses_amp_set_id() {
+        let mut htlc = keysend_htlc(1000, Some("quote"));
+        htlc.amp = Some(lnrpc::Amp {
+            set_id: vec![2; 32],
+            ..Default::default()
+        });
+
+        let invoice = lnrpc::Invoice {
+            r_hash: vec![1; 32],
+            is_amp: true,
+            htlcs: vec![htlc],
+            ..Default::default()
+        };
+
+        let response = keysend_payment_response(&invoice).expect("amp payment should be mapped");
+
+        assert_eq!(response.payment_id, hex::encode([2; 32]));
+    }
+
+    #[test]
+    fn keysend_payment_response_ignores_payments_without_quote_id() {
+        let invoice = lnrpc::Invoice {
+            is_keysend: true,
+            htlcs: vec![keysend_htlc(1000, None)],
+            ..Default::default()
+        };
+
+        assert!(keysend_payment_response(&invoice).is_none());
+
+        let mut unsettled = keysend_htlc(1000, Some("quote"));
+        unsettled.state = InvoiceHtlcState::Accepted as i32;
+        let invoice = lnrpc::Invoice {
+            is_keysend: true,
+            htlcs: vec![unsettled],
+            ..Default::default()
+        };
+
+        assert!(keysend_payment_response(&invoice).is_none());
+    }
 }
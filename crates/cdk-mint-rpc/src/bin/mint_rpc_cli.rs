@@ -104,6 +104,28 @@ enum Commands {
     UpdateNut04QuoteState(subcommands::UpdateNut04QuoteCommand),
     /// Rotate next keyset
     RotateNextKeyset(subcommands::RotateNextKeysetCommand),
+    /// Get fake wallet failure injection settings
+    GetFakeWalletFailureInjection,
+    /// Update fake wallet failure injection settings
+    UpdateFakeWalletFailureInjection(subcommands::UpdateFakeWalletFailureInjectionCommand),
+    /// Schedule a message of the day
+    ScheduleMotd(subcommands::ScheduleMotdCommand),
+    /// Get scheduled messages of the day
+    GetMotdSchedule,
+    /// Clear scheduled messages of the day
+    ClearMotdSchedule,
+    /// Archive spent proofs older than a retention period
+    ArchiveSpentProofs(subcommands::ArchiveSpentProofsCommand),
+    /// Enter or leave maintenance mode
+    SetMaintenanceMode(subcommands::SetMaintenanceModeCommand),
+    /// Export the accounting ledger as CSV or beancount
+    ExportLedger(subcommands::ExportLedgerCommand),
+    /// Get accumulated fee income per unit
+    GetFeeTotals,
+    /// Record a sweep of fee income into a designated balance
+    RecordFeeSweep(subcommands::RecordFeeSweepCommand),
+    /// Release the inputs of a melt stuck pending after its payment failed
+    ReleaseFailedMelt(subcommands::ReleaseFailedMeltCommand),
 }
 
 #[tokio::main]
@@ -181,6 +203,10 @@ async fn main() -> Result<()> {
             println!("motd: {}", info.motd.unwrap_or("None".to_string()));
             println!("icon_url: {}", info.icon_url.unwrap_or("None".to_string()));
             println!("tos_url: {}", info.tos_url.unwrap_or("None".to_string()));
+            match info.maintenance_retry_after {
+                Some(retry_after) => println!("maintenance: on (retry after {retry_after}s)"),
+                None => println!("maintenance: off"),
+            }
 
             for url in info.urls {
                 println!("mint_url: {url}");
@@ -240,6 +266,40 @@ async fn main() -> Result<()> {
         Commands::RotateNextKeyset(sub_command_args) => {
             subcommands::rotate_next_keyset(&mut client, &sub_command_args).await?;
         }
+        Commands::GetFakeWalletFailureInjection => {
+            subcommands::get_fake_wallet_failure_injection(&mut client).await?;
+        }
+        Commands::UpdateFakeWalletFailureInjection(sub_command_args) => {
+            subcommands::update_fake_wallet_failure_injection(&mut client, &sub_command_args)
+                .await?;
+        }
+        Commands::ScheduleMotd(sub_command_args) => {
+            subcommands::schedule_motd(&mut client, &sub_command_args).await?;
+        }
+        Commands::GetMotdSchedule => {
+            subcommands::get_motd_schedule(&mut client).await?;
+        }
+        Commands::ClearMotdSchedule => {
+            subcommands::clear_motd_schedule(&mut client).await?;
+        }
+        Commands::ArchiveSpentProofs(sub_command_args) => {
+            subcommands::archive_spent_proofs(&mut client, &sub_command_args).await?;
+        }
+        Commands::SetMaintenanceMode(sub_command_args) => {
+            subcommands::set_maintenance_mode(&mut client, &sub_command_args).await?;
+        }
+        Commands::ExportLedger(sub_command_args) => {
+            subcommands::export_ledger(&mut client, &sub_command_args).await?;
+        }
+        Commands::GetFeeTotals => {
+            subcommands::get_fee_totals(&mut client).await?;
+        }
+        Commands::RecordFeeSweep(sub_command_args) => {
+            subcommands::record_fee_sweep(&mut client, &sub_command_args).await?;
+        }
+        Commands::ReleaseFailedMelt(sub_command_args) => {
+            subcommands::release_failed_melt(&mut client, &sub_command_args).await?;
+        }
     }
 
     Ok(())
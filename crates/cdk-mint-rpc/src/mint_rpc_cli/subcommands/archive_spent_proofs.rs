@@ -0,0 +1,37 @@
+use anyhow::Result;
+use clap::Args;
+use tonic::Request;
+
+use crate::{ArchiveSpentProofsRequest, InterceptedCdkMintClient};
+
+/// Command to archive spent proofs
+///
+/// Spent proofs older than the retention period are moved out of the proof table
+/// into a compact archive that is still checked for double spends.
+#[derive(Args, Debug)]
+pub struct ArchiveSpentProofsCommand {
+    /// Number of days spent proofs are kept in full before being archived
+    #[arg(short, long)]
+    #[arg(default_value_t = 90)]
+    retention_days: u64,
+}
+
+/// Executes the archive_spent_proofs command against the mint server
+///
+/// # Arguments
+/// * `client` - The RPC client used to communicate with the mint
+/// * `sub_command_args` - The retention period for spent proofs
+pub async fn archive_spent_proofs(
+    client: &mut InterceptedCdkMintClient,
+    sub_command_args: &ArchiveSpentProofsCommand,
+) -> Result<()> {
+    let response = client
+        .archive_spent_proofs(Request::new(ArchiveSpentProofsRequest {
+            retention_days: sub_command_args.retention_days,
+        }))
+        .await?;
+
+    println!("Archived {} spent proofs", response.into_inner().archived);
+
+    Ok(())
+}
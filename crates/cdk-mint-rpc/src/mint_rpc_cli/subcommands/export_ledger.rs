@@ -0,0 +1,49 @@
+use anyhow::Result;
+use cdk_common::util::unix_time;
+use clap::Args;
+use tonic::Request;
+
+use crate::{ExportLedgerRequest, InterceptedCdkMintClient};
+
+/// Command to export the mint accounting ledger
+///
+/// Mint quote payments, melt payments and collected fees are exported as
+/// double-entry postings in CSV or beancount format.
+#[derive(Args, Debug)]
+pub struct ExportLedgerCommand {
+    /// Output format, either csv or beancount
+    #[arg(long, default_value = "csv")]
+    format: String,
+    /// Unix time of the first entry to export
+    #[arg(long, default_value_t = 0)]
+    from: u64,
+    /// Unix time entries must be before, defaults to now
+    #[arg(long)]
+    to: Option<u64>,
+    /// Unit of fees recorded before operations stored their unit
+    #[arg(long)]
+    fee_unit: Option<String>,
+}
+
+/// Executes the export_ledger command against the mint server
+///
+/// # Arguments
+/// * `client` - The RPC client used to communicate with the mint
+/// * `sub_command_args` - The format and time range of the export
+pub async fn export_ledger(
+    client: &mut InterceptedCdkMintClient,
+    sub_command_args: &ExportLedgerCommand,
+) -> Result<()> {
+    let response = client
+        .export_ledger(Request::new(ExportLedgerRequest {
+            from: sub_command_args.from,
+            to: sub_command_args.to.unwrap_or_else(unix_time),
+            format: sub_command_args.format.clone(),
+            fee_unit: sub_command_args.fee_unit.clone(),
+        }))
+        .await?;
+
+    print!("{}", response.into_inner().ledger);
+
+    Ok(())
+}
@@ -0,0 +1,73 @@
+use anyhow::Result;
+use clap::Args;
+use tonic::Request;
+
+use crate::{
+    GetFakeWalletFailureInjectionRequest, InterceptedCdkMintClient,
+    UpdateFakeWalletFailureInjectionRequest,
+};
+
+/// Command to update the failure injection settings of the fake wallet
+///
+/// Only available when the mint runs the fake wallet backend. Settings that are
+/// not provided keep their current value.
+#[derive(Args, Debug)]
+pub struct UpdateFakeWalletFailureInjectionCommand {
+    /// Percentage (0-100) of melts that should fail
+    #[arg(long)]
+    melt_failure_percent: Option<u32>,
+    /// Additional delay (in seconds) before incoming payments are reported as paid
+    #[arg(long)]
+    paid_notification_delay: Option<u64>,
+    /// Number of partial payments incoming bolt12 payments are split into
+    #[arg(long)]
+    bolt12_partial_payments: Option<u32>,
+}
+
+/// Executes the update_fake_wallet_failure_injection command against the mint server
+///
+/// # Arguments
+/// * `client` - The RPC client used to communicate with the mint
+/// * `sub_command_args` - The failure injection settings to change
+pub async fn update_fake_wallet_failure_injection(
+    client: &mut InterceptedCdkMintClient,
+    sub_command_args: &UpdateFakeWalletFailureInjectionCommand,
+) -> Result<()> {
+    let _response = client
+        .update_fake_wallet_failure_injection(Request::new(
+            UpdateFakeWalletFailureInjectionRequest {
+                melt_failure_percent: sub_command_args.melt_failure_percent,
+                paid_notification_delay: sub_command_args.paid_notification_delay,
+                bolt12_partial_payments: sub_command_args.bolt12_partial_payments,
+            },
+        ))
+        .await?;
+
+    Ok(())
+}
+
+/// Executes the get_fake_wallet_failure_injection command against the mint server
+///
+/// # Arguments
+/// * `client` - The RPC client used to communicate with the mint
+pub async fn get_fake_wallet_failure_injection(
+    client: &mut InterceptedCdkMintClient,
+) -> Result<()> {
+    let response = client
+        .get_fake_wallet_failure_injection(Request::new(GetFakeWalletFailureInjectionRequest {}))
+        .await?
+        .into_inner();
+
+    println!("Fake Wallet Failure Injection:");
+    println!("  Melt failure percent: {}%", response.melt_failure_percent);
+    println!(
+        "  Paid notification delay: {} seconds",
+        response.paid_notification_delay
+    );
+    println!(
+        "  Bolt12 partial payments: {}",
+        response.bolt12_partial_payments
+    );
+
+    Ok(())
+}
@@ -0,0 +1,66 @@
+use anyhow::Result;
+use clap::Args;
+use tonic::Request;
+
+use crate::{GetFeeTotalsRequest, InterceptedCdkMintClient, RecordFeeSweepRequest};
+
+/// Command to record a sweep of fee income into a designated balance
+///
+/// All input fees and Lightning fee surplus of the unit that have not been
+/// swept yet are recorded as moved into the destination. Only the ledger is
+/// updated, moving the funds is up to the operator.
+#[derive(Args, Debug)]
+pub struct RecordFeeSweepCommand {
+    /// Balance the fee income is moved into, e.g. a keyset id or account name
+    destination: String,
+    /// Currency unit of the fee income to sweep
+    #[arg(short, long, default_value = "sat")]
+    unit: String,
+}
+
+/// Executes the get_fee_totals command against the mint server
+///
+/// # Arguments
+/// * `client` - The RPC client used to communicate with the mint
+pub async fn get_fee_totals(client: &mut InterceptedCdkMintClient) -> Result<()> {
+    let response = client
+        .get_fee_totals(Request::new(GetFeeTotalsRequest {}))
+        .await?
+        .into_inner();
+
+    for totals in response.totals {
+        println!("{}:", totals.unit);
+        println!("  input fees:        {}", totals.input_fees);
+        println!("  lightning surplus: {}", totals.lightning_surplus);
+        println!("  swept:             {}", totals.swept);
+        println!("  available:         {}", totals.available);
+    }
+
+    Ok(())
+}
+
+/// Executes the record_fee_sweep command against the mint server
+///
+/// # Arguments
+/// * `client` - The RPC client used to communicate with the mint
+/// * `sub_command_args` - The unit and destination of the sweep
+pub async fn record_fee_sweep(
+    client: &mut InterceptedCdkMintClient,
+    sub_command_args: &RecordFeeSweepCommand,
+) -> Result<()> {
+    let response = client
+        .record_fee_sweep(Request::new(RecordFeeSweepRequest {
+            unit: sub_command_args.unit.clone(),
+            destination: sub_command_args.destination.clone(),
+        }))
+        .await?;
+
+    println!(
+        "Recorded sweep of {} {} into {}",
+        response.into_inner().swept,
+        sub_command_args.unit,
+        sub_command_args.destination
+    );
+
+    Ok(())
+}
@@ -1,7 +1,21 @@
 //! Subcommands for the mint RPC CLI
 
+/// Module for archiving old spent proofs
+mod archive_spent_proofs;
+/// Module for exporting the accounting ledger
+mod export_ledger;
+/// Module for controlling fake wallet failure injection
+mod fake_wallet;
+/// Module for fee income totals and sweeps
+mod fees;
+/// Module for releasing the inputs of failed melts
+mod release_failed_melt;
 /// Module for rotating to the next keyset
 mod rotate_next_keyset;
+/// Module for scheduling the mint's message of the day
+mod schedule_motd;
+/// Module for toggling maintenance mode
+mod set_maintenance_mode;
 /// Module for updating mint contact information
 mod update_contact;
 /// Module for updating the mint's icon URL
@@ -27,7 +41,19 @@ mod update_ttl;
 /// Module for managing mint URLs
 mod update_urls;
 
+pub use archive_spent_proofs::{archive_spent_proofs, ArchiveSpentProofsCommand};
+pub use export_ledger::{export_ledger, ExportLedgerCommand};
+pub use fake_wallet::{
+    get_fake_wallet_failure_injection, update_fake_wallet_failure_injection,
+    UpdateFakeWalletFailureInjectionCommand,
+};
+pub use fees::{get_fee_totals, record_fee_sweep, RecordFeeSweepCommand};
+pub use release_failed_melt::{release_failed_melt, ReleaseFailedMeltCommand};
 pub use rotate_next_keyset::{rotate_next_keyset, RotateNextKeysetCommand};
+pub use schedule_motd::{
+    clear_motd_schedule, get_motd_schedule, schedule_motd, ScheduleMotdCommand,
+};
+pub use set_maintenance_mode::{set_maintenance_mode, SetMaintenanceModeCommand};
 pub use update_contact::{add_contact, remove_contact, AddContactCommand, RemoveContactCommand};
 pub use update_icon_url::{update_icon_url, UpdateIconUrlCommand};
 pub use update_long_description::{update_long_description, UpdateLongDescriptionCommand};
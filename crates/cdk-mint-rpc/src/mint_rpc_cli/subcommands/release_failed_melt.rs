@@ -0,0 +1,43 @@
+use anyhow::Result;
+use clap::Args;
+use tonic::Request;
+
+use crate::{InterceptedCdkMintClient, ReleaseFailedMeltRequest};
+
+/// Command to release the inputs of a melt stuck pending after its payment failed
+///
+/// Only use this once the failure of the payment was confirmed with the
+/// Lightning node, the released proofs can be spent again.
+#[derive(Args, Debug)]
+pub struct ReleaseFailedMeltCommand {
+    /// Id of the stuck melt quote
+    quote_id: String,
+    /// Why the melt is released, recorded in the audit log
+    #[arg(short, long)]
+    reason: String,
+}
+
+/// Executes the release_failed_melt command against the mint server
+///
+/// # Arguments
+/// * `client` - The RPC client used to communicate with the mint
+/// * `sub_command_args` - The quote to release and the reason
+pub async fn release_failed_melt(
+    client: &mut InterceptedCdkMintClient,
+    sub_command_args: &ReleaseFailedMeltCommand,
+) -> Result<()> {
+    let response = client
+        .release_failed_melt(Request::new(ReleaseFailedMeltRequest {
+            quote_id: sub_command_args.quote_id.clone(),
+            reason: sub_command_args.reason.clone(),
+        }))
+        .await?
+        .into_inner();
+
+    println!(
+        "Released {} proofs worth {} of melt quote {}",
+        response.released_proofs, response.amount, sub_command_args.quote_id
+    );
+
+    Ok(())
+}
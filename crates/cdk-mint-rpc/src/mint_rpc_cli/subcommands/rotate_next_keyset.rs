@@ -26,6 +26,9 @@ pub struct RotateNextKeysetCommand {
     /// Final expiry unix timestamp for the keyset
     #[arg(long)]
     final_expiry: Option<u64>,
+    /// Pre-generate the keyset and activate it at this unix timestamp
+    #[arg(long)]
+    activate_at: Option<u64>,
 }
 
 /// Executes the rotate_next_keyset command against the mint server
@@ -56,18 +59,29 @@ pub async fn rotate_next_keyset(
             input_fee_ppk: sub_command_args.input_fee_ppk,
             use_keyset_v2: sub_command_args.use_keyset_v2,
             final_expiry: sub_command_args.final_expiry,
+            activate_at: sub_command_args.activate_at,
         }))
         .await?;
 
     let response = response.into_inner();
 
-    println!(
-        "Rotated to new keyset {} for unit {} with amounts {} and fee of {}",
-        response.id,
-        response.unit,
-        serde_json::to_string(&response.amounts)?,
-        response.input_fee_ppk
-    );
+    match sub_command_args.activate_at {
+        Some(activate_at) => println!(
+            "Scheduled keyset {} for unit {} with amounts {} and fee of {} to activate at {}",
+            response.id,
+            response.unit,
+            serde_json::to_string(&response.amounts)?,
+            response.input_fee_ppk,
+            activate_at
+        ),
+        None => println!(
+            "Rotated to new keyset {} for unit {} with amounts {} and fee of {}",
+            response.id,
+            response.unit,
+            serde_json::to_string(&response.amounts)?,
+            response.input_fee_ppk
+        ),
+    }
 
     Ok(())
 }
@@ -0,0 +1,77 @@
+use anyhow::Result;
+use clap::Args;
+use tonic::Request;
+
+use crate::{
+    ClearMotdScheduleRequest, GetMotdScheduleRequest, InterceptedCdkMintClient, ScheduleMotdRequest,
+};
+
+/// Command to schedule a message of the day
+///
+/// The scheduled message replaces the mint's message of the day while the
+/// current time is within the given window. Times are unix timestamps.
+#[derive(Args, Debug)]
+pub struct ScheduleMotdCommand {
+    /// The message of the day text
+    motd: String,
+    /// Unix timestamp from which the message is shown
+    #[arg(long)]
+    start: Option<u64>,
+    /// Unix timestamp until which the message is shown
+    #[arg(long)]
+    end: Option<u64>,
+}
+
+/// Executes the schedule_motd command against the mint server
+///
+/// # Arguments
+/// * `client` - The RPC client used to communicate with the mint
+/// * `sub_command_args` - The message and its time window
+pub async fn schedule_motd(
+    client: &mut InterceptedCdkMintClient,
+    sub_command_args: &ScheduleMotdCommand,
+) -> Result<()> {
+    let _response = client
+        .schedule_motd(Request::new(ScheduleMotdRequest {
+            motd: sub_command_args.motd.clone(),
+            start: sub_command_args.start,
+            end: sub_command_args.end,
+        }))
+        .await?;
+
+    Ok(())
+}
+
+/// Executes the get_motd_schedule command against the mint server
+///
+/// # Arguments
+/// * `client` - The RPC client used to communicate with the mint
+pub async fn get_motd_schedule(client: &mut InterceptedCdkMintClient) -> Result<()> {
+    let response = client
+        .get_motd_schedule(Request::new(GetMotdScheduleRequest {}))
+        .await?
+        .into_inner();
+
+    println!("Scheduled MOTDs:");
+    for scheduled in response.schedule {
+        let start = scheduled
+            .start
+            .map_or("-".to_string(), |start| start.to_string());
+        let end = scheduled.end.map_or("-".to_string(), |end| end.to_string());
+        println!("  [{start} .. {end}] {}", scheduled.motd);
+    }
+
+    Ok(())
+}
+
+/// Executes the clear_motd_schedule command against the mint server
+///
+/// # Arguments
+/// * `client` - The RPC client used to communicate with the mint
+pub async fn clear_motd_schedule(client: &mut InterceptedCdkMintClient) -> Result<()> {
+    let _response = client
+        .clear_motd_schedule(Request::new(ClearMotdScheduleRequest {}))
+        .await?;
+
+    Ok(())
+}
@@ -0,0 +1,38 @@
+use anyhow::Result;
+use clap::Args;
+use tonic::Request;
+
+use crate::{InterceptedCdkMintClient, SetMaintenanceModeRequest};
+
+/// Command to enter or leave maintenance mode
+///
+/// While in maintenance mode the mint rejects quotes, mints, swaps and melts
+/// but keeps serving info, keys and state checks.
+#[derive(Args, Debug)]
+pub struct SetMaintenanceModeCommand {
+    /// Whether the mint should be in maintenance mode
+    #[arg(action = clap::ArgAction::Set)]
+    enabled: bool,
+    /// Seconds wallets are told to wait before retrying
+    #[arg(short, long)]
+    retry_after: Option<u64>,
+}
+
+/// Executes the set_maintenance_mode command against the mint server
+///
+/// # Arguments
+/// * `client` - The RPC client used to communicate with the mint
+/// * `sub_command_args` - Whether to enable maintenance mode and the retry after hint
+pub async fn set_maintenance_mode(
+    client: &mut InterceptedCdkMintClient,
+    sub_command_args: &SetMaintenanceModeCommand,
+) -> Result<()> {
+    client
+        .set_maintenance_mode(Request::new(SetMaintenanceModeRequest {
+            enabled: sub_command_args.enabled,
+            retry_after: sub_command_args.retry_after,
+        }))
+        .await?;
+
+    Ok(())
+}
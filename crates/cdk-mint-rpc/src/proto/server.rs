@@ -20,11 +20,17 @@ use tonic::{Request, Response, Status};
 
 use crate::cdk_mint_server::{CdkMint, CdkMintServer};
 use crate::{
-    ContactInfo, GetInfoRequest, GetInfoResponse, GetQuoteTtlRequest, GetQuoteTtlResponse,
-    RotateNextKeysetRequest, RotateNextKeysetResponse, UpdateContactRequest,
-    UpdateDescriptionRequest, UpdateIconUrlRequest, UpdateMotdRequest, UpdateNameRequest,
-    UpdateNut04QuoteRequest, UpdateNut04Request, UpdateNut05Request, UpdateQuoteTtlRequest,
-    UpdateResponse, UpdateTosUrlRequest, UpdateUrlRequest,
+    ArchiveSpentProofsRequest, ArchiveSpentProofsResponse, ClearMotdScheduleRequest, ContactInfo,
+    ExportLedgerRequest, ExportLedgerResponse, FakeWalletFailureInjection, FeeTotals,
+    GetFakeWalletFailureInjectionRequest, GetFeeTotalsRequest, GetFeeTotalsResponse,
+    GetInfoRequest, GetInfoResponse, GetMotdScheduleRequest, GetMotdScheduleResponse,
+    GetQuoteTtlRequest, GetQuoteTtlResponse, RecordFeeSweepRequest, RecordFeeSweepResponse,
+    ReleaseFailedMeltRequest, ReleaseFailedMeltResponse, RotateNextKeysetRequest,
+    RotateNextKeysetResponse, ScheduleMotdRequest, ScheduledMotd, SetMaintenanceModeRequest,
+    UpdateContactRequest, UpdateDescriptionRequest, UpdateFakeWalletFailureInjectionRequest,
+    UpdateIconUrlRequest, UpdateMotdRequest, UpdateNameRequest, UpdateNut04QuoteRequest,
+    UpdateNut04Request, UpdateNut05Request, UpdateQuoteTtlRequest, UpdateResponse,
+    UpdateTosUrlRequest, UpdateUrlRequest,
 };
 
 /// Error
@@ -49,6 +55,8 @@ pub struct MintRPCServer {
     mint: Arc<Mint>,
     shutdown: Arc<Notify>,
     handle: Option<Arc<JoinHandle<Result<(), Error>>>>,
+    #[cfg(feature = "fakewallet")]
+    fake_wallet_failure_injection: Option<cdk_fake_wallet::FailureInjection>,
 }
 
 impl MintRPCServer {
@@ -64,9 +72,28 @@ impl MintRPCServer {
             mint,
             shutdown: Arc::new(Notify::new()),
             handle: None,
+            #[cfg(feature = "fakewallet")]
+            fake_wallet_failure_injection: None,
         })
     }
 
+    /// Expose failure injection controls of the mint's fake wallets
+    #[cfg(feature = "fakewallet")]
+    pub fn with_fake_wallet_failure_injection(
+        mut self,
+        failure_injection: cdk_fake_wallet::FailureInjection,
+    ) -> Self {
+        self.fake_wallet_failure_injection = Some(failure_injection);
+        self
+    }
+
+    #[cfg(feature = "fakewallet")]
+    fn fake_wallet_failure_injection(&self) -> Result<&cdk_fake_wallet::FailureInjection, Status> {
+        self.fake_wallet_failure_injection
+            .as_ref()
+            .ok_or_else(|| Status::unimplemented("Fake wallet is not enabled".to_string()))
+    }
+
     /// Starts the RPC server
     ///
     /// # Arguments
@@ -248,6 +275,7 @@ impl CdkMint for MintRPCServer {
             urls: info.urls.unwrap_or_default(),
             total_issued: total_issued.into(),
             total_redeemed: total_redeemed.into(),
+            maintenance_retry_after: self.mint.maintenance_mode(),
         });
 
         Ok(response)
@@ -804,17 +832,34 @@ impl CdkMint for MintRPCServer {
 
         let amounts = request.amounts;
 
-        let keyset_info = self
-            .mint
-            .rotate_keyset(
-                unit,
-                amounts,
-                request.input_fee_ppk.unwrap_or(0),
-                request.use_keyset_v2.unwrap_or(true),
-                request.final_expiry,
-            )
-            .await
-            .map_err(|_| Status::invalid_argument("Could not rotate keyset".to_string()))?;
+        let input_fee_ppk = request.input_fee_ppk.unwrap_or(0);
+        let use_keyset_v2 = request.use_keyset_v2.unwrap_or(true);
+
+        let keyset_info = match request.activate_at {
+            Some(activate_at) => self
+                .mint
+                .schedule_keyset_rotation(
+                    unit,
+                    amounts,
+                    input_fee_ppk,
+                    use_keyset_v2,
+                    request.final_expiry,
+                    activate_at,
+                )
+                .await
+                .map_err(|err| Status::invalid_argument(err.to_string()))?,
+            None => self
+                .mint
+                .rotate_keyset(
+                    unit,
+                    amounts,
+                    input_fee_ppk,
+                    use_keyset_v2,
+                    request.final_expiry,
+                )
+                .await
+                .map_err(|_| Status::invalid_argument("Could not rotate keyset".to_string()))?,
+        };
 
         Ok(Response::new(RotateNextKeysetResponse {
             id: keyset_info.id.to_string(),
@@ -823,6 +868,263 @@ impl CdkMint for MintRPCServer {
             input_fee_ppk: keyset_info.input_fee_ppk,
         }))
     }
+
+    /// Gets the failure injection settings of the fake wallet
+    async fn get_fake_wallet_failure_injection(
+        &self,
+        _request: Request<GetFakeWalletFailureInjectionRequest>,
+    ) -> Result<Response<FakeWalletFailureInjection>, Status> {
+        #[cfg(feature = "fakewallet")]
+        {
+            let settings = self.fake_wallet_failure_injection()?.settings().await;
+
+            Ok(Response::new(FakeWalletFailureInjection {
+                melt_failure_percent: settings.melt_failure_percent.into(),
+                paid_notification_delay: settings.paid_notification_delay,
+                bolt12_partial_payments: settings.bolt12_partial_payments,
+            }))
+        }
+
+        #[cfg(not(feature = "fakewallet"))]
+        Err(Status::unimplemented(
+            "Fake wallet is not enabled".to_string(),
+        ))
+    }
+
+    /// Updates the failure injection settings of the fake wallet
+    async fn update_fake_wallet_failure_injection(
+        &self,
+        request: Request<UpdateFakeWalletFailureInjectionRequest>,
+    ) -> Result<Response<UpdateResponse>, Status> {
+        #[cfg(feature = "fakewallet")]
+        {
+            let failure_injection = self.fake_wallet_failure_injection()?;
+            let request = request.into_inner();
+            let mut settings = failure_injection.settings().await;
+
+            if let Some(melt_failure_percent) = request.melt_failure_percent {
+                settings.melt_failure_percent =
+                    u8::try_from(melt_failure_percent).map_err(|_| {
+                        Status::invalid_argument("Invalid melt failure percent".to_string())
+                    })?;
+            }
+            if let Some(paid_notification_delay) = request.paid_notification_delay {
+                settings.paid_notification_delay = paid_notification_delay;
+            }
+            if let Some(bolt12_partial_payments) = request.bolt12_partial_payments {
+                settings.bolt12_partial_payments = bolt12_partial_payments;
+            }
+
+            failure_injection
+                .update(settings)
+                .await
+                .map_err(|err| Status::invalid_argument(err.to_string()))?;
+
+            Ok(Response::new(UpdateResponse {}))
+        }
+
+        #[cfg(not(feature = "fakewallet"))]
+        {
+            let _ = request;
+            Err(Status::unimplemented(
+                "Fake wallet is not enabled".to_string(),
+            ))
+        }
+    }
+
+    /// Schedules a message of the day for a time window
+    async fn schedule_motd(
+        &self,
+        request: Request<ScheduleMotdRequest>,
+    ) -> Result<Response<UpdateResponse>, Status> {
+        let request = request.into_inner();
+        let scheduled =
+            cdk_common::common::ScheduledMotd::new(request.motd, request.start, request.end)
+                .map_err(|err| Status::invalid_argument(err.to_string()))?;
+
+        self.mint
+            .schedule_motd(scheduled)
+            .await
+            .map_err(|err| Status::internal(err.to_string()))?;
+
+        Ok(Response::new(UpdateResponse {}))
+    }
+
+    /// Gets the scheduled messages of the day
+    async fn get_motd_schedule(
+        &self,
+        _request: Request<GetMotdScheduleRequest>,
+    ) -> Result<Response<GetMotdScheduleResponse>, Status> {
+        let schedule = self
+            .mint
+            .motd_schedule()
+            .await
+            .map_err(|err| Status::internal(err.to_string()))?;
+
+        Ok(Response::new(GetMotdScheduleResponse {
+            schedule: schedule
+                .into_iter()
+                .map(|scheduled| ScheduledMotd {
+                    motd: scheduled.motd,
+                    start: scheduled.start,
+                    end: scheduled.end,
+                })
+                .collect(),
+        }))
+    }
+
+    /// Removes all scheduled messages of the day
+    async fn clear_motd_schedule(
+        &self,
+        _request: Request<ClearMotdScheduleRequest>,
+    ) -> Result<Response<UpdateResponse>, Status> {
+        self.mint
+            .set_motd_schedule(vec![])
+            .await
+            .map_err(|err| Status::internal(err.to_string()))?;
+
+        Ok(Response::new(UpdateResponse {}))
+    }
+
+    /// Archives spent proofs older than the requested retention period
+    async fn archive_spent_proofs(
+        &self,
+        request: Request<ArchiveSpentProofsRequest>,
+    ) -> Result<Response<ArchiveSpentProofsResponse>, Status> {
+        let retention_days = request.into_inner().retention_days;
+        let retention = retention_days
+            .checked_mul(24 * 60 * 60)
+            .map(Duration::from_secs)
+            .ok_or_else(|| Status::invalid_argument("Retention period is too long"))?;
+
+        let archived = self
+            .mint
+            .archive_spent_proofs(retention)
+            .await
+            .map_err(|err| Status::internal(err.to_string()))?;
+
+        Ok(Response::new(ArchiveSpentProofsResponse { archived }))
+    }
+
+    /// Enters or leaves maintenance mode
+    async fn set_maintenance_mode(
+        &self,
+        request: Request<SetMaintenanceModeRequest>,
+    ) -> Result<Response<UpdateResponse>, Status> {
+        let request = request.into_inner();
+
+        let retry_after = request.enabled.then(|| {
+            request
+                .retry_after
+                .unwrap_or(cdk::mint::DEFAULT_MAINTENANCE_RETRY_AFTER)
+        });
+        self.mint.set_maintenance_mode(retry_after);
+
+        Ok(Response::new(UpdateResponse {}))
+    }
+
+    /// Exports mint accounting entries over a time range
+    async fn export_ledger(
+        &self,
+        request: Request<ExportLedgerRequest>,
+    ) -> Result<Response<ExportLedgerResponse>, Status> {
+        let request = request.into_inner();
+
+        let format = cdk::mint::LedgerFormat::from_str(&request.format)
+            .map_err(|err| Status::invalid_argument(err.to_string()))?;
+        let fee_unit = match request.fee_unit {
+            Some(unit) => CurrencyUnit::from_str(&unit)
+                .map_err(|_| Status::invalid_argument("Invalid fee unit".to_string()))?,
+            None => CurrencyUnit::Sat,
+        };
+
+        let entries = self
+            .mint
+            .ledger_entries(request.from, request.to, &fee_unit)
+            .await
+            .map_err(|err| Status::internal(err.to_string()))?;
+
+        Ok(Response::new(ExportLedgerResponse {
+            ledger: cdk::mint::format_ledger(&entries, format),
+        }))
+    }
+
+    /// Returns the accumulated fee income per unit
+    async fn get_fee_totals(
+        &self,
+        _request: Request<GetFeeTotalsRequest>,
+    ) -> Result<Response<GetFeeTotalsResponse>, Status> {
+        let totals = self
+            .mint
+            .fee_totals()
+            .await
+            .map_err(|err| Status::internal(err.to_string()))?;
+
+        let mut totals: Vec<FeeTotals> = totals
+            .into_iter()
+            .map(|(unit, totals)| FeeTotals {
+                unit: unit.to_string(),
+                input_fees: totals.input_fees.into(),
+                lightning_surplus: totals.lightning_surplus.into(),
+                swept: totals.swept.into(),
+                available: totals.available().into(),
+            })
+            .collect();
+        totals.sort_by(|a, b| a.unit.cmp(&b.unit));
+
+        Ok(Response::new(GetFeeTotalsResponse { totals }))
+    }
+
+    /// Records unswept fee income of a unit as moved into a designated balance
+    async fn record_fee_sweep(
+        &self,
+        request: Request<RecordFeeSweepRequest>,
+    ) -> Result<Response<RecordFeeSweepResponse>, Status> {
+        let request = request.into_inner();
+        let unit = CurrencyUnit::from_str(&request.unit)
+            .map_err(|_| Status::invalid_argument("Invalid unit".to_string()))?;
+
+        if request.destination.is_empty() {
+            return Err(Status::invalid_argument("Destination is required"));
+        }
+
+        let swept = self
+            .mint
+            .record_fee_sweep(&unit, &request.destination)
+            .await
+            .map_err(|err| Status::internal(err.to_string()))?;
+
+        Ok(Response::new(RecordFeeSweepResponse {
+            swept: swept.into(),
+        }))
+    }
+
+    /// Releases the inputs of a melt that failed on the Lightning side but is stuck pending
+    async fn release_failed_melt(
+        &self,
+        request: Request<ReleaseFailedMeltRequest>,
+    ) -> Result<Response<ReleaseFailedMeltResponse>, Status> {
+        let request = request.into_inner();
+        let quote_id = request
+            .quote_id
+            .parse()
+            .map_err(|_| Status::invalid_argument("Invalid quote id".to_string()))?;
+
+        if request.reason.is_empty() {
+            return Err(Status::invalid_argument("Reason is required"));
+        }
+
+        let entry = self
+            .mint
+            .release_failed_melt(&quote_id, &request.reason)
+            .await
+            .map_err(|err| Status::internal(err.to_string()))?;
+
+        Ok(Response::new(ReleaseFailedMeltResponse {
+            amount: entry.amount.into(),
+            released_proofs: entry.released_ys.len() as u64,
+        }))
+    }
 }
 
 #[cfg(test)]
@@ -859,6 +1161,8 @@ mod tests {
             2,
             CurrencyUnit::Sat,
         );
+        #[cfg(feature = "fakewallet")]
+        let ln_fake_failure_injection = ln_fake.failure_injection();
 
         mint_builder
             .add_payment_processor(
@@ -892,6 +1196,8 @@ mod tests {
             mint: Arc::new(mint),
             shutdown: Arc::new(Notify::new()),
             handle: None,
+            #[cfg(feature = "fakewallet")]
+            fake_wallet_failure_injection: Some(ln_fake_failure_injection),
         }
     }
 
@@ -943,4 +1249,91 @@ mod tests {
 
         assert_eq!(response.into_inner().tos_url.unwrap(), tos);
     }
+
+    #[cfg(feature = "fakewallet")]
+    #[tokio::test]
+    async fn test_update_fake_wallet_failure_injection() {
+        let server = create_test_rpc_server().await;
+
+        server
+            .update_fake_wallet_failure_injection(Request::new(
+                UpdateFakeWalletFailureInjectionRequest {
+                    melt_failure_percent: Some(50),
+                    paid_notification_delay: None,
+                    bolt12_partial_payments: Some(2),
+                },
+            ))
+            .await
+            .unwrap();
+
+        let response = server
+            .get_fake_wallet_failure_injection(Request::new(
+                GetFakeWalletFailureInjectionRequest {},
+            ))
+            .await
+            .unwrap()
+            .into_inner();
+
+        assert_eq!(response.melt_failure_percent, 50);
+        assert_eq!(response.paid_notification_delay, 0);
+        assert_eq!(response.bolt12_partial_payments, 2);
+
+        let err = server
+            .update_fake_wallet_failure_injection(Request::new(
+                UpdateFakeWalletFailureInjectionRequest {
+                    melt_failure_percent: Some(150),
+                    paid_notification_delay: None,
+                    bolt12_partial_payments: None,
+                },
+            ))
+            .await
+            .unwrap_err();
+        assert_eq!(err.code(), tonic::Code::InvalidArgument);
+    }
+
+    #[tokio::test]
+    async fn test_schedule_motd() {
+        let server = create_test_rpc_server().await;
+
+        server
+            .schedule_motd(Request::new(ScheduleMotdRequest {
+                motd: "maintenance tonight".to_string(),
+                start: None,
+                end: Some(u64::MAX),
+            }))
+            .await
+            .unwrap();
+
+        let schedule = server
+            .get_motd_schedule(Request::new(GetMotdScheduleRequest {}))
+            .await
+            .unwrap()
+            .into_inner()
+            .schedule;
+        assert_eq!(schedule.len(), 1);
+        assert_eq!(schedule[0].motd, "maintenance tonight");
+
+        let err = server
+            .schedule_motd(Request::new(ScheduleMotdRequest {
+                motd: "invalid".to_string(),
+                start: Some(20),
+                end: Some(10),
+            }))
+            .await
+            .unwrap_err();
+        assert_eq!(err.code(), tonic::Code::InvalidArgument);
+
+        server
+            .clear_motd_schedule(Request::new(ClearMotdScheduleRequest {}))
+            .await
+            .unwrap();
+
+        let schedule = server
+            .get_motd_schedule(Request::new(GetMotdScheduleRequest {}))
+            .await
+            .unwrap()
+            .into_inner()
+            .schedule;
+        assert!(schedule.is_empty());
+    }
 }
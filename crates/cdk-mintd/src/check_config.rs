@@ -0,0 +1,308 @@
+//! Configuration check for `cdk-mintd check-config`
+//!
+//! Loads the configuration the same way the mint does on startup and then verifies
+//! everything the mint needs to serve requests: listen ports, key derivation, the
+//! database and the payment backends. No server is started and no keysets are created,
+//! so deployment pipelines can gate on the report before rolling out a mint.
+
+use std::fmt;
+use std::net::{SocketAddr, TcpListener};
+use std::path::Path;
+use std::str::FromStr;
+
+use anyhow::{bail, Result};
+use bip39::Mnemonic;
+use bitcoin::bip32::Xpriv;
+use cdk::mint::MintBuilder;
+use serde::Serialize;
+
+use crate::cli::CLIArgs;
+use crate::{config, BackendServices};
+
+/// Outcome of a single check
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CheckStatus {
+    /// Check succeeded
+    Pass,
+    /// Check failed, the mint would not start
+    Fail,
+    /// Check was not run because an earlier check failed
+    Skipped,
+}
+
+impl fmt::Display for CheckStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Pass => write!(f, "PASS"),
+            Self::Fail => write!(f, "FAIL"),
+            Self::Skipped => write!(f, "SKIP"),
+        }
+    }
+}
+
+/// Result of a single check
+#[derive(Debug, Clone, Serialize)]
+pub struct ConfigCheck {
+    /// What was checked
+    pub name: String,
+    /// Outcome of the check
+    pub status: CheckStatus,
+    /// Details or the error that made the check fail
+    pub detail: String,
+}
+
+/// Report produced by [`check_config`]
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ConfigReport {
+    /// Checks in the order they were run
+    pub checks: Vec<ConfigCheck>,
+}
+
+impl ConfigReport {
+    /// Whether every check passed
+    pub fn is_ok(&self) -> bool {
+        self.checks
+            .iter()
+            .all(|check| check.status == CheckStatus::Pass)
+    }
+
+    fn push(&mut self, name: impl Into<String>, status: CheckStatus, detail: String) {
+        self.checks.push(ConfigCheck {
+            name: name.into(),
+            status,
+            detail,
+        });
+    }
+
+    fn record(&mut self, name: impl Into<String>, result: Result<String>) {
+        match result {
+            Ok(detail) => self.push(name, CheckStatus::Pass, detail),
+            Err(err) => self.push(name, CheckStatus::Fail, format!("{err:#}")),
+        }
+    }
+
+    fn skip(&mut self, name: impl Into<String>, reason: &str) {
+        self.push(name, CheckStatus::Skipped, reason.to_string());
+    }
+}
+
+impl fmt::Display for ConfigReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for check in &self.checks {
+            writeln!(f, "[{}] {}: {}", check.status, check.name, check.detail)?;
+        }
+
+        let failed = self
+            .checks
+            .iter()
+            .filter(|check| check.status == CheckStatus::Fail)
+            .count();
+        if failed == 0 {
+            write!(f, "Configuration OK")
+        } else {
+            write!(f, "Configuration invalid: {failed} check(s) failed")
+        }
+    }
+}
+
+/// Run all configuration checks for the given arguments
+pub async fn check_config(
+    work_dir: &Path,
+    args: &CLIArgs,
+    db_password: Option<String>,
+) -> ConfigReport {
+    let mut report = ConfigReport::default();
+
+    let settings = match crate::load_settings_from_args(work_dir, args) {
+        Ok(settings) => {
+            report.record("configuration", Ok("loaded and validated".to_string()));
+            settings
+        }
+        Err(err) => {
+            report.record("configuration", Err(err));
+            for name in [
+                "mint port",
+                "key derivation",
+                "database",
+                "payment backends",
+            ] {
+                report.skip(name, "configuration could not be loaded");
+            }
+            return report;
+        }
+    };
+
+    for (name, address) in listen_addresses(&settings) {
+        report.record(format!("{name} port"), check_port(&address));
+    }
+
+    report.record("key derivation", check_key_derivation(&settings).await);
+
+    let (localstore, kv) = match crate::setup_database(&settings, work_dir, db_password).await {
+        Ok((localstore, _keystore, kv)) => {
+            report.record(
+                "database",
+                Ok(format!(
+                    "connected to {:?} database",
+                    settings.database.engine
+                )),
+            );
+            (localstore, kv)
+        }
+        Err(err) => {
+            report.record("database", Err(err));
+            report.skip("payment backends", "database is not reachable");
+            return report;
+        }
+    };
+
+    let mut services = BackendServices::default();
+    let backends = crate::configure_mint_builder(
+        &settings,
+        MintBuilder::new(localstore),
+        None,
+        work_dir,
+        Some(kv),
+        &mut services,
+    )
+    .await
+    .map(|mint_builder| {
+        let methods = crate::extract_supported_payment_methods(&mint_builder.current_mint_info());
+        format!("reachable, payment methods: {}", methods.join(", "))
+    });
+    report.record("payment backends", backends);
+
+    report
+}
+
+/// Addresses the mint and its optional services listen on
+fn listen_addresses(settings: &config::Settings) -> Vec<(&'static str, String)> {
+    let mut addresses = vec![(
+        "mint",
+        format!(
+            "{}:{}",
+            settings.info.listen_host, settings.info.listen_port
+        ),
+    )];
+
+    #[cfg(feature = "management-rpc")]
+    if let Some(rpc_settings) = settings
+        .mint_management_rpc
+        .as_ref()
+        .filter(|rpc_settings| rpc_settings.enabled)
+    {
+        let address = rpc_settings.address.as_deref().unwrap_or("127.0.0.1");
+        let port = rpc_settings.port.unwrap_or(8086);
+        addresses.push(("management RPC", format!("{address}:{port}")));
+    }
+
+    #[cfg(feature = "prometheus")]
+    if let Some(prometheus_settings) = settings
+        .prometheus
+        .as_ref()
+        .filter(|prometheus_settings| prometheus_settings.enabled)
+    {
+        let address = prometheus_settings
+            .address
+            .as_deref()
+            .unwrap_or("127.0.0.1");
+        let port = prometheus_settings.port.unwrap_or(9000);
+        addresses.push(("prometheus", format!("{address}:{port}")));
+    }
+
+    addresses
+}
+
+/// Check that `address` can be bound, the listener is released right away
+fn check_port(address: &str) -> Result<String> {
+    let address = SocketAddr::from_str(address)?;
+    TcpListener::bind(address).map_err(|err| anyhow::anyhow!("cannot bind {address}: {err}"))?;
+
+    Ok(format!("{address} is available"))
+}
+
+/// Check that the signing keys can be derived or the remote signatory is reachable
+async fn check_key_derivation(settings: &config::Settings) -> Result<String> {
+    if let Some(signatory) = settings.enabled_signatory() {
+        cdk_signatory::SignatoryRpcClient::new(
+            &signatory.address,
+            signatory.port,
+            signatory.tls_dir.clone(),
+        )
+        .await?;
+
+        return Ok(format!(
+            "connected to remote signatory at {}:{}",
+            signatory.address, signatory.port
+        ));
+    }
+
+    let (seed, source) = if let Some(seed) = settings.info.seed.clone().filter(|s| !s.is_empty()) {
+        (seed.into_bytes(), "seed")
+    } else if let Some(mnemonic) = settings.info.mnemonic.as_deref() {
+        (
+            Mnemonic::from_str(mnemonic)?
+                .to_seed_normalized("")
+                .to_vec(),
+            "mnemonic",
+        )
+    } else {
+        bail!("No seed nor remote signatory set");
+    };
+
+    Xpriv::new_master(bitcoin::Network::Bitcoin, &seed)?;
+
+    Ok(format!("derived master key from {source}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TEST_MNEMONIC: &str =
+        "abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon about";
+
+    #[test]
+    fn test_check_port_reports_address_in_use() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let address = listener.local_addr().unwrap().to_string();
+
+        let err = check_port(&address).unwrap_err();
+        assert!(err.to_string().contains("cannot bind"));
+
+        drop(listener);
+        assert!(check_port(&address).is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_check_key_derivation() {
+        let mut settings = config::Settings::default();
+        settings.info.mnemonic = Some(TEST_MNEMONIC.to_string());
+        assert_eq!(
+            check_key_derivation(&settings).await.unwrap(),
+            "derived master key from mnemonic"
+        );
+
+        settings.info.mnemonic = Some("not a mnemonic".to_string());
+        assert!(check_key_derivation(&settings).await.is_err());
+    }
+
+    #[test]
+    fn test_report_fails_on_any_failed_check() {
+        let mut report = ConfigReport::default();
+        report.record("configuration", Ok("loaded and validated".to_string()));
+        assert!(report.is_ok());
+
+        report.record("database", Err(anyhow::anyhow!("connection refused")));
+        report.skip("payment backends", "database is not reachable");
+        assert!(!report.is_ok());
+        assert_eq!(
+            report.to_string(),
+            "[PASS] configuration: loaded and validated\n\
+             [FAIL] database: connection refused\n\
+             [SKIP] payment backends: database is not reachable\n\
+             Configuration invalid: 1 check(s) failed"
+        );
+    }
+}
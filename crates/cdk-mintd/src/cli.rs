@@ -1,6 +1,6 @@
 use std::path::PathBuf;
 
-use clap::Parser;
+use clap::{Args, Parser, Subcommand};
 
 #[derive(Debug, Parser)]
 #[command(about = "A cashu mint written in rust", author = env!("CARGO_PKG_AUTHORS"), version = env!("CARGO_PKG_VERSION"))]
@@ -36,4 +36,55 @@ pub struct CLIArgs {
         default_value = "true"
     )]
     pub enable_logging: bool,
+    #[arg(
+        long,
+        help = "Serve requests even if the startup self check fails",
+        required = false,
+        action = clap::ArgAction::SetTrue
+    )]
+    pub skip_checks: bool,
+    #[command(subcommand)]
+    pub command: Option<Command>,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum Command {
+    /// Validate the configuration, backends, database and ports without starting the mint
+    CheckConfig(CheckConfigArgs),
+    /// Export mint payments and fee income as a double-entry accounting ledger
+    ExportLedger(ExportLedgerArgs),
+    /// Show applied and pending database migrations without applying any
+    Migrations,
+}
+
+#[derive(Debug, Args)]
+pub struct CheckConfigArgs {
+    #[arg(long, help = "Print the report as JSON", action = clap::ArgAction::SetTrue)]
+    pub json: bool,
+}
+
+#[derive(Debug, Args)]
+pub struct ExportLedgerArgs {
+    #[arg(
+        long,
+        help = "Output format, either csv or beancount",
+        default_value = "csv"
+    )]
+    pub format: String,
+    #[arg(
+        long,
+        help = "Unix time of the first entry to export",
+        default_value_t = 0
+    )]
+    pub from: u64,
+    #[arg(long, help = "Unix time entries must be before, defaults to now")]
+    pub to: Option<u64>,
+    #[arg(
+        long,
+        help = "Unit of fees recorded before operations stored their unit",
+        default_value = "sat"
+    )]
+    pub fee_unit: String,
+    #[arg(long, help = "Write the ledger to <file> instead of stdout")]
+    pub output: Option<PathBuf>,
 }
@@ -1,10 +1,12 @@
+use std::collections::HashMap;
 use std::path::PathBuf;
 
 use bitcoin::hashes::{sha256, Hash};
-use cdk::nuts::{CurrencyUnit, PublicKey};
+use cdk::mint::QuoteIdFormat;
+use cdk::nuts::{CurrencyUnit, MeltChangePolicy, PublicKey};
 use cdk::Amount;
-use cdk_axum::cache;
-use cdk_common::common::QuoteTTL;
+use cdk_axum::{cache, headers};
+use cdk_common::common::{QuoteTTL, ScheduledMotd};
 use config::{Config, ConfigError, File};
 use serde::{Deserialize, Serialize};
 
@@ -35,6 +37,84 @@ impl std::str::FromStr for LoggingOutput {
     }
 }
 
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum LoggingFormat {
+    /// Human readable lines (default)
+    #[default]
+    Text,
+    /// One JSON object per line
+    Json,
+}
+
+impl std::str::FromStr for LoggingFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "text" => Ok(LoggingFormat::Text),
+            "json" => Ok(LoggingFormat::Json),
+            _ => Err(format!(
+                "Unknown logging format: {s}. Valid options: text, json"
+            )),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum LoggingRotation {
+    /// New log file every minute
+    Minutely,
+    /// New log file every hour
+    Hourly,
+    /// New log file every day (default)
+    #[default]
+    Daily,
+    /// Single log file
+    Never,
+}
+
+impl std::str::FromStr for LoggingRotation {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "minutely" => Ok(LoggingRotation::Minutely),
+            "hourly" => Ok(LoggingRotation::Hourly),
+            "daily" => Ok(LoggingRotation::Daily),
+            "never" => Ok(LoggingRotation::Never),
+            _ => Err(format!(
+                "Unknown logging rotation: {s}. Valid options: minutely, hourly, daily, never"
+            )),
+        }
+    }
+}
+
+/// OpenTelemetry OTLP trace export
+#[cfg(feature = "otlp")]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(default)]
+pub struct OtlpConfig {
+    /// gRPC endpoint of the OTLP collector
+    pub endpoint: String,
+    /// `service.name` resource attribute of the exported spans
+    pub service_name: String,
+    /// Minimum level of the exported spans (default: "info")
+    pub level: Option<String>,
+}
+
+#[cfg(feature = "otlp")]
+impl Default for OtlpConfig {
+    fn default() -> Self {
+        Self {
+            endpoint: "http://localhost:4317".to_string(),
+            service_name: "cdk-mintd".to_string(),
+            level: None,
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 pub struct LoggingConfig {
     /// Where to output logs: stdout, file, or both
@@ -44,6 +124,21 @@ pub struct LoggingConfig {
     pub console_level: Option<String>,
     /// Log level for file output (when file or both)
     pub file_level: Option<String>,
+    /// Format of console and file output: text or json
+    #[serde(default)]
+    pub format: LoggingFormat,
+    /// Additional `target=level` directives, e.g. "cdk=info,cdk_axum=warn"
+    ///
+    /// Applied on top of the default directives, which quiet noisy dependencies.
+    pub filter: Option<String>,
+    /// How often a new log file is started
+    #[serde(default)]
+    pub file_rotation: LoggingRotation,
+    /// Number of rotated log files to keep, all are kept if unset
+    pub max_log_files: Option<usize>,
+    /// Export traces to an OpenTelemetry collector
+    #[cfg(feature = "otlp")]
+    pub otlp: Option<OtlpConfig>,
 }
 
 #[derive(Clone, Serialize, Deserialize)]
@@ -56,11 +151,18 @@ pub struct Info {
     pub seed: Option<String>,
     pub mnemonic: Option<String>,
     pub input_fee_ppk: Option<u64>,
+    /// Only accept powers of two up to `2^max_order` as denominations
+    pub max_order: Option<u8>,
+    /// Denominations to accept, e.g. `[1, 2, 5, 10, 20, 50]`, instead of powers of two
+    pub amounts: Option<Vec<u64>>,
     /// Use keyset v2
     pub use_keyset_v2: Option<bool>,
 
     pub http_cache: cache::Config,
 
+    /// CORS and security headers of mint responses
+    pub http_headers: headers::Config,
+
     /// Logging configuration
     #[serde(default)]
     pub logging: LoggingConfig,
@@ -76,6 +178,33 @@ pub struct Info {
     /// If not provided, defaults are used.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub quote_ttl: Option<QuoteTTL>,
+
+    /// Format of new quote ids: `uuid_v4`, `uuid_v7` (default) or `ulid`
+    pub quote_id_format: QuoteIdFormat,
+
+    /// Reject malformed NUT-10 witnesses, duplicate signatures and early refund
+    /// signatures with specific error codes
+    pub strict_spending_conditions: bool,
+
+    /// How the unspent fee reserve of a melt is returned as change, surplus that is
+    /// not returned is kept as fee income
+    pub melt_change_policy: MeltChangePolicy,
+
+    /// Node ids of the mint's own lightning backends, melt quotes for invoices
+    /// payable to them are rejected
+    pub own_node_ids: Vec<PublicKey>,
+
+    /// Serve requests even if the startup self check fails
+    pub skip_checks: bool,
+
+    /// Start in maintenance mode, rejecting quotes, mints, swaps and melts
+    pub maintenance_mode: bool,
+
+    /// Seconds wallets are told to wait before retrying while in maintenance mode
+    pub maintenance_retry_after: Option<u64>,
+
+    /// Seconds to wait for pending melts to resolve on shutdown
+    pub shutdown_timeout: Option<u64>,
 }
 
 impl Default for Info {
@@ -87,11 +216,22 @@ impl Default for Info {
             seed: None,
             mnemonic: None,
             input_fee_ppk: None,
+            max_order: None,
+            amounts: None,
             use_keyset_v2: None,
             http_cache: cache::Config::default(),
+            http_headers: headers::Config::default(),
             enable_info_page: Some(true),
             logging: LoggingConfig::default(),
             quote_ttl: None,
+            quote_id_format: QuoteIdFormat::default(),
+            strict_spending_conditions: false,
+            melt_change_policy: MeltChangePolicy::default(),
+            own_node_ids: Vec::new(),
+            skip_checks: false,
+            maintenance_mode: false,
+            maintenance_retry_after: None,
+            shutdown_timeout: None,
         }
     }
 }
@@ -114,10 +254,24 @@ impl std::fmt::Debug for Info {
             .field("listen_port", &self.listen_port)
             .field("mnemonic", &mnemonic_display)
             .field("input_fee_ppk", &self.input_fee_ppk)
+            .field("max_order", &self.max_order)
+            .field("amounts", &self.amounts)
             .field("use_keyset_v2", &self.use_keyset_v2)
             .field("http_cache", &self.http_cache)
+            .field("http_headers", &self.http_headers)
             .field("logging", &self.logging)
             .field("enable_info_page", &self.enable_info_page)
+            .field("quote_id_format", &self.quote_id_format)
+            .field(
+                "strict_spending_conditions",
+                &self.strict_spending_conditions,
+            )
+            .field("melt_change_policy", &self.melt_change_policy)
+            .field("own_node_ids", &self.own_node_ids)
+            .field("skip_checks", &self.skip_checks)
+            .field("maintenance_mode", &self.maintenance_mode)
+            .field("maintenance_retry_after", &self.maintenance_retry_after)
+            .field("shutdown_timeout", &self.shutdown_timeout)
             .finish()
     }
 }
@@ -561,6 +715,13 @@ pub struct LNbits {
     pub fee_percent: f32,
     #[serde(default = "default_reserve_fee_min")]
     pub reserve_fee_min: Amount,
+    /// Public url LNbits calls when an invoice is paid
+    ///
+    /// When set, payment notifications are received through this webhook
+    /// instead of the LNbits websocket.
+    pub webhook_url: Option<String>,
+    /// Secret used to authenticate webhook callbacks
+    pub webhook_secret: Option<String>,
 }
 
 #[cfg(feature = "lnbits")]
@@ -572,6 +733,11 @@ impl std::fmt::Debug for LNbits {
             .field("lnbits_api", &self.lnbits_api)
             .field("fee_percent", &self.fee_percent)
             .field("reserve_fee_min", &self.reserve_fee_min)
+            .field("webhook_url", &self.webhook_url)
+            .field(
+                "webhook_secret",
+                &self.webhook_secret.as_ref().map(|_| "[REDACTED]"),
+            )
             .finish()
     }
 }
@@ -585,6 +751,8 @@ impl Default for LNbits {
             lnbits_api: String::new(),
             fee_percent: 0.02,
             reserve_fee_min: 2.into(),
+            webhook_url: None,
+            webhook_secret: None,
         }
     }
 }
@@ -598,6 +766,9 @@ pub struct Cln {
     pub bolt12: bool,
     #[serde(default)]
     pub expose_private_channels: bool,
+    /// Use hold invoices (requires the CLN `holdinvoice` plugin)
+    #[serde(default)]
+    pub hold_invoices: bool,
     #[serde(default = "default_fee_percent")]
     pub fee_percent: f32,
     #[serde(default = "default_reserve_fee_min")]
@@ -611,6 +782,7 @@ impl Default for Cln {
             rpc_path: PathBuf::new(),
             bolt12: true,
             expose_private_channels: false,
+            hold_invoices: false,
             fee_percent: 0.02,
             reserve_fee_min: 2.into(),
         }
@@ -633,6 +805,9 @@ pub struct Lnd {
     pub fee_percent: f32,
     #[serde(default = "default_reserve_fee_min")]
     pub reserve_fee_min: Amount,
+    /// Accept keysend and AMP payments for mint quotes
+    #[serde(default)]
+    pub keysend: bool,
 }
 
 #[cfg(feature = "lnd")]
@@ -644,6 +819,7 @@ impl Default for Lnd {
             macaroon_file: PathBuf::new(),
             fee_percent: 0.02,
             reserve_fee_min: 2.into(),
+            keysend: false,
         }
     }
 }
@@ -847,6 +1023,9 @@ pub struct FakeWallet {
     /// Additional keyset rotations to create during mint build
     #[serde(default)]
     pub keyset_rotations: Vec<FakeWalletKeysetRotation>,
+    /// Initial failure injection settings, adjustable at runtime via the management RPC
+    #[serde(default)]
+    pub failure_injection: cdk_fake_wallet::FailureInjectionSettings,
 }
 
 #[cfg(feature = "fakewallet")]
@@ -860,6 +1039,7 @@ impl Default for FakeWallet {
             min_delay_time: 1,
             max_delay_time: 3,
             keyset_rotations: Vec::new(),
+            failure_injection: Default::default(),
         }
     }
 }
@@ -956,6 +1136,8 @@ impl std::str::FromStr for DatabaseEngine {
 pub struct Database {
     pub engine: DatabaseEngine,
     pub postgres: Option<PostgresConfig>,
+    /// Archive spent proofs older than this many days (disabled when unset)
+    pub spent_proof_retention_days: Option<u64>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
@@ -1055,6 +1237,15 @@ pub struct Auth {
     /// Enable WebSocket authentication support
     #[serde(default = "default_blind")]
     pub websocket_auth: AuthType,
+    /// Maximum amount a clear auth identity may mint per day and unit
+    #[serde(default)]
+    pub max_mint_per_day: Option<u64>,
+    /// Maximum amount a clear auth identity may melt per day and unit
+    #[serde(default)]
+    pub max_melt_per_day: Option<u64>,
+    /// Maximum number of blind auth tokens a clear auth identity may mint per day
+    #[serde(default)]
+    pub max_bat_per_day: Option<u64>,
 }
 
 fn default_blind() -> AuthType {
@@ -1095,6 +1286,16 @@ pub struct Settings {
     #[cfg(feature = "prometheus")]
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub prometheus: Option<Prometheus>,
+    /// Webhook notifications of mint events
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub webhooks: Option<Webhooks>,
+    /// Lightning addresses creating mint quotes
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub lightning_address: Option<LightningAddress>,
+    /// Swaps between units
+    #[cfg(feature = "conversion")]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub conversion: Option<Conversion>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
@@ -1115,6 +1316,9 @@ pub struct Limits {
     /// Maximum number of outputs allowed per transaction (mint/swap/melt)
     #[serde(default = "default_max_outputs")]
     pub max_outputs: usize,
+    /// Number of threads used to sign blinded messages in parallel
+    #[serde(default = "default_signing_threads")]
+    pub signing_threads: usize,
 }
 
 impl Default for Limits {
@@ -1122,6 +1326,7 @@ impl Default for Limits {
         Self {
             max_inputs: 1000,
             max_outputs: 1000,
+            signing_threads: 1,
         }
     }
 }
@@ -1134,6 +1339,134 @@ fn default_max_outputs() -> usize {
     1000
 }
 
+fn default_signing_threads() -> usize {
+    1
+}
+
+/// Mint event sent to webhooks
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WebhookEventKind {
+    /// A mint quote was paid
+    MintQuotePaid,
+    /// A melt payment failed
+    MeltFailed,
+    /// A new keyset became active
+    KeysetRotated,
+    /// Outstanding ecash of a unit exceeded the liability threshold
+    LiabilityThresholdExceeded,
+}
+
+/// Webhook configuration
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Webhooks {
+    /// URLs events are POSTed to
+    #[serde(default)]
+    pub urls: Vec<String>,
+    /// Secret of the HMAC-SHA256 signature in the `X-Cdk-Signature` header
+    pub secret: String,
+    /// Events to send, all events when empty
+    #[serde(default)]
+    pub events: Vec<WebhookEventKind>,
+    /// Outstanding amount per unit above which `liability_threshold_exceeded` is sent
+    pub liability_threshold: Option<u64>,
+    /// Delivery attempts per event and URL
+    #[serde(default = "default_webhook_max_attempts")]
+    pub max_attempts: u32,
+    /// Seconds between checks for keyset rotations and liabilities
+    #[serde(default = "default_webhook_poll_interval_secs")]
+    pub poll_interval_secs: u64,
+}
+
+impl Default for Webhooks {
+    fn default() -> Self {
+        Self {
+            urls: Vec::new(),
+            secret: String::new(),
+            events: Vec::new(),
+            liability_threshold: None,
+            max_attempts: default_webhook_max_attempts(),
+            poll_interval_secs: default_webhook_poll_interval_secs(),
+        }
+    }
+}
+
+fn default_webhook_max_attempts() -> u32 {
+    5
+}
+
+fn default_webhook_poll_interval_secs() -> u64 {
+    60
+}
+
+/// Unit conversion configuration
+///
+/// Swaps may convert between the units of each configured rate, in both directions.
+#[cfg(feature = "conversion")]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Conversion {
+    /// Spread kept by the mint in basis points
+    #[serde(default)]
+    pub spread_bps: u16,
+    /// Seconds a quoted rate may be used for
+    pub rate_ttl_secs: Option<u64>,
+    /// Exchange rates between units
+    #[serde(default)]
+    pub rates: Vec<UnitRate>,
+}
+
+/// `from_amount` of `from` are worth `to_amount` of `to`
+#[cfg(feature = "conversion")]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UnitRate {
+    pub from: CurrencyUnit,
+    pub to: CurrencyUnit,
+    pub from_amount: u64,
+    pub to_amount: u64,
+}
+
+/// Lightning address (LNURL-pay) configuration
+///
+/// Paying `user@mint` creates a bolt11 mint quote locked to the pubkey of the user.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LightningAddress {
+    /// Pubkeys the mint quotes of each username are locked to
+    #[serde(default)]
+    pub users: HashMap<String, PublicKey>,
+    /// Minimum payable amount in millisatoshis
+    #[serde(default = "default_lightning_address_min_sendable_msat")]
+    pub min_sendable_msat: u64,
+    /// Maximum payable amount in millisatoshis
+    #[serde(default = "default_lightning_address_max_sendable_msat")]
+    pub max_sendable_msat: u64,
+    /// Maximum invoices created per username and minute
+    #[serde(default = "default_lightning_address_max_invoices_per_minute")]
+    pub max_invoices_per_minute: usize,
+}
+
+impl Default for LightningAddress {
+    fn default() -> Self {
+        Self {
+            users: HashMap::new(),
+            min_sendable_msat: default_lightning_address_min_sendable_msat(),
+            max_sendable_msat: default_lightning_address_max_sendable_msat(),
+            max_invoices_per_minute: default_lightning_address_max_invoices_per_minute(),
+        }
+    }
+}
+
+fn default_lightning_address_min_sendable_msat() -> u64 {
+    1_000
+}
+
+fn default_lightning_address_max_sendable_msat() -> u64 {
+    100_000_000
+}
+
+fn default_lightning_address_max_invoices_per_minute() -> usize {
+    10
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 #[serde(default)]
 pub struct MintInfo {
@@ -1155,6 +1488,8 @@ pub struct MintInfo {
     pub contact_email: Option<String>,
     /// URL to the terms of service
     pub tos_url: Option<String>,
+    /// Messages of the day shown instead of `motd` during their time window
+    pub motd_schedule: Vec<ScheduledMotd>,
 }
 
 #[cfg(feature = "management-rpc")]
@@ -1356,6 +1691,35 @@ mod tests {
         assert!(debug_output.contains("<hashed: "));
     }
 
+    #[test]
+    fn test_logging_toml_config() {
+        let logging: LoggingConfig = Config::builder()
+            .add_source(File::from_str(
+                r#"
+output = "file"
+format = "json"
+filter = "cdk=trace"
+file_rotation = "hourly"
+max_log_files = 24
+"#,
+                config::FileFormat::Toml,
+            ))
+            .build()
+            .expect("config should build")
+            .try_deserialize()
+            .expect("logging config should parse");
+
+        assert_eq!(logging.output, LoggingOutput::File);
+        assert_eq!(logging.format, LoggingFormat::Json);
+        assert_eq!(logging.filter.as_deref(), Some("cdk=trace"));
+        assert_eq!(logging.file_rotation, LoggingRotation::Hourly);
+        assert_eq!(logging.max_log_files, Some(24));
+
+        let defaults = LoggingConfig::default();
+        assert_eq!(defaults.format, LoggingFormat::Text);
+        assert_eq!(defaults.file_rotation, LoggingRotation::Daily);
+    }
+
     #[cfg(feature = "bdk")]
     #[test]
     fn test_bdk_default_min_send_amount_sat() {
@@ -2215,6 +2579,11 @@ max_melt = 500000
         );
         env::set_var(crate::env_vars::ENV_LNBITS_FEE_PERCENT, "0.02");
         env::set_var(crate::env_vars::ENV_LNBITS_RESERVE_FEE_MIN, "5");
+        env::set_var(
+            crate::env_vars::ENV_LNBITS_WEBHOOK_URL,
+            "https://mint.example.com/webhook/lnbits",
+        );
+        env::set_var(crate::env_vars::ENV_LNBITS_WEBHOOK_SECRET, "webhook_secret");
 
         // Load settings and apply environment variables (same as production code)
         let mut settings = Settings::try_new(Some(&config_path)).expect("Failed to load config");
@@ -2229,6 +2598,14 @@ max_melt = 500000
         assert_eq!(lnbits_config.fee_percent, 0.02);
         let reserve_fee_u64: u64 = lnbits_config.reserve_fee_min.into();
         assert_eq!(reserve_fee_u64, 5);
+        assert_eq!(
+            lnbits_config.webhook_url.as_deref(),
+            Some("https://mint.example.com/webhook/lnbits")
+        );
+        assert_eq!(
+            lnbits_config.webhook_secret.as_deref(),
+            Some("webhook_secret")
+        );
 
         // Cleanup env vars
         env::remove_var(crate::env_vars::ENV_LN_BACKEND);
@@ -2237,6 +2614,8 @@ max_melt = 500000
         env::remove_var(crate::env_vars::ENV_LNBITS_API);
         env::remove_var(crate::env_vars::ENV_LNBITS_FEE_PERCENT);
         env::remove_var(crate::env_vars::ENV_LNBITS_RESERVE_FEE_MIN);
+        env::remove_var(crate::env_vars::ENV_LNBITS_WEBHOOK_URL);
+        env::remove_var(crate::env_vars::ENV_LNBITS_WEBHOOK_SECRET);
 
         // Cleanup test file
         let _ = fs::remove_dir_all(&temp_dir);
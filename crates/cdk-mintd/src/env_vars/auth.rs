@@ -21,6 +21,9 @@ pub const ENV_AUTH_WEBSOCKET: &str = "CDK_MINTD_AUTH_WEBSOCKET";
 pub const ENV_AUTH_WS_MINT_QUOTE: &str = "CDK_MINTD_AUTH_WS_MINT_QUOTE";
 pub const ENV_AUTH_WS_MELT_QUOTE: &str = "CDK_MINTD_AUTH_WS_MELT_QUOTE";
 pub const ENV_AUTH_WS_PROOF_STATE: &str = "CDK_MINTD_AUTH_WS_PROOF_STATE";
+pub const ENV_AUTH_MAX_MINT_PER_DAY: &str = "CDK_MINTD_AUTH_MAX_MINT_PER_DAY";
+pub const ENV_AUTH_MAX_MELT_PER_DAY: &str = "CDK_MINTD_AUTH_MAX_MELT_PER_DAY";
+pub const ENV_AUTH_MAX_BAT_PER_DAY: &str = "CDK_MINTD_AUTH_MAX_BAT_PER_DAY";
 
 impl Auth {
     pub fn from_env(mut self) -> Self {
@@ -104,6 +107,24 @@ impl Auth {
             }
         }
 
+        if let Ok(max_mint_str) = env::var(ENV_AUTH_MAX_MINT_PER_DAY) {
+            if let Ok(max_mint) = max_mint_str.parse() {
+                self.max_mint_per_day = Some(max_mint);
+            }
+        }
+
+        if let Ok(max_melt_str) = env::var(ENV_AUTH_MAX_MELT_PER_DAY) {
+            if let Ok(max_melt) = max_melt_str.parse() {
+                self.max_melt_per_day = Some(max_melt);
+            }
+        }
+
+        if let Ok(max_bat_str) = env::var(ENV_AUTH_MAX_BAT_PER_DAY) {
+            if let Ok(max_bat) = max_bat_str.parse() {
+                self.max_bat_per_day = Some(max_bat);
+            }
+        }
+
         self
     }
 }
@@ -16,12 +16,38 @@ pub const ENV_SIGNATORY_ALLOW_INSECURE: &str = "CDK_MINTD_SIGNATORY_ALLOW_INSECU
 pub const ENV_SECONDS_QUOTE_VALID: &str = "CDK_MINTD_SECONDS_QUOTE_VALID";
 pub const ENV_CACHE_SECONDS: &str = "CDK_MINTD_CACHE_SECONDS";
 pub const ENV_EXTEND_CACHE_SECONDS: &str = "CDK_MINTD_EXTEND_CACHE_SECONDS";
+pub const ENV_CORS_ALLOWED_ORIGINS: &str = "CDK_MINTD_CORS_ALLOWED_ORIGINS";
+pub const ENV_CORS_ALLOWED_HEADERS: &str = "CDK_MINTD_CORS_ALLOWED_HEADERS";
+pub const ENV_CORS_MAX_AGE: &str = "CDK_MINTD_CORS_MAX_AGE";
+pub const ENV_HSTS_MAX_AGE: &str = "CDK_MINTD_HSTS_MAX_AGE";
+pub const ENV_HSTS_INCLUDE_SUBDOMAINS: &str = "CDK_MINTD_HSTS_INCLUDE_SUBDOMAINS";
+pub const ENV_FRAME_OPTIONS: &str = "CDK_MINTD_FRAME_OPTIONS";
 pub const ENV_INPUT_FEE_PPK: &str = "CDK_MINTD_INPUT_FEE_PPK";
+pub const ENV_MAX_ORDER: &str = "CDK_MINTD_MAX_ORDER";
+pub const ENV_AMOUNTS: &str = "CDK_MINTD_AMOUNTS";
 pub const ENV_QUOTE_TTL_MINT: &str = "CDK_MINTD_QUOTE_TTL_MINT";
 pub const ENV_QUOTE_TTL_MELT: &str = "CDK_MINTD_QUOTE_TTL_MELT";
 pub const ENV_USE_KEYSET_V2: &str = "CDK_MINTD_USE_KEYSET_V2";
+pub const ENV_QUOTE_ID_FORMAT: &str = "CDK_MINTD_QUOTE_ID_FORMAT";
+pub const ENV_SKIP_CHECKS: &str = "CDK_MINTD_SKIP_CHECKS";
+pub const ENV_STRICT_SPENDING_CONDITIONS: &str = "CDK_MINTD_STRICT_SPENDING_CONDITIONS";
+pub const ENV_MELT_CHANGE_POLICY: &str = "CDK_MINTD_MELT_CHANGE_POLICY";
+pub const ENV_OWN_NODE_IDS: &str = "CDK_MINTD_OWN_NODE_IDS";
+pub const ENV_MAINTENANCE_MODE: &str = "CDK_MINTD_MAINTENANCE_MODE";
+pub const ENV_MAINTENANCE_RETRY_AFTER: &str = "CDK_MINTD_MAINTENANCE_RETRY_AFTER";
+pub const ENV_SHUTDOWN_TIMEOUT: &str = "CDK_MINTD_SHUTDOWN_TIMEOUT";
 
 pub const ENV_ENABLE_INFO_PAGE: &str = "CDK_MINTD_ENABLE_INFO_PAGE";
 pub const ENV_LOGGING_OUTPUT: &str = "CDK_MINTD_LOGGING_OUTPUT";
 pub const ENV_LOGGING_CONSOLE_LEVEL: &str = "CDK_MINTD_LOGGING_CONSOLE_LEVEL";
 pub const ENV_LOGGING_FILE_LEVEL: &str = "CDK_MINTD_LOGGING_FILE_LEVEL";
+pub const ENV_LOGGING_FORMAT: &str = "CDK_MINTD_LOGGING_FORMAT";
+pub const ENV_LOGGING_FILTER: &str = "CDK_MINTD_LOGGING_FILTER";
+pub const ENV_LOGGING_FILE_ROTATION: &str = "CDK_MINTD_LOGGING_FILE_ROTATION";
+pub const ENV_LOGGING_MAX_LOG_FILES: &str = "CDK_MINTD_LOGGING_MAX_LOG_FILES";
+#[cfg(feature = "otlp")]
+pub const ENV_LOGGING_OTLP_ENDPOINT: &str = "CDK_MINTD_LOGGING_OTLP_ENDPOINT";
+#[cfg(feature = "otlp")]
+pub const ENV_LOGGING_OTLP_SERVICE_NAME: &str = "CDK_MINTD_LOGGING_OTLP_SERVICE_NAME";
+#[cfg(feature = "otlp")]
+pub const ENV_LOGGING_OTLP_LEVEL: &str = "CDK_MINTD_LOGGING_OTLP_LEVEL";
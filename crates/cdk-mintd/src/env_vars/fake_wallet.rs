@@ -7,6 +7,7 @@ use cdk::nuts::CurrencyUnit;
 use crate::config::{FakeWallet, FakeWalletCustomPaymentMethod, FakeWalletKeysetRotation};
 
 // Fake Wallet environment variables
+pub const ENV_FAKE_WALLET_PREFIX: &str = "CDK_MINTD_FAKE_WALLET";
 pub const ENV_FAKE_WALLET_SUPPORTED_UNITS: &str = "CDK_MINTD_FAKE_WALLET_SUPPORTED_UNITS";
 pub const ENV_FAKE_WALLET_FEE_PERCENT: &str = "CDK_MINTD_FAKE_WALLET_FEE_PERCENT";
 pub const ENV_FAKE_WALLET_RESERVE_FEE_MIN: &str = "CDK_MINTD_FAKE_WALLET_RESERVE_FEE_MIN";
@@ -17,11 +18,23 @@ pub const ENV_FAKE_WALLET_MAX_DELAY: &str = "CDK_MINTD_FAKE_WALLET_MAX_DELAY";
 /// JSON array of keyset rotations, e.g.:
 /// `[{"unit":"sat","version":"v1","input_fee_ppk":0,"expired":true}]`
 pub const ENV_FAKE_WALLET_KEYSET_ROTATIONS: &str = "CDK_MINTD_FAKE_WALLET_KEYSET_ROTATIONS";
+pub const ENV_FAKE_WALLET_MELT_FAILURE_PERCENT: &str = "CDK_MINTD_FAKE_WALLET_MELT_FAILURE_PERCENT";
+pub const ENV_FAKE_WALLET_PAID_NOTIFICATION_DELAY: &str =
+    "CDK_MINTD_FAKE_WALLET_PAID_NOTIFICATION_DELAY";
+pub const ENV_FAKE_WALLET_BOLT12_PARTIAL_PAYMENTS: &str =
+    "CDK_MINTD_FAKE_WALLET_BOLT12_PARTIAL_PAYMENTS";
 
 impl FakeWallet {
-    pub fn from_env(mut self) -> Self {
+    pub fn from_env(self) -> Self {
+        self.from_env_with_prefix(ENV_FAKE_WALLET_PREFIX)
+    }
+
+    /// Reads the variables with `CDK_MINTD_FAKE_WALLET` replaced by `prefix`
+    pub fn from_env_with_prefix(mut self, prefix: &str) -> Self {
+        let var = |key: &str| env::var(key.replacen(ENV_FAKE_WALLET_PREFIX, prefix, 1));
+
         // Supported Units - expects comma-separated list
-        if let Ok(units_str) = env::var(ENV_FAKE_WALLET_SUPPORTED_UNITS) {
+        if let Ok(units_str) = var(ENV_FAKE_WALLET_SUPPORTED_UNITS) {
             if let Ok(units) = units_str
                 .split(',')
                 .map(|s| s.trim().parse())
@@ -31,38 +44,38 @@ impl FakeWallet {
             }
         }
 
-        if let Ok(fee_str) = env::var(ENV_FAKE_WALLET_FEE_PERCENT) {
+        if let Ok(fee_str) = var(ENV_FAKE_WALLET_FEE_PERCENT) {
             if let Ok(fee) = fee_str.parse() {
                 self.fee_percent = fee;
             }
         }
 
-        if let Ok(reserve_fee_str) = env::var(ENV_FAKE_WALLET_RESERVE_FEE_MIN) {
+        if let Ok(reserve_fee_str) = var(ENV_FAKE_WALLET_RESERVE_FEE_MIN) {
             if let Ok(reserve_fee) = reserve_fee_str.parse::<u64>() {
                 self.reserve_fee_min = reserve_fee.into();
             }
         }
 
-        if let Ok(methods_str) = env::var(ENV_FAKE_WALLET_CUSTOM_PAYMENT_METHODS) {
+        if let Ok(methods_str) = var(ENV_FAKE_WALLET_CUSTOM_PAYMENT_METHODS) {
             self.custom_payment_methods = methods_str
                 .split(',')
                 .filter_map(parse_custom_payment_method_env)
                 .collect();
         }
 
-        if let Ok(min_delay_str) = env::var(ENV_FAKE_WALLET_MIN_DELAY) {
+        if let Ok(min_delay_str) = var(ENV_FAKE_WALLET_MIN_DELAY) {
             if let Ok(min_delay) = min_delay_str.parse() {
                 self.min_delay_time = min_delay;
             }
         }
 
-        if let Ok(max_delay_str) = env::var(ENV_FAKE_WALLET_MAX_DELAY) {
+        if let Ok(max_delay_str) = var(ENV_FAKE_WALLET_MAX_DELAY) {
             if let Ok(max_delay) = max_delay_str.parse() {
                 self.max_delay_time = max_delay;
             }
         }
 
-        if let Ok(rotations_str) = env::var(ENV_FAKE_WALLET_KEYSET_ROTATIONS) {
+        if let Ok(rotations_str) = var(ENV_FAKE_WALLET_KEYSET_ROTATIONS) {
             if let Ok(rotations) =
                 serde_json::from_str::<Vec<FakeWalletKeysetRotation>>(&rotations_str)
             {
@@ -70,6 +83,24 @@ impl FakeWallet {
             }
         }
 
+        if let Ok(percent_str) = var(ENV_FAKE_WALLET_MELT_FAILURE_PERCENT) {
+            if let Ok(percent) = percent_str.parse() {
+                self.failure_injection.melt_failure_percent = percent;
+            }
+        }
+
+        if let Ok(delay_str) = var(ENV_FAKE_WALLET_PAID_NOTIFICATION_DELAY) {
+            if let Ok(delay) = delay_str.parse() {
+                self.failure_injection.paid_notification_delay = delay;
+            }
+        }
+
+        if let Ok(parts_str) = var(ENV_FAKE_WALLET_BOLT12_PARTIAL_PAYMENTS) {
+            if let Ok(parts) = parts_str.parse() {
+                self.failure_injection.bolt12_partial_payments = parts;
+            }
+        }
+
         self
     }
 }
@@ -7,6 +7,7 @@ use cdk::nuts::CurrencyUnit;
 use crate::config::GrpcProcessor;
 
 // gRPC Payment Processor environment variables
+pub const ENV_GRPC_PROCESSOR_PREFIX: &str = "CDK_MINTD_GRPC_PAYMENT_PROCESSOR";
 pub const ENV_GRPC_PROCESSOR_SUPPORTED_UNITS: &str =
     "CDK_MINTD_GRPC_PAYMENT_PROCESSOR_SUPPORTED_UNITS";
 pub const ENV_GRPC_PROCESSOR_ADDRESS: &str = "CDK_MINTD_GRPC_PAYMENT_PROCESSOR_ADDRESS";
@@ -16,8 +17,15 @@ pub const ENV_GRPC_PROCESSOR_ALLOW_INSECURE: &str =
     "CDK_MINTD_GRPC_PAYMENT_PROCESSOR_ALLOW_INSECURE";
 
 impl GrpcProcessor {
-    pub fn from_env(mut self) -> Self {
-        if let Ok(units_str) = env::var(ENV_GRPC_PROCESSOR_SUPPORTED_UNITS) {
+    pub fn from_env(self) -> Self {
+        self.from_env_with_prefix(ENV_GRPC_PROCESSOR_PREFIX)
+    }
+
+    /// Reads the variables with `CDK_MINTD_GRPC_PAYMENT_PROCESSOR` replaced by `prefix`
+    pub fn from_env_with_prefix(mut self, prefix: &str) -> Self {
+        let var = |key: &str| env::var(key.replacen(ENV_GRPC_PROCESSOR_PREFIX, prefix, 1));
+
+        if let Ok(units_str) = var(ENV_GRPC_PROCESSOR_SUPPORTED_UNITS) {
             if let Ok(units) = units_str
                 .split(',')
                 .map(|s| s.trim().parse())
@@ -27,21 +35,21 @@ impl GrpcProcessor {
             }
         }
 
-        if let Ok(addr) = env::var(ENV_GRPC_PROCESSOR_ADDRESS) {
+        if let Ok(addr) = var(ENV_GRPC_PROCESSOR_ADDRESS) {
             self.address = addr;
         }
 
-        if let Ok(port) = env::var(ENV_GRPC_PROCESSOR_PORT) {
+        if let Ok(port) = var(ENV_GRPC_PROCESSOR_PORT) {
             if let Ok(port) = port.parse() {
                 self.port = port;
             }
         }
 
-        if let Ok(tls_dir) = env::var(ENV_GRPC_PROCESSOR_TLS_DIR) {
+        if let Ok(tls_dir) = var(ENV_GRPC_PROCESSOR_TLS_DIR) {
             self.tls_dir = Some(tls_dir.into());
         }
 
-        if let Ok(allow_insecure) = env::var(ENV_GRPC_PROCESSOR_ALLOW_INSECURE) {
+        if let Ok(allow_insecure) = var(ENV_GRPC_PROCESSOR_ALLOW_INSECURE) {
             if let Ok(allow_insecure) = allow_insecure.parse() {
                 self.allow_insecure = allow_insecure;
             }
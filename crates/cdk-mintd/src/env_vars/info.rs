@@ -3,13 +3,15 @@
 use std::env;
 use std::str::FromStr;
 
+use anyhow::{anyhow, Result};
+use cdk_axum::headers::FrameOptions;
 use cdk_common::common::QuoteTTL;
 
 use super::common::*;
-use crate::config::{Info, LoggingOutput};
+use crate::config::{Info, LoggingFormat, LoggingOutput, LoggingRotation};
 
 impl Info {
-    pub fn from_env(mut self) -> Self {
+    pub fn from_env(mut self) -> Result<Self> {
         // Required fields
         if let Ok(url) = env::var(ENV_URL) {
             self.url = url;
@@ -45,12 +47,116 @@ impl Info {
             }
         }
 
+        if let Ok(origins_str) = env::var(ENV_CORS_ALLOWED_ORIGINS) {
+            self.http_headers.cors_allowed_origins = split_list(&origins_str);
+        }
+
+        if let Ok(headers_str) = env::var(ENV_CORS_ALLOWED_HEADERS) {
+            self.http_headers.cors_allowed_headers = split_list(&headers_str);
+        }
+
+        if let Ok(max_age_str) = env::var(ENV_CORS_MAX_AGE) {
+            if let Ok(max_age) = max_age_str.parse() {
+                self.http_headers.cors_max_age = Some(max_age);
+            }
+        }
+
+        if let Ok(max_age_str) = env::var(ENV_HSTS_MAX_AGE) {
+            if let Ok(max_age) = max_age_str.parse() {
+                self.http_headers.hsts_max_age = Some(max_age);
+            }
+        }
+
+        if let Ok(include_subdomains_str) = env::var(ENV_HSTS_INCLUDE_SUBDOMAINS) {
+            if let Ok(include_subdomains) = include_subdomains_str.parse() {
+                self.http_headers.hsts_include_subdomains = include_subdomains;
+            }
+        }
+
+        if let Ok(frame_options_str) = env::var(ENV_FRAME_OPTIONS) {
+            if let Ok(frame_options) = FrameOptions::from_str(&frame_options_str) {
+                self.http_headers.frame_options = Some(frame_options);
+            } else {
+                tracing::warn!(
+                    "Invalid frame options '{}' in environment variable. Valid options: deny, same_origin",
+                    frame_options_str
+                );
+            }
+        }
+
         if let Ok(fee_str) = env::var(ENV_INPUT_FEE_PPK) {
             if let Ok(fee) = fee_str.parse() {
                 self.input_fee_ppk = Some(fee);
             }
         }
 
+        if let Ok(max_order_str) = env::var(ENV_MAX_ORDER) {
+            if let Ok(max_order) = max_order_str.parse() {
+                self.max_order = Some(max_order);
+            }
+        }
+
+        if let Ok(amounts_str) = env::var(ENV_AMOUNTS) {
+            let amounts = split_list(&amounts_str)
+                .iter()
+                .map(|amount| amount.parse::<u64>())
+                .collect::<Result<Vec<_>, _>>()
+                .map_err(|err| anyhow!("Invalid {ENV_AMOUNTS} `{amounts_str}`: {err}"))?;
+            self.amounts = Some(amounts);
+        }
+
+        if let Ok(skip_checks_str) = env::var(ENV_SKIP_CHECKS) {
+            if let Ok(skip_checks) = skip_checks_str.parse() {
+                self.skip_checks = skip_checks;
+            }
+        }
+
+        if let Ok(strict_str) = env::var(ENV_STRICT_SPENDING_CONDITIONS) {
+            if let Ok(strict) = strict_str.parse() {
+                self.strict_spending_conditions = strict;
+            }
+        }
+
+        if let Ok(change_policy_str) = env::var(ENV_MELT_CHANGE_POLICY) {
+            if let Ok(change_policy) = change_policy_str.parse() {
+                self.melt_change_policy = change_policy;
+            } else {
+                tracing::warn!(
+                    "Invalid melt change policy '{}' in environment variable. Valid options: always_return, keep_below_threshold:<amount>, round_down:<amount>",
+                    change_policy_str
+                );
+            }
+        }
+
+        if let Ok(node_ids_str) = env::var(ENV_OWN_NODE_IDS) {
+            match split_list(&node_ids_str)
+                .iter()
+                .map(|node_id| node_id.parse())
+                .collect::<Result<Vec<_>, _>>()
+            {
+                Ok(node_ids) => self.own_node_ids = node_ids,
+                Err(err) => tracing::warn!("Invalid own node ids in environment variable: {}", err),
+            }
+        }
+
+        if let Ok(maintenance_mode_str) = env::var(ENV_MAINTENANCE_MODE) {
+            if let Ok(maintenance_mode) = maintenance_mode_str.parse() {
+                self.maintenance_mode = maintenance_mode;
+            }
+        }
+
+        if let Ok(retry_after_str) = env::var(ENV_MAINTENANCE_RETRY_AFTER) {
+            if let Ok(retry_after) = retry_after_str.parse() {
+                self.maintenance_retry_after = Some(retry_after);
+            }
+        }
+
+        if let Ok(shutdown_timeout_str) = env::var(ENV_SHUTDOWN_TIMEOUT) {
+            if let Ok(shutdown_timeout) = shutdown_timeout_str.parse() {
+                self.shutdown_timeout = Some(shutdown_timeout);
+            }
+        }
+
         if let Ok(info_page_str) = env::var(ENV_ENABLE_INFO_PAGE) {
             if let Ok(enable) = info_page_str.parse() {
                 self.enable_info_page = Some(enable);
@@ -63,6 +169,17 @@ impl Info {
             }
         }
 
+        if let Ok(quote_id_format_str) = env::var(ENV_QUOTE_ID_FORMAT) {
+            if let Ok(quote_id_format) = quote_id_format_str.parse() {
+                self.quote_id_format = quote_id_format;
+            } else {
+                tracing::warn!(
+                    "Invalid quote id format '{}' in environment variable. Valid options: uuid_v4, uuid_v7, ulid",
+                    quote_id_format_str
+                );
+            }
+        }
+
         // Logging configuration
         if let Ok(output_str) = env::var(ENV_LOGGING_OUTPUT) {
             if let Ok(output) = LoggingOutput::from_str(&output_str) {
@@ -83,6 +200,55 @@ impl Info {
             self.logging.file_level = Some(file_level);
         }
 
+        if let Ok(format_str) = env::var(ENV_LOGGING_FORMAT) {
+            if let Ok(format) = LoggingFormat::from_str(&format_str) {
+                self.logging.format = format;
+            } else {
+                tracing::warn!(
+                    "Invalid logging format '{}' in environment variable. Valid options: text, json",
+                    format_str
+                );
+            }
+        }
+
+        if let Ok(filter) = env::var(ENV_LOGGING_FILTER) {
+            self.logging.filter = Some(filter);
+        }
+
+        if let Ok(rotation_str) = env::var(ENV_LOGGING_FILE_ROTATION) {
+            if let Ok(rotation) = LoggingRotation::from_str(&rotation_str) {
+                self.logging.file_rotation = rotation;
+            } else {
+                tracing::warn!(
+                    "Invalid logging rotation '{}' in environment variable. Valid options: minutely, hourly, daily, never",
+                    rotation_str
+                );
+            }
+        }
+
+        if let Ok(max_log_files_str) = env::var(ENV_LOGGING_MAX_LOG_FILES) {
+            if let Ok(max_log_files) = max_log_files_str.parse() {
+                self.logging.max_log_files = Some(max_log_files);
+            }
+        }
+
+        #[cfg(feature = "otlp")]
+        if let Ok(endpoint) = env::var(ENV_LOGGING_OTLP_ENDPOINT) {
+            let otlp = self.logging.otlp.get_or_insert_with(Default::default);
+            otlp.endpoint = endpoint;
+        }
+
+        #[cfg(feature = "otlp")]
+        if let Some(otlp) = self.logging.otlp.as_mut() {
+            if let Ok(service_name) = env::var(ENV_LOGGING_OTLP_SERVICE_NAME) {
+                otlp.service_name = service_name;
+            }
+
+            if let Ok(level) = env::var(ENV_LOGGING_OTLP_LEVEL) {
+                otlp.level = Some(level);
+            }
+        }
+
         self.http_cache = self.http_cache.from_env();
 
         // Quote TTL from env
@@ -106,6 +272,14 @@ impl Info {
             });
         }
 
-        self
+        Ok(self)
     }
 }
+
+/// Split a comma separated list, dropping empty entries
+fn split_list(list: &str) -> Vec<String> {
+    list.split(',')
+        .map(|s| s.trim().to_string())
+        .filter(|s| !s.is_empty())
+        .collect()
+}
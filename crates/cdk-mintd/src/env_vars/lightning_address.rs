@@ -0,0 +1,58 @@
+//! Lightning address environment variables
+
+use std::env;
+use std::str::FromStr;
+
+use cdk::nuts::PublicKey;
+
+use crate::config::LightningAddress;
+
+/// Comma separated `username=pubkey` pairs
+pub const ENV_LIGHTNING_ADDRESS_USERS: &str = "CDK_MINTD_LIGHTNING_ADDRESS_USERS";
+pub const ENV_LIGHTNING_ADDRESS_MIN_SENDABLE_MSAT: &str =
+    "CDK_MINTD_LIGHTNING_ADDRESS_MIN_SENDABLE_MSAT";
+pub const ENV_LIGHTNING_ADDRESS_MAX_SENDABLE_MSAT: &str =
+    "CDK_MINTD_LIGHTNING_ADDRESS_MAX_SENDABLE_MSAT";
+pub const ENV_LIGHTNING_ADDRESS_MAX_INVOICES_PER_MINUTE: &str =
+    "CDK_MINTD_LIGHTNING_ADDRESS_MAX_INVOICES_PER_MINUTE";
+
+impl LightningAddress {
+    pub fn from_env(mut self) -> Self {
+        if let Ok(users) = env::var(ENV_LIGHTNING_ADDRESS_USERS) {
+            self.users = users
+                .split(',')
+                .filter(|user| !user.trim().is_empty())
+                .filter_map(|user| {
+                    let (username, pubkey) = user.split_once('=')?;
+                    match PublicKey::from_str(pubkey.trim()) {
+                        Ok(pubkey) => Some((username.trim().to_string(), pubkey)),
+                        Err(_) => {
+                            tracing::warn!("Invalid lightning address pubkey for {}", username);
+                            None
+                        }
+                    }
+                })
+                .collect();
+        }
+
+        if let Ok(min_str) = env::var(ENV_LIGHTNING_ADDRESS_MIN_SENDABLE_MSAT) {
+            if let Ok(min) = min_str.parse() {
+                self.min_sendable_msat = min;
+            }
+        }
+
+        if let Ok(max_str) = env::var(ENV_LIGHTNING_ADDRESS_MAX_SENDABLE_MSAT) {
+            if let Ok(max) = max_str.parse() {
+                self.max_sendable_msat = max;
+            }
+        }
+
+        if let Ok(limit_str) = env::var(ENV_LIGHTNING_ADDRESS_MAX_INVOICES_PER_MINUTE) {
+            if let Ok(limit) = limit_str.parse() {
+                self.max_invoices_per_minute = limit;
+            }
+        }
+
+        self
+    }
+}
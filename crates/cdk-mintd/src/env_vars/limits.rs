@@ -6,6 +6,7 @@ use crate::config::Limits;
 
 pub const ENV_MAX_INPUTS: &str = "CDK_MINTD_MAX_INPUTS";
 pub const ENV_MAX_OUTPUTS: &str = "CDK_MINTD_MAX_OUTPUTS";
+pub const ENV_SIGNING_THREADS: &str = "CDK_MINTD_SIGNING_THREADS";
 
 impl Limits {
     /// Override limits with environment variables if set
@@ -24,6 +25,12 @@ impl Limits {
             }
         }
 
+        if let Ok(signing_threads_str) = env::var(ENV_SIGNING_THREADS) {
+            if let Ok(signing_threads) = signing_threads_str.parse::<usize>() {
+                limits.signing_threads = signing_threads;
+            }
+        }
+
         limits
     }
 }
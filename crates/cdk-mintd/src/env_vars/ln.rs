@@ -1,21 +1,69 @@
 //! Lightning Network common environment variables
 
 use std::env;
+use std::str::FromStr;
 
-use crate::config::Ln;
+use anyhow::{anyhow, bail, Result};
+use cdk::nuts::CurrencyUnit;
+
+use crate::config::{Ln, LnBackend, Settings};
 
 // LN environment variables
+pub const ENV_LN_PREFIX: &str = "CDK_MINTD_LN";
 pub const ENV_LN_BACKEND: &str = "CDK_MINTD_LN_BACKEND";
+pub const ENV_LN_UNIT: &str = "CDK_MINTD_LN_UNIT";
 pub const ENV_LN_INVOICE_DESCRIPTION: &str = "CDK_MINTD_LN_INVOICE_DESCRIPTION";
 pub const ENV_LN_MIN_MINT: &str = "CDK_MINTD_LN_MIN_MINT";
 pub const ENV_LN_MAX_MINT: &str = "CDK_MINTD_LN_MAX_MINT";
 pub const ENV_LN_MIN_MELT: &str = "CDK_MINTD_LN_MIN_MELT";
 pub const ENV_LN_MAX_MELT: &str = "CDK_MINTD_LN_MAX_MELT";
+/// Comma separated names of backend groups, e.g. `cln_sat,lnbits_usd`
+///
+/// Each group is configured with the `CDK_MINTD_<GROUP>_*` variables, where the suffixes
+/// are those of the `CDK_MINTD_LN_*` variables and of the group's backend variables,
+/// e.g. `CDK_MINTD_CLN_SAT_MAX_MINT` and `CDK_MINTD_CLN_SAT_RPC_PATH`.
+pub const ENV_LN_BACKENDS: &str = "CDK_MINTD_LN_BACKENDS";
+
+/// Parses a unit name, rejecting anything that is not a known unit
+fn parse_unit(unit: &str) -> Result<CurrencyUnit> {
+    match CurrencyUnit::from_str(unit) {
+        Ok(CurrencyUnit::Custom(_)) | Err(_) => bail!("Unknown unit: {unit}"),
+        Ok(unit) => Ok(unit),
+    }
+}
+
+/// Splits a group name into its backend and the unit suffix after it
+///
+/// The longest leading `<backend>` followed by `_` that names a known backend wins, so
+/// the unit is whatever follows it.
+fn split_group(group: &str) -> (LnBackend, Option<&str>) {
+    if let Ok(backend) = group.parse() {
+        return (backend, None);
+    }
+
+    group
+        .match_indices('_')
+        .rev()
+        .find_map(|(index, _)| {
+            group[..index]
+                .parse()
+                .ok()
+                .map(|backend| (backend, Some(&group[index + 1..])))
+        })
+        .unwrap_or((LnBackend::None, None))
+}
 
 impl Ln {
-    pub fn from_env(mut self) -> Self {
+    pub fn from_env(self) -> Result<Self> {
+        self.from_env_with_prefix(ENV_LN_PREFIX)
+    }
+
+    /// Reads the variables with `CDK_MINTD_LN` replaced by `prefix`
+    pub fn from_env_with_prefix(mut self, prefix: &str) -> Result<Self> {
+        let var = |key: &str| env::var(key.replacen(ENV_LN_PREFIX, prefix, 1));
+
         // LnBackend
-        if let Ok(backend_str) = env::var(ENV_LN_BACKEND) {
+        if let Ok(backend_str) = var(ENV_LN_BACKEND) {
             if let Ok(backend) = backend_str.parse() {
                 self.ln_backend = backend;
             } else {
@@ -23,36 +71,138 @@ impl Ln {
             }
         }
 
+        // Unit
+        if let Ok(unit_str) = var(ENV_LN_UNIT) {
+            self.unit =
+                parse_unit(&unit_str).map_err(|err| anyhow!("Invalid {prefix}_UNIT: {err}"))?;
+        }
+
         // Optional invoice description
-        if let Ok(description) = env::var(ENV_LN_INVOICE_DESCRIPTION) {
+        if let Ok(description) = var(ENV_LN_INVOICE_DESCRIPTION) {
             self.invoice_description = Some(description);
         }
 
         // Amount fields
-        if let Ok(min_mint_str) = env::var(ENV_LN_MIN_MINT) {
+        if let Ok(min_mint_str) = var(ENV_LN_MIN_MINT) {
             if let Ok(amount) = min_mint_str.parse::<u64>() {
                 self.min_mint = amount.into();
             }
         }
 
-        if let Ok(max_mint_str) = env::var(ENV_LN_MAX_MINT) {
+        if let Ok(max_mint_str) = var(ENV_LN_MAX_MINT) {
             if let Ok(amount) = max_mint_str.parse::<u64>() {
                 self.max_mint = amount.into();
             }
         }
 
-        if let Ok(min_melt_str) = env::var(ENV_LN_MIN_MELT) {
+        if let Ok(min_melt_str) = var(ENV_LN_MIN_MELT) {
             if let Ok(amount) = min_melt_str.parse::<u64>() {
                 self.min_melt = amount.into();
             }
         }
 
-        if let Ok(max_melt_str) = env::var(ENV_LN_MAX_MELT) {
+        if let Ok(max_melt_str) = var(ENV_LN_MAX_MELT) {
             if let Ok(amount) = max_melt_str.parse::<u64>() {
                 self.max_melt = amount.into();
             }
         }
 
-        self
+        Ok(self)
+    }
+}
+
+impl Settings {
+    /// Replaces the `[[ln]]` entries with the groups named in [`ENV_LN_BACKENDS`]
+    ///
+    /// A group named `<backend>_<unit>` defaults to that backend and unit, both can be
+    /// overridden with `CDK_MINTD_<GROUP>_BACKEND` and `CDK_MINTD_<GROUP>_UNIT`. Backend
+    /// settings of the group are applied to the shared section of its backend.
+    pub(crate) fn ln_backend_groups_from_env(&mut self, groups: &str) -> Result<()> {
+        let mut ln = Vec::new();
+
+        for group in groups.split(',').map(str::trim).filter(|g| !g.is_empty()) {
+            let prefix = format!("CDK_MINTD_{}", group.to_uppercase());
+            let (backend, unit) = split_group(group);
+
+            let default = Ln {
+                ln_backend: backend,
+                unit: unit
+                    .map(parse_unit)
+                    .transpose()
+                    .map_err(|err| anyhow!("Invalid unit in backend group {group}: {err}"))?
+                    .unwrap_or_default(),
+                ..Default::default()
+            };
+            let entry = default.from_env_with_prefix(&prefix)?;
+
+            match entry.ln_backend {
+                #[cfg(feature = "cln")]
+                LnBackend::Cln => {
+                    self.cln = Some(
+                        self.cln
+                            .clone()
+                            .unwrap_or_default()
+                            .from_env_with_prefix(&prefix),
+                    );
+                }
+                #[cfg(feature = "lnbits")]
+                LnBackend::LNbits => {
+                    self.lnbits = Some(
+                        self.lnbits
+                            .clone()
+                            .unwrap_or_default()
+                            .from_env_with_prefix(&prefix),
+                    );
+                }
+                #[cfg(feature = "lnd")]
+                LnBackend::Lnd => {
+                    self.lnd = Some(
+                        self.lnd
+                            .clone()
+                            .unwrap_or_default()
+                            .from_env_with_prefix(&prefix),
+                    );
+                }
+                #[cfg(feature = "fakewallet")]
+                LnBackend::FakeWallet => {
+                    self.fake_wallet = Some(
+                        self.fake_wallet
+                            .clone()
+                            .unwrap_or_default()
+                            .from_env_with_prefix(&prefix),
+                    );
+                }
+                #[cfg(feature = "ldk-node")]
+                LnBackend::LdkNode => {
+                    self.ldk_node = Some(
+                        self.ldk_node
+                            .clone()
+                            .unwrap_or_default()
+                            .from_env_with_prefix(&prefix),
+                    );
+                }
+                #[cfg(feature = "grpc-processor")]
+                LnBackend::GrpcProcessor => {
+                    self.grpc_processor = Some(
+                        self.grpc_processor
+                            .clone()
+                            .unwrap_or_default()
+                            .from_env_with_prefix(&prefix),
+                    );
+                }
+                LnBackend::None => {
+                    bail!("No backend for group {group}, name it <backend>_<unit> or set {prefix}_BACKEND");
+                }
+            }
+
+            ln.push(entry);
+        }
+
+        if ln.is_empty() {
+            bail!("{ENV_LN_BACKENDS} does not name any backend group");
+        }
+        self.ln = ln;
+
+        Ok(())
     }
 }
@@ -5,38 +5,56 @@ use std::env;
 use crate::config::LNbits;
 
 // LNBits environment variables
+pub const ENV_LNBITS_PREFIX: &str = "CDK_MINTD_LNBITS";
 pub const ENV_LNBITS_ADMIN_API_KEY: &str = "CDK_MINTD_LNBITS_ADMIN_API_KEY";
 pub const ENV_LNBITS_INVOICE_API_KEY: &str = "CDK_MINTD_LNBITS_INVOICE_API_KEY";
 pub const ENV_LNBITS_API: &str = "CDK_MINTD_LNBITS_API";
 pub const ENV_LNBITS_FEE_PERCENT: &str = "CDK_MINTD_LNBITS_FEE_PERCENT";
 pub const ENV_LNBITS_RESERVE_FEE_MIN: &str = "CDK_MINTD_LNBITS_RESERVE_FEE_MIN";
+pub const ENV_LNBITS_WEBHOOK_URL: &str = "CDK_MINTD_LNBITS_WEBHOOK_URL";
+pub const ENV_LNBITS_WEBHOOK_SECRET: &str = "CDK_MINTD_LNBITS_WEBHOOK_SECRET";
 
 impl LNbits {
-    pub fn from_env(mut self) -> Self {
-        if let Ok(admin_key) = env::var(ENV_LNBITS_ADMIN_API_KEY) {
+    pub fn from_env(self) -> Self {
+        self.from_env_with_prefix(ENV_LNBITS_PREFIX)
+    }
+
+    /// Reads the variables with `CDK_MINTD_LNBITS` replaced by `prefix`
+    pub fn from_env_with_prefix(mut self, prefix: &str) -> Self {
+        let var = |key: &str| env::var(key.replacen(ENV_LNBITS_PREFIX, prefix, 1));
+
+        if let Ok(admin_key) = var(ENV_LNBITS_ADMIN_API_KEY) {
             self.admin_api_key = admin_key;
         }
 
-        if let Ok(invoice_key) = env::var(ENV_LNBITS_INVOICE_API_KEY) {
+        if let Ok(invoice_key) = var(ENV_LNBITS_INVOICE_API_KEY) {
             self.invoice_api_key = invoice_key;
         }
 
-        if let Ok(api) = env::var(ENV_LNBITS_API) {
+        if let Ok(api) = var(ENV_LNBITS_API) {
             self.lnbits_api = api;
         }
 
-        if let Ok(fee_str) = env::var(ENV_LNBITS_FEE_PERCENT) {
+        if let Ok(fee_str) = var(ENV_LNBITS_FEE_PERCENT) {
             if let Ok(fee) = fee_str.parse() {
                 self.fee_percent = fee;
             }
         }
 
-        if let Ok(reserve_fee_str) = env::var(ENV_LNBITS_RESERVE_FEE_MIN) {
+        if let Ok(reserve_fee_str) = var(ENV_LNBITS_RESERVE_FEE_MIN) {
             if let Ok(reserve_fee) = reserve_fee_str.parse::<u64>() {
                 self.reserve_fee_min = reserve_fee.into();
             }
         }
 
+        if let Ok(webhook_url) = var(ENV_LNBITS_WEBHOOK_URL) {
+            self.webhook_url = Some(webhook_url);
+        }
+
+        if let Ok(webhook_secret) = var(ENV_LNBITS_WEBHOOK_SECRET) {
+            self.webhook_secret = Some(webhook_secret);
+        }
+
         self
     }
 }
@@ -6,38 +6,53 @@ use std::path::PathBuf;
 use crate::config::Lnd;
 
 // LND environment variables
+pub const ENV_LND_PREFIX: &str = "CDK_MINTD_LND";
 pub const ENV_LND_ADDRESS: &str = "CDK_MINTD_LND_ADDRESS";
 pub const ENV_LND_CERT_FILE: &str = "CDK_MINTD_LND_CERT_FILE";
 pub const ENV_LND_MACAROON_FILE: &str = "CDK_MINTD_LND_MACAROON_FILE";
 pub const ENV_LND_FEE_PERCENT: &str = "CDK_MINTD_LND_FEE_PERCENT";
 pub const ENV_LND_RESERVE_FEE_MIN: &str = "CDK_MINTD_LND_RESERVE_FEE_MIN";
+pub const ENV_LND_KEYSEND: &str = "CDK_MINTD_LND_KEYSEND";
 
 impl Lnd {
-    pub fn from_env(mut self) -> Self {
-        if let Ok(address) = env::var(ENV_LND_ADDRESS) {
+    pub fn from_env(self) -> Self {
+        self.from_env_with_prefix(ENV_LND_PREFIX)
+    }
+
+    /// Reads the variables with `CDK_MINTD_LND` replaced by `prefix`
+    pub fn from_env_with_prefix(mut self, prefix: &str) -> Self {
+        let var = |key: &str| env::var(key.replacen(ENV_LND_PREFIX, prefix, 1));
+
+        if let Ok(address) = var(ENV_LND_ADDRESS) {
             self.address = address;
         }
 
-        if let Ok(cert_path) = env::var(ENV_LND_CERT_FILE) {
+        if let Ok(cert_path) = var(ENV_LND_CERT_FILE) {
             self.cert_file = PathBuf::from(cert_path);
         }
 
-        if let Ok(macaroon_path) = env::var(ENV_LND_MACAROON_FILE) {
+        if let Ok(macaroon_path) = var(ENV_LND_MACAROON_FILE) {
             self.macaroon_file = PathBuf::from(macaroon_path);
         }
 
-        if let Ok(fee_str) = env::var(ENV_LND_FEE_PERCENT) {
+        if let Ok(fee_str) = var(ENV_LND_FEE_PERCENT) {
             if let Ok(fee) = fee_str.parse() {
                 self.fee_percent = fee;
             }
         }
 
-        if let Ok(reserve_fee_str) = env::var(ENV_LND_RESERVE_FEE_MIN) {
+        if let Ok(reserve_fee_str) = var(ENV_LND_RESERVE_FEE_MIN) {
             if let Ok(reserve_fee) = reserve_fee_str.parse::<u64>() {
                 self.reserve_fee_min = reserve_fee.into();
             }
         }
 
+        if let Ok(keysend_str) = var(ENV_LND_KEYSEND) {
+            if let Ok(keysend) = keysend_str.parse() {
+                self.keysend = keysend;
+            }
+        }
+
         self
     }
 }
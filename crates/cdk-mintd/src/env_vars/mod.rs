@@ -7,11 +7,13 @@
 mod common;
 mod database;
 mod info;
+mod lightning_address;
 mod limits;
 mod ln;
 mod mint_info;
 mod onchain;
 mod signatory;
+mod webhooks;
 
 mod auth;
 #[cfg(feature = "bdk")]
@@ -50,6 +52,7 @@ pub use fake_wallet::*;
 pub use grpc_processor::*;
 #[cfg(feature = "ldk-node")]
 pub use ldk_node::*;
+pub use lightning_address::*;
 pub use limits::*;
 pub use ln::*;
 #[cfg(feature = "lnbits")]
@@ -62,6 +65,7 @@ pub use mint_info::*;
 pub use onchain::*;
 #[cfg(feature = "prometheus")]
 pub use prometheus::*;
+pub use webhooks::*;
 
 use crate::config::{DatabaseEngine, Ln, LnBackend, OnchainBackend, Settings};
 
@@ -72,6 +76,12 @@ impl Settings {
             self.database.engine = engine;
         }
 
+        if let Ok(retention_days) = env::var(ENV_SPENT_PROOF_RETENTION_DAYS) {
+            if let Ok(parsed) = retention_days.parse::<u64>() {
+                self.database.spent_proof_retention_days = Some(parsed);
+            }
+        }
+
         // Parse PostgreSQL-specific configuration from environment variables
         if self.database.engine == DatabaseEngine::Postgres {
             self.database.postgres = Some(
@@ -95,27 +105,33 @@ impl Settings {
             ),
         });
 
-        self.info = self.info.clone().from_env();
+        self.info = self.info.clone().from_env()?;
         self.signatory = Some(self.signatory.clone().unwrap_or_default().from_env());
 
         self.mint_info = self.mint_info.clone().from_env();
         // CDK_MINTD_LN_* env vars only apply when there is exactly one
         // configured Lightning entry. Multi-backend setups must choose units
-        // and backends in the config file so env overrides do not collapse them.
-        match self.ln.len() {
-            0 => {
-                let ln = Ln::default().from_env();
-                if ln.ln_backend != LnBackend::None {
-                    self.ln.push(ln);
+        // and backends in the config file or as named groups in
+        // CDK_MINTD_LN_BACKENDS so env overrides do not collapse them.
+        let ln_backend_groups = env::var(ENV_LN_BACKENDS).ok();
+        if let Some(groups) = ln_backend_groups.as_deref() {
+            self.ln_backend_groups_from_env(groups)?;
+        } else {
+            match self.ln.len() {
+                0 => {
+                    let ln = Ln::default().from_env()?;
+                    if ln.ln_backend != LnBackend::None {
+                        self.ln.push(ln);
+                    }
+                }
+                1 => {
+                    self.ln[0] = self.ln[0].clone().from_env()?;
+                }
+                _ => {
+                    tracing::warn!(
+                        "CDK_MINTD_LN_* environment variables ignored: multiple [[ln]] entries configured"
+                    );
                 }
-            }
-            1 => {
-                self.ln[0] = self.ln[0].clone().from_env();
-            }
-            _ => {
-                tracing::warn!(
-                    "CDK_MINTD_LN_* environment variables ignored: multiple [[ln]] entries configured"
-                );
             }
         }
         self.onchain = Some(self.onchain.clone().unwrap_or_default().from_env());
@@ -148,6 +164,28 @@ impl Settings {
             self.prometheus = Some(self.prometheus.clone().unwrap_or_default().from_env());
         }
 
+        {
+            let webhooks = self.webhooks.clone().unwrap_or_default().from_env();
+            if webhooks.urls.is_empty() {
+                self.webhooks = None;
+            } else {
+                self.webhooks = Some(webhooks);
+            }
+        }
+
+        {
+            let lightning_address = self
+                .lightning_address
+                .clone()
+                .unwrap_or_default()
+                .from_env();
+            if lightning_address.users.is_empty() {
+                self.lightning_address = None;
+            } else {
+                self.lightning_address = Some(lightning_address);
+            }
+        }
+
         #[cfg(feature = "cln")]
         {
             let cln = self.cln.clone().unwrap_or_default().from_env();
@@ -177,7 +215,9 @@ impl Settings {
             let supported_units_configured =
                 fake_wallet.supported_units != vec![cdk::nuts::CurrencyUnit::Sat];
 
-            if fake_wallet_supported_units_from_env || supported_units_configured {
+            if ln_backend_groups.is_none()
+                && (fake_wallet_supported_units_from_env || supported_units_configured)
+            {
                 self.expand_single_fake_wallet_ln_entry(&fake_wallet);
             }
 
@@ -0,0 +1,61 @@
+//! Webhook environment variables
+
+use std::env;
+
+use crate::config::{WebhookEventKind, Webhooks};
+
+pub const ENV_WEBHOOK_URLS: &str = "CDK_MINTD_WEBHOOK_URLS";
+pub const ENV_WEBHOOK_SECRET: &str = "CDK_MINTD_WEBHOOK_SECRET";
+pub const ENV_WEBHOOK_EVENTS: &str = "CDK_MINTD_WEBHOOK_EVENTS";
+pub const ENV_WEBHOOK_LIABILITY_THRESHOLD: &str = "CDK_MINTD_WEBHOOK_LIABILITY_THRESHOLD";
+pub const ENV_WEBHOOK_MAX_ATTEMPTS: &str = "CDK_MINTD_WEBHOOK_MAX_ATTEMPTS";
+pub const ENV_WEBHOOK_POLL_INTERVAL_SECS: &str = "CDK_MINTD_WEBHOOK_POLL_INTERVAL_SECS";
+
+impl Webhooks {
+    pub fn from_env(mut self) -> Self {
+        if let Ok(urls) = env::var(ENV_WEBHOOK_URLS) {
+            self.urls = urls
+                .split(',')
+                .map(|url| url.trim().to_string())
+                .filter(|url| !url.is_empty())
+                .collect();
+        }
+
+        if let Ok(secret) = env::var(ENV_WEBHOOK_SECRET) {
+            self.secret = secret;
+        }
+
+        if let Ok(events) = env::var(ENV_WEBHOOK_EVENTS) {
+            self.events = events
+                .split(',')
+                .filter_map(|event| {
+                    serde_json::from_value::<WebhookEventKind>(serde_json::Value::String(
+                        event.trim().to_string(),
+                    ))
+                    .inspect_err(|_| tracing::warn!("Unknown webhook event: {}", event))
+                    .ok()
+                })
+                .collect();
+        }
+
+        if let Ok(threshold_str) = env::var(ENV_WEBHOOK_LIABILITY_THRESHOLD) {
+            if let Ok(threshold) = threshold_str.parse() {
+                self.liability_threshold = Some(threshold);
+            }
+        }
+
+        if let Ok(max_attempts_str) = env::var(ENV_WEBHOOK_MAX_ATTEMPTS) {
+            if let Ok(max_attempts) = max_attempts_str.parse() {
+                self.max_attempts = max_attempts;
+            }
+        }
+
+        if let Ok(interval_str) = env::var(ENV_WEBHOOK_POLL_INTERVAL_SECS) {
+            if let Ok(interval) = interval_str.parse() {
+                self.poll_interval_secs = interval;
+            }
+        }
+
+        self
+    }
+}
@@ -14,8 +14,8 @@ use anyhow::{anyhow, bail, Context, Result};
 use axum::extract::DefaultBodyLimit;
 use axum::Router;
 use bip39::Mnemonic;
-use cdk::cdk_database::{self, KVStore, MintDatabase, MintKeysDatabase};
-use cdk::mint::{Mint, MintBuilder, MintMeltLimits};
+use cdk::cdk_database::{self, KVStore, MigrationStatus, MintDatabase, MintKeysDatabase};
+use cdk::mint::{AuthQuotas, Mint, MintBuilder, MintMeltLimits};
 use cdk::nuts::nut00::KnownMethod;
 #[cfg(any(
     feature = "cln",
@@ -52,14 +52,16 @@ use tower::ServiceBuilder;
 use tower_http::compression::CompressionLayer;
 use tower_http::decompression::RequestDecompressionLayer;
 use tower_http::trace::TraceLayer;
-use tracing_appender::{non_blocking, rolling};
-use tracing_subscriber::fmt::writer::MakeWriterExt;
-use tracing_subscriber::EnvFilter;
 
+pub mod check_config;
 pub mod cli;
 pub mod config;
 pub mod env_vars;
+mod logging;
 pub mod setup;
+mod webhooks;
+
+pub use logging::{setup_tracing, LoggingGuard};
 
 #[cfg(test)]
 pub(crate) mod test_utils {
@@ -131,124 +133,6 @@ async fn initial_setup(
     Ok((localstore, keystore, kv))
 }
 
-/// Sets up and initializes a tracing subscriber with custom log filtering.
-/// Logs can be configured to output to stdout only, file only, or both.
-/// Returns a guard that must be kept alive and properly dropped on shutdown.
-pub fn setup_tracing(
-    work_dir: &Path,
-    logging_config: &config::LoggingConfig,
-) -> Result<Option<tracing_appender::non_blocking::WorkerGuard>> {
-    let default_filter = "debug";
-    let hyper_filter = "hyper=warn,rustls=warn,reqwest=warn";
-    let h2_filter = "h2=warn";
-    let tower_filter = "tower=warn";
-    let tower_http = "tower_http=warn";
-    let rustls = "rustls=warn";
-    let tungstenite = "tungstenite=warn";
-    let tokio_postgres = "tokio_postgres=warn";
-
-    let env_filter = EnvFilter::new(format!(
-        "{default_filter},{hyper_filter},{h2_filter},{tower_filter},{tower_http},{rustls},{tungstenite},{tokio_postgres}"
-    ));
-
-    use config::LoggingOutput;
-    match logging_config.output {
-        LoggingOutput::Stderr => {
-            // Console output only (stderr)
-            let console_level = logging_config
-                .console_level
-                .as_deref()
-                .unwrap_or("info")
-                .parse::<tracing::Level>()
-                .unwrap_or(tracing::Level::INFO);
-
-            let stderr = std::io::stderr.with_max_level(console_level);
-
-            tracing_subscriber::fmt()
-                .with_env_filter(env_filter)
-                .with_ansi(false)
-                .with_writer(stderr)
-                .init();
-
-            tracing::info!("Logging initialized: console only ({}+)", console_level);
-            Ok(None)
-        }
-        LoggingOutput::File => {
-            // File output only
-            let file_level = logging_config
-                .file_level
-                .as_deref()
-                .unwrap_or("debug")
-                .parse::<tracing::Level>()
-                .unwrap_or(tracing::Level::DEBUG);
-
-            // Create logs directory in work_dir if it doesn't exist
-            let logs_dir = work_dir.join("logs");
-            std::fs::create_dir_all(&logs_dir)?;
-
-            // Set up file appender with daily rotation
-            let file_appender = rolling::daily(&logs_dir, "cdk-mintd.log");
-            let (non_blocking_appender, guard) = non_blocking(file_appender);
-
-            let file_writer = non_blocking_appender.with_max_level(file_level);
-
-            tracing_subscriber::fmt()
-                .with_env_filter(env_filter)
-                .with_ansi(false)
-                .with_writer(file_writer)
-                .init();
-
-            tracing::info!(
-                "Logging initialized: file only at {}/cdk-mintd.log ({}+)",
-                logs_dir.display(),
-                file_level
-            );
-            Ok(Some(guard))
-        }
-        LoggingOutput::Both => {
-            // Both console and file output (stderr + file)
-            let console_level = logging_config
-                .console_level
-                .as_deref()
-                .unwrap_or("info")
-                .parse::<tracing::Level>()
-                .unwrap_or(tracing::Level::INFO);
-            let file_level = logging_config
-                .file_level
-                .as_deref()
-                .unwrap_or("debug")
-                .parse::<tracing::Level>()
-                .unwrap_or(tracing::Level::DEBUG);
-
-            // Create logs directory in work_dir if it doesn't exist
-            let logs_dir = work_dir.join("logs");
-            std::fs::create_dir_all(&logs_dir)?;
-
-            // Set up file appender with daily rotation
-            let file_appender = rolling::daily(&logs_dir, "cdk-mintd.log");
-            let (non_blocking_appender, guard) = non_blocking(file_appender);
-
-            // Combine console output (stderr) and file output
-            let stderr = std::io::stderr.with_max_level(console_level);
-            let file_writer = non_blocking_appender.with_max_level(file_level);
-
-            tracing_subscriber::fmt()
-                .with_env_filter(env_filter)
-                .with_ansi(false)
-                .with_writer(stderr.and(file_writer))
-                .init();
-
-            tracing::info!(
-                "Logging initialized: console ({}+) and file at {}/cdk-mintd.log ({}+)",
-                console_level,
-                logs_dir.display(),
-                file_level
-            );
-            Ok(Some(guard))
-        }
-    }
-}
-
 /// Retrieves the work directory based on command-line arguments, environment variables, or system defaults.
 pub async fn get_work_directory(args: &CLIArgs) -> Result<PathBuf> {
     let work_dir = if let Some(work_dir) = &args.work_dir {
@@ -303,6 +187,7 @@ fn validate_settings(settings: &config::Settings) -> Result<()> {
     validate_auth_config(settings)?;
     validate_management_rpc_config(settings)?;
     validate_prometheus_config(settings)?;
+    validate_webhooks_config(settings)?;
 
     Ok(())
 }
@@ -587,6 +472,32 @@ fn validate_prometheus_config(settings: &config::Settings) -> Result<()> {
     Ok(())
 }
 
+fn validate_webhooks_config(settings: &config::Settings) -> Result<()> {
+    let Some(webhooks) = settings.webhooks.as_ref() else {
+        return Ok(());
+    };
+
+    if webhooks.poll_interval_secs == 0 {
+        bail!("Webhook poll interval [webhooks].poll_interval_secs/CDK_MINTD_WEBHOOK_POLL_INTERVAL_SECS must be greater than 0");
+    }
+
+    if webhooks.secret.is_empty() {
+        bail!("Webhook secret [webhooks].secret/CDK_MINTD_WEBHOOK_SECRET must not be empty");
+    }
+
+    for url in &webhooks.urls {
+        let parsed = url::Url::parse(url).map_err(|err| {
+            anyhow!("Invalid webhook url in [webhooks].urls/CDK_MINTD_WEBHOOK_URLS ({url}): {err}")
+        })?;
+
+        if !matches!(parsed.scheme(), "http" | "https") {
+            bail!("Invalid webhook url in [webhooks].urls/CDK_MINTD_WEBHOOK_URLS ({url}): scheme must be http or https");
+        }
+    }
+
+    Ok(())
+}
+
 /// Loads settings from command line arguments, environment variables, and optional seed file.
 pub fn load_settings_from_args(work_dir: &Path, args: &CLIArgs) -> Result<config::Settings> {
     let mut settings = load_settings_from_sources(work_dir, args.config.clone())?;
@@ -595,6 +506,10 @@ pub fn load_settings_from_args(work_dir: &Path, args: &CLIArgs) -> Result<config
         apply_seed_file(&mut settings, seed_file)?;
     }
 
+    if args.skip_checks {
+        settings.info.skip_checks = true;
+    }
+
     validate_settings(&settings)?;
 
     Ok(settings)
@@ -707,6 +622,72 @@ async fn setup_database(
     }
 }
 
+/// Export the accounting ledger for `args` from the mint database
+///
+/// Only the database is opened, so the ledger can be exported while the mint is
+/// stopped or its payment backends are unreachable.
+pub async fn export_ledger(
+    settings: &config::Settings,
+    work_dir: &Path,
+    db_password: Option<String>,
+    args: &cli::ExportLedgerArgs,
+) -> Result<String> {
+    let format = cdk::mint::LedgerFormat::from_str(&args.format)?;
+    let fee_unit = cdk::nuts::CurrencyUnit::from_str(&args.fee_unit)?;
+    let to = args.to.unwrap_or_else(cdk::util::unix_time);
+
+    let (localstore, _keystore, _kv) = setup_database(settings, work_dir, db_password).await?;
+    let entries = cdk::mint::ledger_entries(&localstore, args.from, to, &fee_unit).await?;
+
+    Ok(cdk::mint::format_ledger(&entries, format))
+}
+
+/// Status of the mint database migrations, without applying any
+pub async fn migration_status(
+    settings: &config::Settings,
+    _work_dir: &Path,
+    _db_password: Option<String>,
+) -> Result<Vec<MigrationStatus>> {
+    match settings.database.engine {
+        #[cfg(feature = "sqlite")]
+        DatabaseEngine::Sqlite => {
+            let sql_db_path = _work_dir.join("cdk-mintd.sqlite");
+            #[cfg(not(feature = "sqlcipher"))]
+            let statuses = MintSqliteDatabase::inspect_migrations(&sql_db_path).await?;
+            #[cfg(feature = "sqlcipher")]
+            let statuses = {
+                let password = _db_password.ok_or_else(|| {
+                    anyhow!("Password required when sqlcipher feature is enabled")
+                })?;
+                MintSqliteDatabase::inspect_migrations((sql_db_path, password)).await?
+            };
+            Ok(statuses)
+        }
+        #[cfg(feature = "postgres")]
+        DatabaseEngine::Postgres => {
+            let pg_config = settings.database.postgres.as_ref().ok_or_else(|| {
+                anyhow!("PostgreSQL configuration is required when using PostgreSQL engine")
+            })?;
+
+            let db_config = PgConfig::new(
+                pg_config.url.as_str(),
+                pg_config.tls_mode.as_deref(),
+                pg_config.max_connections,
+                pg_config.connection_timeout_seconds,
+            );
+            Ok(MintPgDatabase::inspect_migrations(db_config).await?)
+        }
+        #[cfg(not(feature = "sqlite"))]
+        DatabaseEngine::Sqlite => {
+            bail!("SQLite support not compiled in. Enable the 'sqlite' feature to use SQLite database.")
+        }
+        #[cfg(not(feature = "postgres"))]
+        DatabaseEngine::Postgres => {
+            bail!("PostgreSQL support not compiled in. Enable the 'postgres' feature to use PostgreSQL database.")
+        }
+    }
+}
+
 #[cfg(feature = "sqlite")]
 async fn setup_sqlite_database(
     work_dir: &Path,
@@ -730,6 +711,18 @@ async fn setup_sqlite_database(
     Ok(Arc::new(db))
 }
 
+/// Services payment backends expose next to the mint
+#[derive(Default)]
+struct BackendServices {
+    /// Additional HTTP routes merged into the mint router
+    routers: Vec<Router>,
+    /// Hooks run around every request to the mint router
+    middleware: Vec<Arc<dyn cdk_axum::MintMiddleware>>,
+    /// Failure injection controls shared by all fake wallets
+    #[cfg(feature = "fakewallet")]
+    fake_wallet_failure_injection: Option<cdk_fake_wallet::FailureInjection>,
+}
+
 /**
  * Configures a `MintBuilder` instance with provided settings and initializes
  * routers for Lightning Network backends.
@@ -740,6 +733,7 @@ async fn configure_mint_builder(
     runtime: Option<std::sync::Arc<tokio::runtime::Runtime>>,
     work_dir: &Path,
     kv_store: Option<Arc<dyn KVStore<Err = cdk::cdk_database::Error> + Send + Sync>>,
+    services: &mut BackendServices,
 ) -> Result<MintBuilder> {
     settings
         .validate_backend_pairing()
@@ -777,6 +771,7 @@ async fn configure_mint_builder(
         runtime.clone(),
         work_dir,
         kv_store.clone(),
+        services,
     )
     .await?;
 
@@ -796,8 +791,9 @@ async fn configure_mint_builder(
     let mint_builder = configure_cache(settings, mint_builder, &payment_methods).await?;
 
     // Configure transaction limits
-    let mint_builder =
-        mint_builder.with_limits(settings.limits.max_inputs, settings.limits.max_outputs);
+    let mint_builder = mint_builder
+        .with_limits(settings.limits.max_inputs, settings.limits.max_outputs)
+        .with_signing_threads(settings.limits.signing_threads);
 
     // Verify at least one payment processor is configured
     if mint_builder
@@ -880,7 +876,12 @@ fn configure_basic_info(settings: &config::Settings, mint_builder: MintBuilder)
         }
     }
 
-    builder = builder.with_keyset_v2(settings.info.use_keyset_v2);
+    builder = builder
+        .with_keyset_v2(settings.info.use_keyset_v2)
+        .with_quote_id_format(settings.info.quote_id_format)
+        .with_strict_spending_conditions(settings.info.strict_spending_conditions)
+        .with_melt_change_policy(settings.info.melt_change_policy)
+        .with_own_node_ids(settings.info.own_node_ids.clone());
 
     builder
 }
@@ -891,6 +892,11 @@ async fn configure_lightning_backend(
     _runtime: Option<std::sync::Arc<tokio::runtime::Runtime>>,
     work_dir: &Path,
     _kv_store: Option<Arc<dyn KVStore<Err = cdk::cdk_database::Error> + Send + Sync>>,
+    #[cfg_attr(
+        not(any(feature = "lnbits", feature = "fakewallet")),
+        allow(unused_variables)
+    )]
+    services: &mut BackendServices,
 ) -> Result<MintBuilder> {
     if settings.ln.is_empty() {
         tracing::info!("No Lightning backend configured");
@@ -949,6 +955,9 @@ async fn configure_lightning_backend(
                 let lnbits = lnbits_settings
                     .setup(settings, ln_entry.unit.clone(), None, work_dir, None)
                     .await?;
+                if let Some(router) = lnbits.webhook_router() {
+                    services.routers.push(router);
+                }
                 #[cfg(feature = "prometheus")]
                 let lnbits = MetricsMintPayment::new(lnbits);
 
@@ -1005,6 +1014,12 @@ async fn configure_lightning_backend(
                         _kv_store.clone(),
                     )
                     .await?;
+                // All fake wallets share one set of failure injection controls
+                let failure_injection = services
+                    .fake_wallet_failure_injection
+                    .get_or_insert_with(|| fake.failure_injection())
+                    .clone();
+                let fake = fake.with_failure_injection(failure_injection);
                 #[cfg(feature = "prometheus")]
                 let fake = MetricsMintPayment::new(fake);
 
@@ -1115,6 +1130,7 @@ fn configure_fake_wallet_keyset_rotations_once(
             input_fee_ppk: rotation_cfg.input_fee_ppk,
             use_keyset_v2: rotation_cfg.version == "v2",
             final_expiry,
+            activate_at: None,
         });
     }
 
@@ -1299,6 +1315,19 @@ async fn configure_backend_for_methods(
         mint_builder.set_unit_fee(&unit, input_fee)?;
     }
 
+    match (settings.info.max_order, settings.info.amounts.clone()) {
+        (Some(_), Some(_)) => bail!("Only one of max_order and amounts can be set"),
+        (Some(max_order), None) => {
+            if max_order > 63 {
+                bail!("max_order must be at most 63, got {}", max_order);
+            }
+            let amounts = (0..=u32::from(max_order)).map(|i| 2_u64.pow(i)).collect();
+            mint_builder.set_unit_amounts(&unit, amounts)?;
+        }
+        (None, Some(amounts)) => mint_builder.set_unit_amounts(&unit, amounts)?,
+        (None, None) => {}
+    }
+
     Ok(mint_builder)
 }
 
@@ -1510,6 +1539,11 @@ async fn setup_authentication(
         );
         mint_builder =
             mint_builder.with_blind_auth(auth_settings.mint_max_bat, blind_auth_endpoints);
+        mint_builder = mint_builder.with_auth_quotas(AuthQuotas {
+            max_mint_per_day: auth_settings.max_mint_per_day.map(cdk::Amount::from),
+            max_melt_per_day: auth_settings.max_melt_per_day.map(cdk::Amount::from),
+            max_blind_auth_per_day: auth_settings.max_bat_per_day,
+        });
 
         let mut tx = auth_localstore.begin_transaction().await?;
 
@@ -1581,13 +1615,47 @@ async fn build_mint(
     }
 }
 
+/// Unit conversion converting at the configured rates, in both directions of each rate
+#[cfg(feature = "conversion")]
+fn unit_conversion(config: &config::Conversion) -> Result<cdk::mint::UnitConversion> {
+    let mut rates = cdk_common::rate::StaticRates::new();
+    if let Some(ttl) = config.rate_ttl_secs {
+        rates = rates.with_ttl(ttl);
+    }
+    for rate in &config.rates {
+        rates = rates.with_rate(
+            rate.from.clone(),
+            rate.to.clone(),
+            rate.from_amount,
+            rate.to_amount,
+        )?;
+    }
+
+    let mut conversion =
+        cdk::mint::UnitConversion::new(Arc::new(rates)).with_spread_bps(config.spread_bps);
+    for rate in &config.rates {
+        tracing::info!(
+            "Converting between {} and {} at {}:{}",
+            rate.from,
+            rate.to,
+            rate.from_amount,
+            rate.to_amount
+        );
+        conversion = conversion
+            .with_pair(rate.from.clone(), rate.to.clone())
+            .with_pair(rate.to.clone(), rate.from.clone());
+    }
+
+    Ok(conversion)
+}
+
 async fn start_services_with_shutdown(
     mint: Arc<cdk::mint::Mint>,
     settings: &config::Settings,
     _work_dir: &Path,
     mint_builder_info: cdk::nuts::MintInfo,
     shutdown_signal: impl std::future::Future<Output = ()> + Send + 'static,
-    routers: Vec<Router>,
+    services: BackendServices,
     auth_localstore: Option<cdk_common::database::DynMintAuthDatabase>,
 ) -> Result<()> {
     let listen_addr = settings.info.listen_host.clone();
@@ -1610,6 +1678,11 @@ async fn start_services_with_shutdown(
                 let port = rpc_settings.port.unwrap_or(8086);
                 let mut mint_rpc = cdk_mint_rpc::MintRPCServer::new(&addr, port, mint.clone())?;
 
+                #[cfg(feature = "fakewallet")]
+                if let Some(failure_injection) = services.fake_wallet_failure_injection.clone() {
+                    mint_rpc = mint_rpc.with_fake_wallet_failure_injection(failure_injection);
+                }
+
                 let tls_dir = rpc_settings.tls_dir.unwrap_or(_work_dir.join("tls"));
 
                 let tls_dir = if tls_dir.exists() {
@@ -1648,6 +1721,8 @@ async fn start_services_with_shutdown(
             // First boot with RPC enabled: seed from config
             mint.set_mint_info(mint_builder_info).await?;
             mint.set_quote_ttl(desired_quote_ttl).await?;
+            mint.set_motd_schedule(settings.mint_info.motd_schedule.clone())
+                .await?;
         } else {
             // If QuoteTTL has never been persisted, seed it now from config
             if !mint.quote_ttl_is_persisted().await? {
@@ -1677,6 +1752,8 @@ async fn start_services_with_shutdown(
 
         mint.set_mint_info(mint_builder_info).await?;
         mint.set_quote_ttl(desired_quote_ttl).await?;
+        mint.set_motd_schedule(settings.mint_info.motd_schedule.clone())
+            .await?;
     }
 
     let mint_info = mint.mint_info().await?;
@@ -1867,11 +1944,12 @@ async fn start_services_with_shutdown(
         }
     }
 
-    let v1_service = cdk_axum::create_mint_router_with_custom_cache(
+    let v1_service = cdk_axum::create_mint_router_with_headers(
         Arc::clone(&mint),
         cache,
         custom_methods,
         settings.info.enable_info_page.unwrap_or(true),
+        settings.info.http_headers.clone(),
     )
     .await?;
 
@@ -1885,10 +1963,30 @@ async fn start_services_with_shutdown(
         )
         .layer(TraceLayer::new_for_http());
 
-    for router in routers {
+    for router in services.routers {
         mint_service = mint_service.merge(router);
     }
 
+    if let Some(lightning_address) = settings.lightning_address.clone() {
+        tracing::info!(
+            "Serving lightning addresses for {} users",
+            lightning_address.users.len()
+        );
+
+        mint_service = mint_service.merge(cdk_axum::create_lightning_address_router(
+            Arc::clone(&mint),
+            cdk_axum::LightningAddressConfig {
+                url: settings.info.url.clone(),
+                users: lightning_address.users,
+                min_sendable_msat: lightning_address.min_sendable_msat,
+                max_sendable_msat: lightning_address.max_sendable_msat,
+                max_invoices_per_minute: lightning_address.max_invoices_per_minute,
+            },
+        ));
+    }
+
+    mint_service = cdk_axum::with_middleware(mint_service, services.middleware);
+
     // Create a broadcast channel to share shutdown signal between services
     let (shutdown_tx, _) = tokio::sync::broadcast::channel::<()>(1);
 
@@ -1929,8 +2027,41 @@ async fn start_services_with_shutdown(
         }
     };
 
+    if settings.info.maintenance_mode {
+        mint.set_maintenance_mode(Some(
+            settings
+                .info
+                .maintenance_retry_after
+                .unwrap_or(cdk::mint::DEFAULT_MAINTENANCE_RETRY_AFTER),
+        ));
+    }
+
+    // Check the built mint before it recovers sagas or serves requests, so a mint that fails
+    // the checks never writes to the database or accepts ecash
+    let report = mint.self_check().await?;
+    if !report.is_ok() {
+        if settings.info.skip_checks {
+            tracing::warn!("Ignoring failed self check: {}", report);
+        } else {
+            bail!("Mint self check failed: {}", report);
+        }
+    }
+
     mint.start().await?;
 
+    let archive_handle = settings.database.spent_proof_retention_days.map(|days| {
+        spawn_spent_proof_archiver(
+            Arc::clone(&mint),
+            std::time::Duration::from_secs(days.saturating_mul(24 * 60 * 60)),
+            shutdown_tx.subscribe(),
+        )
+    });
+
+    let webhooks_handle = settings
+        .webhooks
+        .clone()
+        .map(|config| webhooks::spawn_webhooks(Arc::clone(&mint), config, shutdown_tx.subscribe()));
+
     let socket_addr = SocketAddr::from_str(&format!("{listen_addr}:{listen_port}"))?;
 
     let listener = tokio::net::TcpListener::bind(socket_addr).await?;
@@ -1938,10 +2069,29 @@ async fn start_services_with_shutdown(
     tracing::info!("listening on {}", listener.local_addr()?);
 
     // Create a task to wait for the shutdown signal and broadcast it
+    //
+    // Pending melts are drained first while the server keeps answering quote state requests.
     let shutdown_broadcast_task = {
         let shutdown_tx = shutdown_tx.clone();
+        let mint = Arc::clone(&mint);
+        let drain_timeout = std::time::Duration::from_secs(
+            settings
+                .info
+                .shutdown_timeout
+                .unwrap_or(DEFAULT_SHUTDOWN_TIMEOUT),
+        );
         tokio::spawn(async move {
             shutdown_signal.await;
+
+            match mint.drain_pending_melts(drain_timeout).await {
+                Ok(pending) if pending.is_empty() => {}
+                Ok(pending) => tracing::warn!(
+                    "Shutting down with {} melts still pending, they are recovered on restart",
+                    pending.len()
+                ),
+                Err(e) => tracing::error!("Failed to drain pending melts: {}", e),
+            }
+
             tracing::info!("Shutdown signal received, broadcasting to all services");
             let _ = shutdown_tx.send(());
         })
@@ -1954,7 +2104,11 @@ async fn start_services_with_shutdown(
     };
 
     // Wait for axum server to complete with custom shutdown signal
-    let axum_result = axum::serve(listener, mint_service).with_graceful_shutdown(axum_shutdown);
+    let axum_result = axum::serve(
+        listener,
+        mint_service.into_make_service_with_connect_info::<SocketAddr>(),
+    )
+    .with_graceful_shutdown(axum_shutdown);
 
     match axum_result.await {
         Ok(_) => {
@@ -1978,6 +2132,18 @@ async fn start_services_with_shutdown(
         }
     }
 
+    if let Some(handle) = archive_handle {
+        if let Err(e) = handle.await {
+            tracing::warn!("Spent proof archive task failed: {}", e);
+        }
+    }
+
+    if let Some(handle) = webhooks_handle {
+        if let Err(e) = handle.await {
+            tracing::warn!("Webhook task failed: {}", e);
+        }
+    }
+
     mint.stop().await?;
 
     #[cfg(feature = "management-rpc")]
@@ -1990,10 +2156,62 @@ async fn start_services_with_shutdown(
     Ok(())
 }
 
+/// How often spent proofs past the retention window are archived
+const SPENT_PROOF_ARCHIVE_INTERVAL: std::time::Duration = std::time::Duration::from_secs(60 * 60);
+
+/// Periodically archive spent proofs older than `retention` until shutdown
+fn spawn_spent_proof_archiver(
+    mint: Arc<Mint>,
+    retention: std::time::Duration,
+    mut shutdown_rx: tokio::sync::broadcast::Receiver<()>,
+) -> tokio::task::JoinHandle<()> {
+    tracing::info!(
+        "Archiving spent proofs older than {} days",
+        retention.as_secs() / (24 * 60 * 60)
+    );
+
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(SPENT_PROOF_ARCHIVE_INTERVAL);
+
+        loop {
+            tokio::select! {
+                _ = shutdown_rx.recv() => break,
+                _ = interval.tick() => {
+                    if let Err(e) = mint.archive_spent_proofs(retention).await {
+                        tracing::error!("Failed to archive spent proofs: {}", e);
+                    }
+                }
+            }
+        }
+    })
+}
+
+/// Seconds to wait for pending melts to resolve on shutdown when not configured
+const DEFAULT_SHUTDOWN_TIMEOUT: u64 = 30;
+
 async fn shutdown_signal() {
-    tokio::signal::ctrl_c()
-        .await
-        .expect("failed to install CTRL+C handler");
+    let ctrl_c = async {
+        tokio::signal::ctrl_c()
+            .await
+            .expect("failed to install CTRL+C handler");
+    };
+
+    #[cfg(unix)]
+    let terminate = async {
+        tokio::signal::unix::signal(tokio::signal::unix::SignalKind::terminate())
+            .expect("failed to install SIGTERM handler")
+            .recv()
+            .await;
+    };
+
+    #[cfg(not(unix))]
+    let terminate = std::future::pending::<()>();
+
+    tokio::select! {
+        _ = ctrl_c => {},
+        _ = terminate => {},
+    }
+
     tracing::info!("Shutdown signal received");
 }
 
@@ -2015,8 +2233,8 @@ pub async fn run_mintd(
     runtime: Option<std::sync::Arc<tokio::runtime::Runtime>>,
     routers: Vec<Router>,
 ) -> Result<()> {
-    let _guard = if enable_logging {
-        setup_tracing(work_dir, &settings.info.logging)?
+    let logging_guard = if enable_logging {
+        Some(setup_tracing(work_dir, &settings.info.logging)?)
     } else {
         None
     };
@@ -2031,12 +2249,9 @@ pub async fn run_mintd(
     )
     .await;
 
-    // Explicitly drop the guard to ensure proper cleanup
-    if let Some(guard) = _guard {
-        tracing::info!("Shutting down logging worker thread");
-        drop(guard);
-        // Give the worker thread a moment to flush any remaining logs
-        tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+    // Explicitly shut down logging to flush remaining logs and spans
+    if let Some(logging_guard) = logging_guard {
+        logging_guard.shutdown().await;
     }
 
     tracing::info!("Mintd shutdown");
@@ -2052,6 +2267,31 @@ pub async fn run_mintd_with_shutdown(
     db_password: Option<String>,
     runtime: Option<std::sync::Arc<tokio::runtime::Runtime>>,
     routers: Vec<Router>,
+) -> Result<()> {
+    run_mintd_with_middleware(
+        work_dir,
+        settings,
+        shutdown_signal,
+        db_password,
+        runtime,
+        routers,
+        Vec::new(),
+    )
+    .await
+}
+
+/// Run mintd with a custom shutdown signal and request middleware
+///
+/// The `middleware` hooks run around every request to the mint, including requests to the
+/// additional `routers` and the lightning address endpoints.
+pub async fn run_mintd_with_middleware(
+    work_dir: &Path,
+    settings: &config::Settings,
+    shutdown_signal: impl std::future::Future<Output = ()> + Send + 'static,
+    db_password: Option<String>,
+    runtime: Option<std::sync::Arc<tokio::runtime::Runtime>>,
+    routers: Vec<Router>,
+    middleware: Vec<Arc<dyn cdk_axum::MintMiddleware>>,
 ) -> Result<()> {
     let (localstore, keystore, kv) = initial_setup(work_dir, settings, db_password.clone()).await?;
 
@@ -2083,8 +2323,20 @@ pub async fn run_mintd_with_shutdown(
         }
     };
 
-    let mint_builder =
-        configure_mint_builder(settings, maybe_mint_builder, runtime, work_dir, Some(kv)).await?;
+    let mut services = BackendServices {
+        routers,
+        middleware,
+        ..Default::default()
+    };
+    let mint_builder = configure_mint_builder(
+        settings,
+        maybe_mint_builder,
+        runtime,
+        work_dir,
+        Some(kv),
+        &mut services,
+    )
+    .await?;
     let (mint_builder, auth_localstore) =
         setup_authentication(settings, work_dir, mint_builder, db_password).await?;
 
@@ -2092,6 +2344,12 @@ pub async fn run_mintd_with_shutdown(
 
     let mint = build_mint(settings, keystore, mint_builder).await?;
 
+    #[cfg(feature = "conversion")]
+    let mint = match settings.conversion.as_ref() {
+        Some(conversion) => mint.with_unit_conversion(unit_conversion(conversion)?),
+        None => mint,
+    };
+
     tracing::debug!("Mint built from builder.");
 
     let mint = Arc::new(mint);
@@ -2102,7 +2360,7 @@ pub async fn run_mintd_with_shutdown(
         work_dir,
         config_mint_info,
         shutdown_signal,
-        routers,
+        services,
         auth_localstore,
     )
     .await
@@ -2293,6 +2551,8 @@ ln_backend = "fakewallet"
             config: Some(config_path),
             seed_file: Some(seed_file),
             enable_logging: false,
+            skip_checks: false,
+            command: None,
         };
 
         let settings = load_settings_from_args(&temp_dir, &args)
@@ -2323,10 +2583,16 @@ ln_backend = "fakewallet"
 
         let localstore = Arc::new(memory::empty().await.unwrap());
         let builder = MintBuilder::new(localstore);
-        let builder =
-            configure_lightning_backend(&settings, builder, None, &std::env::temp_dir(), None)
-                .await
-                .expect("dispatcher should succeed");
+        let builder = configure_lightning_backend(
+            &settings,
+            builder,
+            None,
+            &std::env::temp_dir(),
+            None,
+            &mut BackendServices::default(),
+        )
+        .await
+        .expect("dispatcher should succeed");
 
         let mint_info = builder.current_mint_info();
         let units: Vec<_> = mint_info
@@ -2413,10 +2679,16 @@ ln_backend = "fakewallet"
 
         let localstore = Arc::new(memory::empty().await.unwrap());
         let builder = MintBuilder::new(localstore);
-        let err =
-            configure_lightning_backend(&settings, builder, None, &std::env::temp_dir(), None)
-                .await
-                .expect_err("duplicate unit/method pair should be rejected");
+        let err = configure_lightning_backend(
+            &settings,
+            builder,
+            None,
+            &std::env::temp_dir(),
+            None,
+            &mut BackendServices::default(),
+        )
+        .await
+        .expect_err("duplicate unit/method pair should be rejected");
 
         assert!(err.to_string().contains("Duplicate payment processor"));
     }
@@ -2434,10 +2706,16 @@ ln_backend = "fakewallet"
 
         let localstore = Arc::new(memory::empty().await.unwrap());
         let builder = MintBuilder::new(localstore);
-        let builder =
-            configure_lightning_backend(&settings, builder, None, &std::env::temp_dir(), None)
-                .await
-                .expect("empty ln should succeed");
+        let builder = configure_lightning_backend(
+            &settings,
+            builder,
+            None,
+            &std::env::temp_dir(),
+            None,
+            &mut BackendServices::default(),
+        )
+        .await
+        .expect("empty ln should succeed");
 
         let mint_info = builder.current_mint_info();
         assert!(
@@ -2465,10 +2743,16 @@ ln_backend = "fakewallet"
 
         let localstore = Arc::new(memory::empty().await.unwrap());
         let builder = MintBuilder::new(localstore);
-        let builder =
-            configure_lightning_backend(&settings, builder, None, &std::env::temp_dir(), None)
-                .await
-                .expect("LnBackend::None should succeed");
+        let builder = configure_lightning_backend(
+            &settings,
+            builder,
+            None,
+            &std::env::temp_dir(),
+            None,
+            &mut BackendServices::default(),
+        )
+        .await
+        .expect("LnBackend::None should succeed");
 
         let mint_info = builder.current_mint_info();
         assert!(
@@ -2600,9 +2884,16 @@ ln_backend = "fakewallet"
 
         let localstore = Arc::new(memory::empty().await.unwrap());
         let builder = MintBuilder::new(localstore);
-        let err = configure_mint_builder(&settings, builder, None, &std::env::temp_dir(), None)
-            .await
-            .expect_err("no payment backends should bail");
+        let err = configure_mint_builder(
+            &settings,
+            builder,
+            None,
+            &std::env::temp_dir(),
+            None,
+            &mut BackendServices::default(),
+        )
+        .await
+        .expect_err("no payment backends should bail");
 
         assert!(
             err.to_string().contains("At least one payment backend"),
@@ -2637,9 +2928,16 @@ ln_backend = "fakewallet"
 
         let localstore = Arc::new(memory::empty().await.unwrap());
         let builder = MintBuilder::new(localstore);
-        let err = configure_mint_builder(&settings, builder, None, &std::env::temp_dir(), None)
-            .await
-            .expect_err("fake wallet with BDK onchain should bail");
+        let err = configure_mint_builder(
+            &settings,
+            builder,
+            None,
+            &std::env::temp_dir(),
+            None,
+            &mut BackendServices::default(),
+        )
+        .await
+        .expect_err("fake wallet with BDK onchain should bail");
 
         assert!(
             err.to_string().contains("fakewallet") && err.to_string().contains("bdk"),
@@ -2863,6 +3161,7 @@ ln_backend = "fakewallet"
             "CDK_MINTD_POSTGRES_CONNECTION_TIMEOUT_SECONDS",
             "CDK_MINTD_SEED",
             "CDK_MINTD_MNEMONIC",
+            "CDK_MINTD_AMOUNTS",
             "CDK_MINTD_SIGNATORY_ENABLED",
             "CDK_MINTD_SIGNATORY_ADDRESS",
             "CDK_MINTD_SIGNATORY_PORT",
@@ -2875,6 +3174,11 @@ ln_backend = "fakewallet"
             "CDK_MINTD_LN_MAX_MINT",
             "CDK_MINTD_LN_MIN_MELT",
             "CDK_MINTD_LN_MAX_MELT",
+            "CDK_MINTD_LN_UNIT",
+            "CDK_MINTD_LN_BACKENDS",
+            "CDK_MINTD_FAKEWALLET_USD_MAX_MINT",
+            "CDK_MINTD_FAKEWALLET_USD_FEE_PERCENT",
+            "CDK_MINTD_FAKEWALLET_USD_UNIT",
             "CDK_MINTD_AUTH_ENABLED",
             "CDK_MINTD_AUTH_OPENID_DISCOVERY",
             "CDK_MINTD_AUTH_OPENID_CLIENT_ID",
@@ -3349,6 +3653,79 @@ port = 9090
         );
     }
 
+    #[cfg(feature = "fakewallet")]
+    #[test]
+    fn test_load_settings_rejects_zero_webhook_poll_interval() {
+        assert_load_settings_error(
+            &format!(
+                r#"
+[info]
+mnemonic = "{TEST_MNEMONIC}"
+
+[database]
+engine = "sqlite"
+
+[ln]
+ln_backend = "fakewallet"
+
+[webhooks]
+urls = ["https://example.com/hook"]
+secret = "webhook-secret"
+poll_interval_secs = 0
+"#
+            ),
+            "Webhook poll interval",
+        );
+    }
+
+    #[cfg(feature = "fakewallet")]
+    #[test]
+    fn test_load_settings_rejects_empty_webhook_secret() {
+        assert_load_settings_error(
+            &format!(
+                r#"
+[info]
+mnemonic = "{TEST_MNEMONIC}"
+
+[database]
+engine = "sqlite"
+
+[ln]
+ln_backend = "fakewallet"
+
+[webhooks]
+urls = ["https://example.com/hook"]
+secret = ""
+"#
+            ),
+            "Webhook secret",
+        );
+    }
+
+    #[cfg(feature = "fakewallet")]
+    #[test]
+    fn test_load_settings_reports_invalid_webhook_url() {
+        assert_load_settings_error(
+            &format!(
+                r#"
+[info]
+mnemonic = "{TEST_MNEMONIC}"
+
+[database]
+engine = "sqlite"
+
+[ln]
+ln_backend = "fakewallet"
+
+[webhooks]
+urls = ["ftp://example.com/hook"]
+secret = "webhook-secret"
+"#
+            ),
+            "Invalid webhook url",
+        );
+    }
+
     #[cfg(all(feature = "management-rpc", feature = "fakewallet"))]
     #[test]
     fn test_load_settings_reports_invalid_management_rpc_address() {
@@ -3805,6 +4182,117 @@ engine = "sqlite"
         assert_eq!(settings.ln[0].ln_backend, config::LnBackend::FakeWallet);
     }
 
+    #[cfg(feature = "fakewallet")]
+    #[test]
+    fn test_env_var_ln_backend_groups_replace_toml_ln() {
+        let settings = load_settings_with_env(
+            "cdk_mintd_env_ln_groups",
+            &format!(
+                r#"
+[info]
+mnemonic = "{TEST_MNEMONIC}"
+
+[database]
+engine = "sqlite"
+
+[ln]
+ln_backend = "fakewallet"
+unit = "eur"
+"#
+            ),
+            || {
+                std::env::set_var("CDK_MINTD_LN_BACKENDS", "fakewallet_sat, fakewallet_usd");
+                std::env::set_var("CDK_MINTD_FAKEWALLET_USD_MAX_MINT", "1000");
+                std::env::set_var("CDK_MINTD_FAKEWALLET_USD_FEE_PERCENT", "0.05");
+            },
+        )
+        .expect("env backend groups should load");
+
+        assert_eq!(settings.ln.len(), 2);
+        assert_eq!(settings.ln[0].ln_backend, config::LnBackend::FakeWallet);
+        assert_eq!(settings.ln[0].unit, CurrencyUnit::Sat);
+        assert_eq!(settings.ln[0].max_mint, 500_000.into());
+        assert_eq!(settings.ln[1].unit, CurrencyUnit::Usd);
+        assert_eq!(settings.ln[1].max_mint, 1000.into());
+        assert_eq!(
+            settings
+                .fake_wallet
+                .expect("fake wallet configured")
+                .fee_percent,
+            0.05
+        );
+    }
+
+    #[cfg(feature = "fakewallet")]
+    #[test]
+    fn test_env_var_rejects_unparseable_amounts() {
+        let result = load_settings_with_env(
+            "cdk_mintd_env_invalid_amounts",
+            &format!(
+                r#"
+[info]
+mnemonic = "{TEST_MNEMONIC}"
+
+[database]
+engine = "sqlite"
+
+[ln]
+ln_backend = "fakewallet"
+"#
+            ),
+            || std::env::set_var("CDK_MINTD_AMOUNTS", "1,2,four"),
+        );
+
+        let err = result.expect_err("unparseable amounts should be rejected");
+        assert!(err.to_string().contains("CDK_MINTD_AMOUNTS"));
+    }
+
+    #[cfg(feature = "fakewallet")]
+    #[test]
+    fn test_env_var_ln_backend_group_unit_override() {
+        let settings = load_settings_with_env(
+            "cdk_mintd_env_ln_group_unit",
+            &format!(
+                r#"
+[info]
+mnemonic = "{TEST_MNEMONIC}"
+
+[database]
+engine = "sqlite"
+"#
+            ),
+            || {
+                std::env::set_var("CDK_MINTD_LN_BACKENDS", "fakewallet_usd");
+                std::env::set_var("CDK_MINTD_FAKEWALLET_USD_UNIT", "eur");
+            },
+        )
+        .expect("env backend group unit override should load");
+
+        assert_eq!(settings.ln.len(), 1);
+        assert_eq!(settings.ln[0].ln_backend, config::LnBackend::FakeWallet);
+        assert_eq!(settings.ln[0].unit, CurrencyUnit::Eur);
+    }
+
+    #[cfg(feature = "fakewallet")]
+    #[test]
+    fn test_env_var_ln_backend_group_rejects_unknown_unit() {
+        let result = load_settings_with_env(
+            "cdk_mintd_env_ln_group_unknown_unit",
+            &format!(
+                r#"
+[info]
+mnemonic = "{TEST_MNEMONIC}"
+
+[database]
+engine = "sqlite"
+"#
+            ),
+            || std::env::set_var("CDK_MINTD_LN_BACKENDS", "fakewallet_gbp"),
+        );
+
+        assert!(result.is_err());
+    }
+
     #[cfg(feature = "fakewallet")]
     #[test]
     fn test_env_var_overrides_toml_listen_host() {
@@ -0,0 +1,199 @@
+//! Logging and tracing setup
+//!
+//! Logs go to stderr, to rotated files in `<work_dir>/logs` or both, as text or JSON lines. With
+//! the `otlp` feature, spans can additionally be exported to an OpenTelemetry collector.
+
+use std::path::Path;
+
+use anyhow::{Context, Result};
+use tracing_appender::non_blocking;
+use tracing_appender::non_blocking::WorkerGuard;
+use tracing_appender::rolling::{self, Rotation};
+use tracing_subscriber::fmt::writer::MakeWriterExt;
+use tracing_subscriber::fmt::MakeWriter;
+use tracing_subscriber::layer::SubscriberExt;
+use tracing_subscriber::util::SubscriberInitExt;
+use tracing_subscriber::{fmt, EnvFilter, Layer, Registry};
+
+use crate::config::{LoggingConfig, LoggingFormat, LoggingOutput, LoggingRotation};
+
+/// Directives quieting noisy dependencies, applied before [`LoggingConfig::filter`]
+const DEFAULT_FILTER: &str = "debug,hyper=warn,rustls=warn,reqwest=warn,h2=warn,tower=warn,tower_http=warn,tungstenite=warn,tokio_postgres=warn";
+
+/// File name of the log files, suffixed with the date when rotated
+const LOG_FILE_NAME: &str = "cdk-mintd.log";
+
+type BoxedLayer = Box<dyn Layer<Registry> + Send + Sync>;
+
+/// Keeps the logging workers alive until [`LoggingGuard::shutdown`]
+#[derive(Default)]
+pub struct LoggingGuard {
+    file: Option<WorkerGuard>,
+    #[cfg(feature = "otlp")]
+    tracer_provider: Option<opentelemetry_sdk::trace::SdkTracerProvider>,
+}
+
+impl std::fmt::Debug for LoggingGuard {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("LoggingGuard")
+            .field("file", &self.file.is_some())
+            .finish()
+    }
+}
+
+impl LoggingGuard {
+    /// Flush pending log lines and exported spans
+    pub async fn shutdown(self) {
+        #[cfg(feature = "otlp")]
+        if let Some(tracer_provider) = self.tracer_provider {
+            tracing::info!("Shutting down OpenTelemetry exporter");
+            if let Err(err) = tracer_provider.shutdown() {
+                tracing::warn!("Failed to flush OpenTelemetry spans: {}", err);
+            }
+        }
+
+        if let Some(guard) = self.file {
+            tracing::info!("Shutting down logging worker thread");
+            drop(guard);
+            // Give the worker thread a moment to flush any remaining logs
+            tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+        }
+    }
+}
+
+/// Sets up and initializes a tracing subscriber from the logging configuration.
+/// Logs can be configured to output to stderr only, file only, or both.
+/// Returns a guard that must be kept alive and shut down on exit.
+pub fn setup_tracing(work_dir: &Path, logging_config: &LoggingConfig) -> Result<LoggingGuard> {
+    let env_filter = match logging_config.filter.as_deref() {
+        Some(filter) if !filter.trim().is_empty() => {
+            EnvFilter::try_new(format!("{DEFAULT_FILTER},{filter}"))
+                .with_context(|| format!("Invalid logging filter: {filter}"))?
+        }
+        _ => EnvFilter::new(DEFAULT_FILTER),
+    };
+
+    let mut guard = LoggingGuard::default();
+    let mut layers: Vec<BoxedLayer> = Vec::new();
+    let mut outputs = Vec::new();
+
+    if matches!(
+        logging_config.output,
+        LoggingOutput::Stderr | LoggingOutput::Both
+    ) {
+        let console_level = parse_level(
+            logging_config.console_level.as_deref(),
+            tracing::Level::INFO,
+        );
+        let stderr = std::io::stderr.with_max_level(console_level);
+
+        layers.push(fmt_layer(logging_config.format, stderr));
+        outputs.push(format!("console ({console_level}+)"));
+    }
+
+    if matches!(
+        logging_config.output,
+        LoggingOutput::File | LoggingOutput::Both
+    ) {
+        let file_level = parse_level(logging_config.file_level.as_deref(), tracing::Level::DEBUG);
+
+        // Create logs directory in work_dir if it doesn't exist
+        let logs_dir = work_dir.join("logs");
+        std::fs::create_dir_all(&logs_dir)?;
+
+        let rotation = match logging_config.file_rotation {
+            LoggingRotation::Minutely => Rotation::MINUTELY,
+            LoggingRotation::Hourly => Rotation::HOURLY,
+            LoggingRotation::Daily => Rotation::DAILY,
+            LoggingRotation::Never => Rotation::NEVER,
+        };
+
+        let mut builder = rolling::Builder::new()
+            .rotation(rotation)
+            .filename_prefix(LOG_FILE_NAME);
+        if let Some(max_log_files) = logging_config.max_log_files {
+            builder = builder.max_log_files(max_log_files);
+        }
+        let file_appender = builder
+            .build(&logs_dir)
+            .context("Failed to create log file appender")?;
+        let (non_blocking_appender, file_guard) = non_blocking(file_appender);
+        guard.file = Some(file_guard);
+
+        let file_writer = non_blocking_appender.with_max_level(file_level);
+
+        layers.push(fmt_layer(logging_config.format, file_writer));
+        outputs.push(format!(
+            "file at {}/{} ({}+)",
+            logs_dir.display(),
+            LOG_FILE_NAME,
+            file_level
+        ));
+    }
+
+    #[cfg(feature = "otlp")]
+    if let Some(otlp_config) = logging_config.otlp.as_ref() {
+        let (layer, tracer_provider) = otlp_layer(otlp_config)?;
+        layers.push(layer);
+        guard.tracer_provider = Some(tracer_provider);
+        outputs.push(format!("OTLP at {}", otlp_config.endpoint));
+    }
+
+    tracing_subscriber::registry()
+        .with(layers.with_filter(env_filter))
+        .init();
+
+    tracing::info!("Logging initialized: {}", outputs.join(", "));
+
+    Ok(guard)
+}
+
+fn parse_level(level: Option<&str>, default: tracing::Level) -> tracing::Level {
+    level
+        .map(|level| level.parse().unwrap_or(default))
+        .unwrap_or(default)
+}
+
+fn fmt_layer<W>(format: LoggingFormat, writer: W) -> BoxedLayer
+where
+    W: for<'writer> MakeWriter<'writer> + Send + Sync + 'static,
+{
+    let layer = fmt::layer().with_ansi(false).with_writer(writer);
+
+    match format {
+        LoggingFormat::Text => layer.boxed(),
+        LoggingFormat::Json => layer.json().boxed(),
+    }
+}
+
+#[cfg(feature = "otlp")]
+fn otlp_layer(
+    otlp_config: &crate::config::OtlpConfig,
+) -> Result<(BoxedLayer, opentelemetry_sdk::trace::SdkTracerProvider)> {
+    use opentelemetry::trace::TracerProvider as _;
+    use opentelemetry_otlp::WithExportConfig;
+    use tracing_subscriber::filter::LevelFilter;
+
+    let exporter = opentelemetry_otlp::SpanExporter::builder()
+        .with_tonic()
+        .with_endpoint(otlp_config.endpoint.clone())
+        .build()
+        .context("Failed to create OTLP span exporter")?;
+
+    let tracer_provider = opentelemetry_sdk::trace::SdkTracerProvider::builder()
+        .with_batch_exporter(exporter)
+        .with_resource(
+            opentelemetry_sdk::Resource::builder()
+                .with_service_name(otlp_config.service_name.clone())
+                .build(),
+        )
+        .build();
+
+    let level = parse_level(otlp_config.level.as_deref(), tracing::Level::INFO);
+    let layer = tracing_opentelemetry::layer()
+        .with_tracer(tracer_provider.tracer("cdk-mintd"))
+        .with_filter(LevelFilter::from_level(level))
+        .boxed();
+
+    Ok((layer, tracer_provider))
+}
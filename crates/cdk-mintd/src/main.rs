@@ -3,7 +3,8 @@
 use std::sync::Arc;
 
 use anyhow::Result;
-use cdk_mintd::cli::CLIArgs;
+use cdk_mintd::check_config::check_config;
+use cdk_mintd::cli::{CLIArgs, Command};
 use cdk_mintd::{get_work_directory, load_settings_from_args};
 use clap::Parser;
 use tokio::runtime::Runtime;
@@ -16,7 +17,6 @@ fn main() -> Result<()> {
     rt.block_on(async {
         let args = CLIArgs::parse();
         let work_dir = get_work_directory(&args).await?;
-        let settings = load_settings_from_args(&work_dir, &args)?;
 
         #[cfg(feature = "sqlcipher")]
         let password = Some(CLIArgs::parse().password);
@@ -24,6 +24,42 @@ fn main() -> Result<()> {
         #[cfg(not(feature = "sqlcipher"))]
         let password = None;
 
+        if let Some(Command::CheckConfig(check_args)) = &args.command {
+            let report = check_config(&work_dir, &args, password).await;
+            if check_args.json {
+                println!("{}", serde_json::to_string_pretty(&report)?);
+            } else {
+                println!("{report}");
+            }
+            if !report.is_ok() {
+                std::process::exit(1);
+            }
+            return Ok(());
+        }
+
+        let settings = load_settings_from_args(&work_dir, &args)?;
+
+        if let Some(Command::ExportLedger(export_args)) = &args.command {
+            let ledger =
+                cdk_mintd::export_ledger(&settings, &work_dir, password, export_args).await?;
+            match &export_args.output {
+                Some(path) => std::fs::write(path, ledger)?,
+                None => print!("{ledger}"),
+            }
+            return Ok(());
+        }
+
+        if let Some(Command::Migrations) = &args.command {
+            let statuses = cdk_mintd::migration_status(&settings, &work_dir, password).await?;
+            for status in &statuses {
+                println!("{}: {}", status.name, status.state);
+            }
+            if statuses.iter().any(|status| status.state.is_divergent()) {
+                std::process::exit(1);
+            }
+            return Ok(());
+        }
+
         cdk_mintd::run_mintd(
             &work_dir,
             &settings,
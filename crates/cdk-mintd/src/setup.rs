@@ -89,7 +89,8 @@ impl LnBackendSetup for config::Cln {
             self.expose_private_channels,
             kv_store.expect("Cln needs kv store"),
         )
-        .await?;
+        .await?
+        .with_hold_invoices(self.hold_invoices);
 
         Ok(cln)
     }
@@ -137,8 +138,19 @@ impl LnBackendSetup for config::LNbits {
         )
         .await?;
 
-        // Use v1 websocket API
-        lnbits.subscribe_ws().await?;
+        let lnbits = match &self.webhook_url {
+            Some(webhook_url) => {
+                let Some(webhook_secret) = self.webhook_secret.clone() else {
+                    bail!("LNbits webhook_secret must be set when webhook_url is configured");
+                };
+                lnbits.with_webhook(webhook_url, webhook_secret)?
+            }
+            None => {
+                // Use v1 websocket API
+                lnbits.subscribe_ws().await?;
+                lnbits
+            }
+        };
 
         Ok(lnbits)
     }
@@ -185,7 +197,8 @@ impl LnBackendSetup for config::Lnd {
             fee_reserve,
             kv_store.expect("Lnd needs kv store"),
         )
-        .await?;
+        .await?
+        .with_keysend(self.keysend);
 
         Ok(lnd)
     }
@@ -238,7 +251,10 @@ impl LnBackendSetup for config::FakeWallet {
             delay_time,
             unit,
         )
-        .with_custom_payment_methods(custom_payment_methods);
+        .with_custom_payment_methods(custom_payment_methods)
+        .with_failure_injection(cdk_fake_wallet::FailureInjection::new(
+            self.failure_injection.clone(),
+        )?);
 
         Ok(fake_wallet)
     }
@@ -0,0 +1,348 @@
+//! Webhook notifications
+//!
+//! Mint events are POSTed as JSON to the configured URLs. The body is signed with
+//! HMAC-SHA256 using the configured secret and the hex signature is sent in the
+//! `X-Cdk-Signature` header. Failed deliveries are retried with exponential backoff.
+
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+use std::time::Duration;
+
+use bitcoin::hashes::hmac::{Hmac, HmacEngine};
+use bitcoin::hashes::{sha256, Hash, HashEngine};
+use cdk::mint::{Mint, QuoteId};
+use cdk::nuts::{CurrencyUnit, Id, MeltQuoteState, MintQuoteState, NotificationPayload};
+use cdk::util::unix_time;
+use cdk::Amount;
+use cdk_http_client::HttpClient;
+use serde::Serialize;
+use tokio::sync::{broadcast, mpsc};
+use tokio::task::JoinHandle;
+
+use crate::config::{WebhookEventKind, Webhooks};
+
+/// Header carrying the HMAC-SHA256 signature of the body
+pub const SIGNATURE_HEADER: &str = "X-Cdk-Signature";
+
+/// Delay before the first retry, doubled on every further attempt
+const INITIAL_RETRY_DELAY: Duration = Duration::from_secs(1);
+
+/// Webhook event with its serialized data
+type PendingEvent = (
+    WebhookEventKind,
+    Result<serde_json::Value, serde_json::Error>,
+);
+
+/// Body POSTed to webhooks
+#[derive(Debug, Clone, Serialize)]
+struct WebhookPayload {
+    event: WebhookEventKind,
+    timestamp: u64,
+    data: serde_json::Value,
+}
+
+/// Webhook event of a mint notification, if it is one operators are notified about
+fn notification_event(payload: &NotificationPayload<QuoteId>) -> Option<WebhookEventKind> {
+    let melt_failed =
+        |state: &MeltQuoteState| matches!(state, MeltQuoteState::Unpaid | MeltQuoteState::Failed);
+
+    let paid = match payload {
+        NotificationPayload::MintQuoteBolt11Response(response) => {
+            response.state == MintQuoteState::Paid
+        }
+        NotificationPayload::MintQuoteBolt12Response(response) => {
+            response.amount_paid > response.amount_issued
+        }
+        NotificationPayload::MintQuoteOnchainResponse(response) => {
+            response.amount_paid > response.amount_issued
+        }
+        NotificationPayload::CustomMintQuoteResponse(_, response) => {
+            response.amount_paid > response.amount_issued
+        }
+        NotificationPayload::MeltQuoteBolt11Response(response) if melt_failed(&response.state) => {
+            return Some(WebhookEventKind::MeltFailed)
+        }
+        NotificationPayload::MeltQuoteBolt12Response(response) if melt_failed(&response.state) => {
+            return Some(WebhookEventKind::MeltFailed)
+        }
+        NotificationPayload::MeltQuoteOnchainResponse(response) if melt_failed(&response.state) => {
+            return Some(WebhookEventKind::MeltFailed)
+        }
+        NotificationPayload::CustomMeltQuoteResponse(_, response)
+            if melt_failed(&response.state) =>
+        {
+            return Some(WebhookEventKind::MeltFailed)
+        }
+        _ => false,
+    };
+
+    paid.then_some(WebhookEventKind::MintQuotePaid)
+}
+
+/// Hex HMAC-SHA256 of `body`
+fn sign(secret: &str, body: &[u8]) -> String {
+    let mut engine = HmacEngine::<sha256::Hash>::new(secret.as_bytes());
+    engine.input(body);
+    Hmac::<sha256::Hash>::from_engine(engine).to_string()
+}
+
+/// Send mint events to the configured webhooks until shutdown
+pub(crate) fn spawn_webhooks(
+    mint: Arc<Mint>,
+    config: Webhooks,
+    mut shutdown_rx: broadcast::Receiver<()>,
+) -> JoinHandle<()> {
+    tracing::info!("Sending webhooks to {} urls", config.urls.len());
+
+    let (queue, deliveries) = mpsc::unbounded_channel();
+    let config = Arc::new(config);
+    let delivery_handle = tokio::spawn(deliver(Arc::clone(&config), deliveries));
+
+    tokio::spawn(async move {
+        let mut events = mint.subscribe_events();
+        let mut poll = tokio::time::interval(Duration::from_secs(config.poll_interval_secs));
+        let mut active_keysets: Option<HashSet<Id>> = None;
+        let mut exceeded_units = HashSet::new();
+
+        loop {
+            let payloads = tokio::select! {
+                _ = shutdown_rx.recv() => break,
+                event = events.recv() => match event {
+                    Ok(event) => {
+                        let payload = event.into_inner();
+                        notification_event(&payload)
+                            .map(|kind| (kind, serde_json::to_value(&payload)))
+                            .into_iter()
+                            .collect()
+                    }
+                    Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                        tracing::warn!("Webhooks skipped {} mint events", skipped);
+                        Vec::new()
+                    }
+                    Err(broadcast::error::RecvError::Closed) => break,
+                },
+                _ = poll.tick() => {
+                    let mut payloads = rotated_keysets(&mint, &mut active_keysets);
+                    if let Some(threshold) = config.liability_threshold {
+                        payloads.extend(
+                            exceeded_liabilities(&mint, threshold, &mut exceeded_units).await,
+                        );
+                    }
+                    payloads
+                }
+            };
+
+            for (event, data) in payloads {
+                if !config.events.is_empty() && !config.events.contains(&event) {
+                    continue;
+                }
+
+                match data {
+                    Ok(data) => {
+                        let _ = queue.send(WebhookPayload {
+                            event,
+                            timestamp: unix_time(),
+                            data,
+                        });
+                    }
+                    Err(err) => tracing::error!("Could not serialize webhook event: {}", err),
+                }
+            }
+        }
+
+        drop(queue);
+        let _ = delivery_handle.await;
+    })
+}
+
+/// Keysets that became active since the last check
+fn rotated_keysets(mint: &Mint, active_keysets: &mut Option<HashSet<Id>>) -> Vec<PendingEvent> {
+    let keysets = mint.keysets().keysets;
+    let current: HashSet<Id> = keysets
+        .iter()
+        .filter(|keyset| keyset.active)
+        .map(|keyset| keyset.id)
+        .collect();
+
+    // The first check only records the keysets active at startup
+    let Some(previous) = active_keysets.replace(current.clone()) else {
+        return Vec::new();
+    };
+
+    keysets
+        .iter()
+        .filter(|keyset| keyset.active && !previous.contains(&keyset.id))
+        .map(|keyset| {
+            (
+                WebhookEventKind::KeysetRotated,
+                serde_json::to_value(keyset),
+            )
+        })
+        .collect()
+}
+
+/// Units whose outstanding ecash crossed `threshold` since the last check
+async fn exceeded_liabilities(
+    mint: &Mint,
+    threshold: u64,
+    exceeded_units: &mut HashSet<CurrencyUnit>,
+) -> Vec<PendingEvent> {
+    let (issued, redeemed) = match (mint.total_issued().await, mint.total_redeemed().await) {
+        (Ok(issued), Ok(redeemed)) => (issued, redeemed),
+        (Err(err), _) | (_, Err(err)) => {
+            tracing::warn!("Could not load liabilities for webhooks: {}", err);
+            return Vec::new();
+        }
+    };
+
+    let mut outstanding: HashMap<CurrencyUnit, u64> = HashMap::new();
+    for keyset in mint.keysets().keysets {
+        let issued = issued.get(&keyset.id).copied().unwrap_or(Amount::ZERO);
+        let redeemed = redeemed.get(&keyset.id).copied().unwrap_or(Amount::ZERO);
+        let entry = outstanding.entry(keyset.unit).or_default();
+        *entry = entry.saturating_add(
+            issued
+                .checked_sub(redeemed)
+                .unwrap_or(Amount::ZERO)
+                .to_u64(),
+        );
+    }
+
+    let mut payloads = Vec::new();
+    for (unit, amount) in outstanding {
+        if amount <= threshold {
+            exceeded_units.remove(&unit);
+        } else if exceeded_units.insert(unit.clone()) {
+            payloads.push((
+                WebhookEventKind::LiabilityThresholdExceeded,
+                Ok(serde_json::json!({
+                    "unit": unit,
+                    "outstanding": amount,
+                    "threshold": threshold,
+                })),
+            ));
+        }
+    }
+
+    payloads
+}
+
+/// Deliver queued payloads to every URL, retrying failed deliveries
+async fn deliver(config: Arc<Webhooks>, mut deliveries: mpsc::UnboundedReceiver<WebhookPayload>) {
+    let client = Arc::new(HttpClient::new());
+
+    while let Some(payload) = deliveries.recv().await {
+        // The signature covers exactly the bytes sent as the body
+        let body = match serde_json::to_vec(&payload) {
+            Ok(body) => body,
+            Err(err) => {
+                tracing::error!("Could not serialize webhook payload: {}", err);
+                continue;
+            }
+        };
+        let signature = sign(&config.secret, &body);
+
+        for url in &config.urls {
+            let client = Arc::clone(&client);
+            let url = url.clone();
+            let body = body.clone();
+            let signature = signature.clone();
+            let max_attempts = config.max_attempts.max(1);
+
+            tokio::spawn(async move {
+                let mut delay = INITIAL_RETRY_DELAY;
+
+                for attempt in 1..=max_attempts {
+                    let result = client
+                        .post(&url)
+                        .header("Content-Type", "application/json")
+                        .header(SIGNATURE_HEADER, &signature)
+                        .body(body.clone())
+                        .send()
+                        .await;
+
+                    match result {
+                        Ok(response) if response.is_success() => return,
+                        Ok(response) => tracing::warn!(
+                            "Webhook {} responded with {} (attempt {}/{})",
+                            url,
+                            response.status(),
+                            attempt,
+                            max_attempts
+                        ),
+                        Err(err) => tracing::warn!(
+                            "Webhook {} failed: {} (attempt {}/{})",
+                            url,
+                            err,
+                            attempt,
+                            max_attempts
+                        ),
+                    }
+
+                    if attempt < max_attempts {
+                        tokio::time::sleep(delay).await;
+                        delay = delay.saturating_mul(2);
+                    }
+                }
+
+                tracing::error!("Giving up delivering webhook to {}", url);
+            });
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use cdk::nuts::{MeltQuoteBolt11Response, MintQuoteBolt11Response};
+
+    use super::*;
+
+    #[test]
+    fn signature_is_hmac_sha256() {
+        // RFC 4231 test case 2
+        assert_eq!(
+            sign("Jefe", b"what do ya want for nothing?"),
+            "5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843"
+        );
+    }
+
+    #[test]
+    fn only_paid_mint_quotes_and_failed_melts_are_sent() {
+        let mint_quote: MintQuoteBolt11Response<QuoteId> =
+            serde_json::from_value(serde_json::json!({
+                "quote": "9d745270-1405-46de-b5c5-e2762b4f5e00",
+                "request": "lnbc100n1p",
+                "amount": 10,
+                "unit": "sat",
+                "state": "PAID",
+                "expiry": 1701704757
+            }))
+            .unwrap();
+        let mut melt_quote: MeltQuoteBolt11Response<QuoteId> =
+            serde_json::from_value(serde_json::json!({
+                "quote": "9d745270-1405-46de-b5c5-e2762b4f5e00",
+                "amount": 10,
+                "fee_reserve": 2,
+                "state": "PENDING",
+                "expiry": 1701704757
+            }))
+            .unwrap();
+
+        assert_eq!(
+            notification_event(&NotificationPayload::MintQuoteBolt11Response(mint_quote)),
+            Some(WebhookEventKind::MintQuotePaid)
+        );
+        assert_eq!(
+            notification_event(&NotificationPayload::MeltQuoteBolt11Response(
+                melt_quote.clone()
+            )),
+            None
+        );
+
+        melt_quote.state = MeltQuoteState::Unpaid;
+        assert_eq!(
+            notification_event(&NotificationPayload::MeltQuoteBolt11Response(melt_quote)),
+            Some(WebhookEventKind::MeltFailed)
+        );
+    }
+}
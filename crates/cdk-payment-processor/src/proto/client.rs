@@ -23,7 +23,7 @@ use crate::proto::cdk_payment_processor_client::CdkPaymentProcessorClient;
 use crate::proto::{
     CheckIncomingPaymentRequest, CheckOutgoingPaymentRequest, CreatePaymentRequest, EmptyRequest,
     IncomingPaymentOptions, IntoProtoAmount, MakePaymentRequest, OutgoingPaymentRequestType,
-    PaymentQuoteRequest,
+    PaymentQuoteRequest, SettleIncomingPaymentRequest,
 };
 
 /// Payment Processor
@@ -186,6 +186,8 @@ impl MintPayment for PaymentProcessorClient {
                         amount: opts.amount.map(Into::into),
                         unix_expiry: opts.unix_expiry,
                         extra_json: opts.extra_json.clone(),
+                        quote_id: opts.quote_id.to_string(),
+                        method: opts.method,
                     },
                 )),
             },
@@ -195,6 +197,7 @@ impl MintPayment for PaymentProcessorClient {
                         description: opts.description,
                         amount: Some(opts.amount.into()),
                         unix_expiry: opts.unix_expiry,
+                        hash_description: opts.hash_description,
                     },
                 )),
             },
@@ -525,4 +528,23 @@ impl MintPayment for PaymentProcessorClient {
             .try_into()
             .map_err(|_| cdk_common::payment::Error::UnknownPaymentState)?)
     }
+
+    #[instrument(skip_all)]
+    async fn settle_incoming_payment(
+        &self,
+        payment_identifier: &cdk_common::payment::PaymentIdentifier,
+    ) -> Result<(), Self::Err> {
+        let mut inner = self.inner.clone();
+        inner
+            .settle_incoming_payment(Request::new(SettleIncomingPaymentRequest {
+                request_identifier: Some(payment_identifier.clone().into()),
+            }))
+            .await
+            .map_err(|err| {
+                tracing::error!("Could not settle incoming payment: {}", err);
+                cdk_common::payment::Error::Custom(err.to_string())
+            })?;
+
+        Ok(())
+    }
 }
@@ -233,11 +233,14 @@ impl CdkPaymentProcessor for PaymentProcessorServer {
                 };
                 IncomingPaymentOptions::Custom(Box::new(
                     cdk_common::payment::CustomIncomingPaymentOptions {
-                        method: "".to_string(),
+                        method: opts.method,
                         description: opts.description,
                         amount,
                         unix_expiry: opts.unix_expiry,
                         extra_json: opts.extra_json,
+                        quote_id: opts.quote_id.parse().map_err(|_| {
+                            Status::invalid_argument("Invalid quote_id in Custom options")
+                        })?,
                     },
                 ))
             }
@@ -251,6 +254,7 @@ impl CdkPaymentProcessor for PaymentProcessorServer {
                     description: opts.description,
                     amount,
                     unix_expiry: opts.unix_expiry,
+                    hash_description: opts.hash_description,
                 })
             }
             incoming_payment_options::Options::Bolt12(opts) => {
@@ -571,6 +575,26 @@ impl CdkPaymentProcessor for PaymentProcessorServer {
         Ok(Response::new(check_response.into()))
     }
 
+    async fn settle_incoming_payment(
+        &self,
+        request: Request<SettleIncomingPaymentRequest>,
+    ) -> Result<Response<SettleIncomingPaymentResponse>, Status> {
+        let request = request.into_inner();
+
+        let payment_identifier = request
+            .request_identifier
+            .ok_or_else(|| Status::invalid_argument("Missing request identifier"))?
+            .try_into()
+            .map_err(|_| Status::invalid_argument("Invalid request identifier"))?;
+
+        self.inner
+            .settle_incoming_payment(&payment_identifier)
+            .await
+            .map_err(|_| Status::internal("Could not settle incoming payment"))?;
+
+        Ok(Response::new(SettleIncomingPaymentResponse {}))
+    }
+
     type WaitPaymentEventStream = ResponseStream;
 
     #[allow(clippy::incompatible_msrv)]
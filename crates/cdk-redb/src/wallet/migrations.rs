@@ -5,6 +5,7 @@ use std::str::FromStr;
 use std::sync::Arc;
 
 use cdk_common::mint_url::MintUrl;
+use cdk_common::wallet::Transaction;
 use cdk_common::Id;
 use redb::{
     Database, MultimapTableDefinition, ReadableDatabase, ReadableMultimapTable, ReadableTable,
@@ -14,6 +15,7 @@ use redb::{
 use super::Error;
 use crate::wallet::{
     KEYSETS_TABLE, KEYSET_COUNTER, KEYSET_U32_MAPPING, MINT_KEYS_TABLE, P2PK_SIGNING_KEYS_TABLE,
+    TRANSACTIONS_TABLE, TRANSACTION_SAGAS_TABLE, TRANSACTION_TIMESTAMPS_TABLE,
 };
 
 // <Mint_url, Info>
@@ -220,3 +222,71 @@ pub(crate) fn migrate_04_to_05(db: Arc<Database>) -> Result<u32, Error> {
 
     Ok(5)
 }
+
+pub(crate) fn migrate_05_to_06(db: Arc<Database>) -> Result<u32, Error> {
+    tracing::info!("Starting migration from version 5 to 6: Indexing transactions by saga id");
+    let write_txn = db.begin_write().map_err(Error::from)?;
+
+    {
+        let transactions = write_txn
+            .open_table(TRANSACTIONS_TABLE)
+            .map_err(Error::from)?;
+        let mut saga_table = write_txn
+            .open_table(TRANSACTION_SAGAS_TABLE)
+            .map_err(Error::from)?;
+
+        for (_, transaction) in transactions.iter().map_err(Error::from)?.flatten() {
+            let Ok(transaction) = serde_json::from_str::<Transaction>(transaction.value()) else {
+                continue;
+            };
+
+            if let Some(saga_id) = transaction.saga_id {
+                saga_table
+                    .insert(saga_id.to_string().as_str(), transaction.id().as_slice())
+                    .map_err(Error::from)?;
+            }
+        }
+    }
+
+    write_txn.commit()?;
+    tracing::info!("Finished migration from version 5 to 6: Transactions indexed by saga id");
+
+    Ok(6)
+}
+
+pub(crate) fn migrate_06_to_07(db: Arc<Database>) -> Result<u32, Error> {
+    tracing::info!("Starting migration from version 6 to 7: Indexing transactions by timestamp");
+    let write_txn = db.begin_write().map_err(Error::from)?;
+
+    {
+        let transactions = write_txn
+            .open_table(TRANSACTIONS_TABLE)
+            .map_err(Error::from)?;
+        let mut timestamp_table = write_txn
+            .open_table(TRANSACTION_TIMESTAMPS_TABLE)
+            .map_err(Error::from)?;
+
+        for (_, transaction) in transactions.iter().map_err(Error::from)?.flatten() {
+            let Ok(transaction) = serde_json::from_str::<Transaction>(transaction.value()) else {
+                continue;
+            };
+
+            timestamp_table
+                .insert(
+                    (
+                        transaction.mint_url.to_string().as_str(),
+                        transaction.unit.to_string().as_str(),
+                        transaction.timestamp,
+                        transaction.id().as_slice(),
+                    ),
+                    (),
+                )
+                .map_err(Error::from)?;
+        }
+    }
+
+    write_txn.commit()?;
+    tracing::info!("Finished migration from version 6 to 7: Transactions indexed by timestamp");
+
+    Ok(7)
+}
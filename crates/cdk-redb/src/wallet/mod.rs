@@ -8,7 +8,8 @@ use std::sync::Arc;
 
 use async_trait::async_trait;
 use cdk_common::bitcoin::bip32::DerivationPath;
-use cdk_common::database::{validate_kvstore_params, WalletDatabase};
+use cdk_common::database::wallet::BufferedTransaction;
+use cdk_common::database::{validate_kvstore_params, WalletDatabase, WalletDatabaseTransaction};
 use cdk_common::mint_url::MintUrl;
 use cdk_common::nut00::KnownMethod;
 use cdk_common::util::unix_time;
@@ -25,7 +26,8 @@ use tracing::instrument;
 use crate::error::Error;
 use crate::migrations::migrate_00_to_01;
 use crate::wallet::migrations::{
-    migrate_01_to_02, migrate_02_to_03, migrate_03_to_04, migrate_04_to_05,
+    migrate_01_to_02, migrate_02_to_03, migrate_03_to_04, migrate_04_to_05, migrate_05_to_06,
+    migrate_06_to_07,
 };
 
 mod migrations;
@@ -48,6 +50,12 @@ const CONFIG_TABLE: TableDefinition<&str, &str> = TableDefinition::new("config")
 const KEYSET_COUNTER: TableDefinition<&str, u32> = TableDefinition::new("keyset_counter");
 // <Transaction_id, Transaction>
 const TRANSACTIONS_TABLE: TableDefinition<&[u8], &str> = TableDefinition::new("transactions");
+// <Saga_id, Transaction_id>
+const TRANSACTION_SAGAS_TABLE: TableDefinition<&str, &[u8]> =
+    TableDefinition::new("transaction_sagas");
+// <(Mint_url, Unit, Timestamp, Transaction_id), ()>
+const TRANSACTION_TIMESTAMPS_TABLE: TableDefinition<(&str, &str, u64, &[u8]), ()> =
+    TableDefinition::new("transaction_timestamps");
 // <Saga_id, WalletSaga>
 const SAGAS_TABLE: TableDefinition<&str, &str> = TableDefinition::new("wallet_sagas");
 
@@ -59,7 +67,7 @@ const KEYSET_U32_MAPPING: TableDefinition<u32, &str> = TableDefinition::new("key
 // <(primary_namespace, secondary_namespace, key), value>
 const KV_STORE_TABLE: TableDefinition<(&str, &str, &str), &[u8]> = TableDefinition::new("kv_store");
 
-const DATABASE_VERSION: u32 = 5;
+const DATABASE_VERSION: u32 = 7;
 
 /// Wallet Redb Database
 #[derive(Debug, Clone)]
@@ -127,6 +135,14 @@ impl WalletRedbDatabase {
                                 current_file_version = migrate_04_to_05(Arc::clone(&db))?;
                             }
 
+                            if current_file_version == 5 {
+                                current_file_version = migrate_05_to_06(Arc::clone(&db))?;
+                            }
+
+                            if current_file_version == 6 {
+                                current_file_version = migrate_06_to_07(Arc::clone(&db))?;
+                            }
+
                             if current_file_version != DATABASE_VERSION {
                                 tracing::warn!(
                                     "Database upgrade did not complete at {} current is {}",
@@ -173,6 +189,8 @@ impl WalletRedbDatabase {
                         let _ = write_txn.open_table(PROOFS_TABLE)?;
                         let _ = write_txn.open_table(KEYSET_COUNTER)?;
                         let _ = write_txn.open_table(TRANSACTIONS_TABLE)?;
+                        let _ = write_txn.open_table(TRANSACTION_SAGAS_TABLE)?;
+                        let _ = write_txn.open_table(TRANSACTION_TIMESTAMPS_TABLE)?;
                         let _ = write_txn.open_table(KEYSET_U32_MAPPING)?;
                         let _ = write_txn.open_table(KV_STORE_TABLE)?;
                         let _ = write_txn.open_table(P2PK_SIGNING_KEYS_TABLE)?;
@@ -203,6 +221,13 @@ impl WalletRedbDatabase {
 
 #[async_trait]
 impl WalletDatabase<database::Error> for WalletRedbDatabase {
+    async fn begin_db_transaction(
+        &self,
+    ) -> Result<Box<dyn WalletDatabaseTransaction<database::Error> + Send + Sync>, database::Error>
+    {
+        Ok(Box::new(BufferedTransaction::new(Arc::new(self.clone()))))
+    }
+
     #[instrument(skip(self))]
     async fn get_mint(&self, mint_url: MintUrl) -> Result<Option<MintInfo>, database::Error> {
         let read_txn = self.db.begin_read().map_err(Into::<Error>::into)?;
@@ -510,6 +535,51 @@ impl WalletDatabase<database::Error> for WalletRedbDatabase {
         Ok(transactions)
     }
 
+    #[instrument(skip(self))]
+    async fn list_transactions_page(
+        &self,
+        mint_url: MintUrl,
+        unit: CurrencyUnit,
+        from: u64,
+        to: u64,
+        offset: u64,
+        limit: u64,
+    ) -> Result<Vec<Transaction>, database::Error> {
+        if from >= to {
+            return Ok(Vec::new());
+        }
+
+        let read_txn = self.db.begin_read().map_err(Error::from)?;
+        let timestamp_table = read_txn
+            .open_table(TRANSACTION_TIMESTAMPS_TABLE)
+            .map_err(Error::from)?;
+        let table = read_txn
+            .open_table(TRANSACTIONS_TABLE)
+            .map_err(Error::from)?;
+
+        let mint_url = mint_url.to_string();
+        let unit = unit.to_string();
+        let start: (&str, &str, u64, &[u8]) = (mint_url.as_str(), unit.as_str(), from, &[]);
+        let end: (&str, &str, u64, &[u8]) = (mint_url.as_str(), unit.as_str(), to, &[]);
+
+        let mut transactions = Vec::new();
+        for entry in timestamp_table
+            .range(start..end)
+            .map_err(Error::from)?
+            .skip(offset as usize)
+            .take(limit as usize)
+        {
+            let (key, _) = entry.map_err(Error::from)?;
+            let (_, _, _, id) = key.value();
+
+            if let Some(transaction) = table.get(id).map_err(Error::from)? {
+                transactions.push(serde_json::from_str(transaction.value()).map_err(Error::from)?);
+            }
+        }
+
+        Ok(transactions)
+    }
+
     #[instrument(skip(self, added, removed_ys))]
     async fn update_proofs(
         &self,
@@ -583,13 +653,60 @@ impl WalletDatabase<database::Error> for WalletRedbDatabase {
             let mut table = write_txn
                 .open_table(TRANSACTIONS_TABLE)
                 .map_err(Error::from)?;
-            table
+            let mut saga_table = write_txn
+                .open_table(TRANSACTION_SAGAS_TABLE)
+                .map_err(Error::from)?;
+
+            if let Some(saga_id) = transaction.saga_id {
+                let saga_id = saga_id.to_string();
+                let recorded = saga_table
+                    .get(saga_id.as_str())
+                    .map_err(Error::from)?
+                    .is_some_and(|tx_id| tx_id.value() != id.as_slice());
+
+                if recorded {
+                    tracing::debug!("Transaction for operation {} already recorded", saga_id);
+                    return Ok(());
+                }
+
+                saga_table
+                    .insert(saga_id.as_str(), id.as_slice())
+                    .map_err(Error::from)?;
+            }
+
+            let previous = table
                 .insert(
                     id.as_slice(),
                     serde_json::to_string(&transaction)
                         .map_err(Error::from)?
                         .as_str(),
                 )
+                .map_err(Error::from)?
+                .and_then(|tx| serde_json::from_str::<Transaction>(tx.value()).ok());
+
+            let mut timestamp_table = write_txn
+                .open_table(TRANSACTION_TIMESTAMPS_TABLE)
+                .map_err(Error::from)?;
+            if let Some(previous) = previous {
+                timestamp_table
+                    .remove((
+                        previous.mint_url.to_string().as_str(),
+                        previous.unit.to_string().as_str(),
+                        previous.timestamp,
+                        id.as_slice(),
+                    ))
+                    .map_err(Error::from)?;
+            }
+            timestamp_table
+                .insert(
+                    (
+                        transaction.mint_url.to_string().as_str(),
+                        transaction.unit.to_string().as_str(),
+                        transaction.timestamp,
+                        id.as_slice(),
+                    ),
+                    (),
+                )
                 .map_err(Error::from)?;
         }
         write_txn.commit().map_err(Error::from)?;
@@ -644,15 +761,13 @@ impl WalletDatabase<database::Error> for WalletRedbDatabase {
                 .open_table(MINT_QUOTES_TABLE)
                 .map_err(Error::from)?;
 
-            let unix_time = unix_time();
-
             let quotes: Vec<MintQuote> = table
                 .iter()
                 .map_err(Error::from)?
                 .flatten()
                 .filter_map(|(_, quote)| {
                     let mut q: MintQuote = serde_json::from_str(quote.value()).ok()?;
-                    if q.mint_url == old_mint_url && q.expiry >= unix_time {
+                    if q.mint_url == old_mint_url {
                         q.mint_url = new_mint_url.clone();
                         Some(q)
                     } else {
@@ -671,6 +786,96 @@ impl WalletDatabase<database::Error> for WalletRedbDatabase {
             }
         }
 
+        // Update melt quotes
+        {
+            let mut table = write_txn
+                .open_table(MELT_QUOTES_TABLE)
+                .map_err(Error::from)?;
+
+            let quotes: Vec<wallet::MeltQuote> = table
+                .iter()
+                .map_err(Error::from)?
+                .flatten()
+                .filter_map(|(_, quote)| {
+                    let mut q: wallet::MeltQuote = serde_json::from_str(quote.value()).ok()?;
+                    if q.mint_url.as_ref() == Some(&old_mint_url) {
+                        q.mint_url = Some(new_mint_url.clone());
+                        Some(q)
+                    } else {
+                        None
+                    }
+                })
+                .collect();
+
+            for quote in quotes {
+                table
+                    .insert(
+                        quote.id.as_str(),
+                        serde_json::to_string(&quote).map_err(Error::from)?.as_str(),
+                    )
+                    .map_err(Error::from)?;
+            }
+        }
+
+        // Update transactions
+        {
+            let mut table = write_txn
+                .open_table(TRANSACTIONS_TABLE)
+                .map_err(Error::from)?;
+
+            let transactions: Vec<Transaction> = table
+                .iter()
+                .map_err(Error::from)?
+                .flatten()
+                .filter_map(|(_, transaction)| {
+                    let mut tx: Transaction = serde_json::from_str(transaction.value()).ok()?;
+                    if tx.mint_url == old_mint_url {
+                        tx.mint_url = new_mint_url.clone();
+                        Some(tx)
+                    } else {
+                        None
+                    }
+                })
+                .collect();
+
+            let mut timestamp_table = write_txn
+                .open_table(TRANSACTION_TIMESTAMPS_TABLE)
+                .map_err(Error::from)?;
+
+            for transaction in transactions {
+                let id = transaction.id();
+                table
+                    .insert(
+                        id.as_slice(),
+                        serde_json::to_string(&transaction)
+                            .map_err(Error::from)?
+                            .as_str(),
+                    )
+                    .map_err(Error::from)?;
+
+                let unit = transaction.unit.to_string();
+                timestamp_table
+                    .remove((
+                        old_mint_url.to_string().as_str(),
+                        unit.as_str(),
+                        transaction.timestamp,
+                        id.as_slice(),
+                    ))
+                    .map_err(Error::from)?;
+                timestamp_table
+                    .insert(
+                        (
+                            new_mint_url.to_string().as_str(),
+                            unit.as_str(),
+                            transaction.timestamp,
+                            id.as_slice(),
+                        ),
+                        (),
+                    )
+                    .map_err(Error::from)?;
+            }
+        }
+
         write_txn.commit().map_err(Error::from)?;
         Ok(())
     }
@@ -997,9 +1202,39 @@ impl WalletDatabase<database::Error> for WalletRedbDatabase {
             let mut table = write_txn
                 .open_table(TRANSACTIONS_TABLE)
                 .map_err(Error::from)?;
-            table
+            let removed = table
                 .remove(transaction_id.as_slice())
-                .map_err(Error::from)?;
+                .map_err(Error::from)?
+                .and_then(|tx| serde_json::from_str::<Transaction>(tx.value()).ok());
+
+            if let Some(removed) = &removed {
+                let mut timestamp_table = write_txn
+                    .open_table(TRANSACTION_TIMESTAMPS_TABLE)
+                    .map_err(Error::from)?;
+                timestamp_table
+                    .remove((
+                        removed.mint_url.to_string().as_str(),
+                        removed.unit.to_string().as_str(),
+                        removed.timestamp,
+                        transaction_id.as_slice(),
+                    ))
+                    .map_err(Error::from)?;
+            }
+
+            if let Some(saga_id) = removed.and_then(|tx| tx.saga_id) {
+                let mut saga_table = write_txn
+                    .open_table(TRANSACTION_SAGAS_TABLE)
+                    .map_err(Error::from)?;
+                let saga_id = saga_id.to_string();
+                let indexed = saga_table
+                    .get(saga_id.as_str())
+                    .map_err(Error::from)?
+                    .is_some_and(|tx_id| tx_id.value() == transaction_id.as_slice());
+
+                if indexed {
+                    saga_table.remove(saga_id.as_str()).map_err(Error::from)?;
+                }
+            }
         }
         write_txn.commit().map_err(Error::from)?;
         Ok(())
@@ -8,9 +8,12 @@ use bitcoin::bip32::{DerivationPath, Xpriv};
 use bitcoin::secp256k1::{self, Secp256k1};
 use cdk_common::dhke::{sign_message, verify_message};
 use cdk_common::mint::MintKeySetInfo;
-use cdk_common::nuts::{BlindSignature, BlindedMessage, CurrencyUnit, Id, MintKeySet, Proof};
+use cdk_common::nuts::{
+    BlindSignature, BlindedMessage, CurrencyUnit, Id, MintKeySet, Proof, SecretKey,
+};
+use cdk_common::util::unix_time;
 use cdk_common::{database, Error, PublicKey};
-use tokio::sync::RwLock;
+use tokio::sync::{RwLock, Semaphore};
 use tracing::instrument;
 
 use crate::common::{
@@ -18,6 +21,11 @@ use crate::common::{
 };
 use crate::signatory::{RotateKeyArguments, Signatory, SignatoryKeySet, SignatoryKeysets};
 
+/// Smallest number of blinded messages signed by a single signing task
+///
+/// Smaller batches are signed inline, as handing them to another thread costs more than signing.
+const MIN_SIGNING_BATCH: usize = 64;
+
 /// In-memory Signatory
 ///
 /// This is the default signatory implementation for the mint.
@@ -33,6 +41,8 @@ pub struct DbSignatory {
     custom_paths: HashMap<CurrencyUnit, DerivationPath>,
     xpriv: Xpriv,
     xpub: PublicKey,
+    signing_threads: usize,
+    signing_permits: Arc<Semaphore>,
 }
 
 impl DbSignatory {
@@ -63,12 +73,71 @@ impl DbSignatory {
             xpub: xpriv.to_keypair(&secp_ctx).public_key().into(),
             secp_ctx,
             xpriv,
+            signing_threads: 1,
+            signing_permits: Arc::new(Semaphore::new(1)),
         };
         keys.reload_keys_from_db().await?;
 
         Ok(keys)
     }
 
+    /// Set the number of threads used to sign blinded messages
+    ///
+    /// Large batches are split and signed in parallel on the blocking thread pool, with at most
+    /// `threads` batches being signed at once across all requests. Defaults to 1, which signs
+    /// every batch inline.
+    pub fn with_signing_threads(mut self, threads: usize) -> Self {
+        let threads = threads.max(1);
+        self.signing_threads = threads;
+        self.signing_permits = Arc::new(Semaphore::new(threads));
+        self
+    }
+
+    /// Sign a batch of blinded messages, splitting it across the signing threads if it is large
+    async fn sign_batch(
+        &self,
+        batch: Vec<(BlindedMessage, SecretKey)>,
+    ) -> Result<Vec<BlindSignature>, Error> {
+        // The blocking thread pool is not available on wasm
+        if cfg!(target_arch = "wasm32")
+            || self.signing_threads <= 1
+            || batch.len() < 2 * MIN_SIGNING_BATCH
+        {
+            return sign_batch(&batch);
+        }
+
+        let chunk_size = batch
+            .len()
+            .div_ceil(self.signing_threads)
+            .max(MIN_SIGNING_BATCH);
+
+        let mut tasks = Vec::with_capacity(batch.len().div_ceil(chunk_size));
+        for chunk in batch.chunks(chunk_size) {
+            let permit = Arc::clone(&self.signing_permits)
+                .acquire_owned()
+                .await
+                .map_err(|_| Error::Internal)?;
+            let chunk = chunk.to_vec();
+
+            tasks.push(tokio::task::spawn_blocking(move || {
+                let signatures = sign_batch(&chunk);
+                drop(permit);
+                signatures
+            }));
+        }
+
+        let mut signatures = Vec::with_capacity(batch.len());
+        for task in tasks {
+            let chunk_signatures = task.await.map_err(|err| {
+                tracing::error!("Signing task failed: {}", err);
+                Error::Internal
+            })??;
+            signatures.extend(chunk_signatures);
+        }
+
+        Ok(signatures)
+    }
+
     /// Load all the keysets from the database, even if they are not active.
     ///
     /// Since the database is owned by this process, we can load all the keysets in memory, and use
@@ -97,6 +166,51 @@ impl DbSignatory {
         Ok(())
     }
 
+    /// Keysets scheduled to become active, with their activation time
+    ///
+    /// A keyset is scheduled when it is inactive and newer than the active keyset of its unit.
+    async fn scheduled_keysets(&self) -> HashMap<Id, (CurrencyUnit, u64)> {
+        let keysets = self.keysets.read().await;
+        let active_keysets = self.active_keysets.read().await;
+
+        keysets
+            .values()
+            .filter(|(info, _)| !info.active)
+            .filter_map(|(info, _)| {
+                let (active, _) = keysets.get(active_keysets.get(&info.unit)?)?;
+                (info.valid_from > active.valid_from)
+                    .then(|| (info.id, (info.unit.clone(), info.valid_from)))
+            })
+            .collect()
+    }
+
+    /// Activate the scheduled keysets whose activation time has passed
+    async fn activate_due_keysets(&self) -> Result<(), Error> {
+        let now = unix_time();
+        let mut due = self
+            .scheduled_keysets()
+            .await
+            .into_iter()
+            .filter(|(_, (_, activate_at))| *activate_at <= now)
+            .collect::<Vec<_>>();
+
+        if due.is_empty() {
+            return Ok(());
+        }
+
+        // If several rotations of a unit are due, the latest one wins
+        due.sort_by_key(|(_, (_, activate_at))| *activate_at);
+
+        let mut tx = self.localstore.begin_transaction().await?;
+        for (id, (unit, _)) in due {
+            tracing::info!("Activating scheduled keyset {} for {}", id, unit);
+            tx.set_active_keyset(unit, id).await?;
+        }
+        tx.commit().await?;
+
+        self.reload_keys_from_db().await
+    }
+
     fn generate_keyset(&self, keyset_info: &MintKeySetInfo) -> MintKeySet {
         MintKeySet::generate_from_xpriv(
             &self.secp_ctx,
@@ -122,40 +236,33 @@ impl Signatory for DbSignatory {
         &self,
         blinded_messages: Vec<BlindedMessage>,
     ) -> Result<Vec<BlindSignature>, Error> {
-        let keysets = self.keysets.read().await;
+        let batch = {
+            let keysets = self.keysets.read().await;
 
-        blinded_messages
-            .into_iter()
-            .map(|blinded_message| {
-                let BlindedMessage {
-                    amount,
-                    blinded_secret,
-                    keyset_id,
-                    ..
-                } = blinded_message;
-
-                let (info, key) = keysets.get(&keyset_id).ok_or(Error::UnknownKeySet)?;
-                if !info.active {
-                    return Err(Error::InactiveKeyset);
-                }
-                if info.is_expired() {
-                    return Err(Error::ExpiredKeyset);
-                }
-
-                let key_pair = key.keys.get(&amount).ok_or(Error::UnknownKeySet)?;
-                let c = sign_message(&key_pair.secret_key, &blinded_secret)?;
-
-                let blinded_signature = BlindSignature::new(
-                    amount,
-                    c,
-                    keyset_id,
-                    &blinded_message.blinded_secret,
-                    &key_pair.secret_key,
-                )?;
+            blinded_messages
+                .into_iter()
+                .map(|blinded_message| {
+                    let (info, key) = keysets
+                        .get(&blinded_message.keyset_id)
+                        .ok_or(Error::UnknownKeySet)?;
+                    if !info.active {
+                        return Err(Error::InactiveKeyset);
+                    }
+                    if info.is_expired() {
+                        return Err(Error::ExpiredKeyset);
+                    }
 
-                Ok(blinded_signature)
-            })
-            .collect::<Result<Vec<_>, _>>()
+                    let key_pair = key
+                        .keys
+                        .get(&blinded_message.amount)
+                        .ok_or(Error::UnknownKeySet)?;
+
+                    Ok((blinded_message, key_pair.secret_key.clone()))
+                })
+                .collect::<Result<Vec<_>, _>>()?
+        };
+
+        self.sign_batch(batch).await
     }
 
     #[tracing::instrument(skip_all)]
@@ -172,6 +279,9 @@ impl Signatory for DbSignatory {
 
     #[tracing::instrument(skip_all)]
     async fn keysets(&self) -> Result<SignatoryKeysets, Error> {
+        self.activate_due_keysets().await?;
+        let scheduled = self.scheduled_keysets().await;
+
         Ok(SignatoryKeysets {
             pubkey: self.xpub,
             keysets: self
@@ -179,7 +289,13 @@ impl Signatory for DbSignatory {
                 .read()
                 .await
                 .values()
-                .map(|k| k.into())
+                .map(|k| {
+                    let mut keyset: SignatoryKeySet = k.into();
+                    keyset.activates_at = scheduled
+                        .get(&keyset.id)
+                        .map(|(_, activate_at)| *activate_at);
+                    keyset
+                })
                 .collect::<Vec<_>>(),
         })
     }
@@ -188,6 +304,65 @@ impl Signatory for DbSignatory {
     /// Generate new keyset
     #[tracing::instrument(skip(self))]
     async fn rotate_keyset(&self, args: RotateKeyArguments) -> Result<SignatoryKeySet, Error> {
+        let scheduled = self
+            .scheduled_keysets()
+            .await
+            .into_iter()
+            .find(|(_, (unit, _))| *unit == args.unit);
+        let activate_at = args
+            .activate_at
+            .filter(|activate_at| *activate_at > unix_time());
+
+        if let Some((scheduled_id, (unit, _))) = scheduled {
+            if activate_at.is_some() {
+                return Err(Error::Custom(format!(
+                    "A keyset rotation is already scheduled for {unit}"
+                )));
+            }
+
+            // Rotating now brings the scheduled rotation forward
+            tracing::info!(
+                "Activating scheduled keyset {} for {} early",
+                scheduled_id,
+                unit
+            );
+            let mut info = self
+                .keysets
+                .read()
+                .await
+                .get(&scheduled_id)
+                .map(|(info, _)| info.clone())
+                .ok_or(Error::UnknownKeySet)?;
+            info.valid_from = unix_time();
+
+            let mut tx = self.localstore.begin_transaction().await?;
+            tx.add_keyset_info(info).await?;
+            tx.set_active_keyset(unit, scheduled_id).await?;
+            tx.commit().await?;
+            self.reload_keys_from_db().await?;
+
+            return self
+                .keysets
+                .read()
+                .await
+                .get(&scheduled_id)
+                .map(Into::into)
+                .ok_or(Error::UnknownKeySet);
+        }
+
+        if activate_at.is_some()
+            && self
+                .localstore
+                .get_active_keyset_id(&args.unit)
+                .await?
+                .is_none()
+        {
+            return Err(Error::Custom(format!(
+                "Cannot schedule a rotation for {} without an active keyset",
+                args.unit
+            )));
+        }
+
         let (path_index, amounts) = if let Some(current_keyset_id) =
             self.localstore.get_active_keyset_id(&args.unit).await?
         {
@@ -220,7 +395,7 @@ impl Signatory for DbSignatory {
             args.amounts
         };
 
-        let (keyset, info) = create_new_keyset(
+        let (keyset, mut info) = create_new_keyset(
             &self.secp_ctx,
             self.xpriv,
             derivation_path,
@@ -235,18 +410,45 @@ impl Signatory for DbSignatory {
         let keysets = self.keysets().await?;
         check_unit_string_collision(keysets.keysets, &info)?;
 
+        if let Some(activate_at) = activate_at {
+            info.active = false;
+            info.valid_from = activate_at;
+        }
+
         let id = info.id;
         let mut tx = self.localstore.begin_transaction().await?;
         tx.add_keyset_info(info.clone()).await?;
-        tx.set_active_keyset(args.unit, id).await?;
+        if activate_at.is_none() {
+            tx.set_active_keyset(args.unit, id).await?;
+        }
         tx.commit().await?;
 
         self.reload_keys_from_db().await?;
 
-        Ok((&(info, keyset)).into())
+        let mut keyset: SignatoryKeySet = (&(info, keyset)).into();
+        keyset.activates_at = activate_at;
+        Ok(keyset)
     }
 }
 
+/// Sign blinded messages with the secret keys they were resolved to
+fn sign_batch(batch: &[(BlindedMessage, SecretKey)]) -> Result<Vec<BlindSignature>, Error> {
+    batch
+        .iter()
+        .map(|(blinded_message, secret_key)| {
+            let c = sign_message(secret_key, &blinded_message.blinded_secret)?;
+
+            BlindSignature::new(
+                blinded_message.amount,
+                c,
+                blinded_message.keyset_id,
+                &blinded_message.blinded_secret,
+                secret_key,
+            )
+        })
+        .collect()
+}
+
 #[cfg(test)]
 mod test {
     use std::collections::HashSet;
@@ -282,6 +484,7 @@ mod test {
                 input_fee_ppk: 0,
                 keyset_id_type: cdk_common::nut02::KeySetVersion::Version00,
                 final_expiry: Some(unix_time() - 1),
+                activate_at: None,
             })
             .await
             .expect("rotate_keyset");
@@ -299,6 +502,116 @@ mod test {
         );
     }
 
+    #[tokio::test]
+    async fn scheduled_keyset_is_published_inactive_until_due() {
+        let store = Arc::new(
+            cdk_sqlite::mint::memory::empty()
+                .await
+                .expect("in-memory db"),
+        );
+        let signatory = DbSignatory::new(
+            store,
+            b"test-seed-for-unit-tests",
+            HashMap::from([(CurrencyUnit::Sat, (0, vec![1, 2, 4, 8]))]),
+            Default::default(),
+        )
+        .await
+        .expect("DbSignatory::new");
+
+        let rotate = |activate_at| RotateKeyArguments {
+            unit: CurrencyUnit::Sat,
+            amounts: vec![1, 2, 4, 8],
+            input_fee_ppk: 0,
+            keyset_id_type: cdk_common::nut02::KeySetVersion::Version00,
+            final_expiry: None,
+            activate_at,
+        };
+
+        let activate_at = unix_time() + 1;
+        let scheduled = signatory
+            .rotate_keyset(rotate(Some(activate_at)))
+            .await
+            .expect("schedule rotation");
+        assert!(!scheduled.active);
+        assert_eq!(scheduled.activates_at, Some(activate_at));
+
+        let published = signatory.keysets().await.expect("keysets");
+        let keyset = published
+            .keysets
+            .iter()
+            .find(|keyset| keyset.id == scheduled.id)
+            .expect("scheduled keyset is published");
+        assert!(!keyset.active);
+        assert_eq!(keyset.activates_at, Some(activate_at));
+
+        assert!(signatory
+            .rotate_keyset(rotate(Some(activate_at + 10)))
+            .await
+            .is_err());
+
+        tokio::time::sleep(std::time::Duration::from_secs(2)).await;
+
+        let published = signatory.keysets().await.expect("keysets");
+        let keyset = published
+            .keysets
+            .iter()
+            .find(|keyset| keyset.id == scheduled.id)
+            .expect("scheduled keyset is published");
+        assert!(keyset.active);
+        assert_eq!(keyset.activates_at, None);
+    }
+
+    #[tokio::test]
+    async fn blind_sign_parallel_matches_inline() {
+        let store = Arc::new(
+            cdk_sqlite::mint::memory::empty()
+                .await
+                .expect("in-memory db"),
+        );
+        let signatory = DbSignatory::new(
+            store,
+            b"test-seed-for-unit-tests",
+            HashMap::from([(CurrencyUnit::Sat, (0, vec![1, 2, 4, 8]))]),
+            Default::default(),
+        )
+        .await
+        .expect("DbSignatory::new");
+
+        let keyset_id = *signatory
+            .active_keysets
+            .read()
+            .await
+            .get(&CurrencyUnit::Sat)
+            .expect("active sat keyset");
+
+        let messages: Vec<BlindedMessage> = (0..(4 * MIN_SIGNING_BATCH + 1))
+            .map(|i| {
+                BlindedMessage::new(
+                    Amount::from(1 << (i % 4)),
+                    keyset_id,
+                    SecretKey::generate().public_key(),
+                )
+            })
+            .collect();
+
+        let inline = signatory
+            .blind_sign(messages.clone())
+            .await
+            .expect("inline signing");
+
+        let signatory = signatory.with_signing_threads(4);
+        let parallel = signatory
+            .blind_sign(messages)
+            .await
+            .expect("parallel signing");
+
+        assert_eq!(inline.len(), parallel.len());
+        for (inline, parallel) in inline.iter().zip(parallel.iter()) {
+            assert_eq!(inline.amount, parallel.amount);
+            assert_eq!(inline.c, parallel.c);
+        }
+    }
+
     #[test]
     fn mint_mod_generate_keyset_from_seed() {
         let seed = hex::decode("0000000000000000000000000000000000000000000000000000000000000001")
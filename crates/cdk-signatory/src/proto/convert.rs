@@ -72,6 +72,7 @@ impl TryInto<crate::signatory::SignatoryKeySet> for KeySet {
                 .map(|v| IssuerVersion::from_str(&v))
                 .transpose()
                 .map_err(|e| cdk_common::Error::Custom(e.to_string()))?,
+            activates_at: self.activates_at,
         })
     }
 }
@@ -93,6 +94,7 @@ impl From<crate::signatory::SignatoryKeySet> for KeySet {
             final_expiry: keyset.final_expiry,
             version: Default::default(),
             issuer_version: keyset.issuer_version.map(|v| v.to_string()),
+            activates_at: keyset.activates_at,
         }
     }
 }
@@ -348,6 +350,7 @@ impl From<crate::signatory::RotateKeyArguments> for RotationRequest {
             input_fee_ppk: value.input_fee_ppk,
             keyset_id_type: value.keyset_id_type.to_proto_i32(),
             final_expiry: value.final_expiry,
+            activate_at: value.activate_at,
         }
     }
 }
@@ -366,6 +369,7 @@ impl TryInto<crate::signatory::RotateKeyArguments> for RotationRequest {
             final_expiry: self.final_expiry,
             keyset_id_type: KeySetVersion::from_proto_i32(self.keyset_id_type)
                 .map_err(|err| Status::invalid_argument(err.to_string()))?,
+            activate_at: self.activate_at,
         })
     }
 }
@@ -381,6 +385,7 @@ impl From<cdk_common::KeySetInfo> for KeySet {
             final_expiry: value.final_expiry,
             version: Default::default(),
             issuer_version: None,
+            activates_at: None,
         }
     }
 }
@@ -52,6 +52,8 @@ pub struct RotateKeyArguments {
     pub keyset_id_type: KeySetVersion,
     /// FinalExpiry
     pub final_expiry: Option<u64>,
+    /// When set, the keyset is published inactive and activated at this unix timestamp
+    pub activate_at: Option<u64>,
 }
 
 #[derive(Debug, Clone)]
@@ -87,6 +89,8 @@ pub struct SignatoryKeySet {
     pub issuer_version: Option<IssuerVersion>,
     /// Version is the derivation_path_index
     pub version: u32,
+    /// Unix timestamp at which this inactive keyset is scheduled to become active
+    pub activates_at: Option<u64>,
 }
 
 impl SignatoryKeySet {
@@ -134,7 +138,7 @@ impl From<SignatoryKeySet> for MintKeySetInfo {
             amounts: val.amounts,
             final_expiry: val.final_expiry,
             issuer_version: val.issuer_version,
-            valid_from: 0,
+            valid_from: val.activates_at.unwrap_or_default(),
         }
     }
 }
@@ -151,6 +155,7 @@ impl From<&(MintKeySetInfo, MintKeySet)> for SignatoryKeySet {
             version: info.derivation_path_index.unwrap_or(1),
             final_expiry: key.final_expiry,
             issuer_version: info.issuer_version.clone(),
+            activates_at: None,
         }
     }
 }
@@ -203,6 +208,7 @@ mod tests {
             final_expiry,
             issuer_version: None,
             version: 0,
+            activates_at: None,
         }
     }
 
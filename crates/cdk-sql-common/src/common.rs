@@ -1,9 +1,13 @@
+use std::collections::{HashMap, HashSet};
 use std::fmt::Debug;
 use std::future::Future;
 use std::time::Instant;
 
-use cdk_common::database::Error;
+use bitcoin::hashes::sha256::Hash as Sha256;
+use bitcoin::hashes::Hash;
+use cdk_common::database::{Error, MigrationState, MigrationStatus};
 
+use crate::column_as_string;
 use crate::database::DatabaseExecutor;
 use crate::stmt::query;
 
@@ -69,13 +73,8 @@ where
     result
 }
 
-/// Migrates the migration generated by `build.rs`
-#[inline(always)]
-pub async fn migrate<C>(
-    conn: &C,
-    db_prefix: &str,
-    migrations: &[(&str, &str, &str)],
-) -> Result<(), Error>
+/// Creates the tables tracking applied migrations and their checksums
+async fn create_migration_tables<C>(conn: &C) -> Result<(), Error>
 where
     C: DatabaseExecutor,
 {
@@ -90,22 +89,178 @@ where
     .execute(conn)
     .await?;
 
-    // Apply each migration if it hasn’t been applied yet
-    for (prefix, name, sql) in migrations {
-        if !prefix.is_empty() && *prefix != db_prefix {
-            continue;
+    // Kept in a table of its own, adding a column to `migrations` is not portable
+    query(
+        r#"
+           CREATE TABLE IF NOT EXISTS migration_checksums (
+               name TEXT PRIMARY KEY,
+               checksum TEXT NOT NULL
+           )
+           "#,
+    )?
+    .execute(conn)
+    .await?;
+
+    Ok(())
+}
+
+/// Hex encoded sha256 of a migration
+fn checksum(sql: &str) -> String {
+    Sha256::hash(sql.as_bytes()).to_string()
+}
+
+/// Migrations of `migrations` that apply to `db_prefix`, in the order they are applied
+fn applicable<'a>(
+    db_prefix: &'a str,
+    migrations: &'a [(&'a str, &'a str, &'a str)],
+) -> impl Iterator<Item = &'a (&'a str, &'a str, &'a str)> {
+    migrations
+        .iter()
+        .filter(move |(prefix, _, _)| prefix.is_empty() || *prefix == db_prefix)
+}
+
+/// Status of every migration of `migrations` that applies to `db_prefix`
+///
+/// A pending migration is out of order when a migration after it was already applied, and an
+/// applied migration mismatches when its recorded checksum differs. Migrations applied before
+/// checksums were tracked have no checksum and are reported as applied.
+pub async fn migration_status<C>(
+    conn: &C,
+    db_prefix: &str,
+    migrations: &[(&str, &str, &str)],
+) -> Result<Vec<MigrationStatus>, Error>
+where
+    C: DatabaseExecutor,
+{
+    create_migration_tables(conn).await?;
+
+    let applied = query(r#"SELECT name FROM migrations"#)?
+        .fetch_all(conn)
+        .await?
+        .into_iter()
+        .map(|row| Ok(column_as_string!(&row[0])))
+        .collect::<Result<HashSet<_>, Error>>()?;
+
+    let checksums = query(r#"SELECT name, checksum FROM migration_checksums"#)?
+        .fetch_all(conn)
+        .await?
+        .into_iter()
+        .map(|row| Ok((column_as_string!(&row[0]), column_as_string!(&row[1]))))
+        .collect::<Result<HashMap<_, _>, Error>>()?;
+
+    let mut statuses = applicable(db_prefix, migrations)
+        .map(|(_, name, sql)| {
+            let state = if !applied.contains(*name) {
+                MigrationState::Pending
+            } else if checksums
+                .get(*name)
+                .is_some_and(|recorded| *recorded != checksum(sql))
+            {
+                MigrationState::ChecksumMismatch
+            } else {
+                MigrationState::Applied
+            };
+
+            MigrationStatus {
+                name: name.to_string(),
+                state,
+            }
+        })
+        .collect::<Vec<_>>();
+
+    let mut later_applied = false;
+    for status in statuses.iter_mut().rev() {
+        match status.state {
+            MigrationState::Pending if later_applied => status.state = MigrationState::OutOfOrder,
+            MigrationState::Pending | MigrationState::OutOfOrder => {}
+            MigrationState::Applied | MigrationState::ChecksumMismatch => later_applied = true,
         }
+    }
 
-        let is_missing = query("SELECT name FROM migrations WHERE name = :name")?
+    Ok(statuses)
+}
+
+/// Aborts if the database diverged from `migrations`, otherwise returns the pending migrations
+async fn check_migrations<C>(
+    conn: &C,
+    db_prefix: &str,
+    migrations: &[(&str, &str, &str)],
+) -> Result<Vec<String>, Error>
+where
+    C: DatabaseExecutor,
+{
+    let statuses = migration_status(conn, db_prefix, migrations).await?;
+
+    let diverged = statuses
+        .iter()
+        .filter(|status| status.state.is_divergent())
+        .map(|status| format!("{} ({})", status.name, status.state))
+        .collect::<Vec<_>>();
+
+    if !diverged.is_empty() {
+        tracing::error!("Database diverged from migrations: {}", diverged.join(", "));
+        return Err(Error::MigrationDiverged(diverged.join(", ")));
+    }
+
+    Ok(statuses
+        .into_iter()
+        .filter(|status| status.state == MigrationState::Pending)
+        .map(|status| status.name)
+        .collect())
+}
+
+/// Returns the migrations [`migrate`] would apply, without applying them
+///
+/// Fails like [`migrate`] if the database diverged. Only the bookkeeping tables are created.
+pub async fn migrate_dry_run<C>(
+    conn: &C,
+    db_prefix: &str,
+    migrations: &[(&str, &str, &str)],
+) -> Result<Vec<String>, Error>
+where
+    C: DatabaseExecutor,
+{
+    check_migrations(conn, db_prefix, migrations).await
+}
+
+/// Migrates the migration generated by `build.rs`
+///
+/// Nothing is applied if the database diverged from `migrations`, see [`migration_status`].
+#[inline(always)]
+pub async fn migrate<C>(
+    conn: &C,
+    db_prefix: &str,
+    migrations: &[(&str, &str, &str)],
+) -> Result<(), Error>
+where
+    C: DatabaseExecutor,
+{
+    let pending = check_migrations(conn, db_prefix, migrations)
+        .await?
+        .into_iter()
+        .collect::<HashSet<_>>();
+
+    for (_, name, sql) in applicable(db_prefix, migrations) {
+        if pending.contains(*name) {
+            tracing::info!("Applying migration {}", name);
+            query(sql)?.batch(conn).await?;
+            query(r#"INSERT INTO migrations (name) VALUES (:name)"#)?
+                .bind("name", name)
+                .execute(conn)
+                .await?;
+        }
+
+        // Also records the checksum of migrations applied before checksums were tracked
+        let has_checksum = query("SELECT name FROM migration_checksums WHERE name = :name")?
             .bind("name", name)
             .pluck(conn)
             .await?
-            .is_none();
+            .is_some();
 
-        if is_missing {
-            query(sql)?.batch(conn).await?;
-            query(r#"INSERT INTO migrations (name) VALUES (:name)"#)?
+        if !has_checksum {
+            query(r#"INSERT INTO migration_checksums (name, checksum) VALUES (:name, :checksum)"#)?
                 .bind("name", name)
+                .bind("checksum", checksum(sql))
                 .execute(conn)
                 .await?;
         }
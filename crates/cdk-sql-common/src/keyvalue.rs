@@ -89,6 +89,61 @@ where
     Ok(())
 }
 
+/// Generic implementation of kv_compare_and_write for transactions
+///
+/// The write is a single conditional statement, so a concurrent writer that changed the value
+/// since it was read makes it affect no row.
+#[cfg(feature = "mint")]
+pub(crate) async fn kv_compare_and_write_in_transaction<RM>(
+    conn: &ConnectionWithTransaction<RM::Connection, PooledResource<RM>>,
+    primary_namespace: &str,
+    secondary_namespace: &str,
+    key: &str,
+    expected: Option<&[u8]>,
+    value: &[u8],
+) -> Result<bool, Error>
+where
+    RM: DatabasePool,
+{
+    // Validate parameters according to KV store requirements
+    validate_kvstore_params(primary_namespace, secondary_namespace, Some(key))?;
+
+    let current_time = unix_time();
+
+    let written = match expected {
+        Some(expected) => {
+            query(
+                r#"
+                UPDATE kv_store
+                SET value = :value, updated_time = :updated_time
+                WHERE primary_namespace = :primary_namespace
+                AND secondary_namespace = :secondary_namespace
+                AND key = :key
+                AND value = :expected
+                "#,
+            )?
+            .bind("expected", expected.to_vec())
+        }
+        None => query(
+            r#"
+            INSERT INTO kv_store
+            (primary_namespace, secondary_namespace, key, value, created_time, updated_time)
+            VALUES (:primary_namespace, :secondary_namespace, :key, :value, :updated_time, :updated_time)
+            ON CONFLICT(primary_namespace, secondary_namespace, key) DO NOTHING
+            "#,
+        )?,
+    }
+    .bind("primary_namespace", primary_namespace.to_owned())
+    .bind("secondary_namespace", secondary_namespace.to_owned())
+    .bind("key", key.to_owned())
+    .bind("value", value.to_vec())
+    .bind("updated_time", current_time as i64)
+    .execute(conn)
+    .await?;
+
+    Ok(written == 1)
+}
+
 /// Generic implementation of kv_remove for transactions
 #[cfg(feature = "mint")]
 pub(crate) async fn kv_remove_in_transaction<RM>(
@@ -214,7 +269,7 @@ where
     .collect::<Result<Vec<_>, Error>>()
 }
 
-/// Generic implementation of kv_write for database (non-transactional, standalone)
+/// Generic implementation of kv_write for a wallet connection or transaction
 #[cfg(feature = "wallet")]
 pub(crate) async fn kv_write_standalone<C>(
     conn: &C,
@@ -254,7 +309,7 @@ where
     Ok(())
 }
 
-/// Generic implementation of kv_remove for database (non-transactional, standalone)
+/// Generic implementation of kv_remove for a wallet connection or transaction
 #[cfg(feature = "wallet")]
 pub(crate) async fn kv_remove_standalone<C>(
     conn: &C,
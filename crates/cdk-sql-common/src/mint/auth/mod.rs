@@ -56,7 +56,7 @@ where
 }
 
 #[rustfmt::skip]
-mod migrations {
+pub(crate) mod migrations {
     include!(concat!(env!("OUT_DIR"), "/migrations_mint_auth.rs"));
 }
 
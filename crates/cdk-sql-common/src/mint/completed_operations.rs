@@ -1,17 +1,21 @@
 //! Completed operations database implementation
 
+use std::collections::HashMap;
 use std::str::FromStr;
 
 use async_trait::async_trait;
 use cdk_common::database::mint::{CompletedOperationsDatabase, CompletedOperationsTransaction};
 use cdk_common::database::Error;
 use cdk_common::util::unix_time;
-use cdk_common::{mint, Amount, PaymentMethod};
+use cdk_common::{mint, Amount, CurrencyUnit, PaymentMethod};
 
 use super::{SQLMintDatabase, SQLTransaction};
 use crate::pool::DatabasePool;
 use crate::stmt::{query, Column};
-use crate::{column_as_nullable_string, column_as_number, column_as_string, unpack_into};
+use crate::{
+    column_as_nullable_number, column_as_nullable_string, column_as_number, column_as_string,
+    unpack_into,
+};
 
 fn sql_row_to_completed_operation(row: Vec<Column>) -> Result<mint::Operation, Error> {
     unpack_into!(
@@ -22,7 +26,10 @@ fn sql_row_to_completed_operation(row: Vec<Column>) -> Result<mint::Operation, E
             total_issued,
             total_redeemed,
             fee_collected,
-            payment_method
+            payment_method,
+            payment_amount,
+            payment_fee,
+            unit
         ) = row
     );
 
@@ -48,7 +55,15 @@ fn sql_row_to_completed_operation(row: Vec<Column>) -> Result<mint::Operation, E
         .transpose()
         .map_err(|e| Error::Internal(format!("Invalid payment method: {e}")))?;
 
-    Ok(mint::Operation::new(
+    let unit = column_as_nullable_string!(unit)
+        .map(|s| CurrencyUnit::from_str(&s))
+        .transpose()
+        .map_err(|e| Error::Internal(format!("Invalid unit: {e}")))?;
+
+    let payment_amount: Option<u64> = column_as_nullable_number!(payment_amount);
+    let payment_fee: Option<u64> = column_as_nullable_number!(payment_fee);
+
+    let mut operation = mint::Operation::new(
         operation_id,
         operation_kind,
         total_issued,
@@ -56,7 +71,43 @@ fn sql_row_to_completed_operation(row: Vec<Column>) -> Result<mint::Operation, E
         fee_collected,
         Some(completed_at),
         payment_method,
-    ))
+    );
+
+    if let (Some(payment_amount), Some(payment_fee)) = (payment_amount, payment_fee) {
+        operation.set_payment_details(Amount::from(payment_amount), Amount::from(payment_fee));
+    }
+
+    Ok(match unit {
+        Some(unit) => operation.with_unit(unit),
+        None => operation,
+    })
+}
+
+/// Fee totals per unit from `unit, kind, total` rows
+fn sql_rows_to_fee_totals(
+    rows: Vec<Vec<Column>>,
+) -> Result<HashMap<CurrencyUnit, mint::FeeTotals>, Error> {
+    let mut totals: HashMap<CurrencyUnit, mint::FeeTotals> = HashMap::new();
+
+    for row in rows {
+        unpack_into!(let (unit, kind, total) = row);
+
+        let unit = column_as_string!(unit, CurrencyUnit::from_str);
+        let kind = mint::FeeLedgerKind::from_str(&column_as_string!(&kind))
+            .map_err(|e| Error::Internal(e.to_string()))?;
+        let total: u64 = column_as_number!(total);
+
+        let unit_totals = totals.entry(unit).or_default();
+        match kind {
+            mint::FeeLedgerKind::InputFee => unit_totals.input_fees = Amount::from(total),
+            mint::FeeLedgerKind::LightningSurplus => {
+                unit_totals.lightning_surplus = Amount::from(total)
+            }
+            mint::FeeLedgerKind::Sweep => unit_totals.swept = Amount::from(total),
+        }
+    }
+
+    Ok(totals)
 }
 
 #[async_trait]
@@ -74,9 +125,9 @@ where
         query(
             r#"
             INSERT INTO completed_operations
-            (operation_id, operation_kind, completed_at, total_issued, total_redeemed, fee_collected, payment_amount, payment_fee, payment_method)
+            (operation_id, operation_kind, completed_at, total_issued, total_redeemed, fee_collected, payment_amount, payment_fee, payment_method, unit)
             VALUES
-            (:operation_id, :operation_kind, :completed_at, :total_issued, :total_redeemed, :fee_collected, :payment_amount, :payment_fee, :payment_method)
+            (:operation_id, :operation_kind, :completed_at, :total_issued, :total_redeemed, :fee_collected, :payment_amount, :payment_fee, :payment_method, :unit)
             "#,
         )?
         .bind("operation_id", operation.id().to_string())
@@ -88,6 +139,7 @@ where
         .bind("payment_amount", operation.payment_amount().map(|a| a.to_u64() as i64))
         .bind("payment_fee", operation.payment_fee().map(|a| a.to_u64() as i64))
         .bind("payment_method", operation.payment_method().map(|m| m.to_string()))
+        .bind("unit", operation.unit().map(|u| u.to_string()))
         .execute(&self.inner)
         .await?;
 
@@ -106,11 +158,90 @@ where
                 .bind("fee", fee.to_u64() as i64)
                 .execute(&self.inner)
                 .await?;
+
+                query(
+                    r#"
+                    INSERT INTO fee_ledger (kind, unit, amount, keyset_id, operation_id, created_time)
+                    SELECT :kind, unit, :amount, id, :operation_id, :created_time
+                    FROM keyset
+                    WHERE id = :keyset_id
+                    "#,
+                )?
+                .bind("kind", mint::FeeLedgerKind::InputFee.to_string())
+                .bind("amount", fee.to_u64() as i64)
+                .bind("operation_id", operation.id().to_string())
+                .bind("created_time", unix_time() as i64)
+                .bind("keyset_id", keyset_id.to_string())
+                .execute(&self.inner)
+                .await?;
             }
         }
 
         Ok(())
     }
+
+    async fn add_fee_ledger_entry(
+        &mut self,
+        entry: &mint::FeeLedgerEntry,
+    ) -> Result<(), Self::Err> {
+        query(
+            r#"
+            INSERT INTO fee_ledger
+            (kind, unit, amount, keyset_id, operation_id, destination, created_time)
+            VALUES
+            (:kind, :unit, :amount, :keyset_id, :operation_id, :destination, :created_time)
+            "#,
+        )?
+        .bind("kind", entry.kind.to_string())
+        .bind("unit", entry.unit.to_string())
+        .bind("amount", entry.amount.to_u64() as i64)
+        .bind("keyset_id", entry.keyset_id.map(|id| id.to_string()))
+        .bind("operation_id", entry.operation_id.map(|id| id.to_string()))
+        .bind("destination", entry.destination.clone())
+        .bind("created_time", entry.created_time as i64)
+        .execute(&self.inner)
+        .await?;
+
+        Ok(())
+    }
+
+    async fn get_fee_totals_for_update(
+        &mut self,
+        unit: &CurrencyUnit,
+    ) -> Result<mint::FeeTotals, Self::Err> {
+        // Aggregates cannot be locked, lock the ledger rows of the unit first
+        query(
+            r#"
+            SELECT id
+            FROM fee_ledger
+            WHERE unit = :unit
+            FOR UPDATE
+            "#,
+        )?
+        .bind("unit", unit.to_string())
+        .fetch_all(&self.inner)
+        .await?;
+
+        let rows = query(
+            r#"
+            SELECT
+                unit,
+                kind,
+                CAST(SUM(amount) AS BIGINT) AS total
+            FROM
+                fee_ledger
+            WHERE unit = :unit
+            GROUP BY unit, kind
+            "#,
+        )?
+        .bind("unit", unit.to_string())
+        .fetch_all(&self.inner)
+        .await?;
+
+        Ok(sql_rows_to_fee_totals(rows)?
+            .remove(unit)
+            .unwrap_or_default())
+    }
 }
 
 #[async_trait]
@@ -138,7 +269,10 @@ where
                 total_issued,
                 total_redeemed,
                 fee_collected,
-                payment_method
+                payment_method,
+                payment_amount,
+                payment_fee,
+                unit
             FROM
                 completed_operations
             WHERE
@@ -170,7 +304,10 @@ where
                 total_issued,
                 total_redeemed,
                 fee_collected,
-                payment_method
+                payment_method,
+                payment_amount,
+                payment_fee,
+                unit
             FROM
                 completed_operations
             WHERE
@@ -201,7 +338,10 @@ where
                 total_issued,
                 total_redeemed,
                 fee_collected,
-                payment_method
+                payment_method,
+                payment_amount,
+                payment_fee,
+                unit
             FROM
                 completed_operations
             ORDER BY completed_at DESC
@@ -213,4 +353,70 @@ where
         .map(sql_row_to_completed_operation)
         .collect::<Result<Vec<_>, _>>()?)
     }
+
+    async fn get_completed_operations_in_range(
+        &self,
+        from: u64,
+        to: u64,
+        offset: u64,
+        limit: u64,
+    ) -> Result<Vec<mint::Operation>, Self::Err> {
+        let conn = self
+            .pool
+            .get()
+            .await
+            .map_err(|e| Error::Database(Box::new(e)))?;
+        Ok(query(&format!(
+            r#"
+            SELECT
+                operation_id,
+                operation_kind,
+                completed_at,
+                total_issued,
+                total_redeemed,
+                fee_collected,
+                payment_method,
+                payment_amount,
+                payment_fee,
+                unit
+            FROM
+                completed_operations
+            WHERE
+                completed_at >= :from
+                AND completed_at < :to
+            ORDER BY completed_at, operation_id
+            LIMIT {limit} OFFSET {offset}
+            "#
+        ))?
+        .bind("from", from as i64)
+        .bind("to", to.min(i64::MAX as u64) as i64)
+        .fetch_all(&*conn)
+        .await?
+        .into_iter()
+        .map(sql_row_to_completed_operation)
+        .collect::<Result<Vec<_>, _>>()?)
+    }
+
+    async fn get_fee_totals(&self) -> Result<HashMap<CurrencyUnit, mint::FeeTotals>, Self::Err> {
+        let conn = self
+            .pool
+            .get()
+            .await
+            .map_err(|e| Error::Database(Box::new(e)))?;
+        let rows = query(
+            r#"
+            SELECT
+                unit,
+                kind,
+                CAST(SUM(amount) AS BIGINT) AS total
+            FROM
+                fee_ledger
+            GROUP BY unit, kind
+            "#,
+        )?
+        .fetch_all(&*conn)
+        .await?;
+
+        sql_rows_to_fee_totals(rows)
+    }
 }
@@ -44,6 +44,25 @@ where
         .await
     }
 
+    async fn kv_compare_and_write(
+        &mut self,
+        primary_namespace: &str,
+        secondary_namespace: &str,
+        key: &str,
+        expected: Option<&[u8]>,
+        value: &[u8],
+    ) -> Result<bool, Error> {
+        crate::keyvalue::kv_compare_and_write_in_transaction(
+            &self.inner,
+            primary_namespace,
+            secondary_namespace,
+            key,
+            expected,
+            value,
+        )
+        .await
+    }
+
     async fn kv_remove(
         &mut self,
         primary_namespace: &str,
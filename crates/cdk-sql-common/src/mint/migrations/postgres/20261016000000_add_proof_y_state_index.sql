@@ -0,0 +1,2 @@
+-- Covering index so proof state lookups by Y can use index-only scans
+CREATE INDEX IF NOT EXISTS idx_proof_y_state ON proof(y) INCLUDE (state);
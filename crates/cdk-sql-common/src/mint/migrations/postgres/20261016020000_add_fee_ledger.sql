@@ -0,0 +1,20 @@
+-- Ledger of fee income: input fees, Lightning fee surplus and sweeps
+CREATE TABLE IF NOT EXISTS fee_ledger (
+    id SERIAL PRIMARY KEY,
+    kind TEXT NOT NULL,
+    unit TEXT NOT NULL,
+    amount BIGINT NOT NULL,
+    keyset_id TEXT,
+    operation_id TEXT,
+    destination TEXT,
+    created_time BIGINT NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_fee_ledger_unit_kind ON fee_ledger(unit, kind);
+
+-- Carry over input fees collected before the ledger existed
+INSERT INTO fee_ledger (kind, unit, amount, keyset_id, created_time)
+SELECT 'input_fee', keyset.unit, keyset_amounts.fee_collected, keyset_amounts.keyset_id, 0
+FROM keyset_amounts
+JOIN keyset ON keyset.id = keyset_amounts.keyset_id
+WHERE keyset_amounts.fee_collected > 0;
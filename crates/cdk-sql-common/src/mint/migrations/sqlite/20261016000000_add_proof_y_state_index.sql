@@ -0,0 +1,2 @@
+-- Covering index so proof state lookups by Y do not touch the table rows
+CREATE INDEX IF NOT EXISTS idx_proof_y_state ON proof(y, state);
@@ -0,0 +1,7 @@
+-- Compact archive of spent proofs moved out of the proof table
+CREATE TABLE IF NOT EXISTS spent_proof_archive (
+    y BLOB PRIMARY KEY,
+    keyset_id TEXT NOT NULL,
+    amount INTEGER NOT NULL,
+    archived_time INTEGER NOT NULL
+);
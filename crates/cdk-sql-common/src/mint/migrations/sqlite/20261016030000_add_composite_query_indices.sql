@@ -0,0 +1,13 @@
+-- Composite indices for spent proof archiving and melt quote expiry queries
+--
+-- Query plans on large mints before this migration (SQLite EXPLAIN QUERY PLAN):
+--   SELECT y FROM proof WHERE state = ? AND created_time < ? LIMIT ?
+--     SEARCH proof USING INDEX idx_proof_state_operation (state=?)
+--   SELECT id FROM melt_quote WHERE state = ? AND expiry < ?
+--     SEARCH melt_quote USING INDEX melt_quote_state_index (state=?)
+--
+-- After:
+--     SEARCH proof USING INDEX idx_proof_state_created_time (state=? AND created_time<?)
+--     SEARCH melt_quote USING INDEX idx_melt_quote_state_expiry (state=? AND expiry<?)
+CREATE INDEX IF NOT EXISTS idx_proof_state_created_time ON proof(state, created_time);
+CREATE INDEX IF NOT EXISTS idx_melt_quote_state_expiry ON melt_quote(state, expiry);
@@ -0,0 +1,2 @@
+-- Unit of the amounts of a completed operation, NULL for operations recorded before
+ALTER TABLE completed_operations ADD COLUMN unit TEXT;
@@ -0,0 +1,14 @@
+-- Time a proof was marked spent, spent proofs are archived by this time rather than their creation
+ALTER TABLE proof ADD COLUMN spent_time INTEGER;
+
+-- The spend time of proofs spent before this migration is unknown, so their retention starts now
+UPDATE proof SET spent_time = CAST(strftime('%s', 'now') AS INTEGER) WHERE state = 'SPENT';
+
+-- idx_proof_state_created_time was only used by the spent proof archive query, which now filters
+-- on spent_time instead of created_time. idx_proof_state_spent_time takes its place for that query:
+--   SELECT y FROM proof WHERE state = ? AND spent_time < ? LIMIT ?
+--     SEARCH proof USING INDEX idx_proof_state_spent_time (state=? AND spent_time<?)
+-- No other query filters proofs by state and created_time, so the old index is dropped rather than
+-- kept up to date on every proof insert.
+DROP INDEX IF EXISTS idx_proof_state_created_time;
+CREATE INDEX IF NOT EXISTS idx_proof_state_spent_time ON proof(state, spent_time);
@@ -8,15 +8,18 @@
 //! The trait expects an asynchronous interaction, but it also provides tools to spawn blocking
 //! clients in a pool and expose them to an asynchronous environment, making them compatible with
 //! Mint.
+use std::collections::HashSet;
 use std::fmt::Debug;
 use std::sync::Arc;
 
 use async_trait::async_trait;
-use cdk_common::database::{self, DbTransactionFinalizer, Error, MintDatabase};
+use cdk_common::database::{self, DbTransactionFinalizer, Error, MigrationStatus, MintDatabase};
 
-use crate::common::migrate;
+use crate::column_as_string;
+use crate::common::{migrate, migration_status};
 use crate::database::{ConnectionWithTransaction, DatabaseExecutor};
 use crate::pool::{DatabasePool, Pool, PooledResource};
+use crate::stmt::query;
 
 mod auth;
 mod completed_operations;
@@ -71,6 +74,25 @@ where
         Ok(Self { pool })
     }
 
+    /// Status of the migrations of `db`, without applying any
+    ///
+    /// Unlike [`Self::new`] this also succeeds when the database diverged from the migrations of
+    /// this build, so the divergence can be inspected.
+    pub async fn inspect_migrations<X>(db: X) -> Result<Vec<MigrationStatus>, Error>
+    where
+        X: Into<RM::Config>,
+    {
+        let pool = Pool::<RM>::new(db.into());
+        let tx = ConnectionWithTransaction::new(
+            pool.get().await.map_err(|e| Error::Database(Box::new(e)))?,
+        )
+        .await?;
+        let statuses = migration_status(&tx, RM::Connection::name(), MIGRATIONS).await;
+        // Roll back so not even the bookkeeping tables are created
+        tx.rollback().await?;
+        statuses
+    }
+
     /// Migrate
     async fn migrate(conn: PooledResource<RM>) -> Result<(), Error> {
         let tx = ConnectionWithTransaction::new(conn).await?;
@@ -138,6 +160,41 @@ where
 
         Ok(Box::new(tx))
     }
+
+    async fn unknown_migrations(&self) -> Result<Vec<String>, Error> {
+        let conn = self
+            .pool
+            .get()
+            .await
+            .map_err(|e| Error::Database(Box::new(e)))?;
+
+        // The auth database may share the migrations table with the mint
+        let known = MIGRATIONS
+            .iter()
+            .chain(auth::migrations::MIGRATIONS)
+            .map(|(_, name, _)| *name)
+            .collect::<HashSet<_>>();
+
+        Ok(query(r#"SELECT name FROM migrations"#)?
+            .fetch_all(&*conn)
+            .await?
+            .into_iter()
+            .map(|row| Ok(column_as_string!(&row[0])))
+            .collect::<Result<Vec<_>, Error>>()?
+            .into_iter()
+            .filter(|name| !known.contains(name.as_str()))
+            .collect())
+    }
+
+    async fn migration_status(&self) -> Result<Vec<MigrationStatus>, Error> {
+        let conn = self
+            .pool
+            .get()
+            .await
+            .map_err(|e| Error::Database(Box::new(e)))?;
+
+        migration_status(&*conn, RM::Connection::name(), MIGRATIONS).await
+    }
 }
 
 #[cfg(all(test, feature = "prometheus"))]
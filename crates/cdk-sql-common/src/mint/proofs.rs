@@ -17,7 +17,15 @@ use super::{SQLMintDatabase, SQLTransaction};
 use crate::database::DatabaseExecutor;
 use crate::pool::DatabasePool;
 use crate::stmt::{query, Column};
-use crate::{column_as_nullable_string, column_as_number, column_as_string, unpack_into};
+use crate::{
+    column_as_binary, column_as_nullable_string, column_as_number, column_as_string, unpack_into,
+};
+
+/// Maximum number of `y` values bound into a single `IN` clause
+///
+/// Keeps large lookups below the bind parameter limits of SQLite and Postgres
+/// while still resolving each chunk with a single indexed query.
+const MAX_YS_PER_QUERY: usize = 1_000;
 
 pub(super) async fn get_current_states<C>(
     conn: &C,
@@ -28,22 +36,119 @@ where
     C: DatabaseExecutor + Send + Sync,
 {
     let for_update_clause = if for_update { "FOR UPDATE" } else { "" };
+    let mut states = HashMap::with_capacity(ys.len());
+
+    for chunk in ys.chunks(MAX_YS_PER_QUERY) {
+        let rows = query(&format!(
+            r#"SELECT y, state FROM proof WHERE y IN (:ys) {}"#,
+            for_update_clause
+        ))?
+        .bind_vec("ys", chunk.iter().map(|y| y.to_bytes().to_vec()).collect())?
+        .fetch_all(conn)
+        .await?;
 
-    query(&format!(
-        r#"SELECT y, state FROM proof WHERE y IN (:ys) {}"#,
-        for_update_clause
-    ))?
-    .bind_vec("ys", ys.iter().map(|y| y.to_bytes().to_vec()).collect())?
-    .fetch_all(conn)
-    .await?
-    .into_iter()
-    .map(|row| {
-        Ok((
-            column_as_string!(&row[0], PublicKey::from_hex, PublicKey::from_slice),
-            column_as_string!(&row[1], State::from_str),
-        ))
-    })
-    .collect::<Result<HashMap<_, _>, _>>()
+        for row in rows {
+            states.insert(
+                column_as_string!(&row[0], PublicKey::from_hex, PublicKey::from_slice),
+                column_as_string!(&row[1], State::from_str),
+            );
+        }
+    }
+
+    let missing: Vec<PublicKey> = ys
+        .iter()
+        .filter(|y| !states.contains_key(*y))
+        .copied()
+        .collect();
+
+    for y in get_archived_ys(conn, &missing).await? {
+        states.insert(y, State::Spent);
+    }
+
+    Ok(states)
+}
+
+/// Moves the proofs `ys` from `expected_state` to `new_state`
+///
+/// Each chunk of proofs is moved with a single update that only applies to proofs still in
+/// the state they were read in. If fewer rows changed than requested, the current states are
+/// read back: spent proofs are rejected, any other change is a concurrent update.
+async fn update_proofs_state_conditionally<C>(
+    conn: &C,
+    ys: &[PublicKey],
+    expected_state: State,
+    new_state: State,
+) -> Result<(), Error>
+where
+    C: DatabaseExecutor + Send + Sync,
+{
+    // Spent proofs are archived by the time they were spent
+    let spent_time = (new_state == State::Spent).then(|| unix_time() as i64);
+
+    for chunk in ys.chunks(MAX_YS_PER_QUERY) {
+        let updated = query(
+            r#"
+            UPDATE proof
+            SET state = :new_state, spent_time = COALESCE(:spent_time, spent_time)
+            WHERE y IN (:ys) AND state = :expected_state
+            "#,
+        )?
+        .bind("new_state", new_state.to_string())
+        .bind("spent_time", spent_time)
+        .bind_vec("ys", chunk.iter().map(|y| y.to_bytes().to_vec()).collect())?
+        .bind("expected_state", expected_state.to_string())
+        .execute(conn)
+        .await?;
+
+        if updated == chunk.len() {
+            continue;
+        }
+
+        let current_states = get_current_states(conn, chunk, false).await?;
+
+        if current_states.values().any(|state| *state == State::Spent) {
+            return Err(Error::AttemptUpdateSpentProof);
+        }
+
+        if current_states.len() < chunk.len() {
+            return Err(Error::ProofNotFound);
+        }
+
+        tracing::debug!(
+            "{} of {} proofs changed concurrently, rejecting state update",
+            chunk.len() - updated,
+            chunk.len()
+        );
+
+        return Err(Error::ConcurrentUpdate);
+    }
+
+    Ok(())
+}
+
+/// Ys of `ys` that have been moved to the spent proof archive
+async fn get_archived_ys<C>(conn: &C, ys: &[PublicKey]) -> Result<Vec<PublicKey>, Error>
+where
+    C: DatabaseExecutor + Send + Sync,
+{
+    let mut archived = Vec::new();
+
+    for chunk in ys.chunks(MAX_YS_PER_QUERY) {
+        let rows = query(r#"SELECT y FROM spent_proof_archive WHERE y IN (:ys)"#)?
+            .bind_vec("ys", chunk.iter().map(|y| y.to_bytes().to_vec()).collect())?
+            .fetch_all(conn)
+            .await?;
+
+        for row in rows {
+            archived.push(column_as_string!(
+                &row[0],
+                PublicKey::from_hex,
+                PublicKey::from_slice
+            ));
+        }
+    }
+
+    Ok(archived)
 }
 
 pub(super) fn sql_row_to_proof(row: Vec<Column>) -> Result<Proof, Error> {
@@ -154,6 +259,14 @@ where
             None => Ok(()), // no previous record
         }?;
 
+        // Archived proofs are spent even though they are no longer in the proof table
+        if !get_archived_ys(&self.inner, &proofs.ys()?)
+            .await?
+            .is_empty()
+        {
+            return Err(database::Error::AttemptUpdateSpentProof);
+        }
+
         for proof in &proofs {
             let y = proof.y()?;
 
@@ -198,6 +311,9 @@ where
     ///
     /// The proofs must have been previously acquired via `add_proofs`
     /// or `get_proofs` to ensure they are locked within the current transaction.
+    /// All proofs are moved with one conditional update per chunk, which only applies
+    /// while they are still in the state they were acquired in, otherwise
+    /// [`Error::AttemptUpdateSpentProof`] or [`Error::ConcurrentUpdate`] is returned.
     async fn update_proofs_state(
         &mut self,
         proofs: &mut Acquired<ProofsWithState>,
@@ -205,11 +321,7 @@ where
     ) -> Result<(), Self::Err> {
         let ys = proofs.ys()?;
 
-        query(r#"UPDATE proof SET state = :new_state WHERE y IN (:ys)"#)?
-            .bind("new_state", new_state.to_string())
-            .bind_vec("ys", ys.iter().map(|y| y.to_bytes().to_vec()).collect())?
-            .execute(&self.inner)
-            .await?;
+        update_proofs_state_conditionally(&self.inner, &ys, proofs.state, new_state).await?;
 
         if new_state == State::Spent {
             query(
@@ -316,6 +428,58 @@ where
         .ys()?)
     }
 
+    async fn archive_spent_proofs(
+        &mut self,
+        spent_before: u64,
+        limit: u64,
+    ) -> Result<u64, Self::Err> {
+        let ys = query(&format!(
+            r#"
+            SELECT
+                y
+            FROM
+                proof
+            WHERE
+                state = :state
+                AND spent_time < :spent_before
+            LIMIT {limit}
+            FOR UPDATE
+            "#
+        ))?
+        .bind("state", State::Spent.to_string())
+        .bind("spent_before", spent_before as i64)
+        .fetch_all(&self.inner)
+        .await?
+        .into_iter()
+        .map(|row| Ok(column_as_binary!(&row[0])))
+        .collect::<Result<Vec<_>, Error>>()?;
+
+        let archived_time = unix_time();
+
+        for chunk in ys.chunks(MAX_YS_PER_QUERY) {
+            query(
+                r#"
+                INSERT INTO spent_proof_archive
+                (y, keyset_id, amount, archived_time)
+                SELECT y, keyset_id, amount, :archived_time
+                FROM proof
+                WHERE y IN (:ys)
+                "#,
+            )?
+            .bind("archived_time", archived_time as i64)
+            .bind_vec("ys", chunk.to_vec())?
+            .execute(&self.inner)
+            .await?;
+
+            query(r#"DELETE FROM proof WHERE y IN (:ys)"#)?
+                .bind_vec("ys", chunk.to_vec())?
+                .execute(&self.inner)
+                .await?;
+        }
+
+        Ok(ys.len() as u64)
+    }
+
     async fn get_proof_ys_by_operation_id(
         &mut self,
         operation_id: &uuid::Uuid,
@@ -412,36 +576,37 @@ where
             .get()
             .await
             .map_err(|e| Error::Database(Box::new(e)))?;
-        let mut proofs = query(
-            r#"
-            SELECT
-                amount,
-                keyset_id,
-                secret,
-                c,
-                witness,
-                y
-            FROM
-                proof
-            WHERE
-                y IN (:ys)
-            "#,
-        )?
-        .bind_vec("ys", ys.iter().map(|y| y.to_bytes().to_vec()).collect())?
-        .fetch_all(&*conn)
-        .await?
-        .into_iter()
-        .map(|mut row| {
-            Ok((
-                column_as_string!(
+        let mut proofs = HashMap::with_capacity(ys.len());
+
+        for chunk in ys.chunks(MAX_YS_PER_QUERY) {
+            let rows = query(
+                r#"
+                SELECT
+                    amount,
+                    keyset_id,
+                    secret,
+                    c,
+                    witness,
+                    y
+                FROM
+                    proof
+                WHERE
+                    y IN (:ys)
+                "#,
+            )?
+            .bind_vec("ys", chunk.iter().map(|y| y.to_bytes().to_vec()).collect())?
+            .fetch_all(&*conn)
+            .await?;
+
+            for mut row in rows {
+                let y = column_as_string!(
                     row.pop().ok_or(Error::InvalidDbResponse)?,
                     PublicKey::from_hex,
                     PublicKey::from_slice
-                ),
-                sql_row_to_proof(row)?,
-            ))
-        })
-        .collect::<Result<HashMap<_, _>, Error>>()?;
+                );
+                proofs.insert(y, sql_row_to_proof(row)?);
+            }
+        }
 
         Ok(ys.iter().map(|y| proofs.remove(y)).collect())
     }
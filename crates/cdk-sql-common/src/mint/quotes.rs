@@ -1358,4 +1358,112 @@ where
         .map(sql_row_to_melt_quote)
         .collect::<Result<Vec<_>, _>>()?)
     }
+
+    async fn get_mint_quote_payments_in_range(
+        &self,
+        from: u64,
+        to: u64,
+        offset: u64,
+        limit: u64,
+    ) -> Result<Vec<mint::MintQuotePayment>, Self::Err> {
+        let conn = self
+            .pool
+            .get()
+            .await
+            .map_err(|e| Error::Database(Box::new(e)))?;
+        query(&format!(
+            r#"
+            SELECT
+                p.quote_id,
+                q.payment_method,
+                p.payment_id,
+                p.timestamp,
+                p.amount,
+                q.unit
+            FROM
+                mint_quote_payments p
+            JOIN mint_quote q ON p.quote_id = q.id
+            WHERE
+                p.timestamp >= :from
+                AND p.timestamp < :to
+            ORDER BY p.timestamp, p.payment_id
+            LIMIT {limit} OFFSET {offset}
+            "#
+        ))?
+        .bind("from", from as i64)
+        .bind("to", to.min(i64::MAX as u64) as i64)
+        .fetch_all(&*conn)
+        .await?
+        .into_iter()
+        .map(|row| -> Result<mint::MintQuotePayment, Error> {
+            unpack_into!(let (quote_id, payment_method, payment_id, time, amount, unit) = row);
+
+            let amount: u64 = column_as_number!(amount);
+            let unit = column_as_string!(&unit, CurrencyUnit::from_str);
+
+            Ok(mint::MintQuotePayment {
+                quote_id: QuoteId::from_str(&column_as_string!(&quote_id))?,
+                payment_method: column_as_string!(payment_method, PaymentMethod::from_str),
+                payment: IncomingPayment::new(
+                    Amount::from(amount).with_unit(unit),
+                    column_as_string!(&payment_id),
+                    column_as_number!(time),
+                ),
+            })
+        })
+        .collect()
+    }
+
+    async fn get_melt_quotes_paid_in_range(
+        &self,
+        from: u64,
+        to: u64,
+        offset: u64,
+        limit: u64,
+    ) -> Result<Vec<mint::MeltQuote>, Self::Err> {
+        let conn = self
+            .pool
+            .get()
+            .await
+            .map_err(|e| Error::Database(Box::new(e)))?;
+        Ok(query(&format!(
+            r#"
+            SELECT
+                id,
+                unit,
+                amount,
+                request,
+                fee_reserve,
+                expiry,
+                state,
+                payment_proof,
+                estimated_blocks,
+                request_lookup_id,
+                created_time,
+                paid_time,
+                payment_method,
+                options,
+                request_lookup_id_kind,
+                extra_json,
+                fee_options,
+                selected_fee_index
+            FROM
+                melt_quote
+            WHERE
+                state = :state
+                AND paid_time >= :from
+                AND paid_time < :to
+            ORDER BY paid_time, id
+            LIMIT {limit} OFFSET {offset}
+            "#
+        ))?
+        .bind("state", MeltQuoteState::Paid.to_string())
+        .bind("from", from as i64)
+        .bind("to", to.min(i64::MAX as u64) as i64)
+        .fetch_all(&*conn)
+        .await?
+        .into_iter()
+        .map(sql_row_to_melt_quote)
+        .collect::<Result<Vec<_>, _>>()?)
+    }
 }
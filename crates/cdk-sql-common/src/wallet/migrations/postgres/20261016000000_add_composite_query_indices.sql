@@ -0,0 +1,21 @@
+-- Composite indices for balance, proof selection, history and quote expiry queries
+--
+-- Query plans on large wallets before this migration (SQLite EXPLAIN QUERY PLAN):
+--   SELECT SUM(amount) FROM proof WHERE mint_url = ? AND unit = ? AND state IN (?)
+--     SCAN proof
+--   SELECT ... FROM transactions WHERE mint_url = ? AND unit = ? AND timestamp >= ? AND timestamp < ?
+--   ORDER BY timestamp, id
+--     SEARCH transactions USING INDEX unit_index (unit=?)
+--     USE TEMP B-TREE FOR ORDER BY
+--   SELECT id FROM mint_quote WHERE state = ? AND expiry < ?
+--     SCAN mint_quote
+--
+-- After:
+--     SEARCH proof USING INDEX idx_proof_mint_url_unit_state (mint_url=? AND unit=? AND state=?)
+--     SEARCH transactions USING INDEX idx_transactions_mint_url_unit_timestamp (mint_url=? AND unit=? AND timestamp>? AND timestamp<?)
+--     USE TEMP B-TREE FOR LAST TERM OF ORDER BY
+--     SEARCH mint_quote USING INDEX idx_mint_quote_state_expiry (state=? AND expiry<?)
+CREATE INDEX IF NOT EXISTS idx_proof_mint_url_unit_state ON proof(mint_url, unit, state);
+CREATE INDEX IF NOT EXISTS idx_transactions_mint_url_unit_timestamp ON transactions(mint_url, unit, timestamp);
+CREATE INDEX IF NOT EXISTS idx_mint_quote_state_expiry ON mint_quote(state, expiry);
+CREATE INDEX IF NOT EXISTS idx_melt_quote_state_expiry ON melt_quote(state, expiry);
@@ -0,0 +1,3 @@
+-- Melt options (MPP or amountless) a bolt11 quote was requested with, as JSON,
+-- so an active quote is only reused for a request with the same options.
+ALTER TABLE melt_quote ADD COLUMN melt_options TEXT;
@@ -7,7 +7,9 @@ use std::sync::Arc;
 
 use async_trait::async_trait;
 use bitcoin::bip32::DerivationPath;
-use cdk_common::database::{ConversionError, Error, WalletDatabase};
+use cdk_common::database::{
+    ConversionError, DbTransactionFinalizer, Error, WalletDatabase, WalletDatabaseTransaction,
+};
 use cdk_common::mint_url::MintUrl;
 use cdk_common::nuts::{MeltQuoteState, MintQuoteState};
 use cdk_common::secret::Secret;
@@ -45,6 +47,15 @@ where
     pool: Arc<Pool<RM>>,
 }
 
+/// SQL wallet transaction
+#[allow(missing_debug_implementations)]
+pub struct SQLWalletTransaction<RM>
+where
+    RM: DatabasePool + 'static,
+{
+    inner: ConnectionWithTransaction<RM::Connection, PooledResource<RM>>,
+}
+
 impl<RM> SQLWalletDatabase<RM>
 where
     RM: DatabasePool + 'static,
@@ -71,6 +82,353 @@ where
         Ok(())
     }
 
+    async fn update_proofs_inner<C>(
+        conn: &C,
+        added: Vec<ProofInfo>,
+        removed_ys: Vec<PublicKey>,
+    ) -> Result<(), database::Error>
+    where
+        C: DatabaseExecutor,
+    {
+        for proof in added {
+            query(
+                r#"
+    INSERT INTO proof
+    (y, mint_url, state, spending_condition, unit, amount, keyset_id, secret, c, witness, dleq_e, dleq_s, dleq_r, used_by_operation, created_by_operation, p2pk_e)
+    VALUES
+    (:y, :mint_url, :state, :spending_condition, :unit, :amount, :keyset_id, :secret, :c, :witness, :dleq_e, :dleq_s, :dleq_r, :used_by_operation, :created_by_operation, :p2pk_e)
+    ON CONFLICT(y) DO UPDATE SET
+        mint_url = excluded.mint_url,
+        state = excluded.state,
+        spending_condition = excluded.spending_condition,
+        unit = excluded.unit,
+        amount = excluded.amount,
+        keyset_id = excluded.keyset_id,
+        secret = excluded.secret,
+        c = excluded.c,
+        witness = excluded.witness,
+        dleq_e = excluded.dleq_e,
+        dleq_s = excluded.dleq_s,
+        dleq_r = excluded.dleq_r,
+        used_by_operation = excluded.used_by_operation,
+        created_by_operation = excluded.created_by_operation,
+        p2pk_e = excluded.p2pk_e
+    ;
+            "#,
+            )?
+            .bind("y", proof.y.to_bytes().to_vec())
+            .bind("mint_url", proof.mint_url.to_string())
+            .bind("state", proof.state.to_string())
+            .bind(
+                "spending_condition",
+                proof
+                    .spending_condition
+                    .map(|s| serde_json::to_string(&s).ok()),
+            )
+            .bind("unit", proof.unit.to_string())
+            .bind("amount", u64::from(proof.proof.amount) as i64)
+            .bind("keyset_id", proof.proof.keyset_id.to_string())
+            .bind("secret", proof.proof.secret.to_string())
+            .bind("c", proof.proof.c.to_bytes().to_vec())
+            .bind(
+                "witness",
+                proof
+                    .proof
+                    .witness
+                    .and_then(|w| serde_json::to_string(&w).ok()),
+            )
+            .bind(
+                "dleq_e",
+                proof.proof.dleq.as_ref().map(|dleq| dleq.e.to_secret_bytes().to_vec()),
+            )
+            .bind(
+                "dleq_s",
+                proof.proof.dleq.as_ref().map(|dleq| dleq.s.to_secret_bytes().to_vec()),
+            )
+            .bind(
+                "dleq_r",
+                proof.proof.dleq.as_ref().map(|dleq| dleq.r.to_secret_bytes().to_vec()),
+            )
+            .bind("used_by_operation", proof.used_by_operation.map(|id| id.to_string()))
+            .bind("created_by_operation", proof.created_by_operation.map(|id| id.to_string()))
+            .bind(
+                "p2pk_e",
+                proof
+                    .proof
+                    .p2pk_e
+                    .as_ref()
+                    .map(|pk| pk.to_bytes().to_vec()),
+            )
+            .execute(conn)
+            .await?;
+        }
+
+        if !removed_ys.is_empty() {
+            query(r#"DELETE FROM proof WHERE y IN (:ys)"#)?
+                .bind_vec(
+                    "ys",
+                    removed_ys.iter().map(|y| y.to_bytes().to_vec()).collect(),
+                )?
+                .execute(conn)
+                .await?;
+        }
+
+        Ok(())
+    }
+
+    async fn update_proofs_state_inner<C>(
+        conn: &C,
+        ys: Vec<PublicKey>,
+        state: State,
+    ) -> Result<(), database::Error>
+    where
+        C: DatabaseExecutor,
+    {
+        query("UPDATE proof SET state = :state WHERE y IN (:ys)")?
+            .bind_vec("ys", ys.iter().map(|y| y.to_bytes().to_vec()).collect())?
+            .bind("state", state.to_string())
+            .execute(conn)
+            .await?;
+
+        Ok(())
+    }
+
+    async fn add_transaction_inner<C>(
+        conn: &C,
+        transaction: Transaction,
+    ) -> Result<(), database::Error>
+    where
+        C: DatabaseExecutor,
+    {
+        let mint_url = transaction.mint_url.to_string();
+        let direction = transaction.direction.to_string();
+        let unit = transaction.unit.to_string();
+        let amount = u64::from(transaction.amount) as i64;
+        let fee = u64::from(transaction.fee) as i64;
+        let ys = transaction
+            .ys
+            .iter()
+            .flat_map(|y| y.to_bytes().to_vec())
+            .collect::<Vec<_>>();
+
+        let id = transaction.id();
+
+        if let Some(saga_id) = transaction.saga_id {
+            let recorded =
+                query(r#"SELECT id FROM transactions WHERE saga_id = :saga_id AND id != :id"#)?
+                    .bind("saga_id", saga_id.to_string())
+                    .bind("id", id.as_slice().to_vec())
+                    .pluck(conn)
+                    .await?;
+
+            if recorded.is_some() {
+                tracing::debug!("Transaction for operation {} already recorded", saga_id);
+                return Ok(());
+            }
+        }
+
+        query(
+               r#"
+   INSERT INTO transactions
+   (id, mint_url, direction, unit, amount, fee, ys, timestamp, memo, metadata, quote_id, payment_request, payment_proof, payment_method, saga_id)
+   VALUES
+   (:id, :mint_url, :direction, :unit, :amount, :fee, :ys, :timestamp, :memo, :metadata, :quote_id, :payment_request, :payment_proof, :payment_method, :saga_id)
+   ON CONFLICT(id) DO UPDATE SET
+       mint_url = excluded.mint_url,
+       direction = excluded.direction,
+       unit = excluded.unit,
+       amount = excluded.amount,
+       fee = excluded.fee,
+       timestamp = excluded.timestamp,
+       memo = excluded.memo,
+       metadata = excluded.metadata,
+       quote_id = excluded.quote_id,
+       payment_request = excluded.payment_request,
+       payment_proof = excluded.payment_proof,
+       payment_method = excluded.payment_method,
+       saga_id = excluded.saga_id
+   ;
+           "#,
+           )?
+           .bind("id", id.as_slice().to_vec())
+           .bind("mint_url", mint_url)
+           .bind("direction", direction)
+           .bind("unit", unit)
+           .bind("amount", amount)
+           .bind("fee", fee)
+           .bind("ys", ys)
+           .bind("timestamp", transaction.timestamp as i64)
+           .bind("memo", transaction.memo)
+           .bind(
+               "metadata",
+               serde_json::to_string(&transaction.metadata).map_err(Error::from)?,
+           )
+           .bind("quote_id", transaction.quote_id)
+           .bind("payment_request", transaction.payment_request)
+           .bind("payment_proof", transaction.payment_proof)
+           .bind("payment_method", transaction.payment_method.map(|pm| pm.to_string()))
+           .bind("saga_id", transaction.saga_id.map(|id| id.to_string()))
+           .execute(conn)
+           .await?;
+
+        Ok(())
+    }
+
+    async fn increment_keyset_counter_inner<C>(
+        conn: &C,
+        keyset_id: &Id,
+        count: u32,
+    ) -> Result<u32, database::Error>
+    where
+        C: DatabaseExecutor,
+    {
+        let new_counter = query(
+            r#"
+            INSERT INTO keyset_counter (keyset_id, counter)
+            VALUES (:keyset_id, :count)
+            ON CONFLICT(keyset_id) DO UPDATE SET
+                counter = keyset_counter.counter + :count
+            RETURNING counter
+            "#,
+        )?
+        .bind("keyset_id", keyset_id.to_string())
+        .bind("count", count)
+        .pluck(conn)
+        .await?
+        .map(|n| Ok::<_, Error>(column_as_number!(n)))
+        .transpose()?
+        .ok_or_else(|| Error::Internal("Counter update returned no value".to_owned()))?;
+
+        Ok(new_counter)
+    }
+
+    async fn add_mint_quote_inner<C>(conn: &C, quote: MintQuote) -> Result<(), database::Error>
+    where
+        C: DatabaseExecutor,
+    {
+        let expected_version = quote.version;
+        let new_version = expected_version.wrapping_add(1);
+
+        let rows_affected = query(
+                r#"
+    INSERT INTO mint_quote
+    (id, mint_url, amount, unit, request, state, expiry, secret_key, payment_method, amount_issued, amount_paid, updated_at, estimated_blocks, version, used_by_operation)
+    VALUES
+    (:id, :mint_url, :amount, :unit, :request, :state, :expiry, :secret_key, :payment_method, :amount_issued, :amount_paid, :updated_at, :estimated_blocks, :version, :used_by_operation)
+    ON CONFLICT(id) DO UPDATE SET
+        mint_url = excluded.mint_url,
+        amount = excluded.amount,
+        unit = excluded.unit,
+        request = excluded.request,
+        state = excluded.state,
+        expiry = excluded.expiry,
+        secret_key = excluded.secret_key,
+        payment_method = excluded.payment_method,
+        amount_issued = excluded.amount_issued,
+        amount_paid = excluded.amount_paid,
+        updated_at = excluded.updated_at,
+        estimated_blocks = excluded.estimated_blocks,
+        version = :new_version,
+        used_by_operation = excluded.used_by_operation
+    WHERE mint_quote.version = :expected_version
+    ;
+            "#,
+            )?
+            .bind("id", quote.id.to_string())
+            .bind("mint_url", quote.mint_url.to_string())
+            .bind("amount", quote.amount.map(|a| a.to_i64()))
+            .bind("unit", quote.unit.to_string())
+            .bind("request", quote.request)
+            .bind("state", quote.state.to_string())
+            .bind("expiry", quote.expiry as i64)
+            .bind("secret_key", quote.secret_key.map(|p| p.to_string()))
+            .bind("payment_method", quote.payment_method.to_string())
+            .bind("amount_issued", quote.amount_issued.to_i64())
+            .bind("amount_paid", quote.amount_paid.to_i64())
+            .bind("updated_at", quote.updated_at as i64)
+            .bind("estimated_blocks", quote.estimated_blocks.map(i64::from))
+            .bind("version", quote.version as i64)
+            .bind("new_version", new_version as i64)
+            .bind("expected_version", expected_version as i64)
+            .bind("used_by_operation", quote.used_by_operation)
+            .execute(conn).await?;
+
+        if rows_affected == 0 {
+            return Err(database::Error::ConcurrentUpdate);
+        }
+
+        Ok(())
+    }
+
+    async fn add_melt_quote_inner<C>(
+        conn: &C,
+        quote: wallet::MeltQuote,
+    ) -> Result<(), database::Error>
+    where
+        C: DatabaseExecutor,
+    {
+        let expected_version = quote.version;
+        let new_version = expected_version.wrapping_add(1);
+
+        let rows_affected = query(
+            r#"
+ INSERT INTO melt_quote
+ (id, unit, amount, request, fee_reserve, state, expiry, payment_proof, payment_method, estimated_blocks, fee_index, melt_options, version, mint_url, used_by_operation)
+ VALUES
+ (:id, :unit, :amount, :request, :fee_reserve, :state, :expiry, :payment_proof, :payment_method, :estimated_blocks, :fee_index, :melt_options, :version, :mint_url, :used_by_operation)
+ ON CONFLICT(id) DO UPDATE SET
+     unit = excluded.unit,
+     amount = excluded.amount,
+     request = excluded.request,
+     fee_reserve = excluded.fee_reserve,
+     state = excluded.state,
+     expiry = excluded.expiry,
+     payment_proof = COALESCE(excluded.payment_proof, melt_quote.payment_proof),
+     payment_method = excluded.payment_method,
+     estimated_blocks = excluded.estimated_blocks,
+     fee_index = excluded.fee_index,
+     melt_options = excluded.melt_options,
+     version = :new_version,
+     mint_url = excluded.mint_url,
+     used_by_operation = excluded.used_by_operation
+ WHERE melt_quote.version = :expected_version
+ ;
+         "#,
+        )?
+        .bind("id", quote.id.to_string())
+        .bind("unit", quote.unit.to_string())
+        .bind("amount", u64::from(quote.amount) as i64)
+        .bind("request", quote.request)
+        .bind("fee_reserve", u64::from(quote.fee_reserve) as i64)
+        .bind("state", quote.state.to_string())
+        .bind("expiry", quote.expiry as i64)
+        .bind("payment_proof", quote.payment_proof)
+        .bind("payment_method", quote.payment_method.to_string())
+        .bind("estimated_blocks", quote.estimated_blocks.map(i64::from))
+        .bind("fee_index", quote.fee_index.map(i64::from))
+        .bind(
+            "melt_options",
+            quote
+                .options
+                .map(|options| serde_json::to_string(&options))
+                .transpose()
+                .map_err(Error::from)?,
+        )
+        .bind("version", quote.version as i64)
+        .bind("new_version", new_version as i64)
+        .bind("expected_version", expected_version as i64)
+        .bind("mint_url", quote.mint_url.map(|m| m.to_string()))
+        .bind("used_by_operation", quote.used_by_operation)
+        .execute(conn)
+        .await?;
+
+        if rows_affected == 0 {
+            return Err(database::Error::ConcurrentUpdate);
+        }
+
+        Ok(())
+    }
+
     async fn add_keyset_u32<T>(conn: &T) -> Result<(), Error>
     where
         T: DatabaseExecutor,
@@ -143,11 +501,118 @@ where
     }
 }
 
+#[async_trait]
+impl<RM> DbTransactionFinalizer for SQLWalletTransaction<RM>
+where
+    RM: DatabasePool + 'static,
+{
+    type Err = database::Error;
+
+    async fn commit(self: Box<Self>) -> Result<(), database::Error> {
+        Ok(self.inner.commit().await?)
+    }
+
+    async fn rollback(self: Box<Self>) -> Result<(), database::Error> {
+        Ok(self.inner.rollback().await?)
+    }
+}
+
+#[async_trait]
+impl<RM> WalletDatabaseTransaction<database::Error> for SQLWalletTransaction<RM>
+where
+    RM: DatabasePool + 'static,
+{
+    async fn update_proofs(
+        &mut self,
+        added: Vec<ProofInfo>,
+        removed_ys: Vec<PublicKey>,
+    ) -> Result<(), database::Error> {
+        SQLWalletDatabase::<RM>::update_proofs_inner(&self.inner, added, removed_ys).await
+    }
+
+    async fn update_proofs_state(
+        &mut self,
+        ys: Vec<PublicKey>,
+        state: State,
+    ) -> Result<(), database::Error> {
+        SQLWalletDatabase::<RM>::update_proofs_state_inner(&self.inner, ys, state).await
+    }
+
+    async fn add_transaction(&mut self, transaction: Transaction) -> Result<(), database::Error> {
+        SQLWalletDatabase::<RM>::add_transaction_inner(&self.inner, transaction).await
+    }
+
+    async fn increment_keyset_counter(
+        &mut self,
+        keyset_id: &Id,
+        count: u32,
+    ) -> Result<u32, database::Error> {
+        SQLWalletDatabase::<RM>::increment_keyset_counter_inner(&self.inner, keyset_id, count).await
+    }
+
+    async fn add_mint_quote(&mut self, quote: MintQuote) -> Result<(), database::Error> {
+        SQLWalletDatabase::<RM>::add_mint_quote_inner(&self.inner, quote).await
+    }
+
+    async fn add_melt_quote(&mut self, quote: wallet::MeltQuote) -> Result<(), database::Error> {
+        SQLWalletDatabase::<RM>::add_melt_quote_inner(&self.inner, quote).await
+    }
+
+    async fn kv_write(
+        &mut self,
+        primary_namespace: &str,
+        secondary_namespace: &str,
+        key: &str,
+        value: &[u8],
+    ) -> Result<(), database::Error> {
+        crate::keyvalue::kv_write_standalone(
+            &self.inner,
+            primary_namespace,
+            secondary_namespace,
+            key,
+            value,
+        )
+        .await
+    }
+
+    async fn kv_remove(
+        &mut self,
+        primary_namespace: &str,
+        secondary_namespace: &str,
+        key: &str,
+    ) -> Result<(), database::Error> {
+        crate::keyvalue::kv_remove_standalone(
+            &self.inner,
+            primary_namespace,
+            secondary_namespace,
+            key,
+        )
+        .await
+    }
+}
+
 #[async_trait]
 impl<RM> WalletDatabase<database::Error> for SQLWalletDatabase<RM>
 where
     RM: DatabasePool + 'static,
 {
+    async fn begin_db_transaction(
+        &self,
+    ) -> Result<Box<dyn WalletDatabaseTransaction<database::Error> + Send + Sync>, database::Error>
+    {
+        let tx = SQLWalletTransaction {
+            inner: ConnectionWithTransaction::new(
+                self.pool
+                    .get()
+                    .await
+                    .map_err(|e| Error::Database(Box::new(e)))?,
+            )
+            .await?,
+        };
+
+        Ok(Box::new(tx))
+    }
+
     #[instrument(skip(self))]
     async fn get_melt_quotes(&self) -> Result<Vec<wallet::MeltQuote>, database::Error> {
         let conn = self
@@ -170,6 +635,7 @@ where
                   payment_method,
                   estimated_blocks,
                   fee_index,
+                  melt_options,
                   used_by_operation,
                   version,
                   mint_url
@@ -464,6 +930,7 @@ where
                 payment_method,
                 estimated_blocks,
                 fee_index,
+                melt_options,
                 used_by_operation,
                 version,
                 mint_url
@@ -736,118 +1203,91 @@ where
                 saga_id
             FROM
                 transactions
-            "#,
-        )?
-        .fetch_all(&*conn)
-        .await?
-        .into_iter()
-        .filter_map(|row| {
-            // TODO: Avoid a table scan by passing the heavy lifting of checking to the DB engine
-            let transaction = sql_row_to_transaction(row).ok()?;
-            if transaction.matches_conditions(&mint_url, &direction, &unit) {
-                Some(transaction)
-            } else {
-                None
-            }
-        })
-        .collect::<Vec<_>>())
-    }
-
-    async fn update_proofs(
-        &self,
-        added: Vec<ProofInfo>,
-        removed_ys: Vec<PublicKey>,
-    ) -> Result<(), database::Error> {
-        let conn = self
-            .pool
-            .get()
-            .await
-            .map_err(|e| Error::Database(Box::new(e)))?;
-        let tx = ConnectionWithTransaction::new(conn).await?;
-
-        for proof in added {
-            query(
-                r#"
-    INSERT INTO proof
-    (y, mint_url, state, spending_condition, unit, amount, keyset_id, secret, c, witness, dleq_e, dleq_s, dleq_r, used_by_operation, created_by_operation, p2pk_e)
-    VALUES
-    (:y, :mint_url, :state, :spending_condition, :unit, :amount, :keyset_id, :secret, :c, :witness, :dleq_e, :dleq_s, :dleq_r, :used_by_operation, :created_by_operation, :p2pk_e)
-    ON CONFLICT(y) DO UPDATE SET
-        mint_url = excluded.mint_url,
-        state = excluded.state,
-        spending_condition = excluded.spending_condition,
-        unit = excluded.unit,
-        amount = excluded.amount,
-        keyset_id = excluded.keyset_id,
-        secret = excluded.secret,
-        c = excluded.c,
-        witness = excluded.witness,
-        dleq_e = excluded.dleq_e,
-        dleq_s = excluded.dleq_s,
-        dleq_r = excluded.dleq_r,
-        used_by_operation = excluded.used_by_operation,
-        created_by_operation = excluded.created_by_operation,
-        p2pk_e = excluded.p2pk_e
-    ;
-            "#,
-            )?
-            .bind("y", proof.y.to_bytes().to_vec())
-            .bind("mint_url", proof.mint_url.to_string())
-            .bind("state", proof.state.to_string())
-            .bind(
-                "spending_condition",
-                proof
-                    .spending_condition
-                    .map(|s| serde_json::to_string(&s).ok()),
-            )
-            .bind("unit", proof.unit.to_string())
-            .bind("amount", u64::from(proof.proof.amount) as i64)
-            .bind("keyset_id", proof.proof.keyset_id.to_string())
-            .bind("secret", proof.proof.secret.to_string())
-            .bind("c", proof.proof.c.to_bytes().to_vec())
-            .bind(
-                "witness",
-                proof
-                    .proof
-                    .witness
-                    .and_then(|w| serde_json::to_string(&w).ok()),
-            )
-            .bind(
-                "dleq_e",
-                proof.proof.dleq.as_ref().map(|dleq| dleq.e.to_secret_bytes().to_vec()),
-            )
-            .bind(
-                "dleq_s",
-                proof.proof.dleq.as_ref().map(|dleq| dleq.s.to_secret_bytes().to_vec()),
-            )
-            .bind(
-                "dleq_r",
-                proof.proof.dleq.as_ref().map(|dleq| dleq.r.to_secret_bytes().to_vec()),
-            )
-            .bind("used_by_operation", proof.used_by_operation.map(|id| id.to_string()))
-            .bind("created_by_operation", proof.created_by_operation.map(|id| id.to_string()))
-            .bind(
-                "p2pk_e",
-                proof
-                    .proof
-                    .p2pk_e
-                    .as_ref()
-                    .map(|pk| pk.to_bytes().to_vec()),
-            )
-            .execute(&tx)
-            .await?;
-        }
+            "#,
+        )?
+        .fetch_all(&*conn)
+        .await?
+        .into_iter()
+        .filter_map(|row| {
+            // TODO: Avoid a table scan by passing the heavy lifting of checking to the DB engine
+            let transaction = sql_row_to_transaction(row).ok()?;
+            if transaction.matches_conditions(&mint_url, &direction, &unit) {
+                Some(transaction)
+            } else {
+                None
+            }
+        })
+        .collect::<Vec<_>>())
+    }
 
-        if !removed_ys.is_empty() {
-            query(r#"DELETE FROM proof WHERE y IN (:ys)"#)?
-                .bind_vec(
-                    "ys",
-                    removed_ys.iter().map(|y| y.to_bytes().to_vec()).collect(),
-                )?
-                .execute(&tx)
-                .await?;
-        }
+    #[instrument(skip(self))]
+    async fn list_transactions_page(
+        &self,
+        mint_url: MintUrl,
+        unit: CurrencyUnit,
+        from: u64,
+        to: u64,
+        offset: u64,
+        limit: u64,
+    ) -> Result<Vec<Transaction>, database::Error> {
+        let conn = self
+            .pool
+            .get()
+            .await
+            .map_err(|e| Error::Database(Box::new(e)))?;
+
+        Ok(query(&format!(
+            r#"
+            SELECT
+                mint_url,
+                direction,
+                unit,
+                amount,
+                fee,
+                ys,
+                timestamp,
+                memo,
+                metadata,
+                quote_id,
+                payment_request,
+                payment_proof,
+                payment_method,
+                saga_id
+            FROM
+                transactions
+            WHERE
+                mint_url = :mint_url
+                AND unit = :unit
+                AND timestamp >= :from
+                AND timestamp < :to
+            ORDER BY timestamp, id
+            LIMIT {limit} OFFSET {offset}
+            "#
+        ))?
+        .bind("mint_url", mint_url.to_string())
+        .bind("unit", unit.to_string())
+        .bind("from", from as i64)
+        .bind("to", to.min(i64::MAX as u64) as i64)
+        .fetch_all(&*conn)
+        .await?
+        .into_iter()
+        .map(sql_row_to_transaction)
+        .collect::<Result<Vec<_>, _>>()?)
+    }
+
+    async fn update_proofs(
+        &self,
+        added: Vec<ProofInfo>,
+        removed_ys: Vec<PublicKey>,
+    ) -> Result<(), database::Error> {
+        let conn = self
+            .pool
+            .get()
+            .await
+            .map_err(|e| Error::Database(Box::new(e)))?;
+        let tx = ConnectionWithTransaction::new(conn).await?;
 
+        Self::update_proofs_inner(&tx, added, removed_ys).await?;
         tx.commit().await?;
 
         Ok(())
@@ -865,13 +1305,7 @@ where
             .await
             .map_err(|e| Error::Database(Box::new(e)))?;
 
-        query("UPDATE proof SET state = :state WHERE y IN (:ys)")?
-            .bind_vec("ys", ys.iter().map(|y| y.to_bytes().to_vec()).collect())?
-            .bind("state", state.to_string())
-            .execute(&*conn)
-            .await?;
-
-        Ok(())
+        Self::update_proofs_state_inner(&*conn, ys, state).await
     }
 
     #[instrument(skip(self))]
@@ -882,64 +1316,7 @@ where
             .await
             .map_err(|e| Error::Database(Box::new(e)))?;
 
-        let mint_url = transaction.mint_url.to_string();
-        let direction = transaction.direction.to_string();
-        let unit = transaction.unit.to_string();
-        let amount = u64::from(transaction.amount) as i64;
-        let fee = u64::from(transaction.fee) as i64;
-        let ys = transaction
-            .ys
-            .iter()
-            .flat_map(|y| y.to_bytes().to_vec())
-            .collect::<Vec<_>>();
-
-        let id = transaction.id();
-
-        query(
-               r#"
-   INSERT INTO transactions
-   (id, mint_url, direction, unit, amount, fee, ys, timestamp, memo, metadata, quote_id, payment_request, payment_proof, payment_method, saga_id)
-   VALUES
-   (:id, :mint_url, :direction, :unit, :amount, :fee, :ys, :timestamp, :memo, :metadata, :quote_id, :payment_request, :payment_proof, :payment_method, :saga_id)
-   ON CONFLICT(id) DO UPDATE SET
-       mint_url = excluded.mint_url,
-       direction = excluded.direction,
-       unit = excluded.unit,
-       amount = excluded.amount,
-       fee = excluded.fee,
-       timestamp = excluded.timestamp,
-       memo = excluded.memo,
-       metadata = excluded.metadata,
-       quote_id = excluded.quote_id,
-       payment_request = excluded.payment_request,
-       payment_proof = excluded.payment_proof,
-       payment_method = excluded.payment_method,
-       saga_id = excluded.saga_id
-   ;
-           "#,
-           )?
-           .bind("id", id.as_slice().to_vec())
-           .bind("mint_url", mint_url)
-           .bind("direction", direction)
-           .bind("unit", unit)
-           .bind("amount", amount)
-           .bind("fee", fee)
-           .bind("ys", ys)
-           .bind("timestamp", transaction.timestamp as i64)
-           .bind("memo", transaction.memo)
-           .bind(
-               "metadata",
-               serde_json::to_string(&transaction.metadata).map_err(Error::from)?,
-           )
-           .bind("quote_id", transaction.quote_id)
-           .bind("payment_request", transaction.payment_request)
-           .bind("payment_proof", transaction.payment_proof)
-           .bind("payment_method", transaction.payment_method.map(|pm| pm.to_string()))
-           .bind("saga_id", transaction.saga_id.map(|id| id.to_string()))
-           .execute(&*conn)
-           .await?;
-
-        Ok(())
+        Self::add_transaction_inner(&*conn, transaction).await
     }
 
     #[instrument(skip(self))]
@@ -954,7 +1331,7 @@ where
             .await
             .map_err(|e| Error::Database(Box::new(e)))?;
         let tx = ConnectionWithTransaction::new(conn).await?;
-        let tables = ["mint_quote", "proof"];
+        let tables = ["mint_quote", "melt_quote", "proof", "transactions"];
 
         for table in &tables {
             query(&format!(
@@ -987,24 +1364,7 @@ where
             .await
             .map_err(|e| Error::Database(Box::new(e)))?;
 
-        let new_counter = query(
-            r#"
-            INSERT INTO keyset_counter (keyset_id, counter)
-            VALUES (:keyset_id, :count)
-            ON CONFLICT(keyset_id) DO UPDATE SET
-                counter = keyset_counter.counter + :count
-            RETURNING counter
-            "#,
-        )?
-        .bind("keyset_id", keyset_id.to_string())
-        .bind("count", count)
-        .pluck(&*conn)
-        .await?
-        .map(|n| Ok::<_, Error>(column_as_number!(n)))
-        .transpose()?
-        .ok_or_else(|| Error::Internal("Counter update returned no value".to_owned()))?;
-
-        Ok(new_counter)
+        Self::increment_keyset_counter_inner(&*conn, keyset_id, count).await
     }
 
     #[instrument(skip(self, mint_info))]
@@ -1181,58 +1541,7 @@ where
             .await
             .map_err(|e| Error::Database(Box::new(e)))?;
 
-        let expected_version = quote.version;
-        let new_version = expected_version.wrapping_add(1);
-
-        let rows_affected = query(
-                r#"
-    INSERT INTO mint_quote
-    (id, mint_url, amount, unit, request, state, expiry, secret_key, payment_method, amount_issued, amount_paid, updated_at, estimated_blocks, version, used_by_operation)
-    VALUES
-    (:id, :mint_url, :amount, :unit, :request, :state, :expiry, :secret_key, :payment_method, :amount_issued, :amount_paid, :updated_at, :estimated_blocks, :version, :used_by_operation)
-    ON CONFLICT(id) DO UPDATE SET
-        mint_url = excluded.mint_url,
-        amount = excluded.amount,
-        unit = excluded.unit,
-        request = excluded.request,
-        state = excluded.state,
-        expiry = excluded.expiry,
-        secret_key = excluded.secret_key,
-        payment_method = excluded.payment_method,
-        amount_issued = excluded.amount_issued,
-        amount_paid = excluded.amount_paid,
-        updated_at = excluded.updated_at,
-        estimated_blocks = excluded.estimated_blocks,
-        version = :new_version,
-        used_by_operation = excluded.used_by_operation
-    WHERE mint_quote.version = :expected_version
-    ;
-            "#,
-            )?
-            .bind("id", quote.id.to_string())
-            .bind("mint_url", quote.mint_url.to_string())
-            .bind("amount", quote.amount.map(|a| a.to_i64()))
-            .bind("unit", quote.unit.to_string())
-            .bind("request", quote.request)
-            .bind("state", quote.state.to_string())
-            .bind("expiry", quote.expiry as i64)
-            .bind("secret_key", quote.secret_key.map(|p| p.to_string()))
-            .bind("payment_method", quote.payment_method.to_string())
-            .bind("amount_issued", quote.amount_issued.to_i64())
-            .bind("amount_paid", quote.amount_paid.to_i64())
-            .bind("updated_at", quote.updated_at as i64)
-            .bind("estimated_blocks", quote.estimated_blocks.map(i64::from))
-            .bind("version", quote.version as i64)
-            .bind("new_version", new_version as i64)
-            .bind("expected_version", expected_version as i64)
-            .bind("used_by_operation", quote.used_by_operation)
-            .execute(&*conn).await?;
-
-        if rows_affected == 0 {
-            return Err(database::Error::ConcurrentUpdate);
-        }
-
-        Ok(())
+        Self::add_mint_quote_inner(&*conn, quote).await
     }
 
     #[instrument(skip(self))]
@@ -1259,57 +1568,7 @@ where
             .await
             .map_err(|e| Error::Database(Box::new(e)))?;
 
-        let expected_version = quote.version;
-        let new_version = expected_version.wrapping_add(1);
-
-        let rows_affected = query(
-            r#"
- INSERT INTO melt_quote
- (id, unit, amount, request, fee_reserve, state, expiry, payment_proof, payment_method, estimated_blocks, fee_index, version, mint_url, used_by_operation)
- VALUES
- (:id, :unit, :amount, :request, :fee_reserve, :state, :expiry, :payment_proof, :payment_method, :estimated_blocks, :fee_index, :version, :mint_url, :used_by_operation)
- ON CONFLICT(id) DO UPDATE SET
-     unit = excluded.unit,
-     amount = excluded.amount,
-     request = excluded.request,
-     fee_reserve = excluded.fee_reserve,
-     state = excluded.state,
-     expiry = excluded.expiry,
-     payment_proof = COALESCE(excluded.payment_proof, melt_quote.payment_proof),
-     payment_method = excluded.payment_method,
-     estimated_blocks = excluded.estimated_blocks,
-     fee_index = excluded.fee_index,
-     version = :new_version,
-     mint_url = excluded.mint_url,
-     used_by_operation = excluded.used_by_operation
- WHERE melt_quote.version = :expected_version
- ;
-         "#,
-        )?
-        .bind("id", quote.id.to_string())
-        .bind("unit", quote.unit.to_string())
-        .bind("amount", u64::from(quote.amount) as i64)
-        .bind("request", quote.request)
-        .bind("fee_reserve", u64::from(quote.fee_reserve) as i64)
-        .bind("state", quote.state.to_string())
-        .bind("expiry", quote.expiry as i64)
-        .bind("payment_proof", quote.payment_proof)
-        .bind("payment_method", quote.payment_method.to_string())
-        .bind("estimated_blocks", quote.estimated_blocks.map(i64::from))
-        .bind("fee_index", quote.fee_index.map(i64::from))
-        .bind("version", quote.version as i64)
-        .bind("new_version", new_version as i64)
-        .bind("expected_version", expected_version as i64)
-        .bind("mint_url", quote.mint_url.map(|m| m.to_string()))
-        .bind("used_by_operation", quote.used_by_operation)
-        .execute(&*conn)
-        .await?;
-
-        if rows_affected == 0 {
-            return Err(database::Error::ConcurrentUpdate);
-        }
-
-        Ok(())
+        Self::add_melt_quote_inner(&*conn, quote).await
     }
 
     #[instrument(skip(self))]
@@ -2058,6 +2317,7 @@ fn sql_row_to_melt_quote(row: Vec<Column>) -> Result<wallet::MeltQuote, Error> {
             row_method,
             estimated_blocks,
             fee_index,
+            melt_options,
             used_by_operation,
             version,
             mint_url
@@ -2084,6 +2344,10 @@ fn sql_row_to_melt_quote(row: Vec<Column>) -> Result<wallet::MeltQuote, Error> {
         payment_proof: column_as_nullable_string!(payment_proof),
         estimated_blocks: column_as_nullable_number!(estimated_blocks),
         fee_index: column_as_nullable_number!(fee_index),
+        options: column_as_nullable_string!(melt_options)
+            .map(|options| serde_json::from_str(&options))
+            .transpose()
+            .map_err(Error::from)?,
         payment_method,
         used_by_operation: column_as_nullable_string!(used_by_operation),
         version: version_val,
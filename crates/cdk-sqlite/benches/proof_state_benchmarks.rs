@@ -0,0 +1,154 @@
+//! Benchmarks of moving proofs between states, the database hot path of swaps and melts
+#![allow(missing_docs)]
+#![allow(clippy::unwrap_used)]
+use std::str::FromStr;
+use std::sync::Arc;
+
+use bitcoin::bip32::DerivationPath;
+use cdk_common::common::IssuerVersion;
+use cdk_common::database::{MintDatabase, MintKeysDatabase};
+use cdk_common::mint::{MintKeySetInfo, Operation};
+use cdk_common::nuts::{CurrencyUnit, Id, Proof, PublicKey, State};
+use cdk_common::secret::Secret;
+use cdk_common::{Amount, SecretKey};
+use cdk_sqlite::MintSqliteDatabase;
+use criterion::{criterion_group, criterion_main, BatchSize, BenchmarkId, Criterion};
+use tokio::runtime::Runtime;
+
+/// Concurrent swaps in the concurrency benchmark
+const CONCURRENT_SWAPS: usize = 8;
+
+/// Inputs of each swap in the concurrency benchmark
+const INPUTS_PER_SWAP: usize = 10;
+
+async fn setup_db(name: &str) -> (Arc<MintSqliteDatabase>, Id) {
+    let path = std::env::temp_dir().join(format!(
+        "cdk-proof-state-bench-{name}-{}.sqlite",
+        std::process::id()
+    ));
+    let _ = std::fs::remove_file(&path);
+    let db = MintSqliteDatabase::new(path).await.unwrap();
+
+    let keyset_id = Id::from_str("00916bbf7ef91a36").unwrap();
+    let keyset_info = MintKeySetInfo {
+        id: keyset_id,
+        unit: CurrencyUnit::Sat,
+        active: true,
+        valid_from: 0,
+        final_expiry: None,
+        derivation_path: DerivationPath::from_str("m/0'/0'/0'").unwrap(),
+        derivation_path_index: Some(0),
+        input_fee_ppk: 0,
+        amounts: (0..32).map(|n| 2u64.pow(n)).collect(),
+        issuer_version: IssuerVersion::from_str("cdk/0.1.0").ok(),
+    };
+    let mut tx = MintKeysDatabase::begin_transaction(&db).await.unwrap();
+    tx.add_keyset_info(keyset_info).await.unwrap();
+    tx.commit().await.unwrap();
+
+    (Arc::new(db), keyset_id)
+}
+
+/// Store `count` new unspent proofs and return their Ys
+async fn add_unspent_proofs(
+    db: &MintSqliteDatabase,
+    keyset_id: Id,
+    count: usize,
+) -> Vec<PublicKey> {
+    let proofs: Vec<Proof> = (0..count)
+        .map(|_| Proof {
+            amount: Amount::from(1),
+            keyset_id,
+            secret: Secret::generate(),
+            c: SecretKey::generate().public_key(),
+            witness: None,
+            dleq: None,
+            p2pk_e: None,
+        })
+        .collect();
+    let ys = proofs.iter().map(|proof| proof.y().unwrap()).collect();
+
+    let mut tx = MintDatabase::begin_transaction(db).await.unwrap();
+    tx.add_proofs(
+        proofs,
+        None,
+        &Operation::new_swap(Amount::ZERO, Amount::ZERO, Amount::ZERO),
+    )
+    .await
+    .unwrap();
+    tx.commit().await.unwrap();
+
+    ys
+}
+
+/// Spend the proofs `ys` the way a swap does: reserve them as pending, then mark them spent
+async fn spend(db: &MintSqliteDatabase, ys: &[PublicKey]) {
+    let mut tx = MintDatabase::begin_transaction(db).await.unwrap();
+    let mut proofs = tx.get_proofs(ys).await.unwrap();
+    tx.update_proofs_state(&mut proofs, State::Pending)
+        .await
+        .unwrap();
+    tx.commit().await.unwrap();
+
+    let mut tx = MintDatabase::begin_transaction(db).await.unwrap();
+    let mut proofs = tx.get_proofs(ys).await.unwrap();
+    tx.update_proofs_state(&mut proofs, State::Spent)
+        .await
+        .unwrap();
+    tx.commit().await.unwrap();
+}
+
+fn bench_update_proofs_state(c: &mut Criterion) {
+    let rt = Runtime::new().unwrap();
+    let (db, keyset_id) = rt.block_on(setup_db("single"));
+
+    let mut group = c.benchmark_group("spend_proofs");
+    for count in [1, 10, 100] {
+        group.bench_with_input(BenchmarkId::from_parameter(count), &count, |b, &count| {
+            b.iter_batched(
+                || rt.block_on(add_unspent_proofs(&db, keyset_id, count)),
+                |ys| rt.block_on(spend(&db, &ys)),
+                BatchSize::SmallInput,
+            )
+        });
+    }
+    group.finish();
+}
+
+fn bench_concurrent_spends(c: &mut Criterion) {
+    let rt = Runtime::new().unwrap();
+    let (db, keyset_id) = rt.block_on(setup_db("concurrent"));
+
+    c.bench_function("concurrent_spends_of_different_proofs", |b| {
+        b.iter_batched(
+            || {
+                rt.block_on(async {
+                    let mut swaps = Vec::with_capacity(CONCURRENT_SWAPS);
+                    for _ in 0..CONCURRENT_SWAPS {
+                        swaps.push(add_unspent_proofs(&db, keyset_id, INPUTS_PER_SWAP).await);
+                    }
+                    swaps
+                })
+            },
+            |swaps| {
+                rt.block_on(async {
+                    let handles: Vec<_> = swaps
+                        .into_iter()
+                        .map(|ys| {
+                            let db = Arc::clone(&db);
+                            tokio::spawn(async move { spend(&db, &ys).await })
+                        })
+                        .collect();
+
+                    for handle in handles {
+                        handle.await.unwrap();
+                    }
+                })
+            },
+            BatchSize::SmallInput,
+        )
+    });
+}
+
+criterion_group!(benches, bench_update_proofs_state, bench_concurrent_spends);
+criterion_main!(benches);
@@ -18,6 +18,7 @@ mod test {
     use std::fs::remove_file;
     use std::time::Duration;
 
+    use cdk_common::database::{Error, MigrationState};
     use cdk_common::mint_db_test;
     use cdk_sql_common::pool::Pool;
     use cdk_sql_common::stmt::query;
@@ -87,4 +88,64 @@ mod test {
 
         let _ = remove_file(&file);
     }
+
+    #[tokio::test]
+    async fn migrations_abort_on_divergence() {
+        let file = format!(
+            "{}/migrations-divergence.sqlite",
+            std::env::temp_dir().to_str().unwrap_or_default()
+        );
+        let _ = remove_file(&file);
+
+        #[cfg(not(feature = "sqlcipher"))]
+        let config: Config = file.as_str().into();
+        #[cfg(feature = "sqlcipher")]
+        let config: Config = (file.as_str(), "test").into();
+
+        let statuses = MintSqliteDatabase::inspect_migrations(config.clone())
+            .await
+            .unwrap();
+        assert!(!statuses.is_empty());
+        assert!(statuses
+            .iter()
+            .all(|status| status.state == MigrationState::Pending));
+
+        MintSqliteDatabase::new(config.clone()).await.unwrap();
+        let statuses = MintSqliteDatabase::inspect_migrations(config.clone())
+            .await
+            .unwrap();
+        assert!(statuses
+            .iter()
+            .all(|status| status.state == MigrationState::Applied));
+
+        {
+            let pool = Pool::<SqliteConnectionManager>::new(config.clone());
+            let conn = pool.get().await.expect("valid connection");
+            query("UPDATE migration_checksums SET checksum = 'tampered' WHERE name = :name")
+                .unwrap()
+                .bind("name", statuses[0].name.clone())
+                .execute(&*conn)
+                .await
+                .unwrap();
+            query("DELETE FROM migrations WHERE name = :name")
+                .unwrap()
+                .bind("name", statuses[1].name.clone())
+                .execute(&*conn)
+                .await
+                .unwrap();
+        }
+
+        let diverged = MintSqliteDatabase::inspect_migrations(config.clone())
+            .await
+            .unwrap();
+        assert_eq!(diverged[0].state, MigrationState::ChecksumMismatch);
+        assert_eq!(diverged[1].state, MigrationState::OutOfOrder);
+
+        assert!(matches!(
+            MintSqliteDatabase::new(config).await,
+            Err(Error::MigrationDiverged(_))
+        ));
+
+        let _ = remove_file(&file);
+    }
 }
@@ -0,0 +1,7 @@
+-- Melt options (MPP or amountless) a bolt11 quote was requested with, as JSON,
+-- so an active quote is only reused for a request with the same options.
+
+ALTER TABLE melt_quote ADD COLUMN IF NOT EXISTS melt_options TEXT;
+
+INSERT INTO schema_info (key, value) VALUES ('schema_version', '9')
+ON CONFLICT (key) DO UPDATE SET value = EXCLUDED.value;
@@ -0,0 +1,44 @@
+-- Record when each synced row last changed, in microseconds since the epoch by
+-- the server clock, so the local cache only pulls rows changed since its last pull.
+CREATE OR REPLACE FUNCTION set_changed_at()
+RETURNS TRIGGER
+LANGUAGE plpgsql
+AS $body$
+begin
+  NEW.changed_at = (EXTRACT(EPOCH FROM clock_timestamp()) * 1000000)::BIGINT;
+  RETURN NEW;
+end
+$body$;
+
+ALTER TABLE proof ADD COLUMN IF NOT EXISTS changed_at BIGINT NOT NULL
+    DEFAULT (EXTRACT(EPOCH FROM clock_timestamp()) * 1000000)::BIGINT;
+ALTER TABLE mint_quote ADD COLUMN IF NOT EXISTS changed_at BIGINT NOT NULL
+    DEFAULT (EXTRACT(EPOCH FROM clock_timestamp()) * 1000000)::BIGINT;
+ALTER TABLE melt_quote ADD COLUMN IF NOT EXISTS changed_at BIGINT NOT NULL
+    DEFAULT (EXTRACT(EPOCH FROM clock_timestamp()) * 1000000)::BIGINT;
+ALTER TABLE transactions ADD COLUMN IF NOT EXISTS changed_at BIGINT NOT NULL
+    DEFAULT (EXTRACT(EPOCH FROM clock_timestamp()) * 1000000)::BIGINT;
+
+CREATE TRIGGER set_proof_changed_at
+    BEFORE INSERT OR UPDATE ON proof
+    FOR EACH ROW EXECUTE FUNCTION set_changed_at();
+
+CREATE TRIGGER set_mint_quote_changed_at
+    BEFORE INSERT OR UPDATE ON mint_quote
+    FOR EACH ROW EXECUTE FUNCTION set_changed_at();
+
+CREATE TRIGGER set_melt_quote_changed_at
+    BEFORE INSERT OR UPDATE ON melt_quote
+    FOR EACH ROW EXECUTE FUNCTION set_changed_at();
+
+CREATE TRIGGER set_transactions_changed_at
+    BEFORE INSERT OR UPDATE ON transactions
+    FOR EACH ROW EXECUTE FUNCTION set_changed_at();
+
+CREATE INDEX IF NOT EXISTS idx_proof_changed_at ON proof(wallet_id, changed_at);
+CREATE INDEX IF NOT EXISTS idx_mint_quote_changed_at ON mint_quote(wallet_id, changed_at);
+CREATE INDEX IF NOT EXISTS idx_melt_quote_changed_at ON melt_quote(wallet_id, changed_at);
+CREATE INDEX IF NOT EXISTS idx_transactions_changed_at ON transactions(wallet_id, changed_at);
+
+INSERT INTO schema_info (key, value) VALUES ('schema_version', '10')
+ON CONFLICT (key) DO UPDATE SET value = EXCLUDED.value;
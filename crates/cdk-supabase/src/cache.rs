@@ -0,0 +1,1785 @@
+//! Local SQLite cache in front of Supabase
+//!
+//! Reads are served from a local SQLite database so balance checks and history do not wait on the
+//! network. Writes are applied to the cache and recorded in an outbox stored alongside it, which a
+//! background task pushes to Supabase in order. Once the outbox is empty the task pulls the remote
+//! state back into the cache so changes made on other devices show up locally. Only rows changed
+//! since the previous pull are fetched, along with the keys of all rows to notice deletions.
+//!
+//! A write Supabase cannot be reached for is retried on the next sync, holding back the writes
+//! queued after it. A write Supabase rejects is moved to the failed writes, see
+//! [`CachedSupabaseWalletDatabase::failed_writes`], and the writes after it are pushed; the next
+//! pull then replaces what the rejected write changed in the cache with the remote state.
+//!
+//! Each write and its outbox entry are committed in one local transaction where the local database
+//! supports it. The remaining writes record their outbox entry first, so a crash can leave an entry
+//! whose local write never happened, but never a local write that is not queued; replaying such an
+//! entry and pulling brings the cache back in line with Supabase.
+//!
+//! Keyset counters and reservations are not cached writes: they go to Supabase first, after any
+//! queued writes, and fail while it is unreachable. This keeps two devices from deriving the same
+//! secrets or spending the same proofs.
+//!
+//! Conflicts resolve as last writer wins on the server: local writes are replayed in the order
+//! they were made, and remote rows replace cached rows only when no local write is pending. A
+//! queued quote or saga update that another device already superseded is dropped in favour of the
+//! remote row. A proof the cache already knows to be spent is never moved back to another state,
+//! and keyset counters only ever move forward.
+
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::sync::{Arc, Weak};
+use std::time::Duration;
+
+use async_trait::async_trait;
+use bitcoin::bip32::DerivationPath;
+use cdk_common::database::wallet::{BufferedTransaction, Database, DatabaseTransaction};
+use cdk_common::database::{Error as DatabaseError, KVStoreDatabase};
+use cdk_common::mint_url::MintUrl;
+use cdk_common::nuts::{
+    CurrencyUnit, Id, KeySet, KeySetInfo, Keys, MintInfo, PublicKey, SpendingConditions, State,
+};
+use cdk_common::wallet::{
+    self, MintQuote as WalletMintQuote, ProofInfo, Transaction, TransactionDirection, TransactionId,
+};
+use cdk_sqlite::WalletSqliteDatabase;
+use serde::{Deserialize, Serialize};
+use tokio::sync::{Mutex, Notify};
+use tokio::task::JoinHandle;
+
+use crate::{Error, SupabaseWalletDatabase};
+
+/// How often the cache syncs with Supabase when there are no local writes
+pub const DEFAULT_SYNC_INTERVAL: Duration = Duration::from_secs(30);
+
+/// KV namespace holding the outbox in the local database
+const OUTBOX_PRIMARY_NAMESPACE: &str = "supabase_cache";
+const OUTBOX_SECONDARY_NAMESPACE: &str = "outbox";
+/// KV namespace holding the writes Supabase rejected
+const FAILED_SECONDARY_NAMESPACE: &str = "failed";
+/// KV entry holding how far the cache has pulled
+const PULL_SECONDARY_NAMESPACE: &str = "pull";
+const PULL_CURSOR_KEY: &str = "cursor";
+
+/// How far before the last pulled change a pull starts again, so rows committed out of order by
+/// concurrent writers are not skipped
+const PULL_OVERLAP_MICROS: i64 = 60_000_000;
+
+/// A write applied to the cache that has not reached Supabase yet
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "op", rename_all = "snake_case")]
+enum PendingWrite {
+    UpdateProofs {
+        added: Vec<ProofInfo>,
+        removed_ys: Vec<PublicKey>,
+    },
+    UpdateProofsState {
+        ys: Vec<PublicKey>,
+        state: State,
+    },
+    AddTransaction {
+        transaction: Transaction,
+    },
+    RemoveTransaction {
+        transaction_id: TransactionId,
+    },
+    UpdateMintUrl {
+        old_mint_url: MintUrl,
+        new_mint_url: MintUrl,
+    },
+    AddMint {
+        mint_url: MintUrl,
+        mint_info: Option<MintInfo>,
+    },
+    RemoveMint {
+        mint_url: MintUrl,
+    },
+    AddMintKeysets {
+        mint_url: MintUrl,
+        keysets: Vec<KeySetInfo>,
+    },
+    AddMintQuote {
+        quote: WalletMintQuote,
+    },
+    RemoveMintQuote {
+        quote_id: String,
+    },
+    AddMeltQuote {
+        quote: wallet::MeltQuote,
+    },
+    RemoveMeltQuote {
+        quote_id: String,
+    },
+    AddKeys {
+        keyset: KeySet,
+    },
+    RemoveKeys {
+        id: Id,
+    },
+    AddSaga {
+        saga: wallet::WalletSaga,
+    },
+    UpdateSaga {
+        saga: wallet::WalletSaga,
+    },
+    DeleteSaga {
+        id: uuid::Uuid,
+    },
+    ReleaseProofs {
+        operation_id: uuid::Uuid,
+    },
+    ReleaseMeltQuote {
+        operation_id: uuid::Uuid,
+    },
+    ReleaseMintQuote {
+        operation_id: uuid::Uuid,
+    },
+    KvWrite {
+        primary_namespace: String,
+        secondary_namespace: String,
+        key: String,
+        value: Vec<u8>,
+    },
+    KvRemove {
+        primary_namespace: String,
+        secondary_namespace: String,
+        key: String,
+    },
+    AddP2pkKey {
+        pubkey: PublicKey,
+        derivation_path: DerivationPath,
+        derivation_index: u32,
+    },
+}
+
+impl PendingWrite {
+    /// Replay the write against Supabase
+    async fn apply(
+        self,
+        remote: &SupabaseWalletDatabase,
+        local: &WalletSqliteDatabase,
+    ) -> Result<(), DatabaseError> {
+        match self {
+            Self::UpdateProofs { added, removed_ys } => {
+                remote.update_proofs(added, removed_ys).await
+            }
+            Self::UpdateProofsState { ys, state } => remote.update_proofs_state(ys, state).await,
+            Self::AddTransaction { transaction } => remote.add_transaction(transaction).await,
+            Self::RemoveTransaction { transaction_id } => {
+                remote.remove_transaction(transaction_id).await
+            }
+            Self::UpdateMintUrl {
+                old_mint_url,
+                new_mint_url,
+            } => remote.update_mint_url(old_mint_url, new_mint_url).await,
+            Self::AddMint {
+                mint_url,
+                mint_info,
+            } => remote.add_mint(mint_url, mint_info).await,
+            Self::RemoveMint { mint_url } => remote.remove_mint(mint_url).await,
+            Self::AddMintKeysets { mint_url, keysets } => {
+                remote.add_mint_keysets(mint_url, keysets).await
+            }
+            Self::AddMintQuote { quote } => match remote.add_mint_quote(quote.clone()).await {
+                Err(DatabaseError::ConcurrentUpdate) => {
+                    // Another device moved the quote on first, so its version wins
+                    tracing::warn!(
+                        "Mint quote {} was updated by another device, keeping the remote version",
+                        quote.id
+                    );
+                    let remote_quote = remote.get_mint_quote(&quote.id).await?;
+                    local.remove_mint_quote(&quote.id).await?;
+                    if let Some(remote_quote) = remote_quote {
+                        local.add_mint_quote(remote_quote).await?;
+                    }
+                    Ok(())
+                }
+                result => result,
+            },
+            Self::RemoveMintQuote { quote_id } => remote.remove_mint_quote(&quote_id).await,
+            Self::AddMeltQuote { quote } => match remote.add_melt_quote(quote.clone()).await {
+                Err(DatabaseError::ConcurrentUpdate) => {
+                    tracing::warn!(
+                        "Melt quote {} was updated by another device, keeping the remote version",
+                        quote.id
+                    );
+                    let remote_quote = remote.get_melt_quote(&quote.id).await?;
+                    local.remove_melt_quote(&quote.id).await?;
+                    if let Some(remote_quote) = remote_quote {
+                        local.add_melt_quote(remote_quote).await?;
+                    }
+                    Ok(())
+                }
+                result => result,
+            },
+            Self::RemoveMeltQuote { quote_id } => remote.remove_melt_quote(&quote_id).await,
+            Self::AddKeys { keyset } => remote.add_keys(keyset).await,
+            Self::RemoveKeys { id } => remote.remove_keys(&id).await,
+            Self::AddSaga { saga } => remote.add_saga(saga).await,
+            Self::UpdateSaga { saga } => {
+                if remote.update_saga(saga.clone()).await? {
+                    return Ok(());
+                }
+
+                // Another device moved the saga on first, so its version wins
+                tracing::warn!(
+                    "Saga {} was updated by another device, keeping the remote version",
+                    saga.id
+                );
+                if let Some(remote_saga) = remote.get_saga(&saga.id).await? {
+                    local.delete_saga(&saga.id).await?;
+                    local.add_saga(remote_saga).await?;
+                }
+                Ok(())
+            }
+            Self::DeleteSaga { id } => remote.delete_saga(&id).await,
+            Self::ReleaseProofs { operation_id } => remote.release_proofs(&operation_id).await,
+            Self::ReleaseMeltQuote { operation_id } => {
+                remote.release_melt_quote(&operation_id).await
+            }
+            Self::ReleaseMintQuote { operation_id } => {
+                remote.release_mint_quote(&operation_id).await
+            }
+            Self::KvWrite {
+                primary_namespace,
+                secondary_namespace,
+                key,
+                value,
+            } => {
+                remote
+                    .kv_write(&primary_namespace, &secondary_namespace, &key, &value)
+                    .await
+            }
+            Self::KvRemove {
+                primary_namespace,
+                secondary_namespace,
+                key,
+            } => {
+                remote
+                    .kv_remove(&primary_namespace, &secondary_namespace, &key)
+                    .await
+            }
+            Self::AddP2pkKey {
+                pubkey,
+                derivation_path,
+                derivation_index,
+            } => {
+                remote
+                    .add_p2pk_key(&pubkey, derivation_path, derivation_index)
+                    .await
+            }
+        }
+    }
+}
+
+/// Key of an outbox entry, zero padded so entries sort in write order
+fn outbox_key(seq: u64) -> String {
+    format!("{seq:020}")
+}
+
+/// Whether Supabase rejected a write for good, so replaying it can never succeed
+///
+/// When unsure the error is treated as transient and the write is retried.
+fn is_rejection(err: &DatabaseError) -> bool {
+    match err {
+        DatabaseError::Database(err) => {
+            err.downcast_ref::<Error>().is_some_and(Error::is_rejection)
+        }
+        DatabaseError::Internal(_)
+        | DatabaseError::Locked
+        | DatabaseError::ConcurrentUpdate
+        | DatabaseError::InvalidDbResponse
+        | DatabaseError::Serde(_) => false,
+        _ => true,
+    }
+}
+
+/// A queued write Supabase rejected
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FailedWrite {
+    /// Position of the write in the outbox
+    pub seq: u64,
+    /// The write as it was queued
+    pub write: serde_json::Value,
+    /// Why Supabase rejected it
+    pub error: String,
+}
+
+/// When the last pulled row of each table changed on the server, in microseconds since the epoch
+#[derive(Debug, Default, Clone, Copy, Serialize, Deserialize)]
+struct PullCursor {
+    proofs: Option<i64>,
+    mint_quotes: Option<i64>,
+    melt_quotes: Option<i64>,
+    transactions: Option<i64>,
+}
+
+impl PullCursor {
+    /// Change time to pull a table from, `None` pulling every row
+    fn since(changed_at: Option<i64>) -> Option<i64> {
+        changed_at.map(|changed_at| changed_at.saturating_sub(PULL_OVERLAP_MICROS))
+    }
+}
+
+#[derive(Debug, Default)]
+struct Outbox {
+    next_seq: u64,
+    writes: VecDeque<(u64, PendingWrite)>,
+}
+
+#[derive(Debug)]
+struct Inner {
+    local: WalletSqliteDatabase,
+    remote: SupabaseWalletDatabase,
+    outbox: Mutex<Outbox>,
+    /// Held while a write is applied to the cache and queued, and while remote state is merged
+    write_lock: Mutex<()>,
+    /// Held for the duration of a sync so pushes never run concurrently
+    sync_lock: Mutex<()>,
+    wake: Notify,
+}
+
+/// Wallet database that serves reads from a local SQLite cache and syncs writes to Supabase
+///
+/// Call [`CachedSupabaseWalletDatabase::spawn_sync`] to push and pull in the background, or
+/// [`CachedSupabaseWalletDatabase::sync`] to sync on demand.
+#[derive(Debug, Clone)]
+pub struct CachedSupabaseWalletDatabase {
+    inner: Arc<Inner>,
+}
+
+impl CachedSupabaseWalletDatabase {
+    /// Put `local` in front of `remote`, restoring any writes not yet synced from a previous run
+    pub async fn new(
+        local: WalletSqliteDatabase,
+        remote: SupabaseWalletDatabase,
+    ) -> Result<Self, DatabaseError> {
+        let mut seqs = local
+            .kv_list(OUTBOX_PRIMARY_NAMESPACE, OUTBOX_SECONDARY_NAMESPACE)
+            .await?
+            .into_iter()
+            .filter_map(|key| key.parse::<u64>().ok())
+            .collect::<Vec<_>>();
+        seqs.sort_unstable();
+
+        let mut outbox = Outbox::default();
+        for seq in seqs {
+            let Some(value) = local
+                .kv_read(
+                    OUTBOX_PRIMARY_NAMESPACE,
+                    OUTBOX_SECONDARY_NAMESPACE,
+                    &outbox_key(seq),
+                )
+                .await?
+            else {
+                continue;
+            };
+            let write = serde_json::from_slice(&value)
+                .map_err(|e| DatabaseError::Internal(format!("Invalid outbox entry: {e}")))?;
+            outbox.writes.push_back((seq, write));
+            outbox.next_seq = seq + 1;
+        }
+
+        Ok(Self {
+            inner: Arc::new(Inner {
+                local,
+                remote,
+                outbox: Mutex::new(outbox),
+                write_lock: Mutex::new(()),
+                sync_lock: Mutex::new(()),
+                wake: Notify::new(),
+            }),
+        })
+    }
+
+    /// The Supabase database behind the cache
+    pub fn remote(&self) -> &SupabaseWalletDatabase {
+        &self.inner.remote
+    }
+
+    /// Number of local writes not yet pushed to Supabase
+    pub async fn pending_writes(&self) -> usize {
+        self.inner.outbox.lock().await.writes.len()
+    }
+
+    /// Writes Supabase rejected, oldest first
+    ///
+    /// They are no longer retried and their changes are gone from the cache once it pulls again.
+    pub async fn failed_writes(&self) -> Result<Vec<FailedWrite>, DatabaseError> {
+        let local = &self.inner.local;
+        let mut keys = local
+            .kv_list(OUTBOX_PRIMARY_NAMESPACE, FAILED_SECONDARY_NAMESPACE)
+            .await?;
+        keys.sort_unstable();
+
+        let mut failed = Vec::with_capacity(keys.len());
+        for key in keys {
+            let Some(value) = local
+                .kv_read(OUTBOX_PRIMARY_NAMESPACE, FAILED_SECONDARY_NAMESPACE, &key)
+                .await?
+            else {
+                continue;
+            };
+            failed.push(
+                serde_json::from_slice(&value)
+                    .map_err(|e| DatabaseError::Internal(format!("Invalid failed write: {e}")))?,
+            );
+        }
+        Ok(failed)
+    }
+
+    /// Forget a write Supabase rejected, once it has been dealt with
+    pub async fn remove_failed_write(&self, seq: u64) -> Result<(), DatabaseError> {
+        self.inner
+            .local
+            .kv_remove(
+                OUTBOX_PRIMARY_NAMESPACE,
+                FAILED_SECONDARY_NAMESPACE,
+                &outbox_key(seq),
+            )
+            .await
+    }
+
+    /// Sync in the background, right after local writes and otherwise every `interval`
+    ///
+    /// The task stops once every handle to the database has been dropped.
+    pub fn spawn_sync(&self, interval: Duration) -> JoinHandle<()> {
+        let inner = Arc::downgrade(&self.inner);
+
+        tokio::spawn(async move {
+            loop {
+                let Some(db) = Self::upgrade(&inner) else {
+                    return;
+                };
+                if let Err(err) = db.sync().await {
+                    tracing::warn!("Supabase cache sync failed: {}", err);
+                }
+
+                tokio::select! {
+                    _ = db.inner.wake.notified() => {}
+                    _ = tokio::time::sleep(interval) => {}
+                }
+            }
+        })
+    }
+
+    fn upgrade(inner: &Weak<Inner>) -> Option<Self> {
+        inner.upgrade().map(|inner| Self { inner })
+    }
+
+    /// Push pending writes to Supabase, then pull the remote state into the cache
+    ///
+    /// Writes are pushed in order. A write that could not reach Supabase is retried from the same
+    /// write on the next sync, so nothing is skipped while Supabase is unreachable. A write
+    /// Supabase rejects is moved to the [failed writes](Self::failed_writes) and the push goes on.
+    pub async fn sync(&self) -> Result<(), DatabaseError> {
+        let _sync = self.inner.sync_lock.lock().await;
+
+        self.push().await?;
+        self.pull().await
+    }
+
+    async fn push(&self) -> Result<(), DatabaseError> {
+        loop {
+            let Some((seq, write)) = self.inner.outbox.lock().await.writes.front().cloned() else {
+                return Ok(());
+            };
+
+            match write
+                .clone()
+                .apply(&self.inner.remote, &self.inner.local)
+                .await
+            {
+                Ok(()) => self.discard(seq).await?,
+                Err(err) if is_rejection(&err) => {
+                    tracing::warn!(
+                        "Supabase rejected queued write {}, moving it to the failed writes: {}",
+                        seq,
+                        err
+                    );
+                    self.fail(seq, &write, &err).await?;
+                }
+                Err(err) => return Err(err),
+            }
+            self.inner.outbox.lock().await.writes.pop_front();
+        }
+    }
+
+    /// Move a write Supabase rejected from the outbox to the failed writes
+    ///
+    /// The pull cursor is reset, so the next pull fetches every row and replaces what the write
+    /// changed in the cache.
+    async fn fail(
+        &self,
+        seq: u64,
+        write: &PendingWrite,
+        err: &DatabaseError,
+    ) -> Result<(), DatabaseError> {
+        let failed = FailedWrite {
+            seq,
+            write: serde_json::to_value(write)
+                .map_err(|e| DatabaseError::Internal(format!("Could not record write: {e}")))?,
+            error: err.to_string(),
+        };
+        let value = serde_json::to_vec(&failed)
+            .map_err(|e| DatabaseError::Internal(format!("Could not record write: {e}")))?;
+
+        let mut tx = self.inner.local.begin_db_transaction().await?;
+        tx.kv_write(
+            OUTBOX_PRIMARY_NAMESPACE,
+            FAILED_SECONDARY_NAMESPACE,
+            &outbox_key(seq),
+            &value,
+        )
+        .await?;
+        tx.kv_remove(
+            OUTBOX_PRIMARY_NAMESPACE,
+            OUTBOX_SECONDARY_NAMESPACE,
+            &outbox_key(seq),
+        )
+        .await?;
+        tx.kv_remove(
+            OUTBOX_PRIMARY_NAMESPACE,
+            PULL_SECONDARY_NAMESPACE,
+            PULL_CURSOR_KEY,
+        )
+        .await?;
+        tx.commit().await
+    }
+
+    async fn pull_cursor(&self) -> Result<PullCursor, DatabaseError> {
+        let Some(value) = self
+            .inner
+            .local
+            .kv_read(
+                OUTBOX_PRIMARY_NAMESPACE,
+                PULL_SECONDARY_NAMESPACE,
+                PULL_CURSOR_KEY,
+            )
+            .await?
+        else {
+            return Ok(PullCursor::default());
+        };
+        serde_json::from_slice(&value)
+            .map_err(|e| DatabaseError::Internal(format!("Invalid pull cursor: {e}")))
+    }
+
+    async fn set_pull_cursor(&self, cursor: PullCursor) -> Result<(), DatabaseError> {
+        let value = serde_json::to_vec(&cursor)
+            .map_err(|e| DatabaseError::Internal(format!("Invalid pull cursor: {e}")))?;
+        self.inner
+            .local
+            .kv_write(
+                OUTBOX_PRIMARY_NAMESPACE,
+                PULL_SECONDARY_NAMESPACE,
+                PULL_CURSOR_KEY,
+                &value,
+            )
+            .await
+    }
+
+    /// Pull the rows changed since the last pull, and drop cached rows deleted on the server
+    async fn pull(&self) -> Result<(), DatabaseError> {
+        let remote = &self.inner.remote;
+        let local = &self.inner.local;
+        let cursor = self.pull_cursor().await?;
+
+        let mints = remote.get_mints().await?;
+        let mut keysets = Vec::new();
+        for mint_url in mints.keys() {
+            if let Some(mint_keysets) = remote.get_mint_keysets(mint_url.clone()).await? {
+                keysets.push((mint_url.clone(), mint_keysets));
+            }
+        }
+        let mut counters = Vec::new();
+        for keyset in keysets.iter().flat_map(|(_, keysets)| keysets) {
+            counters.push((
+                keyset.id,
+                remote.increment_keyset_counter(&keyset.id, 0).await?,
+            ));
+        }
+        let (proofs, proofs_changed_at) = remote
+            .proofs_changed_since(PullCursor::since(cursor.proofs))
+            .await?;
+        let (mint_quotes, mint_quotes_changed_at) = remote
+            .mint_quotes_changed_since(PullCursor::since(cursor.mint_quotes))
+            .await?;
+        let (melt_quotes, melt_quotes_changed_at) = remote
+            .melt_quotes_changed_since(PullCursor::since(cursor.melt_quotes))
+            .await?;
+        let (transactions, transactions_changed_at) = remote
+            .transactions_changed_since(PullCursor::since(cursor.transactions))
+            .await?;
+
+        // Listed after the changed rows, so a row deleted in between is not cached again
+        let remote_ys = remote.proof_ys().await?.into_iter().collect::<HashSet<_>>();
+        let remote_mint_quotes = remote
+            .mint_quote_ids()
+            .await?
+            .into_iter()
+            .collect::<HashSet<_>>();
+        let remote_melt_quotes = remote
+            .melt_quote_ids()
+            .await?
+            .into_iter()
+            .collect::<HashSet<_>>();
+        let remote_transactions = remote
+            .transaction_ids()
+            .await?
+            .into_iter()
+            .collect::<HashSet<_>>();
+
+        let _write = self.inner.write_lock.lock().await;
+        if !self.inner.outbox.lock().await.writes.is_empty() {
+            // Written to while pulling; merge on the next sync once those writes are pushed
+            return Ok(());
+        }
+
+        for mint_url in local.get_mints().await?.into_keys() {
+            if !mints.contains_key(&mint_url) {
+                local.remove_mint(mint_url).await?;
+            }
+        }
+        for (mint_url, mint_info) in mints {
+            local.add_mint(mint_url, mint_info).await?;
+        }
+        for (mint_url, mint_keysets) in keysets {
+            local.add_mint_keysets(mint_url, mint_keysets).await?;
+        }
+        for (keyset_id, remote_counter) in counters {
+            self.advance_local_counter(&keyset_id, remote_counter)
+                .await?;
+        }
+
+        let cached_states = local
+            .get_proofs(None, None, None, None)
+            .await?
+            .into_iter()
+            .map(|proof| (proof.y, proof.state))
+            .collect::<HashMap<_, _>>();
+        let removed_ys = cached_states
+            .keys()
+            .filter(|y| !remote_ys.contains(y))
+            .copied()
+            .collect();
+        let added = proofs
+            .into_iter()
+            .filter(|proof| remote_ys.contains(&proof.y))
+            .filter(|proof| {
+                proof.state == State::Spent || cached_states.get(&proof.y) != Some(&State::Spent)
+            })
+            .collect();
+        local.update_proofs(added, removed_ys).await?;
+
+        // Quotes are replaced rather than upserted, since an upsert bumps the cached version past
+        // the server's and every later write would then conflict
+        let mut cached_versions = HashMap::new();
+        for quote in local.get_mint_quotes().await? {
+            if remote_mint_quotes.contains(&quote.id) {
+                cached_versions.insert(quote.id, quote.version);
+            } else {
+                local.remove_mint_quote(&quote.id).await?;
+            }
+        }
+        for quote in mint_quotes {
+            if !remote_mint_quotes.contains(&quote.id) {
+                continue;
+            }
+            match cached_versions.get(&quote.id) {
+                Some(version) if *version == quote.version => continue,
+                Some(_) => local.remove_mint_quote(&quote.id).await?,
+                None => {}
+            }
+            local.add_mint_quote(quote).await?;
+        }
+
+        let mut cached_versions = HashMap::new();
+        for quote in local.get_melt_quotes().await? {
+            if remote_melt_quotes.contains(&quote.id) {
+                cached_versions.insert(quote.id, quote.version);
+            } else {
+                local.remove_melt_quote(&quote.id).await?;
+            }
+        }
+        for quote in melt_quotes {
+            if !remote_melt_quotes.contains(&quote.id) {
+                continue;
+            }
+            match cached_versions.get(&quote.id) {
+                Some(version) if *version == quote.version => continue,
+                Some(_) => local.remove_melt_quote(&quote.id).await?,
+                None => {}
+            }
+            local.add_melt_quote(quote).await?;
+        }
+
+        for transaction in local.list_transactions(None, None, None).await? {
+            if !remote_transactions.contains(&transaction.id()) {
+                local.remove_transaction(transaction.id()).await?;
+            }
+        }
+        for transaction in transactions {
+            if remote_transactions.contains(&transaction.id()) {
+                local.add_transaction(transaction).await?;
+            }
+        }
+
+        self.set_pull_cursor(PullCursor {
+            proofs: proofs_changed_at.or(cursor.proofs),
+            mint_quotes: mint_quotes_changed_at.or(cursor.mint_quotes),
+            melt_quotes: melt_quotes_changed_at.or(cursor.melt_quotes),
+            transactions: transactions_changed_at.or(cursor.transactions),
+        })
+        .await
+    }
+
+    /// Move the cached counter of `keyset_id` up to `counter`, never back
+    async fn advance_local_counter(
+        &self,
+        keyset_id: &Id,
+        counter: u32,
+    ) -> Result<(), DatabaseError> {
+        let local = &self.inner.local;
+        let local_counter = local.increment_keyset_counter(keyset_id, 0).await?;
+        if counter > local_counter {
+            local
+                .increment_keyset_counter(keyset_id, counter - local_counter)
+                .await?;
+        }
+        Ok(())
+    }
+
+    /// Run `remote` against Supabase once every queued write has reached it
+    ///
+    /// Used for writes that must not be made offline, so it fails while Supabase is unreachable.
+    async fn remote_first<T>(
+        &self,
+        remote: impl std::future::Future<Output = Result<T, DatabaseError>>,
+    ) -> Result<T, DatabaseError> {
+        let _sync = self.inner.sync_lock.lock().await;
+        self.push().await?;
+        remote.await
+    }
+
+    /// Serialized outbox entry for `write`, and the sequence number it will be stored under
+    ///
+    /// Must be called with the write lock held.
+    async fn outbox_entry(&self, write: &PendingWrite) -> Result<(u64, Vec<u8>), DatabaseError> {
+        let value = serde_json::to_vec(write)
+            .map_err(|e| DatabaseError::Internal(format!("Could not queue write: {e}")))?;
+        Ok((self.inner.outbox.lock().await.next_seq, value))
+    }
+
+    /// Persist the outbox entry for `write` ahead of the local write it records
+    ///
+    /// Must be called with the write lock held. The entry is only pushed once [`Self::queued`] is
+    /// called, or after a restart if the process stopped in between.
+    async fn persist(&self, write: &PendingWrite) -> Result<u64, DatabaseError> {
+        let (seq, value) = self.outbox_entry(write).await?;
+        self.inner
+            .local
+            .kv_write(
+                OUTBOX_PRIMARY_NAMESPACE,
+                OUTBOX_SECONDARY_NAMESPACE,
+                &outbox_key(seq),
+                &value,
+            )
+            .await?;
+        Ok(seq)
+    }
+
+    /// Drop a persisted outbox entry whose local write did not happen
+    async fn discard(&self, seq: u64) -> Result<(), DatabaseError> {
+        self.inner
+            .local
+            .kv_remove(
+                OUTBOX_PRIMARY_NAMESPACE,
+                OUTBOX_SECONDARY_NAMESPACE,
+                &outbox_key(seq),
+            )
+            .await
+    }
+
+    /// Hand a persisted outbox entry to the sync task
+    async fn queued(&self, seq: u64, write: PendingWrite) {
+        let mut outbox = self.inner.outbox.lock().await;
+        outbox.next_seq = seq + 1;
+        outbox.writes.push_back((seq, write));
+        drop(outbox);
+
+        self.inner.wake.notify_one();
+    }
+}
+
+#[async_trait]
+impl KVStoreDatabase for CachedSupabaseWalletDatabase {
+    type Err = DatabaseError;
+
+    async fn kv_read(
+        &self,
+        primary_namespace: &str,
+        secondary_namespace: &str,
+        key: &str,
+    ) -> Result<Option<Vec<u8>>, Self::Err> {
+        self.inner
+            .local
+            .kv_read(primary_namespace, secondary_namespace, key)
+            .await
+    }
+
+    async fn kv_list(
+        &self,
+        primary_namespace: &str,
+        secondary_namespace: &str,
+    ) -> Result<Vec<String>, Self::Err> {
+        self.inner
+            .local
+            .kv_list(primary_namespace, secondary_namespace)
+            .await
+    }
+}
+
+/// Apply a write to the cache and queue the same write for Supabase in one local transaction
+macro_rules! write_through {
+    ($self:ident, |$tx:ident| $local:expr, $write:expr) => {{
+        let _write = $self.inner.write_lock.lock().await;
+        let mut $tx = $self.inner.local.begin_db_transaction().await?;
+
+        let result = async {
+            let local = $local;
+            let write = $write;
+            let (seq, value) = $self.outbox_entry(&write).await?;
+            let result = local.await?;
+            $tx.kv_write(
+                OUTBOX_PRIMARY_NAMESPACE,
+                OUTBOX_SECONDARY_NAMESPACE,
+                &outbox_key(seq),
+                &value,
+            )
+            .await?;
+            Ok::<_, DatabaseError>((result, seq, write))
+        }
+        .await;
+
+        match result {
+            Ok((result, seq, write)) => {
+                $tx.commit().await?;
+                $self.queued(seq, write).await;
+                Ok(result)
+            }
+            Err(err) => {
+                if let Err(rollback_err) = $tx.rollback().await {
+                    tracing::warn!("Failed to roll back cache write: {}", rollback_err);
+                }
+                Err(err)
+            }
+        }
+    }};
+}
+
+/// Queue a write for Supabase, then apply it to the cache
+///
+/// For writes the local transaction cannot include. The entry is dropped again if the local write
+/// fails.
+macro_rules! write_ahead {
+    ($self:ident, $local:expr, $write:expr) => {{
+        let local = $local;
+        let write = $write;
+        let _write = $self.inner.write_lock.lock().await;
+        let seq = $self.persist(&write).await?;
+
+        match local.await {
+            Ok(result) => {
+                $self.queued(seq, write).await;
+                Ok(result)
+            }
+            Err(err) => {
+                $self.discard(seq).await?;
+                Err(err)
+            }
+        }
+    }};
+}
+
+#[async_trait]
+impl Database<DatabaseError> for CachedSupabaseWalletDatabase {
+    /// Buffered so each write still reaches the cache and the outbox together
+    async fn begin_db_transaction(
+        &self,
+    ) -> Result<Box<dyn DatabaseTransaction<DatabaseError> + Send + Sync>, DatabaseError> {
+        Ok(Box::new(BufferedTransaction::new(Arc::new(self.clone()))))
+    }
+
+    async fn get_mint(&self, mint_url: MintUrl) -> Result<Option<MintInfo>, DatabaseError> {
+        self.inner.local.get_mint(mint_url).await
+    }
+
+    async fn get_mints(&self) -> Result<HashMap<MintUrl, Option<MintInfo>>, DatabaseError> {
+        self.inner.local.get_mints().await
+    }
+
+    async fn get_mint_keysets(
+        &self,
+        mint_url: MintUrl,
+    ) -> Result<Option<Vec<KeySetInfo>>, DatabaseError> {
+        self.inner.local.get_mint_keysets(mint_url).await
+    }
+
+    async fn get_keyset_by_id(&self, keyset_id: &Id) -> Result<Option<KeySetInfo>, DatabaseError> {
+        self.inner.local.get_keyset_by_id(keyset_id).await
+    }
+
+    async fn get_mint_quote(
+        &self,
+        quote_id: &str,
+    ) -> Result<Option<WalletMintQuote>, DatabaseError> {
+        self.inner.local.get_mint_quote(quote_id).await
+    }
+
+    async fn get_mint_quotes(&self) -> Result<Vec<WalletMintQuote>, DatabaseError> {
+        self.inner.local.get_mint_quotes().await
+    }
+
+    async fn get_unissued_mint_quotes(&self) -> Result<Vec<WalletMintQuote>, DatabaseError> {
+        self.inner.local.get_unissued_mint_quotes().await
+    }
+
+    async fn get_melt_quote(
+        &self,
+        quote_id: &str,
+    ) -> Result<Option<wallet::MeltQuote>, DatabaseError> {
+        self.inner.local.get_melt_quote(quote_id).await
+    }
+
+    async fn get_melt_quotes(&self) -> Result<Vec<wallet::MeltQuote>, DatabaseError> {
+        self.inner.local.get_melt_quotes().await
+    }
+
+    async fn get_keys(&self, id: &Id) -> Result<Option<Keys>, DatabaseError> {
+        self.inner.local.get_keys(id).await
+    }
+
+    async fn get_proofs(
+        &self,
+        mint_url: Option<MintUrl>,
+        unit: Option<CurrencyUnit>,
+        state: Option<Vec<State>>,
+        spending_conditions: Option<Vec<SpendingConditions>>,
+    ) -> Result<Vec<ProofInfo>, DatabaseError> {
+        self.inner
+            .local
+            .get_proofs(mint_url, unit, state, spending_conditions)
+            .await
+    }
+
+    async fn get_proofs_by_ys(&self, ys: Vec<PublicKey>) -> Result<Vec<ProofInfo>, DatabaseError> {
+        self.inner.local.get_proofs_by_ys(ys).await
+    }
+
+    async fn get_balance(
+        &self,
+        mint_url: Option<MintUrl>,
+        unit: Option<CurrencyUnit>,
+        state: Option<Vec<State>>,
+    ) -> Result<u64, DatabaseError> {
+        self.inner.local.get_balance(mint_url, unit, state).await
+    }
+
+    async fn get_transaction(
+        &self,
+        transaction_id: TransactionId,
+    ) -> Result<Option<Transaction>, DatabaseError> {
+        self.inner.local.get_transaction(transaction_id).await
+    }
+
+    async fn list_transactions(
+        &self,
+        mint_url: Option<MintUrl>,
+        direction: Option<TransactionDirection>,
+        unit: Option<CurrencyUnit>,
+    ) -> Result<Vec<Transaction>, DatabaseError> {
+        self.inner
+            .local
+            .list_transactions(mint_url, direction, unit)
+            .await
+    }
+
+    async fn update_proofs(
+        &self,
+        added: Vec<ProofInfo>,
+        removed_ys: Vec<PublicKey>,
+    ) -> Result<(), DatabaseError> {
+        write_through!(
+            self,
+            |tx| tx.update_proofs(added.clone(), removed_ys.clone()),
+            PendingWrite::UpdateProofs { added, removed_ys }
+        )
+    }
+
+    async fn update_proofs_state(
+        &self,
+        ys: Vec<PublicKey>,
+        state: State,
+    ) -> Result<(), DatabaseError> {
+        write_through!(
+            self,
+            |tx| tx.update_proofs_state(ys.clone(), state),
+            PendingWrite::UpdateProofsState { ys, state }
+        )
+    }
+
+    async fn add_transaction(&self, transaction: Transaction) -> Result<(), DatabaseError> {
+        write_through!(
+            self,
+            |tx| tx.add_transaction(transaction.clone()),
+            PendingWrite::AddTransaction { transaction }
+        )
+    }
+
+    async fn update_mint_url(
+        &self,
+        old_mint_url: MintUrl,
+        new_mint_url: MintUrl,
+    ) -> Result<(), DatabaseError> {
+        write_ahead!(
+            self,
+            self.inner
+                .local
+                .update_mint_url(old_mint_url.clone(), new_mint_url.clone()),
+            PendingWrite::UpdateMintUrl {
+                old_mint_url,
+                new_mint_url
+            }
+        )
+    }
+
+    async fn increment_keyset_counter(
+        &self,
+        keyset_id: &Id,
+        count: u32,
+    ) -> Result<u32, DatabaseError> {
+        // Counters are allocated on the server so two devices never derive the same secrets
+        let counter = self
+            .remote_first(self.inner.remote.increment_keyset_counter(keyset_id, count))
+            .await?;
+
+        let _write = self.inner.write_lock.lock().await;
+        self.advance_local_counter(keyset_id, counter).await?;
+        Ok(counter)
+    }
+
+    async fn add_mint(
+        &self,
+        mint_url: MintUrl,
+        mint_info: Option<MintInfo>,
+    ) -> Result<(), DatabaseError> {
+        write_ahead!(
+            self,
+            self.inner
+                .local
+                .add_mint(mint_url.clone(), mint_info.clone()),
+            PendingWrite::AddMint {
+                mint_url,
+                mint_info
+            }
+        )
+    }
+
+    async fn remove_mint(&self, mint_url: MintUrl) -> Result<(), DatabaseError> {
+        write_ahead!(
+            self,
+            self.inner.local.remove_mint(mint_url.clone()),
+            PendingWrite::RemoveMint { mint_url }
+        )
+    }
+
+    async fn add_mint_keysets(
+        &self,
+        mint_url: MintUrl,
+        keysets: Vec<KeySetInfo>,
+    ) -> Result<(), DatabaseError> {
+        write_ahead!(
+            self,
+            self.inner
+                .local
+                .add_mint_keysets(mint_url.clone(), keysets.clone()),
+            PendingWrite::AddMintKeysets { mint_url, keysets }
+        )
+    }
+
+    async fn add_mint_quote(&self, quote: WalletMintQuote) -> Result<(), DatabaseError> {
+        write_through!(
+            self,
+            |tx| tx.add_mint_quote(quote.clone()),
+            PendingWrite::AddMintQuote { quote }
+        )
+    }
+
+    async fn remove_mint_quote(&self, quote_id: &str) -> Result<(), DatabaseError> {
+        write_ahead!(
+            self,
+            self.inner.local.remove_mint_quote(quote_id),
+            PendingWrite::RemoveMintQuote {
+                quote_id: quote_id.to_string()
+            }
+        )
+    }
+
+    async fn add_melt_quote(&self, quote: wallet::MeltQuote) -> Result<(), DatabaseError> {
+        write_through!(
+            self,
+            |tx| tx.add_melt_quote(quote.clone()),
+            PendingWrite::AddMeltQuote { quote }
+        )
+    }
+
+    async fn remove_melt_quote(&self, quote_id: &str) -> Result<(), DatabaseError> {
+        write_ahead!(
+            self,
+            self.inner.local.remove_melt_quote(quote_id),
+            PendingWrite::RemoveMeltQuote {
+                quote_id: quote_id.to_string()
+            }
+        )
+    }
+
+    async fn add_keys(&self, keyset: KeySet) -> Result<(), DatabaseError> {
+        write_ahead!(
+            self,
+            self.inner.local.add_keys(keyset.clone()),
+            PendingWrite::AddKeys { keyset }
+        )
+    }
+
+    async fn remove_keys(&self, id: &Id) -> Result<(), DatabaseError> {
+        write_ahead!(
+            self,
+            self.inner.local.remove_keys(id),
+            PendingWrite::RemoveKeys { id: *id }
+        )
+    }
+
+    async fn remove_transaction(&self, transaction_id: TransactionId) -> Result<(), DatabaseError> {
+        write_ahead!(
+            self,
+            self.inner.local.remove_transaction(transaction_id),
+            PendingWrite::RemoveTransaction { transaction_id }
+        )
+    }
+
+    async fn add_saga(&self, saga: wallet::WalletSaga) -> Result<(), DatabaseError> {
+        write_ahead!(
+            self,
+            self.inner.local.add_saga(saga.clone()),
+            PendingWrite::AddSaga { saga }
+        )
+    }
+
+    async fn get_saga(&self, id: &uuid::Uuid) -> Result<Option<wallet::WalletSaga>, DatabaseError> {
+        self.inner.local.get_saga(id).await
+    }
+
+    async fn update_saga(&self, saga: wallet::WalletSaga) -> Result<bool, DatabaseError> {
+        let write = PendingWrite::UpdateSaga { saga: saga.clone() };
+        let _write = self.inner.write_lock.lock().await;
+        let seq = self.persist(&write).await?;
+
+        match self.inner.local.update_saga(saga).await {
+            Ok(true) => {
+                self.queued(seq, write).await;
+                Ok(true)
+            }
+            result => {
+                self.discard(seq).await?;
+                result
+            }
+        }
+    }
+
+    async fn delete_saga(&self, id: &uuid::Uuid) -> Result<(), DatabaseError> {
+        write_ahead!(
+            self,
+            self.inner.local.delete_saga(id),
+            PendingWrite::DeleteSaga { id: *id }
+        )
+    }
+
+    async fn get_incomplete_sagas(&self) -> Result<Vec<wallet::WalletSaga>, DatabaseError> {
+        self.inner.local.get_incomplete_sagas().await
+    }
+
+    async fn reserve_proofs(
+        &self,
+        ys: Vec<PublicKey>,
+        operation_id: &uuid::Uuid,
+    ) -> Result<(), DatabaseError> {
+        // Reserved on the server first so another device cannot spend the same proofs
+        self.remote_first(self.inner.remote.reserve_proofs(ys.clone(), operation_id))
+            .await?;
+
+        let _write = self.inner.write_lock.lock().await;
+        if let Err(err) = self.inner.local.reserve_proofs(ys, operation_id).await {
+            if let Err(release_err) = self.inner.remote.release_proofs(operation_id).await {
+                tracing::warn!(
+                    "Could not release proofs reserved by {}: {}",
+                    operation_id,
+                    release_err
+                );
+            }
+            return Err(err);
+        }
+        Ok(())
+    }
+
+    async fn release_proofs(&self, operation_id: &uuid::Uuid) -> Result<(), DatabaseError> {
+        write_ahead!(
+            self,
+            self.inner.local.release_proofs(operation_id),
+            PendingWrite::ReleaseProofs {
+                operation_id: *operation_id
+            }
+        )
+    }
+
+    async fn get_reserved_proofs(
+        &self,
+        operation_id: &uuid::Uuid,
+    ) -> Result<Vec<ProofInfo>, DatabaseError> {
+        self.inner.local.get_reserved_proofs(operation_id).await
+    }
+
+    async fn reserve_melt_quote(
+        &self,
+        quote_id: &str,
+        operation_id: &uuid::Uuid,
+    ) -> Result<(), DatabaseError> {
+        self.remote_first(self.inner.remote.reserve_melt_quote(quote_id, operation_id))
+            .await?;
+
+        let _write = self.inner.write_lock.lock().await;
+        if let Err(err) = self
+            .inner
+            .local
+            .reserve_melt_quote(quote_id, operation_id)
+            .await
+        {
+            if let Err(release_err) = self.inner.remote.release_melt_quote(operation_id).await {
+                tracing::warn!(
+                    "Could not release melt quote reserved by {}: {}",
+                    operation_id,
+                    release_err
+                );
+            }
+            return Err(err);
+        }
+        Ok(())
+    }
+
+    async fn release_melt_quote(&self, operation_id: &uuid::Uuid) -> Result<(), DatabaseError> {
+        write_ahead!(
+            self,
+            self.inner.local.release_melt_quote(operation_id),
+            PendingWrite::ReleaseMeltQuote {
+                operation_id: *operation_id
+            }
+        )
+    }
+
+    async fn reserve_mint_quote(
+        &self,
+        quote_id: &str,
+        operation_id: &uuid::Uuid,
+    ) -> Result<(), DatabaseError> {
+        self.remote_first(self.inner.remote.reserve_mint_quote(quote_id, operation_id))
+            .await?;
+
+        let _write = self.inner.write_lock.lock().await;
+        if let Err(err) = self
+            .inner
+            .local
+            .reserve_mint_quote(quote_id, operation_id)
+            .await
+        {
+            if let Err(release_err) = self.inner.remote.release_mint_quote(operation_id).await {
+                tracing::warn!(
+                    "Could not release mint quote reserved by {}: {}",
+                    operation_id,
+                    release_err
+                );
+            }
+            return Err(err);
+        }
+        Ok(())
+    }
+
+    async fn release_mint_quote(&self, operation_id: &uuid::Uuid) -> Result<(), DatabaseError> {
+        write_ahead!(
+            self,
+            self.inner.local.release_mint_quote(operation_id),
+            PendingWrite::ReleaseMintQuote {
+                operation_id: *operation_id
+            }
+        )
+    }
+
+    async fn kv_read(
+        &self,
+        primary_namespace: &str,
+        secondary_namespace: &str,
+        key: &str,
+    ) -> Result<Option<Vec<u8>>, DatabaseError> {
+        self.inner
+            .local
+            .kv_read(primary_namespace, secondary_namespace, key)
+            .await
+    }
+
+    async fn kv_list(
+        &self,
+        primary_namespace: &str,
+        secondary_namespace: &str,
+    ) -> Result<Vec<String>, DatabaseError> {
+        self.inner
+            .local
+            .kv_list(primary_namespace, secondary_namespace)
+            .await
+    }
+
+    async fn kv_write(
+        &self,
+        primary_namespace: &str,
+        secondary_namespace: &str,
+        key: &str,
+        value: &[u8],
+    ) -> Result<(), DatabaseError> {
+        write_through!(
+            self,
+            |tx| tx.kv_write(primary_namespace, secondary_namespace, key, value),
+            PendingWrite::KvWrite {
+                primary_namespace: primary_namespace.to_string(),
+                secondary_namespace: secondary_namespace.to_string(),
+                key: key.to_string(),
+                value: value.to_vec(),
+            }
+        )
+    }
+
+    async fn kv_remove(
+        &self,
+        primary_namespace: &str,
+        secondary_namespace: &str,
+        key: &str,
+    ) -> Result<(), DatabaseError> {
+        write_through!(
+            self,
+            |tx| tx.kv_remove(primary_namespace, secondary_namespace, key),
+            PendingWrite::KvRemove {
+                primary_namespace: primary_namespace.to_string(),
+                secondary_namespace: secondary_namespace.to_string(),
+                key: key.to_string(),
+            }
+        )
+    }
+
+    async fn add_p2pk_key(
+        &self,
+        pubkey: &PublicKey,
+        derivation_path: DerivationPath,
+        derivation_index: u32,
+    ) -> Result<(), DatabaseError> {
+        write_ahead!(
+            self,
+            self.inner
+                .local
+                .add_p2pk_key(pubkey, derivation_path.clone(), derivation_index),
+            PendingWrite::AddP2pkKey {
+                pubkey: *pubkey,
+                derivation_path,
+                derivation_index
+            }
+        )
+    }
+
+    async fn get_p2pk_key(
+        &self,
+        pubkey: &PublicKey,
+    ) -> Result<Option<wallet::P2PKSigningKey>, DatabaseError> {
+        self.inner.local.get_p2pk_key(pubkey).await
+    }
+
+    async fn list_p2pk_keys(&self) -> Result<Vec<wallet::P2PKSigningKey>, DatabaseError> {
+        self.inner.local.list_p2pk_keys().await
+    }
+
+    async fn latest_p2pk(&self) -> Result<Option<wallet::P2PKSigningKey>, DatabaseError> {
+        self.inner.local.latest_p2pk().await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use cdk_common::{Amount, PaymentMethod};
+    use mockito::{Matcher, Mock, ServerGuard};
+    use serde_json::json;
+    use url::Url;
+
+    use super::*;
+
+    async fn open_cache(
+        server: &ServerGuard,
+        local: WalletSqliteDatabase,
+    ) -> CachedSupabaseWalletDatabase {
+        let remote = SupabaseWalletDatabase::new(
+            Url::parse(&server.url()).expect("mock server URL should parse"),
+            "anon-key".to_string(),
+        )
+        .await
+        .expect("database should initialize");
+
+        CachedSupabaseWalletDatabase::new(local, remote)
+            .await
+            .expect("cache should open")
+    }
+
+    fn test_quote() -> WalletMintQuote {
+        WalletMintQuote::new(
+            "quote-1".to_string(),
+            MintUrl::from_str("https://mint.example.com").expect("valid mint url"),
+            PaymentMethod::from_str("bolt11").expect("valid payment method"),
+            Some(Amount::from(100)),
+            CurrencyUnit::Sat,
+            "lnbc1".to_string(),
+            u64::MAX / 2,
+            None,
+        )
+    }
+
+    /// When the rows served by the mock server last changed
+    const CHANGED_AT: i64 = 5_000_000_000;
+
+    /// The quote as PostgREST returns it
+    fn quote_row(quote: &WalletMintQuote) -> serde_json::Value {
+        json!({
+            "id": quote.id,
+            "mint_url": quote.mint_url.to_string(),
+            "amount": quote.amount.map(|a| a.to_u64()).unwrap_or(0),
+            "unit": quote.unit.to_string(),
+            "request": quote.request,
+            "state": quote.state.to_string(),
+            "expiry": quote.expiry,
+            "secret_key": null,
+            "payment_method": quote.payment_method.to_string(),
+            "amount_issued": quote.amount_issued.to_u64(),
+            "amount_paid": quote.amount_paid.to_u64(),
+            "updated_at": quote.updated_at,
+            "used_by_operation": null,
+            "version": quote.version,
+            "changed_at": CHANGED_AT,
+        })
+    }
+
+    /// Serve `quotes` as the only remote state
+    async fn mock_remote_state(server: &mut ServerGuard, quotes: &[WalletMintQuote]) -> Vec<Mock> {
+        let mut mocks = Vec::new();
+        for path in [
+            "/rest/v1/mint",
+            "/rest/v1/proof",
+            "/rest/v1/melt_quote",
+            "/rest/v1/transactions",
+        ] {
+            mocks.push(
+                server
+                    .mock("GET", path)
+                    .match_query(Matcher::Any)
+                    .with_status(200)
+                    .with_body("[]")
+                    .create_async()
+                    .await,
+            );
+        }
+        let rows = quotes.iter().map(quote_row).collect::<Vec<_>>();
+        mocks.push(
+            server
+                .mock("GET", "/rest/v1/mint_quote")
+                .match_query(Matcher::Any)
+                .with_status(200)
+                .with_body(serde_json::Value::Array(rows).to_string())
+                .create_async()
+                .await,
+        );
+        mocks
+    }
+
+    #[tokio::test]
+    async fn queued_writes_are_replayed_after_a_restart() {
+        let mut server = mockito::Server::new_async().await;
+        let local = cdk_sqlite::wallet::memory::empty()
+            .await
+            .expect("local database should open");
+        let quote = test_quote();
+
+        let db = open_cache(&server, local.clone()).await;
+        db.add_mint_quote(quote.clone())
+            .await
+            .expect("write should not wait on Supabase");
+        assert_eq!(db.pending_writes().await, 1);
+        // Stopped before the write was pushed
+        drop(db);
+
+        let reopened = open_cache(&server, local.clone()).await;
+        assert_eq!(reopened.pending_writes().await, 1);
+
+        let _patch = server
+            .mock("PATCH", "/rest/v1/mint_quote")
+            .match_query(Matcher::Any)
+            .with_status(200)
+            .with_body("[]")
+            .create_async()
+            .await;
+        let insert = server
+            .mock("POST", "/rest/v1/mint_quote")
+            .match_body(Matcher::PartialJson(
+                json!({ "id": quote.id, "version": 0 }),
+            ))
+            .with_status(201)
+            .expect(1)
+            .create_async()
+            .await;
+        let _state = mock_remote_state(&mut server, &[quote.clone()]).await;
+
+        reopened.sync().await.expect("replay should succeed");
+        assert_eq!(reopened.pending_writes().await, 0);
+        insert.assert_async().await;
+
+        // Pulling keeps the server's version instead of bumping the cached one past it
+        reopened.sync().await.expect("second sync should succeed");
+        let cached = local
+            .get_mint_quote(&quote.id)
+            .await
+            .expect("quote should load")
+            .expect("quote should be cached");
+        assert_eq!(cached.version, 0);
+    }
+
+    #[tokio::test]
+    async fn failed_local_write_is_not_queued() {
+        let server = mockito::Server::new_async().await;
+        let local = cdk_sqlite::wallet::memory::empty()
+            .await
+            .expect("local database should open");
+        let db = open_cache(&server, local.clone()).await;
+
+        let quote = test_quote();
+        db.add_mint_quote(quote.clone())
+            .await
+            .expect("first write should succeed");
+
+        let mut stale = quote;
+        stale.version = 5;
+        assert!(matches!(
+            db.add_mint_quote(stale).await,
+            Err(DatabaseError::ConcurrentUpdate)
+        ));
+
+        assert_eq!(db.pending_writes().await, 1);
+        assert_eq!(
+            local
+                .kv_list(OUTBOX_PRIMARY_NAMESPACE, OUTBOX_SECONDARY_NAMESPACE)
+                .await
+                .expect("outbox should list")
+                .len(),
+            1
+        );
+    }
+
+    #[tokio::test]
+    async fn conflicting_quote_update_keeps_remote_version() {
+        let mut server = mockito::Server::new_async().await;
+        let local = cdk_sqlite::wallet::memory::empty()
+            .await
+            .expect("local database should open");
+        let db = open_cache(&server, local.clone()).await;
+
+        let quote = test_quote();
+        db.add_mint_quote(quote.clone())
+            .await
+            .expect("write should succeed");
+
+        // Another device already moved the quote on to version 3
+        let mut remote_quote = quote.clone();
+        remote_quote.amount_paid = Amount::from(100);
+        remote_quote.version = 3;
+
+        let _patch = server
+            .mock("PATCH", "/rest/v1/mint_quote")
+            .match_query(Matcher::Any)
+            .with_status(200)
+            .with_body("[]")
+            .create_async()
+            .await;
+        let _insert = server
+            .mock("POST", "/rest/v1/mint_quote")
+            .with_status(409)
+            .create_async()
+            .await;
+        let _state = mock_remote_state(&mut server, &[remote_quote]).await;
+
+        db.sync().await.expect("conflict should resolve");
+        assert_eq!(db.pending_writes().await, 0);
+        db.sync().await.expect("later syncs should not conflict");
+
+        let cached = db
+            .get_mint_quote(&quote.id)
+            .await
+            .expect("quote should load")
+            .expect("quote should be cached");
+        assert_eq!(cached.version, 3);
+        assert_eq!(cached.amount_paid, Amount::from(100));
+    }
+
+    #[tokio::test]
+    async fn rejected_write_is_set_aside_and_later_writes_are_pushed() {
+        let mut server = mockito::Server::new_async().await;
+        let local = cdk_sqlite::wallet::memory::empty()
+            .await
+            .expect("local database should open");
+        let db = open_cache(&server, local).await;
+
+        db.remove_mint_quote("rejected-quote")
+            .await
+            .expect("write should not wait on Supabase");
+        db.remove_melt_quote("accepted-quote")
+            .await
+            .expect("write should not wait on Supabase");
+
+        let _rejected = server
+            .mock("DELETE", "/rest/v1/mint_quote")
+            .match_query(Matcher::Any)
+            .with_status(403)
+            .with_body(r#"{"message":"permission denied"}"#)
+            .create_async()
+            .await;
+        let accepted = server
+            .mock("DELETE", "/rest/v1/melt_quote")
+            .match_query(Matcher::UrlEncoded(
+                "id".to_string(),
+                "eq.accepted-quote".to_string(),
+            ))
+            .with_status(204)
+            .expect(1)
+            .create_async()
+            .await;
+        let _state = mock_remote_state(&mut server, &[]).await;
+
+        db.sync()
+            .await
+            .expect("sync should go on past the rejected write");
+        assert_eq!(db.pending_writes().await, 0);
+        accepted.assert_async().await;
+
+        let failed = db.failed_writes().await.expect("failed writes should load");
+        assert_eq!(failed.len(), 1);
+        assert_eq!(failed[0].seq, 0);
+        assert_eq!(failed[0].write["quote_id"], "rejected-quote");
+        assert!(failed[0].error.contains("403"));
+
+        db.remove_failed_write(failed[0].seq)
+            .await
+            .expect("failed write should be removed");
+        assert!(db
+            .failed_writes()
+            .await
+            .expect("failed writes should load")
+            .is_empty());
+    }
+
+    #[tokio::test]
+    async fn pull_fetches_only_rows_changed_since_the_last_pull() {
+        let mut server = mockito::Server::new_async().await;
+        let local = cdk_sqlite::wallet::memory::empty()
+            .await
+            .expect("local database should open");
+        let db = open_cache(&server, local).await;
+
+        let quote = test_quote();
+
+        // Matched first while it has not been hit, so only by the second pull
+        let incremental = server
+            .mock("GET", "/rest/v1/mint_quote")
+            .match_query(Matcher::AllOf(vec![
+                Matcher::UrlEncoded("select".to_string(), "*".to_string()),
+                Matcher::UrlEncoded(
+                    "changed_at".to_string(),
+                    format!("gte.{}", CHANGED_AT - PULL_OVERLAP_MICROS),
+                ),
+            ]))
+            .with_status(200)
+            .with_body(json!([quote_row(&quote)]).to_string())
+            .expect(1)
+            .create_async()
+            .await;
+        let _state = mock_remote_state(&mut server, &[quote.clone()]).await;
+
+        db.sync().await.expect("first sync should pull every row");
+        assert!(db
+            .get_mint_quote(&quote.id)
+            .await
+            .expect("quote should load")
+            .is_some());
+
+        db.sync()
+            .await
+            .expect("second sync should pull changed rows");
+        incremental.assert_async().await;
+        assert!(db
+            .get_mint_quote(&quote.id)
+            .await
+            .expect("quote should load")
+            .is_some());
+    }
+
+    #[tokio::test]
+    async fn keyset_counters_are_allocated_on_the_server() {
+        let mut server = mockito::Server::new_async().await;
+        let local = cdk_sqlite::wallet::memory::empty()
+            .await
+            .expect("local database should open");
+        let db = open_cache(&server, local.clone()).await;
+        let keyset_id = Id::from_str("00deadbeef123456").expect("valid keyset id");
+
+        // Offline, a counter cannot be handed out
+        assert!(db.increment_keyset_counter(&keyset_id, 5).await.is_err());
+        assert_eq!(
+            local
+                .increment_keyset_counter(&keyset_id, 0)
+                .await
+                .expect("counter should load"),
+            0
+        );
+
+        // Another device already used counters up to 20
+        let _rpc = server
+            .mock("POST", "/rest/v1/rpc/increment_keyset_counter")
+            .match_body(Matcher::PartialJson(json!({ "p_increment": 5 })))
+            .with_status(200)
+            .with_body("25")
+            .create_async()
+            .await;
+
+        assert_eq!(
+            db.increment_keyset_counter(&keyset_id, 5)
+                .await
+                .expect("counter should be allocated"),
+            25
+        );
+        assert_eq!(
+            local
+                .increment_keyset_counter(&keyset_id, 0)
+                .await
+                .expect("counter should load"),
+            25
+        );
+        assert_eq!(db.pending_writes().await, 0);
+    }
+}
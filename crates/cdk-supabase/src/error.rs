@@ -1,4 +1,5 @@
 use cdk_common::database::Error as DatabaseError;
+use reqwest::StatusCode;
 use thiserror::Error;
 
 /// Errors that can occur when interacting with Supabase
@@ -19,6 +20,16 @@ pub enum Error {
     /// Supabase-specific error
     #[error("Supabase error: {0}")]
     Supabase(String),
+    /// Supabase answered a request with an error status
+    #[error("{operation} failed: HTTP {status} - {body}")]
+    Http {
+        /// The operation the request was made for
+        operation: String,
+        /// Status of the response
+        status: StatusCode,
+        /// Body of the response
+        body: String,
+    },
     /// Schema version mismatch — the database schema is outdated
     #[error(
         "Schema version mismatch: SDK requires version {required}, \
@@ -40,6 +51,34 @@ pub enum Error {
     SchemaNotInitialized,
 }
 
+impl Error {
+    pub(crate) fn http(operation: &str, status: StatusCode, body: &str) -> Self {
+        Self::Http {
+            operation: operation.to_string(),
+            status,
+            body: body.to_string(),
+        }
+    }
+
+    /// Whether Supabase answered and rejected the request, so repeating it cannot succeed
+    ///
+    /// Expired sessions, timeouts, rate limiting and server errors are not rejections.
+    pub fn is_rejection(&self) -> bool {
+        match self {
+            Self::Http { status, .. } => {
+                status.is_client_error()
+                    && !matches!(
+                        *status,
+                        StatusCode::UNAUTHORIZED
+                            | StatusCode::REQUEST_TIMEOUT
+                            | StatusCode::TOO_MANY_REQUESTS
+                    )
+            }
+            _ => false,
+        }
+    }
+}
+
 impl From<Error> for DatabaseError {
     fn from(e: Error) -> Self {
         match e {
@@ -48,6 +87,7 @@ impl From<Error> for DatabaseError {
             Error::Url(e) => DatabaseError::Database(Box::new(e)),
             Error::Serde(e) => DatabaseError::Database(Box::new(e)),
             Error::Supabase(msg) => DatabaseError::Database(Box::new(std::io::Error::other(msg))),
+            e @ Error::Http { .. } => DatabaseError::Database(Box::new(e)),
             Error::SchemaMismatch { required, found } => {
                 DatabaseError::Database(Box::new(std::io::Error::other(format!(
                     "Schema version mismatch: SDK requires version {required}, \
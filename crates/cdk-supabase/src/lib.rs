@@ -2,11 +2,16 @@
 //!
 //! This crate provides Supabase-based database implementations for the CDK wallet.
 
+#[cfg(feature = "cache")]
+/// Local SQLite cache in front of the Supabase wallet database
+pub mod cache;
 mod error;
 #[cfg(feature = "wallet")]
 /// Wallet database implementation for Supabase
 pub mod wallet;
 
+#[cfg(feature = "cache")]
+pub use cache::{CachedSupabaseWalletDatabase, FailedWrite};
 pub use error::Error;
 #[cfg(feature = "wallet")]
 pub use wallet::{SupabaseAuth, SupabaseAuthResponse, SupabaseWalletDatabase};
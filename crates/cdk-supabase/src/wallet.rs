@@ -2,7 +2,7 @@ use std::collections::HashMap;
 use std::fmt::Debug;
 use std::str::FromStr;
 use std::sync::Arc;
-use std::time::{SystemTime, UNIX_EPOCH};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
 use aes_gcm::aead::{Aead, AeadCore, KeyInit};
 use aes_gcm::{Aes256Gcm, Key, Nonce};
@@ -12,7 +12,7 @@ use bitcoin::secp256k1::rand::rngs::OsRng;
 use bitcoin::secp256k1::rand::RngCore;
 use cdk_common::auth::oidc::OidcClient;
 use cdk_common::common::ProofInfo;
-use cdk_common::database::wallet::Database;
+use cdk_common::database::wallet::{BufferedTransaction, Database, DatabaseTransaction};
 use cdk_common::database::{Error as DatabaseError, KVStoreDatabase};
 use cdk_common::mint_url::MintUrl;
 use cdk_common::nuts::{
@@ -61,6 +61,24 @@ const SCRYPT_LOG_N: u8 = 15;
 const SCRYPT_R: u32 = 8;
 const SCRYPT_P: u32 = 1;
 
+/// Retries of a request that failed with a server or connection error
+const MAX_RETRIES: u32 = 3;
+/// Delay before the first retry, doubled on every further retry
+const RETRY_BASE_DELAY: Duration = Duration::from_millis(200);
+/// Maximum number of Y values in a single `in.(...)` filter, keeping URLs short
+const YS_FILTER_CHUNK: usize = 100;
+
+/// PostgREST `in.(...)` filter value for a set of Y values
+fn ys_filter(ys: &[PublicKey]) -> String {
+    let ys_str: Vec<String> = ys.iter().map(|y| hex::encode(y.to_bytes())).collect();
+    format!("({})", ys_str.join(","))
+}
+
+/// Delay before retry number `attempt`, counted from zero
+fn retry_delay(attempt: u32) -> Duration {
+    RETRY_BASE_DELAY.saturating_mul(2u32.saturating_pow(attempt))
+}
+
 /// Decode JWT expiration from token string (without verification)
 fn decode_jwt_expiry(token: &str) -> Option<u64> {
     let parts: Vec<&str> = token.split('.').collect();
@@ -213,7 +231,7 @@ impl SupabaseWalletDatabase {
     /// This must match the latest `schema_version` value set in the migration files.
     /// When adding new migrations, update this constant and set the same value
     /// in the new migration's `INSERT INTO schema_info` statement.
-    pub const REQUIRED_SCHEMA_VERSION: u32 = 8;
+    pub const REQUIRED_SCHEMA_VERSION: u32 = 10;
 
     /// Get the full database schema SQL
     ///
@@ -349,10 +367,11 @@ impl SupabaseWalletDatabase {
                     "wallet encryption metadata conflicted but could not be loaded".to_string(),
                 )
             }),
-            _ => Err(Error::Supabase(format!(
-                "wallet encryption metadata insert failed: HTTP {} - {}",
-                status, text
-            ))),
+            _ => Err(Error::http(
+                "wallet encryption metadata insert",
+                status,
+                &text,
+            )),
         }
     }
 
@@ -361,10 +380,11 @@ impl SupabaseWalletDatabase {
         let (status, text) = self.get_request(path).await?;
 
         if !status.is_success() && status != StatusCode::NO_CONTENT {
-            return Err(Error::Supabase(format!(
-                "wallet encryption metadata lookup failed: HTTP {} - {}",
-                status, text
-            )));
+            return Err(Error::http(
+                "wallet encryption metadata lookup",
+                status,
+                &text,
+            ));
         }
 
         Ok(Self::parse_response::<EncryptionMetadataTable>(&text)?
@@ -496,10 +516,7 @@ impl SupabaseWalletDatabase {
                 let status = response.status();
                 if !status.is_success() {
                     let text = response.text().await.unwrap_or_default();
-                    return Err(Error::Supabase(format!(
-                        "Supabase token refresh failed: HTTP {} - {}",
-                        status, text
-                    )));
+                    return Err(Error::http("Supabase token refresh", status, &text));
                 }
 
                 let token_response: SupabaseTokenResponse =
@@ -624,10 +641,11 @@ impl SupabaseWalletDatabase {
         let text = res.text().await.map_err(Error::Reqwest)?;
 
         if !status.is_success() {
-            return Err(Error::Supabase(format!(
-                "RPC '{}' failed: HTTP {} - {}",
-                function_name, status, text
-            )));
+            return Err(Error::http(
+                &format!("RPC '{function_name}'"),
+                status,
+                &text,
+            ));
         }
 
         Ok(text)
@@ -666,6 +684,39 @@ impl SupabaseWalletDatabase {
             .map_err(|e| DatabaseError::Internal(e.to_string()))
     }
 
+    /// Send a request built by `build`, retrying server and connection errors with exponential
+    /// backoff
+    ///
+    /// Only used for idempotent requests, as a failed attempt may still have been applied.
+    async fn send_with_retry<F>(&self, build: F) -> Result<(StatusCode, String), Error>
+    where
+        F: Fn() -> reqwest::RequestBuilder,
+    {
+        let mut attempt = 0;
+
+        loop {
+            let result = build().send().await;
+            let retryable = match &result {
+                Ok(res) => res.status().is_server_error(),
+                Err(err) => err.is_connect() || err.is_timeout(),
+            };
+
+            if retryable && attempt < MAX_RETRIES {
+                let delay = retry_delay(attempt);
+                tracing::warn!(attempt, ?delay, "Supabase request failed, retrying");
+                tokio::time::sleep(delay).await;
+                attempt += 1;
+                continue;
+            }
+
+            let res = result.map_err(Error::Reqwest)?;
+            let status = res.status();
+            let text = res.text().await.map_err(Error::Reqwest)?;
+
+            return Ok((status, text));
+        }
+    }
+
     /// Make a GET request and return the response text
     async fn get_request(&self, path: &str) -> Result<(StatusCode, String), Error> {
         let url = self.join_url(path)?;
@@ -673,17 +724,14 @@ impl SupabaseWalletDatabase {
 
         tracing::debug!(method = "GET", url = %url, "Supabase request");
 
-        let res = self
-            .client
-            .get(url.clone())
-            .header("apikey", &self.api_key)
-            .header("Authorization", format!("Bearer {}", auth_bearer))
-            .send()
-            .await
-            .map_err(Error::Reqwest)?;
-
-        let status = res.status();
-        let text = res.text().await.map_err(Error::Reqwest)?;
+        let (status, text) = self
+            .send_with_retry(|| {
+                self.client
+                    .get(url.clone())
+                    .header("apikey", &self.api_key)
+                    .header("Authorization", format!("Bearer {}", auth_bearer))
+            })
+            .await?;
 
         tracing::debug!(method = "GET", url = %url, status = %status, response_len = text.len(), "Supabase response");
 
@@ -701,19 +749,17 @@ impl SupabaseWalletDatabase {
 
         tracing::debug!(method = "POST", url = %url, "Supabase request");
 
-        let res = self
-            .client
-            .post(url.clone())
-            .header("apikey", &self.api_key)
-            .header("Authorization", format!("Bearer {}", auth_bearer))
-            .header("Prefer", "resolution=merge-duplicates,missing=default")
-            .json(body)
-            .send()
-            .await
-            .map_err(Error::Reqwest)?;
-
-        let status = res.status();
-        let text = res.text().await.map_err(Error::Reqwest)?;
+        // Upserts are idempotent and can be retried
+        let (status, text) = self
+            .send_with_retry(|| {
+                self.client
+                    .post(url.clone())
+                    .header("apikey", &self.api_key)
+                    .header("Authorization", format!("Bearer {}", auth_bearer))
+                    .header("Prefer", "resolution=merge-duplicates,missing=default")
+                    .json(body)
+            })
+            .await?;
 
         tracing::debug!(method = "POST", url = %url, status = %status, response_len = text.len(), "Supabase response");
 
@@ -766,18 +812,15 @@ impl SupabaseWalletDatabase {
 
         tracing::debug!(method = "PATCH", url = %url, "Supabase request");
 
-        let res = self
-            .client
-            .patch(url.clone())
-            .header("apikey", &self.api_key)
-            .header("Authorization", format!("Bearer {}", auth_bearer))
-            .json(body)
-            .send()
-            .await
-            .map_err(Error::Reqwest)?;
-
-        let status = res.status();
-        let text = res.text().await.map_err(Error::Reqwest)?;
+        let (status, text) = self
+            .send_with_retry(|| {
+                self.client
+                    .patch(url.clone())
+                    .header("apikey", &self.api_key)
+                    .header("Authorization", format!("Bearer {}", auth_bearer))
+                    .json(body)
+            })
+            .await?;
 
         tracing::debug!(method = "PATCH", url = %url, status = %status, response_len = text.len(), "Supabase response");
 
@@ -823,17 +866,14 @@ impl SupabaseWalletDatabase {
 
         tracing::debug!(method = "DELETE", url = %url, "Supabase request");
 
-        let res = self
-            .client
-            .delete(url.clone())
-            .header("apikey", &self.api_key)
-            .header("Authorization", format!("Bearer {}", auth_bearer))
-            .send()
-            .await
-            .map_err(Error::Reqwest)?;
-
-        let status = res.status();
-        let text = res.text().await.map_err(Error::Reqwest)?;
+        let (status, text) = self
+            .send_with_retry(|| {
+                self.client
+                    .delete(url.clone())
+                    .header("apikey", &self.api_key)
+                    .header("Authorization", format!("Bearer {}", auth_bearer))
+            })
+            .await?;
 
         tracing::debug!(method = "DELETE", url = %url, status = %status, response_len = text.len(), "Supabase response");
 
@@ -854,6 +894,143 @@ impl SupabaseWalletDatabase {
     }
 }
 
+/// Incremental pulls for the local cache
+#[cfg(feature = "cache")]
+impl SupabaseWalletDatabase {
+    /// Rows of `table` changed at or after `since`, or every row if `since` is `None`
+    async fn changed_rows<T: serde::de::DeserializeOwned>(
+        &self,
+        table: &str,
+        since: Option<i64>,
+    ) -> Result<Vec<T>, DatabaseError> {
+        let mut path = format!("rest/v1/{table}?select=*");
+        if let Some(since) = since {
+            path.push_str(&format!("&changed_at=gte.{since}"));
+        }
+
+        let (status, text) = self.get_request(&path).await?;
+        if !status.is_success() {
+            return Err(Error::http(&format!("pull {table}"), status, &text).into());
+        }
+
+        Ok(Self::parse_response::<T>(&text)?.unwrap_or_default())
+    }
+
+    /// `column` of every row of `table`
+    async fn column_values(&self, table: &str, column: &str) -> Result<Vec<String>, DatabaseError> {
+        let path = format!("rest/v1/{table}?select={column}");
+        let (status, text) = self.get_request(&path).await?;
+        if !status.is_success() {
+            return Err(Error::http(&format!("list {table}"), status, &text).into());
+        }
+
+        Ok(
+            Self::parse_response::<HashMap<String, serde_json::Value>>(&text)?
+                .unwrap_or_default()
+                .into_iter()
+                .filter_map(|mut row| match row.remove(column) {
+                    Some(serde_json::Value::String(value)) => Some(value),
+                    _ => None,
+                })
+                .collect(),
+        )
+    }
+
+    /// Proofs changed at or after `since`, and when the last of them changed
+    pub(crate) async fn proofs_changed_since(
+        &self,
+        since: Option<i64>,
+    ) -> Result<(Vec<ProofInfo>, Option<i64>), DatabaseError> {
+        let rows = self.changed_rows::<ProofTable>("proof", since).await?;
+        let changed_at = rows.iter().filter_map(|row| row.changed_at).max();
+
+        let mut proofs = Vec::with_capacity(rows.len());
+        for mut row in rows {
+            self.decrypt_proof_table(&mut row).await;
+            proofs.push(row.try_into()?);
+        }
+        Ok((proofs, changed_at))
+    }
+
+    /// Mint quotes changed at or after `since`, and when the last of them changed
+    pub(crate) async fn mint_quotes_changed_since(
+        &self,
+        since: Option<i64>,
+    ) -> Result<(Vec<MintQuote>, Option<i64>), DatabaseError> {
+        let rows = self
+            .changed_rows::<MintQuoteTable>("mint_quote", since)
+            .await?;
+        let changed_at = rows.iter().filter_map(|row| row.changed_at).max();
+        let quotes = rows
+            .into_iter()
+            .map(|row| row.try_into())
+            .collect::<Result<_, _>>()?;
+        Ok((quotes, changed_at))
+    }
+
+    /// Melt quotes changed at or after `since`, and when the last of them changed
+    pub(crate) async fn melt_quotes_changed_since(
+        &self,
+        since: Option<i64>,
+    ) -> Result<(Vec<wallet::MeltQuote>, Option<i64>), DatabaseError> {
+        let rows = self
+            .changed_rows::<MeltQuoteTable>("melt_quote", since)
+            .await?;
+        let changed_at = rows.iter().filter_map(|row| row.changed_at).max();
+        let quotes = rows
+            .into_iter()
+            .map(|row| row.try_into())
+            .collect::<Result<_, _>>()?;
+        Ok((quotes, changed_at))
+    }
+
+    /// Transactions changed at or after `since`, and when the last of them changed
+    pub(crate) async fn transactions_changed_since(
+        &self,
+        since: Option<i64>,
+    ) -> Result<(Vec<Transaction>, Option<i64>), DatabaseError> {
+        let rows = self
+            .changed_rows::<TransactionTable>("transactions", since)
+            .await?;
+        let changed_at = rows.iter().filter_map(|row| row.changed_at).max();
+        let transactions = rows
+            .into_iter()
+            .map(|row| row.try_into())
+            .collect::<Result<_, _>>()?;
+        Ok((transactions, changed_at))
+    }
+
+    /// Y values of every stored proof
+    pub(crate) async fn proof_ys(&self) -> Result<Vec<PublicKey>, DatabaseError> {
+        self.column_values("proof", "y")
+            .await?
+            .iter()
+            .map(|y| PublicKey::from_hex(y).map_err(|e| DatabaseError::Internal(e.to_string())))
+            .collect()
+    }
+
+    /// Ids of every stored mint quote
+    pub(crate) async fn mint_quote_ids(&self) -> Result<Vec<String>, DatabaseError> {
+        self.column_values("mint_quote", "id").await
+    }
+
+    /// Ids of every stored melt quote
+    pub(crate) async fn melt_quote_ids(&self) -> Result<Vec<String>, DatabaseError> {
+        self.column_values("melt_quote", "id").await
+    }
+
+    /// Ids of every stored transaction
+    pub(crate) async fn transaction_ids(&self) -> Result<Vec<TransactionId>, DatabaseError> {
+        self.column_values("transactions", "id")
+            .await?
+            .iter()
+            .map(|id| {
+                TransactionId::from_hex(id).map_err(|e| DatabaseError::Internal(e.to_string()))
+            })
+            .collect()
+    }
+}
+
 #[async_trait]
 impl KVStoreDatabase for SupabaseWalletDatabase {
     type Err = DatabaseError;
@@ -875,10 +1052,7 @@ impl KVStoreDatabase for SupabaseWalletDatabase {
 
         if status == StatusCode::NO_CONTENT || !status.is_success() {
             if !status.is_success() && status != StatusCode::NO_CONTENT {
-                return Err(DatabaseError::Internal(format!(
-                    "kv_read failed: HTTP {}",
-                    status
-                )));
+                return Err(Error::http("kv_read", status, &text).into());
             }
             return Ok(None);
         }
@@ -910,10 +1084,7 @@ impl KVStoreDatabase for SupabaseWalletDatabase {
         let (status, text) = self.get_request(&path).await?;
 
         if !status.is_success() {
-            return Err(DatabaseError::Internal(format!(
-                "kv_list failed: HTTP {}",
-                status
-            )));
+            return Err(Error::http("kv_list", status, &text).into());
         }
 
         if let Some(items) = Self::parse_response::<KVStoreTable>(&text)? {
@@ -925,6 +1096,13 @@ impl KVStoreDatabase for SupabaseWalletDatabase {
 }
 #[async_trait]
 impl Database<DatabaseError> for SupabaseWalletDatabase {
+    /// Supabase has no multi-request transactions, so writes are buffered until commit
+    async fn begin_db_transaction(
+        &self,
+    ) -> Result<Box<dyn DatabaseTransaction<DatabaseError> + Send + Sync>, DatabaseError> {
+        Ok(Box::new(BufferedTransaction::new(Arc::new(self.clone()))))
+    }
+
     async fn get_mint(&self, mint_url: MintUrl) -> Result<Option<MintInfo>, DatabaseError> {
         let path = format!(
             "rest/v1/mint?mint_url=eq.{}",
@@ -937,10 +1115,7 @@ impl Database<DatabaseError> for SupabaseWalletDatabase {
             return Ok(None);
         }
         if !status.is_success() {
-            return Err(DatabaseError::Internal(format!(
-                "get_mint failed: HTTP {}",
-                status
-            )));
+            return Err(Error::http("get_mint", status, &text).into());
         }
 
         if let Some(mints) = Self::parse_response::<MintTable>(&text)? {
@@ -955,10 +1130,7 @@ impl Database<DatabaseError> for SupabaseWalletDatabase {
         let (status, text) = self.get_request("rest/v1/mint").await?;
 
         if !status.is_success() {
-            return Err(DatabaseError::Internal(format!(
-                "get_mints failed: HTTP {}",
-                status
-            )));
+            return Err(Error::http("get_mints", status, &text).into());
         }
 
         let mut map = HashMap::new();
@@ -985,10 +1157,7 @@ impl Database<DatabaseError> for SupabaseWalletDatabase {
         let (status, text) = self.get_request(&path).await?;
 
         if !status.is_success() {
-            return Err(DatabaseError::Internal(format!(
-                "get_mint_keysets failed: HTTP {}",
-                status
-            )));
+            return Err(Error::http("get_mint_keysets", status, &text).into());
         }
 
         if let Some(keysets) = Self::parse_response::<KeySetTable>(&text)? {
@@ -1008,10 +1177,7 @@ impl Database<DatabaseError> for SupabaseWalletDatabase {
         let (status, text) = self.get_request(&path).await?;
 
         if !status.is_success() {
-            return Err(DatabaseError::Internal(format!(
-                "get_keyset_by_id failed: HTTP {}",
-                status
-            )));
+            return Err(Error::http("get_keyset_by_id", status, &text).into());
         }
 
         if let Some(items) = Self::parse_response::<KeySetTable>(&text)? {
@@ -1027,10 +1193,7 @@ impl Database<DatabaseError> for SupabaseWalletDatabase {
         let (status, text) = self.get_request(&path).await?;
 
         if !status.is_success() {
-            return Err(DatabaseError::Internal(format!(
-                "get_mint_quote failed: HTTP {}",
-                status
-            )));
+            return Err(Error::http("get_mint_quote", status, &text).into());
         }
 
         if let Some(items) = Self::parse_response::<MintQuoteTable>(&text)? {
@@ -1045,10 +1208,7 @@ impl Database<DatabaseError> for SupabaseWalletDatabase {
         let (status, text) = self.get_request("rest/v1/mint_quote").await?;
 
         if !status.is_success() {
-            return Err(DatabaseError::Internal(format!(
-                "get_mint_quotes failed: HTTP {}",
-                status
-            )));
+            return Err(Error::http("get_mint_quotes", status, &text).into());
         }
 
         if let Some(quotes) = Self::parse_response::<MintQuoteTable>(&text)? {
@@ -1064,10 +1224,7 @@ impl Database<DatabaseError> for SupabaseWalletDatabase {
             .await?;
 
         if !status.is_success() {
-            return Err(DatabaseError::Internal(format!(
-                "get_unissued_mint_quotes failed: HTTP {}",
-                status
-            )));
+            return Err(Error::http("get_unissued_mint_quotes", status, &text).into());
         }
 
         if let Some(quotes) = Self::parse_response::<MintQuoteTable>(&text)? {
@@ -1085,10 +1242,7 @@ impl Database<DatabaseError> for SupabaseWalletDatabase {
         let (status, text) = self.get_request(&path).await?;
 
         if !status.is_success() {
-            return Err(DatabaseError::Internal(format!(
-                "get_melt_quote failed: HTTP {}",
-                status
-            )));
+            return Err(Error::http("get_melt_quote", status, &text).into());
         }
 
         if let Some(items) = Self::parse_response::<MeltQuoteTable>(&text)? {
@@ -1103,10 +1257,7 @@ impl Database<DatabaseError> for SupabaseWalletDatabase {
         let (status, text) = self.get_request("rest/v1/melt_quote").await?;
 
         if !status.is_success() {
-            return Err(DatabaseError::Internal(format!(
-                "get_melt_quotes failed: HTTP {}",
-                status
-            )));
+            return Err(Error::http("get_melt_quotes", status, &text).into());
         }
 
         if let Some(quotes) = Self::parse_response::<MeltQuoteTable>(&text)? {
@@ -1121,10 +1272,7 @@ impl Database<DatabaseError> for SupabaseWalletDatabase {
         let (status, text) = self.get_request(&path).await?;
 
         if !status.is_success() {
-            return Err(DatabaseError::Internal(format!(
-                "get_keys failed: HTTP {}",
-                status
-            )));
+            return Err(Error::http("get_keys", status, &text).into());
         }
 
         if let Some(items) = Self::parse_response::<KeyTable>(&text)? {
@@ -1157,10 +1305,7 @@ impl Database<DatabaseError> for SupabaseWalletDatabase {
         let (status, text) = self.get_request(&query).await?;
 
         if !status.is_success() {
-            return Err(DatabaseError::Internal(format!(
-                "get_proofs failed: HTTP {}",
-                status
-            )));
+            return Err(Error::http("get_proofs", status, &text).into());
         }
 
         let mut result = Vec::new();
@@ -1191,30 +1336,27 @@ impl Database<DatabaseError> for SupabaseWalletDatabase {
             return Ok(Vec::new());
         }
 
-        let ys_str: Vec<String> = ys.iter().map(|y| hex::encode(y.to_bytes())).collect();
-        let filter = format!("({})", ys_str.join(","));
-        let path = format!("rest/v1/proof?y=in.{}", filter);
+        let mut result = Vec::new();
 
-        let (status, text) = self.get_request(&path).await?;
+        for chunk in ys.chunks(YS_FILTER_CHUNK) {
+            let path = format!("rest/v1/proof?y=in.{}", ys_filter(chunk));
 
-        if !status.is_success() {
-            return Err(DatabaseError::Internal(format!(
-                "get_proofs_by_ys failed: HTTP {}",
-                status
-            )));
-        }
+            let (status, text) = self.get_request(&path).await?;
 
-        if let Some(proofs) = Self::parse_response::<ProofTable>(&text)? {
-            let mut result = Vec::new();
-            for mut p in proofs {
-                self.decrypt_proof_table(&mut p).await;
+            if !status.is_success() {
+                return Err(Error::http("get_proofs_by_ys", status, &text).into());
+            }
 
-                result.push(p.try_into()?);
+            if let Some(proofs) = Self::parse_response::<ProofTable>(&text)? {
+                for mut p in proofs {
+                    self.decrypt_proof_table(&mut p).await;
+
+                    result.push(p.try_into()?);
+                }
             }
-            Ok(result)
-        } else {
-            Ok(Vec::new())
         }
+
+        Ok(result)
     }
 
     async fn get_balance(
@@ -1243,10 +1385,7 @@ impl Database<DatabaseError> for SupabaseWalletDatabase {
             return Ok(None);
         }
         if !status.is_success() {
-            return Err(DatabaseError::Internal(format!(
-                "get_transaction failed: HTTP {}",
-                status
-            )));
+            return Err(Error::http("get_transaction", status, &text).into());
         }
 
         if let Some(txs) = Self::parse_response::<TransactionTable>(&text)? {
@@ -1277,10 +1416,7 @@ impl Database<DatabaseError> for SupabaseWalletDatabase {
         let (status, text) = self.get_request(&query).await?;
 
         if !status.is_success() {
-            return Err(DatabaseError::Internal(format!(
-                "list_transactions failed: HTTP {}",
-                status
-            )));
+            return Err(Error::http("list_transactions", status, &text).into());
         }
 
         if let Some(txs) = Self::parse_response::<TransactionTable>(&text)? {
@@ -1344,19 +1480,17 @@ impl Database<DatabaseError> for SupabaseWalletDatabase {
             "Supabase atomic update_proofs RPC"
         );
 
-        let res = self
-            .client
-            .post(url.clone())
-            .header("apikey", &self.api_key)
-            .header("Authorization", format!("Bearer {}", auth_bearer))
-            .header("Content-Type", "application/json")
-            .json(&rpc_body)
-            .send()
-            .await
-            .map_err(Error::Reqwest)?;
-
-        let status = res.status();
-        let text = res.text().await.map_err(Error::Reqwest)?;
+        // Adding and removing proofs is idempotent, so the whole batch can be retried
+        let (status, text) = self
+            .send_with_retry(|| {
+                self.client
+                    .post(url.clone())
+                    .header("apikey", &self.api_key)
+                    .header("Authorization", format!("Bearer {}", auth_bearer))
+                    .header("Content-Type", "application/json")
+                    .json(&rpc_body)
+            })
+            .await?;
 
         tracing::debug!(
             method = "POST",
@@ -1385,35 +1519,43 @@ impl Database<DatabaseError> for SupabaseWalletDatabase {
             return Ok(());
         }
 
-        let ys_str: Vec<String> = ys.iter().map(|y| hex::encode(y.to_bytes())).collect();
-        let filter = format!("({})", ys_str.join(","));
-        let path = format!("rest/v1/proof?y=in.{}", filter);
+        let body = serde_json::json!({ "state": state.to_string() });
 
-        let (status, response_text) = self
-            .patch_request(&path, &serde_json::json!({ "state": state.to_string() }))
-            .await?;
+        for chunk in ys.chunks(YS_FILTER_CHUNK) {
+            let path = format!("rest/v1/proof?y=in.{}", ys_filter(chunk));
 
-        if !status.is_success() {
-            return Err(DatabaseError::Internal(format!(
-                "update_proofs_state failed: HTTP {} - {}",
-                status, response_text
-            )));
+            let (status, response_text) = self.patch_request(&path, &body).await?;
+
+            if !status.is_success() {
+                return Err(Error::http("update_proofs_state", status, &response_text).into());
+            }
         }
 
         Ok(())
     }
 
     async fn add_transaction(&self, transaction: Transaction) -> Result<(), DatabaseError> {
+        if let Some(saga_id) = transaction.saga_id {
+            let path = format!(
+                "rest/v1/transactions?select=id&saga_id=eq.{}&id=neq.{}",
+                url_encode(&saga_id.to_string()),
+                url_encode(&transaction.id().to_string())
+            );
+            let (status, text) = self.get_request(&path).await?;
+
+            if status.is_success() && Self::parse_response::<serde_json::Value>(&text)?.is_some() {
+                tracing::debug!("Transaction for operation {} already recorded", saga_id);
+                return Ok(());
+            }
+        }
+
         let item: TransactionTable = transaction.try_into()?;
         let (status, response_text) = self
             .post_request("rest/v1/transactions?on_conflict=id,wallet_id", &item)
             .await?;
 
         if !status.is_success() {
-            return Err(DatabaseError::Internal(format!(
-                "add_transaction failed: HTTP {} - {}",
-                status, response_text
-            )));
+            return Err(Error::http("add_transaction", status, &response_text).into());
         }
         Ok(())
     }
@@ -1425,10 +1567,7 @@ impl Database<DatabaseError> for SupabaseWalletDatabase {
         let (status, response_text) = self.delete_request(&path).await?;
 
         if !status.is_success() {
-            return Err(DatabaseError::Internal(format!(
-                "remove_transaction failed: HTTP {} - {}",
-                status, response_text
-            )));
+            return Err(Error::http("remove_transaction", status, &response_text).into());
         }
         Ok(())
     }
@@ -1445,50 +1584,44 @@ impl Database<DatabaseError> for SupabaseWalletDatabase {
         let path = format!("rest/v1/mint?mint_url=eq.{}", old_encoded);
         let (status, response_text) = self.patch_request(&path, &update_body).await?;
         if !status.is_success() {
-            return Err(DatabaseError::Internal(format!(
-                "update_mint_url (mint) failed: HTTP {} - {}",
-                status, response_text
-            )));
+            return Err(Error::http("update_mint_url (mint)", status, &response_text).into());
         }
 
         // Update keyset table
         let path = format!("rest/v1/keyset?mint_url=eq.{}", old_encoded);
         let (status, response_text) = self.patch_request(&path, &update_body).await?;
         if !status.is_success() {
-            return Err(DatabaseError::Internal(format!(
-                "update_mint_url (keyset) failed: HTTP {} - {}",
-                status, response_text
-            )));
+            return Err(Error::http("update_mint_url (keyset)", status, &response_text).into());
         }
 
         // Update mint_quote table
         let path = format!("rest/v1/mint_quote?mint_url=eq.{}", old_encoded);
         let (status, response_text) = self.patch_request(&path, &update_body).await?;
         if !status.is_success() {
-            return Err(DatabaseError::Internal(format!(
-                "update_mint_url (mint_quote) failed: HTTP {} - {}",
-                status, response_text
-            )));
+            return Err(Error::http("update_mint_url (mint_quote)", status, &response_text).into());
+        }
+
+        // Update melt_quote table
+        let path = format!("rest/v1/melt_quote?mint_url=eq.{}", old_encoded);
+        let (status, response_text) = self.patch_request(&path, &update_body).await?;
+        if !status.is_success() {
+            return Err(Error::http("update_mint_url (melt_quote)", status, &response_text).into());
         }
 
         // Update proof table
         let path = format!("rest/v1/proof?mint_url=eq.{}", old_encoded);
         let (status, response_text) = self.patch_request(&path, &update_body).await?;
         if !status.is_success() {
-            return Err(DatabaseError::Internal(format!(
-                "update_mint_url (proof) failed: HTTP {} - {}",
-                status, response_text
-            )));
+            return Err(Error::http("update_mint_url (proof)", status, &response_text).into());
         }
 
         // Update transactions table
         let path = format!("rest/v1/transactions?mint_url=eq.{}", old_encoded);
         let (status, response_text) = self.patch_request(&path, &update_body).await?;
         if !status.is_success() {
-            return Err(DatabaseError::Internal(format!(
-                "update_mint_url (transactions) failed: HTTP {} - {}",
-                status, response_text
-            )));
+            return Err(
+                Error::http("update_mint_url (transactions)", status, &response_text).into(),
+            );
         }
 
         Ok(())
@@ -1560,10 +1693,7 @@ impl Database<DatabaseError> for SupabaseWalletDatabase {
             .await?;
 
         if !status.is_success() {
-            return Err(DatabaseError::Internal(format!(
-                "add_mint failed: HTTP {} - {}",
-                status, response_text
-            )));
+            return Err(Error::http("add_mint", status, &response_text).into());
         }
         Ok(())
     }
@@ -1576,10 +1706,7 @@ impl Database<DatabaseError> for SupabaseWalletDatabase {
         let (status, response_text) = self.delete_request(&path).await?;
 
         if !status.is_success() {
-            return Err(DatabaseError::Internal(format!(
-                "remove_mint failed: HTTP {} - {}",
-                status, response_text
-            )));
+            return Err(Error::http("remove_mint", status, &response_text).into());
         }
         Ok(())
     }
@@ -1604,10 +1731,7 @@ impl Database<DatabaseError> for SupabaseWalletDatabase {
             .await?;
 
         if !status.is_success() {
-            return Err(DatabaseError::Internal(format!(
-                "add_mint_keysets failed: HTTP {} - {}",
-                status, response_text
-            )));
+            return Err(Error::http("add_mint_keysets", status, &response_text).into());
         }
         Ok(())
     }
@@ -1632,10 +1756,7 @@ impl Database<DatabaseError> for SupabaseWalletDatabase {
         let (status, response_text) = self.patch_request_returning(&path, &item).await?;
 
         if !status.is_success() {
-            return Err(DatabaseError::Internal(format!(
-                "add_mint_quote failed: HTTP {} - {}",
-                status, response_text
-            )));
+            return Err(Error::http("add_mint_quote", status, &response_text).into());
         }
 
         let updated: serde_json::Value =
@@ -1662,10 +1783,7 @@ impl Database<DatabaseError> for SupabaseWalletDatabase {
             return Err(DatabaseError::ConcurrentUpdate);
         }
 
-        Err(DatabaseError::Internal(format!(
-            "add_mint_quote insert failed: HTTP {} - {}",
-            status, response_text
-        )))
+        Err(Error::http("add_mint_quote insert", status, &response_text).into())
     }
 
     async fn remove_mint_quote(&self, quote_id: &str) -> Result<(), DatabaseError> {
@@ -1673,10 +1791,7 @@ impl Database<DatabaseError> for SupabaseWalletDatabase {
         let (status, response_text) = self.delete_request(&path).await?;
 
         if !status.is_success() {
-            return Err(DatabaseError::Internal(format!(
-                "remove_mint_quote failed: HTTP {} - {}",
-                status, response_text
-            )));
+            return Err(Error::http("remove_mint_quote", status, &response_text).into());
         }
         Ok(())
     }
@@ -1701,10 +1816,7 @@ impl Database<DatabaseError> for SupabaseWalletDatabase {
         let (status, response_text) = self.patch_request_returning(&path, &item).await?;
 
         if !status.is_success() {
-            return Err(DatabaseError::Internal(format!(
-                "add_melt_quote failed: HTTP {} - {}",
-                status, response_text
-            )));
+            return Err(Error::http("add_melt_quote", status, &response_text).into());
         }
 
         let updated: serde_json::Value =
@@ -1731,10 +1843,7 @@ impl Database<DatabaseError> for SupabaseWalletDatabase {
             return Err(DatabaseError::ConcurrentUpdate);
         }
 
-        Err(DatabaseError::Internal(format!(
-            "add_melt_quote insert failed: HTTP {} - {}",
-            status, response_text
-        )))
+        Err(Error::http("add_melt_quote insert", status, &response_text).into())
     }
 
     async fn remove_melt_quote(&self, quote_id: &str) -> Result<(), DatabaseError> {
@@ -1742,10 +1851,7 @@ impl Database<DatabaseError> for SupabaseWalletDatabase {
         let (status, response_text) = self.delete_request(&path).await?;
 
         if !status.is_success() {
-            return Err(DatabaseError::Internal(format!(
-                "remove_melt_quote failed: HTTP {} - {}",
-                status, response_text
-            )));
+            return Err(Error::http("remove_melt_quote", status, &response_text).into());
         }
         Ok(())
     }
@@ -1759,10 +1865,7 @@ impl Database<DatabaseError> for SupabaseWalletDatabase {
             .await?;
 
         if !status.is_success() {
-            return Err(DatabaseError::Internal(format!(
-                "add_keys failed: HTTP {} - {}",
-                status, response_text
-            )));
+            return Err(Error::http("add_keys", status, &response_text).into());
         }
         Ok(())
     }
@@ -1772,10 +1875,7 @@ impl Database<DatabaseError> for SupabaseWalletDatabase {
         let (status, response_text) = self.delete_request(&path).await?;
 
         if !status.is_success() {
-            return Err(DatabaseError::Internal(format!(
-                "remove_keys failed: HTTP {} - {}",
-                status, response_text
-            )));
+            return Err(Error::http("remove_keys", status, &response_text).into());
         }
         Ok(())
     }
@@ -1806,10 +1906,7 @@ impl Database<DatabaseError> for SupabaseWalletDatabase {
             .await?;
 
         if !status.is_success() {
-            return Err(DatabaseError::Internal(format!(
-                "kv_write failed: HTTP {} - {}",
-                status, response_text
-            )));
+            return Err(Error::http("kv_write", status, &response_text).into());
         }
         Ok(())
     }
@@ -1829,10 +1926,7 @@ impl Database<DatabaseError> for SupabaseWalletDatabase {
         let (status, response_text) = self.delete_request(&path).await?;
 
         if !status.is_success() {
-            return Err(DatabaseError::Internal(format!(
-                "kv_remove failed: HTTP {} - {}",
-                status, response_text
-            )));
+            return Err(Error::http("kv_remove", status, &response_text).into());
         }
         Ok(())
     }
@@ -1877,10 +1971,7 @@ impl Database<DatabaseError> for SupabaseWalletDatabase {
             .await?;
 
         if !status.is_success() {
-            return Err(DatabaseError::Internal(format!(
-                "add_saga failed: HTTP {} - {}",
-                status, response_text
-            )));
+            return Err(Error::http("add_saga", status, &response_text).into());
         }
         Ok(())
     }
@@ -1890,10 +1981,7 @@ impl Database<DatabaseError> for SupabaseWalletDatabase {
         let (status, text) = self.get_request(&path).await?;
 
         if !status.is_success() {
-            return Err(DatabaseError::Internal(format!(
-                "get_saga failed: HTTP {}",
-                status
-            )));
+            return Err(Error::http("get_saga", status, &text).into());
         }
 
         if let Some(items) = Self::parse_response::<SagaTable>(&text)? {
@@ -1934,10 +2022,7 @@ impl Database<DatabaseError> for SupabaseWalletDatabase {
         let (status, response_text) = self.patch_request_returning(&path, &item).await?;
 
         if !status.is_success() {
-            return Err(DatabaseError::Internal(format!(
-                "update_saga failed: HTTP {} - {}",
-                status, response_text
-            )));
+            return Err(Error::http("update_saga", status, &response_text).into());
         }
 
         let updated: serde_json::Value =
@@ -1952,10 +2037,7 @@ impl Database<DatabaseError> for SupabaseWalletDatabase {
         let (status, response_text) = self.delete_request(&path).await?;
 
         if !status.is_success() {
-            return Err(DatabaseError::Internal(format!(
-                "delete_saga failed: HTTP {} - {}",
-                status, response_text
-            )));
+            return Err(Error::http("delete_saga", status, &response_text).into());
         }
         Ok(())
     }
@@ -1965,10 +2047,7 @@ impl Database<DatabaseError> for SupabaseWalletDatabase {
         let (status, text) = self.get_request(path).await?;
 
         if !status.is_success() {
-            return Err(DatabaseError::Internal(format!(
-                "get_incomplete_sagas failed: HTTP {}",
-                status
-            )));
+            return Err(Error::http("get_incomplete_sagas", status, &text).into());
         }
 
         if let Some(items) = Self::parse_response::<SagaTable>(&text)? {
@@ -2012,10 +2091,7 @@ impl Database<DatabaseError> for SupabaseWalletDatabase {
             let (status, response_text) = self.patch_request(&patch_path, &update).await?;
 
             if !status.is_success() {
-                return Err(DatabaseError::Internal(format!(
-                    "reserve_proofs: update failed: HTTP {} - {}",
-                    status, response_text
-                )));
+                return Err(Error::http("reserve_proofs: update", status, &response_text).into());
             }
 
             // PostgREST returns 204 No Content for success.
@@ -2044,10 +2120,7 @@ impl Database<DatabaseError> for SupabaseWalletDatabase {
         let (status, response_text) = self.patch_request(&path, &update).await?;
 
         if !status.is_success() {
-            return Err(DatabaseError::Internal(format!(
-                "release_proofs failed: HTTP {} - {}",
-                status, response_text
-            )));
+            return Err(Error::http("release_proofs", status, &response_text).into());
         }
         Ok(())
     }
@@ -2064,10 +2137,7 @@ impl Database<DatabaseError> for SupabaseWalletDatabase {
         let (status, text) = self.get_request(&path).await?;
 
         if !status.is_success() {
-            return Err(DatabaseError::Internal(format!(
-                "get_reserved_proofs failed: HTTP {}",
-                status
-            )));
+            return Err(Error::http("get_reserved_proofs", status, &text).into());
         }
 
         if let Some(items) = Self::parse_response::<ProofTable>(&text)? {
@@ -2105,10 +2175,7 @@ impl Database<DatabaseError> for SupabaseWalletDatabase {
         let (status, response_text) = self.patch_request(&patch_path, &update).await?;
 
         if !status.is_success() {
-            return Err(DatabaseError::Internal(format!(
-                "reserve_melt_quote failed: HTTP {} - {}",
-                status, response_text
-            )));
+            return Err(Error::http("reserve_melt_quote", status, &response_text).into());
         }
 
         // Verify that the quote was actually updated by checking if it's reserved for this operation.
@@ -2139,10 +2206,7 @@ impl Database<DatabaseError> for SupabaseWalletDatabase {
         let (status, response_text) = self.patch_request(&path, &update).await?;
 
         if !status.is_success() {
-            return Err(DatabaseError::Internal(format!(
-                "release_melt_quote failed: HTTP {} - {}",
-                status, response_text
-            )));
+            return Err(Error::http("release_melt_quote", status, &response_text).into());
         }
         Ok(())
     }
@@ -2168,10 +2232,7 @@ impl Database<DatabaseError> for SupabaseWalletDatabase {
         let (status, response_text) = self.patch_request(&patch_path, &update).await?;
 
         if !status.is_success() {
-            return Err(DatabaseError::Internal(format!(
-                "reserve_mint_quote failed: HTTP {} - {}",
-                status, response_text
-            )));
+            return Err(Error::http("reserve_mint_quote", status, &response_text).into());
         }
 
         // Verify that the quote was actually updated by checking if it's reserved for this operation.
@@ -2202,10 +2263,7 @@ impl Database<DatabaseError> for SupabaseWalletDatabase {
         let (status, response_text) = self.patch_request(&path, &update).await?;
 
         if !status.is_success() {
-            return Err(DatabaseError::Internal(format!(
-                "release_mint_quote failed: HTTP {} - {}",
-                status, response_text
-            )));
+            return Err(Error::http("release_mint_quote", status, &response_text).into());
         }
         Ok(())
     }
@@ -2237,10 +2295,7 @@ impl Database<DatabaseError> for SupabaseWalletDatabase {
             .await?;
 
         if !status.is_success() {
-            return Err(DatabaseError::Internal(format!(
-                "add_p2pk_key failed: HTTP {} - {}",
-                status, response_text
-            )));
+            return Err(Error::http("add_p2pk_key", status, &response_text).into());
         }
         Ok(())
     }
@@ -2259,10 +2314,7 @@ impl Database<DatabaseError> for SupabaseWalletDatabase {
             return Ok(None);
         }
         if !status.is_success() {
-            return Err(DatabaseError::Internal(format!(
-                "get_p2pk_key failed: HTTP {}",
-                status
-            )));
+            return Err(Error::http("get_p2pk_key", status, &text).into());
         }
 
         if let Some(rows) = Self::parse_response::<P2PKSigningKeyTable>(&text)? {
@@ -2278,10 +2330,7 @@ impl Database<DatabaseError> for SupabaseWalletDatabase {
         let (status, text) = self.get_request(path).await?;
 
         if !status.is_success() {
-            return Err(DatabaseError::Internal(format!(
-                "list_p2pk_keys failed: HTTP {}",
-                status
-            )));
+            return Err(Error::http("list_p2pk_keys", status, &text).into());
         }
 
         if let Some(rows) = Self::parse_response::<P2PKSigningKeyTable>(&text)? {
@@ -2298,10 +2347,7 @@ impl Database<DatabaseError> for SupabaseWalletDatabase {
         let (status, text) = self.get_request(path).await?;
 
         if !status.is_success() {
-            return Err(DatabaseError::Internal(format!(
-                "latest_p2pk failed: HTTP {}",
-                status
-            )));
+            return Err(Error::http("latest_p2pk", status, &text).into());
         }
 
         if let Some(rows) = Self::parse_response::<P2PKSigningKeyTable>(&text)? {
@@ -2573,6 +2619,9 @@ struct MintQuoteTable {
     used_by_operation: Option<String>,
     #[serde(default)]
     version: Option<i32>,
+    /// When the row last changed on the server, in microseconds since the epoch
+    #[serde(default, skip_serializing)]
+    changed_at: Option<i64>,
     /// Extra fields from other applications (captured during deserialization, ignored during serialization)
     #[serde(default, skip_serializing, flatten)]
     _extra: serde_json::Map<String, serde_json::Value>,
@@ -2629,6 +2678,7 @@ impl TryFrom<MintQuote> for MintQuoteTable {
             updated_at: q.updated_at as i64,
             used_by_operation: q.used_by_operation,
             version: Some(q.version as i32),
+            changed_at: None,
             _extra: Default::default(),
         })
     }
@@ -2650,11 +2700,16 @@ struct MeltQuoteTable {
     #[serde(default)]
     fee_index: Option<i64>,
     #[serde(default)]
+    melt_options: Option<String>,
+    #[serde(default)]
     mint_url: Option<String>,
     #[serde(default)]
     used_by_operation: Option<String>,
     #[serde(default)]
     version: Option<i32>,
+    /// When the row last changed on the server, in microseconds since the epoch
+    #[serde(default, skip_serializing)]
+    changed_at: Option<i64>,
     /// Extra fields from other applications (captured during deserialization, ignored during serialization)
     #[serde(default, skip_serializing, flatten)]
     _extra: serde_json::Map<String, serde_json::Value>,
@@ -2692,6 +2747,12 @@ impl TryInto<wallet::MeltQuote> for MeltQuoteTable {
                 .map(u32::try_from)
                 .transpose()
                 .map_err(|_| DatabaseError::Internal("Invalid fee_index".into()))?,
+            options: self
+                .melt_options
+                .as_deref()
+                .map(serde_json::from_str)
+                .transpose()
+                .map_err(|_| DatabaseError::Internal("Invalid melt_options".into()))?,
             used_by_operation: self.used_by_operation,
             version: self.version.unwrap_or(0) as u32,
         })
@@ -2714,8 +2775,14 @@ impl TryFrom<wallet::MeltQuote> for MeltQuoteTable {
             payment_method: q.payment_method.to_string(),
             estimated_blocks: q.estimated_blocks.map(i64::from),
             fee_index: q.fee_index.map(i64::from),
+            melt_options: q
+                .options
+                .map(|options| serde_json::to_string(&options))
+                .transpose()
+                .map_err(|_| DatabaseError::Internal("Invalid melt_options".into()))?,
             used_by_operation: q.used_by_operation,
             version: Some(q.version as i32),
+            changed_at: None,
             _extra: Default::default(),
         })
     }
@@ -2742,6 +2809,9 @@ struct ProofTable {
     created_by_operation: Option<String>,
     #[serde(default)]
     p2pk_e: Option<String>,
+    /// When the row last changed on the server, in microseconds since the epoch
+    #[serde(default, skip_serializing)]
+    changed_at: Option<i64>,
     /// Extra fields from other applications (captured during deserialization, ignored during serialization)
     #[serde(default, skip_serializing, flatten)]
     _extra: serde_json::Map<String, serde_json::Value>,
@@ -2849,6 +2919,7 @@ impl TryFrom<ProofInfo> for ProofTable {
             used_by_operation: p.used_by_operation.map(|u| u.to_string()),
             created_by_operation: p.created_by_operation.map(|u| u.to_string()),
             p2pk_e: p.proof.p2pk_e.map(|e| hex::encode(e.to_bytes())),
+            changed_at: None,
             _extra: Default::default(),
         })
     }
@@ -2872,6 +2943,9 @@ struct TransactionTable {
     payment_method: Option<String>,
     #[serde(default)]
     saga_id: Option<String>,
+    /// When the row last changed on the server, in microseconds since the epoch
+    #[serde(default, skip_serializing)]
+    changed_at: Option<i64>,
     /// Extra fields from other applications (captured during deserialization, ignored during serialization)
     #[serde(default, skip_serializing, flatten)]
     _extra: serde_json::Map<String, serde_json::Value>,
@@ -2955,6 +3029,7 @@ impl TryFrom<Transaction> for TransactionTable {
             payment_proof: t.payment_proof,
             payment_method: t.payment_method.map(|p| p.to_string()),
             saga_id: t.saga_id.map(|u| u.to_string()),
+            changed_at: None,
             _extra: Default::default(),
         })
     }
@@ -3022,10 +3097,7 @@ impl SupabaseAuth {
         let status = response.status();
         if !status.is_success() {
             let text = response.text().await.unwrap_or_default();
-            return Err(Error::Supabase(format!(
-                "Supabase signup failed: HTTP {} - {}",
-                status, text
-            )));
+            return Err(Error::http("Supabase signup", status, &text));
         }
 
         response.json().await.map_err(Error::Reqwest)
@@ -3060,10 +3132,7 @@ impl SupabaseAuth {
         let status = response.status();
         if !status.is_success() {
             let text = response.text().await.unwrap_or_default();
-            return Err(Error::Supabase(format!(
-                "Supabase signin failed: HTTP {} - {}",
-                status, text
-            )));
+            return Err(Error::http("Supabase signin", status, &text));
         }
 
         response.json().await.map_err(Error::Reqwest)
@@ -3298,6 +3367,63 @@ mod tests {
         mock.assert_async().await;
     }
 
+    #[tokio::test]
+    async fn update_proofs_state_chunks_large_filters() {
+        let mut server = mockito::Server::new_async().await;
+        let mock = server
+            .mock("PATCH", "/rest/v1/proof")
+            .match_query(Matcher::Any)
+            .with_status(204)
+            .expect(2)
+            .create_async()
+            .await;
+
+        let db = SupabaseWalletDatabase::new(
+            Url::parse(&server.url()).expect("mock server URL should parse"),
+            "anon-key".to_string(),
+        )
+        .await
+        .expect("database should initialize");
+
+        let ys = (0..YS_FILTER_CHUNK + 1)
+            .map(|_| cdk_common::SecretKey::generate().public_key())
+            .collect();
+        db.update_proofs_state(ys, State::Spent)
+            .await
+            .expect("chunked update should succeed");
+
+        mock.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn server_errors_are_retried_with_backoff() {
+        assert_eq!(retry_delay(0), RETRY_BASE_DELAY);
+        assert_eq!(retry_delay(2), RETRY_BASE_DELAY * 4);
+
+        let mut server = mockito::Server::new_async().await;
+        let mock = server
+            .mock("GET", "/rest/v1/proof")
+            .match_query(Matcher::Any)
+            .with_status(503)
+            .expect(MAX_RETRIES as usize + 1)
+            .create_async()
+            .await;
+
+        let db = SupabaseWalletDatabase::new(
+            Url::parse(&server.url()).expect("mock server URL should parse"),
+            "anon-key".to_string(),
+        )
+        .await
+        .expect("database should initialize");
+
+        let result = db
+            .get_proofs_by_ys(vec![cdk_common::SecretKey::generate().public_key()])
+            .await;
+        assert!(result.is_err());
+
+        mock.assert_async().await;
+    }
+
     #[tokio::test]
     async fn call_rpc_encodes_function_name_path_segments() {
         let mut server = mockito::Server::new_async().await;
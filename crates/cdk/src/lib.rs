@@ -14,8 +14,9 @@ pub mod cdk_database {
     pub use cdk_common::database::WalletDatabase;
     #[cfg(feature = "mint")]
     pub use cdk_common::database::{
-        KVStore, KVStoreDatabase, KVStoreTransaction, MintDatabase, MintKeysDatabase,
-        MintProofsDatabase, MintQuotesDatabase, MintSignaturesDatabase, MintTransaction,
+        KVStore, KVStoreDatabase, KVStoreTransaction, MigrationState, MigrationStatus,
+        MintDatabase, MintKeysDatabase, MintProofsDatabase, MintQuotesDatabase,
+        MintSignaturesDatabase, MintTransaction,
     };
 }
 
@@ -26,6 +27,8 @@ pub mod wallet;
 
 #[cfg(test)]
 mod test_helpers;
+#[cfg(all(feature = "wallet", any(test, feature = "test-utils")))]
+pub mod test_utils;
 
 #[cfg(all(feature = "bip353", not(target_arch = "wasm32")))]
 mod bip353;
@@ -33,6 +36,9 @@ mod bip353;
 #[cfg(feature = "wallet")]
 mod lightning_address;
 
+#[cfg(feature = "lnurl")]
+mod lnurl;
+
 #[cfg(any(feature = "wallet", feature = "mint"))]
 pub use cdk_common::auth::oidc::OidcClient;
 #[cfg(feature = "mint")]
@@ -111,67 +111,78 @@ impl LightningAddress {
     ) -> Result<Bolt11Invoice, Error> {
         let pay_data = self.fetch_pay_request_data(client).await?;
 
-        // Validate amount is within acceptable range
-        let amount_msat_u64: u64 = amount_msat.into();
-        if amount_msat_u64 < pay_data.min_sendable {
-            return Err(Error::AmountBelowMinimum {
-                amount: amount_msat_u64,
-                min: pay_data.min_sendable,
-            });
-        }
-        if amount_msat_u64 > pay_data.max_sendable {
-            return Err(Error::AmountAboveMaximum {
-                amount: amount_msat_u64,
-                max: pay_data.max_sendable,
-            });
-        }
+        request_pay_invoice(client, &pay_data, &self.domain, amount_msat).await
+    }
+}
 
-        // Build callback URL with amount parameter
-        let mut callback_url = validate_lnurl_callback_url(&pay_data.callback, &self.domain)?;
+/// Request an invoice of `amount_msat` from the callback of an LNURL-pay request
+///
+/// The callback must be served from `domain`, the domain the pay request was fetched from.
+pub(crate) async fn request_pay_invoice(
+    client: &Arc<dyn MintConnector + Send + Sync>,
+    pay_data: &LnurlPayResponse,
+    domain: &str,
+    amount_msat: Amount,
+) -> Result<Bolt11Invoice, Error> {
+    // Validate amount is within acceptable range
+    let amount_msat_u64: u64 = amount_msat.into();
+    if amount_msat_u64 < pay_data.min_sendable {
+        return Err(Error::AmountBelowMinimum {
+            amount: amount_msat_u64,
+            min: pay_data.min_sendable,
+        });
+    }
+    if amount_msat_u64 > pay_data.max_sendable {
+        return Err(Error::AmountAboveMaximum {
+            amount: amount_msat_u64,
+            max: pay_data.max_sendable,
+        });
+    }
 
-        callback_url
-            .query_pairs_mut()
-            .append_pair("amount", &amount_msat_u64.to_string());
+    // Build callback URL with amount parameter
+    let mut callback_url = validate_lnurl_callback_url(&pay_data.callback, domain)?;
 
-        tracing::debug!("Requesting invoice from callback: {}", callback_url);
+    callback_url
+        .query_pairs_mut()
+        .append_pair("amount", &amount_msat_u64.to_string());
 
-        // Fetch the invoice
-        let invoice_response = client.fetch_lnurl_invoice(callback_url.as_str()).await?;
+    tracing::debug!("Requesting invoice from callback: {}", callback_url);
 
-        // Check for errors
-        if let Some(ref reason) = invoice_response.reason {
-            return Err(Error::Service(reason.clone()));
-        }
+    // Fetch the invoice
+    let invoice_response = client.fetch_lnurl_invoice(callback_url.as_str()).await?;
 
-        // Parse and return the invoice
-        let pr = invoice_response.pr.ok_or(Error::NoInvoice)?;
+    // Check for errors
+    if let Some(ref reason) = invoice_response.reason {
+        return Err(Error::Service(reason.clone()));
+    }
 
-        let invoice =
-            Bolt11Invoice::from_str(&pr).map_err(|e| Error::InvoiceParse(e.to_string()))?;
-        let invoice_amount_msat = invoice
-            .amount_milli_satoshis()
-            .ok_or(Error::InvoiceAmountUndefined)?;
+    // Parse and return the invoice
+    let pr = invoice_response.pr.ok_or(Error::NoInvoice)?;
 
-        if invoice_amount_msat != amount_msat_u64 {
-            return Err(Error::IncorrectInvoiceAmount {
-                actual: invoice_amount_msat,
-                expected: amount_msat_u64,
-            });
-        }
+    let invoice = Bolt11Invoice::from_str(&pr).map_err(|e| Error::InvoiceParse(e.to_string()))?;
+    let invoice_amount_msat = invoice
+        .amount_milli_satoshis()
+        .ok_or(Error::InvoiceAmountUndefined)?;
 
-        let expected_description_hash = Sha256Hash::hash(pay_data.metadata.as_bytes());
-        if !matches!(
-            invoice.description(),
-            lightning_invoice::Bolt11InvoiceDescriptionRef::Hash(hash)
-                if hash.0.as_byte_array() == expected_description_hash.as_byte_array()
-        ) {
-            tracing::warn!(
-                "LNURL-pay invoice description hash does not match metadata; continuing payment"
-            );
-        }
+    if invoice_amount_msat != amount_msat_u64 {
+        return Err(Error::IncorrectInvoiceAmount {
+            actual: invoice_amount_msat,
+            expected: amount_msat_u64,
+        });
+    }
 
-        Ok(invoice)
+    let expected_description_hash = Sha256Hash::hash(pay_data.metadata.as_bytes());
+    if !matches!(
+        invoice.description(),
+        lightning_invoice::Bolt11InvoiceDescriptionRef::Hash(hash)
+            if hash.0.as_byte_array() == expected_description_hash.as_byte_array()
+    ) {
+        tracing::warn!(
+            "LNURL-pay invoice description hash does not match metadata; continuing payment"
+        );
     }
+
+    Ok(invoice)
 }
 
 impl FromStr for LightningAddress {
@@ -261,7 +272,10 @@ fn validate_lud16_domain(domain: &str) -> Result<(), Error> {
     }
 }
 
-fn validate_lnurl_callback_url(callback: &str, expected_domain: &str) -> Result<Url, Error> {
+pub(crate) fn validate_lnurl_callback_url(
+    callback: &str,
+    expected_domain: &str,
+) -> Result<Url, Error> {
     let url = Url::parse(callback)?;
 
     if url.scheme() != "https" {
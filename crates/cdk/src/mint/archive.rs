@@ -0,0 +1,105 @@
+//! Spent proof archival
+//!
+//! Moves old spent proofs out of the proof table into a compact archive that
+//! only keeps what is needed for double-spend protection.
+
+use std::time::Duration;
+
+use tracing::instrument;
+
+use crate::{Error, Mint};
+
+/// Number of proofs archived per database transaction
+const ARCHIVE_BATCH_SIZE: u64 = 1_000;
+
+impl Mint {
+    /// Archive proofs that were spent longer than `retention` ago
+    ///
+    /// Proofs are moved in batches, each in its own transaction, so the proof
+    /// table stays bounded without holding long locks. Archived proofs are
+    /// still reported as spent and rejected as inputs. Returns the number of
+    /// archived proofs.
+    #[instrument(skip(self))]
+    pub async fn archive_spent_proofs(&self, retention: Duration) -> Result<u64, Error> {
+        let spent_before = self.unix_time().saturating_sub(retention.as_secs());
+        let mut total = 0;
+
+        loop {
+            let mut tx = self.localstore.begin_transaction().await?;
+            let archived = tx
+                .archive_spent_proofs(spent_before, ARCHIVE_BATCH_SIZE)
+                .await?;
+            tx.commit().await?;
+
+            total += archived;
+
+            if archived < ARCHIVE_BATCH_SIZE {
+                break;
+            }
+        }
+
+        if total > 0 {
+            tracing::info!("Archived {} spent proofs", total);
+        }
+
+        Ok(total)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use cdk_common::mint::Operation;
+    use cdk_common::nuts::{CheckStateRequest, ProofsMethods};
+    use cdk_common::{Amount, State};
+
+    use crate::test_helpers::mint::{create_test_mint, mint_test_proofs};
+    use crate::Mint;
+
+    #[tokio::test]
+    async fn test_archive_keeps_recently_spent_proofs() {
+        let mint = create_test_mint().await.unwrap();
+        let proofs = mint_test_proofs(&mint, Amount::from(100)).await.unwrap();
+        let ys = proofs.ys().unwrap();
+        let db = mint.localstore();
+
+        let mut tx = db.begin_transaction().await.unwrap();
+        let mut acquired = tx
+            .add_proofs(
+                proofs,
+                None,
+                &Operation::new_swap(Amount::ZERO, Amount::ZERO, Amount::ZERO),
+            )
+            .await
+            .unwrap();
+        Mint::update_proofs_state(&mut tx, &mut acquired, State::Pending)
+            .await
+            .unwrap();
+        Mint::update_proofs_state(&mut tx, &mut acquired, State::Spent)
+            .await
+            .unwrap();
+        tx.commit().await.unwrap();
+
+        let archived = mint
+            .archive_spent_proofs(Duration::from_secs(3600))
+            .await
+            .unwrap();
+        assert_eq!(archived, 0);
+
+        let response = mint
+            .check_state(&CheckStateRequest::new(ys.clone()))
+            .await
+            .unwrap();
+        assert!(response
+            .states
+            .iter()
+            .all(|proof_state| proof_state.state == State::Spent));
+        assert!(db
+            .get_proofs_by_ys(&ys)
+            .await
+            .unwrap()
+            .iter()
+            .all(Option::is_some));
+    }
+}
@@ -1,5 +1,9 @@
 use tracing::instrument;
 
+mod quota;
+
+pub use quota::{AuthIdentity, AuthQuotas, QuotaCharge, QuotaOperation};
+
 use super::nut21::ProtectedEndpoint;
 use super::{
     AuthProof, AuthRequired, AuthToken, BlindAuthToken, BlindSignature, BlindedMessage,
@@ -31,14 +35,17 @@ impl Mint {
     }
 
     /// Verify Clear auth
+    ///
+    /// Returns the identity of the token, if it carries a `sub` claim.
     #[instrument(skip_all, fields(token_len = token.len()))]
-    pub async fn verify_clear_auth(&self, token: String) -> Result<(), Error> {
+    pub async fn verify_clear_auth(&self, token: String) -> Result<Option<AuthIdentity>, Error> {
         Ok(self
             .oidc_client
             .as_ref()
             .ok_or(Error::OidcNotSet)?
-            .verify_cat(&token)
-            .await?)
+            .verify_cat_subject(&token)
+            .await?
+            .map(AuthIdentity::new))
     }
 
     /// Verify Blind auth
@@ -65,13 +72,14 @@ impl Mint {
 
     /// Verify Auth
     ///
-    /// If it is a blind auth this will also burn the proof
+    /// If it is a blind auth this will also burn the proof. Returns the identity of a clear auth
+    /// token, blind auth tokens are unlinkable and have none.
     #[instrument(skip_all)]
     pub async fn verify_auth(
         &self,
         auth_token: Option<AuthToken>,
         endpoint: &ProtectedEndpoint,
-    ) -> Result<(), Error> {
+    ) -> Result<Option<AuthIdentity>, Error> {
         let auth_required = if let Some(auth_required) = self.is_protected(endpoint).await? {
             tracing::trace!(
                 "Auth required for endpoint: {:?}, type: {:?}",
@@ -81,7 +89,7 @@ impl Mint {
             auth_required
         } else {
             tracing::trace!("No auth required for endpoint: {:?}", endpoint);
-            return Ok(());
+            return Ok(None);
         };
 
         tracing::info!(
@@ -110,11 +118,14 @@ impl Mint {
             },
         };
 
-        match (auth_required, auth_token) {
+        let identity = match (auth_required, auth_token) {
             (AuthRequired::Clear, AuthToken::ClearAuth(token)) => {
                 tracing::debug!("Verifying clear auth token");
                 match self.verify_clear_auth(token.clone()).await {
-                    Ok(_) => tracing::info!("Clear auth verification successful"),
+                    Ok(identity) => {
+                        tracing::info!("Clear auth verification successful");
+                        identity
+                    }
                     Err(e) => {
                         tracing::error!("Clear auth verification failed: {:?}", e);
                         return Err(e);
@@ -143,6 +154,8 @@ impl Mint {
                         tracing::error!("Failed to spend blind auth proof: {:?}", err);
                         err
                     })?;
+
+                None
             }
             (AuthRequired::Blind, other) => {
                 tracing::warn!(
@@ -158,10 +171,10 @@ impl Mint {
                 );
                 return Err(Error::ClearAuthRequired);
             }
-        }
+        };
 
         tracing::debug!("Auth verification completed successfully");
-        Ok(())
+        Ok(identity)
     }
 
     /// Check state of blind auth proof and mark it as spent
@@ -0,0 +1,490 @@
+//! Per-identity quotas
+//!
+//! With clear auth (NUT-21) the mint knows who is minting and melting, so it can bound how much
+//! ecash a single identity mints or melts per day and how many blind auth tokens (NUT-22) it
+//! issues to it. Blind auth tokens are unlinkable, which makes the daily blind auth token quota
+//! the only limit on requests authenticated with blind auth.
+//!
+//! Usage is tracked in the KV store per UTC day, operation, unit and identity. Usage of earlier
+//! days is pruned by the first charge of a new day.
+
+use std::fmt;
+use std::sync::atomic::Ordering;
+
+use bitcoin::hashes::sha256::Hash as Sha256Hash;
+use bitcoin::hashes::Hash;
+use serde::{Deserialize, Serialize};
+use tracing::instrument;
+
+use super::{CurrencyUnit, Error, Mint};
+use crate::mint::{MintInput, QuoteId};
+use crate::Amount;
+
+/// KV store namespace of the quota usage
+const QUOTA_KV_NAMESPACE: &str = "cdk_mint_quota";
+
+/// Seconds in a quota day
+const SECONDS_PER_DAY: u64 = 86_400;
+
+/// Attempts to charge a quota that keeps being changed by concurrent requests
+const MAX_CHARGE_ATTEMPTS: usize = 5;
+
+/// Identity of a clear auth user, the `sub` claim of their clear auth token
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct AuthIdentity(String);
+
+impl AuthIdentity {
+    /// Create a new [`AuthIdentity`]
+    pub fn new(subject: impl Into<String>) -> Self {
+        Self(subject.into())
+    }
+
+    /// OIDC subject of the identity
+    pub fn subject(&self) -> &str {
+        &self.0
+    }
+}
+
+impl fmt::Display for AuthIdentity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+/// Daily limits per auth identity
+///
+/// Amounts are limited per unit, `None` leaves an operation unlimited.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AuthQuotas {
+    /// Maximum amount minted per identity and day
+    pub max_mint_per_day: Option<Amount>,
+    /// Maximum amount melted per identity and day
+    pub max_melt_per_day: Option<Amount>,
+    /// Maximum number of blind auth tokens issued per identity and day
+    pub max_blind_auth_per_day: Option<u64>,
+}
+
+/// Operation limited by [`AuthQuotas`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum QuotaOperation {
+    /// Minting ecash
+    Mint,
+    /// Melting ecash
+    Melt,
+    /// Minting blind auth tokens
+    BlindAuth,
+}
+
+impl fmt::Display for QuotaOperation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Mint => write!(f, "mint"),
+            Self::Melt => write!(f, "melt"),
+            Self::BlindAuth => write!(f, "blind_auth"),
+        }
+    }
+}
+
+/// Usage charged to an identity's quota, refunded with [`Mint::refund_auth_quota`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct QuotaCharge {
+    /// Identity the usage was charged to
+    pub identity: AuthIdentity,
+    /// Limited operation
+    pub operation: QuotaOperation,
+    /// Unit of the amount
+    pub unit: CurrencyUnit,
+    /// Charged amount
+    pub amount: Amount,
+    /// Unix day the usage was charged on
+    day: u64,
+}
+
+impl QuotaOperation {
+    fn limit(&self, quotas: &AuthQuotas) -> Option<Amount> {
+        match self {
+            Self::Mint => quotas.max_mint_per_day,
+            Self::Melt => quotas.max_melt_per_day,
+            Self::BlindAuth => quotas.max_blind_auth_per_day.map(Amount::from),
+        }
+    }
+}
+
+/// KV key of the usage of `identity` in `unit`
+///
+/// Subjects may contain characters that are not allowed in KV keys, so they are hashed.
+fn quota_key(day: u64, unit: &CurrencyUnit, identity: &AuthIdentity) -> String {
+    let hash = Sha256Hash::hash(format!("{unit}:{identity}").as_bytes());
+    format!("{day}_{hash}")
+}
+
+/// Unix day a [`quota_key`] was charged on
+fn quota_key_day(key: &str) -> Option<u64> {
+    key.split_once('_')?.0.parse().ok()
+}
+
+impl Mint {
+    /// Daily limits per auth identity
+    pub fn auth_quotas(&self) -> &AuthQuotas {
+        &self.auth_quotas
+    }
+
+    /// Set the daily limits per auth identity
+    pub fn with_auth_quotas(mut self, auth_quotas: AuthQuotas) -> Self {
+        self.auth_quotas = auth_quotas;
+        self
+    }
+
+    /// Amount `identity` used of its `operation` quota in `unit` today
+    #[instrument(skip(self))]
+    pub async fn auth_quota_usage(
+        &self,
+        identity: &AuthIdentity,
+        operation: QuotaOperation,
+        unit: &CurrencyUnit,
+    ) -> Result<Amount, Error> {
+        let day = self.unix_time() / SECONDS_PER_DAY;
+
+        Ok(self
+            .localstore
+            .kv_read(
+                QUOTA_KV_NAMESPACE,
+                &operation.to_string(),
+                &quota_key(day, unit, identity),
+            )
+            .await?
+            .map(|bytes| serde_json::from_slice(&bytes))
+            .transpose()?
+            .unwrap_or_default())
+    }
+
+    /// Charge `amount` to the daily `operation` quota of `identity`
+    ///
+    /// Returns `None` without charging anything if the operation is unlimited. Fails with
+    /// [`Error::QuotaExceeded`] if the charge would exceed the limit.
+    ///
+    /// The usage is only written if it did not change since it was read, so concurrent requests
+    /// of the same identity cannot both pass the limit. A request that lost the race retries
+    /// with the new usage.
+    #[instrument(skip(self))]
+    pub async fn charge_auth_quota(
+        &self,
+        identity: &AuthIdentity,
+        operation: QuotaOperation,
+        unit: &CurrencyUnit,
+        amount: Amount,
+    ) -> Result<Option<QuotaCharge>, Error> {
+        let Some(limit) = operation.limit(&self.auth_quotas) else {
+            return Ok(None);
+        };
+
+        let now = self.unix_time();
+        let day = now / SECONDS_PER_DAY;
+        let secondary_namespace = operation.to_string();
+        let key = quota_key(day, unit, identity);
+
+        if self.quota_pruned_day.fetch_max(day, Ordering::AcqRel) < day {
+            if let Err(err) = self.prune_auth_quotas(day).await {
+                tracing::warn!("Could not prune quota usage of earlier days: {}", err);
+            }
+        }
+
+        for _ in 0..MAX_CHARGE_ATTEMPTS {
+            let mut tx = self.localstore.begin_transaction().await?;
+
+            let current = tx
+                .kv_read(QUOTA_KV_NAMESPACE, &secondary_namespace, &key)
+                .await?;
+            let used: Amount = match &current {
+                Some(bytes) => serde_json::from_slice(bytes)?,
+                None => Amount::ZERO,
+            };
+
+            let total = used.checked_add(amount).ok_or(Error::AmountOverflow)?;
+
+            if total > limit {
+                tx.rollback().await?;
+
+                tracing::warn!(
+                    "Identity {} exceeded its daily {} quota: requested {}, used {} of {}",
+                    identity,
+                    operation,
+                    amount,
+                    used,
+                    limit
+                );
+
+                return Err(Error::QuotaExceeded {
+                    operation: operation.to_string(),
+                    requested: amount.into(),
+                    used: used.into(),
+                    limit: limit.into(),
+                    retry_after: SECONDS_PER_DAY - now % SECONDS_PER_DAY,
+                });
+            }
+
+            let written = tx
+                .kv_compare_and_write(
+                    QUOTA_KV_NAMESPACE,
+                    &secondary_namespace,
+                    &key,
+                    current.as_deref(),
+                    &serde_json::to_vec(&total)?,
+                )
+                .await?;
+
+            if !written {
+                tracing::debug!("Quota of {} changed concurrently, retrying", identity);
+                tx.rollback().await?;
+                continue;
+            }
+
+            tx.commit().await?;
+
+            return Ok(Some(QuotaCharge {
+                identity: identity.clone(),
+                operation,
+                unit: unit.clone(),
+                amount,
+                day,
+            }));
+        }
+
+        Err(Error::ConcurrentUpdate)
+    }
+
+    /// Return a charge to the quota after the charged operation failed
+    #[instrument(skip(self))]
+    pub async fn refund_auth_quota(&self, charge: &QuotaCharge) -> Result<(), Error> {
+        let secondary_namespace = charge.operation.to_string();
+        let key = quota_key(charge.day, &charge.unit, &charge.identity);
+
+        for _ in 0..MAX_CHARGE_ATTEMPTS {
+            let mut tx = self.localstore.begin_transaction().await?;
+
+            let Some(bytes) = tx
+                .kv_read(QUOTA_KV_NAMESPACE, &secondary_namespace, &key)
+                .await?
+            else {
+                tx.rollback().await?;
+                return Ok(());
+            };
+
+            let used: Amount = serde_json::from_slice(&bytes)?;
+            let remaining = used.checked_sub(charge.amount).unwrap_or(Amount::ZERO);
+
+            let written = tx
+                .kv_compare_and_write(
+                    QUOTA_KV_NAMESPACE,
+                    &secondary_namespace,
+                    &key,
+                    Some(&bytes),
+                    &serde_json::to_vec(&remaining)?,
+                )
+                .await?;
+
+            if written {
+                tx.commit().await?;
+                return Ok(());
+            }
+
+            tx.rollback().await?;
+        }
+
+        Err(Error::ConcurrentUpdate)
+    }
+
+    /// Remove the quota usage of days before `day` and return how many entries were removed
+    ///
+    /// Only the current day's usage is checked against the limits, older entries would otherwise
+    /// accumulate for every identity that ever used the mint.
+    async fn prune_auth_quotas(&self, day: u64) -> Result<usize, Error> {
+        let mut tx = self.localstore.begin_transaction().await?;
+        let mut removed = 0;
+
+        for operation in [
+            QuotaOperation::Mint,
+            QuotaOperation::Melt,
+            QuotaOperation::BlindAuth,
+        ] {
+            let secondary_namespace = operation.to_string();
+
+            for key in tx.kv_list(QUOTA_KV_NAMESPACE, &secondary_namespace).await? {
+                if quota_key_day(&key).is_some_and(|key_day| key_day < day) {
+                    tx.kv_remove(QUOTA_KV_NAMESPACE, &secondary_namespace, &key)
+                        .await?;
+                    removed += 1;
+                }
+            }
+        }
+
+        tx.commit().await?;
+
+        if removed > 0 {
+            tracing::debug!("Pruned {} quota usage entries of earlier days", removed);
+        }
+
+        Ok(removed)
+    }
+
+    /// Charge the outputs of a mint request to the mint quota of `identity`
+    ///
+    /// Requests without an identity, e.g. authenticated with blind auth, are not charged.
+    pub async fn charge_mint_quota(
+        &self,
+        identity: Option<&AuthIdentity>,
+        input: &MintInput,
+    ) -> Result<Option<QuotaCharge>, Error> {
+        let Some(identity) = identity else {
+            return Ok(None);
+        };
+
+        let outputs = match input {
+            MintInput::Single(request) => &request.outputs,
+            MintInput::Batch(request) => &request.outputs,
+        };
+
+        let Some(keyset_id) = outputs.first().map(|output| output.keyset_id) else {
+            return Ok(None);
+        };
+        let unit = self
+            .get_keyset_info(&keyset_id)
+            .ok_or(Error::UnknownKeySet)?
+            .unit;
+        let amount = Amount::try_sum(outputs.iter().map(|output| output.amount))?;
+
+        self.charge_auth_quota(identity, QuotaOperation::Mint, &unit, amount)
+            .await
+    }
+
+    /// Charge the amount of a melt quote to the melt quota of `identity`
+    ///
+    /// Requests without an identity, e.g. authenticated with blind auth, are not charged.
+    pub async fn charge_melt_quota(
+        &self,
+        identity: Option<&AuthIdentity>,
+        quote_id: &QuoteId,
+    ) -> Result<Option<QuotaCharge>, Error> {
+        let Some(identity) = identity else {
+            return Ok(None);
+        };
+
+        let quote = self
+            .localstore
+            .get_melt_quote(quote_id)
+            .await?
+            .ok_or(Error::UnknownQuote)?;
+
+        self.charge_auth_quota(
+            identity,
+            QuotaOperation::Melt,
+            &quote.unit,
+            quote.amount().into(),
+        )
+        .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+    use std::time::Duration;
+
+    use cdk_common::clock::SimulatedClock;
+
+    use super::*;
+    use crate::test_helpers::mint::create_test_mint;
+
+    #[tokio::test]
+    async fn charge_auth_quota_enforces_daily_limit() {
+        let mint = create_test_mint()
+            .await
+            .unwrap()
+            .with_auth_quotas(AuthQuotas {
+                max_mint_per_day: Some(Amount::from(100)),
+                ..Default::default()
+            });
+        let alice = AuthIdentity::new("alice@example.com");
+        let bob = AuthIdentity::new("bob@example.com");
+        let unit = CurrencyUnit::Sat;
+
+        let charge = mint
+            .charge_auth_quota(&alice, QuotaOperation::Mint, &unit, Amount::from(60))
+            .await
+            .unwrap()
+            .expect("mint is limited");
+
+        let err = mint
+            .charge_auth_quota(&alice, QuotaOperation::Mint, &unit, Amount::from(50))
+            .await
+            .unwrap_err();
+        assert!(matches!(
+            err,
+            Error::QuotaExceeded {
+                requested: 50,
+                used: 60,
+                limit: 100,
+                ..
+            }
+        ));
+
+        // Other identities and unlimited operations are not affected
+        mint.charge_auth_quota(&bob, QuotaOperation::Mint, &unit, Amount::from(100))
+            .await
+            .unwrap();
+        assert!(mint
+            .charge_auth_quota(&alice, QuotaOperation::Melt, &unit, Amount::from(1_000))
+            .await
+            .unwrap()
+            .is_none());
+
+        mint.refund_auth_quota(&charge).await.unwrap();
+        assert_eq!(
+            mint.auth_quota_usage(&alice, QuotaOperation::Mint, &unit)
+                .await
+                .unwrap(),
+            Amount::ZERO
+        );
+        mint.charge_auth_quota(&alice, QuotaOperation::Mint, &unit, Amount::from(100))
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn charge_auth_quota_prunes_earlier_days() {
+        let clock = Arc::new(SimulatedClock::new(10 * SECONDS_PER_DAY));
+        let mint = create_test_mint()
+            .await
+            .unwrap()
+            .with_clock(clock.clone())
+            .with_auth_quotas(AuthQuotas {
+                max_mint_per_day: Some(Amount::from(100)),
+                ..Default::default()
+            });
+        let unit = CurrencyUnit::Sat;
+
+        for subject in ["alice@example.com", "bob@example.com"] {
+            mint.charge_auth_quota(
+                &AuthIdentity::new(subject),
+                QuotaOperation::Mint,
+                &unit,
+                Amount::from(10),
+            )
+            .await
+            .unwrap();
+        }
+
+        clock.advance(Duration::from_secs(SECONDS_PER_DAY));
+        let alice = AuthIdentity::new("alice@example.com");
+        mint.charge_auth_quota(&alice, QuotaOperation::Mint, &unit, Amount::from(10))
+            .await
+            .unwrap();
+
+        let keys = mint
+            .localstore
+            .kv_list(QUOTA_KV_NAMESPACE, &QuotaOperation::Mint.to_string())
+            .await
+            .unwrap();
+        assert_eq!(keys, vec![quota_key(11, &unit, &alice)]);
+    }
+}
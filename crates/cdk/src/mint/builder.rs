@@ -4,6 +4,7 @@ use std::collections::HashMap;
 use std::sync::Arc;
 
 use bitcoin::bip32::DerivationPath;
+use cdk_common::clock::{Clock, SystemClock};
 use cdk_common::database::{DynMintAuthDatabase, DynMintDatabase, MintKeysDatabase};
 use cdk_common::error::Error;
 use cdk_common::nut00::KnownMethod;
@@ -18,10 +19,11 @@ use super::nut19::{self, CachedEndpoint};
 use super::Nuts;
 use crate::amount::Amount;
 use crate::cdk_database;
-use crate::mint::Mint;
+use crate::mint::{AuthQuotas, KeysetConfigChange, Mint, QuoteIdFormat};
 use crate::nuts::{
-    AuthRequired, ContactInfo, CurrencyUnit, MeltMethodSettings, MintInfo, MintMethodSettings,
-    MintVersion, MppMethodSettings, PaymentMethod, ProtectedEndpoint,
+    AuthRequired, ContactInfo, CurrencyUnit, MeltChangePolicy, MeltMethodSettings, MintInfo,
+    MintMethodSettings, MintVersion, MppMethodSettings, PaymentMethod, ProtectedEndpoint,
+    PublicKey,
 };
 use crate::types::PaymentProcessorKey;
 
@@ -57,6 +59,8 @@ pub struct KeysetRotation {
     pub use_keyset_v2: bool,
     /// Optional expiry timestamp (unix seconds)
     pub final_expiry: Option<u64>,
+    /// Optional activation timestamp (unix seconds); the keyset stays inactive until then
+    pub activate_at: Option<u64>,
 }
 
 /// Cashu Mint Builder
@@ -75,6 +79,13 @@ pub struct MintBuilder {
     max_inputs: usize,
     max_outputs: usize,
     max_batch_size: Option<u64>,
+    signing_threads: usize,
+    quote_id_format: QuoteIdFormat,
+    strict_spending_conditions: bool,
+    melt_change_policy: MeltChangePolicy,
+    own_node_ids: Vec<PublicKey>,
+    auth_quotas: AuthQuotas,
+    clock: Arc<dyn Clock>,
 }
 
 impl std::fmt::Debug for MintBuilder {
@@ -118,6 +129,13 @@ impl MintBuilder {
             max_inputs: 1000,
             max_outputs: 1000,
             max_batch_size: None,
+            signing_threads: 1,
+            quote_id_format: QuoteIdFormat::default(),
+            strict_spending_conditions: false,
+            melt_change_policy: MeltChangePolicy::default(),
+            own_node_ids: Vec::new(),
+            auth_quotas: AuthQuotas::default(),
+            clock: Arc::new(SystemClock),
         }
     }
 
@@ -313,6 +331,55 @@ impl MintBuilder {
         self
     }
 
+    /// Set the number of threads used to sign blinded messages
+    ///
+    /// Only used by [`build_with_seed`](Self::build_with_seed). Large output batches are signed
+    /// in parallel across this many threads.
+    pub fn with_signing_threads(mut self, signing_threads: usize) -> Self {
+        self.signing_threads = signing_threads;
+        self
+    }
+
+    /// Set the format of newly created quote ids
+    pub fn with_quote_id_format(mut self, quote_id_format: QuoteIdFormat) -> Self {
+        self.quote_id_format = quote_id_format;
+        self
+    }
+
+    /// Reject malformed spending condition witnesses with specific error codes
+    pub fn with_strict_spending_conditions(mut self, strict: bool) -> Self {
+        self.strict_spending_conditions = strict;
+        self
+    }
+
+    /// Set how the unspent fee reserve of a melt is returned as change
+    pub fn with_melt_change_policy(mut self, melt_change_policy: MeltChangePolicy) -> Self {
+        self.melt_change_policy = melt_change_policy;
+        self
+    }
+
+    /// Reject melt quotes for invoices payable to the mint's own lightning nodes
+    pub fn with_own_node_ids(mut self, node_ids: Vec<PublicKey>) -> Self {
+        self.own_node_ids = node_ids;
+        self
+    }
+
+    /// Set the daily mint, melt and blind auth limits per clear auth identity
+    ///
+    /// Only applied when auth is configured.
+    pub fn with_auth_quotas(mut self, auth_quotas: AuthQuotas) -> Self {
+        self.auth_quotas = auth_quotas;
+        self
+    }
+
+    /// Read the current time from `clock` instead of the system time
+    ///
+    /// The clock is handed on to the built [`Mint`].
+    pub fn with_clock(mut self, clock: Arc<dyn Clock>) -> Self {
+        self.clock = clock;
+        self
+    }
+
     /// Set batch minting settings (NUT-29)
     ///
     /// Configures the maximum number of quotes allowed in a single batch request
@@ -351,30 +418,7 @@ impl MintBuilder {
     /// );
     /// ```
     pub fn configure_unit(&mut self, unit: CurrencyUnit, config: UnitConfig) -> Result<(), Error> {
-        // Validate amounts
-        if config.amounts.is_empty() {
-            return Err(Error::Custom("Amounts list cannot be empty".to_string()));
-        }
-
-        // Check for duplicates and ensure sorted
-        let mut sorted = config.amounts.clone();
-        sorted.sort_unstable();
-        sorted.dedup();
-        if sorted.len() != config.amounts.len() {
-            return Err(Error::Custom(
-                "Amounts list contains duplicates".to_string(),
-            ));
-        }
-        if sorted != config.amounts {
-            return Err(Error::Custom(
-                "Amounts must be sorted in ascending order".to_string(),
-            ));
-        }
-
-        // Check all amounts are positive
-        if config.amounts.contains(&0) {
-            return Err(Error::Custom("Amounts must be greater than 0".to_string()));
-        }
+        validate_amounts(&config.amounts)?;
 
         self.supported_units
             .insert(unit, (config.input_fee_ppk, config.amounts));
@@ -580,6 +624,30 @@ impl MintBuilder {
         Ok(())
     }
 
+    /// Restricts the denominations accepted for a given unit to `amounts`
+    ///
+    /// The unit **MUST** already have been added with a ln backend
+    pub fn set_unit_amounts(
+        &mut self,
+        unit: &CurrencyUnit,
+        amounts: Vec<u64>,
+    ) -> Result<(), Error> {
+        validate_amounts(&amounts)?;
+
+        let (input_fee_ppk, _) = self
+            .supported_units
+            .get(unit)
+            .ok_or(Error::UnsupportedUnit)?;
+
+        self.configure_unit(
+            unit.clone(),
+            UnitConfig {
+                amounts,
+                input_fee_ppk: *input_fee_ppk,
+            },
+        )
+    }
+
     /// Build the mint with the provided signatory
     pub async fn build_with_signatory(
         #[allow(unused_mut)] mut self,
@@ -603,6 +671,7 @@ impl MintBuilder {
                 .find(|k| k.active && k.unit == *unit);
 
             let mut rotate = false;
+            let mut config_changed = false;
 
             if let Some(keyset) = keyset {
                 if keyset.is_expired() {
@@ -618,12 +687,14 @@ impl MintBuilder {
                         fee
                     );
                     rotate = true;
+                    config_changed = true;
                 }
 
                 // Check if amounts match
                 if keyset.amounts != *amounts {
                     tracing::info!("Rotating keyset for unit {} due to amounts mismatch", unit);
                     rotate = true;
+                    config_changed = true;
                 }
 
                 // Check if version matches explicit preference
@@ -645,7 +716,7 @@ impl MintBuilder {
             }
 
             if rotate {
-                signatory
+                let new_keyset = signatory
                     .rotate_keyset(RotateKeyArguments {
                         unit: unit.clone(),
                         amounts: amounts.clone(),
@@ -656,8 +727,26 @@ impl MintBuilder {
                             cdk_common::nut02::KeySetVersion::Version00
                         },
                         final_expiry: None,
+                        activate_at: None,
                     })
                     .await?;
+
+                if let Some(previous) = keyset.filter(|_| config_changed) {
+                    super::keysets::record_keyset_config_change(
+                        &self.localstore,
+                        &KeysetConfigChange {
+                            unit: unit.clone(),
+                            previous_keyset_id: previous.id,
+                            keyset_id: new_keyset.id,
+                            previous_input_fee_ppk: previous.input_fee_ppk,
+                            input_fee_ppk: *fee,
+                            previous_amounts: previous.amounts.clone(),
+                            amounts: amounts.clone(),
+                            changed_at: self.clock.unix_time(),
+                        },
+                    )
+                    .await?;
+                }
             }
         }
 
@@ -674,6 +763,7 @@ impl MintBuilder {
                         cdk_common::nut02::KeySetVersion::Version00
                     },
                     final_expiry: rotation.final_expiry,
+                    activate_at: rotation.activate_at,
                 })
                 .await?;
         }
@@ -702,7 +792,7 @@ impl MintBuilder {
                 tx.commit().await?;
             }
 
-            return Mint::new_with_auth(
+            return Ok(Mint::new_with_auth(
                 self.mint_info,
                 signatory,
                 self.localstore,
@@ -711,9 +801,15 @@ impl MintBuilder {
                 self.max_inputs,
                 self.max_outputs,
             )
-            .await;
+            .await?
+            .with_quote_id_format(self.quote_id_format)
+            .with_strict_spending_conditions(self.strict_spending_conditions)
+            .with_melt_change_policy(self.melt_change_policy)
+            .with_own_node_ids(self.own_node_ids)
+            .with_auth_quotas(self.auth_quotas)
+            .with_clock(self.clock));
         }
-        Mint::new(
+        Ok(Mint::new(
             self.mint_info,
             signatory,
             self.localstore,
@@ -721,7 +817,12 @@ impl MintBuilder {
             self.max_inputs,
             self.max_outputs,
         )
-        .await
+        .await?
+        .with_quote_id_format(self.quote_id_format)
+        .with_strict_spending_conditions(self.strict_spending_conditions)
+        .with_melt_change_policy(self.melt_change_policy)
+        .with_own_node_ids(self.own_node_ids)
+        .with_clock(self.clock))
     }
 
     /// Build the mint with the provided keystore and seed
@@ -736,7 +837,8 @@ impl MintBuilder {
             self.supported_units.clone(),
             self.custom_paths.clone(),
         )
-        .await?;
+        .await?
+        .with_signing_threads(self.signing_threads);
 
         let signatory = Arc::new(cdk_signatory::embedded::Service::new(Arc::new(
             in_memory_signatory,
@@ -746,6 +848,31 @@ impl MintBuilder {
     }
 }
 
+/// Checks that keyset `amounts` are non-empty, positive, sorted and free of duplicates
+fn validate_amounts(amounts: &[u64]) -> Result<(), Error> {
+    if amounts.is_empty() {
+        return Err(Error::Custom("Amounts list cannot be empty".to_string()));
+    }
+
+    if amounts.contains(&0) {
+        return Err(Error::Custom("Amounts must be greater than 0".to_string()));
+    }
+
+    if amounts.windows(2).any(|pair| pair[0] == pair[1]) {
+        return Err(Error::Custom(
+            "Amounts list contains duplicates".to_string(),
+        ));
+    }
+
+    if amounts.windows(2).any(|pair| pair[0] > pair[1]) {
+        return Err(Error::Custom(
+            "Amounts must be sorted in ascending order".to_string(),
+        ));
+    }
+
+    Ok(())
+}
+
 /// Mint and Melt Limits
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub struct MintMeltLimits {
@@ -779,6 +906,7 @@ mod tests {
 
     use async_trait::async_trait;
     use bip39::Mnemonic;
+    use cdk_common::clock::SimulatedClock;
     use cdk_common::nut21::{Method, RoutePath};
     use cdk_common::payment::{
         Bolt11Settings, Bolt12Settings, CreateIncomingPaymentResponse, Event,
@@ -856,7 +984,13 @@ mod tests {
 
     async fn builder_with_bolt11_processor() -> (MintBuilder, Arc<MintSqliteDatabase>) {
         let localstore = Arc::new(memory::empty().await.expect("mint db"));
-        let mut builder = MintBuilder::new(localstore.clone());
+        let builder = bolt11_builder_on(localstore.clone()).await;
+
+        (builder, localstore)
+    }
+
+    async fn bolt11_builder_on(localstore: Arc<MintSqliteDatabase>) -> MintBuilder {
+        let mut builder = MintBuilder::new(localstore);
 
         let settings = SettingsResponse {
             unit: "sat".to_string(),
@@ -880,7 +1014,7 @@ mod tests {
             .await
             .expect("payment processor");
 
-        (builder, localstore)
+        builder
     }
 
     fn seed() -> Vec<u8> {
@@ -1050,6 +1184,85 @@ mod tests {
         ));
     }
 
+    #[tokio::test]
+    async fn test_set_unit_amounts_keeps_fee() {
+        let localstore = Arc::new(memory::empty().await.unwrap());
+        let mut builder = MintBuilder::new(localstore);
+
+        assert!(matches!(
+            builder.set_unit_amounts(&CurrencyUnit::Sat, vec![1, 2, 4]),
+            Err(Error::UnsupportedUnit)
+        ));
+
+        builder
+            .configure_unit(
+                CurrencyUnit::Sat,
+                UnitConfig {
+                    amounts: vec![1, 2, 4, 8, 16, 32],
+                    input_fee_ppk: 100,
+                },
+            )
+            .unwrap();
+
+        for amounts in [vec![], vec![0, 1, 2], vec![1, 2, 2, 4], vec![4, 2, 1]] {
+            assert!(builder
+                .set_unit_amounts(&CurrencyUnit::Sat, amounts)
+                .is_err());
+        }
+        builder
+            .set_unit_amounts(&CurrencyUnit::Sat, vec![1, 2, 4])
+            .unwrap();
+
+        assert_eq!(
+            builder.supported_units.get(&CurrencyUnit::Sat),
+            Some(&(100, vec![1, 2, 4]))
+        );
+    }
+
+    #[tokio::test]
+    async fn test_config_fee_change_rotates_keyset_and_is_audited() {
+        let (builder, localstore) = builder_with_bolt11_processor().await;
+        let seed = seed();
+
+        let mint = builder
+            .build_with_seed(localstore.clone(), &seed)
+            .await
+            .expect("mint");
+        let previous_keyset_id = mint.pubkeys().keysets[0].id;
+        assert!(mint.keyset_config_changes().await.unwrap().is_empty());
+
+        // Restart with an unchanged config keeps the keyset
+        let mint = bolt11_builder_on(localstore.clone())
+            .await
+            .build_with_seed(localstore.clone(), &seed)
+            .await
+            .expect("mint");
+        assert_eq!(mint.pubkeys().keysets[0].id, previous_keyset_id);
+        assert!(mint.keyset_config_changes().await.unwrap().is_empty());
+
+        let mut builder = bolt11_builder_on(localstore.clone())
+            .await
+            .with_clock(Arc::new(SimulatedClock::new(1_000_000)));
+        builder.set_unit_fee(&CurrencyUnit::Sat, 100).unwrap();
+        let mint = builder
+            .build_with_seed(localstore.clone(), &seed)
+            .await
+            .expect("mint");
+
+        let keyset = &mint.pubkeys().keysets[0];
+        assert_ne!(keyset.id, previous_keyset_id);
+
+        let changes = mint.keyset_config_changes().await.unwrap();
+        assert_eq!(changes.len(), 1);
+        assert_eq!(changes[0].unit, CurrencyUnit::Sat);
+        assert_eq!(changes[0].previous_keyset_id, previous_keyset_id);
+        assert_eq!(changes[0].keyset_id, keyset.id);
+        assert_eq!(changes[0].previous_input_fee_ppk, 0);
+        assert_eq!(changes[0].input_fee_ppk, 100);
+        assert_eq!(changes[0].previous_amounts, changes[0].amounts);
+        assert_eq!(changes[0].changed_at, 1_000_000);
+    }
+
     #[tokio::test]
     async fn test_add_payment_processor_bolt11() {
         let localstore = Arc::new(memory::empty().await.unwrap());
@@ -2,11 +2,20 @@ use std::collections::HashMap;
 
 use tracing::instrument;
 
-use super::{CheckStateRequest, CheckStateResponse, Mint, ProofState, State};
+use super::{CheckStateRequest, CheckStateResponse, Mint, ProofState, PublicKey, State};
 use crate::Error;
 
+/// Number of ys resolved per database round trip in [`Mint::check_state`]
+///
+/// Bounds the size of each lookup so large requests against a big proof table
+/// stay fast and do not hold a single long-running query.
+const CHECK_STATE_PAGE_SIZE: usize = 500;
+
 impl Mint {
     /// Check state
+    ///
+    /// Answers the page of at most `limit` ys starting at `cursor` and returns the cursor of
+    /// the next page while ys remain. Without a limit every remaining y is answered.
     #[instrument(skip_all)]
     pub async fn check_state(
         &self,
@@ -26,16 +35,36 @@ impl Mint {
             });
         }
 
-        let states = self.localstore.get_proofs_states(&check_state.ys).await?;
+        let start = check_state.cursor.unwrap_or(0).min(ys_count);
+        let end = match check_state.limit {
+            // A zero limit would never advance the cursor
+            Some(limit) => start.saturating_add(limit.max(1)).min(ys_count),
+            None => ys_count,
+        };
+
+        let mut proof_states = Vec::with_capacity(end - start);
+
+        for page in check_state.ys[start..end].chunks(CHECK_STATE_PAGE_SIZE) {
+            proof_states.extend(self.check_state_page(page).await?);
+        }
+
+        Ok(CheckStateResponse {
+            states: proof_states,
+            next_cursor: (end < ys_count).then_some(end),
+        })
+    }
 
-        if check_state.ys.len() != states.len() {
+    /// Look up the states of one page of ys
+    async fn check_state_page(&self, ys: &[PublicKey]) -> Result<Vec<ProofState>, Error> {
+        let states = self.localstore.get_proofs_states(ys).await?;
+
+        if ys.len() != states.len() {
             tracing::error!("Database did not return states for all proofs");
             return Err(Error::UnknownPaymentState);
         }
 
         // Collect ys that need witness fetching (only spent proofs expose witnesses)
-        let ys_needing_witness: Vec<_> = check_state
-            .ys
+        let ys_needing_witness: Vec<_> = ys
             .iter()
             .zip(states.iter())
             .filter_map(|(y, state)| match state {
@@ -57,9 +86,7 @@ impl Mint {
                 .collect()
         };
 
-        // Construct response without additional queries
-        let proof_states = check_state
-            .ys
+        Ok(ys
             .iter()
             .zip(states.iter())
             .map(|(y, state)| ProofState {
@@ -67,18 +94,14 @@ impl Mint {
                 state: state.unwrap_or(State::Unspent),
                 witness: witness_map.get(y).cloned().flatten(),
             })
-            .collect();
-
-        Ok(CheckStateResponse {
-            states: proof_states,
-        })
+            .collect())
     }
 }
 
 #[cfg(test)]
 mod tests {
     use cdk_common::mint::Operation;
-    use cdk_common::nuts::{CheckStateRequest, ProofsMethods};
+    use cdk_common::nuts::{CheckStateRequest, ProofsMethods, SecretKey};
     use cdk_common::{Amount, State};
 
     use crate::test_helpers::mint::{create_test_mint, mint_test_proofs};
@@ -133,7 +156,7 @@ mod tests {
             }
         }
 
-        let response = mint.check_state(&CheckStateRequest { ys }).await.unwrap();
+        let response = mint.check_state(&CheckStateRequest::new(ys)).await.unwrap();
 
         assert!(response
             .states
@@ -160,4 +183,73 @@ mod tests {
     async fn test_check_state_returns_witness_for_spent_proofs() {
         assert!(check_state_witness_for_proof_state(State::Spent).await);
     }
+
+    #[tokio::test]
+    async fn test_check_state_spans_multiple_pages() {
+        let mint = create_test_mint().await.unwrap();
+        let proofs = mint_test_proofs(&mint, Amount::from(100)).await.unwrap();
+
+        let unknown_ys: Vec<_> = (0..super::CHECK_STATE_PAGE_SIZE + 10)
+            .map(|_| SecretKey::generate().public_key())
+            .collect();
+        let mut ys = unknown_ys;
+        ys.extend(proofs.ys().unwrap());
+
+        let response = mint
+            .check_state(&CheckStateRequest::new(ys.clone()))
+            .await
+            .unwrap();
+
+        assert_eq!(response.next_cursor, None);
+        assert_eq!(
+            response
+                .states
+                .iter()
+                .map(|proof_state| proof_state.y)
+                .collect::<Vec<_>>(),
+            ys
+        );
+        assert!(response
+            .states
+            .iter()
+            .all(|proof_state| proof_state.state == State::Unspent));
+    }
+
+    #[tokio::test]
+    async fn test_check_state_pages_visit_every_y_exactly_once() {
+        let mint = create_test_mint().await.unwrap();
+        let proofs = mint_test_proofs(&mint, Amount::from(100)).await.unwrap();
+
+        let mut ys: Vec<_> = (0..25)
+            .map(|_| SecretKey::generate().public_key())
+            .collect();
+        ys.extend(proofs.ys().unwrap());
+
+        let mut visited = Vec::new();
+        let mut cursor = None;
+        let mut pages = 0;
+
+        loop {
+            let response = mint
+                .check_state(&CheckStateRequest {
+                    ys: ys.clone(),
+                    limit: Some(7),
+                    cursor,
+                })
+                .await
+                .unwrap();
+
+            assert!(response.states.len() <= 7);
+            visited.extend(response.states.iter().map(|proof_state| proof_state.y));
+            pages += 1;
+
+            match response.next_cursor {
+                Some(next) => cursor = Some(next),
+                None => break,
+            }
+        }
+
+        assert_eq!(visited, ys);
+        assert_eq!(pages, ys.len().div_ceil(7));
+    }
 }
@@ -0,0 +1,407 @@
+//! Swaps between units
+//!
+//! With the `conversion` feature, a mint with keysets in several units can accept swaps whose
+//! inputs and outputs are in different units, e.g. `sat` proofs for `usd` proofs. The operator
+//! picks the allowed unit pairs and a [`RateOracle`] quoting the exchange rate. The outputs may
+//! be worth at most the inputs minus the input fee, converted at the quoted rate minus the
+//! configured spread; anything left over from rounding stays with the mint.
+//!
+//! Every conversion is logged and recorded as a [`ConversionRecord`] in the
+//! [`AUDIT_KV_NAMESPACE`] KV namespace, keyed by the operation id of the swap.
+//!
+//! [`AUDIT_KV_NAMESPACE`]: super::AUDIT_KV_NAMESPACE
+
+use std::collections::HashSet;
+use std::sync::Arc;
+
+use cdk_common::rate::{RateOracle, SpotRate};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use super::{Mint, Verification, AUDIT_KV_NAMESPACE};
+use crate::nuts::{CurrencyUnit, Proofs};
+use crate::util::unix_time;
+use crate::{Amount, Error};
+
+/// KV store secondary namespace of conversion records, keyed by operation id
+pub(crate) const CONVERSION_KV_SECONDARY_NAMESPACE: &str = "conversion";
+
+/// Basis points in one
+const BPS: u64 = 10_000;
+
+/// Unit pairs the mint converts between and the rate and spread it converts at
+#[derive(Clone)]
+pub struct UnitConversion {
+    oracle: Arc<dyn RateOracle>,
+    pairs: HashSet<(CurrencyUnit, CurrencyUnit)>,
+    spread_bps: u16,
+}
+
+impl std::fmt::Debug for UnitConversion {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("UnitConversion")
+            .field("oracle", &self.oracle)
+            .field("pairs", &self.pairs)
+            .field("spread_bps", &self.spread_bps)
+            .finish()
+    }
+}
+
+impl UnitConversion {
+    /// Convert at the rates of `oracle`, no pairs are allowed until added
+    pub fn new(oracle: Arc<dyn RateOracle>) -> Self {
+        Self {
+            oracle,
+            pairs: HashSet::new(),
+            spread_bps: 0,
+        }
+    }
+
+    /// Allow swaps from `from` to `to`
+    ///
+    /// Only this direction is allowed, add the reverse pair to allow both.
+    pub fn with_pair(mut self, from: CurrencyUnit, to: CurrencyUnit) -> Self {
+        self.pairs.insert((from, to));
+        self
+    }
+
+    /// Keep `spread_bps` basis points of every conversion, capped at 100%
+    pub fn with_spread_bps(mut self, spread_bps: u16) -> Self {
+        self.spread_bps = spread_bps.min(BPS as u16);
+        self
+    }
+
+    /// Spread kept by the mint in basis points
+    pub fn spread_bps(&self) -> u16 {
+        self.spread_bps
+    }
+
+    /// Whether swaps from `from` to `to` are allowed
+    pub fn supports(&self, from: &CurrencyUnit, to: &CurrencyUnit) -> bool {
+        self.pairs.contains(&(from.clone(), to.clone()))
+    }
+
+    /// Convert `amount` of `from` to `to` at the current rate minus the spread
+    ///
+    /// Returns the converted amount and the rate used. Expired rates are refused.
+    pub async fn convert(
+        &self,
+        amount: Amount,
+        from: &CurrencyUnit,
+        to: &CurrencyUnit,
+    ) -> Result<(Amount, SpotRate), Error> {
+        if !self.supports(from, to) {
+            return Err(Error::UnitMismatch);
+        }
+
+        let rate = self.oracle.spot_rate(from, to).await?;
+        if rate.is_expired(unix_time()) {
+            tracing::warn!("Refusing expired rate from {} to {}", from, to);
+            return Err(cdk_common::rate::Error::Expired(from.clone(), to.clone()).into());
+        }
+
+        let converted = self.apply_spread(rate.convert(amount)?);
+
+        Ok((converted, rate))
+    }
+
+    fn apply_spread(&self, amount: Amount) -> Amount {
+        let kept = u128::from(BPS - u64::from(self.spread_bps));
+        // Never larger than `amount`, so it fits in a u64
+        Amount::from((u128::from(amount.to_u64()) * kept / u128::from(BPS)) as u64)
+    }
+}
+
+/// Record of a swap converting between units
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ConversionRecord {
+    /// Operation id of the swap
+    pub operation_id: Uuid,
+    /// Unit of the inputs
+    pub from_unit: CurrencyUnit,
+    /// Unit of the outputs
+    pub to_unit: CurrencyUnit,
+    /// Total of the inputs
+    pub input_amount: Amount,
+    /// Input fee, in the unit of the inputs
+    pub fee: Amount,
+    /// Value of the inputs minus the fee in the output unit, after the spread
+    pub converted_amount: Amount,
+    /// Total of the outputs
+    pub output_amount: Amount,
+    /// Rate the inputs were converted at
+    pub rate: SpotRate,
+    /// Spread kept by the mint in basis points
+    pub spread_bps: u16,
+    /// Unix time of the conversion
+    pub created_time: u64,
+}
+
+impl Mint {
+    /// Accept swaps between the unit pairs of `conversion`
+    pub fn with_unit_conversion(mut self, conversion: UnitConversion) -> Self {
+        self.unit_conversion = Some(Arc::new(conversion));
+        self
+    }
+
+    /// Value of `amount` in `to` at the current conversion rate minus the spread
+    pub async fn convert_amount(
+        &self,
+        amount: Amount<CurrencyUnit>,
+        to: &CurrencyUnit,
+    ) -> Result<Amount<CurrencyUnit>, Error> {
+        let conversion = self.unit_conversion.as_ref().ok_or(Error::UnitMismatch)?;
+
+        let (converted, _) = conversion
+            .convert(amount.clone().into(), amount.unit(), to)
+            .await?;
+
+        Ok(converted.with_unit(to.clone()))
+    }
+
+    /// Verify a swap converting between units is balanced
+    ///
+    /// Returns `None` if inputs and outputs are in the same unit, which is verified by
+    /// [`Mint::verify_transaction_balanced`] instead.
+    pub(crate) async fn verify_conversion_balanced(
+        &self,
+        input_verification: &Verification,
+        output_verification: &Verification,
+        inputs: &Proofs,
+        operation_id: Uuid,
+    ) -> Result<Option<ConversionRecord>, Error> {
+        let from_unit = input_verification.amount.unit();
+        let to_unit = output_verification.amount.unit();
+
+        if from_unit == to_unit {
+            return Ok(None);
+        }
+
+        let conversion = self.unit_conversion.as_ref().ok_or_else(|| {
+            tracing::debug!(
+                "Output unit {:?} does not match input unit {:?}",
+                to_unit,
+                from_unit
+            );
+            Error::UnitMismatch
+        })?;
+
+        let fee = self.get_proofs_fee(inputs).await?.total;
+        let input_amount: Amount = input_verification.amount.clone().into();
+        let output_amount: Amount = output_verification.amount.clone().into();
+
+        let (converted_amount, rate) = conversion
+            .convert(input_amount.try_sub(fee)?, from_unit, to_unit)
+            .await?;
+
+        if output_amount > converted_amount {
+            return Err(Error::TransactionUnbalanced(
+                input_amount.to_u64(),
+                output_amount.to_u64(),
+                fee.to_u64(),
+            ));
+        }
+
+        Ok(Some(ConversionRecord {
+            operation_id,
+            from_unit: from_unit.clone(),
+            to_unit: to_unit.clone(),
+            input_amount,
+            fee,
+            converted_amount,
+            output_amount,
+            rate,
+            spread_bps: conversion.spread_bps(),
+            created_time: self.unix_time(),
+        }))
+    }
+
+    /// Record of the swap `operation_id`, if it converted between units
+    pub async fn conversion_record(
+        &self,
+        operation_id: &Uuid,
+    ) -> Result<Option<ConversionRecord>, Error> {
+        self.localstore
+            .kv_read(
+                AUDIT_KV_NAMESPACE,
+                CONVERSION_KV_SECONDARY_NAMESPACE,
+                &operation_id.to_string(),
+            )
+            .await?
+            .map(|bytes| serde_json::from_slice(&bytes).map_err(Error::from))
+            .transpose()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use bip39::Mnemonic;
+    use cdk_common::amount::SplitTarget;
+    use cdk_common::mint::OperationKind;
+    use cdk_common::nut00::KnownMethod;
+    use cdk_common::rate::StaticRates;
+    use cdk_common::{PaymentMethod, PreMintSecrets, SwapRequest};
+    use cdk_fake_wallet::FakeWallet;
+
+    use super::*;
+    use crate::mint::{MintBuilder, MintMeltLimits};
+    use crate::test_helpers::mint::mint_test_proofs;
+    use crate::types::{FeeReserve, QuoteTTL};
+
+    /// Started mint with `sat` and `usd` keysets converting at `conversion`
+    async fn create_conversion_mint(conversion: UnitConversion) -> Mint {
+        let db = Arc::new(cdk_sqlite::mint::memory::empty().await.unwrap());
+        let mut mint_builder = MintBuilder::new(db.clone());
+
+        for unit in [CurrencyUnit::Sat, CurrencyUnit::Usd] {
+            let backend = FakeWallet::new(
+                FeeReserve {
+                    min_fee_reserve: 1.into(),
+                    percent_fee_reserve: 1.0,
+                },
+                HashMap::default(),
+                HashSet::default(),
+                2,
+                unit.clone(),
+            );
+            mint_builder
+                .add_payment_processor(
+                    unit,
+                    PaymentMethod::Known(KnownMethod::Bolt11),
+                    MintMeltLimits::new(1, 10_000),
+                    Arc::new(backend),
+                )
+                .await
+                .unwrap();
+        }
+
+        let seed = Mnemonic::generate(12).unwrap().to_seed_normalized("");
+        let mint = mint_builder
+            .build_with_seed(db, &seed)
+            .await
+            .unwrap()
+            .with_unit_conversion(conversion);
+        mint.set_quote_ttl(QuoteTTL::new(10000, 10000))
+            .await
+            .unwrap();
+        mint.start().await.unwrap();
+
+        mint
+    }
+
+    /// Blinded messages for `amount` in the active keyset of `unit`
+    fn outputs(mint: &Mint, unit: &CurrencyUnit, amount: u64) -> PreMintSecrets {
+        let keyset_id = *mint.get_active_keysets().get(unit).unwrap();
+        let amounts = (0..32).map(|n| 2u64.pow(n)).collect::<Vec<_>>();
+
+        PreMintSecrets::random(
+            keyset_id,
+            Amount::from(amount),
+            &SplitTarget::None,
+            &(0, amounts).into(),
+        )
+        .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_swap_sat_to_usd() {
+        let rates = StaticRates::new()
+            .with_rate(CurrencyUnit::Sat, CurrencyUnit::Usd, 1_000, 100)
+            .unwrap();
+        let mint = create_conversion_mint(
+            UnitConversion::new(Arc::new(rates)).with_pair(CurrencyUnit::Sat, CurrencyUnit::Usd),
+        )
+        .await;
+
+        let inputs = mint_test_proofs(&mint, Amount::from(1_000)).await.unwrap();
+
+        // More than the inputs are worth is refused
+        let too_much = outputs(&mint, &CurrencyUnit::Usd, 101);
+        assert!(matches!(
+            mint.process_swap_request(SwapRequest::new(
+                inputs.clone(),
+                too_much.blinded_messages()
+            ))
+            .await,
+            Err(Error::TransactionUnbalanced(1_000, 101, 0))
+        ));
+
+        let premint = outputs(&mint, &CurrencyUnit::Usd, 100);
+        let response = mint
+            .process_swap_request(SwapRequest::new(inputs, premint.blinded_messages()))
+            .await
+            .unwrap();
+        let usd_keyset = *mint.get_active_keysets().get(&CurrencyUnit::Usd).unwrap();
+        assert!(response
+            .signatures
+            .iter()
+            .all(|signature| signature.keyset_id == usd_keyset));
+        assert_eq!(
+            Amount::try_sum(response.signatures.iter().map(|signature| signature.amount)).unwrap(),
+            Amount::from(100)
+        );
+
+        // The ledger records the swap in the input unit
+        let operations = mint
+            .localstore
+            .get_completed_operations_by_kind(OperationKind::Swap)
+            .await
+            .unwrap();
+        assert_eq!(operations.len(), 1);
+        let operation = &operations[0];
+        assert_eq!(operation.unit(), Some(&CurrencyUnit::Sat));
+        assert_eq!(operation.total_redeemed(), Amount::from(1_000));
+        assert_eq!(operation.total_issued(), Amount::from(1_000));
+        assert_eq!(operation.fee_collected(), Amount::ZERO);
+
+        let record = mint
+            .conversion_record(operation.id())
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(record.from_unit, CurrencyUnit::Sat);
+        assert_eq!(record.to_unit, CurrencyUnit::Usd);
+        assert_eq!(record.converted_amount, Amount::from(100));
+        assert_eq!(record.output_amount, Amount::from(100));
+    }
+
+    #[tokio::test]
+    async fn test_unit_conversion_spread() {
+        let rates = StaticRates::new()
+            .with_rate(CurrencyUnit::Sat, CurrencyUnit::Usd, 1_000, 100)
+            .unwrap();
+        let conversion = UnitConversion::new(Arc::new(rates))
+            .with_pair(CurrencyUnit::Sat, CurrencyUnit::Usd)
+            .with_spread_bps(100);
+
+        let (converted, rate) = conversion
+            .convert(Amount::from(10_000), &CurrencyUnit::Sat, &CurrencyUnit::Usd)
+            .await
+            .unwrap();
+        assert_eq!(converted, Amount::from(990));
+        assert_eq!((rate.from_amount, rate.to_amount), (1_000, 100));
+
+        // Only the configured direction is allowed
+        assert!(matches!(
+            conversion
+                .convert(Amount::from(100), &CurrencyUnit::Usd, &CurrencyUnit::Sat)
+                .await,
+            Err(Error::UnitMismatch)
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_unit_conversion_missing_rate() {
+        let conversion = UnitConversion::new(Arc::new(StaticRates::new()))
+            .with_pair(CurrencyUnit::Sat, CurrencyUnit::Eur);
+
+        assert!(matches!(
+            conversion
+                .convert(Amount::from(100), &CurrencyUnit::Sat, &CurrencyUnit::Eur)
+                .await,
+            Err(Error::Rate(_))
+        ));
+    }
+}
@@ -0,0 +1,111 @@
+//! Fee income accounting
+//!
+//! Input fees and Lightning fee surplus are recorded in a fee ledger separate
+//! from issued and redeemed ecash, so operators can verify their revenue and
+//! record sweeps of it into a designated balance.
+
+use std::collections::HashMap;
+
+use cdk_common::mint::{FeeLedgerEntry, FeeTotals};
+use cdk_common::nuts::CurrencyUnit;
+use tracing::instrument;
+
+use crate::{Amount, Error, Mint};
+
+impl Mint {
+    /// Accumulated fee income per unit
+    #[instrument(skip_all)]
+    pub async fn fee_totals(&self) -> Result<HashMap<CurrencyUnit, FeeTotals>, Error> {
+        Ok(self.localstore.get_fee_totals().await?)
+    }
+
+    /// Record all fee income of `unit` that has not been swept yet as moved into `destination`
+    ///
+    /// Only the fee ledger is updated, moving the funds is up to the operator. Returns the
+    /// recorded amount.
+    #[instrument(skip(self))]
+    pub async fn record_fee_sweep(
+        &self,
+        unit: &CurrencyUnit,
+        destination: &str,
+    ) -> Result<Amount, Error> {
+        let mut tx = self.localstore.begin_transaction().await?;
+        let available = tx.get_fee_totals_for_update(unit).await?.available();
+
+        if available == Amount::ZERO {
+            tx.rollback().await?;
+            return Ok(Amount::ZERO);
+        }
+
+        tx.add_fee_ledger_entry(&FeeLedgerEntry::sweep(
+            unit.clone(),
+            available,
+            destination.to_string(),
+        ))
+        .await?;
+        tx.commit().await?;
+
+        tracing::info!(
+            "Recorded sweep of {} {} of fee income into {}",
+            available,
+            unit,
+            destination
+        );
+
+        Ok(available)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use cdk_common::mint::{FeeLedgerEntry, FeeTotals, Operation};
+    use cdk_common::nuts::CurrencyUnit;
+    use cdk_common::Amount;
+
+    use crate::test_helpers::mint::{create_test_mint, get_active_keyset_id};
+
+    #[tokio::test]
+    async fn test_fee_totals_and_sweep() {
+        let mint = create_test_mint().await.unwrap();
+        let keyset_id = get_active_keyset_id(&mint).await.unwrap();
+
+        let swap = Operation::new_swap(Amount::from(60), Amount::from(64), Amount::from(4));
+        let surplus =
+            FeeLedgerEntry::lightning_surplus(CurrencyUnit::Sat, Amount::from(2), *swap.id());
+
+        let mut tx = mint.localstore().begin_transaction().await.unwrap();
+        tx.add_completed_operation(&swap, &HashMap::from([(keyset_id, Amount::from(4))]))
+            .await
+            .unwrap();
+        tx.add_fee_ledger_entry(&surplus).await.unwrap();
+        tx.commit().await.unwrap();
+
+        let totals = mint.fee_totals().await.unwrap();
+        assert_eq!(
+            totals[&CurrencyUnit::Sat],
+            FeeTotals {
+                input_fees: Amount::from(4),
+                lightning_surplus: Amount::from(2),
+                swept: Amount::ZERO,
+            }
+        );
+
+        let swept = mint
+            .record_fee_sweep(&CurrencyUnit::Sat, "operator")
+            .await
+            .unwrap();
+        assert_eq!(swept, Amount::from(6));
+
+        let totals = mint.fee_totals().await.unwrap();
+        assert_eq!(totals[&CurrencyUnit::Sat].swept, Amount::from(6));
+        assert_eq!(totals[&CurrencyUnit::Sat].available(), Amount::ZERO);
+
+        let swept = mint
+            .record_fee_sweep(&CurrencyUnit::Sat, "operator")
+            .await
+            .unwrap();
+        assert_eq!(swept, Amount::ZERO);
+    }
+}
@@ -1,7 +1,7 @@
 use tracing::instrument;
 
 use crate::mint::nut22::MintAuthRequest;
-use crate::mint::{AuthToken, MintResponse};
+use crate::mint::{AuthToken, BlindSignature, CurrencyUnit, MintResponse, QuotaOperation};
 use crate::{Amount, Error, Mint};
 
 impl Mint {
@@ -19,7 +19,7 @@ impl Mint {
             return Err(Error::ClearAuthRequired);
         };
 
-        self.verify_clear_auth(cat).await?;
+        let identity = self.verify_clear_auth(cat).await?;
 
         let auth_settings = self
             .mint_info()
@@ -36,6 +36,36 @@ impl Mint {
             ));
         }
 
+        let charge = match &identity {
+            Some(identity) => {
+                self.charge_auth_quota(
+                    identity,
+                    QuotaOperation::BlindAuth,
+                    &CurrencyUnit::Auth,
+                    Amount::from(mint_auth_request.amount()),
+                )
+                .await?
+            }
+            None => None,
+        };
+
+        match self.sign_blind_auth_outputs(&mint_auth_request).await {
+            Ok(blind_signatures) => Ok(MintResponse {
+                signatures: blind_signatures,
+            }),
+            Err(err) => {
+                if let Some(charge) = charge {
+                    self.refund_auth_quota(&charge).await?;
+                }
+                Err(err)
+            }
+        }
+    }
+
+    async fn sign_blind_auth_outputs(
+        &self,
+        mint_auth_request: &MintAuthRequest,
+    ) -> Result<Vec<BlindSignature>, Error> {
         let mut blind_signatures = Vec::with_capacity(mint_auth_request.outputs.len());
 
         for blinded_message in mint_auth_request.outputs.iter() {
@@ -47,8 +77,6 @@ impl Mint {
             blind_signatures.push(blind_signature);
         }
 
-        Ok(MintResponse {
-            signatures: blind_signatures,
-        })
+        Ok(blind_signatures)
     }
 }
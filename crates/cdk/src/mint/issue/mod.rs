@@ -7,7 +7,6 @@ use cdk_common::payment::{
     IncomingPaymentOptions, OnchainIncomingPaymentOptions, WaitPaymentResponse,
 };
 use cdk_common::quote_id::QuoteId;
-use cdk_common::util::unix_time;
 use cdk_common::{
     database, ensure_cdk, Amount, BatchMintRequest, BlindedMessage, CurrencyUnit, Error,
     MintQuoteBolt11Response, MintQuoteBolt12Response, MintQuoteOnchainResponse, MintQuoteState,
@@ -160,23 +159,15 @@ impl Mint {
             .get_settings(&unit, &payment_method)
             .ok_or(Error::UnsupportedUnit)?;
 
-        let min_amount = settings.min_amount;
-        let max_amount = settings.max_amount;
-
         // Check amount limits if an amount is provided
         if let Some(amount) = amount {
-            let is_above_max = max_amount.is_some_and(|max_amount| amount > max_amount);
-            let is_below_min = min_amount.is_some_and(|min_amount| amount < min_amount);
-            let is_out_of_range = is_above_max || is_below_min;
-
-            ensure_cdk!(
-                !is_out_of_range,
-                Error::AmountOutofLimitRange(
-                    min_amount.unwrap_or_default(),
-                    max_amount.unwrap_or_default(),
-                    amount,
-                )
-            );
+            Self::check_amount_limits(
+                &payment_method,
+                &unit,
+                settings.min_amount,
+                settings.max_amount,
+                amount,
+            )?;
         }
 
         Ok(())
@@ -200,11 +191,25 @@ impl Mint {
     pub async fn get_mint_quote(
         &self,
         mint_quote_request: MintQuoteRequest,
+    ) -> Result<MintQuoteResponse<QuoteId>, Error> {
+        self.create_mint_quote(mint_quote_request, false).await
+    }
+
+    /// Creates a new mint quote, see [`Mint::get_mint_quote`]
+    ///
+    /// With `hash_description` a bolt11 invoice commits to the hash of the description, as
+    /// required for LNURL-pay.
+    pub(crate) async fn create_mint_quote(
+        &self,
+        mint_quote_request: MintQuoteRequest,
+        hash_description: bool,
     ) -> Result<MintQuoteResponse<QuoteId>, Error> {
         #[cfg(feature = "prometheus")]
         let metrics = super::MintMetricGuard::new("get_mint_quote");
 
         let result = async {
+            self.check_not_in_maintenance()?;
+
             // Use the new getters for cleaner code
             let unit = mint_quote_request.unit();
             let amount = mint_quote_request.amount();
@@ -219,7 +224,7 @@ impl Mint {
 
             let ln = self.get_payment_processor(unit.clone(), payment_method.clone())?;
 
-            let quote_id = QuoteId::new();
+            let quote_id = self.new_quote_id();
 
             let payment_options = match mint_quote_request {
                 MintQuoteRequest::Bolt11(bolt11_request) => {
@@ -235,7 +240,7 @@ impl Mint {
 
                     let mint_ttl = self.quote_ttl().await?.mint_ttl;
 
-                    let quote_expiry = unix_time() + mint_ttl;
+                    let quote_expiry = self.unix_time() + mint_ttl;
 
                     let settings = ln.get_settings().await?;
 
@@ -252,6 +257,7 @@ impl Mint {
                         description,
                         amount: bolt11_request.amount.with_unit(unit.clone()),
                         unix_expiry: Some(quote_expiry),
+                        hash_description,
                     };
 
                     IncomingPaymentOptions::Bolt11(bolt11_options)
@@ -300,7 +306,7 @@ impl Mint {
                     }
 
                     let mint_ttl = self.quote_ttl().await?.mint_ttl;
-                    let quote_expiry = unix_time() + mint_ttl;
+                    let quote_expiry = self.unix_time() + mint_ttl;
 
                     // Convert extra serde_json::Value to JSON string if not null
                     let extra_json = if request.extra.is_null() {
@@ -315,6 +321,7 @@ impl Mint {
                         amount: request.amount.map(|a| a.with_unit(unit.clone())),
                         unix_expiry: Some(quote_expiry),
                         extra_json,
+                        quote_id: quote_id.clone(),
                     };
 
                     IncomingPaymentOptions::Custom(Box::new(custom_options))
@@ -334,7 +341,7 @@ impl Mint {
                     Error::InvalidPaymentRequest
                 })?;
 
-            let now = unix_time();
+            let now = self.unix_time();
             let quote = MintQuote::new(
                 Some(quote_id),
                 create_invoice_response.request.to_string(),
@@ -649,6 +656,8 @@ impl Mint {
         let metrics = super::MintMetricGuard::new("process_mint_request");
 
         let result = async {
+            self.check_not_in_maintenance()?;
+
             // Phase 1: Validate input structure
             input.validate()?;
 
@@ -846,22 +855,13 @@ impl Mint {
                     .get_settings(&batch_unit, &batch_method)
                     .ok_or(Error::UnsupportedUnit)?;
 
-                let min_amount = settings.min_amount;
-                let max_amount = settings.max_amount;
-                let operation_amount: Amount = outputs_amount.clone().into();
-                let is_above_max =
-                    max_amount.is_some_and(|max_amount| operation_amount > max_amount);
-                let is_below_min =
-                    min_amount.is_some_and(|min_amount| operation_amount < min_amount);
-
-                ensure_cdk!(
-                    !(is_above_max || is_below_min),
-                    Error::AmountOutofLimitRange(
-                        min_amount.unwrap_or_default(),
-                        max_amount.unwrap_or_default(),
-                        operation_amount,
-                    )
-                );
+                Self::check_amount_limits(
+                    &batch_method,
+                    &batch_unit,
+                    settings.min_amount,
+                    settings.max_amount,
+                    outputs_amount.clone().into(),
+                )?;
             }
 
             if outputs_amount.value() > total_expected_value {
@@ -906,7 +906,8 @@ impl Mint {
             // For batch minting, outputs are shared across all quotes and should be persisted once.
             if input.is_batch() {
                 let batch_operation =
-                    Operation::new_batch_mint(outputs_amount.clone().into(), batch_method.clone());
+                    Operation::new_batch_mint(outputs_amount.clone().into(), batch_method.clone())
+                        .with_unit(batch_unit.clone());
                 tx.add_blinded_messages(None, input.outputs(), &batch_operation)
                     .await?;
                 tx.add_blind_signatures(&blinded_secrets, &all_blind_signatures, None)
@@ -949,7 +950,8 @@ impl Mint {
                 let operation = Operation::new_mint(
                     amount_issued.clone().into(),
                     mint_quote.payment_method.clone(),
-                );
+                )
+                .with_unit(mint_quote.unit.clone());
 
                 if !input.is_batch() {
                     tx.add_blinded_messages(Some(quote_id), input.outputs(), &operation)
@@ -1349,7 +1351,11 @@ mod batch_mint_tests {
             .expect_err("onchain mint operation below min_amount must be rejected");
 
         assert!(
-            matches!(err, Error::AmountOutofLimitRange(_, _, _)),
+            matches!(
+                err,
+                Error::AmountBelowMinimum { min, amount, .. }
+                    if min == Amount::from(100) && amount == Amount::from(64)
+            ),
             "unexpected error: {err:?}"
         );
     }
@@ -1367,7 +1373,11 @@ mod batch_mint_tests {
             .expect_err("onchain mint operation above max_amount must be rejected");
 
         assert!(
-            matches!(err, Error::AmountOutofLimitRange(_, _, _)),
+            matches!(
+                err,
+                Error::AmountAboveMaximum { max, amount, .. }
+                    if max == Amount::from(100) && amount == Amount::from(128)
+            ),
             "unexpected error: {err:?}"
         );
     }
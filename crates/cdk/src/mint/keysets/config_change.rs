@@ -0,0 +1,86 @@
+//! Audit log of keysets rotated because the configured fee or amounts changed
+
+use cdk_common::database::DynMintDatabase;
+use serde::{Deserialize, Serialize};
+use tracing::instrument;
+
+use super::{CurrencyUnit, Id, Mint};
+use crate::mint::AUDIT_KV_NAMESPACE;
+use crate::Error;
+
+/// KV store secondary namespace of keyset config changes, keyed by new keyset id
+const KEYSET_CONFIG_KV_SECONDARY_NAMESPACE: &str = "keyset_config";
+
+/// Audit log entry of a keyset rotated on startup because its configuration changed
+///
+/// Written by [`MintBuilder`](crate::mint::MintBuilder) when the configured input fee or amounts
+/// of a unit no longer match its active keyset.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct KeysetConfigChange {
+    /// Unit of the keysets
+    pub unit: CurrencyUnit,
+    /// Keyset that was active before the change
+    pub previous_keyset_id: Id,
+    /// Keyset created with the new configuration
+    pub keyset_id: Id,
+    /// Input fee of the previous keyset
+    pub previous_input_fee_ppk: u64,
+    /// Input fee of the new keyset
+    pub input_fee_ppk: u64,
+    /// Amounts of the previous keyset
+    pub previous_amounts: Vec<u64>,
+    /// Amounts of the new keyset
+    pub amounts: Vec<u64>,
+    /// Unix time of the rotation
+    pub changed_at: u64,
+}
+
+/// Write `change` to the audit log
+pub(crate) async fn record_keyset_config_change(
+    localstore: &DynMintDatabase,
+    change: &KeysetConfigChange,
+) -> Result<(), Error> {
+    let mut tx = localstore.begin_transaction().await?;
+    tx.kv_write(
+        AUDIT_KV_NAMESPACE,
+        KEYSET_CONFIG_KV_SECONDARY_NAMESPACE,
+        &change.keyset_id.to_string(),
+        &serde_json::to_vec(change)?,
+    )
+    .await?;
+    tx.commit().await?;
+
+    Ok(())
+}
+
+impl Mint {
+    /// Keysets rotated on startup because their configured fee or amounts changed
+    ///
+    /// Sorted by the time of the change, oldest first.
+    #[instrument(skip(self))]
+    pub async fn keyset_config_changes(&self) -> Result<Vec<KeysetConfigChange>, Error> {
+        let keys = self
+            .localstore
+            .kv_list(AUDIT_KV_NAMESPACE, KEYSET_CONFIG_KV_SECONDARY_NAMESPACE)
+            .await?;
+
+        let mut changes = Vec::with_capacity(keys.len());
+        for key in keys {
+            if let Some(bytes) = self
+                .localstore
+                .kv_read(
+                    AUDIT_KV_NAMESPACE,
+                    KEYSET_CONFIG_KV_SECONDARY_NAMESPACE,
+                    &key,
+                )
+                .await?
+            {
+                changes.push(serde_json::from_slice::<KeysetConfigChange>(&bytes)?);
+            }
+        }
+
+        changes.sort_by_key(|change| change.changed_at);
+
+        Ok(changes)
+    }
+}
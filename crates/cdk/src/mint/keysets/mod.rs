@@ -1,4 +1,9 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use cdk_common::clock::Clock;
 use cdk_signatory::signatory::RotateKeyArguments;
+use tokio::sync::Notify;
 use tracing::instrument;
 
 use super::{
@@ -7,6 +12,13 @@ use super::{
 use crate::Error;
 
 mod auth;
+mod config_change;
+
+pub(crate) use config_change::record_keyset_config_change;
+pub use config_change::KeysetConfigChange;
+
+/// How often the mint checks whether a scheduled keyset is due
+const KEYSET_ACTIVATION_POLL_INTERVAL: Duration = Duration::from_secs(10);
 
 impl Mint {
     /// Retrieve the public keys of the active keyset for distribution to wallet
@@ -78,6 +90,91 @@ impl Mint {
         input_fee_ppk: u64,
         use_keyset_v2: bool,
         final_expiry: Option<u64>,
+    ) -> Result<MintKeySetInfo, Error> {
+        self.rotate_keyset_inner(
+            unit,
+            amounts,
+            input_fee_ppk,
+            use_keyset_v2,
+            final_expiry,
+            None,
+        )
+        .await
+    }
+
+    /// Pre-generate the next keyset for a unit and schedule its activation
+    ///
+    /// The keyset is published as inactive right away so wallets can fetch its keys, and
+    /// replaces the current active keyset once `activate_at` (unix seconds) is reached.
+    #[instrument(skip(self))]
+    pub async fn schedule_keyset_rotation(
+        &self,
+        unit: CurrencyUnit,
+        amounts: Vec<u64>,
+        input_fee_ppk: u64,
+        use_keyset_v2: bool,
+        final_expiry: Option<u64>,
+        activate_at: u64,
+    ) -> Result<MintKeySetInfo, Error> {
+        self.rotate_keyset_inner(
+            unit,
+            amounts,
+            input_fee_ppk,
+            use_keyset_v2,
+            final_expiry,
+            Some(activate_at),
+        )
+        .await
+    }
+
+    /// Next scheduled keyset activation, if any
+    pub(crate) fn next_keyset_activation(&self) -> Option<u64> {
+        self.keysets
+            .load()
+            .iter()
+            .filter_map(|keyset| keyset.activates_at)
+            .min()
+    }
+
+    /// Reload keysets from the signatory, activating any whose scheduled time has passed
+    #[instrument(skip(self))]
+    pub(crate) async fn refresh_keysets(&self) -> Result<(), Error> {
+        let keysets = self.signatory.keysets().await?;
+        self.keysets.store(keysets.keysets.into());
+        Ok(())
+    }
+
+    /// Spawn a task that activates scheduled keysets once they are due
+    pub(crate) fn spawn_keyset_activation(mint: Arc<Mint>, shutdown: Arc<Notify>) {
+        tokio::spawn(async move {
+            let shutdown = shutdown.notified();
+            tokio::pin!(shutdown);
+
+            loop {
+                tokio::select! {
+                    _ = &mut shutdown => break,
+                    _ = mint.clock.sleep(KEYSET_ACTIVATION_POLL_INTERVAL) => {
+                        if !mint.next_keyset_activation().is_some_and(|at| at <= mint.unix_time()) {
+                            continue;
+                        }
+
+                        if let Err(err) = mint.refresh_keysets().await {
+                            tracing::warn!("Could not activate scheduled keysets: {}", err);
+                        }
+                    }
+                }
+            }
+        });
+    }
+
+    async fn rotate_keyset_inner(
+        &self,
+        unit: CurrencyUnit,
+        amounts: Vec<u64>,
+        input_fee_ppk: u64,
+        use_keyset_v2: bool,
+        final_expiry: Option<u64>,
+        activate_at: Option<u64>,
     ) -> Result<MintKeySetInfo, Error> {
         let result = self
             .signatory
@@ -91,11 +188,11 @@ impl Mint {
                     cdk_common::nut02::KeySetVersion::Version00
                 },
                 final_expiry,
+                activate_at,
             })
             .await?;
 
-        let new_keyset = self.signatory.keysets().await?;
-        self.keysets.store(new_keyset.keysets.into());
+        self.refresh_keysets().await?;
 
         Ok(result.into())
     }
@@ -0,0 +1,473 @@
+//! Accounting ledger export
+//!
+//! Turns mint quote payments, paid melt quotes and collected fees into
+//! double-entry postings that can be imported into accounting tools, either as
+//! CSV or as a [beancount](https://beancount.github.io/) ledger.
+
+use std::collections::BTreeSet;
+use std::fmt;
+use std::str::FromStr;
+
+use cdk_common::database::DynMintDatabase;
+use cdk_common::mint::OperationKind;
+use cdk_common::nuts::{CurrencyUnit, PaymentMethod};
+use tracing::instrument;
+
+use crate::{Amount, Error, Mint};
+
+/// Account holding the ecash the mint has issued and not yet redeemed
+pub const ECASH_LIABILITY_ACCOUNT: &str = "Liabilities:Ecash";
+
+/// Account receiving the input fees charged by the mint
+pub const FEE_INCOME_ACCOUNT: &str = "Income:Fees";
+
+/// Kind of a [`LedgerEntry`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum LedgerEntryKind {
+    /// Payment received for a mint quote
+    MintPayment,
+    /// Payment sent for a melt quote
+    MeltPayment,
+    /// Network fee paid while settling a melt
+    RoutingFee,
+    /// Input fee collected by the mint
+    FeeIncome,
+}
+
+impl fmt::Display for LedgerEntryKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::MintPayment => write!(f, "mint_payment"),
+            Self::MeltPayment => write!(f, "melt_payment"),
+            Self::RoutingFee => write!(f, "routing_fee"),
+            Self::FeeIncome => write!(f, "fee_income"),
+        }
+    }
+}
+
+/// A single double-entry posting
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LedgerEntry {
+    /// Unix time of the entry
+    pub time: u64,
+    /// Kind of the entry
+    pub kind: LedgerEntryKind,
+    /// Quote or operation id the entry originates from
+    pub reference: String,
+    /// Account that is debited
+    pub debit: String,
+    /// Account that is credited
+    pub credit: String,
+    /// Amount moved between the accounts
+    pub amount: Amount,
+    /// Unit of the amount
+    pub unit: CurrencyUnit,
+}
+
+/// Output format of a ledger export
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum LedgerFormat {
+    /// One posting per row
+    #[default]
+    Csv,
+    /// Beancount transactions
+    Beancount,
+}
+
+impl FromStr for LedgerFormat {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "csv" => Ok(Self::Csv),
+            "beancount" => Ok(Self::Beancount),
+            other => Err(Error::Custom(format!("Unknown ledger format: {other}"))),
+        }
+    }
+}
+
+impl fmt::Display for LedgerFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Csv => write!(f, "csv"),
+            Self::Beancount => write!(f, "beancount"),
+        }
+    }
+}
+
+impl Mint {
+    /// Ledger entries with a time in `from..to`
+    ///
+    /// See [`ledger_entries`] for how entries are derived and when `fee_unit` is used.
+    #[instrument(skip(self))]
+    pub async fn ledger_entries(
+        &self,
+        from: u64,
+        to: u64,
+        fee_unit: &CurrencyUnit,
+    ) -> Result<Vec<LedgerEntry>, Error> {
+        ledger_entries(&self.localstore, from, to, fee_unit).await
+    }
+}
+
+/// Number of rows read from the database per query while collecting ledger entries
+const LEDGER_PAGE_SIZE: u64 = 500;
+
+/// Collect ledger entries with a time in `from..to` from the mint database
+///
+/// Mint quote payments and paid melt quotes are booked in the unit of their quote,
+/// fees in the unit of the operation they were collected in. Operations recorded
+/// before their unit was stored are booked in `fee_unit`.
+///
+/// The database is queried in pages of `LEDGER_PAGE_SIZE` rows, but every entry
+/// of the range is collected and sorted in memory before it is returned. Export
+/// long histories in several shorter `from..to` ranges.
+pub async fn ledger_entries(
+    localstore: &DynMintDatabase,
+    from: u64,
+    to: u64,
+    fee_unit: &CurrencyUnit,
+) -> Result<Vec<LedgerEntry>, Error> {
+    let mut entries = Vec::new();
+
+    let mut offset = 0;
+    loop {
+        let payments = localstore
+            .get_mint_quote_payments_in_range(from, to, offset, LEDGER_PAGE_SIZE)
+            .await?;
+        let page_len = payments.len() as u64;
+
+        for quote_payment in payments {
+            entries.push(LedgerEntry {
+                time: quote_payment.payment.time,
+                kind: LedgerEntryKind::MintPayment,
+                reference: quote_payment.quote_id.to_string(),
+                debit: asset_account(&quote_payment.payment_method),
+                credit: ECASH_LIABILITY_ACCOUNT.to_string(),
+                unit: quote_payment.payment.amount.unit().clone(),
+                amount: quote_payment.payment.amount.into(),
+            });
+        }
+
+        if page_len < LEDGER_PAGE_SIZE {
+            break;
+        }
+        offset += page_len;
+    }
+
+    let mut offset = 0;
+    loop {
+        let quotes = localstore
+            .get_melt_quotes_paid_in_range(from, to, offset, LEDGER_PAGE_SIZE)
+            .await?;
+        let page_len = quotes.len() as u64;
+
+        for quote in quotes {
+            let Some(paid_time) = quote.paid_time else {
+                continue;
+            };
+
+            entries.push(LedgerEntry {
+                time: paid_time,
+                kind: LedgerEntryKind::MeltPayment,
+                reference: quote.id.to_string(),
+                debit: ECASH_LIABILITY_ACCOUNT.to_string(),
+                credit: asset_account(&quote.payment_method),
+                amount: quote.amount().into(),
+                unit: quote.unit.clone(),
+            });
+        }
+
+        if page_len < LEDGER_PAGE_SIZE {
+            break;
+        }
+        offset += page_len;
+    }
+
+    let mut offset = 0;
+    loop {
+        let operations = localstore
+            .get_completed_operations_in_range(from, to, offset, LEDGER_PAGE_SIZE)
+            .await?;
+        let page_len = operations.len() as u64;
+
+        for operation in operations {
+            let Some(completed_at) = *operation.completed_at() else {
+                continue;
+            };
+            let unit = operation.unit().unwrap_or(fee_unit);
+
+            if operation.kind() == OperationKind::Melt {
+                if let (Some(payment_fee), Some(method)) =
+                    (operation.payment_fee(), operation.payment_method())
+                {
+                    if payment_fee > Amount::ZERO {
+                        entries.push(LedgerEntry {
+                            time: completed_at,
+                            kind: LedgerEntryKind::RoutingFee,
+                            reference: operation.id().to_string(),
+                            debit: ECASH_LIABILITY_ACCOUNT.to_string(),
+                            credit: asset_account(&method),
+                            amount: payment_fee,
+                            unit: unit.clone(),
+                        });
+                    }
+                }
+            }
+
+            if operation.fee_collected() > Amount::ZERO {
+                entries.push(LedgerEntry {
+                    time: completed_at,
+                    kind: LedgerEntryKind::FeeIncome,
+                    reference: operation.id().to_string(),
+                    debit: ECASH_LIABILITY_ACCOUNT.to_string(),
+                    credit: FEE_INCOME_ACCOUNT.to_string(),
+                    amount: operation.fee_collected(),
+                    unit: unit.clone(),
+                });
+            }
+        }
+
+        if page_len < LEDGER_PAGE_SIZE {
+            break;
+        }
+        offset += page_len;
+    }
+
+    entries.sort_by(|a, b| (a.time, a.kind, &a.reference).cmp(&(b.time, b.kind, &b.reference)));
+
+    Ok(entries)
+}
+
+/// Render ledger entries in the requested format
+pub fn format_ledger(entries: &[LedgerEntry], format: LedgerFormat) -> String {
+    match format {
+        LedgerFormat::Csv => format_csv(entries),
+        LedgerFormat::Beancount => format_beancount(entries),
+    }
+}
+
+fn format_csv(entries: &[LedgerEntry]) -> String {
+    let mut out = String::from("timestamp,date,kind,reference,debit,credit,amount,unit\n");
+
+    for entry in entries {
+        out.push_str(&format!(
+            "{},{},{},{},{},{},{},{}\n",
+            entry.time,
+            format_date_time(entry.time),
+            entry.kind,
+            entry.reference,
+            entry.debit,
+            entry.credit,
+            entry.amount,
+            entry.unit,
+        ));
+    }
+
+    out
+}
+
+fn format_beancount(entries: &[LedgerEntry]) -> String {
+    let mut out = String::new();
+
+    // Beancount requires every account to be opened before it is used
+    if let Some(first) = entries.first() {
+        let open_date = format_date(first.time);
+        let accounts: BTreeSet<_> = entries
+            .iter()
+            .flat_map(|entry| [&entry.debit, &entry.credit])
+            .collect();
+
+        for account in accounts {
+            out.push_str(&format!("{open_date} open {account}\n"));
+        }
+    }
+
+    for entry in entries {
+        let commodity = commodity(&entry.unit);
+        out.push_str(&format!(
+            "\n{} * \"{}\" \"{}\"\n  {}  {} {}\n  {}  -{} {}\n",
+            format_date(entry.time),
+            entry.kind,
+            entry.reference,
+            entry.debit,
+            entry.amount,
+            commodity,
+            entry.credit,
+            entry.amount,
+            commodity,
+        ));
+    }
+
+    out
+}
+
+/// Asset account that settles payments of `method`, e.g. `Assets:Bolt11`
+fn asset_account(method: &PaymentMethod) -> String {
+    let mut chars = method.to_string().chars().collect::<Vec<_>>();
+    chars.retain(|c| c.is_ascii_alphanumeric() || *c == '-');
+    if let Some(first) = chars.first_mut() {
+        first.make_ascii_uppercase();
+    }
+
+    format!("Assets:{}", chars.into_iter().collect::<String>())
+}
+
+/// Beancount commodity for `unit`, e.g. `SAT`
+fn commodity(unit: &CurrencyUnit) -> String {
+    unit.to_string()
+        .to_uppercase()
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '-' })
+        .collect()
+}
+
+/// Format a unix time as `YYYY-MM-DD`
+fn format_date(time: u64) -> String {
+    let (year, month, day) = civil_from_days(time / 86_400);
+    format!("{year:04}-{month:02}-{day:02}")
+}
+
+/// Format a unix time as an RFC 3339 UTC timestamp
+fn format_date_time(time: u64) -> String {
+    let seconds = time % 86_400;
+    format!(
+        "{}T{:02}:{:02}:{:02}Z",
+        format_date(time),
+        seconds / 3_600,
+        seconds % 3_600 / 60,
+        seconds % 60
+    )
+}
+
+/// Convert days since the unix epoch into a proleptic Gregorian date
+fn civil_from_days(days: u64) -> (u64, u64, u64) {
+    let z = days + 719_468;
+    let era = z / 146_097;
+    let day_of_era = z % 146_097;
+    let year_of_era =
+        (day_of_era - day_of_era / 1_460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let mp = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = year_of_era + era * 400 + u64::from(month <= 2);
+
+    (year, month, day)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use cdk_common::mint::Operation;
+
+    use super::*;
+    use crate::test_helpers::mint::{create_test_mint, mint_test_proofs};
+
+    fn entry(time: u64, kind: LedgerEntryKind, debit: &str, credit: &str) -> LedgerEntry {
+        LedgerEntry {
+            time,
+            kind,
+            reference: "q1".to_string(),
+            debit: debit.to_string(),
+            credit: credit.to_string(),
+            amount: Amount::from(100),
+            unit: CurrencyUnit::Sat,
+        }
+    }
+
+    #[test]
+    fn test_format_date() {
+        assert_eq!(format_date_time(0), "1970-01-01T00:00:00Z");
+        assert_eq!(format_date_time(951_782_400), "2000-02-29T00:00:00Z");
+        assert_eq!(format_date_time(1_792_144_245), "2026-10-16T10:04:05Z");
+    }
+
+    #[test]
+    fn test_format_ledger() {
+        let entries = vec![
+            entry(
+                1_792_144_245,
+                LedgerEntryKind::MintPayment,
+                "Assets:Bolt11",
+                ECASH_LIABILITY_ACCOUNT,
+            ),
+            entry(
+                1_792_144_300,
+                LedgerEntryKind::FeeIncome,
+                ECASH_LIABILITY_ACCOUNT,
+                FEE_INCOME_ACCOUNT,
+            ),
+        ];
+
+        assert_eq!(
+            format_ledger(&entries, LedgerFormat::Csv),
+            "timestamp,date,kind,reference,debit,credit,amount,unit\n\
+             1792144245,2026-10-16T10:04:05Z,mint_payment,q1,Assets:Bolt11,Liabilities:Ecash,100,sat\n\
+             1792144300,2026-10-16T10:05:00Z,fee_income,q1,Liabilities:Ecash,Income:Fees,100,sat\n"
+        );
+
+        assert_eq!(
+            format_ledger(&entries, LedgerFormat::Beancount),
+            "2026-10-16 open Assets:Bolt11\n\
+             2026-10-16 open Income:Fees\n\
+             2026-10-16 open Liabilities:Ecash\n\
+             \n\
+             2026-10-16 * \"mint_payment\" \"q1\"\n  \
+               Assets:Bolt11  100 SAT\n  \
+               Liabilities:Ecash  -100 SAT\n\
+             \n\
+             2026-10-16 * \"fee_income\" \"q1\"\n  \
+               Liabilities:Ecash  100 SAT\n  \
+               Income:Fees  -100 SAT\n"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_ledger_entries_include_mint_payments() {
+        let mint = create_test_mint().await.unwrap();
+        mint_test_proofs(&mint, Amount::from(100)).await.unwrap();
+
+        let entries = mint
+            .ledger_entries(0, u64::MAX, &CurrencyUnit::Sat)
+            .await
+            .unwrap();
+
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].kind, LedgerEntryKind::MintPayment);
+        assert_eq!(entries[0].debit, "Assets:Bolt11");
+        assert_eq!(entries[0].credit, ECASH_LIABILITY_ACCOUNT);
+        assert_eq!(entries[0].amount, Amount::from(100));
+        assert_eq!(entries[0].unit, CurrencyUnit::Sat);
+
+        let entries = mint
+            .ledger_entries(0, entries[0].time, &CurrencyUnit::Sat)
+            .await
+            .unwrap();
+        assert!(entries.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_ledger_entries_book_fees_in_operation_unit() {
+        let mint = create_test_mint().await.unwrap();
+
+        let swap = Operation::new_swap(Amount::from(60), Amount::from(64), Amount::from(4))
+            .with_unit(CurrencyUnit::Usd);
+        let mut tx = mint.localstore.begin_transaction().await.unwrap();
+        tx.add_completed_operation(&swap, &HashMap::new())
+            .await
+            .unwrap();
+        tx.commit().await.unwrap();
+
+        let entries = mint
+            .ledger_entries(0, u64::MAX, &CurrencyUnit::Sat)
+            .await
+            .unwrap();
+
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].kind, LedgerEntryKind::FeeIncome);
+        assert_eq!(entries[0].amount, Amount::from(4));
+        assert_eq!(entries[0].unit, CurrencyUnit::Usd);
+    }
+}
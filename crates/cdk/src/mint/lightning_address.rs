@@ -0,0 +1,188 @@
+//! Lightning address minting
+//!
+//! A mint can serve LNURL-pay (LUD-06/LUD-16) lightning addresses for its users, so that anyone
+//! can pay `user@mint` with a regular Lightning wallet. Every payment request creates a bolt11
+//! mint quote locked (NUT-20) to the pubkey registered for the user, which only the holder of the
+//! matching secret key can mint once the invoice is paid.
+//!
+//! Invoices commit to the hash of the LNURL metadata, as LUD-06 requires. The quote ids created
+//! for a pubkey are tracked in the KV store so the user can find them, until the quote is issued
+//! or expires unpaid.
+
+use bitcoin::hashes::sha256::Hash as Sha256Hash;
+use bitcoin::hashes::Hash;
+use cdk_common::mint_quote::{MintQuoteRequest, MintQuoteResponse};
+use cdk_common::quote_id::QuoteId;
+use tracing::instrument;
+
+use crate::amount::MsatRounding;
+use crate::mint::Mint;
+use crate::nuts::{CurrencyUnit, MintQuoteBolt11Request, MintQuoteState, PublicKey};
+use crate::{Amount, Error};
+
+/// KV store namespace of the lightning address quotes, keyed by pubkey
+const LIGHTNING_ADDRESS_KV_NAMESPACE: &str = "cdk_mint_lnaddr";
+
+impl Mint {
+    /// Create a sat bolt11 mint quote for a payment to a lightning address
+    ///
+    /// LNURL-pay amounts are in millisatoshis and must be whole sats. The invoice commits to the
+    /// hash of `metadata`. The quote is locked to `pubkey` and recorded in the quotes of
+    /// [`Mint::lightning_address_quotes`].
+    #[instrument(skip(self))]
+    pub async fn create_lightning_address_quote(
+        &self,
+        pubkey: PublicKey,
+        amount_msat: u64,
+        metadata: String,
+    ) -> Result<MintQuoteResponse<QuoteId>, Error> {
+        let amount = Amount::from_msat(amount_msat, MsatRounding::Exact)?;
+
+        let response = self
+            .create_mint_quote(
+                MintQuoteRequest::Bolt11(MintQuoteBolt11Request {
+                    amount,
+                    unit: CurrencyUnit::Sat,
+                    description: Some(metadata),
+                    pubkey: Some(pubkey),
+                }),
+                true,
+            )
+            .await?;
+
+        let quote_id = response.quote().clone();
+
+        let mut tx = self.localstore.begin_transaction().await?;
+        tx.kv_write(
+            LIGHTNING_ADDRESS_KV_NAMESPACE,
+            &pubkey.to_hex(),
+            &lightning_address_key(&quote_id),
+            quote_id.to_string().as_bytes(),
+        )
+        .await?;
+        tx.commit().await?;
+
+        tracing::debug!(
+            "Created lightning address quote {} for {} msat to {}",
+            quote_id,
+            amount_msat,
+            pubkey
+        );
+
+        Ok(response)
+    }
+
+    /// Mint quotes created by payments to the lightning address of `pubkey`
+    ///
+    /// Only quotes that can still be minted are returned. Records of quotes that were issued,
+    /// expired unpaid or no longer exist are removed.
+    #[instrument(skip(self))]
+    pub async fn lightning_address_quotes(
+        &self,
+        pubkey: &PublicKey,
+    ) -> Result<Vec<QuoteId>, Error> {
+        let secondary_namespace = pubkey.to_hex();
+
+        let keys = self
+            .localstore
+            .kv_list(LIGHTNING_ADDRESS_KV_NAMESPACE, &secondary_namespace)
+            .await?;
+
+        let mut quote_ids = Vec::with_capacity(keys.len());
+        for key in keys {
+            let Some(bytes) = self
+                .localstore
+                .kv_read(LIGHTNING_ADDRESS_KV_NAMESPACE, &secondary_namespace, &key)
+                .await?
+            else {
+                continue;
+            };
+
+            let quote_id: QuoteId = String::from_utf8(bytes)
+                .map_err(|err| Error::Custom(err.to_string()))?
+                .parse()
+                .map_err(|_| Error::UnknownQuote)?;
+
+            let mintable = match self.localstore.get_mint_quote(&quote_id).await? {
+                Some(quote) => match quote.state() {
+                    MintQuoteState::Paid => true,
+                    MintQuoteState::Unpaid => quote.expiry > self.unix_time(),
+                    MintQuoteState::Issued => false,
+                },
+                None => false,
+            };
+
+            if mintable {
+                quote_ids.push(quote_id);
+            } else {
+                let mut tx = self.localstore.begin_transaction().await?;
+                tx.kv_remove(LIGHTNING_ADDRESS_KV_NAMESPACE, &secondary_namespace, &key)
+                    .await?;
+                tx.commit().await?;
+            }
+        }
+
+        Ok(quote_ids)
+    }
+}
+
+/// KV key of `quote_id`
+///
+/// Base64 quote ids may contain characters that are not allowed in KV keys, so they are hashed.
+fn lightning_address_key(quote_id: &QuoteId) -> String {
+    Sha256Hash::hash(quote_id.to_string().as_bytes()).to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use cdk_common::nuts::SecretKey;
+
+    use super::*;
+    use crate::test_helpers::mint::create_test_mint;
+
+    #[tokio::test]
+    async fn lightning_address_quote_is_locked_and_recorded() {
+        let mint = create_test_mint().await.unwrap();
+        let pubkey = SecretKey::generate().public_key();
+
+        let metadata = r#"[["text/plain","zap"]]"#.to_string();
+
+        let err = mint
+            .create_lightning_address_quote(pubkey, 1_500, metadata.clone())
+            .await
+            .unwrap_err();
+        assert!(matches!(err, Error::Custom(_)));
+
+        let response = mint
+            .create_lightning_address_quote(pubkey, 21_000, metadata.clone())
+            .await
+            .unwrap();
+
+        // LUD-06 requires the invoice to commit to the hash of the metadata
+        let invoice: crate::Bolt11Invoice = response.request().parse().unwrap();
+        assert!(matches!(
+            invoice.description(),
+            crate::lightning_invoice::Bolt11InvoiceDescriptionRef::Hash(hash)
+                if hash.0 == Sha256Hash::hash(metadata.as_bytes())
+        ));
+
+        let quote = mint
+            .localstore
+            .get_mint_quote(response.quote())
+            .await
+            .unwrap()
+            .expect("quote is stored");
+        assert_eq!(quote.amount.map(|amount| amount.value()), Some(21));
+        assert_eq!(quote.pubkey, Some(pubkey));
+
+        assert_eq!(
+            mint.lightning_address_quotes(&pubkey).await.unwrap(),
+            vec![response.quote().clone()]
+        );
+        assert!(mint
+            .lightning_address_quotes(&SecretKey::generate().public_key())
+            .await
+            .unwrap()
+            .is_empty());
+    }
+}
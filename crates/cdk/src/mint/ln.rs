@@ -102,6 +102,19 @@ impl Mint {
 
         tx.commit().await?;
 
+        // The payments are now durably recorded so the backend can release
+        // any held funds.
+        if let Err(err) = ln
+            .settle_incoming_payment(&new_quote.request_lookup_id)
+            .await
+        {
+            tracing::warn!(
+                "Failed to settle incoming payment for quote {}: {}",
+                new_quote.id,
+                err
+            );
+        }
+
         // Publish notification AFTER transaction commits so subscribers
         // see the committed state when they query.
         if should_notify {
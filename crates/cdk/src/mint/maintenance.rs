@@ -0,0 +1,126 @@
+//! Maintenance mode
+//!
+//! While in maintenance mode the mint keeps serving info, keys and state checks but rejects new
+//! quotes, mints, swaps and melts, so operators can do work on the mint without taking it offline.
+//! It is also used to drain in-flight melts before shutting down.
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use tokio::time::Instant;
+
+use super::{Error, Mint, QuoteId};
+use crate::nuts::MeltQuoteState;
+
+/// Retry after seconds reported when none is configured
+pub const DEFAULT_MAINTENANCE_RETRY_AFTER: u64 = 60;
+
+/// How often pending melts are checked while draining
+const DRAIN_POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+impl Mint {
+    /// Enter maintenance mode, telling wallets to retry after `retry_after` seconds
+    ///
+    /// Passing `None` leaves maintenance mode.
+    pub fn set_maintenance_mode(&self, retry_after: Option<u64>) {
+        match retry_after {
+            Some(retry_after) => tracing::info!(
+                "Entering maintenance mode, retry after {} seconds",
+                retry_after
+            ),
+            None => tracing::info!("Leaving maintenance mode"),
+        }
+
+        self.maintenance.store(retry_after.map(Arc::new));
+    }
+
+    /// Retry after seconds if the mint is in maintenance mode
+    pub fn maintenance_mode(&self) -> Option<u64> {
+        self.maintenance.load().as_deref().copied()
+    }
+
+    /// Reject the request if the mint is in maintenance mode
+    pub(crate) fn check_not_in_maintenance(&self) -> Result<(), Error> {
+        match self.maintenance_mode() {
+            Some(retry_after) => Err(Error::TemporarilyUnavailable { retry_after }),
+            None => Ok(()),
+        }
+    }
+
+    /// Stop accepting new requests and wait up to `timeout` for pending melts to resolve
+    ///
+    /// Enters maintenance mode if the mint is not already in it. Returns the ids of the melt
+    /// quotes that were still pending when the timeout elapsed.
+    pub async fn drain_pending_melts(&self, timeout: Duration) -> Result<Vec<QuoteId>, Error> {
+        if self.maintenance_mode().is_none() {
+            self.set_maintenance_mode(Some(DEFAULT_MAINTENANCE_RETRY_AFTER));
+        }
+
+        let deadline = Instant::now() + timeout;
+
+        loop {
+            let pending = self
+                .localstore
+                .get_melt_quotes()
+                .await?
+                .into_iter()
+                .filter(|quote| quote.state == MeltQuoteState::Pending)
+                .map(|quote| quote.id)
+                .collect::<Vec<_>>();
+
+            if pending.is_empty() || Instant::now() >= deadline {
+                return Ok(pending);
+            }
+
+            tracing::info!("Waiting for {} pending melts to resolve", pending.len());
+            tokio::time::sleep(DRAIN_POLL_INTERVAL.min(deadline - Instant::now())).await;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use cdk_common::nuts::SwapRequest;
+
+    use super::*;
+    use crate::test_helpers::mint::create_test_mint;
+
+    #[tokio::test]
+    async fn test_maintenance_mode_rejects_swaps() {
+        let mint = create_test_mint().await.unwrap();
+
+        mint.set_maintenance_mode(Some(DEFAULT_MAINTENANCE_RETRY_AFTER));
+        let result = mint
+            .process_swap_request(SwapRequest::new(vec![], vec![]))
+            .await;
+        assert!(matches!(
+            result,
+            Err(Error::TemporarilyUnavailable {
+                retry_after: DEFAULT_MAINTENANCE_RETRY_AFTER
+            })
+        ));
+        assert!(!mint.get_active_keysets().is_empty());
+
+        mint.set_maintenance_mode(None);
+        let result = mint
+            .process_swap_request(SwapRequest::new(vec![], vec![]))
+            .await;
+        assert!(matches!(result, Err(Error::TransactionUnbalanced(..))));
+    }
+
+    #[tokio::test]
+    async fn test_drain_pending_melts_enters_maintenance_mode() {
+        let mint = create_test_mint().await.unwrap();
+
+        let pending = mint
+            .drain_pending_melts(Duration::from_secs(1))
+            .await
+            .unwrap();
+
+        assert!(pending.is_empty());
+        assert_eq!(
+            mint.maintenance_mode(),
+            Some(DEFAULT_MAINTENANCE_RETRY_AFTER)
+        );
+    }
+}
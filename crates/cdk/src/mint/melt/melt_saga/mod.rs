@@ -6,10 +6,7 @@ use cdk_common::mint::{MeltFinalizationData, MeltSagaState, Operation, Saga, Sag
 use cdk_common::nut00::KnownMethod;
 use cdk_common::nuts::MeltQuoteState;
 use cdk_common::payment::OutgoingPaymentOptions;
-use cdk_common::{
-    Amount, CurrencyUnit, Error, ProofsMethods, PublicKey, QuoteId, SpendingConditionVerification,
-    State,
-};
+use cdk_common::{Amount, CurrencyUnit, Error, ProofsMethods, PublicKey, QuoteId, State};
 #[cfg(feature = "prometheus")]
 use cdk_prometheus::MintMetricGuard;
 use tokio::sync::Mutex;
@@ -213,7 +210,7 @@ impl MeltSaga<Initial> {
 
         // Verify spending conditions (NUT-10/NUT-11/NUT-14), i.e. P2PK
         // and HTLC (including SIGALL)
-        melt_request.verify_spending_conditions()?;
+        self.mint.verify_spending_conditions(melt_request)?;
 
         let mut tx = self.db.begin_transaction().await?;
 
@@ -997,7 +994,7 @@ impl MeltSaga<PaymentConfirmed> {
         // - Operation recording (add_completed_operation)
         // - Saga deletion
         // - Melt request cleanup
-        let change = shared::finalize_melt_quote(
+        let (change, change_policy) = shared::finalize_melt_quote(
             &self.mint,
             &self.db,
             &self.pubsub,
@@ -1028,7 +1025,14 @@ impl MeltSaga<PaymentConfirmed> {
 
         self.state_data.quote.payment_proof = payment_proof;
         self.state_data.quote.state = MeltQuoteState::Paid;
-        let response = self.state_data.quote.into_response(change);
+        let mut response = self.state_data.quote.into_response(change);
+
+        // Report how the unspent fee reserve was handled on Lightning melts
+        match &mut response {
+            MeltQuoteResponse::Bolt11(response) => response.change_policy = change_policy,
+            MeltQuoteResponse::Bolt12(response) => response.change_policy = change_policy,
+            _ => (),
+        }
 
         Ok(response)
     }
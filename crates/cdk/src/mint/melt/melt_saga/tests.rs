@@ -540,6 +540,7 @@ async fn test_msat_total_spent_rounds_up_when_recording_sat_melt() {
     )
     .await
     .unwrap()
+    .0
     .expect("rounded spent amount should leave change");
     let change_amount =
         Amount::try_sum(change.iter().map(|sig| sig.amount)).expect("change cannot overflow");
@@ -547,6 +548,146 @@ async fn test_msat_total_spent_rounds_up_when_recording_sat_melt() {
     assert_eq!(change_amount, Amount::from(999));
 }
 
+#[tokio::test]
+async fn test_melt_change_policy_keeps_rounded_surplus() {
+    use cdk_common::nuts::{AppliedMeltChangePolicy, MeltChangePolicy};
+
+    use crate::test_helpers::mint::create_test_blinded_messages;
+
+    let policy = MeltChangePolicy::RoundDown {
+        denomination: Amount::from(256),
+    };
+    let mint = create_test_mint()
+        .await
+        .unwrap()
+        .with_melt_change_policy(policy);
+    let proofs = mint_test_proofs(&mint, Amount::from(10_000)).await.unwrap();
+    let quote = create_test_melt_quote(&mint, Amount::from(9_000)).await;
+    let (change_outputs, _premint) = create_test_blinded_messages(&mint, Amount::from(1_023))
+        .await
+        .unwrap();
+    let melt_request = MeltRequest::new(quote.id.clone(), proofs.clone(), Some(change_outputs));
+
+    let verification = mint.verify_inputs(melt_request.inputs()).await.unwrap();
+    let saga = MeltSaga::new(
+        std::sync::Arc::new(mint.clone()),
+        mint.localstore(),
+        mint.pubsub_manager(),
+    );
+
+    let setup_saga = saga
+        .setup_melt(
+            &melt_request,
+            verification,
+            PaymentMethod::Known(KnownMethod::Bolt11),
+        )
+        .await
+        .unwrap();
+    let operation_id = setup_saga.operation_id;
+    let payment_lookup_id = PaymentIdentifier::CustomId("change_policy_lookup".to_string());
+
+    let (change, change_policy) = finalize_melt_quote(
+        &mint,
+        &mint.localstore(),
+        &mint.pubsub_manager(),
+        &quote,
+        Amount::new(9_000, CurrencyUnit::Sat),
+        Some("change_policy_preimage".to_string()),
+        &payment_lookup_id,
+        Some(operation_id),
+    )
+    .await
+    .unwrap();
+
+    let change = change.expect("rounded surplus should leave change");
+    let change_amount =
+        Amount::try_sum(change.iter().map(|sig| sig.amount)).expect("change cannot overflow");
+
+    assert_eq!(change_amount, Amount::from(768));
+    assert_eq!(
+        change_policy,
+        Some(AppliedMeltChangePolicy {
+            policy,
+            kept: Amount::from(232),
+        })
+    );
+
+    let totals = mint.fee_totals().await.unwrap();
+    assert_eq!(
+        totals[&CurrencyUnit::Sat].lightning_surplus,
+        Amount::from(232)
+    );
+}
+
+/// Test: Change signatures carry DLEQ proofs, also when loaded back for the quote status
+#[tokio::test]
+async fn test_melt_change_signatures_carry_dleq_proofs() {
+    use crate::test_helpers::mint::create_test_blinded_messages;
+
+    let mint = create_test_mint().await.unwrap();
+    let proofs = mint_test_proofs(&mint, Amount::from(10_000)).await.unwrap();
+    let quote = create_test_melt_quote(&mint, Amount::from(9_000)).await;
+    let (change_outputs, _premint) = create_test_blinded_messages(&mint, Amount::from(1_023))
+        .await
+        .unwrap();
+    let melt_request = MeltRequest::new(
+        quote.id.clone(),
+        proofs.clone(),
+        Some(change_outputs.clone()),
+    );
+
+    let verification = mint.verify_inputs(melt_request.inputs()).await.unwrap();
+    let saga = MeltSaga::new(
+        std::sync::Arc::new(mint.clone()),
+        mint.localstore(),
+        mint.pubsub_manager(),
+    );
+    let setup_saga = saga
+        .setup_melt(
+            &melt_request,
+            verification,
+            PaymentMethod::Known(KnownMethod::Bolt11),
+        )
+        .await
+        .unwrap();
+    let operation_id = setup_saga.operation_id;
+    let payment_lookup_id = PaymentIdentifier::CustomId("change_dleq_lookup".to_string());
+
+    let (change, _) = finalize_melt_quote(
+        &mint,
+        &mint.localstore(),
+        &mint.pubsub_manager(),
+        &quote,
+        Amount::new(9_000, CurrencyUnit::Sat),
+        Some("change_dleq_preimage".to_string()),
+        &payment_lookup_id,
+        Some(operation_id),
+    )
+    .await
+    .unwrap();
+    let change = change.expect("melt should return change");
+
+    let stored_change = mint
+        .localstore()
+        .get_blind_signatures_for_quote(&quote.id)
+        .await
+        .unwrap();
+    assert_eq!(stored_change, change);
+
+    for (signature, output) in change.iter().zip(&change_outputs) {
+        let key = mint
+            .keyset(&signature.keyset_id)
+            .unwrap()
+            .keys
+            .amount_key(signature.amount)
+            .unwrap();
+        assert!(signature.dleq.is_some());
+        signature
+            .verify_dleq(key, output.blinded_secret)
+            .expect("change DLEQ proof verifies");
+    }
+}
+
 #[tokio::test]
 async fn test_finalizing_recovery_uses_persisted_payment_fee() {
     let mint = create_test_mint().await.unwrap();
@@ -2545,7 +2686,7 @@ async fn test_finalize_melt_quote_duplicate_success_is_idempotent() {
         .unwrap();
     assert_eq!(finalized_quote.state, MeltQuoteState::Paid);
 
-    let duplicate_change = finalize_melt_quote(
+    let (duplicate_change, change_policy) = finalize_melt_quote(
         &mint,
         &mint.localstore(),
         &mint.pubsub_manager(),
@@ -2559,6 +2700,7 @@ async fn test_finalize_melt_quote_duplicate_success_is_idempotent() {
     .unwrap();
 
     assert_eq!(duplicate_change, first_change.change().cloned());
+    assert!(change_policy.is_none());
 }
 
 #[tokio::test]
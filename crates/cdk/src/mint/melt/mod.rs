@@ -12,7 +12,7 @@ use cdk_common::nut05::MeltMethodOptions;
 use cdk_common::nuts::nut17::{Kind, NotificationPayload};
 use cdk_common::payment::{
     Bolt11OutgoingPaymentOptions, Bolt12OutgoingPaymentOptions, CustomOutgoingPaymentOptions,
-    OutgoingPaymentOptions, PaymentIdentifier,
+    OutgoingPaymentOptions, PaymentIdentifier, PaymentQuoteResponse,
 };
 use cdk_common::quote_id::QuoteId;
 use cdk_common::subscription::Params;
@@ -28,18 +28,19 @@ use super::{
     MeltQuoteBolt12Response, MeltRequest, Mint, PaymentMethod,
 };
 use crate::mint::verification::MAX_REQUEST_FIELD_LEN;
-use crate::nuts::MeltQuoteState;
+use crate::nuts::{MeltQuoteState, MintQuoteState, PublicKey};
 use crate::types::PaymentProcessorKey;
-use crate::util::unix_time;
 use crate::{ensure_cdk, Amount, Error};
 
 pub(crate) mod melt_saga;
+mod release;
 pub(crate) mod shared;
 
 #[cfg(test)]
 mod tests;
 
 use melt_saga::{MeltSaga, PaymentOutcome};
+pub use release::{MeltReleaseAuditEntry, AUDIT_KV_NAMESPACE};
 
 fn pending_melt_wait_timeout() -> Duration {
     if cfg!(test) {
@@ -250,26 +251,141 @@ impl Mint {
             _ => {}
         };
 
-        // Compare using raw values since settings use Amount without unit
-        let amount_value = amount.value();
-        let is_above_max = matches!(settings.max_amount, Some(max) if amount_value > max.into());
-        let is_below_min = matches!(settings.min_amount, Some(min) if amount_value < min.into());
-        match is_above_max || is_below_min {
-            true => {
-                tracing::error!(
-                    "Melt amount out of range: {} is not within {} and {}",
-                    amount,
-                    settings.min_amount.unwrap_or_default(),
-                    settings.max_amount.unwrap_or_default(),
-                );
-                Err(Error::AmountOutofLimitRange(
-                    settings.min_amount.unwrap_or_default(),
-                    settings.max_amount.unwrap_or_default(),
-                    amount.into(),
-                ))
-            }
-            false => Ok(()),
+        Self::check_amount_limits(
+            &method,
+            &unit,
+            settings.min_amount,
+            settings.max_amount,
+            amount.into(),
+        )
+        .inspect_err(|err| tracing::error!("Melt amount out of range: {err}"))
+    }
+
+    /// Reject bolt11 invoices that can never be melted before asking the backend for a quote
+    #[instrument(skip_all)]
+    async fn check_bolt11_invoice_acceptable(
+        &self,
+        melt_request: &MeltQuoteBolt11Request,
+    ) -> Result<(), Error> {
+        let MeltQuoteBolt11Request {
+            request,
+            unit,
+            options,
+            ..
+        } = melt_request;
+        let method = PaymentMethod::Known(KnownMethod::Bolt11);
+
+        let expires_at = request
+            .duration_since_epoch()
+            .saturating_add(request.expiry_time())
+            .as_secs();
+        if expires_at <= self.unix_time() {
+            tracing::debug!("Rejecting melt quote for invoice expired at {}", expires_at);
+            return Err(Error::InvoiceExpired(expires_at));
+        }
+
+        if request.amount_milli_satoshis().unwrap_or_default() == 0 {
+            let Some(MeltOptions::Amountless { .. }) = options else {
+                return Err(Error::InvoiceAmountUndefined);
+            };
+
+            let amountless = self
+                .mint_info()
+                .await?
+                .nuts
+                .nut05
+                .get_settings(unit, &method)
+                .is_some_and(|settings| {
+                    matches!(
+                        settings.options,
+                        Some(MeltMethodOptions::Bolt11 { amountless: true })
+                    )
+                });
+            ensure_cdk!(
+                amountless,
+                Error::AmountlessInvoiceNotSupported(unit.clone(), method)
+            );
+        }
+
+        // Invoices of our own mint quotes are settled internally and never reach the node
+        if !self.own_node_ids.is_empty()
+            && self
+                .own_node_ids
+                .contains(&PublicKey::from(request.get_payee_pub_key()))
+            && self
+                .localstore
+                .get_mint_quote_by_request(&request.to_string())
+                .await?
+                .is_none()
+        {
+            tracing::debug!("Rejecting melt quote for invoice payable to own node");
+            return Err(Error::SelfPaymentNotAllowed);
+        }
+
+        Ok(())
+    }
+
+    /// Quote for paying one of the mint's own mint quotes with its bolt11 invoice
+    ///
+    /// Such melts are settled internally against the mint quote, so the backend is not asked
+    /// for a quote and no lightning fee is reserved. Returns `None` if the invoice was not
+    /// issued by this mint for the requested unit.
+    #[instrument(skip_all)]
+    async fn internal_bolt11_payment_quote(
+        &self,
+        melt_request: &MeltQuoteBolt11Request,
+    ) -> Result<Option<PaymentQuoteResponse>, Error> {
+        let MeltQuoteBolt11Request {
+            request,
+            unit,
+            options,
+            ..
+        } = melt_request;
+
+        // Partial and amountless melts are quoted by the backend as usual
+        if options.is_some() {
+            return Ok(None);
         }
+
+        let Some(mint_quote) = self
+            .localstore
+            .get_mint_quote_by_request(&request.to_string())
+            .await?
+        else {
+            return Ok(None);
+        };
+
+        let Some(amount) = mint_quote.amount.clone().filter(|_| {
+            mint_quote.unit == *unit
+                && mint_quote.payment_method == PaymentMethod::Known(KnownMethod::Bolt11)
+        }) else {
+            return Ok(None);
+        };
+
+        ensure_cdk!(
+            !matches!(
+                mint_quote.state(),
+                MintQuoteState::Paid | MintQuoteState::Issued
+            ),
+            Error::RequestAlreadyPaid
+        );
+
+        tracing::debug!(
+            "Invoice of mint quote {} will be settled internally",
+            mint_quote.id
+        );
+
+        Ok(Some(PaymentQuoteResponse {
+            request_lookup_id: Some(PaymentIdentifier::PaymentHash(
+                *request.payment_hash().as_ref(),
+            )),
+            amount,
+            fee: Amount::ZERO.with_unit(unit.clone()),
+            state: MeltQuoteState::Unpaid,
+            extra_json: None,
+            estimated_blocks: None,
+            fee_options: None,
+        }))
     }
 
     /// Get melt quote for BOLT11, BOLT12, or Custom payment methods
@@ -281,6 +397,8 @@ impl Mint {
         &self,
         melt_quote_request: MeltQuoteRequest,
     ) -> Result<MeltQuoteCreateResponse<QuoteId>, Error> {
+        self.check_not_in_maintenance()?;
+
         match melt_quote_request {
             MeltQuoteRequest::Bolt11(bolt11_request) => Ok(MeltQuoteCreateResponse::Bolt11(
                 self.get_melt_bolt11_quote_impl(&bolt11_request).await?,
@@ -329,34 +447,61 @@ impl Mint {
                     Error::UnsupportedUnit
                 })?;
 
+            self.check_bolt11_invoice_acceptable(melt_request).await?;
+
+            // A partial payment (NUT-15) pays part of the invoice amount, the other parts
+            // being paid by other mints, so it can never exceed the invoice amount
+            if let Some(MeltOptions::Mpp { mpp }) = options {
+                let invoice_msat = request
+                    .amount_milli_satoshis()
+                    .ok_or(Error::InvoiceAmountUndefined)?;
+
+                if mpp.amount == Amount::ZERO || u64::from(mpp.amount) > invoice_msat {
+                    tracing::warn!(
+                        "Partial amount {} msat out of range of invoice amount {} msat",
+                        mpp.amount,
+                        invoice_msat
+                    );
+                    return Err(Error::AmountOutofLimitRange(
+                        Amount::from(1),
+                        Amount::from(invoice_msat),
+                        mpp.amount,
+                    ));
+                }
+            }
+
             // Pre-generate the quote id so we can pass it to the backend in both
             // `get_payment_quote` and the eventual `make_payment`, and use the same
             // id when we persist the quote below.
-            let quote_id = cdk_common::QuoteId::new();
-
-            let bolt11 = Bolt11OutgoingPaymentOptions {
-                bolt11: melt_request.request.clone(),
-                max_fee_amount: None,
-                timeout_secs: None,
-                melt_options: melt_request.options,
-                quote_id: quote_id.clone(),
-            };
+            let quote_id = self.new_quote_id();
+
+            let payment_quote = match self.internal_bolt11_payment_quote(melt_request).await? {
+                Some(payment_quote) => payment_quote,
+                None => {
+                    let bolt11 = Bolt11OutgoingPaymentOptions {
+                        bolt11: melt_request.request.clone(),
+                        max_fee_amount: None,
+                        timeout_secs: None,
+                        melt_options: melt_request.options,
+                        quote_id: quote_id.clone(),
+                    };
+
+                    ln.get_payment_quote(
+                        &melt_request.unit,
+                        OutgoingPaymentOptions::Bolt11(Box::new(bolt11)),
+                    )
+                    .await
+                    .map_err(|err| {
+                        tracing::error!(
+                            "Could not get payment quote for mint quote, {} bolt11, {}",
+                            unit,
+                            err
+                        );
 
-            let payment_quote = ln
-                .get_payment_quote(
-                    &melt_request.unit,
-                    OutgoingPaymentOptions::Bolt11(Box::new(bolt11)),
-                )
-                .await
-                .map_err(|err| {
-                    tracing::error!(
-                        "Could not get payment quote for mint quote, {} bolt11, {}",
-                        unit,
                         err
-                    );
-
-                    err
-                })?;
+                    })?
+                }
+            };
 
             if payment_quote.unit() != unit {
                 return Err(Error::UnitMismatch);
@@ -385,7 +530,7 @@ impl Mint {
                 unit.clone(),
                 quote_amount.clone(),
                 quote_fee,
-                unix_time() + melt_ttl,
+                self.unix_time() + melt_ttl,
                 payment_quote.request_lookup_id.clone(),
                 *options,
                 PaymentMethod::Known(KnownMethod::Bolt11),
@@ -403,6 +548,24 @@ impl Mint {
             );
 
             let mut tx = self.localstore.begin_transaction().await?;
+
+            // Only quotes that are paid or being paid block a new quote for the invoice. Unpaid
+            // ones do not: a wallet may ask again after losing a quote, MPP splits an invoice
+            // over several quotes, and the melt itself locks all quotes of the invoice so it
+            // is not paid twice.
+            if let Some(request_lookup_id) = quote.request_lookup_id.as_ref() {
+                for existing in tx
+                    .get_melt_quotes_by_request_lookup_id(request_lookup_id)
+                    .await?
+                {
+                    match existing.state {
+                        MeltQuoteState::Paid => return Err(Error::RequestAlreadyPaid),
+                        MeltQuoteState::Pending => return Err(Error::PendingQuote),
+                        _ => {}
+                    }
+                }
+            }
+
             tx.add_melt_quote(quote.clone()).await?;
             tx.commit().await?;
 
@@ -448,7 +611,7 @@ impl Mint {
 
             let offer = Offer::from_str(&melt_request.request).map_err(|_| Error::Bolt12parse)?;
 
-            let quote_id = cdk_common::QuoteId::new();
+            let quote_id = self.new_quote_id();
 
             let outgoing_payment_options = Bolt12OutgoingPaymentOptions {
                 offer: offer.clone(),
@@ -501,7 +664,7 @@ impl Mint {
                 unit.clone(),
                 quote_amount.clone(),
                 quote_fee,
-                unix_time() + self.quote_ttl().await?.melt_ttl,
+                self.unix_time() + self.quote_ttl().await?.melt_ttl,
                 payment_quote.request_lookup_id.clone(),
                 *options,
                 PaymentMethod::Known(KnownMethod::Bolt12),
@@ -565,7 +728,7 @@ impl Mint {
             // `PaymentIdentifier::QuoteId(..)`; we validate that echo below and
             // use our locally-generated id as the `MeltQuote.id` so the flow is
             // no longer self-referential via the backend response.
-            let quote_id = QuoteId::new();
+            let quote_id = self.new_quote_id();
 
             let outgoing_payment_options = cdk_common::payment::OnchainOutgoingPaymentOptions {
                 address: melt_request.request.clone(),
@@ -646,7 +809,7 @@ impl Mint {
                 },
                 unit.clone(),
                 payment_quote.amount,
-                unix_time() + melt_ttl,
+                self.unix_time() + melt_ttl,
                 request_lookup_id,
                 payment_quote.extra_json,
                 fee_options,
@@ -715,7 +878,7 @@ impl Mint {
                 Some(extra.to_string())
             };
 
-            let quote_id = cdk_common::QuoteId::new();
+            let quote_id = self.new_quote_id();
 
             let custom_options =
                 OutgoingPaymentOptions::Custom(Box::new(CustomOutgoingPaymentOptions {
@@ -771,7 +934,7 @@ impl Mint {
                 unit.clone(),
                 quote_amount.clone(),
                 quote_fee,
-                unix_time() + melt_ttl,
+                self.unix_time() + melt_ttl,
                 payment_quote.request_lookup_id.clone(),
                 None, // Custom methods don't use options
                 PaymentMethod::from(method.as_str()),
@@ -842,6 +1005,7 @@ impl Mint {
                         request: Some(quote.request.to_string()),
                         unit: Some(quote.unit.clone()),
                         method: PaymentMethod::Known(KnownMethod::Bolt11),
+                        change_policy: None,
                     })
                 }
                 PaymentMethod::Known(KnownMethod::Bolt12) => {
@@ -856,6 +1020,7 @@ impl Mint {
                         request: Some(quote.request.to_string()),
                         unit: Some(quote.unit.clone()),
                         method: PaymentMethod::Known(KnownMethod::Bolt12),
+                        change_policy: None,
                     })
                 }
                 PaymentMethod::Known(KnownMethod::Onchain) => {
@@ -905,6 +1070,8 @@ impl Mint {
     /// Uses MeltSaga typestate pattern for atomic transaction handling with automatic rollback on failure.
     #[instrument(skip_all)]
     pub async fn melt(&self, melt_request: &MeltRequest<QuoteId>) -> Result<PendingMelt, Error> {
+        self.check_not_in_maintenance()?;
+
         // Check max outputs limit (if change outputs are provided)
         if let Some(outputs) = melt_request.outputs() {
             let outputs_count = outputs.len();
@@ -0,0 +1,182 @@
+//! Operator release of failed melts
+//!
+//! A melt whose payment failed on the Lightning side but that the backend keeps reporting as
+//! pending or unknown is never compensated by saga recovery, leaving the input proofs of the
+//! user locked. Once the operator confirmed the failure out of band, the inputs can be released
+//! here instead of editing the database by hand.
+
+use cdk_common::quote_id::QuoteId;
+use cdk_common::{PublicKey, State};
+use serde::{Deserialize, Serialize};
+use tracing::instrument;
+
+use super::shared::release_melt_inputs;
+use crate::mint::Mint;
+use crate::nuts::MeltQuoteState;
+use crate::{Amount, Error};
+
+/// KV store namespace of the operator audit log
+pub const AUDIT_KV_NAMESPACE: &str = "cdk_mint_audit";
+/// KV store secondary namespace of released melts, keyed by quote id
+const MELT_RELEASE_KV_SECONDARY_NAMESPACE: &str = "melt_release";
+
+/// Audit log entry of a melt released by [`Mint::release_failed_melt`]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MeltReleaseAuditEntry {
+    /// Released melt quote
+    pub quote_id: QuoteId,
+    /// Saga of the melt, if it had one
+    pub operation_id: Option<uuid::Uuid>,
+    /// Ys of the input proofs returned to unspent
+    pub released_ys: Vec<PublicKey>,
+    /// Total amount of the released proofs
+    pub amount: Amount,
+    /// Why the operator released the melt
+    pub reason: String,
+    /// Unix time of the release
+    pub released_at: u64,
+}
+
+impl Mint {
+    /// Release the inputs of a melt the operator confirmed failed on the Lightning side
+    ///
+    /// Within one transaction the input proofs are returned to unspent, the change outputs and
+    /// saga are removed, a pending quote is reset to unpaid and a [`MeltReleaseAuditEntry`] is
+    /// written to the [`AUDIT_KV_NAMESPACE`] KV namespace.
+    ///
+    /// Refuses paid quotes, and quotes the payment backend reports as paid. The melt must not be
+    /// in progress in this mint, e.g. it is stuck after a restart.
+    #[instrument(skip(self))]
+    pub async fn release_failed_melt(
+        &self,
+        quote_id: &QuoteId,
+        reason: &str,
+    ) -> Result<MeltReleaseAuditEntry, Error> {
+        let quote = self
+            .localstore
+            .get_melt_quote(quote_id)
+            .await?
+            .ok_or(Error::UnknownQuote)?;
+
+        match quote.state {
+            MeltQuoteState::Paid => return Err(Error::PaidQuote),
+            MeltQuoteState::Unpaid => return Err(Error::UnpaidQuote),
+            _ => {}
+        }
+
+        // Without a lookup id the payment was never handed to the backend
+        if quote.request_lookup_id.is_some()
+            && self.check_melt_payment_status(&quote).await?.status == MeltQuoteState::Paid
+        {
+            tracing::warn!(
+                "Refusing to release melt quote {}, the backend reports it paid",
+                quote_id
+            );
+            return Err(Error::PaidQuote);
+        }
+
+        let saga = self
+            .get_melt_saga_by_quote_id(&quote_id.to_string())
+            .await?;
+        let operation_id = saga.as_ref().map(|saga| saga.operation_id);
+
+        let input_ys = self.localstore.get_proof_ys_by_quote_id(quote_id).await?;
+        let blinded_secrets = match &operation_id {
+            Some(operation_id) => {
+                self.localstore
+                    .get_blinded_secrets_by_operation_id(operation_id)
+                    .await?
+            }
+            None => Vec::new(),
+        };
+
+        let amount = Amount::try_sum(
+            self.localstore
+                .get_proofs_by_ys(&input_ys)
+                .await?
+                .into_iter()
+                .flatten()
+                .map(|proof| proof.amount),
+        )?;
+
+        let entry = MeltReleaseAuditEntry {
+            quote_id: quote_id.clone(),
+            operation_id,
+            released_ys: input_ys.clone(),
+            amount,
+            reason: reason.to_string(),
+            released_at: self.unix_time(),
+        };
+
+        let mut tx = self.localstore.begin_transaction().await?;
+
+        let (proofs_released, reset_quote) = match release_melt_inputs(
+            &mut tx,
+            quote_id,
+            &input_ys,
+            &blinded_secrets,
+            operation_id.as_ref(),
+        )
+        .await
+        {
+            Ok(released) => released,
+            Err(err) => {
+                tx.rollback().await?;
+                return Err(err);
+            }
+        };
+
+        tx.kv_write(
+            AUDIT_KV_NAMESPACE,
+            MELT_RELEASE_KV_SECONDARY_NAMESPACE,
+            &audit_key(quote_id),
+            &serde_json::to_vec(&entry)?,
+        )
+        .await?;
+        tx.commit().await?;
+
+        if proofs_released {
+            for y in &input_ys {
+                self.pubsub_manager.proof_state((*y, State::Unspent));
+            }
+        }
+
+        if let Some(quote) = reset_quote {
+            self.pubsub_manager
+                .melt_quote_status(&quote, None, None, MeltQuoteState::Unpaid);
+        }
+
+        tracing::warn!(
+            "Operator released {} {} of inputs of failed melt quote {}: {}",
+            amount,
+            quote.unit,
+            quote_id,
+            reason
+        );
+
+        Ok(entry)
+    }
+
+    /// Audit log entry of a melt released by [`Mint::release_failed_melt`]
+    pub async fn melt_release_audit_entry(
+        &self,
+        quote_id: &QuoteId,
+    ) -> Result<Option<MeltReleaseAuditEntry>, Error> {
+        self.localstore
+            .kv_read(
+                AUDIT_KV_NAMESPACE,
+                MELT_RELEASE_KV_SECONDARY_NAMESPACE,
+                &audit_key(quote_id),
+            )
+            .await?
+            .map(|bytes| serde_json::from_slice(&bytes).map_err(Error::from))
+            .transpose()
+    }
+}
+
+/// KV key of the audit log entry of `quote_id`
+///
+/// Base64 quote ids may end in `=` padding, which is not allowed in KV keys.
+fn audit_key(quote_id: &QuoteId) -> String {
+    quote_id.to_string().trim_end_matches('=').to_string()
+}
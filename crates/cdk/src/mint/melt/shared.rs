@@ -6,11 +6,13 @@
 //!
 //! The functions here ensure consistency between these two code paths.
 
-use cdk_common::amount::MSAT_IN_SAT;
+use cdk_common::amount::MsatRounding;
 use cdk_common::database::mint::Acquired;
-use cdk_common::database::{self, DynMintDatabase};
+use cdk_common::database::{self, DynMintDatabase, DynMintTransaction};
 use cdk_common::mint::{self as mint_types};
-use cdk_common::nuts::{BlindSignature, BlindedMessage, MeltQuoteState, Proofs, State};
+use cdk_common::nuts::{
+    AppliedMeltChangePolicy, BlindSignature, BlindedMessage, MeltQuoteState, Proofs, State,
+};
 use cdk_common::{Amount, CurrencyUnit, Error, PublicKey, QuoteId};
 #[cfg(feature = "prometheus")]
 use cdk_prometheus::METRICS;
@@ -80,14 +82,9 @@ pub(crate) fn total_spent_for_quote_unit(
     total_spent: &Amount<CurrencyUnit>,
     quote_unit: &CurrencyUnit,
 ) -> Result<Amount<CurrencyUnit>, Error> {
-    match (total_spent.unit(), quote_unit) {
-        (spent_unit, quote_unit) if spent_unit == quote_unit => Ok(total_spent.clone()),
-        (CurrencyUnit::Msat, CurrencyUnit::Sat) => {
-            let rounded_sats = total_spent.value().div_ceil(MSAT_IN_SAT);
-            Ok(Amount::new(rounded_sats, CurrencyUnit::Sat))
-        }
-        _ => total_spent.convert_to(quote_unit).map_err(Error::from),
-    }
+    total_spent
+        .convert_to_with_rounding(quote_unit, MsatRounding::Up)
+        .map_err(Error::from)
 }
 
 /// Rolls back a melt quote by removing all setup artifacts and resetting state.
@@ -138,6 +135,61 @@ pub async fn rollback_melt_quote(
 
     let mut tx = db.begin_transaction().await?;
 
+    let (proofs_recovered, quote_option) = match release_melt_inputs(
+        &mut tx,
+        quote_id,
+        input_ys,
+        blinded_secrets,
+        Some(operation_id),
+    )
+    .await
+    {
+        Ok(released) => released,
+        Err(err) => {
+            tx.rollback().await?;
+            return Err(err);
+        }
+    };
+
+    tx.commit().await?;
+
+    // Publish proof state changes
+    if proofs_recovered {
+        for pk in input_ys.iter() {
+            pubsub.proof_state((*pk, State::Unspent));
+        }
+    }
+
+    if let Some(quote) = quote_option {
+        pubsub.melt_quote_status(&quote, None, None, MeltQuoteState::Unpaid);
+    }
+
+    tracing::info!(
+        "Successfully rolled back melt quote {} and deleted saga {}",
+        quote_id,
+        operation_id
+    );
+
+    Ok(())
+}
+
+/// Releases the inputs of a melt quote within `tx`
+///
+/// Removes the input proofs and change blinded messages, resets a pending quote to unpaid and
+/// deletes the melt request and, if given, the saga. Shared by [`rollback_melt_quote`] and the
+/// operator release of failed melts, which both commit `tx` themselves and roll it back on
+/// error.
+///
+/// # Returns
+///
+/// Whether input proofs were removed, and the quote if it was reset to unpaid
+pub(crate) async fn release_melt_inputs(
+    tx: &mut DynMintTransaction,
+    quote_id: &QuoteId,
+    input_ys: &[PublicKey],
+    blinded_secrets: &[PublicKey],
+    operation_id: Option<&uuid::Uuid>,
+) -> Result<(bool, Option<MeltQuote>), Error> {
     let mut proofs_recovered = false;
 
     // Remove input proofs
@@ -170,7 +222,7 @@ pub async fn rollback_melt_quote(
             MeltQuoteState::Pending => {
                 tx.update_melt_quote_state(&mut quote, MeltQuoteState::Unpaid, None)
                     .await?;
-                Some(quote)
+                Some(quote.inner())
             }
             MeltQuoteState::Unpaid | MeltQuoteState::Failed => {
                 // Already in a non-pending state; fall through to saga /
@@ -179,17 +231,13 @@ pub async fn rollback_melt_quote(
                 // twice or when the quote was reset by another path first.
                 None
             }
-            MeltQuoteState::Paid => {
-                tx.rollback().await?;
-                return Err(Error::PaidQuote);
-            }
+            MeltQuoteState::Paid => return Err(Error::PaidQuote),
             state => {
                 tracing::warn!(
                     "Refusing rollback for melt quote {} in unexpected state {}",
                     quote_id,
                     state
                 );
-                tx.rollback().await?;
                 return Err(Error::UnknownPaymentState);
             }
         }
@@ -201,35 +249,18 @@ pub async fn rollback_melt_quote(
     tx.delete_melt_request(quote_id).await?;
 
     // Delete saga state record
-    if let Err(e) = tx.delete_saga(operation_id).await {
-        tracing::warn!(
-            "Failed to delete saga {} during rollback: {}",
-            operation_id,
-            e
-        );
-        // Continue anyway - saga cleanup is best-effort
-    }
-
-    tx.commit().await?;
-
-    // Publish proof state changes
-    if proofs_recovered {
-        for pk in input_ys.iter() {
-            pubsub.proof_state((*pk, State::Unspent));
+    if let Some(operation_id) = operation_id {
+        if let Err(e) = tx.delete_saga(operation_id).await {
+            tracing::warn!(
+                "Failed to delete saga {} during rollback: {}",
+                operation_id,
+                e
+            );
+            // Continue anyway - saga cleanup is best-effort
         }
     }
 
-    if let Some(quote) = quote_option {
-        pubsub.melt_quote_status(&quote, None, None, MeltQuoteState::Unpaid);
-    }
-
-    tracing::info!(
-        "Successfully rolled back melt quote {} and deleted saga {}",
-        quote_id,
-        operation_id
-    );
-
-    Ok(())
+    Ok((proofs_recovered, quote_option))
 }
 
 /// Processes change for a melt operation.
@@ -262,6 +293,7 @@ pub async fn rollback_melt_quote(
 ///
 /// Tuple of:
 /// - `Option<Vec<BlindSignature>>` - Signed change outputs (if any)
+/// - `Amount` - Fee surplus kept by the mint under its change policy
 /// - `Box<dyn MintTransaction>` - New transaction with signatures stored
 ///
 /// # Errors
@@ -281,11 +313,12 @@ pub async fn process_melt_change(
 ) -> Result<
     (
         Option<Vec<BlindSignature>>,
+        Amount,
         Box<dyn database::MintTransaction<database::Error> + Send + Sync>,
     ),
     Error,
 > {
-    let change_target: Amount = match inputs_amount
+    let surplus: Amount = match inputs_amount
         .checked_sub(&total_spent)
         .ok()
         .and_then(|rem| rem.checked_sub(&inputs_fee).ok())
@@ -294,7 +327,7 @@ pub async fn process_melt_change(
         Some(_) => {
             // Exactly 0 change needed - open transaction and return empty result
             let tx = db.begin_transaction().await?;
-            return Ok((None, tx));
+            return Ok((None, Amount::ZERO, tx));
         }
         None => {
             tracing::warn!(
@@ -305,20 +338,36 @@ pub async fn process_melt_change(
                 inputs_fee
             );
             let tx = db.begin_transaction().await?;
-            return Ok((None, tx));
+            return Ok((None, Amount::ZERO, tx));
         }
     };
 
-    if change_outputs.is_empty() {
+    // Whatever the change policy does not return stays with the mint as fee income
+    let (change_target, kept) = mint.melt_change_policy.split_surplus(surplus);
+
+    if kept > Amount::ZERO {
+        tracing::debug!(
+            "Keeping {} of {} fee surplus for quote {} under change policy {}",
+            kept,
+            surplus,
+            quote_id,
+            mint.melt_change_policy
+        );
+    }
+
+    if change_target == Amount::ZERO || change_outputs.is_empty() {
         let tx = db.begin_transaction().await?;
-        return Ok((None, tx));
+        return Ok((None, kept, tx));
     }
 
     // Get keyset configuration
     let fee_and_amounts = get_keyset_fee_and_amounts(&mint.keysets, &change_outputs);
 
-    // Split change into denominations
+    // Split change into denominations, assigned to the outputs largest first so the order is
+    // deterministic and the largest amounts are returned when too few outputs are provided.
+    // Outputs beyond the ones needed are left unsigned.
     let mut amounts: Vec<Amount> = change_target.split(&fee_and_amounts)?;
+    amounts.sort_by(|a, b| b.cmp(a));
 
     if change_outputs.len() < amounts.len() {
         tracing::debug!(
@@ -326,7 +375,6 @@ pub async fn process_melt_change(
             amounts.len(),
             change_outputs.len()
         );
-        amounts.sort_by(|a, b| b.cmp(a));
     }
 
     // Prepare blinded messages with amounts
@@ -350,7 +398,7 @@ pub async fn process_melt_change(
     tx.add_blind_signatures(&blinded_secrets, &change_sigs, Some(quote_id.clone()))
         .await?;
 
-    Ok((Some(change_sigs), tx))
+    Ok((Some(change_sigs), kept, tx))
 }
 
 /// Loads a melt quote and acquires exclusive locks on all related quotes.
@@ -621,7 +669,10 @@ pub(crate) async fn finalize_melt_core(
 ///
 /// # Returns
 ///
-/// `Option<Vec<BlindSignature>>` - Change signatures (if any)
+/// Tuple of:
+/// - `Option<Vec<BlindSignature>>` - Change signatures (if any)
+/// - `Option<AppliedMeltChangePolicy>` - Change policy applied to the fee surplus, `None` if
+///   the quote was already finalized before
 #[allow(clippy::too_many_arguments)]
 pub async fn finalize_melt_quote(
     mint: &super::super::Mint,
@@ -632,7 +683,7 @@ pub async fn finalize_melt_quote(
     payment_proof: Option<String>,
     payment_lookup_id: &cdk_common::payment::PaymentIdentifier,
     operation_id: Option<uuid::Uuid>,
-) -> Result<Option<Vec<BlindSignature>>, Error> {
+) -> Result<(Option<Vec<BlindSignature>>, Option<AppliedMeltChangePolicy>), Error> {
     tracing::info!("Finalizing melt quote {}", quote.id);
 
     let total_spent = total_spent_for_quote_unit(&total_spent, &quote.unit)?;
@@ -679,7 +730,7 @@ pub async fn finalize_melt_quote(
             }
 
             let sigs = db.get_blind_signatures_for_quote(&quote.id).await?;
-            return Ok(if sigs.is_empty() { None } else { Some(sigs) });
+            return Ok(((!sigs.is_empty()).then_some(sigs), None));
         }
     };
 
@@ -703,7 +754,7 @@ pub async fn finalize_melt_quote(
         }
 
         let sigs = db.get_blind_signatures_for_quote(&quote.id).await?;
-        return Ok(if sigs.is_empty() { None } else { Some(sigs) });
+        return Ok(((!sigs.is_empty()).then_some(sigs), None));
     }
 
     #[cfg(feature = "prometheus")]
@@ -749,7 +800,7 @@ pub async fn finalize_melt_quote(
     };
 
     // Process change (if needed) - opens new transaction
-    let (change_sigs, mut tx) = process_melt_change(
+    let (change_sigs, kept, mut tx) = process_melt_change(
         mint,
         db,
         &quote.id,
@@ -798,7 +849,8 @@ pub async fn finalize_melt_quote(
             fee_breakdown.total,
             None,
             Some(quote.payment_method.clone()),
-        );
+        )
+        .with_unit(quote.unit.clone());
 
         operation.add_change(change_amount);
 
@@ -818,6 +870,28 @@ pub async fn finalize_melt_quote(
             tx.rollback().await?;
             return Err(err.into());
         }
+
+        // Whatever was redeemed beyond the input fee, the payment and the change is
+        // fee reserve the mint keeps
+        let surplus = melt_request_info
+            .inputs_amount
+            .value()
+            .saturating_sub(melt_request_info.inputs_fee.value())
+            .saturating_sub(total_spent.value())
+            .saturating_sub(change_amount.to_u64());
+
+        if surplus > 0 {
+            let entry = mint_types::FeeLedgerEntry::lightning_surplus(
+                quote.unit.clone(),
+                Amount::from(surplus),
+                op_id,
+            );
+
+            if let Err(err) = tx.add_fee_ledger_entry(&entry).await {
+                tx.rollback().await?;
+                return Err(err.into());
+            }
+        }
     }
 
     if let Some(op_id) = operation_id {
@@ -845,5 +919,10 @@ pub async fn finalize_melt_quote(
         record_confirmed_payment_metrics(&quote, &total_spent);
     }
 
-    Ok(change_sigs)
+    let change_policy = AppliedMeltChangePolicy {
+        policy: mint.melt_change_policy,
+        kept,
+    };
+
+    Ok((change_sigs, Some(change_policy)))
 }
@@ -1,7 +1,9 @@
 mod htlc_sigall_spending_conditions_tests;
 mod htlc_spending_conditions_tests;
 mod locktime_spending_conditions_tests;
+mod mpp_quote_tests;
 mod onchain_quote_id_tests;
 mod p2pk_sigall_spending_conditions_tests;
 mod p2pk_spending_conditions_tests;
 mod pending_async_melt_tests;
+mod quote_request_tests;
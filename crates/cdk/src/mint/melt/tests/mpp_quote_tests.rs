@@ -0,0 +1,34 @@
+//! Tests for the partial amount of multi-part (NUT-15) melt quotes.
+
+use cdk_common::melt::MeltQuoteRequest;
+use cdk_common::nuts::{CurrencyUnit, MeltOptions};
+use cdk_common::MeltQuoteBolt11Request;
+use cdk_fake_wallet::create_fake_invoice;
+
+use crate::test_helpers::mint::create_test_mint;
+use crate::Error;
+
+fn mpp_request(invoice_msat: u64, partial_msat: u64) -> MeltQuoteRequest {
+    MeltQuoteRequest::Bolt11(MeltQuoteBolt11Request {
+        request: create_fake_invoice(invoice_msat, String::new()),
+        unit: CurrencyUnit::Sat,
+        options: Some(MeltOptions::new_mpp(partial_msat)),
+    })
+}
+
+#[tokio::test]
+async fn mpp_quote_rejects_partial_amount_outside_invoice_amount() {
+    let mint = create_test_mint().await.unwrap();
+
+    let err = mint
+        .get_melt_quote(mpp_request(10_000, 20_000))
+        .await
+        .unwrap_err();
+    assert!(matches!(err, Error::AmountOutofLimitRange(..)));
+
+    let err = mint
+        .get_melt_quote(mpp_request(10_000, 0))
+        .await
+        .unwrap_err();
+    assert!(matches!(err, Error::AmountOutofLimitRange(..)));
+}
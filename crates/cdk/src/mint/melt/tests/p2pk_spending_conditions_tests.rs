@@ -3,8 +3,6 @@
 //! These tests verify that the mint correctly validates basic P2PK spending conditions
 //! during melt operations.
 
-use std::str::FromStr;
-
 use cdk_common::dhke::construct_proofs;
 use cdk_common::melt::MeltQuoteRequest;
 use cdk_common::nuts::SpendingConditions;
@@ -80,8 +78,7 @@ async fn test_p2pk_basic_sig_inputs() {
     );
 
     // Step 5: Create a real melt quote that we'll use for all tests
-    let bolt11_str = "lnbc100n1pnvpufspp5djn8hrq49r8cghwye9kqw752qjncwyfnrprhprpqk43mwcy4yfsqdq5g9kxy7fqd9h8vmmfvdjscqzzsxqyz5vqsp5uhpjt36rj75pl7jq2sshaukzfkt7uulj456s4mh7uy7l6vx7lvxs9qxpqysgqedwz08acmqwtk8g4vkwm2w78suwt2qyzz6jkkwcgrjm3r3hs6fskyhvud4fan3keru7emjm8ygqpcrwtlmhfjfmer3afs5hhwamgr4cqtactdq";
-    let bolt11 = cdk_common::Bolt11Invoice::from_str(bolt11_str).unwrap();
+    let bolt11 = cdk_fake_wallet::create_fake_invoice(10_000, String::new());
 
     let melt_quote_request = cdk_common::MeltQuoteBolt11Request {
         request: bolt11,
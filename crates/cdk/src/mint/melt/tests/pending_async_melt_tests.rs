@@ -373,6 +373,61 @@ async fn pending_melt_wait_times_out_without_settled_progress() {
     );
 }
 
+#[tokio::test]
+async fn release_failed_melt_unlocks_stuck_pending_inputs() {
+    let backend: Arc<dyn MintPayment<Err = payment::Error> + Send + Sync> =
+        Arc::new(NoEventPendingBackend::new(usize::MAX, None));
+    let mint = create_pending_test_mint(backend).await.unwrap();
+    let proofs = mint_test_proofs(&mint, Amount::from(10_000)).await.unwrap();
+    let input_ys = proofs.ys().unwrap();
+    let quote = create_test_melt_quote(&mint, Amount::from(9_000)).await;
+    let melt_request = create_test_melt_request(&proofs, &quote);
+
+    let pending = mint.melt(&melt_request).await.unwrap();
+    assert!(pending.await.is_err());
+
+    let entry = mint
+        .release_failed_melt(&quote.id, "payment failed on node")
+        .await
+        .unwrap();
+    assert_eq!(entry.amount, proofs.total_amount().unwrap());
+    assert_eq!(entry.released_ys.len(), input_ys.len());
+    assert!(entry.operation_id.is_some());
+
+    let stored_quote = mint
+        .localstore()
+        .get_melt_quote(&quote.id)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(stored_quote.state, MeltQuoteState::Unpaid);
+
+    let proof_states = mint
+        .localstore()
+        .get_proofs_states(&input_ys)
+        .await
+        .unwrap();
+    assert!(proof_states.iter().all(|state| state.is_none()));
+
+    assert!(mint
+        .localstore()
+        .get_melt_saga_by_quote_id(&quote.id)
+        .await
+        .unwrap()
+        .is_none());
+
+    assert_eq!(
+        mint.melt_release_audit_entry(&quote.id).await.unwrap(),
+        Some(entry)
+    );
+
+    let err = mint
+        .release_failed_melt(&quote.id, "again")
+        .await
+        .unwrap_err();
+    assert!(matches!(err, Error::UnpaidQuote));
+}
+
 #[tokio::test]
 async fn pending_melt_persists_payment_lookup_id_when_quote_has_none() {
     // Simulates the bolt12 situation: no lookup id exists at quote creation,
@@ -0,0 +1,176 @@
+//! Tests for the checks on bolt11 invoices before a melt quote is created.
+
+use std::str::FromStr;
+
+use bitcoin::hashes::{sha256, Hash};
+use bitcoin::secp256k1::{Secp256k1, SecretKey};
+use cdk_common::melt::MeltQuoteRequest;
+use cdk_common::mint_quote::MintQuoteRequest;
+use cdk_common::nuts::{CurrencyUnit, MeltQuoteState, MintQuoteBolt11Request, PublicKey};
+use cdk_common::{Amount, Bolt11Invoice, MeltQuoteBolt11Request};
+use cdk_fake_wallet::create_fake_invoice;
+use lightning_invoice::{Currency, InvoiceBuilder, PaymentSecret};
+
+use crate::test_helpers::mint::create_test_mint;
+use crate::Error;
+
+fn bolt11_request(invoice: Bolt11Invoice) -> MeltQuoteRequest {
+    MeltQuoteRequest::Bolt11(MeltQuoteBolt11Request {
+        request: invoice,
+        unit: CurrencyUnit::Sat,
+        options: None,
+    })
+}
+
+fn amountless_invoice() -> Bolt11Invoice {
+    let private_key = SecretKey::from_slice(&[0x42; 32]).unwrap();
+
+    InvoiceBuilder::new(Currency::Bitcoin)
+        .description(String::new())
+        .payment_hash(sha256::Hash::hash(b"amountless"))
+        .payment_secret(PaymentSecret([42u8; 32]))
+        .current_timestamp()
+        .min_final_cltv_expiry_delta(144)
+        .build_signed(|hash| Secp256k1::new().sign_ecdsa_recoverable(hash, &private_key))
+        .unwrap()
+}
+
+#[tokio::test]
+async fn melt_quote_rejects_expired_invoice() {
+    let mint = create_test_mint().await.unwrap();
+    // Created in 2024 with the default expiry of one hour
+    let invoice = Bolt11Invoice::from_str("lnbc100n1pnvpufspp5djn8hrq49r8cghwye9kqw752qjncwyfnrprhprpqk43mwcy4yfsqdq5g9kxy7fqd9h8vmmfvdjscqzzsxqyz5vqsp5uhpjt36rj75pl7jq2sshaukzfkt7uulj456s4mh7uy7l6vx7lvxs9qxpqysgqedwz08acmqwtk8g4vkwm2w78suwt2qyzz6jkkwcgrjm3r3hs6fskyhvud4fan3keru7emjm8ygqpcrwtlmhfjfmer3afs5hhwamgr4cqtactdq").unwrap();
+
+    let err = mint
+        .get_melt_quote(bolt11_request(invoice))
+        .await
+        .unwrap_err();
+    assert!(matches!(err, Error::InvoiceExpired(_)));
+    assert_eq!(err.error_code().to_code(), 20010);
+}
+
+#[tokio::test]
+async fn melt_quote_rejects_zero_amount_invoice() {
+    let mint = create_test_mint().await.unwrap();
+
+    let err = mint
+        .get_melt_quote(bolt11_request(amountless_invoice()))
+        .await
+        .unwrap_err();
+    assert!(matches!(err, Error::InvoiceAmountUndefined));
+}
+
+#[tokio::test]
+async fn melt_quote_rejects_payment_to_own_node() {
+    let invoice = create_fake_invoice(10_000, String::new());
+    let own_node_id = PublicKey::from(invoice.get_payee_pub_key());
+
+    // Without configured node ids the invoice is melted as usual
+    let mint = create_test_mint().await.unwrap();
+    mint.get_melt_quote(bolt11_request(invoice.clone()))
+        .await
+        .unwrap();
+
+    let mint = mint.with_own_node_ids(vec![own_node_id]);
+    let err = mint
+        .get_melt_quote(bolt11_request(invoice))
+        .await
+        .unwrap_err();
+    assert!(matches!(err, Error::SelfPaymentNotAllowed));
+
+    // Invoices of the mint's own quotes are settled internally
+    let mint_quote = mint
+        .get_mint_quote(MintQuoteRequest::Bolt11(MintQuoteBolt11Request {
+            amount: Amount::from(10),
+            unit: CurrencyUnit::Sat,
+            description: None,
+            pubkey: None,
+        }))
+        .await
+        .unwrap();
+    let internal_invoice = Bolt11Invoice::from_str(mint_quote.request()).unwrap();
+    mint.get_melt_quote(bolt11_request(internal_invoice))
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+async fn melt_quote_rejects_invoice_already_being_paid() {
+    let mint = create_test_mint().await.unwrap();
+    let invoice = create_fake_invoice(10_000, String::new());
+
+    // Unpaid quotes for the same invoice do not block a new one
+    let quote = mint
+        .get_melt_quote(bolt11_request(invoice.clone()))
+        .await
+        .unwrap();
+    mint.get_melt_quote(bolt11_request(invoice.clone()))
+        .await
+        .unwrap();
+
+    let quote_id = quote.quote().expect("single-quote method").clone();
+    let mut tx = mint.localstore.begin_transaction().await.unwrap();
+    let mut stored_quote = tx.get_melt_quote(&quote_id).await.unwrap().unwrap();
+    tx.update_melt_quote_state(&mut stored_quote, MeltQuoteState::Pending, None)
+        .await
+        .unwrap();
+    tx.commit().await.unwrap();
+
+    let err = mint
+        .get_melt_quote(bolt11_request(invoice.clone()))
+        .await
+        .unwrap_err();
+    assert!(matches!(err, Error::PendingQuote));
+
+    let mut tx = mint.localstore.begin_transaction().await.unwrap();
+    let mut stored_quote = tx.get_melt_quote(&quote_id).await.unwrap().unwrap();
+    tx.update_melt_quote_state(
+        &mut stored_quote,
+        MeltQuoteState::Paid,
+        Some("preimage".to_string()),
+    )
+    .await
+    .unwrap();
+    tx.commit().await.unwrap();
+
+    let err = mint
+        .get_melt_quote(bolt11_request(invoice))
+        .await
+        .unwrap_err();
+    assert!(matches!(err, Error::RequestAlreadyPaid));
+    assert_eq!(err.error_code().to_code(), 20006);
+}
+
+#[tokio::test]
+async fn melt_quote_for_own_invoice_has_no_fee_reserve() {
+    let mint = create_test_mint().await.unwrap();
+    let mint_quote = mint
+        .get_mint_quote(MintQuoteRequest::Bolt11(MintQuoteBolt11Request {
+            amount: Amount::from(10),
+            unit: CurrencyUnit::Sat,
+            description: None,
+            pubkey: None,
+        }))
+        .await
+        .unwrap();
+    let invoice = Bolt11Invoice::from_str(mint_quote.request()).unwrap();
+
+    let quote = mint.get_melt_quote(bolt11_request(invoice)).await.unwrap();
+
+    // Settled internally, so no lightning fee is reserved
+    let quote_id = quote.quote().expect("single-quote method").clone();
+    let stored_quote = mint
+        .localstore
+        .get_melt_quote(&quote_id)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(
+        stored_quote.amount(),
+        Amount::from(10).with_unit(CurrencyUnit::Sat)
+    );
+    assert_eq!(
+        stored_quote.fee_reserve(),
+        Amount::ZERO.with_unit(CurrencyUnit::Sat)
+    );
+}
@@ -1,16 +1,18 @@
 //! Cashu Mint
 
 use std::collections::HashMap;
+use std::sync::atomic::AtomicU64;
 use std::sync::Arc;
 use std::time::Duration;
 
-use arc_swap::ArcSwap;
-use cdk_common::common::{PaymentProcessorKey, QuoteTTL};
+use arc_swap::{ArcSwap, ArcSwapOption};
+use cdk_common::clock::{Clock, SystemClock};
+use cdk_common::common::{PaymentProcessorKey, QuoteTTL, ScheduledMotd};
 use cdk_common::database::mint::Acquired;
 use cdk_common::database::{self, DynMintAuthDatabase, DynMintDatabase};
 use cdk_common::nuts::{BlindSignature, BlindedMessage, CurrencyUnit, Id};
 use cdk_common::payment::{DynMintPayment, WaitPaymentResponse};
-pub use cdk_common::quote_id::QuoteId;
+pub use cdk_common::quote_id::{QuoteId, QuoteIdFormat};
 #[cfg(feature = "prometheus")]
 use cdk_prometheus::MintMetricGuard;
 use cdk_signatory::signatory::{Signatory, SignatoryKeySet};
@@ -22,35 +24,61 @@ use tokio::task::{JoinHandle, JoinSet};
 use tracing::instrument;
 
 use crate::error::Error;
+use crate::event::MintEvent;
 use crate::fees::calculate_fee;
 use crate::nuts::*;
 use crate::{Amount, OidcClient};
 
+mod archive;
 pub(crate) mod auth;
 mod builder;
 mod check_spendable;
+#[cfg(feature = "conversion")]
+mod conversion;
+mod fee_ledger;
 mod issue;
 mod keysets;
+mod ledger;
+mod lightning_address;
 mod ln;
+mod maintenance;
 mod melt;
+#[cfg(feature = "peering")]
+mod peering;
 mod proofs;
 mod saga_recovery;
+mod self_check;
 mod start_up_check;
 mod subscription;
 mod swap;
 mod verification;
 
+pub use auth::{AuthIdentity, AuthQuotas, QuotaCharge, QuotaOperation};
 pub use builder::{KeysetRotation, MintBuilder, MintMeltLimits, UnitConfig};
-pub use cdk_common::mint::{MeltQuote, MintKeySetInfo, MintQuote};
+pub use cdk_common::mint::{
+    FeeLedgerEntry, FeeLedgerKind, FeeTotals, MeltQuote, MintKeySetInfo, MintQuote,
+};
 pub use cdk_common::mint_quote::{MintQuoteRequest, MintQuoteResponse};
+#[cfg(feature = "conversion")]
+pub use conversion::{ConversionRecord, UnitConversion};
 pub use issue::MintInput;
-pub use melt::PendingMelt;
+pub use keysets::KeysetConfigChange;
+pub use ledger::{
+    format_ledger, ledger_entries, LedgerEntry, LedgerEntryKind, LedgerFormat,
+    ECASH_LIABILITY_ACCOUNT, FEE_INCOME_ACCOUNT,
+};
+pub use maintenance::DEFAULT_MAINTENANCE_RETRY_AFTER;
+pub use melt::{MeltReleaseAuditEntry, PendingMelt, AUDIT_KV_NAMESPACE};
+#[cfg(feature = "peering")]
+pub use peering::{PeerMints, PeerSettlement};
+pub use self_check::SelfCheckReport;
 pub use verification::Verification;
 
 const CDK_MINT_PRIMARY_NAMESPACE: &str = "cdk_mint";
 const CDK_MINT_CONFIG_SECONDARY_NAMESPACE: &str = "config";
 const CDK_MINT_CONFIG_KV_KEY: &str = "mint_info";
 const CDK_MINT_QUOTE_TTL_KV_KEY: &str = "quote_ttl";
+const CDK_MINT_MOTD_SCHEDULE_KV_KEY: &str = "motd_schedule";
 
 /// Cashu Mint
 #[derive(Clone)]
@@ -77,6 +105,28 @@ pub struct Mint {
     max_inputs: usize,
     /// Maximum number of outputs allowed per transaction
     max_outputs: usize,
+    /// Format of newly created quote ids
+    quote_id_format: QuoteIdFormat,
+    /// Retry after seconds reported while the mint is in maintenance mode
+    maintenance: Arc<ArcSwapOption<u64>>,
+    /// Reject malformed spending condition witnesses with specific error codes
+    strict_spending_conditions: bool,
+    /// How the unspent fee reserve of a melt is returned as change
+    melt_change_policy: MeltChangePolicy,
+    /// Lightning node ids of the mint's own backends, invoices payable to them are not melted
+    own_node_ids: Arc<Vec<PublicKey>>,
+    /// Daily limits per auth identity
+    auth_quotas: AuthQuotas,
+    /// Unix day up to which quota usage of earlier days has been pruned
+    quota_pruned_day: Arc<AtomicU64>,
+    /// Source of the current time for quote expiry, clear auth token expiry and sweepers
+    clock: Arc<dyn Clock>,
+    /// Trusted peer mints whose proofs are accepted as swap inputs
+    #[cfg(feature = "peering")]
+    peer_mints: Option<Arc<peering::PeerMints>>,
+    /// Unit pairs swaps may convert between
+    #[cfg(feature = "conversion")]
+    unit_conversion: Option<Arc<conversion::UnitConversion>>,
 }
 
 impl std::fmt::Debug for Mint {
@@ -248,9 +298,94 @@ impl Mint {
             task_state: Arc::new(Mutex::new(TaskState::default())),
             max_inputs,
             max_outputs,
+            quote_id_format: QuoteIdFormat::default(),
+            maintenance: Arc::new(ArcSwapOption::empty()),
+            strict_spending_conditions: false,
+            melt_change_policy: MeltChangePolicy::default(),
+            own_node_ids: Arc::new(Vec::new()),
+            auth_quotas: AuthQuotas::default(),
+            quota_pruned_day: Arc::new(AtomicU64::new(0)),
+            clock: Arc::new(SystemClock),
+            #[cfg(feature = "peering")]
+            peer_mints: None,
+            #[cfg(feature = "conversion")]
+            unit_conversion: None,
         })
     }
 
+    /// Set the format of newly created quote ids
+    ///
+    /// Time-ordered formats keep new rows at the end of the quote table indexes.
+    pub fn with_quote_id_format(mut self, quote_id_format: QuoteIdFormat) -> Self {
+        self.quote_id_format = quote_id_format;
+        self
+    }
+
+    /// Verify spending conditions strictly
+    ///
+    /// Malformed witnesses, duplicate signatures and refund signatures before the
+    /// locktime are rejected with their own error codes instead of a generic witness error.
+    pub fn with_strict_spending_conditions(mut self, strict: bool) -> Self {
+        self.strict_spending_conditions = strict;
+        self
+    }
+
+    /// Set how the unspent fee reserve of a melt is returned as change
+    ///
+    /// Surplus that is not returned is recorded as fee income.
+    pub fn with_melt_change_policy(mut self, melt_change_policy: MeltChangePolicy) -> Self {
+        self.melt_change_policy = melt_change_policy;
+        self
+    }
+
+    /// Reject melt quotes for invoices payable to any of `node_ids`
+    ///
+    /// Paying an invoice of the mint's own lightning node would only circle funds through the
+    /// node while the proofs are burnt. Invoices of the mint's own mint quotes are still settled
+    /// internally.
+    pub fn with_own_node_ids(mut self, node_ids: Vec<PublicKey>) -> Self {
+        self.own_node_ids = Arc::new(node_ids);
+        self
+    }
+
+    /// Read the current time from `clock` instead of the system time
+    ///
+    /// Used by quote expiry, clear auth token expiry and the archive sweeper. Tests can pass a
+    /// [`SimulatedClock`](cdk_common::clock::SimulatedClock) to fast-forward time.
+    pub fn with_clock(mut self, clock: Arc<dyn Clock>) -> Self {
+        self.oidc_client = self
+            .oidc_client
+            .take()
+            .map(|oidc_client| oidc_client.with_clock(clock.clone()));
+        self.clock = clock;
+        self
+    }
+
+    /// Current unix time of the mint's clock
+    pub(crate) fn unix_time(&self) -> u64 {
+        self.clock.unix_time()
+    }
+
+    /// Verify the NUT-10 spending conditions of a request's inputs
+    pub(crate) fn verify_spending_conditions<R>(&self, request: &R) -> Result<(), Error>
+    where
+        R: SpendingConditionVerification,
+    {
+        if self.strict_spending_conditions {
+            request.verify_spending_conditions_strict()?;
+        } else {
+            request.verify_spending_conditions()?;
+        }
+
+        Ok(())
+    }
+
+    /// Create a new quote id in the configured format
+    #[inline]
+    pub(crate) fn new_quote_id(&self) -> QuoteId {
+        QuoteId::new_with_format(self.quote_id_format)
+    }
+
     /// Start the mint's background services and operations
     ///
     /// This function immediately starts background services and returns. The background
@@ -327,6 +462,11 @@ impl Mint {
         let pubsub_manager = Arc::clone(&self.pubsub_manager);
         let shutdown_clone = shutdown_notify.clone();
 
+        #[cfg(feature = "peering")]
+        Self::spawn_peer_settlement(Arc::clone(&mint_clone), shutdown_notify.clone());
+
+        Self::spawn_keyset_activation(Arc::clone(&mint_clone), shutdown_notify.clone());
+
         // Spawn the supervisor task
         let supervisor_handle = tokio::spawn(async move {
             Self::wait_for_paid_invoices(
@@ -460,6 +600,13 @@ impl Mint {
         Ok(custom_methods.into_iter().collect())
     }
 
+    /// Whether a payment processor is registered for the payment method in any unit
+    pub fn supports_payment_method(&self, payment_method: &PaymentMethod) -> bool {
+        self.payment_processors
+            .keys()
+            .any(|key| &key.method == payment_method)
+    }
+
     /// Get the payment processor for the given unit and payment method
     pub fn get_payment_processor(
         &self,
@@ -495,6 +642,11 @@ impl Mint {
         Arc::clone(&self.pubsub_manager)
     }
 
+    /// Receive every state change of the mint, e.g. to forward it to a notification transport
+    pub fn subscribe_events(&self) -> tokio::sync::broadcast::Receiver<MintEvent<QuoteId>> {
+        self.pubsub_manager.subscribe_events()
+    }
+
     /// Get mint info
     #[instrument(skip_all)]
     pub async fn mint_info(&self) -> Result<MintInfo, Error> {
@@ -621,6 +773,90 @@ impl Mint {
         Ok(quote_ttl_bytes.is_some())
     }
 
+    /// Mint info as served to wallets
+    ///
+    /// Same as [`Mint::mint_info`] with the MOTD replaced by the active
+    /// scheduled MOTD, if any. When several scheduled messages overlap the
+    /// one that started last wins.
+    #[instrument(skip_all)]
+    pub async fn public_mint_info(&self) -> Result<MintInfo, Error> {
+        let mut mint_info = self.mint_info().await?;
+        let now = self.unix_time();
+
+        if let Some(scheduled) = self
+            .motd_schedule()
+            .await?
+            .into_iter()
+            .filter(|scheduled| scheduled.is_active(now))
+            .max_by_key(|scheduled| scheduled.start.unwrap_or_default())
+        {
+            mint_info.motd = Some(scheduled.motd);
+        }
+
+        // Advertise the restore page limit so wallets split large restores up front
+        if mint_info.nuts.nut09.supported {
+            mint_info.nuts.nut09.max_outputs = Some(self.max_outputs);
+        }
+
+        Ok(mint_info)
+    }
+
+    /// Get the MOTD schedule
+    #[instrument(skip_all)]
+    pub async fn motd_schedule(&self) -> Result<Vec<ScheduledMotd>, Error> {
+        let schedule_bytes = self
+            .localstore
+            .kv_read(
+                CDK_MINT_PRIMARY_NAMESPACE,
+                CDK_MINT_CONFIG_SECONDARY_NAMESPACE,
+                CDK_MINT_MOTD_SCHEDULE_KV_KEY,
+            )
+            .await?;
+
+        match schedule_bytes {
+            Some(bytes) => Ok(serde_json::from_slice(&bytes)?),
+            None => Ok(Vec::new()),
+        }
+    }
+
+    /// Set the MOTD schedule
+    ///
+    /// Messages whose window has already passed are dropped.
+    #[instrument(skip_all)]
+    pub async fn set_motd_schedule(&self, schedule: Vec<ScheduledMotd>) -> Result<(), Error> {
+        for scheduled in &schedule {
+            scheduled.validate()?;
+        }
+
+        let now = self.unix_time();
+        let schedule: Vec<ScheduledMotd> = schedule
+            .into_iter()
+            .filter(|scheduled| !scheduled.is_expired(now))
+            .collect();
+
+        tracing::info!("Updating MOTD schedule with {} messages", schedule.len());
+
+        let schedule_bytes = serde_json::to_vec(&schedule)?;
+        let mut tx = self.localstore.begin_transaction().await?;
+        tx.kv_write(
+            CDK_MINT_PRIMARY_NAMESPACE,
+            CDK_MINT_CONFIG_SECONDARY_NAMESPACE,
+            CDK_MINT_MOTD_SCHEDULE_KV_KEY,
+            &schedule_bytes,
+        )
+        .await?;
+        tx.commit().await?;
+        Ok(())
+    }
+
+    /// Add a message to the MOTD schedule
+    #[instrument(skip_all)]
+    pub async fn schedule_motd(&self, scheduled: ScheduledMotd) -> Result<(), Error> {
+        let mut schedule = self.motd_schedule().await?;
+        schedule.push(scheduled);
+        self.set_motd_schedule(schedule).await
+    }
+
     #[instrument(skip_all)]
     async fn wait_for_paid_invoices(
         mint: Arc<Mint>,
@@ -732,12 +968,27 @@ impl Mint {
 
                                         match event {
                                             cdk_common::payment::Event::PaymentReceived(wait_payment_response) => {
-                                                if let Err(e) = Self::handle_payment_notification(
+                                                let payment_identifier = wait_payment_response.payment_identifier.clone();
+
+                                                match Self::handle_payment_notification(
                                                     &localstore,
                                                     &pubsub_manager,
                                                     wait_payment_response,
                                                 ).await {
-                                                    tracing::warn!("Payment notification error: {:?}", e);
+                                                    Ok(()) => {
+                                                        // The payment is now durably recorded so the
+                                                        // backend can release any held funds.
+                                                        if let Err(e) = processor.settle_incoming_payment(&payment_identifier).await {
+                                                            tracing::warn!(
+                                                                "Failed to settle incoming payment {}: {}",
+                                                                payment_identifier,
+                                                                e
+                                                            );
+                                                        }
+                                                    }
+                                                    Err(e) => {
+                                                        tracing::warn!("Payment notification error: {:?}", e);
+                                                    }
                                                 }
                                             }
                                             cdk_common::payment::Event::PaymentSuccessful { quote_id, details } => {
@@ -1104,6 +1355,9 @@ impl Mint {
     }
 
     /// Blind Sign
+    ///
+    /// Every returned signature carries a NUT-12 DLEQ proof, whether it is issued for a mint,
+    /// swap or melt change, so a signatory that omits one is treated as failed.
     #[tracing::instrument(skip_all)]
     pub async fn blind_sign(
         &self,
@@ -1119,7 +1373,17 @@ impl Mint {
         #[cfg(feature = "prometheus")]
         let metrics = MintMetricGuard::new("blind_sign");
 
-        let result = self.signatory.blind_sign(blinded_message).await;
+        let result = self
+            .signatory
+            .blind_sign(blinded_message)
+            .await
+            .and_then(|signatures| {
+                if signatures.iter().any(|signature| signature.dleq.is_none()) {
+                    tracing::error!("Signatory returned a blind signature without a DLEQ proof");
+                    return Err(Error::DleqProofNotProvided);
+                }
+                Ok(signatures)
+            });
 
         #[cfg(feature = "prometheus")]
         {
@@ -1148,40 +1412,50 @@ impl Mint {
     }
 
     /// Restore
+    ///
+    /// Answers at most `max_outputs` outputs per call, starting at the request cursor, and
+    /// returns the cursor of the next page while outputs remain.
     #[instrument(skip_all)]
     pub async fn restore(&self, request: RestoreRequest) -> Result<RestoreResponse, Error> {
         #[cfg(feature = "prometheus")]
         let metrics = MintMetricGuard::new("restore");
 
         let result = async {
-            let output_len = request.outputs.len();
+            let request_len = request.outputs.len();
+            let start = request.cursor.unwrap_or(0).min(request_len);
+            let end = start
+                .saturating_add(self.max_outputs.max(1))
+                .min(request_len);
 
-            // Check max outputs limit
-            if output_len > self.max_outputs {
-                tracing::warn!(
-                    "Restore request exceeds max outputs limit: {} > {}",
-                    output_len,
-                    self.max_outputs
+            if start > 0 || end < request_len {
+                tracing::debug!(
+                    "Paging restore request of {} outputs by max outputs limit {}: answering {}..{}",
+                    request_len,
+                    self.max_outputs,
+                    start,
+                    end
                 );
-                return Err(Error::MaxOutputsExceeded {
-                    actual: output_len,
-                    max: self.max_outputs,
-                });
             }
 
+            let page: Vec<BlindedMessage> = request
+                .outputs
+                .into_iter()
+                .skip(start)
+                .take(end - start)
+                .collect();
+            let output_len = page.len();
+
             let mut outputs = Vec::with_capacity(output_len);
             let mut signatures = Vec::with_capacity(output_len);
 
             // Build a position map to track original request order for verification
-            let position_map: HashMap<PublicKey, usize> = request
-                .outputs
+            let position_map: HashMap<PublicKey, usize> = page
                 .iter()
                 .enumerate()
                 .map(|(idx, output)| (output.blinded_secret, idx))
                 .collect();
 
-            let blinded_message: Vec<PublicKey> =
-                request.outputs.iter().map(|b| b.blinded_secret).collect();
+            let blinded_message: Vec<PublicKey> = page.iter().map(|b| b.blinded_secret).collect();
 
             let blinded_signatures = self
                 .localstore
@@ -1192,8 +1466,7 @@ impl Mint {
                 return Err(Error::Internal);
             }
 
-            for (blinded_message, blinded_signature) in
-                request.outputs.into_iter().zip(blinded_signatures)
+            for (blinded_message, blinded_signature) in page.into_iter().zip(blinded_signatures)
             {
                 if let Some(blinded_signature) = blinded_signature {
                     if let Some(keyset_info) = self.get_keyset_info(&blinded_signature.keyset_id) {
@@ -1236,6 +1509,7 @@ impl Mint {
             Ok(RestoreResponse {
                 outputs,
                 signatures,
+                next_cursor: (end < request_len).then_some(end),
             })
         }
         .await;
@@ -1301,6 +1575,7 @@ mod tests {
     use std::str::FromStr;
     use std::sync::Arc;
 
+    use cdk_common::clock::SimulatedClock;
     use cdk_common::melt::MeltQuoteRequest;
     use cdk_common::mint::{OperationKind, SagaStateEnum};
     use cdk_common::nut00::KnownMethod;
@@ -1313,7 +1588,9 @@ mod tests {
 
     use super::*;
     use crate::mint::melt::melt_saga::{MeltSaga, PaymentOutcome};
-    use crate::test_helpers::mint::{create_test_mint, mint_test_proofs};
+    use crate::test_helpers::mint::{
+        create_test_blinded_messages, create_test_mint, mint_test_proofs,
+    };
 
     #[derive(Default)]
     struct MintConfig<'a> {
@@ -1362,6 +1639,7 @@ mod tests {
                     input_fee_ppk: *fee,
                     keyset_id_type: cdk_common::nut02::KeySetVersion::Version00,
                     final_expiry: None,
+                    activate_at: None,
                 })
                 .await
                 .unwrap();
@@ -1409,6 +1687,139 @@ mod tests {
         );
     }
 
+    #[tokio::test]
+    async fn mint_mod_advertises_restore_page_limit() {
+        let mint = create_test_mint().await.unwrap();
+
+        let mint_info = mint.public_mint_info().await.unwrap();
+        assert!(mint_info.nuts.nut09.supported);
+        assert_eq!(mint_info.nuts.nut09.max_outputs, Some(mint.max_outputs));
+
+        // The stored mint info is left untouched
+        assert_eq!(mint.mint_info().await.unwrap().nuts.nut09.max_outputs, None);
+    }
+
+    #[tokio::test]
+    async fn mint_mod_restore_pages_by_max_outputs() {
+        let mut mint = create_test_mint().await.unwrap();
+
+        let inputs = mint_test_proofs(&mint, Amount::from(100)).await.unwrap();
+        let (signed, _) = create_test_blinded_messages(&mint, Amount::from(100))
+            .await
+            .unwrap();
+        mint.process_swap_request(SwapRequest::new(inputs, signed.clone()))
+            .await
+            .unwrap();
+
+        // Interleave outputs the mint never signed
+        let (unsigned, _) = create_test_blinded_messages(&mint, Amount::from(7))
+            .await
+            .unwrap();
+        let outputs: Vec<_> = signed
+            .iter()
+            .zip(unsigned.iter())
+            .flat_map(|(signed, unsigned)| [signed.clone(), unsigned.clone()])
+            .collect();
+
+        mint.max_outputs = 4;
+
+        let mut restored = Vec::new();
+        let mut cursors = Vec::new();
+        let mut cursor = None;
+
+        loop {
+            let response = mint
+                .restore(RestoreRequest {
+                    outputs: outputs.clone(),
+                    cursor,
+                })
+                .await
+                .unwrap();
+            assert_eq!(response.outputs.len(), response.signatures.len());
+            restored.extend(response.outputs);
+
+            match response.next_cursor {
+                Some(next) => {
+                    cursors.push(next);
+                    cursor = Some(next);
+                }
+                None => break,
+            }
+        }
+
+        assert_eq!(cursors, vec![4]);
+        assert_eq!(restored, signed);
+    }
+
+    #[tokio::test]
+    async fn mint_mod_scheduled_motd() {
+        let mint = create_test_mint().await.unwrap();
+        let now = cdk_common::util::unix_time();
+
+        let mut mint_info = mint.mint_info().await.unwrap();
+        mint_info.motd = Some("base".to_string());
+        mint.set_mint_info(mint_info).await.unwrap();
+
+        mint.set_motd_schedule(vec![
+            ScheduledMotd::new("expired".to_string(), Some(now - 20), Some(now - 10)).unwrap(),
+            ScheduledMotd::new("future".to_string(), Some(now + 100), None).unwrap(),
+        ])
+        .await
+        .unwrap();
+
+        // Expired messages are dropped and future ones do not apply yet
+        assert_eq!(mint.motd_schedule().await.unwrap().len(), 1);
+        assert_eq!(
+            mint.public_mint_info().await.unwrap().motd.as_deref(),
+            Some("base")
+        );
+
+        mint.schedule_motd(
+            ScheduledMotd::new("maintenance".to_string(), Some(now - 10), Some(now + 10)).unwrap(),
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(
+            mint.public_mint_info().await.unwrap().motd.as_deref(),
+            Some("maintenance")
+        );
+        // The stored mint info keeps the base message
+        assert_eq!(
+            mint.mint_info().await.unwrap().motd.as_deref(),
+            Some("base")
+        );
+    }
+
+    #[tokio::test]
+    async fn mint_mod_scheduled_motd_simulated_clock() {
+        let clock = Arc::new(SimulatedClock::new(1_000_000));
+        let mint = create_test_mint().await.unwrap().with_clock(clock.clone());
+
+        mint.schedule_motd(
+            ScheduledMotd::new("upgrade".to_string(), Some(1_000_100), Some(1_000_200)).unwrap(),
+        )
+        .await
+        .unwrap();
+
+        assert_ne!(
+            mint.public_mint_info().await.unwrap().motd.as_deref(),
+            Some("upgrade")
+        );
+
+        clock.advance(Duration::from_secs(150));
+        assert_eq!(
+            mint.public_mint_info().await.unwrap().motd.as_deref(),
+            Some("upgrade")
+        );
+
+        clock.advance(Duration::from_secs(100));
+        assert_ne!(
+            mint.public_mint_info().await.unwrap().motd.as_deref(),
+            Some("upgrade")
+        );
+    }
+
     #[tokio::test]
     async fn mint_mod_rotate_keyset() {
         let mut supported_units = HashMap::new();
@@ -1777,6 +2188,7 @@ mod tests {
             input_fee_ppk: 100,
             keyset_id_type: cdk_common::nut02::KeySetVersion::Version00,
             final_expiry: None,
+            activate_at: None,
         };
         let rotation_result = mint.signatory.rotate_keyset(rotate_argument).await;
 
@@ -0,0 +1,656 @@
+//! Peering with trusted sibling mints
+//!
+//! Mints operated by the same entity can accept proofs issued by each other as swap inputs. The
+//! mint holds a [`Wallet`] for every peer. Peer proofs are claimed into that wallet at the peer,
+//! which verifies them against the peer's keys and marks them spent there, before new proofs of
+//! the same value are signed. The ecash collected at each peer is periodically melted to pay an
+//! invoice of this mint's own Lightning backend, settling the balance between the mints.
+//!
+//! A claim that fails without a definitive answer from the peer, e.g. on a timeout, may still
+//! have spent the inputs there. Its outputs stay recorded and the swap is kept pending in the KV
+//! store until [`Mint::resolve_pending_peer_swaps`] checks the inputs at the peer.
+
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+use std::time::Duration;
+
+use cdk_common::database;
+use cdk_common::mint::{Operation, OperationKind};
+use cdk_common::nut00::KnownMethod;
+use cdk_common::payment::{Bolt11IncomingPaymentOptions, IncomingPaymentOptions};
+use cdk_common::wallet::KeysetLoadPolicy;
+use serde::{Deserialize, Serialize};
+use tokio::sync::Notify;
+use tracing::instrument;
+use uuid::Uuid;
+
+use super::Mint;
+use crate::mint_url::MintUrl;
+use crate::nuts::{
+    BlindSignature, BlindedMessage, Id, PaymentMethod, Proofs, PublicKey, State, SwapRequest,
+    SwapResponse,
+};
+use crate::wallet::util::quote_within_fee_reserve;
+use crate::wallet::{ReceiveOptions, StartedSagas, Wallet};
+use crate::{Amount, Error};
+
+/// KV store namespace of the peering state
+const PEERING_KV_NAMESPACE: &str = "cdk_mint_peering";
+
+/// KV store secondary namespace of the peer swaps with an unknown outcome, keyed by operation id
+const PENDING_PEER_SWAPS_KV_SECONDARY_NAMESPACE: &str = "pending_swaps";
+
+/// Default balance held at a peer before it is settled
+pub const DEFAULT_SETTLEMENT_THRESHOLD: u64 = 10_000;
+
+/// Default interval between settlement runs
+pub const DEFAULT_SETTLEMENT_INTERVAL: Duration = Duration::from_secs(3600);
+
+/// Trusted peer mints whose proofs are accepted as swap inputs
+#[derive(Debug, Clone)]
+pub struct PeerMints {
+    peers: Vec<Wallet>,
+    settlement_threshold: Amount,
+    settlement_interval: Duration,
+}
+
+impl PeerMints {
+    /// Accept proofs from the mints of `peers`
+    ///
+    /// Each wallet must be dedicated to this mint; its balance is what the peer owes.
+    pub fn new(peers: Vec<Wallet>) -> Self {
+        Self {
+            peers,
+            settlement_threshold: Amount::from(DEFAULT_SETTLEMENT_THRESHOLD),
+            settlement_interval: DEFAULT_SETTLEMENT_INTERVAL,
+        }
+    }
+
+    /// Only settle with a peer once its balance reaches `threshold`
+    pub fn with_settlement_threshold(mut self, threshold: Amount) -> Self {
+        self.settlement_threshold = threshold;
+        self
+    }
+
+    /// Settle peer balances every `interval` while the mint is running
+    pub fn with_settlement_interval(mut self, interval: Duration) -> Self {
+        self.settlement_interval = interval;
+        self
+    }
+
+    /// Wallets of the peer mints
+    pub fn peers(&self) -> &[Wallet] {
+        &self.peers
+    }
+
+    /// Peer that issued all of `inputs`, if any
+    async fn peer_for_inputs(&self, inputs: &Proofs) -> Option<&Wallet> {
+        let input_keysets: HashSet<Id> = inputs.iter().map(|proof| proof.keyset_id).collect();
+
+        for peer in &self.peers {
+            let peer_keysets = match peer.keysets(KeysetLoadPolicy::default()).await {
+                Ok(keysets) => keysets,
+                Err(err) => {
+                    tracing::warn!("Could not load keysets of peer {}: {}", peer.mint_url, err);
+                    continue;
+                }
+            };
+
+            if input_keysets
+                .iter()
+                .all(|id| peer_keysets.iter().any(|keyset| keyset.id == *id))
+            {
+                return Some(peer);
+            }
+        }
+
+        None
+    }
+}
+
+/// Peer swap whose inputs may have been claimed at the peer
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct PendingPeerSwap {
+    /// Url of the peer mint
+    peer: MintUrl,
+    /// Id of the swap operation
+    operation_id: Uuid,
+    /// Total of the inputs
+    input_amount: Amount,
+    /// Input fee of the peer
+    peer_fee: Amount,
+    /// Inputs claimed at the peer
+    inputs: Proofs,
+    /// Outputs to sign once the peer paid
+    outputs: Vec<BlindedMessage>,
+    /// Sagas the claim left in the wallet of the peer
+    wallet_saga_ids: Vec<Uuid>,
+}
+
+/// Balance settled with a peer mint
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PeerSettlement {
+    /// Url of the peer mint
+    pub mint_url: MintUrl,
+    /// Amount paid by the peer
+    pub amount: Amount,
+    /// Lightning fee paid by the peer
+    pub fee_paid: Amount,
+}
+
+impl Mint {
+    /// Accept swap inputs issued by `peer_mints`
+    pub fn with_peer_mints(mut self, peer_mints: PeerMints) -> Self {
+        self.peer_mints = Some(Arc::new(peer_mints));
+        self
+    }
+
+    /// Peer mint that issued the swap inputs, if they are not issued by this mint
+    pub(crate) async fn peer_for_swap_inputs(&self, inputs: &Proofs) -> Option<&Wallet> {
+        let peer_mints = self.peer_mints.as_ref()?;
+
+        let keysets = self.keysets.load();
+        if inputs
+            .iter()
+            .any(|proof| keysets.iter().any(|keyset| keyset.id == proof.keyset_id))
+        {
+            return None;
+        }
+
+        peer_mints.peer_for_inputs(inputs).await
+    }
+
+    /// Swap proofs issued by `peer` for proofs of this mint
+    ///
+    /// The outputs must add up to the inputs minus the input fee of the peer. The outputs are
+    /// recorded before the inputs are claimed at the peer so they cannot be reused.
+    #[instrument(skip_all, fields(peer = %peer.mint_url))]
+    pub(crate) async fn process_peer_swap_request(
+        &self,
+        peer: &Wallet,
+        swap_request: &SwapRequest,
+    ) -> Result<SwapResponse, Error> {
+        let inputs = swap_request.inputs().clone();
+        let outputs = swap_request.outputs();
+
+        Mint::check_inputs_unique(&inputs)?;
+        self.verify_spending_conditions(swap_request)?;
+
+        let output_verification = self.verify_outputs(outputs)?;
+        if output_verification.amount.unit() != &peer.unit {
+            return Err(Error::UnitMismatch);
+        }
+
+        let input_amount = swap_request.input_amount()?;
+        let output_amount = Amount::from(output_verification.amount.value());
+        let peer_fee = peer.get_proofs_fee(&inputs).await?.total;
+
+        if input_amount.checked_sub(peer_fee) != Some(output_amount) {
+            return Err(Error::TransactionUnbalanced(
+                input_amount.to_u64(),
+                output_amount.to_u64(),
+                peer_fee.to_u64(),
+            ));
+        }
+
+        let operation = Operation::new(
+            Uuid::now_v7(),
+            OperationKind::Swap,
+            output_amount,
+            input_amount,
+            peer_fee,
+            None,
+            None,
+        )
+        .with_unit(peer.unit.clone());
+        let blinded_secrets: Vec<PublicKey> = outputs.iter().map(|bm| bm.blinded_secret).collect();
+
+        let mut tx = self.localstore.begin_transaction().await?;
+        if let Err(err) = tx.add_blinded_messages(None, outputs, &operation).await {
+            tx.rollback().await?;
+            return Err(match err {
+                database::Error::Duplicate => Error::DuplicateOutputs,
+                _ => Error::Database(err),
+            });
+        }
+        tx.commit().await?;
+
+        // Claim the proofs at the peer so they cannot be spent there again
+        let started = StartedSagas::default();
+        let received = match started
+            .scope(peer.receive_proofs(inputs.clone(), ReceiveOptions::default(), None, None))
+            .await
+        {
+            Ok(received) => received,
+            Err(err) if err.is_definitive_failure() => {
+                tracing::debug!("Peer {} rejected the proofs: {}", peer.mint_url, err);
+                let mut tx = self.localstore.begin_transaction().await?;
+                tx.delete_blinded_messages(&blinded_secrets).await?;
+                tx.commit().await?;
+                return Err(err);
+            }
+            Err(err) => {
+                // The peer may have spent the inputs, so the outputs stay recorded until the
+                // inputs are checked there
+                tracing::warn!(
+                    "Outcome of claiming proofs at peer {} is unknown, keeping swap {} pending: {}",
+                    peer.mint_url,
+                    operation.id(),
+                    err
+                );
+                self.record_pending_peer_swap(&PendingPeerSwap {
+                    peer: peer.mint_url.clone(),
+                    operation_id: *operation.id(),
+                    input_amount,
+                    peer_fee,
+                    inputs,
+                    outputs: outputs.to_vec(),
+                    wallet_saga_ids: started.ids(),
+                })
+                .await?;
+                return Err(err);
+            }
+        };
+
+        // The outputs stay recorded but unsigned, so a short payment cannot be retried with them
+        ensure_peer_paid(&peer.mint_url, received, output_amount)?;
+
+        let signatures = self
+            .sign_peer_swap_outputs(peer, &operation, outputs, None)
+            .await?;
+
+        Ok(SwapResponse::new(signatures))
+    }
+
+    /// Sign the outputs of a peer swap whose inputs were claimed at the peer
+    ///
+    /// `pending_key` is the KV key of the [`PendingPeerSwap`] resolved by the signatures.
+    async fn sign_peer_swap_outputs(
+        &self,
+        peer: &Wallet,
+        operation: &Operation,
+        outputs: &[BlindedMessage],
+        pending_key: Option<&str>,
+    ) -> Result<Vec<BlindSignature>, Error> {
+        let blinded_secrets: Vec<PublicKey> = outputs.iter().map(|bm| bm.blinded_secret).collect();
+
+        let signatures = self.blind_sign(outputs.to_vec()).await.inspect_err(|err| {
+            tracing::error!(
+                "Claimed swap {} at peer {} but could not sign outputs: {}",
+                operation.id(),
+                peer.mint_url,
+                err
+            )
+        })?;
+
+        let mut tx = self.localstore.begin_transaction().await?;
+        tx.add_blind_signatures(&blinded_secrets, &signatures, None)
+            .await?;
+        tx.add_completed_operation(operation, &HashMap::new())
+            .await?;
+        if let Some(key) = pending_key {
+            tx.kv_remove(
+                PEERING_KV_NAMESPACE,
+                PENDING_PEER_SWAPS_KV_SECONDARY_NAMESPACE,
+                key,
+            )
+            .await?;
+        }
+        tx.commit().await?;
+
+        Ok(signatures)
+    }
+
+    async fn record_pending_peer_swap(&self, pending: &PendingPeerSwap) -> Result<(), Error> {
+        let mut tx = self.localstore.begin_transaction().await?;
+        tx.kv_write(
+            PEERING_KV_NAMESPACE,
+            PENDING_PEER_SWAPS_KV_SECONDARY_NAMESPACE,
+            &pending.operation_id.to_string(),
+            &serde_json::to_vec(pending)?,
+        )
+        .await?;
+        tx.commit().await?;
+
+        Ok(())
+    }
+
+    /// Resolve peer swaps whose claim at the peer had an unknown outcome
+    ///
+    /// The inputs of every pending swap are checked at the peer. If all of them are spent the
+    /// peer paid and the outputs are signed, so the user can restore them (NUT-09). If any is
+    /// still unspent the claim did not go through and the outputs are released for a retry.
+    /// Swaps with inputs pending at the peer, or whose peer can not be reached, stay pending.
+    #[instrument(skip(self))]
+    pub async fn resolve_pending_peer_swaps(&self) -> Result<(), Error> {
+        let Some(peer_mints) = self.peer_mints.as_ref() else {
+            return Ok(());
+        };
+
+        let keys = self
+            .localstore
+            .kv_list(
+                PEERING_KV_NAMESPACE,
+                PENDING_PEER_SWAPS_KV_SECONDARY_NAMESPACE,
+            )
+            .await?;
+
+        for key in keys {
+            let Some(bytes) = self
+                .localstore
+                .kv_read(
+                    PEERING_KV_NAMESPACE,
+                    PENDING_PEER_SWAPS_KV_SECONDARY_NAMESPACE,
+                    &key,
+                )
+                .await?
+            else {
+                continue;
+            };
+            let pending: PendingPeerSwap = serde_json::from_slice(&bytes)?;
+
+            let Some(peer) = peer_mints
+                .peers()
+                .iter()
+                .find(|peer| peer.mint_url == pending.peer)
+            else {
+                tracing::warn!(
+                    "Pending swap {} belongs to unknown peer {}",
+                    pending.operation_id,
+                    pending.peer
+                );
+                continue;
+            };
+
+            if let Err(err) = self.resolve_pending_peer_swap(peer, &key, pending).await {
+                tracing::warn!("Could not resolve pending peer swap {}: {}", key, err);
+            }
+        }
+
+        Ok(())
+    }
+
+    async fn resolve_pending_peer_swap(
+        &self,
+        peer: &Wallet,
+        key: &str,
+        pending: PendingPeerSwap,
+    ) -> Result<(), Error> {
+        // Proofs claimed by an interrupted receive are recovered into the wallet of the peer
+        peer.recover_sagas_by_id(&pending.wallet_saga_ids).await?;
+
+        let states = peer.check_proofs_spent(pending.inputs.clone()).await?;
+
+        if states.iter().any(|state| state.state == State::Unspent) {
+            tracing::info!(
+                "Inputs of swap {} are unspent at peer {}, releasing its outputs",
+                pending.operation_id,
+                peer.mint_url
+            );
+            let blinded_secrets: Vec<PublicKey> =
+                pending.outputs.iter().map(|bm| bm.blinded_secret).collect();
+
+            let mut tx = self.localstore.begin_transaction().await?;
+            tx.delete_blinded_messages(&blinded_secrets).await?;
+            tx.kv_remove(
+                PEERING_KV_NAMESPACE,
+                PENDING_PEER_SWAPS_KV_SECONDARY_NAMESPACE,
+                key,
+            )
+            .await?;
+            tx.commit().await?;
+
+            return Ok(());
+        }
+
+        if states.len() != pending.inputs.len()
+            || states.iter().any(|state| state.state != State::Spent)
+        {
+            tracing::debug!(
+                "Inputs of swap {} are still pending at peer {}",
+                pending.operation_id,
+                peer.mint_url
+            );
+            return Ok(());
+        }
+
+        let output_amount = pending
+            .outputs
+            .iter()
+            .try_fold(Amount::ZERO, |total, output| {
+                total.checked_add(output.amount)
+            })
+            .ok_or(Error::AmountOverflow)?;
+        let operation = Operation::new(
+            pending.operation_id,
+            OperationKind::Swap,
+            output_amount,
+            pending.input_amount,
+            pending.peer_fee,
+            None,
+            None,
+        )
+        .with_unit(peer.unit.clone());
+
+        self.sign_peer_swap_outputs(peer, &operation, &pending.outputs, Some(key))
+            .await?;
+
+        tracing::info!(
+            "Inputs of swap {} are spent at peer {}, signed its outputs",
+            pending.operation_id,
+            peer.mint_url
+        );
+
+        Ok(())
+    }
+
+    /// Settle the balances held at peer mints over Lightning
+    ///
+    /// Every peer whose balance reached the settlement threshold melts it to pay an invoice of
+    /// this mint's Lightning backend. Peers that fail to settle are skipped.
+    pub async fn settle_peer_balances(&self) -> Result<Vec<PeerSettlement>, Error> {
+        let Some(peer_mints) = self.peer_mints.as_ref() else {
+            return Ok(Vec::new());
+        };
+
+        let mut settlements = Vec::new();
+        for peer in peer_mints.peers() {
+            match self
+                .settle_peer_balance(peer, peer_mints.settlement_threshold)
+                .await
+            {
+                Ok(Some(settlement)) => {
+                    tracing::info!(
+                        "Settled {} with peer {} (fee {})",
+                        settlement.amount,
+                        settlement.mint_url,
+                        settlement.fee_paid
+                    );
+                    settlements.push(settlement);
+                }
+                Ok(None) => {}
+                Err(err) => {
+                    tracing::warn!("Could not settle with peer {}: {}", peer.mint_url, err);
+                }
+            }
+        }
+
+        Ok(settlements)
+    }
+
+    async fn settle_peer_balance(
+        &self,
+        peer: &Wallet,
+        threshold: Amount,
+    ) -> Result<Option<PeerSettlement>, Error> {
+        let balance = peer.total_balance().await?;
+        if balance == Amount::ZERO || balance < threshold {
+            return Ok(None);
+        }
+
+        let processor = self
+            .get_payment_processor(peer.unit.clone(), PaymentMethod::Known(KnownMethod::Bolt11))?;
+
+        // The Lightning fee reserve of the peer is paid from the balance as well, so the amount
+        // is lowered once the reserve is known
+        let processor = &processor;
+        let quoted = quote_within_fee_reserve(balance, balance, Amount::ONE, |amount| async move {
+            let invoice = processor
+                .create_incoming_payment_request(IncomingPaymentOptions::Bolt11(
+                    Bolt11IncomingPaymentOptions {
+                        description: Some(format!("Settlement from {}", peer.mint_url)),
+                        amount: amount.with_unit(peer.unit.clone()),
+                        unix_expiry: None,
+                        hash_description: false,
+                    },
+                ))
+                .await
+                .map_err(|err| {
+                    tracing::error!("Could not create settlement invoice: {}", err);
+                    Error::InvalidPaymentRequest
+                })?;
+
+            let quote = peer
+                .melt_quote(PaymentMethod::BOLT11, invoice.request, None, None)
+                .await?;
+
+            Ok::<_, Error>(((), quote))
+        })
+        .await?;
+
+        let Some(((), quote)) = quoted else {
+            return Ok(None);
+        };
+
+        let melted = peer
+            .prepare_melt(&quote.id, HashMap::new())
+            .await?
+            .confirm()
+            .await?;
+
+        Ok(Some(PeerSettlement {
+            mint_url: peer.mint_url.clone(),
+            amount: melted.amount(),
+            fee_paid: melted.fee_paid(),
+        }))
+    }
+
+    /// Settle peer balances periodically until `shutdown` is notified
+    pub(crate) fn spawn_peer_settlement(mint: Arc<Mint>, shutdown: Arc<Notify>) {
+        let Some(interval) = mint
+            .peer_mints
+            .as_ref()
+            .map(|peer_mints| peer_mints.settlement_interval)
+        else {
+            return;
+        };
+
+        tokio::spawn(async move {
+            let shutdown = shutdown.notified();
+            tokio::pin!(shutdown);
+
+            loop {
+                tokio::select! {
+                    _ = &mut shutdown => break,
+                    _ = tokio::time::sleep(interval) => {
+                        if let Err(err) = mint.resolve_pending_peer_swaps().await {
+                            tracing::warn!("Resolving pending peer swaps failed: {}", err);
+                        }
+                        if let Err(err) = mint.settle_peer_balances().await {
+                            tracing::warn!("Peer settlement failed: {}", err);
+                        }
+                    }
+                }
+            }
+        });
+    }
+}
+
+/// Check that claiming the swap inputs at `peer` paid for all outputs
+fn ensure_peer_paid(peer: &MintUrl, received: Amount, output_amount: Amount) -> Result<(), Error> {
+    if received < output_amount {
+        tracing::error!(
+            "Received {} from peer {} for a swap of {}, not signing outputs",
+            received,
+            peer,
+            output_amount
+        );
+        return Err(Error::TransactionUnbalanced(
+            received.to_u64(),
+            output_amount.to_u64(),
+            0,
+        ));
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use super::*;
+    use crate::test_helpers::mint::create_test_mint;
+
+    #[tokio::test]
+    async fn own_proofs_are_not_routed_to_peers() {
+        let mint = create_test_mint()
+            .await
+            .unwrap()
+            .with_peer_mints(PeerMints::new(Vec::new()));
+
+        let keyset_id = mint.keysets.load()[0].id;
+        let proof: crate::nuts::Proof = serde_json::from_value(serde_json::json!({
+            "amount": 1,
+            "id": keyset_id.to_string(),
+            "secret": "407915bc212be61a77e3e6d2aeb4c727980bda51cd06a6afc29e2861768a7837",
+            "C": "02bc9097997d81afb2cc7346b5e4345a9346bd2a506eb7958598a72f0cf85163ea"
+        }))
+        .unwrap();
+
+        assert!(mint.peer_for_swap_inputs(&vec![proof]).await.is_none());
+        assert!(mint.settle_peer_balances().await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn pending_swap_of_unknown_peer_stays_pending() {
+        let mint = create_test_mint()
+            .await
+            .unwrap()
+            .with_peer_mints(PeerMints::new(Vec::new()));
+
+        let pending = PendingPeerSwap {
+            peer: MintUrl::from_str("https://peer.example.com").unwrap(),
+            operation_id: Uuid::now_v7(),
+            input_amount: Amount::from(8),
+            peer_fee: Amount::ZERO,
+            inputs: Vec::new(),
+            outputs: Vec::new(),
+            wallet_saga_ids: Vec::new(),
+        };
+        mint.record_pending_peer_swap(&pending).await.unwrap();
+
+        mint.resolve_pending_peer_swaps().await.unwrap();
+
+        let keys = mint
+            .localstore
+            .kv_list(
+                PEERING_KV_NAMESPACE,
+                PENDING_PEER_SWAPS_KV_SECONDARY_NAMESPACE,
+            )
+            .await
+            .unwrap();
+        assert_eq!(keys, vec![pending.operation_id.to_string()]);
+    }
+
+    #[test]
+    fn short_paying_peer_is_rejected() {
+        let peer = MintUrl::from_str("https://peer.example.com").unwrap();
+
+        assert!(ensure_peer_paid(&peer, Amount::from(8), Amount::from(8)).is_ok());
+        assert!(matches!(
+            ensure_peer_paid(&peer, Amount::from(7), Amount::from(8)),
+            Err(Error::TransactionUnbalanced(7, 8, 0))
+        ));
+    }
+}
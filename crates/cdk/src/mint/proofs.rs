@@ -18,6 +18,7 @@ impl Mint {
     ///
     /// - [`Error::UnexpectedProofState`] if the state transition is invalid
     /// - [`Error::TokenAlreadySpent`] if the database rejects the update (proofs already spent)
+    /// - [`Error::ConcurrentUpdate`] if the proofs changed since they were acquired
     pub async fn update_proofs_state(
         tx: &mut DynMintTransaction,
         proofs: &mut Acquired<ProofsWithState>,
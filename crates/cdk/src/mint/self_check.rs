@@ -0,0 +1,242 @@
+//! Checks run before the mint starts serving requests
+//!
+//! A mint running against a database written by a newer version, with keys that do not match the
+//! published keysets, or without a reachable payment backend would hand out unusable ecash or
+//! lose funds. These checks let the operator refuse to start instead.
+//!
+//! The payment backends are checked against the methods the mint advertises in its info, so the
+//! checks must run on the built mint before it starts serving requests.
+
+use cdk_common::common::PaymentProcessorKey;
+use cdk_common::dhke::blind_message;
+use cdk_common::nut00::KnownMethod;
+use cdk_common::nuts::{BlindedMessage, CurrencyUnit, Id, PaymentMethod};
+use cdk_common::payment::{DynMintPayment, SettingsResponse};
+use cdk_common::secret::Secret;
+use tracing::instrument;
+
+use super::{Error, Mint};
+use crate::Amount;
+
+/// Failures found by [`Mint::self_check`]
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SelfCheckReport {
+    /// Description of each failed check
+    pub failures: Vec<String>,
+}
+
+impl SelfCheckReport {
+    /// Whether every check passed
+    pub fn is_ok(&self) -> bool {
+        self.failures.is_empty()
+    }
+}
+
+impl std::fmt::Display for SelfCheckReport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.failures.join("; "))
+    }
+}
+
+impl Mint {
+    /// Check the database schema, keysets and payment backends
+    ///
+    /// Every mint and melt method of the mint info must have a reachable backend supporting the
+    /// method, and every melt backend that reports its liquidity must be able to pay at least the
+    /// minimum melt amount.
+    ///
+    /// Failed checks are collected in the report; an error is only returned if the checks could
+    /// not be run at all.
+    #[instrument(skip_all)]
+    pub async fn self_check(&self) -> Result<SelfCheckReport, Error> {
+        let mut report = SelfCheckReport::default();
+
+        let unknown_migrations = self.localstore.unknown_migrations().await?;
+        if !unknown_migrations.is_empty() {
+            report.failures.push(format!(
+                "Database has migrations unknown to this version: {}",
+                unknown_migrations.join(", ")
+            ));
+        }
+
+        for keyset in self.keysets.load().iter().filter(|keyset| keyset.active) {
+            if let Err(err) = self.check_keyset_signs(keyset.id).await {
+                report
+                    .failures
+                    .push(format!("Keyset {} failed to sign: {}", keyset.id, err));
+            }
+        }
+
+        let mint_info = self.mint_info().await?;
+
+        if !mint_info.nuts.nut04.disabled {
+            for settings in &mint_info.nuts.nut04.methods {
+                if let Err(failure) = self.check_backend(&settings.unit, &settings.method).await {
+                    report.failures.push(failure);
+                }
+            }
+        }
+
+        if !mint_info.nuts.nut05.disabled {
+            for settings in &mint_info.nuts.nut05.methods {
+                let processor = match self.check_backend(&settings.unit, &settings.method).await {
+                    Ok(processor) => processor,
+                    Err(failure) => {
+                        report.failures.push(failure);
+                        continue;
+                    }
+                };
+
+                let required = settings.min_amount.map(u64::from).unwrap_or(1).max(1);
+                if let Err(failure) =
+                    check_liquidity(&processor, &settings.unit, &settings.method, required).await
+                {
+                    report.failures.push(failure);
+                }
+            }
+        }
+
+        for failure in &report.failures {
+            tracing::error!("Self check failed: {}", failure);
+        }
+
+        Ok(report)
+    }
+
+    /// Check that the backend of `unit` and `method` is reachable and supports the method
+    async fn check_backend(
+        &self,
+        unit: &CurrencyUnit,
+        method: &PaymentMethod,
+    ) -> Result<DynMintPayment, String> {
+        let processor = self
+            .payment_processors
+            .get(&PaymentProcessorKey::new(unit.clone(), method.clone()))
+            .cloned()
+            .ok_or_else(|| format!("No payment backend for advertised method {unit} {method}"))?;
+
+        let settings = processor
+            .get_settings()
+            .await
+            .map_err(|err| format!("Payment backend {unit} {method} is unreachable: {err}"))?;
+
+        if !supports_method(&settings, method) {
+            return Err(format!(
+                "Payment backend {unit} {method} does not support the advertised method"
+            ));
+        }
+
+        Ok(processor)
+    }
+
+    /// Sign a random message with the smallest amount of `keyset_id` and verify the signature
+    /// against the published key
+    async fn check_keyset_signs(&self, keyset_id: Id) -> Result<(), Error> {
+        let keyset = self
+            .keysets
+            .load()
+            .iter()
+            .find(|keyset| keyset.id == keyset_id)
+            .cloned()
+            .ok_or(Error::UnknownKeySet)?;
+
+        let amount = keyset
+            .amounts
+            .iter()
+            .min()
+            .copied()
+            .map(Amount::from)
+            .ok_or(Error::AmountKey)?;
+        let mint_pubkey = keyset.keys.amount_key(amount).ok_or(Error::AmountKey)?;
+
+        let (blinded_secret, _) = blind_message(&Secret::generate().to_bytes(), None)?;
+        let signature = self
+            .blind_sign(vec![BlindedMessage::new(amount, keyset_id, blinded_secret)])
+            .await?
+            .pop()
+            .ok_or(Error::SignatureMissingOrInvalid)?;
+
+        signature.verify_dleq(mint_pubkey, blinded_secret)?;
+
+        Ok(())
+    }
+}
+
+/// Whether the backend settings enable `method`
+fn supports_method(settings: &SettingsResponse, method: &PaymentMethod) -> bool {
+    match method {
+        PaymentMethod::Known(KnownMethod::Bolt11) => settings.bolt11.is_some(),
+        PaymentMethod::Known(KnownMethod::Bolt12) => settings.bolt12.is_some(),
+        PaymentMethod::Known(KnownMethod::Onchain) => settings.onchain.is_some(),
+        PaymentMethod::Custom(name) => settings.custom.contains_key(name),
+    }
+}
+
+/// Check that a melt backend can pay at least `required` in `unit`
+///
+/// Backends that do not report their liquidity pass; liquidity that cannot be converted to `unit`
+/// only has to be non zero.
+async fn check_liquidity(
+    processor: &DynMintPayment,
+    unit: &CurrencyUnit,
+    method: &PaymentMethod,
+    required: u64,
+) -> Result<(), String> {
+    let Some(liquidity) = processor.outbound_liquidity().await.map_err(|err| {
+        format!("Payment backend {unit} {method} failed to report its liquidity: {err}")
+    })?
+    else {
+        return Ok(());
+    };
+
+    let insufficient = match liquidity.convert_to(unit) {
+        Ok(available) => available.value() < required,
+        Err(_) => liquidity.value() == 0,
+    };
+
+    if insufficient {
+        return Err(format!(
+            "Payment backend {unit} {method} has {} of outbound liquidity, below the minimum melt of {required} {unit}",
+            liquidity.display_with_unit()
+        ));
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use cdk_common::nuts::{CurrencyUnit, MintMethodSettings, PaymentMethod};
+
+    use crate::test_helpers::mint::create_test_mint;
+
+    #[tokio::test]
+    async fn test_self_check_passes_for_healthy_mint() {
+        let mint = create_test_mint().await.unwrap();
+
+        let report = mint.self_check().await.unwrap();
+
+        assert!(report.is_ok(), "{report}");
+    }
+
+    #[tokio::test]
+    async fn test_self_check_fails_for_advertised_method_without_backend() {
+        let mint = create_test_mint().await.unwrap();
+
+        let mut mint_info = mint.mint_info().await.unwrap();
+        mint_info.nuts.nut04.methods.push(MintMethodSettings {
+            method: PaymentMethod::Custom("paypal".to_string()),
+            unit: CurrencyUnit::Sat,
+            method_name: None,
+            min_amount: None,
+            max_amount: None,
+            options: None,
+        });
+        mint.set_mint_info(mint_info).await.unwrap();
+
+        let report = mint.self_check().await.unwrap();
+
+        assert!(!report.is_ok());
+        assert!(report.failures[0].contains("paypal"));
+    }
+}
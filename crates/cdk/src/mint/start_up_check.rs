@@ -24,7 +24,10 @@ enum SwapSagaRecoveryAction {
 
 impl Mint {
     /// Get incomplete melt saga by quote_id
-    async fn get_melt_saga_by_quote_id(&self, quote_id: &str) -> Result<Option<Saga>, Error> {
+    pub(super) async fn get_melt_saga_by_quote_id(
+        &self,
+        quote_id: &str,
+    ) -> Result<Option<Saga>, Error> {
         let incomplete_sagas = self
             .localstore
             .get_incomplete_sagas(OperationKind::Melt)
@@ -11,16 +11,21 @@ use cdk_common::nut17::NotificationId;
 use cdk_common::payment::DynMintPayment;
 use cdk_common::pub_sub::{Pubsub, Spec, Subscriber};
 use cdk_common::subscription::SubId;
+use cdk_common::task::spawn;
 use cdk_common::{
     Amount, BlindSignature, CurrencyUnit, MeltQuoteBolt11Response, MeltQuoteBolt12Response,
     MeltQuoteOnchainResponse, MeltQuoteState, MintQuoteBolt11Response, MintQuoteBolt12Response,
     MintQuoteCustomResponse, MintQuoteOnchainResponse, MintQuoteState, NotificationPayload,
     ProofState, PublicKey, QuoteId,
 };
+use tokio::sync::broadcast;
 
 use super::Mint;
 use crate::event::MintEvent;
 
+/// Number of events buffered on the event bus for slow receivers
+const EVENT_BUS_CAPACITY: usize = 1024;
+
 /// Mint subtopics
 #[derive(Clone)]
 #[allow(missing_debug_implementations)]
@@ -205,8 +210,16 @@ impl Spec for MintPubSubSpec {
 }
 
 /// PubsubManager
+///
+/// Every state change of the mint is published on a broadcast bus and only there. Transports
+/// consume the whole bus through [`PubSubManager::subscribe_events`], like the mintd webhooks,
+/// or subscribe to single topics through the NUT-17 pubsub this manager derefs to, like the
+/// websocket. The NUT-17 pubsub is itself a consumer of the bus.
 #[allow(missing_debug_implementations)]
-pub struct PubSubManager(Pubsub<MintPubSubSpec>);
+pub struct PubSubManager {
+    pubsub: Arc<Pubsub<MintPubSubSpec>>,
+    events: broadcast::Sender<MintEvent<QuoteId>>,
+}
 
 impl PubSubManager {
     /// Create a new instance
@@ -216,7 +229,43 @@ impl PubSubManager {
             Arc<HashMap<PaymentProcessorKey, DynMintPayment>>,
         ),
     ) -> Arc<Self> {
-        Arc::new(Self(Pubsub::new(MintPubSubSpec::new_instance(context))))
+        let pubsub = Arc::new(Pubsub::new(MintPubSubSpec::new_instance(context)));
+        let (events, receiver) = broadcast::channel(EVENT_BUS_CAPACITY);
+
+        spawn(Self::forward_to_topics(Arc::clone(&pubsub), receiver));
+
+        Arc::new(Self { pubsub, events })
+    }
+
+    /// Deliver the events of the bus to the NUT-17 topic subscribers until the bus closes
+    async fn forward_to_topics(
+        pubsub: Arc<Pubsub<MintPubSubSpec>>,
+        mut events: broadcast::Receiver<MintEvent<QuoteId>>,
+    ) {
+        loop {
+            match events.recv().await {
+                Ok(event) => {
+                    if let Err(err) = pubsub.publish_now(event) {
+                        tracing::error!("Could not publish mint event to subscribers: {err}");
+                    }
+                }
+                Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                    tracing::warn!("NUT-17 subscribers skipped {} mint events", skipped);
+                }
+                Err(broadcast::error::RecvError::Closed) => break,
+            }
+        }
+    }
+
+    /// Publish an event on the bus
+    pub fn publish<E: Into<MintEvent<QuoteId>>>(&self, event: E) {
+        // The topic forwarder keeps a receiver for as long as the bus is open
+        let _ = self.events.send(event.into());
+    }
+
+    /// Receive every event published by the mint
+    pub fn subscribe_events(&self) -> broadcast::Receiver<MintEvent<QuoteId>> {
+        self.events.subscribe()
     }
 
     /// Helper function to emit a ProofState status
@@ -362,7 +411,7 @@ impl Deref for PubSubManager {
     type Target = Pubsub<MintPubSubSpec>;
 
     fn deref(&self) -> &Self::Target {
-        &self.0
+        &self.pubsub
     }
 }
 
@@ -370,7 +419,9 @@ impl Deref for PubSubManager {
 mod tests {
     use cdk_common::database::DynMintDatabase;
     use cdk_common::mint::MintQuote;
+    use cdk_common::nut17::Kind;
     use cdk_common::payment::PaymentIdentifier;
+    use cdk_common::subscription::Params;
     use cdk_common::QuoteId;
 
     use super::*;
@@ -443,4 +494,61 @@ mod tests {
 
         assert_eq!(quote_ids, vec![first_quote_id, second_quote_id]);
     }
+
+    #[tokio::test]
+    async fn published_events_reach_the_bus() {
+        let db: DynMintDatabase = Arc::new(
+            cdk_sqlite::mint::memory::empty()
+                .await
+                .expect("in-memory mint database"),
+        );
+        let manager = PubSubManager::new((db, Arc::new(HashMap::new())));
+        let mut events = manager.subscribe_events();
+
+        let quote_id = QuoteId::new();
+        manager.mint_quote_payment(
+            &paid_bolt11_quote(quote_id.clone(), 21),
+            Amount::new(21, CurrencyUnit::Sat),
+        );
+
+        match events.recv().await.expect("event").into_inner() {
+            NotificationPayload::MintQuoteBolt11Response(response) => {
+                assert_eq!(response.quote, quote_id);
+                assert_eq!(response.state, MintQuoteState::Paid);
+            }
+            payload => panic!("unexpected payload: {payload:?}"),
+        }
+    }
+
+    #[tokio::test]
+    async fn topic_subscribers_are_fed_from_the_bus() {
+        let db: DynMintDatabase = Arc::new(
+            cdk_sqlite::mint::memory::empty()
+                .await
+                .expect("in-memory mint database"),
+        );
+        let manager = PubSubManager::new((db, Arc::new(HashMap::new())));
+
+        let quote_id = QuoteId::new();
+        let mut subscription = manager
+            .subscribe(Params {
+                id: Arc::new(SubId::from("bus-test")),
+                kind: Kind::Bolt11MintQuote,
+                filters: vec![quote_id.to_string()],
+            })
+            .expect("subscribe");
+
+        manager.mint_quote_payment(
+            &paid_bolt11_quote(quote_id.clone(), 21),
+            Amount::new(21, CurrencyUnit::Sat),
+        );
+
+        match subscription.recv().await.expect("event").into_inner() {
+            NotificationPayload::MintQuoteBolt11Response(response) => {
+                assert_eq!(response.quote, quote_id);
+                assert_eq!(response.state, MintQuoteState::Paid);
+            }
+            payload => panic!("unexpected payload: {payload:?}"),
+        }
+    }
 }
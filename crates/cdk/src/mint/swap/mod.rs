@@ -1,4 +1,3 @@
-use cdk_common::SpendingConditionVerification;
 use swap_saga::SwapSaga;
 use tracing::instrument;
 
@@ -21,6 +20,8 @@ impl Mint {
         let metrics = super::MintMetricGuard::new("process_swap_request");
 
         let result = async {
+            self.check_not_in_maintenance()?;
+
             swap_request.input_amount()?;
             swap_request.output_amount()?;
 
@@ -34,6 +35,12 @@ impl Mint {
                 ));
             }
 
+            // Inputs issued by a trusted peer mint are claimed at that mint instead
+            #[cfg(feature = "peering")]
+            if let Some(peer) = self.peer_for_swap_inputs(input_proofs).await {
+                return self.process_peer_swap_request(peer, &swap_request).await;
+            }
+
             // Verify inputs (cryptographic verification, no DB needed)
             let input_verification = self.verify_inputs(input_proofs).await.map_err(|err| {
                 tracing::debug!("Input verification failed: {:?}", err);
@@ -42,7 +49,7 @@ impl Mint {
 
             // Verify spending conditions (NUT-10/NUT-11/NUT-14), i.e. P2PK
             // and HTLC (including SIGALL)
-            swap_request.verify_spending_conditions()?;
+            self.verify_spending_conditions(&swap_request)?;
 
             // Step 1: Initialize the swap saga
             let init_saga =
@@ -4,7 +4,7 @@ use std::sync::Arc;
 use cdk_common::database::DynMintDatabase;
 use cdk_common::mint::{Operation, Saga, SwapSagaState};
 use cdk_common::nuts::BlindedMessage;
-use cdk_common::{database, Error, Proofs, ProofsMethods, PublicKey, QuoteId, State};
+use cdk_common::{database, Amount, Error, Proofs, ProofsMethods, PublicKey, QuoteId, State};
 use tracing::instrument;
 
 use self::compensation::{CompensatingAction, RemoveSwapSetup};
@@ -152,32 +152,60 @@ impl<'a> SwapSaga<'a, Initial> {
             err
         })?;
 
-        // Verify balance within the transaction
-        self.mint
-            .verify_transaction_balanced(
-                input_verification.clone(),
-                output_verification.clone(),
+        // Swaps between units are balanced at the conversion rate instead
+        #[cfg(feature = "conversion")]
+        let conversion = self
+            .mint
+            .verify_conversion_balanced(
+                &input_verification,
+                &output_verification,
                 input_proofs,
+                self.operation_id,
             )
             .await?;
+        #[cfg(feature = "conversion")]
+        let is_conversion = conversion.is_some();
+        #[cfg(not(feature = "conversion"))]
+        let is_conversion = false;
+
+        // Verify balance within the transaction
+        if !is_conversion {
+            self.mint
+                .verify_transaction_balanced(
+                    input_verification.clone(),
+                    output_verification.clone(),
+                    input_proofs,
+                )
+                .await?;
+        }
 
         // Calculate amounts to create Operation
         let total_redeemed = input_verification.amount;
-        let total_issued = output_verification.amount;
+        let total_issued: Amount = output_verification.amount.into();
 
         let fee_breakdown = self.mint.get_proofs_fee(input_proofs).await?;
 
+        // The operation is recorded in the unit of the inputs. The outputs of a swap between
+        // units are recorded as the input value they were bought with, their own amount and
+        // unit are kept in the conversion record.
+        #[cfg(feature = "conversion")]
+        let total_issued = match &conversion {
+            Some(record) => record.input_amount.try_sub(record.fee)?,
+            None => total_issued,
+        };
+
         // Create Operation with actual amounts now that we know them
         // Convert typed amounts to untyped for Operation::new
         let operation = Operation::new(
             self.state_data.operation_id,
             cdk_common::mint::OperationKind::Swap,
-            total_issued.clone().into(),
+            total_issued,
             total_redeemed.clone().into(),
             fee_breakdown.total,
             None, // complete_at
             None, // payment_method (not applicable for swap)
-        );
+        )
+        .with_unit(total_redeemed.unit().clone());
 
         let mut tx = self.db.begin_transaction().await?;
 
@@ -259,6 +287,8 @@ impl<'a> SwapSaga<'a, Initial> {
                 ys,
                 operation,
                 fee_breakdown,
+                #[cfg(feature = "conversion")]
+                conversion,
             },
         })
     }
@@ -308,6 +338,8 @@ impl<'a> SwapSaga<'a, SetupComplete> {
                         signatures,
                         operation: self.state_data.operation,
                         fee_breakdown: self.state_data.fee_breakdown,
+                        #[cfg(feature = "conversion")]
+                        conversion: self.state_data.conversion,
                     },
                 })
             }
@@ -425,6 +457,28 @@ impl SwapSaga<'_, Signed> {
             return Err(err.into());
         }
 
+        #[cfg(feature = "conversion")]
+        if let Some(conversion) = &self.state_data.conversion {
+            let written = match serde_json::to_vec(conversion) {
+                Ok(record) => tx
+                    .kv_write(
+                        crate::mint::AUDIT_KV_NAMESPACE,
+                        crate::mint::conversion::CONVERSION_KV_SECONDARY_NAMESPACE,
+                        &self.operation_id.to_string(),
+                        &record,
+                    )
+                    .await
+                    .map_err(Error::from),
+                Err(err) => Err(err.into()),
+            };
+
+            if let Err(err) = written {
+                tx.rollback().await?;
+                self.compensate_all().await?;
+                return Err(err);
+            }
+        }
+
         // Delete saga - swap completed successfully (best-effort, atomic with TX2)
         // Don't fail the swap if saga deletion fails - orphaned saga will be
         // cleaned up on next recovery
@@ -444,6 +498,21 @@ impl SwapSaga<'_, Signed> {
         // Clear compensations - swap is complete
         self.compensations.clear();
 
+        #[cfg(feature = "conversion")]
+        if let Some(conversion) = &self.state_data.conversion {
+            tracing::info!(
+                "Swap {} converted {} {} to {} {} at {}:{} ({} bps spread)",
+                self.operation_id,
+                conversion.input_amount,
+                conversion.from_unit,
+                conversion.output_amount,
+                conversion.to_unit,
+                conversion.rate.from_amount,
+                conversion.rate.to_amount,
+                conversion.spread_bps
+            );
+        }
+
         Ok(cdk_common::nuts::SwapResponse::new(
             self.state_data.signatures,
         ))
@@ -20,6 +20,9 @@ pub struct SetupComplete {
     pub ys: Vec<PublicKey>,
     pub operation: Operation,
     pub fee_breakdown: crate::fees::ProofsFeeBreakdown,
+    /// Conversion between units, if inputs and outputs differ in unit
+    #[cfg(feature = "conversion")]
+    pub conversion: Option<crate::mint::ConversionRecord>,
 }
 
 /// Signed state - has everything including signatures.
@@ -32,4 +35,7 @@ pub struct Signed {
     pub signatures: Vec<BlindSignature>,
     pub operation: Operation,
     pub fee_breakdown: crate::fees::ProofsFeeBreakdown,
+    /// Conversion between units, if inputs and outputs differ in unit
+    #[cfg(feature = "conversion")]
+    pub conversion: Option<crate::mint::ConversionRecord>,
 }
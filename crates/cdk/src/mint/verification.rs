@@ -1,6 +1,8 @@
 use std::collections::HashSet;
 
-use cdk_common::{Amount, BlindedMessage, CurrencyUnit, Id, Proofs, ProofsMethods, PublicKey};
+use cdk_common::{
+    Amount, BlindedMessage, CurrencyUnit, Id, PaymentMethod, Proofs, ProofsMethods, PublicKey,
+};
 use tracing::instrument;
 
 use super::{Error, Mint};
@@ -19,6 +21,37 @@ pub struct Verification {
 }
 
 impl Mint {
+    /// Verify that `amount` is within the limits configured for a payment method and unit
+    pub fn check_amount_limits(
+        method: &PaymentMethod,
+        unit: &CurrencyUnit,
+        min_amount: Option<Amount>,
+        max_amount: Option<Amount>,
+        amount: Amount,
+    ) -> Result<(), Error> {
+        if let Some(min) = min_amount.filter(|min| amount < *min) {
+            tracing::debug!("Amount {amount} is below the {method} {unit} minimum of {min}");
+            return Err(Error::AmountBelowMinimum {
+                method: method.clone(),
+                unit: unit.clone(),
+                min,
+                amount,
+            });
+        }
+
+        if let Some(max) = max_amount.filter(|max| amount > *max) {
+            tracing::debug!("Amount {amount} is above the {method} {unit} maximum of {max}");
+            return Err(Error::AmountAboveMaximum {
+                method: method.clone(),
+                unit: unit.clone(),
+                max,
+                amount,
+            });
+        }
+
+        Ok(())
+    }
+
     /// Verify that the inputs to the transaction are unique
     #[instrument(skip_all)]
     pub fn check_inputs_unique(inputs: &Proofs) -> Result<(), Error> {
@@ -59,7 +92,8 @@ impl Mint {
 
     /// Verify output keyset
     ///
-    /// Checks that the outputs are all of the same unit and the keyset is active
+    /// Checks that the outputs are all of the same unit, the keyset is active and the amounts are
+    /// denominations of the keyset
     #[instrument(skip_all)]
     pub fn verify_outputs_keyset(&self, outputs: &[BlindedMessage]) -> Result<CurrencyUnit, Error> {
         let mut keyset_units = HashSet::new();
@@ -83,6 +117,17 @@ impl Mint {
                         );
                         return Err(Error::ExpiredKeyset);
                     }
+                    if outputs
+                        .iter()
+                        .filter(|output| output.keyset_id == *id)
+                        .any(|output| !keyset.amounts.contains(&output.amount.to_u64()))
+                    {
+                        tracing::debug!(
+                            "Transaction attempted with unsupported denomination in outputs of keyset {}.",
+                            id
+                        );
+                        return Err(Error::AmountKey);
+                    }
                     keyset_units.insert(keyset.unit);
                 }
                 None => {
@@ -111,7 +156,8 @@ impl Mint {
 
     /// Verify input keyset
     ///
-    /// Checks that the inputs are all of the same unit
+    /// Checks that the inputs are all of the same unit and the amounts are denominations of the
+    /// keyset
     #[instrument(skip_all)]
     pub async fn verify_inputs_keyset(&self, inputs: &Proofs) -> Result<CurrencyUnit, Error> {
         let mut keyset_units = HashSet::new();
@@ -128,6 +174,17 @@ impl Mint {
                         );
                         return Err(Error::ExpiredKeyset);
                     }
+                    if inputs
+                        .iter()
+                        .filter(|proof| proof.keyset_id == *id)
+                        .any(|proof| !keyset.amounts.contains(&proof.amount.to_u64()))
+                    {
+                        tracing::debug!(
+                            "Transaction attempted with unsupported denomination in inputs of keyset {}.",
+                            id
+                        );
+                        return Err(Error::AmountKey);
+                    }
                     keyset_units.insert(keyset.unit);
                 }
                 None => {
@@ -0,0 +1,472 @@
+//! Deterministic test fixtures
+//!
+//! With the `test-utils` feature, crates building on cdk can write wallet tests against keysets
+//! whose private keys are known instead of copying fixture code from the tests of this crate.
+//! A [`TestKeyset`] is derived from a seed and signs proofs that verify against its keys,
+//! including their DLEQ proofs. [`FakeMintBuilder`] serves such keysets through a
+//! [`MockMintConnector`] and funds wallets with valid proofs.
+//!
+//! [`MockMintConnector`]: crate::wallet::test_utils::MockMintConnector
+
+use std::collections::BTreeMap;
+use std::str::FromStr;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+
+use bitcoin::hashes::sha256::Hash as Sha256Hash;
+use bitcoin::hashes::Hash;
+use cdk_common::database::{self, WalletDatabase};
+use cdk_common::nut00::KnownMethod;
+use cdk_common::wallet::{MeltQuote, MintQuote, ProofInfo};
+
+use crate::amount::FeeAndAmounts;
+use crate::dhke::{blind_message, construct_proofs, sign_message};
+use crate::mint_url::MintUrl;
+use crate::nuts::{
+    BlindSignature, BlindedMessage, CurrencyUnit, Id, KeySet, KeySetInfo, Keys, MeltQuoteState,
+    MintInfo, MintQuoteState, PaymentMethod, Proof, Proofs, SecretKey, State,
+};
+use crate::secret::Secret;
+use crate::wallet::test_utils::{test_mint_info, MockMintConnector};
+use crate::wallet::{Wallet, WalletBuilder};
+use crate::{Amount, Error};
+
+/// Seed of [`TestKeyset::default`]
+pub const TEST_SEED: &[u8] = b"cdk test keyset";
+
+/// Mint URL of [`FakeMintBuilder::default`]
+pub const TEST_MINT_URL: &str = "https://fake-mint.example.com";
+
+/// Number of powers of two in the keysets of [`TestKeyset::new`]
+const TEST_KEYSET_MAX_ORDER: u32 = 32;
+
+fn tagged_hash(tag: &str, parts: &[&[u8]]) -> [u8; 32] {
+    let mut data = tag.as_bytes().to_vec();
+    for part in parts {
+        data.extend_from_slice(part);
+    }
+    Sha256Hash::hash(&data).to_byte_array()
+}
+
+/// Keyset whose private keys are derived from a seed
+///
+/// The same seed, unit and amounts always give the same keys and keyset id. Proofs created by
+/// [`TestKeyset::proofs`] have deterministic secrets too, each call continuing where the last
+/// one stopped so proofs are never reused.
+#[derive(Debug)]
+pub struct TestKeyset {
+    keyset: KeySet,
+    secret_keys: BTreeMap<Amount, SecretKey>,
+    seed: Vec<u8>,
+    counter: AtomicU64,
+}
+
+impl Clone for TestKeyset {
+    fn clone(&self) -> Self {
+        Self {
+            keyset: self.keyset.clone(),
+            secret_keys: self.secret_keys.clone(),
+            seed: self.seed.clone(),
+            counter: AtomicU64::new(self.counter.load(Ordering::SeqCst)),
+        }
+    }
+}
+
+impl Default for TestKeyset {
+    fn default() -> Self {
+        Self::new(TEST_SEED, CurrencyUnit::Sat)
+    }
+}
+
+impl TestKeyset {
+    /// Active keyset of `unit` with power of two amounts from 1 to 2^31 and no input fee
+    pub fn new(seed: &[u8], unit: CurrencyUnit) -> Self {
+        let amounts: Vec<u64> = (0..TEST_KEYSET_MAX_ORDER).map(|i| 1 << i).collect();
+        Self::with_amounts(seed, unit, &amounts)
+    }
+
+    /// Active keyset of `unit` with keys for `amounts` and no input fee
+    pub fn with_amounts(seed: &[u8], unit: CurrencyUnit, amounts: &[u64]) -> Self {
+        let unit_name = unit.to_string();
+        let secret_keys: BTreeMap<Amount, SecretKey> = amounts
+            .iter()
+            .map(|amount| {
+                let key = tagged_hash(
+                    "cdk_test_keyset_key",
+                    &[seed, unit_name.as_bytes(), amount.to_be_bytes().as_slice()],
+                );
+                // A hash is a valid secret key with overwhelming probability
+                let secret_key = SecretKey::from_slice(&key).expect("Valid secret key");
+                (Amount::from(*amount), secret_key)
+            })
+            .collect();
+
+        let keys = Keys::new(
+            secret_keys
+                .iter()
+                .map(|(amount, secret_key)| (*amount, secret_key.public_key()))
+                .collect(),
+        );
+
+        Self {
+            keyset: KeySet {
+                id: Id::v2_from_data(&keys, &unit, 0, None),
+                unit,
+                active: Some(true),
+                keys,
+                input_fee_ppk: 0,
+                final_expiry: None,
+            },
+            secret_keys,
+            seed: seed.to_vec(),
+            counter: AtomicU64::new(0),
+        }
+    }
+
+    /// Charge `input_fee_ppk` per input, changes the keyset id
+    pub fn with_input_fee_ppk(mut self, input_fee_ppk: u64) -> Self {
+        self.keyset.input_fee_ppk = input_fee_ppk;
+        self.update_id();
+        self
+    }
+
+    /// Expire the keyset at unix time `final_expiry`, changes the keyset id
+    pub fn with_final_expiry(mut self, final_expiry: u64) -> Self {
+        self.keyset.final_expiry = Some(final_expiry);
+        self.update_id();
+        self
+    }
+
+    /// Mark the keyset inactive
+    pub fn inactive(mut self) -> Self {
+        self.keyset.active = Some(false);
+        self
+    }
+
+    fn update_id(&mut self) {
+        self.keyset.id = Id::v2_from_data(
+            &self.keyset.keys,
+            &self.keyset.unit,
+            self.keyset.input_fee_ppk,
+            self.keyset.final_expiry,
+        );
+    }
+
+    /// Keyset id
+    pub fn id(&self) -> Id {
+        self.keyset.id
+    }
+
+    /// Keyset with its public keys, as served by the mint
+    pub fn keyset(&self) -> &KeySet {
+        &self.keyset
+    }
+
+    /// Keyset info, as listed by the mint
+    pub fn keyset_info(&self) -> KeySetInfo {
+        KeySetInfo {
+            id: self.keyset.id,
+            unit: self.keyset.unit.clone(),
+            active: self.keyset.active.unwrap_or(true),
+            input_fee_ppk: self.keyset.input_fee_ppk,
+            final_expiry: self.keyset.final_expiry,
+        }
+    }
+
+    /// Private key of `amount`
+    pub fn secret_key(&self, amount: Amount) -> Option<&SecretKey> {
+        self.secret_keys.get(&amount)
+    }
+
+    /// Blind sign `blinded_message` with a DLEQ proof, as the mint would
+    pub fn sign(&self, blinded_message: &BlindedMessage) -> Result<BlindSignature, Error> {
+        let secret_key = self
+            .secret_key(blinded_message.amount)
+            .ok_or(Error::AmountKey)?;
+        let c = sign_message(secret_key, &blinded_message.blinded_secret)?;
+
+        Ok(BlindSignature::new(
+            blinded_message.amount,
+            c,
+            self.keyset.id,
+            &blinded_message.blinded_secret,
+            secret_key,
+        )?)
+    }
+
+    /// Proof of `amount` for `secret`, signed by this keyset with a DLEQ proof
+    pub fn proof(&self, amount: Amount, secret: Secret) -> Result<Proof, Error> {
+        let blinding_factor = SecretKey::from_slice(&tagged_hash(
+            "cdk_test_keyset_blinding_factor",
+            &[self.seed.as_slice(), secret.as_bytes()],
+        ))?;
+        let (blinded_secret, r) = blind_message(secret.as_bytes(), Some(blinding_factor))?;
+        let signature = self.sign(&BlindedMessage::new(amount, self.keyset.id, blinded_secret))?;
+
+        construct_proofs(vec![signature], vec![r], vec![secret], &self.keyset.keys)?
+            .pop()
+            .ok_or(Error::Internal)
+    }
+
+    /// Proofs adding up to `amount`, split into the amounts of this keyset
+    pub fn proofs(&self, amount: Amount) -> Result<Proofs, Error> {
+        let fee_and_amounts: FeeAndAmounts = (
+            self.keyset.input_fee_ppk,
+            self.secret_keys
+                .keys()
+                .map(|amount| amount.to_u64())
+                .collect(),
+        )
+            .into();
+
+        amount
+            .split(&fee_and_amounts)?
+            .into_iter()
+            .map(|amount| {
+                let index = self.counter.fetch_add(1, Ordering::SeqCst);
+                let secret = Secret::new(
+                    tagged_hash(
+                        "cdk_test_keyset_secret",
+                        &[
+                            self.seed.as_slice(),
+                            self.keyset.id.to_bytes().as_slice(),
+                            index.to_be_bytes().as_slice(),
+                        ],
+                    )
+                    .iter()
+                    .map(|byte| format!("{byte:02x}"))
+                    .collect::<String>(),
+                );
+                self.proof(amount, secret)
+            })
+            .collect()
+    }
+}
+
+/// Unpaid bolt11 mint quote of `amount` with a fixed id and expiry
+pub fn mint_quote(id: &str, mint_url: MintUrl, amount: Amount) -> MintQuote {
+    MintQuote::new(
+        id.to_string(),
+        mint_url,
+        PaymentMethod::Known(KnownMethod::Bolt11),
+        Some(amount),
+        CurrencyUnit::Sat,
+        format!("lnbc{}n1test", amount),
+        u64::MAX,
+        None,
+    )
+}
+
+/// Paid bolt11 mint quote of `amount` with a fixed id and expiry
+pub fn paid_mint_quote(id: &str, mint_url: MintUrl, amount: Amount) -> MintQuote {
+    let mut quote = mint_quote(id, mint_url, amount);
+    quote.state = MintQuoteState::Paid;
+    quote.amount_paid = amount;
+    quote
+}
+
+/// Unpaid bolt11 melt quote of `amount` with a fixed id, expiry and fee reserve
+pub fn melt_quote(id: &str, mint_url: MintUrl, amount: Amount, fee_reserve: Amount) -> MeltQuote {
+    MeltQuote {
+        id: id.to_string(),
+        mint_url: Some(mint_url),
+        unit: CurrencyUnit::Sat,
+        amount,
+        request: format!("lnbc{}n1test", amount),
+        fee_reserve,
+        state: MeltQuoteState::Unpaid,
+        expiry: u64::MAX,
+        payment_proof: None,
+        estimated_blocks: None,
+        fee_index: None,
+        options: None,
+        payment_method: PaymentMethod::Known(KnownMethod::Bolt11),
+        used_by_operation: None,
+        version: 0,
+    }
+}
+
+/// Builder of a [`FakeMint`]
+#[derive(Debug, Clone)]
+pub struct FakeMintBuilder {
+    mint_url: MintUrl,
+    keysets: Vec<TestKeyset>,
+    mint_info: MintInfo,
+}
+
+impl Default for FakeMintBuilder {
+    fn default() -> Self {
+        Self {
+            mint_url: MintUrl::from_str(TEST_MINT_URL).expect("Valid mint url"),
+            keysets: Vec::new(),
+            mint_info: test_mint_info(),
+        }
+    }
+}
+
+impl FakeMintBuilder {
+    /// Fake mint at [`TEST_MINT_URL`], with [`TestKeyset::default`] unless keysets are added
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Serve the mint at `mint_url`
+    pub fn with_mint_url(mut self, mint_url: MintUrl) -> Self {
+        self.mint_url = mint_url;
+        self
+    }
+
+    /// Serve `keyset`, in addition to the keysets added before
+    pub fn with_keyset(mut self, keyset: TestKeyset) -> Self {
+        self.keysets.push(keyset);
+        self
+    }
+
+    /// Serve `mint_info` instead of the default test mint info
+    pub fn with_mint_info(mut self, mint_info: MintInfo) -> Self {
+        self.mint_info = mint_info;
+        self
+    }
+
+    /// Build the fake mint
+    pub fn build(self) -> FakeMint {
+        let keysets = if self.keysets.is_empty() {
+            vec![TestKeyset::default()]
+        } else {
+            self.keysets
+        };
+
+        let connector = MockMintConnector::new();
+        connector.set_mint_keys_response(Ok(keysets
+            .iter()
+            .map(|keyset| keyset.keyset().clone())
+            .collect()));
+        connector.set_mint_info_response(Ok(self.mint_info));
+
+        FakeMint {
+            mint_url: self.mint_url,
+            connector: Arc::new(connector),
+            keysets,
+        }
+    }
+}
+
+/// Mint served by a [`MockMintConnector`] with [`TestKeyset`]s
+///
+/// Responses to quotes, swaps and melts are configured on [`FakeMint::connector`].
+#[derive(Debug, Clone)]
+pub struct FakeMint {
+    mint_url: MintUrl,
+    connector: Arc<MockMintConnector>,
+    keysets: Vec<TestKeyset>,
+}
+
+impl FakeMint {
+    /// Url of the mint
+    pub fn mint_url(&self) -> &MintUrl {
+        &self.mint_url
+    }
+
+    /// Connector to pass to wallets with [`WalletBuilder::shared_client`]
+    pub fn connector(&self) -> Arc<MockMintConnector> {
+        Arc::clone(&self.connector)
+    }
+
+    /// Keysets of the mint
+    pub fn keysets(&self) -> &[TestKeyset] {
+        &self.keysets
+    }
+
+    /// First active keyset of `unit`
+    pub fn active_keyset(&self, unit: &CurrencyUnit) -> Option<&TestKeyset> {
+        self.keysets
+            .iter()
+            .find(|keyset| &keyset.keyset().unit == unit && keyset.keyset().active.unwrap_or(true))
+    }
+
+    /// Wallet of `unit` at this mint
+    pub fn wallet(
+        &self,
+        localstore: Arc<dyn WalletDatabase<database::Error> + Send + Sync>,
+        unit: CurrencyUnit,
+        seed: [u8; 64],
+    ) -> Result<Wallet, Error> {
+        WalletBuilder::new()
+            .mint_url(self.mint_url.clone())
+            .unit(unit)
+            .localstore(localstore)
+            .seed(seed)
+            .shared_client(self.connector())
+            .build()
+    }
+
+    /// Store unspent proofs of `amount` signed by the active keyset in `wallet`
+    pub async fn fund_wallet(&self, wallet: &Wallet, amount: Amount) -> Result<Proofs, Error> {
+        let keyset = self
+            .active_keyset(&wallet.unit)
+            .ok_or(Error::NoActiveKeyset)?;
+        let proofs = keyset.proofs(amount)?;
+
+        let proof_infos = proofs
+            .iter()
+            .map(|proof| {
+                ProofInfo::new(
+                    proof.clone(),
+                    self.mint_url.clone(),
+                    State::Unspent,
+                    wallet.unit.clone(),
+                )
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        wallet.localstore.update_proofs(proof_infos, vec![]).await?;
+
+        Ok(proofs)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_keysets_are_deterministic() {
+        let keyset = TestKeyset::default();
+        assert_eq!(keyset.id(), TestKeyset::default().id());
+        assert!(keyset.keyset().verify_id().is_ok());
+        assert_ne!(
+            keyset.id(),
+            TestKeyset::new(b"other seed", CurrencyUnit::Sat).id()
+        );
+        assert_ne!(keyset.id(), keyset.clone().with_input_fee_ppk(100).id());
+    }
+
+    #[test]
+    fn test_proofs_verify() {
+        let keyset = TestKeyset::new(TEST_SEED, CurrencyUnit::Sat);
+        let proofs = keyset.proofs(Amount::from(13)).unwrap();
+
+        assert_eq!(proofs.len(), 3);
+        for proof in &proofs {
+            let secret_key = keyset.secret_key(proof.amount).unwrap();
+            assert!(
+                crate::dhke::verify_message(secret_key, proof.c, proof.secret.as_bytes()).is_ok()
+            );
+            assert!(proof.verify_dleq(secret_key.public_key()).is_ok());
+        }
+
+        // Later calls never repeat secrets
+        let more = keyset.proofs(Amount::from(13)).unwrap();
+        assert!(more.iter().all(|proof| !proofs.contains(proof)));
+    }
+
+    #[tokio::test]
+    async fn test_fake_mint_funds_wallet() {
+        let mint = FakeMintBuilder::new().build();
+        let db = Arc::new(cdk_sqlite::wallet::memory::empty().await.unwrap());
+        let wallet = mint.wallet(db, CurrencyUnit::Sat, [0; 64]).unwrap();
+
+        mint.fund_wallet(&wallet, Amount::from(100)).await.unwrap();
+
+        assert_eq!(wallet.total_balance().await.unwrap(), Amount::from(100));
+    }
+}
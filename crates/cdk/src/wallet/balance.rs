@@ -1,9 +1,27 @@
+use cdk_common::rate::{convert_amount, RateOracle};
 use tracing::instrument;
 
 use crate::nuts::nut00::ProofsMethods;
-use crate::nuts::State;
+use crate::nuts::{CurrencyUnit, State};
 use crate::{Amount, Error, Wallet};
 
+/// Balance of a wallet split by the state of its funds
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BalanceBreakdown {
+    /// Unit of the wallet
+    pub unit: CurrencyUnit,
+    /// Spendable proofs
+    pub unspent: Amount,
+    /// Proofs used in an operation whose outcome is not known yet
+    pub pending: Amount,
+    /// Proofs being spent that the mint has not confirmed as spent yet
+    pub pending_spent: Amount,
+    /// Proofs reserved for an operation of the wallet
+    pub reserved: Amount,
+    /// Paid mint quotes that have not been minted yet
+    pub incoming: Amount,
+}
+
 impl Wallet {
     /// Total unspent balance of wallet
     #[instrument(skip(self))]
@@ -31,4 +49,130 @@ impl Wallet {
     pub async fn total_reserved_balance(&self) -> Result<Amount, Error> {
         Ok(self.get_reserved_proofs().await?.total_amount()?)
     }
+
+    /// Total unspent balance converted to `unit` at the current rate of `oracle`
+    ///
+    /// Meant for display, e.g. with `Amount::display_with_unit`; the balance can not be spent
+    /// in `unit`.
+    #[instrument(skip(self, oracle))]
+    pub async fn total_balance_in(
+        &self,
+        oracle: &dyn RateOracle,
+        unit: &CurrencyUnit,
+    ) -> Result<Amount, Error> {
+        let balance = self.total_balance().await?.with_unit(self.unit.clone());
+
+        Ok(convert_amount(oracle, balance, unit).await?.into())
+    }
+
+    /// Balance split into spendable and in-flight amounts
+    ///
+    /// Only the local store is read, mint quotes are not checked with the mint.
+    #[instrument(skip(self))]
+    pub async fn balance_breakdown(&self) -> Result<BalanceBreakdown, Error> {
+        let balance = |state: State| async move {
+            self.localstore
+                .get_balance(
+                    Some(self.mint_url.clone()),
+                    Some(self.unit.clone()),
+                    Some(vec![state]),
+                )
+                .await
+                .map(Amount::from)
+        };
+
+        let incoming = Amount::try_sum(
+            self.localstore
+                .get_unissued_mint_quotes()
+                .await?
+                .iter()
+                .filter(|quote| quote.mint_url == self.mint_url && quote.unit == self.unit)
+                .map(|quote| quote.amount_mintable()),
+        )?;
+
+        Ok(BalanceBreakdown {
+            unit: self.unit.clone(),
+            unspent: balance(State::Unspent).await?,
+            pending: balance(State::Pending).await?,
+            pending_spent: balance(State::PendingSpent).await?,
+            reserved: balance(State::Reserved).await?,
+            incoming,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use cdk_common::rate::StaticRates;
+
+    use super::*;
+    use crate::nuts::MintQuoteState;
+    use crate::wallet::test_utils;
+
+    #[tokio::test]
+    async fn total_balance_in_converts_unspent_balance() {
+        let db = test_utils::create_test_db().await;
+        let wallet = test_utils::create_test_wallet(db.clone()).await;
+        let mint_url = test_utils::test_mint_url();
+        let keyset_id = test_utils::test_keyset_id();
+
+        db.update_proofs(
+            vec![test_utils::test_proof_info(keyset_id, 2_000, mint_url)],
+            vec![],
+        )
+        .await
+        .unwrap();
+
+        let rates = StaticRates::new()
+            .with_rate(CurrencyUnit::Sat, CurrencyUnit::Usd, 1_000, 65)
+            .unwrap();
+
+        let balance = wallet
+            .total_balance_in(&rates, &CurrencyUnit::Usd)
+            .await
+            .unwrap();
+        assert_eq!(balance, Amount::from(130));
+        assert_eq!(balance.display_with_unit(&CurrencyUnit::Usd), "1.30 usd");
+    }
+
+    #[tokio::test]
+    async fn balance_breakdown_splits_states_and_paid_quotes() {
+        let db = test_utils::create_test_db().await;
+        let wallet = test_utils::create_test_wallet(db.clone()).await;
+        let mint_url = test_utils::test_mint_url();
+        let keyset_id = test_utils::test_keyset_id();
+
+        let mut proofs = Vec::new();
+        for (amount, state) in [
+            (64, State::Unspent),
+            (8, State::Pending),
+            (4, State::PendingSpent),
+            (2, State::Reserved),
+            (1, State::Spent),
+        ] {
+            let mut proof = test_utils::test_proof_info(keyset_id, amount, mint_url.clone());
+            proof.state = state;
+            proofs.push(proof);
+        }
+        db.update_proofs(proofs, vec![]).await.unwrap();
+
+        let mut paid = test_utils::test_mint_quote(mint_url.clone());
+        paid.state = MintQuoteState::Paid;
+        db.add_mint_quote(paid).await.unwrap();
+        db.add_mint_quote(test_utils::test_mint_quote(mint_url))
+            .await
+            .unwrap();
+
+        assert_eq!(
+            wallet.balance_breakdown().await.unwrap(),
+            BalanceBreakdown {
+                unit: CurrencyUnit::Sat,
+                unspent: Amount::from(64),
+                pending: Amount::from(8),
+                pending_spent: Amount::from(4),
+                reserved: Amount::from(2),
+                incoming: Amount::from(1000),
+            }
+        );
+    }
 }
@@ -0,0 +1,193 @@
+//! Results of operations on many items
+//!
+//! Batch operations process every item even when some of them fail, so callers only need to
+//! retry the items that did not succeed.
+
+use std::collections::HashSet;
+
+use cdk_common::wallet::WalletKey;
+use tracing::instrument;
+
+use super::wallet_repository::WalletRepository;
+use super::ReceiveOptions;
+use crate::{Amount, Error, Wallet};
+
+/// Outcome of a single item of a batch operation
+#[derive(Debug)]
+pub enum BatchOutcome<T> {
+    /// The item was processed
+    Success(T),
+    /// Processing the item failed
+    Failed(Error),
+    /// The item was not processed
+    Skipped(String),
+}
+
+impl<T> BatchOutcome<T> {
+    /// Whether the item was processed
+    pub fn is_success(&self) -> bool {
+        matches!(self, Self::Success(_))
+    }
+}
+
+impl<T> From<Result<T, Error>> for BatchOutcome<T> {
+    fn from(result: Result<T, Error>) -> Self {
+        match result {
+            Ok(value) => Self::Success(value),
+            Err(err) => Self::Failed(err),
+        }
+    }
+}
+
+/// Per item outcomes of a batch operation, in the order the items were processed
+///
+/// Items are identified by `K`, the position of the item in the input unless stated otherwise.
+#[derive(Debug)]
+pub struct BatchResult<T, K = usize> {
+    /// Item identifiers and their outcomes
+    pub items: Vec<(K, BatchOutcome<T>)>,
+}
+
+impl<T, K> Default for BatchResult<T, K> {
+    fn default() -> Self {
+        Self { items: Vec::new() }
+    }
+}
+
+impl<T, K> BatchResult<T, K> {
+    /// Record the outcome of `key`
+    pub fn push(&mut self, key: K, outcome: BatchOutcome<T>) {
+        self.items.push((key, outcome));
+    }
+
+    /// Whether every item was processed
+    pub fn is_complete(&self) -> bool {
+        self.items.iter().all(|(_, outcome)| outcome.is_success())
+    }
+
+    /// Items that were processed
+    pub fn successes(&self) -> impl Iterator<Item = (&K, &T)> {
+        self.items
+            .iter()
+            .filter_map(|(key, outcome)| match outcome {
+                BatchOutcome::Success(value) => Some((key, value)),
+                _ => None,
+            })
+    }
+
+    /// Items that failed
+    pub fn failures(&self) -> impl Iterator<Item = (&K, &Error)> {
+        self.items
+            .iter()
+            .filter_map(|(key, outcome)| match outcome {
+                BatchOutcome::Failed(err) => Some((key, err)),
+                _ => None,
+            })
+    }
+
+    /// Items that were skipped and why
+    pub fn skipped(&self) -> impl Iterator<Item = (&K, &str)> {
+        self.items
+            .iter()
+            .filter_map(|(key, outcome)| match outcome {
+                BatchOutcome::Skipped(reason) => Some((key, reason.as_str())),
+                _ => None,
+            })
+    }
+}
+
+impl<K> BatchResult<Amount, K> {
+    /// Total amount of the items that were processed
+    pub fn total_amount(&self) -> Result<Amount, Error> {
+        Ok(Amount::try_sum(
+            self.successes().map(|(_, amount)| *amount),
+        )?)
+    }
+}
+
+impl Wallet {
+    /// Receive several tokens
+    ///
+    /// A token that cannot be received does not stop the others from being received. Repeated
+    /// tokens are skipped.
+    #[instrument(skip_all)]
+    pub async fn receive_batch(
+        &self,
+        encoded_tokens: &[String],
+        opts: ReceiveOptions,
+    ) -> BatchResult<Amount> {
+        let mut seen = HashSet::new();
+        let mut results = BatchResult::default();
+
+        for (index, encoded_token) in encoded_tokens.iter().enumerate() {
+            if !seen.insert(encoded_token.as_str()) {
+                results.push(index, BatchOutcome::Skipped("Duplicate token".to_string()));
+                continue;
+            }
+
+            let received = self.receive(encoded_token, opts.clone()).await;
+            results.push(index, received.into());
+        }
+
+        results
+    }
+}
+
+impl WalletRepository {
+    /// Check the pending proofs of every wallet
+    ///
+    /// See [`Wallet::check_all_pending_proofs`]. A wallet that fails to check does not stop the
+    /// others from being checked.
+    #[instrument(skip(self))]
+    pub async fn check_all_pending_proofs(&self) -> BatchResult<Amount, WalletKey> {
+        let mut results = BatchResult::default();
+
+        for wallet in self.get_wallets().await {
+            let key = WalletKey::new(wallet.mint_url.clone(), wallet.unit.clone());
+            let pending = wallet.check_all_pending_proofs().await;
+            results.push(key, pending.into());
+        }
+
+        results
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::wallet::test_utils;
+
+    #[tokio::test]
+    async fn receive_batch_reports_each_token() {
+        let db = test_utils::create_test_db().await;
+        let wallet = test_utils::create_test_wallet(db).await;
+
+        let tokens = vec![
+            "not a token".to_string(),
+            "also not a token".to_string(),
+            "not a token".to_string(),
+        ];
+
+        let results = wallet
+            .receive_batch(&tokens, ReceiveOptions::default())
+            .await;
+
+        assert_eq!(results.items.len(), 3);
+        assert!(!results.is_complete());
+        assert_eq!(
+            results
+                .failures()
+                .map(|(index, _)| *index)
+                .collect::<Vec<_>>(),
+            vec![0, 1]
+        );
+        assert_eq!(
+            results
+                .skipped()
+                .map(|(index, _)| *index)
+                .collect::<Vec<_>>(),
+            vec![2]
+        );
+        assert_eq!(results.total_amount().unwrap(), Amount::ZERO);
+    }
+}
@@ -1,7 +1,22 @@
-use crate::nuts::{nut12, BlindSignature, BlindedMessage};
+use std::sync::Arc;
+
+use crate::dhke::construct_proofs;
+use crate::nuts::{
+    nut12, BlindSignature, BlindedMessage, Keys, Proof, Proofs, PublicKey, SecretKey,
+};
+use crate::secret::Secret;
 use crate::wallet::Wallet;
 use crate::{Amount, Error};
 
+/// Smallest number of items handed to a single worker
+///
+/// Smaller batches are processed inline, as moving them to another thread costs more than the
+/// curve operations themselves.
+const MIN_PARALLEL_CHUNK: usize = 64;
+
+/// Upper bound on the number of workers used for one batch
+const MAX_PARALLEL_WORKERS: usize = 8;
+
 /// How strictly returned signature amounts must match requested output amounts.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub(crate) enum SignatureAmountValidation {
@@ -40,6 +55,7 @@ pub(crate) async fn validate_mint_response_signatures<'a>(
         )));
     }
 
+    let mut dleq_checks = Vec::new();
     for (sig, blinded_message) in signatures.iter().zip(blinded_messages) {
         let amount_matches = match amount_validation {
             SignatureAmountValidation::Exact => sig.amount == blinded_message.amount,
@@ -64,11 +80,165 @@ pub(crate) async fn validate_mint_response_signatures<'a>(
 
         let keys = wallet.keyset(sig.keyset_id).await?.keys;
         let key = keys.amount_key(sig.amount).ok_or(Error::AmountKey)?;
-        match sig.verify_dleq(key, blinded_message.blinded_secret) {
-            Ok(_) | Err(nut12::Error::MissingDleqProof) => (),
-            Err(_) => return Err(Error::CouldNotVerifyDleq),
+        if sig.dleq.is_some() {
+            dleq_checks.push((sig.clone(), key, blinded_message.blinded_secret));
         }
     }
 
+    map_in_parallel(dleq_checks, |checks| {
+        checks
+            .into_iter()
+            .try_for_each(
+                |(sig, key, blinded_secret)| match sig.verify_dleq(key, blinded_secret) {
+                    Ok(_) | Err(nut12::Error::MissingDleqProof) => Ok(()),
+                    Err(_) => Err(Error::CouldNotVerifyDleq),
+                },
+            )
+            .map(|_| Vec::<()>::new())
+    })
+    .await?;
+
+    Ok(())
+}
+
+/// Verify the DLEQ proofs of proofs received from another wallet
+///
+/// Each proof is paired with the mint key of its amount. Proofs without a DLEQ proof are accepted.
+pub(crate) async fn verify_proofs_dleq(proofs: Vec<(Proof, PublicKey)>) -> Result<(), Error> {
+    map_in_parallel(proofs, |proofs| {
+        proofs
+            .iter()
+            .try_for_each(|(proof, key)| proof.verify_dleq(*key))
+            .map_err(Error::from)
+            .map(|_| Vec::<()>::new())
+    })
+    .await?;
+
     Ok(())
 }
+
+/// Unblind mint signatures into proofs, spreading large batches across worker threads
+///
+/// Same as [`construct_proofs`], but batches of hundreds of signatures, as returned by restore
+/// or a large receive, are unblinded on the blocking thread pool.
+pub(crate) async fn construct_proofs_parallel(
+    promises: Vec<BlindSignature>,
+    rs: Vec<SecretKey>,
+    secrets: Vec<Secret>,
+    keys: &Keys,
+) -> Result<Proofs, Error> {
+    if promises.len() < 2 * MIN_PARALLEL_CHUNK {
+        return Ok(construct_proofs(promises, rs, secrets, keys)?);
+    }
+
+    if (promises.len() != rs.len()) || (promises.len() != secrets.len()) {
+        return Err(Error::Custom(
+            "Lengths of promises, rs, and secrets must be equal".to_string(),
+        ));
+    }
+
+    let keys = Arc::new(keys.clone());
+    let items = promises
+        .into_iter()
+        .zip(rs)
+        .zip(secrets)
+        .map(|((promise, r), secret)| (promise, r, secret))
+        .collect();
+
+    map_in_parallel(items, move |chunk| {
+        let mut promises = Vec::with_capacity(chunk.len());
+        let mut rs = Vec::with_capacity(chunk.len());
+        let mut secrets = Vec::with_capacity(chunk.len());
+        for (promise, r, secret) in chunk {
+            promises.push(promise);
+            rs.push(r);
+            secrets.push(secret);
+        }
+
+        Ok(construct_proofs(promises, rs, secrets, &keys)?)
+    })
+    .await
+}
+
+/// Apply `f` to chunks of `items` on a bounded set of blocking workers
+///
+/// Results are returned in the order of `items`. Small batches, and every batch on wasm where
+/// there is no blocking thread pool, are processed inline.
+async fn map_in_parallel<T, R, F>(mut items: Vec<T>, f: F) -> Result<Vec<R>, Error>
+where
+    T: Send + 'static,
+    R: Send + 'static,
+    F: Fn(Vec<T>) -> Result<Vec<R>, Error> + Send + Sync + 'static,
+{
+    let workers = std::thread::available_parallelism()
+        .map(|workers| workers.get())
+        .unwrap_or(1)
+        .min(MAX_PARALLEL_WORKERS);
+
+    if cfg!(target_arch = "wasm32") || workers <= 1 || items.len() < 2 * MIN_PARALLEL_CHUNK {
+        return f(items);
+    }
+
+    let chunk_size = items.len().div_ceil(workers).max(MIN_PARALLEL_CHUNK);
+    let total = items.len();
+    let f = Arc::new(f);
+
+    let mut tasks = Vec::with_capacity(workers);
+    while !items.is_empty() {
+        let rest = items.split_off(chunk_size.min(items.len()));
+        let chunk = std::mem::replace(&mut items, rest);
+        let f = Arc::clone(&f);
+        tasks.push(tokio::task::spawn_blocking(move || f(chunk)));
+    }
+
+    let mut results = Vec::with_capacity(total);
+    for task in tasks {
+        let chunk_results = task.await.map_err(|err| {
+            tracing::error!("Wallet worker task failed: {}", err);
+            Error::Internal
+        })??;
+        results.extend(chunk_results);
+    }
+
+    Ok(results)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use super::*;
+    use crate::dhke::{blind_message, sign_message};
+    use crate::nuts::Id;
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_construct_proofs_parallel_matches_serial() {
+        let mint_key = SecretKey::generate();
+        let keys = Keys::new(BTreeMap::from([(Amount::ONE, mint_key.public_key())]));
+        let keyset_id = Id::from_bytes(&[0, 0, 0, 0, 0, 0, 0, 1]).unwrap();
+
+        let mut promises = Vec::new();
+        let mut rs = Vec::new();
+        let mut secrets = Vec::new();
+        for _ in 0..(4 * MIN_PARALLEL_CHUNK + 1) {
+            let secret = Secret::generate();
+            let (blinded, r) = blind_message(secret.as_bytes(), None).unwrap();
+            promises.push(BlindSignature {
+                amount: Amount::ONE,
+                keyset_id,
+                c: sign_message(&mint_key, &blinded).unwrap(),
+                dleq: None,
+            });
+            rs.push(r);
+            secrets.push(secret);
+        }
+
+        let serial =
+            construct_proofs(promises.clone(), rs.clone(), secrets.clone(), &keys).unwrap();
+        let parallel = construct_proofs_parallel(promises, rs, secrets, &keys)
+            .await
+            .unwrap();
+
+        assert_eq!(serial, parallel);
+    }
+}
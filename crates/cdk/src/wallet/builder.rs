@@ -12,7 +12,7 @@ use crate::mint_url::MintUrl;
 use crate::nuts::CurrencyUnit;
 use crate::wallet::auth::{AuthMintConnector, AuthWallet};
 use crate::wallet::mint_metadata_cache::MintMetadataCache;
-use crate::wallet::{HttpClient, MintConnector, SubscriptionManager, Wallet};
+use crate::wallet::{HttpClient, KeysetRefreshPolicy, MintConnector, SubscriptionManager, Wallet};
 
 /// Builder for creating a new [`Wallet`]
 pub struct WalletBuilder {
@@ -90,6 +90,14 @@ impl WalletBuilder {
         self
     }
 
+    /// Set when the wallet refreshes keysets on its own
+    ///
+    /// Shorthand for the matching [`set_metadata_cache_ttl`](Self::set_metadata_cache_ttl).
+    pub fn keyset_refresh_policy(mut self, policy: KeysetRefreshPolicy) -> Self {
+        self.metadata_cache_ttl = policy.cache_ttl();
+        self
+    }
+
     /// If WS is preferred (with fallback to HTTP is it is not supported by the mint) for the wallet
     /// subscriptions to mint events
     pub fn prefer_ws_subscription(mut self) -> Self {
@@ -0,0 +1,213 @@
+//! Consolidation of dust proofs
+//!
+//! Long-lived wallets accumulate many proofs of the same denomination, e.g. from change. Swapping
+//! them for an optimal split keeps the local store small and the number of swap inputs bounded.
+
+use std::collections::HashMap;
+use std::time::Duration;
+
+use cdk_common::amount::SplitTarget;
+use tracing::instrument;
+
+use crate::nuts::nut00::ProofsMethods;
+use crate::nuts::{Proofs, State};
+use crate::{Amount, Error, Wallet};
+
+/// When and how often proofs are consolidated
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ConsolidationPolicy {
+    /// Consolidate denominations held by more proofs than this
+    max_proofs_per_amount: usize,
+    /// Time between consolidation checks, never zero
+    interval: Duration,
+}
+
+impl Default for ConsolidationPolicy {
+    fn default() -> Self {
+        Self {
+            max_proofs_per_amount: 10,
+            interval: Duration::from_secs(600),
+        }
+    }
+}
+
+impl ConsolidationPolicy {
+    /// Consolidate denominations held by more than `max_proofs_per_amount` proofs, checking
+    /// every `interval`
+    ///
+    /// Fails if `interval` is zero.
+    pub fn new(max_proofs_per_amount: usize, interval: Duration) -> Result<Self, Error> {
+        Self::default()
+            .with_max_proofs_per_amount(max_proofs_per_amount)
+            .with_interval(interval)
+    }
+
+    /// Consolidate denominations held by more than `max_proofs_per_amount` proofs
+    pub fn with_max_proofs_per_amount(mut self, max_proofs_per_amount: usize) -> Self {
+        self.max_proofs_per_amount = max_proofs_per_amount;
+        self
+    }
+
+    /// Check for proofs to consolidate every `interval`
+    ///
+    /// Fails if `interval` is zero.
+    pub fn with_interval(mut self, interval: Duration) -> Result<Self, Error> {
+        if interval.is_zero() {
+            return Err(Error::Custom(
+                "Consolidation interval must be greater than zero".to_string(),
+            ));
+        }
+
+        self.interval = interval;
+        Ok(self)
+    }
+
+    /// Denominations held by more proofs than this are consolidated
+    pub fn max_proofs_per_amount(&self) -> usize {
+        self.max_proofs_per_amount
+    }
+
+    /// Time between consolidation checks
+    pub fn interval(&self) -> Duration {
+        self.interval
+    }
+}
+
+impl Wallet {
+    /// Unspent proofs of the denominations held by more than `max_proofs_per_amount` proofs
+    #[instrument(skip(self))]
+    pub async fn proofs_to_consolidate(
+        &self,
+        max_proofs_per_amount: usize,
+    ) -> Result<Proofs, Error> {
+        let mut by_amount: HashMap<Amount, Proofs> = HashMap::new();
+        for proof in self.get_unspent_proofs().await? {
+            by_amount.entry(proof.amount).or_default().push(proof);
+        }
+
+        Ok(by_amount
+            .into_values()
+            .filter(|proofs| proofs.len() > max_proofs_per_amount)
+            .flatten()
+            .collect())
+    }
+
+    /// Swap dust proofs for an optimal split
+    ///
+    /// Returns the number of proofs that were swapped. Nothing is swapped when the input fees
+    /// would consume the whole amount.
+    #[instrument(skip(self))]
+    pub async fn consolidate_proofs(&self, max_proofs_per_amount: usize) -> Result<usize, Error> {
+        let proofs = self.proofs_to_consolidate(max_proofs_per_amount).await?;
+        if proofs.is_empty() {
+            return Ok(0);
+        }
+
+        let fee = self.get_proofs_fee(&proofs).await?.total;
+        if proofs.total_amount()? <= fee {
+            tracing::debug!("Skipping consolidation, fees exceed the dust amount");
+            return Ok(0);
+        }
+
+        let count = proofs.len();
+        self.swap(None, SplitTarget::default(), proofs, None, false, false)
+            .await?;
+
+        tracing::info!("Consolidated {} proofs paying {} in fees", count, fee);
+
+        Ok(count)
+    }
+
+    /// Whether no operation of the wallet is in flight
+    async fn is_idle(&self) -> Result<bool, Error> {
+        Ok(self
+            .get_proofs_by_states(vec![State::Pending, State::Reserved, State::PendingSpent])
+            .await?
+            .is_empty())
+    }
+
+    /// Consolidate proofs in the background according to `policy`
+    ///
+    /// Consolidation is skipped while an operation of the wallet is in flight. The task runs
+    /// until the returned token is cancelled.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn spawn_proof_consolidation(
+        &self,
+        policy: ConsolidationPolicy,
+    ) -> tokio_util::sync::CancellationToken {
+        let cancel = tokio_util::sync::CancellationToken::new();
+        let cancelled = cancel.clone();
+        let wallet = self.clone();
+
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(policy.interval);
+
+            loop {
+                tokio::select! {
+                    _ = cancelled.cancelled() => break,
+                    _ = interval.tick() => {
+                        match wallet.is_idle().await {
+                            Ok(true) => {
+                                if let Err(err) = wallet
+                                    .consolidate_proofs(policy.max_proofs_per_amount)
+                                    .await
+                                {
+                                    tracing::warn!("Could not consolidate proofs: {}", err);
+                                }
+                            }
+                            Ok(false) => {
+                                tracing::debug!("Wallet busy, postponing consolidation");
+                            }
+                            Err(err) => {
+                                tracing::warn!("Could not check wallet state: {}", err);
+                            }
+                        }
+                    }
+                }
+            }
+        });
+
+        cancel
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::wallet::test_utils;
+
+    #[tokio::test]
+    async fn only_crowded_denominations_are_consolidated() {
+        let db = test_utils::create_test_db().await;
+        let wallet = test_utils::create_test_wallet(db.clone()).await;
+        let mint_url = test_utils::test_mint_url();
+        let keyset_id = test_utils::test_keyset_id();
+
+        let mut proofs = Vec::new();
+        for _ in 0..4 {
+            proofs.push(test_utils::test_proof_info(keyset_id, 1, mint_url.clone()));
+        }
+        for _ in 0..2 {
+            proofs.push(test_utils::test_proof_info(keyset_id, 8, mint_url.clone()));
+        }
+        db.update_proofs(proofs, vec![]).await.unwrap();
+
+        let dust = wallet.proofs_to_consolidate(3).await.unwrap();
+        assert_eq!(dust.len(), 4);
+        assert!(dust.iter().all(|proof| proof.amount == Amount::from(1)));
+
+        assert!(wallet.proofs_to_consolidate(4).await.unwrap().is_empty());
+    }
+
+    #[test]
+    fn zero_interval_is_rejected() {
+        assert!(ConsolidationPolicy::new(10, Duration::ZERO).is_err());
+        assert!(ConsolidationPolicy::default()
+            .with_interval(Duration::ZERO)
+            .is_err());
+
+        let policy = ConsolidationPolicy::new(5, Duration::from_secs(1)).unwrap();
+        assert_eq!(policy.max_proofs_per_amount(), 5);
+        assert_eq!(policy.interval(), Duration::from_secs(1));
+    }
+}
@@ -0,0 +1,421 @@
+//! Local state integrity checks
+//!
+//! A crash mid-operation or a botched backend migration can leave the local store with proofs the
+//! mint will never accept, rows whose stored Y does not match the proof, or quotes and proofs
+//! reserved by operations that no longer exist. These checks find such rows and optionally repair
+//! or quarantine them.
+
+use std::collections::{HashMap, HashSet};
+
+use cdk_common::wallet::{ProofInfo, TransactionId};
+use tracing::instrument;
+use uuid::Uuid;
+
+use crate::nuts::{Id, Keys, PublicKey};
+use crate::{Error, Wallet};
+
+/// KV store namespace proofs are moved to by [`Wallet::repair_local_state`]
+pub const INTEGRITY_KV_NAMESPACE: &str = "integrity";
+/// KV store secondary namespace of quarantined proofs, keyed by their Y
+const QUARANTINE_KV_SECONDARY_NAMESPACE: &str = "quarantine";
+
+/// Why a stored proof failed [`Wallet::verify_local_state`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProofIssue {
+    /// The stored Y differs from the Y computed from the secret
+    YMismatch,
+    /// Neither the wallet nor the mint knows the keyset of the proof
+    UnknownKeyset,
+    /// The keyset has no key for the amount of the proof
+    AmountNotInKeyset,
+    /// The proof carries a DLEQ proof that does not verify its signature `C`
+    InvalidDleq,
+}
+
+/// A stored proof that failed [`Wallet::verify_local_state`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InvalidProof {
+    /// Y the proof is stored under
+    pub y: PublicKey,
+    /// What is wrong with the proof
+    pub issue: ProofIssue,
+}
+
+/// Findings of [`Wallet::verify_local_state`]
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct IntegrityReport {
+    /// Proofs that are stored incorrectly or can never be redeemed
+    pub invalid_proofs: Vec<InvalidProof>,
+    /// Ys of proofs reserved by an operation that no longer exists
+    pub orphaned_proof_reservations: Vec<PublicKey>,
+    /// Ids of mint quotes reserved by an operation that no longer exists
+    pub orphaned_mint_quotes: Vec<String>,
+    /// Ids of melt quotes reserved by an operation that no longer exists
+    pub orphaned_melt_quotes: Vec<String>,
+    /// Transactions referencing a quote that is not stored
+    pub orphaned_transactions: Vec<TransactionId>,
+}
+
+impl IntegrityReport {
+    /// Whether no issue was found
+    pub fn is_empty(&self) -> bool {
+        self.invalid_proofs.is_empty()
+            && self.orphaned_proof_reservations.is_empty()
+            && self.orphaned_mint_quotes.is_empty()
+            && self.orphaned_melt_quotes.is_empty()
+            && self.orphaned_transactions.is_empty()
+    }
+}
+
+impl Wallet {
+    /// Cross-check the local store of this wallet without changing it
+    ///
+    /// Proofs are checked against the keys of their keyset, and their DLEQ proof is verified if
+    /// present. Keys that are not stored yet are fetched from the mint, so a keyset is only
+    /// reported unknown if the mint does not know it either. Operations are considered gone once
+    /// they have no incomplete saga.
+    #[instrument(skip(self))]
+    pub async fn verify_local_state(&self) -> Result<IntegrityReport, Error> {
+        let mut report = IntegrityReport::default();
+
+        let live_operations = self
+            .localstore
+            .get_incomplete_sagas()
+            .await?
+            .into_iter()
+            .map(|saga| saga.id)
+            .collect::<HashSet<_>>();
+        let is_orphaned = |operation: &Uuid| !live_operations.contains(operation);
+
+        let proofs = self
+            .localstore
+            .get_proofs(
+                Some(self.mint_url.clone()),
+                Some(self.unit.clone()),
+                None,
+                None,
+            )
+            .await?;
+
+        let mut keys: HashMap<_, Option<Keys>> = HashMap::new();
+        for proof_info in &proofs {
+            let keyset_id = proof_info.proof.keyset_id;
+            if !keys.contains_key(&keyset_id) {
+                keys.insert(keyset_id, self.keyset_keys(keyset_id).await?);
+            }
+
+            if let Some(issue) = proof_issue(proof_info, keys[&keyset_id].as_ref()) {
+                report.invalid_proofs.push(InvalidProof {
+                    y: proof_info.y,
+                    issue,
+                });
+            }
+
+            if proof_info
+                .used_by_operation
+                .as_ref()
+                .is_some_and(is_orphaned)
+            {
+                report.orphaned_proof_reservations.push(proof_info.y);
+            }
+        }
+
+        let mut quote_ids = HashSet::new();
+
+        for quote in self.localstore.get_mint_quotes().await? {
+            if quote.mint_url != self.mint_url || quote.unit != self.unit {
+                continue;
+            }
+
+            if quote.used_by_operation.as_deref().is_some_and(|operation| {
+                Uuid::parse_str(operation)
+                    .ok()
+                    .is_none_or(|id| is_orphaned(&id))
+            }) {
+                report.orphaned_mint_quotes.push(quote.id.clone());
+            }
+            quote_ids.insert(quote.id);
+        }
+
+        for quote in self.localstore.get_melt_quotes().await? {
+            if quote.mint_url.as_ref() != Some(&self.mint_url) || quote.unit != self.unit {
+                continue;
+            }
+
+            if quote.used_by_operation.as_deref().is_some_and(|operation| {
+                Uuid::parse_str(operation)
+                    .ok()
+                    .is_none_or(|id| is_orphaned(&id))
+            }) {
+                report.orphaned_melt_quotes.push(quote.id.clone());
+            }
+            quote_ids.insert(quote.id);
+        }
+
+        for transaction in self
+            .localstore
+            .list_transactions(Some(self.mint_url.clone()), None, Some(self.unit.clone()))
+            .await?
+        {
+            if transaction
+                .quote_id
+                .as_ref()
+                .is_some_and(|quote_id| !quote_ids.contains(quote_id))
+            {
+                report.orphaned_transactions.push(transaction.id());
+            }
+        }
+
+        for invalid in &report.invalid_proofs {
+            tracing::warn!("Stored proof {} is invalid: {:?}", invalid.y, invalid.issue);
+        }
+
+        Ok(report)
+    }
+
+    /// Keys of `keyset_id`, fetched from the mint if they are not stored
+    ///
+    /// Returns `None` if the mint does not list the keyset. Fails if the mint cannot be reached,
+    /// so proofs are never reported invalid only because the wallet is offline.
+    async fn keyset_keys(&self, keyset_id: Id) -> Result<Option<Keys>, Error> {
+        if let Some(keys) = self.localstore.get_keys(&keyset_id).await? {
+            return Ok(Some(keys));
+        }
+
+        let metadata = self
+            .metadata_cache
+            .load_from_mint(&self.localstore, &self.client)
+            .await?;
+
+        Ok(metadata.keys.get(&keyset_id).map(|keys| (**keys).clone()))
+    }
+
+    /// Verify the local store of this wallet and repair what was found
+    ///
+    /// Proofs stored under the wrong Y are stored again under the right one. Proofs that can
+    /// never be redeemed are moved to the [`INTEGRITY_KV_NAMESPACE`] KV namespace, so they stop
+    /// counting towards the balance but are not lost. Orphaned reservations are released.
+    /// Orphaned transactions are only reported, as they are history rather than funds.
+    ///
+    /// Returns the findings before the repair.
+    #[instrument(skip(self))]
+    pub async fn repair_local_state(&self) -> Result<IntegrityReport, Error> {
+        let report = self.verify_local_state().await?;
+
+        let invalid_ys = report
+            .invalid_proofs
+            .iter()
+            .map(|invalid| invalid.y)
+            .collect::<Vec<_>>();
+        let invalid = self
+            .localstore
+            .get_proofs_by_ys(invalid_ys)
+            .await?
+            .into_iter()
+            .map(|proof_info| (proof_info.y, proof_info))
+            .collect::<HashMap<_, _>>();
+
+        for InvalidProof { y, issue } in &report.invalid_proofs {
+            let Some(proof_info) = invalid.get(y) else {
+                continue;
+            };
+
+            if *issue == ProofIssue::YMismatch {
+                let mut repaired = proof_info.clone();
+                repaired.y = proof_info.proof.y()?;
+                self.localstore
+                    .update_proofs(vec![repaired], vec![*y])
+                    .await?;
+                tracing::info!("Stored proof {} under its correct Y", y);
+            } else {
+                self.localstore
+                    .kv_write(
+                        INTEGRITY_KV_NAMESPACE,
+                        QUARANTINE_KV_SECONDARY_NAMESPACE,
+                        &y.to_hex(),
+                        &serde_json::to_vec(proof_info)?,
+                    )
+                    .await?;
+                self.localstore.update_proofs(vec![], vec![*y]).await?;
+                tracing::info!("Quarantined proof {}", y);
+            }
+        }
+
+        let stale_operations = self
+            .localstore
+            .get_proofs_by_ys(report.orphaned_proof_reservations.clone())
+            .await?
+            .into_iter()
+            .filter_map(|proof_info| proof_info.used_by_operation)
+            .collect::<HashSet<_>>();
+        for operation in stale_operations {
+            self.localstore.release_proofs(&operation).await?;
+            tracing::info!("Released proofs of orphaned operation {}", operation);
+        }
+
+        for quote_id in &report.orphaned_mint_quotes {
+            if let Some(operation) = self
+                .localstore
+                .get_mint_quote(quote_id)
+                .await?
+                .and_then(|quote| quote.used_by_operation)
+                .and_then(|operation| Uuid::parse_str(&operation).ok())
+            {
+                self.localstore.release_mint_quote(&operation).await?;
+                tracing::info!("Released orphaned reservation of mint quote {}", quote_id);
+            }
+        }
+
+        for quote_id in &report.orphaned_melt_quotes {
+            if let Some(operation) = self
+                .localstore
+                .get_melt_quote(quote_id)
+                .await?
+                .and_then(|quote| quote.used_by_operation)
+                .and_then(|operation| Uuid::parse_str(&operation).ok())
+            {
+                self.localstore.release_melt_quote(&operation).await?;
+                tracing::info!("Released orphaned reservation of melt quote {}", quote_id);
+            }
+        }
+
+        Ok(report)
+    }
+}
+
+/// First issue found with `proof_info`, given the keys of its keyset
+fn proof_issue(proof_info: &ProofInfo, keys: Option<&Keys>) -> Option<ProofIssue> {
+    if proof_info.proof.y().ok() != Some(proof_info.y) {
+        return Some(ProofIssue::YMismatch);
+    }
+
+    let Some(keys) = keys else {
+        return Some(ProofIssue::UnknownKeyset);
+    };
+
+    let Some(mint_pubkey) = keys.amount_key(proof_info.proof.amount) else {
+        return Some(ProofIssue::AmountNotInKeyset);
+    };
+
+    if proof_info.proof.dleq.is_some() && proof_info.proof.verify_dleq(mint_pubkey).is_err() {
+        return Some(ProofIssue::InvalidDleq);
+    }
+
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+    use std::sync::Arc;
+
+    use cdk_common::wallet::{Transaction, TransactionDirection};
+    use cdk_common::{Amount, SecretKey};
+
+    use super::*;
+    use crate::wallet::test_utils;
+
+    #[tokio::test]
+    async fn verify_and_repair_local_state() {
+        let db = test_utils::create_test_db().await;
+        let mock = Arc::new(test_utils::MockMintConnector::new());
+        let wallet = test_utils::create_test_wallet_with_mock(db.clone(), mock).await;
+        let mint_url = test_utils::test_mint_url();
+        // The keys are not stored yet and have to be fetched from the mint
+        let keyset = test_utils::test_keyset();
+        assert!(db.get_keys(&keyset.id).await.unwrap().is_none());
+
+        let valid = test_utils::test_proof_info(keyset.id, 1, mint_url.clone());
+        let bad_amount = test_utils::test_proof_info(keyset.id, 3, mint_url.clone());
+        let unknown_keyset = test_utils::test_proof_info(
+            Id::from_str("00aaaaaaaaaaaaaa").unwrap(),
+            1,
+            mint_url.clone(),
+        );
+        let mut wrong_y = test_utils::test_proof_info(keyset.id, 2, mint_url.clone());
+        wrong_y.y = SecretKey::generate().public_key();
+        db.update_proofs(
+            vec![
+                valid.clone(),
+                bad_amount.clone(),
+                unknown_keyset.clone(),
+                wrong_y.clone(),
+            ],
+            vec![],
+        )
+        .await
+        .unwrap();
+
+        let orphan_operation = Uuid::new_v4();
+        db.reserve_proofs(vec![valid.y], &orphan_operation)
+            .await
+            .unwrap();
+
+        let mint_quote = test_utils::test_mint_quote(mint_url.clone());
+        db.add_mint_quote(mint_quote.clone()).await.unwrap();
+        db.reserve_mint_quote(&mint_quote.id, &orphan_operation)
+            .await
+            .unwrap();
+
+        let transaction = Transaction {
+            mint_url: mint_url.clone(),
+            direction: TransactionDirection::Incoming,
+            amount: Amount::from(1),
+            fee: Amount::ZERO,
+            unit: wallet.unit.clone(),
+            ys: vec![valid.y],
+            timestamp: 0,
+            memo: None,
+            metadata: HashMap::new(),
+            quote_id: Some("missing".to_string()),
+            payment_request: None,
+            payment_proof: None,
+            payment_method: None,
+            saga_id: None,
+        };
+        db.add_transaction(transaction.clone()).await.unwrap();
+
+        let report = wallet.verify_local_state().await.unwrap();
+        let issues = report
+            .invalid_proofs
+            .iter()
+            .map(|invalid| (invalid.y, invalid.issue))
+            .collect::<HashMap<_, _>>();
+        assert_eq!(issues.len(), 3);
+        assert_eq!(issues[&bad_amount.y], ProofIssue::AmountNotInKeyset);
+        assert_eq!(issues[&unknown_keyset.y], ProofIssue::UnknownKeyset);
+        assert_eq!(issues[&wrong_y.y], ProofIssue::YMismatch);
+        assert_eq!(report.orphaned_proof_reservations, vec![valid.y]);
+        assert_eq!(report.orphaned_mint_quotes, vec![mint_quote.id.clone()]);
+        assert_eq!(report.orphaned_transactions, vec![transaction.id()]);
+
+        wallet.repair_local_state().await.unwrap();
+
+        let report = wallet.verify_local_state().await.unwrap();
+        assert!(report.invalid_proofs.is_empty());
+        assert!(report.orphaned_proof_reservations.is_empty());
+        assert!(report.orphaned_mint_quotes.is_empty());
+        assert_eq!(report.orphaned_transactions, vec![transaction.id()]);
+
+        let stored = db
+            .get_proofs(None, None, None, None)
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|proof_info| proof_info.y)
+            .collect::<HashSet<_>>();
+        assert_eq!(stored, HashSet::from([valid.y, wrong_y.proof.y().unwrap()]));
+
+        let quarantined = db
+            .kv_read(
+                INTEGRITY_KV_NAMESPACE,
+                QUARANTINE_KV_SECONDARY_NAMESPACE,
+                &bad_amount.y.to_hex(),
+            )
+            .await
+            .unwrap()
+            .unwrap();
+        let quarantined: ProofInfo = serde_json::from_slice(&quarantined).unwrap();
+        assert_eq!(quarantined.proof, bad_amount.proof);
+    }
+}
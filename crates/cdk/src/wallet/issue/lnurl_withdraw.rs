@@ -0,0 +1,90 @@
+//! Fund LNURL-withdraw
+//!
+//! Implementation of funding the wallet from an LNURL-withdraw (LUD-03) service
+
+use std::str::FromStr;
+
+use cdk_common::nut00::KnownMethod;
+use cdk_common::PaymentMethod;
+use tracing::instrument;
+
+use crate::amount::MsatRounding;
+use crate::lnurl::Lnurl;
+use crate::nuts::CurrencyUnit;
+use crate::wallet::MintQuote;
+use crate::{Amount, Error, Wallet};
+
+impl Wallet {
+    /// Fund the wallet from an LNURL-withdraw
+    ///
+    /// Creates a bolt11 mint quote for `amount_msat` and submits its invoice to the
+    /// LNURL-withdraw service, which pays it. The returned quote is minted like any other quote
+    /// once paid, e.g. with [`Wallet::mint_unissued_quotes`].
+    ///
+    /// # Arguments
+    ///
+    /// * `lnurl` - bech32 encoded `lnurl1...`, `lnurlw://` URL or HTTPS URL of the service
+    /// * `amount_msat` - Amount to withdraw in millisatoshis, whole sats for sat wallets
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::LnurlParse`] if the LNURL is invalid and [`Error::LnurlRequest`] if the
+    /// service cannot be reached, rejects the amount or refuses to pay the invoice.
+    #[instrument(skip(self, amount_msat), fields(lnurl = %lnurl))]
+    pub async fn fund_from_lnurl_withdraw(
+        &self,
+        lnurl: &str,
+        amount_msat: impl Into<Amount>,
+    ) -> Result<MintQuote, Error> {
+        let amount_msat: u64 = amount_msat.into().into();
+
+        let amount = match self.unit {
+            CurrencyUnit::Msat => Amount::from(amount_msat),
+            CurrencyUnit::Sat => Amount::from_msat(amount_msat, MsatRounding::Exact)
+                .map_err(|e| Error::LnurlRequest(e.to_string()))?,
+            _ => return Err(Error::UnsupportedUnit),
+        };
+
+        let lnurl = Lnurl::from_str(lnurl).map_err(|e| {
+            tracing::error!("Failed to parse LNURL: {}", e);
+            Error::LnurlParse(e.to_string())
+        })?;
+
+        let withdraw = lnurl
+            .fetch_withdraw_request(&self.client)
+            .await
+            .and_then(|withdraw| withdraw.validate_amount(amount_msat).map(|_| withdraw))
+            .map_err(|e| {
+                tracing::error!("LNURL-withdraw request failed: {}", e);
+                Error::LnurlRequest(e.to_string())
+            })?;
+
+        let quote = self
+            .mint_quote(
+                PaymentMethod::Known(KnownMethod::Bolt11),
+                Some(amount),
+                None,
+                None,
+            )
+            .await?;
+
+        lnurl
+            .submit_withdraw_invoice(&self.client, &withdraw, &quote.request)
+            .await
+            .map_err(|e| {
+                tracing::error!(
+                    "LNURL-withdraw service did not accept invoice of quote {}: {}",
+                    quote.id,
+                    e
+                );
+                Error::LnurlRequest(e.to_string())
+            })?;
+
+        tracing::debug!(
+            "LNURL-withdraw service accepted invoice of quote {}",
+            quote.id
+        );
+
+        Ok(quote)
+    }
+}
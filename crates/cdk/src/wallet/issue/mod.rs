@@ -2,6 +2,8 @@
 //!
 //! This module provides functionality for minting new proofs via Bolt11, Bolt12, and Custom methods.
 
+#[cfg(feature = "lnurl")]
+mod lnurl_withdraw;
 pub(crate) mod saga;
 
 use cdk_common::nut00::KnownMethod;
@@ -500,7 +502,7 @@ impl Wallet {
         amount_split_target: SplitTarget,
         spending_conditions: Option<SpendingConditions>,
     ) -> Result<Proofs, Error> {
-        self.retry_on_inactive_keyset(|| async {
+        self.retry_on_stale_keyset(|| async {
             let saga = MintSaga::new(self);
             let saga = saga
                 .prepare(
@@ -600,6 +602,27 @@ impl Wallet {
         Ok(quote)
     }
 
+    /// Fetch a bolt11 mint quote created for this wallet by someone else and store it with the
+    /// `secret_key` it is locked to (NUT-20)
+    ///
+    /// Used to claim quotes created by payments to a lightning address served by the mint, which
+    /// are then minted like any other quote, e.g. with [`Wallet::mint_unissued_quotes`].
+    #[instrument(skip(self, quote_id, secret_key))]
+    pub async fn claim_locked_mint_quote(
+        &self,
+        quote_id: &str,
+        secret_key: SecretKey,
+    ) -> Result<MintQuote, Error> {
+        let mut quote = self
+            .fetch_mint_quote(quote_id, Some(PaymentMethod::Known(KnownMethod::Bolt11)))
+            .await?;
+
+        quote.secret_key = Some(secret_key);
+        self.localstore.add_mint_quote(quote.clone()).await?;
+
+        Ok(quote)
+    }
+
     /// Batch check status of multiple mint quotes from the mint.
     ///
     /// Calls `POST /v1/mint/quote/{method}/check` per NUT-29.
@@ -45,15 +45,15 @@ use tracing::instrument;
 use self::compensation::{MintCompensation, ReleaseMintQuote};
 use self::state::{Finalized, Initial, Prepared, PreparedMintRequest};
 use crate::amount::SplitTarget;
-use crate::dhke::construct_proofs;
 use crate::nuts::nut00::ProofsMethods;
 use crate::nuts::{MintRequest, PreMintSecrets, Proofs, SpendingConditions, State};
 use crate::util::unix_time;
 use crate::wallet::blind_signature::{
-    validate_mint_response_signatures, SignatureAmountValidation,
+    construct_proofs_parallel, validate_mint_response_signatures, SignatureAmountValidation,
 };
 use crate::wallet::saga::{
-    add_compensation, clear_compensations, execute_compensations, new_compensations, Compensations,
+    add_compensation, clear_compensations, execute_compensations, new_compensations,
+    record_started_saga, Compensations,
 };
 use crate::wallet::MintQuote;
 use crate::{Amount, Error, Wallet};
@@ -433,6 +433,7 @@ impl<'a> MintSaga<'a, Initial> {
         );
 
         self.wallet.localstore.add_saga(saga.clone()).await?;
+        record_started_saga(saga.id);
 
         // Register compensation (deletes saga on failure)
         add_compensation(
@@ -747,6 +748,7 @@ impl<'a> MintSaga<'a, Initial> {
         );
 
         self.wallet.localstore.add_saga(saga.clone()).await?;
+        record_started_saga(saga.id);
 
         // Register compensation
         add_compensation(
@@ -871,12 +873,13 @@ impl<'a> MintSaga<'a, Prepared> {
             )
             .await?;
 
-            let proofs = construct_proofs(
+            let proofs = construct_proofs_parallel(
                 mint_res.signatures,
                 premint_secrets.rs(),
                 premint_secrets.secrets(),
                 &keys,
-            )?;
+            )
+            .await?;
 
             let minted_amount = proofs.total_amount()?;
 
@@ -19,11 +19,10 @@ use cdk_common::wallet::{
 use cdk_common::{Amount, PaymentMethod};
 use tracing::instrument;
 
-use crate::dhke::construct_proofs;
 use crate::nuts::{MintRequest, State};
 use crate::util::unix_time;
 use crate::wallet::blind_signature::{
-    validate_mint_response_signatures, SignatureAmountValidation,
+    construct_proofs_parallel, validate_mint_response_signatures, SignatureAmountValidation,
 };
 use crate::wallet::issue::saga::compensation::ReleaseMintQuote;
 use crate::wallet::issue::saga::state::PreparedMintRequest;
@@ -397,12 +396,13 @@ impl Wallet {
             )
             .await?;
 
-            let proofs = construct_proofs(
+            let proofs = construct_proofs_parallel(
                 mint_response.signatures,
                 premint_secrets.rs(),
                 premint_secrets.secrets(),
                 &keys,
-            )?;
+            )
+            .await?;
 
             let proof_infos: Vec<ProofInfo> = proofs
                 .into_iter()
@@ -527,12 +527,13 @@ impl Wallet {
         )
         .await?;
 
-        let proofs = construct_proofs(
+        let proofs = construct_proofs_parallel(
             mint_response.signatures,
             premint_secrets.rs(),
             premint_secrets.secrets(),
             &keys,
-        )?;
+        )
+        .await?;
 
         let proof_infos: Vec<ProofInfo> = proofs
             .into_iter()
@@ -737,6 +738,7 @@ mod tests {
         mock_client._set_restore_response(Ok(RestoreResponse {
             signatures: vec![],
             outputs: vec![],
+            next_cursor: None,
         }));
 
         let wallet = create_test_wallet_with_mock(db.clone(), mock_client).await;
@@ -0,0 +1,188 @@
+//! Process-wide cache of keyset public keys
+//!
+//! Keys are parsed and verified once per keyset id and shared between every wallet of the
+//! process, so wallets created for the same mint do not read, hex-decode and decompress the same
+//! points from the database or the mint again.
+
+use std::collections::HashMap;
+use std::sync::{Arc, LazyLock};
+
+use cdk_common::nuts::Keys;
+use cdk_common::parking_lot::Mutex;
+
+use crate::nuts::Id;
+
+/// Default number of keysets kept in the [`KeysCache`]
+pub const DEFAULT_KEYS_CACHE_CAPACITY: usize = 256;
+
+static GLOBAL_KEYS_CACHE: LazyLock<KeysCache> =
+    LazyLock::new(|| KeysCache::new(DEFAULT_KEYS_CACHE_CAPACITY));
+
+/// Counters of a [`KeysCache`]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct KeysCacheStats {
+    /// Lookups answered from the cache
+    pub hits: u64,
+    /// Lookups that were not in the cache
+    pub misses: u64,
+    /// Keysets dropped to stay within the capacity
+    pub evictions: u64,
+    /// Keysets currently cached
+    pub entries: usize,
+    /// Maximum number of cached keysets
+    pub capacity: usize,
+}
+
+#[derive(Debug)]
+struct Inner {
+    entries: HashMap<Id, (Arc<Keys>, u64)>,
+    capacity: usize,
+    tick: u64,
+    stats: KeysCacheStats,
+}
+
+impl Inner {
+    fn evict(&mut self) {
+        while self.entries.len() > self.capacity {
+            let Some(oldest) = self
+                .entries
+                .iter()
+                .min_by_key(|(_, (_, last_used))| *last_used)
+                .map(|(id, _)| *id)
+            else {
+                break;
+            };
+            self.entries.remove(&oldest);
+            self.stats.evictions += 1;
+        }
+    }
+}
+
+/// Least recently used cache of keyset keys, indexed by keyset id
+///
+/// Keyset ids are derived from the keys, so an id identifies the same keys on every mint. Only
+/// keys verified against their id must be inserted, whether they were fetched from the mint or
+/// read from a database. Cloning is cheap, clones share the same entries.
+#[derive(Debug, Clone)]
+pub struct KeysCache {
+    inner: Arc<Mutex<Inner>>,
+}
+
+impl KeysCache {
+    /// Create a cache holding at most `capacity` keysets
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            inner: Arc::new(Mutex::new(Inner {
+                entries: HashMap::new(),
+                capacity,
+                tick: 0,
+                stats: KeysCacheStats::default(),
+            })),
+        }
+    }
+
+    /// Cache shared by every wallet of the process
+    pub fn global() -> &'static KeysCache {
+        &GLOBAL_KEYS_CACHE
+    }
+
+    /// Get the keys of a keyset, marking them as recently used
+    pub fn get(&self, keyset_id: &Id) -> Option<Arc<Keys>> {
+        let mut inner = self.inner.lock();
+        inner.tick += 1;
+        let tick = inner.tick;
+
+        match inner.entries.get_mut(keyset_id) {
+            Some((keys, last_used)) => {
+                *last_used = tick;
+                let keys = keys.clone();
+                inner.stats.hits += 1;
+                Some(keys)
+            }
+            None => {
+                inner.stats.misses += 1;
+                None
+            }
+        }
+    }
+
+    /// Cache the verified keys of a keyset
+    pub fn insert(&self, keyset_id: Id, keys: Arc<Keys>) {
+        let mut inner = self.inner.lock();
+        inner.tick += 1;
+        let tick = inner.tick;
+        inner.entries.insert(keyset_id, (keys, tick));
+        inner.evict();
+    }
+
+    /// Change the maximum number of cached keysets, evicting the least recently used ones
+    ///
+    /// A capacity of `0` disables the cache.
+    pub fn set_capacity(&self, capacity: usize) {
+        let mut inner = self.inner.lock();
+        inner.capacity = capacity;
+        inner.evict();
+    }
+
+    /// Remove every cached keyset
+    pub fn clear(&self) {
+        self.inner.lock().entries.clear();
+    }
+
+    /// Current counters of the cache
+    pub fn stats(&self) -> KeysCacheStats {
+        let inner = self.inner.lock();
+        KeysCacheStats {
+            entries: inner.entries.len(),
+            capacity: inner.capacity,
+            ..inner.stats
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use super::*;
+    use crate::nuts::SecretKey;
+    use crate::Amount;
+
+    fn keys() -> Arc<Keys> {
+        Arc::new(Keys::new(BTreeMap::from([(
+            Amount::ONE,
+            SecretKey::generate().public_key(),
+        )])))
+    }
+
+    #[test]
+    fn test_keys_cache_evicts_least_recently_used() {
+        let cache = KeysCache::new(2);
+        let first = Id::from_bytes(&[0, 0, 0, 0, 0, 0, 0, 1]).unwrap();
+        let second = Id::from_bytes(&[0, 0, 0, 0, 0, 0, 0, 2]).unwrap();
+        let third = Id::from_bytes(&[0, 0, 0, 0, 0, 0, 0, 3]).unwrap();
+
+        cache.insert(first, keys());
+        cache.insert(second, keys());
+        assert!(cache.get(&first).is_some());
+
+        cache.insert(third, keys());
+        assert!(cache.get(&second).is_none());
+        assert!(cache.get(&first).is_some());
+        assert!(cache.get(&third).is_some());
+
+        assert_eq!(
+            cache.stats(),
+            KeysCacheStats {
+                hits: 3,
+                misses: 1,
+                evictions: 1,
+                entries: 2,
+                capacity: 2,
+            }
+        );
+
+        cache.set_capacity(0);
+        assert_eq!(cache.stats().entries, 0);
+    }
+}
@@ -1,4 +1,4 @@
-use std::collections::HashMap;
+use std::collections::{BTreeSet, HashMap};
 
 use cdk_common::amount::{FeeAndAmounts, KeysetFeeAndAmounts};
 use cdk_common::wallet::KeysetLoadPolicy;
@@ -95,61 +95,58 @@ impl Wallet {
             .ok_or(Error::NoActiveKeyset)
     }
 
-    /// Run an operation and retry once if the mint rejects it with
-    /// [`Error::InactiveKeyset`], refreshing the keyset cache before the
-    /// second attempt.
+    /// Run an operation and retry once if it fails because the wallet's view of
+    /// the mint's keysets is stale.
+    ///
+    /// On [`Error::InactiveKeyset`], [`Error::UnknownKeySet`] or
+    /// [`Error::KeysetUnknown`] the keysets are refreshed from the mint, and the
+    /// operation is retried if the mint now reports different keysets.
     ///
     /// Use this around any code path that selects the active keyset, builds
     /// outputs, and posts them to the mint.
-    pub(crate) async fn retry_on_inactive_keyset<F, Fut, T>(&self, f: F) -> Result<T, Error>
+    pub(crate) async fn retry_on_stale_keyset<F, Fut, T>(&self, f: F) -> Result<T, Error>
     where
         F: Fn() -> Fut,
         Fut: std::future::Future<Output = Result<T, Error>>,
     {
         match f().await {
-            Err(Error::InactiveKeyset) => {
+            Err(err) if is_stale_keyset_error(&err) => {
                 tracing::info!(
-                    "Mint rejected outputs with inactive keyset, refreshing keysets and retrying"
+                    "Mint rejected operation with stale keyset ({}), refreshing keysets and retrying",
+                    err
                 );
 
-                let old_active = self
-                    .keysets(KeysetLoadPolicy::CacheOnly)
-                    .await
-                    .ok()
-                    .and_then(|ks| {
-                        ks.into_iter()
-                            .filter(|k| k.active.unwrap_or(false))
-                            .min_by_key(|k| k.input_fee_ppk)
-                            .map(|k| k.id)
-                    });
-
-                let new_active =
-                    self.keysets(KeysetLoadPolicy::Refresh)
-                        .await
-                        .ok()
-                        .and_then(|ks| {
-                            ks.into_iter()
-                                .filter(|k| k.active.unwrap_or(false))
-                                .min_by_key(|k| k.input_fee_ppk)
-                                .map(|k| k.id)
-                        });
-
-                if new_active.is_some() && new_active != old_active {
+                let old_keysets = self.keyset_snapshot(KeysetLoadPolicy::CacheOnly).await;
+                let new_keysets = self.keyset_snapshot(KeysetLoadPolicy::Refresh).await;
+
+                if new_keysets.is_some() && new_keysets != old_keysets {
                     tracing::info!(
-                        "Active keyset changed from {:?} to {:?}, retrying operation",
-                        old_active,
-                        new_active
+                        "Keysets changed from {:?} to {:?}, retrying operation",
+                        old_keysets,
+                        new_keysets
                     );
                     f().await
                 } else {
-                    tracing::warn!("No new active keyset found after refresh, not retrying");
-                    Err(Error::InactiveKeyset)
+                    tracing::warn!("Keysets unchanged after refresh, not retrying");
+                    Err(err)
                 }
             }
             other => other,
         }
     }
 
+    /// Active keyset and all known keyset ids, used to detect rotations
+    async fn keyset_snapshot(&self, policy: KeysetLoadPolicy) -> Option<(Id, BTreeSet<Id>)> {
+        let keysets = self.keysets(policy).await.ok()?;
+        let active = keysets
+            .iter()
+            .filter(|k| k.active.unwrap_or(false))
+            .min_by_key(|k| k.input_fee_ppk)
+            .map(|k| k.id)?;
+
+        Some((active, keysets.iter().map(|k| k.id).collect()))
+    }
+
     /// Get a single keyset by ID.
     #[instrument(skip(self))]
     pub async fn keyset(&self, keyset_id: Id) -> Result<KeySet, Error> {
@@ -259,6 +256,14 @@ impl Wallet {
     }
 }
 
+/// Whether an error means the wallet used a keyset the mint no longer accepts
+fn is_stale_keyset_error(err: &Error) -> bool {
+    matches!(
+        err,
+        Error::InactiveKeyset | Error::UnknownKeySet | Error::KeysetUnknown(_)
+    )
+}
+
 #[cfg(test)]
 mod tests {
     use std::sync::atomic::{AtomicUsize, Ordering};
@@ -270,6 +275,7 @@ mod tests {
         create_test_db, create_test_wallet_with_mock, make_inactive_keyset, test_keyset,
         test_mint_url, test_proof, MockMintConnector,
     };
+    use crate::wallet::KeysetRefreshPolicy;
 
     #[tokio::test]
     async fn token_proofs_decodes_inactive_keyset_proofs() {
@@ -364,7 +370,7 @@ mod tests {
             .expect("prepare should succeed with inactive keyset proofs");
     }
 
-    /// When the active keyset changes after refresh, retry_on_inactive_keyset
+    /// When the active keyset changes after refresh, retry_on_stale_keyset
     /// should retry and return the second attempt's result.
     #[tokio::test]
     async fn retry_on_inactive_keyset_retries_when_keyset_changes() {
@@ -392,7 +398,7 @@ mod tests {
         let mock_clone = mock.clone();
 
         let result = wallet
-            .retry_on_inactive_keyset(|| {
+            .retry_on_stale_keyset(|| {
                 let call_count = call_count_clone.clone();
                 let mock = mock_clone.clone();
                 let old = old.clone();
@@ -416,7 +422,7 @@ mod tests {
     }
 
     /// When the active keyset does NOT change after refresh,
-    /// retry_on_inactive_keyset should not retry.
+    /// retry_on_stale_keyset should not retry.
     #[tokio::test]
     async fn retry_on_inactive_keyset_no_retry_when_keyset_unchanged() {
         let db = create_test_db().await;
@@ -433,7 +439,7 @@ mod tests {
         let call_count_clone = call_count.clone();
 
         let result: Result<u32, Error> = wallet
-            .retry_on_inactive_keyset(|| {
+            .retry_on_stale_keyset(|| {
                 let call_count = call_count_clone.clone();
                 async move {
                     call_count.fetch_add(1, Ordering::SeqCst);
@@ -447,6 +453,50 @@ mod tests {
         assert_eq!(call_count.load(Ordering::SeqCst), 1);
     }
 
+    /// When the mint reports an unknown keyset and a refresh finds new keysets,
+    /// the operation is retried.
+    #[tokio::test]
+    async fn retry_on_stale_keyset_retries_unknown_keyset_after_rotation() {
+        let db = create_test_db().await;
+        let mint_url = test_mint_url();
+        db.add_mint(mint_url.clone(), None).await.unwrap();
+
+        let mock = Arc::new(MockMintConnector::new());
+        let wallet = create_test_wallet_with_mock(db, mock.clone()).await;
+        wallet.set_keyset_refresh_policy(KeysetRefreshPolicy::Manual);
+        assert_eq!(wallet.keyset_refresh_policy(), KeysetRefreshPolicy::Manual);
+
+        // Prime the cache with the original keyset
+        wallet.keysets(KeysetLoadPolicy::Refresh).await.unwrap();
+
+        // The mint rotates to a new keyset while the wallet's cache never expires
+        let mut rotated = make_inactive_keyset();
+        rotated.active = Some(true);
+        let mut old = test_keyset();
+        old.active = Some(false);
+        mock.set_mint_keys_response(Ok(vec![old, rotated.clone()]));
+
+        let call_count = Arc::new(AtomicUsize::new(0));
+        let call_count_clone = call_count.clone();
+
+        let result = wallet
+            .retry_on_stale_keyset(|| {
+                let call_count = call_count_clone.clone();
+                async move {
+                    if call_count.fetch_add(1, Ordering::SeqCst) == 0 {
+                        Err(Error::UnknownKeySet)
+                    } else {
+                        Ok(7u32)
+                    }
+                }
+            })
+            .await;
+
+        assert_eq!(result.unwrap(), 7);
+        assert_eq!(call_count.load(Ordering::SeqCst), 2);
+        assert_eq!(wallet.active_keyset().await.unwrap().id, rotated.id);
+    }
+
     /// When the operation succeeds on the first try, no retry logic runs.
     #[tokio::test]
     async fn retry_on_inactive_keyset_no_retry_on_success() {
@@ -464,7 +514,7 @@ mod tests {
         let call_count_clone = call_count.clone();
 
         let result = wallet
-            .retry_on_inactive_keyset(|| {
+            .retry_on_stale_keyset(|| {
                 let call_count = call_count_clone.clone();
                 async move {
                     call_count.fetch_add(1, Ordering::SeqCst);
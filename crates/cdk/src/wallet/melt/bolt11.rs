@@ -11,13 +11,32 @@ use crate::{Amount, Error, Wallet};
 
 impl Wallet {
     /// Melt Quote for Bolt11
+    ///
+    /// An active quote of this mint for the same invoice and amount is returned instead of
+    /// requesting a new one, unless `allow_duplicate` is set.
     #[instrument(skip(self, request))]
     pub(crate) async fn melt_bolt11_quote(
         &self,
         request: String,
         options: Option<MeltOptions>,
+        allow_duplicate: bool,
     ) -> Result<MeltQuote, Error> {
         let invoice = Bolt11Invoice::from_str(&request)?;
+        let expected_amount = self.expected_bolt11_quote_amount(&invoice, options)?;
+
+        if !allow_duplicate {
+            if let Some(quote) = self
+                .find_active_bolt11_melt_quote(&invoice, options, expected_amount)
+                .await?
+            {
+                tracing::debug!(
+                    "Reusing melt quote {} for invoice {}",
+                    quote.id,
+                    invoice.payment_hash()
+                );
+                return Ok(quote);
+            }
+        }
 
         let quote_request = MeltQuoteBolt11Request {
             request: invoice.clone(),
@@ -35,16 +54,7 @@ impl Wallet {
             _ => return Err(Error::InvalidPaymentMethod),
         };
 
-        if self.unit == CurrencyUnit::Msat || self.unit == CurrencyUnit::Sat {
-            let amount_msat = options
-                .map(|opt| opt.amount_msat().into())
-                .or_else(|| invoice.amount_milli_satoshis())
-                .ok_or(Error::InvoiceAmountUndefined)?;
-
-            let amount_quote_unit = Amount::new(amount_msat, CurrencyUnit::Msat)
-                .convert_to(&self.unit)?
-                .into();
-
+        if let Some(amount_quote_unit) = expected_amount {
             if quote_res.amount != amount_quote_unit {
                 tracing::warn!(
                     "Mint returned incorrect quote amount. Expected {}, got {}",
@@ -68,6 +78,7 @@ impl Wallet {
             payment_proof: quote_res.payment_preimage,
             estimated_blocks: None,
             fee_index: None,
+            options,
             payment_method: PaymentMethod::Known(KnownMethod::Bolt11),
 
             used_by_operation: None,
@@ -78,4 +89,52 @@ impl Wallet {
 
         Ok(quote)
     }
+
+    /// Amount a melt quote for `invoice` must have, if the wallet unit is denominated in bitcoin
+    fn expected_bolt11_quote_amount(
+        &self,
+        invoice: &Bolt11Invoice,
+        options: Option<MeltOptions>,
+    ) -> Result<Option<Amount>, Error> {
+        if self.unit != CurrencyUnit::Msat && self.unit != CurrencyUnit::Sat {
+            return Ok(None);
+        }
+
+        let amount_msat = options
+            .map(|opt| opt.amount_msat().into())
+            .or_else(|| invoice.amount_milli_satoshis())
+            .ok_or(Error::InvoiceAmountUndefined)?;
+
+        Ok(Some(
+            Amount::new(amount_msat, CurrencyUnit::Msat)
+                .convert_to(&self.unit)?
+                .into(),
+        ))
+    }
+
+    /// Active melt quote of this mint for the same payment hash as `invoice`
+    ///
+    /// Only a quote requested with the same melt options is matched, and only if its amount is
+    /// the expected one when that is known, so a full payment never reuses a partial quote.
+    async fn find_active_bolt11_melt_quote(
+        &self,
+        invoice: &Bolt11Invoice,
+        options: Option<MeltOptions>,
+        expected_amount: Option<Amount>,
+    ) -> Result<Option<MeltQuote>, Error> {
+        let payment_hash = invoice.payment_hash();
+
+        Ok(self
+            .get_active_melt_quotes()
+            .await?
+            .into_iter()
+            .find(|quote| {
+                quote.mint_url.as_ref() == Some(&self.mint_url)
+                    && quote.payment_method == PaymentMethod::Known(KnownMethod::Bolt11)
+                    && Bolt11Invoice::from_str(&quote.request)
+                        .is_ok_and(|stored| stored.payment_hash() == payment_hash)
+                    && quote.options == options
+                    && expected_amount.is_none_or(|amount| quote.amount == amount)
+            }))
+    }
 }
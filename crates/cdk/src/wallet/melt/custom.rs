@@ -56,6 +56,7 @@ impl Wallet {
             payment_proof: quote_res.payment_preimage,
             estimated_blocks: None,
             fee_index: None,
+            options: None,
             payment_method: PaymentMethod::Custom(method.to_string()),
 
             used_by_operation: None,
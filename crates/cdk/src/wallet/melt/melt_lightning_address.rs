@@ -85,7 +85,8 @@ impl Wallet {
 
         // Create a melt quote for the invoice using the existing bolt11 functionality
         // The invoice from LNURL already contains the amount, so we don't need amountless options
-        self.melt_bolt11_quote(invoice.to_string(), None).await
+        self.melt_bolt11_quote(invoice.to_string(), None, false)
+            .await
     }
 }
 
@@ -151,6 +152,7 @@ mod tests {
             request: None,
             unit: None,
             method: PaymentMethod::BOLT11,
+            change_policy: None,
         }));
 
         let wallet = test_wallet_with_connector(connector.clone()).await;
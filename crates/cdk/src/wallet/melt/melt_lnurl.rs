@@ -0,0 +1,71 @@
+//! Melt LNURL
+//!
+//! Implementation of melt functionality for LNURL-pay
+
+use std::collections::HashMap;
+use std::str::FromStr;
+
+use cdk_common::wallet::MeltQuote;
+use tracing::instrument;
+
+use crate::lnurl::Lnurl;
+use crate::types::FinalizedMelt;
+use crate::{Amount, Error, Wallet};
+
+impl Wallet {
+    /// Melt Quote for an LNURL-pay
+    ///
+    /// This method requests a Lightning invoice from the LNURL-pay service (LUD-06) and then
+    /// creates a melt quote for that invoice.
+    ///
+    /// # Arguments
+    ///
+    /// * `lnurl` - bech32 encoded `lnurl1...`, `lnurlp://` URL or HTTPS URL of the service
+    /// * `amount_msat` - Amount to pay in millisatoshis
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::LnurlParse`] if the LNURL is invalid and [`Error::LnurlRequest`] if the
+    /// service cannot be reached, rejects the amount or returns an unusable invoice.
+    #[instrument(skip(self, amount_msat), fields(lnurl = %lnurl))]
+    pub async fn melt_lnurl_quote(
+        &self,
+        lnurl: &str,
+        amount_msat: impl Into<Amount>,
+    ) -> Result<MeltQuote, Error> {
+        let lnurl = Lnurl::from_str(lnurl).map_err(|e| {
+            tracing::error!("Failed to parse LNURL: {}", e);
+            Error::LnurlParse(e.to_string())
+        })?;
+
+        let invoice = lnurl
+            .request_pay_invoice(&self.client, amount_msat.into())
+            .await
+            .map_err(|e| {
+                tracing::error!("Failed to get invoice from LNURL-pay service: {}", e);
+                Error::LnurlRequest(e.to_string())
+            })?;
+
+        tracing::debug!("Received invoice from LNURL-pay service: {}", invoice);
+
+        self.melt_bolt11_quote(invoice.to_string(), None, false)
+            .await
+    }
+
+    /// Pay an LNURL-pay with ecash of this wallet
+    ///
+    /// Creates a melt quote with [`Wallet::melt_lnurl_quote`] and melts it.
+    #[instrument(skip(self, amount_msat), fields(lnurl = %lnurl))]
+    pub async fn melt_to_lnurl(
+        &self,
+        lnurl: &str,
+        amount_msat: impl Into<Amount>,
+    ) -> Result<FinalizedMelt, Error> {
+        let quote = self.melt_lnurl_quote(lnurl, amount_msat).await?;
+
+        self.prepare_melt(&quote.id, HashMap::new())
+            .await?
+            .confirm()
+            .await
+    }
+}
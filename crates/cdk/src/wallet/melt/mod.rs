@@ -51,7 +51,7 @@ use tracing::instrument;
 use uuid::Uuid;
 
 use crate::nuts::nut00::KnownMethod;
-use crate::nuts::{MeltOptions, Proofs, Token};
+use crate::nuts::{MeltOptions, Proofs, SecretKey, Token};
 use crate::types::FinalizedMelt;
 use crate::wallet::subscription::NotificationPayload;
 use crate::wallet::WalletSubscription;
@@ -64,9 +64,13 @@ mod custom;
 mod melt_bip353;
 #[cfg(feature = "wallet")]
 mod melt_lightning_address;
+#[cfg(feature = "lnurl")]
+mod melt_lnurl;
 mod onchain;
+mod probe;
 pub(crate) mod saga;
 
+pub use probe::MeltProbe;
 use saga::state::Prepared;
 use saga::{MeltSaga, MeltSagaResult};
 
@@ -473,6 +477,7 @@ impl MeltQuoteStatusResponse {
                 request: Some(r.request),
                 unit: Some(r.unit),
                 method: PaymentMethod::Known(KnownMethod::Onchain),
+                change_policy: None,
             }),
             _ => Err(Error::Custom(
                 "Cannot convert response to standard bolt11 response".to_string(),
@@ -486,6 +491,10 @@ impl MeltQuoteStatusResponse {
 pub struct MeltConfirmOptions {
     /// Skip the pre-melt swap and send proofs directly to melt.
     pub skip_swap: bool,
+    /// Change outputs to request in addition to the ones the change needs
+    pub extra_change_outputs: u32,
+    /// Keys to sign P2PK-locked inputs with, including the pre-melt swap
+    pub p2pk_signing_keys: Vec<SecretKey>,
 }
 
 impl MeltConfirmOptions {
@@ -496,7 +505,47 @@ impl MeltConfirmOptions {
 
     /// Create options that skip the swap
     pub fn skip_swap() -> Self {
-        Self { skip_swap: true }
+        Self {
+            skip_swap: true,
+            ..Default::default()
+        }
+    }
+
+    /// Request `extra_change_outputs` more change outputs than the change needs
+    ///
+    /// Useful with mints whose denominations need more outputs than a power of two split.
+    pub fn with_extra_change_outputs(mut self, extra_change_outputs: u32) -> Self {
+        self.extra_change_outputs = extra_change_outputs;
+        self
+    }
+
+    /// Sign P2PK-locked inputs with `p2pk_signing_keys`
+    ///
+    /// Inputs locked with SIG_ALL are signed on the final melt or swap request.
+    pub fn with_p2pk_signing_keys(mut self, p2pk_signing_keys: Vec<SecretKey>) -> Self {
+        self.p2pk_signing_keys = p2pk_signing_keys;
+        self
+    }
+}
+
+/// Options for requesting a melt quote
+#[derive(Debug, Clone, Copy, Default)]
+pub struct MeltQuoteOptions {
+    /// Request a new quote even if the wallet holds an active one for the same invoice
+    pub allow_duplicate: bool,
+}
+
+impl MeltQuoteOptions {
+    /// Create options with default settings (active quotes are reused)
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Create options that always request a new quote
+    pub fn allow_duplicate() -> Self {
+        Self {
+            allow_duplicate: true,
+        }
     }
 }
 
@@ -1493,6 +1542,13 @@ impl Wallet {
     /// and [`Wallet::select_onchain_melt_quote`] to persist the chosen one.
     /// Invoking `melt_quote` with [`KnownMethod::Onchain`] returns
     /// [`Error::UnsupportedPaymentMethod`].
+    ///
+    /// # Duplicate quotes
+    ///
+    /// If the wallet already holds an active quote of this mint for the same bolt11
+    /// invoice, that quote is returned instead of requesting another one, so the invoice
+    /// is not paid twice from separately reserved proofs. Use
+    /// [`Wallet::melt_quote_with_options`] to request a new quote anyway.
     #[instrument(skip(self, request, options, extra))]
     pub async fn melt_quote<T, R>(
         &self,
@@ -1501,6 +1557,26 @@ impl Wallet {
         options: Option<MeltOptions>,
         extra: Option<String>,
     ) -> Result<MeltQuote, Error>
+    where
+        T: Into<PaymentMethod> + std::fmt::Debug,
+        R: std::fmt::Display,
+    {
+        self.melt_quote_with_options(method, request, options, extra, MeltQuoteOptions::new())
+            .await
+    }
+
+    /// Melt quote for all payment methods with [`MeltQuoteOptions`]
+    ///
+    /// See [`Wallet::melt_quote`].
+    #[instrument(skip(self, request, options, extra))]
+    pub async fn melt_quote_with_options<T, R>(
+        &self,
+        method: T,
+        request: R,
+        options: Option<MeltOptions>,
+        extra: Option<String>,
+        quote_options: MeltQuoteOptions,
+    ) -> Result<MeltQuote, Error>
     where
         T: Into<PaymentMethod> + std::fmt::Debug,
         R: std::fmt::Display,
@@ -1510,7 +1586,8 @@ impl Wallet {
 
         match method {
             PaymentMethod::Known(KnownMethod::Bolt11) => {
-                self.melt_bolt11_quote(request_str, options).await
+                self.melt_bolt11_quote(request_str, options, quote_options.allow_duplicate)
+                    .await
             }
             PaymentMethod::Known(KnownMethod::Bolt12) => {
                 self.melt_bolt12_quote(request_str, options).await
@@ -2463,6 +2540,7 @@ mod tests {
             change: None,
             unit: Some(CurrencyUnit::Sat),
             method: PaymentMethod::Known(KnownMethod::Bolt11),
+            change_policy: None,
         }
     }
 
@@ -2585,6 +2663,7 @@ mod tests {
         mock_client._set_restore_response(Ok(RestoreResponse {
             outputs: vec![],
             signatures: vec![],
+            next_cursor: None,
         }));
         mock_client.push_melt_quote_status_response(Ok(paid_status.clone()));
         mock_client.push_melt_quote_status_response(Ok(paid_status));
@@ -2789,4 +2868,104 @@ mod tests {
         assert_eq!(transactions.len(), 1);
         assert_eq!(transactions[0].metadata, metadata);
     }
+
+    #[tokio::test]
+    async fn test_melt_quote_reuses_active_quote_for_same_invoice() {
+        let db = create_test_db().await;
+        let mock_client = Arc::new(MockMintConnector::new());
+        let wallet = create_test_wallet_with_mock(db.clone(), mock_client.clone()).await;
+        let invoice = cdk_fake_wallet::create_fake_invoice(1_000_000, String::new());
+
+        mock_client.set_melt_quote_response(Ok(cdk_common::MeltQuoteCreateResponse::Bolt11(
+            bolt11_status("quote1", MeltQuoteState::Unpaid, None),
+        )));
+        let quote = wallet
+            .melt_quote(PaymentMethod::BOLT11, invoice.to_string(), None, None)
+            .await
+            .unwrap();
+        assert_eq!(quote.id, "quote1");
+
+        // The mock has no response left, the stored quote is returned without a request
+        let reused = wallet
+            .melt_quote(PaymentMethod::BOLT11, invoice.to_string(), None, None)
+            .await
+            .unwrap();
+        assert_eq!(reused.id, "quote1");
+
+        mock_client.set_melt_quote_response(Ok(cdk_common::MeltQuoteCreateResponse::Bolt11(
+            bolt11_status("quote2", MeltQuoteState::Unpaid, None),
+        )));
+        let duplicate = wallet
+            .melt_quote_with_options(
+                PaymentMethod::BOLT11,
+                invoice.to_string(),
+                None,
+                None,
+                MeltQuoteOptions::allow_duplicate(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(duplicate.id, "quote2");
+
+        // An expired quote is not reused
+        let mut expired = db.get_melt_quote("quote1").await.unwrap().unwrap();
+        expired.expiry = 0;
+        db.add_melt_quote(expired).await.unwrap();
+        let mut expired = db.get_melt_quote("quote2").await.unwrap().unwrap();
+        expired.expiry = 0;
+        db.add_melt_quote(expired).await.unwrap();
+
+        mock_client.set_melt_quote_response(Ok(cdk_common::MeltQuoteCreateResponse::Bolt11(
+            bolt11_status("quote3", MeltQuoteState::Unpaid, None),
+        )));
+        let fresh = wallet
+            .melt_quote(PaymentMethod::BOLT11, invoice.to_string(), None, None)
+            .await
+            .unwrap();
+        assert_eq!(fresh.id, "quote3");
+    }
+
+    #[tokio::test]
+    async fn test_melt_quote_does_not_reuse_partial_quote_for_full_payment() {
+        let db = create_test_db().await;
+        let mock_client = Arc::new(MockMintConnector::new());
+        let wallet = create_test_wallet_with_mock(db.clone(), mock_client.clone()).await;
+        let invoice = cdk_fake_wallet::create_fake_invoice(1_000_000, String::new());
+        let mpp = MeltOptions::new_mpp(500_000);
+
+        mock_client.set_melt_quote_response(Ok(cdk_common::MeltQuoteCreateResponse::Bolt11(
+            MeltQuoteBolt11Response {
+                amount: Amount::from(500),
+                ..bolt11_status("partial", MeltQuoteState::Unpaid, None)
+            },
+        )));
+        let partial = wallet
+            .melt_quote(PaymentMethod::BOLT11, invoice.to_string(), Some(mpp), None)
+            .await
+            .unwrap();
+        assert_eq!(partial.id, "partial");
+        assert_eq!(partial.options, Some(mpp));
+
+        mock_client.set_melt_quote_response(Ok(cdk_common::MeltQuoteCreateResponse::Bolt11(
+            bolt11_status("full", MeltQuoteState::Unpaid, None),
+        )));
+        let full = wallet
+            .melt_quote(PaymentMethod::BOLT11, invoice.to_string(), None, None)
+            .await
+            .unwrap();
+        assert_eq!(full.id, "full");
+        assert_eq!(full.options, None);
+
+        // Both are stored, each request reuses the quote with its own options
+        let reused = wallet
+            .melt_quote(PaymentMethod::BOLT11, invoice.to_string(), Some(mpp), None)
+            .await
+            .unwrap();
+        assert_eq!(reused.id, "partial");
+        let reused = wallet
+            .melt_quote(PaymentMethod::BOLT11, invoice.to_string(), None, None)
+            .await
+            .unwrap();
+        assert_eq!(reused.id, "full");
+    }
 }
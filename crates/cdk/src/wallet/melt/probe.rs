@@ -0,0 +1,160 @@
+//! Melt probing
+//!
+//! Before paying a bolt11 invoice from one of several mints, the fee reserves of the mints can be
+//! compared. Probing only requests melt quotes, no proofs are reserved, so the quote of the chosen
+//! mint can then be passed to [`Wallet::prepare_melt`].
+
+use cdk_common::wallet::MeltQuote;
+use futures::future::join_all;
+use tracing::instrument;
+
+use crate::mint_url::MintUrl;
+use crate::nuts::{CurrencyUnit, PaymentMethod};
+use crate::wallet::WalletRepository;
+use crate::{Amount, Error, Wallet};
+
+/// Melt quote of a mint for an invoice, used to compare mints before melting
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MeltProbe {
+    /// Mint that issued the quote
+    pub mint_url: MintUrl,
+    /// Melt quote for the invoice
+    pub quote: MeltQuote,
+    /// Balance of the wallet at the mint
+    pub balance: Amount,
+}
+
+impl MeltProbe {
+    /// Fee reserve of the quote
+    pub fn fee_reserve(&self) -> Amount {
+        self.quote.fee_reserve
+    }
+
+    /// Amount plus fee reserve of the quote
+    pub fn total(&self) -> Amount {
+        self.quote
+            .amount
+            .checked_add(self.quote.fee_reserve)
+            .unwrap_or(Amount::from(u64::MAX))
+    }
+
+    /// Whether the balance at the mint covers the amount and fee reserve
+    ///
+    /// Input fees of the proofs are not included.
+    pub fn is_affordable(&self) -> bool {
+        self.balance >= self.total()
+    }
+}
+
+impl Wallet {
+    /// Request a melt quote for a bolt11 invoice without reserving any proofs
+    #[instrument(skip(self, invoice))]
+    pub async fn probe_melt(&self, invoice: &str) -> Result<MeltProbe, Error> {
+        let quote = self
+            .melt_quote(PaymentMethod::BOLT11, invoice, None, None)
+            .await?;
+        let balance = self.total_balance().await?;
+
+        Ok(MeltProbe {
+            mint_url: self.mint_url.clone(),
+            quote,
+            balance,
+        })
+    }
+}
+
+impl WalletRepository {
+    /// Probe the melt of a bolt11 invoice at every mint with a `unit` wallet
+    ///
+    /// Returns the probes ranked from the best to the worst mint to pay from: mints whose balance
+    /// covers the quote first, then by lowest fee reserve. Mints that fail to quote are skipped,
+    /// the error is only returned if no mint could quote the invoice.
+    #[instrument(skip(self, invoice))]
+    pub async fn probe_melt(
+        &self,
+        invoice: &str,
+        unit: &CurrencyUnit,
+    ) -> Result<Vec<MeltProbe>, Error> {
+        let wallets: Vec<Wallet> = self
+            .get_wallets()
+            .await
+            .into_iter()
+            .filter(|wallet| &wallet.unit == unit)
+            .collect();
+
+        let results = join_all(wallets.iter().map(|wallet| wallet.probe_melt(invoice))).await;
+
+        let mut probes = Vec::with_capacity(results.len());
+        let mut last_err = None;
+        for (wallet, result) in wallets.iter().zip(results) {
+            match result {
+                Ok(probe) => probes.push(probe),
+                Err(err) => {
+                    tracing::warn!("Could not probe melt at {}: {}", wallet.mint_url, err);
+                    last_err = Some(err);
+                }
+            }
+        }
+
+        if let (true, Some(err)) = (probes.is_empty(), last_err) {
+            return Err(err);
+        }
+
+        rank_probes(&mut probes);
+
+        Ok(probes)
+    }
+}
+
+/// Sort probes affordable first, then by fee reserve and total
+fn rank_probes(probes: &mut [MeltProbe]) {
+    probes.sort_by_key(|probe| (!probe.is_affordable(), probe.fee_reserve(), probe.total()));
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use cdk_common::MeltQuoteState;
+
+    use super::*;
+
+    fn probe(n: u8, fee_reserve: u64, balance: u64) -> MeltProbe {
+        MeltProbe {
+            mint_url: MintUrl::from_str(&format!("https://mint{n}.example.com")).unwrap(),
+            quote: MeltQuote {
+                id: format!("quote{n}"),
+                mint_url: None,
+                unit: CurrencyUnit::Sat,
+                amount: Amount::from(1_000),
+                request: "lnbc...".to_string(),
+                fee_reserve: Amount::from(fee_reserve),
+                state: MeltQuoteState::Unpaid,
+                expiry: 0,
+                payment_proof: None,
+                estimated_blocks: None,
+                fee_index: None,
+                options: None,
+                payment_method: PaymentMethod::BOLT11,
+                used_by_operation: None,
+                version: 0,
+            },
+            balance: Amount::from(balance),
+        }
+    }
+
+    #[test]
+    fn rank_probes_prefers_affordable_then_cheapest() {
+        let mut probes = vec![
+            probe(1, 20, 5_000),
+            probe(2, 1, 500),
+            probe(3, 5, 5_000),
+            probe(4, 10, 1_010),
+        ];
+
+        rank_probes(&mut probes);
+
+        let ranked: Vec<_> = probes.iter().map(|probe| probe.quote.id.as_str()).collect();
+        assert_eq!(ranked, vec!["quote3", "quote4", "quote1", "quote2"]);
+    }
+}
@@ -53,7 +53,9 @@ use crate::util::unix_time;
 use crate::wallet::blind_signature::{
     validate_mint_response_signatures, SignatureAmountValidation,
 };
-use crate::wallet::saga::{add_compensation, new_compensations, Compensations};
+use crate::wallet::saga::{
+    add_compensation, new_compensations, record_started_saga, Compensations,
+};
 use crate::{ensure_cdk, Amount, Error, Wallet};
 
 pub(crate) mod compensation;
@@ -106,18 +108,23 @@ async fn finalize_melt_common<'a>(
 
     let change_proofs = match change {
         Some(change) => {
+            // The mint signs change outputs in the order they were requested and leaves the
+            // ones it does not need unsigned
             let num_change_proof = change.len();
 
-            let num_change_proof = match (
-                premint_secrets.len() < num_change_proof,
-                premint_secrets.secrets().len() < num_change_proof,
-            ) {
-                (true, _) | (_, true) => {
-                    tracing::error!("Mismatch in change promises to change");
+            if num_change_proof > premint_secrets.len() {
+                tracing::error!("Mismatch in change promises to change");
+                return Err(Error::InvalidMeltChange(format!(
+                    "mint returned {} change signatures for {} outputs",
+                    num_change_proof,
                     premint_secrets.len()
-                }
-                _ => num_change_proof,
-            };
+                )));
+            }
+
+            // DLEQ proofs are kept on the change proofs, so they can be passed on to receivers
+            if change.iter().any(|signature| signature.dleq.is_none()) {
+                tracing::warn!("Mint returned melt change signatures without DLEQ proofs");
+            }
 
             validate_mint_response_signatures(
                 wallet,
@@ -148,7 +155,16 @@ async fn finalize_melt_common<'a>(
     let fee = proofs_total
         .checked_sub(quote_info.amount)
         .and_then(|amount| amount.checked_sub(change_total))
-        .ok_or(Error::AmountOverflow)?;
+        .ok_or_else(|| {
+            Error::InvalidMeltChange(format!(
+                "change of {} exceeds the {} left after paying {}",
+                change_total,
+                proofs_total
+                    .checked_sub(quote_info.amount)
+                    .unwrap_or_default(),
+                quote_info.amount
+            ))
+        })?;
 
     let mut updated_quote = quote_info.clone();
     updated_quote.state = state;
@@ -362,6 +378,7 @@ impl<'a> MeltSaga<'a, Initial> {
             );
 
             self.wallet.localstore.add_saga(saga.clone()).await?;
+            record_started_saga(saga.id);
 
             add_compensation(
                 &mut self.compensations,
@@ -460,6 +477,7 @@ impl<'a> MeltSaga<'a, Initial> {
         );
 
         self.wallet.localstore.add_saga(saga.clone()).await?;
+        record_started_saga(saga.id);
 
         add_compensation(
             &mut self.compensations,
@@ -568,6 +586,7 @@ impl<'a> MeltSaga<'a, Initial> {
         );
 
         self.wallet.localstore.add_saga(saga.clone()).await?;
+        record_started_saga(saga.id);
 
         add_compensation(
             &mut self.compensations,
@@ -732,15 +751,25 @@ impl<'a> MeltSaga<'a, Prepared> {
                     target_swap_amount
                 );
 
+                let mut proofs_to_swap = self.state_data.proofs_to_swap.clone();
+                if let Err(err) = crate::wallet::util::sign_proofs(
+                    &mut proofs_to_swap,
+                    &options.p2pk_signing_keys,
+                ) {
+                    self.compensate().await;
+                    return Err(err);
+                }
+
                 if let Some(swapped) = self
                     .wallet
                     .swap_no_reserve(
                         Some(target_swap_amount),
                         SplitTarget::None,
-                        self.state_data.proofs_to_swap.clone(),
+                        proofs_to_swap,
                         None,
                         false,
                         false,
+                        &options.p2pk_signing_keys,
                     )
                     .await?
                 {
@@ -765,6 +794,14 @@ impl<'a> MeltSaga<'a, Prepared> {
             return Err(Error::InsufficientFunds);
         }
 
+        // SIG_INPUTS proofs are signed here, SIG_ALL proofs once the melt request is built
+        if let Err(err) =
+            crate::wallet::util::sign_proofs(&mut final_proofs, &options.p2pk_signing_keys)
+        {
+            self.compensate().await;
+            return Err(err);
+        }
+
         // Set proofs to Pending state before making melt request
         let proofs_info = final_proofs
             .clone()
@@ -798,13 +835,16 @@ impl<'a> MeltSaga<'a, Prepared> {
         .await;
 
         // Calculate change accounting for input fees
-        let change_amount = proofs_total - quote_info.amount - actual_input_fee;
+        let change_amount = proofs_total
+            .try_sub(quote_info.amount)?
+            .try_sub(actual_input_fee)?;
 
         let premint_secrets = if change_amount <= Amount::ZERO {
             PreMintSecrets::new(active_keyset_id)
         } else {
-            let num_secrets =
-                ((u64::from(change_amount) as f64).log2().ceil() as u64).max(1) as u32;
+            let num_secrets = (((u64::from(change_amount) as f64).log2().ceil() as u64).max(1)
+                as u32)
+                .saturating_add(options.extra_change_outputs);
 
             let new_counter = self
                 .wallet
@@ -814,11 +854,11 @@ impl<'a> MeltSaga<'a, Prepared> {
 
             let count = new_counter - num_secrets;
 
-            PreMintSecrets::from_seed_blank(
+            PreMintSecrets::from_seed_blank_outputs(
                 active_keyset_id,
                 count,
                 &self.wallet.seed,
-                change_amount,
+                num_secrets,
             )?
         };
 
@@ -865,6 +905,7 @@ impl<'a> MeltSaga<'a, Prepared> {
                 quote: quote_info,
                 final_proofs,
                 premint_secrets,
+                p2pk_signing_keys: options.p2pk_signing_keys,
             },
         })
     }
@@ -954,12 +995,21 @@ impl<'a> MeltSaga<'a, MeltRequested> {
         )
         .prefer_async(true);
 
-        let request = if quote_info.payment_method == PaymentMethod::Known(KnownMethod::Onchain) {
+        let mut request = if quote_info.payment_method == PaymentMethod::Known(KnownMethod::Onchain)
+        {
             request.fee_index(quote_info.fee_index.ok_or(Error::InvalidPaymentRequest)?)
         } else {
             request
         };
 
+        // SIG_ALL inputs commit to the quote and change outputs, so they are signed last
+        if let Err(err) =
+            crate::wallet::util::sign_melt_sig_all(&mut request, &self.state_data.p2pk_signing_keys)
+        {
+            self.handle_failure().await;
+            return Err(err);
+        }
+
         let melt_result = self
             .wallet
             .client
@@ -1338,7 +1388,7 @@ mod tests {
     use uuid::Uuid;
 
     use super::{finalize_melt_common, MeltSaga, MeltSagaResult};
-    use crate::nuts::{BlindSignature, PreMintSecrets};
+    use crate::nuts::{BlindSignature, BlindSignatureDleq, PreMintSecrets, SecretKey};
     use crate::wallet::saga::new_compensations;
     use crate::wallet::test_utils::{
         create_test_db, create_test_wallet_with_mock, test_keyset_id, test_melt_quote,
@@ -1804,6 +1854,46 @@ mod tests {
         );
     }
 
+    #[tokio::test]
+    async fn test_finalize_melt_rejects_change_with_invalid_dleq() {
+        let db = create_test_db().await;
+        let mock_client = Arc::new(MockMintConnector::new());
+        mock_client.reset_default_mint_state();
+        let wallet = create_test_wallet_with_mock(db, mock_client).await;
+
+        let keyset_id = test_keyset_id();
+        let quote = test_melt_quote();
+        let final_proofs = vec![test_proof_info(keyset_id, 1008, test_mint_url()).proof];
+        let premint_secrets =
+            PreMintSecrets::blank(keyset_id, Amount::from(8)).expect("blank premint secrets");
+        let change = vec![BlindSignature {
+            amount: Amount::from(8),
+            keyset_id,
+            c: premint_secrets.blinded_messages()[0].blinded_secret,
+            dleq: Some(BlindSignatureDleq {
+                e: SecretKey::generate(),
+                s: SecretKey::generate(),
+            }),
+        }];
+
+        let result = finalize_melt_common(
+            &wallet,
+            new_compensations(),
+            Uuid::new_v4(),
+            &quote,
+            &final_proofs,
+            &premint_secrets,
+            MeltQuoteState::Paid,
+            None,
+            Some(change),
+            HashMap::new(),
+            Default::default(),
+        )
+        .await;
+
+        assert!(matches!(result, Err(Error::CouldNotVerifyDleq)));
+    }
+
     #[tokio::test]
     async fn test_finalize_melt_persists_payment_proof_on_quote() {
         let db = create_test_db().await;
@@ -462,6 +462,7 @@ mod tests {
         RestoreResponse {
             outputs,
             signatures,
+            next_cursor: None,
         }
     }
 
@@ -642,6 +643,7 @@ mod tests {
             change: None,
             unit: Some(CurrencyUnit::Sat),
             method: PaymentMethod::BOLT11,
+            change_policy: None,
         }));
 
         let wallet = create_test_wallet_with_mock(db.clone(), mock_client).await;
@@ -735,6 +737,7 @@ mod tests {
             change: None,
             unit: Some(CurrencyUnit::Sat),
             method: PaymentMethod::BOLT11,
+            change_policy: None,
         }));
 
         let wallet = create_test_wallet_with_mock(db.clone(), mock_client).await;
@@ -806,6 +809,7 @@ mod tests {
             change: None,
             unit: Some(CurrencyUnit::Sat),
             method: PaymentMethod::BOLT11,
+            change_policy: None,
         }));
 
         let wallet = create_test_wallet_with_mock(db.clone(), mock_client).await;
@@ -898,6 +902,7 @@ mod tests {
             change: None,
             unit: Some(CurrencyUnit::Sat),
             method: PaymentMethod::BOLT11,
+            change_policy: None,
         }));
 
         let wallet = create_test_wallet_with_mock(db.clone(), mock_client).await;
@@ -1007,6 +1012,7 @@ mod tests {
             change: None,
             unit: Some(CurrencyUnit::Sat),
             method: PaymentMethod::BOLT11,
+            change_policy: None,
         }));
 
         let wallet = create_test_wallet_with_mock(db.clone(), mock_client).await;
@@ -1104,6 +1110,7 @@ mod tests {
             change: None,
             unit: Some(CurrencyUnit::Sat),
             method: PaymentMethod::BOLT11,
+            change_policy: None,
         }));
 
         let wallet = create_test_wallet_with_mock(db.clone(), mock_client).await;
@@ -1211,6 +1218,7 @@ mod tests {
             change: None,
             unit: Some(CurrencyUnit::Sat),
             method: PaymentMethod::BOLT11,
+            change_policy: None,
         }));
 
         let wallet = create_test_wallet_with_mock(db.clone(), mock_client).await;
@@ -1279,6 +1287,7 @@ mod tests {
             change: None,
             unit: Some(CurrencyUnit::Sat),
             method: PaymentMethod::BOLT11,
+            change_policy: None,
         }));
 
         let wallet = create_test_wallet_with_mock(db.clone(), mock_client).await;
@@ -1357,6 +1366,7 @@ mod tests {
             change: None,
             unit: Some(CurrencyUnit::Sat),
             method: PaymentMethod::BOLT11,
+            change_policy: None,
         }));
 
         let wallet = create_test_wallet_with_mock(db.clone(), mock_client).await;
@@ -1447,6 +1457,7 @@ mod tests {
             change: None,
             unit: Some(CurrencyUnit::Sat),
             method: PaymentMethod::BOLT11,
+            change_policy: None,
         }));
         mock_client._set_restore_response(Ok(restore_response_with_amounts(
             &blinded_messages,
@@ -1546,10 +1557,12 @@ mod tests {
             change: None,
             unit: Some(CurrencyUnit::Sat),
             method: PaymentMethod::BOLT11,
+            change_policy: None,
         }));
         mock_client._set_restore_response(Ok(RestoreResponse {
             outputs: vec![],
             signatures: vec![],
+            next_cursor: None,
         }));
 
         let wallet = create_test_wallet_with_mock(db.clone(), mock_client).await;
@@ -1629,6 +1642,7 @@ mod tests {
             change: None,
             unit: Some(CurrencyUnit::Sat),
             method: PaymentMethod::BOLT11,
+            change_policy: None,
         }));
         mock_client._set_restore_response(Err(crate::Error::Timeout));
 
@@ -1704,6 +1718,7 @@ mod tests {
             change: None,
             unit: Some(CurrencyUnit::Sat),
             method: PaymentMethod::BOLT11,
+            change_policy: None,
         }));
 
         let wallet = create_test_wallet_with_mock(db.clone(), mock_client).await;
@@ -1792,6 +1807,7 @@ mod tests {
             change: None,
             unit: Some(CurrencyUnit::Sat),
             method: PaymentMethod::BOLT11,
+            change_policy: None,
         }));
         mock_client._set_restore_response(Err(crate::Error::HttpError(
             Some(404),
@@ -1879,6 +1895,7 @@ mod tests {
             change: None,
             unit: Some(CurrencyUnit::Sat),
             method: PaymentMethod::BOLT11,
+            change_policy: None,
         }));
 
         let wallet = create_test_wallet_with_mock(db.clone(), mock_client).await;
@@ -1960,6 +1977,7 @@ mod tests {
             change: None,
             unit: Some(CurrencyUnit::Sat),
             method: PaymentMethod::BOLT11,
+            change_policy: None,
         }));
 
         let wallet = create_test_wallet_with_mock(db.clone(), mock_client).await;
@@ -21,7 +21,7 @@ use cdk_common::wallet::{KeysetLoadPolicy, WalletSaga};
 use cdk_common::MeltQuoteState;
 use uuid::Uuid;
 
-use crate::nuts::{PreMintSecrets, Proofs};
+use crate::nuts::{PreMintSecrets, Proofs, SecretKey};
 use crate::wallet::MeltQuote;
 use crate::Amount;
 
@@ -66,6 +66,8 @@ pub struct MeltRequested {
     pub final_proofs: Proofs,
     /// Pre-mint secrets for change
     pub premint_secrets: PreMintSecrets,
+    /// Keys to sign the melt request with if the inputs are locked with SIG_ALL
+    pub p2pk_signing_keys: Vec<SecretKey>,
 }
 
 /// Finalized state - melt completed successfully.
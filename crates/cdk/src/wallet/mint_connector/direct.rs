@@ -0,0 +1,384 @@
+//! In-process mint connector
+//!
+//! [`DirectMintConnector`] calls a [`Mint`] in the same process instead of sending HTTP requests,
+//! so wallet and mint can be exercised together in integration tests and load tests without a
+//! server and without network flakiness.
+
+use std::fmt::{Debug, Formatter};
+use std::str::FromStr;
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use cdk_common::nut00::KnownMethod;
+use cdk_common::quote_id::QuoteId;
+use cdk_common::{MeltQuoteRequest, MeltQuoteResponse, MintQuoteRequest, MintQuoteResponse};
+use tokio::sync::RwLock;
+
+use super::{LnurlPayInvoiceResponse, LnurlPayResponse, MintConnector};
+use crate::mint::Mint;
+use crate::nuts::{
+    BatchCheckMintQuoteRequest, BatchMintRequest, CheckStateRequest, CheckStateResponse, Id,
+    KeySet, KeysetResponse, MeltRequest, MintInfo, MintRequest, MintResponse, PaymentMethod,
+    RestoreRequest, RestoreResponse, SwapRequest, SwapResponse,
+};
+use crate::util::unix_time;
+use crate::wallet::AuthWallet;
+use crate::{Error, MeltQuoteCreateResponse};
+
+/// [`MintConnector`] calling a [`Mint`] in the same process
+#[derive(Clone)]
+pub struct DirectMintConnector {
+    mint: Mint,
+    auth_wallet: Arc<RwLock<Option<AuthWallet>>>,
+}
+
+impl DirectMintConnector {
+    /// Create a connector for `mint`
+    pub fn new(mint: Mint) -> Self {
+        Self {
+            mint,
+            auth_wallet: Arc::new(RwLock::new(None)),
+        }
+    }
+
+    /// Mint the connector calls
+    pub fn mint(&self) -> &Mint {
+        &self.mint
+    }
+}
+
+impl Debug for DirectMintConnector {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "DirectMintConnector")
+    }
+}
+
+/// The mint uses [`QuoteId`]s for quotes while the [`MintConnector`] interface uses strings, so
+/// requests and responses are converted between the two.
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+impl MintConnector for DirectMintConnector {
+    #[cfg(all(feature = "bip353", not(target_arch = "wasm32")))]
+    async fn resolve_dns_txt(&self, _domain: &str) -> Result<Vec<String>, Error> {
+        Err(Error::Custom(
+            "DNS resolution is not supported by DirectMintConnector".to_string(),
+        ))
+    }
+
+    async fn fetch_lnurl_pay_request(&self, _url: &str) -> Result<LnurlPayResponse, Error> {
+        Err(Error::LightningAddressRequest(
+            "Lightning addresses are not supported by DirectMintConnector".to_string(),
+        ))
+    }
+
+    async fn fetch_lnurl_invoice(&self, _url: &str) -> Result<LnurlPayInvoiceResponse, Error> {
+        Err(Error::LightningAddressRequest(
+            "Lightning addresses are not supported by DirectMintConnector".to_string(),
+        ))
+    }
+
+    async fn get_mint_keys(&self) -> Result<Vec<KeySet>, Error> {
+        Ok(self.mint.pubkeys().keysets)
+    }
+
+    async fn get_mint_keyset(&self, keyset_id: Id) -> Result<KeySet, Error> {
+        self.mint.keyset(&keyset_id).ok_or(Error::UnknownKeySet)
+    }
+
+    async fn get_mint_keysets(&self) -> Result<KeysetResponse, Error> {
+        Ok(self.mint.keysets())
+    }
+
+    async fn post_mint_quote(
+        &self,
+        request: MintQuoteRequest,
+    ) -> Result<MintQuoteResponse<String>, Error> {
+        match request {
+            MintQuoteRequest::Bolt11(req) => {
+                let response = self.mint.get_mint_quote(req.into()).await?;
+                match response {
+                    cdk_common::MintQuoteResponse::Bolt11(r) => {
+                        Ok(MintQuoteResponse::Bolt11(r.to_string_id()))
+                    }
+                    _ => Err(Error::InvalidPaymentMethod),
+                }
+            }
+            MintQuoteRequest::Bolt12(req) => {
+                let response = self.mint.get_mint_quote(req.into()).await?;
+                match response {
+                    cdk_common::MintQuoteResponse::Bolt12(r) => {
+                        Ok(MintQuoteResponse::Bolt12(r.to_string_id()))
+                    }
+                    _ => Err(Error::InvalidPaymentMethod),
+                }
+            }
+            MintQuoteRequest::Custom { method, request } => {
+                let response = self
+                    .mint
+                    .get_mint_quote(crate::mint::MintQuoteRequest::Custom { method, request })
+                    .await?;
+                match response {
+                    cdk_common::MintQuoteResponse::Custom { method, response } => {
+                        Ok(MintQuoteResponse::Custom {
+                            method,
+                            response: response.to_string_id(),
+                        })
+                    }
+                    _ => Err(Error::InvalidPaymentMethod),
+                }
+            }
+            MintQuoteRequest::Onchain(req) => {
+                let response = self.mint.get_mint_quote(req.into()).await?;
+                match response {
+                    cdk_common::MintQuoteResponse::Onchain(r) => {
+                        Ok(MintQuoteResponse::Onchain(r.to_string_id()))
+                    }
+                    _ => Err(Error::InvalidPaymentMethod),
+                }
+            }
+        }
+    }
+
+    async fn get_mint_quote_status(
+        &self,
+        method: PaymentMethod,
+        quote_id: &str,
+    ) -> Result<MintQuoteResponse<String>, Error> {
+        let response = self
+            .mint
+            .check_mint_quotes(&[QuoteId::from_str(quote_id)?])
+            .await?
+            .first()
+            .ok_or(Error::UnknownQuote)?
+            .clone();
+
+        match method {
+            PaymentMethod::Known(KnownMethod::Bolt11) => match response {
+                cdk_common::MintQuoteResponse::Bolt11(r) => {
+                    Ok(MintQuoteResponse::Bolt11(r.to_string_id()))
+                }
+                _ => Err(Error::InvalidPaymentMethod),
+            },
+            PaymentMethod::Known(KnownMethod::Bolt12) => match response {
+                cdk_common::MintQuoteResponse::Bolt12(r) => {
+                    Ok(MintQuoteResponse::Bolt12(r.to_string_id()))
+                }
+                _ => Err(Error::InvalidPaymentMethod),
+            },
+            PaymentMethod::Known(KnownMethod::Onchain) => match response {
+                cdk_common::MintQuoteResponse::Onchain(r) => {
+                    Ok(MintQuoteResponse::Onchain(r.to_string_id()))
+                }
+                _ => Err(Error::InvalidPaymentMethod),
+            },
+            PaymentMethod::Custom(_) => match response {
+                cdk_common::MintQuoteResponse::Custom { method, response } => {
+                    Ok(MintQuoteResponse::Custom {
+                        method,
+                        response: response.to_string_id(),
+                    })
+                }
+                _ => Err(Error::InvalidPaymentMethod),
+            },
+        }
+    }
+
+    async fn post_mint(
+        &self,
+        _method: &PaymentMethod,
+        request: MintRequest<String>,
+    ) -> Result<MintResponse, Error> {
+        let request_id: MintRequest<QuoteId> = request.try_into()?;
+        self.mint
+            .process_mint_request(crate::mint::MintInput::Single(request_id))
+            .await
+    }
+
+    async fn post_batch_check_mint_quote_status(
+        &self,
+        _method: &PaymentMethod,
+        request: BatchCheckMintQuoteRequest<String>,
+    ) -> Result<Vec<MintQuoteResponse<String>>, Error> {
+        let quote_ids: Vec<QuoteId> = request
+            .quotes
+            .iter()
+            .filter_map(|s| QuoteId::from_str(s).ok())
+            .collect();
+        self.mint
+            .check_mint_quotes(&quote_ids)
+            .await
+            .map(|responses| responses.into_iter().map(Into::into).collect())
+    }
+
+    async fn post_batch_mint(
+        &self,
+        _method: &PaymentMethod,
+        request: BatchMintRequest<String>,
+    ) -> Result<MintResponse, Error> {
+        let quotes: Vec<QuoteId> = request
+            .quotes
+            .iter()
+            .filter_map(|s| QuoteId::from_str(s).ok())
+            .collect();
+
+        let request_id = BatchMintRequest {
+            quotes,
+            quote_amounts: request.quote_amounts,
+            outputs: request.outputs,
+            signatures: request.signatures,
+        };
+
+        self.mint
+            .process_mint_request(crate::mint::MintInput::Batch(request_id))
+            .await
+    }
+
+    async fn post_melt_quote(
+        &self,
+        request: MeltQuoteRequest,
+    ) -> Result<MeltQuoteCreateResponse<String>, Error> {
+        match request {
+            MeltQuoteRequest::Bolt11(req) => {
+                let response = self.mint.get_melt_quote(req.into()).await?;
+                match response {
+                    cdk_common::MeltQuoteCreateResponse::Bolt11(r) => {
+                        Ok(MeltQuoteCreateResponse::Bolt11(r.to_string_id()))
+                    }
+                    _ => Err(Error::InvalidPaymentMethod),
+                }
+            }
+            MeltQuoteRequest::Bolt12(req) => {
+                let response = self.mint.get_melt_quote(req.into()).await?;
+                match response {
+                    cdk_common::MeltQuoteCreateResponse::Bolt12(r) => {
+                        Ok(MeltQuoteCreateResponse::Bolt12(r.to_string_id()))
+                    }
+                    _ => Err(Error::InvalidPaymentMethod),
+                }
+            }
+            MeltQuoteRequest::Custom(req) => {
+                let response = self.mint.get_melt_quote(req.into()).await?;
+                match response {
+                    cdk_common::MeltQuoteCreateResponse::Custom((method, r)) => {
+                        Ok(MeltQuoteCreateResponse::Custom((method, r.to_string_id())))
+                    }
+                    _ => Err(Error::InvalidPaymentMethod),
+                }
+            }
+            MeltQuoteRequest::Onchain(req) => {
+                let response = self.mint.get_melt_quote(req.into()).await?;
+                match response {
+                    cdk_common::MeltQuoteCreateResponse::Onchain(r) => {
+                        Ok(MeltQuoteCreateResponse::Onchain(r.into()))
+                    }
+                    _ => Err(Error::InvalidPaymentMethod),
+                }
+            }
+        }
+    }
+
+    async fn get_melt_quote_status(
+        &self,
+        method: PaymentMethod,
+        quote_id: &str,
+    ) -> Result<MeltQuoteResponse<String>, Error> {
+        let response = self
+            .mint
+            .check_melt_quote(&QuoteId::from_str(quote_id)?)
+            .await?;
+
+        match method {
+            PaymentMethod::Known(KnownMethod::Bolt11) => match response {
+                cdk_common::MeltQuoteResponse::Bolt11(r) => {
+                    Ok(MeltQuoteResponse::Bolt11(r.to_string_id()))
+                }
+                _ => Err(Error::InvalidPaymentMethod),
+            },
+            PaymentMethod::Known(KnownMethod::Bolt12) => match response {
+                cdk_common::MeltQuoteResponse::Bolt12(r) => {
+                    Ok(MeltQuoteResponse::Bolt12(r.to_string_id()))
+                }
+                _ => Err(Error::InvalidPaymentMethod),
+            },
+            PaymentMethod::Custom(_) => match response {
+                cdk_common::MeltQuoteResponse::Custom((quote_method, r)) => {
+                    Ok(MeltQuoteResponse::Custom((quote_method, r.to_string_id())))
+                }
+                _ => Err(Error::InvalidPaymentMethod),
+            },
+            PaymentMethod::Known(KnownMethod::Onchain) => match response {
+                cdk_common::MeltQuoteResponse::Onchain(r) => {
+                    Ok(MeltQuoteResponse::Onchain(r.into()))
+                }
+                _ => Err(Error::InvalidPaymentMethod),
+            },
+        }
+    }
+
+    async fn post_melt(
+        &self,
+        method: &PaymentMethod,
+        request: MeltRequest<String>,
+    ) -> Result<MeltQuoteResponse<String>, Error> {
+        let request_uuid: MeltRequest<QuoteId> = request.try_into()?;
+        let response = self.mint.melt(&request_uuid).await?.await?;
+
+        match method {
+            PaymentMethod::Known(KnownMethod::Bolt11) => match response {
+                cdk_common::MeltQuoteResponse::Bolt11(r) => {
+                    Ok(MeltQuoteResponse::Bolt11(r.to_string_id()))
+                }
+                _ => Err(Error::InvalidPaymentMethod),
+            },
+            PaymentMethod::Known(KnownMethod::Bolt12) => match response {
+                cdk_common::MeltQuoteResponse::Bolt12(r) => {
+                    Ok(MeltQuoteResponse::Bolt12(r.to_string_id()))
+                }
+                _ => Err(Error::InvalidPaymentMethod),
+            },
+            PaymentMethod::Custom(_) => match response {
+                cdk_common::MeltQuoteResponse::Custom((quote_method, r)) => {
+                    Ok(MeltQuoteResponse::Custom((quote_method, r.to_string_id())))
+                }
+                _ => Err(Error::InvalidPaymentMethod),
+            },
+            PaymentMethod::Known(KnownMethod::Onchain) => match response {
+                cdk_common::MeltQuoteResponse::Onchain(r) => {
+                    Ok(MeltQuoteResponse::Onchain(r.into()))
+                }
+                _ => Err(Error::InvalidPaymentMethod),
+            },
+        }
+    }
+
+    async fn post_swap(&self, swap_request: SwapRequest) -> Result<SwapResponse, Error> {
+        self.mint.process_swap_request(swap_request).await
+    }
+
+    async fn get_mint_info(&self) -> Result<MintInfo, Error> {
+        Ok(self.mint.mint_info().await?.clone().time(unix_time()))
+    }
+
+    async fn post_check_state(
+        &self,
+        request: CheckStateRequest,
+    ) -> Result<CheckStateResponse, Error> {
+        self.mint.check_state(&request).await
+    }
+
+    async fn post_restore(&self, request: RestoreRequest) -> Result<RestoreResponse, Error> {
+        self.mint.restore(request).await
+    }
+
+    /// Get the auth wallet for the client
+    async fn get_auth_wallet(&self) -> Option<AuthWallet> {
+        self.auth_wallet.read().await.clone()
+    }
+
+    /// Set auth wallet on client
+    async fn set_auth_wallet(&self, wallet: Option<AuthWallet>) {
+        let mut auth_wallet = self.auth_wallet.write().await;
+
+        *auth_wallet = wallet;
+    }
+}
@@ -1,5 +1,5 @@
 //! HTTP Mint client with pluggable transport
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 use std::sync::{Arc, RwLock as StdRwLock};
 
 use async_trait::async_trait;
@@ -17,6 +17,7 @@ use tracing::instrument;
 use url::Url;
 use web_time::{Duration, Instant};
 
+use super::telemetry::{self, MintRequestInfo};
 use super::transport::Transport;
 use super::{Error, MintConnector};
 use crate::error::ErrorResponse;
@@ -28,11 +29,18 @@ use crate::nuts::{
     Id, KeySet, KeysResponse, KeysetResponse, MeltOnchainRequest, MeltRequest, MintInfo,
     MintRequest, MintResponse, RestoreRequest, RestoreResponse, SwapRequest, SwapResponse,
 };
+use crate::util::unix_time;
 use crate::wallet::auth::{AuthMintConnector, AuthWallet};
 use crate::OidcClient;
 
 type Cache = (u64, HashSet<(nut19::Method, nut19::Path)>);
 
+/// Last `ETag` and body received for a URL
+type EtagCache = HashMap<Url, (String, Vec<u8>)>;
+
+/// HTTP status of a response to a conditional request for an unchanged resource
+const HTTP_NOT_MODIFIED: u16 = 304;
+
 fn payment_method_path_segment(method: &PaymentMethod) -> Result<&str, Error> {
     match method {
         PaymentMethod::Known(known) => Ok(known.as_str()),
@@ -82,6 +90,7 @@ where
     transport: Arc<T>,
     mint_url: MintUrl,
     cache_support: Arc<StdRwLock<Cache>>,
+    etag_cache: Arc<StdRwLock<EtagCache>>,
     auth_wallet: Arc<RwLock<Option<AuthWallet>>>,
 }
 
@@ -154,6 +163,64 @@ where
             .map_err(Self::map_http_error)
     }
 
+    /// GET a resource the mint serves with an `ETag`, revalidating the cached copy
+    ///
+    /// While keys, keysets or mint info are unchanged the mint answers with `304 Not Modified`
+    /// and the previously received body is used.
+    async fn conditional_http_get<R>(&self, url: Url) -> Result<R, Error>
+    where
+        R: DeserializeOwned,
+    {
+        Ok(self.revalidate_http_get(url).await?.0)
+    }
+
+    /// Like [`Self::conditional_http_get`], also returning whether the cached body was used
+    async fn revalidate_http_get<R>(&self, url: Url) -> Result<(R, bool), Error>
+    where
+        R: DeserializeOwned,
+    {
+        let cached = self
+            .etag_cache
+            .read()
+            .ok()
+            .and_then(|cache| cache.get(&url).cloned());
+
+        let response = self
+            .transport
+            .http_get_conditional(
+                url.clone(),
+                None,
+                cached.as_ref().map(|(etag, _)| etag.as_str()),
+            )
+            .await
+            .map_err(Self::map_http_error)?;
+
+        if response.status() == HTTP_NOT_MODIFIED {
+            if let Some((_, body)) = cached {
+                tracing::trace!("{} not modified, using cached response", url);
+                return serde_json::from_slice(&body)
+                    .map(|value| (value, true))
+                    .map_err(|e| Error::Custom(e.to_string()));
+            }
+        }
+
+        if !response.is_success() {
+            return response
+                .json_or_status_error()
+                .map_err(Self::map_http_error);
+        }
+
+        let etag = response.header("etag").map(str::to_string);
+        let body = response.bytes().await.map_err(Self::map_http_error)?;
+        let value = serde_json::from_slice(&body).map_err(|e| Error::Custom(e.to_string()))?;
+
+        if let (Some(etag), Ok(mut cache)) = (etag, self.etag_cache.write()) {
+            cache.insert(url, (etag, body));
+        }
+
+        Ok((value, false))
+    }
+
     async fn transport_http_post<P, R>(
         &self,
         url: Url,
@@ -181,6 +248,7 @@ where
             mint_url,
             auth_wallet: Arc::new(RwLock::new(auth_wallet)),
             cache_support: Default::default(),
+            etag_cache: Default::default(),
         }
     }
 
@@ -191,6 +259,7 @@ where
             mint_url,
             auth_wallet: Arc::new(RwLock::new(auth_wallet)),
             cache_support: Default::default(),
+            etag_cache: Default::default(),
         }
     }
 
@@ -230,6 +299,7 @@ where
             mint_url,
             auth_wallet: Arc::new(RwLock::new(None)),
             cache_support: Default::default(),
+            etag_cache: Default::default(),
         })
     }
 
@@ -363,6 +433,14 @@ where
         ))
     }
 
+    fn mint_connector(&self, mint_url: MintUrl) -> Arc<dyn MintConnector + Send + Sync> {
+        Arc::new(Self::with_transport(
+            mint_url,
+            self.transport.as_ref().clone(),
+            None,
+        ))
+    }
+
     fn oidc_client(&self, openid_discovery: String, client_id: Option<String>) -> OidcClient {
         OidcClient::with_transport(
             openid_discovery,
@@ -418,13 +496,36 @@ where
         self.transport_http_get(parsed_url, None).await
     }
 
+    /// Fetch LNURL-withdraw request data
+    #[cfg(feature = "lnurl")]
+    #[instrument(skip(self))]
+    async fn fetch_lnurl_withdraw_request(
+        &self,
+        url: &str,
+    ) -> Result<crate::lnurl::LnurlWithdrawResponse, Error> {
+        let parsed_url =
+            url::Url::parse(url).map_err(|e| Error::Custom(format!("Invalid URL: {}", e)))?;
+        self.transport_http_get(parsed_url, None).await
+    }
+
+    /// Submit an invoice to an LNURL-withdraw callback
+    #[cfg(feature = "lnurl")]
+    #[instrument(skip(self, url))]
+    async fn fetch_lnurl_withdraw_callback(
+        &self,
+        url: &str,
+    ) -> Result<crate::lnurl::LnurlStatusResponse, Error> {
+        let parsed_url = parse_lnurl_callback_url(url)?;
+        self.transport_http_get(parsed_url, None).await
+    }
+
     /// Get Active Mint Keys [NUT-01]
     #[instrument(skip(self), fields(mint_url = %self.mint_url))]
     async fn get_mint_keys(&self) -> Result<Vec<KeySet>, Error> {
         let url = self.mint_url.join_paths(&["v1", "keys"])?;
 
         Ok(self
-            .transport_http_get::<KeysResponse>(url, None)
+            .conditional_http_get::<KeysResponse>(url)
             .await?
             .keysets)
     }
@@ -436,7 +537,7 @@ where
             .mint_url
             .join_paths(&["v1", "keys", &keyset_id.to_string()])?;
 
-        let keys_response = self.transport_http_get::<KeysResponse>(url, None).await?;
+        let keys_response = self.conditional_http_get::<KeysResponse>(url).await?;
 
         Ok(keys_response
             .keysets
@@ -449,7 +550,7 @@ where
     #[instrument(skip(self), fields(mint_url = %self.mint_url))]
     async fn get_mint_keysets(&self) -> Result<KeysetResponse, Error> {
         let url = self.mint_url.join_paths(&["v1", "keysets"])?;
-        self.transport_http_get(url, None).await
+        self.conditional_http_get(url).await
     }
 
     /// Mint Quote [NUT-04, NUT-23, NUT-25]
@@ -458,41 +559,46 @@ where
         &self,
         request: MintQuoteRequest,
     ) -> Result<MintQuoteResponse<String>, Error> {
-        let method = request.method();
-        let method_name = payment_method_path_segment(&method)?;
+        let info = MintRequestInfo::new("mint_quote", &self.mint_url);
 
-        let url = self
-            .mint_url
-            .join_paths(&["v1", "mint", "quote", method_name])?;
+        telemetry::traced(info, async {
+            let method = request.method();
+            let method_name = payment_method_path_segment(&method)?;
 
-        let auth_token = self
-            .get_auth_token(Method::Post, RoutePath::MintQuote(method.to_string()))
-            .await?;
+            let url = self
+                .mint_url
+                .join_paths(&["v1", "mint", "quote", method_name])?;
 
-        match &request {
-            MintQuoteRequest::Bolt11(req) => {
-                let response: cdk_common::nut23::MintQuoteBolt11Response<String> =
-                    self.transport_http_post(url, auth_token, req).await?;
-                Ok(MintQuoteResponse::Bolt11(response))
-            }
-            MintQuoteRequest::Bolt12(req) => {
-                let response: cdk_common::nut25::MintQuoteBolt12Response<String> =
-                    self.transport_http_post(url, auth_token, req).await?;
-                Ok(MintQuoteResponse::Bolt12(response))
-            }
-            MintQuoteRequest::Onchain(req) => {
-                let response: cdk_common::nut30::MintQuoteOnchainResponse<String> =
-                    self.transport_http_post(url, auth_token, req).await?;
-                Ok(MintQuoteResponse::Onchain(response))
-            }
-            MintQuoteRequest::Custom { request: req, .. } => {
-                let value: serde_json::Value =
-                    self.transport_http_post(url, auth_token, req).await?;
-                let response: cdk_common::nut04::MintQuoteCustomResponse<String> =
-                    deserialize_with_route_method(value, &method)?;
-                Ok(MintQuoteResponse::Custom { method, response })
+            let auth_token = self
+                .get_auth_token(Method::Post, RoutePath::MintQuote(method.to_string()))
+                .await?;
+
+            match &request {
+                MintQuoteRequest::Bolt11(req) => {
+                    let response: cdk_common::nut23::MintQuoteBolt11Response<String> =
+                        self.transport_http_post(url, auth_token, req).await?;
+                    Ok(MintQuoteResponse::Bolt11(response))
+                }
+                MintQuoteRequest::Bolt12(req) => {
+                    let response: cdk_common::nut25::MintQuoteBolt12Response<String> =
+                        self.transport_http_post(url, auth_token, req).await?;
+                    Ok(MintQuoteResponse::Bolt12(response))
+                }
+                MintQuoteRequest::Onchain(req) => {
+                    let response: cdk_common::nut30::MintQuoteOnchainResponse<String> =
+                        self.transport_http_post(url, auth_token, req).await?;
+                    Ok(MintQuoteResponse::Onchain(response))
+                }
+                MintQuoteRequest::Custom { request: req, .. } => {
+                    let value: serde_json::Value =
+                        self.transport_http_post(url, auth_token, req).await?;
+                    let response: cdk_common::nut04::MintQuoteCustomResponse<String> =
+                        deserialize_with_route_method(value, &method)?;
+                    Ok(MintQuoteResponse::Custom { method, response })
+                }
             }
-        }
+        })
+        .await
     }
 
     /// Mint Quote status with payment method
@@ -582,26 +688,31 @@ where
         method: &PaymentMethod,
         request: MintRequest<String>,
     ) -> Result<MintResponse, Error> {
-        let method_name = payment_method_path_segment(method)?;
-        let auth_token = self
-            .get_auth_token(Method::Post, RoutePath::Mint(method.to_string()))
-            .await?;
+        let info = MintRequestInfo::new("mint", &self.mint_url).with_size(0, request.outputs.len());
 
-        let path = match method {
-            PaymentMethod::Known(KnownMethod::Bolt11) => {
-                nut19::Path::Custom("/v1/mint/bolt11".to_string())
-            }
-            PaymentMethod::Known(KnownMethod::Bolt12) => {
-                nut19::Path::Custom("/v1/mint/bolt12".to_string())
-            }
-            PaymentMethod::Custom(_) => nut19::Path::custom_mint(method_name),
-            PaymentMethod::Known(KnownMethod::Onchain) => {
-                nut19::Path::Custom("/v1/mint/onchain".to_string())
-            }
-        };
+        telemetry::traced(info, async {
+            let method_name = payment_method_path_segment(method)?;
+            let auth_token = self
+                .get_auth_token(Method::Post, RoutePath::Mint(method.to_string()))
+                .await?;
 
-        self.retriable_http_request(nut19::Method::Post, path, auth_token, &request)
-            .await
+            let path = match method {
+                PaymentMethod::Known(KnownMethod::Bolt11) => {
+                    nut19::Path::Custom("/v1/mint/bolt11".to_string())
+                }
+                PaymentMethod::Known(KnownMethod::Bolt12) => {
+                    nut19::Path::Custom("/v1/mint/bolt12".to_string())
+                }
+                PaymentMethod::Custom(_) => nut19::Path::custom_mint(method_name),
+                PaymentMethod::Known(KnownMethod::Onchain) => {
+                    nut19::Path::Custom("/v1/mint/onchain".to_string())
+                }
+            };
+
+            self.retriable_http_request(nut19::Method::Post, path, auth_token, &request)
+                .await
+        })
+        .await
     }
 
     /// Batch check mint quote status [NUT-29]
@@ -668,15 +779,21 @@ where
         method: &PaymentMethod,
         request: BatchMintRequest<String>,
     ) -> Result<MintResponse, Error> {
-        let method_name = payment_method_path_segment(method)?;
-        let auth_token = self
-            .get_auth_token(Method::Post, RoutePath::Mint(method.to_string()))
-            .await?;
+        let info =
+            MintRequestInfo::new("batch_mint", &self.mint_url).with_size(0, request.outputs.len());
 
-        let path = nut19::Path::Custom(format!("/v1/mint/{method_name}/batch"));
+        telemetry::traced(info, async {
+            let method_name = payment_method_path_segment(method)?;
+            let auth_token = self
+                .get_auth_token(Method::Post, RoutePath::Mint(method.to_string()))
+                .await?;
 
-        self.retriable_http_request(nut19::Method::Post, path, auth_token, &request)
-            .await
+            let path = nut19::Path::Custom(format!("/v1/mint/{method_name}/batch"));
+
+            self.retriable_http_request(nut19::Method::Post, path, auth_token, &request)
+                .await
+        })
+        .await
     }
 
     /// Melt Quote [NUT-05]
@@ -685,40 +802,45 @@ where
         &self,
         request: MeltQuoteRequest,
     ) -> Result<MeltQuoteCreateResponse<String>, Error> {
-        let method = request.method();
-        let method_name = payment_method_path_segment(&method)?;
-
-        let url = self
-            .mint_url
-            .join_paths(&["v1", "melt", "quote", method_name])?;
-        let auth_token = self
-            .get_auth_token(Method::Post, RoutePath::MeltQuote(method.to_string()))
-            .await?;
-
-        match &request {
-            MeltQuoteRequest::Bolt11(req) => {
-                let response: cdk_common::nut23::MeltQuoteBolt11Response<String> =
-                    self.transport_http_post(url, auth_token, req).await?;
-                Ok(MeltQuoteCreateResponse::Bolt11(response))
-            }
-            MeltQuoteRequest::Bolt12(req) => {
-                let response: cdk_common::nut25::MeltQuoteBolt12Response<String> =
-                    self.transport_http_post(url, auth_token, req).await?;
-                Ok(MeltQuoteCreateResponse::Bolt12(response))
-            }
-            MeltQuoteRequest::Onchain(req) => {
-                let response: cdk_common::nut30::MeltQuoteOnchainResponse<String> =
-                    self.transport_http_post(url, auth_token, req).await?;
-                Ok(MeltQuoteCreateResponse::Onchain(response))
-            }
-            MeltQuoteRequest::Custom(req) => {
-                let value: serde_json::Value =
-                    self.transport_http_post(url, auth_token, req).await?;
-                let response: cdk_common::nut05::MeltQuoteCustomResponse<String> =
-                    deserialize_with_route_method(value, &method)?;
-                Ok(MeltQuoteCreateResponse::Custom((method, response)))
+        let info = MintRequestInfo::new("melt_quote", &self.mint_url);
+
+        telemetry::traced(info, async {
+            let method = request.method();
+            let method_name = payment_method_path_segment(&method)?;
+
+            let url = self
+                .mint_url
+                .join_paths(&["v1", "melt", "quote", method_name])?;
+            let auth_token = self
+                .get_auth_token(Method::Post, RoutePath::MeltQuote(method.to_string()))
+                .await?;
+
+            match &request {
+                MeltQuoteRequest::Bolt11(req) => {
+                    let response: cdk_common::nut23::MeltQuoteBolt11Response<String> =
+                        self.transport_http_post(url, auth_token, req).await?;
+                    Ok(MeltQuoteCreateResponse::Bolt11(response))
+                }
+                MeltQuoteRequest::Bolt12(req) => {
+                    let response: cdk_common::nut25::MeltQuoteBolt12Response<String> =
+                        self.transport_http_post(url, auth_token, req).await?;
+                    Ok(MeltQuoteCreateResponse::Bolt12(response))
+                }
+                MeltQuoteRequest::Onchain(req) => {
+                    let response: cdk_common::nut30::MeltQuoteOnchainResponse<String> =
+                        self.transport_http_post(url, auth_token, req).await?;
+                    Ok(MeltQuoteCreateResponse::Onchain(response))
+                }
+                MeltQuoteRequest::Custom(req) => {
+                    let value: serde_json::Value =
+                        self.transport_http_post(url, auth_token, req).await?;
+                    let response: cdk_common::nut05::MeltQuoteCustomResponse<String> =
+                        deserialize_with_route_method(value, &method)?;
+                    Ok(MeltQuoteCreateResponse::Custom((method, response)))
+                }
             }
-        }
+        })
+        .await
     }
 
     /// Melt Quote Status
@@ -809,80 +931,100 @@ where
         method: &PaymentMethod,
         request: MeltRequest<String>,
     ) -> Result<MeltQuoteResponse<String>, Error> {
-        let method_name = payment_method_path_segment(method)?;
-        let auth_token = self
-            .get_auth_token(Method::Post, RoutePath::Melt(method.to_string()))
-            .await?;
+        let info = MintRequestInfo::new("melt", &self.mint_url).with_size(
+            request.inputs().len(),
+            request.outputs().as_ref().map_or(0, Vec::len),
+        );
 
-        let path = match method {
-            PaymentMethod::Known(KnownMethod::Bolt11) => {
-                nut19::Path::Custom("/v1/melt/bolt11".to_string())
-            }
-            PaymentMethod::Known(KnownMethod::Bolt12) => {
-                nut19::Path::Custom("/v1/melt/bolt12".to_string())
-            }
-            PaymentMethod::Custom(_) => nut19::Path::custom_melt(method_name),
-            PaymentMethod::Known(KnownMethod::Onchain) => {
-                nut19::Path::Custom("/v1/melt/onchain".to_string())
-            }
-        };
+        telemetry::traced(info, async {
+            let method_name = payment_method_path_segment(method)?;
+            let auth_token = self
+                .get_auth_token(Method::Post, RoutePath::Melt(method.to_string()))
+                .await?;
 
-        match method {
-            PaymentMethod::Known(KnownMethod::Bolt11) => {
-                let res: cdk_common::nuts::MeltQuoteBolt11Response<String> = self
-                    .retriable_http_request(nut19::Method::Post, path, auth_token, &request)
-                    .await?;
-                Ok(MeltQuoteResponse::Bolt11(res))
-            }
-            PaymentMethod::Known(KnownMethod::Bolt12) => {
-                let res: cdk_common::nuts::MeltQuoteBolt12Response<String> = self
-                    .retriable_http_request(nut19::Method::Post, path, auth_token, &request)
-                    .await?;
-                Ok(MeltQuoteResponse::Bolt12(res))
-            }
-            PaymentMethod::Known(KnownMethod::Onchain) => {
-                let request = MeltOnchainRequest {
-                    quote: request.quote_id().clone(),
-                    fee_index: request
-                        .selected_fee_index()
-                        .ok_or(Error::InvalidPaymentRequest)?,
-                    inputs: request.inputs().clone(),
-                    outputs: request.outputs().clone(),
-                };
-                let res: cdk_common::nuts::MeltQuoteOnchainResponse<String> = self
-                    .retriable_http_request(nut19::Method::Post, path, auth_token, &request)
-                    .await?;
-                Ok(MeltQuoteResponse::Onchain(res))
-            }
-            PaymentMethod::Custom(_) => {
-                let value: serde_json::Value = self
-                    .retriable_http_request(nut19::Method::Post, path, auth_token, &request)
-                    .await?;
-                let res: cdk_common::nuts::MeltQuoteCustomResponse<String> =
-                    deserialize_with_route_method(value, method)?;
-                Ok(MeltQuoteResponse::Custom((method.clone(), res)))
+            let path = match method {
+                PaymentMethod::Known(KnownMethod::Bolt11) => {
+                    nut19::Path::Custom("/v1/melt/bolt11".to_string())
+                }
+                PaymentMethod::Known(KnownMethod::Bolt12) => {
+                    nut19::Path::Custom("/v1/melt/bolt12".to_string())
+                }
+                PaymentMethod::Custom(_) => nut19::Path::custom_melt(method_name),
+                PaymentMethod::Known(KnownMethod::Onchain) => {
+                    nut19::Path::Custom("/v1/melt/onchain".to_string())
+                }
+            };
+
+            match method {
+                PaymentMethod::Known(KnownMethod::Bolt11) => {
+                    let res: cdk_common::nuts::MeltQuoteBolt11Response<String> = self
+                        .retriable_http_request(nut19::Method::Post, path, auth_token, &request)
+                        .await?;
+                    Ok(MeltQuoteResponse::Bolt11(res))
+                }
+                PaymentMethod::Known(KnownMethod::Bolt12) => {
+                    let res: cdk_common::nuts::MeltQuoteBolt12Response<String> = self
+                        .retriable_http_request(nut19::Method::Post, path, auth_token, &request)
+                        .await?;
+                    Ok(MeltQuoteResponse::Bolt12(res))
+                }
+                PaymentMethod::Known(KnownMethod::Onchain) => {
+                    let request = MeltOnchainRequest {
+                        quote: request.quote_id().clone(),
+                        fee_index: request
+                            .selected_fee_index()
+                            .ok_or(Error::InvalidPaymentRequest)?,
+                        inputs: request.inputs().clone(),
+                        outputs: request.outputs().clone(),
+                    };
+                    let res: cdk_common::nuts::MeltQuoteOnchainResponse<String> = self
+                        .retriable_http_request(nut19::Method::Post, path, auth_token, &request)
+                        .await?;
+                    Ok(MeltQuoteResponse::Onchain(res))
+                }
+                PaymentMethod::Custom(_) => {
+                    let value: serde_json::Value = self
+                        .retriable_http_request(nut19::Method::Post, path, auth_token, &request)
+                        .await?;
+                    let res: cdk_common::nuts::MeltQuoteCustomResponse<String> =
+                        deserialize_with_route_method(value, method)?;
+                    Ok(MeltQuoteResponse::Custom((method.clone(), res)))
+                }
             }
-        }
+        })
+        .await
     }
 
     /// Swap Token [NUT-03]
     #[instrument(skip(self, swap_request), fields(mint_url = %self.mint_url))]
     async fn post_swap(&self, swap_request: SwapRequest) -> Result<SwapResponse, Error> {
-        let auth_token = self.get_auth_token(Method::Post, RoutePath::Swap).await?;
+        let info = MintRequestInfo::new("swap", &self.mint_url)
+            .with_size(swap_request.inputs().len(), swap_request.outputs().len());
 
-        self.retriable_http_request(
-            nut19::Method::Post,
-            nut19::Path::Swap,
-            auth_token,
-            &swap_request,
-        )
+        telemetry::traced(info, async {
+            let auth_token = self.get_auth_token(Method::Post, RoutePath::Swap).await?;
+
+            self.retriable_http_request(
+                nut19::Method::Post,
+                nut19::Path::Swap,
+                auth_token,
+                &swap_request,
+            )
+            .await
+        })
         .await
     }
 
     /// Helper to get mint info
     async fn get_mint_info(&self) -> Result<MintInfo, Error> {
         let url = self.mint_url.join_paths(&["v1", "info"])?;
-        let info: MintInfo = self.transport_http_get(url, None).await?;
+        let (mut info, not_modified): (MintInfo, _) = self.revalidate_http_get(url).await?;
+
+        // The cached body carries the time of the first fetch, and a `304 Not Modified` has no
+        // body with the current time of the mint
+        if not_modified {
+            info.time = Some(unix_time());
+        }
 
         if let Ok(mut cache_support) = self.cache_support.write() {
             *cache_support = (
@@ -914,12 +1056,18 @@ where
         &self,
         request: CheckStateRequest,
     ) -> Result<CheckStateResponse, Error> {
-        let url = self.mint_url.join_paths(&["v1", "checkstate"])?;
-        let auth_token = self
-            .get_auth_token(Method::Post, RoutePath::Checkstate)
-            .await?;
+        let info =
+            MintRequestInfo::new("checkstate", &self.mint_url).with_size(0, request.ys.len());
 
-        self.transport_http_post(url, auth_token, &request).await
+        telemetry::traced(info, async {
+            let url = self.mint_url.join_paths(&["v1", "checkstate"])?;
+            let auth_token = self
+                .get_auth_token(Method::Post, RoutePath::Checkstate)
+                .await?;
+
+            self.transport_http_post(url, auth_token, &request).await
+        })
+        .await
     }
 
     /// Restore request [NUT-13]
@@ -1160,6 +1308,25 @@ mod tests {
             Ok(RawResponse::new(200, json.into_bytes()))
         }
 
+        async fn http_get_conditional(
+            &self,
+            url: Url,
+            auth: Option<AuthToken>,
+            etag: Option<&str>,
+        ) -> Result<RawResponse, HttpError> {
+            const MOCK_ETAG: &str = "\"mock-etag\"";
+
+            if etag == Some(MOCK_ETAG) {
+                self.get_urls.lock().expect("lock").push(url.to_string());
+                return Ok(RawResponse::new(304, Vec::new()));
+            }
+
+            Ok(self
+                .http_get_raw(url, auth)
+                .await?
+                .with_headers([("etag".to_string(), MOCK_ETAG.to_string())]))
+        }
+
         async fn http_post<P, R>(
             &self,
             _url: Url,
@@ -1524,4 +1691,60 @@ mod tests {
             "invalid LNURL callback must be rejected before transport"
         );
     }
+
+    #[tokio::test]
+    async fn test_get_mint_keysets_revalidates_with_etag() {
+        let canned_json = serde_json::json!({
+            "keysets": [{
+                "id": "009a1f293253e41e",
+                "unit": "sat",
+                "active": true,
+                "input_fee_ppk": 0
+            }]
+        })
+        .to_string();
+
+        let transport = MockTransport {
+            get_response: Arc::new(Mutex::new(Some(canned_json))),
+            ..Default::default()
+        };
+        let get_urls = transport.get_urls.clone();
+        let get_response = transport.get_response.clone();
+
+        let mint_url = MintUrl::from_str("https://mint.example.com").expect("parse url");
+        let client = HttpClient::with_transport(mint_url, transport, None);
+
+        let first = client.get_mint_keysets().await.expect("first fetch");
+
+        // The mint answers `304 Not Modified`, so the cached body must be used
+        *get_response.lock().expect("lock") = None;
+        let second = client.get_mint_keysets().await.expect("revalidated fetch");
+
+        assert_eq!(first, second);
+        assert_eq!(get_urls.lock().expect("lock").len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_get_mint_info_refreshes_time_when_not_modified() {
+        let canned_json =
+            serde_json::to_string(&MintInfo::new().time(1_700_000_000_u64)).expect("serialize");
+
+        let transport = MockTransport {
+            get_response: Arc::new(Mutex::new(Some(canned_json))),
+            ..Default::default()
+        };
+        let get_response = transport.get_response.clone();
+
+        let mint_url = MintUrl::from_str("https://mint.example.com").expect("parse url");
+        let client = HttpClient::with_transport(mint_url, transport, None);
+
+        let first = client.get_mint_info().await.expect("first fetch");
+        assert_eq!(first.time, Some(1_700_000_000));
+
+        *get_response.lock().expect("lock") = None;
+        let second = client.get_mint_info().await.expect("revalidated fetch");
+
+        assert!(second.time > first.time);
+        assert_eq!(second.name, first.name);
+    }
 }
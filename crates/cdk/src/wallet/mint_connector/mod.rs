@@ -12,6 +12,8 @@ use cdk_common::{
 use super::Error;
 // Re-export Lightning address types for trait implementers
 pub use crate::lightning_address::{LnurlPayInvoiceResponse, LnurlPayResponse};
+#[cfg(feature = "lnurl")]
+pub use crate::lnurl::{LnurlStatusResponse, LnurlWithdrawResponse};
 use crate::nuts::{
     BatchCheckMintQuoteRequest, BatchMintRequest, CheckStateRequest, CheckStateResponse, Id,
     KeySet, KeysetResponse, MeltRequest, MintInfo, MintRequest, MintResponse, PaymentMethod,
@@ -20,9 +22,15 @@ use crate::nuts::{
 use crate::wallet::{AuthMintConnector, AuthWallet};
 use crate::OidcClient;
 
+#[cfg(feature = "mint")]
+mod direct;
 pub mod http_client;
+mod telemetry;
 pub mod transport;
 
+#[cfg(feature = "mint")]
+pub use direct::DirectMintConnector;
+
 /// Auth HTTP Client with async transport
 pub type AuthHttpClient = http_client::AuthHttpClient<transport::Async>;
 /// Default Http Client with async transport (non-Tor)
@@ -47,6 +55,14 @@ pub trait MintConnector: Debug {
         Arc::new(AuthHttpClient::new(mint_url, cat))
     }
 
+    /// Create a connector to another mint using the same underlying transport when possible.
+    fn mint_connector(
+        &self,
+        mint_url: crate::mint_url::MintUrl,
+    ) -> Arc<dyn MintConnector + Send + Sync> {
+        Arc::new(HttpClient::new(mint_url, None))
+    }
+
     /// Create an OIDC client using the same underlying transport when possible.
     fn oidc_client(&self, openid_discovery: String, client_id: Option<String>) -> OidcClient {
         OidcClient::new(openid_discovery, client_id)
@@ -83,6 +99,28 @@ pub trait MintConnector: Debug {
         url: &str,
     ) -> Result<crate::lightning_address::LnurlPayInvoiceResponse, Error>;
 
+    /// Fetch LNURL-withdraw request data
+    #[cfg(feature = "lnurl")]
+    async fn fetch_lnurl_withdraw_request(
+        &self,
+        _url: &str,
+    ) -> Result<crate::lnurl::LnurlWithdrawResponse, Error> {
+        Err(Error::LnurlRequest(
+            "LNURL-withdraw is not supported by this connector".to_string(),
+        ))
+    }
+
+    /// Submit an invoice to an LNURL-withdraw callback
+    #[cfg(feature = "lnurl")]
+    async fn fetch_lnurl_withdraw_callback(
+        &self,
+        _url: &str,
+    ) -> Result<crate::lnurl::LnurlStatusResponse, Error> {
+        Err(Error::LnurlRequest(
+            "LNURL-withdraw is not supported by this connector".to_string(),
+        ))
+    }
+
     /// Get Active Mint Keys [NUT-01]
     async fn get_mint_keys(&self) -> Result<Vec<KeySet>, Error>;
     /// Get Keyset Keys [NUT-01]
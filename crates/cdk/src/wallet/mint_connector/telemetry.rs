@@ -0,0 +1,94 @@
+//! OpenTelemetry spans of mint requests
+//!
+//! With the `otel` feature, quote, mint, swap, melt and checkstate requests of the [`HttpClient`]
+//! each run in a `mint_request` span carrying the mint URL and the number of inputs and outputs.
+//! The span follows the OpenTelemetry conventions of `tracing-opentelemetry` (`otel.name`,
+//! `otel.kind` and `otel.status_code`), so apps exporting their traces see one client span per
+//! request and can find slow mints.
+//!
+//! [`HttpClient`]: super::HttpClient
+
+use std::future::Future;
+
+use crate::mint_url::MintUrl;
+use crate::Error;
+
+/// Request made to a mint
+///
+/// Without the `otel` feature the fields are never read.
+#[derive(Debug, Clone, Copy)]
+#[cfg_attr(not(feature = "otel"), allow(dead_code))]
+pub(crate) struct MintRequestInfo<'a> {
+    /// Name of the operation, the name of the span
+    pub operation: &'static str,
+    /// Mint the request is sent to
+    pub mint_url: &'a MintUrl,
+    /// Number of proofs sent
+    pub inputs: usize,
+    /// Number of blinded messages or `Y`s sent
+    pub outputs: usize,
+}
+
+impl<'a> MintRequestInfo<'a> {
+    /// Request without proofs or blinded messages, e.g. a quote
+    pub fn new(operation: &'static str, mint_url: &'a MintUrl) -> Self {
+        Self {
+            operation,
+            mint_url,
+            inputs: 0,
+            outputs: 0,
+        }
+    }
+
+    /// Set the number of inputs and outputs of the request
+    pub fn with_size(mut self, inputs: usize, outputs: usize) -> Self {
+        self.inputs = inputs;
+        self.outputs = outputs;
+        self
+    }
+}
+
+/// Run `request` in a `mint_request` span, recording its outcome as the span status
+#[cfg(feature = "otel")]
+pub(crate) async fn traced<F, R>(info: MintRequestInfo<'_>, request: F) -> Result<R, Error>
+where
+    F: Future<Output = Result<R, Error>>,
+{
+    use tracing::field::{display, Empty};
+    use tracing::Instrument;
+
+    let span = tracing::info_span!(
+        "mint_request",
+        otel.name = info.operation,
+        otel.kind = "client",
+        otel.status_code = Empty,
+        error.message = Empty,
+        cashu.operation = info.operation,
+        cashu.mint_url = %info.mint_url,
+        cashu.inputs = info.inputs,
+        cashu.outputs = info.outputs,
+    );
+
+    let result = request.instrument(span.clone()).await;
+
+    match &result {
+        Ok(_) => {
+            span.record("otel.status_code", "OK");
+        }
+        Err(err) => {
+            span.record("otel.status_code", "ERROR");
+            span.record("error.message", display(err));
+        }
+    }
+
+    result
+}
+
+/// Run `request`, spans are only created with the `otel` feature
+#[cfg(not(feature = "otel"))]
+pub(crate) async fn traced<F, R>(_info: MintRequestInfo<'_>, request: F) -> Result<R, Error>
+where
+    F: Future<Output = Result<R, Error>>,
+{
+    request.await
+}
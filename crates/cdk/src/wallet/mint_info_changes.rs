@@ -0,0 +1,267 @@
+//! Mint capability change detection
+//!
+//! Compares two snapshots of a mint's metadata and reports what changed, so
+//! apps can react when a mint starts or stops supporting a NUT, changes which
+//! endpoints require authentication or rotates its keysets.
+
+use std::collections::{HashMap, HashSet};
+
+use bitcoin::hashes::sha256::Hash as Sha256Hash;
+use bitcoin::hashes::Hash;
+use cdk_common::nuts::ProtectedEndpoint;
+use cdk_common::{CurrencyUnit, MintInfo};
+use tracing::instrument;
+
+use crate::error::Error;
+use crate::nuts::Id;
+use crate::wallet::mint_metadata_cache::MintMetadata;
+use crate::Wallet;
+
+/// A change in the advertised capabilities of a mint
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MintInfoChange {
+    /// The mint started advertising support for a NUT
+    NutAdded(u8),
+    /// The mint stopped advertising support for a NUT
+    NutRemoved(u8),
+    /// An endpoint now requires authentication
+    AuthRequired(ProtectedEndpoint),
+    /// An endpoint no longer requires authentication
+    AuthNoLongerRequired(ProtectedEndpoint),
+    /// The active keyset of a unit changed
+    KeysetRotated {
+        /// Unit of the keyset
+        unit: CurrencyUnit,
+        /// Previously active keysets of the unit
+        previous: Vec<Id>,
+        /// Currently active keysets of the unit
+        active: Vec<Id>,
+    },
+}
+
+/// Fingerprint of the mint info used to detect changes cheaply
+///
+/// The mint's `time` is excluded as it changes on every response.
+pub(crate) fn mint_info_etag(mint_info: &MintInfo) -> Option<String> {
+    let mut mint_info = mint_info.clone();
+    mint_info.time = None;
+
+    serde_json::to_vec(&mint_info)
+        .inspect_err(|err| tracing::warn!("Could not serialize mint info: {}", err))
+        .ok()
+        .map(|bytes| Sha256Hash::hash(&bytes).to_string())
+}
+
+/// Changes between two snapshots of a mint's metadata
+pub(crate) fn diff_mint_metadata(
+    previous: &MintMetadata,
+    current: &MintMetadata,
+) -> Vec<MintInfoChange> {
+    let mut changes = Vec::new();
+
+    if previous.status.etag.is_none() || previous.status.etag != current.status.etag {
+        let previous_nuts: HashSet<u8> = previous
+            .mint_info
+            .nuts
+            .supported_nuts()
+            .into_iter()
+            .collect();
+        let current_nuts: HashSet<u8> = current
+            .mint_info
+            .nuts
+            .supported_nuts()
+            .into_iter()
+            .collect();
+
+        let mut added: Vec<u8> = current_nuts.difference(&previous_nuts).copied().collect();
+        added.sort_unstable();
+        changes.extend(added.into_iter().map(MintInfoChange::NutAdded));
+
+        let mut removed: Vec<u8> = previous_nuts.difference(&current_nuts).copied().collect();
+        removed.sort_unstable();
+        changes.extend(removed.into_iter().map(MintInfoChange::NutRemoved));
+
+        let previous_protected = previous.mint_info.protected_endpoints();
+        let current_protected = current.mint_info.protected_endpoints();
+
+        let mut newly_protected: Vec<ProtectedEndpoint> = current_protected
+            .keys()
+            .filter(|endpoint| !previous_protected.contains_key(*endpoint))
+            .cloned()
+            .collect();
+        newly_protected.sort_by_key(endpoint_sort_key);
+        changes.extend(
+            newly_protected
+                .into_iter()
+                .map(MintInfoChange::AuthRequired),
+        );
+
+        let mut unprotected: Vec<ProtectedEndpoint> = previous_protected
+            .keys()
+            .filter(|endpoint| !current_protected.contains_key(*endpoint))
+            .cloned()
+            .collect();
+        unprotected.sort_by_key(endpoint_sort_key);
+        changes.extend(
+            unprotected
+                .into_iter()
+                .map(MintInfoChange::AuthNoLongerRequired),
+        );
+    }
+
+    let previous_active = active_keysets_by_unit(previous);
+    let current_active = active_keysets_by_unit(current);
+
+    let mut rotated: Vec<MintInfoChange> = current_active
+        .into_iter()
+        .filter_map(|(unit, active)| {
+            let previous = previous_active.get(&unit).cloned().unwrap_or_default();
+            (previous != active).then_some(MintInfoChange::KeysetRotated {
+                unit,
+                previous,
+                active,
+            })
+        })
+        .collect();
+    rotated.sort_by_key(|change| match change {
+        MintInfoChange::KeysetRotated { unit, .. } => unit.to_string(),
+        _ => String::new(),
+    });
+    changes.extend(rotated);
+
+    changes
+}
+
+fn active_keysets_by_unit(metadata: &MintMetadata) -> HashMap<CurrencyUnit, Vec<Id>> {
+    let mut active: HashMap<CurrencyUnit, Vec<Id>> = HashMap::new();
+
+    for keyset in &metadata.active_keysets {
+        active
+            .entry(keyset.unit.clone())
+            .or_default()
+            .push(keyset.id);
+    }
+
+    for ids in active.values_mut() {
+        ids.sort();
+    }
+
+    active
+}
+
+fn endpoint_sort_key(endpoint: &ProtectedEndpoint) -> String {
+    format!("{} {:?}", endpoint.path, endpoint.method)
+}
+
+impl Wallet {
+    /// Fetch mint info from the mint and report how its capabilities changed
+    ///
+    /// Compares against the cached mint info. Returns no changes when nothing
+    /// was cached before. All changes, including the ones detected by
+    /// background refreshes, are also sent to subscribers of
+    /// [`Wallet::subscribe_mint_info_changes`].
+    #[instrument(skip(self))]
+    pub async fn refresh_mint_info(&self) -> Result<Vec<MintInfoChange>, Error> {
+        let previous = self.metadata_cache.load_cached(&self.localstore).await.ok();
+
+        self.fetch_mint_info().await?;
+
+        let Some(previous) = previous else {
+            return Ok(Vec::new());
+        };
+
+        let current = self
+            .metadata_cache
+            .get_cached()
+            .ok_or(Error::CouldNotGetMintInfo)?;
+
+        Ok(diff_mint_metadata(&previous, &current))
+    }
+
+    /// Subscribe to capability changes of the mint
+    ///
+    /// Changes are reported whenever the mint info is fetched from the mint,
+    /// including cache refreshes triggered by other wallet operations.
+    pub fn subscribe_mint_info_changes(&self) -> tokio::sync::broadcast::Receiver<MintInfoChange> {
+        self.metadata_cache.subscribe_changes()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use cdk_common::nuts::{KeySetInfo, Method, RoutePath};
+
+    use super::*;
+    use crate::nuts::{ClearAuthSettings, Nuts};
+
+    fn metadata(nuts: Nuts, active: &[Id]) -> MintMetadata {
+        let mut metadata = MintMetadata {
+            mint_info: MintInfo {
+                nuts,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        metadata.status.etag = mint_info_etag(&metadata.mint_info);
+        metadata.active_keysets = active
+            .iter()
+            .map(|id| {
+                Arc::new(KeySetInfo {
+                    id: *id,
+                    unit: CurrencyUnit::Sat,
+                    active: true,
+                    input_fee_ppk: 0,
+                    final_expiry: None,
+                })
+            })
+            .collect();
+        metadata
+    }
+
+    #[test]
+    fn test_mint_info_etag_ignores_time() {
+        let mint_info = MintInfo::default();
+        let mut later = mint_info.clone();
+        later.time = Some(1_700_000_000);
+
+        assert_eq!(mint_info_etag(&mint_info), mint_info_etag(&later));
+        assert_ne!(
+            mint_info_etag(&mint_info),
+            mint_info_etag(&mint_info.clone().motd("hello"))
+        );
+    }
+
+    #[test]
+    fn test_diff_mint_metadata() {
+        let old_keyset = Id::from_bytes(&[0, 0, 0, 0, 0, 0, 0, 1]).unwrap();
+        let new_keyset = Id::from_bytes(&[0, 0, 0, 0, 0, 0, 0, 2]).unwrap();
+
+        let previous = metadata(Nuts::default().nut07(true), &[old_keyset]);
+        assert!(diff_mint_metadata(&previous, &previous).is_empty());
+
+        let mut nuts = Nuts::default().nut12(true);
+        nuts.nut21 = Some(ClearAuthSettings::new(
+            "https://auth.example.com".to_string(),
+            "cashu-client".to_string(),
+            vec![ProtectedEndpoint::new(Method::Post, RoutePath::Swap)],
+        ));
+        let current = metadata(nuts, &[new_keyset]);
+
+        assert_eq!(
+            diff_mint_metadata(&previous, &current),
+            vec![
+                MintInfoChange::NutAdded(12),
+                MintInfoChange::NutAdded(21),
+                MintInfoChange::NutRemoved(7),
+                MintInfoChange::AuthRequired(ProtectedEndpoint::new(Method::Post, RoutePath::Swap)),
+                MintInfoChange::KeysetRotated {
+                    unit: CurrencyUnit::Sat,
+                    previous: vec![old_keyset],
+                    active: vec![new_keyset],
+                },
+            ]
+        );
+    }
+}
@@ -35,11 +35,13 @@ use cdk_common::mint_url::MintUrl;
 use cdk_common::nuts::{KeySetInfo, Keys};
 use cdk_common::parking_lot::RwLock;
 use cdk_common::{CurrencyUnit, KeySet, MintInfo};
-use tokio::sync::Mutex;
+use tokio::sync::{broadcast, Mutex};
 use web_time::Instant;
 
 use crate::nuts::Id;
-use crate::wallet::{AuthMintConnector, AuthWallet, MintConnector};
+use crate::wallet::keys_cache::KeysCache;
+use crate::wallet::mint_info_changes::{diff_mint_metadata, mint_info_etag, MintInfoChange};
+use crate::wallet::{AuthMintConnector, AuthWallet, KeysetRefreshPolicy, MintConnector};
 use crate::{Error, Wallet};
 
 /// Metadata freshness and versioning information
@@ -54,6 +56,14 @@ pub struct FreshnessStatus {
     /// A future time when the cache would be considered as staled.
     pub updated_at: Instant,
 
+    /// Fingerprint of the last mint info fetched from the mint
+    ///
+    /// `None` if the mint info was loaded from the database only.
+    pub etag: Option<String>,
+
+    /// Unix time of the last successful fetch from the mint
+    pub last_fetched: Option<u64>,
+
     /// Monotonically increasing version number (for database sync tracking)
     version: usize,
 }
@@ -63,6 +73,8 @@ impl Default for FreshnessStatus {
         Self {
             is_populated: false,
             updated_at: Instant::now(),
+            etag: None,
+            last_fetched: None,
             version: 0,
         }
     }
@@ -131,6 +143,12 @@ pub struct MintMetadataCache {
     /// Mutex to ensure only one fetch operation runs at a time
     /// Other callers wait for the lock, then re-read the updated cache
     fetch_lock: Arc<Mutex<()>>,
+
+    /// Broadcasts capability changes detected when fetching from the mint
+    changes: broadcast::Sender<MintInfoChange>,
+
+    /// Verified keys shared with the caches of other mints and wallets
+    keys_cache: KeysCache,
 }
 
 impl std::fmt::Debug for MintMetadataCache {
@@ -154,6 +172,19 @@ impl Wallet {
         self.metadata_cache.set_ttl(ttl);
     }
 
+    /// Sets when the wallet refreshes keysets on its own.
+    ///
+    /// Shorthand for the matching metadata cache TTL, see
+    /// [`set_metadata_cache_ttl`](Self::set_metadata_cache_ttl).
+    pub fn set_keyset_refresh_policy(&self, policy: KeysetRefreshPolicy) {
+        self.metadata_cache.set_ttl(policy.cache_ttl());
+    }
+
+    /// Get the current [`KeysetRefreshPolicy`]
+    pub fn keyset_refresh_policy(&self) -> KeysetRefreshPolicy {
+        KeysetRefreshPolicy::from_cache_ttl(self.metadata_cache.ttl())
+    }
+
     /// Get information about metadata cache info
     pub fn get_metadata_cache_info(&self) -> FreshnessStatus {
         self.metadata_cache.metadata.load().status.clone()
@@ -198,9 +229,22 @@ impl MintMetadataCache {
             ttl: Arc::new(RwLock::new(Some(Duration::from_secs(3600)))),
             db_sync_versions: Arc::new(Default::default()),
             fetch_lock: Arc::new(Mutex::new(())),
+            changes: broadcast::channel(32).0,
+            keys_cache: KeysCache::global().clone(),
         }
     }
 
+    /// Use `keys_cache` instead of the process-wide [`KeysCache::global`]
+    pub fn with_keys_cache(mut self, keys_cache: KeysCache) -> Self {
+        self.keys_cache = keys_cache;
+        self
+    }
+
+    /// Subscribe to capability changes detected when fetching from the mint
+    pub fn subscribe_changes(&self) -> broadcast::Receiver<MintInfoChange> {
+        self.changes.subscribe()
+    }
+
     /// Set the TTL for cached metadata.
     ///
     /// `None` means cached data never expires.
@@ -352,9 +396,31 @@ impl MintMetadataCache {
                     new_metadata.active_keysets.push(keyset_arc);
                 }
 
-                if let Some(keys) = storage.get_keys(&keyset_info.id).await? {
+                if let Some(keys) = self.keys_cache.get(&keyset_info.id) {
+                    new_metadata.keys.insert(keyset_info.id, keys);
+                } else if let Some(keys) = storage.get_keys(&keyset_info.id).await? {
                     tracing::trace!("Loaded keys for keyset {} from database", keyset_info.id);
-                    new_metadata.keys.insert(keyset_info.id, Arc::new(keys));
+
+                    // Stored keys are shared with every wallet through the cache, so they
+                    // are verified like keys fetched from the mint. Keys that do not match
+                    // are skipped and fetched from the mint again.
+                    if let Err(err) = keyset_info.id.verify_against_keys(
+                        &keys,
+                        &keyset_info.unit,
+                        keyset_info.input_fee_ppk,
+                        keyset_info.final_expiry,
+                    ) {
+                        tracing::warn!(
+                            "Stored keys do not match keyset {}: {}",
+                            keyset_info.id,
+                            err
+                        );
+                        continue;
+                    }
+
+                    let keys = Arc::new(keys);
+                    self.keys_cache.insert(keyset_info.id, keys.clone());
+                    new_metadata.keys.insert(keyset_info.id, keys);
                 }
             }
         }
@@ -668,7 +734,8 @@ impl MintMetadataCache {
         tracing::debug!("Fetching mint metadata from HTTP for {}", self.mint_url);
 
         // Start with current cache to preserve data from other sources
-        let mut new_metadata = (*self.metadata.load().clone()).clone();
+        let previous_metadata = self.metadata.load().clone();
+        let mut new_metadata = (*previous_metadata).clone();
         let mut keysets_to_fetch = Vec::new();
 
         // Fetch regular mint data
@@ -718,6 +785,11 @@ impl MintMetadataCache {
             if let std::collections::hash_map::Entry::Vacant(e) =
                 new_metadata.keys.entry(keyset_info.id)
             {
+                if let Some(keys) = self.keys_cache.get(&keyset_info.id) {
+                    e.insert(keys);
+                    continue;
+                }
+
                 let keyset = if keyset_info.unit == CurrencyUnit::Auth {
                     auth_client
                         .as_ref()
@@ -732,10 +804,19 @@ impl MintMetadataCache {
                         .await?
                 };
 
-                // Verify the keyset ID matches the keys
-                keyset.verify_id()?;
+                // Reject mints whose keyset ids are not derived from the keys they serve
+                if let Err(err) = keyset.verify_against_info(&keyset_info) {
+                    tracing::warn!(
+                        "Mint {} served keys that do not match keyset {}",
+                        self.mint_url,
+                        keyset_info.id
+                    );
+                    return Err(err.into());
+                }
 
-                e.insert(Arc::new(keyset.keys));
+                let keys = Arc::new(keyset.keys);
+                self.keys_cache.insert(keyset_info.id, keys.clone());
+                e.insert(keys);
             }
         }
 
@@ -743,6 +824,8 @@ impl MintMetadataCache {
         if client.is_some() {
             new_metadata.status.is_populated = true;
             new_metadata.status.updated_at = Instant::now();
+            new_metadata.status.etag = mint_info_etag(&new_metadata.mint_info);
+            new_metadata.status.last_fetched = Some(crate::util::unix_time());
             new_metadata.status.version += 1;
         }
 
@@ -762,6 +845,15 @@ impl MintMetadataCache {
         // Atomically update cache
         let metadata_arc = Arc::new(new_metadata);
         self.metadata.store(metadata_arc.clone());
+
+        if client.is_some() && previous_metadata.status.is_populated {
+            for change in diff_mint_metadata(&previous_metadata, &metadata_arc) {
+                tracing::info!("Mint {} changed: {:?}", self.mint_url, change);
+                // No receivers is not an error
+                let _ = self.changes.send(change);
+            }
+        }
+
         Ok(metadata_arc)
     }
 
@@ -770,3 +862,40 @@ impl MintMetadataCache {
         &self.mint_url
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::wallet::test_utils::{create_test_db, make_inactive_keyset, test_mint_url};
+
+    #[tokio::test]
+    async fn test_load_from_db_skips_keys_not_matching_keyset_info() {
+        let db = create_test_db().await;
+        let mint_url = test_mint_url();
+        db.add_mint(mint_url.clone(), None).await.unwrap();
+
+        // The stored keys match the id only for the fee they were derived with
+        let keyset = make_inactive_keyset();
+        db.add_keys(keyset.clone()).await.unwrap();
+        db.add_mint_keysets(
+            mint_url.clone(),
+            vec![KeySetInfo {
+                id: keyset.id,
+                unit: keyset.unit.clone(),
+                active: true,
+                input_fee_ppk: keyset.input_fee_ppk + 1,
+                final_expiry: keyset.final_expiry,
+            }],
+        )
+        .await
+        .unwrap();
+
+        let keys_cache = KeysCache::new(4);
+        let cache = MintMetadataCache::new(mint_url).with_keys_cache(keys_cache.clone());
+        let metadata = cache.load_from_db(&db).await.unwrap();
+
+        assert!(metadata.keysets.contains_key(&keyset.id));
+        assert!(!metadata.keys.contains_key(&keyset.id));
+        assert_eq!(keys_cache.stats().entries, 0);
+    }
+}
@@ -1,6 +1,6 @@
 #![doc = include_str!("./README.md")]
 
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::fmt::Debug;
 use std::str::FromStr;
 use std::sync::Arc;
@@ -22,16 +22,16 @@ use tracing::instrument;
 use zeroize::Zeroize;
 
 use crate::amount::SplitTarget;
-use crate::dhke::construct_proofs;
 use crate::error::Error;
 use crate::fees::calculate_fee;
 use crate::mint_url::MintUrl;
 use crate::nuts::nut00::token::Token;
 use crate::nuts::nut17::Kind;
 use crate::nuts::{
-    nut10, CurrencyUnit, Id, Keys, MintInfo, MintQuoteState, PreMintSecrets, Proofs,
-    RestoreRequest, SpendingConditions, State,
+    nut10, CurrencyUnit, Id, KeySet, Keys, MintInfo, MintQuoteState, PreMintSecrets, Proofs,
+    RestoreRequest, RestoreResponse, SpendingConditions, State,
 };
+use crate::wallet::blind_signature::construct_proofs_parallel;
 use crate::wallet::mint_metadata_cache::MintMetadataCache;
 use crate::wallet::p2pk::{P2PK_ACCOUNT, P2PK_PURPOSE};
 use crate::{Amount, OidcClient};
@@ -44,11 +44,16 @@ mod nostr_backup;
 #[cfg(all(feature = "tor", not(target_arch = "wasm32")))]
 pub use mint_connector::{TorAuthHttpClient, TorHttpClient};
 mod balance;
+mod batch;
 mod builder;
+mod consolidation;
+mod integrity;
 mod issue;
+mod keys_cache;
 mod keysets;
 mod melt;
 mod mint_connector;
+mod mint_info_changes;
 mod mint_metadata_cache;
 #[cfg(feature = "npubcash")]
 mod npubcash;
@@ -56,7 +61,10 @@ mod npubcash;
 pub mod nwc;
 mod p2pk;
 pub mod payment_request;
+mod privacy;
 mod proofs;
+mod quote_expiry;
+mod rebalance;
 mod receive;
 mod reclaim;
 mod recovery;
@@ -66,6 +74,7 @@ mod send;
 mod streams;
 pub mod subscription;
 mod swap;
+#[cfg(any(test, feature = "test-utils"))]
 pub mod test_utils;
 mod transactions;
 pub mod util;
@@ -73,6 +82,8 @@ pub mod wallet_repository;
 mod wallet_trait;
 
 pub use auth::{AuthMintConnector, AuthWallet};
+pub use balance::BalanceBreakdown;
+pub use batch::{BatchOutcome, BatchResult};
 #[cfg(all(feature = "bip353", not(target_arch = "wasm32")))]
 pub use bip321::resolve_bip353_payment_instruction;
 pub use bip321::{
@@ -81,14 +92,25 @@ pub use bip321::{
 pub use builder::WalletBuilder;
 pub use cdk_common::wallet as types;
 pub use cdk_common::wallet::{
-    NUT13Options, P2PKLockedProofSendMode, ReceiveOptions, SendMemo, SendOptions,
+    KeysetRefreshPolicy, MintTrustDecision, MintTrustPolicy, NUT13Options, P2PKLockedProofSendMode,
+    ReceiveOptions, SendFeePolicy, SendMemo, SendOptions,
+};
+pub use consolidation::ConsolidationPolicy;
+pub use integrity::{IntegrityReport, InvalidProof, ProofIssue, INTEGRITY_KV_NAMESPACE};
+pub use keys_cache::{KeysCache, KeysCacheStats, DEFAULT_KEYS_CACHE_CAPACITY};
+pub use melt::{
+    MeltConfirmOptions, MeltOutcome, MeltProbe, MeltQuoteOptions, PendingMelt, PreparedMelt,
 };
-pub use melt::{MeltConfirmOptions, MeltOutcome, PendingMelt, PreparedMelt};
 pub use mint_connector::transport::Transport as HttpTransport;
+#[cfg(feature = "mint")]
+pub use mint_connector::DirectMintConnector;
 pub use mint_connector::{
     AuthHttpClient, HttpClient, LnurlPayInvoiceResponse, LnurlPayResponse, MintConnector,
 };
-pub use mint_metadata_cache::MintMetadata;
+#[cfg(feature = "lnurl")]
+pub use mint_connector::{LnurlStatusResponse, LnurlWithdrawResponse};
+pub use mint_info_changes::MintInfoChange;
+pub use mint_metadata_cache::{FreshnessStatus, MintMetadata};
 #[cfg(feature = "nostr")]
 pub use nostr_backup::{BackupOptions, BackupResult, RestoreOptions, RestoreResult};
 #[cfg(feature = "npubcash")]
@@ -98,10 +120,18 @@ pub use nwc::{derive_nwc_secret_key_from_seed, WalletNwcHandler};
 pub use payment_request::CreateRequestParams;
 #[cfg(feature = "nostr")]
 pub use payment_request::NostrWaitInfo;
+pub use privacy::{
+    LinkabilityRisk, PrivacyAction, PrivacyReport, ProofPrivacy, LARGE_DENOMINATION,
+};
+pub use quote_expiry::PurgedQuotes;
+pub use rebalance::{RebalanceBand, RebalanceStrategy, RebalanceTransfer};
+pub use receive::MintTransfer;
 pub use recovery::RecoveryReport;
+pub use saga::StartedSagas;
 pub use send::PreparedSend;
 #[cfg(all(feature = "npubcash", not(target_arch = "wasm32")))]
 pub use streams::npubcash::NpubCashProofStream;
+pub use transactions::TransactionExportFormat;
 pub use types::{MeltQuote, MintQuote, SendKind};
 pub use wallet_repository::{TokenData, WalletConfig, WalletRepository, WalletRepositoryBuilder};
 
@@ -399,6 +429,92 @@ impl Wallet {
         Ok(())
     }
 
+    /// Move the wallet to a new URL of the same mint, e.g. after the mint changed domains
+    ///
+    /// Connects to `new_mint_url` with the transport of the wallet's client. See
+    /// [`Self::migrate_mint_url_with_client`].
+    #[instrument(skip(self))]
+    pub async fn migrate_mint_url(
+        &mut self,
+        old_mint_url: MintUrl,
+        new_mint_url: MintUrl,
+    ) -> Result<(), Error> {
+        let client = self.client.mint_connector(new_mint_url.clone());
+        client
+            .set_auth_wallet(self.auth_wallet.read().await.clone())
+            .await;
+
+        self.migrate_mint_url_with_client(old_mint_url, new_mint_url, client)
+            .await
+    }
+
+    /// Move the wallet to a new URL of the same mint using `client` to reach it
+    ///
+    /// The mint at `new_mint_url` must serve every keyset id stored for `old_mint_url`, otherwise
+    /// nothing is changed and [`Error::KeysetUnknown`] is returned. Quotes, proofs and
+    /// transactions are then moved to the new URL and the keysets of the new URL are fetched and
+    /// verified.
+    #[instrument(skip(self, client))]
+    pub async fn migrate_mint_url_with_client(
+        &mut self,
+        old_mint_url: MintUrl,
+        new_mint_url: MintUrl,
+        client: Arc<dyn MintConnector + Send + Sync>,
+    ) -> Result<(), Error> {
+        if old_mint_url != self.mint_url {
+            return Err(Error::IncorrectMint);
+        }
+
+        if old_mint_url == new_mint_url {
+            return Ok(());
+        }
+
+        let served_keyset_ids: HashSet<Id> = client
+            .get_mint_keysets()
+            .await?
+            .keysets
+            .into_iter()
+            .map(|keyset| keyset.id)
+            .collect();
+
+        let known_keysets = self
+            .localstore
+            .get_mint_keysets(old_mint_url.clone())
+            .await?
+            .unwrap_or_default();
+
+        if let Some(missing) = known_keysets
+            .iter()
+            .find(|keyset| !served_keyset_ids.contains(&keyset.id))
+        {
+            tracing::warn!(
+                "Refusing to migrate {} to {}: keyset {} is not served by the new URL",
+                old_mint_url,
+                new_mint_url,
+                missing.id
+            );
+            return Err(Error::KeysetUnknown(missing.id));
+        }
+
+        self.localstore
+            .update_mint_url(old_mint_url, new_mint_url.clone())
+            .await?;
+
+        let metadata_cache = Arc::new(MintMetadataCache::new(new_mint_url.clone()));
+        metadata_cache.set_ttl(self.metadata_cache.ttl());
+
+        self.mint_url = new_mint_url;
+        self.metadata_cache = metadata_cache;
+        self.client = client;
+
+        // Fetch the keys of the new URL, verifying them against their keyset ids
+        self.metadata_cache
+            .load_from_mint(&self.localstore, &self.client)
+            .await?;
+
+        Ok(())
+    }
+
     /// Query mint for current mint information
     #[instrument(skip(self))]
     pub async fn fetch_mint_info(&self) -> Result<Option<MintInfo>, Error> {
@@ -583,9 +699,41 @@ impl Wallet {
     #[instrument(skip(self))]
     pub async fn restore_with_opts(&self, opts: NUT13Options) -> Result<Restored, Error> {
         let opts = NUT13Options::new(opts.batch_size, opts.max_gap)?;
-        let batch_size = opts.batch_size;
-        let max_gap = opts.max_gap;
 
+        let mut restored_result = Restored::default();
+
+        for keyset in self.restore_keysets().await? {
+            let restored = self.restore_keyset(&keyset, &opts).await?;
+            restored_result.spent += restored.spent;
+            restored_result.unspent += restored.unspent;
+            restored_result.pending += restored.pending;
+        }
+
+        Ok(restored_result)
+    }
+
+    /// Restore proofs from the mint keyset by keyset
+    ///
+    /// A keyset that fails to restore does not stop the others from being restored.
+    #[instrument(skip(self))]
+    pub async fn restore_batch(
+        &self,
+        opts: NUT13Options,
+    ) -> Result<BatchResult<Restored, Id>, Error> {
+        let opts = NUT13Options::new(opts.batch_size, opts.max_gap)?;
+
+        let mut results = BatchResult::default();
+
+        for keyset in self.restore_keysets().await? {
+            let restored = self.restore_keyset(&keyset, &opts).await;
+            results.push(keyset.id, restored.into());
+        }
+
+        Ok(results)
+    }
+
+    /// Keysets to restore proofs of
+    async fn restore_keysets(&self) -> Result<Vec<KeySet>, Error> {
         // Check that mint is in store of mints
         if self
             .localstore
@@ -596,154 +744,225 @@ impl Wallet {
             self.fetch_mint_info().await?;
         }
 
-        let keysets = self.keysets(Default::default()).await?;
-
-        let mut restored_result = Restored::default();
-
-        for keyset in keysets {
-            let keys = self.keyset(keyset.id).await?.keys;
-            let mut empty_batch: u32 = 0;
-            let mut start_counter: u32 = 0;
-            // Track the highest counter value that had a signature
-            let mut highest_counter: Option<u32> = None;
+        self.keysets(Default::default()).await
+    }
 
-            while empty_batch < max_gap {
-                let batch_end = start_counter.saturating_add(batch_size);
-                let premint_secrets =
-                    PreMintSecrets::restore_batch(keyset.id, &self.seed, start_counter, batch_end)?;
+    /// Post a restore request, split into pages the mint accepts
+    ///
+    /// Mints advertise the maximum number of outputs per restore request in their NUT-09
+    /// settings. Larger requests are sent in pages of at most that many outputs, any
+    /// `next_cursor` the mint returns is followed, and the responses are merged in order.
+    pub(crate) async fn post_restore_paged(
+        &self,
+        request: RestoreRequest,
+    ) -> Result<RestoreResponse, Error> {
+        if request.outputs.is_empty() {
+            return self.client.post_restore(request).await;
+        }
 
+        let page_size = match self.load_mint_info().await?.nuts.nut09.max_outputs {
+            Some(max) if max > 0 && max < request.outputs.len() => {
                 tracing::debug!(
-                    "Attempting to restore counter {}-{} for mint {} keyset {}",
-                    start_counter,
-                    batch_end,
+                    "Mint {} accepts {} outputs per restore request, paging",
                     self.mint_url,
-                    keyset.id
+                    max
                 );
+                max
+            }
+            _ => request.outputs.len(),
+        };
 
-                let restore_request = RestoreRequest {
-                    outputs: premint_secrets.blinded_messages(),
-                };
-
-                let response = self.client.post_restore(restore_request).await?;
+        let mut response = RestoreResponse {
+            outputs: Vec::new(),
+            signatures: Vec::new(),
+            next_cursor: None,
+        };
 
-                if response.signatures.is_empty() {
-                    empty_batch += 1;
-                    start_counter = start_counter.saturating_add(batch_size);
-                    continue;
-                }
+        for page in request.outputs.chunks(page_size) {
+            let mut cursor = None;
 
-                // Build a map from blinded_secret to signature for O(1) lookup
-                // This ensures we match signatures to secrets correctly regardless of response order
-                let signature_map: HashMap<_, _> = response
-                    .outputs
-                    .iter()
-                    .zip(response.signatures.iter())
-                    .map(|(output, sig)| (output.blinded_secret, sig.clone()))
-                    .collect();
-
-                // Enumerate secrets to track their original index (which corresponds to counter value)
-                // and match signatures by blinded_secret to ensure correct pairing
-                let matched_secrets: Vec<_> = premint_secrets
-                    .secrets
-                    .iter()
-                    .enumerate()
-                    .filter_map(|(idx, p)| {
-                        signature_map
-                            .get(&p.blinded_message.blinded_secret)
-                            .map(|sig| (idx, p, sig.clone()))
+            loop {
+                let page_response = self
+                    .client
+                    .post_restore(RestoreRequest {
+                        outputs: page.to_vec(),
+                        cursor,
                     })
-                    .collect();
+                    .await?;
+                response.outputs.extend(page_response.outputs);
+                response.signatures.extend(page_response.signatures);
 
-                // Update highest counter based on matched indices
-                if let Some(&(max_idx, _, _)) = matched_secrets.last() {
-                    let counter_value = start_counter + max_idx as u32;
-                    highest_counter =
-                        Some(highest_counter.map_or(counter_value, |c| c.max(counter_value)));
+                match page_response.next_cursor {
+                    Some(next) if next > cursor.unwrap_or(0) && next < page.len() => {
+                        cursor = Some(next)
+                    }
+                    Some(next) => {
+                        return Err(Error::InvalidMintResponse(format!(
+                            "Restore cursor {next} does not advance within {} outputs",
+                            page.len()
+                        )))
+                    }
+                    None => break,
                 }
+            }
+        }
 
-                // the response outputs and premint secrets should be the same after filtering
-                // blinded messages the mint did not have signatures for
-                if response.outputs.len() != matched_secrets.len() {
-                    return Err(Error::InvalidMintResponse(format!(
-                        "restore response outputs ({}) does not match premint secrets ({})",
-                        response.outputs.len(),
-                        matched_secrets.len()
-                    )));
-                }
+        Ok(response)
+    }
 
-                // Extract signatures, rs, and secrets in matching order
-                // Each tuple (idx, premint, signature) ensures correct pairing
-                let proofs = construct_proofs(
-                    matched_secrets
-                        .iter()
-                        .map(|(_, _, sig)| sig.clone())
-                        .collect(),
-                    matched_secrets
-                        .iter()
-                        .map(|(_, p, _)| p.r.clone())
-                        .collect(),
-                    matched_secrets
-                        .iter()
-                        .map(|(_, p, _)| p.secret.clone())
-                        .collect(),
-                    &keys,
-                )?;
+    /// Restore the proofs of a single keyset
+    async fn restore_keyset(
+        &self,
+        keyset: &KeySet,
+        opts: &NUT13Options,
+    ) -> Result<Restored, Error> {
+        let batch_size = opts.batch_size;
+        let max_gap = opts.max_gap;
+        let mut restored_result = Restored::default();
 
-                tracing::debug!("Restored {} proofs", proofs.len());
+        let keys = self.keyset(keyset.id).await?.keys;
+        let mut empty_batch: u32 = 0;
+        let mut start_counter: u32 = 0;
+        // Track the highest counter value that had a signature
+        let mut highest_counter: Option<u32> = None;
+
+        while empty_batch < max_gap {
+            let batch_end = start_counter.saturating_add(batch_size);
+            let premint_secrets =
+                PreMintSecrets::restore_batch(keyset.id, &self.seed, start_counter, batch_end)?;
+
+            tracing::debug!(
+                "Attempting to restore counter {}-{} for mint {} keyset {}",
+                start_counter,
+                batch_end,
+                self.mint_url,
+                keyset.id
+            );
 
-                let states = self.check_proofs_spent(proofs.clone()).await?;
+            let restore_request = RestoreRequest::new(premint_secrets.blinded_messages());
 
-                let (unspent_proofs, updated_restored) = proofs
-                    .into_iter()
-                    .zip(states)
-                    .filter_map(|(p, state)| {
-                        ProofInfo::new(p, self.mint_url.clone(), state.state, keyset.unit.clone())
-                            .ok()
-                    })
-                    .try_fold(
-                        (Vec::new(), restored_result),
-                        |(mut proofs, mut restored_result), proof_info| {
-                            match proof_info.state {
-                                State::Spent => {
-                                    restored_result.spent += proof_info.proof.amount;
-                                }
-                                State::Unspent =>  {
-                                    restored_result.unspent += proof_info.proof.amount;
-                                    proofs.push(proof_info);
-                                }
-                                State::Pending => {
-                                    restored_result.pending += proof_info.proof.amount;
-                                    proofs.push(proof_info);
-                                }
-                                _ => {
-                                    unreachable!("These states are unknown to the mint and cannot be returned")
-                                }
-                            }
-                            Ok::<(Vec<ProofInfo>, Restored), Error>((proofs, restored_result))
-                        },
-                    )?;
+            let response = self.post_restore_paged(restore_request).await?;
 
-                restored_result = updated_restored;
+            if response.signatures.is_empty() {
+                empty_batch += 1;
+                start_counter = start_counter.saturating_add(batch_size);
+                continue;
+            }
 
-                self.localstore
-                    .update_proofs(unspent_proofs, vec![])
-                    .await?;
+            // Build a map from blinded_secret to signature for O(1) lookup
+            // This ensures we match signatures to secrets correctly regardless of response order
+            let signature_map: HashMap<_, _> = response
+                .outputs
+                .iter()
+                .zip(response.signatures.iter())
+                .map(|(output, sig)| (output.blinded_secret, sig.clone()))
+                .collect();
+
+            // Enumerate secrets to track their original index (which corresponds to counter value)
+            // and match signatures by blinded_secret to ensure correct pairing
+            let matched_secrets: Vec<_> = premint_secrets
+                .secrets
+                .iter()
+                .enumerate()
+                .filter_map(|(idx, p)| {
+                    signature_map
+                        .get(&p.blinded_message.blinded_secret)
+                        .map(|sig| (idx, p, sig.clone()))
+                })
+                .collect();
 
-                empty_batch = 0;
-                start_counter = start_counter.saturating_add(batch_size);
+            // Update highest counter based on matched indices
+            if let Some(&(max_idx, _, _)) = matched_secrets.last() {
+                let counter_value = start_counter + max_idx as u32;
+                highest_counter =
+                    Some(highest_counter.map_or(counter_value, |c| c.max(counter_value)));
             }
 
-            if let Some(highest) = highest_counter {
-                self.localstore
-                    .increment_keyset_counter(&keyset.id, highest + 1)
-                    .await?;
-                tracing::debug!(
-                    "Set keyset {} counter to {} after restore",
-                    keyset.id,
-                    highest + 1
-                );
+            // the response outputs and premint secrets should be the same after filtering
+            // blinded messages the mint did not have signatures for
+            if response.outputs.len() != matched_secrets.len() {
+                return Err(Error::InvalidMintResponse(format!(
+                    "restore response outputs ({}) does not match premint secrets ({})",
+                    response.outputs.len(),
+                    matched_secrets.len()
+                )));
             }
+
+            // Extract signatures, rs, and secrets in matching order
+            // Each tuple (idx, premint, signature) ensures correct pairing
+            let proofs = construct_proofs_parallel(
+                matched_secrets
+                    .iter()
+                    .map(|(_, _, sig)| sig.clone())
+                    .collect(),
+                matched_secrets
+                    .iter()
+                    .map(|(_, p, _)| p.r.clone())
+                    .collect(),
+                matched_secrets
+                    .iter()
+                    .map(|(_, p, _)| p.secret.clone())
+                    .collect(),
+                &keys,
+            )
+            .await?;
+
+            tracing::debug!("Restored {} proofs", proofs.len());
+
+            let states = self.check_proofs_spent(proofs.clone()).await?;
+
+            let (unspent_proofs, updated_restored) = proofs
+                .into_iter()
+                .zip(states)
+                .filter_map(|(p, state)| {
+                    ProofInfo::new(p, self.mint_url.clone(), state.state, keyset.unit.clone()).ok()
+                })
+                .try_fold(
+                    (Vec::new(), restored_result),
+                    |(mut proofs, mut restored_result), proof_info| {
+                        match proof_info.state {
+                            State::Spent => {
+                                restored_result.spent += proof_info.proof.amount;
+                            }
+                            State::Unspent => {
+                                restored_result.unspent += proof_info.proof.amount;
+                                proofs.push(proof_info);
+                            }
+                            State::Pending => {
+                                restored_result.pending += proof_info.proof.amount;
+                                proofs.push(proof_info);
+                            }
+                            _ => {
+                                unreachable!(
+                                    "These states are unknown to the mint and cannot be returned"
+                                )
+                            }
+                        }
+                        Ok::<(Vec<ProofInfo>, Restored), Error>((proofs, restored_result))
+                    },
+                )?;
+
+            restored_result = updated_restored;
+
+            self.localstore
+                .update_proofs(unspent_proofs, vec![])
+                .await?;
+
+            empty_batch = 0;
+            start_counter = start_counter.saturating_add(batch_size);
+        }
+
+        if let Some(highest) = highest_counter {
+            self.localstore
+                .increment_keyset_counter(&keyset.id, highest + 1)
+                .await?;
+            tracing::debug!(
+                "Set keyset {} counter to {} after restore",
+                keyset.id,
+                highest + 1
+            );
         }
+
         Ok(restored_result)
     }
 
@@ -1487,4 +1706,151 @@ mod tests {
         assert_eq!(*keysets_calls.lock().expect("lock"), 1);
         assert_eq!(*keyset_calls.lock().expect("lock"), 1);
     }
+
+    #[tokio::test]
+    async fn migrate_mint_url_requires_same_keysets() {
+        use crate::wallet::test_utils::{
+            create_test_db, create_test_wallet_with_mock, make_inactive_keyset, test_keyset_id,
+            test_mint_url, test_proof_info, MockMintConnector,
+        };
+
+        let db = create_test_db().await;
+        let old_mint_url = test_mint_url();
+        let new_mint_url = MintUrl::from_str("https://new-mint.example.com").unwrap();
+
+        let mock = Arc::new(MockMintConnector::new());
+        let mut wallet = create_test_wallet_with_mock(db.clone(), mock.clone()).await;
+        wallet.fetch_mint_info().await.unwrap();
+        db.update_proofs(
+            vec![test_proof_info(test_keyset_id(), 8, old_mint_url.clone())],
+            vec![],
+        )
+        .await
+        .unwrap();
+
+        // A mint that does not serve the stored keysets is rejected
+        let other_mint = Arc::new(MockMintConnector::new());
+        other_mint.set_active_keyset(make_inactive_keyset());
+        let err = wallet
+            .migrate_mint_url_with_client(old_mint_url.clone(), new_mint_url.clone(), other_mint)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, Error::KeysetUnknown(id) if id == test_keyset_id()));
+        assert_eq!(wallet.mint_url, old_mint_url);
+
+        // The new URL is reached through the wallet's own connector
+        mock.set_other_mint_connector(Arc::new(MockMintConnector::new()));
+        wallet
+            .migrate_mint_url(old_mint_url.clone(), new_mint_url.clone())
+            .await
+            .unwrap();
+
+        assert_eq!(wallet.mint_url, new_mint_url);
+        let proofs = db
+            .get_proofs(Some(new_mint_url.clone()), None, None, None)
+            .await
+            .unwrap();
+        assert_eq!(proofs.len(), 1);
+        assert!(db
+            .get_proofs(Some(old_mint_url), None, None, None)
+            .await
+            .unwrap()
+            .is_empty());
+        assert!(db.get_mint_keysets(new_mint_url).await.unwrap().is_some());
+    }
+
+    #[tokio::test]
+    async fn restore_requests_are_paged_to_the_mint_limit() {
+        use crate::wallet::test_utils::{
+            create_test_db, create_test_wallet_with_mock, test_keyset_id, MockMintConnector,
+        };
+
+        let mock = Arc::new(MockMintConnector::new());
+        let mut mint_info = mock.mint_info.lock().unwrap().clone();
+        mint_info.nuts.nut09.max_outputs = Some(2);
+        mock.set_mint_info_response(Ok(mint_info));
+        let wallet = create_test_wallet_with_mock(create_test_db().await, mock.clone()).await;
+
+        let premint_secrets =
+            PreMintSecrets::restore_batch(test_keyset_id(), &wallet.seed, 0, 5).unwrap();
+        let outputs = premint_secrets.blinded_messages();
+
+        for page in outputs.chunks(2) {
+            mock.push_restore_response(Ok(RestoreResponse {
+                outputs: page[..1].to_vec(),
+                signatures: Vec::new(),
+                next_cursor: None,
+            }));
+        }
+
+        let response = wallet
+            .post_restore_paged(RestoreRequest::new(outputs.clone()))
+            .await
+            .unwrap();
+
+        let page_sizes: Vec<usize> = mock
+            .captured_restore_requests()
+            .iter()
+            .map(|request| request.outputs.len())
+            .collect();
+        assert_eq!(page_sizes, vec![2, 2, 1]);
+        assert_eq!(
+            response.outputs,
+            vec![outputs[0].clone(), outputs[2].clone(), outputs[4].clone()]
+        );
+    }
+
+    #[tokio::test]
+    async fn restore_follows_the_mint_cursor() {
+        use crate::wallet::test_utils::{
+            create_test_db, create_test_wallet_with_mock, test_keyset_id, MockMintConnector,
+        };
+
+        let mock = Arc::new(MockMintConnector::new());
+        let wallet = create_test_wallet_with_mock(create_test_db().await, mock.clone()).await;
+
+        let premint_secrets =
+            PreMintSecrets::restore_batch(test_keyset_id(), &wallet.seed, 0, 4).unwrap();
+        let outputs = premint_secrets.blinded_messages();
+
+        mock.push_restore_response(Ok(RestoreResponse {
+            outputs: outputs[..1].to_vec(),
+            signatures: Vec::new(),
+            next_cursor: Some(3),
+        }));
+        mock.push_restore_response(Ok(RestoreResponse {
+            outputs: outputs[3..].to_vec(),
+            signatures: Vec::new(),
+            next_cursor: None,
+        }));
+
+        let response = wallet
+            .post_restore_paged(RestoreRequest::new(outputs.clone()))
+            .await
+            .unwrap();
+
+        let cursors: Vec<Option<usize>> = mock
+            .captured_restore_requests()
+            .iter()
+            .map(|request| request.cursor)
+            .collect();
+        assert_eq!(cursors, vec![None, Some(3)]);
+        assert_eq!(
+            response.outputs,
+            vec![outputs[0].clone(), outputs[3].clone()]
+        );
+
+        // A cursor that does not advance is rejected instead of looping
+        mock.push_restore_response(Ok(RestoreResponse {
+            outputs: Vec::new(),
+            signatures: Vec::new(),
+            next_cursor: Some(0),
+        }));
+        assert!(matches!(
+            wallet
+                .post_restore_paged(RestoreRequest::new(outputs.clone()))
+                .await,
+            Err(Error::InvalidMintResponse(_))
+        ));
+    }
 }
@@ -19,6 +19,9 @@
 //! are converted with a ×1000 factor; sub-satoshi millisat amounts that cannot
 //! be represented exactly are rejected rather than silently rounded. Only `Sat`
 //! and `Msat` wallets are supported.
+//!
+//! Amountless invoices are paid with the `amount` of the `pay_invoice` request,
+//! which requires the mint to support amountless bolt11 melts.
 
 use std::collections::HashMap;
 use std::str::FromStr;
@@ -41,7 +44,7 @@ use nostr_sdk::Timestamp;
 use tracing::instrument;
 
 use crate::error::Error;
-use crate::nuts::{CurrencyUnit, SecretKey};
+use crate::nuts::{CurrencyUnit, MeltOptions, SecretKey};
 use crate::{amount, Amount, Wallet};
 
 /// Derive the NWC wallet-service secret key from a wallet seed.
@@ -409,29 +412,30 @@ impl cdk_nwc::NwcRequestHandler for WalletNwcHandler {
         let invoice = Bolt11Invoice::from_str(&request.invoice)
             .map_err(|e| nip47_err(ErrorCode::Other, format!("invalid bolt11 invoice: {e}")))?;
 
-        // The invoice must carry its own amount: paying amountless invoices
-        // would require an amount override, which is not supported here.
-        let invoice_msat = invoice.amount_milli_satoshis().ok_or_else(|| {
-            nip47_err(
-                ErrorCode::Other,
-                "amountless invoices are not supported; invoice must specify an amount",
-            )
-        })?;
-
-        // A redundant `amount` is accepted only when it matches the invoice;
-        // a mismatch is rejected rather than silently paying a different sum.
-        if let Some(requested) = request.amount {
-            if requested != invoice_msat {
+        // Amountless invoices are paid with the requested `amount` (NUT-05 amountless
+        // melt, if the mint supports it). A redundant `amount` is accepted only when it
+        // matches the invoice; a mismatch is rejected rather than silently paying a
+        // different sum.
+        let (amount_msat, options) = match (invoice.amount_milli_satoshis(), request.amount) {
+            (Some(invoice_msat), Some(requested)) if requested != invoice_msat => {
                 return Err(nip47_err(
                     ErrorCode::Other,
                     "requested amount does not match the invoice amount",
                 ));
             }
-        }
+            (Some(invoice_msat), _) => (invoice_msat, None),
+            (None, Some(requested)) => (requested, Some(MeltOptions::new_amountless(requested))),
+            (None, None) => {
+                return Err(nip47_err(
+                    ErrorCode::Other,
+                    "amountless invoices require an amount",
+                ));
+            }
+        };
 
         // Per-payment cap enforcement (defense in depth, before any state changes).
         if let Some(max_payment_msat) = self.max_payment_msat {
-            if invoice_msat > max_payment_msat {
+            if amount_msat > max_payment_msat {
                 return Err(nip47_err(
                     ErrorCode::QuotaExceeded,
                     "payment exceeds max_payment_msat",
@@ -444,7 +448,7 @@ impl cdk_nwc::NwcRequestHandler for WalletNwcHandler {
             .melt_quote(
                 PaymentMethod::Known(KnownMethod::Bolt11),
                 request.invoice.clone(),
-                None,
+                options,
                 None,
             )
             .await
@@ -879,6 +883,7 @@ mod tests {
                 payment_proof: None,
                 estimated_blocks: None,
                 fee_index: None,
+                options: None,
                 payment_method: PaymentMethod::Known(KnownMethod::Bolt11),
                 used_by_operation: None,
                 version: 0,
@@ -993,6 +998,7 @@ mod tests {
                 payment_proof: None,
                 estimated_blocks: None,
                 fee_index: None,
+                options: None,
                 payment_method: PaymentMethod::Known(KnownMethod::Bolt11),
                 used_by_operation: None,
                 version: 0,
@@ -1258,6 +1264,63 @@ mod tests {
         assert_eq!(err.code, ErrorCode::QuotaExceeded);
     }
 
+    #[tokio::test]
+    async fn pay_invoice_amountless_invoice_uses_requested_amount() {
+        use bitcoin::hashes::sha256::Hash as Sha256Hash;
+        use bitcoin::hashes::Hash;
+        use bitcoin::secp256k1::{Secp256k1, SecretKey as Secp256k1SecretKey};
+        use lightning_invoice::{Currency, InvoiceBuilder, PaymentSecret};
+
+        let private_key = Secp256k1SecretKey::from_slice(&[42; 32]).expect("valid key");
+        let amountless = InvoiceBuilder::new(Currency::Bitcoin)
+            .description("nwc".to_string())
+            .payment_hash(Sha256Hash::hash(b"amountless"))
+            .payment_secret(PaymentSecret([21; 32]))
+            .current_timestamp()
+            .min_final_cltv_expiry_delta(144)
+            .build_signed(|hash| Secp256k1::new().sign_ecdsa_recoverable(hash, &private_key))
+            .expect("amountless invoice")
+            .to_string();
+
+        let localstore = Arc::new(cdk_sqlite::wallet::memory::empty().await.expect("db"));
+        let wallet = Wallet::new(
+            "https://mint.example.com",
+            CurrencyUnit::Sat,
+            localstore,
+            [0x42; 64],
+            None,
+        )
+        .expect("wallet");
+
+        let handler = WalletNwcHandler::new(Arc::new(wallet), Some(1_000));
+
+        let err = cdk_nwc::NwcRequestHandler::pay_invoice(
+            &handler,
+            PayInvoiceRequest {
+                id: None,
+                invoice: amountless.clone(),
+                amount: None,
+            },
+        )
+        .await
+        .expect_err("amountless invoice without amount should be rejected");
+        assert_eq!(err.code, ErrorCode::Other);
+        assert!(err.message.contains("require an amount"));
+
+        // The requested amount is subject to the payment cap
+        let err = cdk_nwc::NwcRequestHandler::pay_invoice(
+            &handler,
+            PayInvoiceRequest {
+                id: None,
+                invoice: amountless,
+                amount: Some(2_000),
+            },
+        )
+        .await
+        .expect_err("over-cap amount should be rejected");
+        assert_eq!(err.code, ErrorCode::QuotaExceeded);
+    }
+
     #[tokio::test]
     async fn lookup_invoice_returns_not_found_for_unknown_hash() {
         let localstore = Arc::new(cdk_sqlite::wallet::memory::empty().await.expect("db"));
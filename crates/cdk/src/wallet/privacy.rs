@@ -0,0 +1,379 @@
+//! Denomination privacy report
+//!
+//! The mint signs the same denominations for all of its users, so a proof is only as private as
+//! the number of proofs of its keyset and amount the mint issued to others. Proofs of old
+//! keysets, rarely issued denominations or without a DLEQ proof stand out when they are spent.
+//! [`Wallet::privacy_report`] scores the unspent proofs of the wallet by how linkable they are
+//! and recommends swaps reducing the risk, as a basis for warnings in wallet UIs.
+//!
+//! Mints do not publish how many proofs of each keyset and amount they issued or redeemed, so
+//! the anonymity set is not measured. The report approximates it with heuristics: inactive or
+//! expiring keysets and denominations from [`LARGE_DENOMINATION`] up are assumed to have few
+//! proofs in circulation.
+
+use std::collections::{BTreeMap, HashMap};
+
+use tracing::instrument;
+
+use super::consolidation::ConsolidationPolicy;
+use crate::nuts::{Id, KeySetInfo, Keys, PublicKey, State};
+use crate::util::unix_time;
+use crate::{Amount, Error, Wallet};
+
+/// Denominations from this amount up are assumed to be rarely issued by the mint
+///
+/// A fixed heuristic, not derived from the mint: about 1 BTC in sats, and far beyond typical
+/// payments in other units.
+pub const LARGE_DENOMINATION: u64 = 1 << 20;
+
+/// Keysets expiring within this many seconds are reported as expiring
+const EXPIRING_KEYSET_WINDOW: u64 = 7 * 24 * 60 * 60;
+
+/// Why a proof may be linked to the wallet when it is spent
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum LinkabilityRisk {
+    /// The proof carries spending conditions, revealing e.g. a public key when spent
+    SpendingConditions,
+    /// The denomination is at least [`LARGE_DENOMINATION`], few such proofs are in circulation
+    LargeDenomination,
+    /// The keyset reaches its final expiry within a week
+    ExpiringKeyset,
+    /// The proof has no DLEQ proof, so the wallet can not rule out a tagged signature
+    MissingDleq,
+    /// The keyset is no longer active, few users still hold its proofs
+    InactiveKeyset,
+    /// The keyset has no key for the amount of the proof
+    NonStandardDenomination,
+    /// The keyset is not known to the wallet
+    UnknownKeyset,
+}
+
+impl LinkabilityRisk {
+    /// Weight of the risk in the [`PrivacyReport::risk_score`], from 0 to 100
+    pub fn weight(&self) -> u8 {
+        match self {
+            Self::SpendingConditions => 20,
+            Self::LargeDenomination => 30,
+            Self::ExpiringKeyset => 40,
+            Self::MissingDleq => 50,
+            Self::InactiveKeyset => 60,
+            Self::NonStandardDenomination => 80,
+            Self::UnknownKeyset => 100,
+        }
+    }
+
+    /// Whether swapping the proof for fresh proofs of the active keyset removes the risk
+    fn is_fixed_by_refresh(&self) -> bool {
+        matches!(
+            self,
+            Self::ExpiringKeyset
+                | Self::MissingDleq
+                | Self::InactiveKeyset
+                | Self::NonStandardDenomination
+        )
+    }
+}
+
+/// An unspent proof with at least one [`LinkabilityRisk`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProofPrivacy {
+    /// Y of the proof
+    pub y: PublicKey,
+    /// Amount of the proof
+    pub amount: Amount,
+    /// Keyset of the proof
+    pub keyset_id: Id,
+    /// Risks of the proof, most severe last
+    pub risks: Vec<LinkabilityRisk>,
+}
+
+/// Swap recommended by [`Wallet::privacy_report`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PrivacyAction {
+    /// Swap proofs of inactive or expiring keysets, non-standard denominations or without a
+    /// DLEQ proof for fresh proofs of the active keyset
+    Refresh {
+        /// Ys of the proofs to swap
+        ys: Vec<PublicKey>,
+        /// Total of the proofs
+        amount: Amount,
+    },
+    /// Split proofs of large denominations into smaller, more common ones
+    SplitLargeDenominations {
+        /// Ys of the proofs to split
+        ys: Vec<PublicKey>,
+        /// Total of the proofs
+        amount: Amount,
+    },
+    /// Consolidate denominations held by many proofs, as spending them together links them
+    Consolidate {
+        /// Number of proofs to consolidate
+        proofs: usize,
+    },
+}
+
+/// Findings of [`Wallet::privacy_report`]
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PrivacyReport {
+    /// Total of the unspent proofs
+    pub total: Amount,
+    /// Number of unspent proofs held in each denomination
+    pub denominations: BTreeMap<Amount, usize>,
+    /// Unspent proofs with a linkability risk
+    pub proofs: Vec<ProofPrivacy>,
+    /// Linkability risk of the balance from 0 (none found) to 100
+    ///
+    /// The amount-weighted average of the weight of the most severe risk of every proof.
+    pub risk_score: u8,
+    /// Swaps reducing the risk
+    pub recommendations: Vec<PrivacyAction>,
+}
+
+impl Wallet {
+    /// Analyze the unspent proofs of this wallet for linkability risks
+    ///
+    /// Only the local store is read; keysets are checked as last fetched from the mint. The
+    /// mint does not publish how many proofs of each keyset and amount it issued, so the risks
+    /// are heuristics: denominations from [`LARGE_DENOMINATION`] up and inactive or expiring
+    /// keysets are assumed to have a small anonymity set.
+    #[instrument(skip(self))]
+    pub async fn privacy_report(&self) -> Result<PrivacyReport, Error> {
+        let proofs = self
+            .localstore
+            .get_proofs(
+                Some(self.mint_url.clone()),
+                Some(self.unit.clone()),
+                Some(vec![State::Unspent]),
+                None,
+            )
+            .await?;
+
+        let keysets: HashMap<Id, KeySetInfo> = self
+            .localstore
+            .get_mint_keysets(self.mint_url.clone())
+            .await?
+            .unwrap_or_default()
+            .into_iter()
+            .map(|keyset| (keyset.id, keyset))
+            .collect();
+
+        let now = unix_time();
+        let mut report = PrivacyReport::default();
+        let mut keys: HashMap<Id, Option<Keys>> = HashMap::new();
+        let mut weighted_risk: u128 = 0;
+
+        for proof_info in &proofs {
+            let proof = &proof_info.proof;
+            report.total = report.total.try_add(proof.amount)?;
+            *report.denominations.entry(proof.amount).or_default() += 1;
+
+            if !keys.contains_key(&proof.keyset_id) {
+                keys.insert(
+                    proof.keyset_id,
+                    self.localstore.get_keys(&proof.keyset_id).await?,
+                );
+            }
+
+            let mut risks = Vec::new();
+            match (keysets.get(&proof.keyset_id), &keys[&proof.keyset_id]) {
+                (Some(keyset), Some(keyset_keys)) => {
+                    if !keyset.active {
+                        risks.push(LinkabilityRisk::InactiveKeyset);
+                    }
+                    if keyset
+                        .final_expiry
+                        .is_some_and(|expiry| expiry <= now.saturating_add(EXPIRING_KEYSET_WINDOW))
+                    {
+                        risks.push(LinkabilityRisk::ExpiringKeyset);
+                    }
+                    if keyset_keys.amount_key(proof.amount).is_none() {
+                        risks.push(LinkabilityRisk::NonStandardDenomination);
+                    }
+                }
+                _ => risks.push(LinkabilityRisk::UnknownKeyset),
+            }
+            if proof.dleq.is_none() {
+                risks.push(LinkabilityRisk::MissingDleq);
+            }
+            if proof.amount >= Amount::from(LARGE_DENOMINATION) {
+                risks.push(LinkabilityRisk::LargeDenomination);
+            }
+            if proof_info.spending_condition.is_some() {
+                risks.push(LinkabilityRisk::SpendingConditions);
+            }
+
+            if risks.is_empty() {
+                continue;
+            }
+
+            risks.sort();
+            if let Some(worst) = risks.last() {
+                weighted_risk += u128::from(proof.amount.to_u64()) * u128::from(worst.weight());
+            }
+
+            report.proofs.push(ProofPrivacy {
+                y: proof_info.y,
+                amount: proof.amount,
+                keyset_id: proof.keyset_id,
+                risks,
+            });
+        }
+
+        if report.total > Amount::ZERO {
+            // Weights are at most 100, so the score is too
+            report.risk_score = (weighted_risk / u128::from(report.total.to_u64())) as u8;
+        }
+
+        report.recommendations = recommendations(
+            &report,
+            ConsolidationPolicy::default().max_proofs_per_amount(),
+        )?;
+
+        Ok(report)
+    }
+}
+
+/// Swaps removing the risks found in `report`
+fn recommendations(
+    report: &PrivacyReport,
+    max_proofs_per_amount: usize,
+) -> Result<Vec<PrivacyAction>, Error> {
+    let mut actions = Vec::new();
+
+    let (refresh, split): (Vec<_>, Vec<_>) = report
+        .proofs
+        .iter()
+        .filter(|proof| {
+            proof.risks.iter().any(|risk| {
+                risk.is_fixed_by_refresh() || *risk == LinkabilityRisk::LargeDenomination
+            })
+        })
+        .partition(|proof| proof.risks.iter().any(LinkabilityRisk::is_fixed_by_refresh));
+
+    if !refresh.is_empty() {
+        actions.push(PrivacyAction::Refresh {
+            ys: refresh.iter().map(|proof| proof.y).collect(),
+            amount: Amount::try_sum(refresh.iter().map(|proof| proof.amount))?,
+        });
+    }
+
+    if !split.is_empty() {
+        actions.push(PrivacyAction::SplitLargeDenominations {
+            ys: split.iter().map(|proof| proof.y).collect(),
+            amount: Amount::try_sum(split.iter().map(|proof| proof.amount))?,
+        });
+    }
+
+    let crowded: usize = report
+        .denominations
+        .values()
+        .filter(|count| **count > max_proofs_per_amount)
+        .sum();
+    if crowded > 0 {
+        actions.push(PrivacyAction::Consolidate { proofs: crowded });
+    }
+
+    Ok(actions)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::wallet::test_utils;
+
+    #[tokio::test]
+    async fn privacy_report_scores_linkable_proofs() {
+        let db = test_utils::create_test_db().await;
+        let wallet = test_utils::create_test_wallet(db.clone()).await;
+        let mint_url = test_utils::test_mint_url();
+        let active = test_utils::test_keyset();
+        let inactive = test_utils::make_inactive_keyset();
+
+        db.add_mint(mint_url.clone(), None).await.unwrap();
+        let info = |keyset: &crate::nuts::KeySet| KeySetInfo {
+            id: keyset.id,
+            unit: keyset.unit.clone(),
+            active: keyset.active.unwrap_or(true),
+            input_fee_ppk: keyset.input_fee_ppk,
+            final_expiry: keyset.final_expiry,
+        };
+        db.add_mint_keysets(mint_url.clone(), vec![info(&active), info(&inactive)])
+            .await
+            .unwrap();
+        db.add_keys(active.clone()).await.unwrap();
+        db.add_keys(inactive.clone()).await.unwrap();
+
+        let old = test_utils::test_proof_info(inactive.id, 2, mint_url.clone());
+        let odd = test_utils::test_proof_info(active.id, 3, mint_url.clone());
+        let plain = test_utils::test_proof_info(active.id, 1, mint_url.clone());
+        db.update_proofs(vec![old.clone(), odd.clone(), plain.clone()], vec![])
+            .await
+            .unwrap();
+
+        let report = wallet.privacy_report().await.unwrap();
+
+        assert_eq!(report.total, Amount::from(6));
+        assert_eq!(report.denominations.len(), 3);
+        assert_eq!(report.proofs.len(), 3);
+
+        let risks_of = |y: PublicKey| {
+            report
+                .proofs
+                .iter()
+                .find(|proof| proof.y == y)
+                .map(|proof| proof.risks.clone())
+                .unwrap()
+        };
+        assert_eq!(
+            risks_of(old.y),
+            vec![
+                LinkabilityRisk::MissingDleq,
+                LinkabilityRisk::InactiveKeyset
+            ]
+        );
+        assert_eq!(
+            risks_of(odd.y),
+            vec![
+                LinkabilityRisk::MissingDleq,
+                LinkabilityRisk::NonStandardDenomination
+            ]
+        );
+        assert_eq!(risks_of(plain.y), vec![LinkabilityRisk::MissingDleq]);
+
+        // (2 * 60 + 3 * 80 + 1 * 50) / 6
+        assert_eq!(report.risk_score, 68);
+        assert!(matches!(
+            report.recommendations.as_slice(),
+            [PrivacyAction::Refresh { ys, amount }] if ys.len() == 3 && *amount == Amount::from(6)
+        ));
+    }
+
+    #[test]
+    fn large_denominations_are_split() {
+        let report = PrivacyReport {
+            total: Amount::from(LARGE_DENOMINATION),
+            denominations: BTreeMap::from([(Amount::from(LARGE_DENOMINATION), 1)]),
+            proofs: vec![ProofPrivacy {
+                y: crate::nuts::SecretKey::generate().public_key(),
+                amount: Amount::from(LARGE_DENOMINATION),
+                keyset_id: test_utils::test_keyset_id(),
+                risks: vec![LinkabilityRisk::LargeDenomination],
+            }],
+            risk_score: 30,
+            recommendations: Vec::new(),
+        };
+
+        assert!(matches!(
+            recommendations(&report, 10).unwrap().as_slice(),
+            [PrivacyAction::SplitLargeDenominations { amount, .. }]
+                if *amount == Amount::from(LARGE_DENOMINATION)
+        ));
+        assert!(matches!(
+            recommendations(&report, 0).unwrap().as_slice(),
+            [
+                PrivacyAction::SplitLargeDenominations { .. },
+                PrivacyAction::Consolidate { proofs: 1 }
+            ]
+        ));
+    }
+}
@@ -79,7 +79,7 @@ impl Wallet {
     pub async fn check_proofs_spent(&self, proofs: Proofs) -> Result<Vec<ProofState>, Error> {
         let spendable = self
             .client
-            .post_check_state(CheckStateRequest { ys: proofs.ys()? })
+            .post_check_state(CheckStateRequest::new(proofs.ys()?))
             .await?;
 
         let spent_ys: Vec<_> = spendable
@@ -310,7 +310,11 @@ impl Wallet {
 
         // Select proofs with the optimal amounts (only split once, not per keyset)
         if let Some(fee_and_amounts) = fee_and_amounts {
-            for optimal_amount in amount.split(fee_and_amounts)? {
+            // Amounts the keyset cannot represent are covered by the closest proofs below
+            let optimal_amounts = amount
+                .split(fee_and_amounts)
+                .unwrap_or_else(|_| vec![amount]);
+            for optimal_amount in optimal_amounts {
                 if !select_proof(&proofs, optimal_amount, true) {
                     // Add the remaining amount to the remaining amounts because proof with the optimal amount was not found
                     remaining_amounts.push(optimal_amount);
@@ -339,43 +343,33 @@ impl Wallet {
             }
         }
 
-        // Select proofs with the remaining amounts by checking for 2 of the half amount, 4 of the quarter amount, etc.
+        // Select proofs with the remaining amounts by filling them with the next smaller
+        // denominations, e.g. 2 of the half amount, 4 of the quarter amount, etc. for powers of two
         tracing::debug!("Selecting proofs with the remaining amounts");
+        let denominations: Vec<Amount> = fee_and_amounts
+            .map(|fee_and_amounts| {
+                fee_and_amounts
+                    .amounts()
+                    .iter()
+                    .rev()
+                    .map(|amount| Amount::from(*amount))
+                    .collect()
+            })
+            .unwrap_or_default();
         for remaining_amount in remaining_amounts {
-            // Number of proofs to select
-            let mut n = 2;
-
-            let mut target_amount = remaining_amount;
-            let mut found = false;
-            while let Some(curr_amount) = target_amount.checked_div(Amount::from(2)) {
-                if curr_amount == Amount::ZERO {
-                    break;
+            let mut needed = remaining_amount;
+            for denomination in denominations.iter().filter(|d| **d < remaining_amount) {
+                while needed >= *denomination && select_proof(&proofs, *denomination, true) {
+                    needed = needed.saturating_sub(*denomination);
                 }
 
-                // Select proofs with the current amount
-                let mut count = 0;
-                for _ in 0..n {
-                    if select_proof(&proofs, curr_amount, true) {
-                        count += 1;
-                    } else {
-                        break;
-                    }
-                }
-                n -= count;
-
-                // All proofs with the current amount are selected
-                if n == 0 {
-                    found = true;
+                if needed == Amount::ZERO {
                     break;
                 }
-
-                // Try to find double the number of the next amount
-                n *= 2;
-                target_amount = curr_amount;
             }
 
             // Find closest amount over the remaining amount
-            if !found {
+            if needed != Amount::ZERO {
                 select_proof(&proofs, remaining_amount, false);
             }
         }
@@ -491,7 +485,7 @@ impl Wallet {
         loop {
             let fee = calculate_fee(&selected_proofs.count_by_keyset(), &keyset_fees)?.total;
             let total = selected_proofs.total_amount()?;
-            let net_amount = total - fee;
+            let net_amount = total.checked_sub(fee).ok_or(Error::InsufficientFunds)?;
 
             tracing::debug!(
                 "Net amount={}, fee={}, total amount={}",
@@ -515,7 +509,7 @@ impl Wallet {
                 return Err(Error::InsufficientFunds);
             }
 
-            let shortfall = amount - net_amount;
+            let shortfall = amount.try_sub(net_amount)?;
             tracing::debug!("Net amount is less than required, shortfall={}", shortfall);
 
             let additional = Wallet::select_proofs(
@@ -702,6 +696,28 @@ mod tests {
             .for_each(|proof| assert_eq!(proof.amount, Amount::ONE));
     }
 
+    #[test]
+    fn test_select_proofs_one_two_five_keyset() {
+        let active_id = id();
+        let mut fee_and_keyset_amounts = HashMap::new();
+        fee_and_keyset_amounts.insert(active_id, (0, vec![1, 2, 5, 10, 20, 50]).into());
+
+        // 15 splits into 10 + 5, the missing 5 is filled with 2 + 2 + 1
+        let proofs = vec![proof(50), proof(10), proof(2), proof(2), proof(1)];
+        let selected_proofs = Wallet::select_proofs(
+            15.into(),
+            proofs,
+            &vec![active_id],
+            &fee_and_keyset_amounts,
+            false,
+        )
+        .unwrap();
+
+        let mut amounts: Vec<u64> = selected_proofs.iter().map(|p| p.amount.into()).collect();
+        amounts.sort();
+        assert_eq!(amounts, vec![1, 2, 2, 10]);
+    }
+
     #[test]
     fn test_select_proof_change() {
         let active_id = id();
@@ -772,6 +788,22 @@ mod tests {
         assert_eq!(selected_proofs[0].amount, 32.into());
     }
 
+    #[test]
+    fn test_select_proofs_include_fees_larger_than_proofs() {
+        let mut keyset_fee_and_amounts = HashMap::new();
+        keyset_fee_and_amounts.insert(id(), (3000, (0..32).map(|x| 2u64.pow(x)).collect()).into());
+
+        // Every proof costs more in fees than it is worth
+        let result = Wallet::select_proofs(
+            2.into(),
+            vec![proof(1), proof(1), proof(1)],
+            &vec![id()],
+            &keyset_fee_and_amounts,
+            true,
+        );
+        assert!(matches!(result, Err(crate::Error::InsufficientFunds)));
+    }
+
     #[test]
     fn test_select_proofs_include_fees_accounts_for_additional_proof_fees() {
         use cdk_common::nuts::nut00::ProofsMethods;
@@ -0,0 +1,265 @@
+//! Cleanup of expired quotes
+//!
+//! Quotes that expired before being paid can never be used again. Long-lived wallets remove them
+//! so they do not pile up in the local store.
+
+use std::time::Duration;
+
+use tracing::instrument;
+
+use super::issue::apply_mint_quote_response;
+use crate::nuts::{MeltQuoteState, MintQuoteState};
+use crate::util::unix_time;
+use crate::wallet::{MeltQuote, MintQuote};
+use crate::{Amount, Error, Wallet};
+
+/// Quotes removed by [`Wallet::purge_expired_quotes`]
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PurgedQuotes {
+    /// Ids of the removed mint quotes
+    pub mint_quotes: Vec<String>,
+    /// Ids of the removed melt quotes
+    pub melt_quotes: Vec<String>,
+}
+
+impl PurgedQuotes {
+    /// Whether no quote was removed
+    pub fn is_empty(&self) -> bool {
+        self.mint_quotes.is_empty() && self.melt_quotes.is_empty()
+    }
+}
+
+impl Wallet {
+    /// Remove unpaid quotes of this wallet that have expired
+    pub async fn purge_expired_quotes(&self) -> Result<PurgedQuotes, Error> {
+        self.purge_quotes_expired_for(Duration::ZERO).await
+    }
+
+    /// Remove unpaid quotes of this wallet that expired more than `grace` ago
+    ///
+    /// Quotes that received a payment or are reserved by an operation are kept. Each candidate is
+    /// checked with the mint first, since a payment can arrive after the wallet last looked; a
+    /// quote the mint reports as paid or issued is kept and its cached state updated. Quotes whose
+    /// state cannot be confirmed, e.g. because the mint is unreachable, are kept for the next purge.
+    #[instrument(skip(self))]
+    pub async fn purge_quotes_expired_for(&self, grace: Duration) -> Result<PurgedQuotes, Error> {
+        let cutoff = unix_time().saturating_sub(grace.as_secs());
+        let expired = |expiry: u64| expiry > 0 && expiry < cutoff;
+
+        let mut purged = PurgedQuotes::default();
+
+        for mut quote in self.localstore.get_mint_quotes().await? {
+            if quote.mint_url != self.mint_url
+                || quote.unit != self.unit
+                || quote.state != MintQuoteState::Unpaid
+                || quote.amount_paid > Amount::ZERO
+                || quote.used_by_operation.is_some()
+                || !expired(quote.expiry)
+            {
+                continue;
+            }
+
+            match self.mint_quote_unpaid(&mut quote).await {
+                Ok(true) => {}
+                Ok(false) => {
+                    tracing::info!("Expired mint quote {} was paid, keeping it", quote.id);
+                    self.localstore.add_mint_quote(quote).await?;
+                    continue;
+                }
+                Err(err) => {
+                    tracing::warn!("Could not check expired mint quote {}: {}", quote.id, err);
+                    continue;
+                }
+            }
+
+            self.localstore.remove_mint_quote(&quote.id).await?;
+            tracing::info!("Removed expired mint quote {}", quote.id);
+            purged.mint_quotes.push(quote.id);
+        }
+
+        for quote in self.localstore.get_melt_quotes().await? {
+            if quote.mint_url.as_ref() != Some(&self.mint_url)
+                || quote.unit != self.unit
+                || quote.state != MeltQuoteState::Unpaid
+                || quote.used_by_operation.is_some()
+                || !expired(quote.expiry)
+            {
+                continue;
+            }
+
+            match self.melt_quote_unpaid(&quote).await {
+                Ok(true) => {}
+                Ok(false) => {
+                    tracing::info!("Expired melt quote {} is not unpaid, keeping it", quote.id);
+                    continue;
+                }
+                Err(err) => {
+                    tracing::warn!("Could not check expired melt quote {}: {}", quote.id, err);
+                    continue;
+                }
+            }
+
+            self.localstore.remove_melt_quote(&quote.id).await?;
+            tracing::info!("Removed expired melt quote {}", quote.id);
+            purged.melt_quotes.push(quote.id);
+        }
+
+        Ok(purged)
+    }
+
+    /// Whether the mint still reports `quote` as unpaid, applying its current state to `quote`
+    async fn mint_quote_unpaid(&self, quote: &mut MintQuote) -> Result<bool, Error> {
+        match self
+            .client
+            .get_mint_quote_status(quote.payment_method.clone(), &quote.id)
+            .await
+        {
+            Ok(response) => {
+                apply_mint_quote_response(quote, &response);
+                Ok(quote.state == MintQuoteState::Unpaid
+                    && quote.amount_paid == Amount::ZERO
+                    && quote.amount_issued == Amount::ZERO)
+            }
+            // The mint no longer knows the quote, so nothing can be paid against it
+            Err(Error::UnknownQuote) => Ok(true),
+            Err(err) => Err(err),
+        }
+    }
+
+    /// Whether the mint still reports `quote` as unpaid
+    async fn melt_quote_unpaid(&self, quote: &MeltQuote) -> Result<bool, Error> {
+        match self
+            .client
+            .get_melt_quote_status(quote.payment_method.clone(), &quote.id)
+            .await
+        {
+            Ok(response) => Ok(response.state() == MeltQuoteState::Unpaid),
+            Err(Error::UnknownQuote) => Ok(true),
+            Err(err) => Err(err),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use super::*;
+    use crate::nuts::{MeltQuoteBolt11Response, MintQuoteBolt11Response, PaymentMethod};
+    use crate::wallet::test_utils::{self, MockMintConnector};
+    use crate::MintQuoteResponse;
+
+    fn melt_status(quote: &MeltQuote, state: MeltQuoteState) -> MeltQuoteBolt11Response<String> {
+        MeltQuoteBolt11Response {
+            quote: quote.id.clone(),
+            amount: quote.amount,
+            fee_reserve: quote.fee_reserve,
+            state,
+            expiry: quote.expiry,
+            payment_preimage: None,
+            change: None,
+            request: None,
+            unit: None,
+            method: PaymentMethod::BOLT11,
+            change_policy: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn only_expired_unpaid_quotes_are_purged() {
+        let db = test_utils::create_test_db().await;
+        let connector = Arc::new(MockMintConnector::new());
+        let wallet = test_utils::create_test_wallet_with_mock(db.clone(), connector.clone()).await;
+        let mint_url = test_utils::test_mint_url();
+
+        let mut expired = test_utils::test_mint_quote(mint_url.clone());
+        expired.expiry = unix_time() - 3600;
+        let mut paid = expired.clone();
+        paid.id = "paid".to_string();
+        paid.state = MintQuoteState::Paid;
+        let active = test_utils::test_mint_quote(mint_url);
+        let mut expired_melt = test_utils::test_melt_quote();
+        expired_melt.expiry = unix_time() - 3600;
+
+        for quote in [expired.clone(), paid.clone(), active.clone()] {
+            db.add_mint_quote(quote).await.unwrap();
+        }
+        db.add_melt_quote(expired_melt.clone()).await.unwrap();
+
+        let purged = wallet
+            .purge_quotes_expired_for(Duration::from_secs(7200))
+            .await
+            .unwrap();
+        assert!(purged.is_empty());
+
+        // The mint is asked about every candidate before it is removed
+        connector.set_mint_quote_status_response(Ok(MintQuoteResponse::Bolt11(
+            MintQuoteBolt11Response {
+                quote: expired.id.clone(),
+                request: expired.request.clone(),
+                amount: expired.amount,
+                unit: Some(expired.unit.clone()),
+                method: PaymentMethod::BOLT11,
+                amount_paid: Amount::ZERO,
+                amount_issued: Amount::ZERO,
+                updated_at: 0,
+                state: MintQuoteState::Unpaid,
+                expiry: Some(expired.expiry),
+                pubkey: None,
+            },
+        )));
+        connector.push_melt_quote_status_response(Ok(melt_status(
+            &expired_melt,
+            MeltQuoteState::Unpaid,
+        )));
+
+        let purged = wallet.purge_expired_quotes().await.unwrap();
+        assert_eq!(purged.mint_quotes, vec![expired.id.clone()]);
+        assert_eq!(purged.melt_quotes, vec![expired_melt.id]);
+
+        assert!(db.get_mint_quote(&expired.id).await.unwrap().is_none());
+        assert!(db.get_mint_quote(&paid.id).await.unwrap().is_some());
+        assert!(db.get_mint_quote(&active.id).await.unwrap().is_some());
+    }
+
+    #[tokio::test]
+    async fn quotes_paid_after_expiry_are_kept() {
+        let db = test_utils::create_test_db().await;
+        let connector = Arc::new(MockMintConnector::new());
+        let wallet = test_utils::create_test_wallet_with_mock(db.clone(), connector.clone()).await;
+
+        let mut expired = test_utils::test_mint_quote(test_utils::test_mint_url());
+        expired.expiry = unix_time() - 3600;
+        db.add_mint_quote(expired.clone()).await.unwrap();
+        let mut expired_melt = test_utils::test_melt_quote();
+        expired_melt.expiry = unix_time() - 3600;
+        db.add_melt_quote(expired_melt.clone()).await.unwrap();
+
+        connector.push_melt_quote_status_response(Ok(melt_status(
+            &expired_melt,
+            MeltQuoteState::Pending,
+        )));
+        connector.set_mint_quote_status_response(Ok(MintQuoteResponse::Bolt11(
+            MintQuoteBolt11Response {
+                quote: expired.id.clone(),
+                request: expired.request.clone(),
+                amount: expired.amount,
+                unit: Some(expired.unit.clone()),
+                method: PaymentMethod::BOLT11,
+                amount_paid: Amount::ZERO,
+                amount_issued: Amount::ZERO,
+                updated_at: 0,
+                state: MintQuoteState::Paid,
+                expiry: Some(expired.expiry),
+                pubkey: None,
+            },
+        )));
+
+        let purged = wallet.purge_expired_quotes().await.unwrap();
+        assert!(purged.is_empty());
+
+        let kept = db.get_mint_quote(&expired.id).await.unwrap().unwrap();
+        assert_eq!(kept.state, MintQuoteState::Paid);
+        assert!(db.get_melt_quote(&expired_melt.id).await.unwrap().is_some());
+    }
+}
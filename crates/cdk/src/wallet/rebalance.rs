@@ -0,0 +1,423 @@
+//! Rebalancing funds between mints of a [`WalletRepository`]
+//!
+//! A [`RebalanceStrategy`] assigns each mint a band its balance should stay within. Mints above
+//! their band melt the surplus to pay a mint quote of a mint below its band.
+
+use std::collections::{BTreeMap, HashMap};
+
+use cdk_common::amount::SplitTarget;
+use cdk_common::wallet::{MeltQuote, MintQuote};
+use tracing::instrument;
+
+use super::util::quote_within_fee_reserve;
+use super::wallet_repository::WalletRepository;
+use super::{BatchOutcome, BatchResult, Error, Wallet};
+use crate::mint_url::MintUrl;
+use crate::nuts::{CurrencyUnit, PaymentMethod};
+use crate::Amount;
+
+/// Balance band of a mint
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RebalanceBand {
+    /// Funds are moved to the mint when its balance drops below `min`
+    pub min: Amount,
+    /// Funds are moved away from the mint when its balance exceeds `max`
+    pub max: Amount,
+}
+
+impl RebalanceBand {
+    /// Create new [`RebalanceBand`]
+    pub fn new(min: Amount, max: Amount) -> Self {
+        Self { min, max }
+    }
+}
+
+/// Policy for moving funds between mints over Lightning
+#[derive(Debug, Clone)]
+pub struct RebalanceStrategy {
+    unit: CurrencyUnit,
+    bands: BTreeMap<MintUrl, RebalanceBand>,
+    max_fee_ppk: Option<u64>,
+    min_transfer: Amount,
+}
+
+impl RebalanceStrategy {
+    /// Create a strategy for the wallets of `unit`
+    pub fn new(unit: CurrencyUnit) -> Self {
+        Self {
+            unit,
+            bands: BTreeMap::new(),
+            max_fee_ppk: None,
+            min_transfer: Amount::from(1),
+        }
+    }
+
+    /// Keep the balance at `mint_url` within `band`
+    ///
+    /// Mints without a band are left untouched.
+    pub fn with_band(mut self, mint_url: MintUrl, band: RebalanceBand) -> Self {
+        self.bands.insert(mint_url, band);
+        self
+    }
+
+    /// Skip transfers whose Lightning fee reserve exceeds `max_fee_ppk` parts per thousand
+    pub fn with_max_fee_ppk(mut self, max_fee_ppk: u64) -> Self {
+        self.max_fee_ppk = Some(max_fee_ppk);
+        self
+    }
+
+    /// Skip transfers smaller than `min_transfer`
+    pub fn with_min_transfer(mut self, min_transfer: Amount) -> Self {
+        self.min_transfer = min_transfer;
+        self
+    }
+
+    /// Transfers that bring `balances` back within their bands, ignoring fees
+    ///
+    /// The largest surpluses are paired with the largest deficits first. Surpluses and deficits
+    /// smaller than `min_transfer` are left in place, so they stay available to other pairings.
+    pub fn plan(&self, balances: &BTreeMap<MintUrl, Amount>) -> Vec<(MintUrl, MintUrl, Amount)> {
+        let mut surpluses = Vec::new();
+        let mut deficits = Vec::new();
+
+        for (mint_url, band) in &self.bands {
+            let balance = balances.get(mint_url).copied().unwrap_or(Amount::ZERO);
+
+            if balance > band.max {
+                surpluses.push((mint_url.clone(), balance - band.max));
+            } else if balance < band.min {
+                deficits.push((mint_url.clone(), band.min - balance));
+            }
+        }
+
+        surpluses.sort_by(|a, b| b.1.cmp(&a.1));
+        deficits.sort_by(|a, b| b.1.cmp(&a.1));
+
+        let mut transfers = Vec::new();
+        let mut surpluses = surpluses.into_iter().peekable();
+
+        for (to, mut needed) in deficits {
+            while needed > Amount::ZERO && needed >= self.min_transfer {
+                let Some((from, available)) = surpluses.peek_mut() else {
+                    return transfers;
+                };
+
+                if *available == Amount::ZERO || *available < self.min_transfer {
+                    surpluses.next();
+                    continue;
+                }
+
+                let amount = needed.min(*available);
+                transfers.push((from.clone(), to.clone(), amount));
+
+                needed -= amount;
+                *available -= amount;
+            }
+        }
+
+        transfers
+    }
+}
+
+/// Transfer of funds between two mints
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RebalanceTransfer {
+    /// Mint the funds are melted at
+    pub from: MintUrl,
+    /// Mint the funds are minted at
+    pub to: MintUrl,
+    /// Amount received at `to`
+    pub amount: Amount,
+    /// Lightning fee reserve when previewed, fee paid once executed
+    pub fee: Amount,
+}
+
+struct QuotedTransfer {
+    from: Wallet,
+    to: Wallet,
+    mint_quote: MintQuote,
+    melt_quote: MeltQuote,
+}
+
+impl WalletRepository {
+    /// Preview the transfers [`WalletRepository::rebalance`] would make
+    ///
+    /// Quotes are requested from the mints to estimate fees, but nothing is paid. Transfers are
+    /// keyed by their source and destination mint.
+    #[instrument(skip_all)]
+    pub async fn preview_rebalance(
+        &self,
+        strategy: &RebalanceStrategy,
+    ) -> Result<BatchResult<RebalanceTransfer, (MintUrl, MintUrl)>, Error> {
+        let mut results = BatchResult::default();
+
+        for (key, outcome) in self.quote_rebalance(strategy).await?.items {
+            let outcome = match outcome {
+                BatchOutcome::Success(quoted) => BatchOutcome::Success(RebalanceTransfer {
+                    from: quoted.from.mint_url,
+                    to: quoted.to.mint_url,
+                    amount: quoted.mint_quote.amount.unwrap_or_default(),
+                    fee: quoted.melt_quote.fee_reserve,
+                }),
+                BatchOutcome::Failed(err) => BatchOutcome::Failed(err),
+                BatchOutcome::Skipped(reason) => BatchOutcome::Skipped(reason),
+            };
+            results.push(key, outcome);
+        }
+
+        Ok(results)
+    }
+
+    /// Move funds between mints until their balances are within the bands of `strategy`
+    ///
+    /// Each transfer melts at the source to pay a mint quote of the destination. If minting fails
+    /// after the payment, the quote is left for [`WalletRepository::check_all_mint_quotes`]. A
+    /// transfer that cannot be quoted or melted is recorded as failed and the others still run.
+    #[instrument(skip_all)]
+    pub async fn rebalance(
+        &self,
+        strategy: &RebalanceStrategy,
+    ) -> Result<BatchResult<RebalanceTransfer, (MintUrl, MintUrl)>, Error> {
+        let mut results = BatchResult::default();
+
+        for (key, outcome) in self.quote_rebalance(strategy).await?.items {
+            let quoted = match outcome {
+                BatchOutcome::Success(quoted) => quoted,
+                BatchOutcome::Failed(err) => {
+                    results.push(key, BatchOutcome::Failed(err));
+                    continue;
+                }
+                BatchOutcome::Skipped(reason) => {
+                    results.push(key, BatchOutcome::Skipped(reason));
+                    continue;
+                }
+            };
+
+            let melted = match quoted
+                .from
+                .prepare_melt(&quoted.melt_quote.id, HashMap::new())
+                .await
+            {
+                Ok(prepared) => prepared.confirm().await,
+                Err(err) => Err(err),
+            };
+            let melted = match melted {
+                Ok(melted) => melted,
+                Err(err) => {
+                    tracing::warn!(
+                        "Could not melt rebalance quote {} at {}: {}",
+                        quoted.melt_quote.id,
+                        quoted.from.mint_url,
+                        err
+                    );
+                    results.push(key, BatchOutcome::Failed(err));
+                    continue;
+                }
+            };
+
+            if let Err(err) = quoted
+                .to
+                .check_mint_quote_status(&quoted.mint_quote.id)
+                .await
+            {
+                tracing::warn!(
+                    "Could not check rebalance quote {}: {}",
+                    quoted.mint_quote.id,
+                    err
+                );
+            } else if let Err(err) = quoted
+                .to
+                .mint(&quoted.mint_quote.id, SplitTarget::default(), None)
+                .await
+            {
+                tracing::warn!(
+                    "Could not mint rebalance quote {}: {}",
+                    quoted.mint_quote.id,
+                    err
+                );
+            }
+
+            results.push(
+                key,
+                BatchOutcome::Success(RebalanceTransfer {
+                    from: quoted.from.mint_url,
+                    to: quoted.to.mint_url,
+                    amount: melted.amount(),
+                    fee: melted.fee_paid(),
+                }),
+            );
+        }
+
+        Ok(results)
+    }
+
+    /// Request quotes for the transfers planned by `strategy`
+    ///
+    /// Only transfers that were quoted take funds from the source balance, so later transfers can
+    /// still use what a skipped or failed one left.
+    async fn quote_rebalance(
+        &self,
+        strategy: &RebalanceStrategy,
+    ) -> Result<BatchResult<QuotedTransfer, (MintUrl, MintUrl)>, Error> {
+        let mut wallets = BTreeMap::new();
+        let mut balances = BTreeMap::new();
+
+        for mint_url in strategy.bands.keys() {
+            let wallet = self.get_wallet(mint_url, &strategy.unit).await?;
+            balances.insert(mint_url.clone(), wallet.total_balance().await?);
+            wallets.insert(mint_url.clone(), wallet);
+        }
+
+        let mut results = BatchResult::default();
+
+        for (from, to, amount) in strategy.plan(&balances) {
+            let key = (from, to);
+            let (Some(from), Some(to)) = (wallets.get(&key.0), wallets.get(&key.1)) else {
+                continue;
+            };
+
+            let balance = balances.get(&from.mint_url).copied().unwrap_or_default();
+
+            // The fee reserve is paid by the source, so a transfer that does not fit is lowered
+            // once by the reserve
+            let quoted = quote_within_fee_reserve(
+                amount,
+                balance,
+                strategy.min_transfer,
+                |amount| async move {
+                    let mint_quote = to
+                        .mint_quote(PaymentMethod::BOLT11, Some(amount), None, None)
+                        .await?;
+                    let melt_quote = from
+                        .melt_quote(
+                            PaymentMethod::BOLT11,
+                            mint_quote.request.clone(),
+                            None,
+                            None,
+                        )
+                        .await?;
+
+                    Ok::<_, Error>((mint_quote, melt_quote))
+                },
+            )
+            .await;
+
+            let (mint_quote, melt_quote) = match quoted {
+                Ok(Some(quotes)) => quotes,
+                Ok(None) => {
+                    results.push(
+                        key,
+                        BatchOutcome::Skipped(
+                            "Balance does not cover the transfer and its fee reserve".to_string(),
+                        ),
+                    );
+                    continue;
+                }
+                Err(err) => {
+                    results.push(key, BatchOutcome::Failed(err));
+                    continue;
+                }
+            };
+
+            if let Some(max_fee_ppk) = strategy.max_fee_ppk {
+                if melt_quote.fee_reserve.to_u64().saturating_mul(1000)
+                    > melt_quote.amount.to_u64().saturating_mul(max_fee_ppk)
+                {
+                    tracing::info!(
+                        "Skipping transfer of {} from {} to {}: fee reserve {} too high",
+                        melt_quote.amount,
+                        from.mint_url,
+                        to.mint_url,
+                        melt_quote.fee_reserve
+                    );
+                    results.push(
+                        key,
+                        BatchOutcome::Skipped(format!(
+                            "Fee reserve {} exceeds the fee limit",
+                            melt_quote.fee_reserve
+                        )),
+                    );
+                    continue;
+                }
+            }
+
+            let required = melt_quote
+                .amount
+                .checked_add(melt_quote.fee_reserve)
+                .ok_or(Error::AmountOverflow)?;
+
+            balances.insert(from.mint_url.clone(), balance - required);
+            results.push(
+                key,
+                BatchOutcome::Success(QuotedTransfer {
+                    from: from.clone(),
+                    to: to.clone(),
+                    mint_quote,
+                    melt_quote,
+                }),
+            );
+        }
+
+        Ok(results)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use super::*;
+
+    fn mint_url(n: u8) -> MintUrl {
+        MintUrl::from_str(&format!("https://mint{n}.example.com")).unwrap()
+    }
+
+    #[test]
+    fn plan_moves_surplus_into_deficits() {
+        let band = RebalanceBand::new(Amount::from(100), Amount::from(500));
+        let strategy = RebalanceStrategy::new(CurrencyUnit::Sat)
+            .with_band(mint_url(1), band)
+            .with_band(mint_url(2), band)
+            .with_band(mint_url(3), band)
+            .with_min_transfer(Amount::from(10));
+
+        let balances = BTreeMap::from([
+            (mint_url(1), Amount::from(800)),
+            (mint_url(2), Amount::from(20)),
+            (mint_url(3), Amount::from(95)),
+        ]);
+
+        assert_eq!(
+            strategy.plan(&balances),
+            vec![(mint_url(1), mint_url(2), Amount::from(80))]
+        );
+    }
+
+    #[test]
+    fn plan_keeps_surplus_of_skipped_transfers() {
+        let band = RebalanceBand::new(Amount::from(100), Amount::from(500));
+        let strategy = RebalanceStrategy::new(CurrencyUnit::Sat)
+            .with_band(mint_url(1), band)
+            .with_band(mint_url(2), band)
+            .with_band(mint_url(3), band)
+            .with_band(mint_url(4), band)
+            .with_min_transfer(Amount::from(10));
+
+        // The 5 sat left of the deficit of mint 3 is too small to move and must not take
+        // surplus of mint 2 away from mint 4
+        let balances = BTreeMap::from([
+            (mint_url(1), Amount::from(550)),
+            (mint_url(2), Amount::from(525)),
+            (mint_url(3), Amount::from(45)),
+            (mint_url(4), Amount::from(75)),
+        ]);
+
+        assert_eq!(
+            strategy.plan(&balances),
+            vec![
+                (mint_url(1), mint_url(3), Amount::from(50)),
+                (mint_url(2), mint_url(4), Amount::from(25)),
+            ]
+        );
+    }
+}
@@ -10,9 +10,12 @@ use crate::nuts::{Proofs, Token};
 use crate::{ensure_cdk, Amount, Error, Wallet};
 
 pub(crate) mod saga;
+mod transfer;
 
+use cdk_common::wallet::MintTrustDecision;
 pub use cdk_common::wallet::ReceiveOptions;
 use saga::ReceiveSaga;
+pub use transfer::MintTransfer;
 
 impl Wallet {
     /// Receive proofs using the saga pattern
@@ -27,7 +30,7 @@ impl Wallet {
         memo: Option<String>,
         token: Option<String>,
     ) -> Result<Amount, Error> {
-        self.retry_on_inactive_keyset(|| async {
+        self.retry_on_stale_keyset(|| async {
             let saga = ReceiveSaga::new(self);
             let saga = saga
                 .prepare(proofs.clone(), opts.clone(), memo.clone(), token.clone())
@@ -74,6 +77,32 @@ impl Wallet {
 
         ensure_cdk!(unit == self.unit, Error::UnsupportedUnit);
 
+        if let Some(policy) = &opts.mint_trust_policy {
+            let token_mint = token.mint_url()?;
+            match policy.evaluate(&token_mint, token.value()?, token_mint == self.mint_url) {
+                MintTrustDecision::Accept => {}
+                MintTrustDecision::SwapToTrusted => {
+                    return Ok(self
+                        .receive_from_other_mint(encoded_token, opts)
+                        .await?
+                        .amount);
+                }
+                MintTrustDecision::Reject(reason) => {
+                    return Err(Error::UntrustedMint {
+                        mint_url: token_mint.to_string(),
+                        reason,
+                    });
+                }
+            }
+        }
+
+        if opts.transfer_from_other_mint && self.mint_url != token.mint_url()? {
+            return Ok(self
+                .receive_from_other_mint(encoded_token, opts)
+                .await?
+                .amount);
+        }
+
         let proofs = self.token_proofs(&token).await?;
 
         if let Token::TokenV3(token) = &token {
@@ -36,6 +36,7 @@ use std::collections::HashMap;
 use bitcoin::hashes::sha256::Hash as Sha256Hash;
 use bitcoin::hashes::Hash;
 use bitcoin::XOnlyPublicKey;
+use cdk_common::database::WalletDatabaseExt;
 use cdk_common::util::unix_time;
 use cdk_common::wallet::{
     OperationData, ProofInfo, ReceiveOperationData, ReceiveSagaState, Transaction,
@@ -46,13 +47,14 @@ use tracing::instrument;
 use self::compensation::RemovePendingProofs;
 use self::state::{Finalized, Initial, Prepared};
 use super::ReceiveOptions;
-use crate::dhke::construct_proofs;
 use crate::nuts::nut00::ProofsMethods;
 use crate::nuts::nut10::Kind;
 use crate::nuts::{Conditions, Proofs, PublicKey, SecretKey, SigFlag, State};
 use crate::util::hex;
+use crate::wallet::blind_signature::{construct_proofs_parallel, verify_proofs_dleq};
 use crate::wallet::saga::{
-    add_compensation, clear_compensations, execute_compensations, new_compensations, Compensations,
+    add_compensation, clear_compensations, execute_compensations, new_compensations,
+    record_started_saga, Compensations,
 };
 use crate::wallet::swap::ProofReservation;
 use crate::{Amount, Error, Wallet, SECP256K1};
@@ -138,19 +140,21 @@ impl<'a> ReceiveSaga<'a, Initial> {
             .map(|s| (s.x_only_public_key(&SECP256K1).0, s.clone()))
             .collect();
 
-        // Process each proof: verify DLEQ, handle P2PK/HTLC
-        for proof in &mut proofs {
-            // Verify that proof DLEQ is valid
-            if proof.dleq.is_some() {
-                let keys = self
-                    .wallet
-                    .keyset_with_policy(proof.keyset_id, keyset_policy)
-                    .await?
-                    .keys;
-                let key = keys.amount_key(proof.amount).ok_or(Error::AmountKey)?;
-                proof.verify_dleq(key)?;
-            }
+        // Verify that proof DLEQs are valid
+        let mut dleq_checks = Vec::new();
+        for proof in proofs.iter().filter(|proof| proof.dleq.is_some()) {
+            let keys = self
+                .wallet
+                .keyset_with_policy(proof.keyset_id, keyset_policy)
+                .await?
+                .keys;
+            let key = keys.amount_key(proof.amount).ok_or(Error::AmountKey)?;
+            dleq_checks.push((proof.clone(), key));
+        }
+        verify_proofs_dleq(dleq_checks).await?;
 
+        // Process each proof: handle P2PK/HTLC
+        for proof in &mut proofs {
             if let Ok(secret) =
                 <crate::secret::Secret as TryInto<crate::nuts::nut10::Secret>>::try_into(
                     proof.secret.clone(),
@@ -189,6 +193,9 @@ impl<'a> ReceiveSaga<'a, Initial> {
                         pubkeys.append(&mut refund_keys);
                     }
 
+                    // SIG_ALL proofs are signed on the swap request once its outputs exist
+                    let sig_all = conditions.sig_flag == SigFlag::SigAll;
+
                     for (i, pubkey) in pubkeys.iter().enumerate() {
                         let slot = match secret.kind() {
                             Kind::P2PK => i as u8,
@@ -204,6 +211,10 @@ impl<'a> ReceiveSaga<'a, Initial> {
                             }
                         }
 
+                        if sig_all {
+                            continue;
+                        }
+
                         if let Some(ephemeral_key) = proof.p2pk_e {
                             for signing_key in p2pk_signing_keys.values() {
                                 if let Ok(r) =
@@ -226,14 +237,15 @@ impl<'a> ReceiveSaga<'a, Initial> {
                         }
                     }
 
+                    if sig_all {
+                        _sig_flag = SigFlag::SigAll;
+                        continue;
+                    }
+
                     match secret.kind() {
                         Kind::P2PK => proof.verify_p2pk()?,
                         Kind::HTLC => proof.verify_htlc()?,
                     }
-
-                    if conditions.sig_flag.eq(&SigFlag::SigAll) {
-                        _sig_flag = SigFlag::SigAll;
-                    }
                 }
             }
         }
@@ -321,6 +333,7 @@ impl<'a> ReceiveSaga<'a, Prepared> {
         );
 
         self.wallet.localstore.add_saga(saga.clone()).await?;
+        record_started_saga(saga.id);
 
         add_compensation(
             &mut self.compensations,
@@ -349,16 +362,18 @@ impl<'a> ReceiveSaga<'a, Prepared> {
             )
             .await?;
 
-        // Determine if SigAll signing is needed
-        let sig_flag = self.determine_sig_flag()?;
-        if sig_flag == SigFlag::SigAll {
-            for blinded_message in pre_swap.swap_request.outputs_mut() {
-                for signing_key in self.state_data.p2pk_signing_keys.values() {
-                    // Sign the outputs of the swap using standard P2PK since output
-                    // P2BK requires ephemeral keys which is handled at creation.
-                    blinded_message.sign_p2pk(signing_key.to_owned().clone())?
-                }
-            }
+        // SIG_ALL inputs commit to the outputs, so they are signed once the request is built
+        let signing_keys: Vec<SecretKey> = self
+            .state_data
+            .p2pk_signing_keys
+            .values()
+            .cloned()
+            .collect();
+        if let Err(err) =
+            crate::wallet::util::sign_swap_sig_all(&mut pre_swap.swap_request, &signing_keys)
+        {
+            execute_compensations(&mut self.compensations).await?;
+            return Err(err);
         }
 
         // Get counter range for recovery (before the swap request is sent)
@@ -398,20 +413,18 @@ impl<'a> ReceiveSaga<'a, Prepared> {
             }
         };
 
-        let recv_proofs = construct_proofs(
+        let recv_proofs = construct_proofs_parallel(
             swap_response.signatures,
             pre_swap.pre_mint_secrets.rs(),
             pre_swap.pre_mint_secrets.secrets(),
             &keys,
-        )?;
-
-        self.wallet
-            .localstore
-            .increment_keyset_counter(&self.state_data.active_keyset_id, recv_proofs.len() as u32)
-            .await?;
+        )
+        .await?;
 
+        let active_keyset_id = self.state_data.active_keyset_id;
+        let recv_count = recv_proofs.len() as u32;
         let total_amount = recv_proofs.total_amount()?;
-        let fee = self.state_data.proofs_amount - total_amount;
+        let fee = self.state_data.proofs_amount.try_sub(total_amount)?;
 
         let recv_proof_infos = recv_proofs
             .into_iter()
@@ -425,31 +438,35 @@ impl<'a> ReceiveSaga<'a, Prepared> {
             })
             .collect::<Result<Vec<ProofInfo>, _>>()?;
 
-        self.wallet
-            .localstore
-            .update_proofs(
-                recv_proof_infos,
-                proofs_info.into_iter().map(|p| p.y).collect(),
-            )
-            .await?;
+        let removed_ys = proofs_info.into_iter().map(|p| p.y).collect();
+        let transaction = Transaction {
+            mint_url: self.wallet.mint_url.clone(),
+            direction: TransactionDirection::Incoming,
+            amount: total_amount,
+            fee,
+            unit: self.wallet.unit.clone(),
+            ys: proofs_ys,
+            timestamp: unix_time(),
+            memo: self.state_data.memo.clone(),
+            metadata: self.state_data.options.metadata.clone(),
+            quote_id: None,
+            payment_request: None,
+            payment_proof: None,
+            payment_method: None,
+            saga_id: Some(operation_id),
+        };
 
+        // Counter, proofs and history are committed together so a crash cannot leave received
+        // proofs without their transaction
         self.wallet
             .localstore
-            .add_transaction(Transaction {
-                mint_url: self.wallet.mint_url.clone(),
-                direction: TransactionDirection::Incoming,
-                amount: total_amount,
-                fee,
-                unit: self.wallet.unit.clone(),
-                ys: proofs_ys,
-                timestamp: unix_time(),
-                memo: self.state_data.memo.clone(),
-                metadata: self.state_data.options.metadata.clone(),
-                quote_id: None,
-                payment_request: None,
-                payment_proof: None,
-                payment_method: None,
-                saga_id: Some(operation_id),
+            .transaction(move |tx| {
+                Box::pin(async move {
+                    tx.increment_keyset_counter(&active_keyset_id, recv_count)
+                        .await?;
+                    tx.update_proofs(recv_proof_infos, removed_ys).await?;
+                    tx.add_transaction(transaction).await
+                })
             })
             .await?;
 
@@ -472,30 +489,6 @@ impl<'a> ReceiveSaga<'a, Prepared> {
             },
         })
     }
-
-    /// Determine the signature flag based on the proofs
-    fn determine_sig_flag(&self) -> Result<SigFlag, Error> {
-        for proof in &self.state_data.proofs {
-            if let Ok(secret) =
-                <crate::secret::Secret as TryInto<crate::nuts::nut10::Secret>>::try_into(
-                    proof.secret.clone(),
-                )
-            {
-                let conditions: Result<Conditions, _> = secret
-                    .secret_data()
-                    .tags()
-                    .cloned()
-                    .unwrap_or_default()
-                    .try_into();
-                if let Ok(conditions) = conditions {
-                    if conditions.sig_flag == SigFlag::SigAll {
-                        return Ok(SigFlag::SigAll);
-                    }
-                }
-            }
-        }
-        Ok(SigFlag::SigInputs)
-    }
 }
 
 impl<'a> ReceiveSaga<'a, Finalized> {
@@ -296,6 +296,7 @@ mod tests {
                 state: State::Unspent, // Not spent yet
                 witness: None,
             }],
+            next_cursor: None,
         }));
         mock_client.set_post_swap_response(Ok(crate::nuts::SwapResponse { signatures: vec![] }));
 
@@ -364,10 +365,12 @@ mod tests {
                 state: State::Spent, // Spent at mint
                 witness: None,
             }],
+            next_cursor: None,
         }));
         mock_client._set_restore_response(Ok(RestoreResponse {
             signatures: vec![],
             outputs: vec![],
+            next_cursor: None,
         }));
 
         let wallet = create_test_wallet_with_mock(db.clone(), mock_client).await;
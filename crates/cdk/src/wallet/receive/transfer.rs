@@ -0,0 +1,244 @@
+//! Receiving tokens issued by another mint
+//!
+//! The token is melted at its issuing mint to pay a mint quote of the wallet's mint, so the
+//! funds end up at the mint the wallet trusts.
+
+use std::str::FromStr;
+
+use cdk_common::wallet::MintQuote;
+use tracing::instrument;
+
+use super::ReceiveOptions;
+use crate::mint_url::MintUrl;
+use crate::nuts::nut00::ProofsMethods;
+use crate::nuts::{PaymentMethod, Proofs, Token};
+use crate::wallet::builder::WalletBuilder;
+use crate::wallet::util::quote_within_fee_reserve;
+use crate::wallet::MeltConfirmOptions;
+use crate::{ensure_cdk, Amount, Error, Wallet};
+
+/// Token received from another mint over Lightning
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MintTransfer {
+    /// Mint that issued the token
+    pub source_mint: MintUrl,
+    /// Amount minted at the wallet's mint
+    pub amount: Amount,
+    /// Lightning and input fees paid at the source mint
+    pub fee_paid: Amount,
+}
+
+impl Wallet {
+    /// Receive a token issued by another mint
+    ///
+    /// The token is melted at its mint to pay a mint quote of this wallet's mint. Change from the
+    /// melt stays in the local store under the source mint. Tokens from this wallet's mint are
+    /// received as usual.
+    #[instrument(skip_all)]
+    pub async fn receive_from_other_mint(
+        &self,
+        encoded_token: &str,
+        opts: ReceiveOptions,
+    ) -> Result<MintTransfer, Error> {
+        let token = Token::from_str(encoded_token)?;
+
+        ensure_cdk!(
+            token.unit().unwrap_or_default() == self.unit,
+            Error::UnsupportedUnit
+        );
+
+        if let Token::TokenV3(token) = &token {
+            ensure_cdk!(!token.is_multi_mint(), Error::MultiMintTokenNotSupported);
+        }
+
+        let source_mint = token.mint_url()?;
+
+        if source_mint == self.mint_url {
+            let proofs = self.token_proofs(&token).await?;
+            let memo = token.memo().clone();
+            let amount = self
+                .receive_proofs(proofs, opts, memo, Some(encoded_token.to_string()))
+                .await?;
+
+            return Ok(MintTransfer {
+                source_mint,
+                amount,
+                fee_paid: Amount::ZERO,
+            });
+        }
+
+        // The source mint is reached over the same transport, e.g. the same proxy or Tor client
+        let source = WalletBuilder::new()
+            .mint_url(source_mint.clone())
+            .unit(self.unit.clone())
+            .localstore(self.localstore.clone())
+            .seed(self.seed)
+            .shared_client(self.client.mint_connector(source_mint.clone()))
+            .build()?;
+
+        let proofs = source.token_proofs(&token).await?;
+        let input_amount = proofs.total_amount()?;
+        let (mint_quote, melt_quote_id) = self.quote_transfer(&source, &proofs).await?;
+
+        let melted = source
+            .prepare_melt_proofs(&melt_quote_id, proofs, opts.metadata)
+            .await?
+            .confirm_with_options(
+                MeltConfirmOptions::new().with_p2pk_signing_keys(opts.p2pk_signing_keys),
+            )
+            .await?;
+
+        let mint_quote = self.check_mint_quote_status(&mint_quote.id).await?;
+        let amount = self
+            .mint(&mint_quote.id, opts.amount_split_target, None)
+            .await?
+            .total_amount()?;
+
+        let change = match melted.change() {
+            Some(change) => change.total_amount()?,
+            None => Amount::ZERO,
+        };
+
+        Ok(MintTransfer {
+            source_mint,
+            amount,
+            fee_paid: input_amount
+                .checked_sub(amount)
+                .and_then(|fee| fee.checked_sub(change))
+                .unwrap_or_default(),
+        })
+    }
+
+    /// Create a mint quote payable with `proofs` melted at `source`
+    ///
+    /// The quote is lowered once by the fee reserve of the source if the proofs do not cover it.
+    async fn quote_transfer(
+        &self,
+        source: &Wallet,
+        proofs: &Proofs,
+    ) -> Result<(MintQuote, String), Error> {
+        let available = proofs
+            .total_amount()?
+            .checked_sub(source.get_proofs_fee(proofs).await?.total)
+            .ok_or(Error::InsufficientFunds)?;
+
+        let quoted =
+            quote_within_fee_reserve(available, available, Amount::ONE, |amount| async move {
+                let mint_quote = self
+                    .mint_quote(PaymentMethod::BOLT11, Some(amount), None, None)
+                    .await?;
+                let melt_quote = source
+                    .melt_quote(
+                        PaymentMethod::BOLT11,
+                        mint_quote.request.clone(),
+                        None,
+                        None,
+                    )
+                    .await?;
+
+                Ok::<_, Error>((mint_quote, melt_quote))
+            })
+            .await?;
+
+        let (mint_quote, melt_quote) = quoted.ok_or(Error::InsufficientFunds)?;
+
+        Ok((mint_quote, melt_quote.id))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use cdk_common::nut00::KnownMethod;
+    use cdk_common::{MeltQuoteCreateResponse, MeltQuoteResponse, MintQuoteResponse};
+
+    use super::*;
+    use crate::nuts::{
+        nut10, CurrencyUnit, MeltQuoteBolt11Response, MeltQuoteState, MintQuoteBolt11Response,
+        MintQuoteState, Proof, SecretKey, SpendingConditions,
+    };
+    use crate::wallet::test_utils::{
+        create_test_db, create_test_wallet_with_mock, test_keyset_id, test_proof, MockMintConnector,
+    };
+
+    fn melt_quote_response(state: MeltQuoteState) -> MeltQuoteBolt11Response<String> {
+        MeltQuoteBolt11Response {
+            quote: "melt-quote".to_string(),
+            state,
+            expiry: 9999999999,
+            fee_reserve: Amount::from(10),
+            amount: Amount::from(1000),
+            request: None,
+            payment_preimage: None,
+            change: None,
+            unit: Some(CurrencyUnit::Sat),
+            method: PaymentMethod::Known(KnownMethod::Bolt11),
+            change_policy: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_receive_from_other_mint_signs_inputs_at_source_mint() {
+        let db = create_test_db().await;
+        let mock_client = Arc::new(MockMintConnector::new());
+        let source_client = Arc::new(MockMintConnector::new());
+        mock_client.set_other_mint_connector(source_client.clone());
+        let wallet = create_test_wallet_with_mock(db, mock_client.clone()).await;
+
+        let invoice = cdk_fake_wallet::create_fake_invoice(1_000_000, String::new());
+        mock_client.set_mint_quote_response(Ok(MintQuoteResponse::Bolt11(
+            MintQuoteBolt11Response {
+                quote: "mint-quote".to_string(),
+                request: invoice.to_string(),
+                amount: Some(Amount::from(1000)),
+                unit: Some(CurrencyUnit::Sat),
+                method: PaymentMethod::BOLT11,
+                amount_paid: Amount::ZERO,
+                amount_issued: Amount::ZERO,
+                updated_at: 0,
+                state: MintQuoteState::Unpaid,
+                expiry: Some(9999999999),
+                pubkey: None,
+            },
+        )));
+        mock_client.set_mint_quote_status_response(Err(Error::Custom("offline".to_string())));
+        source_client.set_melt_quote_response(Ok(MeltQuoteCreateResponse::Bolt11(
+            melt_quote_response(MeltQuoteState::Unpaid),
+        )));
+        source_client.set_post_melt_response(Ok(MeltQuoteResponse::Bolt11(melt_quote_response(
+            MeltQuoteState::Paid,
+        ))));
+
+        // A token locked to a key of the receiver, issued by another mint
+        let signing_key = SecretKey::generate();
+        let conditions = SpendingConditions::new_p2pk(signing_key.public_key(), None);
+        let secret: nut10::Secret = conditions.into();
+        let proof = Proof {
+            secret: secret.try_into().unwrap(),
+            ..test_proof(test_keyset_id(), 1024)
+        };
+        let source_mint = MintUrl::from_str("https://source-mint.example.com").unwrap();
+        let token = Token::new(source_mint, vec![proof], None, CurrencyUnit::Sat);
+
+        let opts = ReceiveOptions {
+            p2pk_signing_keys: vec![signing_key],
+            ..Default::default()
+        };
+
+        // The melt at the source goes through, minting fails as the wallet's mint is offline
+        assert!(wallet
+            .receive_from_other_mint(&token.to_string(), opts)
+            .await
+            .is_err());
+
+        let (_, melt_request) = source_client
+            .last_post_melt_request()
+            .expect("the proofs are melted at the source mint");
+        assert_eq!(melt_request.quote(), "melt-quote");
+        assert!(melt_request
+            .inputs()
+            .iter()
+            .all(|proof| proof.witness.is_some()));
+    }
+}
@@ -14,7 +14,7 @@ impl Wallet {
 
         let statuses = self
             .client
-            .post_check_state(CheckStateRequest { ys: proof_ys })
+            .post_check_state(CheckStateRequest::new(proof_ys))
             .await?
             .states;
 
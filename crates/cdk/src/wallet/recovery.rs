@@ -17,14 +17,13 @@
 use std::collections::HashMap;
 
 use async_trait::async_trait;
-use cdk_common::wallet::{ProofInfo, WalletSagaState};
+use cdk_common::wallet::{ProofInfo, WalletSaga, WalletSagaState};
 use cdk_common::BlindedMessage;
 use tracing::instrument;
 
-use crate::dhke::construct_proofs;
 use crate::nuts::{CheckStateRequest, PreMintSecrets, Proofs, RestoreRequest, State, SwapRequest};
 use crate::wallet::blind_signature::{
-    validate_mint_response_signatures, SignatureAmountValidation,
+    construct_proofs_parallel, validate_mint_response_signatures, SignatureAmountValidation,
 };
 use crate::{Error, Wallet};
 
@@ -155,7 +154,7 @@ impl RecoveryHelpers for Wallet {
         let ys: Vec<_> = proofs.iter().map(|p| p.y).collect();
         let response = self
             .client
-            .post_check_state(CheckStateRequest { ys })
+            .post_check_state(CheckStateRequest::new(ys))
             .await?;
 
         Ok(response.states.iter().all(|s| s.state == State::Spent))
@@ -283,12 +282,13 @@ impl RecoveryHelpers for Wallet {
         .await?;
 
         // Construct proofs
-        let proofs = construct_proofs(
+        let proofs = construct_proofs_parallel(
             swap_response.signatures,
             premint_secrets.rs(),
             premint_secrets.secrets(),
             &keys,
-        )?;
+        )
+        .await?;
 
         // Convert to ProofInfo
         let proof_infos: Vec<ProofInfo> = proofs
@@ -334,13 +334,43 @@ impl Wallet {
     #[instrument(skip(self))]
     pub async fn recover_incomplete_sagas(&self) -> Result<RecoveryReport, Error> {
         self.cleanup_orphaned_quote_reservations().await?;
+        self.recover_sagas(|_| true).await
+    }
+
+    /// Recover the incomplete sagas with the given ids
+    ///
+    /// Rolls back the sagas left behind by a single abandoned operation, as recorded by
+    /// [`StartedSagas`](crate::wallet::StartedSagas), without touching concurrent operations.
+    #[instrument(skip(self))]
+    pub async fn recover_sagas_by_id(
+        &self,
+        saga_ids: &[uuid::Uuid],
+    ) -> Result<RecoveryReport, Error> {
+        self.recover_sagas(|saga| saga_ids.contains(&saga.id)).await
+    }
+
+    /// Resume mint requests interrupted by a crash. Call on startup.
+    ///
+    /// The blinded messages of a mint request are stored with its quote before the request is
+    /// sent. Interrupted requests are re-submitted, or restored from the mint, and the signatures
+    /// unblinded into proofs. Requests that were never sent are rolled back.
+    #[instrument(skip(self))]
+    pub async fn resume_pending_mints(&self) -> Result<RecoveryReport, Error> {
+        self.recover_sagas(|saga| matches!(saga.state, WalletSagaState::Issue(_)))
+            .await
+    }
 
+    /// Recover the incomplete sagas of this wallet matching `filter`
+    async fn recover_sagas(
+        &self,
+        filter: impl Fn(&WalletSaga) -> bool,
+    ) -> Result<RecoveryReport, Error> {
         let sagas = self.localstore.get_incomplete_sagas().await?;
 
         // Filter to only include sagas for this wallet (mint and unit)
         let sagas: Vec<_> = sagas
             .into_iter()
-            .filter(|s| s.mint_url == self.mint_url && s.unit == self.unit)
+            .filter(|s| s.mint_url == self.mint_url && s.unit == self.unit && filter(s))
             .collect();
 
         if sagas.is_empty() {
@@ -438,11 +468,9 @@ impl Wallet {
             params.blinded_messages.len()
         );
 
-        let restore_request = RestoreRequest {
-            outputs: params.blinded_messages.to_vec(),
-        };
+        let restore_request = RestoreRequest::new(params.blinded_messages.to_vec());
 
-        let restore_response = match self.client.post_restore(restore_request).await {
+        let restore_response = match self.post_restore_paged(restore_request).await {
             Ok(response) => response,
             Err(e) => {
                 if e.is_definitive_failure() {
@@ -535,12 +563,13 @@ impl Wallet {
         .await?;
 
         // Construct proofs from signatures
-        let proofs = construct_proofs(
+        let proofs = construct_proofs_parallel(
             restore_response.signatures,
             matched.iter().map(|(p, _)| p.r.clone()).collect(),
             matched.iter().map(|(p, _)| p.secret.clone()).collect(),
             &keys,
-        )?;
+        )
+        .await?;
 
         tracing::info!(
             "{} saga {} - recovered {} proofs",
@@ -725,6 +754,7 @@ mod tests {
     use cdk_common::Amount;
 
     use crate::wallet::test_utils::*;
+    use crate::wallet::{SendOptions, StartedSagas};
 
     #[tokio::test]
     async fn test_recover_receive_proofs_pending() {
@@ -809,6 +839,59 @@ mod tests {
         assert!(db.get_saga(&saga_id).await.unwrap().is_none());
     }
 
+    #[tokio::test]
+    async fn test_resume_pending_mints_only_resumes_issue_sagas() {
+        let db = create_test_db().await;
+        let mint_url = test_mint_url();
+
+        let mut quote = test_mint_quote(mint_url.clone());
+        let issue_saga_id = uuid::Uuid::new_v4();
+        quote.used_by_operation = Some(issue_saga_id.to_string());
+        db.add_mint_quote(quote.clone()).await.unwrap();
+
+        db.add_saga(WalletSaga::new(
+            issue_saga_id,
+            WalletSagaState::Issue(IssueSagaState::SecretsPrepared),
+            Amount::from(1000),
+            mint_url.clone(),
+            cdk_common::nuts::CurrencyUnit::Sat,
+            OperationData::Mint(MintOperationData::new_single(
+                quote.id.clone(),
+                Amount::from(1000),
+                Some(0),
+                Some(10),
+                None,
+            )),
+        ))
+        .await
+        .unwrap();
+
+        let receive_saga_id = uuid::Uuid::new_v4();
+        db.add_saga(WalletSaga::new(
+            receive_saga_id,
+            WalletSagaState::Receive(ReceiveSagaState::ProofsPending),
+            Amount::from(100),
+            mint_url.clone(),
+            cdk_common::nuts::CurrencyUnit::Sat,
+            OperationData::Receive(ReceiveOperationData {
+                token: Some("cashu...".to_string()),
+                counter_start: None,
+                counter_end: None,
+                amount: Some(Amount::from(100)),
+                blinded_messages: None,
+            }),
+        ))
+        .await
+        .unwrap();
+
+        let wallet = create_test_wallet(db.clone()).await;
+        let report = wallet.resume_pending_mints().await.unwrap();
+
+        assert_eq!(report.compensated, 1);
+        assert!(db.get_saga(&issue_saga_id).await.unwrap().is_none());
+        assert!(db.get_saga(&receive_saga_id).await.unwrap().is_some());
+    }
+
     #[tokio::test]
     async fn test_recover_melt_proofs_reserved() {
         // Test that melt saga in ProofsReserved state gets compensated:
@@ -948,6 +1031,55 @@ mod tests {
         assert!(sagas.is_empty());
     }
 
+    #[tokio::test]
+    async fn test_recover_sagas_by_id_keeps_concurrent_prepared_send() {
+        let db = create_test_db().await;
+        let mint_url = test_mint_url();
+        let keyset_id = test_keyset_id();
+        db.update_proofs(
+            vec![
+                test_proof_info(keyset_id, 8, mint_url.clone()),
+                test_proof_info(keyset_id, 8, mint_url),
+            ],
+            vec![],
+        )
+        .await
+        .unwrap();
+
+        let mock_client = Arc::new(MockMintConnector::new());
+        mock_client.reset_default_mint_state();
+        let wallet = create_test_wallet_with_mock(db.clone(), mock_client).await;
+
+        let kept = wallet
+            .prepare_send(Amount::from(8), SendOptions::default())
+            .await
+            .unwrap();
+
+        // The abandoned operation prepares a send of its own
+        let started = StartedSagas::default();
+        let abandoned = started
+            .scope(wallet.prepare_send(Amount::from(8), SendOptions::default()))
+            .await
+            .unwrap();
+        let abandoned_id = abandoned.operation_id();
+        drop(abandoned);
+        assert_eq!(started.ids(), vec![abandoned_id]);
+
+        let report = wallet.recover_sagas_by_id(&started.ids()).await.unwrap();
+        assert_eq!(report.compensated, 1);
+
+        let sagas = db.get_incomplete_sagas().await.unwrap();
+        assert_eq!(sagas.len(), 1);
+        assert_eq!(sagas[0].id, kept.operation_id());
+
+        let reserved = db
+            .get_proofs(None, None, Some(vec![State::Reserved]), None)
+            .await
+            .unwrap();
+        assert_eq!(reserved.len(), 1);
+        assert_eq!(reserved[0].used_by_operation, Some(kept.operation_id()));
+    }
+
     #[tokio::test]
     async fn test_cleanup_orphaned_melt_quote_reservation() {
         // Test that orphaned melt quote reservations are cleaned up
@@ -1043,6 +1175,7 @@ mod tests {
             request: None,
             unit: None,
             method: PaymentMethod::BOLT11,
+            change_policy: None,
         }));
 
         let wallet = create_test_wallet_with_mock(db.clone(), mock_client).await;
@@ -1121,6 +1254,7 @@ mod tests {
             request: None,
             unit: None,
             method: PaymentMethod::BOLT11,
+            change_policy: None,
         }));
 
         let wallet = create_test_wallet_with_mock(db.clone(), mock_client).await;
@@ -1193,6 +1327,7 @@ mod tests {
             request: None,
             unit: None,
             method: PaymentMethod::BOLT11,
+            change_policy: None,
         }));
 
         let wallet = create_test_wallet_with_mock(db.clone(), mock_client).await;
@@ -1275,6 +1410,7 @@ mod tests {
             request: None,
             unit: None,
             method: PaymentMethod::BOLT11,
+            change_policy: None,
         }));
 
         let wallet = create_test_wallet_with_mock(db.clone(), mock_client).await;
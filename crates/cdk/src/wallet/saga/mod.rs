@@ -15,11 +15,14 @@
 //! to undo all completed steps and restore the database to its pre-saga state.
 
 use std::collections::VecDeque;
+use std::future::Future;
 use std::sync::Arc;
 
 use async_trait::async_trait;
 use cdk_common::database::{self, WalletDatabase};
+use cdk_common::parking_lot::Mutex;
 use tracing::instrument;
+use uuid::Uuid;
 
 use crate::nuts::{PublicKey, State};
 use crate::Error;
@@ -80,6 +83,38 @@ pub(crate) async fn add_compensation(
     compensations.push_front(action);
 }
 
+tokio::task_local! {
+    static STARTED_SAGAS: StartedSagas;
+}
+
+/// Ids of the sagas started by an operation run through [`StartedSagas::scope`]
+///
+/// A caller abandoning the operation passes them to
+/// [`Wallet::recover_sagas_by_id`](crate::Wallet::recover_sagas_by_id), so only the sagas
+/// left behind by that operation are rolled back and concurrent operations are untouched.
+#[derive(Debug, Clone, Default)]
+pub struct StartedSagas(Arc<Mutex<Vec<Uuid>>>);
+
+impl StartedSagas {
+    /// Run `operation`, recording the id of every saga it starts
+    pub async fn scope<F>(&self, operation: F) -> F::Output
+    where
+        F: Future,
+    {
+        STARTED_SAGAS.scope(self.clone(), operation).await
+    }
+
+    /// Ids of the sagas started so far
+    pub fn ids(&self) -> Vec<Uuid> {
+        self.0.lock().clone()
+    }
+}
+
+/// Record a saga started by the operation running in [`StartedSagas::scope`], if any
+pub(crate) fn record_started_saga(saga_id: Uuid) {
+    let _ = STARTED_SAGAS.try_with(|started| started.0.lock().push(saga_id));
+}
+
 // Shared Compensation Actions
 
 /// Reverts proof reservation on saga failure.
@@ -404,9 +404,9 @@ pub(crate) fn split_proofs_for_send(
 
         // Check if swap is actually needed
         if !proofs_to_swap.is_empty() {
-            let swap_output_needed = (amount + send_fee)
-                .checked_sub(proofs_to_send.total_amount()?)
-                .unwrap_or(Amount::ZERO);
+            let swap_output_needed = amount
+                .try_add(send_fee)?
+                .saturating_sub(proofs_to_send.total_amount()?);
 
             if swap_output_needed == Amount::ZERO {
                 // proofs_to_send already covers the full amount
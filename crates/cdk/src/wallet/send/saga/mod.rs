@@ -64,11 +64,11 @@
 use std::collections::{HashMap, HashSet};
 
 use bitcoin::XOnlyPublicKey;
-use cdk_common::amount::KeysetFeeAndAmounts;
+use cdk_common::amount::{FeeAndAmounts, KeysetFeeAndAmounts};
 use cdk_common::util::unix_time;
 use cdk_common::wallet::{
-    KeysetLoadPolicy, OperationData, P2PKLockedProofSendMode, SendOperationData, SendSagaState,
-    Transaction, TransactionDirection, WalletSaga, WalletSagaState,
+    KeysetLoadPolicy, OperationData, P2PKLockedProofSendMode, SendFeePolicy, SendOperationData,
+    SendSagaState, Transaction, TransactionDirection, WalletSaga, WalletSagaState,
 };
 use cdk_common::Id;
 use tracing::instrument;
@@ -81,7 +81,7 @@ use crate::nuts::nut00::ProofsMethods;
 use crate::nuts::nut11::{enforce_sig_flag, SigFlag};
 use crate::nuts::{Proofs, State, Token};
 use crate::wallet::saga::{
-    add_compensation, execute_compensations, new_compensations, Compensations,
+    add_compensation, execute_compensations, new_compensations, record_started_saga, Compensations,
     RevertProofReservation,
 };
 use crate::wallet::SendKind;
@@ -201,6 +201,8 @@ fn ensure_selected_proofs_cover_input_fees(
         .filter(|proof| !selected_proofs.contains(proof))
         .collect();
 
+    let required = context.amount.try_add(context.send_fee)?;
+
     loop {
         let selected_net = selected_proofs_net_after_swap_fees(
             selected_proofs.clone(),
@@ -214,7 +216,7 @@ fn ensure_selected_proofs_cover_input_fees(
             },
         )?;
 
-        if selected_net >= context.amount + context.send_fee {
+        if selected_net >= required {
             return Ok(selected_proofs);
         }
 
@@ -222,9 +224,7 @@ fn ensure_selected_proofs_cover_input_fees(
             return Err(Error::InsufficientFunds);
         }
 
-        let shortfall = (context.amount + context.send_fee)
-            .checked_sub(selected_net)
-            .unwrap_or(Amount::ZERO);
+        let shortfall = required.saturating_sub(selected_net);
         let additional = Wallet::select_proofs(
             shortfall,
             remaining_proofs.clone(),
@@ -242,6 +242,107 @@ fn ensure_selected_proofs_cover_input_fees(
     }
 }
 
+/// Add proofs until the selection covers `amount` plus the sender's share of its input fee
+///
+/// [`Wallet::select_proofs`] can only reserve the full input fee, which over-reserves when the
+/// fee is split with the receiver.
+fn include_sender_fee_share(
+    mut selected_proofs: Proofs,
+    proof_pool: Proofs,
+    amount: Amount,
+    fee_policy: SendFeePolicy,
+    active_keyset_ids: &Vec<Id>,
+    keyset_fees: &KeysetFeeAndAmounts,
+) -> Result<Proofs, Error> {
+    let keyset_fee_map: HashMap<Id, u64> = keyset_fees
+        .iter()
+        .map(|(key, values)| (*key, values.fee()))
+        .collect();
+    let mut remaining_proofs: Proofs = proof_pool
+        .into_iter()
+        .filter(|proof| !selected_proofs.contains(proof))
+        .collect();
+
+    loop {
+        let fee = calculate_fee(&selected_proofs.count_by_keyset(), &keyset_fee_map)?.total;
+        let required = amount.try_add(fee_policy.sender_share(fee))?;
+        let total = selected_proofs.total_amount()?;
+
+        if total >= required {
+            return Ok(selected_proofs);
+        }
+
+        let additional = Wallet::select_proofs(
+            required.saturating_sub(total),
+            remaining_proofs.clone(),
+            active_keyset_ids,
+            keyset_fees,
+            false,
+        )?;
+
+        if additional.is_empty() {
+            return Err(Error::InsufficientFunds);
+        }
+
+        remaining_proofs.retain(|proof| !additional.contains(proof));
+        selected_proofs.extend(additional);
+    }
+}
+
+/// Denominations of the token and the part of the receiver's input fee added on top of `amount`
+///
+/// The receiver swaps the token at the active keyset, so its fee is charged per proof of the
+/// split.
+async fn split_send_amount(
+    wallet: &Wallet,
+    amount: Amount,
+    fee_policy: SendFeePolicy,
+    active_keyset_id: Id,
+    fee_and_amounts: &FeeAndAmounts,
+) -> Result<(Vec<Amount>, Amount), Error> {
+    if fee_policy == SendFeePolicy::ReceiverPays {
+        return Ok((amount.split(fee_and_amounts)?, Amount::ZERO));
+    }
+
+    let send_split = amount.split_with_fee(fee_and_amounts)?;
+    let receive_fee = wallet
+        .get_proofs_fee_by_count(
+            vec![(active_keyset_id, send_split.len() as u64)]
+                .into_iter()
+                .collect(),
+        )
+        .await?
+        .total;
+
+    match fee_policy {
+        SendFeePolicy::SenderPays => Ok((send_split, receive_fee)),
+        _ => {
+            let sender_fee = fee_policy.sender_share(receive_fee);
+            Ok((
+                amount.try_add(sender_fee)?.split(fee_and_amounts)?,
+                sender_fee,
+            ))
+        }
+    }
+}
+
+/// Number of proofs in the token once the proofs to swap are swapped for the missing amount
+fn send_proof_count(
+    split_result: &super::ProofSplitResult,
+    total_send_amount: Amount,
+    fee_and_amounts: &FeeAndAmounts,
+) -> Result<usize, Error> {
+    if split_result.proofs_to_swap.is_empty() {
+        return Ok(split_result.proofs_to_send.len());
+    }
+
+    let swap_amount = total_send_amount
+        .checked_sub(split_result.proofs_to_send.total_amount()?)
+        .unwrap_or(Amount::ZERO);
+
+    Ok(split_result.proofs_to_send.len() + swap_amount.split(fee_and_amounts)?.len())
+}
+
 fn split_proofs_for_send_respecting_p2pk_locks(
     proofs: Proofs,
     p2pk_locked_proof_send_mode: P2PKLockedProofSendMode,
@@ -502,21 +603,20 @@ impl<'a> SendSaga<'a, Initial> {
             }
         }
 
-        let send_amounts = if opts.include_fee {
-            let send_split = amount.split_with_fee(&fee_and_amounts)?;
-            let send_fee = self
-                .wallet
-                .get_proofs_fee_by_count(
-                    vec![(active_keyset_id, send_split.len() as u64)]
-                        .into_iter()
-                        .collect(),
-                )
-                .await?;
-            (send_split, send_fee.total)
-        } else {
-            (amount.split(&fee_and_amounts)?, Amount::ZERO)
-        };
-        let selection_amount = amount + send_amounts.1;
+        let fee_policy = opts.effective_fee_policy();
+        let send_amounts = split_send_amount(
+            self.wallet,
+            amount,
+            fee_policy,
+            active_keyset_id,
+            &fee_and_amounts,
+        )
+        .await?;
+        let sender_pays_fee = fee_policy != SendFeePolicy::ReceiverPays;
+        // A split fee only reserves the sender's share, unless the proofs are swapped anyway
+        let split_fee = fee_policy == SendFeePolicy::Split && !force_swap;
+        let include_fee = (sender_pays_fee && !split_fee) || force_swap;
+        let selection_amount = amount.try_add(send_amounts.1)?;
 
         let may_swap_p2pk_locked = opts.p2pk_locked_proof_send_mode
             == P2PKLockedProofSendMode::Swap
@@ -530,17 +630,45 @@ impl<'a> SendSaga<'a, Initial> {
             available_proofs,
             &active_keyset_ids,
             &keyset_fees,
-            opts.include_fee || force_swap,
+            include_fee,
         )?;
+        if split_fee {
+            selected_proofs = include_sender_fee_share(
+                selected_proofs,
+                proof_pool.clone(),
+                selection_amount,
+                fee_policy,
+                &active_keyset_ids,
+                &keyset_fees,
+            )?;
+        }
 
-        let send_fee = if opts.include_fee {
-            self.wallet.get_proofs_fee(&selected_proofs).await?.total
+        // Over the proof limit an online send swaps the selected proofs, so the selection
+        // has to cover the input fee of that swap as well
+        if opts
+            .max_proofs
+            .is_some_and(|max_proofs| selected_proofs.len() > max_proofs)
+            && !opts.send_kind.is_offline()
+            && !include_fee
+        {
+            selected_proofs = Wallet::select_proofs(
+                selection_amount,
+                proof_pool.clone(),
+                &active_keyset_ids,
+                &keyset_fees,
+                true,
+            )?;
+        }
+
+        let send_fee = if sender_pays_fee {
+            fee_policy.sender_share(self.wallet.get_proofs_fee(&selected_proofs).await?.total)
         } else {
             Amount::ZERO
         };
 
         if may_swap_p2pk_locked {
-            let is_exact_or_offline = selected_proofs.total_amount()? == amount + send_fee
+            let is_exact_or_offline = selected_proofs.total_amount()?
+                == amount.try_add(send_fee)?
                 || opts.send_kind.is_offline()
                 || opts.send_kind.has_tolerance();
             selected_proofs = ensure_selected_proofs_cover_input_fees(
@@ -560,7 +688,7 @@ impl<'a> SendSaga<'a, Initial> {
 
         let selected_total = selected_proofs.total_amount()?;
 
-        if selected_total == amount + send_fee {
+        if selected_total == amount.try_add(send_fee)? {
             return self
                 .internal_prepare(amount, opts, selected_proofs, force_swap, keyset_policy)
                 .await;
@@ -574,7 +702,10 @@ impl<'a> SendSaga<'a, Initial> {
             _ => None,
         };
         if let Some(tolerance) = tolerance {
-            if selected_total - amount > tolerance && opts.send_kind.is_offline() {
+            let excess = selected_total
+                .checked_sub(amount)
+                .ok_or(Error::InsufficientFunds)?;
+            if excess > tolerance && opts.send_kind.is_offline() {
                 return Err(Error::InsufficientFunds);
             }
         }
@@ -604,33 +735,26 @@ impl<'a> SendSaga<'a, Initial> {
             .cloned()
             .ok_or(Error::UnknownKeySet)?;
 
-        let (send_amounts, send_fee) = if opts.include_fee {
-            let send_split = amount.split_with_fee(&fee_and_amounts)?;
-            let send_fee = self
-                .wallet
-                .get_proofs_fee_by_count(
-                    vec![(active_keyset_id, send_split.len() as u64)]
-                        .into_iter()
-                        .collect(),
-                )
-                .await?;
-            (send_split, send_fee)
-        } else {
-            let send_split = amount.split(&fee_and_amounts)?;
-            let send_fee = crate::fees::ProofsFeeBreakdown {
-                total: Amount::ZERO,
-                per_keyset: std::collections::HashMap::new(),
-            };
-            (send_split, send_fee)
-        };
+        let (send_amounts, send_fee) = split_send_amount(
+            self.wallet,
+            amount,
+            opts.effective_fee_policy(),
+            active_keyset_id,
+            &fee_and_amounts,
+        )
+        .await?;
 
-        let mut exact_proofs = proofs.total_amount()? == amount + send_fee.total;
-        if let Some(max_proofs) = opts.max_proofs {
-            exact_proofs &= proofs.len() <= max_proofs;
-        }
+        let total_send_amount = amount.try_add(send_fee)?;
+        let within_max_proofs = opts
+            .max_proofs
+            .is_none_or(|max_proofs| proofs.len() <= max_proofs);
+        let exact_proofs = proofs.total_amount()? == total_send_amount && within_max_proofs;
 
-        let is_exact_or_offline =
-            exact_proofs || opts.send_kind.is_offline() || opts.send_kind.has_tolerance();
+        // Online sends over the proof limit swap down to the target denominations instead of
+        // sending the selected proofs as they are
+        let is_exact_or_offline = exact_proofs
+            || opts.send_kind.is_offline()
+            || (opts.send_kind.has_tolerance() && within_max_proofs);
 
         let keyset_fees_and_amounts = self
             .wallet
@@ -647,13 +771,29 @@ impl<'a> SendSaga<'a, Initial> {
             SendSplitContext {
                 send_amounts: &send_amounts,
                 amount,
-                send_fee: send_fee.total,
+                send_fee,
                 keyset_fees: &keyset_fees,
                 force_swap,
                 is_exact_or_offline,
             },
         )?;
 
+        if let Some(max_proofs) = opts.max_proofs {
+            let required = send_proof_count(&split_result, total_send_amount, &fee_and_amounts)?;
+            if required > max_proofs {
+                tracing::debug!(
+                    "Send of {} needs {} proofs, more than the maximum of {}",
+                    amount,
+                    required,
+                    max_proofs
+                );
+                return Err(Error::MaxProofsExceeded {
+                    required,
+                    max: max_proofs,
+                });
+            }
+        }
+
         let mut proof_ys = split_result.proofs_to_swap.ys()?;
         proof_ys.extend(split_result.proofs_to_send.ys()?);
 
@@ -680,6 +820,7 @@ impl<'a> SendSaga<'a, Initial> {
         );
 
         self.wallet.localstore.add_saga(saga.clone()).await?;
+        record_started_saga(saga.id);
 
         add_compensation(
             &mut self.compensations,
@@ -701,7 +842,7 @@ impl<'a> SendSaga<'a, Initial> {
                 proofs_to_swap: split_result.proofs_to_swap,
                 swap_fee: split_result.swap_fee,
                 proofs_to_send: split_result.proofs_to_send,
-                send_fee: send_fee.total,
+                send_fee,
                 saga,
             },
         })
@@ -808,10 +949,10 @@ impl<'a> SendSaga<'a, Prepared> {
         tracing::info!("Confirming prepared send for operation {}", operation_id);
 
         let logic_res = async {
-            let total_send_fee = swap_fee + send_fee;
+            let total_send_fee = swap_fee.try_add(send_fee)?;
             let mut final_proofs_to_send = proofs_to_send.clone();
 
-            let total_send_amount = amount + send_fee;
+            let total_send_amount = amount.try_add(send_fee)?;
 
             let mut counter_start = None;
             let mut counter_end = None;
@@ -881,6 +1022,7 @@ impl<'a> SendSaga<'a, Prepared> {
                         options.conditions.clone(),
                         false,
                         options.use_p2bk,
+                        &keys,
                     )
                     .await?
                 {
@@ -929,12 +1071,24 @@ impl<'a> SendSaga<'a, Prepared> {
                 })
                 .await?;
 
-            let token = Token::new(
+            let mut token = Token::new(
                 self.wallet.mint_url.clone(),
                 final_proofs_to_send.clone(),
                 token_memo,
                 self.wallet.unit.clone(),
             );
+            // Keyset keys go in the non-standard `k` field, so only when the sender opts in
+            if options.include_dleq {
+                let keyset_ids: HashSet<Id> = final_proofs_to_send
+                    .iter()
+                    .map(|proof| proof.keyset_id)
+                    .collect();
+                let mut keys = HashMap::new();
+                for keyset_id in keyset_ids {
+                    keys.insert(keyset_id, self.wallet.keyset(keyset_id).await?.keys);
+                }
+                token = token.with_keys(&keys);
+            }
 
             let mut saga = self.state_data.saga.clone();
             saga.data = OperationData::Send(SendOperationData {
@@ -1051,6 +1205,7 @@ impl<'a> SendSaga<'a, TokenCreated> {
                 None,
                 false,
                 false,
+                &[],
             )
             .await;
 
@@ -1180,17 +1335,18 @@ mod tests {
     use cdk_common::amount::KeysetFeeAndAmounts;
     use cdk_common::nuts::State;
     use cdk_common::wallet::{
-        KeysetLoadPolicy, OperationData, ProofInfo, SendKind, SendOperationData, SendSagaState,
-        WalletSaga, WalletSagaState,
+        KeysetLoadPolicy, OperationData, ProofInfo, SendFeePolicy, SendKind, SendOperationData,
+        SendSagaState, WalletSaga, WalletSagaState,
     };
     use cdk_common::{CurrencyUnit, ProofsMethods};
 
     use super::{ensure_selected_proofs_cover_input_fees, InputFeeCoverageContext, SendSaga};
-    use crate::nuts::{Proof, SecretKey, SpendingConditions};
+    use crate::nuts::nut02::ShortKeysetId;
+    use crate::nuts::{Proof, ProofDleq, SecretKey, SpendingConditions};
     use crate::wallet::send::SendOptions;
     use crate::wallet::test_utils::{
-        create_test_db, create_test_wallet_with_mock, test_keyset_id, test_mint_url, test_proof,
-        test_proof_info, MockMintConnector,
+        create_test_db, create_test_wallet_with_mock, test_keyset, test_keyset_id, test_mint_url,
+        test_proof, test_proof_info, MockMintConnector,
     };
     use crate::Amount;
 
@@ -1560,6 +1716,201 @@ mod tests {
         );
     }
 
+    /// Online sends over the proof limit swap the selected proofs down to fewer proofs.
+    #[tokio::test]
+    async fn test_send_max_proofs_swaps_selected_proofs() {
+        let db = create_test_db().await;
+        let mint_url = test_mint_url();
+        let keyset_id = test_keyset_id();
+
+        let proofs = vec![
+            test_proof_info(keyset_id, 4, mint_url.clone()),
+            test_proof_info(keyset_id, 4, mint_url.clone()),
+            test_proof_info(keyset_id, 4, mint_url),
+        ];
+        db.update_proofs(proofs, vec![]).await.unwrap();
+
+        let mock_client = Arc::new(MockMintConnector::new());
+        mock_client.reset_default_mint_state();
+
+        // The swap down to one proof costs an input fee, so all three proofs are selected
+        let wallet = create_test_wallet_with_mock(db, mock_client).await;
+        let prepared = SendSaga::new(&wallet)
+            .prepare(
+                Amount::from(8),
+                SendOptions {
+                    max_proofs: Some(1),
+                    ..Default::default()
+                },
+            )
+            .await
+            .unwrap();
+
+        assert!(prepared.proofs_to_send().is_empty());
+        assert_eq!(prepared.proofs_to_swap().len(), 3);
+    }
+
+    /// Sends fail when the amount cannot be expressed within the proof limit.
+    #[tokio::test]
+    async fn test_send_max_proofs_exceeded() {
+        let db = create_test_db().await;
+        let mint_url = test_mint_url();
+        let keyset_id = test_keyset_id();
+
+        let proofs = vec![
+            test_proof_info(keyset_id, 4, mint_url.clone()),
+            test_proof_info(keyset_id, 4, mint_url.clone()),
+            test_proof_info(keyset_id, 16, mint_url),
+        ];
+        db.update_proofs(proofs, vec![]).await.unwrap();
+
+        let mock_client = Arc::new(MockMintConnector::new());
+        mock_client.reset_default_mint_state();
+
+        let wallet = create_test_wallet_with_mock(db, mock_client).await;
+        wallet
+            .keysets(cdk_common::wallet::KeysetLoadPolicy::Refresh)
+            .await
+            .unwrap();
+
+        // 7 = 4 + 2 + 1 needs three proofs
+        let err = SendSaga::new(&wallet)
+            .prepare(
+                Amount::from(7),
+                SendOptions {
+                    max_proofs: Some(2),
+                    ..Default::default()
+                },
+            )
+            .await
+            .expect_err("7 cannot be sent in two proofs");
+        assert!(matches!(
+            err,
+            crate::Error::MaxProofsExceeded {
+                required: 3,
+                max: 2
+            }
+        ));
+
+        // Offline sends cannot swap down to fewer proofs
+        let err = SendSaga::new(&wallet)
+            .with_keyset_policy(KeysetLoadPolicy::CacheOnly)
+            .prepare(
+                Amount::from(8),
+                SendOptions {
+                    send_kind: SendKind::OfflineExact,
+                    max_proofs: Some(1),
+                    ..Default::default()
+                },
+            )
+            .await
+            .expect_err("offline send of two proofs exceeds the limit");
+        assert!(matches!(
+            err,
+            crate::Error::MaxProofsExceeded {
+                required: 2,
+                max: 1
+            }
+        ));
+    }
+
+    /// Only senders paying the receive fee add it to the token.
+    #[tokio::test]
+    async fn test_send_fee_policy_adds_receive_fee() {
+        for (input_fee_ppk, fee_policy, expected_fee) in [
+            (101, SendFeePolicy::ReceiverPays, Amount::ZERO),
+            (101, SendFeePolicy::SenderPays, Amount::from(1)),
+            // Half of the 1 sat fee rounds down to nothing
+            (101, SendFeePolicy::Split, Amount::ZERO),
+            (1000, SendFeePolicy::ReceiverPays, Amount::ZERO),
+            (1000, SendFeePolicy::SenderPays, Amount::from(2)),
+            (1000, SendFeePolicy::Split, Amount::from(1)),
+        ] {
+            let db = create_test_db().await;
+            let mint_url = test_mint_url();
+            let keyset_id = test_keyset_id();
+
+            let proofs = vec![
+                test_proof_info(keyset_id, 16, mint_url.clone()),
+                test_proof_info(keyset_id, 8, mint_url.clone()),
+                test_proof_info(keyset_id, 2, mint_url.clone()),
+                test_proof_info(keyset_id, 1, mint_url.clone()),
+                test_proof_info(keyset_id, 1, mint_url),
+            ];
+            db.update_proofs(proofs, vec![]).await.unwrap();
+
+            let mock_client = Arc::new(MockMintConnector::new());
+            mock_client.reset_default_mint_state();
+            let mut keyset = test_keyset();
+            keyset.input_fee_ppk = input_fee_ppk;
+            mock_client.set_active_keyset(keyset);
+
+            let wallet = create_test_wallet_with_mock(db, mock_client).await;
+            let prepared = SendSaga::new(&wallet)
+                .prepare(
+                    Amount::from(8),
+                    SendOptions {
+                        fee_policy,
+                        ..Default::default()
+                    },
+                )
+                .await
+                .unwrap();
+
+            assert_eq!(
+                prepared.send_fee(),
+                expected_fee,
+                "{fee_policy:?} at {input_fee_ppk} ppk"
+            );
+        }
+    }
+
+    /// Tokens keep DLEQ proofs and only carry keyset keys when requested.
+    #[tokio::test]
+    async fn test_send_includes_keys_only_with_include_dleq() {
+        for include_dleq in [false, true] {
+            let db = create_test_db().await;
+            let keyset_id = test_keyset_id();
+
+            let mut proof_info = test_proof_info(keyset_id, 64, test_mint_url());
+            proof_info.proof.dleq = Some(ProofDleq::new(
+                SecretKey::generate(),
+                SecretKey::generate(),
+                SecretKey::generate(),
+            ));
+            db.update_proofs(vec![proof_info], vec![]).await.unwrap();
+
+            let mock_client = Arc::new(MockMintConnector::new());
+            let wallet = create_test_wallet_with_mock(db, mock_client).await;
+
+            let (token, _) = SendSaga::new(&wallet)
+                .prepare(
+                    Amount::from(64),
+                    SendOptions {
+                        send_kind: SendKind::OfflineExact,
+                        include_dleq,
+                        ..Default::default()
+                    },
+                )
+                .await
+                .unwrap()
+                .confirm(None)
+                .await
+                .unwrap();
+
+            assert_eq!(token.dleq_proof_count(), 1);
+            let included_keys = token.included_keys();
+            if include_dleq {
+                assert_eq!(
+                    included_keys.get(&ShortKeysetId::from(keyset_id)),
+                    Some(&wallet.keyset(keyset_id).await.unwrap().keys)
+                );
+            } else {
+                assert!(included_keys.is_empty());
+            }
+        }
+    }
+
     /// Online send loads keysets from network when cache is empty.
     #[tokio::test]
     async fn test_online_send_loads_keysets_from_network() {
@@ -405,6 +405,7 @@ mod tests {
                 state: State::Spent,
                 witness: None,
             }],
+            next_cursor: None,
         }));
 
         let wallet = create_test_wallet_with_mock(db.clone(), mock_client).await;
@@ -461,6 +462,7 @@ mod tests {
                 state: State::Unspent,
                 witness: None,
             }],
+            next_cursor: None,
         }));
 
         let wallet = create_test_wallet_with_mock(db.clone(), mock_client).await;
@@ -519,6 +521,7 @@ mod tests {
                 state: State::Spent,
                 witness: None,
             }],
+            next_cursor: None,
         }));
 
         let wallet = create_test_wallet_with_mock(db.clone(), mock_client).await;
@@ -574,6 +577,7 @@ mod tests {
                 state: State::Unspent,
                 witness: None,
             }],
+            next_cursor: None,
         }));
 
         let wallet = create_test_wallet_with_mock(db.clone(), mock_client).await;
@@ -714,6 +714,7 @@ mod tests {
             request: Some("test_request".to_string()),
             unit: Some(CurrencyUnit::Sat),
             method: PaymentMethod::BOLT11,
+            change_policy: None,
         }
     }
 
@@ -729,6 +730,7 @@ mod tests {
             request: Some("test_request".to_string()),
             unit: Some(CurrencyUnit::Sat),
             method: PaymentMethod::BOLT12,
+            change_policy: None,
         }
     }
 
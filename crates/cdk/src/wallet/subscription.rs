@@ -263,7 +263,7 @@ impl Transport for SubscriptionClient {
         if !proofs.is_empty() {
             for state in self
                 .http_client
-                .post_check_state(CheckStateRequest { ys: proofs })
+                .post_check_state(CheckStateRequest::new(proofs))
                 .await
                 .map_err(|e| PubsubError::Internal(Box::new(e)))?
                 .states
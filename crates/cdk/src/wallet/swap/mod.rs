@@ -9,7 +9,9 @@ use tracing::instrument;
 use crate::amount::SplitTarget;
 use crate::fees::ProofsFeeBreakdown;
 use crate::nuts::nut00::ProofsMethods;
-use crate::nuts::{PreMintSecrets, PreSwap, Proofs, PublicKey, SpendingConditions, SwapRequest};
+use crate::nuts::{
+    PreMintSecrets, PreSwap, Proofs, PublicKey, SecretKey, SpendingConditions, SwapRequest,
+};
 use crate::{Amount, Error, Wallet};
 
 pub(crate) mod saga;
@@ -53,6 +55,7 @@ impl Wallet {
             include_fees,
             use_p2bk,
             ProofReservation::Reserve,
+            &[],
         )
         .await
     }
@@ -62,7 +65,7 @@ impl Wallet {
     /// This is intended for internal use by parent sagas (send, melt, receive)
     /// that have already reserved the proofs. Calling this on unreserved proofs
     /// bypasses the reservation safety check.
-    #[instrument(skip(self, input_proofs))]
+    #[instrument(skip(self, input_proofs, p2pk_signing_keys))]
     #[allow(clippy::too_many_arguments)]
     pub(crate) async fn swap_no_reserve(
         &self,
@@ -72,6 +75,7 @@ impl Wallet {
         spending_conditions: Option<SpendingConditions>,
         include_fees: bool,
         use_p2bk: bool,
+        p2pk_signing_keys: &[SecretKey],
     ) -> Result<Option<Proofs>, Error> {
         self.swap_internal(
             amount,
@@ -81,6 +85,7 @@ impl Wallet {
             include_fees,
             use_p2bk,
             ProofReservation::Skip,
+            p2pk_signing_keys,
         )
         .await
     }
@@ -96,10 +101,11 @@ impl Wallet {
         include_fees: bool,
         use_p2bk: bool,
         proof_reservation: ProofReservation,
+        p2pk_signing_keys: &[SecretKey],
     ) -> Result<Option<Proofs>, Error> {
         tracing::info!("Swapping");
 
-        self.retry_on_inactive_keyset(|| async {
+        self.retry_on_stale_keyset(|| async {
             let saga = SwapSaga::new(self);
             let saga = saga
                 .prepare(
@@ -111,7 +117,8 @@ impl Wallet {
                     include_fees,
                     proof_reservation,
                 )
-                .await?;
+                .await?
+                .with_p2pk_signing_keys(p2pk_signing_keys.to_vec());
             let saga = saga.execute().await?;
             Ok(saga.into_send_proofs())
         })
@@ -38,15 +38,14 @@ use tracing::instrument;
 
 use self::state::{Finalized, Initial, Prepared};
 use crate::amount::SplitTarget;
-use crate::dhke::construct_proofs;
 use crate::nuts::nut00::ProofsMethods;
-use crate::nuts::{nut10, Proofs, SpendingConditions, State};
+use crate::nuts::{nut10, Proofs, SecretKey, SpendingConditions, State};
 use crate::wallet::blind_signature::{
-    validate_mint_response_signatures, SignatureAmountValidation,
+    construct_proofs_parallel, validate_mint_response_signatures, SignatureAmountValidation,
 };
 use crate::wallet::saga::{
-    add_compensation, clear_compensations, execute_compensations, new_compensations, Compensations,
-    RevertProofReservation as RevertSwapProofReservation,
+    add_compensation, clear_compensations, execute_compensations, new_compensations,
+    record_started_saga, Compensations, RevertProofReservation as RevertSwapProofReservation,
 };
 use crate::wallet::swap::ProofReservation;
 use crate::{Amount, Error, Wallet};
@@ -169,6 +168,7 @@ impl<'a> SwapSaga<'a, Initial> {
         );
 
         self.wallet.localstore.add_saga(saga.clone()).await?;
+        record_started_saga(saga.id);
 
         // Only register compensation if we own the proof reservation.
         // When called from a parent saga (send, melt, receive) with
@@ -196,6 +196,7 @@ impl<'a> SwapSaga<'a, Initial> {
                 input_ys,
                 spending_conditions,
                 pre_swap,
+                p2pk_signing_keys: Vec::new(),
                 saga,
             },
         })
@@ -203,6 +204,12 @@ impl<'a> SwapSaga<'a, Initial> {
 }
 
 impl<'a> SwapSaga<'a, Prepared> {
+    /// Keys to sign the swap request with when the inputs are locked with SIG_ALL
+    pub fn with_p2pk_signing_keys(mut self, p2pk_signing_keys: Vec<SecretKey>) -> Self {
+        self.state_data.p2pk_signing_keys = p2pk_signing_keys;
+        self
+    }
+
     /// Execute the swap operation.
     ///
     /// Updates saga state for recovery, posts swap to mint, constructs new
@@ -218,6 +225,15 @@ impl<'a> SwapSaga<'a, Prepared> {
         let unit = &self.wallet.unit;
         let operation_id = self.state_data.operation_id;
 
+        // SIG_ALL inputs commit to the outputs, so they are signed on the final request
+        if let Err(err) = crate::wallet::util::sign_swap_sig_all(
+            &mut self.state_data.pre_swap.swap_request,
+            &self.state_data.p2pk_signing_keys,
+        ) {
+            execute_compensations(&mut self.compensations).await?;
+            return Err(err);
+        }
+
         let mut saga = self.state_data.saga.clone();
         saga.update_state(WalletSagaState::Swap(SwapSagaState::SwapRequested));
         if let OperationData::Swap(ref mut data) = saga.data {
@@ -257,12 +273,13 @@ impl<'a> SwapSaga<'a, Prepared> {
         )
         .await?;
 
-        let post_swap_proofs = construct_proofs(
+        let post_swap_proofs = construct_proofs_parallel(
             swap_response.signatures,
             self.state_data.pre_swap.pre_mint_secrets.rs(),
             self.state_data.pre_swap.pre_mint_secrets.secrets(),
             &active_keys,
-        )?;
+        )
+        .await?;
 
         let mut added_proofs = Vec::new();
         let change_proofs;
@@ -397,6 +397,7 @@ mod tests {
                 state: State::Unspent, // NOT spent - swap failed
                 witness: None,
             }],
+            next_cursor: None,
         }));
 
         let wallet = create_test_wallet_with_mock(db.clone(), mock_client).await;
@@ -8,7 +8,7 @@ use cdk_common::wallet::{KeysetLoadPolicy, WalletSaga};
 use uuid::Uuid;
 
 use crate::amount::SplitTarget;
-use crate::nuts::{PreSwap, Proofs, PublicKey, SpendingConditions};
+use crate::nuts::{PreSwap, Proofs, PublicKey, SecretKey, SpendingConditions};
 use crate::Amount;
 
 /// Initial state - operation ID assigned but no work done yet.
@@ -40,6 +40,8 @@ pub struct Prepared {
     pub spending_conditions: Option<SpendingConditions>,
     /// Pre-swap data (request and secrets)
     pub pre_swap: PreSwap,
+    /// Keys used to sign the request if the inputs are locked with SIG_ALL
+    pub p2pk_signing_keys: Vec<SecretKey>,
     /// Ephemeral key if P2BK was used
     /// The persisted saga for optimistic locking (contains recovery data)
     pub saga: WalletSaga,
@@ -1,12 +1,21 @@
-#![cfg(test)]
+//! Test utilities
+//!
+//! [`MockMintConnector`] is an in-memory [`MintConnector`] returning configured responses, so wallet
+//! flows can be unit tested without a live mint. Outside of this crate it is available with the
+//! `test-utils` feature and is passed to a wallet with [`WalletBuilder::shared_client`].
+//!
+//! [`WalletBuilder::shared_client`]: crate::wallet::WalletBuilder::shared_client
 #![allow(missing_docs)]
 #![allow(clippy::missing_panics_doc)]
+#![allow(clippy::unwrap_used)]
 
 use std::collections::BTreeMap;
 use std::str::FromStr;
 use std::sync::{Arc, Mutex};
 
+#[cfg(test)]
 use bip39::Mnemonic;
+#[cfg(test)]
 use cdk_common::database::WalletDatabase;
 use cdk_common::mint_url::MintUrl;
 use cdk_common::nut00::KnownMethod;
@@ -26,10 +35,13 @@ use crate::nuts::{
     MeltQuoteState, NUT04Settings, NUT05Settings, PaymentMethod, SecretKey, State,
 };
 use crate::secret::Secret;
-use crate::wallet::{MintConnector, Wallet};
+use crate::wallet::MintConnector;
+#[cfg(test)]
+use crate::wallet::Wallet;
 use crate::Error;
 
 /// Create test database
+#[cfg(test)]
 pub async fn create_test_db() -> Arc<dyn WalletDatabase<cdk_common::database::Error> + Send + Sync>
 {
     let db = cdk_sqlite::wallet::memory::empty().await.unwrap();
@@ -369,6 +381,7 @@ pub fn test_melt_quote() -> MeltQuote {
         payment_proof: None,
         estimated_blocks: None,
         fee_index: None,
+        options: None,
         payment_method: PaymentMethod::Known(KnownMethod::Bolt11),
         used_by_operation: None,
         version: 0,
@@ -390,6 +403,7 @@ pub fn test_mint_quote(mint_url: MintUrl) -> MintQuote {
 }
 
 /// Create a test wallet
+#[cfg(test)]
 pub async fn create_test_wallet(
     db: Arc<dyn WalletDatabase<cdk_common::database::Error> + Send + Sync>,
 ) -> Wallet {
@@ -400,6 +414,7 @@ pub async fn create_test_wallet(
 }
 
 /// Create a test wallet with a mock client
+#[cfg(test)]
 pub async fn create_test_wallet_with_mock(
     db: Arc<dyn WalletDatabase<cdk_common::database::Error> + Send + Sync>,
     mock_client: Arc<MockMintConnector>,
@@ -422,6 +437,7 @@ pub async fn create_test_wallet_with_mock(
 /// Useful for exercising subscription-driven flows (e.g. `PendingMelt::wait`)
 /// deterministically against the mock connector, which has no WebSocket
 /// endpoint.
+#[cfg(test)]
 pub async fn create_test_wallet_with_mock_http_subscription(
     db: Arc<dyn WalletDatabase<cdk_common::database::Error> + Send + Sync>,
     mock_client: Arc<MockMintConnector>,
@@ -439,17 +455,32 @@ pub async fn create_test_wallet_with_mock_http_subscription(
         .unwrap()
 }
 
-/// Mock MintConnector for testing recovery scenarios
+/// In-memory [`MintConnector`] returning configured responses
+///
+/// Keys, keysets and mint info are served from its state, unless an error was set for the next
+/// request. Other requests return the response set with the matching `set_*` or `push_*` method
+/// and panic if none was configured.
 #[derive(Debug)]
 pub struct MockMintConnector {
     /// Mock mint keyset state
     pub keysets: Mutex<Vec<KeySet>>,
     /// Mock mint info state
     pub mint_info: Mutex<MintInfo>,
+    /// Error returned by the next keys, keyset or keysets request instead of the keyset state
+    pub keys_error: Mutex<Option<Error>>,
+    /// Error returned by the next mint info request instead of the mint info state
+    pub mint_info_error: Mutex<Option<Error>>,
     /// Response for post_check_state calls
     pub check_state_response: Mutex<Option<Result<CheckStateResponse, Error>>>,
     /// Response for post_restore calls
     pub restore_response: Mutex<Option<Result<RestoreResponse, Error>>>,
+    /// Queue of responses for successive post_restore calls.
+    ///
+    /// When non-empty, each `post_restore` call pops the front entry.
+    /// Takes precedence over `restore_response`.
+    pub restore_responses: Mutex<std::collections::VecDeque<Result<RestoreResponse, Error>>>,
+    /// Captured post_restore requests for test verification.
+    pub captured_restore_requests: Mutex<Vec<RestoreRequest>>,
     /// Response for get_melt_quote_status calls
     pub melt_quote_status_response: Mutex<Option<Result<MeltQuoteBolt11Response<String>, Error>>>,
     /// Queue of responses for successive get_melt_quote_status calls.
@@ -460,6 +491,15 @@ pub struct MockMintConnector {
     /// followed by an authoritative HTTP recheck).
     pub melt_quote_status_responses:
         Mutex<std::collections::VecDeque<Result<MeltQuoteBolt11Response<String>, Error>>>,
+    /// Response for post_mint_quote calls
+    pub mint_quote_response: Mutex<Option<Result<MintQuoteResponse<String>, Error>>>,
+    /// Response for get_mint_quote_status calls
+    pub mint_quote_status_response: Mutex<Option<Result<MintQuoteResponse<String>, Error>>>,
+    /// Response for post_batch_check_mint_quote_status calls
+    pub batch_mint_quote_status_response:
+        Mutex<Option<Result<Vec<MintQuoteResponse<String>>, Error>>>,
+    /// Response for post_melt_quote calls
+    pub melt_quote_response: Mutex<Option<Result<MeltQuoteCreateResponse<String>, Error>>>,
     /// Response for post_mint calls
     pub post_mint_response: Mutex<Option<Result<MintResponse, Error>>>,
     /// Queue of responses for successive post_mint calls.
@@ -492,6 +532,8 @@ pub struct MockMintConnector {
     /// Response for DNS TXT resolution calls
     #[cfg(all(feature = "bip353", not(target_arch = "wasm32")))]
     pub dns_txt_response: Mutex<Option<Result<Vec<String>, Error>>>,
+    /// Connector returned for other mints by `mint_connector`
+    pub other_mint_connector: Mutex<Option<Arc<MockMintConnector>>>,
 }
 
 impl Default for MockMintConnector {
@@ -508,10 +550,18 @@ impl MockMintConnector {
         Self {
             keysets: Mutex::new(vec![keyset]),
             mint_info: Mutex::new(mint_info),
+            keys_error: Mutex::new(None),
+            mint_info_error: Mutex::new(None),
             check_state_response: Mutex::new(None),
             restore_response: Mutex::new(None),
+            restore_responses: Mutex::new(std::collections::VecDeque::new()),
+            captured_restore_requests: Mutex::new(Vec::new()),
             melt_quote_status_response: Mutex::new(None),
             melt_quote_status_responses: Mutex::new(std::collections::VecDeque::new()),
+            mint_quote_response: Mutex::new(None),
+            mint_quote_status_response: Mutex::new(None),
+            batch_mint_quote_status_response: Mutex::new(None),
+            melt_quote_response: Mutex::new(None),
             post_mint_response: Mutex::new(None),
             post_mint_responses: Mutex::new(std::collections::VecDeque::new()),
             post_mint_requests: Mutex::new(Vec::new()),
@@ -526,6 +576,7 @@ impl MockMintConnector {
             lnurl_invoice_response: Mutex::new(None),
             #[cfg(all(feature = "bip353", not(target_arch = "wasm32")))]
             dns_txt_response: Mutex::new(None),
+            other_mint_connector: Mutex::new(None),
         }
     }
 
@@ -538,7 +589,7 @@ impl MockMintConnector {
             Ok(keysets) => {
                 *self.keysets.lock().unwrap() = keysets;
             }
-            Err(_) => unimplemented!("error responses for key state are not supported"),
+            Err(err) => *self.keys_error.lock().unwrap() = Some(err),
         }
     }
 
@@ -552,7 +603,7 @@ impl MockMintConnector {
                     keysets.push(keyset);
                 }
             }
-            Err(_) => unimplemented!("error responses for key state are not supported"),
+            Err(err) => *self.keys_error.lock().unwrap() = Some(err),
         }
     }
 
@@ -569,14 +620,14 @@ impl MockMintConnector {
                     }
                 }
             }
-            Err(_) => unimplemented!("error responses for key state are not supported"),
+            Err(err) => *self.keys_error.lock().unwrap() = Some(err),
         }
     }
 
     pub fn set_mint_info_response(&self, response: Result<MintInfo, Error>) {
         match response {
             Ok(mint_info) => *self.mint_info.lock().unwrap() = mint_info,
-            Err(_) => unimplemented!("error responses for mint info state are not supported"),
+            Err(err) => *self.mint_info_error.lock().unwrap() = Some(err),
         }
     }
 
@@ -593,6 +644,16 @@ impl MockMintConnector {
         *self.restore_response.lock().unwrap() = Some(response);
     }
 
+    /// Enqueue a response for the next `post_restore` call.
+    pub fn push_restore_response(&self, response: Result<RestoreResponse, Error>) {
+        self.restore_responses.lock().unwrap().push_back(response);
+    }
+
+    /// Get all captured restore requests.
+    pub fn captured_restore_requests(&self) -> Vec<RestoreRequest> {
+        self.captured_restore_requests.lock().unwrap().clone()
+    }
+
     pub fn set_melt_quote_status_response(
         &self,
         response: Result<MeltQuoteBolt11Response<String>, Error>,
@@ -614,6 +675,31 @@ impl MockMintConnector {
             .push_back(response);
     }
 
+    pub fn set_mint_quote_response(&self, response: Result<MintQuoteResponse<String>, Error>) {
+        *self.mint_quote_response.lock().unwrap() = Some(response);
+    }
+
+    pub fn set_mint_quote_status_response(
+        &self,
+        response: Result<MintQuoteResponse<String>, Error>,
+    ) {
+        *self.mint_quote_status_response.lock().unwrap() = Some(response);
+    }
+
+    pub fn set_batch_mint_quote_status_response(
+        &self,
+        response: Result<Vec<MintQuoteResponse<String>>, Error>,
+    ) {
+        *self.batch_mint_quote_status_response.lock().unwrap() = Some(response);
+    }
+
+    pub fn set_melt_quote_response(
+        &self,
+        response: Result<MeltQuoteCreateResponse<String>, Error>,
+    ) {
+        *self.melt_quote_response.lock().unwrap() = Some(response);
+    }
+
     pub fn set_post_mint_response(&self, response: Result<MintResponse, Error>) {
         *self.post_mint_response.lock().unwrap() = Some(response);
     }
@@ -681,11 +767,23 @@ impl MockMintConnector {
     pub fn set_dns_txt_response(&self, response: Result<Vec<String>, Error>) {
         *self.dns_txt_response.lock().unwrap() = Some(response);
     }
+
+    /// Serve requests to other mints, e.g. the mint of a received token, with `connector`
+    pub fn set_other_mint_connector(&self, connector: Arc<MockMintConnector>) {
+        *self.other_mint_connector.lock().unwrap() = Some(connector);
+    }
 }
 
 #[cfg_attr(target_arch = "wasm32", async_trait::async_trait(?Send))]
 #[cfg_attr(not(target_arch = "wasm32"), async_trait::async_trait)]
 impl MintConnector for MockMintConnector {
+    fn mint_connector(&self, mint_url: MintUrl) -> Arc<dyn MintConnector + Send + Sync> {
+        match self.other_mint_connector.lock().unwrap().clone() {
+            Some(connector) => connector,
+            None => Arc::new(crate::wallet::HttpClient::new(mint_url, None)),
+        }
+    }
+
     #[cfg(all(feature = "bip353", not(target_arch = "wasm32")))]
     async fn resolve_dns_txt(&self, _domain: &str) -> Result<Vec<String>, Error> {
         self.dns_txt_response
@@ -718,10 +816,18 @@ impl MintConnector for MockMintConnector {
     }
 
     async fn get_mint_keys(&self) -> Result<Vec<crate::nuts::KeySet>, Error> {
+        if let Some(err) = self.keys_error.lock().unwrap().take() {
+            return Err(err);
+        }
+
         Ok(self.keysets.lock().unwrap().clone())
     }
 
     async fn get_mint_keyset(&self, keyset_id: Id) -> Result<crate::nuts::KeySet, Error> {
+        if let Some(err) = self.keys_error.lock().unwrap().take() {
+            return Err(err);
+        }
+
         self.keysets
             .lock()
             .unwrap()
@@ -732,6 +838,10 @@ impl MintConnector for MockMintConnector {
     }
 
     async fn get_mint_keysets(&self) -> Result<KeysetResponse, Error> {
+        if let Some(err) = self.keys_error.lock().unwrap().take() {
+            return Err(err);
+        }
+
         let keysets = self.keysets.lock().unwrap();
 
         Ok(KeysetResponse {
@@ -752,7 +862,11 @@ impl MintConnector for MockMintConnector {
         &self,
         _request: MintQuoteRequest,
     ) -> Result<MintQuoteResponse<String>, Error> {
-        unimplemented!()
+        self.mint_quote_response
+            .lock()
+            .unwrap()
+            .take()
+            .expect("MockMintConnector: post_mint_quote called without configured response")
     }
 
     async fn get_mint_quote_status(
@@ -760,7 +874,11 @@ impl MintConnector for MockMintConnector {
         _method: PaymentMethod,
         _quote_id: &str,
     ) -> Result<MintQuoteResponse<String>, Error> {
-        unimplemented!()
+        self.mint_quote_status_response
+            .lock()
+            .unwrap()
+            .take()
+            .expect("MockMintConnector: get_mint_quote_status called without configured response")
     }
 
     async fn post_mint(
@@ -789,7 +907,11 @@ impl MintConnector for MockMintConnector {
         &self,
         _request: MeltQuoteRequest,
     ) -> Result<MeltQuoteCreateResponse<String>, Error> {
-        unimplemented!()
+        self.melt_quote_response
+            .lock()
+            .unwrap()
+            .take()
+            .expect("MockMintConnector: post_melt_quote called without configured response")
     }
 
     async fn get_melt_quote_status(
@@ -827,6 +949,10 @@ impl MintConnector for MockMintConnector {
     }
 
     async fn get_mint_info(&self) -> Result<crate::nuts::MintInfo, Error> {
+        if let Some(err) = self.mint_info_error.lock().unwrap().take() {
+            return Err(err);
+        }
+
         Ok(self.mint_info.lock().unwrap().clone())
     }
 
@@ -841,12 +967,18 @@ impl MintConnector for MockMintConnector {
             .expect("MockMintConnector: post_check_state called without configured response")
     }
 
-    async fn post_restore(&self, _request: RestoreRequest) -> Result<RestoreResponse, Error> {
-        self.restore_response
-            .lock()
-            .unwrap()
-            .take()
-            .expect("MockMintConnector: post_restore called without configured response")
+    async fn post_restore(&self, request: RestoreRequest) -> Result<RestoreResponse, Error> {
+        self.captured_restore_requests.lock().unwrap().push(request);
+        let queued = self.restore_responses.lock().unwrap().pop_front();
+        match queued {
+            Some(response) => response,
+            None => self
+                .restore_response
+                .lock()
+                .unwrap()
+                .take()
+                .expect("MockMintConnector: post_restore called without configured response"),
+        }
     }
 
     async fn get_auth_wallet(&self) -> Option<crate::wallet::AuthWallet> {
@@ -873,7 +1005,13 @@ impl MintConnector for MockMintConnector {
         _method: &PaymentMethod,
         _request: BatchCheckMintQuoteRequest<String>,
     ) -> Result<Vec<MintQuoteResponse<String>>, Error> {
-        unimplemented!()
+        self.batch_mint_quote_status_response
+            .lock()
+            .unwrap()
+            .take()
+            .expect(
+                "MockMintConnector: post_batch_check_mint_quote_status called without configured response",
+            )
     }
 
     async fn post_batch_mint(
@@ -893,3 +1031,35 @@ impl MintConnector for MockMintConnector {
             .expect("MockMintConnector: post_batch_mint called without configured response")
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_mock_as_connector_trait_object() {
+        let mock = Arc::new(MockMintConnector::new());
+        let connector: Arc<dyn MintConnector + Send + Sync> = mock.clone();
+
+        let keysets = connector.get_mint_keysets().await.unwrap();
+        assert_eq!(keysets.keysets.len(), 1);
+        assert_eq!(keysets.keysets[0].id, test_keyset_id());
+
+        mock.set_check_state_response(Err(Error::UnknownQuote));
+        let request = CheckStateRequest::new(Vec::new());
+        assert!(connector.post_check_state(request).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_mock_returns_error_for_next_state_request() {
+        let mock = MockMintConnector::new();
+
+        mock.set_mint_keysets_response(Err(Error::UnknownKeySet));
+        assert!(mock.get_mint_keysets().await.is_err());
+        assert!(mock.get_mint_keysets().await.is_ok());
+
+        mock.set_mint_info_response(Err(Error::CouldNotGetMintInfo));
+        assert!(mock.get_mint_info().await.is_err());
+        assert!(mock.get_mint_info().await.is_ok());
+    }
+}
@@ -1,8 +1,148 @@
+use std::fmt;
+use std::ops::Range;
+use std::str::FromStr;
+
+use cdk_common::mint_url::MintUrl;
+use cdk_common::nuts::CurrencyUnit;
 use cdk_common::wallet::{Transaction, TransactionDirection, TransactionId};
-use cdk_common::Proofs;
+use cdk_common::{PaymentMethod, Proofs};
+use futures::{stream, Stream};
+use serde::Serialize;
 
 use crate::{Error, Wallet};
 
+/// Number of transactions loaded from the database per exported chunk
+const EXPORT_PAGE_SIZE: u64 = 500;
+
+/// Format of a transaction export
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum TransactionExportFormat {
+    /// One transaction per row with a header line
+    #[default]
+    Csv,
+    /// A JSON array of transaction objects
+    Json,
+}
+
+impl FromStr for TransactionExportFormat {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "csv" => Ok(Self::Csv),
+            "json" => Ok(Self::Json),
+            other => Err(Error::Custom(format!("Unknown export format: {other}"))),
+        }
+    }
+}
+
+impl fmt::Display for TransactionExportFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Csv => write!(f, "csv"),
+            Self::Json => write!(f, "json"),
+        }
+    }
+}
+
+/// Exported fields of a [`Transaction`]
+#[derive(Serialize)]
+struct ExportedTransaction<'a> {
+    id: String,
+    timestamp: u64,
+    direction: TransactionDirection,
+    amount: u64,
+    fee: u64,
+    unit: &'a CurrencyUnit,
+    mint_url: &'a MintUrl,
+    memo: Option<&'a str>,
+    quote_id: Option<&'a str>,
+    payment_method: Option<&'a PaymentMethod>,
+}
+
+impl<'a> From<&'a Transaction> for ExportedTransaction<'a> {
+    fn from(transaction: &'a Transaction) -> Self {
+        Self {
+            id: transaction.id().to_string(),
+            timestamp: transaction.timestamp,
+            direction: transaction.direction,
+            amount: transaction.amount.into(),
+            fee: transaction.fee.into(),
+            unit: &transaction.unit,
+            mint_url: &transaction.mint_url,
+            memo: transaction.memo.as_deref(),
+            quote_id: transaction.quote_id.as_deref(),
+            payment_method: transaction.payment_method.as_ref(),
+        }
+    }
+}
+
+impl TransactionExportFormat {
+    fn header(&self) -> &'static str {
+        match self {
+            Self::Csv => {
+                "id,timestamp,direction,amount,fee,unit,mint_url,memo,quote_id,payment_method\n"
+            }
+            Self::Json => "[",
+        }
+    }
+
+    fn footer(&self) -> &'static str {
+        match self {
+            Self::Csv => "",
+            Self::Json => "]\n",
+        }
+    }
+
+    fn push_record(
+        &self,
+        out: &mut String,
+        transaction: &Transaction,
+        first: bool,
+    ) -> Result<(), Error> {
+        let record = ExportedTransaction::from(transaction);
+
+        match self {
+            Self::Csv => {
+                let fields = [
+                    record.id,
+                    record.timestamp.to_string(),
+                    record.direction.to_string(),
+                    record.amount.to_string(),
+                    record.fee.to_string(),
+                    record.unit.to_string(),
+                    record.mint_url.to_string(),
+                    record.memo.map(csv_field).unwrap_or_default(),
+                    record.quote_id.map(csv_field).unwrap_or_default(),
+                    record
+                        .payment_method
+                        .map(|method| method.to_string())
+                        .unwrap_or_default(),
+                ];
+                out.push_str(&fields.join(","));
+                out.push('\n');
+            }
+            Self::Json => {
+                if !first {
+                    out.push(',');
+                }
+                out.push_str(&serde_json::to_string(&record)?);
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Quote a CSV field if it contains a separator, quote or line break
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
 impl Wallet {
     fn transaction_matches_wallet(&self, transaction: &Transaction) -> bool {
         transaction.matches_conditions(
@@ -31,6 +171,54 @@ impl Wallet {
         Ok(transactions)
     }
 
+    /// Export the transactions of this wallet with a timestamp in `range`
+    ///
+    /// Transactions are read from the database one page at a time and yielded as
+    /// chunks of the export, oldest first, so the full history is never held in
+    /// memory. Concatenating all chunks gives the complete CSV or JSON document.
+    pub fn export_transactions(
+        &self,
+        format: TransactionExportFormat,
+        range: Range<u64>,
+    ) -> impl Stream<Item = Result<String, Error>> + '_ {
+        stream::try_unfold(Some(0), move |offset| {
+            let range = range.clone();
+            async move {
+                let Some(offset) = offset else {
+                    return Ok(None);
+                };
+
+                let page = self
+                    .localstore
+                    .list_transactions_page(
+                        self.mint_url.clone(),
+                        self.unit.clone(),
+                        range.start,
+                        range.end,
+                        offset,
+                        EXPORT_PAGE_SIZE,
+                    )
+                    .await?;
+                let is_last = (page.len() as u64) < EXPORT_PAGE_SIZE;
+
+                let mut chunk = String::new();
+                if offset == 0 {
+                    chunk.push_str(format.header());
+                }
+                for (index, transaction) in page.iter().enumerate() {
+                    format.push_record(&mut chunk, transaction, offset == 0 && index == 0)?;
+                }
+                if is_last {
+                    chunk.push_str(format.footer());
+                }
+
+                let next_offset = (!is_last).then_some(offset + page.len() as u64);
+
+                Ok(Some((chunk, next_offset)))
+            }
+        })
+    }
+
     /// Get transaction by ID
     pub async fn get_transaction(&self, id: TransactionId) -> Result<Option<Transaction>, Error> {
         let transaction = self.localstore.get_transaction(id).await?;
@@ -130,11 +318,90 @@ mod tests {
     use cdk_common::nuts::{CurrencyUnit, State};
     use cdk_common::wallet::{ProofInfo, Transaction, TransactionDirection};
     use cdk_common::Amount;
+    use futures::TryStreamExt;
 
+    use super::TransactionExportFormat;
     use crate::wallet::test_utils::{
-        create_test_db, create_test_wallet, test_keyset_id, test_proof,
+        create_test_db, create_test_wallet, test_keyset_id, test_mint_url, test_proof,
     };
 
+    fn test_transaction(amount: u64, timestamp: u64, memo: Option<&str>) -> Transaction {
+        Transaction {
+            mint_url: test_mint_url(),
+            direction: TransactionDirection::Incoming,
+            amount: Amount::from(amount),
+            fee: Amount::from(1_u64),
+            unit: CurrencyUnit::Sat,
+            ys: vec![test_proof(test_keyset_id(), amount).y().unwrap()],
+            timestamp,
+            memo: memo.map(str::to_string),
+            metadata: HashMap::new(),
+            quote_id: None,
+            payment_request: None,
+            payment_proof: None,
+            payment_method: None,
+            saga_id: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn export_transactions_csv_filters_range_and_escapes_memo() {
+        let db = create_test_db().await;
+        let wallet = create_test_wallet(db.clone()).await;
+
+        let inside = test_transaction(8, 200, Some("rent, \"march\""));
+        let inside_id = inside.id();
+        db.add_transaction(test_transaction(4, 100, None))
+            .await
+            .unwrap();
+        db.add_transaction(inside).await.unwrap();
+        db.add_transaction(test_transaction(16, 300, None))
+            .await
+            .unwrap();
+
+        let export: Vec<String> = wallet
+            .export_transactions(TransactionExportFormat::Csv, 150..300)
+            .try_collect()
+            .await
+            .unwrap();
+
+        assert_eq!(
+            export.concat(),
+            format!(
+                "id,timestamp,direction,amount,fee,unit,mint_url,memo,quote_id,payment_method\n\
+                 {inside_id},200,Incoming,8,1,sat,https://test-mint.example.com,\"rent, \"\"march\"\"\",,\n"
+            )
+        );
+    }
+
+    #[tokio::test]
+    async fn export_transactions_json_pages_through_all_transactions() {
+        let db = create_test_db().await;
+        let wallet = create_test_wallet(db.clone()).await;
+
+        let count = super::EXPORT_PAGE_SIZE + 2;
+        for timestamp in 0..count {
+            db.add_transaction(test_transaction(timestamp + 1, timestamp, None))
+                .await
+                .unwrap();
+        }
+
+        let export: Vec<String> = wallet
+            .export_transactions(TransactionExportFormat::Json, 0..u64::MAX)
+            .try_collect()
+            .await
+            .unwrap();
+        assert_eq!(export.len(), 2);
+
+        let exported: Vec<serde_json::Value> = serde_json::from_str(&export.concat()).unwrap();
+        assert_eq!(exported.len() as u64, count);
+        assert_eq!(exported[0]["timestamp"], 0);
+        assert_eq!(exported[0]["direction"], "Incoming");
+        assert_eq!(exported[0]["amount"], 1);
+        assert_eq!(exported[0]["unit"], "sat");
+        assert_eq!(exported[count as usize - 1]["timestamp"], count - 1);
+    }
+
     #[tokio::test]
     async fn get_proofs_for_transaction_does_not_leak_other_mints_proofs() {
         let db = create_test_db().await;
@@ -1,13 +1,17 @@
 //! Wallet Utility Functions
 
 use std::collections::HashMap;
+use std::future::Future;
 use std::str::FromStr;
 
 use bitcoin::XOnlyPublicKey;
+use cdk_common::wallet::MeltQuote;
 
-use crate::nuts::nut10::Kind;
-use crate::nuts::{Conditions, Proof, Proofs, PublicKey, SecretKey};
-use crate::{Error, SECP256K1};
+use crate::nuts::nut10::{Kind, SpendingConditionVerification};
+use crate::nuts::{
+    Conditions, MeltRequest, Proof, Proofs, PublicKey, SecretKey, SigFlag, SwapRequest,
+};
+use crate::{Amount, Error, SECP256K1};
 
 /// Returns `true` if the proof has a P2PK (NUT-11) spending condition.
 ///
@@ -72,6 +76,103 @@ pub(crate) fn collect_p2pk_pubkeys(proofs: &Proofs) -> Result<Vec<PublicKey>, Er
     Ok(result)
 }
 
+/// Returns `true` if the proof has a NUT-10 secret with the SIG_ALL flag.
+pub(crate) fn is_sig_all(proof: &Proof) -> bool {
+    let Ok(secret) = <crate::secret::Secret as TryInto<crate::nuts::nut10::Secret>>::try_into(
+        proof.secret.clone(),
+    ) else {
+        return false;
+    };
+
+    let conditions: Result<Conditions, _> = secret
+        .secret_data()
+        .tags()
+        .cloned()
+        .unwrap_or_default()
+        .try_into();
+
+    conditions.is_ok_and(|conditions| conditions.sig_flag == SigFlag::SigAll)
+}
+
+/// Keys from `key_map` that can sign for the proof, with P2BK keys already derived.
+///
+/// - P2PK: the data key (slot 0) and any condition keys (slots 1+)
+/// - HTLC: condition keys (slots 1+) only
+fn proof_signing_keys(
+    proof: &Proof,
+    key_map: &HashMap<XOnlyPublicKey, &SecretKey>,
+) -> Result<Vec<SecretKey>, Error> {
+    let mut keys = Vec::new();
+
+    let Ok(secret) = <crate::secret::Secret as TryInto<crate::nuts::nut10::Secret>>::try_into(
+        proof.secret.clone(),
+    ) else {
+        return Ok(keys);
+    };
+
+    let conditions: Result<Conditions, _> = secret
+        .secret_data()
+        .tags()
+        .cloned()
+        .unwrap_or_default()
+        .try_into();
+
+    let Ok(conditions) = conditions else {
+        return Ok(keys);
+    };
+
+    let mut pubkeys = Vec::new();
+
+    match secret.kind() {
+        Kind::P2PK => {
+            let data_key = PublicKey::from_str(secret.secret_data().data())?;
+            pubkeys.push(data_key);
+        }
+        Kind::HTLC => {
+            // HTLC slot 0 is a hash, not a pubkey.
+            // Condition keys (slots 1+) may still need signing.
+            // Preimage injection is handled separately by the caller.
+        }
+    }
+
+    if let Some(mut cond_pubkeys) = conditions.pubkeys {
+        pubkeys.append(&mut cond_pubkeys);
+    }
+    if let Some(mut refund_keys) = conditions.refund_keys {
+        pubkeys.append(&mut refund_keys);
+    }
+
+    for (i, pubkey) in pubkeys.iter().enumerate() {
+        let slot = match secret.kind() {
+            Kind::P2PK => i as u8,
+            Kind::HTLC => (i + 1) as u8,
+        };
+        if let Some(ephemeral_key) = proof.p2pk_e {
+            for signing_key in key_map.values() {
+                if let Ok(r) = crate::nuts::nut28::ecdh_kdf(signing_key, &ephemeral_key, slot) {
+                    if let Ok(derived_key) =
+                        crate::nuts::nut28::derive_signing_key_bip340(signing_key, &r, pubkey)
+                    {
+                        keys.push(derived_key);
+                        break;
+                    }
+                }
+            }
+        } else if let Some(signing) = key_map.get(&pubkey.x_only_public_key()) {
+            keys.push((*signing).clone());
+        }
+    }
+
+    Ok(keys)
+}
+
+fn signing_key_map(p2pk_signing_keys: &[SecretKey]) -> HashMap<XOnlyPublicKey, &SecretKey> {
+    p2pk_signing_keys
+        .iter()
+        .map(|s| (s.x_only_public_key(&SECP256K1).0, s))
+        .collect()
+}
+
 /// Sign P2PK-locked proofs using the provided signing keys.
 ///
 /// For each proof with a recognised NUT-10 secret:
@@ -79,6 +180,7 @@ pub(crate) fn collect_p2pk_pubkeys(proofs: &Proofs) -> Result<Vec<PublicKey>, Er
 /// - HTLC: signs condition keys (slots 1+) only; preimage injection is the caller's responsibility
 ///
 /// Proofs without a NUT-10 secret, or with no matching signing key, are left unchanged.
+/// SIG_ALL proofs are skipped, they are signed on the swap request with [`sign_swap_sig_all`].
 pub(crate) fn sign_proofs(
     proofs: &mut Proofs,
     p2pk_signing_keys: &[SecretKey],
@@ -87,75 +189,122 @@ pub(crate) fn sign_proofs(
         return Ok(());
     }
 
-    let key_map: HashMap<XOnlyPublicKey, &SecretKey> = p2pk_signing_keys
-        .iter()
-        .map(|s| (s.x_only_public_key(&SECP256K1).0, s))
-        .collect();
+    let key_map = signing_key_map(p2pk_signing_keys);
 
     for proof in proofs.iter_mut() {
-        let Ok(secret) = <crate::secret::Secret as TryInto<crate::nuts::nut10::Secret>>::try_into(
-            proof.secret.clone(),
-        ) else {
+        if is_sig_all(proof) {
             continue;
-        };
+        }
 
-        let conditions: Result<Conditions, _> = secret
-            .secret_data()
-            .tags()
-            .cloned()
-            .unwrap_or_default()
-            .try_into();
+        for signing_key in proof_signing_keys(proof, &key_map)? {
+            proof.sign_p2pk(signing_key)?;
+        }
+    }
 
-        let Ok(conditions) = conditions else {
-            continue;
-        };
+    Ok(())
+}
 
-        let mut pubkeys = Vec::new();
+/// Keys that sign the SIG_ALL message of a request spending `inputs`.
+///
+/// Returns `None` if no input is locked with SIG_ALL. The mint requires all SIG_ALL inputs to
+/// share the conditions of the first one, so its keys sign for the whole request.
+fn sig_all_signing_keys(
+    inputs: &Proofs,
+    p2pk_signing_keys: &[SecretKey],
+) -> Result<Option<Vec<SecretKey>>, Error> {
+    let Some(first_input) = inputs.first() else {
+        return Ok(None);
+    };
 
-        match secret.kind() {
-            Kind::P2PK => {
-                let data_key = PublicKey::from_str(secret.secret_data().data())?;
-                pubkeys.push(data_key);
-            }
-            Kind::HTLC => {
-                // HTLC slot 0 is a hash, not a pubkey.
-                // Condition keys (slots 1+) may still need signing.
-                // Preimage injection is handled separately by the caller.
-            }
-        }
+    if !inputs.iter().any(is_sig_all) {
+        return Ok(None);
+    }
 
-        if let Some(mut cond_pubkeys) = conditions.pubkeys {
-            pubkeys.append(&mut cond_pubkeys);
-        }
-        if let Some(mut refund_keys) = conditions.refund_keys {
-            pubkeys.append(&mut refund_keys);
-        }
+    let key_map = signing_key_map(p2pk_signing_keys);
+    proof_signing_keys(first_input, &key_map).map(Some)
+}
 
-        for (i, pubkey) in pubkeys.iter().enumerate() {
-            let slot = match secret.kind() {
-                Kind::P2PK => i as u8,
-                Kind::HTLC => (i + 1) as u8,
-            };
-            if let Some(ephemeral_key) = proof.p2pk_e {
-                for signing_key in key_map.values() {
-                    if let Ok(r) = crate::nuts::nut28::ecdh_kdf(signing_key, &ephemeral_key, slot) {
-                        if let Ok(derived_key) =
-                            crate::nuts::nut28::derive_signing_key_bip340(signing_key, &r, pubkey)
-                        {
-                            proof.sign_p2pk(derived_key)?;
-                            break;
-                        }
-                    }
-                }
-            } else if let Some(signing) = key_map.get(&pubkey.x_only_public_key()) {
-                proof.sign_p2pk((*signing).clone())?;
-            }
-        }
+/// Sign a swap request whose inputs are locked with SIG_ALL.
+///
+/// The signatures commit to all inputs and outputs, so this must run after the outputs
+/// are final. The request is verified afterwards so an unsatisfied lock fails before it
+/// reaches the mint. Requests without SIG_ALL inputs are left unchanged.
+pub(crate) fn sign_swap_sig_all(
+    swap_request: &mut SwapRequest,
+    p2pk_signing_keys: &[SecretKey],
+) -> Result<(), Error> {
+    let Some(signing_keys) = sig_all_signing_keys(swap_request.inputs(), p2pk_signing_keys)? else {
+        return Ok(());
+    };
+
+    for signing_key in signing_keys {
+        swap_request.sign_sig_all(signing_key)?;
+    }
+
+    swap_request.verify_spending_conditions()?;
+
+    Ok(())
+}
+
+/// Sign a melt request whose inputs are locked with SIG_ALL.
+///
+/// Like [`sign_swap_sig_all`], the signatures commit to the quote, the inputs and the change
+/// outputs, so this must run on the final request.
+pub(crate) fn sign_melt_sig_all(
+    melt_request: &mut MeltRequest<String>,
+    p2pk_signing_keys: &[SecretKey],
+) -> Result<(), Error> {
+    let Some(signing_keys) = sig_all_signing_keys(melt_request.inputs(), p2pk_signing_keys)? else {
+        return Ok(());
+    };
+
+    for signing_key in signing_keys {
+        melt_request.sign_sig_all(signing_key)?;
     }
 
+    melt_request.verify_spending_conditions()?;
+
     Ok(())
 }
 
+/// Quote a Lightning payment of `amount` whose melt, fee reserve included, fits into `available`
+///
+/// `quote` requests a payment of the given amount and returns the melt quote paying it, along with
+/// whatever the caller keeps, e.g. the mint quote being paid. The fee reserve is only known once
+/// quoted, so a quote that does not fit is retried once with the amount lowered by the reserve.
+/// Returns `None` if the lowered amount is below `min_amount` or still does not fit.
+pub(crate) async fn quote_within_fee_reserve<T, F, Fut>(
+    amount: Amount,
+    available: Amount,
+    min_amount: Amount,
+    mut quote: F,
+) -> Result<Option<(T, MeltQuote)>, Error>
+where
+    F: FnMut(Amount) -> Fut,
+    Fut: Future<Output = Result<(T, MeltQuote), Error>>,
+{
+    let mut amount = amount;
+    for _ in 0..2 {
+        let (quoted, melt_quote) = quote(amount).await?;
+
+        let required = melt_quote
+            .amount
+            .checked_add(melt_quote.fee_reserve)
+            .ok_or(Error::AmountOverflow)?;
+
+        if required <= available {
+            return Ok(Some((quoted, melt_quote)));
+        }
+
+        amount = match amount.checked_sub(melt_quote.fee_reserve) {
+            Some(amount) if amount >= min_amount => amount,
+            _ => break,
+        };
+    }
+
+    Ok(None)
+}
+
 /// Extract token from text
 pub fn token_from_text(text: &str) -> Option<&str> {
     let text = text.trim();
@@ -242,6 +391,86 @@ mod tests {
         assert!(proofs[0].witness.is_none());
     }
 
+    #[test]
+    fn sign_swap_sig_all_signs_request_instead_of_inputs() {
+        let secret_key = SecretKey::generate();
+        let conditions = Conditions {
+            sig_flag: SigFlag::SigAll,
+            ..Default::default()
+        };
+        let secret: crate::secret::Secret =
+            SpendingConditions::new_p2pk(secret_key.public_key(), Some(conditions))
+                .try_into()
+                .unwrap();
+        let keyset_id = Id::from_str("00916bbf7ef91a36").unwrap();
+        let mut proofs = vec![Proof::new(
+            Amount::from(1),
+            keyset_id,
+            secret,
+            SecretKey::generate().public_key(),
+        )];
+
+        sign_proofs(&mut proofs, std::slice::from_ref(&secret_key)).unwrap();
+        assert!(proofs[0].witness.is_none());
+
+        let output = crate::nuts::BlindedMessage::new(
+            Amount::from(1),
+            keyset_id,
+            SecretKey::generate().public_key(),
+        );
+        let mut swap_request = SwapRequest::new(proofs, vec![output]);
+        assert!(sign_swap_sig_all(&mut swap_request, &[SecretKey::generate()]).is_err());
+
+        sign_swap_sig_all(&mut swap_request, &[secret_key]).unwrap();
+        assert!(swap_request.verify_spending_conditions().is_ok());
+    }
+
+    fn melt_quote(amount: Amount, fee_reserve: u64) -> MeltQuote {
+        let mut quote = crate::wallet::test_utils::test_melt_quote();
+        quote.amount = amount;
+        quote.fee_reserve = Amount::from(fee_reserve);
+        quote
+    }
+
+    #[tokio::test]
+    async fn quote_within_fee_reserve_lowers_amount_by_reserve_once() {
+        let mut requested = Vec::new();
+        let quoted = quote_within_fee_reserve(
+            Amount::from(100),
+            Amount::from(100),
+            Amount::ONE,
+            |amount| {
+                requested.push(amount);
+                async move { Ok::<_, Error>(((), melt_quote(amount, 4))) }
+            },
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(requested, vec![Amount::from(100), Amount::from(96)]);
+        let ((), quote) = quoted.unwrap();
+        assert_eq!(quote.amount, Amount::from(96));
+    }
+
+    #[tokio::test]
+    async fn quote_within_fee_reserve_gives_up_below_min_amount() {
+        let mut requested = Vec::new();
+        let quoted = quote_within_fee_reserve(
+            Amount::from(10),
+            Amount::from(10),
+            Amount::from(8),
+            |amount| {
+                requested.push(amount);
+                async move { Ok::<_, Error>(((), melt_quote(amount, 4))) }
+            },
+        )
+        .await
+        .unwrap();
+
+        assert!(quoted.is_none());
+        assert_eq!(requested, vec![Amount::from(10)]);
+    }
+
     #[test]
     fn test_token_from_text() {
         let text = " Here is some ecash: cashuAeyJ0b2tlbiI6W3sicHJvb2ZzIjpbeyJhbW91bnQiOjIsInNlY3JldCI6ImI2Zjk1ODIxYmZlNjUyYjYwZGQ2ZjYwMDU4N2UyZjNhOTk4MzVhMGMyNWI4MTQzODNlYWIwY2QzOWFiNDFjNzUiLCJDIjoiMDI1YWU4ZGEyOTY2Y2E5OGVmYjA5ZDcwOGMxM2FiZmEwZDkxNGUwYTk3OTE4MmFjMzQ4MDllMjYxODY5YTBhNDJlIiwicmVzZXJ2ZWQiOmZhbHNlLCJpZCI6IjAwOWExZjI5MzI1M2U0MWUifSx7ImFtb3VudCI6Miwic2VjcmV0IjoiZjU0Y2JjNmNhZWZmYTY5MTUyOTgyM2M1MjU1MDkwYjRhMDZjNGQ3ZDRjNzNhNDFlZTFkNDBlM2ExY2EzZGZhNyIsIkMiOiIwMjMyMTIzN2JlYjcyMWU3NGI1NzcwNWE5MjJjNjUxMGQwOTYyYzAzNzlhZDM0OTJhMDYwMDliZTAyNjA5ZjA3NTAiLCJyZXNlcnZlZCI6ZmFsc2UsImlkIjoiMDA5YTFmMjkzMjUzZTQxZSJ9LHsiYW1vdW50IjoxLCJzZWNyZXQiOiJhNzdhM2NjODY4YWM4ZGU3YmNiOWMxMzJmZWI3YzEzMDY4Nzg3ODk5Yzk3YTk2NWE2ZThkZTFiMzliMmQ2NmQ3IiwiQyI6IjAzMTY0YTMxNWVhNjM0NGE5NWI2NzM1NzBkYzg0YmZlMTQ2NDhmMTQwM2EwMDJiZmJlMDhlNWFhMWE0NDQ0YWE0MCIsInJlc2VydmVkIjpmYWxzZSwiaWQiOiIwMDlhMWYyOTMyNTNlNDFlIn1dLCJtaW50IjoiaHR0cHM6Ly90ZXN0bnV0LmNhc2h1LnNwYWNlIn1dLCJ1bml0Ijoic2F0In0= fdfdfg
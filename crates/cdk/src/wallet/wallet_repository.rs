@@ -9,7 +9,7 @@ use std::sync::Arc;
 
 use cdk_common::database;
 use cdk_common::database::WalletDatabase;
-use cdk_common::wallet::WalletKey;
+use cdk_common::wallet::{MintTrustDecision, MintTrustPolicy, ReceiveOptions, WalletKey};
 use tokio::sync::RwLock;
 use tracing::instrument;
 use zeroize::Zeroize;
@@ -17,7 +17,7 @@ use zeroize::Zeroize;
 use super::builder::WalletBuilder;
 use super::{AuthMintConnector, Error, MintConnector};
 use crate::mint_url::MintUrl;
-use crate::nuts::CurrencyUnit;
+use crate::nuts::{CurrencyUnit, Token};
 #[cfg(all(feature = "tor", not(target_arch = "wasm32")))]
 use crate::wallet::mint_connector::transport::TorAsync;
 use crate::{OidcClient, Wallet};
@@ -131,6 +131,8 @@ pub struct WalletRepositoryBuilder {
     seed: Option<[u8; 64]>,
     proxy_config: Option<url::Url>,
     danger_accept_invalid_certs: bool,
+    purge_expired_quotes: Option<std::time::Duration>,
+    mint_trust_policy: Option<MintTrustPolicy>,
     #[cfg(all(feature = "tor", not(target_arch = "wasm32")))]
     use_tor: bool,
 }
@@ -163,6 +165,8 @@ impl WalletRepositoryBuilder {
             seed: None,
             proxy_config: None,
             danger_accept_invalid_certs: false,
+            purge_expired_quotes: None,
+            mint_trust_policy: None,
             #[cfg(all(feature = "tor", not(target_arch = "wasm32")))]
             use_tor: false,
         }
@@ -198,6 +202,22 @@ impl WalletRepositoryBuilder {
         self
     }
 
+    /// Remove unpaid quotes that expired more than `grace` ago when the repository is built
+    ///
+    /// See [`Wallet::purge_quotes_expired_for`].
+    pub fn purge_expired_quotes_on_startup(mut self, grace: std::time::Duration) -> Self {
+        self.purge_expired_quotes = Some(grace);
+        self
+    }
+
+    /// Trust policy every token received by [`WalletRepository::receive`] is checked against
+    ///
+    /// A policy passed in the [`ReceiveOptions`] of a receive takes precedence.
+    pub fn mint_trust_policy(mut self, policy: MintTrustPolicy) -> Self {
+        self.mint_trust_policy = Some(policy);
+        self
+    }
+
     /// Enable Tor transport
     #[cfg(all(feature = "tor", not(target_arch = "wasm32")))]
     pub fn tor(mut self) -> Self {
@@ -220,6 +240,7 @@ impl WalletRepositoryBuilder {
             wallets: Arc::new(RwLock::new(BTreeMap::new())),
             proxy_config: self.proxy_config,
             danger_accept_invalid_certs: self.danger_accept_invalid_certs,
+            mint_trust_policy: self.mint_trust_policy,
             #[cfg(all(feature = "tor", not(target_arch = "wasm32")))]
             shared_tor_transport: if self.use_tor {
                 Some(TorAsync::new())
@@ -229,6 +250,19 @@ impl WalletRepositoryBuilder {
         };
 
         wallet.load_wallets().await?;
+
+        if let Some(grace) = self.purge_expired_quotes {
+            for mint_wallet in wallet.get_wallets().await {
+                if let Err(err) = mint_wallet.purge_quotes_expired_for(grace).await {
+                    tracing::warn!(
+                        "Could not purge expired quotes of {}: {}",
+                        mint_wallet.mint_url,
+                        err
+                    );
+                }
+            }
+        }
+
         Ok(wallet)
     }
 }
@@ -289,6 +323,8 @@ pub struct WalletRepository {
     proxy_config: Option<url::Url>,
     /// Whether proxied HTTPS clients should accept invalid TLS certificates
     danger_accept_invalid_certs: bool,
+    /// Trust policy applied to received tokens
+    mint_trust_policy: Option<MintTrustPolicy>,
     /// Shared Tor transport to be cloned into each TorHttpClient (if enabled)
     #[cfg(all(feature = "tor", not(target_arch = "wasm32")))]
     shared_tor_transport: Option<TorAsync>,
@@ -849,6 +885,89 @@ impl WalletRepository {
         })
     }
 
+    /// Receive a token into the wallet of its mint
+    ///
+    /// Every token is checked against the trust policy of `opts`, or the repository's policy
+    /// set with [`WalletRepositoryBuilder::mint_trust_policy`], so denylisted mints are refused
+    /// even if the repository already holds a wallet for them. The policy decides whether a
+    /// wallet is created for an unknown mint or the token is moved to a trusted mint of the
+    /// repository over Lightning. Without a policy, tokens of mints in the repository are
+    /// received as usual and tokens of other mints are rejected.
+    #[instrument(skip(self, encoded_token))]
+    pub async fn receive(
+        &self,
+        encoded_token: &str,
+        opts: ReceiveOptions,
+    ) -> Result<cdk_common::Amount, Error> {
+        let token = Token::from_str(encoded_token)?;
+        let mint_url = token.mint_url()?;
+        let unit = token.unit().unwrap_or_default();
+        let known = self.has_wallet(&mint_url, &unit).await;
+
+        let policy = opts
+            .mint_trust_policy
+            .clone()
+            .or_else(|| self.mint_trust_policy.clone());
+        // The repository decides on the policy, the receiving wallet only knows its own mint
+        let opts = ReceiveOptions {
+            mint_trust_policy: None,
+            ..opts
+        };
+
+        let Some(policy) = policy else {
+            if known {
+                let wallet = self.get_wallet(&mint_url, &unit).await?;
+                return wallet.receive(encoded_token, opts).await;
+            }
+
+            return Err(Error::UntrustedMint {
+                mint_url: mint_url.to_string(),
+                reason: "mint is not in the wallet".to_string(),
+            });
+        };
+
+        match policy.evaluate(&mint_url, token.value()?, known) {
+            MintTrustDecision::Accept => {
+                let wallet = if known {
+                    self.get_wallet(&mint_url, &unit).await?
+                } else {
+                    self.create_wallet(mint_url, unit, None).await?
+                };
+                wallet.receive(encoded_token, opts).await
+            }
+            MintTrustDecision::SwapToTrusted => {
+                let candidates: Vec<Wallet> = self
+                    .wallets
+                    .read()
+                    .await
+                    .iter()
+                    .filter(|(key, _)| key.unit == unit && key.mint_url != mint_url)
+                    .filter(|(key, _)| policy.is_trusted(&key.mint_url, true))
+                    .map(|(_, wallet)| wallet.clone())
+                    .collect();
+
+                // Prefer mints the policy explicitly allows over ones only known
+                let target = candidates
+                    .iter()
+                    .find(|wallet| policy.allowlist.contains(&wallet.mint_url))
+                    .or_else(|| candidates.first())
+                    .ok_or_else(|| Error::UntrustedMint {
+                        mint_url: mint_url.to_string(),
+                        reason: format!("no trusted {unit} wallet to swap the token to"),
+                    })?;
+
+                Ok(target
+                    .receive_from_other_mint(encoded_token, opts)
+                    .await?
+                    .amount)
+            }
+            MintTrustDecision::Reject(reason) => Err(Error::UntrustedMint {
+                mint_url: mint_url.to_string(),
+                reason,
+            }),
+        }
+    }
+
     /// List proofs for all wallets
     ///
     /// Returns a map of (mint URL, currency unit) to proofs for each wallet in the repository.
@@ -1141,6 +1260,41 @@ mod tests {
         assert!(retrieved.is_ok());
     }
 
+    #[tokio::test]
+    async fn test_receive_applies_trust_policy_to_known_mints() {
+        let token = "cashuAeyJ0b2tlbiI6W3sicHJvb2ZzIjpbeyJhbW91bnQiOjIsInNlY3JldCI6ImI2Zjk1ODIxYmZlNjUyYjYwZGQ2ZjYwMDU4N2UyZjNhOTk4MzVhMGMyNWI4MTQzODNlYWIwY2QzOWFiNDFjNzUiLCJDIjoiMDI1YWU4ZGEyOTY2Y2E5OGVmYjA5ZDcwOGMxM2FiZmEwZDkxNGUwYTk3OTE4MmFjMzQ4MDllMjYxODY5YTBhNDJlIiwicmVzZXJ2ZWQiOmZhbHNlLCJpZCI6IjAwOWExZjI5MzI1M2U0MWUifSx7ImFtb3VudCI6Miwic2VjcmV0IjoiZjU0Y2JjNmNhZWZmYTY5MTUyOTgyM2M1MjU1MDkwYjRhMDZjNGQ3ZDRjNzNhNDFlZTFkNDBlM2ExY2EzZGZhNyIsIkMiOiIwMjMyMTIzN2JlYjcyMWU3NGI1NzcwNWE5MjJjNjUxMGQwOTYyYzAzNzlhZDM0OTJhMDYwMDliZTAyNjA5ZjA3NTAiLCJyZXNlcnZlZCI6ZmFsc2UsImlkIjoiMDA5YTFmMjkzMjUzZTQxZSJ9LHsiYW1vdW50IjoxLCJzZWNyZXQiOiJhNzdhM2NjODY4YWM4ZGU3YmNiOWMxMzJmZWI3YzEzMDY4Nzg3ODk5Yzk3YTk2NWE2ZThkZTFiMzliMmQ2NmQ3IiwiQyI6IjAzMTY0YTMxNWVhNjM0NGE5NWI2NzM1NzBkYzg0YmZlMTQ2NDhmMTQwM2EwMDJiZmJlMDhlNWFhMWE0NDQ0YWE0MCIsInJlc2VydmVkIjpmYWxzZSwiaWQiOiIwMDlhMWYyOTMyNTNlNDFlIn1dLCJtaW50IjoiaHR0cHM6Ly90ZXN0bnV0LmNhc2h1LnNwYWNlIn1dLCJ1bml0Ijoic2F0In0=";
+        let mint_url: MintUrl = "https://testnut.cashu.space".parse().unwrap();
+
+        let localstore: Arc<dyn WalletDatabase<database::Error> + Send + Sync> = Arc::new(
+            cdk_sqlite::wallet::memory::empty()
+                .await
+                .expect("Failed to create in-memory database"),
+        );
+        let repo = WalletRepositoryBuilder::new()
+            .localstore(localstore)
+            .seed([0u8; 64])
+            .mint_trust_policy(MintTrustPolicy {
+                denylist: [mint_url.clone()].into(),
+                ..Default::default()
+            })
+            .build()
+            .await
+            .expect("Failed to create WalletRepository");
+        repo.create_wallet(mint_url, CurrencyUnit::Sat, None)
+            .await
+            .expect("Failed to create wallet");
+
+        let result = repo.receive(token, ReceiveOptions::default()).await;
+        assert!(matches!(result, Err(Error::UntrustedMint { .. })));
+
+        // Tokens of mints outside the repository are refused without a policy
+        let result = create_test_repository()
+            .await
+            .receive(token, ReceiveOptions::default())
+            .await;
+        assert!(matches!(result, Err(Error::UntrustedMint { .. })));
+    }
+
     #[tokio::test]
     async fn test_fetch_mint_info_returns_error_when_proxy_setup_fails() {
         let repo = create_test_repository_with_proxy(unsupported_proxy_url()).await;
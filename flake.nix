@@ -759,8 +759,12 @@
             "-p cdk -p cdk-http-client --no-default-features --features cdk/wallet,cdk-http-client/reqwest"
             "-p cdk -p cdk-http-client --no-default-features --features cdk/wallet,cdk/tor,cdk-http-client/bitreq"
             "-p cdk -p cdk-http-client --no-default-features --features cdk/wallet,cdk/npubcash,cdk-http-client/reqwest"
+            "-p cdk -p cdk-http-client --no-default-features --features cdk/lnurl,cdk-http-client/bitreq"
+            "-p cdk -p cdk-http-client --no-default-features --features cdk/otel,cdk-http-client/bitreq"
             "-p cdk -p cdk-http-client --no-default-features --features cdk/mint,cdk-http-client/bitreq"
             "-p cdk -p cdk-http-client --no-default-features --features cdk/mint,cdk-http-client/reqwest"
+            "-p cdk -p cdk-http-client --no-default-features --features cdk/peering,cdk-http-client/bitreq"
+            "-p cdk --features conversion"
           ];
 
           "storage-and-cli" = [
@@ -794,6 +798,7 @@
             "-p cdk-mintd"
             "-p cdk-mintd --features redis"
             "-p cdk-mintd --features sqlcipher"
+            "-p cdk-mintd --features conversion"
             "-p cdk-signatory"
             "-p cdk-mint-rpc"
             "-p cdk-prometheus"
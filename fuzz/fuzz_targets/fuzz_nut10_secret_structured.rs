@@ -0,0 +1,32 @@
+#![no_main]
+
+//! Structured sibling bin for [`cashu::nuts::nut10::Secret`].
+//!
+//! Consumes a [`Nut10SecretArb`] and asserts that the JSON encoding and the
+//! conversion through the NUT-00 secret string are lossless. Complements
+//! `fuzz_secret`, which exercises the parser on raw strings.
+
+use cashu::nuts::nut10::Secret as Nut10Secret;
+use cashu::secret::Secret;
+use cdk_fuzz::arbitrary_ext::Nut10SecretArb;
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|arb: Nut10SecretArb| {
+    let nut10: Nut10Secret = arb.into_inner();
+
+    // ---------------------------------------------------------------------
+    // 1. JSON round-trip is lossless.
+    // ---------------------------------------------------------------------
+    let json = serde_json::to_string(&nut10).expect("nut10 secret serializes");
+    let parsed: Nut10Secret = serde_json::from_str(&json).expect("nut10 secret json round-trips");
+    assert_eq!(parsed, nut10, "nut10 secret json round-trip mismatch");
+
+    // ---------------------------------------------------------------------
+    // 2. NUT-00 secret string round-trip is lossless.
+    // ---------------------------------------------------------------------
+    let secret = Secret::try_from(nut10.clone()).expect("nut10 secret converts to a secret");
+    match Nut10Secret::try_from(&secret) {
+        Ok(parsed) => assert_eq!(parsed, nut10, "nut10 secret string round-trip mismatch"),
+        Err(e) => panic!("secret of a nut10 secret must convert back: {e:?}"),
+    }
+});
@@ -243,6 +243,48 @@ impl<'a> Arbitrary<'a> for SecretStringArb {
     }
 }
 
+/// Wrapper around a NUT-10 [`Nut10Secret`] with fuzz-controlled data and tags.
+///
+/// Unlike [`SecretStringArb`], the data and tags are not required to form
+/// valid spending conditions, so the JSON encoding is exercised with
+/// arbitrary strings.
+#[derive(Debug, Clone)]
+pub struct Nut10SecretArb(pub Nut10Secret);
+
+impl Nut10SecretArb {
+    /// Consume the wrapper and return the inner value.
+    pub fn into_inner(self) -> Nut10Secret {
+        self.0
+    }
+}
+
+impl<'a> Arbitrary<'a> for Nut10SecretArb {
+    fn arbitrary(u: &mut Unstructured<'a>) -> arbitrary::Result<Self> {
+        let kind = if u.ratio(1, 2)? {
+            Kind::P2PK
+        } else {
+            Kind::HTLC
+        };
+        let data = String::arbitrary(u)?;
+        let tags: Option<Vec<Vec<String>>> = if u.ratio(3, 4)? {
+            let num_tags = u.int_in_range(0..=4)?;
+            let tags = (0..num_tags)
+                .map(|_| {
+                    let num_values = u.int_in_range(1..=4)?;
+                    (0..num_values)
+                        .map(|_| String::arbitrary(u))
+                        .collect::<arbitrary::Result<Vec<String>>>()
+                })
+                .collect::<arbitrary::Result<_>>()?;
+            Some(tags)
+        } else {
+            None
+        };
+
+        Ok(Self(Nut10Secret::new(kind, SecretData::new(data, tags))))
+    }
+}
+
 // Lightweight hex helper without pulling another dep.
 mod hex {
     pub fn encode_lower_bytes(bytes: &[u8]) -> String {
@@ -501,6 +543,7 @@ impl<'a> Arbitrary<'a> for TokenV4Arb {
             groups.push(TokenV4Token {
                 keyset_id: ShortKeysetId::from(keyset_id),
                 proofs,
+                keys: None,
             });
         }
 